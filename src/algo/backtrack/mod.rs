@@ -0,0 +1,5 @@
+mod solver;
+
+pub use solver::SearchProblem;
+pub use solver::SearchStats;
+pub use solver::Solver;
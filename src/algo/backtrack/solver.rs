@@ -0,0 +1,510 @@
+use crate::heap::FibonacciHeap;
+use std::cmp::Ordering;
+
+/// A combinatorial search problem explored by `Solver`. States form a tree rooted at `root`,
+/// where the children of a state are the results of applying its candidates.
+///
+/// For optimization, `score` of solutions is maximized and `bound` gives an optimistic estimate
+/// that lets the solver skip subtrees that can not beat the best known solution.
+pub trait SearchProblem {
+    /// A partial or complete solution
+    type State;
+
+    /// A decision that extends a state
+    type Choice;
+
+    /// Objective value of solutions. Use `()` for satisfaction problems
+    type Score: PartialOrd + Copy;
+
+    /// Returns the initial state
+    fn root(&self) -> Self::State;
+
+    /// Returns choices that extend `state`, in the order they should be explored
+    fn candidates(&self, state: &Self::State) -> Vec<Self::Choice>;
+
+    /// Returns the state resulting from applying `choice` to `state`
+    fn apply(&self, state: &Self::State, choice: &Self::Choice) -> Self::State;
+
+    /// Returns false if `state` violates the constraints, which prunes it with its whole subtree
+    fn is_feasible(&self, _state: &Self::State) -> bool {
+        true
+    }
+
+    /// Returns score of `state` if it is a solution, `None` otherwise
+    fn score(&self, state: &Self::State) -> Option<Self::Score>;
+
+    /// Returns an upper bound of the scores of all solutions in the subtree of `state`,
+    /// or `None` if no bound is known
+    fn bound(&self, _state: &Self::State) -> Option<Self::Score> {
+        None
+    }
+}
+
+/// Statistics of the searches of a `Solver`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// number of states whose candidates were generated
+    pub expanded: usize,
+
+    /// number of states pruned because they were infeasible
+    pub infeasible: usize,
+
+    /// number of states pruned because their bound could not beat the best solution
+    pub bounded: usize,
+
+    /// number of solutions found
+    pub solutions: usize,
+}
+
+// state of the best first search, ordered by bound. unknown bounds come first, ties are broken by depth first order
+struct Entry<P: SearchProblem> {
+    bound: Option<P::Score>,
+    sequence: usize,
+    state: P::State,
+}
+
+impl<P: SearchProblem> Ord for Entry<P> {
+    fn cmp(&self, other: &Entry<P>) -> Ordering {
+        let by_bound = match (&self.bound, &other.bound) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        };
+
+        by_bound.then(self.sequence.cmp(&other.sequence))
+    }
+}
+
+impl<P: SearchProblem> PartialOrd for Entry<P> {
+    fn partial_cmp(&self, other: &Entry<P>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: SearchProblem> PartialEq for Entry<P> {
+    fn eq(&self, other: &Entry<P>) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: SearchProblem> Eq for Entry<P> {}
+
+/// Backtracking and branch and bound solver of a `SearchProblem`.
+/// It offers depth first search for satisfaction and optimization, and best first search driven by a `FibonacciHeap`
+/// ordered by bounds. Every search updates the pruning statistics of the solver.
+///
+/// # Examples
+/// ```
+/// use rudac::algo::backtrack::{SearchProblem, Solver};
+///
+/// // 0/1 knapsack: state is (next item, weight, value)
+/// struct Knapsack {
+///     items: Vec<(u32, u32)>,
+///     capacity: u32,
+/// }
+///
+/// impl SearchProblem for Knapsack {
+///     type State = (usize, u32, u32);
+///     type Choice = bool;
+///     type Score = u32;
+///
+///     fn root(&self) -> Self::State {
+///         (0, 0, 0)
+///     }
+///
+///     fn candidates(&self, state: &Self::State) -> Vec<bool> {
+///         if state.0 == self.items.len() { vec![] } else { vec![true, false] }
+///     }
+///
+///     fn apply(&self, state: &Self::State, take: &bool) -> Self::State {
+///         let (weight, value) = self.items[state.0];
+///         if *take {
+///             (state.0 + 1, state.1 + weight, state.2 + value)
+///         } else {
+///             (state.0 + 1, state.1, state.2)
+///         }
+///     }
+///
+///     fn is_feasible(&self, state: &Self::State) -> bool {
+///         state.1 <= self.capacity
+///     }
+///
+///     fn score(&self, state: &Self::State) -> Option<u32> {
+///         Some(state.2)
+///     }
+///
+///     fn bound(&self, state: &Self::State) -> Option<u32> {
+///         Some(state.2 + self.items[state.0..].iter().map(|item| item.1).sum::<u32>())
+///     }
+/// }
+///
+/// let problem = Knapsack { items: vec![(5, 10), (4, 40), (6, 30), (3, 50)], capacity: 10 };
+/// let mut solver = Solver::init(problem);
+///
+/// let (state, value) = solver.maximize().unwrap();
+/// assert_eq!(value, 90);
+/// assert_eq!(state.1, 7);
+/// assert!(solver.stats().bounded > 0);
+///
+/// assert_eq!(solver.maximize_best_first().unwrap().1, 90);
+/// ```
+pub struct Solver<P: SearchProblem> {
+    problem: P,
+    stats: SearchStats,
+}
+
+impl<P: SearchProblem> Solver<P> {
+    /// Initializes a solver of `problem`
+    pub fn init(problem: P) -> Solver<P> {
+        Solver {
+            problem,
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Returns the problem
+    pub fn problem(&self) -> &P {
+        &self.problem
+    }
+
+    /// Returns statistics accumulated by all searches
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    /// Resets the statistics
+    pub fn reset_stats(&mut self) {
+        self.stats = SearchStats::default();
+    }
+
+    /// Returns the first solution in depth first order, or `None` if there is no solution
+    pub fn find_first(&mut self) -> Option<P::State> {
+        let mut found = None;
+        self.depth_first(None, &mut |state, _| {
+            found = Some(state);
+            false
+        });
+
+        found
+    }
+
+    /// Returns all solutions in depth first order
+    pub fn find_all(&mut self) -> Vec<P::State> {
+        let mut found = Vec::new();
+        self.depth_first(None, &mut |state, _| {
+            found.push(state);
+            true
+        });
+
+        found
+    }
+
+    /// Returns a solution with the highest score and its score using depth first branch and bound,
+    /// or `None` if there is no solution. The first solution found wins ties
+    pub fn maximize(&mut self) -> Option<(P::State, P::Score)> {
+        let mut best: Option<(P::State, P::Score)> = None;
+        let mut best_score: Option<P::Score> = None;
+
+        self.depth_first(Some(&mut best_score), &mut |state, score| {
+            if best.as_ref().is_none_or(|(_, b)| score > *b) {
+                best = Some((state, score));
+            }
+            true
+        });
+
+        best
+    }
+
+    /// Returns a solution with the highest score and its score, exploring states in decreasing order of their bound,
+    /// or `None` if there is no solution. The search stops as soon as no remaining bound can beat the best solution.
+    /// States without a bound are explored first
+    pub fn maximize_best_first(&mut self) -> Option<(P::State, P::Score)> {
+        let mut heap: FibonacciHeap<Entry<P>> = FibonacciHeap::init_max();
+        let mut best: Option<(P::State, P::Score)> = None;
+        let mut sequence = 0;
+
+        let root = self.problem.root();
+        if !self.problem.is_feasible(&root) {
+            self.stats.infeasible += 1;
+            return None;
+        }
+        heap.push(Entry {
+            bound: self.problem.bound(&root),
+            sequence,
+            state: root,
+        });
+
+        while let Some(entry) = heap.pop() {
+            if let (Some(bound), Some((_, score))) = (entry.bound, &best) {
+                if !improves(bound, *score) {
+                    // every remaining state has a bound that is not greater
+                    self.stats.bounded += 1 + heap.size();
+                    break;
+                }
+            }
+
+            let state = entry.state;
+            self.stats.expanded += 1;
+            for choice in self.problem.candidates(&state) {
+                let child = self.problem.apply(&state, &choice);
+                if !self.problem.is_feasible(&child) {
+                    self.stats.infeasible += 1;
+                    continue;
+                }
+
+                sequence += 1;
+                heap.push(Entry {
+                    bound: self.problem.bound(&child),
+                    sequence,
+                    state: child,
+                });
+            }
+
+            if let Some(score) = self.problem.score(&state) {
+                self.stats.solutions += 1;
+                if best.as_ref().is_none_or(|(_, b)| score > *b) {
+                    best = Some((state, score));
+                }
+            }
+        }
+
+        best
+    }
+
+    // explores states depth first. `visit` receives every solution and returns false to stop the search.
+    // if `best` is given, states whose bound does not beat it are pruned and it is updated with better solutions
+    fn depth_first<V>(&mut self, mut best: Option<&mut Option<P::Score>>, visit: &mut V)
+    where
+        V: FnMut(P::State, P::Score) -> bool,
+    {
+        let root = self.problem.root();
+        if !self.problem.is_feasible(&root) {
+            self.stats.infeasible += 1;
+            return;
+        }
+
+        let mut stack = vec![root];
+        while let Some(state) = stack.pop() {
+            if let Some(Some(best_score)) = best.as_deref() {
+                if let Some(bound) = self.problem.bound(&state) {
+                    if !improves(bound, *best_score) {
+                        self.stats.bounded += 1;
+                        continue;
+                    }
+                }
+            }
+
+            let candidates = self.problem.candidates(&state);
+            let mut children = Vec::with_capacity(candidates.len());
+            for choice in candidates.iter() {
+                let child = self.problem.apply(&state, choice);
+                if self.problem.is_feasible(&child) {
+                    children.push(child);
+                } else {
+                    self.stats.infeasible += 1;
+                }
+            }
+            self.stats.expanded += 1;
+
+            if let Some(score) = self.problem.score(&state) {
+                self.stats.solutions += 1;
+                if let Some(best) = best.as_deref_mut() {
+                    if best.is_none_or(|b| score > b) {
+                        *best = Some(score);
+                    }
+                }
+                if !visit(state, score) {
+                    return;
+                }
+            }
+
+            // children are pushed in reverse, so the first candidate is explored first
+            stack.extend(children.into_iter().rev());
+        }
+    }
+}
+
+// returns true if a subtree with `bound` may contain a solution better than `best`
+fn improves<S: PartialOrd>(bound: S, best: S) -> bool {
+    bound.partial_cmp(&best) == Some(Ordering::Greater)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // places one queen per row, state holds the column of every placed queen
+    struct Queens {
+        n: usize,
+    }
+
+    impl SearchProblem for Queens {
+        type State = Vec<usize>;
+        type Choice = usize;
+        type Score = ();
+
+        fn root(&self) -> Vec<usize> {
+            Vec::new()
+        }
+
+        fn candidates(&self, state: &Vec<usize>) -> Vec<usize> {
+            if state.len() == self.n {
+                Vec::new()
+            } else {
+                (0..self.n).collect()
+            }
+        }
+
+        fn apply(&self, state: &Vec<usize>, column: &usize) -> Vec<usize> {
+            let mut next = state.clone();
+            next.push(*column);
+            next
+        }
+
+        fn is_feasible(&self, state: &Vec<usize>) -> bool {
+            if state.is_empty() {
+                return true;
+            }
+            let row = state.len() - 1;
+            let column = state[row];
+
+            (0..row).all(|r| {
+                state[r] != column
+                    && row - r != (state[r] as isize - column as isize).unsigned_abs()
+            })
+        }
+
+        fn score(&self, state: &Vec<usize>) -> Option<()> {
+            if state.len() == self.n {
+                Some(())
+            } else {
+                None
+            }
+        }
+    }
+
+    // picks a subset of items with total weight at most capacity, maximizing total value
+    struct Knapsack {
+        items: Vec<(u32, u32)>,
+        capacity: u32,
+    }
+
+    impl SearchProblem for Knapsack {
+        type State = (usize, u32, u32);
+        type Choice = bool;
+        type Score = u32;
+
+        fn root(&self) -> Self::State {
+            (0, 0, 0)
+        }
+
+        fn candidates(&self, state: &Self::State) -> Vec<bool> {
+            if state.0 == self.items.len() {
+                Vec::new()
+            } else {
+                vec![true, false]
+            }
+        }
+
+        fn apply(&self, state: &Self::State, take: &bool) -> Self::State {
+            let (weight, value) = self.items[state.0];
+            if *take {
+                (state.0 + 1, state.1 + weight, state.2 + value)
+            } else {
+                (state.0 + 1, state.1, state.2)
+            }
+        }
+
+        fn is_feasible(&self, state: &Self::State) -> bool {
+            state.1 <= self.capacity
+        }
+
+        fn score(&self, state: &Self::State) -> Option<u32> {
+            Some(state.2)
+        }
+
+        fn bound(&self, state: &Self::State) -> Option<u32> {
+            Some(state.2 + self.items[state.0..].iter().map(|item| item.1).sum::<u32>())
+        }
+    }
+
+    fn knapsack_brute_force(problem: &Knapsack) -> u32 {
+        let n = problem.items.len();
+        (0..1u32 << n)
+            .filter_map(|mask| {
+                let chosen = (0..n).filter(|i| mask >> i & 1 == 1);
+                let (weight, value) = chosen.fold((0, 0), |(w, v), i| {
+                    (w + problem.items[i].0, v + problem.items[i].1)
+                });
+                if weight <= problem.capacity {
+                    Some(value)
+                } else {
+                    None
+                }
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn algo_backtrack_solver_queens() {
+        let mut solver = Solver::init(Queens { n: 8 });
+
+        assert_eq!(solver.find_all().len(), 92);
+        let stats = solver.stats();
+        assert_eq!(stats.solutions, 92);
+        assert!(stats.infeasible > 0);
+        assert_eq!(stats.bounded, 0);
+
+        solver.reset_stats();
+        let first = solver.find_first().unwrap();
+        assert_eq!(first, vec![0, 4, 7, 5, 2, 6, 1, 3]);
+        assert_eq!(solver.stats().solutions, 1);
+
+        assert_eq!(Solver::init(Queens { n: 3 }).find_first(), None);
+    }
+
+    #[test]
+    fn algo_backtrack_solver_knapsack() {
+        let mut seed: u32 = 17;
+        for _ in 0..20 {
+            let items: Vec<(u32, u32)> = (0..12)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                    let weight = seed >> 16 & 15;
+                    seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                    (weight + 1, seed >> 16 & 31)
+                })
+                .collect();
+            let problem = Knapsack {
+                items,
+                capacity: 30,
+            };
+            let expected = knapsack_brute_force(&problem);
+
+            let mut solver = Solver::init(problem);
+            let (state, value) = solver.maximize().unwrap();
+            assert_eq!(value, expected);
+            assert!(state.1 <= 30);
+            assert!(solver.stats().expanded < 1 << 13);
+
+            let (state, value) = solver.maximize_best_first().unwrap();
+            assert_eq!(value, expected);
+            assert_eq!(state.2, expected);
+        }
+    }
+
+    #[test]
+    fn algo_backtrack_solver_infeasible_root() {
+        let mut solver = Solver::init(Knapsack {
+            items: vec![(1, 1)],
+            capacity: 0,
+        });
+        solver.problem();
+
+        assert_eq!(solver.maximize().unwrap().1, 0);
+        assert_eq!(solver.maximize_best_first().unwrap().1, 0);
+        assert_eq!(solver.stats().infeasible, 2);
+    }
+}
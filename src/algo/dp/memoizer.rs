@@ -0,0 +1,250 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// Error returned by `Memoizer::evaluate` when the value of a state depends on itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceCycle<K> {
+    /// states of the cycle, each one depending on the next and the last one depending on the first
+    pub cycle: Vec<K>,
+}
+
+impl<K: fmt::Debug> fmt::Display for RecurrenceCycle<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let states: Vec<String> = self
+            .cycle
+            .iter()
+            .map(|state| format!("{:?}", state))
+            .collect();
+        write!(f, "Recurrence has the cycle {}", states.join(" -> "))
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for RecurrenceCycle<K> {}
+
+/// Values of the states already evaluated, handed to the recurrence of a `Memoizer`
+pub struct Lookup<'a, K, V> {
+    memo: &'a HashMap<K, V>,
+    missing: Option<K>,
+}
+
+impl<'a, K: Hash + Eq + Clone, V: Clone> Lookup<'a, K, V> {
+    /// Returns the value of `state`, or `None` if it is not evaluated yet. The recurrence should then give up with
+    /// `None` too, `state` is evaluated and the recurrence is called again
+    ///
+    /// # Arguments
+    /// * `state`: the state the value depends on
+    pub fn get(&mut self, state: &K) -> Option<V> {
+        match self.memo.get(state) {
+            Some(value) => Some(value.clone()),
+            None => {
+                if self.missing.is_none() {
+                    self.missing = Some(state.clone());
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Evaluates a recurrence over hashable states with memoization. The recurrence computes the value of a state from
+/// the values of other states, fetched from a `Lookup` with `?`. When a value is missing the recurrence returns
+/// `None`, the missing state is put on an explicit stack and evaluated first, and the recurrence is called again.
+/// States are thus evaluated in topological order of their dependencies without recursion, so deep recurrences
+/// can not overflow the call stack, and a state that depends on itself is reported as a `RecurrenceCycle`.
+/// * Complexity: every state is evaluated once, calling the recurrence at most once per dependency plus one time
+///
+/// # Examples
+/// ```
+/// use rudac::algo::dp::{Lookup, Memoizer};
+///
+/// // number of monotone lattice paths from (0, 0) to (i, j)
+/// let mut paths = Memoizer::init(|&(i, j): &(u64, u64), memo: &mut Lookup<(u64, u64), u64>| {
+///     if i == 0 || j == 0 {
+///         return Some(1);
+///     }
+///     Some(memo.get(&(i - 1, j))? + memo.get(&(i, j - 1))?)
+/// });
+///
+/// assert_eq!(paths.evaluate((2, 2)), Ok(6));
+/// assert_eq!(paths.evaluate((16, 16)), Ok(601_080_390));
+///
+/// // a value that depends on itself is an error
+/// let mut cyclic = Memoizer::init(|n: &u32, memo: &mut Lookup<u32, u32>| memo.get(&((n + 1) % 3)));
+///
+/// assert_eq!(cyclic.evaluate(0).unwrap_err().cycle, vec![0, 1, 2]);
+/// ```
+pub struct Memoizer<K, V, F>
+where
+    F: FnMut(&K, &mut Lookup<K, V>) -> Option<V>,
+{
+    recurrence: F,
+    memo: HashMap<K, V>,
+}
+
+impl<K, V, F> Memoizer<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: FnMut(&K, &mut Lookup<K, V>) -> Option<V>,
+{
+    /// Initializes a memoizer of `recurrence` without evaluated states
+    ///
+    /// # Arguments
+    /// * `recurrence`: computes the value of a state, returning `None` only when a lookup misses
+    pub fn init(recurrence: F) -> Memoizer<K, V, F> {
+        Memoizer {
+            recurrence,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// Returns number of evaluated states
+    pub fn size(&self) -> usize {
+        self.memo.len()
+    }
+
+    /// Returns true if no state is evaluated
+    pub fn is_empty(&self) -> bool {
+        self.memo.is_empty()
+    }
+
+    /// Returns the value of `state` if it is evaluated
+    ///
+    /// # Arguments
+    /// * `state`: the state
+    pub fn get(&self, state: &K) -> Option<&V> {
+        self.memo.get(state)
+    }
+
+    /// Forgets the values of all states
+    pub fn clear(&mut self) {
+        self.memo.clear();
+    }
+
+    /// Returns the value of `state`, evaluating it and the states it depends on as needed. Values evaluated before a
+    /// cycle is found are kept
+    ///
+    /// # Arguments
+    /// * `state`: the state
+    ///
+    /// # Panics
+    /// * panics if the recurrence returns `None` although every lookup succeeded
+    pub fn evaluate(&mut self, state: K) -> Result<V, RecurrenceCycle<K>> {
+        if let Some(value) = self.memo.get(&state) {
+            return Ok(value.clone());
+        }
+
+        // states waiting for their dependencies and their positions on the stack
+        let mut stack = vec![state.clone()];
+        let mut on_stack = HashMap::new();
+        on_stack.insert(state.clone(), 0);
+        while let Some(top) = stack.last() {
+            let mut lookup = Lookup {
+                memo: &self.memo,
+                missing: None,
+            };
+            let value = (self.recurrence)(top, &mut lookup);
+            match (value, lookup.missing) {
+                (Some(value), _) => {
+                    let top = stack.pop().unwrap();
+                    on_stack.remove(&top);
+                    self.memo.insert(top, value);
+                }
+                (None, Some(missing)) => {
+                    if let Some(position) = on_stack.get(&missing) {
+                        return Err(RecurrenceCycle {
+                            cycle: stack.split_off(*position),
+                        });
+                    }
+                    on_stack.insert(missing.clone(), stack.len());
+                    stack.push(missing);
+                }
+                (None, None) => panic!("Recurrence must return a value when every lookup succeeds"),
+            }
+        }
+
+        Ok(self.memo[&state].clone())
+    }
+}
+
+impl<K, V, F> MemoryFootprint for Memoizer<K, V, F>
+where
+    F: FnMut(&K, &mut Lookup<K, V>) -> Option<V>,
+{
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<(K, V)>(self.memo.len()).with_hash_map(&self.memo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn algo_dp_memoizer_edit_distance() {
+        let (a, b): (Vec<char>, Vec<char>) =
+            ("sunday".chars().collect(), "saturday".chars().collect());
+        let mut calls = 0;
+        let mut distance = Memoizer::init(
+            |&(i, j): &(usize, usize), memo: &mut Lookup<(usize, usize), usize>| {
+                calls += 1;
+                if i == 0 || j == 0 {
+                    return Some(i + j);
+                }
+                let substitution = memo.get(&(i - 1, j - 1))? + usize::from(a[i - 1] != b[j - 1]);
+                let deletion = memo.get(&(i - 1, j))? + 1;
+                let insertion = memo.get(&(i, j - 1))? + 1;
+                Some(substitution.min(deletion).min(insertion))
+            },
+        );
+
+        assert_eq!(distance.evaluate((6, 8)), Ok(3));
+        assert_eq!(distance.size(), 7 * 9);
+        assert_eq!(distance.get(&(3, 3)), Some(&2));
+        drop(distance);
+        // every state is called once per dependency at most, plus one time
+        assert!(calls <= 4 * 7 * 9);
+    }
+
+    #[test]
+    fn algo_dp_memoizer_deep_recurrence() {
+        // far deeper than the call stack would allow with recursion
+        let mut sum = Memoizer::init(|n: &u64, memo: &mut Lookup<u64, u64>| {
+            if *n == 0 {
+                return Some(0);
+            }
+            Some(memo.get(&(n - 1))? + n)
+        });
+
+        assert_eq!(sum.evaluate(1_000_000), Ok(500_000_500_000));
+        assert_eq!(sum.size(), 1_000_001);
+
+        sum.clear();
+        assert!(sum.is_empty());
+    }
+
+    #[test]
+    fn algo_dp_memoizer_cycle() {
+        // 0 -> 1 -> 2 -> 3 -> 1, and 4 depends on nothing
+        let mut memoizer = Memoizer::init(|n: &u32, memo: &mut Lookup<u32, u32>| match *n {
+            4 => Some(4),
+            3 => memo.get(&1),
+            n => Some(memo.get(&(n + 1))? + memo.get(&4)?),
+        });
+
+        let error = memoizer.evaluate(0).unwrap_err();
+        assert_eq!(error.cycle, vec![1, 2, 3]);
+        assert_eq!(error.to_string(), "Recurrence has the cycle 1 -> 2 -> 3");
+        assert_eq!(memoizer.evaluate(4), Ok(4));
+    }
+
+    #[test]
+    #[should_panic(expected = "Recurrence must return a value when every lookup succeeds")]
+    fn algo_dp_memoizer_panic_no_value() {
+        let mut memoizer = Memoizer::init(|_: &u32, _: &mut Lookup<u32, u32>| None);
+
+        let _ = memoizer.evaluate(0);
+    }
+}
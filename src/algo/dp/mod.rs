@@ -0,0 +1,5 @@
+mod memoizer;
+
+pub use memoizer::Lookup;
+pub use memoizer::Memoizer;
+pub use memoizer::RecurrenceCycle;
@@ -0,0 +1,268 @@
+use super::GameState;
+use crate::util::Rng;
+
+// a position of the search tree
+struct Node<S: GameState> {
+    state: S,
+    parent: Option<usize>,
+
+    // index of the move of the parent leading here
+    mv: usize,
+
+    // moves of the position whose children are not created yet
+    untried: Vec<usize>,
+    moves: Vec<S::Move>,
+    children: Vec<usize>,
+
+    visits: f64,
+
+    // sum of the outcomes of the playouts through the node for the player who moved into it
+    reward: f64,
+}
+
+impl<S: GameState> Node<S> {
+    fn init(state: S, parent: Option<usize>, mv: usize) -> Node<S> {
+        let moves = state.moves();
+        Node {
+            untried: (0..moves.len()).rev().collect(),
+            state,
+            parent,
+            mv,
+            moves,
+            children: Vec::new(),
+            visits: 0.0,
+            reward: 0.0,
+        }
+    }
+}
+
+/// Monte Carlo tree search. Every iteration descends the search tree by the UCT rule, adds one position to it, plays
+/// random moves from there to the end of the game and credits the outcome to the positions it passed. The sign of
+/// `GameState::evaluate` at the end of a playout is taken as its outcome: a win, a draw or a loss. Needs no
+/// estimate of positions, so it suits games whose positions are hard to judge
+///
+/// # Examples
+/// ```
+/// use rudac::algo::game::{GameState, Mcts};
+/// use rudac::util::SplitMix64;
+///
+/// // players take 1 to 3 stones in turns, the player who can not move loses
+/// #[derive(Clone)]
+/// struct Pile(u32);
+///
+/// impl GameState for Pile {
+///     type Move = u32;
+///
+///     fn moves(&self) -> Vec<u32> {
+///         (1..=self.0.min(3)).collect()
+///     }
+///
+///     fn apply(&self, taken: &u32) -> Pile {
+///         Pile(self.0 - taken)
+///     }
+///
+///     fn evaluate(&self) -> f64 {
+///         if self.0 == 0 { -1.0 } else { 0.0 }
+///     }
+/// }
+///
+/// let mcts = Mcts::init(2000);
+///
+/// // leave a multiple of 4 stones
+/// assert_eq!(mcts.search(&Pile(7), &mut SplitMix64::init(1)), Some(3));
+/// assert_eq!(mcts.search(&Pile(0), &mut SplitMix64::init(1)), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Mcts {
+    iterations: usize,
+    exploration: f64,
+    playout_depth: usize,
+}
+
+impl Mcts {
+    /// Initializes a search of `iterations` playouts with exploration constant sqrt(2) and unbounded playouts
+    ///
+    /// # Arguments
+    /// * `iterations`: number of playouts of a search
+    pub fn init(iterations: usize) -> Mcts {
+        Mcts {
+            iterations,
+            exploration: std::f64::consts::SQRT_2,
+            playout_depth: usize::MAX,
+        }
+    }
+
+    /// Sets the exploration constant of the UCT rule. Larger constants try rarely visited moves more often
+    ///
+    /// # Arguments
+    /// * `exploration`: the exploration constant
+    pub fn with_exploration(mut self, exploration: f64) -> Mcts {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Limits playouts to `playout_depth` moves, the sign of `GameState::evaluate` of the last position is taken
+    /// as the outcome of a playout that is cut short
+    ///
+    /// # Arguments
+    /// * `playout_depth`: maximum number of moves of a playout
+    pub fn with_playout_depth(mut self, playout_depth: usize) -> Mcts {
+        self.playout_depth = playout_depth;
+        self
+    }
+
+    /// Returns number of playouts of a search
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns the most visited move of `state` after the search, None if `state` has no moves
+    ///
+    /// # Arguments
+    /// * `state`: the position to search
+    /// * `rng`: source of randomness
+    pub fn search<S: GameState, R: Rng>(&self, state: &S, rng: &mut R) -> Option<S::Move> {
+        let mut tree = vec![Node::init(state.clone(), None, 0)];
+        if tree[0].moves.is_empty() {
+            return None;
+        }
+
+        for _ in 0..self.iterations {
+            let leaf = self.expand(&mut tree);
+
+            // outcome for the player to move at the leaf
+            let mut value = self.playout(&tree[leaf].state, rng);
+            let mut node = Some(leaf);
+            while let Some(index) = node {
+                tree[index].visits += 1.0;
+                tree[index].reward -= value;
+                value = -value;
+                node = tree[index].parent;
+            }
+        }
+
+        let root = &tree[0];
+        let best = root
+            .children
+            .iter()
+            .max_by(|&&child1, &&child2| tree[child1].visits.total_cmp(&tree[child2].visits))?;
+
+        Some(root.moves[tree[*best].mv].clone())
+    }
+
+    // descends by the UCT rule to a position with untried moves and adds a child for one of them
+    fn expand<S: GameState>(&self, tree: &mut Vec<Node<S>>) -> usize {
+        let mut index = 0;
+        loop {
+            if let Some(mv) = tree[index].untried.pop() {
+                let state = tree[index].state.apply(&tree[index].moves[mv]);
+                tree.push(Node::init(state, Some(index), mv));
+                let child = tree.len() - 1;
+                tree[index].children.push(child);
+
+                return child;
+            }
+
+            let node = &tree[index];
+            if node.children.is_empty() {
+                return index;
+            }
+
+            let log_visits = node.visits.ln();
+            let uct = |child: usize| {
+                let child = &tree[child];
+                child.reward / child.visits + self.exploration * (log_visits / child.visits).sqrt()
+            };
+            index = *node
+                .children
+                .iter()
+                .max_by(|&&child1, &&child2| uct(child1).total_cmp(&uct(child2)))
+                .unwrap();
+        }
+    }
+
+    // returns the outcome of a random playout from `state` for the player to move at `state`
+    fn playout<S: GameState, R: Rng>(&self, state: &S, rng: &mut R) -> f64 {
+        let mut state = state.clone();
+        let mut sign = 1.0;
+        for _ in 0..self.playout_depth {
+            let mut moves = state.moves();
+            if moves.is_empty() {
+                break;
+            }
+            let mv = moves.swap_remove(rng.next_below(moves.len() as u64) as usize);
+            state = state.apply(&mv);
+            sign = -sign;
+        }
+
+        sign * sign_of(state.evaluate())
+    }
+}
+
+// the outcome of a game by its value
+fn sign_of(value: f64) -> f64 {
+    if value > 0.0 {
+        1.0
+    } else if value < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+
+    // nim, a player takes stones from one heap and the player who can not move loses
+    #[derive(Debug, Clone)]
+    struct Nim(Vec<u32>);
+
+    impl GameState for Nim {
+        type Move = (usize, u32);
+
+        fn moves(&self) -> Vec<(usize, u32)> {
+            (0..self.0.len())
+                .flat_map(|heap| (1..=self.0[heap]).map(move |taken| (heap, taken)))
+                .collect()
+        }
+
+        fn apply(&self, &(heap, taken): &(usize, u32)) -> Nim {
+            let mut next = self.clone();
+            next.0[heap] -= taken;
+            next
+        }
+
+        fn evaluate(&self) -> f64 {
+            if self.0.iter().all(|&heap| heap == 0) {
+                -1.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    #[test]
+    fn algo_game_mcts_finds_winning_nim_moves() {
+        let mut rng = SplitMix64::init(5);
+        let mcts = Mcts::init(3000);
+
+        // the winning move leaves heaps whose xor is zero
+        for heaps in [vec![1, 2], vec![2, 5], vec![1, 1, 3], vec![1, 2, 4]] {
+            let (heap, taken) = mcts.search(&Nim(heaps.clone()), &mut rng).unwrap();
+            let next = Nim(heaps).apply(&(heap, taken));
+            assert_eq!(next.0.iter().fold(0, |xor, heap| xor ^ heap), 0);
+        }
+    }
+
+    #[test]
+    fn algo_game_mcts_single_move_and_no_moves() {
+        let mut rng = SplitMix64::init(6);
+        let mcts = Mcts::init(10).with_exploration(0.5).with_playout_depth(3);
+
+        assert_eq!(mcts.iterations(), 10);
+        assert_eq!(mcts.search(&Nim(vec![0, 1]), &mut rng), Some((1, 1)));
+        assert_eq!(mcts.search(&Nim(vec![0, 0]), &mut rng), None);
+    }
+}
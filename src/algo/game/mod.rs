@@ -0,0 +1,12 @@
+mod mcts;
+mod search;
+mod state;
+mod table;
+
+pub use mcts::Mcts;
+pub use search::alpha_beta;
+pub use search::alpha_beta_with_table;
+pub use search::minimax;
+pub use state::Evaluation;
+pub use state::GameState;
+pub use table::TranspositionTable;
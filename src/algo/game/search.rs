@@ -0,0 +1,419 @@
+use super::table::{Bound, Entry};
+use super::{Evaluation, GameState, TranspositionTable};
+use std::hash::Hash;
+
+/// Returns the value of `state` for the player to move, found by looking `depth` moves ahead. Every line of play is
+/// followed to its end or to the depth limit, where positions are valued by `GameState::evaluate`, and each player
+/// picks the move that is best for them
+/// * Complexity: O(b^depth) for b moves per position
+///
+/// # Arguments
+/// * `state`: the position to search
+/// * `depth`: number of moves to look ahead
+///
+/// # Examples
+/// ```
+/// use rudac::algo::game::{minimax, GameState};
+///
+/// // players take 1 to 3 stones in turns, the player who can not move loses
+/// #[derive(Clone)]
+/// struct Pile(u32);
+///
+/// impl GameState for Pile {
+///     type Move = u32;
+///
+///     fn moves(&self) -> Vec<u32> {
+///         (1..=self.0.min(3)).collect()
+///     }
+///
+///     fn apply(&self, taken: &u32) -> Pile {
+///         Pile(self.0 - taken)
+///     }
+///
+///     fn evaluate(&self) -> f64 {
+///         if self.0 == 0 { -1.0 } else { 0.0 }
+///     }
+/// }
+///
+/// // taking one stone leaves a multiple of 4, which is lost for the opponent
+/// let evaluation = minimax(&Pile(9), 9);
+/// assert_eq!(evaluation.value, 1.0);
+/// assert_eq!(evaluation.best, Some(1));
+///
+/// assert_eq!(minimax(&Pile(8), 8).value, -1.0);
+/// ```
+pub fn minimax<S: GameState>(state: &S, depth: usize) -> Evaluation<S::Move> {
+    let mut nodes = 0;
+    let (value, best) = negamax(state, depth, &mut nodes);
+
+    Evaluation {
+        value,
+        best: best.map(|index| state.moves().swap_remove(index)),
+        nodes,
+    }
+}
+
+/// Returns the value of `state` for the player to move like `minimax`, skipping the moves that can not change it.
+/// Once a move is refuted by an answer of the opponent, the rest of the answers are not searched. Positions whose
+/// good moves come first are searched fastest
+/// * Complexity: O(b^depth) for b moves per position, O(b^(depth / 2)) if the best move always comes first
+///
+/// # Arguments
+/// * `state`: the position to search
+/// * `depth`: number of moves to look ahead
+///
+/// # Examples
+/// ```
+/// use rudac::algo::game::{alpha_beta, minimax, GameState};
+///
+/// // players take 1 to 3 stones in turns, the player who can not move loses
+/// #[derive(Clone)]
+/// struct Pile(u32);
+///
+/// impl GameState for Pile {
+///     type Move = u32;
+///
+///     fn moves(&self) -> Vec<u32> {
+///         (1..=self.0.min(3)).collect()
+///     }
+///
+///     fn apply(&self, taken: &u32) -> Pile {
+///         Pile(self.0 - taken)
+///     }
+///
+///     fn evaluate(&self) -> f64 {
+///         if self.0 == 0 { -1.0 } else { 0.0 }
+///     }
+/// }
+///
+/// let pruned = alpha_beta(&Pile(13), 13);
+/// let full = minimax(&Pile(13), 13);
+///
+/// assert_eq!(pruned.value, full.value);
+/// assert_eq!(pruned.best, Some(1));
+/// assert!(pruned.nodes < full.nodes);
+/// ```
+pub fn alpha_beta<S: GameState>(state: &S, depth: usize) -> Evaluation<S::Move> {
+    let mut search = Search {
+        table: &mut (),
+        nodes: 0,
+    };
+    let (value, best) = search.negamax(state, depth, f64::NEG_INFINITY, f64::INFINITY);
+
+    Evaluation {
+        value,
+        best: best.map(|index| state.moves().swap_remove(index)),
+        nodes: search.nodes,
+    }
+}
+
+/// Returns the value of `state` for the player to move like `alpha_beta`, storing the values of searched positions
+/// in `table` and reusing the stored ones. See `TranspositionTable`
+/// * Complexity: O(b^depth) for b moves per position
+///
+/// # Arguments
+/// * `state`: the position to search
+/// * `depth`: number of moves to look ahead
+/// * `table`: values of positions found by earlier searches of the same game
+pub fn alpha_beta_with_table<S>(
+    state: &S,
+    depth: usize,
+    table: &mut TranspositionTable<S>,
+) -> Evaluation<S::Move>
+where
+    S: GameState + Eq + Hash,
+{
+    let mut search = Search { table, nodes: 0 };
+    let (value, best) = search.negamax(state, depth, f64::NEG_INFINITY, f64::INFINITY);
+
+    Evaluation {
+        value,
+        best: best.map(|index| state.moves().swap_remove(index)),
+        nodes: search.nodes,
+    }
+}
+
+// returns the value of `state` and the index of its best move
+fn negamax<S: GameState>(state: &S, depth: usize, nodes: &mut usize) -> (f64, Option<usize>) {
+    *nodes += 1;
+    let moves = state.moves();
+    if depth == 0 || moves.is_empty() {
+        return (state.evaluate(), None);
+    }
+
+    let mut best = (f64::NEG_INFINITY, None);
+    for (index, mv) in moves.iter().enumerate() {
+        let value = -negamax(&state.apply(mv), depth - 1, nodes).0;
+        if value > best.0 {
+            best = (value, Some(index));
+        }
+    }
+
+    best
+}
+
+// values of positions reused by alpha beta search
+trait Transpositions<S> {
+    fn get(&self, state: &S) -> Option<Entry>;
+
+    fn store(&mut self, state: &S, entry: Entry);
+}
+
+// searching without a table
+impl<S> Transpositions<S> for () {
+    fn get(&self, _: &S) -> Option<Entry> {
+        None
+    }
+
+    fn store(&mut self, _: &S, _: Entry) {}
+}
+
+impl<S: GameState + Eq + Hash> Transpositions<S> for TranspositionTable<S> {
+    fn get(&self, state: &S) -> Option<Entry> {
+        TranspositionTable::get(self, state)
+    }
+
+    fn store(&mut self, state: &S, entry: Entry) {
+        TranspositionTable::store(self, state, entry)
+    }
+}
+
+struct Search<'a, T> {
+    table: &'a mut T,
+    nodes: usize,
+}
+
+impl<'a, T> Search<'a, T> {
+    // returns the value of `state` and the index of its best move. a value not above `alpha` is only an upper bound
+    // of the true value, and a value not below `beta` only a lower bound
+    fn negamax<S>(
+        &mut self,
+        state: &S,
+        depth: usize,
+        mut alpha: f64,
+        mut beta: f64,
+    ) -> (f64, Option<usize>)
+    where
+        S: GameState,
+        T: Transpositions<S>,
+    {
+        self.nodes += 1;
+        let window = alpha;
+
+        let entry = self.table.get(state);
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth) {
+            match entry.bound {
+                Bound::Exact => return (entry.value, entry.best),
+                Bound::Lower => alpha = alpha.max(entry.value),
+                Bound::Upper => beta = beta.min(entry.value),
+            }
+            if alpha >= beta {
+                return (entry.value, entry.best);
+            }
+        }
+
+        let moves = state.moves();
+        if depth == 0 || moves.is_empty() {
+            return (state.evaluate(), None);
+        }
+
+        // the best move of an earlier search is tried first
+        let mut order: Vec<usize> = (0..moves.len()).collect();
+        if let Some(first) = entry.and_then(|entry| entry.best) {
+            order.swap(0, first);
+        }
+
+        let mut best = (f64::NEG_INFINITY, None);
+        for index in order {
+            let value = -self
+                .negamax(&state.apply(&moves[index]), depth - 1, -beta, -alpha)
+                .0;
+            if value > best.0 {
+                best = (value, Some(index));
+            }
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best.0 <= window {
+            Bound::Upper
+        } else if best.0 >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(
+            state,
+            Entry {
+                depth,
+                value: best.0,
+                bound,
+                best: best.1,
+            },
+        );
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // tic-tac-toe, cells hold 0 when empty and 1 or 2 for the players
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct TicTacToe {
+        cells: [u8; 9],
+        turn: u8,
+    }
+
+    impl TicTacToe {
+        fn init() -> TicTacToe {
+            TicTacToe {
+                cells: [0; 9],
+                turn: 1,
+            }
+        }
+
+        fn play(cells: &[usize]) -> TicTacToe {
+            cells
+                .iter()
+                .fold(TicTacToe::init(), |state, cell| state.apply(cell))
+        }
+
+        fn winner(&self) -> Option<u8> {
+            const LINES: [[usize; 3]; 8] = [
+                [0, 1, 2],
+                [3, 4, 5],
+                [6, 7, 8],
+                [0, 3, 6],
+                [1, 4, 7],
+                [2, 5, 8],
+                [0, 4, 8],
+                [2, 4, 6],
+            ];
+            LINES
+                .iter()
+                .map(|line| self.cells[line[0]])
+                .zip(LINES.iter())
+                .find(|&(player, line)| {
+                    player != 0 && line.iter().all(|&cell| self.cells[cell] == player)
+                })
+                .map(|(player, _)| player)
+        }
+    }
+
+    impl GameState for TicTacToe {
+        type Move = usize;
+
+        fn moves(&self) -> Vec<usize> {
+            if self.winner().is_some() {
+                return Vec::new();
+            }
+            (0..9).filter(|&cell| self.cells[cell] == 0).collect()
+        }
+
+        fn apply(&self, cell: &usize) -> TicTacToe {
+            let mut next = self.clone();
+            next.cells[*cell] = self.turn;
+            next.turn = 3 - self.turn;
+            next
+        }
+
+        fn evaluate(&self) -> f64 {
+            match self.winner() {
+                Some(player) if player == self.turn => 1.0,
+                Some(_) => -1.0,
+                None => 0.0,
+            }
+        }
+    }
+
+    #[test]
+    fn algo_game_tic_tac_toe_is_a_draw() {
+        let full = minimax(&TicTacToe::init(), 9);
+        let pruned = alpha_beta(&TicTacToe::init(), 9);
+        let mut table = TranspositionTable::init();
+        let cached = alpha_beta_with_table(&TicTacToe::init(), 9, &mut table);
+
+        assert_eq!(full.value, 0.0);
+        assert_eq!(full.nodes, 549946);
+        assert_eq!(pruned.value, 0.0);
+        assert_eq!(cached.value, 0.0);
+        assert!(pruned.nodes < full.nodes / 10);
+        assert!(cached.nodes < pruned.nodes);
+        assert!(table.size() < 5478);
+    }
+
+    #[test]
+    fn algo_game_searches_agree() {
+        // every position after two moves
+        for first in 0..9 {
+            for second in (0..9).filter(|&cell| cell != first) {
+                let state = TicTacToe::play(&[first, second]);
+                for depth in 0..=7 {
+                    let full = minimax(&state, depth);
+                    let pruned = alpha_beta(&state, depth);
+                    let cached =
+                        alpha_beta_with_table(&state, depth, &mut TranspositionTable::init());
+                    assert_eq!(pruned.value, full.value);
+                    assert_eq!(cached.value, full.value);
+
+                    // the best move reaches the value
+                    if let Some(best) = cached.best {
+                        assert_eq!(-minimax(&state.apply(&best), depth - 1).value, full.value);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn algo_game_table_keeps_deeper_values() {
+        let state = TicTacToe::play(&[4]);
+        let mut table = TranspositionTable::init();
+        let deep = alpha_beta_with_table(&state, 8, &mut table);
+        let stored = table.size();
+
+        // the shallow search reuses the value of the deep one instead of stopping early
+        let shallow = alpha_beta_with_table(&state, 2, &mut table);
+        assert_eq!(shallow.value, deep.value);
+        assert_eq!(shallow.nodes, 1);
+        assert_eq!(table.size(), stored);
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(
+            alpha_beta_with_table(&state, 2, &mut table),
+            alpha_beta(&state, 2)
+        );
+    }
+
+    #[test]
+    fn algo_game_takes_win_and_blocks() {
+        // x to move wins at 2
+        let state = TicTacToe::play(&[0, 3, 1, 4]);
+        let evaluation = alpha_beta(&state, 9);
+        assert_eq!(evaluation.value, 1.0);
+        assert_eq!(evaluation.best, Some(2));
+
+        // o to move must block at 2 and draws
+        let state = TicTacToe::play(&[0, 4, 1]);
+        let evaluation = minimax(&state, 9);
+        assert_eq!(evaluation.value, 0.0);
+        assert_eq!(evaluation.best, Some(2));
+    }
+
+    #[test]
+    fn algo_game_depth_zero_evaluates() {
+        let state = TicTacToe::play(&[0, 3, 1, 4, 2]);
+        let evaluation = alpha_beta(&state, 0);
+
+        assert_eq!(evaluation.value, -1.0);
+        assert_eq!(evaluation.best, None);
+        assert_eq!(evaluation.nodes, 1);
+        assert_eq!(minimax(&state, 5), evaluation);
+    }
+}
@@ -0,0 +1,54 @@
+/// A position of a two player, zero-sum game where the players take turns. Values are always seen from the player
+/// to move, so the value of a position for one player is the negated value for the other.
+///
+/// # Examples
+/// ```
+/// use rudac::algo::game::GameState;
+///
+/// // players take 1 to 3 stones in turns, the player who can not move loses
+/// #[derive(Clone, PartialEq, Eq, Hash)]
+/// struct Pile(u32);
+///
+/// impl GameState for Pile {
+///     type Move = u32;
+///
+///     fn moves(&self) -> Vec<u32> {
+///         (1..=self.0.min(3)).collect()
+///     }
+///
+///     fn apply(&self, taken: &u32) -> Pile {
+///         Pile(self.0 - taken)
+///     }
+///
+///     fn evaluate(&self) -> f64 {
+///         if self.0 == 0 { -1.0 } else { 0.0 }
+///     }
+/// }
+/// ```
+pub trait GameState: Clone {
+    /// A move of the player to move
+    type Move: Clone;
+
+    /// Returns the legal moves of the player to move, empty if the game is over
+    fn moves(&self) -> Vec<Self::Move>;
+
+    /// Returns the position after the player to move plays `mv`
+    fn apply(&self, mv: &Self::Move) -> Self;
+
+    /// Returns the value of the position for the player to move, larger is better. For a position without moves
+    /// this is the outcome of the game, for other positions an estimate used when the search stops early
+    fn evaluate(&self) -> f64;
+}
+
+/// Result of a game tree search
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation<M> {
+    /// value of the position for the player to move
+    pub value: f64,
+
+    /// a move reaching the value, None if there are no moves or the search depth is zero
+    pub best: Option<M>,
+
+    /// number of visited positions
+    pub nodes: usize,
+}
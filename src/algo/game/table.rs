@@ -0,0 +1,113 @@
+use super::GameState;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// how the stored value relates to the true value of a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Bound {
+    Exact,
+    // the true value is at least the stored one, the search was cut off
+    Lower,
+    // the true value is at most the stored one, no move reached the window
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Entry {
+    pub(super) depth: usize,
+    pub(super) value: f64,
+    pub(super) bound: Bound,
+
+    // index of the best move among the moves of the position
+    pub(super) best: Option<usize>,
+}
+
+/// Values of positions found by `alpha_beta_with_table`, so positions reached by different orders of moves are
+/// searched once. A stored value is reused by searches that are not deeper than the one that stored it, so a value
+/// may come from a deeper search than asked for, and the stored best move is tried first by deeper ones. Keeping the
+/// table between searches of increasing depth speeds up iterative deepening
+///
+/// # Examples
+/// ```
+/// use rudac::algo::game::{alpha_beta_with_table, GameState, TranspositionTable};
+///
+/// // players take 1 or 2 stones in turns, the player who can not move loses
+/// #[derive(Clone, PartialEq, Eq, Hash)]
+/// struct Pile(u32);
+///
+/// impl GameState for Pile {
+///     type Move = u32;
+///
+///     fn moves(&self) -> Vec<u32> {
+///         (1..=self.0.min(2)).collect()
+///     }
+///
+///     fn apply(&self, taken: &u32) -> Pile {
+///         Pile(self.0 - taken)
+///     }
+///
+///     fn evaluate(&self) -> f64 {
+///         if self.0 == 0 { -1.0 } else { 0.0 }
+///     }
+/// }
+///
+/// let mut table = TranspositionTable::init();
+/// for depth in 1..=20 {
+///     alpha_beta_with_table(&Pile(20), depth, &mut table);
+/// }
+/// let evaluation = alpha_beta_with_table(&Pile(20), 20, &mut table);
+///
+/// // piles that are multiples of 3 are lost for the player to move
+/// assert_eq!(evaluation.value, 1.0);
+/// assert_eq!(evaluation.best, Some(2));
+/// assert_eq!(evaluation.nodes, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TranspositionTable<S> {
+    entries: HashMap<S, Entry>,
+}
+
+impl<S: GameState + Eq + Hash> TranspositionTable<S> {
+    /// Initializes an empty table
+    pub fn init() -> TranspositionTable<S> {
+        TranspositionTable {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns number of stored positions
+    pub fn size(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no position is stored
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every stored position
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(super) fn get(&self, state: &S) -> Option<Entry> {
+        self.entries.get(state).copied()
+    }
+
+    // keeps the entry of the deeper search
+    pub(super) fn store(&mut self, state: &S, entry: Entry) {
+        match self.entries.get_mut(state) {
+            Some(stored) if stored.depth > entry.depth => (),
+            Some(stored) => *stored = entry,
+            None => {
+                self.entries.insert(state.clone(), entry);
+            }
+        }
+    }
+}
+
+impl<S: GameState + Eq + Hash> Default for TranspositionTable<S> {
+    fn default() -> Self {
+        TranspositionTable::init()
+    }
+}
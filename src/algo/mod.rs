@@ -1,4 +1,13 @@
+pub mod backtrack;
+pub mod dp;
 pub mod find;
+pub mod game;
+pub mod optimize;
 pub mod transform;
 pub mod sort;
-pub mod search;
\ No newline at end of file
+pub mod search;
+pub mod sequence;
+pub mod string;
+
+#[cfg(test)]
+mod testing;
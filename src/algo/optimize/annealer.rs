@@ -0,0 +1,195 @@
+use crate::algo::optimize::{CoolingSchedule, OptimizationProblem, Optimum};
+use crate::util::Rng;
+
+/// Simulated annealing. A neighbor that lowers the energy is always accepted, and a neighbor that raises it by `delta`
+/// is accepted with probability `exp(-delta / temperature)`, so the search can escape local minima while it is hot
+/// and settles down as the schedule cools.
+///
+/// # Examples
+/// ```
+/// use rudac::algo::optimize::{Annealer, Exponential, OptimizationProblem};
+/// use rudac::util::{Rng, SplitMix64};
+///
+/// // find a permutation of 0..8 sorted in ascending order, energy is the number of inversions
+/// struct Sorting;
+///
+/// impl OptimizationProblem for Sorting {
+///     type State = Vec<usize>;
+///
+///     fn energy(&self, state: &Vec<usize>) -> f64 {
+///         let mut inversions = 0;
+///         for i in 0..state.len() {
+///             for j in i + 1..state.len() {
+///                 if state[i] > state[j] {
+///                     inversions += 1;
+///                 }
+///             }
+///         }
+///         inversions as f64
+///     }
+///
+///     fn neighbor<R: Rng>(&self, state: &Vec<usize>, rng: &mut R) -> Vec<usize> {
+///         let mut next = state.clone();
+///         let i = rng.next_below(next.len() as u64) as usize;
+///         let j = rng.next_below(next.len() as u64) as usize;
+///         next.swap(i, j);
+///         next
+///     }
+/// }
+///
+/// let annealer = Annealer::init(Exponential::between(5.0, 0.01, 5000), 5000);
+/// let optimum = annealer.run(&Sorting, vec![7, 6, 5, 4, 3, 2, 1, 0], &mut SplitMix64::init(1));
+///
+/// assert_eq!(optimum.energy, 0.0);
+/// assert_eq!(optimum.state, (0..8).collect::<Vec<usize>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Annealer<S: CoolingSchedule> {
+    schedule: S,
+    steps: usize,
+}
+
+impl<S: CoolingSchedule> Annealer<S> {
+    /// Initializes an annealer
+    ///
+    /// # Arguments
+    /// * `schedule`: the cooling schedule
+    /// * `steps`: number of evaluated neighbors of a run
+    pub fn init(schedule: S, steps: usize) -> Annealer<S> {
+        Annealer { schedule, steps }
+    }
+
+    /// Returns the cooling schedule
+    pub fn schedule(&self) -> &S {
+        &self.schedule
+    }
+
+    /// Returns number of steps of a run
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Runs simulated annealing from `initial` and returns the best state visited
+    ///
+    /// # Arguments
+    /// * `problem`: the problem
+    /// * `initial`: the starting state
+    /// * `rng`: source of randomness
+    pub fn run<P, R>(&self, problem: &P, initial: P::State, rng: &mut R) -> Optimum<P::State>
+    where
+        P: OptimizationProblem,
+        R: Rng,
+    {
+        let mut current_energy = problem.energy(&initial);
+        let mut current = initial;
+        let mut optimum = Optimum {
+            state: current.clone(),
+            energy: current_energy,
+            steps: 0,
+            accepted: 0,
+        };
+
+        for step in 0..self.steps {
+            let candidate = problem.neighbor(&current, rng);
+            let energy = problem.energy(&candidate);
+            let delta = energy - current_energy;
+            optimum.steps += 1;
+
+            let accept = delta <= 0.0 || {
+                let temperature = self.schedule.temperature(step, self.steps);
+                temperature > 0.0 && rng.next_f64() < (-delta / temperature).exp()
+            };
+            if !accept {
+                continue;
+            }
+
+            current = candidate;
+            current_energy = energy;
+            optimum.accepted += 1;
+            if current_energy < optimum.energy {
+                optimum.state = current.clone();
+                optimum.energy = current_energy;
+            }
+        }
+
+        optimum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::optimize::{HillClimber, Linear, Logarithmic};
+    use crate::util::SplitMix64;
+
+    // traveling salesman tour over points of a circle, optimal tour visits them in angular order
+    struct Tour {
+        points: Vec<(f64, f64)>,
+    }
+
+    impl Tour {
+        fn circle(n: usize) -> Tour {
+            // points are listed in a scrambled order
+            let points = (0..n)
+                .map(|i| {
+                    let angle = ((i * 7) % n) as f64 / n as f64 * std::f64::consts::PI * 2.0;
+                    (angle.cos(), angle.sin())
+                })
+                .collect();
+            Tour { points }
+        }
+    }
+
+    impl OptimizationProblem for Tour {
+        type State = Vec<usize>;
+
+        fn energy(&self, tour: &Vec<usize>) -> f64 {
+            (0..tour.len())
+                .map(|i| {
+                    let a = self.points[tour[i]];
+                    let b = self.points[tour[(i + 1) % tour.len()]];
+                    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+                })
+                .sum()
+        }
+
+        // reverses a random segment of the tour(2-opt move)
+        fn neighbor<R: Rng>(&self, tour: &Vec<usize>, rng: &mut R) -> Vec<usize> {
+            let mut next = tour.clone();
+            let i = rng.next_below(tour.len() as u64) as usize;
+            let j = rng.next_below(tour.len() as u64) as usize;
+            next[std::cmp::min(i, j)..=std::cmp::max(i, j)].reverse();
+            next
+        }
+    }
+
+    #[test]
+    fn algo_optimize_annealer_tour() {
+        let n = 20;
+        let problem = Tour::circle(n);
+        let optimal = 2.0 * n as f64 * (std::f64::consts::PI / n as f64).sin();
+        let initial: Vec<usize> = (0..n).collect();
+
+        let annealer = Annealer::init(Linear::init(1.0), 20000);
+        let optimum = annealer.run(&problem, initial.clone(), &mut SplitMix64::init(7));
+
+        assert!((optimum.energy - optimal).abs() < 1e-9);
+        assert!((problem.energy(&optimum.state) - optimum.energy).abs() < 1e-12);
+        assert_eq!(optimum.steps, 20000);
+        assert!(optimum.accepted > 0);
+
+        let hill = HillClimber::init(20000, 2000).run(&problem, initial, &mut SplitMix64::init(7));
+        assert!(hill.energy >= optimum.energy - 1e-9);
+    }
+
+    #[test]
+    fn algo_optimize_annealer_deterministic() {
+        let problem = Tour::circle(10);
+        let annealer = Annealer::init(Logarithmic::init(0.5), 1000);
+
+        let a = annealer.run(&problem, (0..10).collect(), &mut SplitMix64::init(3));
+        let b = annealer.run(&problem, (0..10).collect(), &mut SplitMix64::init(3));
+
+        assert_eq!(a, b);
+    }
+}
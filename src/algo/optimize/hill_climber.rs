@@ -0,0 +1,188 @@
+use crate::algo::optimize::{OptimizationProblem, Optimum};
+use crate::util::Rng;
+
+/// Stochastic hill climbing. A random neighbor replaces the current state only if it has a lower energy,
+/// and the search stops after `patience` consecutive neighbors fail to improve it, or after `max_steps` neighbors.
+/// It is fast but gets stuck in local minima, which restarts from different states can mitigate.
+///
+/// # Examples
+/// ```
+/// use rudac::algo::optimize::{HillClimber, OptimizationProblem};
+/// use rudac::util::{Rng, SplitMix64};
+///
+/// // minimize (x - 7)^2 over integers
+/// struct Parabola;
+///
+/// impl OptimizationProblem for Parabola {
+///     type State = i64;
+///
+///     fn energy(&self, x: &i64) -> f64 {
+///         ((x - 7) * (x - 7)) as f64
+///     }
+///
+///     fn neighbor<R: Rng>(&self, x: &i64, rng: &mut R) -> i64 {
+///         if rng.next_below(2) == 0 { x - 1 } else { x + 1 }
+///     }
+/// }
+///
+/// let climber = HillClimber::init(1000, 50);
+/// let optimum = climber.run(&Parabola, -20, &mut SplitMix64::init(0));
+///
+/// assert_eq!(optimum.state, 7);
+/// assert!(optimum.steps < 1000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HillClimber {
+    max_steps: usize,
+    patience: usize,
+}
+
+impl HillClimber {
+    /// Initializes a hill climber
+    ///
+    /// # Arguments
+    /// * `max_steps`: largest number of evaluated neighbors of a run
+    /// * `patience`: number of consecutive non-improving neighbors that ends a run
+    pub fn init(max_steps: usize, patience: usize) -> HillClimber {
+        HillClimber {
+            max_steps,
+            patience,
+        }
+    }
+
+    /// Returns largest number of steps of a run
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// Returns number of consecutive non-improving neighbors that ends a run
+    pub fn patience(&self) -> usize {
+        self.patience
+    }
+
+    /// Climbs down from `initial` and returns the final state
+    ///
+    /// # Arguments
+    /// * `problem`: the problem
+    /// * `initial`: the starting state
+    /// * `rng`: source of randomness
+    pub fn run<P, R>(&self, problem: &P, initial: P::State, rng: &mut R) -> Optimum<P::State>
+    where
+        P: OptimizationProblem,
+        R: Rng,
+    {
+        let mut optimum = Optimum {
+            energy: problem.energy(&initial),
+            state: initial,
+            steps: 0,
+            accepted: 0,
+        };
+
+        let mut failures = 0;
+        while optimum.steps < self.max_steps && failures < self.patience {
+            let candidate = problem.neighbor(&optimum.state, rng);
+            let energy = problem.energy(&candidate);
+            optimum.steps += 1;
+
+            if energy < optimum.energy {
+                optimum.state = candidate;
+                optimum.energy = energy;
+                optimum.accepted += 1;
+                failures = 0;
+            } else {
+                failures += 1;
+            }
+        }
+
+        optimum
+    }
+
+    /// Runs the climber from every state of `initials` and returns the best result.
+    /// Statistics of the result add up all runs. Returns `None` if `initials` is empty
+    ///
+    /// # Arguments
+    /// * `problem`: the problem
+    /// * `initials`: the starting states
+    /// * `rng`: source of randomness
+    pub fn run_restarts<P, R, I>(
+        &self,
+        problem: &P,
+        initials: I,
+        rng: &mut R,
+    ) -> Option<Optimum<P::State>>
+    where
+        P: OptimizationProblem,
+        R: Rng,
+        I: IntoIterator<Item = P::State>,
+    {
+        let mut best: Option<Optimum<P::State>> = None;
+
+        for initial in initials {
+            let result = self.run(problem, initial, rng);
+            best = Some(match best {
+                None => result,
+                Some(best) => {
+                    let (steps, accepted) =
+                        (best.steps + result.steps, best.accepted + result.accepted);
+                    let mut winner = if result.energy < best.energy {
+                        result
+                    } else {
+                        best
+                    };
+                    winner.steps = steps;
+                    winner.accepted = accepted;
+                    winner
+                }
+            });
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+
+    // a function of integers with a local minimum at 0 and the global minimum at 10
+    struct TwoValleys;
+
+    impl OptimizationProblem for TwoValleys {
+        type State = i64;
+
+        fn energy(&self, x: &i64) -> f64 {
+            std::cmp::min(x * x + 5, (x - 10) * (x - 10)) as f64
+        }
+
+        fn neighbor<R: Rng>(&self, x: &i64, rng: &mut R) -> i64 {
+            x + rng.next_below(3) as i64 - 1
+        }
+    }
+
+    #[test]
+    fn algo_optimize_hill_climber_local_minimum() {
+        let climber = HillClimber::init(10000, 100);
+        let mut rng = SplitMix64::init(5);
+
+        assert_eq!(climber.run(&TwoValleys, -6, &mut rng).state, 0);
+        assert_eq!(climber.run(&TwoValleys, 14, &mut rng).state, 10);
+
+        let best = climber
+            .run_restarts(&TwoValleys, vec![-6, 3, 14], &mut rng)
+            .unwrap();
+        assert_eq!(best.state, 10);
+        assert_eq!(best.energy, 0.0);
+        assert!(best.steps >= 300);
+
+        assert_eq!(climber.run_restarts(&TwoValleys, vec![], &mut rng), None);
+    }
+
+    #[test]
+    fn algo_optimize_hill_climber_max_steps() {
+        let optimum = HillClimber::init(5, 100).run(&TwoValleys, 100, &mut SplitMix64::init(1));
+
+        assert_eq!(optimum.steps, 5);
+        assert!(optimum.state > 90);
+    }
+}
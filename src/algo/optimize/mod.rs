@@ -0,0 +1,13 @@
+mod annealer;
+mod hill_climber;
+mod problem;
+mod schedule;
+
+pub use annealer::Annealer;
+pub use hill_climber::HillClimber;
+pub use problem::OptimizationProblem;
+pub use problem::Optimum;
+pub use schedule::CoolingSchedule;
+pub use schedule::Exponential;
+pub use schedule::Linear;
+pub use schedule::Logarithmic;
@@ -0,0 +1,51 @@
+use crate::util::Rng;
+
+/// An optimization problem for local search. Optimizers look for the state with the lowest energy
+/// by moving between neighboring states.
+///
+/// # Examples
+/// ```
+/// use rudac::algo::optimize::OptimizationProblem;
+/// use rudac::util::Rng;
+///
+/// // minimize (x - 7)^2 over integers
+/// struct Parabola;
+///
+/// impl OptimizationProblem for Parabola {
+///     type State = i64;
+///
+///     fn energy(&self, x: &i64) -> f64 {
+///         ((x - 7) * (x - 7)) as f64
+///     }
+///
+///     fn neighbor<R: Rng>(&self, x: &i64, rng: &mut R) -> i64 {
+///         if rng.next_below(2) == 0 { x - 1 } else { x + 1 }
+///     }
+/// }
+/// ```
+pub trait OptimizationProblem {
+    /// A candidate solution
+    type State: Clone;
+
+    /// Returns energy of `state`, which is minimized
+    fn energy(&self, state: &Self::State) -> f64;
+
+    /// Returns a random state close to `state`
+    fn neighbor<R: Rng>(&self, state: &Self::State, rng: &mut R) -> Self::State;
+}
+
+/// Result of a local search
+#[derive(Debug, Clone, PartialEq)]
+pub struct Optimum<T> {
+    /// the best state found
+    pub state: T,
+
+    /// energy of the best state
+    pub energy: f64,
+
+    /// number of evaluated neighbors
+    pub steps: usize,
+
+    /// number of neighbors that replaced the current state
+    pub accepted: usize,
+}
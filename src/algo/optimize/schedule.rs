@@ -0,0 +1,145 @@
+/// Temperature of simulated annealing as a function of progress
+pub trait CoolingSchedule {
+    /// Returns temperature at `step` of a run with `steps` steps in total
+    fn temperature(&self, step: usize, steps: usize) -> f64;
+}
+
+/// Exponential cooling: `initial * alpha^step`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    initial: f64,
+    alpha: f64,
+}
+
+impl Exponential {
+    /// Initializes the schedule
+    ///
+    /// # Arguments
+    /// * `initial`: initial temperature
+    /// * `alpha`: cooling factor of every step
+    ///
+    /// # Panics
+    /// * panics if `initial` is not positive or `alpha` is not in (0, 1]
+    pub fn init(initial: f64, alpha: f64) -> Exponential {
+        if initial.is_nan() || initial <= 0.0 {
+            panic!("Initial temperature must be positive");
+        }
+        if alpha.is_nan() || alpha <= 0.0 || alpha > 1.0 {
+            panic!("Cooling factor must be in range (0, 1]");
+        }
+
+        Exponential { initial, alpha }
+    }
+
+    /// Initializes a schedule that cools from `initial` to `last` over `steps` steps
+    ///
+    /// # Panics
+    /// * panics if `initial` or `last` is not positive, or `last` is greater than `initial`
+    pub fn between(initial: f64, last: f64, steps: usize) -> Exponential {
+        if last.is_nan() || last <= 0.0 || last > initial {
+            panic!(
+                "Final temperature must be positive and not greater than the initial temperature"
+            );
+        }
+
+        let steps = std::cmp::max(steps, 2) - 1;
+        Exponential::init(initial, (last / initial).powf(1.0 / steps as f64))
+    }
+}
+
+impl CoolingSchedule for Exponential {
+    fn temperature(&self, step: usize, _steps: usize) -> f64 {
+        self.initial * self.alpha.powi(step as i32)
+    }
+}
+
+/// Linear cooling from an initial temperature to zero at the last step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Linear {
+    initial: f64,
+}
+
+impl Linear {
+    /// Initializes the schedule
+    ///
+    /// # Panics
+    /// * panics if `initial` is not positive
+    pub fn init(initial: f64) -> Linear {
+        if initial.is_nan() || initial <= 0.0 {
+            panic!("Initial temperature must be positive");
+        }
+
+        Linear { initial }
+    }
+}
+
+impl CoolingSchedule for Linear {
+    fn temperature(&self, step: usize, steps: usize) -> f64 {
+        if steps == 0 {
+            return 0.0;
+        }
+
+        self.initial * (1.0 - step as f64 / steps as f64)
+    }
+}
+
+/// Logarithmic cooling: `c / ln(step + 2)`. It cools slowly, which is the classic schedule with convergence guarantees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Logarithmic {
+    c: f64,
+}
+
+impl Logarithmic {
+    /// Initializes the schedule
+    ///
+    /// # Panics
+    /// * panics if `c` is not positive
+    pub fn init(c: f64) -> Logarithmic {
+        if c.is_nan() || c <= 0.0 {
+            panic!("Initial temperature must be positive");
+        }
+
+        Logarithmic { c }
+    }
+}
+
+impl CoolingSchedule for Logarithmic {
+    fn temperature(&self, step: usize, _steps: usize) -> f64 {
+        self.c / ((step + 2) as f64).ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn algo_optimize_schedule_decreasing() {
+        let schedules: Vec<Box<dyn CoolingSchedule>> = vec![
+            Box::new(Exponential::init(10.0, 0.9)),
+            Box::new(Linear::init(10.0)),
+            Box::new(Logarithmic::init(10.0)),
+        ];
+
+        for schedule in schedules.iter() {
+            for step in 0..99 {
+                assert!(schedule.temperature(step, 100) > schedule.temperature(step + 1, 100));
+            }
+        }
+        assert_eq!(Linear::init(4.0).temperature(50, 100), 2.0);
+    }
+
+    #[test]
+    fn algo_optimize_schedule_between() {
+        let schedule = Exponential::between(100.0, 0.01, 1000);
+
+        assert!((schedule.temperature(0, 1000) - 100.0).abs() < 1e-9);
+        assert!((schedule.temperature(999, 1000) - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cooling factor must be in range (0, 1]")]
+    fn algo_optimize_schedule_invalid_alpha() {
+        Exponential::init(1.0, 1.5);
+    }
+}
@@ -0,0 +1,285 @@
+use super::StatePath;
+use crate::graph::Weight;
+use crate::heap::MinMax;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// marks a state without parent
+pub(super) const NIL: usize = usize::MAX;
+
+/// Returns a cheapest path from `start` to a goal state found by A* search, `None` if no goal state is reachable.
+/// The graph is given implicitly by `successors`, so states are discovered while searching and only the visited
+/// ones are stored. States are expanded in the order of their cost from `start` plus the estimated cost to a goal.
+/// The estimate must be admissible, never larger than the cost of a cheapest path to a goal, for the path to be
+/// cheapest. A consistent estimate, never larger than the cost of a step plus the estimate after it, also expands
+/// every state at most once
+/// * Complexity: O(E log V) over the V visited states and E generated successors, for a consistent estimate
+///
+/// # Arguments
+/// * `start`: state the path starts at
+/// * `successors`: returns the states reachable in one step from a state, together with the costs of the steps
+/// * `heuristic`: estimated cost from a state to the nearest goal state
+/// * `goal`: returns true if a state is a goal
+///
+/// # Panics
+/// * panics if a step has a negative cost
+///
+/// # Examples
+/// ```
+/// use rudac::algo::search::astar_implicit;
+///
+/// // a knight on an unbounded board, moving from (0, 0) to (7, 5)
+/// let moves = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+/// let path = astar_implicit(
+///     (0i64, 0i64),
+///     |&(x, y)| moves.iter().map(move |(dx, dy)| ((x + dx, y + dy), 1u32)),
+///     // a move changes the manhattan distance by at most 3
+///     |&(x, y)| (((7 - x).abs() + (5 - y).abs()) as u32 + 2) / 3,
+///     |&state| state == (7, 5),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path.cost, 4);
+/// assert_eq!(path.states.first(), Some(&(0, 0)));
+/// assert_eq!(path.states.last(), Some(&(7, 5)));
+/// ```
+pub fn astar_implicit<S, W, I, N, H, G>(
+    start: S,
+    mut successors: N,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<StatePath<S, W>>
+where
+    S: Clone + Eq + Hash,
+    W: Weight,
+    I: IntoIterator<Item = (S, W)>,
+    N: FnMut(&S) -> I,
+    H: FnMut(&S) -> W,
+    G: FnMut(&S) -> bool,
+{
+    // visited states are numbered in the order of their discovery
+    let mut indices = HashMap::new();
+    let mut states = vec![start.clone()];
+    let mut costs = vec![W::zero()];
+    let mut estimates = vec![heuristic(&start)];
+    let mut parents = vec![NIL];
+    indices.insert(start, 0);
+
+    // stale entries of improved states are skipped when popped
+    let mut heap = MinMax::init();
+    heap.push((estimates[0], W::zero(), 0));
+
+    while let Some((_, cost, index)) = heap.pop_min() {
+        if cost > costs[index] {
+            continue;
+        }
+        if goal(&states[index]) {
+            return Some(StatePath {
+                states: trace(&states, &parents, index),
+                cost,
+            });
+        }
+
+        for (successor, step) in successors(&states[index]) {
+            if step < W::zero() {
+                panic!("Step costs must not be negative");
+            }
+            let candidate = cost + step;
+            let next = match indices.get(&successor) {
+                Some(&next) if candidate >= costs[next] => continue,
+                Some(&next) => {
+                    costs[next] = candidate;
+                    next
+                }
+                None => {
+                    let next = states.len();
+                    estimates.push(heuristic(&successor));
+                    costs.push(candidate);
+                    parents.push(NIL);
+                    states.push(successor.clone());
+                    indices.insert(successor, next);
+                    next
+                }
+            };
+            parents[next] = index;
+            heap.push((candidate + estimates[next], candidate, next));
+        }
+    }
+
+    None
+}
+
+// returns the states from the start to the state numbered `index`
+pub(super) fn trace<S: Clone>(states: &[S], parents: &[usize], mut index: usize) -> Vec<S> {
+    let mut path = vec![states[index].clone()];
+    while parents[index] != NIL {
+        index = parents[index];
+        path.push(states[index].clone());
+    }
+    path.reverse();
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::shortest_path::dijkstra;
+    use crate::graph::Graph;
+    use crate::util::{Rng, SplitMix64};
+
+    // the sliding tiles of a 3x3 puzzle, 0 being the blank
+    fn puzzle_moves(tiles: &[u8; 9]) -> Vec<([u8; 9], u32)> {
+        let blank = tiles.iter().position(|&tile| tile == 0).unwrap();
+        let (x, y) = (blank % 3, blank / 3);
+        let mut moves = Vec::new();
+        let mut slide = |other: usize| {
+            let mut next = *tiles;
+            next.swap(blank, other);
+            moves.push((next, 1));
+        };
+        if x > 0 {
+            slide(blank - 1);
+        }
+        if x < 2 {
+            slide(blank + 1);
+        }
+        if y > 0 {
+            slide(blank - 3);
+        }
+        if y < 2 {
+            slide(blank + 3);
+        }
+
+        moves
+    }
+
+    fn puzzle_manhattan(tiles: &[u8; 9]) -> u32 {
+        tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, &tile)| tile != 0)
+            .map(|(position, &tile)| {
+                let home = tile as usize - 1;
+                ((position % 3).abs_diff(home % 3) + (position / 3).abs_diff(home / 3)) as u32
+            })
+            .sum()
+    }
+
+    #[test]
+    fn algo_search_astar_puzzle() {
+        const SOLVED: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+        let start = [8, 6, 7, 2, 5, 4, 3, 0, 1];
+
+        let path = astar_implicit(start, puzzle_moves, puzzle_manhattan, |tiles| {
+            *tiles == SOLVED
+        })
+        .unwrap();
+        let blind = astar_implicit(start, puzzle_moves, |_| 0, |tiles| *tiles == SOLVED).unwrap();
+
+        // one of the hardest positions
+        assert_eq!(path.cost, 31);
+        assert_eq!(blind.cost, 31);
+        assert_eq!(path.states.len(), 32);
+        assert_eq!(path.states[0], start);
+        for pair in path.states.windows(2) {
+            assert!(puzzle_moves(&pair[0]).contains(&(pair[1], 1)));
+        }
+    }
+
+    #[test]
+    fn algo_search_astar_unsolvable() {
+        const SOLVED: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+        // swapping two tiles makes the puzzle unsolvable
+        let start = [2, 1, 3, 4, 5, 6, 7, 8, 0];
+
+        let path = astar_implicit(start, puzzle_moves, puzzle_manhattan, |tiles| {
+            *tiles == SOLVED
+        });
+
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn algo_search_astar_matches_dijkstra() {
+        let mut rng = SplitMix64::init(8);
+        let mut graph = Graph::init_directed(80);
+        for _ in 0..300 {
+            let from = rng.next_below(80) as usize;
+            let to = rng.next_below(80) as usize;
+            graph.add_edge(from, to, rng.next_below(30));
+        }
+        let neighbors = |&vertex: &usize| {
+            graph
+                .neighbors(vertex)
+                .iter()
+                .map(|edge| (edge.to, edge.weight))
+                .collect::<Vec<_>>()
+        };
+
+        for source in 0..80 {
+            let paths = dijkstra(&graph, source);
+            for target in 0..80 {
+                let path = astar_implicit(source, neighbors, |_| 0, |&vertex| vertex == target);
+                assert_eq!(path.as_ref().map(|path| path.cost), paths.distance(target));
+                if let Some(path) = path {
+                    let cost: u64 = path
+                        .states
+                        .windows(2)
+                        .map(|pair| {
+                            graph
+                                .neighbors(pair[0])
+                                .iter()
+                                .filter(|edge| edge.to == pair[1])
+                                .map(|edge| edge.weight)
+                                .min()
+                                .unwrap()
+                        })
+                        .sum();
+                    assert_eq!(cost, path.cost);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn algo_search_astar_inconsistent_heuristic() {
+        // 0 -> 1 -> 3 costs 2 and 0 -> 2 -> 3 costs 5, the admissible estimate at 1 is too large to be consistent
+        let edges = |vertex: &u32| match vertex {
+            0 => vec![(1, 1), (2, 1)],
+            1 => vec![(3, 1)],
+            2 => vec![(3, 4)],
+            3 => vec![(4, 10)],
+            _ => vec![],
+        };
+        let heuristic = |vertex: &u32| match vertex {
+            1 => 11,
+            3 => 10,
+            _ => 0,
+        };
+
+        let path = astar_implicit(0, edges, heuristic, |&vertex| vertex == 4).unwrap();
+
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.states, vec![0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn algo_search_astar_start_is_goal() {
+        let path = astar_implicit(5u8, |_| vec![(6u8, 1u8)], |_| 0, |_| true).unwrap();
+
+        assert_eq!(path.states, vec![5]);
+        assert_eq!(path.cost, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Step costs must not be negative")]
+    fn algo_search_astar_panic_negative_cost() {
+        astar_implicit(
+            0,
+            |&state| vec![(state + 1, -1)],
+            |_| 0,
+            |&state| state == 3,
+        );
+    }
+}
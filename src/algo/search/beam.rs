@@ -0,0 +1,213 @@
+use super::astar::{trace, NIL};
+use super::StatePath;
+use crate::graph::Weight;
+use crate::heap::MinMax;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Returns a path from `start` to a goal state found by beam search, `None` if the beam runs empty first.
+/// The search goes breadth first, one step at a time, but keeps only the `width` successors with the lowest
+/// estimated cost to a goal in the beam of the next step, so it needs little memory and time even in huge graphs
+/// but may miss goal states and cheaper paths. A state is never put in a beam twice. Successors dropped from a beam
+/// are forgotten right away, only the states of past beams are kept to trace the path back. The graph is given
+/// implicitly by `successors` like in `astar_implicit`
+/// * Complexity: O(d * width * b * log(width)) time and O(d * width) memory for a goal d steps away in a graph with
+///   b successors per state
+///
+/// # Arguments
+/// * `start`: state the path starts at
+/// * `width`: maximum number of states in the beam
+/// * `successors`: returns the states reachable in one step from a state, together with the costs of the steps
+/// * `heuristic`: estimated cost from a state to the nearest goal state
+/// * `goal`: returns true if a state is a goal
+///
+/// # Panics
+/// * panics if `width` is zero
+/// * panics if a step has a negative cost
+///
+/// # Examples
+/// ```
+/// use rudac::algo::search::beam_search;
+///
+/// // reaching 1000 from 1 by doubling or adding one, each step costing 1
+/// let successors = |&n: &u32| vec![(n * 2, 1u32), (n + 1, 1)];
+/// let distance = |&n: &u32| n.abs_diff(1000);
+///
+/// let path = beam_search(1, 3, successors, distance, |&n| n == 1000).unwrap();
+///
+/// assert_eq!(path.states.first(), Some(&1));
+/// assert_eq!(path.states.last(), Some(&1000));
+/// assert_eq!(path.cost as usize, path.states.len() - 1);
+/// ```
+pub fn beam_search<S, W, I, N, H, G>(
+    start: S,
+    width: usize,
+    mut successors: N,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<StatePath<S, W>>
+where
+    S: Clone + Eq + Hash,
+    W: Weight,
+    I: IntoIterator<Item = (S, W)>,
+    N: FnMut(&S) -> I,
+    H: FnMut(&S) -> W,
+    G: FnMut(&S) -> bool,
+{
+    if width == 0 {
+        panic!("Beam width must be positive");
+    }
+
+    // states that made it into a beam, with the cost of their path and the index of the state before them
+    let mut states = vec![start.clone()];
+    let mut costs = vec![W::zero()];
+    let mut parents = vec![NIL];
+    let mut seen = HashSet::new();
+    seen.insert(start);
+
+    // indices of the states of the current beam
+    let mut beam = 0..1;
+    while !beam.is_empty() {
+        if let Some(index) = beam.clone().find(|&index| goal(&states[index])) {
+            return Some(StatePath {
+                states: trace(&states, &parents, index),
+                cost: costs[index],
+            });
+        }
+
+        // the best successors wait in slots, the worst one is dropped and its slot reused whenever there are too
+        // many, so at most `width + 1` successors are held at once. Equal estimates keep the order of discovery
+        let mut candidates = MinMax::init();
+        let mut slots: Vec<Option<(S, W, usize)>> = Vec::new();
+        let mut free = Vec::new();
+        let mut pending = HashMap::new();
+        let mut discovered = 0;
+        for index in beam.clone() {
+            for (successor, step) in successors(&states[index]) {
+                if step < W::zero() {
+                    panic!("Step costs must not be negative");
+                }
+                if seen.contains(&successor) || pending.contains_key(&successor) {
+                    continue;
+                }
+
+                let estimate = heuristic(&successor);
+                let candidate = Some((successor.clone(), costs[index] + step, index));
+                let slot = match free.pop() {
+                    Some(slot) => {
+                        slots[slot] = candidate;
+                        slot
+                    }
+                    None => {
+                        slots.push(candidate);
+                        slots.len() - 1
+                    }
+                };
+                pending.insert(successor, slot);
+                candidates.push((estimate, discovered, slot));
+                discovered += 1;
+
+                if candidates.size() > width {
+                    let (_, _, dropped) = candidates.pop_max().unwrap();
+                    let (state, _, _) = slots[dropped].take().unwrap();
+                    pending.remove(&state);
+                    free.push(dropped);
+                }
+            }
+        }
+
+        let next = states.len();
+        while let Some((_, _, slot)) = candidates.pop_min() {
+            let (state, cost, parent) = slots[slot].take().unwrap();
+            seen.insert(state.clone());
+            states.push(state);
+            costs.push(cost);
+            parents.push(parent);
+        }
+        beam = next..states.len();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::search::astar_implicit;
+
+    // moves on a 30x30 grid with a wall from (15, 0) to (15, 25)
+    fn grid_moves(&(x, y): &(i32, i32)) -> Vec<((i32, i32), u32)> {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .map(|(dx, dy)| ((x + dx, y + dy), 1))
+            .filter(|&((x, y), _)| {
+                (0..30).contains(&x) && (0..30).contains(&y) && !(x == 15 && y <= 25)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn algo_search_beam_width() {
+        let target = (29, 0);
+        let euclidean = |&(x, y): &(i32, i32)| (((x - 29).pow(2) + y.pow(2)) as f64).sqrt() as u32;
+        let shortest = astar_implicit((0, 0), grid_moves, |_| 0, |&state| state == target).unwrap();
+
+        // a wide beam is a breadth first search and finds a shortest path
+        let wide = beam_search((0, 0), 1000, grid_moves, euclidean, |&state| {
+            state == target
+        })
+        .unwrap();
+        assert_eq!(wide.cost, shortest.cost);
+        assert_eq!(wide.states.len(), shortest.states.len());
+
+        // narrow beams still find a path around the wall
+        for width in [4, 16] {
+            let path = beam_search((0, 0), width, grid_moves, euclidean, |&state| {
+                state == target
+            })
+            .unwrap();
+            assert!(path.cost >= shortest.cost);
+            assert_eq!(path.cost as usize, path.states.len() - 1);
+            for pair in path.states.windows(2) {
+                assert!(grid_moves(&pair[0]).contains(&(pair[1], 1)));
+            }
+        }
+    }
+
+    #[test]
+    fn algo_search_beam_misses_goal() {
+        // the estimate leads a beam of width 1 into the dead end 1 -> 3
+        let edges = |vertex: &u32| match vertex {
+            0 => vec![(1, 1), (2, 1)],
+            1 => vec![(3, 1)],
+            2 => vec![(4, 1)],
+            4 => vec![(5, 1)],
+            _ => vec![],
+        };
+        let heuristic = |vertex: &u32| match vertex {
+            1 | 3 => 0,
+            _ => 1,
+        };
+
+        assert_eq!(
+            beam_search(0, 1, edges, heuristic, |&vertex| vertex == 5),
+            None
+        );
+
+        let path = beam_search(0, 2, edges, heuristic, |&vertex| vertex == 5).unwrap();
+        assert_eq!(path.states, vec![0, 2, 4, 5]);
+        assert_eq!(path.cost, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Beam width must be positive")]
+    fn algo_search_beam_panic_zero_width() {
+        beam_search(
+            0u32,
+            0,
+            |&state| vec![(state + 1, 1u32)],
+            |_| 0,
+            |&state| state == 3,
+        );
+    }
+}
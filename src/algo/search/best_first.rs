@@ -0,0 +1,159 @@
+use super::astar::{trace, NIL};
+use super::StatePath;
+use crate::graph::Weight;
+use crate::heap::MinMax;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Returns a path from `start` to a goal state found by greedy best first search, `None` if no goal state is
+/// reachable. States are expanded in the order of their estimated cost to a goal alone, ignoring the cost from
+/// `start`, so a good estimate goes straight for a goal but the path need not be cheapest. Every state is visited once.
+/// The graph is given implicitly by `successors` like in `astar_implicit`
+/// * Complexity: O(E log V) over the V visited states and E generated successors
+///
+/// # Arguments
+/// * `start`: state the path starts at
+/// * `successors`: returns the states reachable in one step from a state, together with the costs of the steps
+/// * `heuristic`: estimated cost from a state to the nearest goal state
+/// * `goal`: returns true if a state is a goal
+///
+/// # Panics
+/// * panics if a step has a negative cost
+///
+/// # Examples
+/// ```
+/// use rudac::algo::search::best_first_search;
+///
+/// // walking on the integers from 0 to 10, a step left costs 1 and a step right costs 3
+/// let path = best_first_search(
+///     0i32,
+///     |&n| vec![(n - 1, 1u32), (n + 1, 3)],
+///     |&n| (10 - n).unsigned_abs(),
+///     |&n| n == 10,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path.states, (0..=10).collect::<Vec<i32>>());
+/// assert_eq!(path.cost, 30);
+/// ```
+pub fn best_first_search<S, W, I, N, H, G>(
+    start: S,
+    mut successors: N,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<StatePath<S, W>>
+where
+    S: Clone + Eq + Hash,
+    W: Weight,
+    I: IntoIterator<Item = (S, W)>,
+    N: FnMut(&S) -> I,
+    H: FnMut(&S) -> W,
+    G: FnMut(&S) -> bool,
+{
+    // visited states are numbered in the order of their discovery
+    let mut indices = HashMap::new();
+    let mut states = vec![start.clone()];
+    let mut costs = vec![W::zero()];
+    let mut parents = vec![NIL];
+    indices.insert(start, 0);
+
+    // ties are broken by the order of discovery
+    let mut heap = MinMax::init();
+    heap.push((heuristic(&states[0]), 0));
+
+    while let Some((_, index)) = heap.pop_min() {
+        if goal(&states[index]) {
+            return Some(StatePath {
+                states: trace(&states, &parents, index),
+                cost: costs[index],
+            });
+        }
+
+        for (successor, step) in successors(&states[index]) {
+            if step < W::zero() {
+                panic!("Step costs must not be negative");
+            }
+            if indices.contains_key(&successor) {
+                continue;
+            }
+
+            let next = states.len();
+            heap.push((heuristic(&successor), next));
+            costs.push(costs[index] + step);
+            parents.push(index);
+            states.push(successor.clone());
+            indices.insert(successor, next);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::search::astar_implicit;
+
+    #[test]
+    fn algo_search_best_first_visits_fewer_states() {
+        // a 40x40 open grid, unit steps
+        let mut expanded = 0;
+        let successors = |&(x, y): &(i32, i32)| {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .iter()
+                .map(move |(dx, dy)| ((x + dx, y + dy), 1u32))
+                .filter(|((x, y), _)| (0..40).contains(x) && (0..40).contains(y))
+                .collect::<Vec<_>>()
+        };
+        let manhattan = |&(x, y): &(i32, i32)| ((39 - x) + (39 - y)) as u32;
+
+        let path = best_first_search(
+            (0, 0),
+            |state| {
+                expanded += 1;
+                successors(state)
+            },
+            manhattan,
+            |&state| state == (39, 39),
+        )
+        .unwrap();
+
+        // on an open grid the greedy path is also the shortest
+        let shortest =
+            astar_implicit((0, 0), successors, |_| 0, |&state| state == (39, 39)).unwrap();
+        assert_eq!(path.cost, shortest.cost);
+        assert_eq!(path.states.len(), 79);
+        assert_eq!(expanded, 78);
+    }
+
+    #[test]
+    fn algo_search_best_first_not_cheapest() {
+        // the estimate prefers 1, but the path through 2 is cheaper
+        let edges = |vertex: &u32| match vertex {
+            0 => vec![(1, 10), (2, 1)],
+            1 | 2 => vec![(3, 1)],
+            _ => vec![],
+        };
+        let heuristic = |vertex: &u32| if *vertex == 2 { 5 } else { 0 };
+
+        let path = best_first_search(0, edges, heuristic, |&vertex| vertex == 3).unwrap();
+
+        assert_eq!(path.states, vec![0, 1, 3]);
+        assert_eq!(path.cost, 11);
+        assert_eq!(
+            best_first_search(0, edges, heuristic, |&vertex| vertex == 4),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Step costs must not be negative")]
+    fn algo_search_best_first_panic_negative_cost() {
+        best_first_search(
+            0,
+            |&state| vec![(state + 1, -1)],
+            |_| 0,
+            |&state| state == 3,
+        );
+    }
+}
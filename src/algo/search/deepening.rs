@@ -0,0 +1,340 @@
+use super::StatePath;
+use crate::graph::Weight;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// a depth first search from `start` to a goal state, returns the first path found. States on the current path are not
+// visited again, and states for which `prune` returns true given their depth and cost are neither tested nor expanded
+fn bounded_search<S, W, I, N, G, P>(
+    start: &S,
+    successors: &mut N,
+    goal: &mut G,
+    mut prune: P,
+) -> Option<StatePath<S, W>>
+where
+    S: Clone + Eq + Hash,
+    W: Weight,
+    I: IntoIterator<Item = (S, W)>,
+    N: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+    P: FnMut(&S, usize, W) -> bool,
+{
+    // the current path, each state with its cost and its successors left to visit
+    let mut stack = vec![(start.clone(), W::zero(), successors(start).into_iter())];
+    let mut on_path = HashSet::new();
+    on_path.insert(start.clone());
+
+    while let Some((_, cost, children)) = stack.last_mut() {
+        let (next, step) = match children.next() {
+            Some(child) => child,
+            None => {
+                let (state, _, _) = stack.pop().unwrap();
+                on_path.remove(&state);
+                continue;
+            }
+        };
+        if step < W::zero() {
+            panic!("Step costs must not be negative");
+        }
+
+        let cost = *cost + step;
+        if on_path.contains(&next) || prune(&next, stack.len(), cost) {
+            continue;
+        }
+        if goal(&next) {
+            let mut states: Vec<S> = stack.into_iter().map(|(state, _, _)| state).collect();
+            states.push(next);
+            return Some(StatePath { states, cost });
+        }
+
+        on_path.insert(next.clone());
+        let children = successors(&next).into_iter();
+        stack.push((next, cost, children));
+    }
+
+    None
+}
+
+/// Returns a path from `start` to a goal state with the fewest steps, found by iterative deepening depth first search.
+/// `None` if no goal state is at most `max_depth` steps away. Depth first searches to depth 1, 2, ... are run until a
+/// goal state is found, so only the current path is stored. A path never visits a state twice, and the search stops
+/// early once no path is cut off by the depth. The graph is given implicitly by `successors` like in `astar_implicit`
+/// * Complexity: O(b^d) time and O(d) memory for a goal d steps away in a graph with b successors per state
+///
+/// # Arguments
+/// * `start`: state the path starts at
+/// * `successors`: returns the states reachable in one step from a state, together with the costs of the steps
+/// * `goal`: returns true if a state is a goal
+/// * `max_depth`: maximum number of steps of the path
+///
+/// # Panics
+/// * panics if a step has a negative cost
+///
+/// # Examples
+/// ```
+/// use rudac::algo::search::iddfs;
+///
+/// // reaching 100 from 1 by doubling or adding one, each step costing 1
+/// let successors = |&n: &u32| vec![(n * 2, 1), (n + 1, 1)];
+///
+/// let path = iddfs(1, successors, |&n| n == 100, 10).unwrap();
+///
+/// assert_eq!(path.states, vec![1, 2, 3, 6, 12, 24, 25, 50, 100]);
+/// assert_eq!(path.cost, 8);
+/// assert_eq!(iddfs(1, successors, |&n| n == 100, 7), None);
+/// ```
+pub fn iddfs<S, W, I, N, G>(
+    start: S,
+    mut successors: N,
+    mut goal: G,
+    max_depth: usize,
+) -> Option<StatePath<S, W>>
+where
+    S: Clone + Eq + Hash,
+    W: Weight,
+    I: IntoIterator<Item = (S, W)>,
+    N: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    if goal(&start) {
+        return Some(StatePath {
+            states: vec![start],
+            cost: W::zero(),
+        });
+    }
+
+    for limit in 1..=max_depth {
+        let mut cut = false;
+        let prune = |_: &S, depth: usize, _: W| {
+            cut |= depth > limit;
+            depth > limit
+        };
+        if let Some(path) = bounded_search(&start, &mut successors, &mut goal, prune) {
+            return Some(path);
+        }
+        // every path ended before the limit, a deeper search finds nothing new
+        if !cut {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Returns a cheapest path from `start` to a goal state found by iterative deepening A*, `None` if no goal state is
+/// reachable. Depth first searches are run that cut off states whose cost from `start` plus the estimated cost to a
+/// goal is over a bound, starting at the estimate at `start` and raised to the least value cut off by the last search.
+/// Only the current path is stored, so it suits searches too large for the memory of `astar_implicit`, with the same
+/// `successors` and `heuristic`. The estimate must be admissible, never larger than the cost of a cheapest path to a
+/// goal, for the path to be cheapest. A path never visits a state twice
+/// * Complexity: O(d) memory for a goal d steps away, time depends on the estimate and the number of distinct bounds
+///
+/// # Arguments
+/// * `start`: state the path starts at
+/// * `successors`: returns the states reachable in one step from a state, together with the costs of the steps
+/// * `heuristic`: estimated cost from a state to the nearest goal state
+/// * `goal`: returns true if a state is a goal
+///
+/// # Panics
+/// * panics if a step has a negative cost
+///
+/// # Examples
+/// ```
+/// use rudac::algo::search::ida_star;
+///
+/// // a knight on an unbounded board, moving from (0, 0) to (7, 5)
+/// let moves = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+/// let path = ida_star(
+///     (0i64, 0i64),
+///     |&(x, y)| moves.iter().map(move |(dx, dy)| ((x + dx, y + dy), 1u32)),
+///     // a move changes the manhattan distance by at most 3
+///     |&(x, y)| (((7 - x).abs() + (5 - y).abs()) as u32 + 2) / 3,
+///     |&state| state == (7, 5),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path.cost, 4);
+/// assert_eq!(path.states.len(), 5);
+/// ```
+pub fn ida_star<S, W, I, N, H, G>(
+    start: S,
+    mut successors: N,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<StatePath<S, W>>
+where
+    S: Clone + Eq + Hash,
+    W: Weight,
+    I: IntoIterator<Item = (S, W)>,
+    N: FnMut(&S) -> I,
+    H: FnMut(&S) -> W,
+    G: FnMut(&S) -> bool,
+{
+    if goal(&start) {
+        return Some(StatePath {
+            states: vec![start],
+            cost: W::zero(),
+        });
+    }
+
+    let mut bound = heuristic(&start);
+    loop {
+        // least estimate over the bound, the bound of the next search
+        let mut next: Option<W> = None;
+        let prune = |state: &S, _: usize, cost: W| {
+            let estimate = cost + heuristic(state);
+            if estimate > bound {
+                next = Some(next.map_or(estimate, |next| next.min(estimate)));
+            }
+            estimate > bound
+        };
+        if let Some(path) = bounded_search(&start, &mut successors, &mut goal, prune) {
+            return Some(path);
+        }
+
+        bound = next?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::search::astar_implicit;
+    use crate::util::{Rng, SplitMix64};
+
+    // a random directed graph as lists of (successor, cost)
+    fn random_graph(rng: &mut SplitMix64, vertices: usize, edges: usize) -> Vec<Vec<(usize, u64)>> {
+        let mut graph = vec![Vec::new(); vertices];
+        for _ in 0..edges {
+            let from = rng.next_below(vertices as u64) as usize;
+            let to = rng.next_below(vertices as u64) as usize;
+            graph[from].push((to, rng.next_below(10)));
+        }
+
+        graph
+    }
+
+    #[test]
+    fn algo_search_deepening_iddfs_fewest_steps() {
+        let mut rng = SplitMix64::init(3);
+        let graph = random_graph(&mut rng, 12, 24);
+        let successors = |vertex: &usize| graph[*vertex].clone();
+        let unit = |vertex: &usize| {
+            graph[*vertex]
+                .iter()
+                .map(|&(to, _)| (to, 1))
+                .collect::<Vec<(usize, u64)>>()
+        };
+
+        for source in 0..12 {
+            for target in 0..12 {
+                let steps = astar_implicit(source, unit, |_| 0, |&vertex| vertex == target)
+                    .map(|path| path.cost as usize);
+                let path = iddfs(source, successors, |&vertex| vertex == target, 12);
+
+                assert_eq!(path.as_ref().map(|path| path.states.len() - 1), steps);
+                if let Some(path) = path {
+                    assert_eq!(path.states[0], source);
+                    let cost: u64 = path
+                        .states
+                        .windows(2)
+                        .map(|pair| {
+                            graph[pair[0]]
+                                .iter()
+                                .filter(|&&(to, _)| to == pair[1])
+                                .map(|&(_, cost)| cost)
+                                .min()
+                                .unwrap()
+                        })
+                        .sum();
+                    assert!(path.cost >= cost);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn algo_search_deepening_iddfs_stops_on_finite_graph() {
+        // a cycle of 5 states without goal, searches end once no path is cut off
+        let mut searches = 0;
+        let path = iddfs(
+            0u8,
+            |&state| {
+                searches += 1;
+                vec![((state + 1) % 5, 1u8)]
+            },
+            |_| false,
+            1000,
+        );
+
+        assert_eq!(path, None);
+        assert!(searches < 5 * 6);
+    }
+
+    #[test]
+    fn algo_search_deepening_ida_star_matches_astar() {
+        let mut rng = SplitMix64::init(12);
+        let graph = random_graph(&mut rng, 12, 30);
+        let successors = |vertex: &usize| graph[*vertex].clone();
+
+        for source in 0..12 {
+            for target in 0..12 {
+                let cheapest =
+                    astar_implicit(source, successors, |_| 0, |&vertex| vertex == target);
+                let path = ida_star(source, successors, |_| 0, |&vertex| vertex == target);
+
+                assert_eq!(
+                    path.as_ref().map(|path| path.cost),
+                    cheapest.map(|path| path.cost)
+                );
+                if let Some(path) = path {
+                    assert_eq!(path.states.first(), Some(&source));
+                    assert_eq!(path.states.last(), Some(&target));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn algo_search_deepening_ida_star_grid() {
+        // a 6x6 grid with a wall, moving right or down costs 1 and moving left or up costs 2
+        let successors = |&(x, y): &(i32, i32)| {
+            [((1, 0), 1), ((0, 1), 1), ((-1, 0), 2), ((0, -1), 2)]
+                .iter()
+                .map(move |&((dx, dy), cost)| ((x + dx, y + dy), cost))
+                .filter(|&((x, y), _)| {
+                    (0..6).contains(&x) && (0..6).contains(&y) && !(x == 3 && y < 5)
+                })
+                .collect::<Vec<((i32, i32), u32)>>()
+        };
+        let manhattan = |&(x, y): &(i32, i32)| ((5 - x).abs() + y) as u32;
+        let goal = |&state: &(i32, i32)| state == (5, 0);
+
+        let path = ida_star((0, 0), successors, manhattan, goal).unwrap();
+        let cheapest = astar_implicit((0, 0), successors, manhattan, goal).unwrap();
+
+        // around the wall: 5 down, 5 right and 5 up
+        assert_eq!(path.cost, 20);
+        assert_eq!(path.cost, cheapest.cost);
+        assert_eq!(path.states.len(), 16);
+    }
+
+    #[test]
+    fn algo_search_deepening_ida_star_unreachable() {
+        // states 0..4 reach each other, 9 is never reached
+        let successors = |&state: &u8| vec![((state + 1) % 4, 1u8), ((state + 3) % 4, 2)];
+
+        assert_eq!(ida_star(0, successors, |_| 0, |&state| state == 9), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Step costs must not be negative")]
+    fn algo_search_deepening_ida_star_panic_negative_cost() {
+        ida_star(
+            0,
+            |&state| vec![(state + 1, -1)],
+            |_| 0,
+            |&state| state == 3,
+        );
+    }
+}
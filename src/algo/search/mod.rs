@@ -1,12 +1,25 @@
+mod astar;
+mod beam;
+mod best_first;
 mod binary;
+mod deepening;
 mod linear;
 mod exponential;
 mod fibonacci;
 
 
+pub use astar::astar_implicit;
+
+pub use beam::beam_search;
+
+pub use best_first::best_first_search;
+
 pub use binary::binary_search;
 pub use binary::binary_search_with;
 
+pub use deepening::ida_star;
+pub use deepening::iddfs;
+
 pub use linear::linear_search;
 pub use linear::linear_search_with;
 
@@ -16,4 +29,14 @@ pub use exponential::exponential_search_with;
 
 
 pub use fibonacci::fibonacci_search;
-pub use fibonacci::fibonacci_search_with;
\ No newline at end of file
+pub use fibonacci::fibonacci_search_with;
+
+/// A path through the states of an implicitly defined graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatePath<S, W> {
+    /// states of the path, from its start to its end
+    pub states: Vec<S>,
+
+    /// sum of the costs of the steps of the path
+    pub cost: W,
+}
@@ -0,0 +1,115 @@
+/// Returns number of inversions of `items`: pairs of indices i < j with `items[i] > items[j]`. It measures how far
+/// the items are from sorted, from zero for sorted items up to n(n - 1) / 2 for items in reverse order.
+/// Computed by merge sort in O(n log n) time
+///
+/// # Arguments
+/// * `items`: the items
+///
+/// # Examples
+/// ```
+/// use rudac::algo::sequence::count_inversions;
+///
+/// // (3, 1), (3, 2) and (5, 4)
+/// assert_eq!(count_inversions(&[3, 1, 2, 5, 4]), 3);
+/// assert_eq!(count_inversions(&[4, 3, 2, 1]), 6);
+/// assert_eq!(count_inversions(&[1, 1, 2]), 0);
+/// ```
+pub fn count_inversions<T: Ord>(items: &[T]) -> u64 {
+    // sorts indices of the items instead of the items, which need not be copied
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    let mut buffer = order.clone();
+
+    sort_counting(items, &mut order, &mut buffer)
+}
+
+// sorts `order` by the items, counting the pairs merged out of order
+fn sort_counting<T: Ord>(items: &[T], order: &mut [usize], buffer: &mut [usize]) -> u64 {
+    if order.len() <= 1 {
+        return 0;
+    }
+
+    let middle = order.len() / 2;
+    let mut inversions = sort_counting(items, &mut order[..middle], &mut buffer[..middle])
+        + sort_counting(items, &mut order[middle..], &mut buffer[middle..]);
+
+    let (mut left, mut right) = (0, middle);
+    for slot in buffer.iter_mut() {
+        if right == order.len() || (left < middle && items[order[left]] <= items[order[right]]) {
+            *slot = order[left];
+            left += 1;
+        } else {
+            // every item left in the first half is greater than this one
+            inversions += (middle - left) as u64;
+            *slot = order[right];
+            right += 1;
+        }
+    }
+    order.copy_from_slice(buffer);
+
+    inversions
+}
+
+/// Returns for every index i the smallest index j > i with `items[j] < items[i]`, `None` if there is no such index.
+/// Computed with a stack of indices waiting for a smaller item in O(n) time
+///
+/// # Arguments
+/// * `items`: the items
+///
+/// # Examples
+/// ```
+/// use rudac::algo::sequence::next_smaller_indices;
+///
+/// let next = next_smaller_indices(&[4, 2, 5, 5, 1]);
+///
+/// assert_eq!(next, vec![Some(1), Some(4), Some(4), Some(4), None]);
+/// ```
+pub fn next_smaller_indices<T: Ord>(items: &[T]) -> Vec<Option<usize>> {
+    let mut next = vec![None; items.len()];
+
+    // the items of the waiting indices never decrease from bottom to top
+    let mut waiting: Vec<usize> = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        while waiting.last().is_some_and(|top| items[*top] > *item) {
+            next[waiting.pop().unwrap()] = Some(index);
+        }
+        waiting.push(index);
+    }
+
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn algo_sequence_inversions_match_brute_force() {
+        let mut rng = SplitMix64::init(97);
+        for size in [0, 1, 2, 3, 10, 200] {
+            let items: Vec<u64> = (0..size).map(|_| rng.next_below(20)).collect();
+
+            let inversions = (0..size)
+                .flat_map(|i| (i + 1..size).map(move |j| (i, j)))
+                .filter(|(i, j)| items[*i] > items[*j])
+                .count();
+            assert_eq!(count_inversions(&items), inversions as u64);
+
+            let next: Vec<Option<usize>> = (0..size)
+                .map(|i| (i + 1..size).find(|j| items[*j] < items[i]))
+                .collect();
+            assert_eq!(next_smaller_indices(&items), next);
+        }
+    }
+
+    #[test]
+    fn algo_sequence_inversions_of_reversed() {
+        let items: Vec<String> = (0..1000).rev().map(|item| format!("{:04}", item)).collect();
+
+        assert_eq!(count_inversions(&items), 1000 * 999 / 2);
+        assert!(next_smaller_indices(&items)[..999]
+            .iter()
+            .enumerate()
+            .all(|(index, next)| *next == Some(index + 1)));
+    }
+}
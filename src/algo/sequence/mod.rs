@@ -0,0 +1,4 @@
+mod inversions;
+
+pub use inversions::count_inversions;
+pub use inversions::next_smaller_indices;
@@ -0,0 +1,85 @@
+use crate::heap::MinMax;
+
+/// Iterator over the items of another iterator in sorted order, created by `sorted`
+pub struct Sorted<T: Ord> {
+    // items along with their position in the input, which keeps equal items in input order
+    heap: MinMax<(T, usize)>,
+}
+
+impl<T: Ord> Iterator for Sorted<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop_min().map(|(item, _)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.size(), Some(self.heap.size()))
+    }
+}
+
+impl<T: Ord> ExactSizeIterator for Sorted<T> {}
+
+/// Returns an iterator over the items of `iter` in ascending order. The items are collected and heapified in O(n)
+/// time, and every item taken from the result costs O(log n) time, so taking the k smallest items takes
+/// O(n + k log n) time instead of the O(n log n) of a full sort. The sort is stable
+///
+/// # Arguments
+/// * `iter`: the items to be sorted
+///
+/// # Examples
+/// ```
+/// use rudac::algo::sort::sorted;
+///
+/// let smallest: Vec<i32> = sorted(vec![10, 6, 1, 4, 2, 3, 7, 9, 8, 5]).take(3).collect();
+///
+/// assert_eq!(smallest, vec![1, 2, 3]);
+/// assert_eq!(sorted("rudac".chars()).collect::<String>(), "acdru");
+/// ```
+pub fn sorted<T: Ord, I: IntoIterator<Item = T>>(iter: I) -> Sorted<T> {
+    let items = iter
+        .into_iter()
+        .enumerate()
+        .map(|(position, item)| (item, position))
+        .collect();
+
+    Sorted {
+        heap: MinMax::build_heap(items),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::testing::Tagged;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn algo_sort_sorted_matches_sort() {
+        let mut rng = SplitMix64::init(96);
+        for size in [0, 1, 2, 10, 1000] {
+            let items: Vec<u64> = (0..size).map(|_| rng.next_below(50)).collect();
+            let mut expected = items.clone();
+            expected.sort();
+
+            let lazy = sorted(items.iter().copied());
+            assert_eq!(lazy.len(), size);
+            assert_eq!(lazy.collect::<Vec<u64>>(), expected);
+        }
+    }
+
+    #[test]
+    fn algo_sort_sorted_is_stable() {
+        let items = vec![
+            Tagged(2, 'a'),
+            Tagged(1, 'b'),
+            Tagged(2, 'c'),
+            Tagged(1, 'd'),
+            Tagged(2, 'e'),
+        ];
+
+        let order: String = sorted(items).map(|item| item.1).collect();
+
+        assert_eq!(order, "bdace");
+    }
+}
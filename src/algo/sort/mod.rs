@@ -1,7 +1,10 @@
 mod quick;
 mod merge;
+mod lazy;
 
 pub use quick::quick_sort;
 pub use quick::quick_sort_with;
 pub use merge::merge_sort;
-pub use merge::merge_sort_with;
\ No newline at end of file
+pub use merge::merge_sort_with;
+pub use lazy::sorted;
+pub use lazy::Sorted;
\ No newline at end of file
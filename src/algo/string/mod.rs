@@ -0,0 +1,9 @@
+mod rolling_hash;
+mod wildcard;
+
+pub use rolling_hash::compare_substrings;
+pub use rolling_hash::lcp_of_pair;
+pub use rolling_hash::longest_common_suffix;
+pub use rolling_hash::RollingHash;
+pub use wildcard::wildcard_match;
+pub use wildcard::Wildcard;
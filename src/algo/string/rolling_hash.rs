@@ -0,0 +1,402 @@
+use crate::string::suffix_array;
+use crate::structure::RangeMinimum;
+use crate::util::{Footprint, MemoryFootprint};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+// the Mersenne prime 2^61 - 1
+const MODULUS: u64 = (1 << 61) - 1;
+
+// an arbitrary base below the modulus
+const BASE: u64 = 0x1f3d_5b79_a2c4_e687 % MODULUS;
+
+fn multiply(first: u64, second: u64) -> u64 {
+    ((first as u128 * second as u128) % MODULUS as u128) as u64
+}
+
+// the suffixes of a text in sorted order, with the longest common prefixes of neighbors in that order in a sparse
+// table, so the longest common prefix of any two suffixes is a range minimum
+#[derive(Debug, Clone)]
+struct SuffixOrder {
+    // position of every suffix among the sorted suffixes
+    ranks: Vec<usize>,
+
+    // lcps[r] is the longest common prefix of the suffixes at positions r - 1 and r, lcps[0] is 0
+    lcps: RangeMinimum<usize>,
+}
+
+impl SuffixOrder {
+    // sorts the suffixes of `text` and finds the common prefixes of neighbors by Kasai's algorithm in O(n) time
+    fn init(text: &[u8]) -> SuffixOrder {
+        let n = text.len();
+
+        // bytes are shifted by one so that the sentinel, which sorts first, can use symbol 0
+        let mut symbols: Vec<u16> = text.iter().map(|byte| *byte as u16 + 1).collect();
+        symbols.push(0);
+        let suffixes = &suffix_array(&symbols)[1..];
+
+        let mut ranks = vec![0; n];
+        for (rank, suffix) in suffixes.iter().enumerate() {
+            ranks[*suffix] = rank;
+        }
+
+        // the common prefix with the previous suffix shrinks by at most one from a suffix to the next one
+        let mut lcps = vec![0; n];
+        let mut common: usize = 0;
+        for suffix in 0..n {
+            if ranks[suffix] == 0 {
+                common = 0;
+                continue;
+            }
+            let previous = suffixes[ranks[suffix] - 1];
+            while suffix.max(previous) + common < n
+                && text[suffix + common] == text[previous + common]
+            {
+                common += 1;
+            }
+            lcps[ranks[suffix]] = common;
+            common = common.saturating_sub(1);
+        }
+
+        SuffixOrder {
+            ranks,
+            lcps: RangeMinimum::init(lcps),
+        }
+    }
+
+    // longest common prefix of the suffixes starting at `first` and `second`, either may be the empty suffix
+    fn lcp(&self, first: usize, second: usize) -> usize {
+        let n = self.ranks.len();
+        if first == second {
+            return n - first;
+        }
+        if first == n || second == n {
+            return 0;
+        }
+
+        let (low, high) = if self.ranks[first] < self.ranks[second] {
+            (self.ranks[first], self.ranks[second])
+        } else {
+            (self.ranks[second], self.ranks[first])
+        };
+        *self.lcps.min(low + 1, high + 1)
+    }
+}
+
+/// Polynomial hashes of every prefix of a text modulo 2^61 - 1, which give the hash of any substring in O(1) time.
+/// Substrings with equal hashes are taken to be equal: two different substrings of length at most n collide with
+/// probability about n / 2^61, so `hash` and `equal` are correct with high probability.
+///
+/// Along with the hashes the suffixes of the text and of the reversed text are sorted, and the longest common
+/// prefixes of neighboring suffixes are kept in a sparse table for range minimum queries. That makes
+/// `lcp_of_pair`, `longest_common_suffix` and `compare_substrings` exact and O(1) after preprocessing
+///
+/// # Examples
+/// ```
+/// use rudac::algo::string::RollingHash;
+///
+/// let hash = RollingHash::init(b"abcabcx");
+///
+/// assert_eq!(hash.hash(0, 3), hash.hash(3, 6));
+/// assert!(hash.equal(0..3, 3..6));
+/// assert!(!hash.equal(0..3, 4..7));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RollingHash {
+    text: Vec<u8>,
+
+    // prefixes[i] is the hash of the first i bytes, powers[i] is the base to the power i
+    prefixes: Vec<u64>,
+    powers: Vec<u64>,
+
+    // sorted suffixes of the text, and of the reversed text for common suffixes
+    suffixes: SuffixOrder,
+    reversed: SuffixOrder,
+}
+
+impl RollingHash {
+    /// Builds the hashes of every prefix of `text` in O(n) time and sorts the suffixes of the text and of the reversed
+    /// text by prefix doubling in O(n log^2 n) time
+    ///
+    /// # Arguments
+    /// * `text`: the text
+    pub fn init(text: &[u8]) -> RollingHash {
+        let mut prefixes = Vec::with_capacity(text.len() + 1);
+        let mut powers = Vec::with_capacity(text.len() + 1);
+        prefixes.push(0);
+        powers.push(1);
+        for (index, byte) in text.iter().enumerate() {
+            // bytes are shifted by one so that zero bytes change the hash
+            prefixes.push((multiply(prefixes[index], BASE) + *byte as u64 + 1) % MODULUS);
+            powers.push(multiply(powers[index], BASE));
+        }
+
+        let reversed: Vec<u8> = text.iter().rev().copied().collect();
+
+        RollingHash {
+            text: text.to_vec(),
+            prefixes,
+            powers,
+            suffixes: SuffixOrder::init(text),
+            reversed: SuffixOrder::init(&reversed),
+        }
+    }
+
+    /// Returns length of the text
+    pub fn size(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Returns true if the text is empty
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns the text
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+
+    /// Returns the hash of the substring in range [`start`, `end`)
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the substring
+    /// * `end`: end of the substring(exclusive)
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end`
+    /// * panics if `end` is greater than length of the text
+    pub fn hash(&self, start: usize, end: usize) -> u64 {
+        if start > end {
+            panic!("Range must not be reversed");
+        }
+        if end > self.size() {
+            panic!("Index out of range");
+        }
+
+        let shifted = multiply(self.prefixes[start], self.powers[end - start]);
+        (self.prefixes[end] + MODULUS - shifted) % MODULUS
+    }
+
+    /// Returns true if the substrings in ranges `first` and `second` are equal
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `first`: range of the first substring
+    /// * `second`: range of the second substring
+    ///
+    /// # Panics
+    /// * panics if a range is reversed or ends after the text
+    pub fn equal(&self, first: Range<usize>, second: Range<usize>) -> bool {
+        self.hash(first.start, first.end) == self.hash(second.start, second.end)
+            && first.len() == second.len()
+    }
+}
+
+/// Returns length of the longest common prefix of the suffixes of the text of `hash` starting at `first` and
+/// `second`, as the minimum of the common prefixes of the neighboring sorted suffixes between them
+/// * Complexity: O(1)
+///
+/// # Arguments
+/// * `hash`: hashes of the text
+/// * `first`: start of the first suffix
+/// * `second`: start of the second suffix
+///
+/// # Panics
+/// * panics if `first` or `second` is greater than length of the text
+///
+/// # Examples
+/// ```
+/// use rudac::algo::string::{lcp_of_pair, RollingHash};
+///
+/// let hash = RollingHash::init(b"banana");
+///
+/// // "anana" and "ana"
+/// assert_eq!(lcp_of_pair(&hash, 1, 3), 3);
+/// assert_eq!(lcp_of_pair(&hash, 0, 2), 0);
+/// ```
+pub fn lcp_of_pair(hash: &RollingHash, first: usize, second: usize) -> usize {
+    if first.max(second) > hash.size() {
+        panic!("Index out of range");
+    }
+
+    hash.suffixes.lcp(first, second)
+}
+
+/// Returns length of the longest common suffix of the prefixes of the text of `hash` ending at `first` and
+/// `second`(exclusive), which are the longest common prefix of two suffixes of the reversed text
+/// * Complexity: O(1)
+///
+/// # Arguments
+/// * `hash`: hashes of the text
+/// * `first`: end of the first prefix
+/// * `second`: end of the second prefix
+///
+/// # Panics
+/// * panics if `first` or `second` is greater than length of the text
+///
+/// # Examples
+/// ```
+/// use rudac::algo::string::{longest_common_suffix, RollingHash};
+///
+/// let hash = RollingHash::init(b"abcxbc");
+///
+/// // "abc" and "abcxbc"
+/// assert_eq!(longest_common_suffix(&hash, 3, 6), 2);
+/// assert_eq!(longest_common_suffix(&hash, 4, 6), 0);
+/// ```
+pub fn longest_common_suffix(hash: &RollingHash, first: usize, second: usize) -> usize {
+    if first.max(second) > hash.size() {
+        panic!("Index out of range");
+    }
+
+    hash.reversed.lcp(hash.size() - first, hash.size() - second)
+}
+
+/// Compares the substrings of the text of `hash` in ranges `first` and `second` lexicographically by finding their
+/// longest common prefix and comparing the bytes after it
+/// * Complexity: O(1)
+///
+/// # Arguments
+/// * `hash`: hashes of the text
+/// * `first`: range of the first substring
+/// * `second`: range of the second substring
+///
+/// # Panics
+/// * panics if a range is reversed or ends after the text
+///
+/// # Examples
+/// ```
+/// use rudac::algo::string::{compare_substrings, RollingHash};
+/// use std::cmp::Ordering;
+///
+/// let hash = RollingHash::init(b"mississippi");
+///
+/// // "ssi" and "ssippi"
+/// assert_eq!(compare_substrings(&hash, 2..5, 5..11), Ordering::Less);
+/// // "sis" and "sip"
+/// assert_eq!(compare_substrings(&hash, 3..6, 6..9), Ordering::Greater);
+/// assert_eq!(compare_substrings(&hash, 1..4, 4..7), Ordering::Equal);
+/// ```
+pub fn compare_substrings(
+    hash: &RollingHash,
+    first: Range<usize>,
+    second: Range<usize>,
+) -> Ordering {
+    for range in [&first, &second] {
+        if range.start > range.end {
+            panic!("Range must not be reversed");
+        }
+        if range.end > hash.size() {
+            panic!("Index out of range");
+        }
+    }
+
+    let common = lcp_of_pair(hash, first.start, second.start)
+        .min(first.len())
+        .min(second.len());
+    if common == first.len() || common == second.len() {
+        return first.len().cmp(&second.len());
+    }
+
+    hash.text[first.start + common].cmp(&hash.text[second.start + common])
+}
+
+impl MemoryFootprint for RollingHash {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<u8>(self.text.len())
+            .with_vec(&self.text)
+            .with_vec(&self.prefixes)
+            .with_vec(&self.powers)
+            .with_vec(&self.suffixes.ranks)
+            .with_vec(&self.reversed.ranks)
+            .with(self.suffixes.lcps.footprint())
+            .with(self.reversed.lcps.footprint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn algo_string_rolling_hash_matches_slices() {
+        let mut rng = SplitMix64::init(98);
+        // a small alphabet makes long common prefixes likely
+        let text: Vec<u8> = (0..120).map(|_| rng.next_below(3) as u8).collect();
+        let hash = RollingHash::init(&text);
+
+        for first in 0..=text.len() {
+            for second in 0..=text.len() {
+                let common = text[first..]
+                    .iter()
+                    .zip(&text[second..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                assert_eq!(lcp_of_pair(&hash, first, second), common);
+
+                let common = text[..first]
+                    .iter()
+                    .rev()
+                    .zip(text[..second].iter().rev())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                assert_eq!(longest_common_suffix(&hash, first, second), common);
+            }
+        }
+        for _ in 0..5000 {
+            let mut range = || {
+                let start = rng.next_below(text.len() as u64 + 1) as usize;
+                start..start + rng.next_below((text.len() - start) as u64 + 1) as usize
+            };
+            let (first, second) = (range(), range());
+            assert_eq!(
+                compare_substrings(&hash, first.clone(), second.clone()),
+                text[first.clone()].cmp(&text[second.clone()])
+            );
+            assert_eq!(
+                hash.equal(first.clone(), second.clone()),
+                text[first] == text[second]
+            );
+        }
+    }
+
+    #[test]
+    fn algo_string_rolling_hash_sorts_suffixes() {
+        let text = b"abracadabra";
+        let hash = RollingHash::init(text);
+
+        let mut suffixes: Vec<usize> = (0..text.len()).collect();
+        suffixes.sort_by(|a, b| compare_substrings(&hash, *a..text.len(), *b..text.len()));
+
+        assert_eq!(suffixes, vec![10, 7, 0, 3, 5, 8, 1, 4, 6, 9, 2]);
+    }
+
+    #[test]
+    fn algo_string_rolling_hash_short_texts() {
+        let empty = RollingHash::init(b"");
+        assert_eq!(lcp_of_pair(&empty, 0, 0), 0);
+        assert_eq!(longest_common_suffix(&empty, 0, 0), 0);
+        assert_eq!(compare_substrings(&empty, 0..0, 0..0), Ordering::Equal);
+
+        let single = RollingHash::init(b"a");
+        assert_eq!(lcp_of_pair(&single, 0, 0), 1);
+        assert_eq!(lcp_of_pair(&single, 0, 1), 0);
+        assert_eq!(longest_common_suffix(&single, 1, 1), 1);
+        assert_eq!(compare_substrings(&single, 0..1, 1..1), Ordering::Greater);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn algo_string_common_suffix_panic_out_of_range() {
+        longest_common_suffix(&RollingHash::init(b"abc"), 4, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn algo_string_rolling_hash_panic_out_of_range() {
+        RollingHash::init(b"abc").hash(1, 4);
+    }
+}
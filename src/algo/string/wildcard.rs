@@ -0,0 +1,408 @@
+use crate::math::ModInt998244353;
+use crate::util::{Rng, SplitMix64};
+
+type Mint = ModInt998244353;
+
+// a primitive root of 998244353 = 119 * 2^23 + 1
+const ROOT: u64 = 3;
+
+// an arbitrary seed for the weights of the fingerprints
+const SEED: u64 = 0x5eed_3c1a_97b2_d04f;
+
+/// Returns true if `text` matches the wildcard `pattern` as a whole, where `?` matches any single character and `*`
+/// matches any sequence of characters, including the empty one. Compiles the pattern into a [`Wildcard`] and matches
+/// it once
+///
+/// * Complexity: O(n + m) if the pattern has no `?`, O((n + m) log m) otherwise
+///
+/// # Arguments
+/// * `pattern`: the pattern
+/// * `text`: the text
+///
+/// # Examples
+/// ```
+/// use rudac::algo::string::wildcard_match;
+///
+/// assert!(wildcard_match("*.rs", "main.rs"));
+/// assert!(wildcard_match("h?llo*", "hello world"));
+/// assert!(!wildcard_match("*.rs", "main.rso"));
+/// assert!(wildcard_match("", ""));
+/// ```
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    Wildcard::init(pattern).is_match(text)
+}
+
+// number-theoretic transform in place, the length of `values` must be a power of two dividing 2^23
+fn transform(values: &mut [Mint], invert: bool) {
+    let n = values.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut length = 2;
+    while length <= n {
+        let mut step = Mint::new(ROOT).pow((Mint::modulus() - 1) / length as u64);
+        if invert {
+            step = step.inverse().unwrap();
+        }
+        for block in values.chunks_mut(length) {
+            let (low, high) = block.split_at_mut(length / 2);
+            let mut twiddle = Mint::new(1);
+            for (first, second) in low.iter_mut().zip(high.iter_mut()) {
+                let odd = *second * twiddle;
+                *second = *first - odd;
+                *first += odd;
+                twiddle *= step;
+            }
+        }
+        length <<= 1;
+    }
+
+    if invert {
+        let scale = Mint::new(n as u64).inverse().unwrap();
+        for value in values.iter_mut() {
+            *value *= scale;
+        }
+    }
+}
+
+// a pseudorandom value of every character
+fn fingerprint(character: char) -> Mint {
+    Mint::new(SplitMix64::init(character as u64).next_u64())
+}
+
+// how the leftmost occurrence of a segment is found
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Search {
+    // a segment of only `?` matches anywhere
+    Anywhere,
+
+    // a segment without `?` is found by Knuth-Morris-Pratt, failure[i] is the longest proper border of the first
+    // i + 1 symbols
+    Exact { failure: Vec<usize> },
+
+    // a segment with `?` is found by convolving the text with the fingerprints of its symbols, weighted at random and
+    // reversed, with zero for `?`. Every occurrence sums to `target`, other positions very rarely do and are ruled
+    // out by comparing the symbols. The text is convolved in blocks of `kernel.len() - segment.len() + 1` positions
+    Masked { kernel: Vec<Mint>, target: Mint },
+}
+
+// the symbols between two stars, `None` for `?`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segment {
+    symbols: Vec<Option<char>>,
+    search: Search,
+}
+
+impl Segment {
+    fn init(symbols: Vec<Option<char>>) -> Segment {
+        let search = if symbols.iter().all(Option::is_none) {
+            Search::Anywhere
+        } else if symbols.iter().all(Option::is_some) {
+            let mut failure = vec![0; symbols.len()];
+            let mut border = 0;
+            for i in 1..symbols.len() {
+                while border > 0 && symbols[i] != symbols[border] {
+                    border = failure[border - 1];
+                }
+                if symbols[i] == symbols[border] {
+                    border += 1;
+                }
+                failure[i] = border;
+            }
+
+            Search::Exact { failure }
+        } else {
+            // a block is at least as long as the segment, so wrapping around pollutes no sum that is read
+            let length = symbols.len();
+            let mut kernel = vec![Mint::new(0); (2 * length).next_power_of_two()];
+            let mut target = Mint::new(0);
+            let mut rng = SplitMix64::init(SEED);
+            for (i, symbol) in symbols.iter().enumerate() {
+                if let Some(symbol) = symbol {
+                    let weight = Mint::new(rng.next_u64());
+                    kernel[length - 1 - i] = weight;
+                    target += weight * fingerprint(*symbol);
+                }
+            }
+            transform(&mut kernel, false);
+
+            Search::Masked { kernel, target }
+        };
+
+        Segment { symbols, search }
+    }
+
+    fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    fn matches_at(&self, text: &[char], start: usize) -> bool {
+        self.symbols
+            .iter()
+            .zip(&text[start..])
+            .all(|(symbol, character)| symbol.is_none_or(|symbol| symbol == *character))
+    }
+
+    // leftmost position at or after `start` where the segment matches and ends by `end`, scanning O(p - start + m)
+    // characters of the text, where p is that position and m the length of the segment
+    fn find(&self, text: &[char], start: usize, end: usize) -> Option<usize> {
+        if start + self.len() > end {
+            return None;
+        }
+
+        match &self.search {
+            Search::Anywhere => Some(start),
+            Search::Exact { failure } => {
+                let mut matched = 0;
+                for (position, character) in text.iter().enumerate().take(end).skip(start) {
+                    let character = Some(*character);
+                    while matched > 0 && self.symbols[matched] != character {
+                        matched = failure[matched - 1];
+                    }
+                    if self.symbols[matched] == character {
+                        matched += 1;
+                    }
+                    if matched == self.len() {
+                        return Some(position + 1 - self.len());
+                    }
+                }
+
+                None
+            }
+            Search::Masked { kernel, target } => {
+                let block = kernel.len() - self.len() + 1;
+                let mut values = vec![Mint::new(0); kernel.len()];
+                for first in (start..=end - self.len()).step_by(block) {
+                    // the sum for position first + j lands at j + m - 1
+                    let window = &text[first..end.min(first + kernel.len())];
+                    for (value, character) in values.iter_mut().zip(window) {
+                        *value = fingerprint(*character);
+                    }
+                    for value in values.iter_mut().skip(window.len()) {
+                        *value = Mint::new(0);
+                    }
+                    transform(&mut values, false);
+                    for (value, weight) in values.iter_mut().zip(kernel) {
+                        *value *= *weight;
+                    }
+                    transform(&mut values, true);
+
+                    let last = (first + block).min(end - self.len() + 1);
+                    if let Some(position) = (first..last).find(|position| {
+                        values[position - first + self.len() - 1] == *target
+                            && self.matches_at(text, *position)
+                    }) {
+                        return Some(position);
+                    }
+                }
+
+                None
+            }
+        }
+    }
+}
+
+/// A wildcard pattern compiled for matching many texts, with `?` matching any single character and `*` matching any
+/// sequence of characters. The pattern is split at its stars into segments. The first segment must match at the
+/// start of the text and the last one at its end, and the segments in between are matched greedily at their
+/// leftmost position, which never rules out a match. A segment without `?` is found by Knuth-Morris-Pratt, one with
+/// `?` by convolving the text with fingerprints of its symbols in blocks about as long as the segment
+///
+/// * Complexity: O(n + m) if the pattern has no `?`, O((n + m) log m) otherwise, for a text of length n and a pattern
+///   of length m. Compiling takes O(m log m) time
+///
+/// # Examples
+/// ```
+/// use rudac::algo::string::Wildcard;
+///
+/// let pattern = Wildcard::init("src/*/mod.?s");
+///
+/// assert!(pattern.is_match("src/heap/mod.rs"));
+/// assert!(pattern.is_match("src/a/b/mod.ts"));
+/// assert!(!pattern.is_match("src/mod.rs"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wildcard {
+    segments: Vec<Segment>,
+    has_star: bool,
+}
+
+impl Wildcard {
+    /// Compiles `pattern`
+    ///
+    /// # Arguments
+    /// * `pattern`: the pattern
+    pub fn init(pattern: &str) -> Wildcard {
+        let segments = pattern
+            .split('*')
+            .map(|segment| {
+                Segment::init(
+                    segment
+                        .chars()
+                        .map(|symbol| if symbol == '?' { None } else { Some(symbol) })
+                        .collect(),
+                )
+            })
+            .collect();
+
+        Wildcard {
+            segments,
+            has_star: pattern.contains('*'),
+        }
+    }
+
+    /// Returns the least number of characters a text needs to match
+    pub fn min_length(&self) -> usize {
+        self.segments.iter().map(|segment| segment.len()).sum()
+    }
+
+    /// Returns true if `text` matches the pattern as a whole
+    ///
+    /// # Arguments
+    /// * `text`: the text
+    pub fn is_match(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        if !self.has_star {
+            return text.len() == self.segments[0].len() && self.segments[0].matches_at(&text, 0);
+        }
+
+        let (first, rest) = self.segments.split_first().unwrap();
+        let (last, middle) = rest.split_last().unwrap();
+        if text.len() < self.min_length()
+            || !first.matches_at(&text, 0)
+            || !last.matches_at(&text, text.len() - last.len())
+        {
+            return false;
+        }
+
+        // the middle segments must fit between the first and the last one
+        let mut start = first.len();
+        let end = text.len() - last.len();
+        for segment in middle {
+            match segment.find(&text, start, end) {
+                Some(position) => start = position + segment.len(),
+                None => return false,
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    // matches by dynamic programming over prefixes
+    fn naive(pattern: &[char], text: &[char]) -> bool {
+        let mut matched = vec![vec![false; text.len() + 1]; pattern.len() + 1];
+        matched[0][0] = true;
+        for p in 1..=pattern.len() {
+            for t in 0..=text.len() {
+                matched[p][t] = match pattern[p - 1] {
+                    '*' => matched[p - 1][t] || (t > 0 && matched[p][t - 1]),
+                    symbol => {
+                        t > 0 && matched[p - 1][t - 1] && (symbol == '?' || symbol == text[t - 1])
+                    }
+                };
+            }
+        }
+
+        matched[pattern.len()][text.len()]
+    }
+
+    #[test]
+    fn algo_string_wildcard_matches_naive() {
+        let mut rng = SplitMix64::init(99);
+        let symbols = ['a', 'b', '?', '*'];
+        for _ in 0..5000 {
+            let pattern: String = (0..rng.next_below(7))
+                .map(|_| symbols[rng.next_below(4) as usize])
+                .collect();
+            let text: String = (0..rng.next_below(9))
+                .map(|_| symbols[rng.next_below(2) as usize])
+                .collect();
+            let expected = naive(
+                &pattern.chars().collect::<Vec<char>>(),
+                &text.chars().collect::<Vec<char>>(),
+            );
+
+            assert_eq!(
+                wildcard_match(&pattern, &text),
+                expected,
+                "{} {}",
+                pattern,
+                text
+            );
+            assert_eq!(
+                Wildcard::init(&pattern).is_match(&text),
+                expected,
+                "{} {}",
+                pattern,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn algo_string_wildcard_long_segments_match_naive() {
+        let mut rng = SplitMix64::init(7);
+        let symbols = ['a', 'b', '?', '*'];
+        for _ in 0..300 {
+            // stars are rare so that the segments between them get long
+            let pattern: String = (0..rng.next_below(60))
+                .map(|_| match rng.next_below(20) {
+                    0 => '*',
+                    1..=4 => '?',
+                    draw => symbols[draw as usize % 2],
+                })
+                .collect();
+            let text: String = (0..rng.next_below(300))
+                .map(|_| symbols[rng.next_below(2) as usize])
+                .collect();
+            let expected = naive(
+                &pattern.chars().collect::<Vec<char>>(),
+                &text.chars().collect::<Vec<char>>(),
+            );
+
+            assert_eq!(
+                wildcard_match(&pattern, &text),
+                expected,
+                "{} {}",
+                pattern,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn algo_string_wildcard_backtracking_worst_case() {
+        // retrying every start of the segment would take quadratic time on these
+        let text = "a".repeat(200_000);
+        let exact = format!("*{}b*", "a".repeat(5_000));
+        let masked = format!("*{}b*", "a?".repeat(2_500));
+
+        assert!(!wildcard_match(&exact, &text));
+        assert!(!wildcard_match(&masked, &text));
+        assert!(wildcard_match(&exact, &(text.clone() + "b")));
+        assert!(wildcard_match(&masked, &(text + "b")));
+    }
+
+    #[test]
+    fn algo_string_wildcard_multibyte_characters() {
+        assert!(wildcard_match("gr??e", "grüße"));
+        assert!(Wildcard::init("*ß?").is_match("grüße"));
+        assert_eq!(Wildcard::init("a*b?*c").min_length(), 4);
+    }
+}
@@ -0,0 +1,26 @@
+// fixtures shared by the tests of the algorithms
+use std::cmp::Ordering;
+
+// an item ordered by its key alone, the tag tells equal items apart to check stability
+#[derive(Debug)]
+pub(crate) struct Tagged(pub(crate) u32, pub(crate) char);
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::heap::FibonacciHeap;
+
+/// Iterator over the items of several sorted iterators in sorted order, created by `kway_merge`
+pub struct KwayMerge<T: Ord, I: Iterator<Item = T>> {
+    iters: Vec<I>,
+
+    // the next item of every iterator that is not exhausted, along with the index of the iterator, which keeps equal
+    // items in the order of their iterators
+    heap: FibonacciHeap<(T, usize)>,
+}
+
+impl<T: Ord, I: Iterator<Item = T>> Iterator for KwayMerge<T, I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let (item, index) = self.heap.pop()?;
+        if let Some(next) = self.iters[index].next() {
+            self.heap.push((next, index));
+        }
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iters.iter().fold(
+            (self.heap.size(), Some(self.heap.size())),
+            |(low, high), iter| {
+                let (iter_low, iter_high) = iter.size_hint();
+                let high = high
+                    .zip(iter_high)
+                    .and_then(|(high, iter_high)| high.checked_add(iter_high));
+
+                (low.saturating_add(iter_low), high)
+            },
+        )
+    }
+}
+
+/// Returns an iterator that merges the sorted iterators of `iters` into one sorted iterator. The first item of every
+/// iterator waits in a Fibonacci heap, and every item taken from the result is replaced by the next item of its
+/// iterator, so the iterators are read lazily, one item ahead at most. Equal items come out in the order of their
+/// iterators. Merging sorted runs, log files or the tables of a log-structured merge tree are common uses
+/// * Complexity: O(k) to start and amortized O(log k) per item, for k iterators
+///
+/// # Arguments
+/// * `iters`: iterators whose items are in ascending order
+///
+/// # Examples
+/// ```
+/// use rudac::algo::transform::kway_merge;
+///
+/// let runs = vec![vec![1, 4, 9], vec![2, 3, 10], vec![], vec![5]];
+/// let merged: Vec<i32> = kway_merge(runs).collect();
+///
+/// assert_eq!(merged, vec![1, 2, 3, 4, 5, 9, 10]);
+///
+/// // the iterators may be endless as long as only a part of the result is taken
+/// let squares: Box<dyn Iterator<Item = u64>> = Box::new((1..).map(|x| x * x));
+/// let cubes: Box<dyn Iterator<Item = u64>> = Box::new((1..).map(|x| x * x * x));
+/// let powers: Vec<u64> = kway_merge(vec![squares, cubes]).take(6).collect();
+///
+/// assert_eq!(powers, vec![1, 1, 4, 8, 9, 16]);
+/// ```
+pub fn kway_merge<T: Ord, I: IntoIterator<Item = T>>(iters: Vec<I>) -> KwayMerge<T, I::IntoIter> {
+    let mut iters: Vec<I::IntoIter> = iters.into_iter().map(IntoIterator::into_iter).collect();
+    let mut heap = FibonacciHeap::init_min();
+    for (index, iter) in iters.iter_mut().enumerate() {
+        if let Some(item) = iter.next() {
+            heap.push((item, index));
+        }
+    }
+
+    KwayMerge { iters, heap }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::testing::Tagged;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn algo_transform_kway_merge_matches_sort() {
+        let mut rng = SplitMix64::init(131);
+        for count in [0, 1, 2, 5, 40] {
+            let runs: Vec<Vec<u64>> = (0..count)
+                .map(|_| {
+                    let mut run: Vec<u64> = (0..rng.next_below(30))
+                        .map(|_| rng.next_below(50))
+                        .collect();
+                    run.sort();
+                    run
+                })
+                .collect();
+            let mut expected: Vec<u64> = runs.iter().flatten().copied().collect();
+            expected.sort();
+
+            let merged = kway_merge(runs);
+            assert_eq!(merged.size_hint(), (expected.len(), Some(expected.len())));
+            assert_eq!(merged.collect::<Vec<u64>>(), expected);
+        }
+    }
+
+    #[test]
+    fn algo_transform_kway_merge_is_stable() {
+        let runs = vec![
+            vec![Tagged(1, 'a'), Tagged(2, 'b'), Tagged(2, 'c')],
+            vec![Tagged(1, 'd'), Tagged(2, 'e')],
+            vec![Tagged(0, 'f'), Tagged(2, 'g')],
+        ];
+
+        let order: String = kway_merge(runs).map(|item| item.1).collect();
+
+        assert_eq!(order, "fadbceg");
+    }
+}
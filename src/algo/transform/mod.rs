@@ -1,4 +1,7 @@
+mod kway;
 mod partition;
 
+pub use kway::kway_merge;
+pub use kway::KwayMerge;
 pub use partition::partition;
 pub use partition::partition_with;
\ No newline at end of file
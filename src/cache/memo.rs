@@ -0,0 +1,420 @@
+use crate::cache::{Lru, Policy};
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Statistics gathered by a memoization cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// number of lookups answered from the cache
+    pub hits: usize,
+
+    /// number of lookups that required calling the function
+    pub misses: usize,
+
+    /// number of values evicted to make room for new values
+    pub evictions: usize,
+
+    /// number of values dropped because they were expired
+    pub expirations: usize,
+}
+
+/// Memo wraps a function with a bounded cache. Results of previous calls are kept in the cache and
+/// an eviction policy decides which result is dropped when the cache is full.
+///
+/// # Examples
+/// ```
+/// use rudac::cache::Memo;
+///
+/// let mut square = Memo::init(2, |x: &u64| x * x);
+///
+/// assert_eq!(*square.get(3), 9);
+/// assert_eq!(*square.get(3), 9);
+///
+/// assert_eq!(square.stats().hits, 1);
+/// assert_eq!(square.stats().misses, 1);
+/// ```
+pub struct Memo<K, V, F, P = Lru<K>>
+where
+    F: FnMut(&K) -> V,
+    P: Policy<K>,
+{
+    // function to be memoized
+    function: F,
+
+    // cached results
+    cache: HashMap<K, V>,
+
+    // maximum number of cached results
+    capacity: usize,
+
+    // eviction policy
+    policy: P,
+
+    stats: Stats,
+}
+
+impl<K, V, F> Memo<K, V, F, Lru<K>>
+where
+    K: Hash + Eq + Clone,
+    F: FnMut(&K) -> V,
+{
+    /// Initializes a memoization cache with LRU eviction policy
+    ///
+    /// # Arguments
+    /// * `capacity`: maximum number of cached results
+    /// * `function`: function to be memoized
+    ///
+    /// # Panics
+    /// * panics if `capacity` is zero
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut length = Memo::init(16, |text: &String| text.len());
+    ///
+    /// assert_eq!(*length.get(String::from("rudac")), 5);
+    /// ```
+    pub fn init(capacity: usize, function: F) -> Memo<K, V, F, Lru<K>> {
+        Memo::with_policy(capacity, Lru::init(), function)
+    }
+}
+
+impl<K, V, F, P> Memo<K, V, F, P>
+where
+    K: Hash + Eq + Clone,
+    F: FnMut(&K) -> V,
+    P: Policy<K>,
+{
+    /// Initializes a memoization cache with the specified eviction `policy`
+    ///
+    /// # Arguments
+    /// * `capacity`: maximum number of cached results
+    /// * `policy`: eviction policy
+    /// * `function`: function to be memoized
+    ///
+    /// # Panics
+    /// * panics if `capacity` is zero
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::{Memo, Lfu};
+    ///
+    /// let mut double = Memo::with_policy(1, Lfu::init(), |x: &i32| x * 2);
+    ///
+    /// assert_eq!(*double.get(2), 4);
+    /// ```
+    pub fn with_policy(capacity: usize, policy: P, function: F) -> Memo<K, V, F, P> {
+        if capacity == 0 {
+            panic!("Capacity must be greater than zero");
+        }
+
+        Memo {
+            function,
+            cache: HashMap::with_capacity(capacity),
+            capacity,
+            policy,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Returns a reference to the result of calling the function with `key`.
+    /// Function is only called if result is not cached or cached result is expired
+    ///
+    /// # Arguments
+    /// * `key`: argument of the function
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut calls = 0;
+    /// let mut memo = Memo::init(4, |x: &usize| { calls += 1; x + 1 });
+    ///
+    /// memo.get(1);
+    /// memo.get(1);
+    /// drop(memo);
+    ///
+    /// assert_eq!(calls, 1);
+    /// ```
+    pub fn get(&mut self, key: K) -> &V {
+        if self.cache.contains_key(&key) && self.policy.is_expired(&key) {
+            self.cache.remove(&key);
+            self.policy.on_remove(&key);
+            self.stats.expirations += 1;
+        }
+
+        if self.cache.contains_key(&key) {
+            self.stats.hits += 1;
+            self.policy.on_access(&key);
+        } else {
+            self.stats.misses += 1;
+
+            let value = (self.function)(&key);
+
+            // make room for the new value
+            while self.cache.len() >= self.capacity {
+                match self.policy.victim() {
+                    Some(victim) => {
+                        self.cache.remove(&victim);
+                        self.stats.evictions += 1;
+                    }
+                    None => break,
+                }
+            }
+
+            self.policy.on_insert(&key);
+            self.cache.insert(key.clone(), value);
+        }
+
+        self.cache.get(&key).unwrap()
+    }
+
+    /// Returns a reference to the cached result for `key` without calling the function or updating the statistics.
+    /// Returns `None` if result is not cached
+    ///
+    /// # Arguments
+    /// * `key`: argument of the function
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut memo = Memo::init(4, |x: &usize| x + 1);
+    /// assert_eq!(memo.peek(&1), None);
+    ///
+    /// memo.get(1);
+    /// assert_eq!(memo.peek(&1), Some(&2));
+    /// ```
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Removes the cached result for `key` and returns it
+    ///
+    /// # Arguments
+    /// * `key`: argument of the function
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut memo = Memo::init(4, |x: &usize| x + 1);
+    /// memo.get(1);
+    ///
+    /// assert_eq!(memo.invalidate(&1), Some(2));
+    /// assert_eq!(memo.invalidate(&1), None);
+    /// ```
+    pub fn invalidate(&mut self, key: &K) -> Option<V> {
+        let value = self.cache.remove(key)?;
+        self.policy.on_remove(key);
+
+        Some(value)
+    }
+
+    /// Removes all cached results. Statistics are kept
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut memo = Memo::init(4, |x: &usize| x + 1);
+    /// memo.get(1);
+    /// memo.get(2);
+    ///
+    /// memo.clear();
+    ///
+    /// assert_eq!(memo.size(), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        for key in self.cache.keys() {
+            self.policy.on_remove(key);
+        }
+        self.cache.clear();
+    }
+
+    /// Returns the statistics gathered since creation or since the latest call to `reset_stats`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut memo = Memo::init(1, |x: &usize| x + 1);
+    /// memo.get(1);
+    /// memo.get(2);
+    /// memo.get(2);
+    ///
+    /// let stats = memo.stats();
+    /// assert_eq!(stats.hits, 1);
+    /// assert_eq!(stats.misses, 2);
+    /// assert_eq!(stats.evictions, 1);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Resets all statistics to zero
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut memo = Memo::init(1, |x: &usize| x + 1);
+    /// memo.get(1);
+    ///
+    /// memo.reset_stats();
+    ///
+    /// assert_eq!(memo.stats().misses, 0);
+    /// ```
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /// Returns number of cached results
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let mut memo = Memo::init(4, |x: &usize| x + 1);
+    /// memo.get(1);
+    ///
+    /// assert_eq!(memo.size(), 1);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns maximum number of cached results
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Memo;
+    ///
+    /// let memo = Memo::init(4, |x: &usize| x + 1);
+    ///
+    /// assert_eq!(memo.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<K, V, F, P> MemoryFootprint for Memo<K, V, F, P>
+where
+    F: FnMut(&K) -> V,
+    P: Policy<K> + MemoryFootprint,
+{
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<(K, V)>(self.cache.len())
+            .with_hash_map(&self.cache)
+            .with(self.policy.footprint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{Lfu, Ttl};
+    use std::time::Duration;
+
+    #[test]
+    fn cache_memo_lru_eviction() {
+        let mut memo = Memo::init(2, |x: &usize| x * 10);
+
+        memo.get(1);
+        memo.get(2);
+        memo.get(1);
+        memo.get(3);
+
+        assert_eq!(memo.peek(&1), Some(&10));
+        assert_eq!(memo.peek(&2), None);
+        assert_eq!(memo.peek(&3), Some(&30));
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 1,
+                misses: 3,
+                evictions: 1,
+                expirations: 0
+            }
+        );
+    }
+
+    #[test]
+    fn cache_memo_lfu_eviction() {
+        let mut memo = Memo::with_policy(2, Lfu::init(), |x: &usize| x * 10);
+
+        memo.get(1);
+        memo.get(1);
+        memo.get(1);
+        memo.get(2);
+        memo.get(3);
+
+        assert_eq!(memo.peek(&1), Some(&10));
+        assert_eq!(memo.peek(&2), None);
+        assert_eq!(memo.size(), 2);
+    }
+
+    #[test]
+    fn cache_memo_ttl_expiration() {
+        let mut calls = 0;
+        let mut memo = Memo::with_policy(4, Ttl::init(Duration::from_secs(0)), |x: &usize| {
+            calls += 1;
+            x * 10
+        });
+
+        memo.get(1);
+        memo.get(1);
+        let stats = memo.stats();
+        drop(memo);
+
+        assert_eq!(calls, 2);
+        assert_eq!(stats.expirations, 1);
+        assert_eq!(stats.hits, 0);
+    }
+
+    #[test]
+    fn cache_memo_invalidate_and_clear() {
+        let mut memo = Memo::init(4, |x: &usize| x * 10);
+
+        memo.get(1);
+        memo.get(2);
+        memo.get(3);
+
+        assert_eq!(memo.invalidate(&2), Some(20));
+        assert_eq!(memo.size(), 2);
+
+        memo.clear();
+        assert_eq!(memo.size(), 0);
+
+        memo.get(4);
+        assert_eq!(memo.stats().misses, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Capacity must be greater than zero")]
+    fn cache_memo_zero_capacity() {
+        Memo::init(0, |x: &usize| *x);
+    }
+
+    #[test]
+    fn cache_memo_footprint() {
+        let mut memo = Memo::init(16, |x: &u64| x * 10);
+        assert_eq!(memo.footprint().elements(), 0);
+
+        for i in 0..10 {
+            memo.get(i);
+        }
+
+        let footprint = memo.footprint();
+        let policy = memo.policy.footprint();
+        assert_eq!(footprint.elements(), 10);
+        assert_eq!(footprint.payload_bytes(), 160);
+        assert_eq!(policy.elements(), 10);
+        assert!(policy.nodes() >= 2);
+        assert!(footprint.nodes() > policy.nodes());
+        assert!(footprint.allocated_bytes() > policy.allocated_bytes() + 160);
+    }
+}
@@ -0,0 +1,9 @@
+mod memo;
+mod policy;
+
+pub use memo::Memo;
+pub use memo::Stats;
+pub use policy::Lfu;
+pub use policy::Lru;
+pub use policy::Policy;
+pub use policy::Ttl;
@@ -0,0 +1,384 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// An eviction policy decides which key must leave a cache when the cache is full.
+/// Cache notifies the policy about every insertion, access and removal so the policy can keep its bookkeeping up to date.
+///
+/// # Examples
+/// ```
+/// use rudac::cache::{Lru, Policy};
+///
+/// let mut lru = Lru::init();
+///
+/// lru.on_insert(&1);
+/// lru.on_insert(&2);
+/// lru.on_access(&1);
+///
+/// // 2 is the least recently used key
+/// assert_eq!(lru.victim(), Some(2));
+/// ```
+pub trait Policy<K> {
+    /// Called after `key` is inserted into the cache
+    fn on_insert(&mut self, key: &K);
+
+    /// Called after `key` is read from the cache
+    fn on_access(&mut self, key: &K);
+
+    /// Called after `key` is removed from the cache for any reason other than eviction by `victim`
+    fn on_remove(&mut self, key: &K);
+
+    /// Chooses a key to be evicted from the cache and forgets about it. Returns `None` if policy is tracking no keys
+    fn victim(&mut self) -> Option<K>;
+
+    /// Returns true if cached value of `key` is no longer valid and must be recomputed
+    fn is_expired(&self, _key: &K) -> bool {
+        false
+    }
+}
+
+/// Least recently used eviction policy
+///
+/// # Examples
+/// ```
+/// use rudac::cache::{Lru, Policy};
+///
+/// let mut lru = Lru::init();
+///
+/// lru.on_insert(&"a");
+/// lru.on_insert(&"b");
+///
+/// assert_eq!(lru.victim(), Some("a"));
+/// ```
+#[derive(Debug)]
+pub struct Lru<K> {
+    // logical time of the latest access to each key
+    last_access: HashMap<K, u64>,
+
+    // keys ordered by the logical time of their latest access
+    order: BTreeMap<u64, K>,
+
+    // logical clock
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone> Lru<K> {
+    /// Initializes an empty LRU policy
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Lru;
+    ///
+    /// let lru: Lru<usize> = Lru::init();
+    /// ```
+    pub fn init() -> Lru<K> {
+        Lru {
+            last_access: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(old_tick) = self.last_access.insert(key.clone(), self.tick) {
+            self.order.remove(&old_tick);
+        }
+        self.order.insert(self.tick, key.clone());
+        self.tick += 1;
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for Lru<K> {
+    fn default() -> Self {
+        Lru::init()
+    }
+}
+
+impl<K: Hash + Eq + Clone> Policy<K> for Lru<K> {
+    fn on_insert(&mut self, key: &K) {
+        self.touch(key);
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.touch(key);
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(tick) = self.last_access.remove(key) {
+            self.order.remove(&tick);
+        }
+    }
+
+    fn victim(&mut self) -> Option<K> {
+        let tick = *self.order.keys().next()?;
+        let key = self.order.remove(&tick).unwrap();
+        self.last_access.remove(&key);
+
+        Some(key)
+    }
+}
+
+/// Least frequently used eviction policy. Ties are broken by evicting the least recently used key
+///
+/// # Examples
+/// ```
+/// use rudac::cache::{Lfu, Policy};
+///
+/// let mut lfu = Lfu::init();
+///
+/// lfu.on_insert(&"a");
+/// lfu.on_insert(&"b");
+/// lfu.on_access(&"a");
+/// lfu.on_access(&"a");
+/// lfu.on_access(&"b");
+///
+/// assert_eq!(lfu.victim(), Some("b"));
+/// ```
+#[derive(Debug)]
+pub struct Lfu<K> {
+    // (frequency, logical time of latest access) of each key
+    usage: HashMap<K, (u64, u64)>,
+
+    // keys ordered by frequency and then by recency
+    order: BTreeMap<(u64, u64), K>,
+
+    // logical clock
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone> Lfu<K> {
+    /// Initializes an empty LFU policy
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Lfu;
+    ///
+    /// let lfu: Lfu<usize> = Lfu::init();
+    /// ```
+    pub fn init() -> Lfu<K> {
+        Lfu {
+            usage: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for Lfu<K> {
+    fn default() -> Self {
+        Lfu::init()
+    }
+}
+
+impl<K: Hash + Eq + Clone> Policy<K> for Lfu<K> {
+    fn on_insert(&mut self, key: &K) {
+        self.on_remove(key);
+
+        self.usage.insert(key.clone(), (1, self.tick));
+        self.order.insert((1, self.tick), key.clone());
+        self.tick += 1;
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if let Some(usage) = self.usage.get_mut(key) {
+            self.order.remove(usage);
+
+            *usage = (usage.0 + 1, self.tick);
+            self.order.insert(*usage, key.clone());
+            self.tick += 1;
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(usage) = self.usage.remove(key) {
+            self.order.remove(&usage);
+        }
+    }
+
+    fn victim(&mut self) -> Option<K> {
+        let usage = *self.order.keys().next()?;
+        let key = self.order.remove(&usage).unwrap();
+        self.usage.remove(&key);
+
+        Some(key)
+    }
+}
+
+/// Time to live eviction policy. Every key expires after a fixed duration from its insertion.
+/// When cache is full, the oldest key is evicted
+///
+/// # Examples
+/// ```
+/// use rudac::cache::{Ttl, Policy};
+/// use std::time::Duration;
+///
+/// let mut ttl = Ttl::init(Duration::from_secs(60));
+///
+/// ttl.on_insert(&"a");
+///
+/// assert_eq!(ttl.is_expired(&"a"), false);
+/// ```
+#[derive(Debug)]
+pub struct Ttl<K> {
+    // how long each key stays valid
+    time_to_live: Duration,
+
+    // insertion time and insertion order of each key
+    inserted: HashMap<K, (Instant, u64)>,
+
+    // keys ordered by insertion
+    order: BTreeMap<u64, K>,
+
+    // logical clock
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone> Ttl<K> {
+    /// Initializes an empty TTL policy
+    ///
+    /// # Arguments
+    /// * `time_to_live`: how long a key stays valid after insertion
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::cache::Ttl;
+    /// use std::time::Duration;
+    ///
+    /// let ttl: Ttl<usize> = Ttl::init(Duration::from_millis(500));
+    /// ```
+    pub fn init(time_to_live: Duration) -> Ttl<K> {
+        Ttl {
+            time_to_live,
+            inserted: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> Policy<K> for Ttl<K> {
+    fn on_insert(&mut self, key: &K) {
+        self.on_remove(key);
+
+        self.inserted
+            .insert(key.clone(), (Instant::now(), self.tick));
+        self.order.insert(self.tick, key.clone());
+        self.tick += 1;
+    }
+
+    fn on_access(&mut self, _key: &K) {}
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some((_, tick)) = self.inserted.remove(key) {
+            self.order.remove(&tick);
+        }
+    }
+
+    fn victim(&mut self) -> Option<K> {
+        let tick = *self.order.keys().next()?;
+        let key = self.order.remove(&tick).unwrap();
+        self.inserted.remove(&key);
+
+        Some(key)
+    }
+
+    fn is_expired(&self, key: &K) -> bool {
+        match self.inserted.get(key) {
+            Some((instant, _)) => instant.elapsed() >= self.time_to_live,
+            None => false,
+        }
+    }
+}
+
+impl<K> MemoryFootprint for Lru<K> {
+    fn footprint(&self) -> Footprint {
+        Footprint::init(self.last_access.len(), 0)
+            .with_hash_map(&self.last_access)
+            .with_btree_map(&self.order)
+    }
+}
+
+impl<K> MemoryFootprint for Lfu<K> {
+    fn footprint(&self) -> Footprint {
+        Footprint::init(self.usage.len(), 0)
+            .with_hash_map(&self.usage)
+            .with_btree_map(&self.order)
+    }
+}
+
+impl<K> MemoryFootprint for Ttl<K> {
+    fn footprint(&self) -> Footprint {
+        Footprint::init(self.inserted.len(), 0)
+            .with_hash_map(&self.inserted)
+            .with_btree_map(&self.order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_policy_lru() {
+        let mut lru = Lru::init();
+
+        lru.on_insert(&1);
+        lru.on_insert(&2);
+        lru.on_insert(&3);
+        lru.on_access(&1);
+        lru.on_remove(&3);
+
+        assert_eq!(lru.victim(), Some(2));
+        assert_eq!(lru.victim(), Some(1));
+        assert_eq!(lru.victim(), None);
+    }
+
+    #[test]
+    fn cache_policy_lfu() {
+        let mut lfu = Lfu::init();
+
+        lfu.on_insert(&1);
+        lfu.on_insert(&2);
+        lfu.on_insert(&3);
+        lfu.on_access(&1);
+        lfu.on_access(&1);
+        lfu.on_access(&3);
+        lfu.on_access(&2);
+
+        // 2 and 3 are accessed equally, but 3 is accessed earlier
+        assert_eq!(lfu.victim(), Some(3));
+        assert_eq!(lfu.victim(), Some(2));
+        assert_eq!(lfu.victim(), Some(1));
+        assert_eq!(lfu.victim(), None);
+    }
+
+    #[test]
+    fn cache_policy_lfu_reinsert_resets_frequency() {
+        let mut lfu = Lfu::init();
+
+        lfu.on_insert(&1);
+        lfu.on_access(&1);
+        lfu.on_insert(&2);
+        lfu.on_access(&2);
+        lfu.on_insert(&1);
+
+        assert_eq!(lfu.victim(), Some(1));
+    }
+
+    #[test]
+    fn cache_policy_ttl() {
+        let mut expiring = Ttl::init(Duration::from_secs(0));
+        let mut lasting = Ttl::init(Duration::from_secs(3600));
+
+        expiring.on_insert(&1);
+        lasting.on_insert(&1);
+        lasting.on_insert(&2);
+        lasting.on_access(&1);
+
+        assert!(expiring.is_expired(&1));
+        assert!(!lasting.is_expired(&1));
+        assert!(!lasting.is_expired(&3));
+        assert_eq!(lasting.victim(), Some(1));
+    }
+}
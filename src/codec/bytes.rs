@@ -0,0 +1,192 @@
+use alloc::vec::Vec;
+
+/// Output buffer of the binary format. Integers are written as LEB128 varints, so small values take a single byte
+///
+/// # Examples
+/// ```
+/// use rudac::codec::{Reader, Writer};
+///
+/// let mut writer = Writer::init();
+/// writer.write_u8(7);
+/// writer.write_varint(300);
+/// writer.write_bytes(b"abc");
+///
+/// let bytes = writer.into_bytes();
+/// assert_eq!(bytes, vec![7, 0xac, 0x02, b'a', b'b', b'c']);
+///
+/// let mut reader = Reader::init(&bytes);
+/// assert_eq!(reader.read_u8(), Some(7));
+/// assert_eq!(reader.read_varint(), Some(300));
+/// assert_eq!(reader.read_bytes(3), Some(&b"abc"[..]));
+/// assert!(reader.is_at_end());
+/// assert_eq!(reader.read_u8(), None);
+/// ```
+#[derive(Debug, Default)]
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    /// Initializes an empty writer
+    pub fn init() -> Writer {
+        Writer { bytes: Vec::new() }
+    }
+
+    /// Appends a byte
+    ///
+    /// # Arguments
+    /// * `byte`: the byte
+    pub fn write_u8(&mut self, byte: u8) {
+        self.bytes.push(byte);
+    }
+
+    /// Appends `value` as a LEB128 varint
+    ///
+    /// # Arguments
+    /// * `value`: the value
+    pub fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    /// Appends raw bytes
+    ///
+    /// # Arguments
+    /// * `bytes`: the bytes
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Returns the written bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the writer and returns the written bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Input cursor of the binary format. Every read returns `None` instead of panicking when the input is too short
+/// or malformed, so corrupted snapshots are rejected
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Initializes a reader at the start of `bytes`
+    ///
+    /// # Arguments
+    /// * `bytes`: the input
+    pub fn init(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, position: 0 }
+    }
+
+    /// Returns true if all bytes have been read
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+
+    /// Returns number of unread bytes
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Reads a byte
+    pub fn read_u8(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.position)?;
+        self.position += 1;
+
+        Some(byte)
+    }
+
+    /// Reads a LEB128 varint. Returns `None` if it does not fit in 64 bits
+    pub fn read_varint(&mut self) -> Option<u64> {
+        let mut value: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift == 63 && byte > 1 {
+                return None;
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+            shift += 7;
+            if shift > 63 {
+                return None;
+            }
+        }
+    }
+
+    /// Reads a varint length or count. Returns `None` if it is larger than the number of unread bytes,
+    /// which bounds allocations made for malformed input
+    pub fn read_len(&mut self) -> Option<usize> {
+        let len = self.read_varint()?;
+        if len > self.remaining() as u64 {
+            return None;
+        }
+
+        Some(len as usize)
+    }
+
+    /// Reads `len` raw bytes
+    ///
+    /// # Arguments
+    /// * `len`: number of bytes
+    pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.remaining() {
+            return None;
+        }
+        let bytes = &self.bytes[self.position..self.position + len];
+        self.position += len;
+
+        Some(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_bytes_varint_round_trip() {
+        let values = [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX - 1, u64::MAX];
+        let mut writer = Writer::init();
+        for value in values.iter() {
+            writer.write_varint(*value);
+        }
+
+        let bytes = writer.into_bytes();
+        let mut reader = Reader::init(&bytes);
+        for value in values.iter() {
+            assert_eq!(reader.read_varint(), Some(*value));
+        }
+        assert!(reader.is_at_end());
+    }
+
+    #[test]
+    fn codec_bytes_rejects_malformed_varint() {
+        // eleven continuation bytes do not fit in 64 bits
+        let mut reader = Reader::init(&[0xff; 11]);
+        assert_eq!(reader.read_varint(), None);
+
+        let mut reader =
+            Reader::init(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]);
+        assert_eq!(reader.read_varint(), None);
+
+        // truncated
+        let mut reader = Reader::init(&[0x80]);
+        assert_eq!(reader.read_varint(), None);
+
+        // a length larger than the input
+        let mut reader = Reader::init(&[5, 1, 2]);
+        assert_eq!(reader.read_len(), None);
+    }
+}
@@ -0,0 +1,273 @@
+use super::{Reader, Writer};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Types that can write themselves in the binary format. Structures visit their elements through this trait,
+/// so any element type implementing it can be snapshotted
+pub trait Encode {
+    /// Writes `self` to `writer`
+    fn encode(&self, writer: &mut Writer);
+}
+
+/// Types that can be read back from the binary format
+pub trait Decode: Sized {
+    /// Reads a value from `reader`, or returns `None` if the input is malformed
+    fn decode(reader: &mut Reader) -> Option<Self>;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, writer: &mut Writer) {
+                    writer.write_varint(*self as u64);
+                }
+            }
+
+            impl Decode for $t {
+                fn decode(reader: &mut Reader) -> Option<Self> {
+                    let value = reader.read_varint()?;
+                    if value > <$t>::MAX as u64 {
+                        return None;
+                    }
+
+                    Some(value as $t)
+                }
+            }
+        )*
+    };
+}
+
+// signed integers are zigzag encoded so that small negative numbers stay short
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, writer: &mut Writer) {
+                    let value = *self as i64;
+                    writer.write_varint(((value << 1) ^ (value >> 63)) as u64);
+                }
+            }
+
+            impl Decode for $t {
+                fn decode(reader: &mut Reader) -> Option<Self> {
+                    let raw = reader.read_varint()?;
+                    let value = (raw >> 1) as i64 ^ -((raw & 1) as i64);
+                    if value < <$t>::MIN as i64 || value > <$t>::MAX as i64 {
+                        return None;
+                    }
+
+                    Some(value as $t)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, usize);
+impl_signed!(i8, i16, i32, i64, isize);
+
+impl Encode for () {
+    fn encode(&self, _writer: &mut Writer) {}
+}
+
+impl Decode for () {
+    fn decode(_reader: &mut Reader) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_u8(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        match reader.read_u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Encode for char {
+    fn encode(&self, writer: &mut Writer) {
+        (*self as u32).encode(writer);
+    }
+}
+
+impl Decode for char {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        core::char::from_u32(u32::decode(reader)?)
+    }
+}
+
+impl Encode for f32 {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_bytes(&self.to_bits().to_le_bytes());
+    }
+}
+
+impl Decode for f32 {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        let mut bits = [0; 4];
+        bits.copy_from_slice(reader.read_bytes(4)?);
+
+        Some(f32::from_bits(u32::from_le_bytes(bits)))
+    }
+}
+
+impl Encode for f64 {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_bytes(&self.to_bits().to_le_bytes());
+    }
+}
+
+impl Decode for f64 {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        let mut bits = [0; 8];
+        bits.copy_from_slice(reader.read_bytes(8)?);
+
+        Some(f64::from_bits(u64::from_le_bytes(bits)))
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_varint(self.len() as u64);
+        writer.write_bytes(self.as_bytes());
+    }
+}
+
+impl Decode for String {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        let len = reader.read_len()?;
+        let bytes = reader.read_bytes(len)?;
+
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            None => writer.write_u8(0),
+            Some(value) => {
+                writer.write_u8(1);
+                value.encode(writer);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        match reader.read_u8()? {
+            0 => Some(None),
+            1 => Some(Some(T::decode(reader)?)),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_varint(self.len() as u64);
+        for item in self {
+            item.encode(writer);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        let len = reader.read_len()?;
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::decode(reader)?);
+        }
+
+        Some(items)
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, writer: &mut Writer) {
+        self.0.encode(writer);
+        self.1.encode(writer);
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        Some((A::decode(reader)?, B::decode(reader)?))
+    }
+}
+
+/// Writes the header of a structure snapshot: a tag naming the structure and the format version
+pub(crate) fn write_header(writer: &mut Writer, tag: &[u8; 2]) {
+    writer.write_bytes(tag);
+    writer.write_u8(super::VERSION);
+}
+
+/// Reads and checks the header of a structure snapshot
+pub(crate) fn read_header(reader: &mut Reader, tag: &[u8; 2]) -> Option<()> {
+    if reader.read_bytes(2)? != tag || reader.read_u8()? != super::VERSION {
+        return None;
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: Encode + Decode>(value: &T) -> Option<T> {
+        let mut writer = Writer::init();
+        value.encode(&mut writer);
+        let bytes = writer.into_bytes();
+        let mut reader = Reader::init(&bytes);
+        let decoded = T::decode(&mut reader);
+        assert!(reader.is_at_end());
+        decoded
+    }
+
+    #[test]
+    fn codec_encode_round_trip() {
+        for value in [0i64, -1, 1, i64::MIN, i64::MAX, -300].iter() {
+            assert_eq!(round_trip(value), Some(*value));
+        }
+        for value in [0u64, 1, u64::MAX].iter() {
+            assert_eq!(round_trip(value), Some(*value));
+        }
+        assert_eq!(round_trip(&i8::MIN), Some(i8::MIN));
+        assert_eq!(round_trip(&'ß'), Some('ß'));
+        assert_eq!(round_trip(&-0.5f64), Some(-0.5));
+        assert_eq!(
+            round_trip(&String::from("rudac")),
+            Some(String::from("rudac"))
+        );
+        assert_eq!(
+            round_trip(&vec![(1u32, Some(true)), (2, None)]),
+            Some(vec![(1, Some(true)), (2, None)])
+        );
+    }
+
+    #[test]
+    fn codec_encode_rejects_out_of_range() {
+        let mut writer = Writer::init();
+        300u32.encode(&mut writer);
+        (-200i32).encode(&mut writer);
+        writer.write_u8(2);
+
+        let bytes = writer.into_bytes();
+        let mut reader = Reader::init(&bytes);
+        assert_eq!(u8::decode(&mut reader), None);
+        assert_eq!(i8::decode(&mut reader), None);
+        assert_eq!(bool::decode(&mut reader), None);
+    }
+}
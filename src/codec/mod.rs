@@ -0,0 +1,18 @@
+//! A compact binary format for snapshots of rudac structures. Unlike a list of elements, a snapshot keeps the exact
+//! shape of the structure(node layout, colors, tree order of heaps), so a restored structure behaves identically to
+//! the original one. Structures provide `to_bytes` and `from_bytes` for element types implementing `Encode` and `Decode`.
+
+mod bytes;
+mod encode;
+
+pub use bytes::Reader;
+pub use bytes::Writer;
+pub use encode::Decode;
+pub use encode::Encode;
+pub(crate) use encode::{read_header, write_header};
+
+// version of the snapshot format, written after the structure tag
+const VERSION: u8 = 1;
+
+// deepest node accepted when restoring a tree, deeper input can not come from a balanced structure
+pub(crate) const MAX_DEPTH: usize = 128;
@@ -0,0 +1,178 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+/// An append-only sequence of bits packed into bytes, most significant bit first.
+/// Encoders write their output into a bit buffer and decoders read it back by index or with `iter`.
+///
+/// # Examples
+/// ```
+/// use rudac::compress::BitBuffer;
+///
+/// let mut bits = BitBuffer::init();
+/// bits.push(true);
+/// bits.push_bits(0b0110, 4);
+///
+/// assert_eq!(bits.size(), 5);
+/// assert_eq!(bits.bytes(), &[0b1011_0000]);
+/// assert_eq!(bits.iter().collect::<Vec<bool>>(), vec![true, false, true, true, false]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitBuffer {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitBuffer {
+    /// Initializes an empty bit buffer
+    pub fn init() -> BitBuffer {
+        BitBuffer {
+            bytes: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Initializes a bit buffer holding the first `len` bits of `bytes`
+    ///
+    /// # Arguments
+    /// * `bytes`: packed bits, most significant bit first
+    /// * `len`: number of bits
+    ///
+    /// # Panics
+    /// * panics if `bytes` holds fewer than `len` bits
+    pub fn from_bytes(mut bytes: Vec<u8>, len: usize) -> BitBuffer {
+        if len > bytes.len() * 8 {
+            panic!("Length exceeds the number of bits");
+        }
+
+        // keeps unused bits zero so that equal buffers have equal bytes
+        bytes.truncate(len.div_ceil(8));
+        if !len.is_multiple_of(8) {
+            let last = bytes.len() - 1;
+            bytes[last] &= 0xff << (8 - len % 8);
+        }
+
+        BitBuffer { bytes, len }
+    }
+
+    /// Returns number of bits
+    pub fn size(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a bit
+    ///
+    /// # Arguments
+    /// * `bit`: the bit
+    pub fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            let last = self.bytes.len() - 1;
+            self.bytes[last] |= 0x80 >> (self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    /// Appends the lowest `count` bits of `value`, most significant first
+    ///
+    /// # Arguments
+    /// * `value`: the bits
+    /// * `count`: number of bits to append
+    ///
+    /// # Panics
+    /// * panics if `count` is greater than 64
+    pub fn push_bits(&mut self, value: u64, count: usize) {
+        if count > 64 {
+            panic!("Count must be at most 64");
+        }
+
+        for shift in (0..count).rev() {
+            self.push(value >> shift & 1 == 1);
+        }
+    }
+
+    /// Returns bit at `index`
+    ///
+    /// # Arguments
+    /// * `index`: index of the bit
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn get(&self, index: usize) -> bool {
+        if index >= self.len {
+            panic!("Index out of range");
+        }
+
+        self.bytes[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+
+    /// Returns the packed bits. Unused bits of the last byte are zero
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the buffer and returns the packed bits
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns an iterator over the bits
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |index| self.get(index))
+    }
+
+    /// Removes all bits
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.len = 0;
+    }
+}
+
+impl MemoryFootprint for BitBuffer {
+    fn footprint(&self) -> Footprint {
+        // every element is a single bit
+        Footprint::init(self.len, self.len.div_ceil(8)).with_vec(&self.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compress_bit_buffer_push_and_get() {
+        let mut bits = BitBuffer::init();
+        let pattern: Vec<bool> = (0..37).map(|i| i % 3 == 0 || i % 7 == 0).collect();
+        for bit in pattern.iter() {
+            bits.push(*bit);
+        }
+
+        assert_eq!(bits.size(), 37);
+        assert_eq!(bits.bytes().len(), 5);
+        assert_eq!(bits.iter().collect::<Vec<bool>>(), pattern);
+
+        let restored = BitBuffer::from_bytes(bits.bytes().to_vec(), 37);
+        assert_eq!(restored, bits);
+    }
+
+    #[test]
+    fn compress_bit_buffer_from_bytes_clears_unused_bits() {
+        let bits = BitBuffer::from_bytes(vec![0xff, 0xff], 10);
+        assert_eq!(bits.bytes(), &[0xff, 0xc0]);
+
+        let mut other = BitBuffer::init();
+        other.push_bits(0x3ff, 10);
+        assert_eq!(bits, other);
+    }
+
+    #[test]
+    #[should_panic(expected = "Length exceeds the number of bits")]
+    fn compress_bit_buffer_from_bytes_too_short() {
+        BitBuffer::from_bytes(vec![0], 9);
+    }
+}
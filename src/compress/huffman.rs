@@ -0,0 +1,388 @@
+use super::BitBuffer;
+use crate::heap::MinMax;
+
+// longest code length accepted by `from_code_lengths`, codes are kept in a u128
+const MAX_CODE_LENGTH: usize = 127;
+
+/// A Huffman code is an optimal prefix code for symbols with known frequencies: frequent symbols get short codes.
+/// Symbols are `0..alphabet size`. Codes are canonical, so the code is fully described by the code length of each
+/// symbol and a decoder can be rebuilt from `code_lengths` alone.
+///
+/// # Examples
+/// ```
+/// use rudac::compress::Huffman;
+///
+/// let text = b"abracadabra";
+/// let huffman = Huffman::from_data(text);
+///
+/// // 'a' is the most frequent symbol
+/// assert_eq!(huffman.code_lengths()[b'a' as usize], 1);
+///
+/// let symbols: Vec<usize> = text.iter().map(|byte| *byte as usize).collect();
+/// let bits = huffman.encode(&symbols);
+/// assert_eq!(bits.size(), 23);
+///
+/// // the receiver only needs the code lengths
+/// let decoder = Huffman::from_code_lengths(huffman.code_lengths());
+/// assert_eq!(decoder.decode(&bits), Some(symbols));
+/// ```
+pub struct Huffman {
+    lengths: Vec<u8>,
+    codes: Vec<u128>,
+    // symbols with a code, sorted by code length then symbol
+    sorted: Vec<usize>,
+    // number of codes, first code and index of the first symbol in `sorted` of every code length
+    counts: Vec<usize>,
+    first: Vec<u128>,
+    offsets: Vec<usize>,
+}
+
+impl Huffman {
+    /// Builds an optimal code for symbols with the given frequencies. Symbols with zero frequency get no code.
+    /// If a single symbol has a non-zero frequency, its code is one bit long
+    /// * Complexity: O(n log n)
+    ///
+    /// # Arguments
+    /// * `frequencies`: frequency of every symbol
+    ///
+    /// # Panics
+    /// * panics if all frequencies are zero
+    pub fn from_frequencies(frequencies: &[u64]) -> Huffman {
+        let symbols: Vec<usize> = (0..frequencies.len())
+            .filter(|symbol| frequencies[*symbol] > 0)
+            .collect();
+        if symbols.is_empty() {
+            panic!("At least one symbol must have a non-zero frequency");
+        }
+
+        // leaves are 0..symbols.len(), internal nodes are appended after them
+        let mut parents: Vec<usize> = vec![0; symbols.len()];
+        let mut heap = MinMax::with_capacity(symbols.len());
+        for (leaf, symbol) in symbols.iter().enumerate() {
+            heap.push((frequencies[*symbol] as u128, leaf));
+        }
+        while heap.size() > 1 {
+            let (left_weight, left) = heap.pop_min().unwrap();
+            let (right_weight, right) = heap.pop_min().unwrap();
+            let parent = parents.len();
+            parents.push(parent);
+            parents[left] = parent;
+            parents[right] = parent;
+            heap.push((left_weight + right_weight, parent));
+        }
+
+        // parents are created after their children, so depths can be filled from the root down
+        let root = parents.len() - 1;
+        let mut depths = vec![0; parents.len()];
+        for node in (0..root).rev() {
+            depths[node] = depths[parents[node]] + 1;
+        }
+
+        let mut lengths = vec![0; frequencies.len()];
+        for (leaf, symbol) in symbols.iter().enumerate() {
+            lengths[*symbol] = depths[leaf].max(1) as u8;
+        }
+
+        Huffman::from_code_lengths(&lengths)
+    }
+
+    /// Builds an optimal code for the bytes of `data`
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `data`: the data
+    ///
+    /// # Panics
+    /// * panics if `data` is empty
+    pub fn from_data(data: &[u8]) -> Huffman {
+        let mut frequencies = vec![0; 256];
+        for byte in data {
+            frequencies[*byte as usize] += 1;
+        }
+
+        Huffman::from_frequencies(&frequencies)
+    }
+
+    /// Rebuilds the canonical code with the given code lengths. Symbols with length zero get no code
+    /// * Complexity: O(n log n)
+    ///
+    /// # Arguments
+    /// * `lengths`: code length of every symbol
+    ///
+    /// # Panics
+    /// * panics if the lengths are longer than 127 bits or there are too many short codes to form a prefix code
+    pub fn from_code_lengths(lengths: &[u8]) -> Huffman {
+        let max_length = lengths.iter().copied().max().unwrap_or(0) as usize;
+        let mut counts = vec![0; max_length + 1];
+        for length in lengths.iter().filter(|length| **length > 0) {
+            counts[*length as usize] += 1;
+        }
+
+        // Kraft's inequality: sum of 2^-length must be at most one
+        let fits = max_length <= MAX_CODE_LENGTH
+            && counts
+                .iter()
+                .enumerate()
+                .skip(1)
+                .try_fold(0u128, |sum, (length, count)| {
+                    let sum = sum + ((*count as u128) << (max_length - length));
+                    if sum > 1 << max_length {
+                        None
+                    } else {
+                        Some(sum)
+                    }
+                })
+                .is_some();
+        if !fits {
+            panic!("Code lengths do not form a prefix code");
+        }
+
+        let mut first = vec![0; max_length + 1];
+        let mut offsets = vec![0; max_length + 1];
+        let mut code = 0;
+        for length in 1..=max_length {
+            code = (code + counts[length - 1] as u128) << 1;
+            first[length] = code;
+            offsets[length] = offsets[length - 1] + counts[length - 1];
+        }
+        counts[0] = 0;
+
+        let mut sorted: Vec<usize> = (0..lengths.len())
+            .filter(|symbol| lengths[*symbol] > 0)
+            .collect();
+        sorted.sort_by_key(|symbol| (lengths[*symbol], *symbol));
+
+        let mut codes = vec![0; lengths.len()];
+        let mut next = first.clone();
+        for symbol in sorted.iter() {
+            let length = lengths[*symbol] as usize;
+            codes[*symbol] = next[length];
+            next[length] += 1;
+        }
+
+        Huffman {
+            lengths: lengths.to_vec(),
+            codes,
+            sorted,
+            counts,
+            first,
+            offsets,
+        }
+    }
+
+    /// Returns number of symbols in the alphabet
+    pub fn alphabet_size(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Returns code length of every symbol, zero for symbols without a code
+    pub fn code_lengths(&self) -> &[u8] {
+        &self.lengths
+    }
+
+    /// Returns code of `symbol` and its length in bits, or `None` if the symbol has no code
+    ///
+    /// # Arguments
+    /// * `symbol`: the symbol
+    pub fn code(&self, symbol: usize) -> Option<(u128, usize)> {
+        match self.lengths.get(symbol) {
+            Some(length) if *length > 0 => Some((self.codes[symbol], *length as usize)),
+            _ => None,
+        }
+    }
+
+    /// Appends code of `symbol` to `bits`
+    ///
+    /// # Arguments
+    /// * `symbol`: the symbol
+    /// * `bits`: the output
+    ///
+    /// # Panics
+    /// * panics if `symbol` has no code
+    pub fn encode_symbol(&self, symbol: usize, bits: &mut BitBuffer) {
+        let (code, length) = match self.code(symbol) {
+            Some(code) => code,
+            None => panic!("Symbol has no code"),
+        };
+
+        if length > 64 {
+            bits.push_bits((code >> 64) as u64, length - 64);
+            bits.push_bits(code as u64, 64);
+        } else {
+            bits.push_bits(code as u64, length);
+        }
+    }
+
+    /// Encodes `symbols`
+    /// * Complexity: O(number of output bits)
+    ///
+    /// # Arguments
+    /// * `symbols`: the symbols
+    ///
+    /// # Panics
+    /// * panics if a symbol has no code
+    pub fn encode(&self, symbols: &[usize]) -> BitBuffer {
+        let mut bits = BitBuffer::init();
+        for symbol in symbols {
+            self.encode_symbol(*symbol, &mut bits);
+        }
+
+        bits
+    }
+
+    /// Decodes `bits` into symbols. Returns `None` if the bits do not end at a code boundary
+    /// or contain a sequence that is not a code
+    /// * Complexity: O(number of input bits)
+    ///
+    /// # Arguments
+    /// * `bits`: the encoded symbols
+    pub fn decode(&self, bits: &BitBuffer) -> Option<Vec<usize>> {
+        let mut symbols = Vec::new();
+        let mut code = 0;
+        let mut length = 0;
+        for bit in bits.iter() {
+            code = code << 1 | bit as u128;
+            length += 1;
+            if length >= self.counts.len() {
+                return None;
+            }
+
+            let first = self.first[length];
+            if code >= first && code - first < self.counts[length] as u128 {
+                symbols.push(self.sorted[self.offsets[length] + (code - first) as usize]);
+                code = 0;
+                length = 0;
+            }
+        }
+
+        if length == 0 {
+            Some(symbols)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn cost(huffman: &Huffman, frequencies: &[u64]) -> u64 {
+        frequencies
+            .iter()
+            .zip(huffman.code_lengths())
+            .map(|(frequency, length)| frequency * *length as u64)
+            .sum()
+    }
+
+    // optimal cost by repeatedly merging the two smallest weights
+    fn optimal_cost(frequencies: &[u64]) -> u64 {
+        let mut weights: Vec<u64> = frequencies.iter().copied().filter(|f| *f > 0).collect();
+        let mut cost = 0;
+        while weights.len() > 1 {
+            weights.sort_unstable_by(|a, b| b.cmp(a));
+            let merged = weights.pop().unwrap() + weights.pop().unwrap();
+            cost += merged;
+            weights.push(merged);
+        }
+        cost
+    }
+
+    #[test]
+    fn compress_huffman_is_optimal_and_round_trips() {
+        let mut rng = SplitMix64::init(9);
+        for alphabet in [2, 3, 10, 256].iter() {
+            let frequencies: Vec<u64> = (0..*alphabet)
+                .map(|_| {
+                    if rng.next_below(4) == 0 {
+                        0
+                    } else {
+                        rng.next_below(1000) + 1
+                    }
+                })
+                .collect();
+            if frequencies.iter().all(|f| *f == 0) {
+                continue;
+            }
+            let huffman = Huffman::from_frequencies(&frequencies);
+            if frequencies.iter().filter(|f| **f > 0).count() > 1 {
+                assert_eq!(cost(&huffman, &frequencies), optimal_cost(&frequencies));
+            }
+
+            let coded: Vec<usize> = (0..*alphabet).filter(|s| frequencies[*s] > 0).collect();
+            let symbols: Vec<usize> = (0..500)
+                .map(|_| coded[rng.next_below(coded.len() as u64) as usize])
+                .collect();
+            let bits = huffman.encode(&symbols);
+            assert_eq!(huffman.decode(&bits), Some(symbols));
+        }
+    }
+
+    #[test]
+    fn compress_huffman_canonical_codes() {
+        let huffman = Huffman::from_code_lengths(&[3, 3, 3, 3, 3, 2, 4, 4]);
+        let codes: Vec<(u128, usize)> = (0..8).map(|s| huffman.code(s).unwrap()).collect();
+        assert_eq!(
+            codes,
+            vec![
+                (0b010, 3),
+                (0b011, 3),
+                (0b100, 3),
+                (0b101, 3),
+                (0b110, 3),
+                (0b00, 2),
+                (0b1110, 4),
+                (0b1111, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn compress_huffman_single_symbol_and_invalid_input() {
+        let huffman = Huffman::from_frequencies(&[0, 5, 0]);
+        assert_eq!(huffman.code(1), Some((0, 1)));
+        assert_eq!(huffman.code(0), None);
+        assert_eq!(
+            huffman.decode(&huffman.encode(&[1, 1, 1])),
+            Some(vec![1, 1, 1])
+        );
+
+        // `1` is not a code and `0` followed by nothing is fine, but a trailing partial code is not
+        let mut bits = BitBuffer::init();
+        bits.push(true);
+        assert_eq!(huffman.decode(&bits), None);
+
+        let two = Huffman::from_code_lengths(&[1, 2, 0]);
+        let mut partial = BitBuffer::init();
+        partial.push(true);
+        assert_eq!(two.decode(&partial), None);
+    }
+
+    #[test]
+    fn compress_huffman_long_codes() {
+        // Fibonacci frequencies give the deepest possible tree
+        let mut frequencies = vec![1u64, 1];
+        while frequencies.len() < 80 {
+            let next = frequencies[frequencies.len() - 1] + frequencies[frequencies.len() - 2];
+            frequencies.push(next);
+        }
+        let huffman = Huffman::from_frequencies(&frequencies);
+        assert_eq!(huffman.code_lengths().iter().max(), Some(&79));
+
+        let symbols: Vec<usize> = (0..80).collect();
+        assert_eq!(huffman.decode(&huffman.encode(&symbols)), Some(symbols));
+    }
+
+    #[test]
+    #[should_panic(expected = "Code lengths do not form a prefix code")]
+    fn compress_huffman_invalid_lengths() {
+        Huffman::from_code_lengths(&[1, 1, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "At least one symbol must have a non-zero frequency")]
+    fn compress_huffman_no_symbols() {
+        Huffman::from_frequencies(&[0, 0]);
+    }
+}
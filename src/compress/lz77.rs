@@ -0,0 +1,431 @@
+const MIN_MATCH: usize = 3;
+const DEFAULT_MAX_MATCH: usize = 258;
+const DEFAULT_MAX_CHAIN: usize = 128;
+const HASH_BITS: usize = 15;
+const NONE: usize = usize::MAX;
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (value.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Output of the LZ77 encoder: either a literal byte, or a copy of `length` bytes starting `distance` bytes back.
+/// The copied range may overlap the bytes it produces, e.g. a match with distance 1 repeats the last byte
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Literal(u8),
+    Match { distance: usize, length: usize },
+}
+
+/// LZ77 compression with a sliding window. The encoder finds earlier occurrences of the upcoming bytes through hash
+/// chains over 3 byte prefixes and greedily replaces the longest one within the window by a back reference.
+/// Encoding and decoding can also be done in a streaming fashion with `encoder` and `decoder`, which only keep
+/// about two windows of data in memory. The tokens are meant to be entropy coded, for example with `Huffman`.
+///
+/// # Examples
+/// ```
+/// use rudac::compress::{Lz77, Token};
+///
+/// let lz77 = Lz77::init(1 << 15);
+/// let tokens = lz77.encode(b"abcabcabcd");
+///
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         Token::Literal(b'a'),
+///         Token::Literal(b'b'),
+///         Token::Literal(b'c'),
+///         Token::Match { distance: 3, length: 6 },
+///         Token::Literal(b'd'),
+///     ]
+/// );
+/// assert_eq!(lz77.decode(&tokens), Some(b"abcabcabcd".to_vec()));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Lz77 {
+    window_size: usize,
+    max_match: usize,
+    max_chain: usize,
+}
+
+impl Lz77 {
+    /// Initializes the compressor with a window of `window_size` bytes, matches of up to 258 bytes
+    /// and hash chains searched up to 128 candidates deep
+    ///
+    /// # Arguments
+    /// * `window_size`: largest distance of a match
+    ///
+    /// # Panics
+    /// * panics if `window_size` is zero
+    pub fn init(window_size: usize) -> Lz77 {
+        Lz77::with_limits(window_size, DEFAULT_MAX_MATCH, DEFAULT_MAX_CHAIN)
+    }
+
+    /// Initializes the compressor with the given limits. Longer chains find longer matches at the cost of speed
+    ///
+    /// # Arguments
+    /// * `window_size`: largest distance of a match
+    /// * `max_match`: largest length of a match
+    /// * `max_chain`: largest number of candidates examined for a match
+    ///
+    /// # Panics
+    /// * panics if `window_size` or `max_chain` is zero, or `max_match` is less than 3
+    pub fn with_limits(window_size: usize, max_match: usize, max_chain: usize) -> Lz77 {
+        if window_size == 0 {
+            panic!("Window size must be greater than zero");
+        }
+        if max_match < MIN_MATCH {
+            panic!("Maximum match length must be at least 3");
+        }
+        if max_chain == 0 {
+            panic!("Maximum chain length must be greater than zero");
+        }
+
+        Lz77 {
+            window_size,
+            max_match,
+            max_chain,
+        }
+    }
+
+    /// Returns the window size
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Returns largest length of a match
+    pub fn max_match(&self) -> usize {
+        self.max_match
+    }
+
+    /// Returns largest number of candidates examined for a match
+    pub fn max_chain(&self) -> usize {
+        self.max_chain
+    }
+
+    /// Encodes `data` into tokens
+    /// * Complexity: O(n * max_chain * max_match)
+    ///
+    /// # Arguments
+    /// * `data`: the data
+    pub fn encode(&self, data: &[u8]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut encoder = self.encoder();
+        encoder.write(data, &mut tokens);
+        encoder.finish(&mut tokens);
+
+        tokens
+    }
+
+    /// Decodes `tokens`. Returns `None` if a match refers to bytes before the start of the data or outside the window
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `tokens`: the encoded data
+    pub fn decode(&self, tokens: &[Token]) -> Option<Vec<u8>> {
+        let mut output = Vec::new();
+        if self.decoder().write(tokens, &mut output) {
+            Some(output)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a streaming encoder
+    pub fn encoder(&self) -> Encoder {
+        Encoder {
+            config: *self,
+            buffer: Vec::new(),
+            start: 0,
+            position: 0,
+            hashed: 0,
+            head: vec![NONE; 1 << HASH_BITS],
+            previous: vec![NONE; self.window_size],
+        }
+    }
+
+    /// Returns a streaming decoder
+    pub fn decoder(&self) -> Decoder {
+        Decoder {
+            window_size: self.window_size,
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Streaming LZ77 encoder. Bytes are buffered until enough lookahead is available to find the longest match
+///
+/// # Examples
+/// ```
+/// use rudac::compress::Lz77;
+///
+/// let lz77 = Lz77::init(4096);
+/// let data: Vec<u8> = b"to be or not to be, ".iter().cycle().take(1000).copied().collect();
+///
+/// let mut tokens = Vec::new();
+/// let mut encoder = lz77.encoder();
+/// for chunk in data.chunks(64) {
+///     encoder.write(chunk, &mut tokens);
+/// }
+/// encoder.finish(&mut tokens);
+///
+/// assert!(tokens.len() < 30);
+/// assert_eq!(lz77.decode(&tokens), Some(data));
+/// ```
+pub struct Encoder {
+    config: Lz77,
+    buffer: Vec<u8>,
+    // absolute position of `buffer[0]` and of the next byte to encode
+    start: usize,
+    position: usize,
+    // positions before `hashed` are in the hash chains
+    hashed: usize,
+    head: Vec<usize>,
+    previous: Vec<usize>,
+}
+
+impl Encoder {
+    /// Feeds `data` to the encoder and appends the tokens that can already be produced to `tokens`
+    ///
+    /// # Arguments
+    /// * `data`: the next bytes
+    /// * `tokens`: the output
+    pub fn write(&mut self, data: &[u8], tokens: &mut Vec<Token>) {
+        self.buffer.extend_from_slice(data);
+        self.encode(self.config.max_match, tokens);
+        self.trim();
+    }
+
+    /// Encodes the buffered bytes and appends the last tokens to `tokens`
+    ///
+    /// # Arguments
+    /// * `tokens`: the output
+    pub fn finish(mut self, tokens: &mut Vec<Token>) {
+        self.encode(1, tokens);
+    }
+
+    fn end(&self) -> usize {
+        self.start + self.buffer.len()
+    }
+
+    // encodes while at least `lookahead` bytes are buffered after the current position
+    fn encode(&mut self, lookahead: usize, tokens: &mut Vec<Token>) {
+        while self.end() - self.position >= lookahead && self.position < self.end() {
+            self.insert_hashes();
+            let (distance, length) = self.longest_match();
+            if length >= MIN_MATCH {
+                tokens.push(Token::Match { distance, length });
+                self.position += length;
+            } else {
+                tokens.push(Token::Literal(self.buffer[self.position - self.start]));
+                self.position += 1;
+            }
+        }
+    }
+
+    // adds the positions before the current one to the hash chains, as soon as their 3 byte prefix is buffered
+    fn insert_hashes(&mut self) {
+        while self.hashed < self.position && self.hashed + MIN_MATCH <= self.end() {
+            let key = hash(&self.buffer[self.hashed - self.start..]);
+            self.previous[self.hashed % self.config.window_size] = self.head[key];
+            self.head[key] = self.hashed;
+            self.hashed += 1;
+        }
+    }
+
+    fn longest_match(&self) -> (usize, usize) {
+        let available = (self.end() - self.position).min(self.config.max_match);
+        if available < MIN_MATCH {
+            return (0, 0);
+        }
+
+        let current = &self.buffer[self.position - self.start..];
+        let mut best = (0, 0);
+        let mut candidate = self.head[hash(current)];
+        let mut steps = 0;
+        while candidate != NONE
+            && self.position - candidate <= self.config.window_size
+            && steps < self.config.max_chain
+        {
+            let earlier = &self.buffer[candidate - self.start..];
+            let length = (0..available)
+                .take_while(|i| earlier[*i] == current[*i])
+                .count();
+            if length > best.1 {
+                best = (self.position - candidate, length);
+                if length == available {
+                    break;
+                }
+            }
+
+            // a slot is reused once the window moves past it, the chain ends when positions stop decreasing
+            let next = self.previous[candidate % self.config.window_size];
+            if next == NONE || next >= candidate {
+                break;
+            }
+            candidate = next;
+            steps += 1;
+        }
+
+        best
+    }
+
+    // drops bytes that can no longer be referenced
+    fn trim(&mut self) {
+        let keep_from = self.position.saturating_sub(self.config.window_size);
+        if keep_from - self.start > self.config.window_size {
+            self.buffer.drain(..keep_from - self.start);
+            self.start = keep_from;
+        }
+    }
+}
+
+/// Streaming LZ77 decoder keeping the last window of output to resolve matches
+pub struct Decoder {
+    window_size: usize,
+    history: Vec<u8>,
+}
+
+impl Decoder {
+    /// Decodes `tokens` and appends the bytes to `output`.
+    /// Returns false if a match refers to bytes before the start of the data or outside the window
+    ///
+    /// # Arguments
+    /// * `tokens`: the next tokens
+    /// * `output`: the output
+    pub fn write(&mut self, tokens: &[Token], output: &mut Vec<u8>) -> bool {
+        for token in tokens {
+            match *token {
+                Token::Literal(byte) => self.history.push(byte),
+                Token::Match { distance, length } => {
+                    if distance == 0 || distance > self.window_size || distance > self.history.len()
+                    {
+                        return false;
+                    }
+                    for _ in 0..length {
+                        self.history
+                            .push(self.history[self.history.len() - distance]);
+                    }
+                }
+            }
+            output.extend_from_slice(&self.history[self.history.len() - token_length(token)..]);
+
+            if self.history.len() > 2 * self.window_size {
+                self.history.drain(..self.history.len() - self.window_size);
+            }
+        }
+
+        true
+    }
+}
+
+fn token_length(token: &Token) -> usize {
+    match token {
+        Token::Literal(_) => 1,
+        Token::Match { length, .. } => *length,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn sample(len: usize, seed: u64) -> Vec<u8> {
+        let mut rng = SplitMix64::init(seed);
+        let mut data = Vec::with_capacity(len);
+        while data.len() < len {
+            if data.len() > 10 && rng.next_below(2) == 0 {
+                let distance = 1 + rng.next_below(data.len().min(300) as u64) as usize;
+                for _ in 0..rng.next_below(40) {
+                    data.push(data[data.len() - distance]);
+                }
+            } else {
+                data.push(b'a' + rng.next_below(6) as u8);
+            }
+        }
+        data.truncate(len);
+        data
+    }
+
+    #[test]
+    fn compress_lz77_round_trip() {
+        for &(window, len) in [(1, 50), (16, 2000), (100, 5000), (1 << 15, 5000)].iter() {
+            let lz77 = Lz77::init(window);
+            let data = sample(len, window as u64);
+            let tokens = lz77.encode(&data);
+
+            for token in tokens.iter() {
+                if let Token::Match { distance, length } = token {
+                    assert!(*distance <= window);
+                    assert!(*length >= MIN_MATCH && *length <= lz77.max_match());
+                }
+            }
+            assert_eq!(lz77.decode(&tokens), Some(data));
+        }
+    }
+
+    #[test]
+    fn compress_lz77_streaming_matches_one_shot() {
+        let lz77 = Lz77::with_limits(64, 20, 8);
+        let data = sample(3000, 1);
+        let expected = lz77.encode(&data);
+
+        for chunk_size in [1, 7, 100].iter() {
+            let mut tokens = Vec::new();
+            let mut encoder = lz77.encoder();
+            for chunk in data.chunks(*chunk_size) {
+                encoder.write(chunk, &mut tokens);
+            }
+            encoder.finish(&mut tokens);
+            assert_eq!(tokens, expected);
+
+            let mut output = Vec::new();
+            let mut decoder = lz77.decoder();
+            for chunk in tokens.chunks(*chunk_size) {
+                assert!(decoder.write(chunk, &mut output));
+            }
+            assert_eq!(output, data);
+        }
+    }
+
+    #[test]
+    fn compress_lz77_overlapping_match() {
+        let lz77 = Lz77::init(8);
+        let tokens = lz77.encode(b"aaaaaaaa");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Literal(b'a'),
+                Token::Match {
+                    distance: 1,
+                    length: 7
+                }
+            ]
+        );
+        assert_eq!(lz77.encode(b""), vec![]);
+    }
+
+    #[test]
+    fn compress_lz77_invalid_distance() {
+        let lz77 = Lz77::init(4);
+        let far = Token::Match {
+            distance: 5,
+            length: 3,
+        };
+        let before_start = Token::Match {
+            distance: 2,
+            length: 3,
+        };
+        let literals = vec![Token::Literal(b'x'); 6];
+
+        assert_eq!(lz77.decode(&[literals.clone(), vec![far]].concat()), None);
+        assert_eq!(lz77.decode(&[Token::Literal(b'x'), before_start]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Window size must be greater than zero")]
+    fn compress_lz77_zero_window() {
+        Lz77::init(0);
+    }
+}
@@ -0,0 +1,15 @@
+mod bit_buffer;
+mod huffman;
+mod lz77;
+mod range_coder;
+
+pub use bit_buffer::BitBuffer;
+pub use huffman::Huffman;
+pub use lz77::Decoder;
+pub use lz77::Encoder;
+pub use lz77::Lz77;
+pub use lz77::Token;
+pub use range_coder::AdaptiveModel;
+pub use range_coder::RangeCoder;
+pub use range_coder::RangeDecoder;
+pub use range_coder::RangeEncoder;
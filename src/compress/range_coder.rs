@@ -0,0 +1,401 @@
+use crate::tree::Fenwick;
+
+const TOP: u32 = 1 << 24;
+// totals are kept below 2^16 so that `range / total` keeps enough precision
+const MAX_TOTAL: u32 = 1 << 16;
+const INCREMENT: u32 = 32;
+
+/// An adaptive frequency model for a range coder. Every symbol starts with frequency one and gains weight each time
+/// it is coded, so the model learns the distribution of the data as it goes. Cumulative frequencies are kept in a
+/// Fenwick tree, which makes both updating a symbol and finding the symbol of a cumulative frequency O(log n).
+/// Frequencies are halved once their total reaches 2^16.
+///
+/// Encoder and decoder must start with identical models and update them in the same order.
+pub struct AdaptiveModel {
+    frequencies: Fenwick<u32>,
+    total: u32,
+}
+
+impl AdaptiveModel {
+    /// Initializes a model of `alphabet_size` symbols with equal frequencies
+    ///
+    /// # Arguments
+    /// * `alphabet_size`: number of symbols
+    ///
+    /// # Panics
+    /// * panics if `alphabet_size` is zero or not less than 2^15
+    pub fn init(alphabet_size: usize) -> AdaptiveModel {
+        if alphabet_size == 0 || alphabet_size as u32 >= MAX_TOTAL / 2 {
+            panic!("Alphabet size must be between 1 and 2^15 - 1");
+        }
+
+        AdaptiveModel {
+            frequencies: Fenwick::from_slice(&vec![1; alphabet_size]),
+            total: alphabet_size as u32,
+        }
+    }
+
+    /// Returns number of symbols
+    pub fn alphabet_size(&self) -> usize {
+        self.frequencies.size()
+    }
+
+    /// Returns frequency of `symbol`
+    ///
+    /// # Arguments
+    /// * `symbol`: the symbol
+    ///
+    /// # Panics
+    /// * panics if `symbol` is out of range
+    pub fn frequency(&self, symbol: usize) -> u32 {
+        self.frequencies.get(symbol)
+    }
+
+    /// Returns sum of the frequencies of all symbols
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Increases frequency of `symbol`
+    ///
+    /// # Arguments
+    /// * `symbol`: the coded symbol
+    ///
+    /// # Panics
+    /// * panics if `symbol` is out of range
+    pub fn update(&mut self, symbol: usize) {
+        self.frequencies.add(symbol, INCREMENT);
+        self.total += INCREMENT;
+
+        if self.total >= MAX_TOTAL {
+            let halved: Vec<u32> = (0..self.alphabet_size())
+                .map(|symbol| self.frequencies.get(symbol).div_ceil(2))
+                .collect();
+            self.total = halved.iter().sum();
+            self.frequencies = Fenwick::from_slice(&halved);
+        }
+    }
+
+    // cumulative frequency of the symbols before `symbol` and frequency of `symbol`
+    fn interval(&self, symbol: usize) -> (u32, u32) {
+        let start = self.frequencies.prefix_sum(symbol);
+        (start, self.frequencies.prefix_sum(symbol + 1) - start)
+    }
+
+    // symbol whose interval contains `target`
+    fn find(&self, target: u32) -> usize {
+        self.frequencies.search(target)
+    }
+}
+
+/// Range encoder writing bytes, with carry propagation through a cached byte and a count of pending 0xff bytes
+pub struct RangeEncoder {
+    low: u64,
+    range: u32,
+    cache: u8,
+    pending: usize,
+    output: Vec<u8>,
+}
+
+impl RangeEncoder {
+    /// Initializes an encoder
+    pub fn init() -> RangeEncoder {
+        RangeEncoder {
+            low: 0,
+            range: u32::MAX,
+            cache: 0,
+            pending: 1,
+            output: Vec::new(),
+        }
+    }
+
+    /// Encodes `symbol` with `model` and updates the model
+    ///
+    /// # Arguments
+    /// * `model`: the model
+    /// * `symbol`: the symbol
+    ///
+    /// # Panics
+    /// * panics if `symbol` is not in the alphabet of `model`
+    pub fn encode(&mut self, model: &mut AdaptiveModel, symbol: usize) {
+        if symbol >= model.alphabet_size() {
+            panic!("Symbol out of range");
+        }
+
+        let (start, frequency) = model.interval(symbol);
+        self.encode_interval(start, frequency, model.total());
+        model.update(symbol);
+    }
+
+    /// Encodes the interval `[start, start + frequency)` out of `total`.
+    /// This is the building block of custom models
+    ///
+    /// # Arguments
+    /// * `start`: cumulative frequency of the preceding symbols
+    /// * `frequency`: frequency of the symbol
+    /// * `total`: sum of all frequencies
+    ///
+    /// # Panics
+    /// * panics if the interval is empty, does not fit in `total` or `total` is greater than 2^16
+    pub fn encode_interval(&mut self, start: u32, frequency: u32, total: u32) {
+        if frequency == 0 || start + frequency > total || total > MAX_TOTAL {
+            panic!("Invalid interval");
+        }
+
+        let step = self.range / total;
+        self.low += u64::from(step) * u64::from(start);
+        self.range = step * frequency;
+        while self.range < TOP {
+            self.range <<= 8;
+            self.shift_low();
+        }
+    }
+
+    /// Flushes the encoder and returns the encoded bytes
+    pub fn finish(mut self) -> Vec<u8> {
+        for _ in 0..5 {
+            self.shift_low();
+        }
+
+        self.output
+    }
+
+    fn shift_low(&mut self) {
+        if self.low < 0xff00_0000 || self.low >= 1 << 32 {
+            let carry = (self.low >> 32) as u8;
+            let mut byte = self.cache;
+            while self.pending > 0 {
+                self.output.push(byte.wrapping_add(carry));
+                byte = 0xff;
+                self.pending -= 1;
+            }
+            self.cache = (self.low >> 24) as u8;
+        }
+        self.pending += 1;
+        self.low = (self.low & 0x00ff_ffff) << 8;
+    }
+}
+
+impl Default for RangeEncoder {
+    fn default() -> Self {
+        RangeEncoder::init()
+    }
+}
+
+/// Range decoder reading the output of `RangeEncoder`. Reading past the end of the input yields zero bytes
+pub struct RangeDecoder<'a> {
+    input: &'a [u8],
+    position: usize,
+    code: u32,
+    range: u32,
+}
+
+impl<'a> RangeDecoder<'a> {
+    /// Initializes a decoder of `input`
+    ///
+    /// # Arguments
+    /// * `input`: the encoded bytes
+    pub fn init(input: &'a [u8]) -> RangeDecoder<'a> {
+        let mut decoder = RangeDecoder {
+            input,
+            position: 0,
+            code: 0,
+            range: u32::MAX,
+        };
+        // the first byte is always zero, it holds the carry of the encoder
+        decoder.next_byte();
+        for _ in 0..4 {
+            decoder.code = decoder.code << 8 | decoder.next_byte() as u32;
+        }
+
+        decoder
+    }
+
+    /// Decodes a symbol with `model` and updates the model
+    ///
+    /// # Arguments
+    /// * `model`: the model, in the same state as the encoder's model was
+    pub fn decode(&mut self, model: &mut AdaptiveModel) -> usize {
+        let target = self.target(model.total());
+        let symbol = model.find(target);
+        let (start, frequency) = model.interval(symbol);
+        self.consume(start, frequency, model.total());
+        model.update(symbol);
+
+        symbol
+    }
+
+    /// Returns the cumulative frequency the next symbol's interval contains. It must be followed by `consume` with that
+    /// interval. Together they are the building block of custom models
+    ///
+    /// # Arguments
+    /// * `total`: sum of all frequencies
+    pub fn target(&self, total: u32) -> u32 {
+        (self.code / (self.range / total)).min(total - 1)
+    }
+
+    /// Removes the interval `[start, start + frequency)` out of `total` from the input
+    ///
+    /// # Arguments
+    /// * `start`: cumulative frequency of the preceding symbols
+    /// * `frequency`: frequency of the symbol
+    /// * `total`: sum of all frequencies
+    pub fn consume(&mut self, start: u32, frequency: u32, total: u32) {
+        let step = self.range / total;
+        self.code = self.code.wrapping_sub(step * start);
+        self.range = step * frequency;
+        while self.range < TOP {
+            self.code = self.code << 8 | self.next_byte() as u32;
+            self.range <<= 8;
+        }
+    }
+
+    fn next_byte(&mut self) -> u8 {
+        let byte = self.input.get(self.position).copied().unwrap_or(0);
+        self.position += 1;
+        byte
+    }
+}
+
+/// An adaptive order-0 range coder: arithmetic coding with an `AdaptiveModel`, approaching the entropy of the data
+/// without transmitting a code table. Unlike Huffman coding it can spend fractions of a bit per symbol.
+///
+/// # Examples
+/// ```
+/// use rudac::compress::RangeCoder;
+///
+/// let coder = RangeCoder::init(256);
+/// let data: Vec<usize> = b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab"
+///     .iter()
+///     .map(|byte| *byte as usize)
+///     .collect();
+///
+/// let bytes = coder.encode(&data);
+/// assert!(bytes.len() < 20);
+/// assert_eq!(coder.decode(&bytes, data.len()), data);
+/// ```
+pub struct RangeCoder {
+    alphabet_size: usize,
+}
+
+impl RangeCoder {
+    /// Initializes a coder of symbols `0..alphabet_size`
+    ///
+    /// # Arguments
+    /// * `alphabet_size`: number of symbols
+    ///
+    /// # Panics
+    /// * panics if `alphabet_size` is zero or not less than 2^15
+    pub fn init(alphabet_size: usize) -> RangeCoder {
+        AdaptiveModel::init(alphabet_size);
+
+        RangeCoder { alphabet_size }
+    }
+
+    /// Returns number of symbols
+    pub fn alphabet_size(&self) -> usize {
+        self.alphabet_size
+    }
+
+    /// Encodes `symbols`
+    /// * Complexity: O(n log alphabet)
+    ///
+    /// # Arguments
+    /// * `symbols`: the symbols
+    ///
+    /// # Panics
+    /// * panics if a symbol is out of range
+    pub fn encode(&self, symbols: &[usize]) -> Vec<u8> {
+        let mut model = AdaptiveModel::init(self.alphabet_size);
+        let mut encoder = RangeEncoder::init();
+        for symbol in symbols {
+            encoder.encode(&mut model, *symbol);
+        }
+
+        encoder.finish()
+    }
+
+    /// Decodes `count` symbols from `bytes`. The number of symbols is not stored in the encoded bytes
+    /// * Complexity: O(n log alphabet)
+    ///
+    /// # Arguments
+    /// * `bytes`: the encoded symbols
+    /// * `count`: number of symbols to decode
+    pub fn decode(&self, bytes: &[u8], count: usize) -> Vec<usize> {
+        let mut model = AdaptiveModel::init(self.alphabet_size);
+        let mut decoder = RangeDecoder::init(bytes);
+
+        (0..count).map(|_| decoder.decode(&mut model)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn compress_range_coder_round_trip() {
+        let mut rng = SplitMix64::init(21);
+        for &(alphabet, len) in [(1, 100), (2, 5000), (5, 3000), (256, 20000)].iter() {
+            let coder = RangeCoder::init(alphabet);
+            // skewed distribution: small symbols are much more likely
+            let symbols: Vec<usize> = (0..len)
+                .map(|_| {
+                    let value = rng.next_below(alphabet as u64) as usize;
+                    value * rng.next_below(alphabet as u64 + 1) as usize / alphabet
+                })
+                .collect();
+
+            let bytes = coder.encode(&symbols);
+            assert_eq!(coder.decode(&bytes, symbols.len()), symbols);
+        }
+    }
+
+    #[test]
+    fn compress_range_coder_approaches_entropy() {
+        let mut rng = SplitMix64::init(4);
+        // p(0) = 0.9, p(1) = 0.1, entropy is about 0.469 bits per symbol
+        let symbols: Vec<usize> = (0..100_000)
+            .map(|_| if rng.next_below(10) == 0 { 1 } else { 0 })
+            .collect();
+
+        let coder = RangeCoder::init(2);
+        let bytes = coder.encode(&symbols);
+        let bits_per_symbol = bytes.len() as f64 * 8.0 / symbols.len() as f64;
+        assert!(bits_per_symbol < 0.5, "{}", bits_per_symbol);
+        assert_eq!(coder.decode(&bytes, symbols.len()), symbols);
+    }
+
+    #[test]
+    fn compress_range_coder_model_rescales() {
+        let mut model = AdaptiveModel::init(3);
+        for _ in 0..5000 {
+            model.update(1);
+            assert!(model.total() < MAX_TOTAL);
+            assert_eq!(
+                model.total(),
+                (0..3).map(|symbol| model.frequency(symbol)).sum::<u32>()
+            );
+        }
+        assert!(model.frequency(0) >= 1);
+        assert!(model.frequency(1) > 1000);
+    }
+
+    #[test]
+    fn compress_range_coder_carry_propagation() {
+        // runs of the most probable symbol push `low` towards 0xff.. and exercise the pending bytes
+        let coder = RangeCoder::init(3);
+        let mut symbols = vec![2; 3000];
+        symbols.extend(vec![0; 10]);
+        symbols.extend(vec![2; 3000]);
+        let bytes = coder.encode(&symbols);
+        assert_eq!(coder.decode(&bytes, symbols.len()), symbols);
+    }
+
+    #[test]
+    #[should_panic(expected = "Symbol out of range")]
+    fn compress_range_coder_symbol_out_of_range() {
+        RangeCoder::init(4).encode(&[4]);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::geometry::{orientation_determinant, Point};
+
+/// Returns the convex hull of `points` in counter clockwise order starting from the lowest-leftmost point,
+/// using Andrew's monotone chain algorithm. Collinear points on the hull boundary are not included.
+/// * Complexity: O(n log n)
+///
+/// # Arguments
+/// * `points`: set of points
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{convex_hull, Point};
+///
+/// let points = vec![Point::new(0, 0), Point::new(2, 0), Point::new(1, 1), Point::new(2, 2), Point::new(0, 2)];
+///
+/// assert_eq!(
+///     convex_hull(&points),
+///     vec![Point::new(0, 0), Point::new(2, 0), Point::new(2, 2), Point::new(0, 2)]
+/// );
+/// ```
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut sorted = points.to_vec();
+    sorted.sort();
+    sorted.dedup();
+
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<Point> = Vec::with_capacity(2 * sorted.len());
+
+    // lower hull
+    for p in sorted.iter() {
+        while hull.len() >= 2
+            && orientation_determinant(&hull[hull.len() - 2], &hull[hull.len() - 1], p) <= 0
+        {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+
+    // upper hull
+    let lower_len = hull.len() + 1;
+    for p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower_len
+            && orientation_determinant(&hull[hull.len() - 2], &hull[hull.len() - 1], p) <= 0
+        {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+
+    // last point is the same as the first one
+    hull.pop();
+
+    hull
+}
+
+/// Returns the pair of points with the largest distance(diameter of the set) using rotating calipers over the convex hull.
+/// Returns `None` if there are less than two distinct points.
+/// * Complexity: O(n log n)
+///
+/// # Arguments
+/// * `points`: set of points
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{diameter, Point};
+///
+/// let points = vec![Point::new(0, 0), Point::new(1, 3), Point::new(4, 1), Point::new(2, 1)];
+///
+/// assert_eq!(diameter(&points), Some((Point::new(0, 0), Point::new(4, 1))));
+/// ```
+pub fn diameter(points: &[Point]) -> Option<(Point, Point)> {
+    let hull = convex_hull(points);
+    let n = hull.len();
+
+    if n < 2 {
+        return None;
+    }
+    if n == 2 {
+        return Some((hull[0], hull[1]));
+    }
+
+    let mut best = (hull[0], hull[1]);
+    let mut best_distance = Point::distance_squared(&hull[0], &hull[1]);
+
+    // for each edge (i, i + 1), j is the vertex farthest from the edge. j only moves forward
+    let mut j = 1;
+    for i in 0..n {
+        let next_i = (i + 1) % n;
+        while edge_distance(&hull, i, (j + 1) % n) > edge_distance(&hull, i, j) {
+            j = (j + 1) % n;
+        }
+
+        for candidate in &[i, next_i] {
+            let distance = Point::distance_squared(&hull[*candidate], &hull[j]);
+            if distance > best_distance {
+                best_distance = distance;
+                best = (hull[*candidate], hull[j]);
+            }
+        }
+    }
+
+    if best.0 > best.1 {
+        best = (best.1, best.0);
+    }
+
+    Some(best)
+}
+
+/// Returns the width of the set of points, which is the minimum distance between two parallel lines enclosing all the points.
+/// It is computed with rotating calipers over the convex hull. Returns `0.0` for collinear points.
+/// * Complexity: O(n log n)
+///
+/// # Arguments
+/// * `points`: set of points
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{width, Point};
+///
+/// let rectangle = vec![Point::new(0, 0), Point::new(10, 0), Point::new(10, 2), Point::new(0, 2)];
+///
+/// assert_eq!(width(&rectangle), 2.0);
+/// ```
+pub fn width(points: &[Point]) -> f64 {
+    let hull = convex_hull(points);
+    let n = hull.len();
+
+    if n < 3 {
+        return 0.0;
+    }
+
+    let mut best = f64::INFINITY;
+    let mut j = 1;
+    for i in 0..n {
+        while edge_distance(&hull, i, (j + 1) % n) > edge_distance(&hull, i, j) {
+            j = (j + 1) % n;
+        }
+
+        let edge_length = (Point::distance_squared(&hull[i], &hull[(i + 1) % n]) as f64).sqrt();
+        let distance = edge_distance(&hull, i, j) as f64 / edge_length;
+
+        if distance < best {
+            best = distance;
+        }
+    }
+
+    best
+}
+
+// twice the area of the triangle made by edge (i, i + 1) and vertex j, which is proportional to distance of j from the edge
+fn edge_distance(hull: &[Point], i: usize, j: usize) -> i128 {
+    orientation_determinant(&hull[i], &hull[(i + 1) % hull.len()], &hull[j]).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_calipers_hull_degenerate() {
+        assert_eq!(convex_hull(&[]), vec![]);
+        assert_eq!(
+            convex_hull(&[Point::new(1, 1), Point::new(1, 1)]),
+            vec![Point::new(1, 1)]
+        );
+        assert_eq!(
+            convex_hull(&[Point::new(0, 0), Point::new(1, 1), Point::new(2, 2)]),
+            vec![Point::new(0, 0), Point::new(2, 2)]
+        );
+    }
+
+    #[test]
+    fn geometry_calipers_hull_grid() {
+        let mut points = Vec::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                points.push(Point::new(x, y));
+            }
+        }
+
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                Point::new(0, 0),
+                Point::new(4, 0),
+                Point::new(4, 4),
+                Point::new(0, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn geometry_calipers_diameter_matches_brute_force() {
+        let points: Vec<Point> = (0..40)
+            .map(|i: i64| Point::new((i * 37) % 23 - 11, (i * 17) % 29 - 14))
+            .collect();
+
+        let mut expected = 0;
+        for a in &points {
+            for b in &points {
+                expected = std::cmp::max(expected, Point::distance_squared(a, b));
+            }
+        }
+
+        let (a, b) = diameter(&points).unwrap();
+        assert_eq!(Point::distance_squared(&a, &b), expected);
+        assert_eq!(diameter(&[Point::new(0, 0)]), None);
+    }
+
+    #[test]
+    fn geometry_calipers_width() {
+        let triangle = vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 3)];
+
+        // smallest height of the triangle is the one over the hypotenuse
+        assert!((width(&triangle) - 12.0 / 5.0).abs() < 1e-12);
+        assert_eq!(width(&[Point::new(0, 0), Point::new(5, 5)]), 0.0);
+    }
+}
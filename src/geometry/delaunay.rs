@@ -0,0 +1,704 @@
+use crate::geometry::{incircle_determinant, orientation_determinant, Point, Voronoi};
+use std::collections::HashMap;
+
+// largest absolute value of a coordinate for which incircle tests are exact
+const COORDINATE_LIMIT: i64 = 1 << 29;
+
+// vertex at infinity. triangles containing it (ghost triangles) lie outside of the convex hull
+const INFINITE: usize = usize::MAX;
+
+// triangle of the working triangulation. neighbors[i] is the triangle across the edge opposite vertices[i]
+#[derive(Clone, Copy)]
+struct Face {
+    vertices: [usize; 3],
+    neighbors: [usize; 3],
+    alive: bool,
+}
+
+impl Face {
+    fn is_ghost(&self) -> bool {
+        self.vertices.contains(&INFINITE)
+    }
+
+    fn index_of(&self, vertex: usize) -> usize {
+        self.vertices.iter().position(|v| *v == vertex).unwrap()
+    }
+}
+
+/// Delaunay triangulation of a set of points.
+/// No point of the set is strictly inside the circumcircle of any triangle.
+///
+/// Triangles and adjacency lists refer to points by their index in the input.
+/// Duplicate points are triangulated once, by their first occurrence, and later occurrences are left isolated.
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{delaunay, Point};
+///
+/// let points = vec![Point::new(0, 0), Point::new(4, 0), Point::new(4, 4), Point::new(0, 4), Point::new(2, 1)];
+/// let triangulation = delaunay(&points);
+///
+/// assert_eq!(triangulation.size(), 4);
+/// assert_eq!(triangulation.adjacent_vertices(4).len(), 4);
+/// assert_eq!(triangulation.nearest(&Point::new(3, 1)), Some(4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Triangulation {
+    points: Vec<Point>,
+    triangles: Vec<[usize; 3]>,
+    neighbors: Vec<[Option<usize>; 3]>,
+    adjacent_vertices: Vec<Vec<usize>>,
+    incident_triangles: Vec<Vec<usize>>,
+    on_hull: Vec<bool>,
+}
+
+/// Returns the Delaunay triangulation of `points` using the Bowyer-Watson algorithm.
+/// Points are inserted one by one, each point is located by walking through the triangulation
+/// and the triangles whose circumcircle contains it are replaced. All predicates are exact.
+/// * Complexity: O(n log n) expected for evenly distributed points, O(n^2) in the worst case
+///
+/// # Arguments
+/// * `points`: set of points
+///
+/// # Panics
+/// * panics if absolute value of any coordinate is not less than 2^29
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{delaunay, Point};
+///
+/// let points = vec![Point::new(0, 0), Point::new(2, 0), Point::new(1, 3)];
+///
+/// assert_eq!(delaunay(&points).triangles(), &[[0, 1, 2]]);
+/// ```
+pub fn delaunay(points: &[Point]) -> Triangulation {
+    for point in points {
+        if point.x.abs() >= COORDINATE_LIMIT || point.y.abs() >= COORDINATE_LIMIT {
+            panic!("Coordinates must have absolute value less than 2^29");
+        }
+    }
+
+    // first occurrence of every distinct point
+    let mut seen = HashMap::new();
+    let distinct: Vec<usize> = (0..points.len())
+        .filter(|i| seen.insert(points[*i], *i).is_none())
+        .collect();
+
+    match initial_triangle(points, &distinct) {
+        Some(initial) => Builder::init(points, initial).build(&distinct),
+        None => Triangulation::collinear(points, &distinct),
+    }
+}
+
+// returns three points making a counter clockwise triangle, or None if all points are collinear
+fn initial_triangle(points: &[Point], distinct: &[usize]) -> Option<[usize; 3]> {
+    if distinct.len() < 3 {
+        return None;
+    }
+
+    let a = distinct[0];
+    let b = distinct[1];
+    for c in distinct.iter().skip(2) {
+        let determinant = orientation_determinant(&points[a], &points[b], &points[*c]);
+        if determinant > 0 {
+            return Some([a, b, *c]);
+        } else if determinant < 0 {
+            return Some([a, *c, b]);
+        }
+    }
+
+    None
+}
+
+// incremental construction of the triangulation. the convex hull is closed by ghost triangles sharing the infinite vertex
+struct Builder<'a> {
+    points: &'a [Point],
+    faces: Vec<Face>,
+    last: usize,
+}
+
+impl<'a> Builder<'a> {
+    fn init(points: &'a [Point], initial: [usize; 3]) -> Builder<'a> {
+        let [a, b, c] = initial;
+        let faces = vec![
+            Face {
+                vertices: [a, b, c],
+                neighbors: [2, 3, 1],
+                alive: true,
+            },
+            // ghost triangles across edges (a, b), (b, c) and (c, a)
+            Face {
+                vertices: [b, a, INFINITE],
+                neighbors: [3, 2, 0],
+                alive: true,
+            },
+            Face {
+                vertices: [c, b, INFINITE],
+                neighbors: [1, 3, 0],
+                alive: true,
+            },
+            Face {
+                vertices: [a, c, INFINITE],
+                neighbors: [2, 1, 0],
+                alive: true,
+            },
+        ];
+
+        Builder {
+            points,
+            faces,
+            last: 0,
+        }
+    }
+
+    fn build(mut self, distinct: &[usize]) -> Triangulation {
+        let initial = self.faces[0].vertices;
+        for &vertex in distinct {
+            if !initial.contains(&vertex) {
+                self.insert(vertex);
+            }
+        }
+
+        self.finish()
+    }
+
+    // returns true if `vertex` is strictly inside the circumcircle of the face.
+    // circumcircle of a ghost triangle is the open half plane outside of its hull edge, plus the edge itself
+    fn conflicts(&self, face: usize, vertex: usize) -> bool {
+        let face = &self.faces[face];
+        let p = &self.points[vertex];
+
+        match face.vertices.iter().position(|v| *v == INFINITE) {
+            Some(i) => {
+                let a = &self.points[face.vertices[(i + 1) % 3]];
+                let b = &self.points[face.vertices[(i + 2) % 3]];
+                let determinant = orientation_determinant(a, b, p);
+
+                determinant > 0
+                    || (determinant == 0 && std::cmp::min(a, b) < p && p < std::cmp::max(a, b))
+            }
+            None => {
+                let [a, b, c] = face.vertices;
+                incircle_determinant(&self.points[a], &self.points[b], &self.points[c], p) > 0
+            }
+        }
+    }
+
+    // walks from the last created triangle towards `vertex` and returns a face in conflict with it
+    fn locate(&self, vertex: usize) -> usize {
+        let p = &self.points[vertex];
+        let mut current = self.last;
+
+        loop {
+            let face = &self.faces[current];
+            if face.is_ghost() {
+                if self.conflicts(current, vertex) {
+                    return current;
+                }
+                current = face.neighbors[face.index_of(INFINITE)];
+                continue;
+            }
+
+            let next = (0..3).find(|i| {
+                let a = &self.points[face.vertices[(i + 1) % 3]];
+                let b = &self.points[face.vertices[(i + 2) % 3]];
+                orientation_determinant(a, b, p) < 0
+            });
+
+            match next {
+                Some(i) => current = face.neighbors[i],
+                None => return current,
+            }
+        }
+    }
+
+    fn insert(&mut self, vertex: usize) {
+        // faces whose circumcircle contains the point make a star shaped cavity around it
+        let start = self.locate(vertex);
+        let mut cavity = vec![start];
+        self.faces[start].alive = false;
+
+        let mut i = 0;
+        while i < cavity.len() {
+            let neighbors = self.faces[cavity[i]].neighbors;
+            for neighbor in neighbors.iter() {
+                if self.faces[*neighbor].alive && self.conflicts(*neighbor, vertex) {
+                    self.faces[*neighbor].alive = false;
+                    cavity.push(*neighbor);
+                }
+            }
+            i += 1;
+        }
+
+        // connect the point to every boundary edge of the cavity
+        let mut open_edges: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        for &face in cavity.iter() {
+            for i in 0..3 {
+                let outside = self.faces[face].neighbors[i];
+                if !self.faces[outside].alive {
+                    continue;
+                }
+
+                let u = self.faces[face].vertices[(i + 1) % 3];
+                let w = self.faces[face].vertices[(i + 2) % 3];
+                let created = self.faces.len();
+                self.faces.push(Face {
+                    vertices: [u, w, vertex],
+                    neighbors: [INFINITE, INFINITE, outside],
+                    alive: true,
+                });
+
+                let j = self.faces[outside]
+                    .neighbors
+                    .iter()
+                    .position(|n| *n == face)
+                    .unwrap();
+                self.faces[outside].neighbors[j] = created;
+
+                // edges (w, vertex) and (vertex, u) are shared with other new faces
+                for (k, edge) in [(0, (w, vertex)), (1, (vertex, u))].iter() {
+                    match open_edges.remove(&(edge.1, edge.0)) {
+                        Some((other, l)) => {
+                            self.faces[created].neighbors[*k] = other;
+                            self.faces[other].neighbors[l] = created;
+                        }
+                        None => {
+                            open_edges.insert(*edge, (created, *k));
+                        }
+                    }
+                }
+
+                if !self.faces[created].is_ghost() {
+                    self.last = created;
+                }
+            }
+        }
+    }
+
+    fn finish(self) -> Triangulation {
+        let n = self.points.len();
+
+        // index of every real face in the final triangulation
+        let mut index = vec![INFINITE; self.faces.len()];
+        let mut triangles = Vec::new();
+        for (i, face) in self.faces.iter().enumerate() {
+            if face.alive && !face.is_ghost() {
+                index[i] = triangles.len();
+                triangles.push(face.vertices);
+            }
+        }
+
+        let mut neighbors = Vec::with_capacity(triangles.len());
+        let mut any_face = vec![INFINITE; n];
+        for (i, face) in self.faces.iter().enumerate() {
+            if index[i] == INFINITE {
+                continue;
+            }
+
+            let mut adjacent = [None; 3];
+            for k in 0..3 {
+                let neighbor = index[face.neighbors[k]];
+                if neighbor != INFINITE {
+                    adjacent[k] = Some(neighbor);
+                }
+                any_face[face.vertices[k]] = i;
+            }
+            neighbors.push(adjacent);
+        }
+
+        let mut adjacent_vertices = vec![Vec::new(); n];
+        let mut incident_triangles = vec![Vec::new(); n];
+        let mut on_hull = vec![false; n];
+        for vertex in 0..n {
+            if any_face[vertex] == INFINITE {
+                continue;
+            }
+
+            // rotate counter clockwise around the vertex through real and ghost faces
+            let mut ring = Vec::new();
+            let mut current = any_face[vertex];
+            loop {
+                ring.push(current);
+                let face = &self.faces[current];
+                current = face.neighbors[(face.index_of(vertex) + 1) % 3];
+                if current == any_face[vertex] {
+                    break;
+                }
+            }
+
+            // start right after a ghost face so that triangles of hull vertices are contiguous
+            let len = ring.len();
+            let ghost = (0..len)
+                .find(|i| index[ring[*i]] == INFINITE && index[ring[(i + 1) % len]] != INFINITE);
+            if let Some(ghost) = ghost {
+                on_hull[vertex] = true;
+                ring.rotate_left((ghost + 1) % len);
+            }
+
+            let ring: Vec<usize> = ring.into_iter().filter(|f| index[*f] != INFINITE).collect();
+            for face in ring.iter() {
+                let face = &self.faces[*face];
+                adjacent_vertices[vertex].push(face.vertices[(face.index_of(vertex) + 1) % 3]);
+            }
+            if on_hull[vertex] {
+                let face = &self.faces[*ring.last().unwrap()];
+                adjacent_vertices[vertex].push(face.vertices[(face.index_of(vertex) + 2) % 3]);
+            }
+            incident_triangles[vertex] = ring.iter().map(|f| index[*f]).collect();
+        }
+
+        Triangulation {
+            points: self.points.to_vec(),
+            triangles,
+            neighbors,
+            adjacent_vertices,
+            incident_triangles,
+            on_hull,
+        }
+    }
+}
+
+impl Triangulation {
+    // triangulation of collinear points has no triangles. consecutive points along the line are adjacent
+    fn collinear(points: &[Point], distinct: &[usize]) -> Triangulation {
+        let n = points.len();
+        let mut sorted = distinct.to_vec();
+        sorted.sort_by_key(|i| points[*i]);
+
+        let mut adjacent_vertices = vec![Vec::new(); n];
+        for pair in sorted.windows(2) {
+            adjacent_vertices[pair[0]].push(pair[1]);
+            adjacent_vertices[pair[1]].push(pair[0]);
+        }
+
+        let mut on_hull = vec![false; n];
+        for vertex in distinct {
+            on_hull[*vertex] = true;
+        }
+
+        Triangulation {
+            points: points.to_vec(),
+            triangles: Vec::new(),
+            neighbors: Vec::new(),
+            adjacent_vertices,
+            incident_triangles: vec![Vec::new(); n],
+            on_hull,
+        }
+    }
+
+    /// Returns the triangulated points
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// Returns triangles of the triangulation. Vertices of each triangle are in counter clockwise order
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// Returns number of triangles
+    pub fn size(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Returns true if there are no triangles, which happens if there are less than three non-collinear points
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Returns triangles adjacent to `triangle`. The i-th item is the triangle across the edge opposite to the i-th vertex,
+    /// or `None` if the edge is on the convex hull
+    ///
+    /// # Arguments
+    /// * `triangle`: index of the triangle
+    ///
+    /// # Panics
+    /// * panics if `triangle` is out of range
+    pub fn neighbors(&self, triangle: usize) -> [Option<usize>; 3] {
+        self.neighbors[triangle]
+    }
+
+    /// Returns vertices connected to `vertex` by an edge in counter clockwise order around it.
+    /// For a vertex on the convex hull, the list starts and ends with its neighbors on the hull
+    ///
+    /// # Arguments
+    /// * `vertex`: index of the point
+    ///
+    /// # Panics
+    /// * panics if `vertex` is out of range
+    pub fn adjacent_vertices(&self, vertex: usize) -> &[usize] {
+        &self.adjacent_vertices[vertex]
+    }
+
+    /// Returns triangles having `vertex` as a corner in counter clockwise order around it
+    ///
+    /// # Arguments
+    /// * `vertex`: index of the point
+    ///
+    /// # Panics
+    /// * panics if `vertex` is out of range
+    pub fn incident_triangles(&self, vertex: usize) -> &[usize] {
+        &self.incident_triangles[vertex]
+    }
+
+    /// Returns true if `vertex` is on the boundary of the convex hull of the points
+    ///
+    /// # Arguments
+    /// * `vertex`: index of the point
+    ///
+    /// # Panics
+    /// * panics if `vertex` is out of range
+    pub fn is_on_hull(&self, vertex: usize) -> bool {
+        self.on_hull[vertex]
+    }
+
+    /// Returns all edges `(u, v)` with `u < v`
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = Vec::new();
+        for (u, adjacent) in self.adjacent_vertices.iter().enumerate() {
+            for v in adjacent.iter() {
+                if u < *v {
+                    edges.push((u, *v));
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Returns index of the point nearest to `query`. Returns `None` if there are no points.
+    /// It greedily walks along the edges towards the query, which always finds the nearest point in a Delaunay triangulation.
+    /// * Complexity: O(n) in the worst case, O(sqrt(n)) for evenly distributed points
+    ///
+    /// # Arguments
+    /// * `query`: the query point
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::geometry::{delaunay, Point};
+    ///
+    /// let points = vec![Point::new(0, 0), Point::new(10, 0), Point::new(5, 8), Point::new(5, 3)];
+    /// let triangulation = delaunay(&points);
+    ///
+    /// assert_eq!(triangulation.nearest(&Point::new(9, 1)), Some(1));
+    /// assert_eq!(triangulation.nearest(&Point::new(5, 4)), Some(3));
+    /// ```
+    pub fn nearest(&self, query: &Point) -> Option<usize> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let mut current = 0;
+        let mut best = Point::distance_squared(&self.points[current], query);
+        loop {
+            let closer = self.adjacent_vertices[current]
+                .iter()
+                .map(|v| (Point::distance_squared(&self.points[*v], query), *v))
+                .min()
+                .filter(|(distance, _)| *distance < best);
+
+            match closer {
+                Some((distance, vertex)) => {
+                    best = distance;
+                    current = vertex;
+                }
+                None => return Some(current),
+            }
+        }
+    }
+
+    /// Returns the Voronoi diagram of the points, which is the dual of the triangulation
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::geometry::{delaunay, Point};
+    ///
+    /// let points = vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 4), Point::new(4, 4), Point::new(2, 1)];
+    /// let voronoi = delaunay(&points).voronoi();
+    ///
+    /// assert!(voronoi.is_bounded(4));
+    /// assert!(!voronoi.is_bounded(0));
+    /// assert_eq!(voronoi.cell(4).len(), 4);
+    /// ```
+    pub fn voronoi(&self) -> Voronoi {
+        let vertices = self
+            .triangles
+            .iter()
+            .map(|[a, b, c]| circumcenter(&self.points[*a], &self.points[*b], &self.points[*c]))
+            .collect();
+        let bounded = self
+            .on_hull
+            .iter()
+            .zip(self.incident_triangles.iter())
+            .map(|(on_hull, triangles)| !on_hull && !triangles.is_empty())
+            .collect();
+
+        Voronoi::init(vertices, self.incident_triangles.clone(), bounded)
+    }
+}
+
+// center of the circle through three non-collinear points
+fn circumcenter(a: &Point, b: &Point, c: &Point) -> Point<f64> {
+    let (bx, by) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+    let (cx, cy) = (c.x as i128 - a.x as i128, c.y as i128 - a.y as i128);
+    let b_norm = bx * bx + by * by;
+    let c_norm = cx * cx + cy * cy;
+    let d = (2 * (bx * cy - by * cx)) as f64;
+
+    Point::new(
+        a.x as f64 + (cy * b_norm - by * c_norm) as f64 / d,
+        a.y as f64 + (bx * c_norm - cx * b_norm) as f64 / d,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{convex_hull, incircle, CircleLocation};
+
+    fn random_points(n: usize, range: i64, seed: u64) -> Vec<Point> {
+        let mut seed = seed;
+        let mut next = || -> i64 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % range as u64) as i64
+        };
+
+        (0..n).map(|_| Point::new(next(), next())).collect()
+    }
+
+    fn assert_delaunay(points: &[Point], triangulation: &Triangulation) {
+        let distinct: std::collections::HashSet<Point> = points.iter().copied().collect();
+        let hull = convex_hull(points).len();
+
+        // euler formula, counting collinear points on the hull boundary as hull vertices
+        let boundary = points
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| triangulation.is_on_hull(*i))
+            .count();
+        assert!(boundary >= hull);
+        assert_eq!(triangulation.size(), 2 * distinct.len() - 2 - boundary);
+
+        for [a, b, c] in triangulation.triangles() {
+            let (a, b, c) = (&points[*a], &points[*b], &points[*c]);
+            assert!(orientation_determinant(a, b, c) > 0);
+            for d in distinct.iter() {
+                assert_ne!(incircle(a, b, c, d), CircleLocation::Inside);
+            }
+        }
+
+        for (t, adjacent) in (0..triangulation.size()).map(|t| (t, triangulation.neighbors(t))) {
+            for neighbor in adjacent.iter().flatten() {
+                assert!(triangulation.neighbors(*neighbor).contains(&Some(t)));
+            }
+        }
+    }
+
+    #[test]
+    fn geometry_delaunay_degenerate() {
+        assert!(delaunay(&[]).is_empty());
+        assert_eq!(delaunay(&[]).nearest(&Point::new(0, 0)), None);
+
+        let points = vec![
+            Point::new(2, 2),
+            Point::new(0, 0),
+            Point::new(1, 1),
+            Point::new(1, 1),
+        ];
+        let triangulation = delaunay(&points);
+
+        assert!(triangulation.is_empty());
+        assert_eq!(triangulation.edges(), vec![(0, 2), (1, 2)]);
+        assert!(triangulation.adjacent_vertices(3).is_empty());
+        assert_eq!(triangulation.nearest(&Point::new(-5, 0)), Some(1));
+    }
+
+    #[test]
+    fn geometry_delaunay_square_grid() {
+        // all four corners of every cell are cocircular
+        let mut points = Vec::new();
+        for x in 0..6 {
+            for y in 0..6 {
+                points.push(Point::new(x, y));
+            }
+        }
+
+        let triangulation = delaunay(&points);
+
+        assert_eq!(triangulation.size(), 50);
+        assert_delaunay(&points, &triangulation);
+    }
+
+    #[test]
+    fn geometry_delaunay_random() {
+        for seed in 0..10 {
+            let points = random_points(60, if seed % 2 == 0 { 10 } else { 10000 }, seed);
+            let triangulation = delaunay(&points);
+
+            assert_delaunay(&points, &triangulation);
+        }
+    }
+
+    #[test]
+    fn geometry_delaunay_adjacency_order() {
+        let points = vec![
+            Point::new(0, 0),
+            Point::new(2, -2),
+            Point::new(4, 0),
+            Point::new(2, 2),
+            Point::new(2, 0),
+        ];
+        let triangulation = delaunay(&points);
+
+        let mut adjacent = triangulation.adjacent_vertices(4).to_vec();
+        let first = adjacent.iter().position(|v| *v == 0).unwrap();
+        adjacent.rotate_left(first);
+        assert_eq!(adjacent, vec![0, 1, 2, 3]);
+
+        // hull vertex: starts and ends on the hull
+        assert_eq!(triangulation.adjacent_vertices(1), &[2, 4, 0]);
+        assert_eq!(triangulation.incident_triangles(1).len(), 2);
+        assert!(triangulation.is_on_hull(1));
+        assert!(!triangulation.is_on_hull(4));
+    }
+
+    #[test]
+    fn geometry_delaunay_nearest_matches_brute_force() {
+        let points = random_points(200, 1000, 42);
+        let triangulation = delaunay(&points);
+
+        for query in random_points(100, 1200, 7) {
+            let expected = points
+                .iter()
+                .map(|p| Point::distance_squared(p, &query))
+                .min()
+                .unwrap();
+            let nearest = triangulation.nearest(&query).unwrap();
+
+            assert_eq!(Point::distance_squared(&points[nearest], &query), expected);
+        }
+    }
+
+    #[test]
+    fn geometry_delaunay_large_coordinates() {
+        let r = COORDINATE_LIMIT - 1;
+        let points = vec![
+            Point::new(-r, -r),
+            Point::new(r, -r),
+            Point::new(r, r),
+            Point::new(-r, r),
+            Point::new(0, 1),
+            Point::new(1, 0),
+        ];
+
+        assert_delaunay(&points, &delaunay(&points));
+    }
+
+    #[test]
+    #[should_panic(expected = "Coordinates must have absolute value less than 2^29")]
+    fn geometry_delaunay_coordinates_out_of_range() {
+        delaunay(&[Point::new(0, -COORDINATE_LIMIT)]);
+    }
+}
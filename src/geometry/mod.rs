@@ -0,0 +1,36 @@
+mod calipers;
+mod delaunay;
+mod point;
+mod polygon;
+mod predicates;
+mod segment;
+mod sweep;
+mod voronoi;
+
+pub use point::Point;
+
+pub use predicates::incircle;
+pub use predicates::incircle_determinant;
+pub use predicates::orientation;
+pub use predicates::orientation_determinant;
+pub use predicates::CircleLocation;
+pub use predicates::Orientation;
+
+pub use polygon::convex_polygon_contains;
+pub use polygon::convex_polygon_intersection;
+pub use polygon::polygon_area;
+pub use polygon::polygon_centroid;
+pub use polygon::polygon_twice_area;
+
+pub use calipers::convex_hull;
+pub use calipers::diameter;
+pub use calipers::width;
+
+pub use segment::Segment;
+
+pub use sweep::segment_intersections;
+
+pub use delaunay::delaunay;
+pub use delaunay::Triangulation;
+
+pub use voronoi::Voronoi;
@@ -0,0 +1,143 @@
+use std::ops::{Add, Mul, Sub};
+
+/// A point (or vector) in the plane.
+///
+/// Points with `i64` coordinates are used as input of exact algorithms. Points with `f64` coordinates are
+/// returned by constructions whose results are not always integral(ex. centroids and intersection points)
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::Point;
+///
+/// let a = Point::new(1, 2);
+/// let b = Point::new(3, 5);
+///
+/// assert_eq!(b - a, Point::new(2, 3));
+/// assert_eq!(Point::cross(&a, &b), -1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point<T = i64> {
+    /// x coordinate
+    pub x: T,
+
+    /// y coordinate
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    /// Creates a new point
+    ///
+    /// # Arguments
+    /// * `x`: x coordinate
+    /// * `y`: y coordinate
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl Point<i64> {
+    /// Returns the z component of the cross product of `a` and `b` as vectors.
+    /// It is computed in 128 bit arithmetic so it is exact
+    pub fn cross(a: &Point<i64>, b: &Point<i64>) -> i128 {
+        a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128
+    }
+
+    /// Returns the dot product of `a` and `b` as vectors, computed exactly
+    pub fn dot(a: &Point<i64>, b: &Point<i64>) -> i128 {
+        a.x as i128 * b.x as i128 + a.y as i128 * b.y as i128
+    }
+
+    /// Returns squared euclidean distance between `a` and `b`, computed exactly
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::geometry::Point;
+    ///
+    /// assert_eq!(Point::distance_squared(&Point::new(0, 0), &Point::new(3, 4)), 25);
+    /// ```
+    pub fn distance_squared(a: &Point<i64>, b: &Point<i64>) -> i128 {
+        let dx = a.x as i128 - b.x as i128;
+        let dy = a.y as i128 - b.y as i128;
+
+        dx * dx + dy * dy
+    }
+
+    /// Converts the point to floating point coordinates
+    pub fn to_f64(&self) -> Point<f64> {
+        Point::new(self.x as f64, self.y as f64)
+    }
+}
+
+impl Point<f64> {
+    /// Returns euclidean distance between `a` and `b`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::geometry::Point;
+    ///
+    /// assert_eq!(Point::distance(&Point::new(0.0, 0.0), &Point::new(3.0, 4.0)), 5.0);
+    /// ```
+    pub fn distance(a: &Point<f64>, b: &Point<f64>) -> f64 {
+        (a.x - b.x).hypot(a.y - b.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, factor: T) -> Point<T> {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_point_arithmetic() {
+        let a = Point::new(2, -1);
+        let b = Point::new(-3, 4);
+
+        assert_eq!(a + b, Point::new(-1, 3));
+        assert_eq!(a * 3, Point::new(6, -3));
+        assert_eq!(Point::dot(&a, &b), -10);
+        assert_eq!(Point::cross(&a, &b), 5);
+    }
+
+    #[test]
+    fn geometry_point_exact_for_large_coordinates() {
+        let a = Point::new(i64::MAX / 2, i64::MAX / 2);
+        let b = Point::new(-(i64::MAX / 2), i64::MAX / 2);
+
+        assert!(Point::cross(&a, &b) > 0);
+        assert_eq!(Point::dot(&a, &b), 0);
+    }
+
+    #[test]
+    fn geometry_point_ordering_is_lexicographic() {
+        let mut points = vec![Point::new(1, 0), Point::new(0, 5), Point::new(0, 1)];
+        points.sort();
+
+        assert_eq!(
+            points,
+            vec![Point::new(0, 1), Point::new(0, 5), Point::new(1, 0)]
+        );
+    }
+}
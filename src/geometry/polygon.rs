@@ -0,0 +1,298 @@
+use crate::geometry::{orientation_determinant, Point};
+
+/// Returns twice the signed area of a simple polygon using the shoelace formula.
+/// Area is positive if vertices are in counter clockwise order. Result is exact
+///
+/// # Arguments
+/// * `polygon`: vertices of the polygon in order
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{polygon_twice_area, Point};
+///
+/// let square = vec![Point::new(0, 0), Point::new(3, 0), Point::new(3, 3), Point::new(0, 3)];
+///
+/// assert_eq!(polygon_twice_area(&square), 18);
+/// ```
+pub fn polygon_twice_area(polygon: &[Point]) -> i128 {
+    let n = polygon.len();
+    let mut area = 0;
+
+    for i in 0..n {
+        area += Point::cross(&polygon[i], &polygon[(i + 1) % n]);
+    }
+
+    area
+}
+
+/// Returns the unsigned area of a simple polygon
+///
+/// # Arguments
+/// * `polygon`: vertices of the polygon in order
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{polygon_area, Point};
+///
+/// let triangle = vec![Point::new(0, 0), Point::new(0, 3), Point::new(1, 0)];
+///
+/// assert_eq!(polygon_area(&triangle), 1.5);
+/// ```
+pub fn polygon_area(polygon: &[Point]) -> f64 {
+    polygon_twice_area(polygon).abs() as f64 / 2.0
+}
+
+/// Returns the centroid(center of mass) of a simple polygon.
+/// Returns `None` if the polygon has zero area
+///
+/// # Arguments
+/// * `polygon`: vertices of the polygon in order
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{polygon_centroid, Point};
+///
+/// let square = vec![Point::new(0, 0), Point::new(2, 0), Point::new(2, 2), Point::new(0, 2)];
+///
+/// assert_eq!(polygon_centroid(&square), Some(Point::new(1.0, 1.0)));
+/// ```
+pub fn polygon_centroid(polygon: &[Point]) -> Option<Point<f64>> {
+    let twice_area = polygon_twice_area(polygon);
+    if twice_area == 0 {
+        return None;
+    }
+
+    let n = polygon.len();
+    let mut cx = 0;
+    let mut cy = 0;
+
+    for i in 0..n {
+        let a = &polygon[i];
+        let b = &polygon[(i + 1) % n];
+        let cross = Point::cross(a, b);
+
+        cx += (a.x as i128 + b.x as i128) * cross;
+        cy += (a.y as i128 + b.y as i128) * cross;
+    }
+
+    let divisor = 3.0 * twice_area as f64;
+
+    Some(Point::new(cx as f64 / divisor, cy as f64 / divisor))
+}
+
+/// Returns the intersection of two convex polygons using Sutherland-Hodgman clipping.
+/// Returns an empty vector if polygons do not overlap with positive area.
+/// * Complexity: O(n * m)
+///
+/// # Arguments
+/// * `subject`: vertices of the first convex polygon in counter clockwise order
+/// * `clip`: vertices of the second convex polygon in counter clockwise order
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{convex_polygon_intersection, Point};
+///
+/// let a = vec![Point::new(0, 0), Point::new(2, 0), Point::new(2, 2), Point::new(0, 2)];
+/// let b = vec![Point::new(1, 1), Point::new(3, 1), Point::new(3, 3), Point::new(1, 3)];
+///
+/// let intersection = convex_polygon_intersection(&a, &b);
+///
+/// assert_eq!(intersection.len(), 4);
+/// assert!(intersection.contains(&Point::new(1.0, 1.0)));
+/// assert!(intersection.contains(&Point::new(2.0, 2.0)));
+/// ```
+pub fn convex_polygon_intersection(subject: &[Point], clip: &[Point]) -> Vec<Point<f64>> {
+    let mut output: Vec<Point<f64>> = subject.iter().map(|p| p.to_f64()).collect();
+    let m = clip.len();
+
+    for i in 0..m {
+        if output.is_empty() {
+            break;
+        }
+
+        let edge_start = clip[i];
+        let edge_end = clip[(i + 1) % m];
+
+        // signed distance-like value. inside means left of the clip edge
+        let side = |p: &Point<f64>| -> f64 {
+            (edge_end.x - edge_start.x) as f64 * (p.y - edge_start.y as f64)
+                - (edge_end.y - edge_start.y) as f64 * (p.x - edge_start.x as f64)
+        };
+
+        let input = std::mem::take(&mut output);
+        let n = input.len();
+
+        for j in 0..n {
+            let current = input[j];
+            let next = input[(j + 1) % n];
+            let current_side = side(&current);
+            let next_side = side(&next);
+
+            if current_side >= 0.0 {
+                output.push(current);
+            }
+
+            if (current_side >= 0.0) != (next_side >= 0.0) {
+                let t = current_side / (current_side - next_side);
+                output.push(Point::new(
+                    current.x + (next.x - current.x) * t,
+                    current.y + (next.y - current.y) * t,
+                ));
+            }
+        }
+    }
+
+    output.dedup();
+    if output.len() > 1 && output.first() == output.last() {
+        output.pop();
+    }
+
+    // a point or a segment is not a polygon with positive area
+    if output.len() < 3 {
+        return Vec::new();
+    }
+
+    output
+}
+
+/// Returns true if `point` lies inside or on the boundary of a convex polygon.
+/// * Complexity: O(log n)
+///
+/// # Arguments
+/// * `polygon`: vertices of the convex polygon in counter clockwise order, with at least three vertices
+/// * `point`: query point
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{convex_polygon_contains, Point};
+///
+/// let triangle = vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 4)];
+///
+/// assert!(convex_polygon_contains(&triangle, &Point::new(1, 1)));
+/// assert!(convex_polygon_contains(&triangle, &Point::new(2, 2)));
+/// assert!(!convex_polygon_contains(&triangle, &Point::new(3, 3)));
+/// ```
+pub fn convex_polygon_contains(polygon: &[Point], point: &Point) -> bool {
+    let n = polygon.len();
+    if n < 3 {
+        return false;
+    }
+
+    let origin = &polygon[0];
+    if orientation_determinant(origin, &polygon[1], point) < 0
+        || orientation_determinant(origin, &polygon[n - 1], point) > 0
+    {
+        return false;
+    }
+
+    // binary search the fan triangle (origin, polygon[low], polygon[low + 1]) containing the point
+    let mut low = 1;
+    let mut high = n - 1;
+    while high - low > 1 {
+        let mid = (low + high) / 2;
+        if orientation_determinant(origin, &polygon[mid], point) >= 0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    orientation_determinant(&polygon[low], &polygon[low + 1], point) >= 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: i64, y: i64, size: i64) -> Vec<Point> {
+        vec![
+            Point::new(x, y),
+            Point::new(x + size, y),
+            Point::new(x + size, y + size),
+            Point::new(x, y + size),
+        ]
+    }
+
+    #[test]
+    fn geometry_polygon_area_orientation() {
+        let mut polygon = square(0, 0, 2);
+
+        assert_eq!(polygon_twice_area(&polygon), 8);
+
+        polygon.reverse();
+        assert_eq!(polygon_twice_area(&polygon), -8);
+        assert_eq!(polygon_area(&polygon), 4.0);
+    }
+
+    #[test]
+    fn geometry_polygon_centroid_concave() {
+        // L shape made of three unit squares
+        let polygon = vec![
+            Point::new(0, 0),
+            Point::new(2, 0),
+            Point::new(2, 1),
+            Point::new(1, 1),
+            Point::new(1, 2),
+            Point::new(0, 2),
+        ];
+
+        let centroid = polygon_centroid(&polygon).unwrap();
+
+        assert!((centroid.x - 5.0 / 6.0).abs() < 1e-12);
+        assert!((centroid.y - 5.0 / 6.0).abs() < 1e-12);
+        assert_eq!(
+            polygon_centroid(&[Point::new(0, 0), Point::new(1, 1), Point::new(2, 2)]),
+            None
+        );
+    }
+
+    #[test]
+    fn geometry_polygon_intersection_area() {
+        let a = square(0, 0, 4);
+        let b = vec![Point::new(2, -2), Point::new(6, 2), Point::new(2, 6)];
+
+        let intersection = convex_polygon_intersection(&a, &b);
+
+        // area by shoelace in floating point
+        let n = intersection.len();
+        let mut area = 0.0;
+        for i in 0..n {
+            let p = intersection[i];
+            let q = intersection[(i + 1) % n];
+            area += p.x * q.y - p.y * q.x;
+        }
+
+        assert!((area / 2.0 - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn geometry_polygon_intersection_disjoint_and_contained() {
+        assert!(convex_polygon_intersection(&square(0, 0, 1), &square(5, 5, 1)).is_empty());
+
+        // touching at a single edge has zero area
+        assert!(convex_polygon_intersection(&square(0, 0, 1), &square(1, 0, 1)).is_empty());
+
+        let inner = convex_polygon_intersection(&square(1, 1, 1), &square(0, 0, 4));
+        assert_eq!(inner.len(), 4);
+        assert!(inner.contains(&Point::new(1.0, 1.0)));
+        assert!(inner.contains(&Point::new(2.0, 2.0)));
+    }
+
+    #[test]
+    fn geometry_polygon_contains() {
+        let polygon = vec![
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(6, 2),
+            Point::new(4, 4),
+            Point::new(0, 4),
+        ];
+
+        assert!(convex_polygon_contains(&polygon, &Point::new(5, 2)));
+        assert!(convex_polygon_contains(&polygon, &Point::new(0, 2)));
+        assert!(convex_polygon_contains(&polygon, &Point::new(4, 4)));
+        assert!(!convex_polygon_contains(&polygon, &Point::new(6, 3)));
+        assert!(!convex_polygon_contains(&polygon, &Point::new(-1, 2)));
+        assert!(!convex_polygon_contains(&polygon, &Point::new(2, 5)));
+    }
+}
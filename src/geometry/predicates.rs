@@ -0,0 +1,196 @@
+use crate::geometry::Point;
+
+/// Orientation of an ordered triple of points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// points make a left turn
+    CounterClockwise,
+
+    /// points make a right turn
+    Clockwise,
+
+    /// points lie on a single line
+    Collinear,
+}
+
+/// Location of a point relative to a circle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircleLocation {
+    /// point is strictly inside the circle
+    Inside,
+
+    /// point is on the circle
+    On,
+
+    /// point is strictly outside the circle
+    Outside,
+}
+
+/// Returns orientation of the triple (`a`, `b`, `c`).
+/// Computation is done in 128 bit integers so the result is exact for coordinates with absolute value less than 2^62
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{orientation, Orientation, Point};
+///
+/// let a = Point::new(0, 0);
+/// let b = Point::new(4, 0);
+///
+/// assert_eq!(orientation(&a, &b, &Point::new(1, 1)), Orientation::CounterClockwise);
+/// assert_eq!(orientation(&a, &b, &Point::new(1, -1)), Orientation::Clockwise);
+/// assert_eq!(orientation(&a, &b, &Point::new(9, 0)), Orientation::Collinear);
+/// ```
+pub fn orientation(a: &Point, b: &Point, c: &Point) -> Orientation {
+    let determinant = orientation_determinant(a, b, c);
+
+    if determinant > 0 {
+        Orientation::CounterClockwise
+    } else if determinant < 0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+/// Returns twice the signed area of the triangle (`a`, `b`, `c`).
+/// It is positive if the points are in counter clockwise order
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{orientation_determinant, Point};
+///
+/// assert_eq!(orientation_determinant(&Point::new(0, 0), &Point::new(2, 0), &Point::new(0, 2)), 4);
+/// ```
+pub fn orientation_determinant(a: &Point, b: &Point, c: &Point) -> i128 {
+    let abx = b.x as i128 - a.x as i128;
+    let aby = b.y as i128 - a.y as i128;
+    let acx = c.x as i128 - a.x as i128;
+    let acy = c.y as i128 - a.y as i128;
+
+    abx * acy - aby * acx
+}
+
+/// Returns location of `d` relative to the circle passing through `a`, `b` and `c`.
+/// Computation is done in 128 bit integers so the result is exact for coordinates with absolute value less than 2^29
+///
+/// # Panics
+/// * panics if `a`, `b` and `c` are collinear
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{incircle, CircleLocation, Point};
+///
+/// let a = Point::new(-1, 0);
+/// let b = Point::new(1, 0);
+/// let c = Point::new(0, 1);
+///
+/// assert_eq!(incircle(&a, &b, &c, &Point::new(0, 0)), CircleLocation::Inside);
+/// assert_eq!(incircle(&a, &b, &c, &Point::new(0, -1)), CircleLocation::On);
+/// assert_eq!(incircle(&a, &b, &c, &Point::new(2, 2)), CircleLocation::Outside);
+/// ```
+pub fn incircle(a: &Point, b: &Point, c: &Point, d: &Point) -> CircleLocation {
+    let orient = orientation_determinant(a, b, c);
+    if orient == 0 {
+        panic!("First three points must not be collinear");
+    }
+
+    let determinant = incircle_determinant(a, b, c, d);
+
+    // determinant is positive when d is inside and a, b, c are counter clockwise
+    let inside = if orient > 0 {
+        determinant
+    } else {
+        -determinant
+    };
+
+    if inside > 0 {
+        CircleLocation::Inside
+    } else if inside < 0 {
+        CircleLocation::Outside
+    } else {
+        CircleLocation::On
+    }
+}
+
+/// Returns the incircle determinant of (`a`, `b`, `c`, `d`).
+/// It is positive if `d` is inside the circle through counter clockwise points `a`, `b` and `c`
+pub fn incircle_determinant(a: &Point, b: &Point, c: &Point, d: &Point) -> i128 {
+    let adx = a.x as i128 - d.x as i128;
+    let ady = a.y as i128 - d.y as i128;
+    let bdx = b.x as i128 - d.x as i128;
+    let bdy = b.y as i128 - d.y as i128;
+    let cdx = c.x as i128 - d.x as i128;
+    let cdy = c.y as i128 - d.y as i128;
+
+    let ad = adx * adx + ady * ady;
+    let bd = bdx * bdx + bdy * bdy;
+    let cd = cdx * cdx + cdy * cdy;
+
+    adx * (bdy * cd - bd * cdy) - ady * (bdx * cd - bd * cdx) + ad * (bdx * cdy - bdy * cdx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_predicates_orientation_near_degenerate() {
+        // floating point evaluation of these points gives wrong answers, integer evaluation must not
+        let a = Point::new(1 << 52, 1 << 52);
+        let b = Point::new((1 << 52) + 1, (1 << 52) + 1);
+        let c = Point::new((1 << 53) + 3, (1 << 53) + 2);
+
+        assert_eq!(orientation(&a, &b, &c), Orientation::Clockwise);
+        assert_eq!(orientation(&a, &c, &b), Orientation::CounterClockwise);
+        assert_eq!(
+            orientation(&a, &b, &Point::new((1 << 53) + 2, (1 << 53) + 2)),
+            Orientation::Collinear
+        );
+    }
+
+    #[test]
+    fn geometry_predicates_incircle_orientation_independent() {
+        let a = Point::new(0, 0);
+        let b = Point::new(4, 0);
+        let c = Point::new(0, 4);
+
+        for d in &[Point::new(1, 1), Point::new(4, 4), Point::new(5, 5)] {
+            assert_eq!(incircle(&a, &b, &c, d), incircle(&a, &c, &b, d));
+        }
+
+        assert_eq!(incircle(&a, &b, &c, &Point::new(4, 4)), CircleLocation::On);
+        assert_eq!(
+            incircle(&a, &b, &c, &Point::new(5, 5)),
+            CircleLocation::Outside
+        );
+    }
+
+    #[test]
+    fn geometry_predicates_incircle_large_coordinates() {
+        let r = 1 << 28;
+        let a = Point::new(-r, 0);
+        let b = Point::new(r, 0);
+        let c = Point::new(0, r);
+
+        assert_eq!(incircle(&a, &b, &c, &Point::new(0, -r)), CircleLocation::On);
+        assert_eq!(
+            incircle(&a, &b, &c, &Point::new(0, -r - 1)),
+            CircleLocation::Outside
+        );
+        assert_eq!(
+            incircle(&a, &b, &c, &Point::new(0, -r + 1)),
+            CircleLocation::Inside
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "First three points must not be collinear")]
+    fn geometry_predicates_incircle_collinear() {
+        incircle(
+            &Point::new(0, 0),
+            &Point::new(1, 1),
+            &Point::new(2, 2),
+            &Point::new(0, 1),
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::geometry::{orientation_determinant, Point};
+
+/// A closed line segment between two points
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{Point, Segment};
+///
+/// let a = Segment::new(Point::new(0, 0), Point::new(2, 2));
+/// let b = Segment::new(Point::new(0, 2), Point::new(2, 0));
+///
+/// assert!(Segment::intersects(&a, &b));
+/// assert_eq!(Segment::intersection(&a, &b), Some(Point::new(1.0, 1.0)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// first endpoint
+    pub start: Point,
+
+    /// second endpoint
+    pub end: Point,
+}
+
+impl Segment {
+    /// Creates a new segment
+    ///
+    /// # Arguments
+    /// * `start`: first endpoint
+    /// * `end`: second endpoint
+    pub fn new(start: Point, end: Point) -> Segment {
+        Segment { start, end }
+    }
+
+    /// Returns endpoints of the segment ordered lexicographically: (left, right)
+    pub fn endpoints(&self) -> (Point, Point) {
+        if self.start <= self.end {
+            (self.start, self.end)
+        } else {
+            (self.end, self.start)
+        }
+    }
+
+    /// Returns true if `point` lies on the segment
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::geometry::{Point, Segment};
+    ///
+    /// let segment = Segment::new(Point::new(0, 0), Point::new(4, 2));
+    ///
+    /// assert!(segment.contains(&Point::new(2, 1)));
+    /// assert!(!segment.contains(&Point::new(6, 3)));
+    /// ```
+    pub fn contains(&self, point: &Point) -> bool {
+        let (left, right) = self.endpoints();
+
+        orientation_determinant(&left, &right, point) == 0 && left <= *point && *point <= right
+    }
+
+    /// Returns true if two segments have at least one common point. The test is exact
+    ///
+    /// # Arguments
+    /// * `first`: first segment
+    /// * `second`: second segment
+    pub fn intersects(first: &Segment, second: &Segment) -> bool {
+        let d1 = orientation_determinant(&first.start, &first.end, &second.start).signum();
+        let d2 = orientation_determinant(&first.start, &first.end, &second.end).signum();
+        let d3 = orientation_determinant(&second.start, &second.end, &first.start).signum();
+        let d4 = orientation_determinant(&second.start, &second.end, &first.end).signum();
+
+        if d1 * d2 < 0 && d3 * d4 < 0 {
+            return true;
+        }
+
+        (d1 == 0 && first.contains(&second.start))
+            || (d2 == 0 && first.contains(&second.end))
+            || (d3 == 0 && second.contains(&first.start))
+            || (d4 == 0 && second.contains(&first.end))
+    }
+
+    /// Returns the common point of two segments.
+    /// If segments overlap along a collinear piece, the lexicographically smallest common point is returned.
+    /// Returns `None` if segments do not intersect
+    ///
+    /// # Arguments
+    /// * `first`: first segment
+    /// * `second`: second segment
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::geometry::{Point, Segment};
+    ///
+    /// let a = Segment::new(Point::new(0, 0), Point::new(4, 0));
+    /// let b = Segment::new(Point::new(2, 0), Point::new(6, 0));
+    ///
+    /// assert_eq!(Segment::intersection(&a, &b), Some(Point::new(2.0, 0.0)));
+    /// ```
+    pub fn intersection(first: &Segment, second: &Segment) -> Option<Point<f64>> {
+        if !Segment::intersects(first, second) {
+            return None;
+        }
+
+        match Segment::crossing_point(first, second) {
+            Some((x, y, d)) => Some(Point::new(x as f64 / d as f64, y as f64 / d as f64)),
+            None => {
+                // collinear overlap: larger of the two left endpoints
+                let (a, _) = first.endpoints();
+                let (b, _) = second.endpoints();
+
+                Some(std::cmp::max(a, b).to_f64())
+            }
+        }
+    }
+
+    // exact intersection point of the supporting lines as (x numerator, y numerator, positive denominator).
+    // returns None if the segments are parallel. callers must make sure segments intersect
+    pub(crate) fn crossing_point(first: &Segment, second: &Segment) -> Option<(i128, i128, i128)> {
+        let r = first.end - first.start;
+        let q = second.end - second.start;
+
+        let mut denominator = Point::cross(&r, &q);
+        if denominator == 0 {
+            return None;
+        }
+
+        let mut t = Point::cross(&(second.start - first.start), &q);
+        if denominator < 0 {
+            denominator = -denominator;
+            t = -t;
+        }
+
+        let x = first.start.x as i128 * denominator + r.x as i128 * t;
+        let y = first.start.y as i128 * denominator + r.y as i128 * t;
+
+        Some((x, y, denominator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(x1: i64, y1: i64, x2: i64, y2: i64) -> Segment {
+        Segment::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    #[test]
+    fn geometry_segment_intersects() {
+        assert!(Segment::intersects(
+            &segment(0, 0, 4, 4),
+            &segment(0, 4, 4, 0)
+        ));
+        assert!(Segment::intersects(
+            &segment(0, 0, 4, 4),
+            &segment(4, 4, 5, 0)
+        ));
+        assert!(Segment::intersects(
+            &segment(0, 0, 4, 0),
+            &segment(4, 0, 8, 0)
+        ));
+        assert!(!Segment::intersects(
+            &segment(0, 0, 4, 0),
+            &segment(5, 0, 8, 0)
+        ));
+        assert!(!Segment::intersects(
+            &segment(0, 0, 4, 4),
+            &segment(0, 1, 4, 5)
+        ));
+        assert!(!Segment::intersects(
+            &segment(0, 0, 2, 2),
+            &segment(3, 0, 3, 5)
+        ));
+    }
+
+    #[test]
+    fn geometry_segment_intersection_point() {
+        assert_eq!(
+            Segment::intersection(&segment(0, 0, 3, 1), &segment(0, 1, 3, 0)),
+            Some(Point::new(1.5, 0.5))
+        );
+        assert_eq!(
+            Segment::intersection(&segment(0, 0, 0, 4), &segment(0, 6, 0, 2)),
+            Some(Point::new(0.0, 2.0))
+        );
+        assert_eq!(
+            Segment::intersection(&segment(0, 0, 1, 0), &segment(0, 1, 1, 1)),
+            None
+        );
+    }
+}
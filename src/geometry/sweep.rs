@@ -0,0 +1,484 @@
+use crate::geometry::{Point, Segment};
+use crate::tree::AVL;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::rc::Rc;
+
+// largest absolute value of a coordinate for which all predicates of the sweep are exact
+const COORDINATE_LIMIT: i64 = 1 << 28;
+
+/// Returns all pairs of intersecting segments using the Bentley-Ottmann sweep line algorithm.
+/// Each pair `(i, j)` with `i < j` is reported once along with the intersection point.
+/// If two segments overlap along a collinear piece, the lexicographically smallest common point is reported.
+/// Pairs are sorted by indices of the segments.
+///
+/// Sweep status is kept in an `AVL` tree and every predicate is evaluated exactly with rational arithmetic,
+/// so vertical, overlapping and degenerate(single point) segments are handled correctly.
+/// * Complexity: O((n + k) log n), where k is the number of intersections
+///
+/// # Arguments
+/// * `segments`: set of segments
+///
+/// # Panics
+/// * panics if absolute value of any coordinate is not less than 2^28
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{segment_intersections, Point, Segment};
+///
+/// let segments = vec![
+///     Segment::new(Point::new(0, 0), Point::new(4, 4)),
+///     Segment::new(Point::new(0, 4), Point::new(4, 0)),
+///     Segment::new(Point::new(5, 0), Point::new(5, 4)),
+/// ];
+///
+/// assert_eq!(segment_intersections(&segments), vec![(0, 1, Point::new(2.0, 2.0))]);
+/// ```
+pub fn segment_intersections(segments: &[Segment]) -> Vec<(usize, usize, Point<f64>)> {
+    let mut queue: BTreeMap<EventPoint, Vec<usize>> = BTreeMap::new();
+    let mut normalized = Vec::with_capacity(segments.len());
+
+    for (index, segment) in segments.iter().enumerate() {
+        for point in &[segment.start, segment.end] {
+            if point.x.abs() >= COORDINATE_LIMIT || point.y.abs() >= COORDINATE_LIMIT {
+                panic!("Coordinates must have absolute value less than 2^28");
+            }
+        }
+
+        let (left, right) = segment.endpoints();
+        queue
+            .entry(EventPoint::from_point(&left))
+            .or_default()
+            .push(index);
+        queue.entry(EventPoint::from_point(&right)).or_default();
+
+        normalized.push(Segment::new(left, right));
+    }
+
+    let state = Rc::new(SweepState {
+        segments: normalized,
+        point: Cell::new(EventPoint::from_point(&Point::default())),
+        before: Cell::new(false),
+    });
+    let mut status: AVL<StatusKey, ()> = AVL::init();
+    let mut reported: HashSet<(usize, usize)> = HashSet::new();
+    let mut result = Vec::new();
+
+    while let Some((p, upper)) = queue.pop_first() {
+        state.point.set(p);
+        let probe = StatusKey::probe(&state);
+
+        // segments in the status passing through p are consecutive and start right after the probe
+        let mut through = Vec::new();
+        let mut k = status.rank(&probe);
+        while k < status.size() {
+            let key = status.select(k).unwrap().0;
+            if key.value_cmp_point() != Ordering::Equal {
+                break;
+            }
+            through.push(key.segment.unwrap());
+            k += 1;
+        }
+
+        let mut all = upper.clone();
+        all.extend(through.iter().copied());
+        if all.len() > 1 {
+            all.sort_unstable();
+            for (position, &i) in all.iter().enumerate() {
+                for &j in &all[position + 1..] {
+                    if reported.insert((i, j)) {
+                        result.push((i, j, p.to_f64()));
+                    }
+                }
+            }
+        }
+
+        // remove segments ending at or passing through p, then insert segments continuing to the right of p
+        state.before.set(true);
+        for &index in &through {
+            status.delete(&StatusKey::segment(&state, index));
+        }
+
+        state.before.set(false);
+        let ends_at_p = |index: usize| EventPoint::from_point(&state.segments[index].end) == p;
+        let mut inserted = 0;
+        for &index in upper.iter().chain(through.iter()) {
+            if !ends_at_p(index) {
+                status.insert(StatusKey::segment(&state, index), ());
+                inserted += 1;
+            }
+        }
+
+        // inserted segments occupy positions r..r + inserted. only their outer neighbours can meet them
+        let r = status.rank(&probe);
+        if r > 0 {
+            find_event(&status, &mut queue, &state, r - 1, r);
+        }
+        if inserted > 0 {
+            find_event(&status, &mut queue, &state, r + inserted - 1, r + inserted);
+        }
+    }
+
+    result.sort_by_key(|a| (a.0, a.1));
+
+    result
+}
+
+// adds intersection of segments at positions `a` and `b` of the status to the queue, if it is to the right of the sweep line
+fn find_event(
+    status: &AVL<StatusKey, ()>,
+    queue: &mut BTreeMap<EventPoint, Vec<usize>>,
+    state: &SweepState,
+    a: usize,
+    b: usize,
+) {
+    if b >= status.size() {
+        return;
+    }
+
+    let first = &state.segments[status.select(a).unwrap().0.segment.unwrap()];
+    let second = &state.segments[status.select(b).unwrap().0.segment.unwrap()];
+
+    if !Segment::intersects(first, second) {
+        return;
+    }
+
+    // parallel segments only intersect along an overlap, which is found at endpoint events
+    if let Some((x, y, d)) = Segment::crossing_point(first, second) {
+        let event = EventPoint { x, y, d };
+        if event > state.point.get() {
+            queue.entry(event).or_default();
+        }
+    }
+}
+
+// compares a / b with c / d exactly, without overflow. b and d must be positive
+fn cmp_fraction(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let q1 = a.div_euclid(b);
+    let q2 = c.div_euclid(d);
+    if q1 != q2 {
+        return q1.cmp(&q2);
+    }
+
+    let r1 = a - q1 * b;
+    let r2 = c - q2 * d;
+    match (r1 == 0, r2 == 0) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        // r1 / b < r2 / d if and only if d / r2 < b / r1
+        (false, false) => cmp_fraction(d, r2, b, r1),
+    }
+}
+
+// a point with rational coordinates (x / d, y / d), d > 0
+#[derive(Debug, Clone, Copy)]
+struct EventPoint {
+    x: i128,
+    y: i128,
+    d: i128,
+}
+
+impl EventPoint {
+    fn from_point(point: &Point) -> EventPoint {
+        EventPoint {
+            x: point.x as i128,
+            y: point.y as i128,
+            d: 1,
+        }
+    }
+
+    fn to_f64(self) -> Point<f64> {
+        Point::new(self.x as f64 / self.d as f64, self.y as f64 / self.d as f64)
+    }
+}
+
+impl Ord for EventPoint {
+    fn cmp(&self, other: &EventPoint) -> Ordering {
+        cmp_fraction(self.x, self.d, other.x, other.d)
+            .then_with(|| cmp_fraction(self.y, self.d, other.y, other.d))
+    }
+}
+
+impl PartialOrd for EventPoint {
+    fn partial_cmp(&self, other: &EventPoint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for EventPoint {
+    fn eq(&self, other: &EventPoint) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for EventPoint {}
+
+// state of the sweep shared by all keys of the status tree
+struct SweepState {
+    // segments with endpoints ordered from left to right
+    segments: Vec<Segment>,
+
+    // current event point
+    point: Cell<EventPoint>,
+
+    // whether segments through the event point are ordered as just before it(for deletion) or just after it(for insertion)
+    before: Cell<bool>,
+}
+
+// key of the status tree. `segment` is None for a probe which lies just below the current event point
+struct StatusKey {
+    segment: Option<usize>,
+    state: Rc<SweepState>,
+}
+
+impl StatusKey {
+    fn segment(state: &Rc<SweepState>, index: usize) -> StatusKey {
+        StatusKey {
+            segment: Some(index),
+            state: Rc::clone(state),
+        }
+    }
+
+    fn probe(state: &Rc<SweepState>) -> StatusKey {
+        StatusKey {
+            segment: None,
+            state: Rc::clone(state),
+        }
+    }
+
+    // y coordinate of the segment on the sweep line as a fraction with positive denominator
+    fn value(&self, index: usize) -> (i128, i128) {
+        let p = self.state.point.get();
+        let segment = &self.state.segments[index];
+        let (x1, y1) = (segment.start.x as i128, segment.start.y as i128);
+        let (x2, y2) = (segment.end.x as i128, segment.end.y as i128);
+
+        if x1 == x2 {
+            // a vertical segment is on the sweep line exactly at the event point
+            return (p.y, p.d);
+        }
+
+        let dx = x2 - x1;
+        (y1 * dx * p.d + (y2 - y1) * (p.x - x1 * p.d), dx * p.d)
+    }
+
+    // compares y coordinate of the segment on the sweep line with the event point. probe is below the event point
+    fn value_cmp_point(&self) -> Ordering {
+        match self.segment {
+            Some(index) => {
+                let p = self.state.point.get();
+                let (numerator, denominator) = self.value(index);
+                cmp_fraction(numerator, denominator, p.y, p.d)
+            }
+            None => Ordering::Less,
+        }
+    }
+
+    // compares slopes of two segments. vertical segments have infinite slope
+    fn slope_cmp(&self, first: usize, second: usize) -> Ordering {
+        let a = &self.state.segments[first];
+        let b = &self.state.segments[second];
+        let (adx, ady) = (
+            a.end.x as i128 - a.start.x as i128,
+            a.end.y as i128 - a.start.y as i128,
+        );
+        let (bdx, bdy) = (
+            b.end.x as i128 - b.start.x as i128,
+            b.end.y as i128 - b.start.y as i128,
+        );
+
+        match (adx == 0, bdx == 0) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => (ady * bdx).cmp(&(bdy * adx)),
+        }
+    }
+}
+
+impl Ord for StatusKey {
+    fn cmp(&self, other: &StatusKey) -> Ordering {
+        let (first, second) = match (self.segment, other.segment) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => {
+                return match other.value_cmp_point() {
+                    Ordering::Less => Ordering::Greater,
+                    _ => Ordering::Less,
+                }
+            }
+            (Some(_), None) => {
+                return match self.value_cmp_point() {
+                    Ordering::Less => Ordering::Less,
+                    _ => Ordering::Greater,
+                }
+            }
+            (Some(first), Some(second)) => (first, second),
+        };
+
+        let (n1, d1) = self.value(first);
+        let (n2, d2) = self.value(second);
+
+        cmp_fraction(n1, d1, n2, d2)
+            .then_with(|| {
+                // segments meet on the sweep line. just after the meeting point the one with smaller slope is below
+                let by_slope = self.slope_cmp(first, second);
+                if self.state.before.get() {
+                    by_slope.reverse()
+                } else {
+                    by_slope
+                }
+            })
+            .then_with(|| first.cmp(&second))
+    }
+}
+
+impl PartialOrd for StatusKey {
+    fn partial_cmp(&self, other: &StatusKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for StatusKey {
+    fn eq(&self, other: &StatusKey) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for StatusKey {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(x1: i64, y1: i64, x2: i64, y2: i64) -> Segment {
+        Segment::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    fn brute_force(segments: &[Segment]) -> Vec<(usize, usize, Point<f64>)> {
+        let mut result = Vec::new();
+        for i in 0..segments.len() {
+            for j in i + 1..segments.len() {
+                if let Some(point) = Segment::intersection(&segments[i], &segments[j]) {
+                    result.push((i, j, point));
+                }
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn geometry_sweep_fraction_comparison() {
+        assert_eq!(cmp_fraction(1, 3, 2, 6), Ordering::Equal);
+        assert_eq!(cmp_fraction(-1, 3, -1, 4), Ordering::Less);
+        assert_eq!(cmp_fraction(7, 5, 4, 3), Ordering::Greater);
+        assert_eq!(
+            cmp_fraction(i128::MAX - 1, i128::MAX, i128::MAX - 2, i128::MAX - 1),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn geometry_sweep_empty_and_disjoint() {
+        assert!(segment_intersections(&[]).is_empty());
+        assert!(segment_intersections(&[segment(0, 0, 1, 1), segment(2, 2, 3, 5)]).is_empty());
+    }
+
+    #[test]
+    fn geometry_sweep_grid() {
+        let mut segments = Vec::new();
+        for i in 0..5 {
+            segments.push(segment(0, i * 2, 10, i * 2));
+            segments.push(segment(i * 2 + 1, -1, i * 2 + 1, 9));
+        }
+
+        let result = segment_intersections(&segments);
+
+        assert_eq!(result.len(), 25);
+        assert_eq!(result, brute_force(&segments));
+    }
+
+    #[test]
+    fn geometry_sweep_common_point() {
+        // a star of segments through the origin along with some sharing endpoints
+        let segments = vec![
+            segment(-3, -3, 3, 3),
+            segment(-3, 3, 3, -3),
+            segment(0, -5, 0, 5),
+            segment(-4, 0, 4, 0),
+            segment(3, 3, 6, 0),
+            segment(0, 0, 2, 7),
+            segment(0, 0, 0, 0),
+        ];
+
+        let result = segment_intersections(&segments);
+
+        assert_eq!(result, brute_force(&segments));
+        assert!(result.contains(&(0, 4, Point::new(3.0, 3.0))));
+        assert!(result.contains(&(5, 6, Point::new(0.0, 0.0))));
+    }
+
+    #[test]
+    fn geometry_sweep_overlapping() {
+        let segments = vec![
+            segment(0, 0, 10, 0),
+            segment(8, 0, 2, 0),
+            segment(5, -1, 5, 1),
+            segment(0, 0, 6, 6),
+            segment(2, 2, 4, 4),
+            segment(5, 0, 5, 5),
+            segment(5, 3, 5, 8),
+        ];
+
+        assert_eq!(segment_intersections(&segments), brute_force(&segments));
+    }
+
+    #[test]
+    fn geometry_sweep_matches_brute_force() {
+        let mut seed: u64 = 7;
+        let mut next = |range: i64| -> i64 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % range as u64) as i64
+        };
+
+        for round in 0..30 {
+            // small coordinates produce many degenerate configurations
+            let range = if round % 2 == 0 { 8 } else { 1000 };
+            let segments: Vec<Segment> = (0..40)
+                .map(|_| segment(next(range), next(range), next(range), next(range)))
+                .collect();
+
+            let expected = brute_force(&segments);
+            let result = segment_intersections(&segments);
+
+            assert_eq!(result.len(), expected.len());
+            for (a, b) in result.iter().zip(expected.iter()) {
+                assert_eq!((a.0, a.1), (b.0, b.1));
+                assert!(Point::distance(&a.2, &b.2) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn geometry_sweep_large_coordinates() {
+        let limit = COORDINATE_LIMIT - 1;
+        let segments = vec![
+            segment(-limit, -limit, limit, limit),
+            segment(-limit, limit, limit, -limit + 1),
+            segment(-limit, limit - 1, limit, -limit),
+        ];
+
+        // last two segments are almost parallel and do not meet
+        assert_eq!(segment_intersections(&segments).len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Coordinates must have absolute value less than 2^28")]
+    fn geometry_sweep_coordinates_out_of_range() {
+        segment_intersections(&[segment(0, 0, COORDINATE_LIMIT, 0)]);
+    }
+}
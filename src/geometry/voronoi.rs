@@ -0,0 +1,184 @@
+use crate::geometry::Point;
+
+/// Voronoi diagram of a set of points(sites), obtained as the dual of their Delaunay triangulation.
+/// Every vertex of the diagram is the circumcenter of a Delaunay triangle and has the same index as that triangle.
+///
+/// Cells of sites on the convex hull are unbounded. Their listed vertices are the finite part of the boundary,
+/// which is closed by two rays perpendicular to the hull edges at the site.
+///
+/// # Examples
+/// ```
+/// use rudac::geometry::{delaunay, Point};
+///
+/// let points = vec![Point::new(0, 0), Point::new(2, 0), Point::new(0, 2), Point::new(2, 2), Point::new(1, 1)];
+/// let voronoi = delaunay(&points).voronoi();
+///
+/// // the center site is surrounded by a diamond
+/// let cell: Vec<Point<f64>> = voronoi.cell(4).iter().map(|v| voronoi.vertices()[*v]).collect();
+///
+/// assert_eq!(cell.len(), 4);
+/// assert!(cell.contains(&Point::new(1.0, 0.0)));
+/// assert!(cell.contains(&Point::new(0.0, 1.0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Voronoi {
+    vertices: Vec<Point<f64>>,
+    cells: Vec<Vec<usize>>,
+    bounded: Vec<bool>,
+}
+
+impl Voronoi {
+    pub(crate) fn init(
+        vertices: Vec<Point<f64>>,
+        cells: Vec<Vec<usize>>,
+        bounded: Vec<bool>,
+    ) -> Voronoi {
+        Voronoi {
+            vertices,
+            cells,
+            bounded,
+        }
+    }
+
+    /// Returns vertices of the diagram
+    pub fn vertices(&self) -> &[Point<f64>] {
+        &self.vertices
+    }
+
+    /// Returns number of sites
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns true if there are no sites
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns indices of the vertices on the boundary of the cell of `site` in counter clockwise order.
+    /// Cell of a duplicate site, or of any site when all sites are collinear, has no vertices
+    ///
+    /// # Arguments
+    /// * `site`: index of the site
+    ///
+    /// # Panics
+    /// * panics if `site` is out of range
+    pub fn cell(&self, site: usize) -> &[usize] {
+        &self.cells[site]
+    }
+
+    /// Returns true if the cell of `site` is bounded, which is the case for sites strictly inside the convex hull
+    ///
+    /// # Arguments
+    /// * `site`: index of the site
+    ///
+    /// # Panics
+    /// * panics if `site` is out of range
+    pub fn is_bounded(&self, site: usize) -> bool {
+        self.bounded[site]
+    }
+
+    /// Returns the area of the cell of `site`, or `None` if the cell is unbounded
+    ///
+    /// # Arguments
+    /// * `site`: index of the site
+    ///
+    /// # Panics
+    /// * panics if `site` is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::geometry::{delaunay, Point};
+    ///
+    /// let mut points = Vec::new();
+    /// for x in 0..3 {
+    ///     for y in 0..3 {
+    ///         points.push(Point::new(x, y));
+    ///     }
+    /// }
+    /// let voronoi = delaunay(&points).voronoi();
+    ///
+    /// assert_eq!(voronoi.cell_area(4), Some(1.0));
+    /// assert_eq!(voronoi.cell_area(0), None);
+    /// ```
+    pub fn cell_area(&self, site: usize) -> Option<f64> {
+        if !self.bounded[site] {
+            return None;
+        }
+
+        let cell = &self.cells[site];
+        let n = cell.len();
+        let mut area = 0.0;
+        for i in 0..n {
+            let a = &self.vertices[cell[i]];
+            let b = &self.vertices[cell[(i + 1) % n]];
+            area += a.x * b.y - a.y * b.x;
+        }
+
+        Some(area / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{delaunay, Point};
+
+    #[test]
+    fn geometry_voronoi_cells_are_equidistant() {
+        let points = vec![
+            Point::new(0, 0),
+            Point::new(10, 1),
+            Point::new(3, 9),
+            Point::new(4, 4),
+            Point::new(9, 8),
+            Point::new(-2, 6),
+        ];
+        let voronoi = delaunay(&points).voronoi();
+
+        assert_eq!(voronoi.size(), points.len());
+
+        for site in 0..points.len() {
+            let p = points[site].to_f64();
+            for vertex in voronoi.cell(site) {
+                let v = voronoi.vertices()[*vertex];
+                let distance = Point::distance(&p, &v);
+
+                // a voronoi vertex is not closer to any other site
+                for other in points.iter() {
+                    assert!(Point::distance(&other.to_f64(), &v) >= distance - 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn geometry_voronoi_grid_cells() {
+        let mut points = Vec::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                points.push(Point::new(x * 3, y * 3));
+            }
+        }
+        let voronoi = delaunay(&points).voronoi();
+
+        let bounded: Vec<usize> = (0..points.len())
+            .filter(|s| voronoi.is_bounded(*s))
+            .collect();
+
+        assert_eq!(bounded.len(), 9);
+        for site in bounded {
+            // every inner site of the grid owns a square, vertices are in counter clockwise order
+            assert!((voronoi.cell_area(site).unwrap() - 9.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn geometry_voronoi_collinear_and_duplicates() {
+        let points = vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 0)];
+        let voronoi = delaunay(&points).voronoi();
+
+        assert!(voronoi.vertices().is_empty());
+        assert!(voronoi.cell(2).is_empty());
+        assert!(!voronoi.is_bounded(2));
+    }
+}
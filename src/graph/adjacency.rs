@@ -0,0 +1,283 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+/// An edge as seen from one of its end points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge<W> {
+    /// the other end point
+    pub to: usize,
+
+    /// weight of the edge
+    pub weight: W,
+}
+
+/// A weighted graph stored as adjacency lists. Vertices are numbered from zero in the order they were added.
+/// A directed graph also keeps the incoming edges of every vertex, so searches can run backwards from a target;
+/// an undirected edge is stored once in the lists of both of its end points.
+/// Parallel edges and self loops are allowed
+///
+/// # Examples
+/// ```
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_directed(3);
+/// graph.add_edge(0, 1, 4);
+/// graph.add_edge(1, 2, 5);
+/// graph.add_edge(0, 2, 10);
+///
+/// assert_eq!(graph.vertex_count(), 3);
+/// assert_eq!(graph.edge_count(), 3);
+/// assert_eq!(graph.weight(0, 2), Some(&10));
+/// assert_eq!(graph.weight(2, 0), None);
+/// assert_eq!(graph.incoming(2).len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph<W = u64> {
+    directed: bool,
+    outgoing: Vec<Vec<Edge<W>>>,
+
+    // incoming edges of every vertex of a directed graph, empty for undirected graphs
+    incoming: Vec<Vec<Edge<W>>>,
+
+    edge_count: usize,
+}
+
+impl<W> Graph<W> {
+    /// Initializes a directed graph with `vertices` vertices and no edges
+    ///
+    /// # Arguments
+    /// * `vertices`: number of vertices
+    pub fn init_directed(vertices: usize) -> Graph<W> {
+        Graph {
+            directed: true,
+            outgoing: (0..vertices).map(|_| Vec::new()).collect(),
+            incoming: (0..vertices).map(|_| Vec::new()).collect(),
+            edge_count: 0,
+        }
+    }
+
+    /// Initializes an undirected graph with `vertices` vertices and no edges
+    ///
+    /// # Arguments
+    /// * `vertices`: number of vertices
+    pub fn init_undirected(vertices: usize) -> Graph<W> {
+        Graph {
+            directed: false,
+            outgoing: (0..vertices).map(|_| Vec::new()).collect(),
+            incoming: Vec::new(),
+            edge_count: 0,
+        }
+    }
+
+    /// Returns true if the edges of the graph are directed
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Returns number of edges, an undirected edge is counted once
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Adds a vertex without edges and returns its index
+    pub fn add_vertex(&mut self) -> usize {
+        self.outgoing.push(Vec::new());
+        if self.directed {
+            self.incoming.push(Vec::new());
+        }
+
+        self.outgoing.len() - 1
+    }
+
+    /// Returns the edges leaving `vertex`, all edges of `vertex` in an undirected graph
+    ///
+    /// # Arguments
+    /// * `vertex`: the vertex
+    ///
+    /// # Panics
+    /// * panics if `vertex` does not exist
+    pub fn neighbors(&self, vertex: usize) -> &[Edge<W>] {
+        self.check_vertex(vertex);
+
+        &self.outgoing[vertex]
+    }
+
+    /// Returns the edges entering `vertex`, all edges of `vertex` in an undirected graph.
+    /// The `to` field of an incoming edge is the vertex the edge comes from
+    ///
+    /// # Arguments
+    /// * `vertex`: the vertex
+    ///
+    /// # Panics
+    /// * panics if `vertex` does not exist
+    pub fn incoming(&self, vertex: usize) -> &[Edge<W>] {
+        self.check_vertex(vertex);
+
+        if self.directed {
+            &self.incoming[vertex]
+        } else {
+            &self.outgoing[vertex]
+        }
+    }
+
+    /// Returns the weight of the first edge from `from` to `to`, `None` if there is no such edge
+    ///
+    /// # Arguments
+    /// * `from`: start of the edge
+    /// * `to`: end of the edge
+    ///
+    /// # Panics
+    /// * panics if `from` does not exist
+    pub fn weight(&self, from: usize, to: usize) -> Option<&W> {
+        self.neighbors(from)
+            .iter()
+            .find(|edge| edge.to == to)
+            .map(|edge| &edge.weight)
+    }
+
+    /// Returns true if there is an edge from `from` to `to`
+    ///
+    /// # Arguments
+    /// * `from`: start of the edge
+    /// * `to`: end of the edge
+    ///
+    /// # Panics
+    /// * panics if `from` does not exist
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.weight(from, to).is_some()
+    }
+
+    /// Returns every edge as `(from, to, weight)`. Undirected edges are returned once, with `from <= to`
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, &W)> + '_ {
+        let directed = self.directed;
+        self.outgoing
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |edge| (from, edge.to, &edge.weight)))
+            .filter(move |(from, to, _)| directed || from <= to)
+    }
+
+    /// Returns out-degree of `vertex`, the number of edges of `vertex` in an undirected graph
+    ///
+    /// # Arguments
+    /// * `vertex`: the vertex
+    ///
+    /// # Panics
+    /// * panics if `vertex` does not exist
+    pub fn degree(&self, vertex: usize) -> usize {
+        self.neighbors(vertex).len()
+    }
+
+    pub(crate) fn check_vertex(&self, vertex: usize) {
+        if vertex >= self.vertex_count() {
+            panic!("Vertex does not exist");
+        }
+    }
+}
+
+impl<W: Clone> Graph<W> {
+    /// Adds an edge from `from` to `to`, an edge between them if the graph is undirected
+    ///
+    /// # Arguments
+    /// * `from`: start of the edge
+    /// * `to`: end of the edge
+    /// * `weight`: weight of the edge
+    ///
+    /// # Panics
+    /// * panics if `from` or `to` does not exist
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) {
+        self.check_vertex(from);
+        self.check_vertex(to);
+
+        if self.directed {
+            self.incoming[to].push(Edge {
+                to: from,
+                weight: weight.clone(),
+            });
+        } else if from != to {
+            self.outgoing[to].push(Edge {
+                to: from,
+                weight: weight.clone(),
+            });
+        }
+        self.outgoing[from].push(Edge { to, weight });
+        self.edge_count += 1;
+    }
+
+    /// Returns the graph with every edge reversed. An undirected graph is returned unchanged
+    pub fn reversed(&self) -> Graph<W> {
+        if !self.directed {
+            return self.clone();
+        }
+
+        Graph {
+            directed: true,
+            outgoing: self.incoming.clone(),
+            incoming: self.outgoing.clone(),
+            edge_count: self.edge_count,
+        }
+    }
+}
+
+impl<W> MemoryFootprint for Graph<W> {
+    fn footprint(&self) -> Footprint {
+        let lists = self.outgoing.iter().chain(self.incoming.iter());
+        lists.fold(
+            Footprint::of::<Edge<W>>(self.edge_count)
+                .with_vec(&self.outgoing)
+                .with_vec(&self.incoming),
+            |footprint, edges| footprint.with_vec(edges),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_adjacency_undirected() {
+        let mut graph = Graph::init_undirected(2);
+        let vertex = graph.add_vertex();
+        graph.add_edge(0, 1, 'a');
+        graph.add_edge(vertex, 1, 'b');
+        graph.add_edge(2, 2, 'c');
+
+        assert_eq!(vertex, 2);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.weight(1, 0), Some(&'a'));
+        assert_eq!(graph.degree(1), 2);
+        assert_eq!(graph.incoming(1), graph.neighbors(1));
+
+        let mut edges: Vec<(usize, usize, char)> =
+            graph.edges().map(|(u, v, w)| (u, v, *w)).collect();
+        edges.sort();
+        assert_eq!(edges, vec![(0, 1, 'a'), (1, 2, 'b'), (2, 2, 'c')]);
+        assert_eq!(graph.reversed(), graph);
+    }
+
+    #[test]
+    fn graph_adjacency_reversed() {
+        let mut graph = Graph::init_directed(3);
+        graph.add_edge(0, 1, 1);
+        graph.add_edge(0, 2, 2);
+        graph.add_edge(2, 0, 3);
+
+        let reversed = graph.reversed();
+        assert_eq!(reversed.weight(1, 0), Some(&1));
+        assert_eq!(reversed.weight(0, 2), Some(&3));
+        assert!(!reversed.has_edge(0, 1));
+        assert_eq!(reversed.reversed(), graph);
+    }
+
+    #[test]
+    #[should_panic(expected = "Vertex does not exist")]
+    fn graph_adjacency_panic_missing_vertex() {
+        let mut graph = Graph::init_directed(2);
+        graph.add_edge(0, 2, ());
+    }
+}
@@ -0,0 +1,349 @@
+//! Centrality measures ranking the vertices of a graph by importance. Weights are ignored: PageRank follows every
+//! edge with the same probability and path based measures count edges
+
+use super::Graph;
+use std::collections::VecDeque;
+
+// distances in edges from `source`, `None` for unreachable vertices, and the vertices in the order they were reached
+fn breadth_first<W>(graph: &Graph<W>, source: usize) -> (Vec<Option<usize>>, Vec<usize>) {
+    let mut distances = vec![None; graph.vertex_count()];
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+    distances[source] = Some(0);
+    queue.push_back(source);
+    while let Some(vertex) = queue.pop_front() {
+        order.push(vertex);
+        let distance = distances[vertex].unwrap();
+        for edge in graph.neighbors(vertex) {
+            if distances[edge.to].is_none() {
+                distances[edge.to] = Some(distance + 1);
+                queue.push_back(edge.to);
+            }
+        }
+    }
+
+    (distances, order)
+}
+
+/// Returns the PageRank of every vertex: the probability of finding a random surfer at the vertex, who follows
+/// a random edge leaving the current vertex with probability `damping` and jumps to a random vertex otherwise.
+/// Vertices without leaving edges jump to a random vertex. The ranks are refined by power iteration
+/// until they change by less than `tolerance` in total, and sum to one
+///
+/// # Arguments
+/// * `graph`: the graph, an undirected edge can be followed both ways
+/// * `damping`: probability of following an edge, usually 0.85
+/// * `tolerance`: total change of the ranks at which the iteration stops
+///
+/// # Panics
+/// * panics if `damping` is not in range [0, 1)
+/// * panics if `tolerance` is not positive
+///
+/// # Examples
+/// ```
+/// use rudac::graph::centrality::pagerank;
+/// use rudac::graph::Graph;
+///
+/// // 1 and 2 link to 0, which links back to 1
+/// let mut graph = Graph::init_directed(3);
+/// graph.add_edge(1, 0, ());
+/// graph.add_edge(2, 0, ());
+/// graph.add_edge(0, 1, ());
+///
+/// let ranks = pagerank(&graph, 0.85, 1e-9);
+///
+/// assert!(ranks[0] > ranks[1] && ranks[1] > ranks[2]);
+/// assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+/// ```
+pub fn pagerank<W>(graph: &Graph<W>, damping: f64, tolerance: f64) -> Vec<f64> {
+    if !(0.0..1.0).contains(&damping) {
+        panic!("Damping must be in range [0, 1)");
+    }
+    if tolerance <= 0.0 || tolerance.is_nan() {
+        panic!("Tolerance must be positive");
+    }
+
+    let vertices = graph.vertex_count();
+    if vertices == 0 {
+        return Vec::new();
+    }
+
+    let uniform = 1.0 / vertices as f64;
+    let mut ranks = vec![uniform; vertices];
+    let mut next = vec![0.0; vertices];
+    loop {
+        let dangling: f64 = (0..vertices)
+            .filter(|vertex| graph.degree(*vertex) == 0)
+            .map(|vertex| ranks[vertex])
+            .sum();
+        next.fill((1.0 - damping + damping * dangling) * uniform);
+        for (vertex, rank) in ranks.iter().enumerate() {
+            let edges = graph.neighbors(vertex);
+            let share = damping * rank / edges.len() as f64;
+            for edge in edges {
+                next[edge.to] += share;
+            }
+        }
+
+        let change: f64 = ranks
+            .iter()
+            .zip(next.iter())
+            .map(|(old, new)| (old - new).abs())
+            .sum();
+        std::mem::swap(&mut ranks, &mut next);
+        // every iteration shrinks the error by the damping factor, so this terminates
+        if change < tolerance {
+            return ranks;
+        }
+    }
+}
+
+/// Returns the betweenness centrality of every vertex: the sum over all pairs of other vertices `s`, `t` of the
+/// fraction of shortest paths from `s` to `t` that pass through the vertex, with path lengths counted in edges.
+/// Pairs of an undirected graph are counted once. Computed with Brandes' algorithm in O(VE) time
+///
+/// # Arguments
+/// * `graph`: the graph
+///
+/// # Examples
+/// ```
+/// use rudac::graph::centrality::betweenness;
+/// use rudac::graph::Graph;
+///
+/// // a path 0 - 1 - 2 - 3
+/// let mut graph = Graph::init_undirected(4);
+/// graph.add_edge(0, 1, 1);
+/// graph.add_edge(1, 2, 1);
+/// graph.add_edge(2, 3, 1);
+///
+/// assert_eq!(betweenness(&graph), vec![0.0, 2.0, 2.0, 0.0]);
+/// ```
+pub fn betweenness<W>(graph: &Graph<W>) -> Vec<f64> {
+    let vertices = graph.vertex_count();
+    let mut centrality = vec![0.0; vertices];
+    let mut paths = vec![0.0; vertices];
+    let mut dependency = vec![0.0; vertices];
+    for source in 0..vertices {
+        let (distances, order) = breadth_first(graph, source);
+
+        // number of shortest paths from the source, in the order of the search
+        paths.fill(0.0);
+        paths[source] = 1.0;
+        for vertex in &order {
+            for edge in graph.neighbors(*vertex) {
+                if distances[edge.to] == distances[*vertex].map(|distance| distance + 1) {
+                    paths[edge.to] += paths[*vertex];
+                }
+            }
+        }
+
+        // dependencies accumulate from the farthest vertices back towards the source
+        dependency.fill(0.0);
+        for vertex in order.iter().rev() {
+            for edge in graph.neighbors(*vertex) {
+                if distances[edge.to] == distances[*vertex].map(|distance| distance + 1) {
+                    dependency[*vertex] +=
+                        paths[*vertex] / paths[edge.to] * (1.0 + dependency[edge.to]);
+                }
+            }
+            if *vertex != source {
+                centrality[*vertex] += dependency[*vertex];
+            }
+        }
+    }
+
+    if !graph.is_directed() {
+        for value in centrality.iter_mut() {
+            *value /= 2.0;
+        }
+    }
+
+    centrality
+}
+
+/// Returns the closeness centrality of every vertex: the number of other vertices it reaches divided by the sum of
+/// their distances in edges, scaled by the fraction of the other vertices it reaches so that vertices of small
+/// components do not score high. Vertices that reach no other vertex have closeness zero
+///
+/// # Arguments
+/// * `graph`: the graph, distances follow the edges leaving a vertex
+///
+/// # Examples
+/// ```
+/// use rudac::graph::centrality::closeness;
+/// use rudac::graph::Graph;
+///
+/// // a star with center 0
+/// let mut graph = Graph::init_undirected(4);
+/// for leaf in 1..4 {
+///     graph.add_edge(0, leaf, 1);
+/// }
+///
+/// let closeness = closeness(&graph);
+///
+/// assert_eq!(closeness[0], 1.0);
+/// assert_eq!(closeness[1], 3.0 / 5.0);
+/// ```
+pub fn closeness<W>(graph: &Graph<W>) -> Vec<f64> {
+    let vertices = graph.vertex_count();
+    (0..vertices)
+        .map(|source| {
+            let (distances, order) = breadth_first(graph, source);
+            let total: usize = distances.iter().flatten().sum();
+            let reached = (order.len() - 1) as f64;
+            if total == 0 {
+                return 0.0;
+            }
+
+            reached / total as f64 * reached / (vertices - 1) as f64
+        })
+        .collect()
+}
+
+/// Returns the degree centrality of every vertex: its number of edges divided by the number of other vertices.
+/// Both the leaving and the entering edges of a directed graph count
+///
+/// # Arguments
+/// * `graph`: the graph
+///
+/// # Examples
+/// ```
+/// use rudac::graph::centrality::degree;
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_directed(3);
+/// graph.add_edge(0, 1, ());
+/// graph.add_edge(0, 2, ());
+///
+/// assert_eq!(degree(&graph), vec![1.0, 0.5, 0.5]);
+/// ```
+pub fn degree<W>(graph: &Graph<W>) -> Vec<f64> {
+    let vertices = graph.vertex_count();
+    if vertices < 2 {
+        return vec![0.0; vertices];
+    }
+
+    (0..vertices)
+        .map(|vertex| {
+            let mut edges = graph.degree(vertex);
+            if graph.is_directed() {
+                edges += graph.incoming(vertex).len();
+            }
+
+            edges as f64 / (vertices - 1) as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::{barabasi_albert, erdos_renyi};
+    use crate::util::SplitMix64;
+
+    fn close(first: f64, second: f64) -> bool {
+        (first - second).abs() < 1e-9
+    }
+
+    #[test]
+    fn graph_centrality_pagerank() {
+        // every vertex of a directed cycle has the same rank
+        let mut cycle = Graph::init_directed(5);
+        for vertex in 0..5 {
+            cycle.add_edge(vertex, (vertex + 1) % 5, ());
+        }
+        assert!(pagerank(&cycle, 0.85, 1e-12)
+            .iter()
+            .all(|rank| close(*rank, 0.2)));
+
+        // a rank is the jump probability plus the damped shares of the vertices linking to it
+        let graph = erdos_renyi(40, 0.1, true, &mut SplitMix64::init(1));
+        let ranks = pagerank(&graph, 0.85, 1e-13);
+        let dangling: f64 = (0..40)
+            .filter(|vertex| graph.degree(*vertex) == 0)
+            .map(|vertex| ranks[vertex])
+            .sum();
+        for vertex in 0..40 {
+            let linked: f64 = graph
+                .incoming(vertex)
+                .iter()
+                .map(|edge| ranks[edge.to] / graph.degree(edge.to) as f64)
+                .sum();
+            let expected = (1.0 - 0.85 + 0.85 * dangling) / 40.0 + 0.85 * linked;
+            assert!((ranks[vertex] - expected).abs() < 1e-10);
+        }
+
+        assert!(pagerank(&Graph::<()>::init_undirected(0), 0.5, 0.1).is_empty());
+    }
+
+    #[test]
+    fn graph_centrality_betweenness_matches_path_counting() {
+        for directed in [true, false] {
+            let graph = erdos_renyi(25, 0.12, directed, &mut SplitMix64::init(5));
+            let searches: Vec<(Vec<Option<usize>>, Vec<f64>)> = (0..25)
+                .map(|source| {
+                    let (distances, order) = breadth_first(&graph, source);
+                    let mut paths = vec![0.0; 25];
+                    paths[source] = 1.0;
+                    for vertex in order {
+                        for edge in graph.neighbors(vertex) {
+                            if distances[edge.to] == distances[vertex].map(|distance| distance + 1)
+                            {
+                                paths[edge.to] += paths[vertex];
+                            }
+                        }
+                    }
+                    (distances, paths)
+                })
+                .collect();
+
+            // a shortest path from s to t passes v if d(s, v) + d(v, t) = d(s, t)
+            let mut expected = [0.0; 25];
+            for (vertex, expected) in expected.iter_mut().enumerate() {
+                for source in (0..25).filter(|source| *source != vertex) {
+                    for target in (0..25).filter(|target| *target != vertex && *target != source) {
+                        let (distances, paths) = &searches[source];
+                        let (from_vertex, paths_from_vertex) = &searches[vertex];
+                        if let (Some(total), Some(first), Some(second)) =
+                            (distances[target], distances[vertex], from_vertex[target])
+                        {
+                            if first + second == total {
+                                *expected +=
+                                    paths[vertex] * paths_from_vertex[target] / paths[target];
+                            }
+                        }
+                    }
+                }
+            }
+            if !directed {
+                expected.iter_mut().for_each(|value| *value /= 2.0);
+            }
+
+            let centrality = betweenness(&graph);
+            assert!(centrality
+                .iter()
+                .zip(expected.iter())
+                .all(|(found, expected)| close(*found, *expected)));
+        }
+    }
+
+    #[test]
+    fn graph_centrality_star() {
+        let graph = barabasi_albert(30, 1, &mut SplitMix64::init(2));
+        let mut star = Graph::init_undirected(6);
+        for leaf in 1..6 {
+            star.add_edge(0, leaf, ());
+        }
+
+        assert_eq!(betweenness(&star)[0], 10.0);
+        assert_eq!(degree(&star)[0], 1.0);
+        assert!(closeness(&star)[1..]
+            .iter()
+            .all(|value| close(*value, 5.0 / 9.0)));
+
+        // a tree has n - 1 edges, so degrees sum to 2 (n - 1) / (n - 1)
+        assert!(close(degree(&graph).iter().sum::<f64>(), 2.0));
+        let isolated = Graph::<()>::init_directed(3);
+        assert_eq!(closeness(&isolated), vec![0.0; 3]);
+    }
+}
@@ -0,0 +1,317 @@
+//! Graph coloring for register allocation: interference graphs connect values that are live at the same time, and
+//! a coloring with k colors assigns them k registers so that interfering values never share one
+
+use super::Graph;
+use std::collections::HashSet;
+
+/// Result of `allocate_registers`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    /// register of every value, `None` for spilled values
+    pub registers: Vec<Option<usize>>,
+
+    /// values that got no register and have to live in memory, sorted
+    pub spilled: Vec<usize>,
+
+    /// values that were set aside as potential spills when every remaining value had k or more neighbors, in the
+    /// order they were chosen. Most of them usually still get a register
+    pub spill_candidates: Vec<usize>,
+}
+
+fn interference_from_pairs(
+    values: usize,
+    pairs: impl Iterator<Item = (usize, usize)>,
+) -> Graph<()> {
+    let mut seen = HashSet::new();
+    let mut graph = Graph::init_undirected(values);
+    for (first, second) in pairs {
+        if first.max(second) >= values {
+            panic!("Value does not exist");
+        }
+        if first != second && seen.insert((first.min(second), first.max(second))) {
+            graph.add_edge(first, second, ());
+        }
+    }
+
+    graph
+}
+
+/// Returns the interference graph of values `0..values`: an undirected graph with an edge between every two values
+/// that are live at the same program point
+///
+/// # Arguments
+/// * `values`: number of values
+/// * `live_sets`: values live at every program point
+///
+/// # Panics
+/// * panics if a live set contains a value that does not exist
+///
+/// # Examples
+/// ```
+/// use rudac::graph::coloring::interference_graph;
+///
+/// let graph = interference_graph(4, &[vec![0, 1], vec![1, 2, 3], vec![0, 1]]);
+///
+/// assert_eq!(graph.edge_count(), 4);
+/// assert!(graph.has_edge(2, 3));
+/// assert!(!graph.has_edge(0, 2));
+/// ```
+pub fn interference_graph(values: usize, live_sets: &[Vec<usize>]) -> Graph<()> {
+    let pairs = live_sets.iter().flat_map(|set| {
+        (0..set.len()).flat_map(move |first| {
+            (first + 1..set.len()).map(move |second| (set[first], set[second]))
+        })
+    });
+
+    interference_from_pairs(values, pairs)
+}
+
+/// Returns the interference graph of values with live ranges `ranges`, where value `i` is live from instruction
+/// `ranges[i].0` up to but not including instruction `ranges[i].1`. Values interfere if their ranges overlap.
+/// Such interval graphs can always be colored with as many colors as values are live at once
+///
+/// # Arguments
+/// * `ranges`: live range of every value
+///
+/// # Examples
+/// ```
+/// use rudac::graph::coloring::interference_from_ranges;
+///
+/// let graph = interference_from_ranges(&[(0, 4), (1, 2), (2, 6), (4, 5)]);
+///
+/// assert!(graph.has_edge(0, 1) && graph.has_edge(0, 2) && graph.has_edge(2, 3));
+/// assert!(!graph.has_edge(1, 2) && !graph.has_edge(0, 3));
+/// ```
+pub fn interference_from_ranges(ranges: &[(usize, usize)]) -> Graph<()> {
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_unstable_by_key(|value| ranges[*value]);
+
+    // sweeps the ranges by start, keeping the ones still live
+    let mut live: Vec<usize> = Vec::new();
+    let mut pairs = Vec::new();
+    for value in order {
+        let (start, end) = ranges[value];
+        live.retain(|other| ranges[*other].1 > start);
+        if start < end {
+            pairs.extend(live.iter().map(|other| (*other, value)));
+            live.push(value);
+        }
+    }
+
+    interference_from_pairs(ranges.len(), pairs.into_iter())
+}
+
+/// Assigns at most `registers` registers to the vertices of the interference graph `graph` with Chaitin's
+/// simplify and select phases and Briggs' optimistic spilling. Vertices with fewer than k neighbors are removed
+/// one by one, since they can always be colored later. When none is left, the vertex with the lowest spill cost
+/// per neighbor becomes a spill candidate and is removed too. The vertices are then colored in reverse order of
+/// removal with the lowest free register, and candidates without a free register are spilled
+///
+/// # Arguments
+/// * `graph`: the undirected interference graph, weights are ignored
+/// * `registers`: number of registers k
+/// * `spill_costs`: cost of spilling every vertex, like its number of uses weighted by loop depth
+///
+/// # Panics
+/// * panics if `graph` is directed
+/// * panics if `spill_costs` does not have one cost for every vertex
+///
+/// # Examples
+/// ```
+/// use rudac::graph::coloring::{allocate_registers, interference_graph};
+///
+/// // a cycle 0 - 1 - 2 - 3 - 0: every value has two neighbors, yet two registers are enough
+/// let graph = interference_graph(4, &[vec![0, 1], vec![1, 2], vec![2, 3], vec![3, 0]]);
+///
+/// let allocation = allocate_registers(&graph, 2, &[1.0; 4]);
+///
+/// assert_eq!(allocation.spill_candidates.len(), 1);
+/// assert!(allocation.spilled.is_empty());
+/// assert_ne!(allocation.registers[0], allocation.registers[1]);
+///
+/// // a triangle does not fit in two registers
+/// let graph = interference_graph(3, &[vec![0, 1, 2]]);
+///
+/// let allocation = allocate_registers(&graph, 2, &[3.0, 1.0, 2.0]);
+///
+/// assert_eq!(allocation.spilled, vec![1]);
+/// ```
+pub fn allocate_registers<W>(
+    graph: &Graph<W>,
+    registers: usize,
+    spill_costs: &[f64],
+) -> Allocation {
+    if graph.is_directed() {
+        panic!("Graph must be undirected");
+    }
+    if spill_costs.len() != graph.vertex_count() {
+        panic!("Spill costs must be given for every vertex");
+    }
+
+    let vertices = graph.vertex_count();
+    let neighbors: Vec<Vec<usize>> = (0..vertices)
+        .map(|vertex| {
+            let mut neighbors: Vec<usize> = graph
+                .neighbors(vertex)
+                .iter()
+                .map(|edge| edge.to)
+                .filter(|neighbor| *neighbor != vertex)
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors
+        })
+        .collect();
+
+    // simplify: removes vertices with fewer than k remaining neighbors, or a spill candidate if there is none
+    let mut degree: Vec<usize> = neighbors.iter().map(|neighbors| neighbors.len()).collect();
+    let mut removed = vec![false; vertices];
+    let mut low: Vec<usize> = (0..vertices)
+        .rev()
+        .filter(|vertex| degree[*vertex] < registers)
+        .collect();
+    let mut stack = Vec::with_capacity(vertices);
+    let mut spill_candidates = Vec::new();
+    while stack.len() < vertices {
+        let vertex = match low.pop() {
+            Some(vertex) if removed[vertex] => continue,
+            Some(vertex) => vertex,
+            None => {
+                let candidate = (0..vertices)
+                    .filter(|vertex| !removed[*vertex])
+                    .min_by(|first, second| {
+                        let cost =
+                            |vertex: usize| spill_costs[vertex] / degree[vertex].max(1) as f64;
+                        cost(*first).total_cmp(&cost(*second))
+                    })
+                    .unwrap();
+                spill_candidates.push(candidate);
+                candidate
+            }
+        };
+
+        removed[vertex] = true;
+        stack.push(vertex);
+        for neighbor in &neighbors[vertex] {
+            if !removed[*neighbor] {
+                degree[*neighbor] -= 1;
+                if degree[*neighbor] + 1 == registers {
+                    low.push(*neighbor);
+                }
+            }
+        }
+    }
+
+    // select: colors the vertices in reverse order of removal
+    let mut assigned: Vec<Option<usize>> = vec![None; vertices];
+    let mut used = vec![false; registers];
+    while let Some(vertex) = stack.pop() {
+        used.fill(false);
+        for neighbor in &neighbors[vertex] {
+            if let Some(register) = assigned[*neighbor] {
+                used[register] = true;
+            }
+        }
+        assigned[vertex] = used.iter().position(|used| !used);
+    }
+
+    let spilled = (0..vertices)
+        .filter(|vertex| assigned[*vertex].is_none())
+        .collect();
+
+    Allocation {
+        registers: assigned,
+        spilled,
+        spill_candidates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::erdos_renyi;
+    use crate::util::{Rng, SplitMix64};
+
+    fn is_valid<W>(graph: &Graph<W>, allocation: &Allocation, registers: usize) -> bool {
+        graph.edges().all(|(from, to, _)| {
+            from == to
+                || allocation.registers[from].is_none()
+                || allocation.registers[from] != allocation.registers[to]
+        }) && allocation
+            .registers
+            .iter()
+            .flatten()
+            .all(|register| *register < registers)
+            && allocation
+                .spilled
+                .iter()
+                .all(|vertex| allocation.spill_candidates.contains(vertex))
+    }
+
+    #[test]
+    fn graph_coloring_intervals_need_no_spills() {
+        let mut rng = SplitMix64::init(70);
+        for _ in 0..30 {
+            let ranges: Vec<(usize, usize)> = (0..30)
+                .map(|_| {
+                    let start = rng.next_below(50) as usize;
+                    (start, start + 1 + rng.next_below(10) as usize)
+                })
+                .collect();
+            let graph = interference_from_ranges(&ranges);
+
+            // the most values live at one instruction
+            let pressure = (0..60)
+                .map(|point| {
+                    ranges
+                        .iter()
+                        .filter(|(start, end)| (*start..*end).contains(&point))
+                        .count()
+                })
+                .max()
+                .unwrap();
+            for (first, second, _) in graph.edges() {
+                let (a, b) = (ranges[first], ranges[second]);
+                assert!(a.0 < b.1 && b.0 < a.1);
+            }
+
+            let allocation = allocate_registers(&graph, pressure, &[1.0; 30]);
+            assert!(is_valid(&graph, &allocation, pressure));
+            assert!(allocation.spill_candidates.is_empty() && allocation.spilled.is_empty());
+
+            let allocation = allocate_registers(&graph, pressure - 1, &[1.0; 30]);
+            assert!(is_valid(&graph, &allocation, pressure - 1));
+            assert!(!allocation.spilled.is_empty());
+        }
+    }
+
+    #[test]
+    fn graph_coloring_random_graphs() {
+        let mut rng = SplitMix64::init(71);
+        for registers in 0..6 {
+            let graph = erdos_renyi(40, 0.15, false, &mut rng);
+            let costs: Vec<f64> = (0..40).map(|_| rng.next_f64()).collect();
+            let allocation = allocate_registers(&graph, registers, &costs);
+
+            assert!(is_valid(&graph, &allocation, registers));
+            let missing: Vec<usize> = (0..40)
+                .filter(|vertex| allocation.registers[*vertex].is_none())
+                .collect();
+            assert_eq!(allocation.spilled, missing);
+        }
+    }
+
+    #[test]
+    fn graph_coloring_interference_graph_deduplicates() {
+        let graph = interference_graph(3, &[vec![0, 1], vec![1, 0, 0], vec![2]]);
+
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(interference_from_ranges(&[(0, 0), (0, 3)]).edge_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Spill costs must be given for every vertex")]
+    fn graph_coloring_panic_missing_costs() {
+        allocate_registers(&Graph::<()>::init_undirected(2), 1, &[1.0]);
+    }
+}
@@ -0,0 +1,445 @@
+//! Community detection: partitions of the vertices into groups that are densely connected inside and sparsely
+//! connected to each other, scored by modularity. Weights are ignored and parallel edges count as stronger ties.
+//! Directed graphs are treated as undirected
+
+use super::Graph;
+use crate::util::Rng;
+
+// modularity gains below this are rounding noise, ignoring them guarantees that the moves terminate
+const EPSILON: f64 = 1e-12;
+
+/// A partition of the vertices of a graph into communities
+#[derive(Debug, Clone, PartialEq)]
+pub struct Communities {
+    /// community of every vertex, numbered from zero in the order of their smallest vertex
+    pub assignment: Vec<usize>,
+
+    /// number of communities
+    pub count: usize,
+
+    /// modularity of the partition
+    pub modularity: f64,
+}
+
+// a symmetric weighted graph: the weights to the other vertices and the weight of the self loop of every vertex
+struct Level {
+    adjacency: Vec<Vec<(usize, f64)>>,
+    loops: Vec<f64>,
+}
+
+impl Level {
+    fn init<W>(graph: &Graph<W>) -> Level {
+        let vertices = graph.vertex_count();
+        let mut adjacency = vec![Vec::new(); vertices];
+        let mut loops = vec![0.0; vertices];
+        for vertex in 0..vertices {
+            let mut edges: Vec<usize> =
+                graph.neighbors(vertex).iter().map(|edge| edge.to).collect();
+            if graph.is_directed() {
+                edges.extend(graph.incoming(vertex).iter().map(|edge| edge.to));
+            }
+            edges.sort_unstable();
+
+            for to in edges {
+                // a loop adds two to the degree, a directed one is listed as leaving and as entering
+                if to == vertex {
+                    loops[vertex] += if graph.is_directed() { 1.0 } else { 2.0 };
+                } else {
+                    match adjacency[vertex].last_mut() {
+                        Some((last, weight)) if *last == to => *weight += 1.0,
+                        _ => adjacency[vertex].push((to, 1.0)),
+                    }
+                }
+            }
+        }
+
+        Level { adjacency, loops }
+    }
+
+    fn degree(&self, vertex: usize) -> f64 {
+        self.loops[vertex]
+            + self.adjacency[vertex]
+                .iter()
+                .map(|(_, weight)| weight)
+                .sum::<f64>()
+    }
+
+    // total weight, every edge counted from both of its ends
+    fn total(&self) -> f64 {
+        (0..self.loops.len())
+            .map(|vertex| self.degree(vertex))
+            .sum()
+    }
+
+    fn modularity(&self, assignment: &[usize], count: usize) -> f64 {
+        let total = self.total();
+        if total == 0.0 {
+            return 0.0;
+        }
+
+        let mut inside = vec![0.0; count];
+        let mut degrees = vec![0.0; count];
+        for (vertex, community) in assignment.iter().enumerate() {
+            inside[*community] += self.loops[vertex];
+            degrees[*community] += self.degree(vertex);
+            for (to, weight) in &self.adjacency[vertex] {
+                if assignment[*to] == *community {
+                    inside[*community] += weight;
+                }
+            }
+        }
+
+        inside
+            .iter()
+            .zip(degrees.iter())
+            .map(|(inside, degree)| inside / total - (degree / total) * (degree / total))
+            .sum()
+    }
+
+    // merges every community into a single vertex
+    fn aggregate(&self, assignment: &[usize], count: usize) -> Level {
+        let mut adjacency = vec![Vec::new(); count];
+        let mut loops = vec![0.0; count];
+        for (vertex, community) in assignment.iter().enumerate() {
+            loops[*community] += self.loops[vertex];
+            for (to, weight) in &self.adjacency[vertex] {
+                if assignment[*to] == *community {
+                    loops[*community] += weight;
+                } else {
+                    adjacency[*community].push((assignment[*to], *weight));
+                }
+            }
+        }
+        for edges in adjacency.iter_mut() {
+            edges.sort_unstable_by_key(|(to, _)| *to);
+            let mut merged: Vec<(usize, f64)> = Vec::with_capacity(edges.len());
+            for (to, weight) in edges.drain(..) {
+                match merged.last_mut() {
+                    Some((last, total)) if *last == to => *total += weight,
+                    _ => merged.push((to, weight)),
+                }
+            }
+            *edges = merged;
+        }
+
+        Level { adjacency, loops }
+    }
+}
+
+// numbers the labels from zero in the order they first appear
+fn renumber(labels: &[usize]) -> (Vec<usize>, usize) {
+    let mut numbers = vec![usize::MAX; labels.len()];
+    let mut count = 0;
+    let assignment = labels
+        .iter()
+        .map(|label| {
+            if numbers[*label] == usize::MAX {
+                numbers[*label] = count;
+                count += 1;
+            }
+            numbers[*label]
+        })
+        .collect();
+
+    (assignment, count)
+}
+
+/// Returns the modularity of a partition of the vertices of `graph`: the fraction of the edges inside communities
+/// minus the fraction expected if the edges were placed at random with the same degrees. It ranges from -1/2 to 1,
+/// partitions with dense communities score high. A graph without edges has modularity zero
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `assignment`: community of every vertex, numbered from zero
+///
+/// # Panics
+/// * panics if `assignment` does not have an entry for every vertex
+///
+/// # Examples
+/// ```
+/// use rudac::graph::community::modularity;
+/// use rudac::graph::Graph;
+///
+/// // two separate edges
+/// let mut graph = Graph::init_undirected(4);
+/// graph.add_edge(0, 1, ());
+/// graph.add_edge(2, 3, ());
+///
+/// assert_eq!(modularity(&graph, &[0, 0, 1, 1]), 0.5);
+/// assert_eq!(modularity(&graph, &[0, 0, 0, 0]), 0.0);
+/// ```
+pub fn modularity<W>(graph: &Graph<W>, assignment: &[usize]) -> f64 {
+    if assignment.len() != graph.vertex_count() {
+        panic!("Every vertex must be assigned to a community");
+    }
+
+    let count = assignment.iter().max().map_or(0, |max| max + 1);
+    Level::init(graph).modularity(assignment, count)
+}
+
+/// Detects communities by label propagation: every vertex starts in its own community, then the vertices
+/// repeatedly adopt the community most common among their neighbors, visiting the vertices in random order
+/// and breaking ties at random, until every vertex is in one of the most common communities of its neighbors.
+/// Runs in near linear time per round and needs few rounds, but the result depends on the random choices
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `rng`: source of randomness
+///
+/// # Examples
+/// ```
+/// use rudac::graph::community::label_propagation;
+/// use rudac::graph::Graph;
+/// use rudac::util::SplitMix64;
+///
+/// // two triangles joined by the edge 2 - 3
+/// let mut graph = Graph::init_undirected(6);
+/// for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+///     graph.add_edge(from, to, ());
+/// }
+///
+/// let communities = label_propagation(&graph, &mut SplitMix64::init(4));
+///
+/// assert_eq!(communities.assignment, vec![0, 0, 0, 1, 1, 1]);
+/// assert!((communities.modularity - 5.0 / 14.0).abs() < 1e-9);
+/// ```
+pub fn label_propagation<W, R: Rng>(graph: &Graph<W>, rng: &mut R) -> Communities {
+    let level = Level::init(graph);
+    let vertices = graph.vertex_count();
+    let mut labels: Vec<usize> = (0..vertices).collect();
+    let mut order: Vec<usize> = (0..vertices).collect();
+    let mut weights = vec![0.0; vertices];
+    let mut best = Vec::new();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for index in (1..vertices).rev() {
+            order.swap(index, rng.next_below(index as u64 + 1) as usize);
+        }
+
+        for vertex in order.iter().copied() {
+            if level.adjacency[vertex].is_empty() {
+                continue;
+            }
+            for (to, weight) in &level.adjacency[vertex] {
+                weights[labels[*to]] += weight;
+            }
+            let heaviest = level.adjacency[vertex]
+                .iter()
+                .map(|(to, _)| weights[labels[*to]])
+                .fold(0.0, f64::max);
+            best.clear();
+            for (to, _) in &level.adjacency[vertex] {
+                let label = labels[*to];
+                if weights[label] == heaviest && !best.contains(&label) {
+                    best.push(label);
+                }
+            }
+            for (to, _) in &level.adjacency[vertex] {
+                weights[labels[*to]] = 0.0;
+            }
+
+            // a vertex keeps its community while it is among the most common ones
+            if !best.contains(&labels[vertex]) {
+                labels[vertex] = best[rng.next_below(best.len() as u64) as usize];
+                changed = true;
+            }
+        }
+    }
+
+    let (assignment, count) = renumber(&labels);
+    let modularity = level.modularity(&assignment, count);
+
+    Communities {
+        assignment,
+        count,
+        modularity,
+    }
+}
+
+/// Detects communities with the Louvain method: every vertex starts in its own community and vertices move to the
+/// neighboring community that increases modularity the most, until no move helps. The communities are then merged
+/// into single vertices and the process repeats on the smaller graph, until no vertex moves.
+/// Finds partitions of high modularity in near linear time in practice
+///
+/// # Arguments
+/// * `graph`: the graph
+///
+/// # Examples
+/// ```
+/// use rudac::graph::community::louvain;
+/// use rudac::graph::Graph;
+///
+/// // a ring of four 4-cliques
+/// let mut graph = Graph::init_undirected(16);
+/// for clique in 0..4 {
+///     for first in 0..4 {
+///         for second in first + 1..4 {
+///             graph.add_edge(4 * clique + first, 4 * clique + second, ());
+///         }
+///     }
+///     graph.add_edge(4 * clique, (4 * clique + 5) % 16, ());
+/// }
+///
+/// let communities = louvain(&graph);
+///
+/// assert_eq!(communities.count, 4);
+/// assert!((0..16).all(|vertex| communities.assignment[vertex] == vertex / 4));
+/// ```
+pub fn louvain<W>(graph: &Graph<W>) -> Communities {
+    let original = Level::init(graph);
+    let total = original.total();
+    let mut assignment: Vec<usize> = (0..graph.vertex_count()).collect();
+
+    let mut level = Level::init(graph);
+    loop {
+        let vertices = level.loops.len();
+        let degrees: Vec<f64> = (0..vertices).map(|vertex| level.degree(vertex)).collect();
+        let mut communities: Vec<usize> = (0..vertices).collect();
+        let mut community_degrees = degrees.clone();
+        let mut weights = vec![0.0; vertices];
+
+        let mut moved = false;
+        let mut improved = true;
+        while improved && total > 0.0 {
+            improved = false;
+            for vertex in 0..vertices {
+                let current = communities[vertex];
+                community_degrees[current] -= degrees[vertex];
+                for (to, weight) in &level.adjacency[vertex] {
+                    weights[communities[*to]] += weight;
+                }
+
+                // proportional to the modularity gain of joining a community
+                let gain = |community: usize, weights: &[f64], community_degrees: &[f64]| {
+                    weights[community] - community_degrees[community] * degrees[vertex] / total
+                };
+                let mut best = current;
+                let mut best_gain = gain(current, &weights, &community_degrees);
+                for (to, _) in &level.adjacency[vertex] {
+                    let candidate = communities[*to];
+                    let candidate_gain = gain(candidate, &weights, &community_degrees);
+                    if candidate_gain > best_gain + EPSILON {
+                        best = candidate;
+                        best_gain = candidate_gain;
+                    }
+                }
+                for (to, _) in &level.adjacency[vertex] {
+                    weights[communities[*to]] = 0.0;
+                }
+
+                community_degrees[best] += degrees[vertex];
+                if best != current {
+                    communities[vertex] = best;
+                    improved = true;
+                    moved = true;
+                }
+            }
+        }
+
+        if !moved {
+            break;
+        }
+        let (communities, level_count) = renumber(&communities);
+        for community in assignment.iter_mut() {
+            *community = communities[*community];
+        }
+        level = level.aggregate(&communities, level_count);
+    }
+
+    let (assignment, count) = renumber(&assignment);
+    let modularity = original.modularity(&assignment, count);
+
+    Communities {
+        assignment,
+        count,
+        modularity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+
+    // `groups` groups of `size` vertices, with edges inside groups far more likely than between them
+    fn planted_partition(groups: usize, size: usize, rng: &mut SplitMix64) -> Graph<()> {
+        let vertices = groups * size;
+        let mut graph = Graph::init_undirected(vertices);
+        for from in 0..vertices {
+            for to in from + 1..vertices {
+                let probability = if from / size == to / size { 0.5 } else { 0.01 };
+                if rng.next_f64() < probability {
+                    graph.add_edge(from, to, ());
+                }
+            }
+        }
+
+        graph
+    }
+
+    fn recovers_groups(communities: &Communities, size: usize) -> bool {
+        (0..communities.assignment.len()).all(|vertex| {
+            communities.assignment[vertex] == communities.assignment[vertex / size * size]
+        }) && communities.count == communities.assignment.len() / size
+    }
+
+    #[test]
+    fn graph_community_planted_partition() {
+        let mut rng = SplitMix64::init(10);
+        let graph = planted_partition(5, 20, &mut rng);
+
+        let louvain = louvain(&graph);
+        assert!(recovers_groups(&louvain, 20));
+        assert!((louvain.modularity - modularity(&graph, &louvain.assignment)).abs() < 1e-9);
+
+        let propagation = label_propagation(&graph, &mut rng);
+        assert!(recovers_groups(&propagation, 20));
+        assert!((propagation.modularity - louvain.modularity).abs() < 1e-9);
+    }
+
+    #[test]
+    fn graph_community_directed_and_loops() {
+        // a directed graph is treated like the undirected graph with the same edges
+        let mut rng = SplitMix64::init(2);
+        let mut directed = Graph::init_directed(40);
+        let mut undirected = Graph::init_undirected(40);
+        for _ in 0..120 {
+            let from = rng.next_below(40) as usize;
+            let to = rng.next_below(40) as usize;
+            directed.add_edge(from, to, ());
+            undirected.add_edge(from, to, ());
+        }
+
+        assert_eq!(louvain(&directed), louvain(&undirected));
+        let assignment: Vec<usize> = (0..40).map(|vertex| vertex % 3).collect();
+        assert!(
+            (modularity(&directed, &assignment) - modularity(&undirected, &assignment)).abs()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn graph_community_without_edges() {
+        let graph: Graph<()> = Graph::init_undirected(3);
+        let expected = Communities {
+            assignment: vec![0, 1, 2],
+            count: 3,
+            modularity: 0.0,
+        };
+
+        assert_eq!(louvain(&graph), expected);
+        assert_eq!(
+            label_propagation(&graph, &mut SplitMix64::init(1)),
+            expected
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Every vertex must be assigned to a community")]
+    fn graph_community_panic_short_assignment() {
+        let graph: Graph<()> = Graph::init_undirected(3);
+        modularity(&graph, &[0, 0]);
+    }
+}
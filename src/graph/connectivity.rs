@@ -0,0 +1,260 @@
+use crate::structure::PersistentDisjointSet;
+use std::collections::HashMap;
+
+/// Operation on an undirected graph whose edges come and go, given to `offline_dynamic_connectivity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectivityQuery {
+    /// adds an edge between two vertices, an edge may be added several times
+    Insert(usize, usize),
+
+    /// removes one copy of an edge between two vertices
+    Delete(usize, usize),
+
+    /// asks whether two vertices are connected by the edges present at this point
+    Connected(usize, usize),
+}
+
+/// Answers the `Connected` queries of a timeline of edge insertions and deletions on an undirected graph, in the
+/// order they appear. Vertices are numbered from 0 up to the largest vertex mentioned by a query.
+/// Every copy of an edge is present during an interval of the timeline, which is split over O(log q) nodes of a
+/// segment tree on the positions of the queries. A depth first walk of the segment tree joins the edges of a node
+/// on the way down and undoes them on the way up in a disjoint set union without path compression, so every leaf
+/// sees exactly the edges present at its query. Takes O(q log q log n) time for q queries on n vertices
+///
+/// # Arguments
+/// * `queries`: the timeline of operations
+///
+/// # Panics
+/// * panics if a query deletes an edge that is not present
+///
+/// # Examples
+/// ```
+/// use rudac::graph::offline_dynamic_connectivity;
+/// use rudac::graph::ConnectivityQuery::*;
+///
+/// let answers = offline_dynamic_connectivity(&[
+///     Insert(0, 1),
+///     Insert(1, 2),
+///     Connected(0, 2),
+///     Delete(0, 1),
+///     Connected(0, 2),
+///     Insert(2, 0),
+///     Connected(1, 0),
+/// ]);
+///
+/// assert_eq!(answers, vec![true, false, true]);
+/// ```
+pub fn offline_dynamic_connectivity(queries: &[ConnectivityQuery]) -> Vec<bool> {
+    let vertices = queries
+        .iter()
+        .map(|query| match *query {
+            ConnectivityQuery::Insert(first, second)
+            | ConnectivityQuery::Delete(first, second)
+            | ConnectivityQuery::Connected(first, second) => first.max(second) + 1,
+        })
+        .max()
+        .unwrap_or(0);
+
+    // edges present during every node of the segment tree over the positions of the queries
+    let mut edges: Vec<Vec<(usize, usize)>> = vec![Vec::new(); 4 * queries.len().max(1)];
+
+    // positions at which the present copies of every edge were inserted
+    let mut inserted: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (position, query) in queries.iter().enumerate() {
+        match *query {
+            ConnectivityQuery::Insert(first, second) => {
+                let edge = (first.min(second), first.max(second));
+                inserted.entry(edge).or_default().push(position);
+            }
+            ConnectivityQuery::Delete(first, second) => {
+                let edge = (first.min(second), first.max(second));
+                let start = inserted
+                    .get_mut(&edge)
+                    .and_then(|starts| starts.pop())
+                    .unwrap_or_else(|| panic!("Edge does not exist"));
+                add_edge(&mut edges, 1, 0, queries.len(), start, position, edge);
+            }
+            ConnectivityQuery::Connected(_, _) => {}
+        }
+    }
+    for (edge, starts) in inserted {
+        for start in starts {
+            add_edge(&mut edges, 1, 0, queries.len(), start, queries.len(), edge);
+        }
+    }
+
+    let mut disjoint_set = PersistentDisjointSet::init(vertices);
+    let mut answers = Vec::new();
+    if !queries.is_empty() {
+        answer(
+            queries,
+            &edges,
+            &mut disjoint_set,
+            &mut answers,
+            1,
+            0,
+            queries.len(),
+        );
+    }
+
+    answers
+}
+
+// adds `edge` to the nodes covering positions [start, end) in the subtree of `node`, which spans [left, right)
+fn add_edge(
+    edges: &mut Vec<Vec<(usize, usize)>>,
+    node: usize,
+    left: usize,
+    right: usize,
+    start: usize,
+    end: usize,
+    edge: (usize, usize),
+) {
+    if end <= left || right <= start {
+        return;
+    }
+    if start <= left && right <= end {
+        edges[node].push(edge);
+        return;
+    }
+
+    let middle = (left + right) / 2;
+    add_edge(edges, 2 * node, left, middle, start, end, edge);
+    add_edge(edges, 2 * node + 1, middle, right, start, end, edge);
+}
+
+// answers the queries in the subtree of `node`, spanning [left, right), with the edges of its ancestors joined
+fn answer(
+    queries: &[ConnectivityQuery],
+    edges: &[Vec<(usize, usize)>],
+    disjoint_set: &mut PersistentDisjointSet,
+    answers: &mut Vec<bool>,
+    node: usize,
+    left: usize,
+    right: usize,
+) {
+    for &(first, second) in &edges[node] {
+        disjoint_set.union(first, second);
+    }
+
+    if right - left == 1 {
+        if let ConnectivityQuery::Connected(first, second) = queries[left] {
+            answers.push(disjoint_set.connected(first, second));
+        }
+    } else {
+        let middle = (left + right) / 2;
+        answer(
+            queries,
+            edges,
+            disjoint_set,
+            answers,
+            2 * node,
+            left,
+            middle,
+        );
+        answer(
+            queries,
+            edges,
+            disjoint_set,
+            answers,
+            2 * node + 1,
+            middle,
+            right,
+        );
+    }
+
+    for _ in &edges[node] {
+        disjoint_set.undo();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    // answers every query by a search over the edges present at that point
+    fn brute_force(queries: &[ConnectivityQuery]) -> Vec<bool> {
+        let mut present: Vec<(usize, usize)> = Vec::new();
+        let mut answers = Vec::new();
+        for query in queries {
+            match *query {
+                ConnectivityQuery::Insert(first, second) => present.push((first, second)),
+                ConnectivityQuery::Delete(first, second) => {
+                    let index = present
+                        .iter()
+                        .position(|&(u, v)| (u, v) == (first, second) || (v, u) == (first, second))
+                        .unwrap();
+                    present.swap_remove(index);
+                }
+                ConnectivityQuery::Connected(first, second) => {
+                    let mut reached = vec![first];
+                    let mut stack = vec![first];
+                    while let Some(vertex) = stack.pop() {
+                        for &(u, v) in &present {
+                            for (from, to) in [(u, v), (v, u)] {
+                                if from == vertex && !reached.contains(&to) {
+                                    reached.push(to);
+                                    stack.push(to);
+                                }
+                            }
+                        }
+                    }
+                    answers.push(reached.contains(&second));
+                }
+            }
+        }
+
+        answers
+    }
+
+    #[test]
+    fn graph_offline_dynamic_connectivity_matches_brute_force() {
+        let mut rng = SplitMix64::init(103);
+        for vertices in [1, 2, 5, 12] {
+            let mut present: Vec<(usize, usize)> = Vec::new();
+            let mut queries = Vec::new();
+            for _ in 0..300 {
+                let first = rng.next_below(vertices) as usize;
+                let second = rng.next_below(vertices) as usize;
+                match rng.next_below(3) {
+                    0 => {
+                        present.push((first, second));
+                        queries.push(ConnectivityQuery::Insert(first, second));
+                    }
+                    1 if !present.is_empty() => {
+                        let (first, second) =
+                            present.swap_remove(rng.next_below(present.len() as u64) as usize);
+                        // deletions may name the end points in either order
+                        queries.push(ConnectivityQuery::Delete(second, first));
+                    }
+                    _ => queries.push(ConnectivityQuery::Connected(first, second)),
+                }
+            }
+
+            assert_eq!(
+                offline_dynamic_connectivity(&queries),
+                brute_force(&queries)
+            );
+        }
+    }
+
+    #[test]
+    fn graph_offline_dynamic_connectivity_empty() {
+        assert!(offline_dynamic_connectivity(&[]).is_empty());
+        assert_eq!(
+            offline_dynamic_connectivity(&[ConnectivityQuery::Connected(3, 3)]),
+            vec![true]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Edge does not exist")]
+    fn graph_offline_dynamic_connectivity_panic_delete() {
+        offline_dynamic_connectivity(&[
+            ConnectivityQuery::Insert(0, 1),
+            ConnectivityQuery::Delete(0, 1),
+            ConnectivityQuery::Delete(1, 0),
+        ]);
+    }
+}
@@ -0,0 +1,238 @@
+//! Fast approximations for covering problems that are NP-hard to solve exactly. Every function states how far
+//! its result can be from an optimal one. Edges of directed graphs are covered like undirected ones,
+//! domination follows the direction of the edges
+
+use super::Graph;
+use crate::heap::MinMax;
+use std::cmp::Reverse;
+
+/// Returns a vertex cover of `graph`: a set of vertices that touches every edge. The cover consists of the end
+/// points of a maximal matching, which every cover has to touch once per matched edge, so the cover is at most
+/// twice as large as a minimum one. Runs in linear time
+///
+/// # Arguments
+/// * `graph`: the graph
+///
+/// # Examples
+/// ```
+/// use rudac::graph::cover::vertex_cover;
+/// use rudac::graph::Graph;
+///
+/// // a star: the center alone covers every edge
+/// let mut graph = Graph::init_undirected(5);
+/// for leaf in 1..5 {
+///     graph.add_edge(0, leaf, ());
+/// }
+///
+/// let cover = vertex_cover(&graph);
+///
+/// assert!(cover.len() <= 2);
+/// assert!(graph.edges().all(|(from, to, _)| cover.contains(&from) || cover.contains(&to)));
+/// ```
+pub fn vertex_cover<W>(graph: &Graph<W>) -> Vec<usize> {
+    let mut covered = vec![false; graph.vertex_count()];
+    let mut cover = Vec::new();
+    for (from, to, _) in graph.edges() {
+        if covered[from] || covered[to] {
+            continue;
+        }
+        covered[from] = true;
+        cover.push(from);
+        // a self loop is covered by its only end point
+        if from != to {
+            covered[to] = true;
+            cover.push(to);
+        }
+    }
+    cover.sort_unstable();
+
+    cover
+}
+
+/// Returns the indices of sets whose union is the universe `0..universe`, or `None` if all sets together do not
+/// cover it. Greedily picks the set covering most uncovered elements, which uses at most H(s) ≤ ln(s) + 1 times
+/// as many sets as a minimum cover, where s is the size of the largest set. No polynomial algorithm is
+/// asymptotically better unless P = NP. Counts are updated lazily, so the time is near linear in the total size of the sets
+///
+/// # Arguments
+/// * `universe`: number of elements
+/// * `sets`: the sets, as lists of elements
+///
+/// # Panics
+/// * panics if a set contains an element outside the universe
+///
+/// # Examples
+/// ```
+/// use rudac::graph::cover::set_cover;
+///
+/// let sets = vec![vec![0, 1, 2], vec![2, 3], vec![3, 4, 5], vec![0, 3], vec![1, 4]];
+///
+/// assert_eq!(set_cover(6, &sets), Some(vec![0, 2]));
+/// assert_eq!(set_cover(7, &sets), None);
+/// ```
+pub fn set_cover(universe: usize, sets: &[Vec<usize>]) -> Option<Vec<usize>> {
+    if sets.iter().flatten().any(|element| *element >= universe) {
+        panic!("Element is outside the universe");
+    }
+
+    let mut covered = vec![false; universe];
+    let mut uncovered = universe;
+    let uncovered_in = |set: &[usize], covered: &[bool]| {
+        let mut elements: Vec<usize> = set
+            .iter()
+            .copied()
+            .filter(|element| !covered[*element])
+            .collect();
+        elements.sort_unstable();
+        elements.dedup();
+        elements.len()
+    };
+
+    // (uncovered elements when last counted, set), ties go to the set with the smaller index
+    let mut heap = MinMax::build_heap(
+        sets.iter()
+            .enumerate()
+            .map(|(index, set)| (uncovered_in(set, &covered), Reverse(index)))
+            .collect(),
+    );
+    let mut chosen = Vec::new();
+    while uncovered > 0 {
+        let (count, Reverse(index)) = heap.pop_max()?;
+        if count == 0 {
+            return None;
+        }
+
+        // counts only shrink, so a set whose count is still correct beats every other set
+        let current = uncovered_in(&sets[index], &covered);
+        if current < count {
+            heap.push((current, Reverse(index)));
+            continue;
+        }
+
+        for element in &sets[index] {
+            if !covered[*element] {
+                covered[*element] = true;
+                uncovered -= 1;
+            }
+        }
+        chosen.push(index);
+    }
+
+    Some(chosen)
+}
+
+/// Returns a dominating set of `graph`: a set of vertices such that every vertex is in the set or is reached by
+/// an edge from it. Greedily picks the vertex dominating most undominated vertices, which gives at most
+/// ln(Δ + 1) + 1 times as many vertices as a minimum dominating set, where Δ is the largest degree
+///
+/// # Arguments
+/// * `graph`: the graph
+///
+/// # Examples
+/// ```
+/// use rudac::graph::cover::dominating_set;
+/// use rudac::graph::Graph;
+///
+/// // a path 0 - 1 - 2 - 3 - 4 - 5 - 6
+/// let mut graph = Graph::init_undirected(7);
+/// for vertex in 0..6 {
+///     graph.add_edge(vertex, vertex + 1, ());
+/// }
+///
+/// assert_eq!(dominating_set(&graph), vec![1, 4, 5]);
+/// ```
+pub fn dominating_set<W>(graph: &Graph<W>) -> Vec<usize> {
+    let neighborhoods: Vec<Vec<usize>> = (0..graph.vertex_count())
+        .map(|vertex| {
+            std::iter::once(vertex)
+                .chain(graph.neighbors(vertex).iter().map(|edge| edge.to))
+                .collect()
+        })
+        .collect();
+
+    // every vertex dominates itself, so the neighborhoods always cover all vertices
+    let mut set = set_cover(graph.vertex_count(), &neighborhoods).unwrap();
+    set.sort_unstable();
+
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::erdos_renyi;
+    use crate::util::SplitMix64;
+
+    // size of the smallest subset of vertices accepted by `is_valid`
+    fn minimum(vertices: usize, is_valid: impl Fn(&[usize]) -> bool) -> usize {
+        (0u32..1 << vertices)
+            .filter_map(|mask| {
+                let subset: Vec<usize> = (0..vertices)
+                    .filter(|vertex| mask & (1 << vertex) != 0)
+                    .collect();
+                if is_valid(&subset) {
+                    Some(subset.len())
+                } else {
+                    None
+                }
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn graph_cover_vertex_cover_within_factor_two() {
+        let mut rng = SplitMix64::init(14);
+        for directed in [true, false] {
+            for _ in 0..20 {
+                let graph = erdos_renyi(12, 0.25, directed, &mut rng);
+                let is_cover = |cover: &[usize]| {
+                    graph
+                        .edges()
+                        .all(|(from, to, _)| cover.contains(&from) || cover.contains(&to))
+                };
+
+                let cover = vertex_cover(&graph);
+                assert!(is_cover(&cover));
+                assert!(cover.len() <= 2 * minimum(12, is_cover));
+            }
+        }
+    }
+
+    #[test]
+    fn graph_cover_dominating_set_within_bound() {
+        let mut rng = SplitMix64::init(15);
+        for directed in [true, false] {
+            for _ in 0..20 {
+                let graph = erdos_renyi(12, 0.2, directed, &mut rng);
+                let is_dominating = |set: &[usize]| {
+                    (0..12).all(|vertex| {
+                        set.contains(&vertex)
+                            || set.iter().any(|member| graph.has_edge(*member, vertex))
+                    })
+                };
+
+                let set = dominating_set(&graph);
+                assert!(is_dominating(&set));
+                let largest = (0..12).map(|vertex| graph.degree(vertex)).max().unwrap();
+                let bound = ((largest + 1) as f64).ln() + 1.0;
+                assert!(set.len() as f64 <= bound * minimum(12, is_dominating) as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn graph_cover_set_cover_edge_cases() {
+        assert_eq!(set_cover(0, &[]), Some(Vec::new()));
+        assert_eq!(set_cover(1, &[]), None);
+        assert_eq!(set_cover(2, &[vec![1, 1], vec![0]]), Some(vec![0, 1]));
+        assert!(dominating_set(&Graph::<()>::init_directed(0)).is_empty());
+        assert_eq!(dominating_set(&Graph::<()>::init_directed(2)), vec![0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Element is outside the universe")]
+    fn graph_cover_panic_element_outside_universe() {
+        set_cover(2, &[vec![2]]);
+    }
+}
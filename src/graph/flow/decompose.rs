@@ -0,0 +1,235 @@
+use super::FlowPath;
+use crate::graph::Weight;
+use std::collections::HashMap;
+use std::ops::Sub;
+
+/// Decomposition of a flow into flows along paths from the source to the sink and flows around cycles, computed by
+/// `decompose`. Adding up the flows of all paths and cycles gives back the decomposed flow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decomposition<W> {
+    /// paths from the source to the sink, their amounts add up to the value of the flow
+    pub paths: Vec<FlowPath<W>>,
+
+    /// cycles, flow that circulates without leaving the source or reaching the sink
+    pub cycles: Vec<FlowPath<W>>,
+}
+
+// flows left on the edges, with a pointer per vertex to the first leaving edge that may still carry flow
+struct Remaining<W> {
+    edges: Vec<(usize, usize, W)>,
+    outgoing: HashMap<usize, Vec<usize>>,
+    next: HashMap<usize, usize>,
+}
+
+impl<W: Weight + Sub<Output = W>> Remaining<W> {
+    fn next_edge(&mut self, vertex: usize) -> Option<usize> {
+        let edges = self.outgoing.get(&vertex)?;
+        let next = self.next.entry(vertex).or_insert(0);
+        while *next < edges.len() && self.edges[edges[*next]].2 == W::zero() {
+            *next += 1;
+        }
+
+        edges.get(*next).copied()
+    }
+
+    // removes the bottleneck amount from the edges and returns it
+    fn take(&mut self, edges: &[usize]) -> W {
+        let amount = edges.iter().map(|edge| self.edges[*edge].2).min().unwrap();
+        for edge in edges {
+            self.edges[*edge].2 = self.edges[*edge].2 - amount;
+        }
+
+        amount
+    }
+
+    // follows the flow from `start` until it reaches `target` or closes a cycle, which is taken out of the flow
+    fn walk(&mut self, start: usize, target: Option<usize>, decomposition: &mut Decomposition<W>) {
+        let mut vertices = vec![start];
+        let mut edges = Vec::new();
+        let mut on_path = HashMap::new();
+        on_path.insert(start, 0);
+        loop {
+            let vertex = *vertices.last().unwrap();
+            if Some(vertex) == target {
+                let amount = self.take(&edges);
+                decomposition.paths.push(FlowPath { vertices, amount });
+                return;
+            }
+
+            let edge = match self.next_edge(vertex) {
+                Some(edge) => edge,
+                None => panic!("Flow must be conserved"),
+            };
+            let to = self.edges[edge].1;
+            edges.push(edge);
+            if let Some(index) = on_path.get(&to).copied() {
+                let amount = self.take(&edges[index..]);
+                decomposition.cycles.push(FlowPath {
+                    vertices: vertices[index..].to_vec(),
+                    amount,
+                });
+                return;
+            }
+            on_path.insert(to, vertices.len());
+            vertices.push(to);
+        }
+    }
+}
+
+/// Decomposes a flow from `source` to `sink` into at most E paths and cycles, where E is the number of edges
+/// carrying flow. Paths are found by following the flow out of the source, and cycles met on the way are split off.
+/// Runs in O(VE) time
+///
+/// # Arguments
+/// * `flows`: flow on the edges as `(from, to, amount)`, like `MaxFlow::flows`
+/// * `source`: vertex the flow leaves
+/// * `sink`: vertex the flow enters
+///
+/// # Panics
+/// * panics if `source` and `sink` are the same vertex
+/// * panics if an amount is negative
+/// * panics if the flow into a vertex other than the source and the sink differs from the flow out of it
+///
+/// # Examples
+/// ```
+/// use rudac::graph::flow::{decompose, max_flow};
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_directed(4);
+/// graph.add_edge(0, 1, 2);
+/// graph.add_edge(0, 2, 1);
+/// graph.add_edge(1, 3, 1);
+/// graph.add_edge(1, 2, 1);
+/// graph.add_edge(2, 3, 2);
+///
+/// let flow = max_flow(&graph, 0, 3);
+/// let decomposition = decompose(&flow.flows, 0, 3);
+///
+/// let total: u64 = decomposition.paths.iter().map(|path| path.amount).sum();
+/// assert_eq!(total, 3);
+/// assert!(decomposition.cycles.is_empty());
+///
+/// // a circulation is made of cycles only
+/// let decomposition = decompose(&[(1, 2, 4), (2, 1, 4)], 0, 3);
+/// assert_eq!(decomposition.cycles[0].vertices, vec![1, 2]);
+/// assert_eq!(decomposition.cycles[0].amount, 4);
+/// ```
+pub fn decompose<W: Weight + Sub<Output = W>>(
+    flows: &[(usize, usize, W)],
+    source: usize,
+    sink: usize,
+) -> Decomposition<W> {
+    if source == sink {
+        panic!("Source and sink must be different vertices");
+    }
+    if flows.iter().any(|(_, _, amount)| *amount < W::zero()) {
+        panic!("Flow amounts must not be negative");
+    }
+
+    let mut remaining = Remaining {
+        edges: flows.to_vec(),
+        outgoing: HashMap::new(),
+        next: HashMap::new(),
+    };
+    for (index, (from, _, _)) in flows.iter().enumerate() {
+        remaining.outgoing.entry(*from).or_default().push(index);
+    }
+
+    let mut decomposition = Decomposition {
+        paths: Vec::new(),
+        cycles: Vec::new(),
+    };
+    while remaining.next_edge(source).is_some() {
+        remaining.walk(source, Some(sink), &mut decomposition);
+    }
+
+    // what is left circulates, every walk ends in a cycle
+    let mut starts: Vec<usize> = remaining.outgoing.keys().copied().collect();
+    starts.sort_unstable();
+    for start in starts {
+        while remaining.next_edge(start).is_some() {
+            remaining.walk(start, None, &mut decomposition);
+        }
+    }
+
+    decomposition
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::flow::max_flow;
+    use crate::graph::generate::{erdos_renyi, random_weights};
+    use crate::util::SplitMix64;
+
+    // adds up the flow of every edge over the paths and cycles
+    fn recompose(
+        decomposition: &Decomposition<u64>,
+        source: usize,
+        sink: usize,
+    ) -> HashMap<(usize, usize), u64> {
+        let mut flows = HashMap::new();
+        for path in &decomposition.paths {
+            assert_eq!(path.vertices.first(), Some(&source));
+            assert_eq!(path.vertices.last(), Some(&sink));
+            for pair in path.vertices.windows(2) {
+                *flows.entry((pair[0], pair[1])).or_default() += path.amount;
+            }
+        }
+        for cycle in &decomposition.cycles {
+            for (index, from) in cycle.vertices.iter().enumerate() {
+                let to = cycle.vertices[(index + 1) % cycle.vertices.len()];
+                *flows.entry((*from, to)).or_default() += cycle.amount;
+            }
+        }
+
+        flows
+    }
+
+    fn totals(flows: &[(usize, usize, u64)]) -> HashMap<(usize, usize), u64> {
+        let mut totals = HashMap::new();
+        for (from, to, amount) in flows {
+            if *amount > 0 {
+                *totals.entry((*from, *to)).or_default() += *amount;
+            }
+        }
+
+        totals
+    }
+
+    #[test]
+    fn graph_flow_decompose_recomposes_flow() {
+        let mut rng = SplitMix64::init(51);
+        for _ in 0..20 {
+            let graph = erdos_renyi(12, 0.3, true, &mut rng);
+            let graph = random_weights(&graph, 1, 20, &mut rng);
+            let flow = max_flow(&graph, 0, 11);
+
+            // a circulation 3 -> 4 -> 5 -> 3 on top of the maximum flow
+            let mut flows = flow.flows.clone();
+            flows.extend([(3, 4, 2), (4, 5, 2), (5, 3, 2)]);
+            let decomposition = decompose(&flows, 0, 11);
+
+            let value: u64 = decomposition.paths.iter().map(|path| path.amount).sum();
+            assert_eq!(value, flow.value);
+            assert!(decomposition.paths.len() + decomposition.cycles.len() <= flows.len());
+            assert!(!decomposition.cycles.is_empty());
+            assert_eq!(recompose(&decomposition, 0, 11), totals(&flows));
+        }
+    }
+
+    #[test]
+    fn graph_flow_decompose_cycle_through_source() {
+        let flows = [(0, 1, 3), (1, 0, 1), (1, 2, 2)];
+        let decomposition = decompose(&flows, 0, 2);
+
+        assert_eq!(decomposition.cycles.len(), 1);
+        assert_eq!(recompose(&decomposition, 0, 2), totals(&flows));
+    }
+
+    #[test]
+    #[should_panic(expected = "Flow must be conserved")]
+    fn graph_flow_decompose_panic_not_conserved() {
+        decompose(&[(0, 1, 3), (1, 2, 2)], 0, 2);
+    }
+}
@@ -0,0 +1,173 @@
+use super::max_flow;
+use crate::graph::{Graph, Weight};
+use crate::util::{Footprint, MemoryFootprint};
+use std::ops::Sub;
+
+/// Gomory-Hu tree of an undirected graph: a weighted tree on the same vertices in which the minimum cut between any
+/// two vertices of the graph equals the lightest edge on the tree path between them. Built by Gusfield's algorithm
+/// with V - 1 maximum flow computations on the original graph, without contracting vertices
+///
+/// # Examples
+/// ```
+/// use rudac::graph::flow::GomoryHuTree;
+/// use rudac::graph::Graph;
+///
+/// // two triangles joined by a single edge of capacity 1
+/// let mut graph = Graph::init_undirected(6);
+/// for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)] {
+///     graph.add_edge(from, to, 4);
+/// }
+/// graph.add_edge(2, 3, 1);
+///
+/// let tree = GomoryHuTree::init(&graph);
+///
+/// assert_eq!(tree.min_cut(0, 5), Some(1));
+/// assert_eq!(tree.min_cut(0, 1), Some(8));
+/// assert_eq!(tree.min_cut(2, 2), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GomoryHuTree<W> {
+    // parent of every vertex in the tree rooted at vertex 0, and capacity of the edge to it
+    parent: Vec<usize>,
+    capacity: Vec<W>,
+}
+
+impl<W: Weight + Sub<Output = W>> GomoryHuTree<W> {
+    /// Builds the Gomory-Hu tree of `graph` in V - 1 maximum flow computations
+    ///
+    /// # Arguments
+    /// * `graph`: the undirected graph, edge weights are capacities
+    ///
+    /// # Panics
+    /// * panics if `graph` is directed
+    /// * panics if a capacity is negative
+    pub fn init(graph: &Graph<W>) -> GomoryHuTree<W> {
+        if graph.is_directed() {
+            panic!("Graph must be undirected");
+        }
+
+        let vertices = graph.vertex_count();
+        let mut parent = vec![0; vertices];
+        let mut capacity = vec![W::zero(); vertices];
+        for vertex in 1..vertices {
+            let flow = max_flow(graph, vertex, parent[vertex]);
+            capacity[vertex] = flow.value;
+
+            // later vertices that share the parent and fall on this side of the cut hang below this vertex
+            let mut on_side = vec![false; vertices];
+            for member in &flow.source_side {
+                on_side[*member] = true;
+            }
+            for later in vertex + 1..vertices {
+                if on_side[later] && parent[later] == parent[vertex] {
+                    parent[later] = vertex;
+                }
+            }
+        }
+
+        GomoryHuTree { parent, capacity }
+    }
+
+    /// Returns number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Returns the edges of the tree as `(vertex, parent, capacity)`, for every vertex but vertex 0
+    pub fn edges(&self) -> Vec<(usize, usize, W)> {
+        (1..self.vertex_count())
+            .map(|vertex| (vertex, self.parent[vertex], self.capacity[vertex]))
+            .collect()
+    }
+
+    fn depth(&self, mut vertex: usize) -> usize {
+        let mut depth = 0;
+        while vertex != 0 {
+            vertex = self.parent[vertex];
+            depth += 1;
+        }
+
+        depth
+    }
+
+    /// Returns the capacity of a minimum cut between `first` and `second`, `None` if they are the same vertex.
+    /// Walks the tree path between them in O(V) time
+    ///
+    /// # Arguments
+    /// * `first`: the first vertex
+    /// * `second`: the second vertex
+    ///
+    /// # Panics
+    /// * panics if `first` or `second` does not exist
+    pub fn min_cut(&self, first: usize, second: usize) -> Option<W> {
+        if first >= self.vertex_count() || second >= self.vertex_count() {
+            panic!("Vertex does not exist");
+        }
+        if first == second {
+            return None;
+        }
+
+        let (mut first, mut second) = (first, second);
+        let (mut first_depth, mut second_depth) = (self.depth(first), self.depth(second));
+        let mut lightest: Option<W> = None;
+        while first != second {
+            // moves the deeper end one edge up
+            let deeper = if first_depth >= second_depth {
+                first_depth -= 1;
+                &mut first
+            } else {
+                second_depth -= 1;
+                &mut second
+            };
+            let capacity = self.capacity[*deeper];
+            *deeper = self.parent[*deeper];
+            lightest = Some(lightest.map_or(capacity, |lightest| W::min(lightest, capacity)));
+        }
+
+        lightest
+    }
+}
+
+impl<W> MemoryFootprint for GomoryHuTree<W> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<(usize, W)>(self.parent.len())
+            .with_vec(&self.parent)
+            .with_vec(&self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::{erdos_renyi, random_weights};
+    use crate::util::SplitMix64;
+
+    #[test]
+    fn graph_flow_gomory_hu_matches_max_flows() {
+        let mut rng = SplitMix64::init(52);
+        for _ in 0..10 {
+            let graph = erdos_renyi(12, 0.3, false, &mut rng);
+            let graph = random_weights(&graph, 1, 10, &mut rng);
+            let tree = GomoryHuTree::init(&graph);
+            assert_eq!(tree.edges().len(), 11);
+
+            for first in 0..12 {
+                for second in first + 1..12 {
+                    let expected = max_flow(&graph, first, second).value;
+                    assert_eq!(tree.min_cut(first, second), Some(expected));
+                    assert_eq!(tree.min_cut(second, first), Some(expected));
+                }
+            }
+        }
+
+        assert!(GomoryHuTree::init(&Graph::<u64>::init_undirected(0))
+            .edges()
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Graph must be undirected")]
+    fn graph_flow_gomory_hu_panic_directed() {
+        GomoryHuTree::init(&Graph::<u64>::init_directed(2));
+    }
+}
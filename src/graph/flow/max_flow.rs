@@ -0,0 +1,257 @@
+use crate::graph::{Graph, Weight};
+use std::collections::VecDeque;
+use std::ops::Sub;
+
+/// A maximum flow from a source to a sink and the minimum cut that limits it, computed by `max_flow`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxFlow<W> {
+    /// total flow leaving the source, equal to the capacity of the minimum cut
+    pub value: W,
+
+    /// flow on every edge carrying flow as `(from, to, amount)`, in the order of `Graph::edges`.
+    /// Flow on an undirected edge is given in the direction it goes
+    pub flows: Vec<(usize, usize, W)>,
+
+    /// vertices on the source side of the minimum cut closest to the source, sorted
+    pub source_side: Vec<usize>,
+}
+
+// an edge of the residual network, arcs 2i and 2i + 1 are reverses of each other
+struct Arc<W> {
+    to: usize,
+    residual: W,
+}
+
+// residual network of Dinic's algorithm
+struct Network<W> {
+    arcs: Vec<Arc<W>>,
+    outgoing: Vec<Vec<usize>>,
+    level: Vec<Option<usize>>,
+    next: Vec<usize>,
+}
+
+impl<W: Weight + Sub<Output = W>> Network<W> {
+    // levels of a breadth first search over arcs with residual capacity, true if the sink is reached
+    fn levels(&mut self, source: usize, sink: usize) -> bool {
+        self.level.fill(None);
+        self.level[source] = Some(0);
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        while let Some(vertex) = queue.pop_front() {
+            for arc in &self.outgoing[vertex] {
+                let arc = &self.arcs[*arc];
+                if arc.residual > W::zero() && self.level[arc.to].is_none() {
+                    self.level[arc.to] = self.level[vertex].map(|level| level + 1);
+                    queue.push_back(arc.to);
+                }
+            }
+        }
+
+        self.level[sink].is_some()
+    }
+
+    // pushes at most `limit` along level increasing arcs from `vertex` to `sink`, `None` is no limit
+    fn push(&mut self, vertex: usize, sink: usize, limit: Option<W>) -> W {
+        if vertex == sink {
+            return limit.unwrap();
+        }
+
+        let mut pushed = W::zero();
+        while self.next[vertex] < self.outgoing[vertex].len() {
+            let index = self.outgoing[vertex][self.next[vertex]];
+            let (to, residual) = (self.arcs[index].to, self.arcs[index].residual);
+            let remaining = limit.map(|limit| limit - pushed);
+            if residual > W::zero() && self.level[to] == self.level[vertex].map(|level| level + 1) {
+                let amount = remaining.map_or(residual, |remaining| W::min(remaining, residual));
+                let sent = self.push(to, sink, Some(amount));
+                if sent > W::zero() {
+                    self.arcs[index].residual = self.arcs[index].residual - sent;
+                    self.arcs[index ^ 1].residual = self.arcs[index ^ 1].residual + sent;
+                    pushed = pushed + sent;
+                    if limit == Some(pushed) {
+                        return pushed;
+                    }
+                    continue;
+                }
+            }
+            self.next[vertex] += 1;
+        }
+
+        pushed
+    }
+}
+
+/// Returns a maximum flow from `source` to `sink` and a minimum cut separating them, computed by Dinic's algorithm
+/// in O(V^2 E) time. An undirected edge carries flow in either direction up to its capacity
+///
+/// # Arguments
+/// * `graph`: the graph, edge weights are capacities
+/// * `source`: vertex the flow leaves
+/// * `sink`: vertex the flow enters
+///
+/// # Panics
+/// * panics if `source` or `sink` does not exist
+/// * panics if `source` and `sink` are the same vertex
+/// * panics if a capacity is negative
+///
+/// # Examples
+/// ```
+/// use rudac::graph::flow::max_flow;
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_directed(4);
+/// graph.add_edge(0, 1, 3);
+/// graph.add_edge(0, 2, 2);
+/// graph.add_edge(1, 2, 5);
+/// graph.add_edge(1, 3, 2);
+/// graph.add_edge(2, 3, 3);
+///
+/// let flow = max_flow(&graph, 0, 3);
+///
+/// assert_eq!(flow.value, 5);
+/// assert_eq!(flow.source_side, vec![0]);
+/// ```
+pub fn max_flow<W: Weight + Sub<Output = W>>(
+    graph: &Graph<W>,
+    source: usize,
+    sink: usize,
+) -> MaxFlow<W> {
+    graph.check_vertex(source);
+    graph.check_vertex(sink);
+    if source == sink {
+        panic!("Source and sink must be different vertices");
+    }
+
+    let vertices = graph.vertex_count();
+    let mut network = Network {
+        arcs: Vec::new(),
+        outgoing: vec![Vec::new(); vertices],
+        level: vec![None; vertices],
+        next: vec![0; vertices],
+    };
+    let edges: Vec<(usize, usize, W)> = graph
+        .edges()
+        .map(|(from, to, weight)| (from, to, *weight))
+        .collect();
+    for (from, to, capacity) in &edges {
+        if *capacity < W::zero() {
+            panic!("Capacities must not be negative");
+        }
+        let reverse = if graph.is_directed() {
+            W::zero()
+        } else {
+            *capacity
+        };
+        network.outgoing[*from].push(network.arcs.len());
+        network.arcs.push(Arc {
+            to: *to,
+            residual: *capacity,
+        });
+        network.outgoing[*to].push(network.arcs.len());
+        network.arcs.push(Arc {
+            to: *from,
+            residual: reverse,
+        });
+    }
+
+    let mut value = W::zero();
+    while network.levels(source, sink) {
+        network.next.fill(0);
+        value = value + network.push(source, sink, None);
+    }
+
+    // an arc carries the capacity it lost, an undirected edge carries flow in the direction that lost capacity
+    let mut flows = Vec::new();
+    for (index, (from, to, capacity)) in edges.into_iter().enumerate() {
+        let (forward, backward) = (
+            network.arcs[2 * index].residual,
+            network.arcs[2 * index + 1].residual,
+        );
+        if from == to {
+            continue;
+        }
+        if forward < capacity {
+            flows.push((from, to, capacity - forward));
+        } else if !graph.is_directed() && backward < capacity {
+            flows.push((to, from, capacity - backward));
+        }
+    }
+
+    // after the last search the levels mark the vertices reachable in the residual network
+    let source_side = (0..vertices)
+        .filter(|vertex| network.level[*vertex].is_some())
+        .collect();
+
+    MaxFlow {
+        value,
+        flows,
+        source_side,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::{erdos_renyi, random_weights};
+    use crate::util::SplitMix64;
+
+    // capacity of the edges leaving `side`, undirected edges count in both directions
+    fn cut_capacity(graph: &Graph<u64>, side: &[usize]) -> u64 {
+        graph
+            .edges()
+            .filter(|(from, to, _)| {
+                side.contains(from) != side.contains(to)
+                    && (!graph.is_directed() || side.contains(from))
+            })
+            .map(|(_, _, weight)| weight)
+            .sum()
+    }
+
+    #[test]
+    fn graph_flow_max_flow_equals_min_cut() {
+        let mut rng = SplitMix64::init(50);
+        for directed in [true, false] {
+            for _ in 0..20 {
+                let graph = erdos_renyi(10, 0.3, directed, &mut rng);
+                let graph = random_weights(&graph, 0, 9, &mut rng);
+                let flow = max_flow(&graph, 0, 9);
+
+                // the flow is conserved and within the capacities
+                let mut balance = [0i64; 10];
+                for (from, to, amount) in &flow.flows {
+                    balance[*from] -= *amount as i64;
+                    balance[*to] += *amount as i64;
+                }
+                assert_eq!(balance[0], -(flow.value as i64));
+                assert_eq!(balance[9], flow.value as i64);
+                assert!(balance[1..9].iter().all(|balance| *balance == 0));
+                assert!(flow.flows.iter().all(|(from, to, amount)| {
+                    graph
+                        .neighbors(*from)
+                        .iter()
+                        .any(|edge| edge.to == *to && edge.weight >= *amount)
+                }));
+
+                // no cut is smaller than the one found, which has the capacity of the flow
+                assert!(flow.source_side.contains(&0) && !flow.source_side.contains(&9));
+                assert_eq!(cut_capacity(&graph, &flow.source_side), flow.value);
+                let smallest = (0u32..1 << 10)
+                    .filter(|mask| mask & 1 == 1 && mask & 1 << 9 == 0)
+                    .map(|mask| {
+                        let side: Vec<usize> =
+                            (0..10).filter(|vertex| mask & 1 << vertex != 0).collect();
+                        cut_capacity(&graph, &side)
+                    })
+                    .min()
+                    .unwrap();
+                assert_eq!(flow.value, smallest);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Source and sink must be different vertices")]
+    fn graph_flow_panic_same_source_and_sink() {
+        max_flow(&Graph::<u64>::init_directed(2), 1, 1);
+    }
+}
@@ -0,0 +1,22 @@
+//! Maximum flows and minimum cuts, with tools to analyze them: decompositions of a flow into paths and cycles and
+//! Gomory-Hu trees holding the minimum cuts between all pairs of vertices. Edge weights are capacities
+
+mod decompose;
+mod gomory_hu;
+mod max_flow;
+
+pub use decompose::decompose;
+pub use decompose::Decomposition;
+pub use gomory_hu::GomoryHuTree;
+pub use max_flow::max_flow;
+pub use max_flow::MaxFlow;
+
+/// Flow sent along a path or around a cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowPath<W> {
+    /// vertices of the path, from its start to its end. A cycle returns from the last vertex to the first one
+    pub vertices: Vec<usize>,
+
+    /// flow on every edge of the path
+    pub amount: W,
+}
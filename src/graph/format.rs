@@ -0,0 +1,522 @@
+use super::Graph;
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+// escapes the characters with a meaning in XML text and attribute values
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// a start, end or empty element tag and the text that follows it
+struct Tag<'a> {
+    name: &'a str,
+    closing: bool,
+    empty: bool,
+    attributes: HashMap<&'a str, String>,
+    text: &'a str,
+}
+
+impl Tag<'_> {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+}
+
+// splits a document into its tags, skipping the prolog, comments and declarations
+fn xml_tags(document: &str) -> Option<Vec<Tag<'_>>> {
+    let mut tags = Vec::new();
+    let mut rest = document;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if rest.starts_with("<!--") {
+            rest = &rest[rest.find("-->")? + 3..];
+            continue;
+        }
+        let end = rest.find('>')?;
+        let inner = &rest[1..end];
+        rest = &rest[end + 1..];
+        if inner.starts_with('?') || inner.starts_with('!') {
+            continue;
+        }
+
+        let closing = inner.starts_with('/');
+        let empty = inner.ends_with('/');
+        let inner = inner.trim_start_matches('/').trim_end_matches('/');
+        let name_end = inner.find(char::is_whitespace).unwrap_or(inner.len());
+
+        let mut attributes = HashMap::new();
+        let mut remaining = inner[name_end..].trim_start();
+        while !remaining.is_empty() {
+            let equals = remaining.find('=')?;
+            let name = remaining[..equals].trim();
+            let value = remaining[equals + 1..].trim_start();
+            let quote = value
+                .chars()
+                .next()
+                .filter(|quote| *quote == '"' || *quote == '\'')?;
+            let value_end = value[1..].find(quote)? + 1;
+            attributes.insert(name, unescape_xml(&value[1..value_end]));
+            remaining = value[value_end + 1..].trim_start();
+        }
+
+        tags.push(Tag {
+            name: &inner[..name_end],
+            closing,
+            empty,
+            attributes,
+            text: &rest[..rest.find('<').unwrap_or(rest.len())],
+        });
+    }
+
+    Some(tags)
+}
+
+impl<W: Display> Graph<W> {
+    /// Returns the edges of the graph as lines `from to weight`. Vertices after the last one with an edge
+    /// are not part of the output, an undirected edge is written once
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::Graph;
+    ///
+    /// let mut graph = Graph::init_directed(3);
+    /// graph.add_edge(0, 2, 7);
+    /// graph.add_edge(2, 1, 1);
+    ///
+    /// assert_eq!(graph.to_edge_list(), "0 2 7\n2 1 1\n");
+    /// ```
+    pub fn to_edge_list(&self) -> String {
+        let mut text = String::new();
+        for (from, to, weight) in self.edges() {
+            writeln!(text, "{} {} {}", from, to, weight).unwrap();
+        }
+
+        text
+    }
+
+    /// Returns the graph in the DIMACS shortest path format: a problem line `p sp vertices arcs`
+    /// followed by arc lines `a from to weight` with vertices numbered from one.
+    /// The format only has directed arcs, so an undirected edge is written as two arcs
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::Graph;
+    ///
+    /// let mut graph = Graph::init_undirected(3);
+    /// graph.add_edge(0, 1, 5);
+    ///
+    /// assert_eq!(graph.to_dimacs(), "p sp 3 2\na 1 2 5\na 2 1 5\n");
+    /// ```
+    pub fn to_dimacs(&self) -> String {
+        let arcs: usize = (0..self.vertex_count())
+            .map(|vertex| self.degree(vertex))
+            .sum();
+        let mut text = format!("p sp {} {}\n", self.vertex_count(), arcs);
+        for from in 0..self.vertex_count() {
+            for edge in self.neighbors(from) {
+                writeln!(text, "a {} {} {}", from + 1, edge.to + 1, edge.weight).unwrap();
+            }
+        }
+
+        text
+    }
+
+    /// Returns the graph as a GraphML document. Vertex `i` becomes the node `ni`
+    /// and edge weights are stored in the `weight` attribute, typed as `long`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::Graph;
+    ///
+    /// let mut graph = Graph::init_undirected(2);
+    /// graph.add_edge(0, 1, 3);
+    ///
+    /// let document = graph.to_graphml();
+    ///
+    /// assert!(document.contains(r#"<graph id="G" edgedefault="undirected">"#));
+    /// assert!(document.contains(r#"<edge source="n0" target="n1"><data key="weight">3</data></edge>"#));
+    /// assert_eq!(Graph::from_graphml(&document, 1), Some(graph));
+    /// ```
+    pub fn to_graphml(&self) -> String {
+        let mut text = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        text.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        text.push_str(
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"long\"/>\n",
+        );
+        let direction = if self.is_directed() {
+            "directed"
+        } else {
+            "undirected"
+        };
+        writeln!(text, "  <graph id=\"G\" edgedefault=\"{}\">", direction).unwrap();
+        for vertex in 0..self.vertex_count() {
+            writeln!(text, "    <node id=\"n{}\"/>", vertex).unwrap();
+        }
+        for (from, to, weight) in self.edges() {
+            writeln!(
+                text,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"weight\">{}</data></edge>",
+                from,
+                to,
+                escape_xml(&weight.to_string())
+            )
+            .unwrap();
+        }
+        text.push_str("  </graph>\n</graphml>\n");
+
+        text
+    }
+
+    /// Returns the graph in the DOT language of Graphviz, with edge weights in the `weight` attribute
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::Graph;
+    ///
+    /// let mut graph = Graph::init_directed(2);
+    /// graph.add_edge(1, 0, 4);
+    ///
+    /// assert_eq!(graph.to_dot(), "digraph {\n    0;\n    1;\n    1 -> 0 [weight=\"4\"];\n}\n");
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let (kind, connector) = if self.is_directed() {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        let mut text = format!("{} {{\n", kind);
+        for vertex in 0..self.vertex_count() {
+            writeln!(text, "    {};", vertex).unwrap();
+        }
+        for (from, to, weight) in self.edges() {
+            let weight = weight
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            writeln!(
+                text,
+                "    {} {} {} [weight=\"{}\"];",
+                from, connector, to, weight
+            )
+            .unwrap();
+        }
+        text.push_str("}\n");
+
+        text
+    }
+}
+
+impl<W: FromStr + Clone> Graph<W> {
+    /// Reads a graph from lines `from to weight` or `from to`, the format of most published edge lists.
+    /// Empty lines and lines starting with `#` or `%` are skipped. The graph has as many vertices
+    /// as the largest vertex index plus one. Returns `None` if a line can not be parsed
+    ///
+    /// # Arguments
+    /// * `text`: the edge list
+    /// * `directed`: whether the edges are directed
+    /// * `default_weight`: weight of edges given without one
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::Graph;
+    ///
+    /// let graph: Graph<u32> = Graph::from_edge_list("# road network\n0 1 30\n1 3\n", false, 1).unwrap();
+    ///
+    /// assert_eq!(graph.vertex_count(), 4);
+    /// assert_eq!(graph.weight(1, 0), Some(&30));
+    /// assert_eq!(graph.weight(3, 1), Some(&1));
+    /// assert!(Graph::<u32>::from_edge_list("0 x\n", false, 1).is_none());
+    /// ```
+    pub fn from_edge_list(text: &str, directed: bool, default_weight: W) -> Option<Graph<W>> {
+        let mut edges = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with('%') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let weight = match fields.len() {
+                2 => default_weight.clone(),
+                3 => fields[2].parse().ok()?,
+                _ => return None,
+            };
+            edges.push((fields[0].parse().ok()?, fields[1].parse().ok()?, weight));
+        }
+
+        let vertices = edges
+            .iter()
+            .map(|(from, to, _)| usize::max(*from, *to) + 1)
+            .max()
+            .unwrap_or(0);
+
+        Some(Graph::with_edges(directed, vertices, edges))
+    }
+
+    /// Reads a directed graph in the DIMACS shortest path format: comment lines starting with `c`,
+    /// one problem line `p sp vertices arcs` and arc lines `a from to weight` with vertices numbered from one.
+    /// Returns `None` if a line can not be parsed, a vertex is out of range
+    /// or the number of arcs differs from the problem line
+    ///
+    /// # Arguments
+    /// * `text`: the DIMACS file
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::Graph;
+    ///
+    /// let text = "c a triangle\np sp 3 3\na 1 2 4\na 2 3 4\na 3 1 4\n";
+    /// let graph: Graph<u64> = Graph::from_dimacs(text).unwrap();
+    ///
+    /// assert!(graph.is_directed());
+    /// assert_eq!(graph.weight(2, 0), Some(&4));
+    /// ```
+    pub fn from_dimacs(text: &str) -> Option<Graph<W>> {
+        let mut problem: Option<(usize, usize)> = None;
+        let mut edges = Vec::new();
+        for line in text.lines().map(str::trim) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.first() {
+                None | Some(&"c") => {}
+                Some(&"p") if fields.len() == 4 && fields[1] == "sp" && problem.is_none() => {
+                    problem = Some((fields[2].parse().ok()?, fields[3].parse().ok()?));
+                }
+                Some(&"a") if fields.len() == 4 => {
+                    let (vertices, _) = problem?;
+                    let from: usize = fields[1].parse().ok()?;
+                    let to: usize = fields[2].parse().ok()?;
+                    if from == 0 || to == 0 || from > vertices || to > vertices {
+                        return None;
+                    }
+                    edges.push((from - 1, to - 1, fields[3].parse().ok()?));
+                }
+                _ => return None,
+            }
+        }
+
+        let (vertices, arcs) = problem?;
+        if edges.len() != arcs {
+            return None;
+        }
+
+        Some(Graph::with_edges(true, vertices, edges))
+    }
+
+    /// Reads the first graph of a GraphML document. Nodes are numbered in the order they appear and edge
+    /// weights are read from the edge attribute named `weight`. Only the structure of GraphML is understood:
+    /// nested graphs, hyperedges and ports are not supported. Returns `None` if the document is malformed,
+    /// an edge refers to an unknown node or a weight can not be parsed
+    ///
+    /// # Arguments
+    /// * `document`: the GraphML document
+    /// * `default_weight`: weight of edges without a `weight` attribute
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::Graph;
+    ///
+    /// let document = r#"<graphml>
+    ///   <key id="d0" for="edge" attr.name="weight" attr.type="int"/>
+    ///   <graph edgedefault="directed">
+    ///     <node id="a"/><node id="b"/><node id="c"/>
+    ///     <edge source="a" target="b"><data key="d0">12</data></edge>
+    ///     <edge source="b" target="c"/>
+    ///   </graph>
+    /// </graphml>"#;
+    /// let graph: Graph<i32> = Graph::from_graphml(document, 1).unwrap();
+    ///
+    /// assert_eq!(graph.vertex_count(), 3);
+    /// assert_eq!(graph.weight(0, 1), Some(&12));
+    /// assert_eq!(graph.weight(1, 2), Some(&1));
+    /// ```
+    pub fn from_graphml(document: &str, default_weight: W) -> Option<Graph<W>> {
+        let tags = xml_tags(document)?;
+
+        let mut weight_key = None;
+        let mut directed = None;
+        let mut nodes = HashMap::new();
+        let mut edges = Vec::new();
+        // the edge whose data is being read
+        let mut open_edge: Option<(usize, usize, W)> = None;
+        for tag in &tags {
+            match (tag.name, tag.closing) {
+                ("key", false) => {
+                    let for_edges = tag
+                        .attribute("for")
+                        .is_none_or(|kind| kind == "edge" || kind == "all");
+                    if for_edges && tag.attribute("attr.name") == Some("weight") {
+                        weight_key = Some(tag.attribute("id")?.to_string());
+                    }
+                }
+                ("graph", false) => {
+                    if directed.is_some() {
+                        return None;
+                    }
+                    directed = Some(tag.attribute("edgedefault") != Some("undirected"));
+                }
+                ("graph", true) => break,
+                ("node", false) => {
+                    let index = nodes.len();
+                    if nodes.insert(tag.attribute("id")?, index).is_some() {
+                        return None;
+                    }
+                }
+                ("edge", false) => {
+                    let from = *nodes.get(tag.attribute("source")?)?;
+                    let to = *nodes.get(tag.attribute("target")?)?;
+                    if tag.empty {
+                        edges.push((from, to, default_weight.clone()));
+                    } else {
+                        open_edge = Some((from, to, default_weight.clone()));
+                    }
+                }
+                ("edge", true) => edges.push(open_edge.take()?),
+                ("data", false) => {
+                    if let Some((_, _, weight)) = open_edge.as_mut() {
+                        if weight_key
+                            .as_deref()
+                            .is_some_and(|key| tag.attribute("key") == Some(key))
+                        {
+                            *weight = unescape_xml(tag.text).trim().parse().ok()?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(Graph::with_edges(directed?, nodes.len(), edges))
+    }
+
+    fn with_edges(directed: bool, vertices: usize, edges: Vec<(usize, usize, W)>) -> Graph<W> {
+        let mut graph = if directed {
+            Graph::init_directed(vertices)
+        } else {
+            Graph::init_undirected(vertices)
+        };
+        for (from, to, weight) in edges {
+            graph.add_edge(from, to, weight);
+        }
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn random_graph(directed: bool, seed: u64) -> Graph<i64> {
+        let mut rng = SplitMix64::init(seed);
+        let mut graph = if directed {
+            Graph::init_directed(30)
+        } else {
+            Graph::init_undirected(30)
+        };
+        for _ in 0..80 {
+            let from = rng.next_below(30) as usize;
+            let to = rng.next_below(30) as usize;
+            graph.add_edge(from, to, rng.next_below(200) as i64 - 100);
+        }
+
+        graph
+    }
+
+    fn sorted_edges(graph: &Graph<i64>) -> Vec<(usize, usize, i64)> {
+        let mut edges: Vec<(usize, usize, i64)> = graph
+            .edges()
+            .map(|(from, to, weight)| (from, to, *weight))
+            .collect();
+        edges.sort();
+
+        edges
+    }
+
+    #[test]
+    fn graph_format_round_trips() {
+        for directed in [true, false] {
+            let graph = random_graph(directed, 7 + directed as u64);
+
+            let from_edge_list = Graph::from_edge_list(&graph.to_edge_list(), directed, 0).unwrap();
+            assert_eq!(sorted_edges(&from_edge_list), sorted_edges(&graph));
+            assert!(from_edge_list.vertex_count() <= graph.vertex_count());
+
+            let from_graphml = Graph::from_graphml(&graph.to_graphml(), 0).unwrap();
+            assert_eq!(from_graphml.is_directed(), directed);
+            assert_eq!(from_graphml.vertex_count(), graph.vertex_count());
+            assert_eq!(sorted_edges(&from_graphml), sorted_edges(&graph));
+
+            let from_dimacs = Graph::from_dimacs(&graph.to_dimacs()).unwrap();
+            assert!(from_dimacs.is_directed());
+            assert_eq!(from_dimacs.vertex_count(), graph.vertex_count());
+            for from in 0..graph.vertex_count() {
+                assert_eq!(from_dimacs.neighbors(from), graph.neighbors(from));
+            }
+        }
+    }
+
+    #[test]
+    fn graph_format_invalid_input() {
+        assert!(Graph::<u8>::from_edge_list("0 1 2 3\n", true, 0).is_none());
+        assert!(Graph::<u8>::from_edge_list("0 1 256\n", true, 0).is_none());
+        assert_eq!(
+            Graph::<u8>::from_edge_list("", true, 0)
+                .unwrap()
+                .vertex_count(),
+            0
+        );
+
+        assert!(Graph::<u8>::from_dimacs("a 1 2 3\np sp 2 1\n").is_none());
+        assert!(Graph::<u8>::from_dimacs("p sp 2 1\na 1 3 3\n").is_none());
+        assert!(Graph::<u8>::from_dimacs("p sp 2 2\na 1 2 3\n").is_none());
+        assert!(Graph::<u8>::from_dimacs("p sp 2 1\nx\na 1 2 3\n").is_none());
+
+        assert!(Graph::<u8>::from_graphml("<graphml><node id=\"a\"/></graphml>", 0).is_none());
+        assert!(Graph::<u8>::from_graphml(
+            "<graph edgedefault=\"directed\"><edge source=\"a\" target=\"b\"/></graph>",
+            0
+        )
+        .is_none());
+        assert!(
+            Graph::<u8>::from_graphml("<graph><node id='a' /><node id='a'/></graph>", 0).is_none()
+        );
+    }
+
+    #[test]
+    fn graph_format_escaping() {
+        let mut graph = Graph::init_undirected(2);
+        graph.add_edge(0, 1, String::from("<\"a\" & 'b'>"));
+
+        let document = graph.to_graphml();
+        assert!(!document.contains("<\"a\""));
+        assert_eq!(
+            Graph::from_graphml(&document, String::new()),
+            Some(graph.clone())
+        );
+        assert!(graph
+            .to_dot()
+            .contains(r#"0 -- 1 [weight="<\"a\" & 'b'>"];"#));
+    }
+}
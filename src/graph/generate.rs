@@ -0,0 +1,384 @@
+//! Random and regular graphs for testing and benchmarking graph algorithms. Generated edges have weight one,
+//! `random_weights` assigns random weights to any graph. Randomness comes from the given generator,
+//! so a seeded generator always produces the same graph
+
+use super::Graph;
+use crate::util::Rng;
+
+fn empty_graph(vertices: usize, directed: bool) -> Graph<u64> {
+    if directed {
+        Graph::init_directed(vertices)
+    } else {
+        Graph::init_undirected(vertices)
+    }
+}
+
+/// Returns a random graph where every pair of distinct vertices is joined by an edge with probability
+/// `probability`, independently of the other pairs. A directed graph decides on both directions of a pair separately.
+/// Runs in time proportional to the number of vertices and edges by skipping over the missing edges
+///
+/// # Arguments
+/// * `vertices`: number of vertices
+/// * `probability`: probability of every edge
+/// * `directed`: whether the graph is directed
+/// * `rng`: source of randomness
+///
+/// # Panics
+/// * panics if `probability` is not between 0 and 1
+///
+/// # Examples
+/// ```
+/// use rudac::graph::generate::erdos_renyi;
+/// use rudac::util::SplitMix64;
+///
+/// let graph = erdos_renyi(100, 0.1, false, &mut SplitMix64::init(1));
+///
+/// assert_eq!(graph.vertex_count(), 100);
+/// // about 0.1 * 100 * 99 / 2 edges
+/// assert!(400 < graph.edge_count() && graph.edge_count() < 600);
+/// ```
+pub fn erdos_renyi<R: Rng>(
+    vertices: usize,
+    probability: f64,
+    directed: bool,
+    rng: &mut R,
+) -> Graph<u64> {
+    if !(0.0..=1.0).contains(&probability) {
+        panic!("Probability must be between 0 and 1");
+    }
+
+    let mut graph = empty_graph(vertices, directed);
+    if vertices < 2 || probability == 0.0 {
+        return graph;
+    }
+
+    // pairs are visited row by row, row `from` has the `from` smaller vertices of an undirected graph
+    // and all other vertices of a directed one
+    let row_length = |from: usize| if directed { vertices - 1 } else { from };
+    let log_miss = (1.0 - probability).ln();
+    let (mut from, mut column) = (0, 0);
+    loop {
+        if probability < 1.0 {
+            // number of pairs skipped before the next edge, geometrically distributed
+            let skip = ((1.0 - rng.next_f64()).ln() / log_miss).floor();
+            column += if skip < usize::MAX as f64 {
+                skip as usize
+            } else {
+                usize::MAX / 2
+            };
+        }
+        while from < vertices && column >= row_length(from) {
+            column -= row_length(from);
+            from += 1;
+        }
+        if from == vertices {
+            break;
+        }
+
+        let to = if directed && column >= from {
+            column + 1
+        } else {
+            column
+        };
+        graph.add_edge(from, to, 1);
+        column += 1;
+    }
+
+    graph
+}
+
+/// Returns a random undirected graph grown by preferential attachment: starting from a complete graph on
+/// `edges_per_vertex + 1` vertices, every further vertex is joined to `edges_per_vertex` distinct earlier vertices
+/// chosen with probability proportional to their degree. The degrees follow a power law, like in many real networks
+///
+/// # Arguments
+/// * `vertices`: number of vertices
+/// * `edges_per_vertex`: number of edges added with every vertex
+/// * `rng`: source of randomness
+///
+/// # Panics
+/// * panics if `edges_per_vertex` is zero
+/// * panics if `vertices` is not larger than `edges_per_vertex`
+///
+/// # Examples
+/// ```
+/// use rudac::graph::generate::barabasi_albert;
+/// use rudac::util::SplitMix64;
+///
+/// let graph = barabasi_albert(1000, 2, &mut SplitMix64::init(5));
+///
+/// assert_eq!(graph.edge_count(), 3 + 997 * 2);
+/// // early vertices collect far more edges than the average of 4
+/// assert!(graph.degree(0) > 20);
+/// ```
+pub fn barabasi_albert<R: Rng>(
+    vertices: usize,
+    edges_per_vertex: usize,
+    rng: &mut R,
+) -> Graph<u64> {
+    if edges_per_vertex == 0 {
+        panic!("Every vertex must add at least one edge");
+    }
+    if vertices <= edges_per_vertex {
+        panic!("Number of vertices must be larger than the number of edges per vertex");
+    }
+
+    let mut graph = Graph::init_undirected(vertices);
+    // every vertex appears once for each of its edges, so a uniform pick is proportional to the degree
+    let mut endpoints = Vec::with_capacity(2 * vertices * edges_per_vertex);
+    for from in 0..=edges_per_vertex {
+        for to in 0..from {
+            graph.add_edge(from, to, 1);
+            endpoints.push(from);
+            endpoints.push(to);
+        }
+    }
+
+    let mut targets = Vec::with_capacity(edges_per_vertex);
+    for from in edges_per_vertex + 1..vertices {
+        targets.clear();
+        while targets.len() < edges_per_vertex {
+            let target = endpoints[rng.next_below(endpoints.len() as u64) as usize];
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        for to in &targets {
+            graph.add_edge(from, *to, 1);
+            endpoints.push(from);
+            endpoints.push(*to);
+        }
+    }
+
+    graph
+}
+
+/// Returns a tree chosen uniformly among all labelled trees on `vertices` vertices, decoded from a random
+/// Prüfer sequence in linear time
+///
+/// # Arguments
+/// * `vertices`: number of vertices
+/// * `rng`: source of randomness
+///
+/// # Examples
+/// ```
+/// use rudac::graph::generate::random_tree;
+/// use rudac::util::SplitMix64;
+///
+/// let tree = random_tree(50, &mut SplitMix64::init(3));
+///
+/// assert_eq!(tree.edge_count(), 49);
+/// assert!((0..50).all(|vertex| tree.degree(vertex) > 0));
+/// ```
+pub fn random_tree<R: Rng>(vertices: usize, rng: &mut R) -> Graph<u64> {
+    let mut tree = Graph::init_undirected(vertices);
+    if vertices < 2 {
+        return tree;
+    }
+
+    let sequence: Vec<usize> = (0..vertices - 2)
+        .map(|_| rng.next_below(vertices as u64) as usize)
+        .collect();
+    let mut degree = vec![1; vertices];
+    for vertex in &sequence {
+        degree[*vertex] += 1;
+    }
+
+    // the smallest leaf is found by a pointer that only moves forward, unless the vertex that just became a leaf
+    // is smaller than it and is used right away
+    let mut pointer = degree.iter().position(|degree| *degree == 1).unwrap();
+    let mut leaf = pointer;
+    for vertex in sequence {
+        tree.add_edge(leaf, vertex, 1);
+        degree[vertex] -= 1;
+        if degree[vertex] == 1 && vertex < pointer {
+            leaf = vertex;
+        } else {
+            pointer += 1;
+            while degree[pointer] != 1 {
+                pointer += 1;
+            }
+            leaf = pointer;
+        }
+    }
+    tree.add_edge(leaf, vertices - 1, 1);
+
+    tree
+}
+
+/// Returns an undirected grid graph of `rows` times `columns` vertices, the vertex in row `r` and column `c`
+/// is `r * columns + c` and is joined to its horizontal and vertical neighbors
+///
+/// # Arguments
+/// * `rows`: number of rows
+/// * `columns`: number of columns
+///
+/// # Examples
+/// ```
+/// use rudac::graph::generate::grid;
+///
+/// let graph = grid(3, 4);
+///
+/// assert_eq!(graph.vertex_count(), 12);
+/// assert_eq!(graph.edge_count(), 3 * 3 + 2 * 4);
+/// assert!(graph.has_edge(5, 9));
+/// ```
+pub fn grid(rows: usize, columns: usize) -> Graph<u64> {
+    let mut graph = Graph::init_undirected(rows * columns);
+    for row in 0..rows {
+        for column in 0..columns {
+            let vertex = row * columns + column;
+            if column + 1 < columns {
+                graph.add_edge(vertex, vertex + 1, 1);
+            }
+            if row + 1 < rows {
+                graph.add_edge(vertex, vertex + columns, 1);
+            }
+        }
+    }
+
+    graph
+}
+
+/// Returns a copy of `graph` whose edges have weights drawn uniformly from `low` to `high`, inclusive.
+/// Both directions of an undirected edge get the same weight
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `low`: smallest weight
+/// * `high`: largest weight
+/// * `rng`: source of randomness
+///
+/// # Panics
+/// * panics if `low` is larger than `high`
+///
+/// # Examples
+/// ```
+/// use rudac::graph::generate::{grid, random_weights};
+/// use rudac::util::SplitMix64;
+///
+/// let graph = random_weights(&grid(10, 10), 1, 100, &mut SplitMix64::init(9));
+///
+/// assert!(graph.edges().all(|(_, _, weight)| (1..=100).contains(weight)));
+/// assert_eq!(graph.weight(0, 1), graph.weight(1, 0));
+/// ```
+pub fn random_weights<W, R: Rng>(graph: &Graph<W>, low: u64, high: u64, rng: &mut R) -> Graph<u64> {
+    if low > high {
+        panic!("Lowest weight must not be larger than the highest weight");
+    }
+
+    let mut weighted = empty_graph(graph.vertex_count(), graph.is_directed());
+    for (from, to, _) in graph.edges() {
+        let weight = match (high - low).checked_add(1) {
+            Some(range) => low + rng.next_below(range),
+            None => rng.next_u64(),
+        };
+        weighted.add_edge(from, to, weight);
+    }
+
+    weighted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+    use std::collections::HashSet;
+
+    fn is_simple(graph: &Graph<u64>) -> bool {
+        let mut seen = HashSet::new();
+        graph
+            .edges()
+            .all(|(from, to, _)| from != to && seen.insert((from, to)))
+    }
+
+    fn is_connected(graph: &Graph<u64>) -> bool {
+        let mut visited = vec![false; graph.vertex_count()];
+        let mut stack = vec![0];
+        visited[0] = true;
+        while let Some(vertex) = stack.pop() {
+            for edge in graph.neighbors(vertex) {
+                if !visited[edge.to] {
+                    visited[edge.to] = true;
+                    stack.push(edge.to);
+                }
+            }
+        }
+
+        visited.into_iter().all(|visited| visited)
+    }
+
+    #[test]
+    fn graph_generate_erdos_renyi() {
+        let mut rng = SplitMix64::init(2);
+        for directed in [true, false] {
+            let complete = erdos_renyi(20, 1.0, directed, &mut rng);
+            let pairs = if directed { 20 * 19 } else { 20 * 19 / 2 };
+            assert_eq!(complete.edge_count(), pairs);
+            assert!(is_simple(&complete));
+            assert_eq!(erdos_renyi(20, 0.0, directed, &mut rng).edge_count(), 0);
+            assert_eq!(erdos_renyi(1, 1.0, directed, &mut rng).edge_count(), 0);
+
+            let mut total = 0;
+            for _ in 0..20 {
+                let graph = erdos_renyi(200, 0.05, directed, &mut rng);
+                assert!(is_simple(&graph));
+                total += graph.edge_count();
+            }
+            let pairs = if directed { 200 * 199 } else { 200 * 199 / 2 };
+            let expected = 20.0 * 0.05 * pairs as f64;
+            assert!((total as f64 - expected).abs() < 0.05 * expected);
+        }
+    }
+
+    #[test]
+    fn graph_generate_barabasi_albert() {
+        let graph = barabasi_albert(300, 3, &mut SplitMix64::init(4));
+
+        assert_eq!(graph.edge_count(), 6 + 296 * 3);
+        assert!(is_simple(&graph));
+        assert!(is_connected(&graph));
+        assert!((4..300).all(|vertex| graph.degree(vertex) >= 3));
+    }
+
+    #[test]
+    fn graph_generate_random_tree_is_uniform() {
+        let mut rng = SplitMix64::init(6);
+        for vertices in 0..30 {
+            let tree = random_tree(vertices, &mut rng);
+            assert_eq!(tree.edge_count(), vertices.saturating_sub(1));
+            assert!(vertices == 0 || is_connected(&tree));
+        }
+
+        // Cayley's formula: there are 4^2 = 16 labelled trees on 4 vertices
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..16000 {
+            let tree = random_tree(4, &mut rng);
+            let mut edges: Vec<(usize, usize)> =
+                tree.edges().map(|(from, to, _)| (from, to)).collect();
+            edges.sort();
+            *counts.entry(edges).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 16);
+        assert!(counts.values().all(|count| (800..1200).contains(count)));
+    }
+
+    #[test]
+    fn graph_generate_random_weights() {
+        let mut rng = SplitMix64::init(8);
+        let graph = erdos_renyi(50, 0.2, true, &mut rng);
+        let weighted = random_weights(&graph, 5, 5, &mut rng);
+
+        assert_eq!(weighted.edge_count(), graph.edge_count());
+        assert!(weighted
+            .edges()
+            .all(|(from, to, weight)| *weight == 5 && graph.has_edge(from, to)));
+        random_weights(&graph, 0, u64::MAX, &mut rng);
+    }
+
+    #[test]
+    #[should_panic(expected = "Probability must be between 0 and 1")]
+    fn graph_generate_panic_probability() {
+        erdos_renyi(10, 1.5, false, &mut SplitMix64::init(1));
+    }
+}
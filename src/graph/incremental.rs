@@ -0,0 +1,601 @@
+//! Directed graphs that grow one edge at a time while their strongly connected components, a topological order of
+//! the components and cycle detection are kept up to date
+
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+/// Error returned by `IncrementalScc::add_edge` when the edge would close a cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleDetected {
+    /// vertices of the cycle, starting with the start of the rejected edge and followed by its end
+    pub cycle: Vec<usize>,
+}
+
+impl fmt::Display for CycleDetected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let vertices: Vec<String> = self
+            .cycle
+            .iter()
+            .map(|vertex| format!("{}", vertex))
+            .collect();
+        write!(f, "Edge closes the cycle {}", vertices.join(" -> "))
+    }
+}
+
+impl std::error::Error for CycleDetected {}
+
+/// Directed graph that maintains its strongly connected components and a topological order of them under edge
+/// insertions, with the Pearce-Kelly algorithm. Inserting an edge that agrees with the order costs O(1), otherwise
+/// only the components between its end points in the order are searched and reordered.
+/// `add_edge` keeps the graph acyclic by rejecting edges that close a cycle, which suits online cycle detection
+/// like in dependency managers. `insert_edge` accepts every edge and merges the components on the new cycles
+///
+/// # Examples
+/// ```
+/// use rudac::graph::incremental::IncrementalScc;
+///
+/// // packages and their dependencies
+/// let mut graph = IncrementalScc::init(4);
+/// graph.add_edge(0, 1).unwrap();
+/// graph.add_edge(1, 2).unwrap();
+/// graph.add_edge(3, 0).unwrap();
+///
+/// assert!(graph.reaches(3, 2));
+/// assert_eq!(graph.add_edge(2, 0).unwrap_err().cycle, vec![2, 0, 1]);
+///
+/// // cycles are accepted by insert_edge and merge components
+/// assert!(graph.insert_edge(2, 3));
+/// assert!(graph.same_component(0, 3));
+/// assert_eq!(graph.component_count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalScc {
+    outgoing: Vec<Vec<usize>>,
+    incoming: Vec<Vec<usize>>,
+    edge_count: usize,
+
+    // representative of the component of every vertex, a member of the component
+    parent: Vec<usize>,
+
+    // vertices of every component, indexed by its representative
+    members: Vec<Vec<usize>>,
+
+    // position of every component in the topological order, indexed by its representative. Positions are unique
+    // but not contiguous
+    position: Vec<usize>,
+    next_position: usize,
+}
+
+impl IncrementalScc {
+    /// Initializes a graph with `vertices` vertices and no edges
+    ///
+    /// # Arguments
+    /// * `vertices`: number of vertices
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::incremental::IncrementalScc;
+    ///
+    /// let graph = IncrementalScc::init(3);
+    ///
+    /// assert_eq!(graph.vertex_count(), 3);
+    /// assert_eq!(graph.component_count(), 3);
+    /// ```
+    pub fn init(vertices: usize) -> IncrementalScc {
+        IncrementalScc {
+            outgoing: vec![Vec::new(); vertices],
+            incoming: vec![Vec::new(); vertices],
+            edge_count: 0,
+            parent: (0..vertices).collect(),
+            members: (0..vertices).map(|vertex| vec![vertex]).collect(),
+            position: (0..vertices).collect(),
+            next_position: vertices,
+        }
+    }
+
+    /// Returns number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Returns number of edges
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Returns number of strongly connected components
+    pub fn component_count(&self) -> usize {
+        self.members
+            .iter()
+            .filter(|members| !members.is_empty())
+            .count()
+    }
+
+    /// Adds a vertex without edges at the end of the topological order and returns its index
+    pub fn add_vertex(&mut self) -> usize {
+        let vertex = self.vertex_count();
+        self.outgoing.push(Vec::new());
+        self.incoming.push(Vec::new());
+        self.parent.push(vertex);
+        self.members.push(vec![vertex]);
+        self.position.push(self.next_position);
+        self.next_position += 1;
+
+        vertex
+    }
+
+    fn check_vertex(&self, vertex: usize) {
+        if vertex >= self.vertex_count() {
+            panic!("Vertex does not exist");
+        }
+    }
+
+    // representative of the component of `vertex`
+    fn find(&self, vertex: usize) -> usize {
+        self.parent[vertex]
+    }
+
+    /// Returns the representative of the strongly connected component of `vertex`, a vertex of the component.
+    /// Representatives change when components merge
+    ///
+    /// # Arguments
+    /// * `vertex`: the vertex
+    ///
+    /// # Panics
+    /// * panics if `vertex` does not exist
+    pub fn component(&self, vertex: usize) -> usize {
+        self.check_vertex(vertex);
+        self.find(vertex)
+    }
+
+    /// Returns true if `first` and `second` are in the same strongly connected component
+    ///
+    /// # Arguments
+    /// * `first`: the first vertex
+    /// * `second`: the second vertex
+    ///
+    /// # Panics
+    /// * panics if `first` or `second` does not exist
+    pub fn same_component(&self, first: usize, second: usize) -> bool {
+        self.component(first) == self.component(second)
+    }
+
+    /// Returns true if there is a path from `from` to `to`. Every vertex reaches itself. Only the components
+    /// between the two in the topological order are searched
+    ///
+    /// # Arguments
+    /// * `from`: start of the path
+    /// * `to`: end of the path
+    ///
+    /// # Panics
+    /// * panics if `from` or `to` does not exist
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::incremental::IncrementalScc;
+    ///
+    /// let mut graph = IncrementalScc::init(3);
+    /// graph.add_edge(2, 1).unwrap();
+    /// graph.add_edge(1, 0).unwrap();
+    ///
+    /// assert!(graph.reaches(2, 0));
+    /// assert!(!graph.reaches(0, 2));
+    /// ```
+    pub fn reaches(&self, from: usize, to: usize) -> bool {
+        let (from, to) = (self.component(from), self.component(to));
+        self.position[from] <= self.position[to]
+            && self.forward(from, self.position[to]).contains(&to)
+    }
+
+    /// Returns the strongly connected components as lists of vertices, in topological order: every edge between
+    /// two components leads to a later one
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::incremental::IncrementalScc;
+    ///
+    /// let mut graph = IncrementalScc::init(3);
+    /// graph.insert_edge(2, 0);
+    /// graph.insert_edge(0, 2);
+    /// graph.insert_edge(1, 2);
+    ///
+    /// assert_eq!(graph.components(), vec![vec![1], vec![2, 0]]);
+    /// ```
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut representatives: Vec<usize> = (0..self.vertex_count())
+            .filter(|vertex| self.parent[*vertex] == *vertex)
+            .collect();
+        representatives.sort_unstable_by_key(|representative| self.position[*representative]);
+
+        representatives
+            .into_iter()
+            .map(|representative| self.members[representative].clone())
+            .collect()
+    }
+
+    // components reachable from component `start` through components at positions up to `limit`
+    fn forward(&self, start: usize, limit: usize) -> HashSet<usize> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start];
+        visited.insert(start);
+        while let Some(component) = stack.pop() {
+            for vertex in &self.members[component] {
+                for next in &self.outgoing[*vertex] {
+                    let next = self.find(*next);
+                    if self.position[next] <= limit && visited.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+
+        visited
+    }
+
+    // components reaching component `start` through components at positions from `limit` on
+    fn backward(&self, start: usize, limit: usize) -> HashSet<usize> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start];
+        visited.insert(start);
+        while let Some(component) = stack.pop() {
+            for vertex in &self.members[component] {
+                for previous in &self.incoming[*vertex] {
+                    let previous = self.find(*previous);
+                    if self.position[previous] >= limit && visited.insert(previous) {
+                        stack.push(previous);
+                    }
+                }
+            }
+        }
+
+        visited
+    }
+
+    fn push_edge(&mut self, from: usize, to: usize) {
+        self.outgoing[from].push(to);
+        self.incoming[to].push(from);
+        self.edge_count += 1;
+    }
+
+    // shortest path of vertices from `from` to `to` within the components `allowed`
+    fn path(&self, from: usize, to: usize, allowed: &HashSet<usize>) -> Vec<usize> {
+        let mut parents = vec![(from, from)];
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(from);
+        queue.push_back((from, 0));
+        while let Some((vertex, index)) = queue.pop_front() {
+            if vertex == to {
+                let mut path = Vec::new();
+                let mut index = index;
+                loop {
+                    let (vertex, parent) = parents[index];
+                    path.push(vertex);
+                    if index == 0 {
+                        break;
+                    }
+                    index = parent;
+                }
+                path.reverse();
+                return path;
+            }
+            for next in &self.outgoing[vertex] {
+                if allowed.contains(&self.find(*next)) && seen.insert(*next) {
+                    parents.push((*next, index));
+                    queue.push_back((*next, parents.len() - 1));
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Adds an edge from `from` to `to` unless it would close a cycle, in which case the graph is left unchanged
+    /// and the cycle is returned. A self loop is a cycle. Graphs built by `add_edge` only stay acyclic
+    ///
+    /// # Arguments
+    /// * `from`: start of the edge
+    /// * `to`: end of the edge
+    ///
+    /// # Panics
+    /// * panics if `from` or `to` does not exist
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::incremental::IncrementalScc;
+    ///
+    /// let mut graph = IncrementalScc::init(3);
+    /// graph.add_edge(0, 1).unwrap();
+    /// graph.add_edge(1, 2).unwrap();
+    ///
+    /// let error = graph.add_edge(2, 0).unwrap_err();
+    ///
+    /// assert_eq!(error.cycle, vec![2, 0, 1]);
+    /// assert_eq!(format!("{}", error), "Edge closes the cycle 2 -> 0 -> 1");
+    /// assert_eq!(graph.edge_count(), 2);
+    /// ```
+    pub fn add_edge(&mut self, from: usize, to: usize) -> Result<(), CycleDetected> {
+        let (first, second) = (self.component(from), self.component(to));
+        if self.position[first] < self.position[second] {
+            self.push_edge(from, to);
+            return Ok(());
+        }
+
+        let forward = self.forward(second, self.position[first]);
+        if forward.contains(&first) {
+            let mut cycle = vec![from];
+            if from != to {
+                cycle.extend(self.path(to, from, &forward));
+                cycle.pop();
+            }
+            return Err(CycleDetected { cycle });
+        }
+
+        let backward = self.backward(first, self.position[second]);
+        self.reorder(backward, forward);
+        self.push_edge(from, to);
+
+        Ok(())
+    }
+
+    /// Adds an edge from `from` to `to`, merging the strongly connected components on the cycles it closes.
+    /// Returns true if components were merged
+    ///
+    /// # Arguments
+    /// * `from`: start of the edge
+    /// * `to`: end of the edge
+    ///
+    /// # Panics
+    /// * panics if `from` or `to` does not exist
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::graph::incremental::IncrementalScc;
+    ///
+    /// let mut graph = IncrementalScc::init(4);
+    /// assert!(!graph.insert_edge(0, 1));
+    /// assert!(!graph.insert_edge(1, 2));
+    /// assert!(graph.insert_edge(2, 0));
+    ///
+    /// assert!(graph.same_component(0, 2));
+    /// assert!(!graph.same_component(0, 3));
+    /// ```
+    pub fn insert_edge(&mut self, from: usize, to: usize) -> bool {
+        let (first, second) = (self.component(from), self.component(to));
+        if first == second || self.position[first] < self.position[second] {
+            self.push_edge(from, to);
+            return false;
+        }
+
+        let forward = self.forward(second, self.position[first]);
+        let backward = self.backward(first, self.position[second]);
+        let merged = forward.contains(&first);
+        self.reorder(backward, forward);
+        self.push_edge(from, to);
+
+        merged
+    }
+
+    // moves the components reaching the new edge before the ones it reaches, reusing their positions. Components
+    // in both sets lie on a new cycle and are merged. The components only reaching the edge take the lowest of the
+    // positions and the ones only reached take the highest, with the merged component between them, so none of
+    // them passes a component outside both sets. The positions of the other merged components are left unused
+    fn reorder(&mut self, backward: HashSet<usize>, forward: HashSet<usize>) {
+        let mut positions: Vec<usize> = backward
+            .union(&forward)
+            .map(|component| self.position[*component])
+            .collect();
+        positions.sort_unstable();
+
+        let by_position = |set: Vec<usize>, position: &[usize]| {
+            let mut set = set;
+            set.sort_unstable_by_key(|component| position[*component]);
+            set
+        };
+        let before = by_position(
+            backward.difference(&forward).copied().collect(),
+            &self.position,
+        );
+        let after = by_position(
+            forward.difference(&backward).copied().collect(),
+            &self.position,
+        );
+        let mut cycle: Vec<usize> = backward.intersection(&forward).copied().collect();
+        cycle.sort_unstable();
+
+        let first_after = positions.len() - after.len();
+        if let Some(root) = self.merge(&cycle) {
+            self.position[root] = positions[before.len()];
+        }
+        for (component, position) in before.into_iter().zip(&positions) {
+            self.position[component] = *position;
+        }
+        for (component, position) in after.into_iter().zip(&positions[first_after..]) {
+            self.position[component] = *position;
+        }
+    }
+
+    // merges the components into the largest one and returns its representative
+    fn merge(&mut self, components: &[usize]) -> Option<usize> {
+        let root = *components
+            .iter()
+            .max_by_key(|component| self.members[**component].len())?;
+        for component in components {
+            if *component != root {
+                let members = std::mem::take(&mut self.members[*component]);
+                for vertex in &members {
+                    self.parent[*vertex] = root;
+                }
+                self.members[root].extend(members);
+            }
+        }
+
+        Some(root)
+    }
+}
+
+impl MemoryFootprint for IncrementalScc {
+    fn footprint(&self) -> Footprint {
+        let lists = self
+            .outgoing
+            .iter()
+            .chain(self.incoming.iter())
+            .chain(self.members.iter());
+        lists.fold(
+            Footprint::of::<usize>(self.edge_count)
+                .with_vec(&self.outgoing)
+                .with_vec(&self.incoming)
+                .with_vec(&self.parent)
+                .with_vec(&self.members)
+                .with_vec(&self.position),
+            |footprint, list| footprint.with_vec(list),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    // transitive closure of the edges by a search from every vertex
+    fn closure(vertices: usize, edges: &[(usize, usize)]) -> Vec<Vec<bool>> {
+        (0..vertices)
+            .map(|source| {
+                let mut reached = vec![false; vertices];
+                let mut stack = vec![source];
+                reached[source] = true;
+                while let Some(vertex) = stack.pop() {
+                    for (from, to) in edges {
+                        if *from == vertex && !reached[*to] {
+                            reached[*to] = true;
+                            stack.push(*to);
+                        }
+                    }
+                }
+                reached
+            })
+            .collect()
+    }
+
+    // checks components, reachability and the order against the closure
+    fn check(graph: &IncrementalScc, edges: &[(usize, usize)]) {
+        let vertices = graph.vertex_count();
+        let reached = closure(vertices, edges);
+        for (first, row) in reached.iter().enumerate() {
+            for (second, reaches) in row.iter().enumerate() {
+                assert_eq!(graph.reaches(first, second), *reaches);
+                assert_eq!(
+                    graph.same_component(first, second),
+                    *reaches && reached[second][first]
+                );
+            }
+        }
+
+        let mut index = vec![0; vertices];
+        for (position, component) in graph.components().iter().enumerate() {
+            for vertex in component {
+                index[*vertex] = position;
+            }
+        }
+        assert!(edges.iter().all(|(from, to)| index[*from] <= index[*to]));
+        assert_eq!(graph.component_count(), graph.components().len());
+        assert_eq!(graph.edge_count(), edges.len());
+    }
+
+    #[test]
+    fn graph_incremental_add_edge_rejects_cycles() {
+        let mut rng = SplitMix64::init(40);
+        for _ in 0..20 {
+            let mut graph = IncrementalScc::init(12);
+            let mut edges = Vec::new();
+            for _ in 0..40 {
+                let from = rng.next_below(12) as usize;
+                let to = rng.next_below(12) as usize;
+                let closes_cycle = closure(12, &edges)[to][from];
+                match graph.add_edge(from, to) {
+                    Ok(()) => {
+                        assert!(!closes_cycle);
+                        edges.push((from, to));
+                    }
+                    Err(CycleDetected { cycle }) => {
+                        assert!(closes_cycle);
+                        assert_eq!(cycle[0], from);
+                        assert_eq!(cycle.get(1).copied().unwrap_or(to), to);
+                        for index in 0..cycle.len() {
+                            let next = cycle[(index + 1) % cycle.len()];
+                            assert!(
+                                (cycle[index], next) == (from, to)
+                                    || edges.contains(&(cycle[index], next))
+                            );
+                        }
+                    }
+                }
+            }
+            check(&graph, &edges);
+            assert_eq!(graph.component_count(), 12);
+        }
+    }
+
+    #[test]
+    fn graph_incremental_insert_edge_merges_components() {
+        let mut rng = SplitMix64::init(41);
+        for _ in 0..20 {
+            let mut graph = IncrementalScc::init(10);
+            let mut edges = Vec::new();
+            for _ in 0..25 {
+                let from = rng.next_below(10) as usize;
+                let to = rng.next_below(10) as usize;
+                let before = graph.component_count();
+                let merged = graph.insert_edge(from, to);
+                edges.push((from, to));
+                assert_eq!(merged, graph.component_count() < before);
+                check(&graph, &edges);
+            }
+
+            let vertex = graph.add_vertex();
+            graph.insert_edge(0, vertex);
+            edges.push((0, vertex));
+            check(&graph, &edges);
+        }
+    }
+
+    #[test]
+    fn graph_incremental_insert_edge_keeps_order_around_merge() {
+        // the last edge merges 0, 1, 4 and 5 while 2 and 3 lie between them in the order
+        let mut graph = IncrementalScc::init(6);
+        let edges = [(1, 5), (0, 4), (3, 2), (4, 1), (4, 2), (5, 0)];
+        for (from, to) in edges {
+            graph.insert_edge(from, to);
+        }
+
+        assert!(graph.reaches(3, 2));
+        assert!(graph.reaches(0, 2));
+        check(&graph, &edges);
+    }
+
+    #[test]
+    fn graph_incremental_insert_edge_matches_closure() {
+        let mut rng = SplitMix64::init(42);
+        for vertices in [4, 8, 16, 30] {
+            for _ in 0..30 {
+                let mut graph = IncrementalScc::init(vertices);
+                let mut edges = Vec::new();
+                for _ in 0..vertices * 2 {
+                    let from = rng.next_below(vertices as u64) as usize;
+                    let to = rng.next_below(vertices as u64) as usize;
+                    graph.insert_edge(from, to);
+                    edges.push((from, to));
+                    check(&graph, &edges);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Vertex does not exist")]
+    fn graph_incremental_panic_missing_vertex() {
+        IncrementalScc::init(2).add_edge(0, 2).unwrap();
+    }
+}
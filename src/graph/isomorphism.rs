@@ -0,0 +1,496 @@
+//! Graph isomorphism and subgraph matching with the VF2 algorithm. Only the structure of the graphs is compared:
+//! weights and parallel edges are ignored, self loops have to match
+
+use super::Graph;
+
+// marks an unmapped vertex
+const NIL: usize = usize::MAX;
+
+// neighbor lists of a graph, sorted and without duplicates or self loops
+struct Adjacency {
+    directed: bool,
+    outgoing: Vec<Vec<usize>>,
+    incoming: Vec<Vec<usize>>,
+    loops: Vec<bool>,
+}
+
+impl Adjacency {
+    fn init<W>(graph: &Graph<W>) -> Adjacency {
+        let vertices = graph.vertex_count();
+        let mut loops = vec![false; vertices];
+        let mut lists = |incoming: bool| -> Vec<Vec<usize>> {
+            (0..vertices)
+                .map(|vertex| {
+                    let edges = if incoming {
+                        graph.incoming(vertex)
+                    } else {
+                        graph.neighbors(vertex)
+                    };
+                    let mut neighbors: Vec<usize> = edges.iter().map(|edge| edge.to).collect();
+                    neighbors.sort_unstable();
+                    neighbors.dedup();
+                    if let Ok(index) = neighbors.binary_search(&vertex) {
+                        neighbors.remove(index);
+                        loops[vertex] = true;
+                    }
+                    neighbors
+                })
+                .collect()
+        };
+        let outgoing = lists(false);
+        let incoming = lists(true);
+
+        Adjacency {
+            directed: graph.is_directed(),
+            outgoing,
+            incoming,
+            loops,
+        }
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.outgoing[from].binary_search(&to).is_ok()
+    }
+}
+
+// the mapping of one graph during the search: partner of every vertex and the depth at which
+// vertices entered the sets of successors and predecessors of mapped vertices
+struct Side {
+    adjacency: Adjacency,
+    core: Vec<usize>,
+    successors: Vec<usize>,
+    predecessors: Vec<usize>,
+}
+
+impl Side {
+    fn init(adjacency: Adjacency) -> Side {
+        let vertices = adjacency.vertex_count();
+        Side {
+            adjacency,
+            core: vec![NIL; vertices],
+            successors: vec![0; vertices],
+            predecessors: vec![0; vertices],
+        }
+    }
+
+    fn map(&mut self, vertex: usize, partner: usize, depth: usize) {
+        self.core[vertex] = partner;
+        for (terminal, neighbors) in [
+            (&mut self.successors, &self.adjacency.outgoing[vertex]),
+            (&mut self.predecessors, &self.adjacency.incoming[vertex]),
+        ] {
+            for neighbor in neighbors.iter().chain(std::iter::once(&vertex)) {
+                if terminal[*neighbor] == 0 {
+                    terminal[*neighbor] = depth;
+                }
+            }
+        }
+    }
+
+    fn unmap(&mut self, vertex: usize, depth: usize) {
+        self.core[vertex] = NIL;
+        for (terminal, neighbors) in [
+            (&mut self.successors, &self.adjacency.outgoing[vertex]),
+            (&mut self.predecessors, &self.adjacency.incoming[vertex]),
+        ] {
+            for neighbor in neighbors.iter().chain(std::iter::once(&vertex)) {
+                if terminal[*neighbor] == depth {
+                    terminal[*neighbor] = 0;
+                }
+            }
+        }
+    }
+
+    // unmapped vertices in a terminal set, or all unmapped vertices
+    fn candidates<'a>(&'a self, terminal: Option<&'a [usize]>) -> impl Iterator<Item = usize> + 'a {
+        (0..self.core.len()).filter(move |vertex| {
+            self.core[*vertex] == NIL && terminal.is_none_or(|terminal| terminal[*vertex] > 0)
+        })
+    }
+
+    // numbers of unmapped neighbors that are successors, predecessors and neither
+    fn look_ahead(&self, neighbors: &[usize]) -> [usize; 3] {
+        let mut counts = [0; 3];
+        for neighbor in neighbors {
+            if self.core[*neighbor] != NIL {
+                continue;
+            }
+            let successor = self.successors[*neighbor] > 0;
+            let predecessor = self.predecessors[*neighbor] > 0;
+            if successor {
+                counts[0] += 1;
+            }
+            if predecessor {
+                counts[1] += 1;
+            }
+            if !successor && !predecessor {
+                counts[2] += 1;
+            }
+        }
+
+        counts
+    }
+}
+
+// a pattern vertex and the target vertices still to be tried for it
+struct Frame {
+    vertex: usize,
+    candidates: Vec<usize>,
+    next: usize,
+    mapped: bool,
+}
+
+/// Iterator over the mappings of the vertices of a pattern graph into a target graph, found lazily by
+/// a depth first VF2 search. A mapping is a vector whose `i`th element is the target vertex of pattern vertex `i`
+pub struct Matches {
+    pattern: Side,
+    target: Side,
+
+    // isomorphisms need equal counts of unmapped neighbors, subgraph matches only enough of them in the target
+    exact: bool,
+
+    stack: Vec<Frame>,
+    started: bool,
+}
+
+impl Matches {
+    fn init<W, V>(pattern: &Graph<W>, target: &Graph<V>, exact: bool) -> Matches {
+        if pattern.is_directed() != target.is_directed() {
+            panic!("Graphs must both be directed or both be undirected");
+        }
+
+        Matches {
+            pattern: Side::init(Adjacency::init(pattern)),
+            target: Side::init(Adjacency::init(target)),
+            exact,
+            stack: Vec::new(),
+            started: false,
+        }
+    }
+
+    // chooses the next pattern vertex and its candidates, following the terminal sets of both sides
+    fn frame(&self) -> Frame {
+        let pattern = &self.pattern;
+        let target = &self.target;
+        let sets = [
+            (&pattern.successors, &target.successors),
+            (&pattern.predecessors, &target.predecessors),
+        ];
+
+        for (pattern_set, target_set) in sets {
+            if let Some(vertex) = pattern.candidates(Some(pattern_set.as_slice())).next() {
+                return Frame {
+                    vertex,
+                    candidates: target.candidates(Some(target_set.as_slice())).collect(),
+                    next: 0,
+                    mapped: false,
+                };
+            }
+        }
+
+        Frame {
+            vertex: pattern.candidates(None).next().unwrap(),
+            candidates: target.candidates(None).collect(),
+            next: 0,
+            mapped: false,
+        }
+    }
+
+    fn is_feasible(&self, vertex: usize, candidate: usize) -> bool {
+        let pattern = &self.pattern;
+        let target = &self.target;
+        if pattern.adjacency.loops[vertex] != target.adjacency.loops[candidate] {
+            return false;
+        }
+
+        // edges between the new pair and mapped vertices must exist on both sides
+        let consistent = |side: &Side, other: &Side, vertex: usize, partner: usize| {
+            side.adjacency.outgoing[vertex].iter().all(|neighbor| {
+                side.core[*neighbor] == NIL
+                    || other.adjacency.has_edge(partner, side.core[*neighbor])
+            }) && side.adjacency.incoming[vertex].iter().all(|neighbor| {
+                side.core[*neighbor] == NIL
+                    || other.adjacency.has_edge(side.core[*neighbor], partner)
+            })
+        };
+        if !consistent(pattern, target, vertex, candidate)
+            || !consistent(target, pattern, candidate, vertex)
+        {
+            return false;
+        }
+
+        let mut lists = vec![(
+            &pattern.adjacency.outgoing[vertex],
+            &target.adjacency.outgoing[candidate],
+        )];
+        if pattern.adjacency.directed {
+            lists.push((
+                &pattern.adjacency.incoming[vertex],
+                &target.adjacency.incoming[candidate],
+            ));
+        }
+        lists
+            .into_iter()
+            .all(|(pattern_neighbors, target_neighbors)| {
+                let needed = pattern.look_ahead(pattern_neighbors);
+                let available = target.look_ahead(target_neighbors);
+                if self.exact {
+                    needed == available
+                } else {
+                    needed
+                        .iter()
+                        .zip(available.iter())
+                        .all(|(needed, available)| needed <= available)
+                }
+            })
+    }
+}
+
+impl Iterator for Matches {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let vertices = self.pattern.core.len();
+        if !self.started {
+            self.started = true;
+            if vertices > self.target.core.len() {
+                return None;
+            }
+            if vertices == 0 {
+                return Some(Vec::new());
+            }
+            let frame = self.frame();
+            self.stack.push(frame);
+        }
+
+        while let Some(mut frame) = self.stack.pop() {
+            let depth = self.stack.len() + 1;
+            if frame.mapped {
+                let candidate = frame.candidates[frame.next - 1];
+                self.pattern.unmap(frame.vertex, depth);
+                self.target.unmap(candidate, depth);
+                frame.mapped = false;
+            }
+
+            while frame.next < frame.candidates.len()
+                && !self.is_feasible(frame.vertex, frame.candidates[frame.next])
+            {
+                frame.next += 1;
+            }
+            if frame.next == frame.candidates.len() {
+                continue;
+            }
+
+            let candidate = frame.candidates[frame.next];
+            frame.next += 1;
+            frame.mapped = true;
+            self.pattern.map(frame.vertex, candidate, depth);
+            self.target.map(candidate, frame.vertex, depth);
+            self.stack.push(frame);
+
+            if depth == vertices {
+                return Some(self.pattern.core.clone());
+            }
+            let frame = self.frame();
+            self.stack.push(frame);
+        }
+
+        None
+    }
+}
+
+/// Returns true if the graphs are isomorphic: there is a one to one mapping of their vertices that maps
+/// the edges of one graph exactly onto the edges of the other
+///
+/// # Arguments
+/// * `first`: the first graph
+/// * `second`: the second graph
+///
+/// # Panics
+/// * panics if one graph is directed and the other is not
+///
+/// # Examples
+/// ```
+/// use rudac::graph::isomorphism::is_isomorphic;
+/// use rudac::graph::Graph;
+///
+/// // a path 0 - 1 - 2 - 3 and the same path with shuffled labels
+/// let mut first = Graph::init_undirected(4);
+/// first.add_edge(0, 1, 1);
+/// first.add_edge(1, 2, 1);
+/// first.add_edge(2, 3, 1);
+/// let mut second = Graph::init_undirected(4);
+/// second.add_edge(2, 0, 5);
+/// second.add_edge(0, 3, 5);
+/// second.add_edge(3, 1, 5);
+///
+/// assert!(is_isomorphic(&first, &second));
+///
+/// second.add_edge(1, 2, 5);
+/// assert!(!is_isomorphic(&first, &second));
+/// ```
+pub fn is_isomorphic<W, V>(first: &Graph<W>, second: &Graph<V>) -> bool {
+    first.vertex_count() == second.vertex_count()
+        && Matches::init(first, second, true).next().is_some()
+}
+
+/// Returns the mappings of `pattern` onto induced subgraphs of `target`: one to one mappings of the vertices of
+/// `pattern` to vertices of `target` such that two pattern vertices are adjacent exactly if their images are.
+/// Mappings are found one at a time as the iterator advances, so taking the first match is cheap
+///
+/// # Arguments
+/// * `pattern`: the graph to look for
+/// * `target`: the graph to search in
+///
+/// # Panics
+/// * panics if one graph is directed and the other is not
+///
+/// # Examples
+/// ```
+/// use rudac::graph::isomorphism::subgraph_matches;
+/// use rudac::graph::Graph;
+///
+/// // a directed edge and a directed triangle
+/// let mut pattern = Graph::init_directed(2);
+/// pattern.add_edge(0, 1, ());
+/// let mut target = Graph::init_directed(3);
+/// target.add_edge(0, 1, ());
+/// target.add_edge(1, 2, ());
+/// target.add_edge(2, 0, ());
+///
+/// let matches: Vec<Vec<usize>> = subgraph_matches(&pattern, &target).collect();
+///
+/// assert_eq!(matches, vec![vec![0, 1], vec![1, 2], vec![2, 0]]);
+/// ```
+pub fn subgraph_matches<W, V>(pattern: &Graph<W>, target: &Graph<V>) -> Matches {
+    Matches::init(pattern, target, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::erdos_renyi;
+    use crate::util::{Rng, SplitMix64};
+
+    fn relabel(graph: &Graph<u64>, permutation: &[usize]) -> Graph<u64> {
+        let mut relabelled = if graph.is_directed() {
+            Graph::init_directed(graph.vertex_count())
+        } else {
+            Graph::init_undirected(graph.vertex_count())
+        };
+        for (from, to, weight) in graph.edges() {
+            relabelled.add_edge(permutation[from], permutation[to], *weight);
+        }
+
+        relabelled
+    }
+
+    fn shuffled(vertices: usize, rng: &mut SplitMix64) -> Vec<usize> {
+        let mut permutation: Vec<usize> = (0..vertices).collect();
+        for index in (1..vertices).rev() {
+            permutation.swap(index, rng.next_below(index as u64 + 1) as usize);
+        }
+
+        permutation
+    }
+
+    // checks that `mapping` maps `pattern` onto an induced subgraph of `target`
+    fn is_induced(pattern: &Graph<u64>, target: &Graph<u64>, mapping: &[usize]) -> bool {
+        let mut images = mapping.to_vec();
+        images.sort_unstable();
+        images.dedup();
+
+        images.len() == mapping.len()
+            && (0..mapping.len()).all(|from| {
+                (0..mapping.len()).all(|to| {
+                    pattern.has_edge(from, to) == target.has_edge(mapping[from], mapping[to])
+                })
+            })
+    }
+
+    #[test]
+    fn graph_isomorphism_random_relabelling() {
+        let mut rng = SplitMix64::init(12);
+        for directed in [true, false] {
+            for _ in 0..10 {
+                let graph = erdos_renyi(25, 0.2, directed, &mut rng);
+                let relabelled = relabel(&graph, &shuffled(25, &mut rng));
+                assert!(is_isomorphic(&graph, &relabelled));
+
+                let mapping = Matches::init(&graph, &relabelled, true).next().unwrap();
+                assert!(is_induced(&graph, &relabelled, &mapping));
+
+                // an extra edge breaks the isomorphism
+                let mut changed = relabelled.clone();
+                let missing = (0..25)
+                    .flat_map(|from| (0..25).map(move |to| (from, to)))
+                    .find(|(from, to)| from != to && !changed.has_edge(*from, *to))
+                    .unwrap();
+                changed.add_edge(missing.0, missing.1, 1);
+                assert!(!is_isomorphic(&graph, &changed));
+            }
+        }
+    }
+
+    #[test]
+    fn graph_isomorphism_counts_subgraph_matches() {
+        // every pair of adjacent vertices is matched by an undirected edge twice, once in each direction
+        let mut rng = SplitMix64::init(3);
+        let target = erdos_renyi(30, 0.15, false, &mut rng);
+        let mut edge = Graph::init_undirected(2);
+        edge.add_edge(0, 1, 1);
+        assert_eq!(
+            subgraph_matches(&edge, &target).count(),
+            2 * target.edge_count()
+        );
+
+        // a path of three vertices is induced only where the ends are not adjacent
+        let mut path = Graph::init_undirected(3);
+        path.add_edge(0, 1, 1);
+        path.add_edge(1, 2, 1);
+        let mut expected = 0;
+        for middle in 0..30 {
+            for first in target.neighbors(middle) {
+                for second in target.neighbors(middle) {
+                    if first.to != second.to && !target.has_edge(first.to, second.to) {
+                        expected += 1;
+                    }
+                }
+            }
+        }
+        let matches: Vec<Vec<usize>> = subgraph_matches(&path, &target).collect();
+        assert_eq!(matches.len(), expected);
+        assert!(matches
+            .iter()
+            .all(|mapping| is_induced(&path, &target, mapping)));
+    }
+
+    #[test]
+    fn graph_isomorphism_edge_cases() {
+        let empty: Graph<u64> = Graph::init_directed(0);
+        let single: Graph<u64> = Graph::init_directed(1);
+        let mut looped = Graph::init_directed(1);
+        looped.add_edge(0, 0, 1);
+
+        assert_eq!(
+            subgraph_matches(&empty, &single).collect::<Vec<_>>(),
+            vec![Vec::<usize>::new()]
+        );
+        assert_eq!(subgraph_matches(&single, &empty).count(), 0);
+        assert!(is_isomorphic(&empty, &empty));
+        assert!(!is_isomorphic(&single, &looped));
+        assert!(is_isomorphic(&looped, &looped));
+    }
+
+    #[test]
+    #[should_panic(expected = "Graphs must both be directed or both be undirected")]
+    fn graph_isomorphism_panic_mixed_graphs() {
+        let directed: Graph<u64> = Graph::init_directed(1);
+        let undirected: Graph<u64> = Graph::init_undirected(1);
+        is_isomorphic(&directed, &undirected);
+    }
+}
@@ -0,0 +1,24 @@
+//! Weighted graphs and graph algorithms
+
+mod adjacency;
+pub mod centrality;
+pub mod coloring;
+pub mod community;
+mod connectivity;
+pub mod cover;
+pub mod flow;
+mod format;
+pub mod generate;
+pub mod incremental;
+pub mod isomorphism;
+pub mod mst;
+pub mod shortest_path;
+pub mod steiner;
+pub mod tsp;
+mod weight;
+
+pub use adjacency::Edge;
+pub use adjacency::Graph;
+pub use connectivity::offline_dynamic_connectivity;
+pub use connectivity::ConnectivityQuery;
+pub use weight::Weight;
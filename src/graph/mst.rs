@@ -0,0 +1,426 @@
+//! Spanning trees of minimum weight. Directed graphs need arborescences, trees whose edges all lead away from a
+//! root, which the greedy undirected algorithms cannot find
+
+use super::{Graph, Weight};
+use std::ops::Sub;
+
+// marks a vertex without an incoming edge or outside of every cycle
+const NIL: usize = usize::MAX;
+
+/// A spanning arborescence, computed by `minimum_arborescence`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arborescence<W> {
+    /// edges of the arborescence as `(from, to, weight)`, one entering every vertex but the root, sorted by `to`
+    pub edges: Vec<(usize, usize, W)>,
+
+    /// total weight of the edges
+    pub weight: W,
+}
+
+/// A spanning tree of a complete graph, computed by `dense_minimum_spanning_tree`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanningTree<W> {
+    /// edges of the tree as `(parent, child, weight)` with vertex 0 as root, in the order they joined the tree
+    pub edges: Vec<(usize, usize, W)>,
+
+    /// total weight of the edges
+    pub weight: W,
+}
+
+// indices of the edges of a minimum arborescence of `edges`, `None` if a vertex cannot be reached from `root`
+fn chu_liu_edmonds<W: Weight + Sub<Output = W>>(
+    vertices: usize,
+    root: usize,
+    edges: &[(usize, usize, W)],
+) -> Option<Vec<usize>> {
+    // the lightest edge entering every vertex but the root
+    let mut lightest = vec![NIL; vertices];
+    for (index, (from, to, weight)) in edges.iter().enumerate() {
+        if from != to && *to != root && (lightest[*to] == NIL || *weight < edges[lightest[*to]].2) {
+            lightest[*to] = index;
+        }
+    }
+    if (0..vertices).any(|vertex| vertex != root && lightest[vertex] == NIL) {
+        return None;
+    }
+
+    // follows the lightest edges backwards from every vertex, a walk that meets itself found a cycle
+    let mut cycle = vec![NIL; vertices];
+    let mut visited = vec![NIL; vertices];
+    let mut cycles = 0;
+    for start in 0..vertices {
+        let mut vertex = start;
+        while vertex != root && visited[vertex] == NIL {
+            visited[vertex] = start;
+            vertex = edges[lightest[vertex]].0;
+        }
+        if vertex != root && visited[vertex] == start && cycle[vertex] == NIL {
+            while cycle[vertex] == NIL {
+                cycle[vertex] = cycles;
+                vertex = edges[lightest[vertex]].0;
+            }
+            cycles += 1;
+        }
+    }
+    if cycles == 0 {
+        return Some(lightest.into_iter().filter(|edge| *edge != NIL).collect());
+    }
+
+    // contracts every cycle into a vertex, entering edges pay only what they save over the cycle edge they replace
+    let mut component = vec![NIL; vertices];
+    let mut components = cycles;
+    for vertex in 0..vertices {
+        component[vertex] = if cycle[vertex] == NIL {
+            components += 1;
+            components - 1
+        } else {
+            cycle[vertex]
+        };
+    }
+    let mut contracted = Vec::new();
+    let mut origins = Vec::new();
+    for (index, (from, to, weight)) in edges.iter().enumerate() {
+        if component[*from] != component[*to] {
+            let weight = if cycle[*to] == NIL {
+                *weight
+            } else {
+                *weight - edges[lightest[*to]].2
+            };
+            contracted.push((component[*from], component[*to], weight));
+            origins.push(index);
+        }
+    }
+
+    let chosen = chu_liu_edmonds(components, component[root], &contracted)?;
+    let mut entered = vec![NIL; cycles];
+    let mut result: Vec<usize> = chosen.into_iter().map(|edge| origins[edge]).collect();
+    for edge in &result {
+        let to = edges[*edge].1;
+        if cycle[to] != NIL {
+            entered[cycle[to]] = to;
+        }
+    }
+
+    // a cycle keeps its edges except the one entering the vertex the cycle is entered at
+    result.extend(
+        (0..vertices)
+            .filter(|vertex| cycle[*vertex] != NIL && entered[cycle[*vertex]] != *vertex)
+            .map(|vertex| lightest[vertex]),
+    );
+
+    Some(result)
+}
+
+/// Returns a minimum spanning arborescence of `graph` rooted at `root`: edges of minimum total weight such that
+/// exactly one enters every vertex but the root and every vertex is reached from the root. Returns `None` if a
+/// vertex cannot be reached from the root. Computed by the Chu-Liu/Edmonds algorithm in O(VE) time, which picks
+/// the lightest edge entering every vertex and contracts the cycles they form. Weights may be negative
+///
+/// # Arguments
+/// * `graph`: the directed graph
+/// * `root`: root of the arborescence
+///
+/// # Panics
+/// * panics if `graph` is undirected
+/// * panics if `root` does not exist
+///
+/// # Examples
+/// ```
+/// use rudac::graph::mst::minimum_arborescence;
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_directed(4);
+/// graph.add_edge(0, 1, 10);
+/// graph.add_edge(0, 2, 13);
+/// graph.add_edge(1, 2, 3);
+/// graph.add_edge(2, 1, 2);
+/// graph.add_edge(2, 3, 4);
+/// graph.add_edge(3, 1, 1);
+///
+/// let arborescence = minimum_arborescence(&graph, 0).unwrap();
+///
+/// assert_eq!(arborescence.weight, 17);
+/// assert_eq!(arborescence.edges, vec![(0, 1, 10), (1, 2, 3), (2, 3, 4)]);
+/// assert!(minimum_arborescence(&graph, 3).is_none());
+/// ```
+pub fn minimum_arborescence<W: Weight + Sub<Output = W>>(
+    graph: &Graph<W>,
+    root: usize,
+) -> Option<Arborescence<W>> {
+    if !graph.is_directed() {
+        panic!("Graph must be directed");
+    }
+    graph.check_vertex(root);
+
+    let edges: Vec<(usize, usize, W)> = graph
+        .edges()
+        .map(|(from, to, weight)| (from, to, *weight))
+        .collect();
+    let chosen = chu_liu_edmonds(graph.vertex_count(), root, &edges)?;
+    let mut edges: Vec<(usize, usize, W)> = chosen.into_iter().map(|edge| edges[edge]).collect();
+    edges.sort_unstable_by_key(|(_, to, _)| *to);
+    let weight = edges
+        .iter()
+        .fold(W::zero(), |total, (_, _, weight)| total + *weight);
+
+    Some(Arborescence { edges, weight })
+}
+
+/// Returns a minimum spanning tree of the complete graph given by `distances`, where `distances[i][j]` is the
+/// weight of the edge between `i` and `j`. Computed by Prim's algorithm without a heap, which scans every vertex
+/// once per step and suits complete graphs better than the sparse algorithms
+/// * Complexity: O(V^2)
+///
+/// # Arguments
+/// * `distances`: the distance matrix, symmetric
+///
+/// # Panics
+/// * panics if `distances` is not square
+///
+/// # Examples
+/// ```
+/// use rudac::graph::mst::dense_minimum_spanning_tree;
+///
+/// let distances = vec![
+///     vec![0, 4, 1, 5],
+///     vec![4, 0, 2, 6],
+///     vec![1, 2, 0, 3],
+///     vec![5, 6, 3, 0],
+/// ];
+///
+/// let tree = dense_minimum_spanning_tree(&distances);
+///
+/// assert_eq!(tree.weight, 6);
+/// assert_eq!(tree.edges, vec![(0, 2, 1), (2, 1, 2), (2, 3, 3)]);
+/// ```
+pub fn dense_minimum_spanning_tree<W: Weight>(distances: &[Vec<W>]) -> SpanningTree<W> {
+    if distances.iter().any(|row| row.len() != distances.len()) {
+        panic!("Distance matrix must be square");
+    }
+    let vertices = distances.len();
+    let mut edges = Vec::new();
+    if vertices == 0 {
+        return SpanningTree {
+            edges,
+            weight: W::zero(),
+        };
+    }
+
+    // the closest tree vertex of every vertex outside the tree
+    let mut in_tree = vec![false; vertices];
+    let mut closest: Vec<(W, usize)> = (0..vertices)
+        .map(|vertex| (distances[0][vertex], 0))
+        .collect();
+    in_tree[0] = true;
+    for _ in 1..vertices {
+        let next = (0..vertices)
+            .filter(|vertex| !in_tree[*vertex])
+            .min_by_key(|vertex| closest[*vertex].0)
+            .unwrap();
+        in_tree[next] = true;
+        let (weight, parent) = closest[next];
+        edges.push((parent, next, weight));
+        for vertex in 0..vertices {
+            if !in_tree[vertex] && distances[next][vertex] < closest[vertex].0 {
+                closest[vertex] = (distances[next][vertex], next);
+            }
+        }
+    }
+    let weight = edges
+        .iter()
+        .fold(W::zero(), |total, (_, _, weight)| total + *weight);
+
+    SpanningTree { edges, weight }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::erdos_renyi;
+    use crate::util::{Rng, SplitMix64};
+
+    // lightest choice of an entering edge per vertex that leads every vertex back to the root
+    fn brute_force(graph: &Graph<i64>, root: usize) -> Option<i64> {
+        let vertices = graph.vertex_count();
+        let entering: Vec<Vec<(usize, i64)>> = (0..vertices)
+            .map(|vertex| {
+                graph
+                    .incoming(vertex)
+                    .iter()
+                    .filter(|edge| vertex != root && edge.to != vertex)
+                    .map(|edge| (edge.to, edge.weight))
+                    .collect()
+            })
+            .collect();
+
+        let mut best = None;
+        let mut choice = vec![0; vertices];
+        loop {
+            let valid = (0..vertices).all(|vertex| vertex == root || !entering[vertex].is_empty());
+            if !valid {
+                return None;
+            }
+            let reaches_root = (0..vertices).all(|start| {
+                let mut vertex = start;
+                for _ in 0..vertices {
+                    if vertex == root {
+                        return true;
+                    }
+                    vertex = entering[vertex][choice[vertex]].0;
+                }
+                vertex == root
+            });
+            if reaches_root {
+                let weight = (0..vertices)
+                    .filter(|vertex| *vertex != root)
+                    .map(|vertex| entering[vertex][choice[vertex]].1)
+                    .sum::<i64>();
+                best = Some(best.map_or(weight, |best: i64| best.min(weight)));
+            }
+
+            // next combination of choices
+            let mut vertex = 0;
+            while vertex < vertices {
+                if vertex != root && choice[vertex] + 1 < entering[vertex].len() {
+                    choice[vertex] += 1;
+                    break;
+                }
+                choice[vertex] = 0;
+                vertex += 1;
+            }
+            if vertex == vertices {
+                return best;
+            }
+        }
+    }
+
+    #[test]
+    fn graph_mst_arborescence_matches_brute_force() {
+        let mut rng = SplitMix64::init(60);
+        for _ in 0..60 {
+            let shape = erdos_renyi(6, 0.45, true, &mut rng);
+            let mut graph = Graph::init_directed(6);
+            for (from, to, _) in shape.edges() {
+                graph.add_edge(from, to, rng.next_below(21) as i64 - 5);
+            }
+            let root = rng.next_below(6) as usize;
+
+            let arborescence = minimum_arborescence(&graph, root);
+            assert_eq!(
+                arborescence.as_ref().map(|found| found.weight),
+                brute_force(&graph, root)
+            );
+            if let Some(arborescence) = arborescence {
+                assert_eq!(arborescence.edges.len(), 5);
+                let mut parent = [NIL; 6];
+                for (from, to, weight) in &arborescence.edges {
+                    assert!(graph
+                        .neighbors(*from)
+                        .iter()
+                        .any(|edge| edge.to == *to && edge.weight == *weight));
+                    parent[*to] = *from;
+                }
+                assert_eq!(parent[root], NIL);
+            }
+        }
+    }
+
+    #[test]
+    fn graph_mst_arborescence_nested_cycles() {
+        // a cycle 1 -> 2 -> 3 -> 1 inside a cycle through 4, entered only from the root
+        let mut graph = Graph::init_directed(5);
+        graph.add_edge(1, 2, 1i64);
+        graph.add_edge(2, 3, 1);
+        graph.add_edge(3, 1, 1);
+        graph.add_edge(3, 4, 1);
+        graph.add_edge(4, 2, 0);
+        graph.add_edge(0, 4, 9);
+        graph.add_edge(0, 1, 7);
+
+        let arborescence = minimum_arborescence(&graph, 0).unwrap();
+
+        assert_eq!(arborescence.weight, 10);
+        assert_eq!(
+            arborescence.edges,
+            vec![(0, 1, 7), (1, 2, 1), (2, 3, 1), (3, 4, 1)]
+        );
+        assert_eq!(
+            minimum_arborescence(&Graph::<i64>::init_directed(1), 0)
+                .unwrap()
+                .edges,
+            vec![]
+        );
+    }
+
+    #[test]
+    fn graph_mst_dense_matches_kruskal() {
+        let mut rng = SplitMix64::init(61);
+        for vertices in [1, 2, 5, 30] {
+            // a symmetric matrix, read from the upper triangle of random weights
+            let weights: Vec<Vec<u64>> = (0..vertices)
+                .map(|_| (0..vertices).map(|_| rng.next_below(50)).collect())
+                .collect();
+            let distances: Vec<Vec<u64>> = (0..vertices)
+                .map(|first| {
+                    (0..vertices)
+                        .map(|second| {
+                            if first == second {
+                                0
+                            } else {
+                                weights[first.min(second)][first.max(second)]
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+
+            // Kruskal's algorithm over the sorted edges with a plain union find
+            let mut pairs: Vec<(u64, usize, usize)> = (0..vertices)
+                .flat_map(|first| (first + 1..vertices).map(move |second| (first, second)))
+                .map(|(first, second)| (distances[first][second], first, second))
+                .collect();
+            pairs.sort();
+            let mut roots: Vec<usize> = (0..vertices).collect();
+            fn root(roots: &mut [usize], mut vertex: usize) -> usize {
+                while roots[vertex] != vertex {
+                    roots[vertex] = roots[roots[vertex]];
+                    vertex = roots[vertex];
+                }
+                vertex
+            }
+            let mut expected = 0;
+            for (weight, first, second) in pairs {
+                let (first, second) = (root(&mut roots, first), root(&mut roots, second));
+                if first != second {
+                    roots[first] = second;
+                    expected += weight;
+                }
+            }
+
+            let tree = dense_minimum_spanning_tree(&distances);
+            assert_eq!(tree.weight, expected);
+            assert_eq!(tree.edges.len(), vertices - 1);
+            let mut joined = vec![false; vertices];
+            joined[0] = true;
+            for (parent, child, weight) in &tree.edges {
+                assert!(joined[*parent] && !joined[*child]);
+                assert_eq!(*weight, distances[*parent][*child]);
+                joined[*child] = true;
+            }
+        }
+
+        assert_eq!(dense_minimum_spanning_tree::<u64>(&[]).edges, vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Distance matrix must be square")]
+    fn graph_mst_dense_panic_not_square() {
+        dense_minimum_spanning_tree(&[vec![0u64, 1]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Graph must be directed")]
+    fn graph_mst_panic_undirected() {
+        minimum_arborescence(&Graph::<i64>::init_undirected(2), 0);
+    }
+}
@@ -0,0 +1,331 @@
+use super::dijkstra::{check_weight, trace, NIL};
+use super::Path;
+use crate::graph::{Edge, Graph, Weight};
+use crate::heap::MinMax;
+
+// one direction of a bidirectional search
+struct Frontier<W: Weight> {
+    forward: bool,
+    distances: Vec<Option<W>>,
+    parents: Vec<usize>,
+    settled: Vec<bool>,
+
+    // (distance plus estimate, vertex), stale entries of improved vertices are skipped
+    heap: MinMax<(W, usize)>,
+}
+
+impl<W: Weight> Frontier<W> {
+    fn init(vertices: usize, root: usize, key: W, forward: bool) -> Frontier<W> {
+        let mut frontier = Frontier {
+            forward,
+            distances: vec![None; vertices],
+            parents: vec![NIL; vertices],
+            settled: vec![false; vertices],
+            heap: MinMax::init(),
+        };
+        frontier.distances[root] = Some(W::zero());
+        frontier.heap.push((key, root));
+
+        frontier
+    }
+
+    // returns the smallest key of an unsettled vertex
+    fn min_key(&mut self) -> Option<W> {
+        while let Some((key, vertex)) = self.heap.peek_min() {
+            if !self.settled[*vertex] {
+                return Some(*key);
+            }
+            self.heap.pop_min();
+        }
+
+        None
+    }
+
+    fn edges<'a>(&self, graph: &'a Graph<W>, vertex: usize) -> &'a [Edge<W>] {
+        if self.forward {
+            graph.neighbors(vertex)
+        } else {
+            graph.incoming(vertex)
+        }
+    }
+}
+
+// runs both searches until `stop` tells that the best path found so far is a shortest one
+fn search<W: Weight, F, B, S>(
+    graph: &Graph<W>,
+    source: usize,
+    target: usize,
+    to_target: F,
+    to_source: B,
+    stop: S,
+) -> Option<Path<W>>
+where
+    F: Fn(usize) -> W,
+    B: Fn(usize) -> W,
+    S: Fn(W, W, W) -> bool,
+{
+    let vertices = graph.vertex_count();
+    graph.check_vertex(source);
+    graph.check_vertex(target);
+
+    let mut forward = Frontier::init(vertices, source, to_target(source), true);
+    let mut backward = Frontier::init(vertices, target, to_source(target), false);
+
+    // length of the shortest path found so far and the vertex where its two halves meet
+    let mut best: Option<(W, usize)> = if source == target {
+        Some((W::zero(), source))
+    } else {
+        None
+    };
+
+    // a direction that ran out of vertices has met the other one on every path
+    while let (Some(forward_key), Some(backward_key)) = (forward.min_key(), backward.min_key()) {
+        if let Some((length, _)) = best {
+            if stop(forward_key, backward_key, length) {
+                break;
+            }
+        }
+
+        let (frontier, other, estimate): (_, _, &dyn Fn(usize) -> W) =
+            if forward_key <= backward_key {
+                (&mut forward, &backward, &to_target)
+            } else {
+                (&mut backward, &forward, &to_source)
+            };
+
+        let (_, vertex) = frontier.heap.pop_min().unwrap();
+        frontier.settled[vertex] = true;
+        let distance = frontier.distances[vertex].unwrap();
+        for edge in frontier.edges(graph, vertex) {
+            check_weight(edge.weight);
+            let candidate = distance + edge.weight;
+            if frontier.settled[edge.to]
+                || frontier.distances[edge.to].is_some_and(|known| known <= candidate)
+            {
+                continue;
+            }
+
+            frontier.distances[edge.to] = Some(candidate);
+            frontier.parents[edge.to] = vertex;
+            frontier.heap.push((candidate + estimate(edge.to), edge.to));
+
+            if let Some(rest) = other.distances[edge.to] {
+                if best.is_none_or(|(length, _)| candidate + rest < length) {
+                    best = Some((candidate + rest, edge.to));
+                }
+            }
+        }
+    }
+
+    let (length, middle) = best?;
+    let mut vertices = trace(&forward.parents, middle);
+    let mut rest = trace(&backward.parents, middle);
+    rest.reverse();
+    vertices.extend(rest.into_iter().skip(1));
+
+    Some(Path { vertices, length })
+}
+
+/// Returns a shortest path from `source` to `target`, `None` if `target` is not reachable.
+/// Two Dijkstra searches grow from `source` and backwards from `target` until they meet, which visits
+/// roughly the vertices within half the distance of either end instead of all vertices within the full distance.
+/// Directed graphs are searched backwards along their incoming edges
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `source`: start of the path
+/// * `target`: end of the path
+///
+/// # Panics
+/// * panics if `source` or `target` does not exist
+/// * panics if a visited edge has a negative weight
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::bidirectional_dijkstra;
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_directed(5);
+/// graph.add_edge(0, 1, 2);
+/// graph.add_edge(1, 2, 2);
+/// graph.add_edge(2, 4, 2);
+/// graph.add_edge(0, 3, 1);
+/// graph.add_edge(3, 4, 9);
+///
+/// let path = bidirectional_dijkstra(&graph, 0, 4).unwrap();
+///
+/// assert_eq!(path.length, 6);
+/// assert_eq!(path.vertices, vec![0, 1, 2, 4]);
+/// assert!(bidirectional_dijkstra(&graph, 4, 0).is_none());
+/// ```
+pub fn bidirectional_dijkstra<W: Weight>(
+    graph: &Graph<W>,
+    source: usize,
+    target: usize,
+) -> Option<Path<W>> {
+    // every path shorter than the best one would pass between the two frontiers
+    search(
+        graph,
+        source,
+        target,
+        |_| W::zero(),
+        |_| W::zero(),
+        |forward_key, backward_key, best| forward_key + backward_key >= best,
+    )
+}
+
+/// Returns a shortest path from `source` to `target` found by bidirectional A* search,
+/// `None` if `target` is not reachable. The forward search is guided by an estimate of the distance to `target`
+/// and the backward search by an estimate of the distance from `source`. Both estimates must be consistent:
+/// zero at their goal and never larger than the weight of an edge plus the estimate at its other end
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `source`: start of the path
+/// * `target`: end of the path
+/// * `to_target`: estimated distance from a vertex to `target`
+/// * `to_source`: estimated distance from `source` to a vertex
+///
+/// # Panics
+/// * panics if `source` or `target` does not exist
+/// * panics if a visited edge has a negative weight
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::bidirectional_a_star;
+/// use rudac::graph::Graph;
+///
+/// // a path 0 - 1 - ... - 9 with unit weights
+/// let mut graph = Graph::init_undirected(10);
+/// for vertex in 0..9 {
+///     graph.add_edge(vertex, vertex + 1, 1);
+/// }
+///
+/// let path = bidirectional_a_star(&graph, 2, 7, |vertex| 7usize.abs_diff(vertex), |vertex| 2usize.abs_diff(vertex));
+///
+/// assert_eq!(path.unwrap().vertices, vec![2, 3, 4, 5, 6, 7]);
+/// ```
+pub fn bidirectional_a_star<W: Weight, F, B>(
+    graph: &Graph<W>,
+    source: usize,
+    target: usize,
+    to_target: F,
+    to_source: B,
+) -> Option<Path<W>>
+where
+    F: Fn(usize) -> W,
+    B: Fn(usize) -> W,
+{
+    // the keys of a direction bound every path through its unsettled vertices from below
+    search(
+        graph,
+        source,
+        target,
+        to_target,
+        to_source,
+        |forward_key, backward_key, best| forward_key >= best || backward_key >= best,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::shortest_path::dijkstra;
+    use crate::util::{Rng, SplitMix64};
+
+    fn check_path(graph: &Graph<u64>, path: &Path<u64>, source: usize, target: usize) {
+        assert_eq!(path.vertices[0], source);
+        assert_eq!(*path.vertices.last().unwrap(), target);
+        let length: u64 = path
+            .vertices
+            .windows(2)
+            .map(|pair| {
+                graph
+                    .neighbors(pair[0])
+                    .iter()
+                    .filter(|edge| edge.to == pair[1])
+                    .map(|edge| edge.weight)
+                    .min()
+                    .unwrap()
+            })
+            .sum();
+        assert_eq!(length, path.length);
+    }
+
+    #[test]
+    fn graph_bidirectional_matches_dijkstra() {
+        let mut rng = SplitMix64::init(17);
+        for directed in [true, false] {
+            let mut graph = if directed {
+                Graph::init_directed(50)
+            } else {
+                Graph::init_undirected(50)
+            };
+            for _ in 0..120 {
+                let from = rng.next_below(50) as usize;
+                let to = rng.next_below(50) as usize;
+                graph.add_edge(from, to, rng.next_below(10));
+            }
+
+            for source in 0..50 {
+                let paths = dijkstra(&graph, source);
+                for target in 0..50 {
+                    let path = bidirectional_dijkstra(&graph, source, target);
+                    assert_eq!(
+                        path.as_ref().map(|path| path.length),
+                        paths.distance(target)
+                    );
+                    if let Some(path) = path {
+                        check_path(&graph, &path, source, target);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn graph_bidirectional_a_star_grid() {
+        // a directed 25x25 grid with random weights of at least 1 in both directions, vertex x + 25y
+        let mut rng = SplitMix64::init(9);
+        let mut graph = Graph::init_directed(625);
+        for y in 0..25 {
+            for x in 0..25 {
+                let vertex = x + 25 * y;
+                if x < 24 {
+                    graph.add_edge(vertex, vertex + 1, 1 + rng.next_below(4));
+                    graph.add_edge(vertex + 1, vertex, 1 + rng.next_below(4));
+                }
+                if y < 24 {
+                    graph.add_edge(vertex, vertex + 25, 1 + rng.next_below(4));
+                    graph.add_edge(vertex + 25, vertex, 1 + rng.next_below(4));
+                }
+            }
+        }
+        let manhattan = |first: usize, second: usize| {
+            ((first % 25).abs_diff(second % 25) + (first / 25).abs_diff(second / 25)) as u64
+        };
+
+        for _ in 0..60 {
+            let source = rng.next_below(625) as usize;
+            let target = rng.next_below(625) as usize;
+
+            let path = bidirectional_a_star(
+                &graph,
+                source,
+                target,
+                |vertex| manhattan(vertex, target),
+                |vertex| manhattan(source, vertex),
+            )
+            .unwrap();
+            assert_eq!(Some(path.length), dijkstra(&graph, source).distance(target));
+            check_path(&graph, &path, source, target);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Vertex does not exist")]
+    fn graph_bidirectional_panic_missing_vertex() {
+        let graph: Graph<u64> = Graph::init_undirected(3);
+        bidirectional_dijkstra(&graph, 0, 3);
+    }
+}
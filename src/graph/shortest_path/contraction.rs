@@ -0,0 +1,553 @@
+use super::dijkstra::check_weight;
+use super::Path;
+use crate::graph::{Graph, Weight};
+use crate::heap::MinMax;
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::HashMap;
+
+// vertices settled by a witness search before it gives up and a shortcut is added anyway
+const WITNESS_LIMIT: usize = 64;
+
+// an edge of the original graph or a shortcut standing for the two links it bypasses
+#[derive(Debug, Clone)]
+struct Link<W> {
+    from: usize,
+    to: usize,
+    weight: W,
+    children: Option<(usize, usize)>,
+}
+
+/// Contraction hierarchy of a static graph: preprocessing contracts the vertices one by one from the least to the
+/// most important, adding shortcut edges that keep the distances between the remaining vertices.
+/// A query then runs a bidirectional Dijkstra search that only moves upwards in the contraction order,
+/// which settles a few hundred vertices even on large road networks.
+/// Shortcuts are unpacked, so queries return paths of the original graph
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::ContractionHierarchy;
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_undirected(5);
+/// graph.add_edge(0, 1, 2);
+/// graph.add_edge(1, 2, 2);
+/// graph.add_edge(2, 3, 2);
+/// graph.add_edge(3, 4, 2);
+/// graph.add_edge(0, 4, 10);
+///
+/// let hierarchy = ContractionHierarchy::init(&graph);
+///
+/// assert_eq!(hierarchy.distance(0, 4), Some(8));
+/// assert_eq!(hierarchy.path(4, 1).unwrap().vertices, vec![4, 3, 2, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractionHierarchy<W> {
+    links: Vec<Link<W>>,
+
+    // position of every vertex in the contraction order
+    rank: Vec<usize>,
+
+    // links from every vertex to vertices contracted after it
+    upward: Vec<Vec<usize>>,
+
+    // links into every vertex from vertices contracted after it
+    downward: Vec<Vec<usize>>,
+}
+
+// the graph of the vertices not contracted yet, with the shortcuts added so far
+struct Contraction<W> {
+    links: Vec<Link<W>>,
+    outgoing: Vec<Vec<usize>>,
+    incoming: Vec<Vec<usize>>,
+    contracted: Vec<bool>,
+    contracted_neighbors: Vec<i64>,
+}
+
+impl<W: Weight> Contraction<W> {
+    // adds a link from `from` to `to`, or shortens an existing one
+    fn connect(&mut self, from: usize, to: usize, weight: W, children: Option<(usize, usize)>) {
+        let existing = self.outgoing[from]
+            .iter()
+            .copied()
+            .find(|link| self.links[*link].to == to);
+
+        match existing {
+            Some(link) => {
+                if weight < self.links[link].weight {
+                    self.links[link].weight = weight;
+                    self.links[link].children = children;
+                }
+            }
+            None => {
+                self.outgoing[from].push(self.links.len());
+                self.incoming[to].push(self.links.len());
+                self.links.push(Link {
+                    from,
+                    to,
+                    weight,
+                    children,
+                });
+            }
+        }
+    }
+
+    // returns for every target whether a path from `from` that avoids `avoid` reaches it within its limit
+    fn witness(&self, from: usize, avoid: usize, targets: &[(usize, W)]) -> Vec<bool> {
+        let limit = targets.iter().map(|(_, limit)| *limit).max().unwrap();
+        let mut distances: HashMap<usize, W> = HashMap::new();
+        let mut heap = MinMax::init();
+        distances.insert(from, W::zero());
+        heap.push((W::zero(), from));
+
+        let mut settled = 0;
+        while let Some((distance, vertex)) = heap.pop_min() {
+            if distance > limit || settled == WITNESS_LIMIT {
+                break;
+            }
+            if distances[&vertex] < distance {
+                continue;
+            }
+            settled += 1;
+
+            for link in &self.outgoing[vertex] {
+                let link = &self.links[*link];
+                if link.to == avoid || self.contracted[link.to] {
+                    continue;
+                }
+                let candidate = distance + link.weight;
+                if distances
+                    .get(&link.to)
+                    .is_none_or(|known| candidate < *known)
+                {
+                    distances.insert(link.to, candidate);
+                    heap.push((candidate, link.to));
+                }
+            }
+        }
+
+        targets
+            .iter()
+            .map(|(target, limit)| {
+                distances
+                    .get(target)
+                    .is_some_and(|distance| distance <= limit)
+            })
+            .collect()
+    }
+
+    // returns the shortcuts (incoming link, outgoing link) needed to contract `vertex`
+    fn shortcuts(&self, vertex: usize) -> Vec<(usize, usize)> {
+        let outgoing: Vec<usize> = self.outgoing[vertex]
+            .iter()
+            .copied()
+            .filter(|link| !self.contracted[self.links[*link].to])
+            .collect();
+
+        let mut shortcuts = Vec::new();
+        for incoming in self.incoming[vertex].iter().copied() {
+            let from = self.links[incoming].from;
+            if self.contracted[from] || from == vertex {
+                continue;
+            }
+
+            let candidates: Vec<usize> = outgoing
+                .iter()
+                .copied()
+                .filter(|link| self.links[*link].to != from)
+                .collect();
+            if candidates.is_empty() {
+                continue;
+            }
+            let targets: Vec<(usize, W)> = candidates
+                .iter()
+                .map(|link| {
+                    (
+                        self.links[*link].to,
+                        self.links[incoming].weight + self.links[*link].weight,
+                    )
+                })
+                .collect();
+
+            let witnessed = self.witness(from, vertex, &targets);
+            for (link, witnessed) in candidates.into_iter().zip(witnessed) {
+                if !witnessed {
+                    shortcuts.push((incoming, link));
+                }
+            }
+        }
+
+        shortcuts
+    }
+
+    // importance of a vertex: contracting vertices that add few shortcuts first keeps the hierarchy small,
+    // and preferring vertices with few contracted neighbors spreads the contraction evenly over the graph
+    fn priority(&self, vertex: usize, shortcuts: usize) -> i64 {
+        let edges = self.outgoing[vertex]
+            .iter()
+            .chain(self.incoming[vertex].iter())
+            .filter(|link| {
+                let link = &self.links[**link];
+                !self.contracted[link.from] && !self.contracted[link.to]
+            })
+            .count();
+
+        shortcuts as i64 - edges as i64 + self.contracted_neighbors[vertex]
+    }
+
+    fn contract(&mut self, vertex: usize, shortcuts: Vec<(usize, usize)>) {
+        for (incoming, outgoing) in shortcuts {
+            let from = self.links[incoming].from;
+            let to = self.links[outgoing].to;
+            let weight = self.links[incoming].weight + self.links[outgoing].weight;
+            self.connect(from, to, weight, Some((incoming, outgoing)));
+        }
+
+        self.contracted[vertex] = true;
+        for link in self.outgoing[vertex]
+            .iter()
+            .chain(self.incoming[vertex].iter())
+        {
+            let link = &self.links[*link];
+            for neighbor in [link.from, link.to] {
+                if neighbor != vertex {
+                    self.contracted_neighbors[neighbor] += 1;
+                }
+            }
+        }
+    }
+}
+
+// one direction of a query, distances and the links they were reached by
+struct Search<W: Weight> {
+    distances: HashMap<usize, (W, Option<usize>)>,
+    heap: MinMax<(W, usize)>,
+}
+
+impl<W: Weight> Search<W> {
+    fn init(root: usize) -> Search<W> {
+        let mut search = Search {
+            distances: HashMap::new(),
+            heap: MinMax::init(),
+        };
+        search.distances.insert(root, (W::zero(), None));
+        search.heap.push((W::zero(), root));
+
+        search
+    }
+
+    // returns the smallest distance of an unsettled vertex, dropping outdated heap entries
+    fn min_key(&mut self) -> Option<W> {
+        while let Some((distance, vertex)) = self.heap.peek_min() {
+            if self.distances[vertex].0 == *distance {
+                return Some(*distance);
+            }
+            self.heap.pop_min();
+        }
+
+        None
+    }
+}
+
+impl<W: Weight> ContractionHierarchy<W> {
+    /// Preprocesses `graph` for shortest path queries. The hierarchy does not change with the graph,
+    /// so it has to be built again after the graph is modified
+    ///
+    /// # Arguments
+    /// * `graph`: the graph, directed or undirected
+    ///
+    /// # Panics
+    /// * panics if an edge has a negative weight
+    pub fn init(graph: &Graph<W>) -> ContractionHierarchy<W> {
+        let vertices = graph.vertex_count();
+        let mut contraction = Contraction {
+            links: Vec::new(),
+            outgoing: vec![Vec::new(); vertices],
+            incoming: vec![Vec::new(); vertices],
+            contracted: vec![false; vertices],
+            contracted_neighbors: vec![0; vertices],
+        };
+        for from in 0..vertices {
+            for edge in graph.neighbors(from) {
+                check_weight(edge.weight);
+                // self loops are never part of a shortest path
+                if edge.to != from {
+                    contraction.connect(from, edge.to, edge.weight, None);
+                }
+            }
+        }
+
+        let mut queue = MinMax::init();
+        for vertex in 0..vertices {
+            let shortcuts = contraction.shortcuts(vertex).len();
+            queue.push((contraction.priority(vertex, shortcuts), vertex));
+        }
+
+        let mut rank = vec![0; vertices];
+        let mut next_rank = 0;
+        while let Some((_, vertex)) = queue.pop_min() {
+            // priorities change as neighbors are contracted, they are updated lazily
+            let shortcuts = contraction.shortcuts(vertex);
+            let priority = contraction.priority(vertex, shortcuts.len());
+            if queue.peek_min().is_some_and(|(next, _)| *next < priority) {
+                queue.push((priority, vertex));
+                continue;
+            }
+
+            contraction.contract(vertex, shortcuts);
+            rank[vertex] = next_rank;
+            next_rank += 1;
+        }
+
+        let mut upward = vec![Vec::new(); vertices];
+        let mut downward = vec![Vec::new(); vertices];
+        for (index, link) in contraction.links.iter().enumerate() {
+            if rank[link.from] < rank[link.to] {
+                upward[link.from].push(index);
+            } else {
+                downward[link.to].push(index);
+            }
+        }
+
+        ContractionHierarchy {
+            links: contraction.links,
+            rank,
+            upward,
+            downward,
+        }
+    }
+
+    /// Returns number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.rank.len()
+    }
+
+    /// Returns number of shortcut edges added by the preprocessing
+    pub fn shortcut_count(&self) -> usize {
+        self.links
+            .iter()
+            .filter(|link| link.children.is_some())
+            .count()
+    }
+
+    /// Returns length of the shortest path from `source` to `target`, `None` if `target` is not reachable
+    ///
+    /// # Arguments
+    /// * `source`: start of the path
+    /// * `target`: end of the path
+    ///
+    /// # Panics
+    /// * panics if `source` or `target` does not exist
+    pub fn distance(&self, source: usize, target: usize) -> Option<W> {
+        self.search(source, target).map(|(length, _, _, _)| length)
+    }
+
+    /// Returns a shortest path from `source` to `target`, `None` if `target` is not reachable
+    ///
+    /// # Arguments
+    /// * `source`: start of the path
+    /// * `target`: end of the path
+    ///
+    /// # Panics
+    /// * panics if `source` or `target` does not exist
+    pub fn path(&self, source: usize, target: usize) -> Option<Path<W>> {
+        let (length, middle, forward, backward) = self.search(source, target)?;
+
+        // links of the path in the hierarchy, from source to target
+        let mut links = Vec::new();
+        let mut vertex = middle;
+        while let Some(link) = forward.distances[&vertex].1 {
+            links.push(link);
+            vertex = self.links[link].from;
+        }
+        links.reverse();
+        let mut vertex = middle;
+        while let Some(link) = backward.distances[&vertex].1 {
+            links.push(link);
+            vertex = self.links[link].to;
+        }
+
+        let mut vertices = vec![source];
+        for link in links {
+            self.unpack(link, &mut vertices);
+        }
+
+        Some(Path { vertices, length })
+    }
+
+    // appends the vertices after the start of `link` in the original graph
+    fn unpack(&self, link: usize, vertices: &mut Vec<usize>) {
+        let mut stack = vec![link];
+        while let Some(link) = stack.pop() {
+            match self.links[link].children {
+                Some((first, second)) => {
+                    stack.push(second);
+                    stack.push(first);
+                }
+                None => vertices.push(self.links[link].to),
+            }
+        }
+    }
+
+    // returns the length of the shortest path, the vertex of the path contracted last and both searches
+    #[allow(clippy::type_complexity)]
+    fn search(&self, source: usize, target: usize) -> Option<(W, usize, Search<W>, Search<W>)> {
+        if source >= self.vertex_count() || target >= self.vertex_count() {
+            panic!("Vertex does not exist");
+        }
+
+        let mut forward = Search::init(source);
+        let mut backward = Search::init(target);
+        let mut best: Option<(W, usize)> = if source == target {
+            Some((W::zero(), source))
+        } else {
+            None
+        };
+
+        loop {
+            // a direction is done once it can not lead to a shorter path
+            let bound =
+                |key: Option<W>| key.filter(|key| best.is_none_or(|(length, _)| *key < length));
+            let (forward_key, backward_key) = (bound(forward.min_key()), bound(backward.min_key()));
+            let is_forward = match (forward_key, backward_key) {
+                (None, None) => break,
+                (Some(forward_key), Some(backward_key)) => forward_key <= backward_key,
+                (forward_key, _) => forward_key.is_some(),
+            };
+
+            let (search, other, links) = if is_forward {
+                (&mut forward, &backward, &self.upward)
+            } else {
+                (&mut backward, &forward, &self.downward)
+            };
+            let (distance, vertex) = search.heap.pop_min().unwrap();
+            for link in &links[vertex] {
+                let next = if is_forward {
+                    self.links[*link].to
+                } else {
+                    self.links[*link].from
+                };
+                let candidate = distance + self.links[*link].weight;
+                if search
+                    .distances
+                    .get(&next)
+                    .is_some_and(|(known, _)| *known <= candidate)
+                {
+                    continue;
+                }
+
+                search.distances.insert(next, (candidate, Some(*link)));
+                search.heap.push((candidate, next));
+                if let Some((rest, _)) = other.distances.get(&next) {
+                    if best.is_none_or(|(length, _)| candidate + *rest < length) {
+                        best = Some((candidate + *rest, next));
+                    }
+                }
+            }
+        }
+
+        best.map(|(length, middle)| (length, middle, forward, backward))
+    }
+}
+
+impl<W> MemoryFootprint for ContractionHierarchy<W> {
+    fn footprint(&self) -> Footprint {
+        let lists = self.upward.iter().chain(self.downward.iter());
+        lists.fold(
+            Footprint::of::<Link<W>>(self.links.len())
+                .with_vec(&self.links)
+                .with_vec(&self.rank)
+                .with_vec(&self.upward)
+                .with_vec(&self.downward),
+            |footprint, links| footprint.with_vec(links),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::shortest_path::dijkstra;
+    use crate::util::{Rng, SplitMix64};
+
+    fn check(graph: &Graph<u64>, seed: u64, sources: usize) {
+        let hierarchy = ContractionHierarchy::init(graph);
+        let vertices = graph.vertex_count();
+        assert_eq!(hierarchy.vertex_count(), vertices);
+
+        let mut rng = SplitMix64::init(seed);
+        for _ in 0..sources {
+            let source = rng.next_below(vertices as u64) as usize;
+            let paths = dijkstra(graph, source);
+            for target in 0..vertices {
+                assert_eq!(hierarchy.distance(source, target), paths.distance(target));
+
+                if let Some(path) = hierarchy.path(source, target) {
+                    assert_eq!(path.vertices[0], source);
+                    assert_eq!(*path.vertices.last().unwrap(), target);
+                    let length: u64 = path
+                        .vertices
+                        .windows(2)
+                        .map(|pair| {
+                            graph
+                                .neighbors(pair[0])
+                                .iter()
+                                .filter(|edge| edge.to == pair[1])
+                                .map(|edge| edge.weight)
+                                .min()
+                                .unwrap()
+                        })
+                        .sum();
+                    assert_eq!(length, path.length);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn graph_contraction_random_graphs() {
+        let mut rng = SplitMix64::init(31);
+        for directed in [true, false] {
+            let mut graph = if directed {
+                Graph::init_directed(80)
+            } else {
+                Graph::init_undirected(80)
+            };
+            for _ in 0..240 {
+                let from = rng.next_below(80) as usize;
+                let to = rng.next_below(80) as usize;
+                graph.add_edge(from, to, rng.next_below(15));
+            }
+
+            check(&graph, rng.next_u64(), 40);
+        }
+    }
+
+    #[test]
+    fn graph_contraction_grid() {
+        // a 20x20 grid with random weights, vertex x + 20y
+        let mut rng = SplitMix64::init(3);
+        let mut graph = Graph::init_undirected(400);
+        for y in 0..20 {
+            for x in 0..20 {
+                if x < 19 {
+                    graph.add_edge(x + 20 * y, x + 1 + 20 * y, 1 + rng.next_below(9));
+                }
+                if y < 19 {
+                    graph.add_edge(x + 20 * y, x + 20 * (y + 1), 1 + rng.next_below(9));
+                }
+            }
+        }
+
+        let hierarchy = ContractionHierarchy::init(&graph);
+        // shortcuts replace witness paths only where needed, far fewer than all pairs
+        assert!(hierarchy.shortcut_count() < 4 * graph.edge_count());
+        check(&graph, 5, 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Vertex does not exist")]
+    fn graph_contraction_panic_missing_vertex() {
+        let graph: Graph<u64> = Graph::init_directed(2);
+        ContractionHierarchy::init(&graph).distance(0, 2);
+    }
+}
@@ -0,0 +1,327 @@
+use super::Path;
+use crate::graph::{Graph, Weight};
+use crate::heap::MinMax;
+
+// marks a vertex without parent
+pub(super) const NIL: usize = usize::MAX;
+
+/// Distances and shortest path tree from a single source, computed by `dijkstra`
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::dijkstra;
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_directed(4);
+/// graph.add_edge(0, 1, 1);
+/// graph.add_edge(1, 2, 2);
+/// graph.add_edge(0, 2, 5);
+///
+/// let paths = dijkstra(&graph, 0);
+///
+/// assert_eq!(paths.distance(2), Some(3));
+/// assert_eq!(paths.path(2).unwrap().vertices, vec![0, 1, 2]);
+/// assert_eq!(paths.distance(3), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPaths<W> {
+    pub(super) source: usize,
+    pub(super) distances: Vec<Option<W>>,
+    pub(super) parents: Vec<usize>,
+}
+
+impl<W: Weight> ShortestPaths<W> {
+    /// Returns the source of the paths
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    /// Returns length of the shortest path from the source to `vertex`, `None` if `vertex` is not reachable
+    ///
+    /// # Arguments
+    /// * `vertex`: end of the path
+    pub fn distance(&self, vertex: usize) -> Option<W> {
+        self.distances[vertex]
+    }
+
+    /// Returns the shortest path from the source to `vertex`, `None` if `vertex` is not reachable
+    ///
+    /// # Arguments
+    /// * `vertex`: end of the path
+    pub fn path(&self, vertex: usize) -> Option<Path<W>> {
+        let length = self.distances[vertex]?;
+
+        Some(Path {
+            vertices: trace(&self.parents, vertex),
+            length,
+        })
+    }
+}
+
+// returns the vertices from the root of the parent tree to `vertex`
+pub(super) fn trace(parents: &[usize], mut vertex: usize) -> Vec<usize> {
+    let mut vertices = vec![vertex];
+    while parents[vertex] != NIL {
+        vertex = parents[vertex];
+        vertices.push(vertex);
+    }
+    vertices.reverse();
+
+    vertices
+}
+
+// panics if `weight` is negative, searches that settle vertices are only correct for non-negative weights
+pub(super) fn check_weight<W: Weight>(weight: W) {
+    if weight < W::zero() {
+        panic!("Edge weights must not be negative");
+    }
+}
+
+// a settling search from `source`, ordered by distance plus `heuristic` and stopped once `target` is settled
+fn search<W: Weight, H: Fn(usize) -> W>(
+    graph: &Graph<W>,
+    source: usize,
+    target: Option<usize>,
+    heuristic: H,
+) -> ShortestPaths<W> {
+    let vertices = graph.vertex_count();
+    let mut distances = vec![None; vertices];
+    let mut parents = vec![NIL; vertices];
+    let mut settled = vec![false; vertices];
+
+    // stale entries of improved vertices are skipped when popped
+    let mut heap = MinMax::init();
+    graph.check_vertex(source);
+    distances[source] = Some(W::zero());
+    heap.push((heuristic(source), source));
+
+    while let Some((_, vertex)) = heap.pop_min() {
+        if settled[vertex] {
+            continue;
+        }
+        settled[vertex] = true;
+        if target == Some(vertex) {
+            break;
+        }
+
+        let distance = distances[vertex].unwrap();
+        for edge in graph.neighbors(vertex) {
+            check_weight(edge.weight);
+            let candidate = distance + edge.weight;
+            if !settled[edge.to] && distances[edge.to].is_none_or(|known| candidate < known) {
+                distances[edge.to] = Some(candidate);
+                parents[edge.to] = vertex;
+                heap.push((candidate + heuristic(edge.to), edge.to));
+            }
+        }
+    }
+
+    ShortestPaths {
+        source,
+        distances,
+        parents,
+    }
+}
+
+/// Returns the shortest paths from `source` to every vertex
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `source`: start of the paths
+///
+/// # Panics
+/// * panics if `source` does not exist
+/// * panics if a reachable edge has a negative weight
+pub fn dijkstra<W: Weight>(graph: &Graph<W>, source: usize) -> ShortestPaths<W> {
+    search(graph, source, None, |_| W::zero())
+}
+
+/// Returns a shortest path from `source` to `target`, `None` if `target` is not reachable.
+/// The search stops as soon as the distance of `target` is known, so only vertices closer to `source`
+/// than `target` are visited
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `source`: start of the path
+/// * `target`: end of the path
+///
+/// # Panics
+/// * panics if `source` or `target` does not exist
+/// * panics if a visited edge has a negative weight
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::dijkstra_to;
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_undirected(4);
+/// graph.add_edge(0, 1, 7);
+/// graph.add_edge(0, 2, 2);
+/// graph.add_edge(2, 1, 3);
+/// graph.add_edge(1, 3, 1);
+///
+/// let path = dijkstra_to(&graph, 0, 3).unwrap();
+///
+/// assert_eq!(path.length, 6);
+/// assert_eq!(path.vertices, vec![0, 2, 1, 3]);
+/// ```
+pub fn dijkstra_to<W: Weight>(graph: &Graph<W>, source: usize, target: usize) -> Option<Path<W>> {
+    graph.check_vertex(target);
+
+    search(graph, source, Some(target), |_| W::zero()).path(target)
+}
+
+/// Returns a shortest path from `source` to `target` found by A* search, `None` if `target` is not reachable.
+/// Vertices are visited in the order of their distance from `source` plus the estimated distance to `target`,
+/// so a good estimate visits far fewer vertices than `dijkstra_to`. The estimate must be consistent:
+/// zero at `target` and never larger than the weight of an edge plus the estimate at its end
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `source`: start of the path
+/// * `target`: end of the path
+/// * `heuristic`: estimated distance from a vertex to `target`
+///
+/// # Panics
+/// * panics if `source` or `target` does not exist
+/// * panics if a visited edge has a negative weight
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::a_star;
+/// use rudac::graph::Graph;
+///
+/// // a 10x10 grid, vertex x + 10y
+/// let mut graph = Graph::init_undirected(100);
+/// for y in 0..10 {
+///     for x in 0..10 {
+///         if x < 9 {
+///             graph.add_edge(x + 10 * y, x + 1 + 10 * y, 1);
+///         }
+///         if y < 9 {
+///             graph.add_edge(x + 10 * y, x + 10 * (y + 1), 1);
+///         }
+///     }
+/// }
+///
+/// // manhattan distance to the corner (9, 9)
+/// let path = a_star(&graph, 0, 99, |vertex| (9 - vertex % 10) + (9 - vertex / 10)).unwrap();
+///
+/// assert_eq!(path.length, 18);
+/// assert_eq!(path.vertices.len(), 19);
+/// ```
+pub fn a_star<W: Weight, H: Fn(usize) -> W>(
+    graph: &Graph<W>,
+    source: usize,
+    target: usize,
+    heuristic: H,
+) -> Option<Path<W>> {
+    graph.check_vertex(target);
+
+    search(graph, source, Some(target), heuristic).path(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn path_length(graph: &Graph<u64>, vertices: &[usize]) -> u64 {
+        vertices
+            .windows(2)
+            .map(|pair| {
+                graph
+                    .neighbors(pair[0])
+                    .iter()
+                    .filter(|edge| edge.to == pair[1])
+                    .map(|edge| edge.weight)
+                    .min()
+                    .unwrap()
+            })
+            .sum()
+    }
+
+    // slow but simple Bellman-Ford distances
+    fn distances(graph: &Graph<u64>, source: usize) -> Vec<Option<u64>> {
+        let mut distances = vec![None; graph.vertex_count()];
+        distances[source] = Some(0);
+        for _ in 0..graph.vertex_count() {
+            for vertex in 0..graph.vertex_count() {
+                if let Some(distance) = distances[vertex] {
+                    for edge in graph.neighbors(vertex) {
+                        let candidate = distance + edge.weight;
+                        if distances[edge.to].is_none_or(|known| candidate < known) {
+                            distances[edge.to] = Some(candidate);
+                        }
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+
+    #[test]
+    fn graph_dijkstra_matches_bellman_ford() {
+        let mut rng = SplitMix64::init(21);
+        let mut graph = Graph::init_directed(60);
+        for _ in 0..180 {
+            let from = rng.next_below(60) as usize;
+            let to = rng.next_below(60) as usize;
+            graph.add_edge(from, to, rng.next_below(20));
+        }
+
+        for source in 0..60 {
+            let expected = distances(&graph, source);
+            let paths = dijkstra(&graph, source);
+            assert_eq!(paths.source(), source);
+            for (target, expected) in expected.into_iter().enumerate() {
+                assert_eq!(paths.distance(target), expected);
+                let path = dijkstra_to(&graph, source, target);
+                assert_eq!(path.as_ref().map(|path| path.length), expected);
+                if let Some(path) = path {
+                    assert_eq!(path.vertices[0], source);
+                    assert_eq!(*path.vertices.last().unwrap(), target);
+                    assert_eq!(path_length(&graph, &path.vertices), path.length);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn graph_dijkstra_a_star_grid() {
+        // a 30x30 grid with random weights of at least 1, vertex x + 30y
+        let mut rng = SplitMix64::init(4);
+        let mut graph = Graph::init_undirected(900);
+        for y in 0..30 {
+            for x in 0..30 {
+                if x < 29 {
+                    graph.add_edge(x + 30 * y, x + 1 + 30 * y, 1 + rng.next_below(5));
+                }
+                if y < 29 {
+                    graph.add_edge(x + 30 * y, x + 30 * (y + 1), 1 + rng.next_below(5));
+                }
+            }
+        }
+
+        for _ in 0..50 {
+            let source = rng.next_below(900) as usize;
+            let target = rng.next_below(900) as usize;
+            let manhattan = |vertex: usize| {
+                ((vertex % 30).abs_diff(target % 30) + (vertex / 30).abs_diff(target / 30)) as u64
+            };
+
+            let path = a_star(&graph, source, target, manhattan).unwrap();
+            assert_eq!(Some(path.length), dijkstra(&graph, source).distance(target));
+            assert_eq!(path_length(&graph, &path.vertices), path.length);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Edge weights must not be negative")]
+    fn graph_dijkstra_panic_negative_weight() {
+        let mut graph = Graph::init_directed(2);
+        graph.add_edge(0, 1, -1);
+        dijkstra(&graph, 0);
+    }
+}
@@ -0,0 +1,26 @@
+//! Single source and point to point shortest paths on graphs with non-negative edge weights
+
+mod bidirectional;
+mod contraction;
+mod dijkstra;
+mod small_weight;
+
+pub use bidirectional::bidirectional_a_star;
+pub use bidirectional::bidirectional_dijkstra;
+pub use contraction::ContractionHierarchy;
+pub use dijkstra::a_star;
+pub use dijkstra::dijkstra;
+pub use dijkstra::dijkstra_to;
+pub use dijkstra::ShortestPaths;
+pub use small_weight::dial;
+pub use small_weight::zero_one_bfs;
+
+/// A path between two vertices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<W> {
+    /// vertices of the path, from its start to its end
+    pub vertices: Vec<usize>,
+
+    /// sum of the weights of the edges of the path
+    pub length: W,
+}
@@ -0,0 +1,276 @@
+use super::dijkstra::{check_weight, ShortestPaths, NIL};
+use crate::graph::{Graph, Weight};
+use std::collections::VecDeque;
+use std::convert::TryInto;
+
+// returns `weight` as a number of steps, panics if it is negative or too large
+fn steps<W: Weight + TryInto<usize>>(weight: W) -> usize {
+    check_weight(weight);
+
+    weight
+        .try_into()
+        .unwrap_or_else(|_| panic!("Edge weight does not fit in usize"))
+}
+
+/// Returns the shortest paths from `source` to every vertex of a graph whose edge weights are all 0 or 1.
+/// Vertices are kept in a deque instead of a heap: the end of an edge of weight 0 is pushed to the front and the
+/// end of an edge of weight 1 to the back, so the deque stays sorted by distance.
+/// * Complexity: O(V + E)
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `source`: start of the paths
+///
+/// # Panics
+/// * panics if `source` does not exist
+/// * panics if a reachable edge has a weight other than 0 or 1
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::zero_one_bfs;
+/// use rudac::graph::Graph;
+///
+/// // the edge of weight 0 makes the longer path the shorter one
+/// let mut graph = Graph::init_directed(4);
+/// graph.add_edge(0, 1, 0u8);
+/// graph.add_edge(1, 3, 1);
+/// graph.add_edge(0, 2, 1);
+/// graph.add_edge(2, 3, 1);
+///
+/// let paths = zero_one_bfs(&graph, 0);
+///
+/// assert_eq!(paths.distance(3), Some(1));
+/// assert_eq!(paths.path(3).unwrap().vertices, vec![0, 1, 3]);
+/// ```
+pub fn zero_one_bfs<W: Weight + TryInto<usize>>(
+    graph: &Graph<W>,
+    source: usize,
+) -> ShortestPaths<W> {
+    let vertices = graph.vertex_count();
+    let mut distances = vec![None; vertices];
+    let mut parents = vec![NIL; vertices];
+    let mut settled = vec![false; vertices];
+
+    // a vertex may be queued again when it is improved, the stale entries are skipped when popped
+    let mut deque = VecDeque::new();
+    graph.check_vertex(source);
+    distances[source] = Some(W::zero());
+    deque.push_back(source);
+
+    while let Some(vertex) = deque.pop_front() {
+        if settled[vertex] {
+            continue;
+        }
+        settled[vertex] = true;
+
+        let distance = distances[vertex].unwrap();
+        for edge in graph.neighbors(vertex) {
+            let free = match steps(edge.weight) {
+                0 => true,
+                1 => false,
+                _ => panic!("Edge weights must be 0 or 1"),
+            };
+            let candidate = distance + edge.weight;
+            if !settled[edge.to] && distances[edge.to].is_none_or(|known| candidate < known) {
+                distances[edge.to] = Some(candidate);
+                parents[edge.to] = vertex;
+                if free {
+                    deque.push_front(edge.to);
+                } else {
+                    deque.push_back(edge.to);
+                }
+            }
+        }
+    }
+
+    ShortestPaths {
+        source,
+        distances,
+        parents,
+    }
+}
+
+/// Returns the shortest paths from `source` to every vertex of a graph with small non-negative integer edge
+/// weights, using Dial's algorithm. Vertices wait in a bucket queue, one bucket per distance, which is scanned in
+/// order of distance instead of keeping a heap. Only the distances between the current one and the current one
+/// plus the largest weight can be pending, so C + 1 buckets are reused in a circle for the largest weight C.
+/// * Complexity: O(V + E + D + C), D being the largest distance of a reachable vertex
+///
+/// # Arguments
+/// * `graph`: the graph
+/// * `source`: start of the paths
+///
+/// # Panics
+/// * panics if `source` does not exist
+/// * panics if an edge has a negative weight
+///
+/// # Examples
+/// ```
+/// use rudac::graph::shortest_path::{dial, dijkstra};
+/// use rudac::graph::Graph;
+///
+/// let mut graph = Graph::init_undirected(5);
+/// graph.add_edge(0, 1, 4u32);
+/// graph.add_edge(0, 2, 1);
+/// graph.add_edge(2, 1, 2);
+/// graph.add_edge(1, 3, 5);
+/// graph.add_edge(2, 3, 8);
+///
+/// let paths = dial(&graph, 0);
+///
+/// assert_eq!(paths.distance(3), Some(8));
+/// assert_eq!(paths.path(3).unwrap().vertices, vec![0, 2, 1, 3]);
+/// assert_eq!(paths.distance(4), None);
+/// assert_eq!(paths, dijkstra(&graph, 0));
+/// ```
+pub fn dial<W: Weight + TryInto<usize>>(graph: &Graph<W>, source: usize) -> ShortestPaths<W> {
+    let vertices = graph.vertex_count();
+    let mut distances = vec![None; vertices];
+    let mut parents = vec![NIL; vertices];
+    let mut settled = vec![false; vertices];
+
+    // distances as numbers of steps, which pick the buckets
+    let mut lengths = vec![0; vertices];
+
+    let largest = (0..vertices)
+        .flat_map(|vertex| graph.neighbors(vertex))
+        .map(|edge| steps(edge.weight))
+        .max()
+        .unwrap_or(0);
+
+    // bucket of distance d is at d modulo the number of buckets, stale entries of improved vertices are skipped
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); largest + 1];
+    let mut pending = 1;
+    graph.check_vertex(source);
+    distances[source] = Some(W::zero());
+    buckets[0].push(source);
+
+    let mut length = 0;
+    while pending > 0 {
+        let bucket = length % buckets.len();
+
+        // edges of weight 0 push to the bucket being emptied
+        while let Some(vertex) = buckets[bucket].pop() {
+            pending -= 1;
+            if settled[vertex] || lengths[vertex] != length {
+                continue;
+            }
+            settled[vertex] = true;
+
+            let distance = distances[vertex].unwrap();
+            for edge in graph.neighbors(vertex) {
+                let candidate = distance + edge.weight;
+                if !settled[edge.to] && distances[edge.to].is_none_or(|known| candidate < known) {
+                    distances[edge.to] = Some(candidate);
+                    parents[edge.to] = vertex;
+                    lengths[edge.to] = length + steps(edge.weight);
+                    let next = lengths[edge.to] % buckets.len();
+                    buckets[next].push(edge.to);
+                    pending += 1;
+                }
+            }
+        }
+
+        length += 1;
+    }
+
+    ShortestPaths {
+        source,
+        distances,
+        parents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::shortest_path::dijkstra;
+    use crate::util::{Rng, SplitMix64};
+
+    fn random_graph(rng: &mut SplitMix64, directed: bool, largest: u64) -> Graph<u64> {
+        let mut graph = if directed {
+            Graph::init_directed(80)
+        } else {
+            Graph::init_undirected(80)
+        };
+        for _ in 0..200 {
+            let from = rng.next_below(80) as usize;
+            let to = rng.next_below(80) as usize;
+            graph.add_edge(from, to, rng.next_below(largest + 1));
+        }
+
+        graph
+    }
+
+    // paths may differ between equally short ones, so they are checked by their length
+    fn check(graph: &Graph<u64>, paths: &ShortestPaths<u64>) {
+        let expected = dijkstra(graph, paths.source());
+        for vertex in 0..graph.vertex_count() {
+            assert_eq!(paths.distance(vertex), expected.distance(vertex));
+            if let Some(path) = paths.path(vertex) {
+                assert_eq!(path.vertices[0], paths.source());
+                let length: u64 = path
+                    .vertices
+                    .windows(2)
+                    .map(|pair| {
+                        graph
+                            .neighbors(pair[0])
+                            .iter()
+                            .filter(|edge| edge.to == pair[1])
+                            .map(|edge| edge.weight)
+                            .min()
+                            .unwrap()
+                    })
+                    .sum();
+                assert_eq!(length, path.length);
+            }
+        }
+    }
+
+    #[test]
+    fn graph_zero_one_bfs_matches_dijkstra() {
+        let mut rng = SplitMix64::init(109);
+        for directed in [true, false] {
+            let graph = random_graph(&mut rng, directed, 1);
+            for source in 0..graph.vertex_count() {
+                check(&graph, &zero_one_bfs(&graph, source));
+            }
+        }
+    }
+
+    #[test]
+    fn graph_dial_matches_dijkstra() {
+        let mut rng = SplitMix64::init(113);
+        for largest in [0, 1, 3, 10] {
+            for directed in [true, false] {
+                let graph = random_graph(&mut rng, directed, largest);
+                for source in 0..graph.vertex_count() {
+                    check(&graph, &dial(&graph, source));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn graph_dial_single_vertex() {
+        let graph: Graph<u8> = Graph::init_directed(1);
+        assert_eq!(dial(&graph, 0).distance(0), Some(0));
+        assert_eq!(zero_one_bfs(&graph, 0).path(0).unwrap().vertices, vec![0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Edge weights must be 0 or 1")]
+    fn graph_zero_one_bfs_panic_weight() {
+        let mut graph = Graph::init_directed(2);
+        graph.add_edge(0, 1, 2);
+        zero_one_bfs(&graph, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Edge weights must not be negative")]
+    fn graph_dial_panic_negative_weight() {
+        let mut graph = Graph::init_directed(2);
+        graph.add_edge(1, 0, -3);
+        dial(&graph, 0);
+    }
+}
@@ -0,0 +1,419 @@
+//! Steiner trees: trees of minimum total weight in an undirected graph that connect a given set of terminal
+//! vertices, possibly through other vertices. Finding one is NP-hard, `dreyfus_wagner` is exact and exponential in
+//! the number of terminals only, `mst_approximation` is fast and at most twice as heavy as a minimum tree
+
+use super::shortest_path::dijkstra;
+use super::{Graph, Weight};
+use crate::heap::MinMax;
+use std::collections::{HashMap, HashSet};
+
+/// A tree connecting the terminals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteinerTree<W> {
+    /// edges of the tree as `(from, to, weight)` with `from < to`, sorted
+    pub edges: Vec<(usize, usize, W)>,
+
+    /// total weight of the edges
+    pub weight: W,
+}
+
+impl<W: Weight> SteinerTree<W> {
+    fn from_edges(mut edges: Vec<(usize, usize, W)>) -> SteinerTree<W> {
+        edges.sort_unstable();
+        edges.dedup_by_key(|(from, to, _)| (*from, *to));
+        let weight = edges
+            .iter()
+            .fold(W::zero(), |total, (_, _, weight)| total + *weight);
+
+        SteinerTree { edges, weight }
+    }
+}
+
+// how the best tree of a terminal subset containing a vertex was found
+#[derive(Clone, Copy)]
+enum Origin<W> {
+    Terminal,
+    Split(usize),
+    Edge(usize, W),
+}
+
+// checks the graph and terminals, returns the distinct terminals
+fn check_input<W: Weight>(graph: &Graph<W>, terminals: &[usize]) -> Vec<usize> {
+    if graph.is_directed() {
+        panic!("Graph must be undirected");
+    }
+    for terminal in terminals {
+        graph.check_vertex(*terminal);
+    }
+    if graph.edges().any(|(_, _, weight)| *weight < W::zero()) {
+        panic!("Edge weights must not be negative");
+    }
+
+    let mut distinct = terminals.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    distinct
+}
+
+fn ordered<W>(from: usize, to: usize, weight: W) -> (usize, usize, W) {
+    (usize::min(from, to), usize::max(from, to), weight)
+}
+
+/// Returns a minimum Steiner tree connecting `terminals`, or `None` if they are not all connected. Computed with
+/// the Dreyfus-Wagner dynamic program, which finds the best tree for every subset of terminals joined at every
+/// vertex, in O(3^k V + 2^k E log V) time and O(2^k V) space for k terminals. Practical for up to about 15 terminals
+///
+/// # Arguments
+/// * `graph`: the undirected graph
+/// * `terminals`: vertices to connect
+///
+/// # Panics
+/// * panics if `graph` is directed
+/// * panics if a terminal does not exist
+/// * panics if an edge weight is negative
+///
+/// # Examples
+/// ```
+/// use rudac::graph::steiner::dreyfus_wagner;
+/// use rudac::graph::Graph;
+///
+/// // three terminals around a center vertex 3
+/// let mut graph = Graph::init_undirected(4);
+/// graph.add_edge(0, 1, 5);
+/// graph.add_edge(1, 2, 5);
+/// graph.add_edge(0, 2, 5);
+/// for terminal in 0..3 {
+///     graph.add_edge(terminal, 3, 3);
+/// }
+///
+/// let tree = dreyfus_wagner(&graph, &[0, 1, 2]).unwrap();
+///
+/// assert_eq!(tree.weight, 9);
+/// assert_eq!(tree.edges, vec![(0, 3, 3), (1, 3, 3), (2, 3, 3)]);
+/// ```
+pub fn dreyfus_wagner<W: Weight>(graph: &Graph<W>, terminals: &[usize]) -> Option<SteinerTree<W>> {
+    let terminals = check_input(graph, terminals);
+    if terminals.len() <= 1 {
+        return Some(SteinerTree::from_edges(Vec::new()));
+    }
+
+    // weight of the best tree connecting the terminals of each subset and a vertex
+    let vertices = graph.vertex_count();
+    let subsets = 1usize << terminals.len();
+    let mut weights: Vec<Vec<Option<W>>> = vec![vec![None; vertices]; subsets];
+    let mut origins: Vec<Vec<Origin<W>>> = vec![vec![Origin::Terminal; vertices]; subsets];
+    for subset in 1..subsets {
+        let (weights, smaller) = {
+            let (smaller, rest) = weights.split_at_mut(subset);
+            (&mut rest[0], smaller)
+        };
+        let origins = &mut origins[subset];
+        if subset.is_power_of_two() {
+            weights[terminals[subset.trailing_zeros() as usize]] = Some(W::zero());
+        } else {
+            // joins two trees at a vertex, the part holding the lowest terminal is enumerated only
+            let lowest = subset & subset.wrapping_neg();
+            let mut part = (subset - 1) & subset;
+            while part > 0 {
+                if part & lowest != 0 {
+                    for vertex in 0..vertices {
+                        if let (Some(first), Some(second)) =
+                            (smaller[part][vertex], smaller[subset ^ part][vertex])
+                        {
+                            if weights[vertex].is_none_or(|best| first + second < best) {
+                                weights[vertex] = Some(first + second);
+                                origins[vertex] = Origin::Split(part);
+                            }
+                        }
+                    }
+                }
+                part = (part - 1) & subset;
+            }
+        }
+
+        // extends the trees along shortest paths
+        let mut heap = MinMax::init();
+        for (vertex, weight) in weights.iter().enumerate() {
+            if let Some(weight) = weight {
+                heap.push((*weight, vertex));
+            }
+        }
+        while let Some((weight, vertex)) = heap.pop_min() {
+            if weights[vertex] != Some(weight) {
+                continue;
+            }
+            for edge in graph.neighbors(vertex) {
+                let extended = weight + edge.weight;
+                if weights[edge.to].is_none_or(|best| extended < best) {
+                    weights[edge.to] = Some(extended);
+                    origins[edge.to] = Origin::Edge(vertex, edge.weight);
+                    heap.push((extended, edge.to));
+                }
+            }
+        }
+    }
+
+    let full = subsets - 1;
+    weights[full][terminals[0]]?;
+    let mut edges = Vec::new();
+    let mut pending = vec![(full, terminals[0])];
+    while let Some((subset, vertex)) = pending.pop() {
+        match origins[subset][vertex] {
+            Origin::Terminal => {}
+            Origin::Split(part) => {
+                pending.push((part, vertex));
+                pending.push((subset ^ part, vertex));
+            }
+            Origin::Edge(from, weight) => {
+                edges.push(ordered(from, vertex, weight));
+                pending.push((subset, from));
+            }
+        }
+    }
+
+    Some(SteinerTree::from_edges(edges))
+}
+
+// minimum spanning tree of the vertices reachable from `root` by Prim's algorithm, as edges
+fn prim<W: Weight>(
+    adjacency: &HashMap<usize, Vec<(usize, W)>>,
+    root: usize,
+) -> Vec<(usize, usize, W)> {
+    let mut in_tree = HashSet::new();
+    let mut tree = Vec::new();
+    let mut heap = MinMax::init();
+    heap.push((W::zero(), root, root));
+    while let Some((weight, to, from)) = heap.pop_min() {
+        if !in_tree.insert(to) {
+            continue;
+        }
+        if to != root {
+            tree.push(ordered(from, to, weight));
+        }
+        for (next, weight) in &adjacency[&to] {
+            if !in_tree.contains(next) {
+                heap.push((*weight, *next, to));
+            }
+        }
+    }
+
+    tree
+}
+
+/// Returns a Steiner tree connecting `terminals` at most 2 (1 - 1 / k) times as heavy as a minimum one for k
+/// terminals, or `None` if they are not all connected. Follows Kou, Markowsky and Berman: a minimum spanning tree
+/// of the terminals under shortest path distances is expanded into paths of the graph, spanned again, and pruned of
+/// leaves that are not terminals. Runs in O(k E log V) time
+///
+/// # Arguments
+/// * `graph`: the undirected graph
+/// * `terminals`: vertices to connect
+///
+/// # Panics
+/// * panics if `graph` is directed
+/// * panics if a terminal does not exist
+/// * panics if an edge weight is negative
+///
+/// # Examples
+/// ```
+/// use rudac::graph::steiner::{dreyfus_wagner, mst_approximation};
+/// use rudac::graph::Graph;
+///
+/// // a wheel: terminals on the rim, a cheap hub in the middle
+/// let mut graph = Graph::init_undirected(7);
+/// for rim in 0..6 {
+///     graph.add_edge(rim, (rim + 1) % 6, 5);
+///     graph.add_edge(rim, 6, 3);
+/// }
+///
+/// let terminals = [0, 2, 4];
+/// let tree = mst_approximation(&graph, &terminals).unwrap();
+/// let optimum = dreyfus_wagner(&graph, &terminals).unwrap();
+///
+/// assert_eq!(optimum.weight, 9);
+/// assert!(tree.weight <= 2 * optimum.weight);
+/// ```
+pub fn mst_approximation<W: Weight>(
+    graph: &Graph<W>,
+    terminals: &[usize],
+) -> Option<SteinerTree<W>> {
+    let terminals = check_input(graph, terminals);
+    if terminals.len() <= 1 {
+        return Some(SteinerTree::from_edges(Vec::new()));
+    }
+
+    // spanning tree of the terminals under shortest path distances
+    let searches: Vec<_> = terminals
+        .iter()
+        .map(|terminal| dijkstra(graph, *terminal))
+        .collect();
+    let mut closure: HashMap<usize, Vec<(usize, W)>> = HashMap::new();
+    for (first, search) in searches.iter().enumerate() {
+        let neighbors = (0..terminals.len())
+            .filter(|second| *second != first)
+            .filter_map(|second| Some((second, search.distance(terminals[second])?)))
+            .collect();
+        closure.insert(first, neighbors);
+    }
+    let spanning = prim(&closure, 0);
+    if spanning.len() + 1 < terminals.len() {
+        return None;
+    }
+
+    // the graph formed by the shortest paths, using the lightest edge between consecutive vertices
+    let mut subgraph: HashMap<usize, Vec<(usize, W)>> = HashMap::new();
+    for (first, second, _) in spanning {
+        let path = searches[first].path(terminals[second]).unwrap();
+        for pair in path.vertices.windows(2) {
+            let weight = graph
+                .neighbors(pair[0])
+                .iter()
+                .filter(|edge| edge.to == pair[1])
+                .map(|edge| edge.weight)
+                .min()
+                .unwrap();
+            subgraph.entry(pair[0]).or_default().push((pair[1], weight));
+            subgraph.entry(pair[1]).or_default().push((pair[0], weight));
+        }
+    }
+    let mut edges = prim(&subgraph, terminals[0]);
+
+    // removes leaves that are not terminals until none is left
+    loop {
+        let mut degrees: HashMap<usize, usize> = HashMap::new();
+        for (from, to, _) in &edges {
+            *degrees.entry(*from).or_default() += 1;
+            *degrees.entry(*to).or_default() += 1;
+        }
+        let is_removable =
+            |vertex: &usize| degrees[vertex] == 1 && terminals.binary_search(vertex).is_err();
+        let before = edges.len();
+        edges.retain(|(from, to, _)| !is_removable(from) && !is_removable(to));
+        if edges.len() == before {
+            break;
+        }
+    }
+
+    Some(SteinerTree::from_edges(edges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generate::{erdos_renyi, random_weights};
+    use crate::util::{Rng, SplitMix64};
+
+    // weight of a minimum spanning tree of the vertices in `mask`, `None` if they are not connected
+    fn spanning_weight(graph: &Graph<u64>, mask: usize) -> Option<u64> {
+        let mut adjacency: HashMap<usize, Vec<(usize, u64)>> = HashMap::new();
+        for vertex in (0..graph.vertex_count()).filter(|vertex| mask & 1 << vertex != 0) {
+            let edges = graph
+                .neighbors(vertex)
+                .iter()
+                .filter(|edge| mask & 1 << edge.to != 0)
+                .map(|edge| (edge.to, edge.weight))
+                .collect();
+            adjacency.insert(vertex, edges);
+        }
+        let tree = prim(&adjacency, mask.trailing_zeros() as usize);
+        if tree.len() + 1 != mask.count_ones() as usize {
+            return None;
+        }
+
+        Some(tree.iter().map(|(_, _, weight)| weight).sum())
+    }
+
+    // a minimum Steiner tree spans the terminals and some other vertices
+    fn brute_force(graph: &Graph<u64>, terminals: &[usize]) -> Option<u64> {
+        let required: usize = terminals.iter().map(|terminal| 1 << terminal).sum();
+        (0usize..1 << graph.vertex_count())
+            .filter(|mask| mask & required == required)
+            .filter_map(|mask| spanning_weight(graph, mask))
+            .min()
+    }
+
+    // checks that `tree` is a tree of graph edges that connects the terminals
+    fn is_steiner_tree(graph: &Graph<u64>, terminals: &[usize], tree: &SteinerTree<u64>) -> bool {
+        let mut adjacency: HashMap<usize, Vec<(usize, u64)>> = HashMap::new();
+        for (from, to, weight) in &tree.edges {
+            if !graph
+                .neighbors(*from)
+                .iter()
+                .any(|edge| edge.to == *to && edge.weight == *weight)
+            {
+                return false;
+            }
+            adjacency.entry(*from).or_default().push((*to, *weight));
+            adjacency.entry(*to).or_default().push((*from, *weight));
+        }
+        if tree.edges.is_empty() {
+            return terminals.len() <= 1;
+        }
+
+        let spanning = prim(&adjacency, terminals[0]);
+        spanning.len() == tree.edges.len()
+            && spanning.len() + 1 == adjacency.len()
+            && terminals
+                .iter()
+                .all(|terminal| adjacency.contains_key(terminal))
+            && tree.weight == tree.edges.iter().map(|(_, _, weight)| weight).sum::<u64>()
+    }
+
+    #[test]
+    fn graph_steiner_matches_brute_force() {
+        let mut rng = SplitMix64::init(30);
+        for round in 0..40 {
+            let graph = erdos_renyi(10, 0.35, false, &mut rng);
+            let graph = random_weights(&graph, 1, 10, &mut rng);
+            let terminals: Vec<usize> = (0..2 + round % 4)
+                .map(|_| rng.next_below(10) as usize)
+                .collect();
+
+            let exact = dreyfus_wagner(&graph, &terminals);
+            let approximate = mst_approximation(&graph, &terminals);
+            let mut distinct = terminals.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            match brute_force(&graph, &distinct) {
+                None => assert!(exact.is_none() && approximate.is_none()),
+                Some(optimum) => {
+                    let (exact, approximate) = (exact.unwrap(), approximate.unwrap());
+                    assert!(is_steiner_tree(&graph, &distinct, &exact));
+                    assert!(is_steiner_tree(&graph, &distinct, &approximate));
+                    assert_eq!(exact.weight, optimum);
+                    assert!(approximate.weight >= optimum && approximate.weight <= 2 * optimum);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn graph_steiner_trivial_terminals() {
+        let mut graph = Graph::init_undirected(3);
+        graph.add_edge(0, 1, 2u64);
+        graph.add_edge(0, 1, 1);
+
+        let empty = SteinerTree {
+            edges: Vec::new(),
+            weight: 0,
+        };
+        assert_eq!(dreyfus_wagner(&graph, &[]), Some(empty.clone()));
+        assert_eq!(mst_approximation(&graph, &[2, 2]), Some(empty));
+        assert_eq!(
+            dreyfus_wagner(&graph, &[1, 0]).unwrap().edges,
+            vec![(0, 1, 1)]
+        );
+        assert_eq!(
+            mst_approximation(&graph, &[1, 0]).unwrap().edges,
+            vec![(0, 1, 1)]
+        );
+        assert_eq!(dreyfus_wagner(&graph, &[0, 2]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Graph must be undirected")]
+    fn graph_steiner_panic_directed() {
+        dreyfus_wagner(&Graph::<u64>::init_directed(2), &[0, 1]);
+    }
+}
@@ -0,0 +1,536 @@
+//! Travelling salesman solvers on complete graphs given as distance matrices, where `distances[i][j]` is the length
+//! of the edge from `i` to `j`. Tours visit every vertex once and return to their start
+
+use super::mst::dense_minimum_spanning_tree;
+use super::Weight;
+
+// largest number of vertices held_karp accepts, its table has n 2^(n - 1) entries
+const HELD_KARP_LIMIT: usize = 20;
+
+// largest number of odd vertices christofides matches exactly, the table has 2^k entries
+const MATCHING_LIMIT: usize = 20;
+
+/// A closed tour through every vertex
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<W> {
+    /// vertices in the order they are visited, the tour returns from the last one to the first one
+    pub vertices: Vec<usize>,
+
+    /// total length of the tour
+    pub length: W,
+}
+
+fn check_matrix<W>(distances: &[Vec<W>]) {
+    if distances.iter().any(|row| row.len() != distances.len()) {
+        panic!("Distance matrix must be square");
+    }
+}
+
+fn tour_length<W: Weight>(distances: &[Vec<W>], vertices: &[usize]) -> W {
+    (0..vertices.len()).fold(W::zero(), |length, index| {
+        length + distances[vertices[index]][vertices[(index + 1) % vertices.len()]]
+    })
+}
+
+/// Returns a shortest tour, computed exactly by the Held-Karp dynamic program over subsets of vertices
+/// in O(2^n n^2) time and O(2^n n) space
+///
+/// # Arguments
+/// * `distances`: the distance matrix
+///
+/// # Panics
+/// * panics if `distances` is not square
+/// * panics if there are more than 20 vertices
+///
+/// # Examples
+/// ```
+/// use rudac::graph::tsp::held_karp;
+///
+/// let distances = vec![
+///     vec![0, 2, 9, 10],
+///     vec![1, 0, 6, 4],
+///     vec![15, 7, 0, 8],
+///     vec![6, 3, 12, 0],
+/// ];
+///
+/// let tour = held_karp(&distances);
+///
+/// assert_eq!(tour.length, 21);
+/// assert_eq!(tour.vertices, vec![0, 2, 3, 1]);
+/// ```
+pub fn held_karp<W: Weight>(distances: &[Vec<W>]) -> Tour<W> {
+    check_matrix(distances);
+    let vertices = distances.len();
+    if vertices > HELD_KARP_LIMIT {
+        panic!("Held-Karp supports at most 20 vertices");
+    }
+    if vertices <= 1 {
+        return Tour {
+            vertices: (0..vertices).collect(),
+            length: W::zero(),
+        };
+    }
+
+    // shortest path from 0 through the vertices of `subset` (bit i - 1 for vertex i), ending at vertex `last` of it
+    let others = vertices - 1;
+    let index = |subset: usize, last: usize| subset * others + last - 1;
+    let mut table = vec![W::zero(); (1 << others) * others];
+    for subset in 1usize..1 << others {
+        for last in (1..vertices).filter(|last| subset & 1 << (last - 1) != 0) {
+            let rest = subset & !(1 << (last - 1));
+            table[index(subset, last)] = if rest == 0 {
+                distances[0][last]
+            } else {
+                (1..vertices)
+                    .filter(|previous| rest & 1 << (previous - 1) != 0)
+                    .map(|previous| table[index(rest, previous)] + distances[previous][last])
+                    .min()
+                    .unwrap()
+            };
+        }
+    }
+
+    // walks the table back from the full subset
+    let mut subset = (1 << others) - 1;
+    let mut last = (1..vertices)
+        .min_by_key(|last| table[index(subset, *last)] + distances[*last][0])
+        .unwrap();
+    let length = table[index(subset, last)] + distances[last][0];
+    let mut tour = vec![last];
+    while subset & !(1 << (last - 1)) != 0 {
+        let rest = subset & !(1 << (last - 1));
+        let previous = (1..vertices)
+            .filter(|previous| rest & 1 << (previous - 1) != 0)
+            .find(|previous| {
+                table[index(rest, *previous)] + distances[*previous][last]
+                    == table[index(subset, last)]
+            })
+            .unwrap();
+        tour.push(previous);
+        subset = rest;
+        last = previous;
+    }
+    tour.push(0);
+    tour.reverse();
+
+    Tour {
+        vertices: tour,
+        length,
+    }
+}
+
+/// Returns the tour that starts at `start` and always moves to the nearest unvisited vertex. Runs in O(n^2) time,
+/// the tour can be up to O(log n) times longer than a shortest one on metric instances
+///
+/// # Arguments
+/// * `distances`: the distance matrix
+/// * `start`: first vertex of the tour
+///
+/// # Panics
+/// * panics if `distances` is not square
+/// * panics if `start` does not exist
+///
+/// # Examples
+/// ```
+/// use rudac::graph::tsp::nearest_neighbor;
+///
+/// // points 0, 1, 3 and 7 on a line
+/// let points: [i64; 4] = [0, 7, 1, 3];
+/// let distances: Vec<Vec<i64>> = points.iter().map(|a| points.iter().map(|b| (a - b).abs()).collect()).collect();
+///
+/// let tour = nearest_neighbor(&distances, 0);
+///
+/// assert_eq!(tour.vertices, vec![0, 2, 3, 1]);
+/// assert_eq!(tour.length, 14);
+/// ```
+pub fn nearest_neighbor<W: Weight>(distances: &[Vec<W>], start: usize) -> Tour<W> {
+    check_matrix(distances);
+    if start >= distances.len() {
+        panic!("Vertex does not exist");
+    }
+
+    let mut visited = vec![false; distances.len()];
+    let mut vertices = vec![start];
+    visited[start] = true;
+    for _ in 1..distances.len() {
+        let current = *vertices.last().unwrap();
+        let next = (0..distances.len())
+            .filter(|vertex| !visited[*vertex])
+            .min_by_key(|vertex| distances[current][*vertex])
+            .unwrap();
+        visited[next] = true;
+        vertices.push(next);
+    }
+
+    let length = tour_length(distances, &vertices);
+    Tour { vertices, length }
+}
+
+/// Improves `tour` with 2-opt moves, reversing a section of the tour whenever that replaces two edges by two
+/// shorter ones, until no such move is left. Every pass over the pairs of edges takes O(n^2) time
+///
+/// # Arguments
+/// * `distances`: the distance matrix, symmetric
+/// * `tour`: vertices of a tour, every vertex exactly once
+///
+/// # Panics
+/// * panics if `distances` is not square
+/// * panics if `tour` does not visit every vertex exactly once
+///
+/// # Examples
+/// ```
+/// use rudac::graph::tsp::two_opt;
+///
+/// // corners of a square, the tour 0 2 1 3 crosses itself
+/// let points: [(u32, u32); 4] = [(0, 0), (0, 1), (1, 1), (1, 0)];
+/// let distances: Vec<Vec<u32>> = points
+///     .iter()
+///     .map(|(x, y)| points.iter().map(|(a, b)| x.abs_diff(*a) + y.abs_diff(*b)).collect())
+///     .collect();
+///
+/// let tour = two_opt(&distances, &[0, 2, 1, 3]);
+///
+/// assert_eq!(tour.length, 4);
+/// ```
+pub fn two_opt<W: Weight>(distances: &[Vec<W>], tour: &[usize]) -> Tour<W> {
+    check_matrix(distances);
+    let mut seen = vec![false; distances.len()];
+    if tour.len() != distances.len()
+        || tour
+            .iter()
+            .any(|vertex| *vertex >= seen.len() || std::mem::replace(&mut seen[*vertex], true))
+    {
+        panic!("Tour must visit every vertex exactly once");
+    }
+
+    let mut vertices = tour.to_vec();
+    let count = vertices.len();
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for first in 0..count {
+            for second in first + 2..count {
+                if first == 0 && second == count - 1 {
+                    continue;
+                }
+
+                // reversing vertices[first + 1..=second] replaces edges a b and c d by a c and b d
+                let (a, b) = (vertices[first], vertices[first + 1]);
+                let (c, d) = (vertices[second], vertices[(second + 1) % count]);
+                if distances[a][c] + distances[b][d] < distances[a][b] + distances[c][d] {
+                    vertices[first + 1..=second].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    let length = tour_length(distances, &vertices);
+    Tour { vertices, length }
+}
+
+/// Returns a tour built by Christofides' algorithm: a minimum spanning tree, a minimum weight perfect matching of
+/// its odd degree vertices and an Euler tour of both, with repeated vertices skipped. For symmetric distances that
+/// satisfy the triangle inequality the tour is at most 1.5 times longer than a shortest one.
+///
+/// The matching is exact, by a dynamic program over subsets, for up to 20 odd degree vertices. Beyond that the
+/// closest remaining pairs are matched greedily, which can be far heavier than a minimum matching, so the 1.5 bound
+/// no longer holds. In that case the preorder walk of the spanning tree, the double tree tour which is at most twice
+/// as long as a shortest tour, is built as well and the shorter of the two tours is returned: the bound becomes 2
+/// * Complexity: O(n^2 + 2^k k) for k <= 20 odd degree vertices of the spanning tree, O(n^2 log n) beyond
+///
+/// # Arguments
+/// * `distances`: the distance matrix, symmetric
+///
+/// # Panics
+/// * panics if `distances` is not square
+///
+/// # Examples
+/// ```
+/// use rudac::graph::tsp::{christofides, held_karp};
+///
+/// // manhattan distances between points on a grid
+/// let points: [(u32, u32); 6] = [(0, 0), (2, 1), (5, 0), (4, 3), (1, 4), (3, 5)];
+/// let distances: Vec<Vec<u32>> = points
+///     .iter()
+///     .map(|(x, y)| points.iter().map(|(a, b)| x.abs_diff(*a) + y.abs_diff(*b)).collect())
+///     .collect();
+///
+/// let tour = christofides(&distances);
+///
+/// assert!(2 * tour.length <= 3 * held_karp(&distances).length);
+/// ```
+pub fn christofides<W: Weight>(distances: &[Vec<W>]) -> Tour<W> {
+    check_matrix(distances);
+    let vertices = distances.len();
+    if vertices <= 1 {
+        return Tour {
+            vertices: (0..vertices).collect(),
+            length: W::zero(),
+        };
+    }
+
+    let tree = dense_minimum_spanning_tree(distances);
+    let mut adjacency = vec![Vec::new(); vertices];
+    for (parent, child, _) in &tree.edges {
+        adjacency[*parent].push(*child);
+        adjacency[*child].push(*parent);
+    }
+
+    let odd: Vec<usize> = (0..vertices)
+        .filter(|vertex| adjacency[*vertex].len() % 2 == 1)
+        .collect();
+    let exact = odd.len() <= MATCHING_LIMIT;
+
+    // the double tree tour backs up a greedy matching, it visits the vertices of the tree in preorder
+    let double_tree: Option<Vec<usize>> = if exact {
+        None
+    } else {
+        let mut children = vec![Vec::new(); vertices];
+        for (parent, child, _) in &tree.edges {
+            children[*parent].push(*child);
+        }
+        let mut preorder = Vec::with_capacity(vertices);
+        let mut stack = vec![0];
+        while let Some(vertex) = stack.pop() {
+            preorder.push(vertex);
+            stack.extend(children[vertex].iter().rev());
+        }
+        Some(preorder)
+    };
+
+    let matching = if exact {
+        perfect_matching(distances, &odd)
+    } else {
+        greedy_matching(distances, &odd)
+    };
+    for (first, second) in matching {
+        adjacency[first].push(second);
+        adjacency[second].push(first);
+    }
+
+    // Hierholzer's algorithm, every edge is used once from each of its two lists
+    let mut used: Vec<Vec<bool>> = adjacency
+        .iter()
+        .map(|edges| vec![false; edges.len()])
+        .collect();
+    let mut next_edge = vec![0; vertices];
+    let mut stack = vec![0];
+    let mut circuit = Vec::new();
+    while let Some(vertex) = stack.last().copied() {
+        while next_edge[vertex] < adjacency[vertex].len() && used[vertex][next_edge[vertex]] {
+            next_edge[vertex] += 1;
+        }
+        if next_edge[vertex] == adjacency[vertex].len() {
+            circuit.push(stack.pop().unwrap());
+            continue;
+        }
+
+        let to = adjacency[vertex][next_edge[vertex]];
+        used[vertex][next_edge[vertex]] = true;
+        let back = (0..adjacency[to].len())
+            .find(|index| adjacency[to][*index] == vertex && !used[to][*index])
+            .unwrap();
+        used[to][back] = true;
+        stack.push(to);
+    }
+
+    let mut visited = vec![false; vertices];
+    let tour: Vec<usize> = circuit
+        .into_iter()
+        .rev()
+        .filter(|vertex| !std::mem::replace(&mut visited[*vertex], true))
+        .collect();
+    let length = tour_length(distances, &tour);
+    if let Some(double_tree) = double_tree {
+        let double_length = tour_length(distances, &double_tree);
+        if double_length < length {
+            return Tour {
+                vertices: double_tree,
+                length: double_length,
+            };
+        }
+    }
+
+    Tour {
+        vertices: tour,
+        length,
+    }
+}
+
+// pairs up `vertices`, of which there is an even number, closest remaining pairs first
+fn greedy_matching<W: Weight>(distances: &[Vec<W>], vertices: &[usize]) -> Vec<(usize, usize)> {
+    let count = vertices.len();
+    let mut pairs: Vec<(W, usize, usize)> = (0..count)
+        .flat_map(|first| (first + 1..count).map(move |second| (first, second)))
+        .map(|(first, second)| (distances[vertices[first]][vertices[second]], first, second))
+        .collect();
+    pairs.sort();
+    let mut matched = vec![false; count];
+    pairs
+        .into_iter()
+        .filter(|(_, first, second)| {
+            if matched[*first] || matched[*second] {
+                return false;
+            }
+            matched[*first] = true;
+            matched[*second] = true;
+            true
+        })
+        .map(|(_, first, second)| (vertices[first], vertices[second]))
+        .collect()
+}
+
+// pairs up `vertices`, of which there are an even number and at most MATCHING_LIMIT, with minimum total distance
+fn perfect_matching<W: Weight>(distances: &[Vec<W>], vertices: &[usize]) -> Vec<(usize, usize)> {
+    let count = vertices.len();
+
+    // cost of matching the vertices of every subset, the lowest vertex of a subset is matched first
+    let full = (1usize << count) - 1;
+    let mut table: Vec<Option<W>> = vec![None; 1 << count];
+    table[0] = Some(W::zero());
+    for subset in 1..=full {
+        if subset.count_ones() % 2 == 1 {
+            continue;
+        }
+        let first = subset.trailing_zeros() as usize;
+        table[subset] = (first + 1..count)
+            .filter(|second| subset & 1 << second != 0)
+            .filter_map(|second| {
+                let rest = table[subset & !(1 << first) & !(1 << second)]?;
+                Some(rest + distances[vertices[first]][vertices[second]])
+            })
+            .min();
+    }
+
+    let mut pairs = Vec::new();
+    let mut subset = full;
+    while subset != 0 {
+        let first = subset.trailing_zeros() as usize;
+        let second = (first + 1..count)
+            .filter(|second| subset & 1 << second != 0)
+            .find(|second| {
+                let rest = table[subset & !(1 << first) & !(1 << second)];
+                rest.map(|rest| rest + distances[vertices[first]][vertices[*second]])
+                    == table[subset]
+            })
+            .unwrap();
+        pairs.push((vertices[first], vertices[second]));
+        subset &= !(1 << first) & !(1 << second);
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn random_points(count: usize, rng: &mut SplitMix64) -> Vec<Vec<u64>> {
+        let points: Vec<(u64, u64)> = (0..count)
+            .map(|_| (rng.next_below(100), rng.next_below(100)))
+            .collect();
+        points
+            .iter()
+            .map(|(x, y)| {
+                points
+                    .iter()
+                    .map(|(a, b)| x.abs_diff(*a) + y.abs_diff(*b))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // shortest tour by trying every order of the vertices after vertex 0
+    fn brute_force(distances: &[Vec<u64>]) -> u64 {
+        fn extend(distances: &[Vec<u64>], tour: &mut Vec<usize>, best: &mut u64) {
+            if tour.len() == distances.len() {
+                *best = u64::min(*best, tour_length(distances, tour));
+                return;
+            }
+            for vertex in 1..distances.len() {
+                if !tour.contains(&vertex) {
+                    tour.push(vertex);
+                    extend(distances, tour, best);
+                    tour.pop();
+                }
+            }
+        }
+
+        let mut best = u64::MAX;
+        extend(distances, &mut vec![0], &mut best);
+        best
+    }
+
+    fn is_tour(tour: &Tour<u64>, distances: &[Vec<u64>]) -> bool {
+        let mut vertices = tour.vertices.clone();
+        vertices.sort_unstable();
+        vertices == (0..distances.len()).collect::<Vec<usize>>()
+            && tour_length(distances, &tour.vertices) == tour.length
+    }
+
+    #[test]
+    fn graph_tsp_held_karp_is_optimal() {
+        let mut rng = SplitMix64::init(20);
+        for count in 2..9 {
+            // asymmetric random distances
+            let distances: Vec<Vec<u64>> = (0..count)
+                .map(|_| (0..count).map(|_| rng.next_below(50)).collect())
+                .collect();
+            let tour = held_karp(&distances);
+            assert!(is_tour(&tour, &distances));
+            assert_eq!(tour.length, brute_force(&distances));
+        }
+
+        assert_eq!(held_karp::<u64>(&[]).vertices, Vec::<usize>::new());
+        assert_eq!(held_karp(&[vec![0u64]]).vertices, vec![0]);
+    }
+
+    #[test]
+    fn graph_tsp_heuristics_on_metric_instances() {
+        let mut rng = SplitMix64::init(21);
+        for count in 1..11 {
+            let distances = random_points(count, &mut rng);
+            let optimum = held_karp(&distances).length;
+
+            let nearest = nearest_neighbor(&distances, count - 1);
+            assert!(is_tour(&nearest, &distances));
+            let improved = two_opt(&distances, &nearest.vertices);
+            assert!(is_tour(&improved, &distances));
+            assert!(improved.length <= nearest.length);
+
+            let christofides = christofides(&distances);
+            assert!(is_tour(&christofides, &distances));
+            assert!(2 * christofides.length <= 3 * optimum);
+        }
+    }
+
+    #[test]
+    fn graph_tsp_matching_beyond_limit() {
+        // 60 points give more odd vertices than the exact matching handles, so the greedy matching is used and the
+        // tour is no longer than the double tree tour, at most twice the spanning tree
+        let mut rng = SplitMix64::init(22);
+        for _ in 0..5 {
+            let distances = random_points(60, &mut rng);
+            let tree = dense_minimum_spanning_tree(&distances);
+            let mut degrees = [0; 60];
+            for (parent, child, _) in &tree.edges {
+                degrees[*parent] += 1;
+                degrees[*child] += 1;
+            }
+            assert!(degrees.iter().filter(|degree| *degree % 2 == 1).count() > MATCHING_LIMIT);
+
+            let tour = christofides(&distances);
+            assert!(is_tour(&tour, &distances));
+            assert!(tour.length <= 2 * tree.weight);
+            assert!(two_opt(&distances, &tour.vertices).length <= tour.length);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Tour must visit every vertex exactly once")]
+    fn graph_tsp_panic_invalid_tour() {
+        two_opt(&[vec![0u64, 1], vec![1, 0]], &[1, 1]);
+    }
+}
@@ -0,0 +1,21 @@
+use std::ops::Add;
+
+/// Edge weights that can be added up along paths and compared. Implemented for the primitive integer types
+pub trait Weight: Copy + Ord + Add<Output = Self> {
+    /// Returns the weight of an empty path
+    fn zero() -> Self;
+}
+
+macro_rules! impl_weight {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                fn zero() -> Self {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_weight!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
@@ -0,0 +1,363 @@
+use super::DaryHeap;
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::{Add, Sub};
+
+/// A min heap of items with priorities where every priority can be shifted by the same amount in O(1) time.
+/// Priorities are stored relative to a global offset, so a shift only changes the offset and never touches the
+/// items. The item with the smallest priority is kept apart with its actual priority, which a shift updates too, so
+/// it can be lent out as a pair. Schedulers use this to age waiting tasks: lowering every priority favors the tasks
+/// that already waited over the ones pushed later. The default value of `P` is taken as zero, and since pushing
+/// subtracts the offset, `P` is usually a signed type
+///
+/// # Examples
+/// ```
+/// use rudac::heap::AgingHeap;
+///
+/// let mut heap = AgingHeap::init();
+/// heap.push(10, "old task");
+///
+/// // every waiting task gets 8 closer to the front
+/// heap.shift(-8);
+/// heap.push(5, "new task");
+///
+/// assert_eq!(heap.pop(), Some((2, "old task")));
+/// assert_eq!(heap.pop(), Some((5, "new task")));
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AgingHeap<P, T> {
+    // the item with the smallest priority and its actual priority, None only if the heap is empty
+    top: Option<(P, T)>,
+
+    // binary heap of the other items, by priority minus the offset at the time of the push
+    heap: DaryHeap<(P, T), 2>,
+    offset: P,
+}
+
+// order of the binary heap, by priority alone
+fn by_priority<P: Ord, T>(item1: &(P, T), item2: &(P, T)) -> Ordering {
+    item1.0.cmp(&item2.0)
+}
+
+impl<P, T> AgingHeap<P, T>
+where
+    P: Copy + Ord + Default + Add<Output = P> + Sub<Output = P>,
+{
+    /// Initializes an empty heap
+    pub fn init() -> AgingHeap<P, T> {
+        AgingHeap {
+            top: None,
+            heap: DaryHeap::from_vec_ordered(Vec::new(), Order::Pointer(by_priority)),
+            offset: P::default(),
+        }
+    }
+
+    /// Returns number of items in the heap
+    pub fn size(&self) -> usize {
+        self.heap.size() + self.top.is_some() as usize
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.top.is_none()
+    }
+
+    /// Returns the total shift applied to the heap so far
+    pub fn offset(&self) -> P {
+        self.offset
+    }
+
+    /// Adds `delta` to the priority of every item in the heap
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `delta`: amount added to every priority
+    pub fn shift(&mut self, delta: P) {
+        self.offset = self.offset + delta;
+        if let Some(top) = self.top.as_mut() {
+            top.0 = top.0 + delta;
+        }
+    }
+
+    /// Pushes `item` with priority `priority` into the heap
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `priority`: current priority of the item
+    /// * `item`: data to be pushed
+    pub fn push(&mut self, priority: P, item: T) {
+        match self.top.as_mut() {
+            Some(top) if priority < top.0 => {
+                let (priority, item) = core::mem::replace(top, (priority, item));
+                self.heap.push((priority - self.offset, item));
+            }
+            Some(_) => self.heap.push((priority - self.offset, item)),
+            None => self.top = Some((priority, item)),
+        }
+    }
+
+    /// Returns the smallest priority and a reference to its item, None if the heap is empty
+    pub fn peek(&self) -> Option<(P, &T)> {
+        self.top.as_ref().map(|(priority, item)| (*priority, item))
+    }
+
+    // the item with the smallest priority along with its priority
+    pub(crate) fn top(&self) -> Option<&(P, T)> {
+        self.top.as_ref()
+    }
+
+    /// Removes and returns the item with the smallest priority along with its priority, None if the heap is empty
+    /// * Complexity: O(log n)
+    pub fn pop(&mut self) -> Option<(P, T)> {
+        let top = self.top.take()?;
+        let offset = self.offset;
+        self.top = self
+            .heap
+            .pop()
+            .map(|(priority, item)| (priority + offset, item));
+
+        Some(top)
+    }
+
+    /// Moves every item of `other` into this heap, keeping the priorities they have in `other`. The items of `other`
+    /// are rebased on the offset of this heap and appended, then the binary heap is rebuilt bottom up
+    /// * Complexity: O(n + m) for m items in `other`
+    ///
+    /// # Arguments
+    /// * `other`: the heap to be melded into this one
+    pub fn meld(&mut self, other: AgingHeap<P, T>) {
+        let (offset, other_offset) = (self.offset, other.offset);
+        let mut items = core::mem::replace(
+            &mut self.heap,
+            DaryHeap::from_vec_ordered(Vec::new(), Order::Pointer(by_priority)),
+        )
+        .into_vec();
+        items.extend(
+            self.top
+                .take()
+                .into_iter()
+                .chain(other.top)
+                .map(|(priority, item)| (priority - offset, item)),
+        );
+        items.extend(
+            other
+                .heap
+                .into_iter()
+                .map(|(priority, item)| (priority + other_offset - offset, item)),
+        );
+        self.heap = DaryHeap::from_vec_ordered(items, Order::Pointer(by_priority));
+        self.top = self
+            .heap
+            .pop()
+            .map(|(priority, item)| (priority + offset, item));
+    }
+
+    /// Removes every item of the heap and resets the offset to zero
+    pub fn clear(&mut self) {
+        self.top = None;
+        self.heap.clear();
+        self.offset = P::default();
+    }
+
+    /// Returns an iterator over the priorities and references to the items of the heap in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (P, &T)> {
+        let offset = self.offset;
+        self.top
+            .iter()
+            .map(|(priority, item)| (*priority, item))
+            .chain(
+                self.heap
+                    .iter()
+                    .map(move |(priority, item)| (*priority + offset, item)),
+            )
+    }
+
+    /// Consumes the heap and returns an iterator over its items and their priorities from the smallest priority
+    /// to the largest
+    /// * Complexity: O(log n) per item
+    pub fn into_iter_sorted(mut self) -> impl Iterator<Item = (P, T)> {
+        core::iter::from_fn(move || self.pop())
+    }
+
+    /// Removes every item of the heap and returns an iterator over them and their priorities from the smallest
+    /// priority to the largest. The heap is empty even if the iterator is not consumed, and keeps its offset
+    /// * Complexity: O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::AgingHeap;
+    ///
+    /// let mut heap: AgingHeap<i32, char> = vec![(3, 'c'), (1, 'a'), (2, 'b')].into_iter().collect();
+    /// heap.shift(10);
+    ///
+    /// assert_eq!(heap.drain_sorted().collect::<Vec<_>>(), vec![(11, 'a'), (12, 'b'), (13, 'c')]);
+    /// assert!(heap.is_empty());
+    /// assert_eq!(heap.offset(), 10);
+    /// ```
+    pub fn drain_sorted(&mut self) -> impl Iterator<Item = (P, T)> {
+        AgingHeap {
+            top: self.top.take(),
+            heap: core::mem::replace(
+                &mut self.heap,
+                DaryHeap::from_vec_ordered(Vec::new(), Order::Pointer(by_priority)),
+            ),
+            offset: self.offset,
+        }
+        .into_iter_sorted()
+    }
+}
+
+impl<P, T> core::iter::FromIterator<(P, T)> for AgingHeap<P, T>
+where
+    P: Copy + Ord + Default + Add<Output = P> + Sub<Output = P>,
+{
+    fn from_iter<I: IntoIterator<Item = (P, T)>>(iter: I) -> Self {
+        let mut heap = AgingHeap::init();
+        heap.extend(iter);
+
+        heap
+    }
+}
+
+impl<P, T> Extend<(P, T)> for AgingHeap<P, T>
+where
+    P: Copy + Ord + Default + Add<Output = P> + Sub<Output = P>,
+{
+    fn extend<I: IntoIterator<Item = (P, T)>>(&mut self, iter: I) {
+        for (priority, item) in iter {
+            self.push(priority, item);
+        }
+    }
+}
+
+impl<P, T> Default for AgingHeap<P, T>
+where
+    P: Copy + Ord + Default + Add<Output = P> + Sub<Output = P>,
+{
+    fn default() -> Self {
+        AgingHeap::init()
+    }
+}
+
+impl<P, T> MemoryFootprint for AgingHeap<P, T> {
+    fn footprint(&self) -> Footprint {
+        self.heap.footprint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn heap_aging_matches_shifted_list() {
+        let mut rng = SplitMix64::init(90);
+        let mut heap = AgingHeap::init();
+        let mut items: Vec<(i64, usize)> = Vec::new();
+        for step in 0..2000 {
+            match rng.next_below(4) {
+                0 | 1 => {
+                    let priority = rng.next_below(1000) as i64 - 500;
+                    heap.push(priority, step);
+                    items.push((priority, step));
+                }
+                2 => {
+                    let delta = rng.next_below(21) as i64 - 10;
+                    heap.shift(delta);
+                    for item in items.iter_mut() {
+                        item.0 += delta;
+                    }
+                }
+                _ => {
+                    let smallest = items.iter().map(|item| item.0).min();
+                    let popped = heap.pop();
+                    assert_eq!(popped.map(|item| item.0), smallest);
+                    if let Some(popped) = popped {
+                        let index = items.iter().position(|item| *item == popped).unwrap();
+                        items.swap_remove(index);
+                    }
+                }
+            }
+            assert_eq!(heap.size(), items.len());
+            assert_eq!(
+                heap.peek().map(|item| item.0),
+                items.iter().map(|item| item.0).min()
+            );
+        }
+    }
+
+    #[test]
+    fn heap_aging_meld_keeps_priorities() {
+        let mut first = AgingHeap::init();
+        first.push(4, 'a');
+        first.shift(10);
+
+        let mut second = AgingHeap::init();
+        second.push(20, 'b');
+        second.push(1, 'c');
+        second.shift(-5);
+
+        first.meld(second);
+
+        assert_eq!(first.offset(), 10);
+        assert_eq!(first.pop(), Some((-4, 'c')));
+        assert_eq!(first.pop(), Some((14, 'a')));
+        assert_eq!(first.pop(), Some((15, 'b')));
+        assert!(first.is_empty());
+
+        first.clear();
+        assert_eq!(first.offset(), 0);
+
+        // melding rebuilds the heap, which must still pop in order
+        let mut rng = SplitMix64::init(91);
+        let mut melded = AgingHeap::init();
+        let mut expected = Vec::new();
+        for _ in 0..20 {
+            let mut other = AgingHeap::init();
+            for _ in 0..rng.next_below(50) {
+                let priority = rng.next_below(1000) as i64;
+                other.push(priority, ());
+                expected.push(priority);
+            }
+            let delta = rng.next_below(11) as i64 - 5;
+            other.shift(delta);
+            for priority in expected.iter_mut().skip(melded.size()) {
+                *priority += delta;
+            }
+            melded.meld(other);
+        }
+        expected.sort();
+        assert_eq!(
+            melded
+                .into_iter_sorted()
+                .map(|item| item.0)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn heap_aging_iterators() {
+        let mut heap: AgingHeap<i64, char> = vec![(5, 'a'), (-2, 'b')].into_iter().collect();
+        heap.shift(3);
+        heap.extend(vec![(4, 'c')]);
+
+        let mut items: Vec<(i64, char)> = heap
+            .iter()
+            .map(|(priority, &item)| (priority, item))
+            .collect();
+        items.sort();
+        assert_eq!(items, vec![(1, 'b'), (4, 'c'), (8, 'a')]);
+
+        assert_eq!(
+            heap.clone().into_iter_sorted().collect::<Vec<_>>(),
+            vec![(1, 'b'), (4, 'c'), (8, 'a')]
+        );
+        assert_eq!(heap.drain_sorted().count(), 3);
+        assert!(heap.is_empty());
+        assert_eq!(heap.offset(), 3);
+    }
+}
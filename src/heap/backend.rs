@@ -0,0 +1,621 @@
+use super::{
+    AgingHeap, BinomialHeap, BoundedHeap, DaryHeap, FibonacciHeap, IndexedHeap, LazyDeleteHeap,
+    LazyEntry, MeldableHeap, MinMax, PairingHeap, RadixHeap, StableHeap,
+};
+use crate::util::Rng;
+use alloc::collections::BinaryHeap;
+use core::ops::{Add, Sub};
+
+/// Operations shared by the heaps of this module, so structures like `LazyDeleteHeap`, searches or benchmarks can
+/// be written once and run over any of them. Items come out in the order of the heap: smallest first for min heaps,
+/// largest first for max heaps. `MinMax` serves as a min heap and `BinaryHeap` of the standard library as a max heap.
+/// `BoundedHeap` pops its worst kept item first and drops the worst item when it is pushed past its capacity
+///
+/// Heaps that keep a priority next to every item take pairs: `AgingHeap` and `RadixHeap` take (priority, item)
+/// pairs, and a radix heap panics on keys below the last popped one. `IndexedHeap` takes (index, priority) pairs and
+/// panics on an index already in the heap. `LazyDeleteHeap` drops the handles returned by its own `push`. Every heap
+/// of this module implements the trait except `SoftHeap`, which corrupts keys and so pops items only roughly in order
+///
+/// # Examples
+/// ```
+/// use rudac::heap::{FibonacciHeap, Heap, MinMax};
+///
+/// fn drain<H: Heap<i32>>(mut heap: H) -> Vec<i32> {
+///     for item in [3, 1, 2] {
+///         heap.push(item);
+///     }
+///     std::iter::from_fn(|| heap.pop()).collect()
+/// }
+///
+/// assert_eq!(drain(FibonacciHeap::init_max()), vec![3, 2, 1]);
+/// assert_eq!(drain(MinMax::init()), vec![1, 2, 3]);
+/// ```
+pub trait Heap<T> {
+    /// Pushes `item` into the heap
+    fn push(&mut self, item: T);
+
+    /// Removes and returns the item at the top of the heap, None if the heap is empty
+    fn pop(&mut self) -> Option<T>;
+
+    /// Returns a reference to the item at the top of the heap, None if the heap is empty
+    fn peek(&self) -> Option<&T>;
+
+    /// Returns number of items in the heap
+    fn size(&self) -> usize;
+
+    /// Returns number of items in the heap, the same as `size`
+    fn len(&self) -> usize {
+        self.size()
+    }
+
+    /// Returns true if there are no items in the heap
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+}
+
+/// Heaps that can take over every item of another heap of the same type, usually faster than pushing them one by one.
+/// `StableHeap` does not meld, since the push order of items from two heaps is undefined, and neither does
+/// `BoundedHeap`, whose capacity would have to drop items. `IndexedHeap`, `RadixHeap` and `LazyDeleteHeap` do not meld
+/// either, as their indices, last popped keys or handles would clash
+///
+/// # Examples
+/// ```
+/// use rudac::heap::{BinomialHeap, Heap, Meld, PairingHeap};
+///
+/// fn meld_all<H: Meld<i32>>(mut heaps: Vec<H>) -> Vec<i32> {
+///     let mut melded = heaps.pop().unwrap();
+///     for heap in heaps {
+///         melded.meld(heap);
+///     }
+///     std::iter::from_fn(|| melded.pop()).collect()
+/// }
+///
+/// let heaps: Vec<BinomialHeap<i32>> = vec![vec![5, 1], vec![4], vec![2, 3]]
+///     .into_iter()
+///     .map(|items| items.into_iter().collect())
+///     .collect();
+/// assert_eq!(meld_all(heaps), vec![1, 2, 3, 4, 5]);
+///
+/// let heaps = vec![PairingHeap::init_max(), PairingHeap::init_max()];
+/// assert!(meld_all(heaps).is_empty());
+/// ```
+pub trait Meld<T>: Heap<T> {
+    /// Moves every item of `other` into the heap
+    ///
+    /// # Arguments
+    /// * `other`: the heap whose items are moved
+    ///
+    /// # Panics
+    /// * panics if the heaps do not order their items the same way, like a min heap and a max heap
+    fn meld(&mut self, other: Self);
+}
+
+impl<T> Heap<T> for BinomialHeap<T> {
+    fn push(&mut self, item: T) {
+        BinomialHeap::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        BinomialHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        BinomialHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        BinomialHeap::size(self)
+    }
+}
+
+// pushing into a full bounded heap drops the worst item, which is the one at the top
+impl<T> Heap<T> for BoundedHeap<T> {
+    fn push(&mut self, item: T) {
+        BoundedHeap::push(self, item);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        BoundedHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        BoundedHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        BoundedHeap::size(self)
+    }
+}
+
+impl<T, const D: usize> Heap<T> for DaryHeap<T, D> {
+    fn push(&mut self, item: T) {
+        DaryHeap::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        DaryHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        DaryHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        DaryHeap::size(self)
+    }
+}
+
+impl<T> Heap<T> for FibonacciHeap<T> {
+    fn push(&mut self, item: T) {
+        FibonacciHeap::push(self, item);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        FibonacciHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        FibonacciHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        FibonacciHeap::size(self)
+    }
+}
+
+impl<T> Heap<T> for PairingHeap<T> {
+    fn push(&mut self, item: T) {
+        PairingHeap::push(self, item);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        PairingHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        PairingHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        PairingHeap::size(self)
+    }
+}
+
+impl<T> Heap<T> for StableHeap<T> {
+    fn push(&mut self, item: T) {
+        StableHeap::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        StableHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        StableHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        StableHeap::size(self)
+    }
+}
+
+impl<T: Ord, R: Rng> Heap<T> for MeldableHeap<T, R> {
+    fn push(&mut self, item: T) {
+        MeldableHeap::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        MeldableHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        MeldableHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        MeldableHeap::size(self)
+    }
+}
+
+// a min-max heap serves as a min heap
+impl<T: Ord> Heap<T> for MinMax<T> {
+    fn push(&mut self, item: T) {
+        MinMax::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek_min()
+    }
+
+    fn size(&self) -> usize {
+        MinMax::size(self)
+    }
+}
+
+impl<T: Ord> Heap<T> for BinaryHeap<T> {
+    fn push(&mut self, item: T) {
+        BinaryHeap::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        BinaryHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        BinaryHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<P, T> Heap<(P, T)> for AgingHeap<P, T>
+where
+    P: Copy + Ord + Default + Add<Output = P> + Sub<Output = P>,
+{
+    fn push(&mut self, (priority, item): (P, T)) {
+        AgingHeap::push(self, priority, item)
+    }
+
+    fn pop(&mut self) -> Option<(P, T)> {
+        AgingHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&(P, T)> {
+        self.top()
+    }
+
+    fn size(&self) -> usize {
+        AgingHeap::size(self)
+    }
+}
+
+impl<P> Heap<(usize, P)> for IndexedHeap<P> {
+    fn push(&mut self, (index, priority): (usize, P)) {
+        IndexedHeap::push(self, index, priority)
+    }
+
+    fn pop(&mut self) -> Option<(usize, P)> {
+        IndexedHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&(usize, P)> {
+        self.top()
+    }
+
+    fn size(&self) -> usize {
+        IndexedHeap::size(self)
+    }
+}
+
+impl<T> Heap<(u64, T)> for RadixHeap<T> {
+    fn push(&mut self, (key, item): (u64, T)) {
+        RadixHeap::push(self, key, item)
+    }
+
+    fn pop(&mut self) -> Option<(u64, T)> {
+        RadixHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&(u64, T)> {
+        self.top()
+    }
+
+    fn size(&self) -> usize {
+        RadixHeap::size(self)
+    }
+}
+
+impl<T: Ord, H: Heap<LazyEntry<T>>> Heap<T> for LazyDeleteHeap<T, H> {
+    fn push(&mut self, item: T) {
+        LazyDeleteHeap::push(self, item);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        LazyDeleteHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        LazyDeleteHeap::peek(self)
+    }
+
+    fn size(&self) -> usize {
+        LazyDeleteHeap::size(self)
+    }
+}
+
+impl<T> Meld<T> for BinomialHeap<T> {
+    fn meld(&mut self, other: Self) {
+        BinomialHeap::meld(self, other)
+    }
+}
+
+impl<T> Meld<T> for FibonacciHeap<T> {
+    fn meld(&mut self, other: Self) {
+        FibonacciHeap::meld(self, other)
+    }
+}
+
+impl<T> Meld<T> for PairingHeap<T> {
+    fn meld(&mut self, other: Self) {
+        PairingHeap::meld(self, other)
+    }
+}
+
+impl<P, T> Meld<(P, T)> for AgingHeap<P, T>
+where
+    P: Copy + Ord + Default + Add<Output = P> + Sub<Output = P>,
+{
+    fn meld(&mut self, other: Self) {
+        AgingHeap::meld(self, other)
+    }
+}
+
+impl<T, const D: usize> Meld<T> for DaryHeap<T, D> {
+    fn meld(&mut self, other: Self) {
+        DaryHeap::meld(self, other)
+    }
+}
+
+impl<T: Ord> Meld<T> for MinMax<T> {
+    fn meld(&mut self, other: Self) {
+        MinMax::meld(self, other)
+    }
+}
+
+impl<T: Ord, R: Rng> Meld<T> for MeldableHeap<T, R> {
+    fn meld(&mut self, other: Self) {
+        MeldableHeap::meld(self, other)
+    }
+}
+
+// appending moves the smaller heap into the larger one and rebuilds it, which is linear rather than logarithmic
+impl<T: Ord> Meld<T> for BinaryHeap<T> {
+    fn meld(&mut self, mut other: Self) {
+        self.append(&mut other)
+    }
+}
+
+/// Iterator over the items of a heap in the order they are popped, created by the `into_iter_sorted` and
+/// `drain_sorted` methods of the heaps
+///
+/// # Examples
+/// ```
+/// use rudac::heap::MinMax;
+///
+/// let heap: MinMax<i32> = vec![4, 1, 3, 2].into_iter().collect();
+///
+/// assert_eq!(heap.into_iter_sorted().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+/// ```
+#[derive(Debug)]
+pub struct IntoIterSorted<T, H> {
+    heap: H,
+    _item: core::marker::PhantomData<T>,
+}
+
+impl<T, H: Heap<T>> IntoIterSorted<T, H> {
+    pub(crate) fn init(heap: H) -> IntoIterSorted<T, H> {
+        IntoIterSorted {
+            heap,
+            _item: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, H: Heap<T>> Iterator for IntoIterSorted<T, H> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.size(), Some(self.heap.size()))
+    }
+}
+
+impl<T, H: Heap<T>> ExactSizeIterator for IntoIterSorted<T, H> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+    use std::cmp::Reverse;
+
+    // binomial heaps are created with a first item, default ones are min heaps
+    fn binomial_max() -> BinomialHeap<u64> {
+        let mut heap = BinomialHeap::init_max(0);
+        heap.pop();
+
+        heap
+    }
+
+    // pushes and pops random items through the trait, checking the heap against a sorted vector
+    fn matches_model<H: Heap<u64>>(mut heap: H, min: bool, seed: u64) {
+        let mut rng = SplitMix64::init(seed);
+        let mut model: Vec<u64> = Vec::new();
+        for _ in 0..2000 {
+            if rng.next_below(3) == 0 {
+                let expected = if min { model.first() } else { model.last() }.copied();
+                assert_eq!(heap.pop(), expected);
+                if expected.is_some() {
+                    model.remove(if min { 0 } else { model.len() - 1 });
+                }
+            } else {
+                let item = rng.next_below(100);
+                heap.push(item);
+                let position = model.partition_point(|&known| known <= item);
+                model.insert(position, item);
+            }
+
+            assert_eq!(heap.size(), model.len());
+            assert_eq!(heap.len(), model.len());
+            assert_eq!(heap.is_empty(), model.is_empty());
+            assert_eq!(heap.peek(), if min { model.first() } else { model.last() });
+        }
+    }
+
+    #[test]
+    fn heap_backend_all_heaps_match_model() {
+        matches_model(BinomialHeap::default(), true, 1);
+        matches_model(binomial_max(), false, 2);
+
+        // large enough to keep every item, a bounded heap of the largest items pops the smallest first
+        matches_model(BoundedHeap::init_max(2000), true, 22);
+        matches_model(BoundedHeap::init_min(2000), false, 23);
+        matches_model(DaryHeap::<u64, 4>::init_min(), true, 3);
+        matches_model(DaryHeap::<u64, 2>::init_max(), false, 4);
+        matches_model(FibonacciHeap::init_min(), true, 5);
+        matches_model(FibonacciHeap::init_max(), false, 6);
+        matches_model(PairingHeap::init_min(), true, 7);
+        matches_model(PairingHeap::init_max(), false, 8);
+        matches_model(StableHeap::init_min(), true, 9);
+        matches_model(StableHeap::init_max(), false, 10);
+        matches_model(MeldableHeap::init(), true, 11);
+        matches_model(MinMax::init(), true, 12);
+        matches_model(BinaryHeap::new(), false, 13);
+        matches_model(LazyDeleteHeap::init(FibonacciHeap::init_min()), true, 14);
+        matches_model(
+            LazyDeleteHeap::init(DaryHeap::<_, 2>::init_max()),
+            false,
+            15,
+        );
+    }
+
+    // pushes random keys, never below the last popped one, with distinct ids through the trait, checking the keys
+    // against a sorted vector and that every pop returns the peeked item
+    fn matches_keyed_model<T, H>(
+        mut heap: H,
+        seed: u64,
+        entry: fn(u64, usize) -> T,
+        key: fn(&T) -> u64,
+    ) where
+        T: Clone + PartialEq + core::fmt::Debug,
+        H: Heap<T>,
+    {
+        let mut rng = SplitMix64::init(seed);
+        let mut model: Vec<u64> = Vec::new();
+        let mut last = 0;
+        for id in 0..2000 {
+            if rng.next_below(3) == 0 {
+                let peeked = heap.peek().cloned();
+                let popped = heap.pop();
+                assert_eq!(popped, peeked);
+                assert_eq!(popped.as_ref().map(key), model.first().copied());
+                if !model.is_empty() {
+                    last = model.remove(0);
+                }
+            } else {
+                let item = last + rng.next_below(100);
+                heap.push(entry(item, id));
+                let position = model.partition_point(|&known| known <= item);
+                model.insert(position, item);
+            }
+
+            assert_eq!(heap.size(), model.len());
+            assert_eq!(heap.peek().map(key), model.first().copied());
+        }
+    }
+
+    #[test]
+    fn heap_backend_keyed_heaps_match_model() {
+        matches_keyed_model(AgingHeap::init(), 16, |key, id| (key, id), |item| item.0);
+        matches_keyed_model(
+            IndexedHeap::init_min(),
+            17,
+            |key, id| (id, key),
+            |item| item.1,
+        );
+        matches_keyed_model(RadixHeap::init(), 18, |key, id| (key, id), |item| item.0);
+    }
+
+    #[test]
+    fn heap_backend_aging_heap_shifts_peeked_priority() {
+        let mut heap = AgingHeap::init();
+        Heap::push(&mut heap, (5, 'a'));
+        Heap::push(&mut heap, (3, 'b'));
+        heap.shift(-10);
+
+        assert_eq!(Heap::peek(&heap), Some(&(-7, 'b')));
+
+        let mut other = AgingHeap::init();
+        other.push(-20, 'c');
+        Meld::meld(&mut heap, other);
+
+        assert_eq!(Heap::peek(&heap), Some(&(-20, 'c')));
+        assert_eq!(Heap::len(&heap), 3);
+    }
+
+    // melds random heaps through the trait and checks that every item comes out in order
+    fn melds<H: Meld<u64>>(init: fn() -> H, min: bool, seed: u64) {
+        let mut rng = SplitMix64::init(seed);
+        let mut melded = init();
+        let mut model = Vec::new();
+        for _ in 0..20 {
+            let mut other = init();
+            for _ in 0..rng.next_below(30) {
+                let item = rng.next_below(100);
+                other.push(item);
+                model.push(item);
+            }
+            melded.meld(other);
+            assert_eq!(melded.size(), model.len());
+        }
+
+        if min {
+            model.sort_unstable();
+        } else {
+            model.sort_unstable_by_key(|&item| Reverse(item));
+        }
+        let popped: Vec<u64> = std::iter::from_fn(|| melded.pop()).collect();
+        assert_eq!(popped, model);
+    }
+
+    #[test]
+    fn heap_backend_meld() {
+        melds(BinomialHeap::default, true, 14);
+        melds(binomial_max, false, 15);
+        melds(FibonacciHeap::init_min, true, 16);
+        melds(FibonacciHeap::init_max, false, 17);
+        melds(PairingHeap::init_min, true, 18);
+        melds(PairingHeap::init_max, false, 19);
+        melds(DaryHeap::<u64, 4>::init_min, true, 24);
+        melds(DaryHeap::<u64, 2>::init_max, false, 25);
+        melds(MinMax::init, true, 26);
+        melds(MeldableHeap::init, true, 20);
+        melds(BinaryHeap::new, false, 21);
+    }
+
+    #[test]
+    fn heap_backend_bounded_drops_worst() {
+        let mut heap = BoundedHeap::init_min(3);
+        for item in [5, 1, 4, 2, 3] {
+            Heap::push(&mut heap, item);
+        }
+
+        assert_eq!(Heap::len(&heap), 3);
+        assert_eq!(Heap::peek(&heap), Some(&3));
+        let popped: Vec<i32> = std::iter::from_fn(|| Heap::pop(&mut heap)).collect();
+        assert_eq!(popped, vec![3, 2, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Both d-ary heaps must be of the same type")]
+    fn heap_backend_meld_panic_dary_order() {
+        let mut heap = DaryHeap::<u64, 3>::init_min();
+        Meld::meld(&mut heap, DaryHeap::init_max());
+    }
+
+    #[test]
+    #[should_panic(expected = "Both binomial heaps must be of the same type")]
+    fn heap_backend_meld_panic_order() {
+        let mut heap = BinomialHeap::default();
+        Meld::meld(&mut heap, binomial_max());
+    }
+}
@@ -1,4 +1,20 @@
+use super::IntoIterSorted;
+use crate::codec::{read_header, write_header, Decode, Encode, Reader, Writer};
+use crate::replay::{Op, Replayable};
 use crate::tree::BinomialTree;
+#[cfg(feature = "serde")]
+use crate::util::serialize::{deserialize_items, serialize_items, HEAP_FIELDS};
+use crate::util::stats::{self, Recorder};
+#[cfg(feature = "stats")]
+use crate::util::OpStats;
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// A binomial heap is a data structure that acts as a priority queue but also allows pairs of heaps to be merged together
 ///
@@ -26,8 +42,8 @@ use crate::tree::BinomialTree;
 ///     format!("Rank 0: \nRank 1: \nRank 2: 0 1 2 3\n")
 /// );
 /// ```
-#[derive(Debug)]
-pub struct BinomialHeap<T: std::cmp::Ord> {
+#[derive(Debug, Clone)]
+pub struct BinomialHeap<T> {
     // stores binomial trees of different ranks
     // index of the vector represents the rank of the tree
     // ex. tree at index=2 has rank=2 thus has 4 nodes in it
@@ -40,27 +56,109 @@ pub struct BinomialHeap<T: std::cmp::Ord> {
 
     // indicates wether current heap is a min heap or not
     min: bool,
+
+    // order of the items, the item that is least by it has the highest priority
+    order: Order<T>,
+
+    stats: Recorder,
 }
 
-impl<T: std::cmp::Ord> BinomialHeap<T> {
-    // initializes binomial heap based on the type specified by `min` argument
-    fn init(payload: T, min: bool) -> BinomialHeap<T> {
-        // create a binomial tree with rank 0
-        let root = Some(BinomialTree::init(payload, min));
+/// Mutable reference to the item with highest priority of a `BinomialHeap`, created by `BinomialHeap::peek_mut`.
+/// The item is pushed back into the heap when the reference is dropped
+pub struct BinomialPeekMut<'a, T> {
+    heap: &'a mut BinomialHeap<T>,
 
-        let mut roots = Vec::new();
+    // the item taken out of the heap, None once it is popped
+    item: Option<T>,
+}
 
-        // push the binomial tree into heap
-        roots.push(root);
+impl<'a, T> BinomialPeekMut<'a, T> {
+    /// Removes the item from the heap and returns it
+    ///
+    /// # Arguments
+    /// * `this`: the reference to the item
+    pub fn pop(mut this: BinomialPeekMut<'a, T>) -> T {
+        this.item.take().unwrap()
+    }
+}
 
-        BinomialHeap {
-            roots: roots,
-            size: 1,
-            candidate_root_index: 0,
-            min,
+impl<'a, T> core::ops::Deref for BinomialPeekMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item.as_ref().unwrap()
+    }
+}
+
+impl<'a, T> core::ops::DerefMut for BinomialPeekMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.item.as_mut().unwrap()
+    }
+}
+
+impl<'a, T> Drop for BinomialPeekMut<'a, T> {
+    fn drop(&mut self) {
+        if let Some(item) = self.item.take() {
+            self.heap.push(item);
         }
     }
+}
+
+/// Iterator over the items of a `BinomialHeap` in no particular order, created by `BinomialHeap::iter`
+pub struct BinomialIter<'a, T> {
+    // trees whose roots are not yielded yet
+    stack: Vec<&'a BinomialTree<T>>,
+
+    // number of items not yielded yet
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for BinomialIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let tree = self.stack.pop()?;
+        self.stack.extend(tree.children().iter().flatten());
+        self.remaining -= 1;
+
+        tree.peek_payload().as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for BinomialIter<'a, T> {}
+
+/// Iterator over the items of a `BinomialHeap` in no particular order, created by `into_iter`
+pub struct BinomialIntoIter<T> {
+    // trees whose roots are not yielded yet
+    stack: Vec<BinomialTree<T>>,
+
+    // number of items not yielded yet
+    remaining: usize,
+}
+
+impl<T> Iterator for BinomialIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let mut tree = self.stack.pop()?;
+        self.stack.extend(tree.children_mut().drain(..).flatten());
+        self.remaining -= 1;
+
+        Some(tree.get_payload())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for BinomialIntoIter<T> {}
 
+impl<T: core::cmp::Ord> BinomialHeap<T> {
     /// Initializes a min heap with the specified `payload`
     ///
     /// # Arguments:
@@ -73,7 +171,7 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
     /// let binomial_heap = BinomialHeap::init_min("rudac is awesome");
     /// ```
     pub fn init_min(payload: T) -> BinomialHeap<T> {
-        BinomialHeap::init(payload, true)
+        BinomialHeap::init(payload, true, Order::natural(true))
     }
 
     /// Initializes a max heap with the specified `payload`
@@ -88,10 +186,129 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
     /// let binomial_heap = BinomialHeap::init_max("rudac is awesome");
     /// ```
     pub fn init_max(payload: T) -> BinomialHeap<T> {
-        BinomialHeap::init(payload, false)
+        BinomialHeap::init(payload, false, Order::natural(false))
+    }
+
+    /// Builds a min heap of the items of `vector` by linking trees of equal rank, like counting to n in binary
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `vector`: items of the heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// let binomial_heap = BinomialHeap::from_vec(vec![4, 1, 3, 2, 0]);
+    ///
+    /// assert_eq!(
+    ///     BinomialHeap::preorder(&binomial_heap),
+    ///     String::from("Rank 0: 0\nRank 1: \nRank 2: 1 4 2 3\n")
+    /// );
+    /// assert_eq!(binomial_heap.into_sorted_vec(), vec![0, 1, 2, 3, 4]);
+    /// ```
+    pub fn from_vec(vector: Vec<T>) -> BinomialHeap<T> {
+        let mut binomial_heap = BinomialHeap::empty(true, Order::natural(true));
+        let _stats = binomial_heap.stats.scope();
+        binomial_heap.size = vector.len();
+        for payload in vector {
+            let new_node = BinomialTree::init_ordered(payload, true, binomial_heap.order.clone());
+            binomial_heap.link(new_node);
+        }
+        binomial_heap.candidate_root_index = binomial_heap.find_candidate_root_index();
+
+        binomial_heap
+    }
+}
+
+impl<T> BinomialHeap<T> {
+    // initializes binomial heap based on the type specified by `min` argument and its order
+    fn init(payload: T, min: bool, order: Order<T>) -> BinomialHeap<T> {
+        // create a binomial tree with rank 0
+        let root = Some(BinomialTree::init_ordered(payload, min, order.clone()));
+
+        // push the binomial tree into heap
+        let roots = vec![root];
+
+        BinomialHeap {
+            roots,
+            size: 1,
+            candidate_root_index: 0,
+            min,
+            order,
+            stats: Recorder::default(),
+        }
+    }
+
+    // initializes an empty binomial heap, with an empty rank to push into
+    fn empty(min: bool, order: Order<T>) -> BinomialHeap<T> {
+        BinomialHeap {
+            roots: vec![None],
+            size: 0,
+            candidate_root_index: 0,
+            min,
+            order,
+            stats: Recorder::default(),
+        }
+    }
+
+    /// Initializes a heap with the specified `payload`, ordered by `compare` instead of `Ord`.
+    /// The least item by `compare` is popped first, so the heap counts as a min heap
+    ///
+    /// # Arguments:
+    /// * `payload`: data to be pushed in the heap
+    /// * `compare`: order of the items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// // longest word first
+    /// let mut binomial_heap = BinomialHeap::init_with("heap", |word1: &&str, word2: &&str| {
+    ///     word2.len().cmp(&word1.len())
+    /// });
+    /// binomial_heap.push("binomial");
+    /// binomial_heap.push("rudac");
+    ///
+    /// assert_eq!(binomial_heap.pop(), Some("binomial"));
+    /// assert_eq!(binomial_heap.pop(), Some("rudac"));
+    /// assert_eq!(binomial_heap.pop(), Some("heap"));
+    /// ```
+    pub fn init_with<F>(payload: T, compare: F) -> BinomialHeap<T>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        BinomialHeap::init(payload, true, Order::with(compare))
+    }
+
+    /// Initializes a heap with the specified `payload`, ordered by the key `key` extracts from every item.
+    /// The item with the least key is popped first, so the heap counts as a min heap. The key is computed on every
+    /// comparison, so it should be cheap
+    ///
+    /// # Arguments:
+    /// * `payload`: data to be pushed in the heap
+    /// * `key`: key of an item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// // earliest deadline first
+    /// let mut binomial_heap = BinomialHeap::init_by_key(("report", 5), |task: &(&str, u32)| task.1);
+    /// binomial_heap.push(("review", 2));
+    ///
+    /// assert_eq!(binomial_heap.pop(), Some(("review", 2)));
+    /// ```
+    pub fn init_by_key<K, F>(payload: T, key: F) -> BinomialHeap<T>
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        BinomialHeap::init(payload, true, Order::by_key(key))
     }
 
-    /// Merges two binomial heaps and returns the merged binomial heap
+    /// Merges two binomial heaps and returns the merged binomial heap.
+    /// Heaps initialized by `init_with` or `init_by_key` must have the same order
     ///
     /// # Arguments:
     /// * `binomial_heap_1`: first binomial heap
@@ -131,11 +348,57 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
         }
     }
 
+    /// Moves every item of `other` into this heap by linking its trees into the trees of this heap.
+    /// Heaps initialized by `init_with` or `init_by_key` must have the same order
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments:
+    /// * `other`: the heap to be melded into this one
+    ///
+    /// # Panics:
+    /// * panics if two binomial heaps are not the same kind(ex. one is min heap and the other is max heap)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// let mut binomial_heap = BinomialHeap::init_max(1);
+    ///
+    /// let mut other = BinomialHeap::init_max(3);
+    /// other.push(0);
+    ///
+    /// binomial_heap.meld(other);
+    ///
+    /// assert_eq!(binomial_heap.size(), 3);
+    /// assert_eq!(binomial_heap.into_sorted_vec(), vec![3, 1, 0]);
+    /// ```
+    pub fn meld(&mut self, mut other: BinomialHeap<T>) {
+        if self.is_min() != other.is_min() {
+            panic!("Both binomial heaps must be of the same type(both min or both max)");
+        }
+
+        let _stats = self.stats.scope();
+        self.stats.absorb(&other.stats);
+
+        // every rank of other must have a place among the roots, the last one grows the roots when it carries over
+        if self.max_tree_rank() < other.max_tree_rank() {
+            self.roots.resize_with(other.max_tree_rank(), || None);
+        }
+        for binomial_tree in other.roots.drain(..).flatten() {
+            self.link(binomial_tree);
+        }
+        self.size += other.size;
+        self.candidate_root_index = self.find_candidate_root_index();
+    }
+
     // merges smaller heap(binomial_heap_1) with a larger heap(binomial_heap_2)
     fn _merge(
         mut binomial_heap_1: BinomialHeap<T>,
         mut binomial_heap_2: BinomialHeap<T>,
     ) -> BinomialHeap<T> {
+        let _stats = binomial_heap_2.stats.scope();
+        binomial_heap_2.stats.absorb(&binomial_heap_1.stats);
+
         // size of heap2 will be heap2.size + heap1.size
         binomial_heap_2.set_size(binomial_heap_2.size() + binomial_heap_1.size());
 
@@ -167,8 +430,9 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
     /// assert_eq!(BinomialHeap::preorder(&binomial_heap), String::from("Rank 0: \nRank 1: 0 1\n"))
     /// ```
     pub fn push(&mut self, payload: T) {
-        // create a compatible binomial tree with rank 0 that is compatible with the current heap(hence the passing of self.is_min())
-        let new_node = BinomialTree::init(payload, self.is_min());
+        let _stats = self.stats.scope();
+        // create a compatible binomial tree with rank 0 that is compatible with the current heap(hence the passing of its order)
+        let new_node = BinomialTree::init_ordered(payload, self.min, self.order.clone());
 
         self._push(new_node);
 
@@ -176,7 +440,15 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
     }
 
     // pushes a binomial tree into heap
-    fn _push(&mut self, mut new_node: BinomialTree<T>) {
+    fn _push(&mut self, new_node: BinomialTree<T>) {
+        self.link(new_node);
+
+        // update candidate index
+        self.candidate_root_index = self.find_candidate_root_index();
+    }
+
+    // places a binomial tree among the roots, merging it with the roots of equal rank
+    fn link(&mut self, mut new_node: BinomialTree<T>) {
         // maximum rank in the heap(end of roots vector)
         let max_rank = self.roots.len();
 
@@ -196,6 +468,7 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
                     // if iteration reaches the highest rank and there is still no place empty for the new node to be inserted,
                     // then allocate a new rank(max_rank + 1) and insert the new node there
                     if i == max_rank - 1 {
+                        stats::growth(self.roots.len(), self.roots.capacity());
                         self.roots.push(Some(new_node));
                         break;
                     }
@@ -207,9 +480,6 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
                 }
             }
         }
-
-        // update candidate index
-        self.candidate_root_index = self.find_candidate_root_index();
     }
 
     /// Pops and returns item with highest priority. Returns `None` if heap is empty
@@ -223,6 +493,7 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
     /// assert_eq!(binomial_heap.pop(), Some(0));
     /// ```
     pub fn pop(&mut self) -> Option<T> {
+        let _stats = self.stats.scope();
         if self.is_empty() {
             return None;
         }
@@ -242,11 +513,14 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
 
         self.size -= 1;
 
+        // popping a root without children leaves no push to update the candidate
+        self.candidate_root_index = self.find_candidate_root_index();
+
         // return payload the popped node
         Some(popped_node.get_payload())
     }
 
-    /// Returns a reference to item with highest priority
+    /// Returns a reference to item with highest priority, None if the heap is empty
     ///
     /// # Examples
     /// ```
@@ -256,23 +530,114 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
     /// let bh2 = BinomialHeap::init_min(1);
     /// let mut merged_heap = BinomialHeap::merge(bh1, bh2);
     ///
-    /// assert_eq!(*merged_heap.peek(), Some(0));
+    /// assert_eq!(merged_heap.peek(), Some(&0));
     /// merged_heap.pop();
     ///
-    /// assert_eq!(*merged_heap.peek(), Some(1));
+    /// assert_eq!(merged_heap.peek(), Some(&1));
     /// merged_heap.pop();
     ///
-    /// assert_eq!(*merged_heap.peek(), None);
+    /// assert_eq!(merged_heap.peek(), None);
     /// ```
-    pub fn peek(&self) -> &Option<T> {
+    pub fn peek(&self) -> Option<&T> {
         if self.is_empty() {
-            return &None;
+            return None;
         }
 
         self.roots[self.candidate_root_index]
             .as_ref()
             .unwrap()
             .peek_payload()
+            .as_ref()
+    }
+
+    /// Returns a mutable reference to item with highest priority, None if the heap is empty.
+    /// The item is taken out of the heap and pushed back when the reference is dropped
+    /// * Complexity: O(log n)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::{BinomialHeap, BinomialPeekMut};
+    ///
+    /// let mut binomial_heap = BinomialHeap::init_max(5);
+    /// binomial_heap.push(3);
+    /// binomial_heap.push(4);
+    ///
+    /// *binomial_heap.peek_mut().unwrap() = 1;
+    /// assert_eq!(binomial_heap.peek(), Some(&4));
+    ///
+    /// let top = binomial_heap.peek_mut().unwrap();
+    /// assert_eq!(BinomialPeekMut::pop(top), 4);
+    /// assert_eq!(binomial_heap.size(), 2);
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<BinomialPeekMut<'_, T>> {
+        let item = self.pop()?;
+
+        Some(BinomialPeekMut {
+            heap: self,
+            item: Some(item),
+        })
+    }
+
+    /// Returns an iterator over the items of the heap in no particular order
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// let mut binomial_heap = BinomialHeap::init_min(2);
+    /// binomial_heap.push(1);
+    ///
+    /// assert_eq!(binomial_heap.iter().sum::<i32>(), 3);
+    /// ```
+    pub fn iter(&self) -> BinomialIter<'_, T> {
+        BinomialIter {
+            stack: self.roots.iter().flatten().collect(),
+            remaining: self.size,
+        }
+    }
+
+    /// Consumes the heap and returns an iterator over its items in the order they would be popped
+    /// * Complexity: O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// let mut binomial_heap = BinomialHeap::init_max(1);
+    /// binomial_heap.extend(vec![3, 2]);
+    ///
+    /// assert_eq!(binomial_heap.into_iter_sorted().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// ```
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, BinomialHeap<T>> {
+        IntoIterSorted::init(self)
+    }
+
+    /// Consumes the heap and returns its items in the order they would be popped
+    /// * Complexity: O(n log n)
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter_sorted().collect()
+    }
+
+    /// Removes every item of the heap and returns an iterator over them in the order they would be popped.
+    /// The heap is empty even if the iterator is not consumed
+    /// * Complexity: O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// let mut binomial_heap = BinomialHeap::init_min(2);
+    /// binomial_heap.push(1);
+    ///
+    /// assert_eq!(binomial_heap.drain_sorted().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert!(binomial_heap.is_empty());
+    /// ```
+    pub fn drain_sorted(&mut self) -> IntoIterSorted<T, BinomialHeap<T>> {
+        let mut drained =
+            core::mem::replace(self, BinomialHeap::empty(self.min, self.order.clone()));
+        core::mem::swap(&mut self.stats, &mut drained.stats);
+
+        IntoIterSorted::init(drained)
     }
 
     /// Clears the heap and resets internal flags
@@ -293,6 +658,19 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
         self.size = 0;
     }
 
+    /// Returns counts of the operations performed by the heap since it was created or since the last `reset_stats`.
+    /// Available with the `stats` feature
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> OpStats {
+        self.stats.stats()
+    }
+
+    /// Sets all operation counts to zero. Available with the `stats` feature
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
     /// Returns number of items in heap
     ///
     /// # Examples
@@ -313,7 +691,7 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
         self.size = size;
     }
 
-    /// Returns true if the current heap is a min heap
+    /// Returns true if the current heap is a min heap, or initialized by `init_with` or `init_by_key`
     ///
     /// # Examples
     /// ```
@@ -389,17 +767,14 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
         // iteration will start at the next index of the candidate index
         for i in candidate_index + 1..self.roots.len() {
             match (&self.roots[i], candidate_node_option) {
-                (Some(node), Some(largest_priority_node)) => {
-                    // in two cases candidate node will be replaced with the current node in the iteration
-                    // 1- heap is a min heap and current node has a smaller root than the candidate
-                    // 2- heap is a max heap and current node has a larger root than the candidate
-                    if (self.is_min()
-                        && BinomialTree::is_smaller_or_equal(&node, largest_priority_node))
-                        || (self.is_max()
-                            && BinomialTree::is_greater_or_equal(&node, largest_priority_node))
-                    {
-                        candidate_index = i; // update candidate index
-                    }
+                // in two cases candidate node will be replaced with the current node in the iteration
+                // 1- heap is a min heap and current node has a smaller root than the candidate
+                // 2- heap is a max heap and current node has a larger root than the candidate
+                // a heap initialized by `init_with` or `init_by_key` counts as a min heap of its order
+                (Some(node), Some(largest_priority_node))
+                    if BinomialTree::has_higher_priority(node, largest_priority_node) =>
+                {
+                    candidate_index = i; // update candidate index
                 }
                 _ => (),
             }
@@ -410,7 +785,43 @@ impl<T: std::cmp::Ord> BinomialHeap<T> {
     }
 }
 
-impl<T: std::cmp::Ord + std::fmt::Display> BinomialHeap<T> {
+impl<T: core::cmp::Ord> core::iter::FromIterator<T> for BinomialHeap<T> {
+    // collects into a min heap
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        BinomialHeap::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T> Extend<T> for BinomialHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for payload in iter {
+            self.push(payload);
+        }
+    }
+}
+
+impl<T> IntoIterator for BinomialHeap<T> {
+    type Item = T;
+    type IntoIter = BinomialIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BinomialIntoIter {
+            stack: self.roots.into_iter().flatten().collect(),
+            remaining: self.size,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BinomialHeap<T> {
+    type Item = &'a T;
+    type IntoIter = BinomialIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: core::fmt::Display> BinomialHeap<T> {
     /// Returns the preorder representation of the heap. it has the form of:</br>
     /// Rank i: *preorder representation of the binomial tree of rank i*\n
     ///
@@ -455,6 +866,162 @@ impl<T: std::cmp::Ord + std::fmt::Display> BinomialHeap<T> {
     }
 }
 
+impl<T: Encode> BinomialHeap<T> {
+    /// Returns a snapshot of the heap in the binary format of `rudac::codec`.
+    /// The snapshot keeps the exact shape of every binomial tree in the heap, not only its items.
+    /// The order of a heap initialized by `init_with` or `init_by_key` is not kept
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BinomialHeap;
+    ///
+    /// let mut binomial_heap = BinomialHeap::init_min(0);
+    /// for payload in 1..7 {
+    ///     binomial_heap.push(payload);
+    /// }
+    ///
+    /// let bytes = binomial_heap.to_bytes();
+    /// let restored = BinomialHeap::<i32>::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(
+    ///     BinomialHeap::preorder(&restored),
+    ///     BinomialHeap::preorder(&binomial_heap)
+    /// );
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"BH");
+        self.min.encode(&mut writer);
+        self.size.encode(&mut writer);
+        self.candidate_root_index.encode(&mut writer);
+        writer.write_varint(self.roots.len() as u64);
+        for root in self.roots.iter() {
+            match root {
+                None => writer.write_u8(0),
+                Some(root) => {
+                    writer.write_u8(1);
+                    root.write(&mut writer);
+                }
+            }
+        }
+
+        writer.into_bytes()
+    }
+}
+
+impl<T: core::cmp::Ord + Decode> BinomialHeap<T> {
+    /// Restores a heap from a snapshot made by `to_bytes`.
+    /// Returns `None` if the bytes are malformed or do not describe a valid binomial heap
+    ///
+    /// # Arguments
+    /// * `bytes`: the snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Option<BinomialHeap<T>> {
+        let mut reader = Reader::init(bytes);
+        read_header(&mut reader, b"BH")?;
+        let min = bool::decode(&mut reader)?;
+        let size = usize::decode(&mut reader)?;
+        let candidate_root_index = usize::decode(&mut reader)?;
+
+        let count = reader.read_len()?;
+        let mut roots = Vec::with_capacity(count);
+        let mut items: usize = 0;
+        for rank in 0..count {
+            let root = match reader.read_u8()? {
+                0 => None,
+                1 => Some(BinomialTree::read(&mut reader, min, 0)?),
+                _ => return None,
+            };
+            if let Some(root) = &root {
+                if root.rank() != rank || rank >= usize::BITS as usize {
+                    return None;
+                }
+                items = items.checked_add(1 << rank)?;
+            }
+            roots.push(root);
+        }
+
+        if !reader.is_at_end() || items != size || (count > 0 && candidate_root_index >= count) {
+            return None;
+        }
+
+        Some(BinomialHeap {
+            roots,
+            candidate_root_index,
+            size,
+            min,
+            order: Order::natural(min),
+            stats: Recorder::default(),
+        })
+    }
+}
+
+impl<T: core::cmp::Ord> Default for BinomialHeap<T> {
+    fn default() -> Self {
+        BinomialHeap::empty(true, Order::natural(true))
+    }
+}
+
+// heaps are equal when both are min or both are max heaps with the same trees, orders given to `init_with` or
+// `init_by_key` are not compared
+impl<T: PartialEq> PartialEq for BinomialHeap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.min == other.min
+            && self.size == other.size
+            && self.roots.iter().flatten().eq(other.roots.iter().flatten())
+    }
+}
+
+impl<T: Eq> Eq for BinomialHeap<T> {}
+
+impl<T> MemoryFootprint for BinomialHeap<T> {
+    fn footprint(&self) -> Footprint {
+        self.roots.iter().flatten().fold(
+            Footprint::of::<T>(self.size).with_vec(&self.roots),
+            |footprint, tree| footprint.with(tree.footprint()),
+        )
+    }
+}
+
+impl<T: core::cmp::Ord> Replayable for BinomialHeap<T> {
+    type Key = T;
+    type Value = ();
+
+    fn apply(&mut self, op: Op<T>) {
+        match op {
+            Op::Push(item) => self.push(item),
+            Op::Pop => {
+                self.pop();
+            }
+            Op::Insert(..) | Op::Remove(_) => {}
+        }
+    }
+}
+
+// a heap is written as its items in the order they would be popped and read back ordered by `Ord`, heaps made by
+// `init_with` or `init_by_key` come back as min heaps
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for BinomialHeap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort_by(|item1, item2| self.order.compare(item1, item2));
+
+        serialize_items(serializer, "BinomialHeap", HEAP_FIELDS, self.min, &items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: core::cmp::Ord + Deserialize<'de>> Deserialize<'de> for BinomialHeap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (min, items): (bool, Vec<T>) =
+            deserialize_items(deserializer, "BinomialHeap", HEAP_FIELDS)?;
+
+        let mut binomial_heap = BinomialHeap::empty(min, Order::natural(min));
+        binomial_heap.extend(items);
+
+        Ok(binomial_heap)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -795,7 +1362,7 @@ mod tests {
     fn heap_binomial_peek_min_1() {
         let bh1 = BinomialHeap::init_min(0);
 
-        assert_eq!(*bh1.peek(), Some(0));
+        assert_eq!(bh1.peek(), Some(&0));
         assert_eq!(BinomialHeap::preorder(&bh1), format!("Rank 0: 0\n"));
     }
 
@@ -806,7 +1373,7 @@ mod tests {
 
         let merged_heap = BinomialHeap::merge(bh1, bh2);
 
-        assert_eq!(*merged_heap.peek(), Some(0));
+        assert_eq!(merged_heap.peek(), Some(&0));
         assert_eq!(
             BinomialHeap::preorder(&merged_heap),
             format!("Rank 0: \nRank 1: 0 1\n")
@@ -822,7 +1389,7 @@ mod tests {
 
         merged_heap.pop();
         merged_heap.pop();
-        assert_eq!(*merged_heap.peek(), None);
+        assert_eq!(merged_heap.peek(), None);
         assert_eq!(
             BinomialHeap::preorder(&merged_heap),
             format!("Rank 0: \nRank 1: \n")
@@ -833,7 +1400,7 @@ mod tests {
     fn heap_binomial_peek_max_1() {
         let bh1 = BinomialHeap::init_max(0);
 
-        assert_eq!(*bh1.peek(), Some(0));
+        assert_eq!(bh1.peek(), Some(&0));
         assert_eq!(BinomialHeap::preorder(&bh1), format!("Rank 0: 0\n"));
     }
 
@@ -844,7 +1411,7 @@ mod tests {
 
         let merged_heap = BinomialHeap::merge(bh1, bh2);
 
-        assert_eq!(*merged_heap.peek(), Some(1));
+        assert_eq!(merged_heap.peek(), Some(&1));
         assert_eq!(
             BinomialHeap::preorder(&merged_heap),
             format!("Rank 0: \nRank 1: 1 0\n")
@@ -860,7 +1427,7 @@ mod tests {
 
         merged_heap.pop();
         merged_heap.pop();
-        assert_eq!(*merged_heap.peek(), None);
+        assert_eq!(merged_heap.peek(), None);
         assert_eq!(
             BinomialHeap::preorder(&merged_heap),
             format!("Rank 0: \nRank 1: \n")
@@ -874,4 +1441,230 @@ mod tests {
         let bh2 = BinomialHeap::init_max(1);
         BinomialHeap::merge(bh1, bh2);
     }
+
+    #[test]
+    fn heap_binomial_bytes_round_trip() {
+        let mut binomial_heap = BinomialHeap::init_max(0);
+        for payload in 1..50 {
+            binomial_heap.push((payload * 17) % 50);
+        }
+        for _ in 0..10 {
+            binomial_heap.pop();
+        }
+
+        let bytes = binomial_heap.to_bytes();
+        let mut restored = BinomialHeap::<i32>::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            BinomialHeap::preorder(&restored),
+            BinomialHeap::preorder(&binomial_heap)
+        );
+        assert_eq!(restored.size(), binomial_heap.size());
+        assert!(restored.is_max());
+        while let Some(payload) = binomial_heap.pop() {
+            assert_eq!(restored.pop(), Some(payload));
+        }
+
+        // a wrong size is rejected
+        let mut corrupted = bytes.clone();
+        corrupted[4] += 1;
+        assert!(BinomialHeap::<i32>::from_bytes(&corrupted).is_none());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn heap_binomial_stats() {
+        let mut binomial_heap = BinomialHeap::init_min(0);
+        for i in 1..8 {
+            binomial_heap.push(i);
+        }
+
+        // 8 items end up in a single tree of rank 3 after 7 links
+        assert_eq!(binomial_heap.stats().consolidations, 7);
+        assert!(binomial_heap.stats().comparisons >= 7);
+
+        let other = BinomialHeap::init_min(8);
+        let before = binomial_heap.stats();
+        let mut merged = BinomialHeap::merge(binomial_heap, other);
+        assert_eq!(merged.stats().consolidations, before.consolidations);
+
+        merged.reset_stats();
+        assert_eq!(merged.pop(), Some(0));
+        assert!(merged.stats().consolidations > 0);
+    }
+
+    #[test]
+    fn heap_binomial_peek_after_popping_childless_root() {
+        let mut bh = BinomialHeap::init_min(3);
+        bh.push(1);
+        bh.push(0);
+        assert_eq!(bh.pop(), Some(0));
+
+        assert_eq!(bh.peek(), Some(&1));
+    }
+
+    #[test]
+    fn heap_binomial_peek_mut() {
+        let mut bh = BinomialHeap::init_min(5);
+        for payload in [8, 2, 7, 4] {
+            bh.push(payload);
+        }
+
+        *bh.peek_mut().unwrap() = 6;
+        assert_eq!(bh.peek(), Some(&4));
+        assert_eq!(bh.size(), 5);
+
+        let top = bh.peek_mut().unwrap();
+        assert_eq!(BinomialPeekMut::pop(top), 4);
+
+        let popped: Vec<i32> = std::iter::from_fn(|| bh.pop()).collect();
+        assert_eq!(popped, vec![5, 6, 7, 8]);
+        assert!(bh.peek_mut().is_none());
+    }
+
+    #[test]
+    fn heap_binomial_init_with() {
+        // pairs by their second item, ties broken by the first item in reverse
+        let mut bh = BinomialHeap::init_with((0, 5), |p1: &(i32, i32), p2: &(i32, i32)| {
+            p1.1.cmp(&p2.1).then(p2.0.cmp(&p1.0))
+        });
+        for pair in [(1, 3), (2, 5), (3, 3), (4, 1), (5, 8), (6, 1)] {
+            bh.push(pair);
+        }
+
+        assert!(bh.is_min());
+        assert_eq!(bh.peek(), Some(&(6, 1)));
+        *bh.peek_mut().unwrap() = (6, 9);
+
+        let popped: Vec<(i32, i32)> = std::iter::from_fn(|| bh.pop()).collect();
+        assert_eq!(
+            popped,
+            vec![(4, 1), (3, 3), (1, 3), (2, 5), (0, 5), (5, 8), (6, 9)]
+        );
+    }
+
+    #[test]
+    fn heap_binomial_init_with_merge() {
+        let by_length = |s1: &String, s2: &String| s1.len().cmp(&s2.len());
+        let mut bh1 = BinomialHeap::init_with(String::from("abc"), by_length);
+        let mut bh2 = BinomialHeap::init_with(String::from("a"), by_length);
+        for length in 4..10 {
+            bh1.push("x".repeat(length));
+            bh2.push("y".repeat(length + 6));
+        }
+
+        let mut merged = BinomialHeap::merge(bh1, bh2);
+        let mut lengths = Vec::new();
+        while let Some(item) = merged.pop() {
+            lengths.push(item.len());
+        }
+
+        let mut expected: Vec<usize> = (4..10).chain(10..16).chain([1, 3]).collect();
+        expected.sort();
+        assert_eq!(lengths, expected);
+    }
+
+    #[test]
+    fn heap_binomial_iterators() {
+        let mut bh: BinomialHeap<usize> = (0..37).rev().collect();
+        assert!(bh.is_min());
+        assert_eq!(bh.iter().len(), 37);
+
+        let mut items: Vec<usize> = bh.iter().copied().collect();
+        items.sort();
+        assert_eq!(items, (0..37).collect::<Vec<_>>());
+
+        let drained: Vec<usize> = bh.drain_sorted().collect();
+        assert_eq!(drained, (0..37).collect::<Vec<_>>());
+        assert!(bh.is_empty());
+        assert_eq!(bh.pop(), None);
+
+        bh.extend(vec![3, 1, 2]);
+        assert_eq!(bh.peek(), Some(&1));
+        let mut owned: Vec<usize> = bh.into_iter().collect();
+        owned.sort();
+        assert_eq!(owned, vec![1, 2, 3]);
+
+        let empty: BinomialHeap<usize> = std::iter::empty().collect();
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().next(), None);
+
+        let mut bh = BinomialHeap::init_max(2);
+        bh.extend(vec![5, 1]);
+        assert_eq!(bh.into_iter_sorted().collect::<Vec<_>>(), vec![5, 2, 1]);
+    }
+
+    #[test]
+    fn heap_binomial_from_vec() {
+        for size in 0..70 {
+            let items: Vec<usize> = (0..size).map(|item| (item * 37) % 71).collect();
+            let mut bh = BinomialHeap::from_vec(items.clone());
+            assert_eq!(bh.size(), size);
+            assert_eq!(bh.peek(), items.iter().min());
+
+            // one tree for every bit of the size
+            let trees = bh.roots.iter().filter(|root| root.is_some()).count();
+            assert_eq!(trees, size.count_ones() as usize);
+
+            bh.push(100);
+            let mut sorted = items;
+            sorted.push(100);
+            sorted.sort();
+            assert_eq!(bh.into_sorted_vec(), sorted);
+        }
+    }
+
+    #[test]
+    fn heap_binomial_meld() {
+        for (size1, size2) in [(0, 5), (5, 0), (1, 7), (7, 1), (6, 6), (13, 30), (30, 13)] {
+            let items1: Vec<usize> = (0..size1).map(|item| item * 3).collect();
+            let items2: Vec<usize> = (0..size2).map(|item| item * 2 + 1).collect();
+            let mut bh = BinomialHeap::from_vec(items1.clone());
+            bh.meld(BinomialHeap::from_vec(items2.clone()));
+            assert_eq!(bh.size(), size1 + size2);
+
+            let mut expected: Vec<usize> = items1.into_iter().chain(items2).collect();
+            expected.sort();
+            assert_eq!(bh.peek(), expected.first());
+            assert_eq!(bh.into_sorted_vec(), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Both binomial heaps must be of the same type(both min or both max)")]
+    fn heap_binomial_panic_meld() {
+        let mut bh = BinomialHeap::init_min(0);
+        bh.meld(BinomialHeap::init_max(1));
+    }
+
+    #[test]
+    fn heap_binomial_clone_and_eq() {
+        let mut bh: BinomialHeap<usize> = BinomialHeap::default();
+        bh.extend(vec![5, 3, 8, 1, 9]);
+
+        let mut copy = bh.clone();
+        assert!(copy == bh);
+        assert_eq!(copy.pop(), Some(1));
+        assert!(copy != bh);
+        assert_eq!(bh.size(), 5);
+
+        // a max heap of the same items is not equal
+        let mut max = BinomialHeap::init_max(5);
+        max.extend(vec![3, 8, 1, 9]);
+        assert!(max != bh);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn heap_binomial_serde() {
+        let mut binomial_heap = BinomialHeap::init_max(4);
+        binomial_heap.extend(vec![1, 7, 3, 9, 2]);
+
+        let json = serde_json::to_string(&binomial_heap).unwrap();
+        assert_eq!(json, r#"{"min":false,"items":[9,7,4,3,2,1]}"#);
+
+        let restored: BinomialHeap<i32> = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_max());
+        assert_eq!(restored.into_sorted_vec(), binomial_heap.into_sorted_vec());
+        assert!(serde_json::from_str::<BinomialHeap<i32>>(r#"{"items":[1]}"#).is_err());
+    }
 }
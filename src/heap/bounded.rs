@@ -0,0 +1,385 @@
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// A bounded heap keeps the best `capacity` items ever pushed into it, which is the top-k of a stream: the smallest
+/// items for a min heap and the largest ones for a max heap. Internally it is a binary heap with the worst kept item
+/// at the root, so a new item is compared with that item only and either replaces it or is rejected. Memory stays
+/// O(k) however many items are pushed
+///
+/// # Examples
+/// ```
+/// use rudac::heap::BoundedHeap;
+///
+/// // three largest readings of a stream
+/// let mut bounded_heap = BoundedHeap::init_max(3);
+/// for reading in [12, 7, 30, 5, 18, 25, 9] {
+///     bounded_heap.push(reading);
+/// }
+///
+/// assert_eq!(bounded_heap.peek(), Some(&18));
+/// assert_eq!(bounded_heap.into_sorted_vec(), vec![30, 25, 18]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoundedHeap<T> {
+    // binary heap whose root is the worst kept item
+    tree: Vec<T>,
+
+    // largest number of kept items
+    capacity: usize,
+
+    // indicates wether current heap is initialized as a min heap or not
+    min: bool,
+
+    // order of the items, the least items by it are kept
+    order: Order<T>,
+}
+
+impl<T: core::cmp::Ord> BoundedHeap<T> {
+    /// Initializes a heap that keeps the `capacity` smallest items
+    ///
+    /// # Arguments
+    /// * `capacity`: number of kept items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BoundedHeap;
+    ///
+    /// let bounded_heap: BoundedHeap<usize> = BoundedHeap::init_min(10);
+    ///
+    /// assert_eq!(bounded_heap.is_min(), true);
+    /// assert_eq!(bounded_heap.capacity(), 10);
+    /// ```
+    pub fn init_min(capacity: usize) -> BoundedHeap<T> {
+        BoundedHeap::init(capacity, true, Order::natural(true))
+    }
+
+    /// Initializes a heap that keeps the `capacity` largest items
+    ///
+    /// # Arguments
+    /// * `capacity`: number of kept items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BoundedHeap;
+    ///
+    /// let bounded_heap: BoundedHeap<usize> = BoundedHeap::init_max(10);
+    ///
+    /// assert_eq!(bounded_heap.is_max(), true);
+    /// ```
+    pub fn init_max(capacity: usize) -> BoundedHeap<T> {
+        BoundedHeap::init(capacity, false, Order::natural(false))
+    }
+}
+
+impl<T> BoundedHeap<T> {
+    fn init(capacity: usize, min: bool, order: Order<T>) -> BoundedHeap<T> {
+        BoundedHeap {
+            tree: Vec::with_capacity(capacity),
+            capacity,
+            min,
+            order,
+        }
+    }
+
+    /// Initializes a heap that keeps the `capacity` least items by `compare` instead of `Ord`.
+    /// Such a heap counts as a min heap, so the largest items of a custom order are kept by swapping the arguments of
+    /// `compare`
+    ///
+    /// # Arguments
+    /// * `capacity`: number of kept items
+    /// * `compare`: order of the items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BoundedHeap;
+    ///
+    /// // two longest words
+    /// let mut bounded_heap: BoundedHeap<&str> = BoundedHeap::init_with(2, |word1: &&str, word2: &&str| {
+    ///     word2.len().cmp(&word1.len())
+    /// });
+    /// bounded_heap.extend(vec!["a", "bounded", "heap", "of", "words"]);
+    ///
+    /// assert_eq!(bounded_heap.into_sorted_vec(), vec!["bounded", "words"]);
+    /// ```
+    pub fn init_with<F>(capacity: usize, compare: F) -> BoundedHeap<T>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        BoundedHeap::init(capacity, true, Order::with(compare))
+    }
+
+    /// Initializes a heap that keeps the `capacity` items with the least keys, the key of an item being what `key`
+    /// extracts from it. The key is computed on every comparison, so it should be cheap. Such a heap counts as a min
+    /// heap, so the items with the largest keys are kept by wrapping the key in `core::cmp::Reverse`
+    ///
+    /// # Arguments
+    /// * `capacity`: number of kept items
+    /// * `key`: key of an item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BoundedHeap;
+    /// use std::cmp::Reverse;
+    ///
+    /// // two best scores
+    /// let mut bounded_heap = BoundedHeap::init_by_key(2, |player: &(&str, u32)| Reverse(player.1));
+    /// bounded_heap.extend(vec![("ann", 12), ("bob", 30), ("cid", 21)]);
+    ///
+    /// assert_eq!(bounded_heap.into_sorted_vec(), vec![("bob", 30), ("cid", 21)]);
+    /// ```
+    pub fn init_by_key<K, F>(capacity: usize, key: F) -> BoundedHeap<T>
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        BoundedHeap::init(capacity, true, Order::by_key(key))
+    }
+
+    // returns true if the item at `index1` is worse than the item at `index2`
+    fn is_worse(&self, index1: usize, index2: usize) -> bool {
+        self.order.compare(&self.tree[index1], &self.tree[index2]) == Ordering::Greater
+    }
+
+    // moves the item at `index` up until its parent is at least as bad
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if !self.is_worse(index, parent) {
+                break;
+            }
+            self.tree.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    // moves the item at `index` down until it is at least as bad as its children
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let left = 2 * index + 1;
+            if left >= self.tree.len() {
+                break;
+            }
+
+            let right = left + 1;
+            let worst = if right < self.tree.len() && self.is_worse(right, left) {
+                right
+            } else {
+                left
+            };
+            if !self.is_worse(worst, index) {
+                break;
+            }
+            self.tree.swap(index, worst);
+            index = worst;
+        }
+    }
+
+    /// Pushes `item` into the heap. If the heap is full, the worst of the kept items and `item` is evicted and
+    /// returned, ties keep the item that was pushed first
+    /// * Complexity: O(log k)
+    ///
+    /// # Arguments
+    /// * `item`: data to be pushed into heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::BoundedHeap;
+    ///
+    /// let mut bounded_heap = BoundedHeap::init_min(2);
+    ///
+    /// assert_eq!(bounded_heap.push(4), None);
+    /// assert_eq!(bounded_heap.push(2), None);
+    /// assert_eq!(bounded_heap.push(3), Some(4));
+    /// assert_eq!(bounded_heap.push(9), Some(9));
+    /// ```
+    pub fn push(&mut self, mut item: T) -> Option<T> {
+        if self.tree.len() < self.capacity {
+            self.tree.push(item);
+            self.sift_up(self.tree.len() - 1);
+            return None;
+        }
+
+        if let Some(root) = self.tree.first_mut() {
+            if self.order.compare(&item, root) == Ordering::Less {
+                core::mem::swap(root, &mut item);
+                self.sift_down(0);
+            }
+        }
+
+        Some(item)
+    }
+
+    /// Pops and returns the worst kept item, the one that would be evicted next. Returns `None` if heap is empty
+    /// * Complexity: O(log k)
+    pub fn pop(&mut self) -> Option<T> {
+        if self.tree.is_empty() {
+            return None;
+        }
+
+        let item = self.tree.swap_remove(0);
+        self.sift_down(0);
+
+        Some(item)
+    }
+
+    /// Returns a reference to the worst kept item, None if the heap is empty. Once the heap is full, only items
+    /// better than it are kept
+    pub fn peek(&self) -> Option<&T> {
+        self.tree.first()
+    }
+
+    /// Returns number of kept items
+    pub fn size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns true if the heap keeps `capacity` items, so every push evicts an item
+    pub fn is_full(&self) -> bool {
+        self.tree.len() == self.capacity
+    }
+
+    /// Returns the largest number of kept items
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Removes every item of the heap
+    pub fn clear(&mut self) {
+        self.tree.clear();
+    }
+
+    /// Returns true if the heap is initialized as a min heap, or by `init_with` or `init_by_key`
+    pub fn is_min(&self) -> bool {
+        self.min
+    }
+
+    /// Returns true if the heap is initialized as a max heap
+    pub fn is_max(&self) -> bool {
+        !self.is_min()
+    }
+
+    /// Consumes the heap and returns the kept items in no particular order
+    pub fn into_vec(self) -> Vec<T> {
+        self.tree
+    }
+
+    /// Returns an iterator over the kept items in no particular order
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.tree.iter()
+    }
+
+    /// Consumes the heap and returns the kept items from best to worst: ascending for a min heap and descending for
+    /// a max heap
+    /// * Complexity: O(k log k)
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let mut vector = self.tree;
+        let order = self.order;
+        vector.sort_by(|item1, item2| order.compare(item1, item2));
+
+        vector
+    }
+}
+
+impl<T> Extend<T> for BoundedHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> IntoIterator for BoundedHeap<T> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    // items in the order of the array layout
+    fn into_iter(self) -> Self::IntoIter {
+        self.tree.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BoundedHeap<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> MemoryFootprint for BoundedHeap<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.tree.len()).with_vec(&self.tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn heap_bounded_matches_sorted_stream() {
+        let mut rng = SplitMix64::init(17);
+        for capacity in [0, 1, 2, 7, 50] {
+            let stream: Vec<u64> = (0..500).map(|_| rng.next_below(300)).collect();
+            let mut smallest = BoundedHeap::init_min(capacity);
+            let mut largest = BoundedHeap::init_max(capacity);
+            let mut evicted = 0;
+            for (index, &item) in stream.iter().enumerate() {
+                if smallest.push(item).is_some() {
+                    evicted += 1;
+                }
+                largest.push(item);
+
+                let mut sorted = stream[..=index].to_vec();
+                sorted.sort_unstable();
+                let kept = sorted.len().min(capacity);
+                assert_eq!(smallest.size(), kept);
+                assert_eq!(smallest.peek(), sorted[..kept].last());
+                assert_eq!(largest.peek(), sorted[sorted.len() - kept..].first());
+            }
+            assert_eq!(evicted, stream.len() - capacity.min(stream.len()));
+
+            let mut sorted = stream;
+            sorted.sort_unstable();
+            let kept = capacity.min(sorted.len());
+            assert_eq!(smallest.into_sorted_vec(), sorted[..kept].to_vec());
+
+            sorted.reverse();
+            assert_eq!(largest.into_sorted_vec(), sorted[..kept].to_vec());
+        }
+    }
+
+    #[test]
+    fn heap_bounded_pop_worst_first() {
+        let mut bounded_heap = BoundedHeap::init_min(3);
+        bounded_heap.extend(vec![5, 1, 4, 2, 3]);
+
+        assert!(bounded_heap.is_full());
+        assert_eq!(bounded_heap.pop(), Some(3));
+        assert!(!bounded_heap.is_full());
+        assert_eq!(bounded_heap.pop(), Some(2));
+        assert_eq!(bounded_heap.pop(), Some(1));
+        assert_eq!(bounded_heap.pop(), None);
+    }
+
+    #[test]
+    fn heap_bounded_ties_keep_first() {
+        let mut bounded_heap =
+            BoundedHeap::init_with(2, |item1: &(u32, char), item2: &(u32, char)| {
+                item1.0.cmp(&item2.0)
+            });
+        bounded_heap.push((1, 'a'));
+        bounded_heap.push((2, 'b'));
+
+        assert_eq!(bounded_heap.push((2, 'c')), Some((2, 'c')));
+        assert_eq!(bounded_heap.into_sorted_vec(), vec![(1, 'a'), (2, 'b')]);
+    }
+}
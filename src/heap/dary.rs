@@ -0,0 +1,593 @@
+use super::IntoIterSorted;
+#[cfg(feature = "serde")]
+use crate::util::serialize::{deserialize_items, serialize_items, HEAP_FIELDS};
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A d-ary heap is a heap-ordered tree where every node has up to `D` children, laid out level by level in a flat
+/// vector like a binary heap. Wider nodes make the tree shallower, so pushing and increasing priorities climb fewer
+/// levels, while popping compares more children per level. The children of a node are adjacent in memory, which makes
+/// 4-ary and 8-ary heaps faster than binary heaps on modern CPUs for workloads with many pushes. With `D = 2` it is
+/// the binary heap of this crate: unlike `BinaryHeap` of the standard library, which is a max heap over `Ord` only,
+/// it is built as a min heap, a max heap, or ordered by a comparator or a key
+///
+/// # Examples
+/// ```
+/// use rudac::heap::DaryHeap;
+///
+/// let mut dary_heap: DaryHeap<usize, 4> = DaryHeap::init_min();
+/// dary_heap.push(5);
+/// dary_heap.push(1);
+/// dary_heap.push(3);
+///
+/// assert_eq!(dary_heap.peek(), Some(&1));
+/// assert_eq!(dary_heap.pop(), Some(1));
+/// assert_eq!(dary_heap.pop(), Some(3));
+/// assert_eq!(dary_heap.pop(), Some(5));
+/// assert_eq!(dary_heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DaryHeap<T, const D: usize> {
+    // children of the item at index i are at indices D * i + 1 ..= D * i + D
+    tree: Vec<T>,
+
+    // indicates wether current heap is initialized as a min heap or not
+    min: bool,
+
+    // order of the items, the item that is least by it has the highest priority
+    order: Order<T>,
+}
+
+impl<T: core::cmp::Ord, const D: usize> DaryHeap<T, D> {
+    /// Initializes a min heap
+    ///
+    /// # Panics
+    /// * panics if `D` is less than 2
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::DaryHeap;
+    ///
+    /// let dary_heap: DaryHeap<usize, 8> = DaryHeap::init_min();
+    ///
+    /// assert_eq!(dary_heap.is_min(), true);
+    /// assert_eq!(dary_heap.arity(), 8);
+    /// ```
+    pub fn init_min() -> DaryHeap<T, D> {
+        DaryHeap::init(Vec::new(), true, Order::natural(true))
+    }
+
+    /// Initializes a max heap
+    ///
+    /// # Panics
+    /// * panics if `D` is less than 2
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::DaryHeap;
+    ///
+    /// let dary_heap: DaryHeap<usize, 3> = DaryHeap::init_max();
+    ///
+    /// assert_eq!(dary_heap.is_max(), true);
+    /// ```
+    pub fn init_max() -> DaryHeap<T, D> {
+        DaryHeap::init(Vec::new(), false, Order::natural(false))
+    }
+
+    /// Builds a min heap of the items of `vector` by sifting down every inner node, from the last to the root
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `vector`: items of the heap
+    ///
+    /// # Panics
+    /// * panics if `D` is less than 2
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::DaryHeap;
+    ///
+    /// let dary_heap: DaryHeap<i32, 4> = DaryHeap::from_vec(vec![9, 8, 2, 3, 4, 5, 11, 6, 7, 1]);
+    ///
+    /// assert_eq!(dary_heap.peek(), Some(&1));
+    /// assert_eq!(dary_heap.into_sorted_vec(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 11]);
+    /// ```
+    pub fn from_vec(vector: Vec<T>) -> DaryHeap<T, D> {
+        DaryHeap::from_vec_ordered(vector, Order::natural(true))
+    }
+}
+
+impl<T, const D: usize> DaryHeap<T, D> {
+    // initializes a heap of the items of `tree`, which must already be in heap order
+    fn init(tree: Vec<T>, min: bool, order: Order<T>) -> DaryHeap<T, D> {
+        if D < 2 {
+            panic!("Arity must be at least 2");
+        }
+
+        DaryHeap { tree, min, order }
+    }
+
+    /// Initializes a heap ordered by `compare` instead of `Ord`, popping the least item by `compare` first.
+    /// Such a heap counts as a min heap, so a max heap of a custom order is made by swapping the arguments of `compare`
+    ///
+    /// # Arguments
+    /// * `compare`: order of the items
+    ///
+    /// # Panics
+    /// * panics if `D` is less than 2
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::DaryHeap;
+    ///
+    /// // shortest words first
+    /// let mut dary_heap: DaryHeap<&str, 4> = DaryHeap::init_with(|word1: &&str, word2: &&str| {
+    ///     word1.len().cmp(&word2.len())
+    /// });
+    /// dary_heap.push("heap");
+    /// dary_heap.push("d-ary");
+    /// dary_heap.push("a");
+    ///
+    /// assert_eq!(dary_heap.pop(), Some("a"));
+    /// assert_eq!(dary_heap.pop(), Some("heap"));
+    /// ```
+    pub fn init_with<F>(compare: F) -> DaryHeap<T, D>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        DaryHeap::init(Vec::new(), true, Order::with(compare))
+    }
+
+    /// Initializes a heap ordered by the key `key` extracts from every item, popping the item with the least key
+    /// first. The key is computed on every comparison, so it should be cheap, like a field or a tuple of fields.
+    /// Such a heap counts as a min heap, a max heap by key is made by wrapping the key in `core::cmp::Reverse`
+    ///
+    /// # Arguments
+    /// * `key`: key of an item
+    ///
+    /// # Panics
+    /// * panics if `D` is less than 2
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::DaryHeap;
+    /// use std::cmp::Reverse;
+    ///
+    /// // a binary heap of the oldest people first
+    /// let mut dary_heap: DaryHeap<(&str, u32), 2> = DaryHeap::init_by_key(|person: &(&str, u32)| Reverse(person.1));
+    /// dary_heap.push(("ada", 36));
+    /// dary_heap.push(("alan", 41));
+    ///
+    /// assert_eq!(dary_heap.pop(), Some(("alan", 41)));
+    /// ```
+    pub fn init_by_key<K, F>(key: F) -> DaryHeap<T, D>
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        DaryHeap::init(Vec::new(), true, Order::by_key(key))
+    }
+
+    /// Builds a heap of the items of `vector` ordered by `compare`, like `from_vec`
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `vector`: items of the heap
+    /// * `compare`: order of the items
+    ///
+    /// # Panics
+    /// * panics if `D` is less than 2
+    pub fn from_vec_with<F>(vector: Vec<T>, compare: F) -> DaryHeap<T, D>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        DaryHeap::from_vec_ordered(vector, Order::with(compare))
+    }
+
+    // builds a heap of the items of `vector` by `order`, counting as a min heap
+    pub(crate) fn from_vec_ordered(vector: Vec<T>, order: Order<T>) -> DaryHeap<T, D> {
+        let mut dary_heap = DaryHeap::init(vector, true, order);
+        dary_heap.heapify();
+
+        dary_heap
+    }
+
+    // restores the heap order of the whole tree by sifting down every inner node, from the last to the root
+    fn heapify(&mut self) {
+        // nodes after the parent of the last node are leaves
+        if self.size() > 1 {
+            for index in (0..=parent::<D>(self.size() - 1)).rev() {
+                self.sift_down(index);
+            }
+        }
+    }
+
+    // returns true if the item at `index1` has higher priority than the item at `index2`
+    fn has_higher_priority(&self, index1: usize, index2: usize) -> bool {
+        self.order.compare(&self.tree[index1], &self.tree[index2]) == Ordering::Less
+    }
+
+    // moves the item at `index` up until its parent has at least its priority
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = parent::<D>(index);
+            if !self.has_higher_priority(index, parent) {
+                break;
+            }
+            self.tree.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    // moves the item at `index` down until it has at least the priority of its children
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let first = D * index + 1;
+            if first >= self.tree.len() {
+                break;
+            }
+
+            let last = (first + D).min(self.tree.len());
+            let mut best = first;
+            for child in first + 1..last {
+                if self.has_higher_priority(child, best) {
+                    best = child;
+                }
+            }
+            if !self.has_higher_priority(best, index) {
+                break;
+            }
+            self.tree.swap(index, best);
+            index = best;
+        }
+    }
+
+    /// Pushes `item` into the heap
+    /// * Complexity: O(log n / log D)
+    ///
+    /// # Arguments
+    /// * `item`: data to be pushed into heap
+    pub fn push(&mut self, item: T) {
+        self.tree.push(item);
+        self.sift_up(self.tree.len() - 1);
+    }
+
+    /// Pops and returns item with highest priority. Returns `None` if heap is empty
+    /// * Complexity: O(D log n / log D)
+    pub fn pop(&mut self) -> Option<T> {
+        if self.tree.is_empty() {
+            return None;
+        }
+
+        let item = self.tree.swap_remove(0);
+        self.sift_down(0);
+
+        Some(item)
+    }
+
+    /// Returns a reference to item with highest priority, None if the heap is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.tree.first()
+    }
+
+    /// Pushes `item` and pops the item with highest priority, which is faster than a push followed by a pop
+    /// * Complexity: O(D log n / log D)
+    ///
+    /// # Arguments
+    /// * `item`: data to be pushed into heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::DaryHeap;
+    ///
+    /// let mut dary_heap: DaryHeap<i32, 4> = DaryHeap::from_vec(vec![3, 5]);
+    ///
+    /// assert_eq!(dary_heap.push_pop(1), 1);
+    /// assert_eq!(dary_heap.push_pop(4), 3);
+    /// assert_eq!(dary_heap.into_sorted_vec(), vec![4, 5]);
+    /// ```
+    pub fn push_pop(&mut self, mut item: T) -> T {
+        if let Some(top) = self.tree.first_mut() {
+            if self.order.compare(top, &item) == Ordering::Less {
+                core::mem::swap(top, &mut item);
+                self.sift_down(0);
+            }
+        }
+
+        item
+    }
+
+    /// Moves every item of `other` into the heap by appending them and rebuilding the heap bottom up like `from_vec`
+    /// * Complexity: O(n + m) for m items in `other`
+    ///
+    /// # Arguments
+    /// * `other`: the heap whose items are moved
+    ///
+    /// # Panics
+    /// * panics if one heap is a min heap and the other one a max heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::DaryHeap;
+    ///
+    /// let mut dary_heap1: DaryHeap<i32, 4> = DaryHeap::from_vec(vec![5, 1, 8]);
+    /// let dary_heap2: DaryHeap<i32, 4> = DaryHeap::from_vec(vec![3, 9]);
+    ///
+    /// dary_heap1.meld(dary_heap2);
+    ///
+    /// assert_eq!(dary_heap1.into_sorted_vec(), vec![1, 3, 5, 8, 9]);
+    /// ```
+    pub fn meld(&mut self, mut other: DaryHeap<T, D>) {
+        if self.min != other.min {
+            panic!("Both d-ary heaps must be of the same type(both min or both max)");
+        }
+        if other.is_empty() {
+            return;
+        }
+
+        self.tree.append(&mut other.tree);
+        self.heapify();
+    }
+
+    /// Returns the number of children of every inner node
+    pub fn arity(&self) -> usize {
+        D
+    }
+
+    /// Returns number of items in heap
+    pub fn size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Removes every item of the heap
+    pub fn clear(&mut self) {
+        self.tree.clear();
+    }
+
+    /// Returns capacity of the heap
+    pub fn capacity(&self) -> usize {
+        self.tree.capacity()
+    }
+
+    /// Reserves capacity for `additional` more items to be pushed into heap
+    pub fn reserve(&mut self, additional: usize) {
+        self.tree.reserve(additional);
+    }
+
+    /// Returns true if the heap is initialized as a min heap, or by `init_with` or `init_by_key`
+    pub fn is_min(&self) -> bool {
+        self.min
+    }
+
+    /// Returns true if the heap is initialized as a max heap
+    pub fn is_max(&self) -> bool {
+        !self.is_min()
+    }
+
+    /// Consumes the heap and returns the internal vector
+    pub fn into_vec(self) -> Vec<T> {
+        self.tree
+    }
+
+    /// Returns an iterator over the items of the heap in no particular order
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.tree.iter()
+    }
+
+    /// Consumes the heap and returns an iterator over its items in the order they are popped
+    /// * Complexity: O(D log n / log D) per item
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, DaryHeap<T, D>> {
+        IntoIterSorted::init(self)
+    }
+
+    /// Consumes the heap and returns its items in the order they are popped
+    /// * Complexity: O(D n log n / log D)
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter_sorted().collect()
+    }
+}
+
+// index of the parent of the item at `index`
+fn parent<const D: usize>(index: usize) -> usize {
+    (index - 1) / D
+}
+
+impl<T: core::cmp::Ord, const D: usize> core::iter::FromIterator<T> for DaryHeap<T, D> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        DaryHeap::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T, const D: usize> Extend<T> for DaryHeap<T, D> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T, const D: usize> IntoIterator for DaryHeap<T, D> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    // items in the order of the array layout
+    fn into_iter(self) -> Self::IntoIter {
+        self.tree.into_iter()
+    }
+}
+
+impl<'a, T, const D: usize> IntoIterator for &'a DaryHeap<T, D> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: core::cmp::Ord, const D: usize> Default for DaryHeap<T, D> {
+    fn default() -> Self {
+        DaryHeap::init_min()
+    }
+}
+
+impl<T, const D: usize> MemoryFootprint for DaryHeap<T, D> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.tree.len()).with_vec(&self.tree)
+    }
+}
+
+// a heap is written as its items in the order they would be popped and read back ordered by `Ord`, heaps made by
+// `init_with` or `init_by_key` come back as min heaps
+#[cfg(feature = "serde")]
+impl<T: Serialize, const D: usize> Serialize for DaryHeap<T, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort_by(|item1, item2| self.order.compare(item1, item2));
+
+        serialize_items(serializer, "DaryHeap", HEAP_FIELDS, self.min, &items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: core::cmp::Ord + Deserialize<'de>, const D: usize> Deserialize<'de>
+    for DaryHeap<T, D>
+{
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let (min, items): (bool, Vec<T>) =
+            deserialize_items(deserializer, "DaryHeap", HEAP_FIELDS)?;
+
+        let mut dary_heap = if min {
+            DaryHeap::init_min()
+        } else {
+            DaryHeap::init_max()
+        };
+        dary_heap.extend(items);
+
+        Ok(dary_heap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    // pushes and pops random items and compares every pop with a sorted model
+    fn matches_model<const D: usize>(seed: u64) {
+        let mut rng = SplitMix64::init(seed);
+        let mut dary_heap: DaryHeap<u64, D> = DaryHeap::init_max();
+        let mut model: Vec<u64> = Vec::new();
+        for _ in 0..3000 {
+            if rng.next_below(3) == 0 {
+                model.sort_unstable();
+                assert_eq!(dary_heap.pop(), model.pop());
+            } else {
+                let item = rng.next_below(200);
+                dary_heap.push(item);
+                model.push(item);
+            }
+            assert_eq!(dary_heap.size(), model.len());
+            assert_eq!(dary_heap.peek(), model.iter().max());
+        }
+    }
+
+    #[test]
+    fn heap_dary_matches_model() {
+        matches_model::<2>(1);
+        matches_model::<3>(2);
+        matches_model::<4>(3);
+        matches_model::<8>(4);
+        matches_model::<16>(5);
+    }
+
+    #[test]
+    fn heap_dary_from_vec() {
+        let mut rng = SplitMix64::init(6);
+        for size in [0, 1, 2, 5, 17, 1000] {
+            let items: Vec<u64> = (0..size).map(|_| rng.next_below(100)).collect();
+            let mut sorted = items.clone();
+            sorted.sort_unstable();
+
+            let dary_heap: DaryHeap<u64, 4> = DaryHeap::from_vec(items.clone());
+            assert_eq!(dary_heap.into_sorted_vec(), sorted);
+
+            let dary_heap: DaryHeap<u64, 3> = items.into_iter().collect();
+            assert_eq!(dary_heap.into_iter_sorted().collect::<Vec<_>>(), sorted);
+        }
+    }
+
+    #[test]
+    fn heap_dary_push_pop() {
+        let mut dary_heap: DaryHeap<i32, 4> = DaryHeap::init_min();
+        assert_eq!(dary_heap.push_pop(3), 3);
+        assert!(dary_heap.is_empty());
+
+        dary_heap.extend(vec![5, 2, 8]);
+        assert_eq!(dary_heap.push_pop(1), 1);
+        assert_eq!(dary_heap.push_pop(6), 2);
+        assert_eq!(dary_heap.into_sorted_vec(), vec![5, 6, 8]);
+    }
+
+    #[test]
+    fn heap_dary_init_with() {
+        let mut dary_heap: DaryHeap<(u32, f64), 4> =
+            DaryHeap::init_with(|task1: &(u32, f64), task2: &(u32, f64)| {
+                task2.1.total_cmp(&task1.1)
+            });
+        dary_heap.push((0, 2.5));
+        dary_heap.push((1, 7.0));
+        dary_heap.push((2, 4.25));
+
+        assert!(dary_heap.is_min());
+        assert_eq!(dary_heap.pop(), Some((1, 7.0)));
+        assert_eq!(dary_heap.pop(), Some((2, 4.25)));
+    }
+
+    #[test]
+    fn heap_dary_init_by_key() {
+        let mut rng = SplitMix64::init(20);
+        let items: Vec<(u32, u64)> = (0..100).map(|id| (id, rng.next_below(50))).collect();
+        let mut dary_heap: DaryHeap<(u32, u64), 3> =
+            DaryHeap::init_by_key(|item: &(u32, u64)| core::cmp::Reverse(item.1));
+        dary_heap.extend(items.iter().copied());
+
+        let popped: Vec<u64> = std::iter::from_fn(|| dary_heap.pop())
+            .map(|item| item.1)
+            .collect();
+        let mut expected: Vec<u64> = items.iter().map(|item| item.1).collect();
+        expected.sort_unstable_by(|key1, key2| key2.cmp(key1));
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Arity must be at least 2")]
+    fn heap_dary_panic_arity() {
+        let _: DaryHeap<u32, 1> = DaryHeap::init_min();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn heap_dary_serde() {
+        let dary_heap: DaryHeap<u32, 3> = (0..10).map(|item| item * 7 % 10).collect();
+
+        let json = serde_json::to_string(&dary_heap).unwrap();
+        assert_eq!(json, r#"{"min":true,"items":[0,1,2,3,4,5,6,7,8,9]}"#);
+
+        let restored: DaryHeap<u32, 3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.into_iter_sorted().collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,104 +1,316 @@
-use std::collections::LinkedList;
+use super::IntoIterSorted;
+use crate::codec::{read_header, write_header, Decode, Encode, Reader, Writer, MAX_DEPTH};
+use crate::replay::{Op, Replayable};
+#[cfg(feature = "serde")]
+use crate::util::serialize::{deserialize_items, serialize_items, HEAP_FIELDS};
+use crate::util::stats::{self, Recorder};
+#[cfg(feature = "stats")]
+use crate::util::OpStats;
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// source of the stamps of nodes, shared by all heaps so that a handle never matches a node of another item
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(0);
+
+// position of a node: the segment holding it and its slot in the segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct NodeId {
+    segment: usize,
+    slot: usize,
+}
 
-#[derive(Debug)]
-pub struct InternalTree<T: std::cmp::Ord> {
-    // number of direct children of the current node
-    degree: usize,
+impl NodeId {
+    // returns the position this one moves to when `offset` segments are put in front of its segment
+    fn shift(self, offset: usize) -> NodeId {
+        NodeId {
+            segment: self.segment + offset,
+            slot: self.slot,
+        }
+    }
 
-    // data stored in the current node
-    payload: Option<T>,
+    // returns the link that a node at this position keeps to `target`
+    fn link_to(self, target: Option<NodeId>) -> Option<Link> {
+        target.map(|target| Link {
+            offset: target.segment as isize - self.segment as isize,
+            slot: target.slot,
+        })
+    }
+
+    // returns the position that `link`, kept by a node at this position, refers to
+    fn follow(self, link: Option<Link>) -> Option<NodeId> {
+        link.map(|link| NodeId {
+            segment: (self.segment as isize + link.offset) as usize,
+            slot: link.slot,
+        })
+    }
+}
 
-    // children of the current node
-    children_list: LinkedList<InternalTree<T>>,
+// a link from one node to another. the segment of the target is kept relative to the segment of the node holding
+// the link, so links between the nodes of a heap stay valid when its segments are moved behind another heap's
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    offset: isize,
+    slot: usize,
+}
 
-    // indicates wether current node is a min heap-ordered tree or not
-    min: bool,
+// nodes of a heap. melding appends the segments of the other heap instead of moving its nodes
+#[derive(Debug, Clone)]
+struct Arena<T> {
+    segments: Vec<Vec<InternalTree<T>>>,
 }
 
-impl<T: std::cmp::Ord> InternalTree<T> {
-    // initializes an internal tree which is min or max heap ordered tree based on min parameter
-    fn init(payload: T, min: bool) -> InternalTree<T> {
-        InternalTree {
-            degree: 0,
-            payload: Some(payload),
-            children_list: LinkedList::new(),
-            min,
+impl<T> Arena<T> {
+    fn init() -> Arena<T> {
+        Arena {
+            segments: Vec::new(),
         }
     }
 
-    // returns true if tree1.payload <= tree2.payload
-    fn is_smaller_or_equal(
-        internal_tree_1: &InternalTree<T>,
-        internal_tree_2: &InternalTree<T>,
-    ) -> bool {
-        match (
-            internal_tree_1.peek_payload(),
-            internal_tree_2.peek_payload(),
-        ) {
-            (Some(payload1), Some(payload2)) => payload1 <= payload2,
-            _ => panic!("Payloads can not be empty"),
+    // returns the node at `node`, None if there is no such position
+    fn get(&self, node: NodeId) -> Option<&InternalTree<T>> {
+        self.segments.get(node.segment)?.get(node.slot)
+    }
+
+    // stores `node` at the end of the last segment and returns its position
+    fn push(&mut self, node: InternalTree<T>) -> NodeId {
+        if self.segments.is_empty() {
+            self.segments.push(Vec::new());
+        }
+        let segment = self.segments.len() - 1;
+        self.segments[segment].push(node);
+
+        NodeId {
+            segment,
+            slot: self.segments[segment].len() - 1,
         }
     }
-    // returns true if tree1.payload >= tree2.payload
-    fn is_greater_or_equal(
-        internal_tree_1: &InternalTree<T>,
-        internal_tree_2: &InternalTree<T>,
-    ) -> bool {
-        match (
-            internal_tree_1.peek_payload(),
-            internal_tree_2.peek_payload(),
-        ) {
-            (Some(payload1), Some(payload2)) => payload1 >= payload2,
-            _ => panic!("Payloads can not be empty"),
+
+    // moves the segments of `other` behind the segments of this arena, without touching their nodes.
+    // returns the number of segments a position of `other` is shifted by
+    fn append(&mut self, mut other: Arena<T>) -> usize {
+        let offset = self.segments.len();
+        self.segments.append(&mut other.segments);
+
+        offset
+    }
+
+    fn parent(&self, node: NodeId) -> Option<NodeId> {
+        node.follow(self[node].parent)
+    }
+
+    fn set_parent(&mut self, node: NodeId, parent: Option<NodeId>) {
+        self[node].parent = node.link_to(parent);
+    }
+
+    fn previous(&self, node: NodeId) -> Option<NodeId> {
+        node.follow(self[node].previous)
+    }
+
+    fn set_previous(&mut self, node: NodeId, previous: Option<NodeId>) {
+        self[node].previous = node.link_to(previous);
+    }
+
+    fn next(&self, node: NodeId) -> Option<NodeId> {
+        node.follow(self[node].next)
+    }
+
+    fn set_next(&mut self, node: NodeId, next: Option<NodeId>) {
+        self[node].next = node.link_to(next);
+    }
+
+    // returns the list of children of `node`
+    fn children(&self, node: NodeId) -> List {
+        List {
+            first: node.follow(self[node].first_child),
+            last: node.follow(self[node].last_child),
+            len: self[node].degree,
         }
     }
 
-    // returns true if tree1 has higher priority than tree2
-    // it means:
-    // if trees are min heap-ordered higher priority means smaller values
-    // if trees are max heap-ordered higher priority means larger values
-    fn has_higher_priority(
-        internal_tree_1: &InternalTree<T>,
-        internal_tree_2: &InternalTree<T>,
-        is_min: bool,
-    ) -> bool {
-        if (is_min && InternalTree::is_smaller_or_equal(&internal_tree_1, &internal_tree_2))
-            || (!is_min && InternalTree::is_greater_or_equal(&internal_tree_1, &internal_tree_2))
-        {
-            true
-        } else {
-            false
+    // makes `children` the list of children of `node`, its length becomes the degree of `node`
+    fn set_children(&mut self, node: NodeId, children: List) {
+        self[node].first_child = node.link_to(children.first);
+        self[node].last_child = node.link_to(children.last);
+        self[node].degree = children.len;
+    }
+
+    // adds the buffers of the segments to `footprint`
+    fn with_buffers(&self, footprint: Footprint) -> Footprint {
+        self.segments
+            .iter()
+            .fold(footprint.with_vec(&self.segments), |footprint, segment| {
+                footprint.with_vec(segment)
+            })
+    }
+}
+
+impl<T> core::ops::Index<NodeId> for Arena<T> {
+    type Output = InternalTree<T>;
+
+    fn index(&self, node: NodeId) -> &InternalTree<T> {
+        &self.segments[node.segment][node.slot]
+    }
+}
+
+impl<T> core::ops::IndexMut<NodeId> for Arena<T> {
+    fn index_mut(&mut self, node: NodeId) -> &mut InternalTree<T> {
+        &mut self.segments[node.segment][node.slot]
+    }
+}
+
+// a doubly linked list of nodes, linked through their `previous` and `next` fields
+#[derive(Debug, Clone, Copy, Default)]
+struct List {
+    first: Option<NodeId>,
+    last: Option<NodeId>,
+    len: usize,
+}
+
+impl List {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    // returns the same list after `offset` segments are put in front of the segments of its nodes
+    fn shift(self, offset: usize) -> List {
+        List {
+            first: self.first.map(|node| node.shift(offset)),
+            last: self.last.map(|node| node.shift(offset)),
+            len: self.len,
         }
     }
 
-    // merges two heap-ordered trees and returns the merged tree
-    fn merge(
-        mut internal_tree_1: InternalTree<T>,
-        mut internal_tree_2: InternalTree<T>,
-    ) -> InternalTree<T> {
-        // make sure both tree are of the same kind
-        if internal_tree_1.is_min() != internal_tree_2.is_min() {
-            panic!("Both internal trees must be of same type. Both min or both max")
+    // returns the nodes of the list in order
+    fn iter<'a, T>(&self, nodes: &'a Arena<T>) -> impl Iterator<Item = NodeId> + 'a {
+        core::iter::successors(self.first, move |node| nodes.next(*node))
+    }
+
+    fn push_back<T>(&mut self, nodes: &mut Arena<T>, node: NodeId) {
+        nodes.set_previous(node, self.last);
+        nodes.set_next(node, None);
+        match self.last {
+            Some(last) => nodes.set_next(last, Some(node)),
+            None => self.first = Some(node),
         }
+        self.last = Some(node);
+        self.len += 1;
+    }
 
-        let trees_are_min = internal_tree_1.is_min();
+    fn push_front<T>(&mut self, nodes: &mut Arena<T>, node: NodeId) {
+        nodes.set_previous(node, None);
+        nodes.set_next(node, self.first);
+        match self.first {
+            Some(first) => nodes.set_previous(first, Some(node)),
+            None => self.last = Some(node),
+        }
+        self.first = Some(node);
+        self.len += 1;
+    }
 
-        // tree with lower priority must be child of the tree with higher priority
-        if InternalTree::has_higher_priority(&internal_tree_1, &internal_tree_2, trees_are_min) {
-            internal_tree_1.add_child(internal_tree_2);
+    // unlinks `node`, which must be in the list
+    fn remove<T>(&mut self, nodes: &mut Arena<T>, node: NodeId) {
+        let (previous, next) = (nodes.previous(node), nodes.next(node));
+        match previous {
+            Some(previous) => nodes.set_next(previous, next),
+            None => self.first = next,
+        }
+        match next {
+            Some(next) => nodes.set_previous(next, previous),
+            None => self.last = previous,
+        }
+        nodes.set_previous(node, None);
+        nodes.set_next(node, None);
+        self.len -= 1;
+    }
 
-            internal_tree_1
-        } else {
-            internal_tree_2.add_child(internal_tree_1);
+    fn pop_front<T>(&mut self, nodes: &mut Arena<T>) -> Option<NodeId> {
+        let first = self.first?;
+        self.remove(nodes, first);
 
-            internal_tree_2
+        Some(first)
+    }
+
+    // moves every node of `other` to the end of the list
+    fn append<T>(&mut self, nodes: &mut Arena<T>, other: List) {
+        let (Some(first), Some(last)) = (other.first, other.last) else {
+            return;
+        };
+        nodes.set_previous(first, self.last);
+        match self.last {
+            Some(own_last) => nodes.set_next(own_last, Some(first)),
+            None => self.first = Some(first),
+        }
+        self.last = Some(last);
+        self.len += other.len;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InternalTree<T> {
+    // number of direct children of the current node
+    degree: usize,
+
+    // data stored in the current node, None if the node was removed and its slot is free
+    payload: Option<T>,
+
+    // ends of the list of children of the current node
+    first_child: Option<Link>,
+    last_child: Option<Link>,
+
+    // parent of the current node, None for roots
+    parent: Option<Link>,
+
+    // neighbors of the current node in the children list of its parent, in the list of roots or in the list of
+    // free slots
+    previous: Option<Link>,
+    next: Option<Link>,
+
+    // indicates wether current node lost a child since it became a child itself
+    mark: bool,
+
+    // tells the items that used the same slot apart, so handles of removed items are recognized
+    stamp: u64,
+}
+
+impl<T> InternalTree<T> {
+    // initializes a node without parent, children or neighbors
+    fn init(payload: T) -> InternalTree<T> {
+        InternalTree {
+            degree: 0,
+            payload: Some(payload),
+            first_child: None,
+            last_child: None,
+            parent: None,
+            previous: None,
+            next: None,
+            mark: false,
+            stamp: NEXT_STAMP.fetch_add(1, AtomicOrdering::Relaxed),
         }
     }
 
-    // add another internal tree as a child
-    fn add_child(&mut self, internal_tree: InternalTree<T>) {
-        self.children_list.push_back(internal_tree);
-        self.degree += 1;
+    // returns true if tree1 has higher priority than tree2, meaning its payload is not greater by `order`
+    fn has_higher_priority(
+        internal_tree_1: &InternalTree<T>,
+        internal_tree_2: &InternalTree<T>,
+        order: &Order<T>,
+    ) -> bool {
+        stats::comparison();
+        match (
+            internal_tree_1.peek_payload(),
+            internal_tree_2.peek_payload(),
+        ) {
+            (Some(payload1), Some(payload2)) => {
+                order.compare(payload1, payload2) != Ordering::Greater
+            }
+            _ => panic!("Payloads can not be empty"),
+        }
     }
 
     // returns degree of current tree
@@ -119,157 +331,118 @@ impl<T: std::cmp::Ord> InternalTree<T> {
 
         self.payload.take().unwrap()
     }
-
-    // returns a reference to list of children of the current node
-    fn children_list(&self) -> &LinkedList<InternalTree<T>> {
-        &self.children_list
-    }
-
-    // returns true if tree is initialized as a min heap-ordered tree
-    fn is_min(&self) -> bool {
-        self.min
-    }
 }
 
-impl<T> InternalTree<T>
-where
-    T: std::cmp::Ord + std::fmt::Display,
-{
-    // It's like preorder function of Binomial Heap
-    pub fn preorder(internal_tree: &InternalTree<T>) -> String {
-        return String::from(InternalTree::_preorder(&Some(internal_tree)).trim());
-    }
+/// Refers to an item pushed into a `FibonacciHeap`, to change or remove it later.
+/// A handle is only valid for the heap that returned it and stops being valid when its item leaves the heap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FibonacciHandle {
+    node: NodeId,
+    stamp: u64,
+}
 
-    fn _preorder(node_opt: &Option<&InternalTree<T>>) -> String {
-        let mut node_list = String::from("");
+/// Mutable reference to the item with highest priority of a `FibonacciHeap`, created by `FibonacciHeap::peek_mut`.
+/// The heap is restored when the reference is dropped
+pub struct FibonacciPeekMut<'a, T> {
+    heap: &'a mut FibonacciHeap<T>,
 
-        match node_opt {
-            None => node_list,
-            Some(node) => {
-                match node.peek_payload() {
-                    Some(value) => node_list.push_str(format!("{} ", value).as_str()),
-                    None => (),
-                }
-                for item in node.children_list() {
-                    node_list
-                        .push_str(format!("{}", InternalTree::_preorder(&Some(&item))).as_str());
-                }
-                node_list
-            }
-        }
-    }
+    // whether the item was borrowed mutably, so the heap may need to be restored
+    changed: bool,
 }
 
-#[cfg(test)]
-mod internal_tree_tests {
-    use super::*;
-
-    #[test]
-    fn heap_fibonacci_internal_tree_init() {
-        let it = InternalTree::init(1, true);
+impl<'a, T> FibonacciPeekMut<'a, T> {
+    /// Removes the item from the heap and returns it, even if it was changed to a lower priority
+    ///
+    /// # Arguments
+    /// * `this`: the reference to the item
+    pub fn pop(mut this: FibonacciPeekMut<'a, T>) -> T {
+        // popping restores the heap anyway
+        this.changed = false;
 
-        assert_eq!(it.degree(), 0);
-        assert_eq!(*it.peek_payload(), Some(1));
+        this.heap.pop().unwrap()
     }
+}
 
-    #[test]
-    fn heap_fibonacci_internal_tree_is_smaller() {
-        let it1 = InternalTree::init(0, true);
-        let it2 = InternalTree::init(1, true);
-        let it3 = InternalTree::init(0, true);
+impl<'a, T> core::ops::Deref for FibonacciPeekMut<'a, T> {
+    type Target = T;
 
-        assert_eq!(InternalTree::is_smaller_or_equal(&it1, &it2), true);
-        assert_eq!(InternalTree::is_smaller_or_equal(&it1, &it3), true);
-        assert_eq!(InternalTree::is_smaller_or_equal(&it2, &it1), false);
+    fn deref(&self) -> &T {
+        self.heap.peek().unwrap()
     }
+}
 
-    #[test]
-    fn heap_fibonacci_internal_tree_add_child_1() {
-        let mut it1 = InternalTree::init(0, true);
-        let it2 = InternalTree::init(1, true);
-
-        it1.add_child(it2);
+impl<'a, T> core::ops::DerefMut for FibonacciPeekMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        let priority_node = self.heap.priority_pointer.unwrap();
+        self.heap.nodes[priority_node].payload.as_mut().unwrap()
+    }
+}
 
-        assert_eq!(it1.degree(), 1);
-        assert_eq!(
-            *it1.children_list.pop_back().unwrap().peek_payload(),
-            Some(1)
-        );
+impl<'a, T> Drop for FibonacciPeekMut<'a, T> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.heap.restore_priority();
+        }
     }
+}
 
-    #[test]
-    fn heap_fibonacci_internal_tree_add_child_2() {
-        let it1 = InternalTree::init(0, true);
-        let mut it2 = InternalTree::init(1, true);
+/// Iterator over the items of a `FibonacciHeap` in no particular order, created by `FibonacciHeap::iter`
+pub struct FibonacciIter<'a, T> {
+    nodes: core::iter::Flatten<core::slice::Iter<'a, Vec<InternalTree<T>>>>,
 
-        it2.add_child(it1);
+    // number of items not yielded yet
+    remaining: usize,
+}
 
-        assert_eq!(it2.degree(), 1);
-        assert_eq!(
-            *it2.children_list.pop_back().unwrap().peek_payload(),
-            Some(0)
-        );
-    }
+impl<'a, T> Iterator for FibonacciIter<'a, T> {
+    type Item = &'a T;
 
-    #[test]
-    fn heap_fibonacci_internal_tree_merge_1() {
-        let it1 = InternalTree::init(0, true);
-        let it2 = InternalTree::init(1, true);
+    fn next(&mut self) -> Option<&'a T> {
+        // free slots hold no payload
+        let payload = self.nodes.find_map(|node| node.payload.as_ref())?;
+        self.remaining -= 1;
 
-        let mut merged_tree = InternalTree::merge(it1, it2);
+        Some(payload)
+    }
 
-        assert_eq!(merged_tree.degree(), 1);
-        assert_eq!(
-            *merged_tree.children_list.pop_back().unwrap().peek_payload(),
-            Some(1)
-        );
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
+}
 
-    #[test]
-    fn heap_fibonacci_internal_tree_merge_2() {
-        let it1 = InternalTree::init(0, true);
-        let it2 = InternalTree::init(1, true);
+impl<'a, T> ExactSizeIterator for FibonacciIter<'a, T> {}
 
-        let mut merged_tree = InternalTree::merge(it2, it1);
+/// Iterator over the items of a `FibonacciHeap` in no particular order, created by `into_iter`
+pub struct FibonacciIntoIter<T> {
+    nodes: core::iter::Flatten<alloc::vec::IntoIter<Vec<InternalTree<T>>>>,
 
-        assert_eq!(merged_tree.degree(), 1);
-        assert_eq!(
-            *merged_tree.children_list.pop_back().unwrap().peek_payload(),
-            Some(1)
-        );
-    }
+    // number of items not yielded yet
+    remaining: usize,
+}
 
-    #[test]
-    fn heap_fibonacci_internal_tree_merge_3() {
-        let it1 = InternalTree::init(0, true);
-        let it2 = InternalTree::init(1, true);
-        let merged_tree_1 = InternalTree::merge(it2, it1);
-        let it3 = InternalTree::init(2, true);
-        let it4 = InternalTree::init(3, true);
-        let merged_tree_2 = InternalTree::merge(it3, it4);
+impl<T> Iterator for FibonacciIntoIter<T> {
+    type Item = T;
 
-        let merged_tree = InternalTree::merge(merged_tree_1, merged_tree_2);
+    fn next(&mut self) -> Option<T> {
+        let payload = self.nodes.find_map(|node| node.payload)?;
+        self.remaining -= 1;
 
-        assert_eq!(merged_tree.degree(), 2);
-        assert_eq!(
-            InternalTree::preorder(&merged_tree),
-            String::from("0 1 2 3")
-        );
+        Some(payload)
     }
 
-    #[test]
-    #[should_panic(expected = "Both internal trees must be of same type. Both min or both max")]
-    fn heap_fibonacci_internal_tree_panic_merge() {
-        let it1 = InternalTree::init(0, true);
-        let it2 = InternalTree::init(1, false);
-
-        InternalTree::merge(it1, it2);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
+impl<T> ExactSizeIterator for FibonacciIntoIter<T> {}
+
 // ------------- Fibonacci Heap -------------
 /// A Fibonacci heap is a data structure for priority queue operations.
 /// It has a better amortized running time than binary heap and binomial heap.
+/// Pushing an item returns a handle which can increase the priority of the item in amortized O(1) time
+/// or remove it in amortized O(log n) time
 ///
 /// # Examples
 /// ```
@@ -292,32 +465,33 @@ mod internal_tree_tests {
 ///     String::from("Priority: 0\nTree 1: 1\nTree 2: 3\n")
 /// )
 /// ```
-#[derive(Debug)]
-pub struct FibonacciHeap<T: std::cmp::Ord> {
+#[derive(Debug, Clone)]
+pub struct FibonacciHeap<T> {
+    // every node of the heap, trees refer to their nodes by position
+    nodes: Arena<T>,
+
+    // slots of removed nodes, reused by later pushes
+    free: List,
+
     // doubly linked list of internal trees
-    children_list: LinkedList<InternalTree<T>>,
+    children_list: List,
 
     // total number of items in the heap
     size: usize,
 
     // pointer to root containing the highest priority
-    priority_pointer: Option<InternalTree<T>>,
+    priority_pointer: Option<NodeId>,
 
     // indicates wether current heap is initialized as a min heap or not
     min: bool,
-}
 
-impl<T: std::cmp::Ord> FibonacciHeap<T> {
-    // initializes a fibonacci heap
-    fn init(min: bool) -> FibonacciHeap<T> {
-        FibonacciHeap {
-            children_list: LinkedList::new(),
-            size: 0,
-            priority_pointer: None,
-            min,
-        }
-    }
+    // order of the items, the item that is least by it has the highest priority
+    order: Order<T>,
+
+    stats: Recorder,
+}
 
+impl<T: core::cmp::Ord> FibonacciHeap<T> {
     /// Initializes a min heap with the specified `payload`
     ///
     /// # Examples
@@ -329,7 +503,7 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     /// assert_eq!(fibonacci_heap.is_min(), true);
     /// ```
     pub fn init_min() -> FibonacciHeap<T> {
-        FibonacciHeap::init(true)
+        FibonacciHeap::init(true, Order::natural(true))
     }
 
     /// Initializes a max heap with the specified `payload`
@@ -343,10 +517,155 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     /// assert_eq!(fibonacci_heap.is_max(), true);
     /// ```
     pub fn init_max() -> FibonacciHeap<T> {
-        FibonacciHeap::init(false)
+        FibonacciHeap::init(false, Order::natural(false))
+    }
+
+    /// Builds a min heap of the items of `vector`. The items become the roots of the heap without being linked,
+    /// so the work of building the trees is left to the pops
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `vector`: items of the heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let fibonacci_heap = FibonacciHeap::from_vec(vec![4, 1, 3, 2]);
+    ///
+    /// assert_eq!(fibonacci_heap.peek(), Some(&1));
+    /// assert_eq!(fibonacci_heap.into_sorted_vec(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn from_vec(vector: Vec<T>) -> FibonacciHeap<T> {
+        let mut fibonacci_heap = FibonacciHeap::init_min();
+        let _stats = fibonacci_heap.stats.scope();
+        let nodes: Vec<InternalTree<T>> = vector.into_iter().map(InternalTree::init).collect();
+        fibonacci_heap.size = nodes.len();
+        fibonacci_heap.nodes.segments.push(nodes);
+
+        // every node is in the first segment
+        let at = |slot| NodeId { segment: 0, slot };
+        let mut priority_node = at(0);
+        for node in (1..fibonacci_heap.size).map(at) {
+            if !InternalTree::has_higher_priority(
+                &fibonacci_heap.nodes[priority_node],
+                &fibonacci_heap.nodes[node],
+                &fibonacci_heap.order,
+            ) {
+                priority_node = node;
+            }
+        }
+
+        // every node but the priority node goes to the list of roots
+        for node in (0..fibonacci_heap.size)
+            .map(at)
+            .filter(|&node| node != priority_node)
+        {
+            stats::allocation();
+            fibonacci_heap
+                .children_list
+                .push_back(&mut fibonacci_heap.nodes, node);
+        }
+        if fibonacci_heap.size > 0 {
+            fibonacci_heap.priority_pointer = Some(priority_node);
+        }
+
+        fibonacci_heap
+    }
+}
+
+impl<T> FibonacciHeap<T> {
+    // initializes a fibonacci heap
+    fn init(min: bool, order: Order<T>) -> FibonacciHeap<T> {
+        FibonacciHeap {
+            nodes: Arena::init(),
+            free: List::default(),
+            children_list: List::default(),
+            size: 0,
+            priority_pointer: None,
+            min,
+            order,
+            stats: Recorder::default(),
+        }
+    }
+
+    /// Initializes a heap ordered by `compare` instead of `Ord`, popping the least item by `compare` first.
+    /// Such a heap counts as a min heap, so a max heap of a custom order is made by swapping the arguments of `compare`
+    ///
+    /// # Arguments:
+    /// * `compare`: order of the items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// // tasks with the latest deadline first, floats are not `Ord`
+    /// let mut fibonacci_heap = FibonacciHeap::init_with(|task1: &(&str, f64), task2: &(&str, f64)| {
+    ///     task2.1.total_cmp(&task1.1)
+    /// });
+    /// fibonacci_heap.push(("write", 2.5));
+    /// fibonacci_heap.push(("review", 7.0));
+    /// fibonacci_heap.push(("merge", 4.25));
+    ///
+    /// assert_eq!(fibonacci_heap.pop(), Some(("review", 7.0)));
+    /// assert_eq!(fibonacci_heap.pop(), Some(("merge", 4.25)));
+    /// assert!(fibonacci_heap.is_min());
+    /// ```
+    pub fn init_with<F>(compare: F) -> FibonacciHeap<T>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        FibonacciHeap::init(true, Order::with(compare))
+    }
+
+    /// Initializes a heap ordered by the key `key` extracts from every item, popping the item with the least key
+    /// first. The key is computed on every comparison, so it should be cheap. Such a heap counts as a min heap, so a
+    /// max heap by key is made by wrapping the key in `core::cmp::Reverse`
+    ///
+    /// # Arguments:
+    /// * `key`: key of an item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    /// use std::cmp::Reverse;
+    ///
+    /// // longest words first, no need to wrap every item
+    /// let mut fibonacci_heap = FibonacciHeap::init_by_key(|word: &String| Reverse(word.len()));
+    /// fibonacci_heap.push(String::from("heap"));
+    /// fibonacci_heap.push(String::from("fibonacci"));
+    ///
+    /// assert_eq!(fibonacci_heap.pop().as_deref(), Some("fibonacci"));
+    /// ```
+    pub fn init_by_key<K, F>(key: F) -> FibonacciHeap<T>
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        FibonacciHeap::init(true, Order::by_key(key))
+    }
+
+    // stores `payload` in a new node, reusing a free slot if there is one
+    fn allocate(&mut self, payload: T) -> NodeId {
+        let node = InternalTree::init(payload);
+        match self.free.pop_front(&mut self.nodes) {
+            Some(slot) => {
+                self.nodes[slot] = node;
+                slot
+            }
+            None => self.nodes.push(node),
+        }
+    }
+
+    // returns the node of `handle`, None if its item is no longer in the heap
+    fn locate(&self, handle: FibonacciHandle) -> Option<NodeId> {
+        self.nodes
+            .get(handle.node)
+            .filter(|node| node.payload.is_some() && node.stamp == handle.stamp)
+            .map(|_| handle.node)
     }
 
-    /// Pushes specified `payload` into heap
+    /// Pushes specified `payload` into heap and returns a handle to it
     ///
     /// # Arguments:
     /// * `payload`: data to be pushed into heap
@@ -371,37 +690,45 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     ///     String::from("Priority: 0\nTree 1: 1\nTree 2: 3\n")
     /// )
     /// ```
-    pub fn push(&mut self, payload: T) {
-        // create a compatible root with current heap, containing the payload
-        let new_node = InternalTree::init(payload, self.is_min());
-
-        let heap_is_min = self.is_min();
+    pub fn push(&mut self, payload: T) -> FibonacciHandle {
+        let _stats = self.stats.scope();
+        // create a root containing the payload
+        let new_node = self.allocate(payload);
 
         // if there is no priority node, assign the newly created node as priority node
-        if self.priority_pointer.is_none() {
-            self.priority_pointer = Some(new_node);
-        } else {
+        if let Some(priority_node) = self.priority_pointer {
             if InternalTree::has_higher_priority(
                 // if new node has higher priority, it must become priority node
-                &new_node,
-                &self.priority_pointer.as_ref().unwrap(),
-                heap_is_min,
+                &self.nodes[new_node],
+                &self.nodes[priority_node],
+                &self.order,
             ) {
                 // swap new node and priority node
-                let temp = self.priority_pointer.take().unwrap();
                 self.priority_pointer = Some(new_node);
-                self.children_list.push_back(temp);
+                stats::allocation();
+                self.children_list.push_back(&mut self.nodes, priority_node);
             } else {
                 // if new node has lower priority, just add it to children list of the heap
-                self.children_list.push_back(new_node);
+                stats::allocation();
+                self.children_list.push_back(&mut self.nodes, new_node);
             }
+        } else {
+            self.priority_pointer = Some(new_node);
         }
 
         // account for newly added node
         self.size += 1;
+
+        FibonacciHandle {
+            node: new_node,
+            stamp: self.nodes[new_node].stamp,
+        }
     }
 
-    /// Merges two fibonacci heaps and returns the merged fibonacci heap
+    /// Merges two fibonacci heaps and returns the merged fibonacci heap.
+    /// Handles returned by `fibonacci_heap_1` stay valid in the merged heap, handles returned by `fibonacci_heap_2`
+    /// do not, unless `fibonacci_heap_1` is empty. The merged heap is ordered like `fibonacci_heap_1`,
+    /// so heaps initialized by `init_with` or `init_by_key` must have the same order
     ///
     /// # Arguments:
     /// * `fibonacci_heap_1`: first fibonacci heap
@@ -431,50 +758,92 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     /// ```
     pub fn merge(
         mut fibonacci_heap_1: FibonacciHeap<T>,
-        mut fibonacci_heap_2: FibonacciHeap<T>,
+        fibonacci_heap_2: FibonacciHeap<T>,
     ) -> FibonacciHeap<T> {
+        fibonacci_heap_1.meld(fibonacci_heap_2);
+
+        fibonacci_heap_1
+    }
+
+    /// Moves every item of `other` into this heap. The node segments of `other` are put behind the segments of this
+    /// heap as they are, since nodes link to each other relative to their own segment, and the root list of `other`
+    /// is spliced behind the root list of this heap. No node is moved or copied and the trees of `other` keep their
+    /// shape. Handles returned by this heap stay valid, handles returned by `other` do not, unless this heap is empty.
+    /// Heaps initialized by `init_with` or `init_by_key` must have the same order
+    /// * Complexity: O(1) if no heap was melded into `other`, otherwise O(s) for its s segments, one per melded heap
+    ///
+    /// # Arguments:
+    /// * `other`: the heap to be melded into this one
+    ///
+    /// # Panics:
+    /// * panics if two fibonacci heaps are not the same kind(ex. one is min heap and the other is max heap)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_min();
+    /// let handle = fibonacci_heap.push(4);
+    ///
+    /// let mut other = FibonacciHeap::init_min();
+    /// other.push(2);
+    /// other.push(3);
+    ///
+    /// fibonacci_heap.meld(other);
+    /// fibonacci_heap.decrease_key(handle, 1);
+    ///
+    /// assert_eq!(fibonacci_heap.size(), 3);
+    /// assert_eq!(fibonacci_heap.into_sorted_vec(), vec![1, 2, 3]);
+    /// ```
+    pub fn meld(&mut self, other: FibonacciHeap<T>) {
         // if one heap is min and the other is max, panic!. merge is not possible
-        if fibonacci_heap_1.is_min() != fibonacci_heap_2.is_min() {
+        if self.is_min() != other.is_min() {
             panic!("Two heaps must be of same type in order for merge to be possible")
         }
 
-        // if either heaps are empty, return the other one as result
-        if fibonacci_heap_1.is_empty() {
-            return fibonacci_heap_2;
-        } else if fibonacci_heap_2.is_empty() {
-            return fibonacci_heap_1;
+        let _stats = self.stats.scope();
+        self.stats.absorb(&other.stats);
+
+        if other.is_empty() {
+            return;
         }
 
-        // concatenate children list of heap1 and heap2
-        fibonacci_heap_1
-            .children_list
-            .append(&mut fibonacci_heap_2.children_list);
+        // no handle of this heap is valid, so the nodes of other are taken over as they are
+        if self.is_empty() {
+            self.nodes = other.nodes;
+            self.free = other.free;
+            self.children_list = other.children_list;
+            self.size = other.size;
+            self.priority_pointer = other.priority_pointer;
+            return;
+        }
 
-        let heap_is_min = fibonacci_heap_1.is_min();
+        // put the segments of other behind the segments of this heap and splice its lists of roots and free slots
+        let offset = self.nodes.append(other.nodes);
+        self.children_list
+            .append(&mut self.nodes, other.children_list.shift(offset));
+        self.free.append(&mut self.nodes, other.free.shift(offset));
+
+        let priority_node_1 = self.priority_pointer.unwrap();
+        let priority_node_2 = other.priority_pointer.unwrap().shift(offset);
 
-        // update priority node in merged heap
-        // if priority node in heap2 has higher priority than priority node in heap1, priority node in heap2 must become the new priority node of merged heap
+        // the priority node of lower priority becomes a root, taking its children along
         if InternalTree::has_higher_priority(
-            &fibonacci_heap_2.priority_pointer.as_ref().unwrap(),
-            &fibonacci_heap_1.priority_pointer.as_ref().unwrap(),
-            heap_is_min,
+            &self.nodes[priority_node_2],
+            &self.nodes[priority_node_1],
+            &self.order,
         ) {
-            // swap priority nodes of heap2 and heap1
-            let temp = fibonacci_heap_1.priority_pointer.take().unwrap();
-            fibonacci_heap_1.priority_pointer = fibonacci_heap_2.priority_pointer.take();
-            fibonacci_heap_1.children_list.push_back(temp);
+            self.priority_pointer = Some(priority_node_2);
+            stats::allocation();
+            self.children_list
+                .push_back(&mut self.nodes, priority_node_1);
         } else {
-            // if priority node of heap2 has lower priority then just add it to children list of heap1
-            fibonacci_heap_1
-                .children_list
-                .push_back(fibonacci_heap_2.priority_pointer.unwrap());
+            stats::allocation();
+            self.children_list
+                .push_back(&mut self.nodes, priority_node_2);
         }
 
-        // calculate size of merged heap
-        fibonacci_heap_1.size += fibonacci_heap_2.size;
-
-        // return merged heap
-        fibonacci_heap_1
+        self.size += other.size;
     }
 
     /// Pops and returns item with highest priority. Returns `None` if heap is empty. After pop, heap will be consolidated
@@ -492,7 +861,7 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     /// // before pop
     /// assert_eq!(
     ///     FibonacciHeap::preorder(&fibonacci_heap),
-    ///     String::from("Priority: 0\nTree 1: 3\nTree 2: 2\nTree 3: 1\n")    
+    ///     String::from("Priority: 0\nTree 1: 3\nTree 2: 2\nTree 3: 1\n")
     /// );
     ///
     /// assert_eq!(fibonacci_heap.pop(), Some(0));
@@ -504,33 +873,36 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     /// );
     /// ```
     pub fn pop(&mut self) -> Option<T> {
+        let _stats = self.stats.scope();
         if self.is_empty() {
             return None;
         }
 
         // extract node with highest priority from heap
-        let mut priority_node = self.priority_pointer.take().unwrap();
+        let priority_node = self.priority_pointer.take().unwrap();
 
         // account for deleted node
         self.size -= 1;
 
         // iterate over children of removed node and add them to children list of heap
-        let mut next = priority_node.children_list.pop_front();
-        while !next.is_none() {
-            let child = next.unwrap();
+        let mut children_list = self.nodes.children(priority_node);
+        while let Some(child) = children_list.pop_front(&mut self.nodes) {
+            self.nodes.set_parent(child, None);
+            self.nodes[child].mark = false;
 
-            self.children_list.push_back(child);
-
-            next = priority_node.children_list.pop_front();
+            stats::allocation();
+            self.children_list.push_back(&mut self.nodes, child);
         }
 
-        // extract payload of priority node
-        let payload = priority_node.get_payload();
+        // extract payload of priority node and free its slot
+        self.nodes.set_children(priority_node, List::default());
+        let payload = self.nodes[priority_node].get_payload();
+        self.free.push_back(&mut self.nodes, priority_node);
 
         // if there is nodes in heap, consolidate them
         if !self.is_empty() {
             // a temp priority node just for consolidate method to work
-            self.priority_pointer = self.children_list.pop_front();
+            self.priority_pointer = self.children_list.pop_front(&mut self.nodes);
 
             self.consolidate();
         }
@@ -539,6 +911,37 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
         Some(payload)
     }
 
+    // merges two heap-ordered trees and returns the root of the merged tree
+    fn merge_trees(&mut self, internal_tree_1: NodeId, internal_tree_2: NodeId) -> NodeId {
+        stats::consolidation();
+
+        // tree with lower priority must be child of the tree with higher priority
+        if InternalTree::has_higher_priority(
+            &self.nodes[internal_tree_1],
+            &self.nodes[internal_tree_2],
+            &self.order,
+        ) {
+            self.add_child(internal_tree_1, internal_tree_2);
+
+            internal_tree_1
+        } else {
+            self.add_child(internal_tree_2, internal_tree_1);
+
+            internal_tree_2
+        }
+    }
+
+    // adds the tree `child` as the last child of `parent`
+    fn add_child(&mut self, parent: NodeId, child: NodeId) {
+        stats::allocation();
+        self.nodes.set_parent(child, Some(parent));
+        self.nodes[child].mark = false;
+
+        let mut children_list = self.nodes.children(parent);
+        children_list.push_back(&mut self.nodes, child);
+        self.nodes.set_children(parent, children_list);
+    }
+
     // this method consolidate trees in fibonacci heap
     // until each tree in children list of the heap has a unique degree
     // ex after consolidate there can not be two trees with degree 0 like: 0 <-> 1
@@ -552,71 +955,214 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
         // use a helper vector for consolidating
         // vector keeps track of degree of present trees
         // therefore we can make sure each degree is associated with a unique tree
-        // array size will be log(heap size) with base 1.61803
-        let array_size = ((self.size as f32).log(1.61803_f32) + 1.0) as usize;
+        // array size will be log(heap size) with base 1.61803, which is less than 3/2 log(heap size) with base 2
+        let array_size = self.size.ilog2() as usize * 3 / 2 + 2;
 
         // helper vector for tracking current degrees present in consolidating process
-        let mut a: Vec<Option<InternalTree<T>>> = Vec::with_capacity(array_size);
-
-        // initialize consolidate array
-        for _ in 0..array_size {
-            a.push(None);
-        }
+        stats::allocation();
+        let mut a: Vec<Option<NodeId>> = vec![None; array_size];
 
         // add priority node to children list
         // because we have to iterate over all nodes
+        stats::allocation();
+        let priority_node = self.priority_pointer.take().unwrap();
         self.children_list
-            .push_front(self.priority_pointer.take().unwrap());
+            .push_front(&mut self.nodes, priority_node);
 
         // iterate over children and merge trees with same degrees
-        let mut next = self.children_list.pop_front();
-        while !next.is_none() {
-            let mut x = next.unwrap(); // current internal tree
-            let mut d = x.degree(); // degree of current internal tree
-            while !a[d].is_none() {
-                // iterate over consolidate array to find the place for x
-                let y = a[d].take().unwrap(); // if there exists a tree with degree of x like y
-                x = InternalTree::merge(x, y); // merge x and y and store merged tree in x
+        while let Some(mut x) = self.children_list.pop_front(&mut self.nodes) {
+            let mut d = self.nodes[x].degree(); // degree of current internal tree
+            while let Some(y) = a.get_mut(d).and_then(|slot| slot.take()) {
+                // if there exists a tree with degree of x like y, merge x and y and store merged tree in x
+                x = self.merge_trees(x, y);
                 d += 1; // degree of x is now d + 1 because it has y as its child
             }
+            // the bound on degrees is approximate, grow the array if a degree goes past it
+            if d >= a.len() {
+                a.resize(d + 1, None);
+            }
             a[d] = Some(x); // finally when a degree is free(a[d]), means degree of x is unique. store it in consolidate array
-
-            next = self.children_list.pop_front(); // go to next tree in heap
         }
 
         // update priority pointer and children list
-        self.priority_pointer = None;
-        let heap_is_min = self.is_min();
-
         // after consolidate, "a" has all the nodes in the heap
         // we have to find minimum between these nodes and add rest of them to children list of heap
         // so iterate over consolidate array
-        for i in 0..array_size {
-            if !a[i].is_none() {
-                if self.priority_pointer.is_none() {
-                    self.priority_pointer = a[i].take(); // first node becomes new priority node
-                } else {
+        for tree in a.into_iter().flatten() {
+            match self.priority_pointer {
+                // first node becomes new priority node
+                None => self.priority_pointer = Some(tree),
+                Some(priority_node) => {
                     // current tree in a has higher priority than latest found priority node, swap them
                     if InternalTree::has_higher_priority(
-                        &a[i].as_ref().unwrap(),
-                        &self.priority_pointer.as_ref().unwrap(),
-                        heap_is_min,
+                        &self.nodes[tree],
+                        &self.nodes[priority_node],
+                        &self.order,
                     ) {
-                        // swap priority node with current node in a
-                        let temp = self.priority_pointer.take().unwrap();
-                        self.priority_pointer = a[i].take();
+                        self.priority_pointer = Some(tree);
                         // add old priority node to children list of heap
-                        self.children_list.push_back(temp);
+                        stats::allocation();
+                        self.children_list.push_back(&mut self.nodes, priority_node);
                     } else {
                         // if current tree in has lower priority than latest found priority node, just add to children list of heap
-                        self.children_list.push_back(a[i].take().unwrap());
+                        stats::allocation();
+                        self.children_list.push_back(&mut self.nodes, tree);
                     }
                 }
             }
         }
     }
 
-    /// Returns a reference to item with highest priority
+    // moves the tree of `node` from the children of its parent to the roots
+    fn cut(&mut self, node: NodeId, parent: NodeId) {
+        let mut children_list = self.nodes.children(parent);
+        children_list.remove(&mut self.nodes, node);
+        self.nodes.set_children(parent, children_list);
+
+        self.nodes.set_parent(node, None);
+        self.nodes[node].mark = false;
+        self.children_list.push_back(&mut self.nodes, node);
+    }
+
+    // cuts marked ancestors starting at `node` and marks the first unmarked one, so that a node loses at most
+    // two children before it becomes a root, which keeps degrees logarithmic
+    fn cascading_cut(&mut self, mut node: NodeId) {
+        while let Some(parent) = self.nodes.parent(node) {
+            if !self.nodes[node].mark {
+                self.nodes[node].mark = true;
+                return;
+            }
+            self.cut(node, parent);
+            node = parent;
+        }
+    }
+
+    // makes the root `node` the priority node
+    fn make_priority(&mut self, node: NodeId) {
+        let priority_node = self.priority_pointer.unwrap();
+        if priority_node != node {
+            self.children_list.remove(&mut self.nodes, node);
+            self.children_list.push_back(&mut self.nodes, priority_node);
+            self.priority_pointer = Some(node);
+        }
+    }
+
+    /// Returns a reference to the item of `handle`, None if the item is no longer in the heap
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    pub fn get(&self, handle: FibonacciHandle) -> Option<&T> {
+        self.nodes[self.locate(handle)?].payload.as_ref()
+    }
+
+    /// Returns true if the item of `handle` is still in the heap
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    pub fn contains(&self, handle: FibonacciHandle) -> bool {
+        self.locate(handle).is_some()
+    }
+
+    /// Replaces the item of `handle` with `payload`, which must have at least the same priority: smaller or equal
+    /// in a min heap, greater or equal in a max heap, not greater by the order of a heap initialized by `init_with`
+    /// or `init_by_key`. If the item now has higher priority than its parent, its tree is cut off and becomes a root.
+    /// A parent that loses a second child is cut off as well.
+    /// * Complexity: amortized O(1)
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    /// * `payload`: the new item
+    ///
+    /// # Panics:
+    /// * panics if the item of `handle` is no longer in the heap
+    /// * panics if `payload` has lower priority than the item it replaces
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_min();
+    /// fibonacci_heap.push(1);
+    /// let handle = fibonacci_heap.push(5);
+    /// fibonacci_heap.push(3);
+    ///
+    /// fibonacci_heap.decrease_key(handle, 0);
+    ///
+    /// assert_eq!(fibonacci_heap.pop(), Some(0));
+    /// assert_eq!(fibonacci_heap.pop(), Some(1));
+    /// assert!(!fibonacci_heap.contains(handle));
+    /// ```
+    pub fn decrease_key(&mut self, handle: FibonacciHandle, payload: T) {
+        let _stats = self.stats.scope();
+        let node = match self.locate(handle) {
+            Some(node) => node,
+            None => panic!("Handle does not refer to an item in the heap"),
+        };
+        let current = self.nodes[node].payload.as_ref().unwrap();
+        if self.order.compare(&payload, current) == Ordering::Greater {
+            panic!("New payload must not have lower priority than the current one");
+        }
+        self.nodes[node].payload = Some(payload);
+
+        if let Some(parent) = self.nodes.parent(node) {
+            if !InternalTree::has_higher_priority(
+                &self.nodes[parent],
+                &self.nodes[node],
+                &self.order,
+            ) {
+                self.cut(node, parent);
+                self.cascading_cut(parent);
+            }
+        }
+
+        let priority_node = self.priority_pointer.unwrap();
+        if self.nodes.parent(node).is_none()
+            && node != priority_node
+            && InternalTree::has_higher_priority(
+                &self.nodes[node],
+                &self.nodes[priority_node],
+                &self.order,
+            )
+        {
+            self.make_priority(node);
+        }
+    }
+
+    /// Removes the item of `handle` from the heap and returns it, None if the item is no longer in the heap.
+    /// The tree of the item is cut off like in `decrease_key` and the item is then popped
+    /// * Complexity: amortized O(log n)
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_max();
+    /// fibonacci_heap.push(1);
+    /// let handle = fibonacci_heap.push(5);
+    /// fibonacci_heap.push(3);
+    ///
+    /// assert_eq!(fibonacci_heap.delete(handle), Some(5));
+    /// assert_eq!(fibonacci_heap.delete(handle), None);
+    /// assert_eq!(fibonacci_heap.pop(), Some(3));
+    /// ```
+    pub fn delete(&mut self, handle: FibonacciHandle) -> Option<T> {
+        let node = self.locate(handle)?;
+        {
+            let _stats = self.stats.scope();
+            if let Some(parent) = self.nodes.parent(node) {
+                self.cut(node, parent);
+                self.cascading_cut(parent);
+            }
+            self.make_priority(node);
+        }
+
+        self.pop()
+    }
+
+    /// Returns a reference to item with highest priority, None if the heap is empty
     ///
     /// # Examples
     /// ```
@@ -626,14 +1172,126 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     ///
     /// fibonacci_heap.push(0);
     ///
-    /// assert_eq!(*fibonacci_heap.peek(), Some(0));
+    /// assert_eq!(fibonacci_heap.peek(), Some(&0));
     /// ```
-    pub fn peek(&self) -> &Option<T> {
-        if self.is_empty() {
-            return &None;
+    pub fn peek(&self) -> Option<&T> {
+        self.nodes[self.priority_pointer?].payload.as_ref()
+    }
+
+    /// Returns a mutable reference to item with highest priority, None if the heap is empty.
+    /// If the item is changed through the reference, the heap is restored when the reference is dropped:
+    /// children of the item become roots and the trees are consolidated, like in `pop`. Handles stay valid
+    /// * Complexity: amortized O(log n) if the item is changed, O(1) otherwise
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    /// use rudac::heap::FibonacciPeekMut;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_min();
+    /// let handle = fibonacci_heap.push(1);
+    /// fibonacci_heap.push(5);
+    /// fibonacci_heap.push(3);
+    ///
+    /// *fibonacci_heap.peek_mut().unwrap() = 4;
+    ///
+    /// assert_eq!(fibonacci_heap.peek(), Some(&3));
+    /// assert_eq!(fibonacci_heap.get(handle), Some(&4));
+    ///
+    /// // the item can also be popped through the reference
+    /// let top = fibonacci_heap.peek_mut().unwrap();
+    /// assert_eq!(FibonacciPeekMut::pop(top), 3);
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<FibonacciPeekMut<'_, T>> {
+        self.priority_pointer?;
+
+        Some(FibonacciPeekMut {
+            heap: self,
+            changed: false,
+        })
+    }
+
+    // makes the children of the priority node roots and finds the priority node again, after its item changed
+    fn restore_priority(&mut self) {
+        let _stats = self.stats.scope();
+        let priority_node = self.priority_pointer.unwrap();
+        let mut children_list = self.nodes.children(priority_node);
+        while let Some(child) = children_list.pop_front(&mut self.nodes) {
+            self.nodes.set_parent(child, None);
+            self.nodes[child].mark = false;
+
+            stats::allocation();
+            self.children_list.push_back(&mut self.nodes, child);
         }
+        self.nodes.set_children(priority_node, List::default());
+
+        self.consolidate();
+    }
 
-        self.priority_pointer.as_ref().unwrap().peek_payload()
+    /// Returns an iterator over the items of the heap in no particular order
+    /// * Complexity: O(n) for the whole iteration, n including the free slots
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_min();
+    /// fibonacci_heap.push(2);
+    /// fibonacci_heap.push(1);
+    ///
+    /// assert_eq!(fibonacci_heap.iter().sum::<i32>(), 3);
+    /// ```
+    pub fn iter(&self) -> FibonacciIter<'_, T> {
+        FibonacciIter {
+            nodes: self.nodes.segments.iter().flatten(),
+            remaining: self.size,
+        }
+    }
+
+    /// Consumes the heap and returns an iterator over its items in the order they would be popped
+    /// * Complexity: amortized O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_max();
+    /// fibonacci_heap.extend(vec![1, 3, 2]);
+    ///
+    /// assert_eq!(fibonacci_heap.into_iter_sorted().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// ```
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, FibonacciHeap<T>> {
+        IntoIterSorted::init(self)
+    }
+
+    /// Consumes the heap and returns its items in the order they would be popped
+    /// * Complexity: amortized O(n log n)
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter_sorted().collect()
+    }
+
+    /// Removes every item of the heap and returns an iterator over them in the order they would be popped.
+    /// The heap is empty even if the iterator is not consumed, and handles to the removed items are no longer valid
+    /// * Complexity: amortized O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_min();
+    /// let handle = fibonacci_heap.push(2);
+    /// fibonacci_heap.push(1);
+    ///
+    /// assert_eq!(fibonacci_heap.drain_sorted().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert!(fibonacci_heap.is_empty());
+    /// assert!(!fibonacci_heap.contains(handle));
+    /// ```
+    pub fn drain_sorted(&mut self) -> IntoIterSorted<T, FibonacciHeap<T>> {
+        let mut drained =
+            core::mem::replace(self, FibonacciHeap::init(self.min, self.order.clone()));
+        core::mem::swap(&mut self.stats, &mut drained.stats);
+
+        IntoIterSorted::init(drained)
     }
 
     /// Clears the heap and resets internal flags
@@ -651,11 +1309,26 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
     /// assert_eq!(fibonacci_heap.pop(), None);
     /// ```
     pub fn clear(&mut self) {
-        self.children_list.clear();
+        self.nodes = Arena::init();
+        self.free = List::default();
+        self.children_list = List::default();
         self.size = 0;
         self.priority_pointer = None;
     }
 
+    /// Returns counts of the operations performed by the heap since it was created or since the last `reset_stats`.
+    /// Available with the `stats` feature
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> OpStats {
+        self.stats.stats()
+    }
+
+    /// Sets all operation counts to zero. Available with the `stats` feature
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
     /// Returns true if there are no more items in the heap
     ///
     /// # Examples
@@ -674,105 +1347,564 @@ impl<T: std::cmp::Ord> FibonacciHeap<T> {
         self.size == 0
     }
 
-    /// Returns number of items in heap
-    ///
-    /// # Examples
-    /// ```
-    /// use rudac::heap::FibonacciHeap;
-    ///
-    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_min();
-    /// fibonacci_heap.push(0);
-    /// fibonacci_heap.push(1);
-    ///
-    /// assert_eq!(fibonacci_heap.size(), 2);
-    /// ```
-    pub fn size(&self) -> usize {
-        self.size
+    /// Returns number of items in heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_min();
+    /// fibonacci_heap.push(0);
+    /// fibonacci_heap.push(1);
+    ///
+    /// assert_eq!(fibonacci_heap.size(), 2);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if the heap is initialized as a min heap, or by `init_with` or `init_by_key`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_min();
+    ///
+    /// assert_eq!(fibonacci_heap.is_min(), true);
+    /// ```
+    pub fn is_min(&self) -> bool {
+        self.min
+    }
+
+    /// Returns true if the heap is initialized as a max heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_max();
+    ///
+    /// assert_eq!(fibonacci_heap.is_max(), true);
+    /// ```
+    pub fn is_max(&self) -> bool {
+        !self.is_min()
+    }
+}
+
+impl<T> FibonacciHeap<T>
+where
+    T: core::fmt::Display,
+{
+    // It's like preorder function of Binomial Heap
+    fn tree_preorder(&self, node: NodeId) -> String {
+        String::from(self._preorder(node).trim())
+    }
+
+    fn _preorder(&self, node: NodeId) -> String {
+        let mut node_list = String::from("");
+
+        if let Some(value) = self.nodes[node].peek_payload() {
+            node_list.push_str(format!("{} ", value).as_str());
+        }
+        for child in self.nodes.children(node).iter(&self.nodes) {
+            node_list.push_str(self._preorder(child).as_str());
+        }
+
+        node_list
+    }
+
+    /// Returns the preorder representation of the heap. it has the form of:</br>
+    /// Priority: *preorder representation of tree containing priority value*\n
+    /// Tree i: *preorder representation of the internal tree of rank i*\n
+    ///
+    /// # Arguments:
+    /// * `fibonacci_heap`: reference to a fibonacci heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_min();
+    /// for i in 0..14 {
+    ///     fibonacci_heap.push(i);
+    /// }
+    ///
+    /// fibonacci_heap.pop();
+    ///
+    /// assert_eq!(
+    ///     FibonacciHeap::preorder(&fibonacci_heap),
+    ///     String::from("Priority: 1 2 3 4 5 6 7 8\nTree 1: 13\nTree 2: 9 10 11 12\n")
+    /// );
+    /// ```
+    pub fn preorder(fibonacci_heap: &FibonacciHeap<T>) -> String {
+        let mut node_list = String::from("");
+
+        if let Some(priority_node) = fibonacci_heap.priority_pointer {
+            node_list.push_str(
+                format!(
+                    "Priority: {}\n",
+                    fibonacci_heap.tree_preorder(priority_node)
+                )
+                .as_str(),
+            );
+        }
+
+        for (index, internal_tree) in fibonacci_heap
+            .children_list
+            .iter(&fibonacci_heap.nodes)
+            .enumerate()
+        {
+            node_list.push_str(format!("Tree {}: ", index + 1).as_str());
+            node_list.push_str(fibonacci_heap.tree_preorder(internal_tree).as_str());
+
+            node_list.push('\n');
+        }
+
+        node_list
+    }
+}
+
+impl<T: core::cmp::Ord> core::iter::FromIterator<T> for FibonacciHeap<T> {
+    // collects into a min heap
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        FibonacciHeap::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T> Extend<T> for FibonacciHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for payload in iter {
+            self.push(payload);
+        }
+    }
+}
+
+impl<T> IntoIterator for FibonacciHeap<T> {
+    type Item = T;
+    type IntoIter = FibonacciIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        FibonacciIntoIter {
+            nodes: self.nodes.segments.into_iter().flatten(),
+            remaining: self.size,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a FibonacciHeap<T> {
+    type Item = &'a T;
+    type IntoIter = FibonacciIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Encode> FibonacciHeap<T> {
+    // writes payload and children of `node` in preorder, the min flag is written once by the heap
+    fn write_tree(&self, node: NodeId, writer: &mut Writer) {
+        self.nodes[node].payload.encode(writer);
+        let children_list = self.nodes.children(node);
+        writer.write_varint(children_list.len() as u64);
+        for child in children_list.iter(&self.nodes) {
+            self.write_tree(child, writer);
+        }
+    }
+
+    /// Returns a snapshot of the heap in the binary format of `rudac::codec`.
+    /// The snapshot keeps the exact shape and order of the trees in the heap, not only its items.
+    /// Mark bits, handles and the order of a heap initialized by `init_with` or `init_by_key` are not kept
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::FibonacciHeap;
+    ///
+    /// let mut fibonacci_heap = FibonacciHeap::init_min();
+    /// for payload in 0..6 {
+    ///     fibonacci_heap.push(payload);
+    /// }
+    /// fibonacci_heap.pop();
+    ///
+    /// let bytes = fibonacci_heap.to_bytes();
+    /// let restored = FibonacciHeap::<i32>::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(
+    ///     FibonacciHeap::preorder(&restored),
+    ///     FibonacciHeap::preorder(&fibonacci_heap)
+    /// );
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"FH");
+        self.min.encode(&mut writer);
+        self.size.encode(&mut writer);
+        match self.priority_pointer {
+            None => writer.write_u8(0),
+            Some(priority_node) => {
+                writer.write_u8(1);
+                self.write_tree(priority_node, &mut writer);
+            }
+        }
+        writer.write_varint(self.children_list.len() as u64);
+        for tree in self.children_list.iter(&self.nodes) {
+            self.write_tree(tree, &mut writer);
+        }
+
+        writer.into_bytes()
+    }
+}
+
+impl<T: core::cmp::Ord + Decode> FibonacciHeap<T> {
+    // reads a tree written by `write_tree` into `nodes`, returns its root and its number of nodes, checking the heap order
+    fn read_tree(
+        reader: &mut Reader,
+        nodes: &mut Arena<T>,
+        order: &Order<T>,
+        depth: usize,
+    ) -> Option<(NodeId, usize)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        // nodes of a Fibonacci heap always hold a payload
+        let payload = Option::<T>::decode(reader)?;
+        let degree = reader.read_len()?;
+        let root = nodes.push(InternalTree::init(payload?));
+
+        let mut children_list = List::default();
+        let mut count: usize = 1;
+        for _ in 0..degree {
+            let (child, child_nodes) = FibonacciHeap::read_tree(reader, nodes, order, depth + 1)?;
+            if !InternalTree::has_higher_priority(&nodes[root], &nodes[child], order) {
+                return None;
+            }
+            count = count.checked_add(child_nodes)?;
+            nodes.set_parent(child, Some(root));
+            children_list.push_back(nodes, child);
+        }
+        nodes.set_children(root, children_list);
+
+        Some((root, count))
+    }
+
+    /// Restores a heap from a snapshot made by `to_bytes`.
+    /// Returns `None` if the bytes are malformed or do not describe a valid Fibonacci heap
+    ///
+    /// # Arguments
+    /// * `bytes`: the snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Option<FibonacciHeap<T>> {
+        let mut reader = Reader::init(bytes);
+        read_header(&mut reader, b"FH")?;
+        let min = bool::decode(&mut reader)?;
+        let size = usize::decode(&mut reader)?;
+        let heap = if min {
+            FibonacciHeap::init_min()
+        } else {
+            FibonacciHeap::init_max()
+        };
+
+        let mut nodes = Arena::init();
+        let mut count: usize = 0;
+        let priority_pointer = match reader.read_u8()? {
+            0 => None,
+            1 => {
+                let (tree, tree_nodes) =
+                    FibonacciHeap::read_tree(&mut reader, &mut nodes, &heap.order, 0)?;
+                count = tree_nodes;
+                Some(tree)
+            }
+            _ => return None,
+        };
+
+        let trees = reader.read_len()?;
+        let mut children_list = List::default();
+        for _ in 0..trees {
+            let (tree, tree_nodes) =
+                FibonacciHeap::read_tree(&mut reader, &mut nodes, &heap.order, 0)?;
+            // the tree under the priority pointer has the highest priority root
+            if let Some(top) = priority_pointer {
+                if !InternalTree::has_higher_priority(&nodes[top], &nodes[tree], &heap.order) {
+                    return None;
+                }
+            }
+            count = count.checked_add(tree_nodes)?;
+            children_list.push_back(&mut nodes, tree);
+        }
+
+        if !reader.is_at_end() || count != size || (priority_pointer.is_none() && trees > 0) {
+            return None;
+        }
+
+        Some(FibonacciHeap {
+            nodes,
+            children_list,
+            size,
+            priority_pointer,
+            ..heap
+        })
+    }
+}
+
+impl<T: core::cmp::Ord> Replayable for FibonacciHeap<T> {
+    type Key = T;
+    type Value = ();
+
+    fn apply(&mut self, op: Op<T>) {
+        match op {
+            Op::Push(item) => {
+                self.push(item);
+            }
+            Op::Pop => {
+                self.pop();
+            }
+            Op::Insert(..) | Op::Remove(_) => {}
+        }
+    }
+}
+
+impl<T: core::cmp::Ord> Default for FibonacciHeap<T> {
+    fn default() -> Self {
+        FibonacciHeap::init_min()
+    }
+}
+
+// heaps are equal when both are min or both are max heaps whose trees have the same shape, payloads and marks in
+// the same order, with the highest priority at the same root. orders given to `init_with` or `init_by_key` are not
+// compared
+impl<T: PartialEq> PartialEq for FibonacciHeap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        let priority_root = |heap: &FibonacciHeap<T>| {
+            let priority_pointer = heap.priority_pointer?;
+            heap.children_list
+                .iter(&heap.nodes)
+                .position(|node| node == priority_pointer)
+        };
+        if self.min != other.min
+            || self.size != other.size
+            || priority_root(self) != priority_root(other)
+        {
+            return false;
+        }
+
+        // nodes at the same place of both heaps, their next siblings and first children are compared later
+        let mut stack = vec![(self.children_list.first, other.children_list.first)];
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (None, None) => {}
+                (Some(node), Some(other_node)) => {
+                    let (tree, other_tree) = (&self.nodes[node], &other.nodes[other_node]);
+                    if tree.payload != other_tree.payload
+                        || tree.degree != other_tree.degree
+                        || tree.mark != other_tree.mark
+                    {
+                        return false;
+                    }
+                    stack.push((self.nodes.next(node), other.nodes.next(other_node)));
+                    stack.push((
+                        self.nodes.children(node).first,
+                        other.nodes.children(other_node).first,
+                    ));
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+impl<T: Eq> Eq for FibonacciHeap<T> {}
+
+impl<T> MemoryFootprint for FibonacciHeap<T> {
+    fn footprint(&self) -> Footprint {
+        self.nodes.with_buffers(Footprint::of::<T>(self.size))
+    }
+}
+
+// a heap is written as its items in the order they would be popped and read back ordered by `Ord`, heaps made by
+// `init_with` or `init_by_key` come back as min heaps
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for FibonacciHeap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort_by(|item1, item2| self.order.compare(item1, item2));
+
+        serialize_items(serializer, "FibonacciHeap", HEAP_FIELDS, self.min, &items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: core::cmp::Ord + Deserialize<'de>> Deserialize<'de> for FibonacciHeap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (min, items): (bool, Vec<T>) =
+            deserialize_items(deserializer, "FibonacciHeap", HEAP_FIELDS)?;
+
+        let mut fibonacci_heap = if min {
+            FibonacciHeap::init_min()
+        } else {
+            FibonacciHeap::init_max()
+        };
+        fibonacci_heap.extend(items);
+
+        Ok(fibonacci_heap)
+    }
+}
+
+#[cfg(test)]
+mod internal_tree_tests {
+    use super::*;
+
+    // a heap holding the detached trees of `payloads`
+    fn arena(payloads: &[usize]) -> FibonacciHeap<usize> {
+        let mut fh = FibonacciHeap::init_min();
+        for payload in payloads {
+            fh.allocate(*payload);
+        }
+
+        fh
+    }
+
+    // position of the node of the `slot`th payload given to `arena`
+    fn at(slot: usize) -> NodeId {
+        NodeId { segment: 0, slot }
+    }
+
+    #[test]
+    fn heap_fibonacci_internal_tree_init() {
+        let it = InternalTree::init(1);
+
+        assert_eq!(it.degree(), 0);
+        assert_eq!(*it.peek_payload(), Some(1));
+        assert!(it.parent.is_none());
+        assert!(!it.mark);
+    }
+
+    #[test]
+    fn heap_fibonacci_internal_tree_is_smaller() {
+        let it1 = InternalTree::init(0);
+        let it2 = InternalTree::init(1);
+        let it3 = InternalTree::init(0);
+        let order = Order::natural(true);
+
+        assert!(InternalTree::has_higher_priority(&it1, &it2, &order));
+        assert!(InternalTree::has_higher_priority(&it1, &it3, &order));
+        assert!(!InternalTree::has_higher_priority(&it2, &it1, &order));
     }
 
-    /// Returns true if the heap is initialized as a min heap
-    ///
-    /// # Examples
-    /// ```
-    /// use rudac::heap::FibonacciHeap;
-    ///
-    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_min();
-    ///
-    /// assert_eq!(fibonacci_heap.is_min(), true);
-    /// ```
-    pub fn is_min(&self) -> bool {
-        self.min
+    #[test]
+    fn heap_fibonacci_internal_tree_add_child_1() {
+        let mut fh = arena(&[0, 1]);
+
+        fh.add_child(at(0), at(1));
+
+        assert_eq!(fh.nodes[at(0)].degree(), 1);
+        assert_eq!(fh.nodes.parent(at(1)), Some(at(0)));
+        assert_eq!(fh.nodes.children(at(0)).last, Some(at(1)));
     }
 
-    /// Returns true if the heap is initialized as a max heap
-    ///
-    /// # Examples
-    /// ```
-    /// use rudac::heap::FibonacciHeap;
-    ///
-    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_max();
-    ///
-    /// assert_eq!(fibonacci_heap.is_max(), true);
-    /// ```
-    pub fn is_max(&self) -> bool {
-        !self.is_min()
+    #[test]
+    fn heap_fibonacci_internal_tree_add_child_2() {
+        let mut fh = arena(&[0, 1]);
+
+        fh.add_child(at(1), at(0));
+
+        assert_eq!(fh.nodes[at(1)].degree(), 1);
+        assert_eq!(fh.nodes.parent(at(0)), Some(at(1)));
+        assert_eq!(fh.nodes.children(at(1)).last, Some(at(0)));
     }
-}
 
-impl<T> FibonacciHeap<T>
-where
-    T: std::cmp::Ord + std::fmt::Display,
-{
-    /// Returns the preorder representation of the heap. it has the form of:</br>
-    /// Priority: *preorder representation of tree containing priority value*\n
-    /// Tree i: *preorder representation of the internal tree of rank i*\n
-    ///
-    /// # Arguments:
-    /// * `fibonacci_heap`: reference to a fibonacci heap
-    ///
-    /// # Examples
-    /// ```
-    /// use rudac::heap::FibonacciHeap;
-    ///
-    /// let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::init_min();
-    /// for i in 0..14 {
-    ///     fibonacci_heap.push(i)
-    /// }
-    ///
-    /// fibonacci_heap.pop();
-    ///
-    /// assert_eq!(
-    ///     FibonacciHeap::preorder(&fibonacci_heap),
-    ///     String::from("Priority: 1 2 3 4 5 6 7 8\nTree 1: 13\nTree 2: 9 10 11 12\n")
-    /// );
-    /// ```
-    pub fn preorder(fibonacci_heap: &FibonacciHeap<T>) -> String {
-        let mut node_list = String::from("");
+    #[test]
+    fn heap_fibonacci_internal_tree_merge_1() {
+        let mut fh = arena(&[0, 1]);
 
-        if !fibonacci_heap.priority_pointer.is_none() {
-            node_list.push_str(
-                format!(
-                    "Priority: {}\n",
-                    InternalTree::preorder(&fibonacci_heap.priority_pointer.as_ref().unwrap())
-                )
-                .as_str(),
-            );
-        }
+        let merged_tree = fh.merge_trees(at(0), at(1));
 
-        for (index, internal_tree) in fibonacci_heap.children_list.iter().enumerate() {
-            node_list.push_str(format!("Tree {}: ", index + 1).as_str());
-            node_list.push_str(InternalTree::preorder(&internal_tree).as_str());
+        assert_eq!(merged_tree, at(0));
+        assert_eq!(fh.nodes[merged_tree].degree(), 1);
+        assert_eq!(fh.tree_preorder(merged_tree), String::from("0 1"));
+    }
 
-            node_list.push_str("\n");
-        }
+    #[test]
+    fn heap_fibonacci_internal_tree_merge_2() {
+        let mut fh = arena(&[0, 1]);
 
-        node_list
+        let merged_tree = fh.merge_trees(at(1), at(0));
+
+        assert_eq!(merged_tree, at(0));
+        assert_eq!(fh.nodes[merged_tree].degree(), 1);
+        assert_eq!(fh.tree_preorder(merged_tree), String::from("0 1"));
+    }
+
+    #[test]
+    fn heap_fibonacci_internal_tree_merge_3() {
+        let mut fh = arena(&[0, 1, 2, 3]);
+        let merged_tree_1 = fh.merge_trees(at(1), at(0));
+        let merged_tree_2 = fh.merge_trees(at(2), at(3));
+
+        let merged_tree = fh.merge_trees(merged_tree_1, merged_tree_2);
+
+        assert_eq!(fh.nodes[merged_tree].degree(), 2);
+        assert_eq!(fh.tree_preorder(merged_tree), String::from("0 1 2 3"));
+    }
+
+    #[test]
+    fn heap_fibonacci_internal_tree_list() {
+        let mut fh = arena(&[0, 1, 2, 3]);
+        let mut list = List::default();
+        list.push_back(&mut fh.nodes, at(1));
+        list.push_back(&mut fh.nodes, at(2));
+        list.push_front(&mut fh.nodes, at(0));
+        let mut other = List::default();
+        other.push_back(&mut fh.nodes, at(3));
+        list.append(&mut fh.nodes, other);
+
+        assert_eq!(
+            list.iter(&fh.nodes).collect::<Vec<_>>(),
+            vec![at(0), at(1), at(2), at(3)]
+        );
+
+        list.remove(&mut fh.nodes, at(2));
+        list.remove(&mut fh.nodes, at(3));
+        assert_eq!(list.pop_front(&mut fh.nodes), Some(at(0)));
+        assert_eq!(list.iter(&fh.nodes).collect::<Vec<_>>(), vec![at(1)]);
+        assert_eq!(
+            (list.first, list.last, list.len()),
+            (Some(at(1)), Some(at(1)), 1)
+        );
+    }
+
+    #[test]
+    fn heap_fibonacci_internal_tree_links_across_segments() {
+        let mut fh = arena(&[0, 1]);
+        fh.nodes.segments.push(Vec::new());
+        let far = fh.nodes.push(InternalTree::init(2));
+        assert_eq!(far, at(0).shift(1));
+
+        fh.add_child(at(1), far);
+        fh.add_child(far, at(0));
+        assert_eq!(fh.nodes.parent(far), Some(at(1)));
+        assert_eq!(fh.nodes.parent(at(0)), Some(far));
+
+        // links are relative, so they survive segments put in front of theirs
+        let mut front = arena(&[5]);
+        let offset = front.nodes.append(fh.nodes);
+        assert_eq!(offset, 1);
+        assert_eq!(
+            front.nodes.parent(far.shift(offset)),
+            Some(at(1).shift(offset))
+        );
+        assert_eq!(
+            front.nodes.children(far.shift(offset)).first,
+            Some(at(0).shift(offset))
+        );
     }
 }
 
 #[cfg(test)]
 mod fibonacci_heap_tests {
     use super::*;
+    use crate::util::{Rng, SplitMix64};
 
     #[test]
     fn heap_fibonacci_init() {
@@ -792,14 +1924,7 @@ mod fibonacci_heap_tests {
         fh.push(3);
 
         assert_eq!(fh.children_list.len(), 2);
-        assert_eq!(
-            fh.priority_pointer
-                .as_ref()
-                .unwrap()
-                .peek_payload()
-                .unwrap(),
-            0
-        );
+        assert_eq!(*fh.peek().unwrap(), 0);
 
         assert_eq!(
             FibonacciHeap::preorder(&fh),
@@ -816,14 +1941,7 @@ mod fibonacci_heap_tests {
         fh.push(0);
 
         assert_eq!(fh.children_list.len(), 2);
-        assert_eq!(
-            fh.priority_pointer
-                .as_ref()
-                .unwrap()
-                .peek_payload()
-                .unwrap(),
-            0
-        );
+        assert_eq!(*fh.peek().unwrap(), 0);
 
         assert_eq!(
             FibonacciHeap::preorder(&fh),
@@ -842,15 +1960,7 @@ mod fibonacci_heap_tests {
         let merged_heap = FibonacciHeap::merge(fh1, fh2);
 
         assert_eq!(merged_heap.size, 2);
-        assert_eq!(
-            merged_heap
-                .priority_pointer
-                .as_ref()
-                .unwrap()
-                .peek_payload()
-                .unwrap(),
-            0
-        );
+        assert_eq!(*merged_heap.peek().unwrap(), 0);
         assert_eq!(
             FibonacciHeap::preorder(&merged_heap),
             String::from("Priority: 0\nTree 1: 1\n")
@@ -870,15 +1980,7 @@ mod fibonacci_heap_tests {
         let merged_heap = FibonacciHeap::merge(fh2, fh1);
 
         assert_eq!(merged_heap.size, 4);
-        assert_eq!(
-            merged_heap
-                .priority_pointer
-                .as_ref()
-                .unwrap()
-                .peek_payload()
-                .unwrap(),
-            0
-        );
+        assert_eq!(*merged_heap.peek().unwrap(), 0);
         assert_eq!(
             FibonacciHeap::preorder(&merged_heap),
             String::from("Priority: 0\nTree 1: 3\nTree 2: 2\nTree 3: 1\n")
@@ -896,15 +1998,7 @@ mod fibonacci_heap_tests {
         let merged_heap = FibonacciHeap::merge(fh2, fh1);
 
         assert_eq!(merged_heap.size, 2);
-        assert_eq!(
-            merged_heap
-                .priority_pointer
-                .as_ref()
-                .unwrap()
-                .peek_payload()
-                .unwrap(),
-            0
-        );
+        assert_eq!(*merged_heap.peek().unwrap(), 0);
         assert_eq!(
             FibonacciHeap::preorder(&merged_heap),
             String::from("Priority: 0\nTree 1: 2\n")
@@ -925,14 +2019,14 @@ mod fibonacci_heap_tests {
     fn heap_fibonacci_merge_after_consolidate() {
         let mut fh1: FibonacciHeap<usize> = FibonacciHeap::init_min();
         for i in 0..14 {
-            fh1.push(i)
+            fh1.push(i);
         }
 
         fh1.consolidate();
 
         let mut fh2: FibonacciHeap<usize> = FibonacciHeap::init_min();
         for i in 14..20 {
-            fh2.push(i)
+            fh2.push(i);
         }
 
         fh2.consolidate();
@@ -1018,7 +2112,7 @@ mod fibonacci_heap_tests {
     fn heap_fibonacci_consolidate_6() {
         let mut fh: FibonacciHeap<usize> = FibonacciHeap::init_min();
         for i in 0..14 {
-            fh.push(i)
+            fh.push(i);
         }
 
         fh.consolidate();
@@ -1116,7 +2210,7 @@ mod fibonacci_heap_tests {
     fn heap_fibonacci_pop_multi_2() {
         let mut fh: FibonacciHeap<usize> = FibonacciHeap::init_min();
         for i in 0..5 {
-            fh.push(i)
+            fh.push(i);
         }
         assert_eq!(fh.pop(), Some(0));
         assert_eq!(fh.size(), 4);
@@ -1147,4 +2241,503 @@ mod fibonacci_heap_tests {
         assert_eq!(fh.size(), 0);
         assert_eq!(FibonacciHeap::preorder(&fh), String::from(""));
     }
+
+    #[test]
+    fn heap_fibonacci_bytes_round_trip() {
+        let mut fibonacci_heap = FibonacciHeap::init_min();
+        for payload in 0..60 {
+            fibonacci_heap.push((payload * 23) % 60);
+        }
+        for _ in 0..7 {
+            fibonacci_heap.pop();
+        }
+        fibonacci_heap.push(-1);
+
+        let bytes = fibonacci_heap.to_bytes();
+        let mut restored = FibonacciHeap::<i32>::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            FibonacciHeap::preorder(&restored),
+            FibonacciHeap::preorder(&fibonacci_heap)
+        );
+        while let Some(payload) = fibonacci_heap.pop() {
+            assert_eq!(restored.pop(), Some(payload));
+        }
+        assert!(restored.is_empty());
+
+        let empty = FibonacciHeap::<i32>::init_max().to_bytes();
+        assert!(FibonacciHeap::<i32>::from_bytes(&empty).unwrap().is_max());
+        assert!(FibonacciHeap::<i32>::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn heap_fibonacci_stats() {
+        let mut fibonacci_heap = FibonacciHeap::init_min();
+        for i in 0..16 {
+            fibonacci_heap.push(i);
+        }
+
+        // pushes are lazy: a comparison with the priority node and no links
+        assert_eq!(fibonacci_heap.stats().consolidations, 0);
+        assert_eq!(fibonacci_heap.stats().comparisons, 15);
+
+        // the first pop links the remaining 15 roots
+        assert_eq!(fibonacci_heap.pop(), Some(0));
+        assert_eq!(fibonacci_heap.stats().consolidations, 11);
+
+        let mut other = FibonacciHeap::init_min();
+        other.push(100);
+        let merged = FibonacciHeap::merge(fibonacci_heap, other);
+        assert_eq!(merged.stats().consolidations, 11);
+        // pushes, links, choosing the priority node among the 4 roots left and the merge
+        assert_eq!(merged.stats().comparisons, 15 + 11 + 3 + 1);
+    }
+
+    #[test]
+    fn heap_fibonacci_decrease_key_and_delete_match_model() {
+        let mut rng = SplitMix64::init(101);
+        for min in [true, false] {
+            let mut fh = if min {
+                FibonacciHeap::init_min()
+            } else {
+                FibonacciHeap::init_max()
+            };
+            // every live item with its handle
+            let mut model: Vec<(FibonacciHandle, i64)> = Vec::new();
+            for _ in 0..4000 {
+                match rng.next_below(6) {
+                    0 | 1 => {
+                        let payload = rng.next_below(1000) as i64;
+                        model.push((fh.push(payload), payload));
+                    }
+                    2 if !model.is_empty() => {
+                        let index = rng.next_below(model.len() as u64) as usize;
+                        let change = rng.next_below(100) as i64;
+                        let payload = if min {
+                            model[index].1 - change
+                        } else {
+                            model[index].1 + change
+                        };
+                        fh.decrease_key(model[index].0, payload);
+                        model[index].1 = payload;
+                    }
+                    3 if !model.is_empty() => {
+                        let index = rng.next_below(model.len() as u64) as usize;
+                        let (handle, payload) = model.swap_remove(index);
+                        assert_eq!(fh.delete(handle), Some(payload));
+                        assert!(!fh.contains(handle));
+                    }
+                    _ => {
+                        let expected = if min {
+                            model.iter().map(|(_, payload)| *payload).min()
+                        } else {
+                            model.iter().map(|(_, payload)| *payload).max()
+                        };
+                        assert_eq!(fh.pop(), expected);
+                        if let Some(expected) = expected {
+                            let index = model
+                                .iter()
+                                .position(|(handle, payload)| {
+                                    *payload == expected && !fh.contains(*handle)
+                                })
+                                .unwrap();
+                            model.swap_remove(index);
+                        }
+                    }
+                }
+                assert_eq!(fh.size(), model.len());
+                for (handle, payload) in &model {
+                    assert_eq!(fh.get(*handle), Some(payload));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn heap_fibonacci_meld_matches_model() {
+        let mut rng = SplitMix64::init(104);
+        let mut fh = FibonacciHeap::init_min();
+        // live items pushed into `fh` with their handles, and items melded in from other heaps
+        let mut model: Vec<(FibonacciHandle, i64)> = Vec::new();
+        let mut melded: Vec<i64> = Vec::new();
+        let mut segments = 0;
+        for _ in 0..3000 {
+            match rng.next_below(5) {
+                0 => {
+                    let payload = rng.next_below(1000) as i64;
+                    model.push((fh.push(payload), payload));
+                }
+                1 => {
+                    // popping leaves free slots in the other heap, which the melded heap reuses
+                    let mut other = FibonacciHeap::init_min();
+                    for _ in 0..rng.next_below(30) {
+                        other.push(rng.next_below(1000) as i64);
+                    }
+                    other.pop();
+                    melded.extend(other.iter());
+                    // the segment of other is put behind, unless one of the heaps is empty
+                    if !other.is_empty() {
+                        segments = if fh.is_empty() { 1 } else { segments + 1 };
+                    }
+                    fh.meld(other);
+                    assert_eq!(fh.nodes.segments.len(), segments);
+                }
+                2 if !model.is_empty() => {
+                    let index = rng.next_below(model.len() as u64) as usize;
+                    model[index].1 -= rng.next_below(100) as i64;
+                    fh.decrease_key(model[index].0, model[index].1);
+                }
+                _ => {
+                    let expected = model
+                        .iter()
+                        .map(|(_, payload)| *payload)
+                        .chain(melded.iter().copied())
+                        .min();
+                    assert_eq!(fh.pop(), expected);
+                    if let Some(expected) = expected {
+                        match model.iter().position(|(handle, payload)| {
+                            *payload == expected && !fh.contains(*handle)
+                        }) {
+                            Some(index) => {
+                                model.swap_remove(index);
+                            }
+                            None => {
+                                let index = melded.iter().position(|payload| *payload == expected);
+                                melded.swap_remove(index.unwrap());
+                            }
+                        }
+                    }
+                }
+            }
+            assert_eq!(fh.size(), model.len() + melded.len());
+            for (handle, payload) in &model {
+                assert_eq!(fh.get(*handle), Some(payload));
+            }
+        }
+    }
+
+    #[test]
+    fn heap_fibonacci_decrease_key_cascading_cut() {
+        let mut fh = FibonacciHeap::init_min();
+        let handles: Vec<FibonacciHandle> = (0..9).map(|i| fh.push(i * 10)).collect();
+        fh.pop();
+        assert_eq!(
+            FibonacciHeap::preorder(&fh),
+            String::from("Priority: 10 20 30 40 50 60 70 80\n")
+        );
+
+        // cutting a child marks its parent, cutting a second child cuts the parent too
+        fh.decrease_key(handles[8], 75);
+        assert_eq!(
+            FibonacciHeap::preorder(&fh),
+            String::from("Priority: 10 20 30 40 50 60 70 75\n")
+        );
+        fh.decrease_key(handles[8], 45);
+        assert_eq!(
+            FibonacciHeap::preorder(&fh),
+            String::from("Priority: 10 20 30 40 50 60 70\nTree 1: 45\n")
+        );
+        fh.decrease_key(handles[7], 5);
+        assert_eq!(
+            FibonacciHeap::preorder(&fh),
+            String::from("Priority: 5\nTree 1: 45\nTree 2: 10 20 30 40 50 60\n")
+        );
+        fh.decrease_key(handles[6], 15);
+        assert_eq!(
+            FibonacciHeap::preorder(&fh),
+            String::from("Priority: 5\nTree 1: 45\nTree 2: 10 20 30 40\nTree 3: 15\nTree 4: 50\n")
+        );
+
+        assert_eq!(fh.delete(handles[3]), Some(30));
+        let mut popped = Vec::new();
+        while let Some(payload) = fh.pop() {
+            popped.push(payload);
+        }
+        assert_eq!(popped, vec![5, 10, 15, 20, 40, 45, 50]);
+    }
+
+    #[test]
+    fn heap_fibonacci_handles_after_merge() {
+        let mut fh1 = FibonacciHeap::init_max();
+        let handle = fh1.push(3);
+        fh1.push(5);
+        let mut fh2 = FibonacciHeap::init_max();
+        fh2.push(4);
+
+        let mut merged = FibonacciHeap::merge(fh1, fh2);
+        merged.decrease_key(handle, 9);
+
+        assert_eq!(merged.pop(), Some(9));
+        assert_eq!(merged.get(handle), None);
+        assert_eq!(merged.delete(handle), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "New payload must not have lower priority than the current one")]
+    fn heap_fibonacci_panic_decrease_key() {
+        let mut fh = FibonacciHeap::init_min();
+        let handle = fh.push(3);
+
+        fh.decrease_key(handle, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Handle does not refer to an item in the heap")]
+    fn heap_fibonacci_panic_decrease_key_removed() {
+        let mut fh = FibonacciHeap::init_min();
+        let handle = fh.push(3);
+        fh.pop();
+        // the slot is reused, but the handle still refers to the popped item
+        fh.push(3);
+
+        fh.decrease_key(handle, 1);
+    }
+
+    #[test]
+    fn heap_fibonacci_peek_mut_keeps_handles() {
+        let mut rng = SplitMix64::init(103);
+        let mut fh = FibonacciHeap::init_min();
+        let mut model: Vec<(FibonacciHandle, u64)> = Vec::new();
+        // payloads are distinct, so the model knows which handle holds the top item
+        for i in 0..200 {
+            let payload = rng.next_below(1000) * 1000 + i;
+            model.push((fh.push(payload), payload));
+        }
+        fh.pop();
+        model.sort_by_key(|(_, payload)| *payload);
+        model.remove(0);
+
+        for i in 200..700 {
+            let payload = rng.next_below(1000) * 1000 + i;
+            *fh.peek_mut().unwrap() = payload;
+            model.sort_by_key(|(_, payload)| *payload);
+            model[0].1 = payload;
+
+            assert_eq!(fh.peek(), model.iter().map(|(_, payload)| payload).min());
+            for (handle, payload) in &model {
+                assert_eq!(fh.get(*handle), Some(payload));
+            }
+        }
+
+        // reading through the reference leaves the heap as it is
+        let shape = FibonacciHeap::preorder(&fh);
+        let top = *fh.peek().unwrap();
+        assert_eq!(*fh.peek_mut().unwrap(), top);
+        assert_eq!(FibonacciHeap::preorder(&fh), shape);
+
+        let top = fh.peek_mut().unwrap();
+        let expected = *top;
+        assert_eq!(FibonacciPeekMut::pop(top), expected);
+        assert_eq!(fh.size(), model.len() - 1);
+    }
+
+    #[test]
+    fn heap_fibonacci_init_with_matches_model() {
+        // points ordered by their distance from the origin, farthest first
+        let farthest =
+            |p1: &(f64, f64), p2: &(f64, f64)| p2.0.hypot(p2.1).total_cmp(&p1.0.hypot(p1.1));
+        let mut rng = SplitMix64::init(17);
+        let mut fh = FibonacciHeap::init_with(farthest);
+        let mut model = Vec::new();
+        for _ in 0..300 {
+            let point = (rng.next_f64() * 10.0, rng.next_f64() * 10.0);
+            model.push((fh.push(point), point));
+        }
+
+        for _ in 0..100 {
+            // moving a point away from the origin increases its priority
+            let index = rng.next_below(model.len() as u64) as usize;
+            let (handle, (x, y)) = model[index];
+            let point = (x * 2.0, y * 2.0);
+            fh.decrease_key(handle, point);
+            model[index].1 = point;
+
+            model.sort_by(|(_, p1), (_, p2)| farthest(p1, p2));
+            assert_eq!(fh.pop(), Some(model.remove(0).1));
+        }
+
+        let popped: Vec<(f64, f64)> = std::iter::from_fn(|| fh.pop()).collect();
+        let expected: Vec<(f64, f64)> = model.iter().map(|(_, point)| *point).collect();
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    fn heap_fibonacci_init_with_captured_state() {
+        // vertices ordered by a distance table the comparator owns
+        let mut rng = SplitMix64::init(19);
+        let distances: Vec<u64> = (0..200).map(|_| rng.next_below(1000)).collect();
+        let table = distances.clone();
+        let mut fh =
+            FibonacciHeap::init_with(move |v1: &usize, v2: &usize| table[*v1].cmp(&table[*v2]));
+        fh.extend(0..distances.len());
+
+        let cloned = fh.clone();
+        let popped: Vec<u64> = std::iter::from_fn(|| fh.pop())
+            .map(|v| distances[v])
+            .collect();
+        let mut expected = distances.clone();
+        expected.sort_unstable();
+        assert_eq!(popped, expected);
+        assert_eq!(cloned.into_sorted_vec().len(), distances.len());
+    }
+
+    #[test]
+    fn heap_fibonacci_init_by_key() {
+        let mut fh = FibonacciHeap::init_by_key(|word: &String| (word.len(), word.clone()));
+        for word in ["pairing", "fibonacci", "dary", "heap"] {
+            fh.push(String::from(word));
+        }
+
+        assert!(fh.is_min());
+        assert_eq!(fh.pop().as_deref(), Some("dary"));
+        assert_eq!(fh.pop().as_deref(), Some("heap"));
+        assert_eq!(fh.pop().as_deref(), Some("pairing"));
+        assert_eq!(fh.pop().as_deref(), Some("fibonacci"));
+    }
+
+    #[test]
+    #[should_panic(expected = "New payload must not have lower priority than the current one")]
+    fn heap_fibonacci_init_with_panic_decrease_key() {
+        let mut fh = FibonacciHeap::init_with(|s1: &&str, s2: &&str| s1.len().cmp(&s2.len()));
+        let handle = fh.push("rudac");
+
+        fh.decrease_key(handle, "fibonacci");
+    }
+
+    #[test]
+    fn heap_fibonacci_iterators() {
+        let mut fh: FibonacciHeap<usize> = (0..20).rev().collect();
+        assert!(fh.is_min());
+
+        // popping and deleting leaves free slots that the iterators skip
+        let handle = fh.push(100);
+        fh.pop();
+        fh.delete(handle);
+        assert_eq!(fh.iter().len(), 19);
+        let mut items: Vec<usize> = fh.iter().copied().collect();
+        items.sort();
+        assert_eq!(items, (1..20).collect::<Vec<_>>());
+
+        let mut owned: Vec<usize> = FibonacciHeap::merge(fh, FibonacciHeap::init_min())
+            .into_iter()
+            .collect();
+        owned.sort();
+        assert_eq!(owned, (1..20).collect::<Vec<_>>());
+
+        let mut fh = FibonacciHeap::init_with(|s1: &&str, s2: &&str| s1.len().cmp(&s2.len()));
+        fh.extend(vec!["heap", "fibonacci", "rudac"]);
+        assert_eq!(
+            fh.drain_sorted().collect::<Vec<_>>(),
+            vec!["heap", "rudac", "fibonacci"]
+        );
+        assert!(fh.is_empty());
+
+        // the drained heap keeps its order
+        fh.extend(vec!["ab", "a"]);
+        assert_eq!(fh.into_iter_sorted().collect::<Vec<_>>(), vec!["a", "ab"]);
+    }
+
+    #[test]
+    fn heap_fibonacci_from_vec() {
+        let empty: FibonacciHeap<u64> = FibonacciHeap::from_vec(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.peek(), None);
+
+        let mut rng = SplitMix64::init(12);
+        let items: Vec<u64> = (0..200).map(|_| rng.next_below(50)).collect();
+        let mut fh = FibonacciHeap::from_vec(items.clone());
+        assert_eq!(fh.size(), 200);
+        assert_eq!(fh.peek(), items.iter().min());
+
+        // pushes and handles work on a heap built in bulk
+        let handle = fh.push(25);
+        fh.decrease_key(handle, 0);
+        assert_eq!(fh.pop(), Some(0));
+
+        let mut sorted = items;
+        sorted.sort();
+        assert_eq!(fh.into_sorted_vec(), sorted);
+    }
+
+    #[test]
+    fn heap_fibonacci_meld_keeps_children_of_priority_node() {
+        // popping consolidates the heaps, so both priority nodes have children
+        let mut fh = FibonacciHeap::from_vec((10..30).collect());
+        fh.pop();
+        let mut other = FibonacciHeap::from_vec((0..10).collect());
+        let handle = other.push(100);
+        other.pop();
+        assert!(other.nodes[other.priority_pointer.unwrap()].degree() > 0);
+
+        fh.meld(other);
+        assert_eq!(fh.size(), 29);
+        assert_eq!(fh.peek(), Some(&1));
+        // handles of the melded heap are not valid
+        assert!(!fh.contains(handle));
+        assert_eq!(
+            fh.into_sorted_vec(),
+            (1..10).chain(11..30).chain([100]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn heap_fibonacci_meld_keeps_handles() {
+        let mut fh = FibonacciHeap::init_min();
+        let handles: Vec<_> = (0..10).map(|item| fh.push(item * 10)).collect();
+        fh.pop();
+
+        let mut other = FibonacciHeap::init_min();
+        for item in 0..10 {
+            other.push(item * 10 + 5);
+        }
+        other.pop();
+        fh.meld(other);
+
+        fh.decrease_key(handles[9], 1);
+        assert_eq!(fh.delete(handles[4]), Some(40));
+        assert_eq!(fh.pop(), Some(1));
+        assert_eq!(fh.size(), 16);
+
+        // an empty heap takes the nodes of the other heap over along with their handles
+        let mut empty = FibonacciHeap::init_min();
+        let mut other = FibonacciHeap::init_min();
+        let handle = other.push(7);
+        empty.meld(other);
+        assert_eq!(empty.get(handle), Some(&7));
+    }
+
+    #[test]
+    fn heap_fibonacci_clone_and_eq() {
+        let mut fibonacci_heap: FibonacciHeap<usize> = FibonacciHeap::default();
+        let handles: Vec<FibonacciHandle> =
+            (10..20).map(|item| fibonacci_heap.push(item)).collect();
+        fibonacci_heap.pop();
+        fibonacci_heap.decrease_key(handles[7], 2);
+
+        // handles of the original refer to the same items of the copy
+        let mut copy = fibonacci_heap.clone();
+        assert!(copy == fibonacci_heap);
+        copy.decrease_key(handles[5], 1);
+        assert!(copy != fibonacci_heap);
+        assert_eq!(copy.peek(), Some(&1));
+        assert_eq!(fibonacci_heap.peek(), Some(&2));
+
+        // equal items in a different shape are not equal
+        let flat = FibonacciHeap::from_vec(fibonacci_heap.clone().into_sorted_vec());
+        assert!(flat != fibonacci_heap);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn heap_fibonacci_serde() {
+        let mut fibonacci_heap = FibonacciHeap::from_vec(vec![5, 1, 8, 3]);
+        fibonacci_heap.pop();
+
+        let json = serde_json::to_string(&fibonacci_heap).unwrap();
+        assert_eq!(json, r#"{"min":true,"items":[3,5,8]}"#);
+
+        let restored: FibonacciHeap<i32> = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_min());
+        assert_eq!(restored.into_sorted_vec(), vec![3, 5, 8]);
+    }
 }
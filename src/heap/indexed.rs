@@ -0,0 +1,435 @@
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// An indexed heap is a binary heap of priorities addressed by small integer indices, such as the vertices of a
+/// graph. Along with the heap it keeps the position of every index, so the priority of an index can be read, changed
+/// or removed in O(log n) time without handles. Indices share one position table, so its size follows the largest
+/// index pushed
+///
+/// # Examples
+/// ```
+/// use rudac::heap::IndexedHeap;
+///
+/// let mut indexed_heap = IndexedHeap::init_min();
+/// indexed_heap.push(0, 7);
+/// indexed_heap.push(3, 4);
+/// indexed_heap.push(5, 9);
+///
+/// indexed_heap.change_priority(5, 1);
+///
+/// assert_eq!(indexed_heap.contains(3), true);
+/// assert_eq!(indexed_heap.get(0), Some(&7));
+/// assert_eq!(indexed_heap.pop(), Some((5, 1)));
+/// assert_eq!(indexed_heap.pop(), Some((3, 4)));
+/// assert_eq!(indexed_heap.pop(), Some((0, 7)));
+/// assert_eq!(indexed_heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<P> {
+    // binary heap of indices and their priorities
+    tree: Vec<(usize, P)>,
+
+    // position[i] is the position of index i in the tree, None if i is not in the heap
+    position: Vec<Option<usize>>,
+
+    // indicates wether current heap is initialized as a min heap or not
+    min: bool,
+
+    // order of the priorities, the index whose priority is least by it is popped first
+    order: Order<P>,
+}
+
+impl<P: core::cmp::Ord> IndexedHeap<P> {
+    /// Initializes a min heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::IndexedHeap;
+    ///
+    /// let indexed_heap: IndexedHeap<u64> = IndexedHeap::init_min();
+    ///
+    /// assert_eq!(indexed_heap.is_min(), true);
+    /// ```
+    pub fn init_min() -> IndexedHeap<P> {
+        IndexedHeap::init(true, Order::natural(true))
+    }
+
+    /// Initializes a max heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::IndexedHeap;
+    ///
+    /// let indexed_heap: IndexedHeap<u64> = IndexedHeap::init_max();
+    ///
+    /// assert_eq!(indexed_heap.is_max(), true);
+    /// ```
+    pub fn init_max() -> IndexedHeap<P> {
+        IndexedHeap::init(false, Order::natural(false))
+    }
+}
+
+impl<P> IndexedHeap<P> {
+    fn init(min: bool, order: Order<P>) -> IndexedHeap<P> {
+        IndexedHeap {
+            tree: Vec::new(),
+            position: Vec::new(),
+            min,
+            order,
+        }
+    }
+
+    /// Initializes a heap ordered by `compare` instead of `Ord`, popping the least priority by `compare` first.
+    /// Such a heap counts as a min heap
+    ///
+    /// # Arguments
+    /// * `compare`: order of the priorities
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::IndexedHeap;
+    ///
+    /// let mut indexed_heap: IndexedHeap<f64> = IndexedHeap::init_with(f64::total_cmp);
+    /// indexed_heap.push(1, 2.5);
+    /// indexed_heap.push(2, 0.5);
+    ///
+    /// assert_eq!(indexed_heap.pop(), Some((2, 0.5)));
+    /// ```
+    pub fn init_with<F>(compare: F) -> IndexedHeap<P>
+    where
+        F: Fn(&P, &P) -> Ordering + Send + Sync + 'static,
+    {
+        IndexedHeap::init(true, Order::with(compare))
+    }
+
+    /// Initializes a heap ordered by the key `key` extracts from every priority, popping the index whose priority
+    /// has the least key first. The key is computed on every comparison, so it should be cheap. Such a heap counts
+    /// as a min heap
+    ///
+    /// # Arguments
+    /// * `key`: key of a priority
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::IndexedHeap;
+    ///
+    /// // vertices by distance, the predecessor rides along
+    /// let mut indexed_heap = IndexedHeap::init_by_key(|entry: &(u64, usize)| entry.0);
+    /// indexed_heap.push(3, (7, 0));
+    /// indexed_heap.push(4, (2, 1));
+    ///
+    /// assert_eq!(indexed_heap.pop(), Some((4, (2, 1))));
+    /// ```
+    pub fn init_by_key<K, F>(key: F) -> IndexedHeap<P>
+    where
+        K: Ord,
+        F: Fn(&P) -> K + Send + Sync + 'static,
+    {
+        IndexedHeap::init(true, Order::by_key(key))
+    }
+
+    // returns true if the item at `position1` has higher priority than the item at `position2`
+    fn has_higher_priority(&self, position1: usize, position2: usize) -> bool {
+        self.order
+            .compare(&self.tree[position1].1, &self.tree[position2].1)
+            == Ordering::Less
+    }
+
+    // swaps the items at `position1` and `position2` and keeps their positions up to date
+    fn swap(&mut self, position1: usize, position2: usize) {
+        self.tree.swap(position1, position2);
+        self.position[self.tree[position1].0] = Some(position1);
+        self.position[self.tree[position2].0] = Some(position2);
+    }
+
+    // moves the item at `position` up until its parent has at least its priority
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !self.has_higher_priority(position, parent) {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    // moves the item at `position` down until it has at least the priority of its children
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let left = 2 * position + 1;
+            if left >= self.tree.len() {
+                break;
+            }
+
+            let right = left + 1;
+            let best = if right < self.tree.len() && self.has_higher_priority(right, left) {
+                right
+            } else {
+                left
+            };
+            if !self.has_higher_priority(best, position) {
+                break;
+            }
+            self.swap(position, best);
+            position = best;
+        }
+    }
+
+    // returns the position of `index` in the tree
+    fn locate(&self, index: usize) -> Option<usize> {
+        self.position.get(index).copied().flatten()
+    }
+
+    /// Pushes `index` with `priority` into the heap
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    /// * `priority`: priority of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is already in the heap
+    pub fn push(&mut self, index: usize, priority: P) {
+        if self.contains(index) {
+            panic!("Index is already in the heap");
+        }
+        if index >= self.position.len() {
+            self.position.resize(index + 1, None);
+        }
+
+        self.tree.push((index, priority));
+        self.position[index] = Some(self.tree.len() - 1);
+        self.sift_up(self.tree.len() - 1);
+    }
+
+    /// Changes the priority of `index` to `priority`, which may be higher or lower than the current one
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    /// * `priority`: new priority of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is not in the heap
+    pub fn change_priority(&mut self, index: usize, priority: P) {
+        let position = match self.locate(index) {
+            Some(position) => position,
+            None => panic!("Index is not in the heap"),
+        };
+
+        self.tree[position].1 = priority;
+        self.sift_up(position);
+        self.sift_down(self.position[index].unwrap());
+    }
+
+    /// Pushes `index` with `priority`, or changes its priority to `priority` if it is already in the heap
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    /// * `priority`: priority of the item
+    pub fn push_or_change(&mut self, index: usize, priority: P) {
+        if self.contains(index) {
+            self.change_priority(index, priority);
+        } else {
+            self.push(index, priority);
+        }
+    }
+
+    /// Removes `index` from the heap and returns its priority, None if it is not in the heap
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    pub fn remove(&mut self, index: usize) -> Option<P> {
+        let position = self.locate(index)?;
+
+        let last = self.tree.len() - 1;
+        self.swap(position, last);
+        let (_, priority) = self.tree.pop().unwrap();
+        self.position[index] = None;
+        if position < self.tree.len() {
+            // the last item took the place of the removed one and may have to move either way
+            let moved = self.tree[position].0;
+            self.sift_up(position);
+            self.sift_down(self.position[moved].unwrap());
+        }
+
+        Some(priority)
+    }
+
+    /// Pops and returns the index with highest priority along with its priority. Returns `None` if heap is empty
+    /// * Complexity: O(log n)
+    pub fn pop(&mut self) -> Option<(usize, P)> {
+        let index = self.tree.first()?.0;
+        let priority = self.remove(index).unwrap();
+
+        Some((index, priority))
+    }
+
+    /// Returns the index with highest priority along with a reference to its priority, None if the heap is empty
+    pub fn peek(&self) -> Option<(usize, &P)> {
+        self.top().map(|(index, priority)| (*index, priority))
+    }
+
+    // the index with highest priority along with its priority
+    pub(crate) fn top(&self) -> Option<&(usize, P)> {
+        self.tree.first()
+    }
+
+    /// Returns a reference to the priority of `index`, None if it is not in the heap
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    pub fn get(&self, index: usize) -> Option<&P> {
+        self.locate(index).map(|position| &self.tree[position].1)
+    }
+
+    /// Returns true if `index` is in the heap
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    pub fn contains(&self, index: usize) -> bool {
+        self.locate(index).is_some()
+    }
+
+    /// Returns number of items in heap
+    pub fn size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Removes every item of the heap
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.position.clear();
+    }
+
+    /// Returns true if the heap is initialized as a min heap, or by `init_with` or `init_by_key`
+    pub fn is_min(&self) -> bool {
+        self.min
+    }
+
+    /// Returns true if the heap is initialized as a max heap
+    pub fn is_max(&self) -> bool {
+        !self.is_min()
+    }
+
+    /// Returns an iterator over the indices and priorities of the heap in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &P)> {
+        self.tree.iter().map(|(index, priority)| (*index, priority))
+    }
+}
+
+impl<P: core::cmp::Ord> Default for IndexedHeap<P> {
+    fn default() -> Self {
+        IndexedHeap::init_min()
+    }
+}
+
+impl<P> MemoryFootprint for IndexedHeap<P> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<P>(self.tree.len())
+            .with_vec(&self.tree)
+            .with_vec(&self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn heap_indexed_matches_model() {
+        let mut rng = SplitMix64::init(29);
+        let mut indexed_heap = IndexedHeap::init_max();
+        let mut model: Vec<Option<u64>> = vec![None; 50];
+        for _ in 0..5000 {
+            let index = rng.next_below(50) as usize;
+            let priority = rng.next_below(100);
+            match rng.next_below(4) {
+                0 => {
+                    let best = (0..50)
+                        .filter_map(|i| model[i].map(|p| (p, i)))
+                        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+                    match indexed_heap.pop() {
+                        Some((index, priority)) => {
+                            assert_eq!(Some(priority), best.map(|b| b.0));
+                            assert_eq!(model[index], Some(priority));
+                            model[index] = None;
+                        }
+                        None => assert!(best.is_none()),
+                    }
+                }
+                1 => assert_eq!(indexed_heap.remove(index), model[index].take()),
+                _ => {
+                    indexed_heap.push_or_change(index, priority);
+                    model[index] = Some(priority);
+                }
+            }
+
+            assert_eq!(indexed_heap.size(), model.iter().flatten().count());
+            assert_eq!(indexed_heap.get(index), model[index].as_ref());
+            assert_eq!(
+                indexed_heap.peek().map(|(_, p)| *p),
+                model.iter().flatten().max().copied()
+            );
+        }
+    }
+
+    #[test]
+    fn heap_indexed_dijkstra() {
+        // edges as (from, to, weight)
+        let edges = [
+            (0, 1, 7),
+            (0, 2, 9),
+            (0, 5, 14),
+            (1, 2, 10),
+            (1, 3, 15),
+            (2, 3, 11),
+            (2, 5, 2),
+            (3, 4, 6),
+            (4, 5, 9),
+        ];
+        let mut distance = vec![u64::MAX; 6];
+        let mut indexed_heap = IndexedHeap::init_min();
+        distance[0] = 0;
+        indexed_heap.push(0, 0);
+
+        while let Some((vertex, d)) = indexed_heap.pop() {
+            for &(a, b, w) in &edges {
+                for (from, to) in [(a, b), (b, a)] {
+                    if from == vertex && d + w < distance[to] {
+                        distance[to] = d + w;
+                        indexed_heap.push_or_change(to, d + w);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(distance, vec![0, 7, 9, 20, 20, 11]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index is already in the heap")]
+    fn heap_indexed_panic_push_twice() {
+        let mut indexed_heap = IndexedHeap::init_min();
+        indexed_heap.push(3, 1);
+        indexed_heap.push(3, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index is not in the heap")]
+    fn heap_indexed_panic_change_missing() {
+        let mut indexed_heap = IndexedHeap::init_min();
+        indexed_heap.push(3, 1);
+        indexed_heap.change_priority(4, 2);
+    }
+}
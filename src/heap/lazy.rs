@@ -0,0 +1,343 @@
+use super::Heap;
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::collections::BTreeMap;
+use core::cmp::Ordering;
+
+/// An item of a `LazyDeleteHeap` as stored in its backend heap, ordered by the item and then by the time it was
+/// pushed
+#[derive(Debug, Clone)]
+pub struct LazyEntry<T> {
+    item: T,
+    handle: usize,
+    stamp: u64,
+}
+
+impl<T> LazyEntry<T> {
+    /// Returns a reference to the item
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+}
+
+impl<T: Ord> Ord for LazyEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.item
+            .cmp(&other.item)
+            .then(self.stamp.cmp(&other.stamp))
+    }
+}
+
+impl<T: Ord> PartialOrd for LazyEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for LazyEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for LazyEntry<T> {}
+
+/// Adds removal and update of arbitrary items to any heap. `push` returns a handle for the item. Removing an item
+/// only marks its entry as stale, and updating it pushes a new entry and marks the old one as stale. Stale entries
+/// are discarded as soon as they reach the top of the backend heap, so the top is always live. This needs no
+/// decrease-key support from the backend, at the cost of keeping stale entries until they reach the top
+///
+/// # Examples
+/// ```
+/// use rudac::heap::{FibonacciHeap, LazyDeleteHeap};
+///
+/// let mut heap = LazyDeleteHeap::init(FibonacciHeap::init_min());
+/// let a = heap.push(5);
+/// let b = heap.push(3);
+/// heap.push(4);
+///
+/// heap.remove(b);
+/// heap.update(a, 1);
+///
+/// assert_eq!(heap.size(), 2);
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.pop(), Some(4));
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LazyDeleteHeap<T, H> {
+    heap: H,
+
+    // stamp of the current entry of every live handle, entries with another stamp are stale
+    live: BTreeMap<usize, u64>,
+    next_handle: usize,
+    next_stamp: u64,
+    _item: core::marker::PhantomData<T>,
+}
+
+impl<T: Ord, H: Heap<LazyEntry<T>>> LazyDeleteHeap<T, H> {
+    /// Initializes an empty heap over `backend`
+    ///
+    /// # Arguments
+    /// * `backend`: an empty heap that stores the entries
+    ///
+    /// # Panics
+    /// * panics if `backend` is not empty
+    pub fn init(backend: H) -> LazyDeleteHeap<T, H> {
+        if !backend.is_empty() {
+            panic!("Backend heap must be empty");
+        }
+
+        LazyDeleteHeap {
+            heap: backend,
+            live: BTreeMap::new(),
+            next_handle: 0,
+            next_stamp: 0,
+            _item: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns number of items in the heap, stale entries excluded
+    pub fn size(&self) -> usize {
+        self.live.len()
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    /// Returns number of stale entries still held by the backend heap
+    pub fn stale(&self) -> usize {
+        self.heap.size() - self.live.len()
+    }
+
+    /// Returns true if the item of `handle` is still in the heap
+    ///
+    /// # Arguments
+    /// * `handle`: handle returned by `push`
+    pub fn contains(&self, handle: usize) -> bool {
+        self.live.contains_key(&handle)
+    }
+
+    fn push_entry(&mut self, handle: usize, item: T) {
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        self.live.insert(handle, stamp);
+        self.heap.push(LazyEntry {
+            item,
+            handle,
+            stamp,
+        });
+    }
+
+    /// Pushes `item` into the heap and returns its handle
+    ///
+    /// # Arguments
+    /// * `item`: data to be pushed
+    pub fn push(&mut self, item: T) -> usize {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        self.push_entry(handle, item);
+
+        handle
+    }
+
+    /// Replaces the item of `handle` with `item`. Returns false and leaves the heap unchanged if the item of `handle`
+    /// is no longer in the heap
+    ///
+    /// # Arguments
+    /// * `handle`: handle returned by `push`
+    /// * `item`: the new item
+    pub fn update(&mut self, handle: usize, item: T) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+        self.push_entry(handle, item);
+        self.discard_stale();
+
+        true
+    }
+
+    /// Removes the item of `handle` from the heap. Returns false if it is no longer in the heap
+    ///
+    /// # Arguments
+    /// * `handle`: handle returned by `push`
+    pub fn remove(&mut self, handle: usize) -> bool {
+        let removed = self.live.remove(&handle).is_some();
+        self.discard_stale();
+
+        removed
+    }
+
+    // pops stale entries off the top of the backend heap
+    fn discard_stale(&mut self) {
+        while let Some(entry) = self.heap.peek() {
+            if self.live.get(&entry.handle) == Some(&entry.stamp) {
+                break;
+            }
+            self.heap.pop();
+        }
+    }
+
+    /// Returns a reference to the item at the top of the heap, None if the heap is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(|entry| &entry.item)
+    }
+
+    /// Removes and returns the item at the top of the heap, None if the heap is empty
+    pub fn pop(&mut self) -> Option<T> {
+        self.pop_with_handle().map(|(_, item)| item)
+    }
+
+    /// Removes and returns the item at the top of the heap along with its handle, None if the heap is empty
+    pub fn pop_with_handle(&mut self) -> Option<(usize, T)> {
+        let entry = self.heap.pop()?;
+        self.live.remove(&entry.handle);
+        self.discard_stale();
+
+        Some((entry.handle, entry.item))
+    }
+}
+
+impl<T: Ord, H: Heap<LazyEntry<T>>> LazyDeleteHeap<T, H> {
+    /// Consumes the heap and returns an iterator over its items in the order they would be popped
+    pub fn into_iter_sorted(mut self) -> impl Iterator<Item = T> {
+        core::iter::from_fn(move || self.pop())
+    }
+}
+
+impl<T, H> LazyDeleteHeap<T, H>
+where
+    for<'a> &'a H: IntoIterator<Item = &'a LazyEntry<T>>,
+{
+    /// Returns an iterator over the items of the heap in no particular order, stale entries excluded
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::{LazyDeleteHeap, MinMax};
+    ///
+    /// let mut heap = LazyDeleteHeap::init(MinMax::init());
+    /// let handle = heap.push(1);
+    /// heap.push(2);
+    /// heap.update(handle, 3);
+    ///
+    /// let mut items: Vec<_> = heap.iter().copied().collect();
+    /// items.sort();
+    ///
+    /// assert_eq!(items, vec![2, 3]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (&self.heap)
+            .into_iter()
+            .filter(move |entry| self.live.get(&entry.handle) == Some(&entry.stamp))
+            .map(|entry| &entry.item)
+    }
+}
+
+impl<T: Ord, H: Heap<LazyEntry<T>>> Extend<T> for LazyDeleteHeap<T, H> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T, H: MemoryFootprint> MemoryFootprint for LazyDeleteHeap<T, H> {
+    fn footprint(&self) -> Footprint {
+        self.heap.footprint().with_btree_map(&self.live)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heap::{BinomialHeap, FibonacciHeap, MinMax};
+    use crate::util::{Rng, SplitMix64};
+
+    fn matches_model<H: Heap<LazyEntry<u64>>>(backend: H, seed: u64) {
+        let mut rng = SplitMix64::init(seed);
+        let mut heap = LazyDeleteHeap::init(backend);
+        let mut model: BTreeMap<usize, u64> = BTreeMap::new();
+        for _ in 0..3000 {
+            let handle = rng.next_below(heap.next_handle as u64 + 1) as usize;
+            match rng.next_below(5) {
+                0 | 1 => {
+                    let item = rng.next_below(100);
+                    model.insert(heap.push(item), item);
+                }
+                2 => {
+                    let item = rng.next_below(100);
+                    assert_eq!(heap.update(handle, item), model.contains_key(&handle));
+                    if let Some(current) = model.get_mut(&handle) {
+                        *current = item;
+                    }
+                }
+                3 => assert_eq!(heap.remove(handle), model.remove(&handle).is_some()),
+                _ => {
+                    let smallest = model.values().min().copied();
+                    match heap.pop_with_handle() {
+                        Some((handle, item)) => {
+                            assert_eq!(Some(item), smallest);
+                            assert_eq!(model.remove(&handle), Some(item));
+                        }
+                        None => assert!(model.is_empty()),
+                    }
+                }
+            }
+            assert_eq!(heap.size(), model.len());
+            assert_eq!(heap.peek().copied(), model.values().min().copied());
+        }
+    }
+
+    #[test]
+    fn heap_lazy_matches_model_on_every_backend() {
+        matches_model(FibonacciHeap::init_min(), 91);
+        matches_model(MinMax::init(), 92);
+
+        // a binomial heap cannot be created empty
+        let mut binomial = BinomialHeap::init_min(LazyEntry {
+            item: 0,
+            handle: 0,
+            stamp: 0,
+        });
+        binomial.pop();
+        matches_model(binomial, 93);
+    }
+
+    #[test]
+    fn heap_lazy_counts_stale_entries() {
+        let mut heap = LazyDeleteHeap::init(MinMax::init());
+        let handle = heap.push(10);
+        heap.update(handle, 20);
+        heap.update(handle, 5);
+
+        // the entry of 10 reached the top when it went stale, the one of 20 is still below 5
+        assert_eq!(heap.size(), 1);
+        assert_eq!(heap.stale(), 1);
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.stale(), 0);
+        assert!(!heap.contains(handle));
+        assert_eq!(heap.pop(), None);
+
+        let first = heap.push(1);
+        heap.push(2);
+        let third = heap.push(3);
+        heap.remove(third);
+        assert_eq!(heap.stale(), 1);
+        heap.remove(first);
+        assert_eq!(heap.stale(), 1);
+        assert_eq!(heap.peek(), Some(&2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Backend heap must be empty")]
+    fn heap_lazy_panic_non_empty_backend() {
+        let mut backend = MinMax::init();
+        backend.push(LazyEntry {
+            item: 1,
+            handle: 0,
+            stamp: 0,
+        });
+        LazyDeleteHeap::init(backend);
+    }
+}
@@ -0,0 +1,560 @@
+use super::IntoIterSorted;
+use crate::replay::{Op, Replayable};
+use crate::util::{Footprint, MemoryFootprint, Rng, SeedableRng, SplitMix64};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// seed of the generator created by `init`
+const DEFAULT_SEED: u64 = 0x6d65_6c64;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    payload: T,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+/// A randomized meldable heap is a min heap made of a heap-ordered binary tree. Two heaps are melded by walking
+/// down random paths of both trees, which takes O(log n) expected time. Random choices are made by the generator `R`,
+/// so heaps built from the same seed and the same operations have the same shape on every run
+///
+/// # Examples
+/// ```
+/// use rudac::heap::MeldableHeap;
+///
+/// let mut heap: MeldableHeap<i32> = MeldableHeap::with_seed(7);
+/// heap.push(5);
+/// heap.push(1);
+///
+/// let mut other: MeldableHeap<i32> = MeldableHeap::with_seed(8);
+/// other.push(3);
+///
+/// heap.meld(other);
+///
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.pop(), Some(3));
+/// assert_eq!(heap.pop(), Some(5));
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeldableHeap<T: Ord, R: Rng = SplitMix64> {
+    root: Link<T>,
+    size: usize,
+    rng: R,
+}
+
+impl<T: Ord> MeldableHeap<T> {
+    /// Initializes an empty heap whose generator starts from a fixed seed
+    pub fn init() -> MeldableHeap<T> {
+        MeldableHeap::with_rng(SplitMix64::init(DEFAULT_SEED))
+    }
+}
+
+impl<T: Ord, R: SeedableRng> MeldableHeap<T, R> {
+    /// Initializes an empty heap with a generator created from `seed`
+    ///
+    /// # Arguments
+    /// * `seed`: seed of the generator
+    pub fn with_seed(seed: u64) -> MeldableHeap<T, R> {
+        MeldableHeap::with_rng(R::from_seed(seed))
+    }
+}
+
+impl<T: Ord, R: Rng> MeldableHeap<T, R> {
+    /// Initializes an empty heap that makes its random choices with `rng`
+    ///
+    /// # Arguments
+    /// * `rng`: the generator
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MeldableHeap;
+    /// use rudac::util::ScriptedRng;
+    ///
+    /// // always descend to the left
+    /// let mut heap = MeldableHeap::with_rng(ScriptedRng::init(vec![0]));
+    /// for i in (0..4).rev() {
+    ///     heap.push(i);
+    /// }
+    ///
+    /// assert_eq!(heap.height(), 4);
+    /// ```
+    pub fn with_rng(rng: R) -> MeldableHeap<T, R> {
+        MeldableHeap {
+            root: None,
+            size: 0,
+            rng,
+        }
+    }
+
+    /// Returns number of items in the heap
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns number of nodes on the longest path from the root to a leaf
+    pub fn height(&self) -> usize {
+        fn height<T>(link: &Link<T>) -> usize {
+            match link {
+                Some(node) => 1 + height(&node.left).max(height(&node.right)),
+                None => 0,
+            }
+        }
+
+        height(&self.root)
+    }
+
+    /// Returns the generator of the heap
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Pushes `payload` into the heap
+    ///
+    /// # Arguments
+    /// * `payload`: data to be pushed
+    pub fn push(&mut self, payload: T) {
+        let node = Some(Box::new(Node {
+            payload,
+            left: None,
+            right: None,
+        }));
+
+        let root = self.root.take();
+        self.root = merge(root, node, &mut self.rng);
+        self.size += 1;
+    }
+
+    /// Returns a reference to the smallest item of the heap, None if the heap is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.payload)
+    }
+
+    /// Returns a mutable reference to the smallest item of the heap, None if the heap is empty.
+    /// If the item is changed through the reference, its node is melded again with the rest of the heap when the
+    /// reference is dropped, which takes O(log n) expected time
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::{MeldableHeap, MeldablePeekMut};
+    ///
+    /// let mut heap: MeldableHeap<i32> = MeldableHeap::init();
+    /// heap.push(1);
+    /// heap.push(2);
+    ///
+    /// *heap.peek_mut().unwrap() += 2;
+    /// assert_eq!(heap.peek(), Some(&2));
+    ///
+    /// let top = heap.peek_mut().unwrap();
+    /// assert_eq!(MeldablePeekMut::pop(top), 2);
+    /// assert_eq!(heap.pop(), Some(3));
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<MeldablePeekMut<'_, T, R>> {
+        self.root.as_ref()?;
+
+        Some(MeldablePeekMut {
+            heap: self,
+            changed: false,
+        })
+    }
+
+    /// Removes and returns the smallest item of the heap, None if the heap is empty
+    pub fn pop(&mut self) -> Option<T> {
+        let root = *self.root.take()?;
+        self.root = merge(root.left, root.right, &mut self.rng);
+        self.size -= 1;
+
+        Some(root.payload)
+    }
+
+    /// Moves every item of `other` into this heap. The generator of `other` is dropped
+    ///
+    /// # Arguments
+    /// * `other`: the heap to be melded into this one
+    pub fn meld<S: Rng>(&mut self, other: MeldableHeap<T, S>) {
+        let root = self.root.take();
+        self.root = merge(root, other.root, &mut self.rng);
+        self.size += other.size;
+    }
+
+    /// Removes every item of the heap
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.size = 0;
+    }
+
+    /// Returns an iterator over the items of the heap in no particular order
+    pub fn iter(&self) -> MeldableIter<'_, T> {
+        MeldableIter {
+            stack: self.root.iter().map(|node| &**node).collect(),
+            remaining: self.size,
+        }
+    }
+
+    /// Consumes the heap and returns an iterator over its items from the smallest to the largest
+    /// * Complexity: O(log n) expected per item
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, MeldableHeap<T, R>> {
+        IntoIterSorted::init(self)
+    }
+
+    /// Removes every item of the heap and returns an iterator over them from the smallest to the largest.
+    /// The heap is empty even if the iterator is not consumed. The drained items are melded by a generator seeded
+    /// from the generator of the heap
+    /// * Complexity: O(log n) expected per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MeldableHeap;
+    ///
+    /// let mut heap: MeldableHeap<i32> = vec![3, 1, 2].into_iter().collect();
+    ///
+    /// assert_eq!(heap.drain_sorted().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn drain_sorted(&mut self) -> IntoIterSorted<T, MeldableHeap<T>> {
+        let mut drained = MeldableHeap::with_rng(SplitMix64::init(self.rng.next_u64()));
+        drained.root = self.root.take();
+        drained.size = core::mem::take(&mut self.size);
+
+        IntoIterSorted::init(drained)
+    }
+}
+
+impl<T: Ord> core::iter::FromIterator<T> for MeldableHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = MeldableHeap::init();
+        heap.extend(iter);
+
+        heap
+    }
+}
+
+impl<T: Ord, R: Rng> Extend<T> for MeldableHeap<T, R> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for payload in iter {
+            self.push(payload);
+        }
+    }
+}
+
+impl<T: Ord, R: Rng> IntoIterator for MeldableHeap<T, R> {
+    type Item = T;
+    type IntoIter = MeldableIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MeldableIntoIter {
+            stack: self.root.into_iter().collect(),
+            remaining: self.size,
+        }
+    }
+}
+
+impl<'a, T: Ord, R: Rng> IntoIterator for &'a MeldableHeap<T, R> {
+    type Item = &'a T;
+    type IntoIter = MeldableIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the items of a `MeldableHeap` in no particular order, created by `MeldableHeap::iter`
+pub struct MeldableIter<'a, T> {
+    // nodes whose payloads are not yielded yet
+    stack: Vec<&'a Node<T>>,
+
+    // number of items not yielded yet
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for MeldableIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.left.as_deref());
+        self.stack.extend(node.right.as_deref());
+        self.remaining -= 1;
+
+        Some(&node.payload)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for MeldableIter<'a, T> {}
+
+/// Iterator over the items of a `MeldableHeap` in no particular order, created by `into_iter`
+pub struct MeldableIntoIter<T> {
+    // nodes whose payloads are not yielded yet
+    stack: Vec<Box<Node<T>>>,
+
+    // number of items not yielded yet
+    remaining: usize,
+}
+
+impl<T> Iterator for MeldableIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let node = *self.stack.pop()?;
+        self.stack.extend(node.left);
+        self.stack.extend(node.right);
+        self.remaining -= 1;
+
+        Some(node.payload)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for MeldableIntoIter<T> {}
+
+/// Mutable reference to the smallest item of a `MeldableHeap`, created by `MeldableHeap::peek_mut`.
+/// The heap is restored when the reference is dropped
+pub struct MeldablePeekMut<'a, T: Ord, R: Rng = SplitMix64> {
+    heap: &'a mut MeldableHeap<T, R>,
+
+    // whether the item was borrowed mutably, so the heap may need to be restored
+    changed: bool,
+}
+
+impl<'a, T: Ord, R: Rng> MeldablePeekMut<'a, T, R> {
+    /// Removes the item from the heap and returns it
+    ///
+    /// # Arguments
+    /// * `this`: the reference to the item
+    pub fn pop(mut this: MeldablePeekMut<'a, T, R>) -> T {
+        // popping restores the heap anyway
+        this.changed = false;
+
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<'a, T: Ord, R: Rng> core::ops::Deref for MeldablePeekMut<'a, T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.heap.peek().unwrap()
+    }
+}
+
+impl<'a, T: Ord, R: Rng> core::ops::DerefMut for MeldablePeekMut<'a, T, R> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        &mut self.heap.root.as_mut().unwrap().payload
+    }
+}
+
+impl<'a, T: Ord, R: Rng> Drop for MeldablePeekMut<'a, T, R> {
+    fn drop(&mut self) {
+        if self.changed {
+            // detach the root from its subtrees and meld it back as a single node
+            let mut root = self.heap.root.take().unwrap();
+            let rest = merge(root.left.take(), root.right.take(), &mut self.heap.rng);
+            self.heap.root = merge(rest, Some(root), &mut self.heap.rng);
+        }
+    }
+}
+
+// merges two heap-ordered trees, descending into a random child of the smaller root
+fn merge<T: Ord, R: Rng>(first: Link<T>, second: Link<T>, rng: &mut R) -> Link<T> {
+    let (mut first, mut second) = match (first, second) {
+        (None, link) | (link, None) => return link,
+        (Some(first), Some(second)) => (first, second),
+    };
+
+    if second.payload < first.payload {
+        core::mem::swap(&mut first, &mut second);
+    }
+
+    if rng.next_u64() & 1 == 0 {
+        first.left = merge(first.left.take(), Some(second), rng);
+    } else {
+        first.right = merge(first.right.take(), Some(second), rng);
+    }
+
+    Some(first)
+}
+
+impl<T: Ord> Default for MeldableHeap<T> {
+    fn default() -> Self {
+        MeldableHeap::init()
+    }
+}
+
+impl<T: Ord, R: Rng> MemoryFootprint for MeldableHeap<T, R> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.size).with_boxes::<Node<T>>(self.size)
+    }
+}
+
+impl<T: Ord, R: Rng> Replayable for MeldableHeap<T, R> {
+    type Key = T;
+    type Value = ();
+
+    fn apply(&mut self, op: Op<T>) {
+        match op {
+            Op::Push(item) => self.push(item),
+            Op::Pop => {
+                self.pop();
+            }
+            Op::Insert(..) | Op::Remove(_) => {}
+        }
+    }
+}
+
+// a heap is written as its items in increasing order, the state of its generator is not kept
+#[cfg(feature = "serde")]
+impl<T: Ord + Serialize, R: Rng> Serialize for MeldableHeap<T, R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort();
+
+        serializer.collect_seq(items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Ord + Deserialize<'de>> Deserialize<'de> for MeldableHeap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items: Vec<T> = Vec::deserialize(deserializer)?;
+
+        Ok(items.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::ScriptedRng;
+
+    #[test]
+    fn heap_meldable_pops_in_order() {
+        let mut rng = SplitMix64::init(3);
+        let mut heap = MeldableHeap::init();
+        let mut items = Vec::new();
+        for _ in 0..500 {
+            let item = rng.next_below(100);
+            heap.push(item);
+            items.push(item);
+        }
+        items.sort();
+
+        assert_eq!(heap.size(), 500);
+        assert_eq!(heap.peek(), Some(&items[0]));
+
+        let popped: Vec<u64> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(popped, items);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn heap_meldable_peek_mut() {
+        let mut rng = SplitMix64::init(4);
+        let mut heap = MeldableHeap::init();
+        let mut items = Vec::new();
+        for _ in 0..300 {
+            let item = rng.next_below(1000);
+            heap.push(item);
+            items.push(item);
+        }
+        for _ in 0..300 {
+            items.sort();
+            let change = rng.next_below(1000);
+            *heap.peek_mut().unwrap() = change;
+            items[0] = change;
+            // reading through the reference leaves the heap as it is
+            assert_eq!(*heap.peek_mut().unwrap(), *items.iter().min().unwrap());
+        }
+        items.sort();
+
+        let popped: Vec<u64> =
+            std::iter::from_fn(|| heap.peek_mut().map(MeldablePeekMut::pop)).collect();
+        assert_eq!(popped, items);
+    }
+
+    #[test]
+    fn heap_meldable_same_seed_same_shape() {
+        let mut a = MeldableHeap::<u64>::with_seed(11);
+        let mut b = MeldableHeap::<u64>::with_seed(11);
+        for i in 0..200 {
+            a.push(i * 7 % 200);
+            b.push(i * 7 % 200);
+        }
+        a.pop();
+        b.pop();
+
+        assert_eq!(a.height(), b.height());
+        assert_eq!(format!("{:?}", a.root), format!("{:?}", b.root));
+    }
+
+    #[test]
+    fn heap_meldable_scripted_rng_forces_shape() {
+        // always descending to the right turns the heap into a sorted list
+        let mut heap = MeldableHeap::with_rng(ScriptedRng::init(vec![1]));
+        for i in 0..10 {
+            heap.push(i);
+        }
+        assert_eq!(heap.height(), 10);
+        assert_eq!(heap.rng().draws(), 45);
+
+        let mut other = MeldableHeap::with_rng(ScriptedRng::init(vec![0]));
+        other.push(100);
+        heap.meld(other);
+        assert_eq!(heap.size(), 11);
+        assert_eq!(heap.height(), 11);
+    }
+
+    #[test]
+    fn heap_meldable_iterators() {
+        let mut rng = SplitMix64::init(4);
+        let mut items: Vec<u64> = (0..300).map(|_| rng.next_below(50)).collect();
+        let mut heap: MeldableHeap<u64> = items.iter().copied().collect();
+        items.sort();
+
+        let mut borrowed: Vec<u64> = heap.iter().copied().collect();
+        borrowed.sort();
+        assert_eq!(borrowed, items);
+        assert_eq!((&heap).into_iter().len(), 300);
+
+        assert_eq!(heap.drain_sorted().collect::<Vec<_>>(), items);
+        assert!(heap.is_empty());
+        assert_eq!(heap.iter().next(), None);
+
+        heap.extend(vec![2, 1, 3]);
+        let mut owned: Vec<u64> = heap.clone().into_iter().collect();
+        owned.sort();
+        assert_eq!(owned, vec![1, 2, 3]);
+        assert_eq!(heap.into_iter_sorted().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn heap_meldable_serde() {
+        let meldable_heap: MeldableHeap<u32> = vec![6, 2, 9, 4].into_iter().collect();
+
+        let json = serde_json::to_string(&meldable_heap).unwrap();
+        assert_eq!(json, "[2,4,6,9]");
+
+        let restored: MeldableHeap<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.into_iter_sorted().collect::<Vec<_>>(),
+            vec![2, 4, 6, 9]
+        );
+    }
+}
@@ -1,3 +1,15 @@
+use super::IntoIterSorted;
+use crate::codec::{read_header, write_header, Decode, Encode, Reader, Writer};
+use crate::replay::{Op, Replayable};
+use crate::util::stats::{self, Recorder};
+#[cfg(feature = "stats")]
+use crate::util::OpStats;
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 /// A min-max heap provides constant time retrieval and logarithmic time removal of both the min and max elements in it.
 /// This makes the min-max heap a very useful data structure to implement a double-ended priority queue
 ///
@@ -18,11 +30,82 @@
 /// assert_eq!(*built_heap.peek_min().unwrap(), 1);
 /// assert_eq!(*built_heap.peek_max().unwrap(), 11);
 /// ```
-pub struct MinMax<T: std::cmp::Ord> {
+#[derive(Clone)]
+pub struct MinMax<T: core::cmp::Ord> {
     tree: Vec<T>,
+    stats: Recorder,
 }
 
-impl<T: std::cmp::Ord> MinMax<T> {
+/// `MinMax` under the name of the other heaps of this module. Both ends are served in O(log n), which makes it a
+/// bounded priority queue: after every push beyond the bound, the item at the wrong end is popped
+///
+/// # Examples
+/// ```
+/// use rudac::heap::MinMaxHeap;
+///
+/// // keep the 3 smallest items of a stream
+/// let mut smallest: MinMaxHeap<i32> = MinMaxHeap::init();
+/// for item in [7, 2, 9, 4, 1, 8, 3] {
+///     smallest.push(item);
+///     if smallest.size() > 3 {
+///         smallest.pop_max();
+///     }
+/// }
+///
+/// assert_eq!(smallest.peek_max(), Some(&3));
+/// assert_eq!(smallest.into_sorted_vec(), vec![1, 2, 3]);
+/// ```
+pub type MinMaxHeap<T> = MinMax<T>;
+
+/// Mutable reference to the min or the max value of a `MinMax` heap, created by `MinMax::peek_min_mut` or
+/// `MinMax::peek_max_mut`. The heap is restored when the reference is dropped
+pub struct MinMaxPeekMut<'a, T: core::cmp::Ord> {
+    heap: &'a mut MinMax<T>,
+
+    // position of the value in the heap
+    index: usize,
+
+    // whether the value was borrowed mutably, so the heap may need to be restored
+    changed: bool,
+}
+
+impl<'a, T: core::cmp::Ord> MinMaxPeekMut<'a, T> {
+    /// Removes the value from the heap and returns it
+    ///
+    /// # Arguments
+    /// * `this`: the reference to the value
+    pub fn pop(mut this: MinMaxPeekMut<'a, T>) -> T {
+        // removing restores the heap anyway
+        this.changed = false;
+
+        this.heap.remove(this.index)
+    }
+}
+
+impl<'a, T: core::cmp::Ord> core::ops::Deref for MinMaxPeekMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.tree[self.index]
+    }
+}
+
+impl<'a, T: core::cmp::Ord> core::ops::DerefMut for MinMaxPeekMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        &mut self.heap.tree[self.index]
+    }
+}
+
+impl<'a, T: core::cmp::Ord> Drop for MinMaxPeekMut<'a, T> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.heap.restore(self.index);
+        }
+    }
+}
+
+impl<T: core::cmp::Ord> MinMax<T> {
     /// Initializes a heap with zero capacity
     ///
     /// # Examples
@@ -34,7 +117,10 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(minmax.capacity(), 0);
     /// ```
     pub fn init() -> MinMax<T> {
-        MinMax { tree: Vec::new() }
+        MinMax {
+            tree: Vec::new(),
+            stats: Recorder::default(),
+        }
     }
 
     /// Initializes a heap with specified `capacity`
@@ -50,6 +136,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
     pub fn with_capacity(capacity: usize) -> MinMax<T> {
         MinMax {
             tree: Vec::with_capacity(capacity),
+            stats: Recorder::default(),
         }
     }
 
@@ -69,7 +156,11 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 11);
     /// ```
     pub fn build_heap(vector: Vec<T>) -> MinMax<T> {
-        let mut minmax_heap = MinMax { tree: vector };
+        let mut minmax_heap = MinMax {
+            tree: vector,
+            stats: Recorder::default(),
+        };
+        let _stats = minmax_heap.stats.scope();
 
         // to achieve O(n) complexity, method must traverse only inner nodes and escape leaves
         // thus it should iterate over from last inner node till the root
@@ -84,6 +175,47 @@ impl<T: std::cmp::Ord> MinMax<T> {
         minmax_heap
     }
 
+    /// Builds a heap of the items of `vector` like `build_heap`
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `vector`: items of the heap
+    pub fn from_vec(vector: Vec<T>) -> MinMax<T> {
+        MinMax::build_heap(vector)
+    }
+
+    /// Moves every item of `other` into the heap by appending them and rebuilding the heap bottom up like
+    /// `build_heap`
+    /// * Complexity: O(n + m) for m items in `other`
+    ///
+    /// # Arguments
+    /// * `other`: the heap whose items are moved
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MinMax;
+    ///
+    /// let mut minmax1 = MinMax::build_heap(vec![5, 1, 8]);
+    /// let minmax2 = MinMax::build_heap(vec![3, 9, 0]);
+    ///
+    /// minmax1.meld(minmax2);
+    ///
+    /// assert_eq!(minmax1.size(), 6);
+    /// assert_eq!(*minmax1.peek_min().unwrap(), 0);
+    /// assert_eq!(*minmax1.peek_max().unwrap(), 9);
+    /// ```
+    pub fn meld(&mut self, mut other: MinMax<T>) {
+        if other.is_empty() {
+            return;
+        }
+
+        let _stats = self.stats.scope();
+        self.tree.append(&mut other.tree);
+        for i in (0..self.size() / 2).rev() {
+            self.push_down(i);
+        }
+    }
+
     // pushes down a node down the heap
     // it first determines wether node is one a max level or min level
     // then calls the appropriate method
@@ -105,18 +237,21 @@ impl<T: std::cmp::Ord> MinMax<T> {
             // if smallest node is a grandchild of the current node, we must take care of the child of the current node
             // on the other hand if smallest node is the direct child of the current node, just swap them
             if is_grandchild {
+                stats::comparison();
                 if self.tree[smallest_index] < self.tree[index] {
                     self.tree.swap(smallest_index, index);
 
                     // because smallest index refers to a grandchild of current node, swapping them *may* invalidate the heap property
                     // parent of the specified grandchild might be smaller than the current node(which is swapped)
                     // thus after swapping we must check wether parent of the node referred by smallest index is smaller than it or not
+                    stats::comparison();
                     if self.tree[smallest_index] > self.tree[parent(smallest_index)] {
                         self.tree.swap(smallest_index, parent(smallest_index));
                     }
                 }
             } else {
                 // just swap the parent and child
+                stats::comparison();
                 if self.tree[smallest_index] < self.tree[index] {
                     self.tree.swap(index, smallest_index);
                 }
@@ -131,14 +266,17 @@ impl<T: std::cmp::Ord> MinMax<T> {
         while has_child(index, self.size()) {
             let (greatest_index, is_grandchild) = self.greatest_child_or_grandchild(index);
             if is_grandchild {
+                stats::comparison();
                 if self.tree[greatest_index] > self.tree[index] {
                     self.tree.swap(index, greatest_index);
 
+                    stats::comparison();
                     if self.tree[greatest_index] < self.tree[parent(greatest_index)] {
                         self.tree.swap(greatest_index, parent(greatest_index));
                     }
                 }
             } else {
+                stats::comparison();
                 if self.tree[greatest_index] > self.tree[index] {
                     self.tree.swap(index, greatest_index);
                 }
@@ -156,6 +294,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
 
             let mut smallest_index = left_child_index;
             let mut is_grandchild = false;
+            stats::comparison();
             if self.tree[left_child_index] < self.tree[smallest_index] {
                 smallest_index = left_child_index;
                 is_grandchild = false;
@@ -164,6 +303,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             // check grandchildren of left sub tree
             if has_left_child(left_child_index, self.size()) {
                 let left_grandchild_index = left_child(left_child_index);
+                stats::comparison();
                 if self.tree[left_grandchild_index] < self.tree[smallest_index] {
                     smallest_index = left_grandchild_index;
                     is_grandchild = true;
@@ -171,6 +311,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             }
             if has_right_child(left_child_index, self.size()) {
                 let right_grandchild_index = right_child(left_child_index);
+                stats::comparison();
                 if self.tree[right_grandchild_index] < self.tree[smallest_index] {
                     smallest_index = right_grandchild_index;
                     is_grandchild = true;
@@ -180,6 +321,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             // check right sub tree
             if has_right_child(index, self.size()) {
                 let right_child_index = right_child(index);
+                stats::comparison();
                 if self.tree[right_child_index] < self.tree[smallest_index] {
                     smallest_index = right_child_index;
                     is_grandchild = false;
@@ -188,6 +330,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
                 // check grandchildren of right sub tree
                 if has_left_child(right_child_index, self.size()) {
                     let left_grandchild_index = left_child(right_child_index);
+                    stats::comparison();
                     if self.tree[left_grandchild_index] < self.tree[smallest_index] {
                         smallest_index = left_grandchild_index;
                         is_grandchild = true;
@@ -196,6 +339,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
 
                 if has_right_child(right_child_index, self.size()) {
                     let right_grandchild_index = right_child(right_child_index);
+                    stats::comparison();
                     if self.tree[right_grandchild_index] < self.tree[smallest_index] {
                         smallest_index = right_grandchild_index;
                         is_grandchild = true;
@@ -218,6 +362,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             let mut greatest_index = left_child_index;
             let mut is_grandchild = false;
 
+            stats::comparison();
             if self.tree[left_child_index] > self.tree[greatest_index] {
                 greatest_index = left_child_index;
                 is_grandchild = false;
@@ -226,6 +371,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             // check grandchildren of left sub tree
             if has_left_child(left_child_index, self.size()) {
                 let left_grandchild_index = left_child(left_child_index);
+                stats::comparison();
                 if self.tree[left_grandchild_index] > self.tree[greatest_index] {
                     greatest_index = left_grandchild_index;
                     is_grandchild = true;
@@ -233,6 +379,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             }
             if has_right_child(left_child_index, self.size()) {
                 let right_grandchild_index = right_child(left_child_index);
+                stats::comparison();
                 if self.tree[right_grandchild_index] > self.tree[greatest_index] {
                     greatest_index = right_grandchild_index;
                     is_grandchild = true;
@@ -242,6 +389,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             // check right sub tree
             if has_right_child(index, self.size()) {
                 let right_child_index = right_child(index);
+                stats::comparison();
                 if self.tree[right_child_index] > self.tree[greatest_index] {
                     greatest_index = right_child_index;
                     is_grandchild = false;
@@ -250,6 +398,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
                 // check grandchildren of right sub tree
                 if has_left_child(right_child_index, self.size()) {
                     let left_grandchild_index = left_child(right_child_index);
+                    stats::comparison();
                     if self.tree[left_grandchild_index] > self.tree[greatest_index] {
                         greatest_index = left_grandchild_index;
                         is_grandchild = true;
@@ -257,6 +406,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
                 }
                 if has_right_child(right_child_index, self.size()) {
                     let right_grandchild_index = right_child(right_child_index);
+                    stats::comparison();
                     if self.tree[right_grandchild_index] > self.tree[greatest_index] {
                         greatest_index = right_grandchild_index;
                         is_grandchild = true;
@@ -292,7 +442,9 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 12);
     /// ```
     pub fn push(&mut self, item: T) {
+        let _stats = self.stats.scope();
         // append the data at end of the heap
+        stats::growth(self.tree.len(), self.tree.capacity());
         self.tree.push(item);
 
         // bubble up the node until heap property is restored
@@ -306,6 +458,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             if is_on_min_level(index) {
                 // if node is on a min level but is greater than its parent, node can replace its parent
                 // parent must be pushed up as a max node
+                stats::comparison();
                 if self.tree[index] > self.tree[parent(index)] {
                     self.tree.swap(index, parent(index));
                     self.push_up_max(parent(index));
@@ -316,6 +469,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             } else {
                 // if node is on a max level but is smaller than its parent, node can replace its parent
                 // parent must be pushed up as a min node
+                stats::comparison();
                 if self.tree[index] < self.tree[parent(index)] {
                     self.tree.swap(index, parent(index));
                     self.push_up_min(parent(index));
@@ -330,7 +484,11 @@ impl<T: std::cmp::Ord> MinMax<T> {
     // bubbles up a node until heap property is restored
     fn push_up_min(&mut self, mut index: usize) {
         // until node is smaller than its grandparent, swap them and iterate to the top of the heap
-        while has_grandparent(index) && self.tree[index] < self.tree[grandparent(index)] {
+        while has_grandparent(index) {
+            stats::comparison();
+            if self.tree[index] >= self.tree[grandparent(index)] {
+                break;
+            }
             self.tree.swap(index, grandparent(index));
 
             index = grandparent(index);
@@ -340,7 +498,11 @@ impl<T: std::cmp::Ord> MinMax<T> {
     // bubbles up a node until heap property is restored
     fn push_up_max(&mut self, mut index: usize) {
         // until node is greater than its grandparent, swap them and iterate to the top of the heap
-        while has_grandparent(index) && self.tree[index] > self.tree[grandparent(index)] {
+        while has_grandparent(index) {
+            stats::comparison();
+            if self.tree[index] <= self.tree[grandparent(index)] {
+                break;
+            }
             self.tree.swap(index, grandparent(index));
 
             index = grandparent(index);
@@ -378,12 +540,14 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 11);
     /// ```
     pub fn peek_max(&self) -> Option<&T> {
+        let _stats = self.stats.scope();
         match self.size() {
             0 => None,                // if heap is empty return None
             1 => Some(&self.tree[0]), // if there is only one item, it is max
             2 => Some(&self.tree[1]), // if there are only two item, item at index 1 is max
             _ => {
                 // if there are more than 2 items, max is either at index 1 or 2
+                stats::comparison();
                 if self.tree[1] > self.tree[2] {
                     Some(&self.tree[1])
                 } else {
@@ -393,6 +557,89 @@ impl<T: std::cmp::Ord> MinMax<T> {
         }
     }
 
+    /// Returns a mutable reference to the min value, None if the heap is empty.
+    /// If the value is changed through the reference, it is pushed down when the reference is dropped
+    /// * Complexity: O(log n) if the value is changed, O(1) otherwise
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MinMax;
+    ///
+    /// let mut minmax = MinMax::build_heap(vec![4, 1, 3, 2]);
+    ///
+    /// *minmax.peek_min_mut().unwrap() = 5;
+    ///
+    /// assert_eq!(*minmax.peek_min().unwrap(), 2);
+    /// assert_eq!(*minmax.peek_max().unwrap(), 5);
+    /// ```
+    pub fn peek_min_mut(&mut self) -> Option<MinMaxPeekMut<'_, T>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        Some(MinMaxPeekMut {
+            heap: self,
+            index: 0,
+            changed: false,
+        })
+    }
+
+    /// Returns a mutable reference to the max value, None if the heap is empty.
+    /// If the value is changed through the reference, it is pushed down when the reference is dropped
+    /// * Complexity: O(log n) if the value is changed, O(1) otherwise
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::{MinMax, MinMaxPeekMut};
+    ///
+    /// let mut minmax = MinMax::build_heap(vec![4, 1, 3, 2]);
+    ///
+    /// *minmax.peek_max_mut().unwrap() = 0;
+    /// assert_eq!(*minmax.peek_min().unwrap(), 0);
+    /// assert_eq!(*minmax.peek_max().unwrap(), 3);
+    ///
+    /// let max = minmax.peek_max_mut().unwrap();
+    /// assert_eq!(MinMaxPeekMut::pop(max), 3);
+    /// assert_eq!(minmax.size(), 3);
+    /// ```
+    pub fn peek_max_mut(&mut self) -> Option<MinMaxPeekMut<'_, T>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let index = self.find_max_index();
+        Some(MinMaxPeekMut {
+            heap: self,
+            index,
+            changed: false,
+        })
+    }
+
+    // restores the heap after the item at `index`, which was the min or the max, changed
+    fn restore(&mut self, index: usize) {
+        let _stats = self.stats.scope();
+        // a former max can become smaller than the min
+        if index != 0 {
+            stats::comparison();
+            if self.tree[index] < self.tree[0] {
+                self.tree.swap(index, 0);
+            }
+        }
+
+        self.push_down(index);
+    }
+
+    // removes the item at `index`, which holds the min or the max
+    fn remove(&mut self, index: usize) -> T {
+        let mut last_item = self.tree.pop().unwrap();
+        if index < self.tree.len() {
+            core::mem::swap(&mut last_item, &mut self.tree[index]);
+            self.restore(index);
+        }
+
+        last_item
+    }
+
     /// Pops min value from heap and returns it. returns None if heap is empty
     /// * Complexity: O(log n)
     ///
@@ -406,13 +653,14 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_min().unwrap(), 2);
     /// ```
     pub fn pop_min(&mut self) -> Option<T> {
+        let _stats = self.stats.scope();
         match self.size() {
             0 => None,                           // if heap is empty return None
             1 => Some(self.tree.pop().unwrap()), // if there is only one item, it is min
             _ => {
                 let mut last_item = self.tree.pop().unwrap(); // pop last leaf
 
-                std::mem::swap(&mut last_item, &mut self.tree[0]); // swap min with leaf
+                core::mem::swap(&mut last_item, &mut self.tree[0]); // swap min with leaf
                 self.push_down(0); // push down the leaf until heap property is restored
                 Some(last_item) // return min node
             }
@@ -432,21 +680,21 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 9);
     /// ```
     pub fn pop_max(&mut self) -> Option<T> {
+        let _stats = self.stats.scope();
         match self.size() {
             0 => None,                               // if heap is empty, return None
             1 | 2 => Some(self.tree.pop().unwrap()), // if there are only 1 or 2 item, max is at the end of the heap
             _ => {
                 // if there are more than 2 items, max is at index 1 or 2
-                let mut last_item: T;
+                stats::comparison();
+                let max_index = if self.tree[1] > self.tree[2] { 1 } else { 2 };
 
-                if self.tree[1] > self.tree[2] {
-                    last_item = self.tree.pop().unwrap(); // pop last leaf
-                    std::mem::swap(&mut last_item, &mut self.tree[1]); // swap max with leaf
-                    self.push_down(1); // push down leaf until heap property is restored
-                } else {
-                    last_item = self.tree.pop().unwrap();
-                    std::mem::swap(&mut last_item, &mut self.tree[2]);
-                    self.push_down(2);
+                let mut last_item = self.tree.pop().unwrap(); // pop last leaf
+
+                // the max may be the last leaf itself
+                if max_index < self.tree.len() {
+                    core::mem::swap(&mut last_item, &mut self.tree[max_index]); // swap max with leaf
+                    self.push_down(max_index); // push down leaf until heap property is restored
                 }
 
                 Some(last_item)
@@ -471,14 +719,19 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 13);
     /// ```
     pub fn push_pop_min(&mut self, mut item: T) -> Option<T> {
+        let _stats = self.stats.scope();
         // if heap is empty or item is already smaller than min value in heap,
         // nothing should be done just return the item
-        if self.is_empty() || item < self.tree[0] {
+        if self.is_empty() {
+            return Some(item);
+        }
+        stats::comparison();
+        if item < self.tree[0] {
             return Some(item);
         }
 
         // swap the min value with item
-        std::mem::swap(&mut item, &mut self.tree[0]);
+        core::mem::swap(&mut item, &mut self.tree[0]);
 
         // push down item until heap property is restored
         self.push_down(0);
@@ -504,18 +757,21 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 9);
     /// ```
     pub fn push_pop_max(&mut self, mut item: T) -> Option<T> {
+        let _stats = self.stats.scope();
         match self.size() {
             0 => Some(item), // if heap is empty just return the item
             _ => {
                 let max_index = self.find_max_index(); // find index of maximum value in heap
 
                 // if item is already greater than the max value in heap, just return the item
+                stats::comparison();
                 if item > self.tree[max_index] {
                     Some(item)
                 } else {
-                    std::mem::swap(&mut item, &mut self.tree[max_index]);
+                    core::mem::swap(&mut item, &mut self.tree[max_index]);
 
                     // check if `item` is smaller than root
+                    stats::comparison();
                     if self.tree[max_index] < self.tree[0] {
                         self.tree.swap(max_index, 0);
                     }
@@ -546,6 +802,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 4);
     /// ```
     pub fn replace_min(&mut self, mut item: T) -> Option<T> {
+        let _stats = self.stats.scope();
         // if heap is empty just push the item
         if self.is_empty() {
             self.push(item);
@@ -553,7 +810,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
         }
 
         // replace min with item
-        std::mem::swap(&mut item, &mut self.tree[0]);
+        core::mem::swap(&mut item, &mut self.tree[0]);
 
         // push down item until heap property is restored
         self.push_down(0);
@@ -579,6 +836,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
     /// assert_eq!(*minmax.peek_max().unwrap(), 4);
     /// ```
     pub fn replace_max(&mut self, mut item: T) -> Option<T> {
+        let _stats = self.stats.scope();
         // if heap is empty just push the item
         if self.is_empty() {
             self.push(item);
@@ -589,9 +847,10 @@ impl<T: std::cmp::Ord> MinMax<T> {
         let max_index = self.find_max_index();
 
         // swap max value with item
-        std::mem::swap(&mut item, &mut self.tree[max_index]);
+        core::mem::swap(&mut item, &mut self.tree[max_index]);
 
         // check if item is smaller than root
+        stats::comparison();
         if self.tree[max_index] < self.tree[0] {
             self.tree.swap(max_index, 0);
         }
@@ -610,6 +869,7 @@ impl<T: std::cmp::Ord> MinMax<T> {
             2 => 1, // if there are only two items, max has index 1
             _ => {
                 // if there are more than 2 items in heap, max is in index 1 or 2
+                stats::comparison();
                 if self.tree[1] > self.tree[2] {
                     1
                 } else {
@@ -639,6 +899,64 @@ impl<T: std::cmp::Ord> MinMax<T> {
         self.tree
     }
 
+    /// Returns an iterator over the items of the heap in no particular order
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.tree.iter()
+    }
+
+    /// Consumes the heap and returns an iterator over its items from the smallest to the largest
+    /// * Complexity: O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MinMax;
+    ///
+    /// let minmax = MinMax::build_heap(vec![3, 1, 2]);
+    ///
+    /// assert_eq!(minmax.into_iter_sorted().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, MinMax<T>> {
+        IntoIterSorted::init(self)
+    }
+
+    /// Consumes the heap and returns its items from the smallest to the largest
+    /// * Complexity: O(n log n)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MinMax;
+    ///
+    /// let minmax = MinMax::from_vec(vec![3, 1, 2]);
+    ///
+    /// assert_eq!(minmax.into_sorted_vec(), vec![1, 2, 3]);
+    /// ```
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter_sorted().collect()
+    }
+
+    /// Removes every item of the heap and returns an iterator over them from the smallest to the largest.
+    /// The heap is empty even if the iterator is not consumed
+    /// * Complexity: O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MinMax;
+    ///
+    /// let mut minmax = MinMax::build_heap(vec![3, 1, 2]);
+    /// let drained: Vec<_> = minmax.drain_sorted().collect();
+    ///
+    /// assert_eq!(drained, vec![1, 2, 3]);
+    /// assert!(minmax.is_empty());
+    /// ```
+    pub fn drain_sorted(&mut self) -> IntoIterSorted<T, MinMax<T>> {
+        let tree = core::mem::take(&mut self.tree);
+
+        IntoIterSorted::init(MinMax {
+            tree,
+            stats: Recorder::default(),
+        })
+    }
+
     /// Total number of elements in the heap
     pub fn size(&self) -> usize {
         self.tree.len()
@@ -658,10 +976,121 @@ impl<T: std::cmp::Ord> MinMax<T> {
     pub fn capacity(&self) -> usize {
         self.tree.capacity()
     }
+
+    /// Returns counts of the operations performed by the heap since it was created or since the last `reset_stats`.
+    /// Available with the `stats` feature
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> OpStats {
+        self.stats.stats()
+    }
+
+    /// Sets all operation counts to zero. Available with the `stats` feature
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+}
+
+impl<T: core::cmp::Ord> core::iter::FromIterator<T> for MinMax<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        MinMax::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T: core::cmp::Ord> Extend<T> for MinMax<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T: core::cmp::Ord> IntoIterator for MinMax<T> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    // items in the order of the array layout
+    fn into_iter(self) -> Self::IntoIter {
+        self.tree.into_iter()
+    }
+}
+
+impl<'a, T: core::cmp::Ord> IntoIterator for &'a MinMax<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: core::cmp::Ord + Encode> MinMax<T> {
+    /// Returns a snapshot of the heap in the binary format of `rudac::codec`.
+    /// The snapshot keeps the array layout of the heap, not only its items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MinMax;
+    ///
+    /// let minmax = MinMax::build_heap(vec![9, 8, 2, 3, 4, 5, 11, 6, 7, 1]);
+    ///
+    /// let bytes = minmax.to_bytes();
+    /// let restored = MinMax::<i32>::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(restored.into_vec(), minmax.into_vec());
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"MM");
+        self.tree.encode(&mut writer);
+
+        writer.into_bytes()
+    }
+}
+
+impl<T: core::cmp::Ord + Decode> MinMax<T> {
+    /// Restores a heap from a snapshot made by `to_bytes`.
+    /// Returns `None` if the bytes are malformed or do not describe a valid min-max heap
+    ///
+    /// # Arguments
+    /// * `bytes`: the snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Option<MinMax<T>> {
+        let mut reader = Reader::init(bytes);
+        read_header(&mut reader, b"MM")?;
+        let tree = Vec::<T>::decode(&mut reader)?;
+        if !reader.is_at_end() {
+            return None;
+        }
+
+        // checking every node against its parent and grandparent covers all ancestors by transitivity
+        for index in 1..tree.len() {
+            let mut ancestors = vec![parent(index)];
+            if has_grandparent(index) {
+                ancestors.push(grandparent(index));
+            }
+            for ancestor in ancestors {
+                let valid = if is_on_min_level(ancestor) {
+                    tree[ancestor] <= tree[index]
+                } else {
+                    tree[ancestor] >= tree[index]
+                };
+                if !valid {
+                    return None;
+                }
+            }
+        }
+
+        Some(MinMax {
+            tree,
+            stats: Recorder::default(),
+        })
+    }
 }
 
 fn is_on_min_level(index: usize) -> bool {
-    (((index + 1) as f32).log(2.0) as usize) % 2 == 0
+    (index + 1).ilog2().is_multiple_of(2)
 }
 
 fn has_grandparent(index: usize) -> bool {
@@ -700,9 +1129,64 @@ pub fn grandparent(index: usize) -> usize {
     parent(parent(index))
 }
 
+impl<T: core::cmp::Ord> Default for MinMax<T> {
+    fn default() -> Self {
+        MinMax::init()
+    }
+}
+
+// heaps are equal when they keep the same items at the same places
+impl<T: core::cmp::Ord> PartialEq for MinMax<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree == other.tree
+    }
+}
+
+impl<T: core::cmp::Ord> Eq for MinMax<T> {}
+
+impl<T: core::cmp::Ord> MemoryFootprint for MinMax<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.tree.len()).with_vec(&self.tree)
+    }
+}
+
+impl<T: core::cmp::Ord> Replayable for MinMax<T> {
+    type Key = T;
+    type Value = ();
+
+    fn apply(&mut self, op: Op<T>) {
+        match op {
+            Op::Push(item) => self.push(item),
+            Op::Pop => {
+                self.pop_min();
+            }
+            Op::Insert(..) | Op::Remove(_) => {}
+        }
+    }
+}
+
+// a heap is written as its items in increasing order and rebuilt from them
+#[cfg(feature = "serde")]
+impl<T: core::cmp::Ord + Serialize> Serialize for MinMax<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort();
+
+        serializer.collect_seq(items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: core::cmp::Ord + Deserialize<'de>> Deserialize<'de> for MinMax<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(MinMax::build_heap(Vec::deserialize(deserializer)?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::{Rng, SplitMix64};
 
     #[test]
     fn heap_minmax_tree_is_on_min_level() {
@@ -1154,4 +1638,193 @@ mod tests {
         assert_eq!(*minmax.peek_min().unwrap(), 0);
         assert_eq!(*minmax.peek_max().unwrap(), 2);
     }
+
+    #[test]
+    fn heap_minmax_bytes_round_trip() {
+        let mut minmax = MinMax::init();
+        for item in 0..100 {
+            minmax.push((item * 53) % 100);
+        }
+        minmax.pop_min();
+        minmax.pop_max();
+
+        let bytes = minmax.to_bytes();
+        let restored = MinMax::<i32>::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.tree, minmax.tree);
+
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"MM");
+        vec![5, 1, 9].encode(&mut writer);
+        assert!(MinMax::<i32>::from_bytes(writer.bytes()).is_none());
+        assert!(MinMax::<i32>::from_bytes(b"XX").is_none());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn heap_minmax_stats() {
+        let mut minmax = MinMax::init();
+        for i in 0..16 {
+            minmax.push(i);
+        }
+
+        // the buffer doubles from 4 to 8 and 16
+        assert_eq!(minmax.stats().allocations, 3);
+        assert_eq!(minmax.stats().rotations, 0);
+
+        minmax.reset_stats();
+        assert_eq!(minmax.peek_min(), Some(&0));
+        assert_eq!(minmax.stats().comparisons, 0);
+        assert_eq!(minmax.peek_max(), Some(&15));
+        assert_eq!(minmax.stats().comparisons, 1);
+
+        // replace_max pushes when the heap is empty, the nested push is counted once
+        let mut empty = MinMax::init();
+        empty.replace_max(1);
+        empty.replace_max(2);
+        assert_eq!(empty.stats().allocations, 1);
+        assert!(empty.stats().comparisons >= 1);
+
+        let built = MinMax::build_heap((0..100).rev().collect());
+        assert!(built.stats().comparisons > 0);
+        assert_eq!(built.stats().allocations, 0);
+    }
+
+    #[test]
+    fn heap_minmax_pop_max_from_last_leaf() {
+        let mut minmax = MinMax::build_heap(vec![1, 2, 3]);
+
+        assert_eq!(minmax.pop_max(), Some(3));
+        assert_eq!(minmax.pop_max(), Some(2));
+        assert_eq!(minmax.pop_max(), Some(1));
+    }
+
+    #[test]
+    fn heap_minmax_peek_mut_matches_model() {
+        let mut rng = SplitMix64::init(5);
+        let mut minmax = MinMax::init();
+        let mut model: Vec<u64> = Vec::new();
+        for _ in 0..3000 {
+            match rng.next_below(5) {
+                0 | 1 => {
+                    let item = rng.next_below(100);
+                    minmax.push(item);
+                    model.push(item);
+                }
+                2 if !model.is_empty() => {
+                    let item = rng.next_below(100);
+                    *minmax.peek_min_mut().unwrap() = item;
+                    model.sort();
+                    model[0] = item;
+                }
+                3 if !model.is_empty() => {
+                    let item = rng.next_below(100);
+                    *minmax.peek_max_mut().unwrap() = item;
+                    model.sort();
+                    *model.last_mut().unwrap() = item;
+                }
+                _ => {
+                    model.sort();
+                    let popped = if rng.next_below(2) == 0 {
+                        minmax.peek_min_mut().map(MinMaxPeekMut::pop)
+                    } else {
+                        model.reverse();
+                        minmax.peek_max_mut().map(MinMaxPeekMut::pop)
+                    };
+                    assert_eq!(popped, model.first().copied());
+                    if !model.is_empty() {
+                        model.remove(0);
+                    }
+                }
+            }
+            assert_eq!(minmax.peek_min(), model.iter().min());
+            assert_eq!(minmax.peek_max(), model.iter().max());
+        }
+    }
+
+    #[test]
+    fn heap_minmax_iterators() {
+        let mut minmax: MinMax<i32> = vec![5, 1, 4].into_iter().collect();
+        minmax.extend(vec![3, 2]);
+
+        let mut items: Vec<i32> = minmax.iter().copied().collect();
+        items.sort();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+        assert_eq!((&minmax).into_iter().len(), 5);
+
+        assert_eq!(
+            minmax.drain_sorted().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert!(minmax.is_empty());
+
+        minmax.extend(vec![7, 6]);
+        let mut owned: Vec<i32> = minmax.into_iter().collect();
+        owned.sort();
+        assert_eq!(owned, vec![6, 7]);
+    }
+
+    #[test]
+    fn heap_minmax_from_vec_into_sorted_vec() {
+        let mut rng = SplitMix64::init(13);
+        let items: Vec<u64> = (0..300).map(|_| rng.next_below(100)).collect();
+        let minmax = MinMax::from_vec(items.clone());
+
+        assert_eq!(minmax.peek_min(), items.iter().min());
+        assert_eq!(minmax.peek_max(), items.iter().max());
+
+        let mut sorted = items;
+        sorted.sort();
+        assert_eq!(minmax.into_sorted_vec(), sorted);
+    }
+
+    #[test]
+    fn heap_minmax_running_median() {
+        // the lower half keeps its max at hand, the upper half its min, and the halves differ by at most one item
+        let mut rng = SplitMix64::init(12);
+        let mut lower: MinMaxHeap<u64> = MinMaxHeap::init();
+        let mut upper: MinMaxHeap<u64> = MinMaxHeap::init();
+        let mut items = Vec::new();
+        for _ in 0..500 {
+            let item = rng.next_below(1000);
+            items.push(item);
+            match lower.peek_max() {
+                Some(max) if item > *max => upper.push(item),
+                _ => lower.push(item),
+            }
+            if lower.size() > upper.size() + 1 {
+                upper.push(lower.pop_max().unwrap());
+            } else if upper.size() > lower.size() {
+                lower.push(upper.pop_min().unwrap());
+            }
+
+            items.sort_unstable();
+            assert_eq!(lower.peek_max(), Some(&items[(items.len() - 1) / 2]));
+        }
+    }
+
+    #[test]
+    fn heap_minmax_clone_and_eq() {
+        let mut mm: MinMax<usize> = MinMax::default();
+        mm.extend(vec![4, 1, 7, 3]);
+
+        let mut copy = mm.clone();
+        assert!(copy == mm);
+        copy.pop_max();
+        assert!(copy != mm);
+        assert_eq!(mm.peek_max(), Some(&7));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn heap_minmax_serde() {
+        let minmax = MinMax::build_heap(vec![9, 8, 2, 3, 4, 5, 11, 6, 7, 1]);
+
+        let json = serde_json::to_string(&minmax).unwrap();
+        assert_eq!(json, "[1,2,3,4,5,6,7,8,9,11]");
+
+        let restored: MinMax<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.peek_min(), Some(&1));
+        assert_eq!(restored.peek_max(), Some(&11));
+        assert_eq!(restored.size(), 10);
+    }
 }
@@ -1,7 +1,72 @@
+mod aging;
+mod backend;
 mod binomial;
+mod bounded;
+mod dary;
 mod fibonacci;
+mod indexed;
+mod lazy;
+mod meldable;
 mod minmax;
+mod pairing;
+mod radix;
+mod soft;
+mod stable;
 
+pub use aging::AgingHeap;
+pub use backend::Heap;
+pub use backend::IntoIterSorted;
+pub use backend::Meld;
 pub use binomial::BinomialHeap;
+pub use binomial::BinomialIntoIter;
+pub use binomial::BinomialIter;
+pub use binomial::BinomialPeekMut;
+pub use bounded::BoundedHeap;
+pub use dary::DaryHeap;
+pub use fibonacci::FibonacciHandle;
 pub use fibonacci::FibonacciHeap;
+pub use fibonacci::FibonacciIntoIter;
+pub use fibonacci::FibonacciIter;
+pub use fibonacci::FibonacciPeekMut;
+pub use indexed::IndexedHeap;
+pub use lazy::LazyDeleteHeap;
+pub use lazy::LazyEntry;
+pub use meldable::MeldableHeap;
+pub use meldable::MeldableIntoIter;
+pub use meldable::MeldableIter;
+pub use meldable::MeldablePeekMut;
 pub use minmax::MinMax;
+pub use minmax::MinMaxHeap;
+pub use minmax::MinMaxPeekMut;
+pub use pairing::PairingHandle;
+pub use pairing::PairingHeap;
+pub use pairing::PairingIntoIter;
+pub use pairing::PairingIter;
+pub use pairing::PairingPeekMut;
+pub use radix::RadixHeap;
+pub use soft::SoftHeap;
+pub use stable::StableHeap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_send_and_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn heap_containers_are_send_and_sync() {
+        is_send_and_sync::<AgingHeap<u32, String>>();
+        is_send_and_sync::<BinomialHeap<String>>();
+        is_send_and_sync::<BoundedHeap<String>>();
+        is_send_and_sync::<DaryHeap<String, 4>>();
+        is_send_and_sync::<FibonacciHeap<String>>();
+        is_send_and_sync::<IndexedHeap<String>>();
+        is_send_and_sync::<LazyDeleteHeap<String, DaryHeap<LazyEntry<String>, 2>>>();
+        is_send_and_sync::<MeldableHeap<String>>();
+        is_send_and_sync::<MinMax<String>>();
+        is_send_and_sync::<PairingHeap<String>>();
+        is_send_and_sync::<RadixHeap<String>>();
+        is_send_and_sync::<SoftHeap<String>>();
+        is_send_and_sync::<StableHeap<String>>();
+    }
+}
@@ -0,0 +1,1317 @@
+use super::IntoIterSorted;
+#[cfg(feature = "serde")]
+use crate::util::serialize::{deserialize_items, serialize_items, HEAP_FIELDS};
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// source of the stamps of nodes, shared by all heaps so that a handle never matches a node of another item
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    // data stored in the node, None if the node was removed and its slot is free
+    payload: Option<T>,
+
+    // leftmost child of the node
+    child: Option<usize>,
+
+    // right sibling of the node
+    next: Option<usize>,
+
+    // left sibling of the node, or its parent if the node is the leftmost child. None for the root
+    previous: Option<usize>,
+
+    // tells the items that used the same slot apart, so handles of removed items are recognized
+    stamp: u64,
+}
+
+impl<T> Node<T> {
+    fn init(payload: T) -> Node<T> {
+        Node {
+            payload: Some(payload),
+            child: None,
+            next: None,
+            previous: None,
+            stamp: NEXT_STAMP.fetch_add(1, AtomicOrdering::Relaxed),
+        }
+    }
+}
+
+/// Refers to an item pushed into a `PairingHeap`, to change or remove it later.
+/// A handle is only valid for the heap that returned it and stops being valid when its item leaves the heap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PairingHandle {
+    index: usize,
+    stamp: u64,
+}
+
+/// Mutable reference to the item with highest priority of a `PairingHeap`, created by `PairingHeap::peek_mut`.
+/// The heap is restored when the reference is dropped
+pub struct PairingPeekMut<'a, T> {
+    heap: &'a mut PairingHeap<T>,
+
+    // whether the item was borrowed mutably, so the heap may need to be restored
+    changed: bool,
+}
+
+impl<'a, T> PairingPeekMut<'a, T> {
+    /// Removes the item from the heap and returns it, even if it was changed to a lower priority
+    ///
+    /// # Arguments
+    /// * `this`: the reference to the item
+    pub fn pop(mut this: PairingPeekMut<'a, T>) -> T {
+        // popping restores the heap anyway
+        this.changed = false;
+
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<'a, T> core::ops::Deref for PairingPeekMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.heap.peek().unwrap()
+    }
+}
+
+impl<'a, T> core::ops::DerefMut for PairingPeekMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        let root = self.heap.root.unwrap();
+        self.heap.nodes[root].payload.as_mut().unwrap()
+    }
+}
+
+impl<'a, T> Drop for PairingPeekMut<'a, T> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.heap.restore_root();
+        }
+    }
+}
+
+/// Iterator over the items of a `PairingHeap` in no particular order, created by `PairingHeap::iter`
+pub struct PairingIter<'a, T> {
+    nodes: core::slice::Iter<'a, Node<T>>,
+
+    // number of items not yielded yet
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for PairingIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        // free slots hold no payload
+        let payload = self.nodes.find_map(|node| node.payload.as_ref())?;
+        self.remaining -= 1;
+
+        Some(payload)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for PairingIter<'a, T> {}
+
+/// Iterator over the items of a `PairingHeap` in no particular order, created by `into_iter`
+pub struct PairingIntoIter<T> {
+    nodes: alloc::vec::IntoIter<Node<T>>,
+
+    // number of items not yielded yet
+    remaining: usize,
+}
+
+impl<T> Iterator for PairingIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let payload = self.nodes.find_map(|node| node.payload)?;
+        self.remaining -= 1;
+
+        Some(payload)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for PairingIntoIter<T> {}
+
+/// A pairing heap is a heap-ordered tree whose root keeps its children in a list. Pushing and melding link two
+/// trees in O(1), and popping pairs the children of the root from left to right and links the pairs from right
+/// to left. It is much simpler than a Fibonacci heap and usually faster in practice, which makes it a good choice
+/// for Dijkstra's and Prim's algorithms. Pushing an item returns a handle which can increase the priority of the item
+/// or remove it
+///
+/// # Examples
+/// ```
+/// use rudac::heap::PairingHeap;
+///
+/// let mut pairing_heap = PairingHeap::init_min();
+/// pairing_heap.push(4);
+/// let handle = pairing_heap.push(7);
+/// pairing_heap.push(2);
+///
+/// pairing_heap.decrease_key(handle, 1);
+///
+/// assert_eq!(pairing_heap.pop(), Some(1));
+/// assert_eq!(pairing_heap.pop(), Some(2));
+/// assert_eq!(pairing_heap.pop(), Some(4));
+/// assert_eq!(pairing_heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PairingHeap<T> {
+    // every node of the heap, trees refer to their nodes by index
+    nodes: Vec<Node<T>>,
+
+    // slots of removed nodes, reused by later pushes
+    free: Vec<usize>,
+
+    // node containing the highest priority
+    root: Option<usize>,
+
+    // total number of items in the heap
+    size: usize,
+
+    // indicates wether current heap is initialized as a min heap or not
+    min: bool,
+
+    // order of the items, the item that is least by it has the highest priority
+    order: Order<T>,
+}
+
+impl<T: core::cmp::Ord> PairingHeap<T> {
+    /// Initializes a min heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let pairing_heap: PairingHeap<usize> = PairingHeap::init_min();
+    ///
+    /// assert_eq!(pairing_heap.is_min(), true);
+    /// ```
+    pub fn init_min() -> PairingHeap<T> {
+        PairingHeap::init(true, Order::natural(true))
+    }
+
+    /// Initializes a max heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let pairing_heap: PairingHeap<usize> = PairingHeap::init_max();
+    ///
+    /// assert_eq!(pairing_heap.is_max(), true);
+    /// ```
+    pub fn init_max() -> PairingHeap<T> {
+        PairingHeap::init(false, Order::natural(false))
+    }
+
+    /// Builds a min heap of the items of `vector`, linking every item with the root like `push` does, so the work
+    /// of pairing the children is left to the pops
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `vector`: items of the heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let pairing_heap = PairingHeap::from_vec(vec![4, 1, 3, 2]);
+    ///
+    /// assert_eq!(pairing_heap.peek(), Some(&1));
+    /// assert_eq!(pairing_heap.into_sorted_vec(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn from_vec(vector: Vec<T>) -> PairingHeap<T> {
+        let mut pairing_heap = PairingHeap::init_min();
+        pairing_heap.nodes.reserve(vector.len());
+        pairing_heap.extend(vector);
+
+        pairing_heap
+    }
+}
+
+impl<T> PairingHeap<T> {
+    // initializes a pairing heap
+    fn init(min: bool, order: Order<T>) -> PairingHeap<T> {
+        PairingHeap {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            size: 0,
+            min,
+            order,
+        }
+    }
+
+    /// Initializes a heap ordered by `compare` instead of `Ord`, popping the least item by `compare` first.
+    /// Such a heap counts as a min heap, so a max heap of a custom order is made by swapping the arguments of `compare`
+    ///
+    /// # Arguments:
+    /// * `compare`: order of the items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// // closest points first, floats are not `Ord`
+    /// let mut pairing_heap = PairingHeap::init_with(|point1: &(f64, f64), point2: &(f64, f64)| {
+    ///     point1.0.hypot(point1.1).total_cmp(&point2.0.hypot(point2.1))
+    /// });
+    /// pairing_heap.push((3.0, 4.0));
+    /// pairing_heap.push((1.0, 0.5));
+    ///
+    /// assert_eq!(pairing_heap.pop(), Some((1.0, 0.5)));
+    /// assert!(pairing_heap.is_min());
+    /// ```
+    pub fn init_with<F>(compare: F) -> PairingHeap<T>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        PairingHeap::init(true, Order::with(compare))
+    }
+
+    /// Initializes a heap ordered by the key `key` extracts from every item, popping the item with the least key
+    /// first. The key is computed on every comparison, so it should be cheap. Such a heap counts as a min heap, so a
+    /// max heap by key is made by wrapping the key in `core::cmp::Reverse`
+    ///
+    /// # Arguments:
+    /// * `key`: key of an item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// // edges by weight, the endpoints ride along
+    /// let mut pairing_heap = PairingHeap::init_by_key(|edge: &(usize, usize, u32)| edge.2);
+    /// pairing_heap.push((0, 1, 9));
+    /// let handle = pairing_heap.push((1, 2, 5));
+    /// pairing_heap.decrease_key(handle, (1, 2, 3));
+    ///
+    /// assert_eq!(pairing_heap.pop(), Some((1, 2, 3)));
+    /// ```
+    pub fn init_by_key<K, F>(key: F) -> PairingHeap<T>
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        PairingHeap::init(true, Order::by_key(key))
+    }
+
+    // stores `payload` in a new node, reusing a free slot if there is one
+    fn allocate(&mut self, payload: T) -> usize {
+        let node = Node::init(payload);
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // returns the node of `handle`, None if its item is no longer in the heap
+    fn locate(&self, handle: PairingHandle) -> Option<usize> {
+        self.nodes
+            .get(handle.index)
+            .filter(|node| node.payload.is_some() && node.stamp == handle.stamp)
+            .map(|_| handle.index)
+    }
+
+    // returns true if `node1` has higher priority than `node2`, meaning its payload is not greater by `compare`
+    fn has_higher_priority(&self, node1: usize, node2: usize) -> bool {
+        match (&self.nodes[node1].payload, &self.nodes[node2].payload) {
+            (Some(payload1), Some(payload2)) => {
+                self.order.compare(payload1, payload2) != Ordering::Greater
+            }
+            _ => panic!("Payloads can not be empty"),
+        }
+    }
+
+    // links the trees of the roots `node1` and `node2` and returns the root of the linked tree
+    fn link(&mut self, node1: usize, node2: usize) -> usize {
+        let (parent, child) = if self.has_higher_priority(node1, node2) {
+            (node1, node2)
+        } else {
+            (node2, node1)
+        };
+
+        // the tree of lower priority becomes the leftmost child
+        if let Some(first) = self.nodes[parent].child {
+            self.nodes[first].previous = Some(child);
+        }
+        self.nodes[child].next = self.nodes[parent].child;
+        self.nodes[child].previous = Some(parent);
+        self.nodes[parent].child = Some(child);
+
+        parent
+    }
+
+    // links the trees of the list of siblings starting at `first` into one tree and returns its root.
+    // pairs are linked from left to right, then the pairs are linked from right to left
+    fn combine(&mut self, first: Option<usize>) -> Option<usize> {
+        let mut pairs = Vec::new();
+        let mut sibling = first;
+        while let Some(node1) = sibling {
+            let node2 = self.nodes[node1].next;
+            sibling = node2.and_then(|node2| self.nodes[node2].next);
+
+            self.detach(node1);
+            match node2 {
+                Some(node2) => {
+                    self.detach(node2);
+                    pairs.push(self.link(node1, node2));
+                }
+                None => pairs.push(node1),
+            }
+        }
+
+        let last = pairs.pop()?;
+        Some(
+            pairs
+                .into_iter()
+                .rev()
+                .fold(last, |root, pair| self.link(pair, root)),
+        )
+    }
+
+    // forgets the siblings and the parent of `node`, its neighbors are left untouched
+    fn detach(&mut self, node: usize) {
+        self.nodes[node].previous = None;
+        self.nodes[node].next = None;
+    }
+
+    // removes the tree of the non-root `node` from the children of its parent
+    fn cut(&mut self, node: usize) {
+        let previous = self.nodes[node].previous.unwrap();
+        let next = self.nodes[node].next;
+        if self.nodes[previous].child == Some(node) {
+            self.nodes[previous].child = next;
+        } else {
+            self.nodes[previous].next = next;
+        }
+        if let Some(next) = next {
+            self.nodes[next].previous = Some(previous);
+        }
+        self.detach(node);
+    }
+
+    // makes the tree of `node` part of the heap
+    fn insert_tree(&mut self, node: usize) {
+        self.root = match self.root {
+            Some(root) => Some(self.link(root, node)),
+            None => Some(node),
+        };
+    }
+
+    /// Pushes specified `payload` into heap and returns a handle to it
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments:
+    /// * `payload`: data to be pushed into heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_max();
+    /// pairing_heap.push(1);
+    /// let handle = pairing_heap.push(3);
+    ///
+    /// assert_eq!(pairing_heap.peek(), Some(&3));
+    /// assert_eq!(pairing_heap.get(handle), Some(&3));
+    /// ```
+    pub fn push(&mut self, payload: T) -> PairingHandle {
+        let node = self.allocate(payload);
+        self.insert_tree(node);
+        self.size += 1;
+
+        PairingHandle {
+            index: node,
+            stamp: self.nodes[node].stamp,
+        }
+    }
+
+    /// Pops and returns item with highest priority. Returns `None` if heap is empty
+    /// * Complexity: amortized O(log n)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_min();
+    /// pairing_heap.push(2);
+    /// pairing_heap.push(0);
+    /// pairing_heap.push(1);
+    ///
+    /// assert_eq!(pairing_heap.pop(), Some(0));
+    /// assert_eq!(pairing_heap.pop(), Some(1));
+    /// assert_eq!(pairing_heap.size(), 1);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root?;
+        let children = self.nodes[root].child.take();
+        self.root = self.combine(children);
+
+        Some(self.release(root))
+    }
+
+    // takes the payload of `node` and frees its slot
+    fn release(&mut self, node: usize) -> T {
+        self.size -= 1;
+        self.free.push(node);
+
+        self.nodes[node].payload.take().unwrap()
+    }
+
+    /// Merges two pairing heaps and returns the merged pairing heap.
+    /// Handles returned by `pairing_heap_1` stay valid in the merged heap, handles returned by `pairing_heap_2`
+    /// do not, unless `pairing_heap_1` is empty. The merged heap is ordered like `pairing_heap_1`,
+    /// so heaps initialized by `init_with` or `init_by_key` must have the same order
+    ///
+    /// # Arguments:
+    /// * `pairing_heap_1`: first pairing heap
+    /// * `pairing_heap_2`: second pairing heap
+    ///
+    /// # Panics:
+    /// * panics if two pairing heaps are not the same kind(ex. one is min heap and the other is max heap)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap_1 = PairingHeap::init_min();
+    /// pairing_heap_1.push(0);
+    /// pairing_heap_1.push(2);
+    ///
+    /// let mut pairing_heap_2 = PairingHeap::init_min();
+    /// pairing_heap_2.push(1);
+    /// pairing_heap_2.push(3);
+    ///
+    /// let mut merged_heap = PairingHeap::merge(pairing_heap_1, pairing_heap_2);
+    ///
+    /// assert_eq!(merged_heap.size(), 4);
+    /// assert_eq!(merged_heap.pop(), Some(0));
+    /// assert_eq!(merged_heap.pop(), Some(1));
+    /// ```
+    pub fn merge(
+        mut pairing_heap_1: PairingHeap<T>,
+        pairing_heap_2: PairingHeap<T>,
+    ) -> PairingHeap<T> {
+        pairing_heap_1.meld(pairing_heap_2);
+
+        pairing_heap_1
+    }
+
+    /// Moves every item of `other` into this heap by linking the two trees. Handles returned by this heap stay
+    /// valid, handles returned by `other` do not, unless this heap is empty. Heaps initialized by `init_with` or
+    /// `init_by_key` must have the same order
+    /// * Complexity: O(1) if this heap is empty, otherwise O(m) to move the m node slots of `other`
+    ///
+    /// # Arguments:
+    /// * `other`: the heap to be melded into this one
+    ///
+    /// # Panics:
+    /// * panics if two pairing heaps are not the same kind(ex. one is min heap and the other is max heap)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_min();
+    /// let handle = pairing_heap.push(4);
+    ///
+    /// let mut other = PairingHeap::init_min();
+    /// other.push(2);
+    /// other.push(3);
+    ///
+    /// pairing_heap.meld(other);
+    /// pairing_heap.decrease_key(handle, 1);
+    ///
+    /// assert_eq!(pairing_heap.pop(), Some(1));
+    /// assert_eq!(pairing_heap.pop(), Some(2));
+    /// ```
+    pub fn meld(&mut self, mut other: PairingHeap<T>) {
+        // if one heap is min and the other is max, panic!. merge is not possible
+        if self.is_min() != other.is_min() {
+            panic!("Two heaps must be of same type in order for merge to be possible")
+        }
+
+        if other.is_empty() {
+            return;
+        }
+
+        // no handle of this heap is valid, so the nodes of other are taken over as they are
+        if self.is_empty() {
+            self.nodes = other.nodes;
+            self.free = other.free;
+            self.root = other.root;
+            self.size = other.size;
+            return;
+        }
+
+        // move the nodes of other behind the nodes of this heap
+        let offset = self.nodes.len();
+        let shift = |link: Option<usize>| link.map(|index| index + offset);
+        self.nodes.reserve(other.nodes.len());
+        for mut node in other.nodes.drain(..) {
+            node.child = shift(node.child);
+            node.next = shift(node.next);
+            node.previous = shift(node.previous);
+            self.nodes.push(node);
+        }
+        self.free
+            .extend(other.free.iter().map(|index| index + offset));
+
+        self.insert_tree(other.root.unwrap() + offset);
+        self.size += other.size;
+    }
+
+    /// Returns a reference to the item of `handle`, None if the item is no longer in the heap
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    pub fn get(&self, handle: PairingHandle) -> Option<&T> {
+        self.nodes[self.locate(handle)?].payload.as_ref()
+    }
+
+    /// Returns true if the item of `handle` is still in the heap
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    pub fn contains(&self, handle: PairingHandle) -> bool {
+        self.locate(handle).is_some()
+    }
+
+    /// Replaces the item of `handle` with `payload`, which must have at least the same priority: smaller or equal
+    /// in a min heap, greater or equal in a max heap, not greater by the order of a heap initialized by `init_with`
+    /// or `init_by_key`. The tree of the item is cut off from its parent and linked with the root
+    /// * Complexity: O(1), amortized o(log n) counting the work left to later pops
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    /// * `payload`: the new item
+    ///
+    /// # Panics:
+    /// * panics if the item of `handle` is no longer in the heap
+    /// * panics if `payload` has lower priority than the item it replaces
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_max();
+    /// pairing_heap.push(5);
+    /// let handle = pairing_heap.push(1);
+    ///
+    /// pairing_heap.decrease_key(handle, 8);
+    ///
+    /// assert_eq!(pairing_heap.pop(), Some(8));
+    /// assert!(!pairing_heap.contains(handle));
+    /// ```
+    pub fn decrease_key(&mut self, handle: PairingHandle, payload: T) {
+        let node = match self.locate(handle) {
+            Some(node) => node,
+            None => panic!("Handle does not refer to an item in the heap"),
+        };
+        let current = self.nodes[node].payload.as_ref().unwrap();
+        if self.order.compare(&payload, current) == Ordering::Greater {
+            panic!("New payload must not have lower priority than the current one");
+        }
+        self.nodes[node].payload = Some(payload);
+
+        if self.root != Some(node) {
+            self.cut(node);
+            self.insert_tree(node);
+        }
+    }
+
+    /// Removes the item of `handle` from the heap and returns it, None if the item is no longer in the heap.
+    /// The tree of the item is cut off and its children are linked like in `pop`
+    /// * Complexity: amortized O(log n)
+    ///
+    /// # Arguments:
+    /// * `handle`: handle returned when the item was pushed
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_min();
+    /// pairing_heap.push(1);
+    /// let handle = pairing_heap.push(5);
+    /// pairing_heap.push(3);
+    ///
+    /// assert_eq!(pairing_heap.delete(handle), Some(5));
+    /// assert_eq!(pairing_heap.delete(handle), None);
+    /// assert_eq!(pairing_heap.size(), 2);
+    /// ```
+    pub fn delete(&mut self, handle: PairingHandle) -> Option<T> {
+        let node = self.locate(handle)?;
+        if self.root == Some(node) {
+            return self.pop();
+        }
+
+        self.cut(node);
+        let children = self.nodes[node].child.take();
+        if let Some(tree) = self.combine(children) {
+            self.insert_tree(tree);
+        }
+
+        Some(self.release(node))
+    }
+
+    /// Returns a reference to item with highest priority, None if the heap is empty
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap: PairingHeap<usize> = PairingHeap::init_min();
+    ///
+    /// pairing_heap.push(0);
+    ///
+    /// assert_eq!(pairing_heap.peek(), Some(&0));
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        self.nodes[self.root?].payload.as_ref()
+    }
+
+    /// Returns a mutable reference to item with highest priority, None if the heap is empty.
+    /// If the item is changed through the reference, the heap is restored when the reference is dropped:
+    /// the children of the item are paired like in `pop` and linked with it again. Handles stay valid
+    /// * Complexity: amortized O(log n) if the item is changed, O(1) otherwise
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    /// use rudac::heap::PairingPeekMut;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_min();
+    /// let handle = pairing_heap.push(1);
+    /// pairing_heap.push(5);
+    /// pairing_heap.push(3);
+    ///
+    /// *pairing_heap.peek_mut().unwrap() = 4;
+    ///
+    /// assert_eq!(pairing_heap.peek(), Some(&3));
+    /// assert_eq!(pairing_heap.get(handle), Some(&4));
+    ///
+    /// // the item can also be popped through the reference
+    /// let top = pairing_heap.peek_mut().unwrap();
+    /// assert_eq!(PairingPeekMut::pop(top), 3);
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<PairingPeekMut<'_, T>> {
+        self.root?;
+
+        Some(PairingPeekMut {
+            heap: self,
+            changed: false,
+        })
+    }
+
+    // pairs the children of the root and links them with the root again, after its item changed
+    fn restore_root(&mut self) {
+        let root = self.root.unwrap();
+        let children = self.nodes[root].child.take();
+        if let Some(tree) = self.combine(children) {
+            self.insert_tree(tree);
+        }
+    }
+
+    /// Returns an iterator over the items of the heap in no particular order
+    /// * Complexity: O(n) for the whole iteration, n including the free slots
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_min();
+    /// pairing_heap.push(2);
+    /// pairing_heap.push(1);
+    ///
+    /// assert_eq!(pairing_heap.iter().sum::<i32>(), 3);
+    /// ```
+    pub fn iter(&self) -> PairingIter<'_, T> {
+        PairingIter {
+            nodes: self.nodes.iter(),
+            remaining: self.size,
+        }
+    }
+
+    /// Consumes the heap and returns an iterator over its items in the order they would be popped
+    /// * Complexity: amortized O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_max();
+    /// pairing_heap.extend(vec![1, 3, 2]);
+    ///
+    /// assert_eq!(pairing_heap.into_iter_sorted().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// ```
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, PairingHeap<T>> {
+        IntoIterSorted::init(self)
+    }
+
+    /// Consumes the heap and returns its items in the order they would be popped
+    /// * Complexity: amortized O(n log n)
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter_sorted().collect()
+    }
+
+    /// Removes every item of the heap and returns an iterator over them in the order they would be popped.
+    /// The heap is empty even if the iterator is not consumed, and handles to the removed items are no longer valid
+    /// * Complexity: amortized O(log n) per item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap = PairingHeap::init_min();
+    /// let handle = pairing_heap.push(2);
+    /// pairing_heap.push(1);
+    ///
+    /// assert_eq!(pairing_heap.drain_sorted().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert!(pairing_heap.is_empty());
+    /// assert!(!pairing_heap.contains(handle));
+    /// ```
+    pub fn drain_sorted(&mut self) -> IntoIterSorted<T, PairingHeap<T>> {
+        let drained = core::mem::replace(self, PairingHeap::init(self.min, self.order.clone()));
+
+        IntoIterSorted::init(drained)
+    }
+
+    /// Removes every item of the heap. Handles returned before stop being valid
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::PairingHeap;
+    ///
+    /// let mut pairing_heap: PairingHeap<usize> = PairingHeap::init_min();
+    /// pairing_heap.push(0);
+    ///
+    /// pairing_heap.clear();
+    ///
+    /// assert_eq!(pairing_heap.size(), 0);
+    /// assert_eq!(pairing_heap.pop(), None);
+    /// ```
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = None;
+        self.size = 0;
+    }
+
+    /// Returns true if there are no more items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns number of items in heap
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if the heap is initialized as a min heap, or by `init_with` or `init_by_key`
+    pub fn is_min(&self) -> bool {
+        self.min
+    }
+
+    /// Returns true if the heap is initialized as a max heap
+    pub fn is_max(&self) -> bool {
+        !self.is_min()
+    }
+}
+
+impl<T: core::cmp::Ord> Default for PairingHeap<T> {
+    fn default() -> Self {
+        PairingHeap::init_min()
+    }
+}
+
+impl<T: core::cmp::Ord> core::iter::FromIterator<T> for PairingHeap<T> {
+    // collects into a min heap
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        PairingHeap::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T> Extend<T> for PairingHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for payload in iter {
+            self.push(payload);
+        }
+    }
+}
+
+impl<T> IntoIterator for PairingHeap<T> {
+    type Item = T;
+    type IntoIter = PairingIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        PairingIntoIter {
+            nodes: self.nodes.into_iter(),
+            remaining: self.size,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PairingHeap<T> {
+    type Item = &'a T;
+    type IntoIter = PairingIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// heaps are equal when both are min or both are max heaps whose trees have the same shape and payloads, orders
+// given to `init_with` or `init_by_key` are not compared
+impl<T: PartialEq> PartialEq for PairingHeap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.min != other.min || self.size != other.size {
+            return false;
+        }
+
+        // nodes at the same place of both heaps, their right siblings and leftmost children are compared later
+        let mut stack = vec![(self.root, other.root)];
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (None, None) => {}
+                (Some(node), Some(other_node)) => {
+                    let node = &self.nodes[node];
+                    let other_node = &other.nodes[other_node];
+                    if node.payload != other_node.payload {
+                        return false;
+                    }
+                    stack.push((node.next, other_node.next));
+                    stack.push((node.child, other_node.child));
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+impl<T: Eq> Eq for PairingHeap<T> {}
+
+impl<T> MemoryFootprint for PairingHeap<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.size)
+            .with_vec(&self.nodes)
+            .with_vec(&self.free)
+    }
+}
+
+// a heap is written as its items in the order they would be popped and read back ordered by `Ord`, heaps made by
+// `init_with` or `init_by_key` come back as min heaps
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for PairingHeap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items: Vec<&T> = self
+            .nodes
+            .iter()
+            .filter_map(|node| node.payload.as_ref())
+            .collect();
+        items.sort_by(|item1, item2| self.order.compare(item1, item2));
+
+        serialize_items(serializer, "PairingHeap", HEAP_FIELDS, self.min, &items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: core::cmp::Ord + Deserialize<'de>> Deserialize<'de> for PairingHeap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (min, items): (bool, Vec<T>) =
+            deserialize_items(deserializer, "PairingHeap", HEAP_FIELDS)?;
+
+        let mut pairing_heap = if min {
+            PairingHeap::init_min()
+        } else {
+            PairingHeap::init_max()
+        };
+        for item in items {
+            pairing_heap.push(item);
+        }
+
+        Ok(pairing_heap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn heap_pairing_init() {
+        let pairing_heap: PairingHeap<usize> = PairingHeap::init_min();
+
+        assert_eq!(pairing_heap.size(), 0);
+        assert!(pairing_heap.is_empty());
+        assert_eq!(pairing_heap.peek(), None);
+        assert!(pairing_heap.is_min());
+    }
+
+    #[test]
+    fn heap_pairing_pop_sorted() {
+        let mut rng = SplitMix64::init(31);
+        let mut items: Vec<u64> = (0..1000).map(|_| rng.next_below(500)).collect();
+
+        let mut pairing_heap = PairingHeap::init_max();
+        for item in &items {
+            pairing_heap.push(*item);
+        }
+        items.sort_unstable_by(|item1, item2| item2.cmp(item1));
+
+        let popped: Vec<u64> = std::iter::from_fn(|| pairing_heap.pop()).collect();
+        assert_eq!(popped, items);
+        assert!(pairing_heap.is_empty());
+    }
+
+    #[test]
+    fn heap_pairing_decrease_key_and_delete_match_model() {
+        let mut rng = SplitMix64::init(32);
+        for min in [true, false] {
+            let mut ph = if min {
+                PairingHeap::init_min()
+            } else {
+                PairingHeap::init_max()
+            };
+            // every live item with its handle
+            let mut model: Vec<(PairingHandle, i64)> = Vec::new();
+            for _ in 0..4000 {
+                match rng.next_below(6) {
+                    0 | 1 => {
+                        let payload = rng.next_below(1000) as i64;
+                        model.push((ph.push(payload), payload));
+                    }
+                    2 if !model.is_empty() => {
+                        let index = rng.next_below(model.len() as u64) as usize;
+                        let change = rng.next_below(100) as i64;
+                        let payload = if min {
+                            model[index].1 - change
+                        } else {
+                            model[index].1 + change
+                        };
+                        ph.decrease_key(model[index].0, payload);
+                        model[index].1 = payload;
+                    }
+                    3 if !model.is_empty() => {
+                        let index = rng.next_below(model.len() as u64) as usize;
+                        let (handle, payload) = model.swap_remove(index);
+                        assert_eq!(ph.delete(handle), Some(payload));
+                        assert!(!ph.contains(handle));
+                    }
+                    _ => {
+                        let expected = if min {
+                            model.iter().map(|(_, payload)| *payload).min()
+                        } else {
+                            model.iter().map(|(_, payload)| *payload).max()
+                        };
+                        assert_eq!(ph.pop(), expected);
+                        if let Some(expected) = expected {
+                            let index = model
+                                .iter()
+                                .position(|(handle, payload)| {
+                                    *payload == expected && !ph.contains(*handle)
+                                })
+                                .unwrap();
+                            model.swap_remove(index);
+                        }
+                    }
+                }
+                assert_eq!(ph.size(), model.len());
+                for (handle, payload) in &model {
+                    assert_eq!(ph.get(*handle), Some(payload));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn heap_pairing_meld_keeps_handles() {
+        let mut pairing_heap = PairingHeap::init_min();
+        let handles: Vec<PairingHandle> = (10..20).map(|item| pairing_heap.push(item)).collect();
+        pairing_heap.pop();
+
+        let mut other = PairingHeap::init_min();
+        for item in (0..10).rev() {
+            other.push(item);
+        }
+        other.pop();
+
+        pairing_heap.meld(other);
+        assert_eq!(pairing_heap.size(), 18);
+        assert!(!pairing_heap.contains(handles[0]));
+
+        pairing_heap.decrease_key(handles[9], 0);
+        assert_eq!(pairing_heap.delete(handles[5]), Some(15));
+        assert_eq!(pairing_heap.pop(), Some(0));
+
+        let rest: Vec<i32> = std::iter::from_fn(|| pairing_heap.pop()).collect();
+        let expected: Vec<i32> = (1..19).filter(|&item| item != 10 && item != 15).collect();
+        assert_eq!(rest, expected);
+    }
+
+    #[test]
+    fn heap_pairing_meld_into_empty_keeps_handles() {
+        let mut pairing_heap = PairingHeap::init_min();
+        let mut other = PairingHeap::init_min();
+        let handle = other.push(3);
+        other.push(1);
+
+        pairing_heap.meld(other);
+        pairing_heap.decrease_key(handle, 0);
+
+        assert_eq!(pairing_heap.pop(), Some(0));
+        assert_eq!(pairing_heap.pop(), Some(1));
+    }
+
+    #[test]
+    fn heap_pairing_dijkstra() {
+        // grid graph with random weights, distances checked against a scan of all nodes
+        let mut rng = SplitMix64::init(33);
+        let side = 12;
+        let weights: Vec<u64> = (0..side * side).map(|_| 1 + rng.next_below(9)).collect();
+        let neighbors = |node: usize| {
+            let (row, column) = (node / side, node % side);
+            let mut neighbors = Vec::new();
+            if row > 0 {
+                neighbors.push(node - side);
+            }
+            if row + 1 < side {
+                neighbors.push(node + side);
+            }
+            if column > 0 {
+                neighbors.push(node - 1);
+            }
+            if column + 1 < side {
+                neighbors.push(node + 1);
+            }
+            neighbors
+        };
+
+        let mut pairing_heap = PairingHeap::init_min();
+        let mut handles = vec![None; side * side];
+        let mut distances = vec![u64::MAX; side * side];
+        distances[0] = 0;
+        handles[0] = Some(pairing_heap.push((0, 0)));
+        while let Some((distance, node)) = pairing_heap.pop() {
+            for next in neighbors(node) {
+                let candidate = distance + weights[next];
+                if candidate < distances[next] {
+                    distances[next] = candidate;
+                    match handles[next] {
+                        Some(handle) if pairing_heap.contains(handle) => {
+                            pairing_heap.decrease_key(handle, (candidate, next))
+                        }
+                        _ => handles[next] = Some(pairing_heap.push((candidate, next))),
+                    }
+                }
+            }
+        }
+
+        // bellman-ford relaxation as the reference
+        let mut expected = vec![u64::MAX; side * side];
+        expected[0] = 0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in 0..side * side {
+                for next in neighbors(node) {
+                    if expected[node] != u64::MAX && expected[node] + weights[next] < expected[next]
+                    {
+                        expected[next] = expected[node] + weights[next];
+                        changed = true;
+                    }
+                }
+            }
+        }
+        assert_eq!(distances, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Two heaps must be of same type in order for merge to be possible")]
+    fn heap_pairing_panic_merge() {
+        let pairing_heap_1: PairingHeap<usize> = PairingHeap::init_min();
+        let pairing_heap_2: PairingHeap<usize> = PairingHeap::init_max();
+
+        PairingHeap::merge(pairing_heap_1, pairing_heap_2);
+    }
+
+    #[test]
+    #[should_panic(expected = "New payload must not have lower priority than the current one")]
+    fn heap_pairing_panic_decrease_key() {
+        let mut pairing_heap = PairingHeap::init_min();
+        let handle = pairing_heap.push(3);
+
+        pairing_heap.decrease_key(handle, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Handle does not refer to an item in the heap")]
+    fn heap_pairing_panic_decrease_key_removed() {
+        let mut pairing_heap = PairingHeap::init_min();
+        let handle = pairing_heap.push(3);
+        pairing_heap.pop();
+        pairing_heap.push(5);
+
+        pairing_heap.decrease_key(handle, 1);
+    }
+
+    #[test]
+    fn heap_pairing_clone_and_eq() {
+        let mut pairing_heap: PairingHeap<usize> = PairingHeap::default();
+        for item in [5, 3, 8, 1, 9, 2] {
+            pairing_heap.push(item);
+        }
+        pairing_heap.pop();
+
+        let mut copy = pairing_heap.clone();
+        assert!(copy == pairing_heap);
+        copy.pop();
+        assert!(copy != pairing_heap);
+        assert_eq!(pairing_heap.size(), 5);
+        assert_eq!(pairing_heap.peek(), Some(&2));
+    }
+
+    #[test]
+    fn heap_pairing_peek_mut_keeps_handles() {
+        let mut rng = SplitMix64::init(35);
+        let mut pairing_heap = PairingHeap::init_min();
+        let mut model: Vec<(PairingHandle, u64)> = Vec::new();
+        // payloads are distinct, so the model knows which handle holds the top item
+        for i in 0..200 {
+            let payload = rng.next_below(1000) * 1000 + i;
+            model.push((pairing_heap.push(payload), payload));
+        }
+        pairing_heap.pop();
+        model.sort_by_key(|(_, payload)| *payload);
+        model.remove(0);
+
+        for i in 200..700 {
+            let payload = rng.next_below(1000) * 1000 + i;
+            *pairing_heap.peek_mut().unwrap() = payload;
+            model.sort_by_key(|(_, payload)| *payload);
+            model[0].1 = payload;
+
+            assert_eq!(
+                pairing_heap.peek(),
+                model.iter().map(|(_, payload)| payload).min()
+            );
+            for (handle, payload) in &model {
+                assert_eq!(pairing_heap.get(*handle), Some(payload));
+            }
+        }
+
+        // reading through the reference leaves the heap as it is
+        let shape = pairing_heap.clone();
+        let top = *pairing_heap.peek().unwrap();
+        assert_eq!(*pairing_heap.peek_mut().unwrap(), top);
+        assert_eq!(pairing_heap, shape);
+
+        let top = pairing_heap.peek_mut().unwrap();
+        let expected = *top;
+        assert_eq!(PairingPeekMut::pop(top), expected);
+        assert_eq!(pairing_heap.size(), model.len() - 1);
+    }
+
+    #[test]
+    fn heap_pairing_iterators() {
+        let mut pairing_heap: PairingHeap<usize> = (0..20).rev().collect();
+        assert!(pairing_heap.is_min());
+
+        // popping and deleting leaves free slots that the iterators skip
+        let handle = pairing_heap.push(100);
+        pairing_heap.pop();
+        pairing_heap.delete(handle);
+        assert_eq!(pairing_heap.iter().len(), 19);
+        let mut items: Vec<usize> = pairing_heap.iter().copied().collect();
+        items.sort();
+        assert_eq!(items, (1..20).collect::<Vec<_>>());
+
+        let mut owned: Vec<usize> = PairingHeap::merge(pairing_heap, PairingHeap::init_min())
+            .into_iter()
+            .collect();
+        owned.sort();
+        assert_eq!(owned, (1..20).collect::<Vec<_>>());
+
+        let mut pairing_heap =
+            PairingHeap::init_with(|s1: &&str, s2: &&str| s1.len().cmp(&s2.len()));
+        pairing_heap.extend(vec!["heap", "pairing", "rudac"]);
+        assert_eq!(
+            pairing_heap.drain_sorted().collect::<Vec<_>>(),
+            vec!["heap", "rudac", "pairing"]
+        );
+        assert!(pairing_heap.is_empty());
+
+        // the drained heap keeps its order
+        pairing_heap.extend(vec!["ab", "a"]);
+        assert_eq!(
+            pairing_heap.into_iter_sorted().collect::<Vec<_>>(),
+            vec!["a", "ab"]
+        );
+    }
+
+    #[test]
+    fn heap_pairing_from_vec() {
+        let empty: PairingHeap<u64> = PairingHeap::from_vec(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.peek(), None);
+
+        let mut rng = SplitMix64::init(36);
+        let items: Vec<u64> = (0..200).map(|_| rng.next_below(50)).collect();
+        let mut pairing_heap = PairingHeap::from_vec(items.clone());
+        assert_eq!(pairing_heap.size(), 200);
+        assert_eq!(pairing_heap.peek(), items.iter().min());
+
+        // pushes and handles work on a heap built in bulk
+        let handle = pairing_heap.push(25);
+        pairing_heap.decrease_key(handle, 0);
+        assert_eq!(pairing_heap.pop(), Some(0));
+
+        let mut sorted = items;
+        sorted.sort();
+        assert_eq!(pairing_heap.into_sorted_vec(), sorted);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn heap_pairing_serde() {
+        let mut pairing_heap = PairingHeap::init_max();
+        for item in [4, 9, 1, 6] {
+            pairing_heap.push(item);
+        }
+        let handle = pairing_heap.push(7);
+        pairing_heap.delete(handle);
+
+        let json = serde_json::to_string(&pairing_heap).unwrap();
+        assert_eq!(json, r#"{"min":false,"items":[9,6,4,1]}"#);
+
+        let mut restored: PairingHeap<i32> = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_max());
+        let popped: Vec<i32> = std::iter::from_fn(|| restored.pop()).collect();
+        assert_eq!(popped, vec![9, 6, 4, 1]);
+    }
+}
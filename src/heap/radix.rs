@@ -0,0 +1,241 @@
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec::Vec;
+
+/// A radix heap is a monotone priority queue for unsigned integer keys: popped keys never decrease and a pushed key
+/// must not be smaller than the last popped one, which is the case in Dijkstra's algorithm with non-negative integer
+/// weights and in event simulations. Items are kept in 65 buckets by the highest bit in which their key differs from
+/// the last popped key. When the bucket of keys equal to it runs empty, the first non-empty bucket is spread over
+/// the lower buckets around its least key, and every item moves to a lower bucket at most 64 times over its life.
+/// Pushing takes O(1) time and popping amortized O(log C) for keys up to C, without comparing items
+///
+/// # Examples
+/// ```
+/// use rudac::heap::RadixHeap;
+///
+/// let mut radix_heap = RadixHeap::init();
+/// radix_heap.push(7, "c");
+/// radix_heap.push(2, "a");
+/// radix_heap.push(5, "b");
+///
+/// assert_eq!(radix_heap.pop(), Some((2, "a")));
+///
+/// // keys may be pushed as long as they are not below the last popped key
+/// radix_heap.push(3, "d");
+/// assert_eq!(radix_heap.pop(), Some((3, "d")));
+/// assert_eq!(radix_heap.pop(), Some((5, "b")));
+/// assert_eq!(radix_heap.pop(), Some((7, "c")));
+/// assert_eq!(radix_heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RadixHeap<T> {
+    // buckets[0] holds keys equal to `last`, buckets[i] keys whose highest bit differing from `last` is bit i - 1
+    buckets: Vec<Vec<(u64, T)>>,
+
+    // last popped key, every key in the heap is at least this large
+    last: u64,
+    size: usize,
+}
+
+impl<T> RadixHeap<T> {
+    /// Initializes an empty heap
+    pub fn init() -> RadixHeap<T> {
+        RadixHeap {
+            buckets: (0..65).map(|_| Vec::new()).collect(),
+            last: 0,
+            size: 0,
+        }
+    }
+
+    // returns the bucket of `key`
+    fn bucket(&self, key: u64) -> usize {
+        64 - (key ^ self.last).leading_zeros() as usize
+    }
+
+    /// Pushes `item` with `key` into the heap
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `key`: priority of the item, smaller keys are popped first
+    /// * `item`: data to be pushed into heap
+    ///
+    /// # Panics
+    /// * panics if `key` is smaller than the last popped key
+    pub fn push(&mut self, key: u64, item: T) {
+        if key < self.last {
+            panic!("Key must not be smaller than the last popped key");
+        }
+
+        let bucket = self.bucket(key);
+        self.buckets[bucket].push((key, item));
+        self.size += 1;
+    }
+
+    /// Pops and returns the item with the smallest key along with its key. Returns `None` if heap is empty.
+    /// Items with equal keys come out in no particular order
+    /// * Complexity: amortized O(log C) for keys up to C
+    pub fn pop(&mut self) -> Option<(u64, T)> {
+        if self.buckets[0].is_empty() {
+            let bucket = (1..65).find(|bucket| !self.buckets[*bucket].is_empty())?;
+
+            // the least key of the bucket becomes the last key, every other key of the bucket now differs from it in
+            // a lower bit, so each moves to a lower bucket
+            let items = core::mem::take(&mut self.buckets[bucket]);
+            self.last = items.iter().map(|(key, _)| *key).min().unwrap();
+            for (key, item) in items {
+                let bucket = self.bucket(key);
+                self.buckets[bucket].push((key, item));
+            }
+        }
+
+        self.size -= 1;
+        self.buckets[0].pop()
+    }
+
+    /// Returns the smallest key in the heap, None if the heap is empty
+    /// * Complexity: O(n) in the worst case, O(1) while items with the last popped key remain
+    pub fn peek_key(&self) -> Option<u64> {
+        self.top().map(|(key, _)| *key)
+    }
+
+    // the item `pop` returns next along with its key. Spreading a bucket keeps the order of its items, so out of its
+    // least keys the last one ends up on top of the first bucket
+    pub(crate) fn top(&self) -> Option<&(u64, T)> {
+        if let Some(top) = self.buckets[0].last() {
+            return Some(top);
+        }
+
+        self.buckets
+            .iter()
+            .find(|bucket| !bucket.is_empty())
+            .and_then(|bucket| bucket.iter().rev().min_by_key(|(key, _)| *key))
+    }
+
+    /// Returns the last popped key, the smallest key that may still be pushed. 0 before the first pop
+    pub fn last_key(&self) -> u64 {
+        self.last
+    }
+
+    /// Returns number of items in heap
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Removes every item of the heap and allows every key to be pushed again
+    pub fn clear(&mut self) {
+        for bucket in self.buckets.iter_mut() {
+            bucket.clear();
+        }
+        self.last = 0;
+        self.size = 0;
+    }
+}
+
+impl<T> Default for RadixHeap<T> {
+    fn default() -> Self {
+        RadixHeap::init()
+    }
+}
+
+impl<T> MemoryFootprint for RadixHeap<T> {
+    fn footprint(&self) -> Footprint {
+        let footprint = Footprint::of::<T>(self.size).with_vec(&self.buckets);
+        self.buckets
+            .iter()
+            .fold(footprint, |footprint, bucket| footprint.with_vec(bucket))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    #[test]
+    fn heap_radix_matches_model() {
+        let mut rng = SplitMix64::init(73);
+        let mut radix_heap = RadixHeap::init();
+        let mut model = BinaryHeap::new();
+        for label in 0..5000u64 {
+            if rng.next_below(3) == 0 {
+                let expected = model.pop().map(|Reverse(entry)| entry);
+                let popped = radix_heap.pop();
+                assert_eq!(popped.map(|(key, _)| key), expected.map(|(key, _)| key));
+            } else {
+                // keys spread over every bucket, never below the last popped key
+                let offset = rng.next_u64() >> rng.next_below(64);
+                let key = radix_heap.last_key().saturating_add(offset);
+                radix_heap.push(key, label);
+                model.push(Reverse((key, label)));
+            }
+            assert_eq!(radix_heap.size(), model.len());
+            assert_eq!(
+                radix_heap.peek_key(),
+                model.peek().map(|Reverse((key, _))| *key)
+            );
+        }
+    }
+
+    #[test]
+    fn heap_radix_dijkstra() {
+        let mut rng = SplitMix64::init(79);
+        let vertices = 200;
+        let edges: Vec<Vec<(usize, u64)>> = (0..vertices)
+            .map(|_| {
+                (0..5)
+                    .map(|_| {
+                        (
+                            rng.next_below(vertices as u64) as usize,
+                            rng.next_below(1000),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut radix_heap = RadixHeap::init();
+        let mut distance = vec![u64::MAX; vertices];
+        distance[0] = 0;
+        radix_heap.push(0, 0);
+        while let Some((d, vertex)) = radix_heap.pop() {
+            if d > distance[vertex] {
+                continue;
+            }
+            for &(to, weight) in &edges[vertex] {
+                if d + weight < distance[to] {
+                    distance[to] = d + weight;
+                    radix_heap.push(d + weight, to);
+                }
+            }
+        }
+
+        // Bellman-Ford
+        let mut expected = vec![u64::MAX; vertices];
+        expected[0] = 0;
+        for _ in 0..vertices {
+            for from in 0..vertices {
+                for &(to, weight) in &edges[from] {
+                    if expected[from] != u64::MAX && expected[from] + weight < expected[to] {
+                        expected[to] = expected[from] + weight;
+                    }
+                }
+            }
+        }
+        assert_eq!(distance, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Key must not be smaller than the last popped key")]
+    fn heap_radix_panic_key_below_last() {
+        let mut radix_heap = RadixHeap::init();
+        radix_heap.push(10, ());
+        radix_heap.pop();
+        radix_heap.push(9, ());
+    }
+}
@@ -0,0 +1,427 @@
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+// node of a soft heap tree. its items share `key`, which is at least as large as each of them and at most the keys
+// of its children
+#[derive(Debug, Clone)]
+struct Node<T> {
+    key: T,
+    items: Vec<T>,
+    rank: usize,
+
+    // number of items the node is refilled up to from its children
+    target: usize,
+
+    // the right child is only present along with the left one
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+impl<T: Ord + Clone> Node<T> {
+    fn leaf(item: T) -> Box<Node<T>> {
+        Box::new(Node {
+            key: item.clone(),
+            items: vec![item],
+            rank: 0,
+            target: 1,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.left.is_none()
+    }
+
+    // moves the items of the children up until the node has `target` items or no children. every move takes the
+    // key of the child with the smaller key, which corrupts the items already in the node
+    fn sift(&mut self) {
+        while self.items.len() < self.target && !self.is_leaf() {
+            if let (Some(left), Some(right)) = (&self.left, &self.right) {
+                if left.key > right.key {
+                    core::mem::swap(&mut self.left, &mut self.right);
+                }
+            }
+
+            let left = self.left.as_mut().unwrap();
+            self.key = left.key.clone();
+            self.items.append(&mut left.items);
+            if !left.is_leaf() {
+                left.sift();
+            }
+            if left.items.is_empty() {
+                self.left = self.right.take();
+            }
+        }
+    }
+}
+
+/// A soft heap is an approximate min heap: to speed up its operations it may raise the keys of some items, which
+/// are then called corrupted, and pops items in the order of their current keys. For an error rate ε at most εn of
+/// the items in the heap are corrupted after n pushes, popping takes amortized O(1) time and pushing amortized
+/// O(log 1/ε). This beats the Ω(log n) bound of exact heaps, and is what selection in linear
+/// time and Chazelle's minimum spanning tree algorithm are built on.
+/// It is the binary tree version of Kaplan, Tarjan and Zwick: trees of equal rank are linked like in a binomial
+/// heap, nodes above rank ⌈log₂(8/ε)⌉ keep lists of items that grow by a factor of 3/2 per rank and share a key,
+/// and a node whose list runs empty is refilled from the child with the smaller key
+///
+/// # Examples
+/// ```
+/// use rudac::heap::SoftHeap;
+///
+/// let mut soft_heap = SoftHeap::init(0.25);
+/// for item in (0..100).rev() {
+///     soft_heap.push(item);
+/// }
+///
+/// // every popped item is at most the current key it was popped with, and the keys never decrease
+/// let mut last_key = 0;
+/// while let Some(&key) = soft_heap.peek_key() {
+///     let item = soft_heap.pop().unwrap();
+///     assert!(item <= key && last_key <= key);
+///     last_key = key;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoftHeap<T> {
+    // roots[k] is the root of rank k, if there is one
+    roots: Vec<Option<Box<Node<T>>>>,
+
+    // suffix_min[k] is the rank of the root with the least key among the roots of rank k and above
+    suffix_min: Vec<Option<usize>>,
+
+    epsilon: f64,
+
+    // nodes up to this rank keep a single item, so they hold no corrupted items
+    threshold: usize,
+    size: usize,
+}
+
+impl<T: Ord + Clone> SoftHeap<T> {
+    /// Initializes an empty soft heap with error rate `epsilon`
+    ///
+    /// # Arguments
+    /// * `epsilon`: fraction of the pushed items that may be corrupted
+    ///
+    /// # Panics
+    /// * panics if `epsilon` is not between 0 and 1
+    pub fn init(epsilon: f64) -> SoftHeap<T> {
+        if epsilon.is_nan() || epsilon <= 0.0 || epsilon >= 1.0 {
+            panic!("Epsilon must be between 0 and 1");
+        }
+
+        // smallest rank whose power of two reaches 8 / epsilon
+        let mut threshold = 0;
+        while ((1u64 << threshold) as f64) * epsilon < 8.0 {
+            threshold += 1;
+        }
+
+        SoftHeap {
+            roots: Vec::new(),
+            suffix_min: Vec::new(),
+            epsilon,
+            threshold,
+            size: 0,
+        }
+    }
+
+    // links two trees of equal rank under a new root
+    fn link(&self, first: Box<Node<T>>, second: Box<Node<T>>) -> Box<Node<T>> {
+        let rank = first.rank + 1;
+        let target = if rank <= self.threshold {
+            1
+        } else {
+            (3 * first.target).div_ceil(2)
+        };
+
+        let mut root = Box::new(Node {
+            key: first.key.clone(),
+            items: Vec::new(),
+            rank,
+            target,
+            left: Some(first),
+            right: Some(second),
+        });
+        root.sift();
+
+        root
+    }
+
+    // adds `root` to the roots, linking it with the roots of equal rank, and returns the rank it ends up with
+    fn add_root(&mut self, mut root: Box<Node<T>>) -> usize {
+        loop {
+            let rank = root.rank;
+            if rank == self.roots.len() {
+                self.roots.push(None);
+                self.suffix_min.push(None);
+            }
+
+            match self.roots[rank].take() {
+                Some(other) => root = self.link(other, root),
+                None => {
+                    self.roots[rank] = Some(root);
+                    return rank;
+                }
+            }
+        }
+    }
+
+    // recomputes the suffix minima from rank `rank` down, after the roots up to it changed
+    fn update_suffix_min(&mut self, rank: usize) {
+        while self.roots.last().is_some_and(|root| root.is_none()) {
+            self.roots.pop();
+            self.suffix_min.pop();
+        }
+
+        for rank in (0..=rank.min(self.roots.len())).rev() {
+            if rank == self.roots.len() {
+                continue;
+            }
+
+            let next = self.suffix_min.get(rank + 1).copied().flatten();
+            self.suffix_min[rank] = match (&self.roots[rank], next) {
+                (None, next) => next,
+                (Some(root), Some(next)) if self.roots[next].as_ref().unwrap().key < root.key => {
+                    Some(next)
+                }
+                (Some(_), _) => Some(rank),
+            };
+        }
+    }
+
+    /// Pushes `item` into the heap
+    /// * Complexity: amortized O(log 1/ε)
+    ///
+    /// # Arguments
+    /// * `item`: data to be pushed into heap
+    pub fn push(&mut self, item: T) {
+        let rank = self.add_root(Node::leaf(item));
+        self.update_suffix_min(rank);
+        self.size += 1;
+    }
+
+    /// Pops and returns an item with the least current key, which may be larger than the item if it is corrupted.
+    /// Returns `None` if heap is empty
+    /// * Complexity: amortized O(1)
+    pub fn pop(&mut self) -> Option<T> {
+        let rank = self.suffix_min.first().copied().flatten()?;
+        let root = self.roots[rank].as_mut().unwrap();
+        let item = root.items.pop();
+
+        if root.items.is_empty() {
+            root.sift();
+            if root.items.is_empty() {
+                self.roots[rank] = None;
+            }
+            self.update_suffix_min(rank);
+        }
+        self.size -= 1;
+
+        item
+    }
+
+    /// Returns a reference to the item that is popped next, None if the heap is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.min_root().and_then(|root| root.items.last())
+    }
+
+    /// Returns the least current key, which the item that is popped next is not larger than. None if the heap is
+    /// empty
+    pub fn peek_key(&self) -> Option<&T> {
+        self.min_root().map(|root| &root.key)
+    }
+
+    fn min_root(&self) -> Option<&Node<T>> {
+        let rank = self.suffix_min.first().copied().flatten()?;
+
+        self.roots[rank].as_deref()
+    }
+
+    /// Moves every item of `other` into the heap
+    /// * Complexity: O(log n) plus amortized O(log 1/ε) per tree that is linked
+    ///
+    /// # Arguments
+    /// * `other`: the heap whose items are moved
+    ///
+    /// # Panics
+    /// * panics if the heaps do not have the same error rate
+    pub fn meld(&mut self, other: SoftHeap<T>) {
+        if self.epsilon != other.epsilon {
+            panic!("Both soft heaps must have the same error rate");
+        }
+
+        for root in other.roots.into_iter().flatten() {
+            self.add_root(root);
+        }
+        self.update_suffix_min(self.roots.len());
+        self.size += other.size;
+    }
+
+    /// Returns an iterator over the items of the heap in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut stack: Vec<&Node<T>> = self.roots.iter().flatten().map(|root| &**root).collect();
+        let mut items = Vec::with_capacity(self.size);
+        while let Some(node) = stack.pop() {
+            items.extend(node.items.iter());
+            stack.extend(node.left.as_deref());
+            stack.extend(node.right.as_deref());
+        }
+
+        items.into_iter()
+    }
+}
+
+impl<T> SoftHeap<T> {
+    /// Returns number of items in heap
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the error rate of the heap
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Removes every item of the heap
+    pub fn clear(&mut self) {
+        self.roots.clear();
+        self.suffix_min.clear();
+        self.size = 0;
+    }
+}
+
+impl<T: Ord + Clone> Extend<T> for SoftHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> MemoryFootprint for SoftHeap<T> {
+    fn footprint(&self) -> Footprint {
+        let mut footprint = Footprint::of::<T>(self.size)
+            .with_vec(&self.roots)
+            .with_vec(&self.suffix_min);
+        let mut stack: Vec<&Node<T>> = self.roots.iter().flatten().map(|root| &**root).collect();
+        while let Some(node) = stack.pop() {
+            footprint = footprint.with_boxes::<Node<T>>(1).with_vec(&node.items);
+            stack.extend(node.left.as_deref());
+            stack.extend(node.right.as_deref());
+        }
+
+        footprint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+    use std::collections::BTreeMap;
+
+    // returns the number of corrupted items, whose key was raised by the heap
+    fn corrupted(soft_heap: &SoftHeap<u64>) -> usize {
+        let mut count = 0;
+        let mut stack: Vec<&Node<u64>> = soft_heap
+            .roots
+            .iter()
+            .flatten()
+            .map(|root| &**root)
+            .collect();
+        while let Some(node) = stack.pop() {
+            count += node.items.iter().filter(|item| **item < node.key).count();
+            stack.extend(node.left.as_deref());
+            stack.extend(node.right.as_deref());
+        }
+
+        count
+    }
+
+    #[test]
+    fn heap_soft_corruption_is_bounded() {
+        let mut rng = SplitMix64::init(89);
+        for epsilon in [0.5, 0.2, 0.05] {
+            let mut soft_heap = SoftHeap::init(epsilon);
+            let mut model: BTreeMap<u64, usize> = BTreeMap::new();
+            let mut pushes = 0;
+            for step in 0..20000 {
+                if rng.next_below(3) == 0 {
+                    let key = soft_heap.peek_key().copied();
+                    let item = soft_heap.pop();
+                    assert_eq!(item.is_some(), !model.is_empty());
+                    if let (Some(item), Some(key)) = (item, key) {
+                        assert!(item <= key);
+                        let count = model.get_mut(&item).unwrap();
+                        *count -= 1;
+                        if *count == 0 {
+                            model.remove(&item);
+                        }
+                    }
+                } else {
+                    let item = rng.next_below(1 << 20);
+                    soft_heap.push(item);
+                    *model.entry(item).or_insert(0) += 1;
+                    pushes += 1;
+                }
+
+                if step % 50 == 0 {
+                    assert_eq!(soft_heap.size(), model.values().sum::<usize>());
+                    assert!(corrupted(&soft_heap) as f64 <= epsilon * pushes as f64);
+                }
+            }
+
+            let mut items: Vec<u64> = soft_heap.iter().copied().collect();
+            items.sort_unstable();
+            let expected: Vec<u64> = model
+                .iter()
+                .flat_map(|(item, count)| std::iter::repeat_n(*item, *count))
+                .collect();
+            assert_eq!(items, expected);
+        }
+    }
+
+    #[test]
+    fn heap_soft_meld() {
+        let mut first = SoftHeap::init(0.1);
+        let mut second = SoftHeap::init(0.1);
+        first.extend((0..500).map(|item| 2 * item));
+        second.extend((0..300).map(|item| 2 * item + 1));
+        first.meld(second);
+
+        assert_eq!(first.size(), 800);
+        let mut popped = Vec::new();
+        while let Some(item) = first.pop() {
+            popped.push(item);
+        }
+        popped.sort_unstable();
+        let mut expected: Vec<u64> = (0..500).map(|item| 2 * item).collect();
+        expected.extend((0..300).map(|item| 2 * item + 1));
+        expected.sort_unstable();
+        assert_eq!(popped, expected);
+        assert!(first.is_empty());
+    }
+
+    #[test]
+    fn heap_soft_exact_below_threshold() {
+        // a single tree up to the threshold rank holds no corrupted items, so the heap pops in order
+        let mut soft_heap = SoftHeap::init(0.01);
+        soft_heap.extend([5, 3, 9, 1, 7, 2, 8]);
+
+        let popped: Vec<i32> = std::iter::from_fn(|| soft_heap.pop()).collect();
+        assert_eq!(popped, vec![1, 2, 3, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Epsilon must be between 0 and 1")]
+    fn heap_soft_panic_epsilon() {
+        SoftHeap::<u32>::init(1.5);
+    }
+}
@@ -0,0 +1,382 @@
+use super::IntoIterSorted;
+#[cfg(feature = "serde")]
+use crate::util::serialize::{deserialize_items, serialize_items, HEAP_FIELDS};
+use crate::util::{Footprint, MemoryFootprint, Order};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A stable heap pops items of equal priority in the order they were pushed, first in first out, which the other
+/// heaps of this module do not promise. Every item is tagged with a sequence number when it is pushed and ties of
+/// the order are broken by it, which is what schedulers and discrete event simulations need to stay deterministic.
+/// Internally it is a binary heap, so pushing and popping take O(log n) time
+///
+/// # Examples
+/// ```
+/// use rudac::heap::StableHeap;
+///
+/// // tasks by priority, equal priorities in arrival order
+/// let mut stable_heap: StableHeap<(u32, &str)> = StableHeap::init_with(|task1: &(u32, &str), task2: &(u32, &str)| {
+///     task2.0.cmp(&task1.0)
+/// });
+/// stable_heap.push((1, "log rotation"));
+/// stable_heap.push((5, "first request"));
+/// stable_heap.push((5, "second request"));
+/// stable_heap.push((5, "third request"));
+///
+/// assert_eq!(stable_heap.pop(), Some((5, "first request")));
+/// assert_eq!(stable_heap.pop(), Some((5, "second request")));
+/// assert_eq!(stable_heap.pop(), Some((5, "third request")));
+/// assert_eq!(stable_heap.pop(), Some((1, "log rotation")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StableHeap<T> {
+    // binary heap of items with the sequence number they were pushed with
+    tree: Vec<(u64, T)>,
+
+    // sequence number of the next pushed item
+    next: u64,
+
+    // indicates wether current heap is initialized as a min heap or not
+    min: bool,
+
+    // order of the items, the item that is least by it has the highest priority
+    order: Order<T>,
+}
+
+impl<T: core::cmp::Ord> StableHeap<T> {
+    /// Initializes a min heap
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::StableHeap;
+    ///
+    /// let stable_heap: StableHeap<usize> = StableHeap::init_min();
+    ///
+    /// assert_eq!(stable_heap.is_min(), true);
+    /// ```
+    pub fn init_min() -> StableHeap<T> {
+        StableHeap::init(true, Order::natural(true))
+    }
+
+    /// Initializes a max heap. Items of equal priority still come out first in first out
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::StableHeap;
+    ///
+    /// let stable_heap: StableHeap<usize> = StableHeap::init_max();
+    ///
+    /// assert_eq!(stable_heap.is_max(), true);
+    /// ```
+    pub fn init_max() -> StableHeap<T> {
+        StableHeap::init(false, Order::natural(false))
+    }
+}
+
+impl<T> StableHeap<T> {
+    fn init(min: bool, order: Order<T>) -> StableHeap<T> {
+        StableHeap {
+            tree: Vec::new(),
+            next: 0,
+            min,
+            order,
+        }
+    }
+
+    /// Initializes a heap ordered by `compare` instead of `Ord`, popping the least item by `compare` first and
+    /// items that `compare` finds equal in the order they were pushed. Such a heap counts as a min heap
+    ///
+    /// # Arguments
+    /// * `compare`: order of the items
+    pub fn init_with<F>(compare: F) -> StableHeap<T>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        StableHeap::init(true, Order::with(compare))
+    }
+
+    /// Initializes a heap ordered by the key `key` extracts from every item, popping the item with the least key
+    /// first and items of equal keys in the order they were pushed. The key is computed on every comparison, so it
+    /// should be cheap. Such a heap counts as a min heap
+    ///
+    /// # Arguments
+    /// * `key`: key of an item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::StableHeap;
+    ///
+    /// // jobs by priority, then first come first served
+    /// let mut stable_heap = StableHeap::init_by_key(|job: &(u8, &str)| job.0);
+    /// stable_heap.push((1, "backup"));
+    /// stable_heap.push((0, "deploy"));
+    /// stable_heap.push((1, "report"));
+    ///
+    /// assert_eq!(stable_heap.pop(), Some((0, "deploy")));
+    /// assert_eq!(stable_heap.pop(), Some((1, "backup")));
+    /// assert_eq!(stable_heap.pop(), Some((1, "report")));
+    /// ```
+    pub fn init_by_key<K, F>(key: F) -> StableHeap<T>
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        StableHeap::init(true, Order::by_key(key))
+    }
+
+    // returns true if the item at `index1` has higher priority than the item at `index2`
+    fn has_higher_priority(&self, index1: usize, index2: usize) -> bool {
+        let (sequence1, item1) = &self.tree[index1];
+        let (sequence2, item2) = &self.tree[index2];
+
+        self.order
+            .compare(item1, item2)
+            .then(sequence1.cmp(sequence2))
+            == Ordering::Less
+    }
+
+    // moves the item at `index` up until its parent has higher priority
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if !self.has_higher_priority(index, parent) {
+                break;
+            }
+            self.tree.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    // moves the item at `index` down until it has higher priority than its children
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let left = 2 * index + 1;
+            if left >= self.tree.len() {
+                break;
+            }
+
+            let right = left + 1;
+            let best = if right < self.tree.len() && self.has_higher_priority(right, left) {
+                right
+            } else {
+                left
+            };
+            if !self.has_higher_priority(best, index) {
+                break;
+            }
+            self.tree.swap(index, best);
+            index = best;
+        }
+    }
+
+    /// Pushes `item` into the heap, after every item of equal priority already in it
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `item`: data to be pushed into heap
+    pub fn push(&mut self, item: T) {
+        self.tree.push((self.next, item));
+        self.next += 1;
+        self.sift_up(self.tree.len() - 1);
+    }
+
+    /// Pops and returns item with highest priority, the earliest pushed one among equals.
+    /// Returns `None` if heap is empty
+    /// * Complexity: O(log n)
+    pub fn pop(&mut self) -> Option<T> {
+        if self.tree.is_empty() {
+            return None;
+        }
+
+        let (_, item) = self.tree.swap_remove(0);
+        self.sift_down(0);
+
+        Some(item)
+    }
+
+    /// Returns a reference to item with highest priority, None if the heap is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.tree.first().map(|(_, item)| item)
+    }
+
+    /// Returns number of items in heap
+    pub fn size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns true if there are no items in the heap
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Removes every item of the heap
+    pub fn clear(&mut self) {
+        self.tree.clear();
+    }
+
+    /// Returns true if the heap is initialized as a min heap, or by `init_with` or `init_by_key`
+    pub fn is_min(&self) -> bool {
+        self.min
+    }
+
+    /// Returns true if the heap is initialized as a max heap
+    pub fn is_max(&self) -> bool {
+        !self.is_min()
+    }
+
+    /// Returns an iterator over the items of the heap in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.tree.iter().map(|(_, item)| item)
+    }
+
+    /// Consumes the heap and returns an iterator over its items in the order they are popped
+    /// * Complexity: O(log n) per item
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, StableHeap<T>> {
+        IntoIterSorted::init(self)
+    }
+
+    /// Consumes the heap and returns its items in the order they are popped
+    /// * Complexity: O(n log n)
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let order = self.order;
+        let mut tree = self.tree;
+        tree.sort_by(|(sequence1, item1), (sequence2, item2)| {
+            order.compare(item1, item2).then(sequence1.cmp(sequence2))
+        });
+
+        tree.into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+impl<T: core::cmp::Ord> core::iter::FromIterator<T> for StableHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stable_heap = StableHeap::init_min();
+        stable_heap.extend(iter);
+
+        stable_heap
+    }
+}
+
+impl<T> Extend<T> for StableHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T: core::cmp::Ord> Default for StableHeap<T> {
+    fn default() -> Self {
+        StableHeap::init_min()
+    }
+}
+
+impl<T> MemoryFootprint for StableHeap<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.tree.len()).with_vec(&self.tree)
+    }
+}
+
+// a heap is written as its items in the order they would be popped and read back ordered by `Ord`, heaps made by
+// `init_with` or `init_by_key` come back as min heaps
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for StableHeap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // items of equal priority keep the order they were pushed in
+        let mut tree: Vec<&(u64, T)> = self.tree.iter().collect();
+        tree.sort_by(|(sequence1, item1), (sequence2, item2)| {
+            self.order
+                .compare(item1, item2)
+                .then(sequence1.cmp(sequence2))
+        });
+        let items: Vec<&T> = tree.iter().map(|(_, item)| item).collect();
+
+        serialize_items(serializer, "StableHeap", HEAP_FIELDS, self.min, &items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: core::cmp::Ord + Deserialize<'de>> Deserialize<'de> for StableHeap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (min, items): (bool, Vec<T>) =
+            deserialize_items(deserializer, "StableHeap", HEAP_FIELDS)?;
+
+        let mut stable_heap = if min {
+            StableHeap::init_min()
+        } else {
+            StableHeap::init_max()
+        };
+        stable_heap.extend(items);
+
+        Ok(stable_heap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn heap_stable_matches_stable_sort() {
+        let mut rng = SplitMix64::init(67);
+        let mut stable_heap =
+            StableHeap::init_with(|item1: &(u64, usize), item2: &(u64, usize)| {
+                item2.0.cmp(&item1.0)
+            });
+        let mut model: Vec<(u64, usize)> = Vec::new();
+        for label in 0..3000 {
+            if rng.next_below(3) == 0 {
+                // the stable sort keeps equal priorities in the order they were pushed
+                model.sort_by_key(|item| std::cmp::Reverse(item.0));
+                let expected = if model.is_empty() {
+                    None
+                } else {
+                    Some(model.remove(0))
+                };
+                assert_eq!(stable_heap.pop(), expected);
+            } else {
+                let item = (rng.next_below(10), label);
+                stable_heap.push(item);
+                model.push(item);
+            }
+            assert_eq!(stable_heap.size(), model.len());
+        }
+
+        model.sort_by_key(|item| std::cmp::Reverse(item.0));
+        assert_eq!(stable_heap.clone().into_sorted_vec(), model);
+        assert_eq!(stable_heap.into_iter_sorted().collect::<Vec<_>>(), model);
+    }
+
+    #[test]
+    fn heap_stable_min_and_max() {
+        let mut min = StableHeap::init_min();
+        let mut max = StableHeap::init_max();
+        for item in [3, 1, 2, 1, 3] {
+            min.push(item);
+            max.push(item);
+        }
+
+        assert_eq!(min.into_sorted_vec(), vec![1, 1, 2, 3, 3]);
+        assert_eq!(max.peek(), Some(&3));
+        assert_eq!(max.into_sorted_vec(), vec![3, 3, 2, 1, 1]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn heap_stable_serde() {
+        let mut stable_heap = StableHeap::init_max();
+        stable_heap.extend(vec![(1, 'a'), (3, 'b'), (1, 'c'), (3, 'd')]);
+
+        let json = serde_json::to_string(&stable_heap).unwrap();
+        let restored: StableHeap<(i32, char)> = serde_json::from_str(&json).unwrap();
+
+        assert!(restored.is_max());
+        assert_eq!(
+            restored.into_iter_sorted().collect::<Vec<_>>(),
+            stable_heap.into_iter_sorted().collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,9 +1,44 @@
 //! # Rudac
 //! Rudac is a **Ru**st **D**ata structure and **A**lgorithm **C**rate.
 //! It aims to be a comprehensive crate of useful data structure and algorithms.
+//!
+//! The default `std` feature can be turned off to use the crate in `no_std` environments that have an allocator.
+//! The heaps, queues, trees, sequences, codec and replay modules are kept there, the other modules need `std`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub mod heap;
+#[cfg(feature = "std")]
+pub mod math;
 pub mod queue;
 pub mod tree;
 pub mod util;
-pub mod algo;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub mod algo;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod geometry;
+#[cfg(feature = "std")]
+pub mod probabilistic;
+#[cfg(feature = "std")]
+pub mod spatial;
+#[cfg(feature = "std")]
+pub mod structure;
+pub mod sequence;
+#[cfg(feature = "std")]
+pub mod logic;
+#[cfg(feature = "std")]
+pub mod map;
+#[cfg(feature = "std")]
+pub mod string;
+#[cfg(feature = "std")]
+pub mod compress;
+pub mod codec;
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod sim;
+#[cfg(feature = "std")]
+pub mod graph;
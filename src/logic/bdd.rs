@@ -0,0 +1,595 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::HashMap;
+
+/// Handle of a boolean function in a `Bdd`. Diagrams are reduced and shared through a unique table,
+/// so two handles of the same `Bdd` are equal if and only if they represent the same function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BddId(usize);
+
+/// Binary operators supported by `Bdd::apply`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BddOp {
+    /// conjunction
+    And,
+    /// disjunction
+    Or,
+    /// exclusive disjunction
+    Xor,
+    /// implication
+    Implies,
+}
+
+impl BddOp {
+    fn evaluate(self, a: bool, b: bool) -> bool {
+        match self {
+            BddOp::And => a && b,
+            BddOp::Or => a || b,
+            BddOp::Xor => a != b,
+            BddOp::Implies => !a || b,
+        }
+    }
+
+    fn is_commutative(self) -> bool {
+        self != BddOp::Implies
+    }
+}
+
+// decision node. terminals have level equal to number of variables
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: usize,
+    low: BddId,
+    high: BddId,
+}
+
+const FALSE: BddId = BddId(0);
+const TRUE: BddId = BddId(1);
+
+/// A reduced ordered binary decision diagram package. It owns the nodes of all functions built with it,
+/// shares equal subdiagrams through a unique table and caches results of `apply`.
+///
+/// Variables are tested in a fixed order, from the first to the last variable of the order. Size of the diagrams
+/// heavily depends on the order, so related variables are better kept close to each other.
+///
+/// # Examples
+/// ```
+/// use rudac::logic::Bdd;
+///
+/// let mut bdd = Bdd::init(3);
+/// let x = bdd.variable(0);
+/// let y = bdd.variable(1);
+/// let z = bdd.variable(2);
+///
+/// // (x and y) or z
+/// let xy = bdd.and(x, y);
+/// let f = bdd.or(xy, z);
+///
+/// assert_eq!(bdd.sat_count(f), 5);
+/// assert_eq!(bdd.evaluate(f, &[true, true, false]), true);
+///
+/// // f with z = false is x and y
+/// assert_eq!(bdd.restrict(f, 2, false), xy);
+///
+/// // equal functions share the same node
+/// let not_x = bdd.not(x);
+/// let g = bdd.or(not_x, y);
+/// assert_eq!(g, bdd.implies(x, y));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bdd {
+    // order[level] is the variable tested at level
+    order: Vec<usize>,
+    // levels[variable] is the level of variable
+    levels: Vec<usize>,
+
+    nodes: Vec<Node>,
+    unique: HashMap<(usize, BddId, BddId), BddId>,
+    cache: HashMap<(BddOp, BddId, BddId), BddId>,
+}
+
+impl Bdd {
+    /// Initializes a package with `variables` variables, ordered by their index
+    ///
+    /// # Arguments
+    /// * `variables`: number of variables
+    ///
+    /// # Panics
+    /// * panics if `variables` is greater than 127
+    pub fn init(variables: usize) -> Bdd {
+        Bdd::with_order((0..variables).collect())
+    }
+
+    /// Initializes a package whose variables are tested in `order`
+    ///
+    /// # Arguments
+    /// * `order`: a permutation of the variables, `order[0]` is tested first
+    ///
+    /// # Panics
+    /// * panics if `order` is not a permutation of `0..order.len()`
+    /// * panics if number of variables is greater than 127
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::logic::Bdd;
+    ///
+    /// // x0 = x1 and x2 = x3, with related variables adjacent or interleaved
+    /// fn size(order: Vec<usize>) -> usize {
+    ///     let mut bdd = Bdd::with_order(order);
+    ///     let (x0, x1, x2, x3) = (bdd.variable(0), bdd.variable(1), bdd.variable(2), bdd.variable(3));
+    ///     let a = bdd.xor(x0, x1);
+    ///     let b = bdd.xor(x2, x3);
+    ///     let a = bdd.not(a);
+    ///     let b = bdd.not(b);
+    ///     let f = bdd.and(a, b);
+    ///     bdd.size(f)
+    /// }
+    ///
+    /// assert!(size(vec![0, 1, 2, 3]) < size(vec![0, 2, 1, 3]));
+    /// ```
+    pub fn with_order(order: Vec<usize>) -> Bdd {
+        let variables = order.len();
+        if variables > 127 {
+            panic!("Number of variables must not be greater than 127");
+        }
+
+        let mut levels = vec![usize::MAX; variables];
+        for (level, variable) in order.iter().enumerate() {
+            if *variable >= variables || levels[*variable] != usize::MAX {
+                panic!("Order must be a permutation of the variables");
+            }
+            levels[*variable] = level;
+        }
+
+        let terminal = Node {
+            level: variables,
+            low: FALSE,
+            high: FALSE,
+        };
+
+        Bdd {
+            order,
+            levels,
+            nodes: vec![terminal, terminal],
+            unique: HashMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Returns number of variables
+    pub fn variables(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Returns order of the variables
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Returns number of nodes owned by the package, including the two terminals
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the constant false function
+    pub fn zero(&self) -> BddId {
+        FALSE
+    }
+
+    /// Returns the constant true function
+    pub fn one(&self) -> BddId {
+        TRUE
+    }
+
+    /// Returns the function that is true if and only if `variable` is true
+    ///
+    /// # Panics
+    /// * panics if `variable` is out of range
+    pub fn variable(&mut self, variable: usize) -> BddId {
+        let level = self.level_of(variable);
+
+        self.make(level, FALSE, TRUE)
+    }
+
+    /// Returns true if `f` is one of the constant functions
+    pub fn is_constant(&self, f: BddId) -> bool {
+        f == FALSE || f == TRUE
+    }
+
+    /// Returns the negation of `f`
+    pub fn not(&mut self, f: BddId) -> BddId {
+        self.apply(BddOp::Xor, f, TRUE)
+    }
+
+    /// Returns conjunction of `f` and `g`
+    pub fn and(&mut self, f: BddId, g: BddId) -> BddId {
+        self.apply(BddOp::And, f, g)
+    }
+
+    /// Returns disjunction of `f` and `g`
+    pub fn or(&mut self, f: BddId, g: BddId) -> BddId {
+        self.apply(BddOp::Or, f, g)
+    }
+
+    /// Returns exclusive disjunction of `f` and `g`
+    pub fn xor(&mut self, f: BddId, g: BddId) -> BddId {
+        self.apply(BddOp::Xor, f, g)
+    }
+
+    /// Returns implication `f -> g`
+    pub fn implies(&mut self, f: BddId, g: BddId) -> BddId {
+        self.apply(BddOp::Implies, f, g)
+    }
+
+    /// Combines `f` and `g` with `op`.
+    /// * Complexity: O(size(f) * size(g))
+    ///
+    /// # Arguments
+    /// * `op`: the operator
+    /// * `f`: left operand
+    /// * `g`: right operand
+    pub fn apply(&mut self, op: BddOp, f: BddId, g: BddId) -> BddId {
+        if self.is_constant(f) && self.is_constant(g) {
+            return if op.evaluate(f == TRUE, g == TRUE) {
+                TRUE
+            } else {
+                FALSE
+            };
+        }
+        if let Some(result) = Bdd::shortcut(op, f, g) {
+            return result;
+        }
+
+        let key = if op.is_commutative() && g < f {
+            (op, g, f)
+        } else {
+            (op, f, g)
+        };
+        if let Some(result) = self.cache.get(&key) {
+            return *result;
+        }
+
+        let level = std::cmp::min(self.nodes[f.0].level, self.nodes[g.0].level);
+        let (f_low, f_high) = self.cofactors(f, level);
+        let (g_low, g_high) = self.cofactors(g, level);
+
+        let low = self.apply(op, f_low, g_low);
+        let high = self.apply(op, f_high, g_high);
+        let result = self.make(level, low, high);
+
+        self.cache.insert(key, result);
+        result
+    }
+
+    /// Returns `f` with `variable` fixed to `value`
+    ///
+    /// # Arguments
+    /// * `f`: the function
+    /// * `variable`: variable to be fixed
+    /// * `value`: value of the variable
+    ///
+    /// # Panics
+    /// * panics if `variable` is out of range
+    pub fn restrict(&mut self, f: BddId, variable: usize, value: bool) -> BddId {
+        let level = self.level_of(variable);
+        let mut memo = HashMap::new();
+
+        self.restrict_level(f, level, value, &mut memo)
+    }
+
+    fn restrict_level(
+        &mut self,
+        f: BddId,
+        level: usize,
+        value: bool,
+        memo: &mut HashMap<BddId, BddId>,
+    ) -> BddId {
+        let node = self.nodes[f.0];
+        if node.level > level {
+            return f;
+        }
+        if node.level == level {
+            return if value { node.high } else { node.low };
+        }
+        if let Some(result) = memo.get(&f) {
+            return *result;
+        }
+
+        let low = self.restrict_level(node.low, level, value, memo);
+        let high = self.restrict_level(node.high, level, value, memo);
+        let result = self.make(node.level, low, high);
+
+        memo.insert(f, result);
+        result
+    }
+
+    /// Returns existential quantification of `f` over `variable`, which is `f[variable = 0] or f[variable = 1]`
+    ///
+    /// # Panics
+    /// * panics if `variable` is out of range
+    pub fn exists(&mut self, f: BddId, variable: usize) -> BddId {
+        let low = self.restrict(f, variable, false);
+        let high = self.restrict(f, variable, true);
+
+        self.or(low, high)
+    }
+
+    /// Returns value of `f` under `assignment`
+    ///
+    /// # Arguments
+    /// * `f`: the function
+    /// * `assignment`: value of every variable, indexed by variable
+    ///
+    /// # Panics
+    /// * panics if length of `assignment` is not equal to number of variables
+    pub fn evaluate(&self, f: BddId, assignment: &[bool]) -> bool {
+        if assignment.len() != self.variables() {
+            panic!("Assignment must have a value for every variable");
+        }
+
+        let mut current = f;
+        while !self.is_constant(current) {
+            let node = self.nodes[current.0];
+            current = if assignment[self.order[node.level]] {
+                node.high
+            } else {
+                node.low
+            };
+        }
+
+        current == TRUE
+    }
+
+    /// Returns number of assignments of all variables that satisfy `f`.
+    /// * Complexity: O(size(f))
+    pub fn sat_count(&self, f: BddId) -> u128 {
+        let mut memo = HashMap::new();
+        let count = self.count_below(f, &mut memo);
+
+        count << self.nodes[f.0].level
+    }
+
+    // number of satisfying assignments of the variables at or below the level of f
+    fn count_below(&self, f: BddId, memo: &mut HashMap<BddId, u128>) -> u128 {
+        if self.is_constant(f) {
+            return if f == TRUE { 1 } else { 0 };
+        }
+        if let Some(count) = memo.get(&f) {
+            return *count;
+        }
+
+        let node = self.nodes[f.0];
+        let low =
+            self.count_below(node.low, memo) << (self.nodes[node.low.0].level - node.level - 1);
+        let high =
+            self.count_below(node.high, memo) << (self.nodes[node.high.0].level - node.level - 1);
+
+        memo.insert(f, low + high);
+        low + high
+    }
+
+    /// Returns a satisfying assignment of `f` indexed by variable, or `None` if `f` is unsatisfiable.
+    /// Variables that do not affect the result are false
+    pub fn any_sat(&self, f: BddId) -> Option<Vec<bool>> {
+        if f == FALSE {
+            return None;
+        }
+
+        // every node except the false terminal has a path to the true terminal
+        let mut assignment = vec![false; self.variables()];
+        let mut current = f;
+        while !self.is_constant(current) {
+            let node = self.nodes[current.0];
+            if node.low == FALSE {
+                assignment[self.order[node.level]] = true;
+                current = node.high;
+            } else {
+                current = node.low;
+            }
+        }
+
+        Some(assignment)
+    }
+
+    /// Returns number of nodes of the diagram of `f`, including its terminals
+    pub fn size(&self, f: BddId) -> usize {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![f];
+        let mut count = 0;
+
+        while let Some(current) = stack.pop() {
+            if visited[current.0] {
+                continue;
+            }
+            visited[current.0] = true;
+            count += 1;
+
+            if !self.is_constant(current) {
+                let node = self.nodes[current.0];
+                stack.push(node.low);
+                stack.push(node.high);
+            }
+        }
+
+        count
+    }
+
+    /// Removes cached results of `apply`. Nodes are kept, so handles stay valid
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    fn level_of(&self, variable: usize) -> usize {
+        if variable >= self.variables() {
+            panic!("Variable out of range");
+        }
+
+        self.levels[variable]
+    }
+
+    // returns the node testing level with the given children, reusing an existing node if possible
+    fn make(&mut self, level: usize, low: BddId, high: BddId) -> BddId {
+        if low == high {
+            return low;
+        }
+
+        let next = BddId(self.nodes.len());
+        let id = *self.unique.entry((level, low, high)).or_insert(next);
+        if id == next {
+            self.nodes.push(Node { level, low, high });
+        }
+
+        id
+    }
+
+    fn cofactors(&self, f: BddId, level: usize) -> (BddId, BddId) {
+        let node = self.nodes[f.0];
+        if node.level == level {
+            (node.low, node.high)
+        } else {
+            (f, f)
+        }
+    }
+
+    fn shortcut(op: BddOp, f: BddId, g: BddId) -> Option<BddId> {
+        match op {
+            BddOp::And if f == FALSE || g == FALSE => Some(FALSE),
+            BddOp::And if f == TRUE || f == g => Some(g),
+            BddOp::And if g == TRUE => Some(f),
+            BddOp::Or if f == TRUE || g == TRUE => Some(TRUE),
+            BddOp::Or if f == FALSE || f == g => Some(g),
+            BddOp::Or if g == FALSE => Some(f),
+            BddOp::Xor if f == g => Some(FALSE),
+            BddOp::Xor if f == FALSE => Some(g),
+            BddOp::Xor if g == FALSE => Some(f),
+            BddOp::Implies if f == FALSE || g == TRUE || f == g => Some(TRUE),
+            BddOp::Implies if f == TRUE => Some(g),
+            _ => None,
+        }
+    }
+}
+
+impl MemoryFootprint for Bdd {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<Node>(self.nodes.len())
+            .with_vec(&self.order)
+            .with_vec(&self.levels)
+            .with_vec(&self.nodes)
+            .with_hash_map(&self.unique)
+            .with_hash_map(&self.cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignments(variables: usize) -> Vec<Vec<bool>> {
+        (0..1usize << variables)
+            .map(|mask| (0..variables).map(|v| mask >> v & 1 == 1).collect())
+            .collect()
+    }
+
+    #[test]
+    fn logic_bdd_matches_truth_table() {
+        let mut bdd = Bdd::with_order(vec![2, 0, 3, 1]);
+        let x: Vec<BddId> = (0..4).map(|v| bdd.variable(v)).collect();
+
+        // (x0 xor x1) -> (x2 and not x3)
+        let left = bdd.xor(x[0], x[1]);
+        let not_x3 = bdd.not(x[3]);
+        let right = bdd.and(x[2], not_x3);
+        let f = bdd.implies(left, right);
+
+        let mut count = 0;
+        for a in assignments(4) {
+            let expected = a[0] == a[1] || (a[2] && !a[3]);
+            assert_eq!(bdd.evaluate(f, &a), expected);
+            if expected {
+                count += 1;
+            }
+        }
+        assert_eq!(bdd.sat_count(f), count);
+        assert!(bdd.evaluate(f, &bdd.any_sat(f).unwrap()));
+    }
+
+    #[test]
+    fn logic_bdd_canonical() {
+        let mut bdd = Bdd::init(3);
+        let x = bdd.variable(0);
+        let y = bdd.variable(1);
+        let z = bdd.variable(2);
+
+        // distributivity: x and (y or z) = (x and y) or (x and z)
+        let y_or_z = bdd.or(y, z);
+        let left = bdd.and(x, y_or_z);
+        let xy = bdd.and(x, y);
+        let xz = bdd.and(x, z);
+        let right = bdd.or(xy, xz);
+        assert_eq!(left, right);
+
+        // de morgan
+        let not_x = bdd.not(x);
+        let not_y = bdd.not(y);
+        let x_and_y = bdd.and(x, y);
+        let left = bdd.not(x_and_y);
+        let right = bdd.or(not_x, not_y);
+        assert_eq!(left, right);
+
+        let tautology = bdd.or(x, not_x);
+        assert_eq!(tautology, bdd.one());
+        assert_eq!(bdd.sat_count(tautology), 8);
+        assert_eq!(bdd.sat_count(bdd.zero()), 0);
+        assert_eq!(bdd.any_sat(bdd.zero()), None);
+    }
+
+    #[test]
+    fn logic_bdd_restrict_and_exists() {
+        let mut bdd = Bdd::init(3);
+        let x = bdd.variable(0);
+        let y = bdd.variable(1);
+        let z = bdd.variable(2);
+
+        let xy = bdd.and(x, y);
+        let f = bdd.xor(xy, z);
+
+        let not_z = bdd.not(z);
+        assert_eq!(bdd.restrict(f, 0, false), z);
+        let restricted = bdd.restrict(f, 1, true);
+        assert_eq!(restricted, bdd.xor(x, z));
+        assert_eq!(bdd.restrict(f, 2, true), bdd.not(xy));
+        assert_eq!(bdd.exists(f, 2), bdd.one());
+
+        let g = bdd.and(xy, not_z);
+        assert_eq!(bdd.exists(g, 0), bdd.and(y, not_z));
+    }
+
+    #[test]
+    fn logic_bdd_sharing() {
+        let mut bdd = Bdd::init(20);
+        let mut parity = bdd.zero();
+        for v in 0..20 {
+            let x = bdd.variable(v);
+            parity = bdd.xor(parity, x);
+        }
+
+        // parity has two nodes per level except the first one
+        assert_eq!(bdd.size(parity), 2 * 20 - 1 + 2);
+        assert_eq!(bdd.sat_count(parity), 1 << 19);
+
+        let before = bdd.node_count();
+        bdd.clear_cache();
+        let mut again = bdd.zero();
+        for v in 0..20 {
+            let x = bdd.variable(v);
+            again = bdd.xor(again, x);
+        }
+        assert_eq!(again, parity);
+        assert_eq!(bdd.node_count(), before);
+    }
+
+    #[test]
+    #[should_panic(expected = "Order must be a permutation of the variables")]
+    fn logic_bdd_invalid_order() {
+        Bdd::with_order(vec![0, 2, 2]);
+    }
+}
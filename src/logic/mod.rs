@@ -0,0 +1,5 @@
+mod bdd;
+
+pub use bdd::Bdd;
+pub use bdd::BddId;
+pub use bdd::BddOp;
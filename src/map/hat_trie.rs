@@ -0,0 +1,682 @@
+use crate::replay::{Op, Replayable};
+use crate::util::{Footprint, MemoryFootprint};
+
+const SLOT_COUNT: usize = 32;
+const DEFAULT_BURST_THRESHOLD: usize = 512;
+
+// FNV-1a hash of a key suffix
+fn hash(key: &[u8]) -> u64 {
+    key.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn to_key(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).expect("Keys are built from valid UTF-8 strings")
+}
+
+// appends `len` as a LEB128 varint
+fn write_len(bytes: &mut Vec<u8>, mut len: usize) {
+    while len >= 0x80 {
+        bytes.push((len as u8 & 0x7f) | 0x80);
+        len >>= 7;
+    }
+    bytes.push(len as u8);
+}
+
+// reads a LEB128 varint at `offset`, returns the value and the number of bytes it occupies
+fn read_len(bytes: &[u8], offset: usize) -> (usize, usize) {
+    let mut len = 0;
+    let mut read = 0;
+    loop {
+        let byte = bytes[offset + read];
+        len |= ((byte & 0x7f) as usize) << (7 * read);
+        read += 1;
+        if byte & 0x80 == 0 {
+            return (len, read);
+        }
+    }
+}
+
+// A slot of an array hash: all suffixes are stored length-prefixed in one contiguous buffer,
+// values are stored in the same order in a separate vector
+struct Slot<V> {
+    keys: Vec<u8>,
+    values: Vec<V>,
+}
+
+impl<V> Slot<V> {
+    fn init() -> Slot<V> {
+        Slot {
+            keys: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    // returns index of the value, byte offset of the entry and its encoded length
+    fn find(&self, key: &[u8]) -> Option<(usize, usize, usize)> {
+        let mut offset = 0;
+        for index in 0..self.values.len() {
+            let (len, header) = read_len(&self.keys, offset);
+            let start = offset + header;
+            if &self.keys[start..start + len] == key {
+                return Some((index, offset, header + len));
+            }
+            offset = start + len;
+        }
+
+        None
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&[u8], &V)> {
+        let mut offset = 0;
+        self.values.iter().map(move |value| {
+            let (len, header) = read_len(&self.keys, offset);
+            let start = offset + header;
+            offset = start + len;
+            (&self.keys[start..offset], value)
+        })
+    }
+}
+
+// An array hash bucket holding the suffixes of all keys below a trie position
+struct Bucket<V> {
+    slots: Vec<Slot<V>>,
+    size: usize,
+}
+
+impl<V> Bucket<V> {
+    fn init() -> Bucket<V> {
+        Bucket {
+            slots: (0..SLOT_COUNT).map(|_| Slot::init()).collect(),
+            size: 0,
+        }
+    }
+
+    fn slot(&self, key: &[u8]) -> usize {
+        (hash(key) % SLOT_COUNT as u64) as usize
+    }
+
+    fn get(&self, key: &[u8]) -> Option<&V> {
+        let slot = &self.slots[self.slot(key)];
+        slot.find(key).map(|(index, _, _)| &slot.values[index])
+    }
+
+    fn get_mut(&mut self, key: &[u8]) -> Option<&mut V> {
+        let position = self.slot(key);
+        let slot = &mut self.slots[position];
+        match slot.find(key) {
+            Some((index, _, _)) => Some(&mut slot.values[index]),
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let position = self.slot(key);
+        let slot = &mut self.slots[position];
+        if let Some((index, _, _)) = slot.find(key) {
+            return Some(std::mem::replace(&mut slot.values[index], value));
+        }
+
+        write_len(&mut slot.keys, key.len());
+        slot.keys.extend_from_slice(key);
+        slot.values.push(value);
+        self.size += 1;
+
+        None
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        let position = self.slot(key);
+        let slot = &mut self.slots[position];
+        let (index, offset, len) = slot.find(key)?;
+        slot.keys.drain(offset..offset + len);
+        self.size -= 1;
+
+        Some(slot.values.remove(index))
+    }
+
+    fn sorted_entries(&self) -> Vec<(&[u8], &V)> {
+        let mut entries: Vec<(&[u8], &V)> =
+            self.slots.iter().flat_map(|slot| slot.entries()).collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        entries
+    }
+
+    // turns the bucket into a trie node distributing the suffixes by their first byte
+    fn burst(self, threshold: usize) -> TrieNode<V> {
+        let mut node = TrieNode::init();
+        for slot in self.slots {
+            let mut offset = 0;
+            for value in slot.values {
+                let (len, header) = read_len(&slot.keys, offset);
+                let start = offset + header;
+                offset = start + len;
+                match slot.keys[start..offset].split_first() {
+                    None => node.value = Some(value),
+                    Some((byte, rest)) => {
+                        let child = node.children[*byte as usize]
+                            .get_or_insert_with(|| Node::Bucket(Bucket::init()));
+                        if let Node::Bucket(bucket) = child {
+                            bucket.insert(rest, value);
+                        }
+                    }
+                }
+            }
+        }
+
+        // all suffixes may share their first byte, so children can be over the threshold too
+        for child in node.children.iter_mut() {
+            if let Some(Node::Bucket(bucket)) = child {
+                if bucket.size > threshold {
+                    let bucket = std::mem::replace(bucket, Bucket::init());
+                    *child = Some(Node::Trie(Box::new(bucket.burst(threshold))));
+                }
+            }
+        }
+
+        node
+    }
+}
+
+struct TrieNode<V> {
+    value: Option<V>,
+    children: Vec<Option<Node<V>>>,
+}
+
+impl<V> TrieNode<V> {
+    fn init() -> TrieNode<V> {
+        TrieNode {
+            value: None,
+            children: (0..256).map(|_| None).collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.iter().all(|child| child.is_none())
+    }
+}
+
+enum Node<V> {
+    Trie(Box<TrieNode<V>>),
+    Bucket(Bucket<V>),
+}
+
+impl<V> Node<V> {
+    fn is_empty(&self) -> bool {
+        match self {
+            Node::Trie(node) => node.is_empty(),
+            Node::Bucket(bucket) => bucket.size == 0,
+        }
+    }
+
+    fn insert(&mut self, key: &[u8], value: V, threshold: usize) -> Option<V> {
+        match self {
+            Node::Trie(node) => match key.split_first() {
+                None => node.value.replace(value),
+                Some((byte, rest)) => node.children[*byte as usize]
+                    .get_or_insert_with(|| Node::Bucket(Bucket::init()))
+                    .insert(rest, value, threshold),
+            },
+            Node::Bucket(bucket) => {
+                let previous = bucket.insert(key, value);
+                if bucket.size > threshold {
+                    let bucket = std::mem::replace(bucket, Bucket::init());
+                    *self = Node::Trie(Box::new(bucket.burst(threshold)));
+                }
+
+                previous
+            }
+        }
+    }
+
+    // removes `key` below this node and prunes children left empty
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        match self {
+            Node::Trie(node) => match key.split_first() {
+                None => node.value.take(),
+                Some((byte, rest)) => {
+                    let child = node.children[*byte as usize].as_mut()?;
+                    let removed = child.remove(rest);
+                    if child.is_empty() {
+                        node.children[*byte as usize] = None;
+                    }
+
+                    removed
+                }
+            },
+            Node::Bucket(bucket) => bucket.remove(key),
+        }
+    }
+}
+
+/// A HAT-trie is a cache conscious map from string keys to values. The upper levels form a trie with an array of 256
+/// children per node, while keys below them are kept as suffixes in array hash buckets: small hash tables whose slots
+/// store their strings contiguously in a single buffer. A bucket bursts into a trie node with new buckets as children
+/// once it holds more than the burst threshold number of keys.
+/// Iteration visits the keys in lexicographic(byte) order, each bucket is sorted when it is reached.
+///
+/// # Examples
+/// ```
+/// use rudac::map::HatTrie;
+///
+/// let mut map = HatTrie::init();
+/// map.insert("banana", 3);
+/// map.insert("apple", 1);
+/// map.insert("cherry", 7);
+///
+/// assert_eq!(map.get("apple"), Some(&1));
+/// assert_eq!(map.insert("apple", 2), Some(1));
+/// assert_eq!(map.remove("cherry"), Some(7));
+///
+/// let entries: Vec<(String, i32)> = map.iter().map(|(key, value)| (key, *value)).collect();
+/// assert_eq!(
+///     entries,
+///     vec![(String::from("apple"), 2), (String::from("banana"), 3)]
+/// );
+/// ```
+pub struct HatTrie<V> {
+    root: Node<V>,
+    size: usize,
+    burst_threshold: usize,
+}
+
+impl<V> HatTrie<V> {
+    /// Initializes an empty HAT-trie with the default burst threshold
+    pub fn init() -> HatTrie<V> {
+        HatTrie::with_burst_threshold(DEFAULT_BURST_THRESHOLD)
+    }
+
+    /// Initializes an empty HAT-trie whose buckets burst once they hold more than `burst_threshold` keys.
+    /// Smaller thresholds give deeper tries with smaller buckets
+    ///
+    /// # Arguments
+    /// * `burst_threshold`: largest number of keys a bucket can hold
+    ///
+    /// # Panics
+    /// * panics if `burst_threshold` is zero
+    pub fn with_burst_threshold(burst_threshold: usize) -> HatTrie<V> {
+        if burst_threshold == 0 {
+            panic!("Burst threshold must be greater than zero");
+        }
+
+        HatTrie {
+            root: Node::Bucket(Bucket::init()),
+            size: 0,
+            burst_threshold,
+        }
+    }
+
+    /// Returns the burst threshold
+    pub fn burst_threshold(&self) -> usize {
+        self.burst_threshold
+    }
+
+    /// Returns number of keys in the map
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no keys in the map
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Inserts `key` with `value`. Returns the previous value of the key, if any
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    /// * `value`: value of the key
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        let previous = self
+            .root
+            .insert(key.as_bytes(), value, self.burst_threshold);
+        if previous.is_none() {
+            self.size += 1;
+        }
+
+        previous
+    }
+
+    /// Returns value of `key`, or `None` if the key is not in the map
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn get(&self, key: &str) -> Option<&V> {
+        let mut node = &self.root;
+        let mut key = key.as_bytes();
+        loop {
+            match node {
+                Node::Trie(trie) => match key.split_first() {
+                    None => return trie.value.as_ref(),
+                    Some((byte, rest)) => {
+                        node = trie.children[*byte as usize].as_ref()?;
+                        key = rest;
+                    }
+                },
+                Node::Bucket(bucket) => return bucket.get(key),
+            }
+        }
+    }
+
+    /// Returns a mutable reference to value of `key`, or `None` if the key is not in the map
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        let mut key = key.as_bytes();
+        loop {
+            match node {
+                Node::Trie(trie) => match key.split_first() {
+                    None => return trie.value.as_mut(),
+                    Some((byte, rest)) => {
+                        node = trie.children[*byte as usize].as_mut()?;
+                        key = rest;
+                    }
+                },
+                Node::Bucket(bucket) => return bucket.get_mut(key),
+            }
+        }
+    }
+
+    /// Returns true if `key` is in the map
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` and returns its value, or `None` if the key is not in the map
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let removed = self.root.remove(key.as_bytes());
+        if removed.is_some() {
+            self.size -= 1;
+        }
+
+        removed
+    }
+
+    /// Removes all keys
+    pub fn clear(&mut self) {
+        self.root = Node::Bucket(Bucket::init());
+        self.size = 0;
+    }
+
+    /// Returns an iterator over the entries in lexicographic order of their keys
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            stack: vec![Frame::init(&self.root, 0)],
+            prefix: Vec::new(),
+            remaining: self.size,
+        }
+    }
+
+    /// Returns an iterator over the keys in lexicographic order
+    pub fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values in lexicographic order of their keys
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<V> Default for HatTrie<V> {
+    fn default() -> Self {
+        HatTrie::init()
+    }
+}
+
+impl<S: AsRef<str>, V> std::iter::FromIterator<(S, V)> for HatTrie<V> {
+    fn from_iter<I: IntoIterator<Item = (S, V)>>(iter: I) -> Self {
+        let mut map = HatTrie::init();
+        for (key, value) in iter {
+            map.insert(key.as_ref(), value);
+        }
+
+        map
+    }
+}
+
+impl<'a, V> IntoIterator for &'a HatTrie<V> {
+    type Item = (String, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+enum Frame<'a, V> {
+    // `next` is 0 before the value of the node is visited, otherwise one more than the next child to visit
+    Trie {
+        node: &'a TrieNode<V>,
+        depth: usize,
+        next: usize,
+    },
+    Bucket {
+        entries: std::vec::IntoIter<(&'a [u8], &'a V)>,
+        depth: usize,
+    },
+}
+
+impl<'a, V> Frame<'a, V> {
+    fn init(node: &'a Node<V>, depth: usize) -> Frame<'a, V> {
+        match node {
+            Node::Trie(node) => Frame::Trie {
+                node,
+                depth,
+                next: 0,
+            },
+            Node::Bucket(bucket) => Frame::Bucket {
+                entries: bucket.sorted_entries().into_iter(),
+                depth,
+            },
+        }
+    }
+}
+
+/// Iterator over the entries of a `HatTrie` in lexicographic order of their keys
+pub struct Iter<'a, V> {
+    stack: Vec<Frame<'a, V>>,
+    prefix: Vec<u8>,
+    remaining: usize,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (String, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let child = match self.stack.last_mut()? {
+                Frame::Trie { node, depth, next } => {
+                    self.prefix.truncate(*depth);
+                    if *next == 0 {
+                        *next = 1;
+                        if let Some(value) = &node.value {
+                            self.remaining -= 1;
+                            return Some((to_key(self.prefix.clone()), value));
+                        }
+                    }
+
+                    let mut child = None;
+                    while child.is_none() && *next <= 256 {
+                        let byte = *next - 1;
+                        *next += 1;
+                        if let Some(node) = &node.children[byte] {
+                            self.prefix.push(byte as u8);
+                            child = Some(Frame::init(node, *depth + 1));
+                        }
+                    }
+
+                    child
+                }
+                Frame::Bucket { entries, depth } => {
+                    if let Some((suffix, value)) = entries.next() {
+                        let mut key = self.prefix[..*depth].to_vec();
+                        key.extend_from_slice(suffix);
+                        self.remaining -= 1;
+                        return Some((to_key(key), value));
+                    }
+
+                    None
+                }
+            };
+
+            match child {
+                Some(frame) => self.stack.push(frame),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, V> ExactSizeIterator for Iter<'a, V> {}
+
+impl<V> Node<V> {
+    // returns the allocations of this node and of all its descendants
+    fn footprint(&self) -> Footprint {
+        match self {
+            Node::Trie(node) => node.children.iter().flatten().fold(
+                Footprint::init(0, 0)
+                    .with_boxes::<TrieNode<V>>(1)
+                    .with_vec(&node.children),
+                |footprint, child| footprint.with(child.footprint()),
+            ),
+            Node::Bucket(bucket) => bucket.slots.iter().fold(
+                Footprint::init(0, 0).with_vec(&bucket.slots),
+                |footprint, slot| footprint.with_vec(&slot.keys).with_vec(&slot.values),
+            ),
+        }
+    }
+}
+
+impl<V> MemoryFootprint for HatTrie<V> {
+    /// Only values count as payload, the bytes of the keys are reported as overhead
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<V>(self.size).with(self.root.footprint())
+    }
+}
+
+impl<V> Replayable for HatTrie<V> {
+    type Key = String;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<String, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(&key, value);
+            }
+            Op::Remove(key) => {
+                self.remove(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+    use std::collections::BTreeMap;
+
+    fn random_key<R: Rng>(rng: &mut R) -> String {
+        let length = rng.next_below(6) as usize;
+        (0..length)
+            .map(|_| ['a', 'b', 'c', 'é', 'z'][rng.next_below(5) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn map_hat_trie_matches_btree_map() {
+        let mut rng = SplitMix64::init(7);
+        let mut map = HatTrie::with_burst_threshold(4);
+        let mut expected = BTreeMap::new();
+
+        for step in 0..3000 {
+            let key = random_key(&mut rng);
+            if rng.next_below(3) == 0 {
+                assert_eq!(map.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(map.insert(&key, step), expected.insert(key.clone(), step));
+            }
+            assert_eq!(map.get(&key), expected.get(&key));
+            assert_eq!(map.size(), expected.len());
+        }
+
+        let entries: Vec<(String, usize)> = map.iter().map(|(key, value)| (key, *value)).collect();
+        let expected: Vec<(String, usize)> = expected.into_iter().collect();
+        assert_eq!(entries, expected);
+        assert_eq!(map.iter().len(), expected.len());
+    }
+
+    #[test]
+    fn map_hat_trie_bursts_buckets() {
+        let mut map = HatTrie::with_burst_threshold(2);
+        for key in ["aaa", "aab", "aac", "aad", ""].iter() {
+            map.insert(key, key.len());
+        }
+
+        match &map.root {
+            Node::Trie(node) => assert!(node.children[b'a' as usize].is_some()),
+            Node::Bucket(_) => panic!("Root bucket did not burst"),
+        }
+        assert_eq!(
+            map.keys().collect::<Vec<String>>(),
+            vec!["", "aaa", "aab", "aac", "aad"]
+        );
+
+        for key in ["aaa", "aab", "aac", "aad"].iter() {
+            map.remove(key);
+        }
+        match &map.root {
+            Node::Trie(node) => assert!(node.children.iter().all(|child| child.is_none())),
+            Node::Bucket(_) => panic!("Root trie node was replaced"),
+        }
+        assert_eq!(map.keys().collect::<Vec<String>>(), vec![""]);
+    }
+
+    #[test]
+    fn map_hat_trie_get_mut_and_clear() {
+        let mut map: HatTrie<i32> = vec![("one", 1), ("two", 2)].into_iter().collect();
+        *map.get_mut("two").unwrap() += 40;
+        assert_eq!(map.get("two"), Some(&42));
+        assert!(map.get_mut("three").is_none());
+        assert!(map.contains_key("one"));
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.iter().next(), None);
+    }
+
+    #[test]
+    fn map_hat_trie_long_keys() {
+        let mut map = HatTrie::init();
+        let long = "x".repeat(300);
+        map.insert(&long, 1);
+        map.insert("x", 2);
+        assert_eq!(map.get(&long), Some(&1));
+        assert_eq!(map.remove(&long), Some(1));
+        assert_eq!(map.get("x"), Some(&2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Burst threshold must be greater than zero")]
+    fn map_hat_trie_zero_threshold() {
+        let _map: HatTrie<i32> = HatTrie::with_burst_threshold(0);
+    }
+}
@@ -0,0 +1,6 @@
+mod hat_trie;
+mod skip_list;
+
+pub use hat_trie::HatTrie;
+pub use hat_trie::Iter;
+pub use skip_list::SkipList;
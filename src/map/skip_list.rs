@@ -0,0 +1,385 @@
+use crate::replay::{Op, Replayable};
+use crate::util::{Footprint, MemoryFootprint, Rng, SeedableRng, SplitMix64};
+use std::cmp::Ordering;
+
+// seed of the generator created by `init`
+const DEFAULT_SEED: u64 = 0x736b_6970;
+
+// a tower never has more levels than bits in a drawn number
+const MAX_LEVEL: usize = 64;
+
+// index of the head tower, which holds no entry
+const HEAD: usize = 0;
+
+// marks the end of a level
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct Tower<K, V> {
+    entry: Option<(K, V)>,
+    next: Vec<usize>,
+}
+
+/// A skip list is an ordered map made of a sorted linked list of towers. Every tower links to the next tower
+/// of the same height or taller on each of its levels, so a search skips most entries and takes O(log n)
+/// expected time. Tower heights are drawn from the generator `R`: a tower has `l` levels with probability 2^-l,
+/// so skip lists built from the same seed and the same operations have the same towers on every run.
+/// Towers are kept in a vector and linked by index, slots of removed towers are reused
+///
+/// # Examples
+/// ```
+/// use rudac::map::SkipList;
+///
+/// let mut map: SkipList<&str, i32> = SkipList::with_seed(3);
+/// map.insert("banana", 3);
+/// map.insert("apple", 1);
+/// map.insert("cherry", 7);
+///
+/// assert_eq!(map.get(&"apple"), Some(&1));
+/// assert_eq!(map.insert("apple", 2), Some(1));
+/// assert_eq!(map.remove(&"cherry"), Some(7));
+///
+/// let entries: Vec<(&&str, &i32)> = map.iter().collect();
+/// assert_eq!(entries, vec![(&"apple", &2), (&"banana", &3)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SkipList<K: Ord, V, R: Rng = SplitMix64> {
+    towers: Vec<Tower<K, V>>,
+    free: Vec<usize>,
+    size: usize,
+    rng: R,
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    /// Initializes an empty skip list whose generator starts from a fixed seed
+    pub fn init() -> SkipList<K, V> {
+        SkipList::with_rng(SplitMix64::init(DEFAULT_SEED))
+    }
+}
+
+impl<K: Ord, V, R: SeedableRng> SkipList<K, V, R> {
+    /// Initializes an empty skip list with a generator created from `seed`
+    ///
+    /// # Arguments
+    /// * `seed`: seed of the generator
+    pub fn with_seed(seed: u64) -> SkipList<K, V, R> {
+        SkipList::with_rng(R::from_seed(seed))
+    }
+}
+
+impl<K: Ord, V, R: Rng> SkipList<K, V, R> {
+    /// Initializes an empty skip list that draws tower heights from `rng`
+    ///
+    /// # Arguments
+    /// * `rng`: the generator
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::map::SkipList;
+    /// use rudac::util::ScriptedRng;
+    ///
+    /// // the number of trailing ones of a drawn number is the number of extra levels
+    /// let mut map = SkipList::with_rng(ScriptedRng::init(vec![0b0111, 0]));
+    /// map.insert(1, ());
+    /// map.insert(2, ());
+    ///
+    /// assert_eq!(map.levels(), 4);
+    /// ```
+    pub fn with_rng(rng: R) -> SkipList<K, V, R> {
+        SkipList {
+            towers: vec![Tower {
+                entry: None,
+                next: Vec::new(),
+            }],
+            free: Vec::new(),
+            size: 0,
+            rng,
+        }
+    }
+
+    /// Returns number of entries in the skip list
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no entries in the skip list
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns number of levels of the tallest tower, zero if the skip list is empty
+    pub fn levels(&self) -> usize {
+        self.towers[HEAD].next.len()
+    }
+
+    /// Returns the generator of the skip list
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Inserts `key` with `value` and returns the previous value of the key, if any.
+    /// A tower height is drawn only when the key is new
+    ///
+    /// # Arguments
+    /// * `key`: key to be inserted
+    /// * `value`: value associated with the key
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let path = self.path(&key);
+        if let Some(index) = self.found(&path, &key) {
+            let (_, existing) = self.towers[index].entry.as_mut().unwrap();
+            return Some(std::mem::replace(existing, value));
+        }
+
+        let height = (self.rng.next_u64().trailing_ones() as usize + 1).min(MAX_LEVEL);
+        while self.levels() < height {
+            self.towers[HEAD].next.push(NIL);
+        }
+
+        // levels added to the head above are not on the search path, the head precedes the new tower there
+        let previous = |level: usize| path.get(level).copied().unwrap_or(HEAD);
+        let next = (0..height)
+            .map(|level| self.towers[previous(level)].next[level])
+            .collect();
+
+        let tower = Tower {
+            entry: Some((key, value)),
+            next,
+        };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.towers[index] = tower;
+                index
+            }
+            None => {
+                self.towers.push(tower);
+                self.towers.len() - 1
+            }
+        };
+
+        for level in 0..height {
+            self.towers[previous(level)].next[level] = index;
+        }
+        self.size += 1;
+
+        None
+    }
+
+    /// Returns a reference to the value associated with `key`, None if the key does not exist
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let path = self.path(key);
+        let index = self.found(&path, key)?;
+
+        self.towers[index].entry.as_ref().map(|(_, value)| value)
+    }
+
+    /// Returns a mutable reference to the value associated with `key`, None if the key does not exist
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let path = self.path(key);
+        let index = self.found(&path, key)?;
+
+        self.towers[index].entry.as_mut().map(|(_, value)| value)
+    }
+
+    /// Returns true if the skip list contains `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` and returns its value, None if the key does not exist
+    ///
+    /// # Arguments
+    /// * `key`: key to be removed
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let path = self.path(key);
+        let index = self.found(&path, key)?;
+
+        let next = std::mem::take(&mut self.towers[index].next);
+        for (level, successor) in next.into_iter().enumerate() {
+            self.towers[path[level]].next[level] = successor;
+        }
+        while self.towers[HEAD].next.last() == Some(&NIL) {
+            self.towers[HEAD].next.pop();
+        }
+
+        self.free.push(index);
+        self.size -= 1;
+
+        self.towers[index].entry.take().map(|(_, value)| value)
+    }
+
+    /// Removes every entry of the skip list
+    pub fn clear(&mut self) {
+        self.towers.truncate(1);
+        self.towers[HEAD].next.clear();
+        self.free.clear();
+        self.size = 0;
+    }
+
+    /// Returns an iterator over the entries in ascending order of their keys
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut current = self.towers[HEAD].next.first().copied().unwrap_or(NIL);
+        std::iter::from_fn(move || {
+            if current == NIL {
+                return None;
+            }
+
+            let tower = &self.towers[current];
+            current = tower.next[0];
+            tower.entry.as_ref().map(|(key, value)| (key, value))
+        })
+    }
+
+    /// Returns an iterator over the keys in ascending order
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values in ascending order of their keys
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    fn key(&self, index: usize) -> &K {
+        &self.towers[index].entry.as_ref().unwrap().0
+    }
+
+    // returns for every level the last tower whose key is smaller than `key`
+    fn path(&self, key: &K) -> Vec<usize> {
+        let mut path = vec![HEAD; self.levels()];
+        let mut current = HEAD;
+        for level in (0..self.levels()).rev() {
+            loop {
+                let next = self.towers[current].next[level];
+                if next == NIL || self.key(next).cmp(key) != Ordering::Less {
+                    break;
+                }
+                current = next;
+            }
+            path[level] = current;
+        }
+
+        path
+    }
+
+    // returns the tower holding `key` given the search path of the key
+    fn found(&self, path: &[usize], key: &K) -> Option<usize> {
+        let next = self.towers[*path.first()?].next[0];
+        if next != NIL && self.key(next) == key {
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
+impl<K: Ord, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        SkipList::init()
+    }
+}
+
+impl<K: Ord, V, R: Rng> MemoryFootprint for SkipList<K, V, R> {
+    fn footprint(&self) -> Footprint {
+        self.towers.iter().fold(
+            Footprint::of::<(K, V)>(self.size)
+                .with_vec(&self.towers)
+                .with_vec(&self.free),
+            |footprint, tower| footprint.with_vec(&tower.next),
+        )
+    }
+}
+
+impl<K: Ord, V, R: Rng> Replayable for SkipList<K, V, R> {
+    type Key = K;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<K, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(key, value);
+            }
+            Op::Remove(key) => {
+                self.remove(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::ScriptedRng;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn map_skip_list_matches_btree_map() {
+        let mut rng = SplitMix64::init(21);
+        let mut skip_list = SkipList::init();
+        let mut map = BTreeMap::new();
+        for _ in 0..3000 {
+            let key = rng.next_below(400);
+            if rng.next_below(3) == 0 {
+                assert_eq!(skip_list.remove(&key), map.remove(&key));
+            } else {
+                assert_eq!(skip_list.insert(key, key * 3), map.insert(key, key * 3));
+            }
+        }
+
+        assert_eq!(skip_list.size(), map.len());
+        assert!(skip_list.iter().eq(map.iter()));
+        for key in 0..400 {
+            assert_eq!(skip_list.get(&key), map.get(&key));
+        }
+        assert!(skip_list.towers.len() <= 400 + 1);
+        assert!(skip_list.levels() < 30);
+    }
+
+    #[test]
+    fn map_skip_list_same_seed_same_towers() {
+        let mut a = SkipList::<u64, ()>::with_seed(4);
+        let mut b = SkipList::<u64, ()>::with_seed(4);
+        for i in 0..200 {
+            a.insert(i * 13 % 200, ());
+            b.insert(i * 13 % 200, ());
+        }
+        a.remove(&100);
+        b.remove(&100);
+
+        assert_eq!(format!("{:?}", a.towers), format!("{:?}", b.towers));
+    }
+
+    #[test]
+    fn map_skip_list_scripted_rng_forces_levels() {
+        // a single level turns the skip list into a sorted linked list
+        let mut skip_list = SkipList::with_rng(ScriptedRng::init(vec![0]));
+        for i in (0..10).rev() {
+            skip_list.insert(i, i);
+        }
+        assert_eq!(skip_list.levels(), 1);
+        assert_eq!(
+            skip_list.keys().copied().collect::<Vec<i32>>(),
+            (0..10).collect::<Vec<i32>>()
+        );
+
+        // all ones gives the tallest tower, replacing a value draws nothing
+        let mut skip_list = SkipList::with_rng(ScriptedRng::init(vec![u64::MAX]));
+        skip_list.insert(1, 1);
+        skip_list.insert(1, 2);
+        assert_eq!(skip_list.levels(), MAX_LEVEL);
+        assert_eq!(skip_list.rng().draws(), 1);
+
+        assert_eq!(skip_list.remove(&1), Some(2));
+        assert_eq!(skip_list.levels(), 0);
+        assert!(skip_list.is_empty());
+    }
+}
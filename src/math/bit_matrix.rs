@@ -0,0 +1,603 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+const WORD_BITS: usize = 64;
+
+/// A dense matrix of bits. Each row is packed into 64 bit words so row operations are performed 64 columns at a time.
+/// Arithmetic is over GF(2): addition is xor and multiplication is and.
+///
+/// # Examples
+/// ```
+/// use rudac::math::BitMatrix;
+///
+/// // adjacency matrix of the graph 0 -> 1 -> 2
+/// let mut adjacency = BitMatrix::init(3, 3);
+/// adjacency.set(0, 1, true);
+/// adjacency.set(1, 2, true);
+///
+/// let reachability = adjacency.transitive_closure();
+///
+/// assert_eq!(reachability.get(0, 2), true);
+/// assert_eq!(reachability.get(2, 0), false);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+
+    // number of words used to store each row
+    words_per_row: usize,
+
+    // row major storage
+    data: Vec<u64>,
+}
+
+impl BitMatrix {
+    /// Initializes a matrix of zeros
+    ///
+    /// # Arguments
+    /// * `rows`: number of rows
+    /// * `cols`: number of columns
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let matrix = BitMatrix::init(2, 3);
+    ///
+    /// assert_eq!(matrix.rows(), 2);
+    /// assert_eq!(matrix.cols(), 3);
+    /// assert_eq!(matrix.count_ones(), 0);
+    /// ```
+    pub fn init(rows: usize, cols: usize) -> BitMatrix {
+        let words_per_row = cols.div_ceil(WORD_BITS);
+
+        BitMatrix {
+            rows,
+            cols,
+            words_per_row,
+            data: vec![0; rows * words_per_row],
+        }
+    }
+
+    /// Initializes an `n` by `n` identity matrix
+    ///
+    /// # Arguments
+    /// * `n`: number of rows and columns
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let identity = BitMatrix::identity(3);
+    ///
+    /// assert_eq!(identity.get(1, 1), true);
+    /// assert_eq!(identity.get(0, 1), false);
+    /// ```
+    pub fn identity(n: usize) -> BitMatrix {
+        let mut matrix = BitMatrix::init(n, n);
+        for i in 0..n {
+            matrix.set(i, i, true);
+        }
+
+        matrix
+    }
+
+    /// Returns number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns value of the bit at `row` and `col`
+    ///
+    /// # Panics
+    /// * panics if `row` or `col` is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::init(2, 2);
+    /// matrix.set(1, 0, true);
+    ///
+    /// assert_eq!(matrix.get(1, 0), true);
+    /// ```
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        let (word, mask) = self.position(row, col);
+
+        self.data[word] & mask != 0
+    }
+
+    /// Sets value of the bit at `row` and `col`
+    ///
+    /// # Panics
+    /// * panics if `row` or `col` is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::init(2, 2);
+    /// matrix.set(0, 1, true);
+    /// matrix.set(0, 1, false);
+    ///
+    /// assert_eq!(matrix.get(0, 1), false);
+    /// ```
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        let (word, mask) = self.position(row, col);
+
+        if value {
+            self.data[word] |= mask;
+        } else {
+            self.data[word] &= !mask;
+        }
+    }
+
+    /// Flips the bit at `row` and `col`
+    ///
+    /// # Panics
+    /// * panics if `row` or `col` is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::init(2, 2);
+    /// matrix.flip(0, 0);
+    ///
+    /// assert_eq!(matrix.get(0, 0), true);
+    /// ```
+    pub fn flip(&mut self, row: usize, col: usize) {
+        let (word, mask) = self.position(row, col);
+
+        self.data[word] ^= mask;
+    }
+
+    /// Returns total number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.data
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns columns of the set bits in the specified `row` in increasing order
+    ///
+    /// # Panics
+    /// * panics if `row` is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::init(1, 100);
+    /// matrix.set(0, 3, true);
+    /// matrix.set(0, 70, true);
+    ///
+    /// assert_eq!(matrix.row_ones(0), vec![3, 70]);
+    /// ```
+    pub fn row_ones(&self, row: usize) -> Vec<usize> {
+        let mut ones = Vec::new();
+
+        for (i, &word) in self.row(row).iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                ones.push(i * WORD_BITS + word.trailing_zeros() as usize);
+                word &= word - 1;
+            }
+        }
+
+        ones
+    }
+
+    /// Swaps two rows
+    ///
+    /// # Panics
+    /// * panics if either row is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::identity(2);
+    /// matrix.swap_rows(0, 1);
+    ///
+    /// assert_eq!(matrix.get(0, 1), true);
+    /// assert_eq!(matrix.get(1, 0), true);
+    /// ```
+    pub fn swap_rows(&mut self, row1: usize, row2: usize) {
+        self.check_row(row1);
+        self.check_row(row2);
+
+        for i in 0..self.words_per_row {
+            self.data
+                .swap(row1 * self.words_per_row + i, row2 * self.words_per_row + i);
+        }
+    }
+
+    /// Adds `source` row to `target` row over GF(2): target ^= source
+    ///
+    /// # Panics
+    /// * panics if either row is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::identity(2);
+    /// matrix.xor_row(0, 1);
+    ///
+    /// assert_eq!(matrix.row_ones(0), vec![0, 1]);
+    /// ```
+    pub fn xor_row(&mut self, target: usize, source: usize) {
+        self.combine_rows(target, source, |t, s| t ^ s);
+    }
+
+    /// Merges `source` row into `target` row: target |= source
+    ///
+    /// # Panics
+    /// * panics if either row is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::identity(2);
+    /// matrix.or_row(0, 1);
+    /// matrix.or_row(0, 1);
+    ///
+    /// assert_eq!(matrix.row_ones(0), vec![0, 1]);
+    /// ```
+    pub fn or_row(&mut self, target: usize, source: usize) {
+        self.combine_rows(target, source, |t, s| t | s);
+    }
+
+    /// Intersects `target` row with `source` row: target &= source
+    ///
+    /// # Panics
+    /// * panics if either row is out of range
+    pub fn and_row(&mut self, target: usize, source: usize) {
+        self.combine_rows(target, source, |t, s| t & s);
+    }
+
+    fn combine_rows(&mut self, target: usize, source: usize, op: fn(u64, u64) -> u64) {
+        self.check_row(target);
+        self.check_row(source);
+
+        let w = self.words_per_row;
+        for i in 0..w {
+            self.data[target * w + i] = op(self.data[target * w + i], self.data[source * w + i]);
+        }
+    }
+
+    /// Returns the transpose of the matrix
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::init(1, 2);
+    /// matrix.set(0, 1, true);
+    ///
+    /// let transposed = matrix.transpose();
+    ///
+    /// assert_eq!(transposed.rows(), 2);
+    /// assert_eq!(transposed.get(1, 0), true);
+    /// ```
+    pub fn transpose(&self) -> BitMatrix {
+        let mut transposed = BitMatrix::init(self.cols, self.rows);
+
+        for row in 0..self.rows {
+            for col in self.row_ones(row) {
+                transposed.set(col, row, true);
+            }
+        }
+
+        transposed
+    }
+
+    /// Multiplies two matrices over GF(2)
+    ///
+    /// # Arguments
+    /// * `other`: right hand side of the multiplication
+    ///
+    /// # Panics
+    /// * panics if number of columns of `self` is not equal to number of rows of `other`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut a = BitMatrix::init(1, 2);
+    /// a.set(0, 0, true);
+    /// a.set(0, 1, true);
+    ///
+    /// let mut b = BitMatrix::init(2, 1);
+    /// b.set(0, 0, true);
+    /// b.set(1, 0, true);
+    ///
+    /// // 1 * 1 + 1 * 1 = 0 in GF(2)
+    /// assert_eq!(a.multiply(&b).get(0, 0), false);
+    /// ```
+    pub fn multiply(&self, other: &BitMatrix) -> BitMatrix {
+        self.product(other, |t, s| t ^ s)
+    }
+
+    /// Multiplies two matrices over the boolean semiring, where addition is or instead of xor
+    ///
+    /// # Arguments
+    /// * `other`: right hand side of the multiplication
+    ///
+    /// # Panics
+    /// * panics if number of columns of `self` is not equal to number of rows of `other`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut a = BitMatrix::init(1, 2);
+    /// a.set(0, 0, true);
+    /// a.set(0, 1, true);
+    ///
+    /// let mut b = BitMatrix::init(2, 1);
+    /// b.set(0, 0, true);
+    /// b.set(1, 0, true);
+    ///
+    /// assert_eq!(a.boolean_multiply(&b).get(0, 0), true);
+    /// ```
+    pub fn boolean_multiply(&self, other: &BitMatrix) -> BitMatrix {
+        self.product(other, |t, s| t | s)
+    }
+
+    // row i of the product is the combination of rows k of other for every set bit k in row i of self
+    fn product(&self, other: &BitMatrix, add: fn(u64, u64) -> u64) -> BitMatrix {
+        if self.cols != other.rows {
+            panic!("Number of columns of the left matrix must be equal to number of rows of the right matrix");
+        }
+
+        let mut result = BitMatrix::init(self.rows, other.cols);
+        let w = other.words_per_row;
+
+        for i in 0..self.rows {
+            for k in self.row_ones(i) {
+                for j in 0..w {
+                    result.data[i * w + j] = add(result.data[i * w + j], other.data[k * w + j]);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns rank of the matrix over GF(2)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut matrix = BitMatrix::identity(3);
+    /// matrix.xor_row(2, 1);
+    /// matrix.xor_row(2, 0);
+    /// assert_eq!(matrix.rank(), 3);
+    ///
+    /// matrix.set(2, 2, false);
+    /// assert_eq!(matrix.rank(), 2);
+    /// ```
+    pub fn rank(&self) -> usize {
+        let mut matrix = self.clone();
+        let mut rank = 0;
+
+        for col in 0..self.cols {
+            if rank == self.rows {
+                break;
+            }
+
+            let pivot = match (rank..self.rows).find(|&row| matrix.get(row, col)) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+
+            matrix.swap_rows(rank, pivot);
+            for row in 0..self.rows {
+                if row != rank && matrix.get(row, col) {
+                    matrix.xor_row(row, rank);
+                }
+            }
+
+            rank += 1;
+        }
+
+        rank
+    }
+
+    /// Returns the transitive closure of a square matrix interpreted as a relation(ex. adjacency matrix of a directed graph).
+    /// bit (i, j) of the result is set if j is reachable from i using one or more steps.
+    /// It runs Warshall's algorithm on packed rows in O(n^3 / 64)
+    ///
+    /// # Panics
+    /// * panics if matrix is not square
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::BitMatrix;
+    ///
+    /// let mut relation = BitMatrix::init(3, 3);
+    /// relation.set(0, 1, true);
+    /// relation.set(1, 0, true);
+    ///
+    /// let closure = relation.transitive_closure();
+    ///
+    /// assert_eq!(closure.get(0, 0), true);
+    /// assert_eq!(closure.get(2, 2), false);
+    /// ```
+    pub fn transitive_closure(&self) -> BitMatrix {
+        if self.rows != self.cols {
+            panic!("Transitive closure is only defined for square matrices");
+        }
+
+        let mut closure = self.clone();
+
+        for k in 0..self.rows {
+            for i in 0..self.rows {
+                if closure.get(i, k) {
+                    closure.or_row(i, k);
+                }
+            }
+        }
+
+        closure
+    }
+
+    /// Returns the reflexive transitive closure, where every element is also related to itself
+    ///
+    /// # Panics
+    /// * panics if matrix is not square
+    pub fn reflexive_transitive_closure(&self) -> BitMatrix {
+        let mut closure = self.transitive_closure();
+        for i in 0..self.rows {
+            closure.set(i, i, true);
+        }
+
+        closure
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        self.check_row(row);
+
+        &self.data[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    fn check_row(&self, row: usize) {
+        if row >= self.rows {
+            panic!("Row index out of range");
+        }
+    }
+
+    fn position(&self, row: usize, col: usize) -> (usize, u64) {
+        self.check_row(row);
+        if col >= self.cols {
+            panic!("Column index out of range");
+        }
+
+        (
+            row * self.words_per_row + col / WORD_BITS,
+            1 << (col % WORD_BITS),
+        )
+    }
+}
+
+impl MemoryFootprint for BitMatrix {
+    fn footprint(&self) -> Footprint {
+        // every element is a single bit
+        let elements = self.rows * self.cols;
+        Footprint::init(elements, elements.div_ceil(8)).with_vec(&self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rows(rows: &[&[u8]]) -> BitMatrix {
+        let mut matrix = BitMatrix::init(rows.len(), rows[0].len());
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &bit) in row.iter().enumerate() {
+                matrix.set(i, j, bit == 1);
+            }
+        }
+
+        matrix
+    }
+
+    #[test]
+    fn math_bit_matrix_set_get_wide() {
+        let mut matrix = BitMatrix::init(3, 130);
+
+        matrix.set(2, 129, true);
+        matrix.set(2, 64, true);
+        matrix.set(0, 0, true);
+        matrix.flip(0, 0);
+
+        assert!(matrix.get(2, 129));
+        assert!(!matrix.get(0, 0));
+        assert_eq!(matrix.row_ones(2), vec![64, 129]);
+        assert_eq!(matrix.count_ones(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Column index out of range")]
+    fn math_bit_matrix_out_of_range() {
+        let matrix = BitMatrix::init(2, 2);
+
+        matrix.get(0, 2);
+    }
+
+    #[test]
+    fn math_bit_matrix_multiply() {
+        let a = from_rows(&[&[1, 1, 0], &[0, 1, 1]]);
+        let b = from_rows(&[&[1, 0], &[1, 1], &[0, 1]]);
+
+        assert_eq!(a.multiply(&b), from_rows(&[&[0, 1], &[1, 0]]));
+        assert_eq!(a.boolean_multiply(&b), from_rows(&[&[1, 1], &[1, 1]]));
+    }
+
+    #[test]
+    fn math_bit_matrix_multiply_identity() {
+        let a = from_rows(&[&[1, 0, 1], &[0, 1, 1], &[1, 1, 1]]);
+
+        assert_eq!(a.multiply(&BitMatrix::identity(3)), a);
+        assert_eq!(BitMatrix::identity(3).multiply(&a), a);
+    }
+
+    #[test]
+    fn math_bit_matrix_transpose() {
+        let a = from_rows(&[&[1, 1, 0], &[0, 0, 1]]);
+
+        assert_eq!(a.transpose(), from_rows(&[&[1, 0], &[1, 0], &[0, 1]]));
+        assert_eq!(a.transpose().transpose(), a);
+    }
+
+    #[test]
+    fn math_bit_matrix_rank() {
+        let a = from_rows(&[&[1, 1, 0], &[0, 1, 1], &[1, 0, 1]]);
+        assert_eq!(a.rank(), 2);
+
+        assert_eq!(BitMatrix::identity(70).rank(), 70);
+        assert_eq!(BitMatrix::init(3, 3).rank(), 0);
+    }
+
+    #[test]
+    fn math_bit_matrix_transitive_closure() {
+        // 0 -> 1 -> 2 -> 1, 3 isolated
+        let a = from_rows(&[&[0, 1, 0, 0], &[0, 0, 1, 0], &[0, 1, 0, 0], &[0, 0, 0, 0]]);
+
+        let closure = a.transitive_closure();
+
+        assert_eq!(
+            closure,
+            from_rows(&[&[0, 1, 1, 0], &[0, 1, 1, 0], &[0, 1, 1, 0], &[0, 0, 0, 0]])
+        );
+
+        let reflexive = a.reflexive_transitive_closure();
+        assert!(reflexive.get(0, 0));
+        assert!(reflexive.get(3, 3));
+    }
+
+    #[test]
+    fn math_bit_matrix_row_operations() {
+        let mut a = from_rows(&[&[1, 1, 0], &[0, 1, 1]]);
+
+        a.xor_row(0, 1);
+        assert_eq!(a, from_rows(&[&[1, 0, 1], &[0, 1, 1]]));
+
+        a.and_row(0, 1);
+        assert_eq!(a, from_rows(&[&[0, 0, 1], &[0, 1, 1]]));
+
+        a.swap_rows(0, 1);
+        assert_eq!(a, from_rows(&[&[0, 1, 1], &[0, 0, 1]]));
+    }
+}
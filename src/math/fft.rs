@@ -0,0 +1,207 @@
+use std::ops::{Add, Mul, Sub};
+
+/// A complex number with `f64` components
+///
+/// # Examples
+/// ```
+/// use rudac::math::Complex;
+///
+/// let i = Complex::new(0.0, 1.0);
+///
+/// assert_eq!(i * i, Complex::new(-1.0, 0.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    /// real part
+    pub re: f64,
+
+    /// imaginary part
+    pub im: f64,
+}
+
+impl Complex {
+    /// Creates a new complex number
+    ///
+    /// # Arguments
+    /// * `re`: real part
+    /// * `im`: imaginary part
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    /// Returns the complex number with absolute value 1 and argument `theta`
+    ///
+    /// # Arguments
+    /// * `theta`: argument in radians
+    pub fn from_polar(theta: f64) -> Complex {
+        Complex::new(theta.cos(), theta.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+/// Computes the discrete fourier transform of `values` in place using the iterative radix-2 Cooley-Tukey algorithm.
+/// * Complexity: O(n log n)
+///
+/// # Arguments
+/// * `values`: input sequence. its length must be a power of two
+///
+/// # Panics
+/// * panics if length of `values` is not a power of two
+///
+/// # Examples
+/// ```
+/// use rudac::math::{fft, ifft, Complex};
+///
+/// let mut values = vec![Complex::new(1.0, 0.0), Complex::new(2.0, 0.0)];
+///
+/// fft(&mut values);
+/// assert_eq!(values, vec![Complex::new(3.0, 0.0), Complex::new(-1.0, 0.0)]);
+///
+/// ifft(&mut values);
+/// assert_eq!(values, vec![Complex::new(1.0, 0.0), Complex::new(2.0, 0.0)]);
+/// ```
+pub fn fft(values: &mut [Complex]) {
+    transform(values, false);
+}
+
+/// Computes the inverse discrete fourier transform of `values` in place.
+/// * Complexity: O(n log n)
+///
+/// # Arguments
+/// * `values`: input sequence. its length must be a power of two
+///
+/// # Panics
+/// * panics if length of `values` is not a power of two
+pub fn ifft(values: &mut [Complex]) {
+    transform(values, true);
+
+    let n = values.len() as f64;
+    for value in values.iter_mut() {
+        value.re /= n;
+        value.im /= n;
+    }
+}
+
+fn transform(values: &mut [Complex], invert: bool) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+    if !n.is_power_of_two() {
+        panic!("Length of the input must be a power of two");
+    }
+
+    // bit reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    // combine transforms of length len / 2 into transforms of length len
+    let mut len = 2;
+    while len <= n {
+        let sign = if invert { -1.0 } else { 1.0 };
+        let root = Complex::from_polar(sign * -2.0 * std::f64::consts::PI / len as f64);
+
+        for start in (0..n).step_by(len) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..len / 2 {
+                let u = values[start + k];
+                let v = values[start + k + len / 2] * w;
+
+                values[start + k] = u + v;
+                values[start + k + len / 2] = u - v;
+
+                w = w * root;
+            }
+        }
+
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Complex, b: Complex) -> bool {
+        (a.re - b.re).abs() < 1e-9 && (a.im - b.im).abs() < 1e-9
+    }
+
+    #[test]
+    fn math_fft_matches_naive_dft() {
+        let input: Vec<Complex> = (0..8)
+            .map(|i| Complex::new(i as f64, (i * i) as f64 % 3.0))
+            .collect();
+
+        let mut transformed = input.clone();
+        fft(&mut transformed);
+
+        for (k, value) in transformed.iter().enumerate() {
+            let mut expected = Complex::default();
+            for (t, x) in input.iter().enumerate() {
+                let theta = -2.0 * std::f64::consts::PI * (k * t) as f64 / 8.0;
+                expected = expected + *x * Complex::from_polar(theta);
+            }
+            assert!(close(*value, expected));
+        }
+    }
+
+    #[test]
+    fn math_fft_round_trip() {
+        let input: Vec<Complex> = (0..16)
+            .map(|i| Complex::new(i as f64, -(i as f64)))
+            .collect();
+
+        let mut values = input.clone();
+        fft(&mut values);
+        ifft(&mut values);
+
+        for (a, b) in values.iter().zip(input.iter()) {
+            assert!(close(*a, *b));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Length of the input must be a power of two")]
+    fn math_fft_panic_length() {
+        let mut values = vec![Complex::default(); 3];
+
+        fft(&mut values);
+    }
+}
@@ -0,0 +1,4 @@
+mod simplex;
+
+pub use simplex::LpResult;
+pub use simplex::Simplex;
@@ -0,0 +1,417 @@
+// tolerance of comparisons with zero
+const EPSILON: f64 = 1e-9;
+
+/// Result of a linear program
+#[derive(Debug, Clone, PartialEq)]
+pub enum LpResult {
+    /// the program has an optimal solution
+    Optimal {
+        /// optimal value of the objective
+        value: f64,
+
+        /// values of the variables at the optimum
+        solution: Vec<f64>,
+    },
+
+    /// the objective can grow without limit
+    Unbounded,
+
+    /// no point satisfies all constraints
+    Infeasible,
+}
+
+impl LpResult {
+    /// Returns optimal value of the objective, or `None` if the program has no optimal solution
+    pub fn value(&self) -> Option<f64> {
+        match self {
+            LpResult::Optimal { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns an optimal solution, or `None` if the program has no optimal solution
+    pub fn solution(&self) -> Option<&[f64]> {
+        match self {
+            LpResult::Optimal { solution, .. } => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+/// Simplex solver of linear programs in the form: maximize `cᵀx` subject to `Ax ≤ b` and `x ≥ 0`.
+/// Constraints with negative right hand side are supported through a first phase that finds a feasible point,
+/// and Bland's rule prevents cycling on degenerate programs.
+///
+/// The solver uses a dense tableau, so it suits small and medium programs with up to a few hundred variables and constraints.
+///
+/// # Examples
+/// ```
+/// use rudac::math::lp::{LpResult, Simplex};
+///
+/// // maximize 3x + 2y
+/// let mut lp = Simplex::init(vec![3.0, 2.0]);
+///
+/// // x + y <= 4
+/// lp.add_constraint(vec![1.0, 1.0], 4.0);
+/// // x + 3y <= 6
+/// lp.add_constraint(vec![1.0, 3.0], 6.0);
+/// // x <= 3
+/// lp.add_constraint(vec![1.0, 0.0], 3.0);
+///
+/// let result = lp.solve();
+///
+/// assert!((result.value().unwrap() - 11.0).abs() < 1e-9);
+/// let solution = result.solution().unwrap();
+/// assert!((solution[0] - 3.0).abs() < 1e-9 && (solution[1] - 1.0).abs() < 1e-9);
+///
+/// // x + y >= 5 contradicts x + y <= 4
+/// lp.add_constraint_ge(vec![1.0, 1.0], 5.0);
+/// assert_eq!(lp.solve(), LpResult::Infeasible);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simplex {
+    objective: Vec<f64>,
+    constraints: Vec<Vec<f64>>,
+    bounds: Vec<f64>,
+}
+
+impl Simplex {
+    /// Initializes a program without constraints
+    ///
+    /// # Arguments
+    /// * `objective`: coefficients `c` of the objective to be maximized
+    pub fn init(objective: Vec<f64>) -> Simplex {
+        Simplex {
+            objective,
+            constraints: Vec::new(),
+            bounds: Vec::new(),
+        }
+    }
+
+    /// Returns number of variables
+    pub fn variables(&self) -> usize {
+        self.objective.len()
+    }
+
+    /// Returns number of constraints, in the `Ax ≤ b` form
+    pub fn constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Adds constraint `aᵀx ≤ b`
+    ///
+    /// # Arguments
+    /// * `coefficients`: coefficients `a` of the constraint
+    /// * `bound`: right hand side `b`
+    ///
+    /// # Panics
+    /// * panics if number of coefficients is not equal to number of variables
+    pub fn add_constraint(&mut self, coefficients: Vec<f64>, bound: f64) {
+        if coefficients.len() != self.objective.len() {
+            panic!("Number of coefficients must be equal to number of variables");
+        }
+
+        self.constraints.push(coefficients);
+        self.bounds.push(bound);
+    }
+
+    /// Adds constraint `aᵀx ≥ b`, which is `-aᵀx ≤ -b`
+    ///
+    /// # Arguments
+    /// * `coefficients`: coefficients `a` of the constraint
+    /// * `bound`: right hand side `b`
+    ///
+    /// # Panics
+    /// * panics if number of coefficients is not equal to number of variables
+    pub fn add_constraint_ge(&mut self, coefficients: Vec<f64>, bound: f64) {
+        self.add_constraint(coefficients.iter().map(|a| -a).collect(), -bound);
+    }
+
+    /// Adds constraint `aᵀx = b` as a pair of inequalities
+    ///
+    /// # Arguments
+    /// * `coefficients`: coefficients `a` of the constraint
+    /// * `bound`: right hand side `b`
+    ///
+    /// # Panics
+    /// * panics if number of coefficients is not equal to number of variables
+    pub fn add_constraint_eq(&mut self, coefficients: Vec<f64>, bound: f64) {
+        self.add_constraint_ge(coefficients.clone(), bound);
+        self.add_constraint(coefficients, bound);
+    }
+
+    /// Solves the program
+    /// * Complexity: O(m * n) per pivot, where m is number of constraints and n is number of variables.
+    ///   Number of pivots is small in practice but exponential in the worst case
+    pub fn solve(&self) -> LpResult {
+        Tableau::init(self).solve()
+    }
+}
+
+// dictionary form of the program. variables are numbered 0..n for the original ones, n..n + m for the slacks
+// and None for the auxiliary variable of the first phase
+struct Tableau {
+    m: usize,
+    n: usize,
+    basic: Vec<Option<usize>>,
+    non_basic: Vec<Option<usize>>,
+    // m constraint rows, the objective row and the auxiliary objective row. last two columns are
+    // the auxiliary variable and the right hand side
+    d: Vec<Vec<f64>>,
+}
+
+impl Tableau {
+    fn init(program: &Simplex) -> Tableau {
+        let m = program.constraints.len();
+        let n = program.objective.len();
+        let mut d = vec![vec![0.0; n + 2]; m + 2];
+
+        for (i, row) in d.iter_mut().take(m).enumerate() {
+            row[..n].copy_from_slice(&program.constraints[i]);
+            row[n] = -1.0;
+            row[n + 1] = program.bounds[i];
+        }
+        for (j, c) in program.objective.iter().enumerate() {
+            d[m][j] = -c;
+        }
+        d[m + 1][n] = 1.0;
+
+        let mut non_basic: Vec<Option<usize>> = (0..n).map(Some).collect();
+        non_basic.push(None);
+
+        Tableau {
+            m,
+            n,
+            basic: (n..n + m).map(Some).collect(),
+            non_basic,
+            d,
+        }
+    }
+
+    fn pivot(&mut self, r: usize, s: usize) {
+        let inverse = 1.0 / self.d[r][s];
+        let pivot_row = self.d[r].clone();
+
+        for i in 0..self.m + 2 {
+            if i == r {
+                continue;
+            }
+            let factor = self.d[i][s] * inverse;
+            for (j, value) in pivot_row.iter().enumerate() {
+                if j != s {
+                    self.d[i][j] -= value * factor;
+                }
+            }
+            self.d[i][s] = -factor;
+        }
+        for (j, value) in self.d[r].iter_mut().enumerate() {
+            if j != s {
+                *value *= inverse;
+            }
+        }
+        self.d[r][s] = inverse;
+
+        std::mem::swap(&mut self.basic[r], &mut self.non_basic[s]);
+    }
+
+    // Bland's rule orders variables by their number, with the auxiliary variable first
+    fn bland_key(variable: Option<usize>) -> (bool, usize) {
+        match variable {
+            Some(v) => (true, v),
+            None => (false, 0),
+        }
+    }
+
+    // optimizes the objective of `row`. returns false if it is unbounded
+    fn optimize(&mut self, row: usize, first_phase: bool) -> bool {
+        loop {
+            // the entering variable is the one with the smallest number among those improving the objective
+            let mut s: Option<usize> = None;
+            for j in 0..=self.n {
+                if !first_phase && self.non_basic[j].is_none() {
+                    continue;
+                }
+                if self.d[row][j] >= -EPSILON {
+                    continue;
+                }
+                let better = match s {
+                    None => true,
+                    Some(s) => {
+                        Tableau::bland_key(self.non_basic[j])
+                            < Tableau::bland_key(self.non_basic[s])
+                    }
+                };
+                if better {
+                    s = Some(j);
+                }
+            }
+
+            let s = match s {
+                Some(s) => s,
+                None => return true,
+            };
+
+            let mut r: Option<usize> = None;
+            for i in 0..self.m {
+                if self.d[i][s] < EPSILON {
+                    continue;
+                }
+                let better = match r {
+                    None => true,
+                    Some(r) => {
+                        let ratio = self.d[i][self.n + 1] / self.d[i][s];
+                        let best = self.d[r][self.n + 1] / self.d[r][s];
+                        ratio < best
+                            || (ratio == best
+                                && Tableau::bland_key(self.basic[i])
+                                    < Tableau::bland_key(self.basic[r]))
+                    }
+                };
+                if better {
+                    r = Some(i);
+                }
+            }
+
+            match r {
+                Some(r) => self.pivot(r, s),
+                None => return false,
+            }
+        }
+    }
+
+    fn solve(mut self) -> LpResult {
+        let (m, n) = (self.m, self.n);
+
+        // the most violated constraint enters the auxiliary variable, which makes the dictionary feasible
+        let mut r = 0;
+        for i in 1..m {
+            if self.d[i][n + 1] < self.d[r][n + 1] {
+                r = i;
+            }
+        }
+        if m > 0 && self.d[r][n + 1] < -EPSILON {
+            self.pivot(r, n);
+            if !self.optimize(m + 1, true) || self.d[m + 1][n + 1] < -EPSILON {
+                return LpResult::Infeasible;
+            }
+
+            // the auxiliary variable leaves the basis if it is still in it
+            for i in 0..m {
+                if self.basic[i].is_none() {
+                    let mut s = 0;
+                    for j in 1..=n {
+                        if self.d[i][j] < self.d[i][s]
+                            || (self.d[i][j] == self.d[i][s]
+                                && Tableau::bland_key(self.non_basic[j])
+                                    < Tableau::bland_key(self.non_basic[s]))
+                        {
+                            s = j;
+                        }
+                    }
+                    self.pivot(i, s);
+                }
+            }
+        }
+
+        if !self.optimize(m, false) {
+            return LpResult::Unbounded;
+        }
+
+        let mut solution = vec![0.0; n];
+        for i in 0..m {
+            if let Some(variable) = self.basic[i] {
+                if variable < n {
+                    solution[variable] = self.d[i][n + 1];
+                }
+            }
+        }
+
+        LpResult::Optimal {
+            value: self.d[m][n + 1],
+            solution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_optimal(result: &LpResult, value: f64, solution: &[f64]) {
+        assert!((result.value().unwrap() - value).abs() < 1e-7);
+        for (x, expected) in result.solution().unwrap().iter().zip(solution.iter()) {
+            assert!((x - expected).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn math_lp_simplex_textbook() {
+        // maximize 3x + 5y subject to x <= 4, 2y <= 12, 3x + 2y <= 18
+        let mut lp = Simplex::init(vec![3.0, 5.0]);
+        lp.add_constraint(vec![1.0, 0.0], 4.0);
+        lp.add_constraint(vec![0.0, 2.0], 12.0);
+        lp.add_constraint(vec![3.0, 2.0], 18.0);
+
+        assert_optimal(&lp.solve(), 36.0, &[2.0, 6.0]);
+    }
+
+    #[test]
+    fn math_lp_simplex_negative_bounds() {
+        // minimize x + y subject to x + 2y >= 4, 3x + y >= 6, written as maximization of -(x + y)
+        let mut lp = Simplex::init(vec![-1.0, -1.0]);
+        lp.add_constraint_ge(vec![1.0, 2.0], 4.0);
+        lp.add_constraint_ge(vec![3.0, 1.0], 6.0);
+
+        assert_optimal(&lp.solve(), -2.8, &[1.6, 1.2]);
+    }
+
+    #[test]
+    fn math_lp_simplex_equality() {
+        // maximize x + 2y + 3z subject to x + y + z = 1
+        let mut lp = Simplex::init(vec![1.0, 2.0, 3.0]);
+        lp.add_constraint_eq(vec![1.0, 1.0, 1.0], 1.0);
+
+        assert_optimal(&lp.solve(), 3.0, &[0.0, 0.0, 1.0]);
+        assert_eq!(lp.constraints(), 2);
+    }
+
+    #[test]
+    fn math_lp_simplex_unbounded() {
+        let mut lp = Simplex::init(vec![1.0, 1.0]);
+        lp.add_constraint(vec![1.0, -1.0], 1.0);
+
+        assert_eq!(lp.solve(), LpResult::Unbounded);
+        assert_eq!(lp.solve().value(), None);
+    }
+
+    #[test]
+    fn math_lp_simplex_infeasible() {
+        let mut lp = Simplex::init(vec![1.0]);
+        lp.add_constraint(vec![1.0], -1.0);
+
+        assert_eq!(lp.solve(), LpResult::Infeasible);
+    }
+
+    #[test]
+    fn math_lp_simplex_degenerate() {
+        // a classic cycling example for the largest coefficient rule
+        let mut lp = Simplex::init(vec![10.0, -57.0, -9.0, -24.0]);
+        lp.add_constraint(vec![0.5, -5.5, -2.5, 9.0], 0.0);
+        lp.add_constraint(vec![0.5, -1.5, -0.5, 1.0], 0.0);
+        lp.add_constraint(vec![1.0, 0.0, 0.0, 0.0], 1.0);
+
+        assert_optimal(&lp.solve(), 1.0, &[1.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn math_lp_simplex_no_constraints() {
+        assert_optimal(&Simplex::init(vec![-1.0, 0.0]).solve(), 0.0, &[0.0, 0.0]);
+        assert_eq!(Simplex::init(vec![1.0]).solve(), LpResult::Unbounded);
+    }
+
+    #[test]
+    #[should_panic(expected = "Number of coefficients must be equal to number of variables")]
+    fn math_lp_simplex_wrong_size() {
+        Simplex::init(vec![1.0, 2.0]).add_constraint(vec![1.0], 1.0);
+    }
+}
@@ -0,0 +1,22 @@
+pub mod lp;
+
+mod bit_matrix;
+mod fft;
+mod modint;
+mod polynomial;
+mod scalar;
+mod sparse_matrix;
+mod sparse_vec;
+
+pub use bit_matrix::BitMatrix;
+pub use fft::fft;
+pub use fft::ifft;
+pub use fft::Complex;
+pub use modint::FactorialTable;
+pub use modint::ModInt;
+pub use modint::ModInt1000000007;
+pub use modint::ModInt998244353;
+pub use polynomial::Polynomial;
+pub use scalar::Scalar;
+pub use sparse_matrix::SparseMatrix;
+pub use sparse_vec::SparseVec;
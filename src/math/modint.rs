@@ -0,0 +1,490 @@
+use crate::math::Scalar;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// An integer modulo `M`. All arithmetic operations are performed modulo `M` and never overflow.
+/// Division is only defined when the divisor is coprime with `M`, which is always the case for non-zero values when `M` is prime.
+///
+/// # Examples
+/// ```
+/// use rudac::math::ModInt;
+///
+/// type Mint = ModInt<7>;
+///
+/// let a = Mint::new(5);
+/// let b = Mint::new(4);
+///
+/// assert_eq!(a + b, Mint::new(2));
+/// assert_eq!(a * b, Mint::new(6));
+/// assert_eq!(a / b, Mint::new(3));
+/// assert_eq!(a.pow(6), Mint::new(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct ModInt<const M: u64> {
+    value: u64,
+}
+
+/// Integers modulo 1e9 + 7
+pub type ModInt1000000007 = ModInt<1_000_000_007>;
+
+/// Integers modulo 998244353
+pub type ModInt998244353 = ModInt<998_244_353>;
+
+impl<const M: u64> ModInt<M> {
+    /// Creates a new modular integer equal to `value` mod `M`
+    ///
+    /// # Arguments
+    /// * `value`: integer value
+    ///
+    /// # Panics
+    /// * panics if `M` is zero
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::ModInt;
+    ///
+    /// assert_eq!(ModInt::<5>::new(12).value(), 2);
+    /// ```
+    pub fn new(value: u64) -> ModInt<M> {
+        if M == 0 {
+            panic!("Modulus must be greater than zero");
+        }
+
+        ModInt { value: value % M }
+    }
+
+    /// Creates a new modular integer from a signed value. Negative values wrap around: -1 becomes `M` - 1
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::ModInt;
+    ///
+    /// assert_eq!(ModInt::<5>::from_signed(-1).value(), 4);
+    /// ```
+    pub fn from_signed(value: i64) -> ModInt<M> {
+        let value = (value as i128).rem_euclid(M as i128);
+
+        ModInt::new(value as u64)
+    }
+
+    /// Returns the modulus
+    pub fn modulus() -> u64 {
+        M
+    }
+
+    /// Returns the canonical representative in range [0, `M`)
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Raises the value to the power of `exponent` using binary exponentiation
+    ///
+    /// # Arguments
+    /// * `exponent`: power
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::ModInt;
+    ///
+    /// assert_eq!(ModInt::<1_000_000_007>::new(2).pow(10).value(), 1024);
+    /// ```
+    pub fn pow(&self, mut exponent: u64) -> ModInt<M> {
+        let mut base = *self;
+        let mut result = ModInt::new(1);
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exponent >>= 1;
+        }
+
+        result
+    }
+
+    /// Returns the multiplicative inverse using the extended euclidean algorithm.
+    /// Returns `None` if value is not coprime with `M`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::ModInt;
+    ///
+    /// assert_eq!(ModInt::<7>::new(3).inverse(), Some(ModInt::new(5)));
+    /// assert_eq!(ModInt::<8>::new(4).inverse(), None);
+    /// ```
+    pub fn inverse(&self) -> Option<ModInt<M>> {
+        let (mut old_r, mut r) = (self.value as i128, M as i128);
+        let (mut old_s, mut s) = (1i128, 0i128);
+
+        while r != 0 {
+            let quotient = old_r / r;
+            let next_r = old_r - quotient * r;
+            old_r = r;
+            r = next_r;
+
+            let next_s = old_s - quotient * s;
+            old_s = s;
+            s = next_s;
+        }
+
+        if old_r != 1 {
+            if M == 1 {
+                return Some(ModInt::new(0));
+            }
+            return None;
+        }
+
+        Some(ModInt::new(old_s.rem_euclid(M as i128) as u64))
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> ModInt<M> {
+        ModInt::new(value)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(value: i64) -> ModInt<M> {
+        ModInt::from_signed(value)
+    }
+}
+
+impl<const M: u64> std::fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn add(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt {
+            value: ((self.value as u128 + other.value as u128) % M as u128) as u64,
+        }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn sub(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt {
+            value: ((self.value as u128 + M as u128 - other.value as u128) % M as u128) as u64,
+        }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn mul(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt {
+            value: ((self.value as u128 * other.value as u128) % M as u128) as u64,
+        }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn div(self, other: ModInt<M>) -> ModInt<M> {
+        match other.inverse() {
+            Some(inverse) => Mul::mul(self, inverse),
+            None => panic!("Divisor is not invertible modulo {}", M),
+        }
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn neg(self) -> ModInt<M> {
+        ModInt::new(0) - self
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, other: ModInt<M>) {
+        *self = *self + other;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, other: ModInt<M>) {
+        *self = *self - other;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, other: ModInt<M>) {
+        *self = *self * other;
+    }
+}
+
+impl<const M: u64> DivAssign for ModInt<M> {
+    fn div_assign(&mut self, other: ModInt<M>) {
+        *self = *self / other;
+    }
+}
+
+impl<const M: u64> std::iter::Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = ModInt<M>>>(iter: I) -> ModInt<M> {
+        iter.fold(ModInt::new(0), |a, b| a + b)
+    }
+}
+
+impl<const M: u64> std::iter::Product for ModInt<M> {
+    fn product<I: Iterator<Item = ModInt<M>>>(iter: I) -> ModInt<M> {
+        iter.fold(ModInt::new(1), |a, b| a * b)
+    }
+}
+
+impl<const M: u64> Scalar for ModInt<M> {
+    fn zero() -> Self {
+        ModInt::new(0)
+    }
+
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+
+    fn from_usize(n: usize) -> Self {
+        ModInt::new(n as u64)
+    }
+}
+
+/// Precomputed factorials and inverse factorials modulo a prime `M`,
+/// answering binomial coefficient and permutation queries in O(1)
+///
+/// # Examples
+/// ```
+/// use rudac::math::FactorialTable;
+///
+/// let table = FactorialTable::<1_000_000_007>::init(100);
+///
+/// assert_eq!(table.choose(5, 2).value(), 10);
+/// assert_eq!(table.permutations(5, 2).value(), 20);
+/// assert_eq!(table.factorial(20).value(), 146_326_063);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FactorialTable<const M: u64> {
+    factorials: Vec<ModInt<M>>,
+    inverse_factorials: Vec<ModInt<M>>,
+}
+
+impl<const M: u64> FactorialTable<M> {
+    /// Precomputes factorials of 0..=`n` in O(n)
+    ///
+    /// # Arguments
+    /// * `n`: largest value the table can answer queries for
+    ///
+    /// # Panics
+    /// * panics if `n` >= `M`, because n! is not invertible in that case
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::FactorialTable;
+    ///
+    /// let table = FactorialTable::<7>::init(6);
+    ///
+    /// assert_eq!(table.size(), 6);
+    /// ```
+    pub fn init(n: usize) -> FactorialTable<M> {
+        if n as u64 >= M {
+            panic!("Table size must be less than the modulus");
+        }
+
+        let mut factorials = Vec::with_capacity(n + 1);
+        factorials.push(ModInt::new(1));
+        for i in 1..=n {
+            let previous = factorials[i - 1];
+            factorials.push(previous * ModInt::new(i as u64));
+        }
+
+        let mut inverse_factorials = vec![ModInt::new(1); n + 1];
+        inverse_factorials[n] = factorials[n].inverse().unwrap();
+        for i in (0..n).rev() {
+            inverse_factorials[i] = inverse_factorials[i + 1] * ModInt::new(i as u64 + 1);
+        }
+
+        FactorialTable {
+            factorials,
+            inverse_factorials,
+        }
+    }
+
+    /// Returns largest value the table can answer queries for
+    pub fn size(&self) -> usize {
+        self.factorials.len() - 1
+    }
+
+    /// Returns `n`!
+    ///
+    /// # Panics
+    /// * panics if `n` is larger than size of the table
+    pub fn factorial(&self, n: usize) -> ModInt<M> {
+        self.check(n);
+
+        self.factorials[n]
+    }
+
+    /// Returns the inverse of `n`!
+    ///
+    /// # Panics
+    /// * panics if `n` is larger than size of the table
+    pub fn inverse_factorial(&self, n: usize) -> ModInt<M> {
+        self.check(n);
+
+        self.inverse_factorials[n]
+    }
+
+    /// Returns number of ways to choose `k` items out of `n` items. Returns zero if `k` > `n`
+    ///
+    /// # Panics
+    /// * panics if `n` is larger than size of the table
+    pub fn choose(&self, n: usize, k: usize) -> ModInt<M> {
+        if k > n {
+            return ModInt::new(0);
+        }
+
+        self.factorial(n) * self.inverse_factorials[k] * self.inverse_factorials[n - k]
+    }
+
+    /// Returns number of ordered arrangements of `k` items out of `n` items. Returns zero if `k` > `n`
+    ///
+    /// # Panics
+    /// * panics if `n` is larger than size of the table
+    pub fn permutations(&self, n: usize, k: usize) -> ModInt<M> {
+        if k > n {
+            return ModInt::new(0);
+        }
+
+        self.factorial(n) * self.inverse_factorials[n - k]
+    }
+
+    fn check(&self, n: usize) {
+        if n > self.size() {
+            panic!("Value is out of range of the table");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Polynomial;
+
+    type Mint = ModInt1000000007;
+
+    #[test]
+    fn math_modint_arithmetic_wraps() {
+        let a = Mint::new(1_000_000_006);
+        let b = Mint::new(5);
+
+        assert_eq!((a + b).value(), 4);
+        assert_eq!((b - a).value(), 6);
+        assert_eq!((-b).value(), 1_000_000_002);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!(Mint::from(-3i64).value(), 1_000_000_004);
+    }
+
+    #[test]
+    fn math_modint_large_modulus() {
+        type Big = ModInt<{ (1 << 63) + 29 }>;
+
+        let a = Big::new((1 << 63) + 28);
+
+        assert_eq!(a * a, Big::new(1));
+        assert_eq!(a + a, Big::new((1 << 63) + 27));
+    }
+
+    #[test]
+    fn math_modint_inverse_and_division() {
+        for v in 1..50u64 {
+            let a = Mint::new(v);
+            assert_eq!(a * a.inverse().unwrap(), Mint::new(1));
+            assert_eq!(a / a, Mint::new(1));
+        }
+
+        assert_eq!(ModInt::<12>::new(6).inverse(), None);
+        assert_eq!(ModInt::<12>::new(5).inverse(), Some(ModInt::new(5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Divisor is not invertible modulo 12")]
+    fn math_modint_division_panic() {
+        let _ = ModInt::<12>::new(1) / ModInt::new(4);
+    }
+
+    #[test]
+    fn math_modint_pow_fermat() {
+        let a = Mint::new(123_456_789);
+
+        assert_eq!(a.pow(1_000_000_006), Mint::new(1));
+        assert_eq!(a.pow(0), Mint::new(1));
+    }
+
+    #[test]
+    fn math_modint_sum_product() {
+        let values: Vec<Mint> = (1..=5).map(Mint::new).collect();
+
+        assert_eq!(values.iter().copied().sum::<Mint>(), Mint::new(15));
+        assert_eq!(values.iter().copied().product::<Mint>(), Mint::new(120));
+    }
+
+    #[test]
+    fn math_modint_factorial_table() {
+        let table = FactorialTable::<1_000_000_007>::init(1000);
+
+        // pascal's rule
+        for n in 1..30 {
+            for k in 1..n {
+                assert_eq!(
+                    table.choose(n, k),
+                    table.choose(n - 1, k - 1) + table.choose(n - 1, k)
+                );
+            }
+        }
+
+        assert_eq!(table.choose(3, 4), Mint::new(0));
+        assert_eq!(table.choose(1000, 500).value(), 159_835_829);
+        assert_eq!(
+            table.factorial(5) * table.inverse_factorial(5),
+            Mint::new(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Table size must be less than the modulus")]
+    fn math_modint_factorial_table_too_large() {
+        FactorialTable::<7>::init(7);
+    }
+
+    #[test]
+    fn math_modint_polynomial_coefficients() {
+        type M7 = ModInt<7>;
+
+        // (1 + x)^7 = 1 + x^7 over GF(7)
+        let base = Polynomial::new(vec![M7::new(1), M7::new(1)]);
+        let mut power = Polynomial::constant(M7::new(1));
+        for _ in 0..7 {
+            power = &power * &base;
+        }
+
+        assert_eq!(
+            power,
+            Polynomial::new(vec![
+                M7::new(1),
+                M7::new(0),
+                M7::new(0),
+                M7::new(0),
+                M7::new(0),
+                M7::new(0),
+                M7::new(0),
+                M7::new(1)
+            ])
+        );
+    }
+}
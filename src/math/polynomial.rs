@@ -0,0 +1,485 @@
+use crate::math::{fft, ifft, Complex, Scalar};
+use crate::util::{Footprint, MemoryFootprint};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A polynomial with coefficients of type `T`. It also serves as a truncated formal power series
+/// when used with `truncate`.
+///
+/// Coefficients are stored from the lowest degree to the highest degree and trailing zeros are always removed,
+/// so two equal polynomials have identical representations.
+///
+/// # Examples
+/// ```
+/// use rudac::math::Polynomial;
+///
+/// // p(x) = 1 + 2x
+/// let p = Polynomial::new(vec![1.0, 2.0]);
+///
+/// // q(x) = 3x^2
+/// let q = Polynomial::new(vec![0.0, 0.0, 3.0]);
+///
+/// assert_eq!(&p + &q, Polynomial::new(vec![1.0, 2.0, 3.0]));
+/// assert_eq!(&p * &q, Polynomial::new(vec![0.0, 0.0, 3.0, 6.0]));
+/// assert_eq!(p.evaluate(2.0), 5.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T: Scalar> {
+    coefficients: Vec<T>,
+}
+
+impl<T: Scalar> Polynomial<T> {
+    /// Creates a polynomial from its coefficients
+    ///
+    /// # Arguments
+    /// * `coefficients`: coefficients from the lowest degree to the highest degree
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// // 1 + x^2
+    /// let p = Polynomial::new(vec![1.0, 0.0, 1.0, 0.0]);
+    ///
+    /// assert_eq!(p.degree(), Some(2));
+    /// ```
+    pub fn new(coefficients: Vec<T>) -> Polynomial<T> {
+        let mut polynomial = Polynomial { coefficients };
+        polynomial.normalize();
+
+        polynomial
+    }
+
+    /// Returns the zero polynomial
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// let zero = Polynomial::<f64>::zero();
+    ///
+    /// assert_eq!(zero.is_zero(), true);
+    /// assert_eq!(zero.degree(), None);
+    /// ```
+    pub fn zero() -> Polynomial<T> {
+        Polynomial {
+            coefficients: Vec::new(),
+        }
+    }
+
+    /// Returns the constant polynomial `value`
+    pub fn constant(value: T) -> Polynomial<T> {
+        Polynomial::new(vec![value])
+    }
+
+    /// Returns true if this is the zero polynomial
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Returns degree of the polynomial. Zero polynomial has no degree
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// assert_eq!(Polynomial::new(vec![5.0]).degree(), Some(0));
+    /// assert_eq!(Polynomial::new(vec![0.0, 1.0]).degree(), Some(1));
+    /// ```
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Returns coefficients from the lowest degree to the highest degree
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    /// Returns coefficient of x^`power`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// let p = Polynomial::new(vec![1.0, 2.0]);
+    ///
+    /// assert_eq!(p.coefficient(1), 2.0);
+    /// assert_eq!(p.coefficient(10), 0.0);
+    /// ```
+    pub fn coefficient(&self, power: usize) -> T {
+        match self.coefficients.get(power) {
+            Some(c) => *c,
+            None => T::zero(),
+        }
+    }
+
+    /// Evaluates the polynomial at `x` using Horner's method
+    ///
+    /// # Arguments
+    /// * `x`: point of evaluation
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// // 1 - 3x + x^2
+    /// let p = Polynomial::new(vec![1.0, -3.0, 1.0]);
+    ///
+    /// assert_eq!(p.evaluate(3.0), 1.0);
+    /// ```
+    pub fn evaluate(&self, x: T) -> T {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(T::zero(), |result, c| result * x + *c)
+    }
+
+    /// Returns the derivative of the polynomial
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// // 1 + x + x^2 -> 1 + 2x
+    /// let p = Polynomial::new(vec![1.0, 1.0, 1.0]);
+    ///
+    /// assert_eq!(p.derivative(), Polynomial::new(vec![1.0, 2.0]));
+    /// ```
+    pub fn derivative(&self) -> Polynomial<T> {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, c)| T::from_usize(i) * *c)
+                .collect(),
+        )
+    }
+
+    /// Returns the antiderivative of the polynomial with zero as its constant term
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// // 1 + 2x -> x + x^2
+    /// let p = Polynomial::new(vec![1.0, 2.0]);
+    ///
+    /// assert_eq!(p.integral(), Polynomial::new(vec![0.0, 1.0, 1.0]));
+    /// ```
+    pub fn integral(&self) -> Polynomial<T> {
+        let mut coefficients = Vec::with_capacity(self.coefficients.len() + 1);
+        coefficients.push(T::zero());
+
+        for (i, c) in self.coefficients.iter().enumerate() {
+            coefficients.push(*c / T::from_usize(i + 1));
+        }
+
+        Polynomial::new(coefficients)
+    }
+
+    /// Multiplies two polynomials using the schoolbook algorithm.
+    /// * Complexity: O(n * m)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// let p = Polynomial::new(vec![1.0, 1.0]);
+    ///
+    /// assert_eq!(p.multiply_naive(&p), Polynomial::new(vec![1.0, 2.0, 1.0]));
+    /// ```
+    pub fn multiply_naive(&self, other: &Polynomial<T>) -> Polynomial<T> {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let mut coefficients =
+            vec![T::zero(); self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] = coefficients[i + j] + *a * *b;
+            }
+        }
+
+        Polynomial::new(coefficients)
+    }
+
+    /// Divides the polynomial by `divisor` and returns the quotient and the remainder
+    ///
+    /// # Arguments
+    /// * `divisor`: polynomial to divide by
+    ///
+    /// # Panics
+    /// * panics if `divisor` is the zero polynomial
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// // x^2 + 3x + 3 = (x + 1)(x + 2) + 1
+    /// let dividend = Polynomial::new(vec![3.0, 3.0, 1.0]);
+    /// let divisor = Polynomial::new(vec![1.0, 1.0]);
+    ///
+    /// let (quotient, remainder) = dividend.div_rem(&divisor);
+    ///
+    /// assert_eq!(quotient, Polynomial::new(vec![2.0, 1.0]));
+    /// assert_eq!(remainder, Polynomial::new(vec![1.0]));
+    /// ```
+    pub fn div_rem(&self, divisor: &Polynomial<T>) -> (Polynomial<T>, Polynomial<T>) {
+        let divisor_degree = match divisor.degree() {
+            Some(degree) => degree,
+            None => panic!("Division by zero polynomial"),
+        };
+
+        let mut remainder = self.coefficients.clone();
+        if remainder.len() <= divisor_degree {
+            return (Polynomial::zero(), self.clone());
+        }
+
+        let leading = divisor.coefficients[divisor_degree];
+        let mut quotient = vec![T::zero(); remainder.len() - divisor_degree];
+
+        for i in (0..quotient.len()).rev() {
+            let factor = remainder[i + divisor_degree] / leading;
+            quotient[i] = factor;
+
+            for (j, c) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] = remainder[i + j] - factor * *c;
+            }
+        }
+
+        remainder.truncate(divisor_degree);
+
+        (Polynomial::new(quotient), Polynomial::new(remainder))
+    }
+
+    /// Keeps only the terms with degree less than `n`, which is the same as computing the polynomial mod x^n.
+    /// This is the basic operation for working with truncated formal power series
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// let p = Polynomial::new(vec![1.0, 2.0, 3.0]);
+    ///
+    /// assert_eq!(p.truncate(2), Polynomial::new(vec![1.0, 2.0]));
+    /// ```
+    pub fn truncate(&self, n: usize) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.iter().take(n).copied().collect())
+    }
+
+    fn normalize(&mut self) {
+        while let Some(last) = self.coefficients.last() {
+            if *last != T::zero() {
+                break;
+            }
+            self.coefficients.pop();
+        }
+    }
+}
+
+impl Polynomial<f64> {
+    /// Multiplies two polynomials using the fast fourier transform.
+    /// Result is subject to floating point rounding errors.
+    /// * Complexity: O((n + m) log(n + m))
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::math::Polynomial;
+    ///
+    /// let p = Polynomial::new(vec![1.0, 1.0]);
+    /// let product = p.multiply_fft(&p);
+    ///
+    /// assert!((product.coefficient(1) - 2.0).abs() < 1e-9);
+    /// ```
+    pub fn multiply_fft(&self, other: &Polynomial<f64>) -> Polynomial<f64> {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let result_len = self.coefficients.len() + other.coefficients.len() - 1;
+        let n = result_len.next_power_of_two();
+
+        let to_complex = |coefficients: &Vec<f64>| {
+            let mut values: Vec<Complex> =
+                coefficients.iter().map(|c| Complex::new(*c, 0.0)).collect();
+            values.resize(n, Complex::default());
+            values
+        };
+
+        let mut a = to_complex(&self.coefficients);
+        let mut b = to_complex(&other.coefficients);
+
+        fft(&mut a);
+        fft(&mut b);
+        for i in 0..n {
+            a[i] = a[i] * b[i];
+        }
+        ifft(&mut a);
+
+        Polynomial::new(a.iter().take(result_len).map(|c| c.re).collect())
+    }
+}
+
+impl<T: Scalar> Add for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, other: &Polynomial<T>) -> Polynomial<T> {
+        let len = std::cmp::max(self.coefficients.len(), other.coefficients.len());
+
+        Polynomial::new(
+            (0..len)
+                .map(|i| self.coefficient(i) + other.coefficient(i))
+                .collect(),
+        )
+    }
+}
+
+impl<T: Scalar> Sub for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, other: &Polynomial<T>) -> Polynomial<T> {
+        let len = std::cmp::max(self.coefficients.len(), other.coefficients.len());
+
+        Polynomial::new(
+            (0..len)
+                .map(|i| self.coefficient(i) - other.coefficient(i))
+                .collect(),
+        )
+    }
+}
+
+impl<T: Scalar> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, other: &Polynomial<T>) -> Polynomial<T> {
+        self.multiply_naive(other)
+    }
+}
+
+impl<T: Scalar> Neg for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.iter().map(|c| -*c).collect())
+    }
+}
+
+impl<T: Scalar> Add for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, other: Polynomial<T>) -> Polynomial<T> {
+        &self + &other
+    }
+}
+
+impl<T: Scalar> Sub for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, other: Polynomial<T>) -> Polynomial<T> {
+        &self - &other
+    }
+}
+
+impl<T: Scalar> Mul for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, other: Polynomial<T>) -> Polynomial<T> {
+        &self * &other
+    }
+}
+
+impl<T: Scalar> Neg for Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Polynomial<T> {
+        -&self
+    }
+}
+
+impl<T: Scalar> MemoryFootprint for Polynomial<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.coefficients.len()).with_vec(&self.coefficients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_polynomial_normalize() {
+        let p = Polynomial::new(vec![0.0, 0.0]);
+
+        assert!(p.is_zero());
+        assert_eq!(p, Polynomial::zero());
+        assert_eq!(Polynomial::constant(2.0).degree(), Some(0));
+    }
+
+    #[test]
+    fn math_polynomial_add_sub_cancel() {
+        let p = Polynomial::new(vec![1.0, 2.0, 3.0]);
+        let q = Polynomial::new(vec![1.0, 1.0, 3.0]);
+
+        assert_eq!(&p - &q, Polynomial::new(vec![0.0, 1.0]));
+        assert_eq!(p.clone() + (-q.clone()), p - q);
+    }
+
+    #[test]
+    fn math_polynomial_multiply_fft_matches_naive() {
+        let p = Polynomial::new((0..37).map(|i| (i % 7) as f64 - 3.0).collect());
+        let q = Polynomial::new((0..23).map(|i| (i % 5) as f64).collect());
+
+        let naive = p.multiply_naive(&q);
+        let fast = p.multiply_fft(&q);
+
+        assert_eq!(naive.degree(), fast.degree());
+        for i in 0..naive.coefficients().len() {
+            assert!((naive.coefficient(i) - fast.coefficient(i)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn math_polynomial_multiply_zero() {
+        let p = Polynomial::new(vec![1.0, 2.0]);
+
+        assert!((&p * &Polynomial::zero()).is_zero());
+        assert!(p.multiply_fft(&Polynomial::zero()).is_zero());
+    }
+
+    #[test]
+    fn math_polynomial_div_rem_identity() {
+        let dividend = Polynomial::new(vec![5.0, -4.0, 0.0, 2.0, 1.0]);
+        let divisor = Polynomial::new(vec![1.0, 0.0, 2.0]);
+
+        let (quotient, remainder) = dividend.div_rem(&divisor);
+
+        assert!(remainder.degree().unwrap_or(0) < divisor.degree().unwrap());
+        assert_eq!(&(&quotient * &divisor) + &remainder, dividend);
+    }
+
+    #[test]
+    fn math_polynomial_div_rem_smaller_dividend() {
+        let dividend = Polynomial::new(vec![1.0, 1.0]);
+        let divisor = Polynomial::new(vec![0.0, 0.0, 1.0]);
+
+        let (quotient, remainder) = dividend.div_rem(&divisor);
+
+        assert!(quotient.is_zero());
+        assert_eq!(remainder, dividend);
+    }
+
+    #[test]
+    #[should_panic(expected = "Division by zero polynomial")]
+    fn math_polynomial_div_by_zero() {
+        Polynomial::new(vec![1.0]).div_rem(&Polynomial::zero());
+    }
+
+    #[test]
+    fn math_polynomial_derivative_integral() {
+        let p = Polynomial::new(vec![3.0, 2.0, 6.0]);
+
+        assert_eq!(p.integral().derivative(), p);
+        assert!(Polynomial::constant(4.0).derivative().is_zero());
+    }
+}
@@ -0,0 +1,77 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Values that support field arithmetic. It is implemented for floating point types and can be
+/// implemented for any other field(ex. rationals or integers modulo a prime) to use them with the math structures
+///
+/// # Examples
+/// ```
+/// use rudac::math::Scalar;
+///
+/// fn average<T: Scalar>(values: &[T]) -> T {
+///     let mut sum = T::zero();
+///     for value in values {
+///         sum = sum + *value;
+///     }
+///     sum / T::from_usize(values.len())
+/// }
+///
+/// assert_eq!(average(&[1.0, 2.0, 6.0]), 3.0);
+/// ```
+pub trait Scalar:
+    Copy
+    + PartialEq
+    + std::fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Returns the additive identity
+    fn zero() -> Self;
+
+    /// Returns the multiplicative identity
+    fn one() -> Self;
+
+    /// Converts an unsigned integer into the scalar
+    fn from_usize(n: usize) -> Self;
+}
+
+impl Scalar for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_usize(n: usize) -> Self {
+        n as f64
+    }
+}
+
+impl Scalar for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_usize(n: usize) -> Self {
+        n as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_scalar_identities() {
+        assert_eq!(f64::zero() + f64::one(), 1.0);
+        assert_eq!(f32::from_usize(3) * f32::one(), 3.0);
+    }
+}
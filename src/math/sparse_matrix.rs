@@ -0,0 +1,636 @@
+use crate::math::{Scalar, SparseVec};
+use crate::util::{Footprint, MemoryFootprint};
+use std::ops::{Add, Neg, Sub};
+
+/// A sparse matrix in compressed sparse row(CSR) format. Non-zero entries of row `r` are at positions
+/// `row_offsets[r]..row_offsets[r + 1]` of `col_indices` and `values`, sorted by column.
+///
+/// Compressed sparse column(CSC) format of a matrix is the CSR format of its transpose,
+/// so `from_csc` and `to_csc` convert between the formats through `transpose` in O(nnz) time.
+///
+/// # Examples
+/// ```
+/// use rudac::math::SparseMatrix;
+///
+/// // adjacency matrix of the directed cycle 0 -> 1 -> 2 -> 0
+/// let a = SparseMatrix::from_triplets(3, 3, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0)]);
+///
+/// // number of walks of length 2
+/// let walks = a.multiply(&a);
+/// assert_eq!(walks.get(0, 2), 1.0);
+/// assert_eq!(walks.get(0, 1), 0.0);
+///
+/// assert_eq!(a.multiply_dense(&[1.0, 2.0, 3.0]), vec![2.0, 3.0, 1.0]);
+/// assert_eq!(a.transpose().get(1, 0), 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix<T: Scalar> {
+    rows: usize,
+    cols: usize,
+    row_offsets: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Scalar> SparseMatrix<T> {
+    /// Initializes a zero matrix
+    ///
+    /// # Arguments
+    /// * `rows`: number of rows
+    /// * `cols`: number of columns
+    pub fn init(rows: usize, cols: usize) -> SparseMatrix<T> {
+        SparseMatrix {
+            rows,
+            cols,
+            row_offsets: vec![0; rows + 1],
+            col_indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Returns the `n` by `n` identity matrix
+    pub fn identity(n: usize) -> SparseMatrix<T> {
+        SparseMatrix {
+            rows: n,
+            cols: n,
+            row_offsets: (0..=n).collect(),
+            col_indices: (0..n).collect(),
+            values: vec![T::one(); n],
+        }
+    }
+
+    /// Creates a matrix from (row, column, value) triplets. Values of repeated positions are added up
+    ///
+    /// # Arguments
+    /// * `rows`: number of rows
+    /// * `cols`: number of columns
+    /// * `triplets`: entries of the matrix
+    ///
+    /// # Panics
+    /// * panics if any position is out of range
+    pub fn from_triplets(
+        rows: usize,
+        cols: usize,
+        triplets: &[(usize, usize, T)],
+    ) -> SparseMatrix<T> {
+        let mut triplets = triplets.to_vec();
+        triplets.sort_by_key(|(row, col, _)| (*row, *col));
+
+        let mut matrix = SparseMatrix::init(rows, cols);
+        let mut last: Option<(usize, usize)> = None;
+        for (row, col, value) in triplets {
+            if row >= rows || col >= cols {
+                panic!("Index out of range");
+            }
+
+            if last == Some((row, col)) {
+                let position = matrix.values.len() - 1;
+                matrix.values[position] = matrix.values[position] + value;
+            } else {
+                matrix.col_indices.push(col);
+                matrix.values.push(value);
+                matrix.row_offsets[row + 1] += 1;
+                last = Some((row, col));
+            }
+        }
+        for row in 0..rows {
+            matrix.row_offsets[row + 1] += matrix.row_offsets[row];
+        }
+        matrix.remove_zeros();
+
+        matrix
+    }
+
+    /// Creates a matrix from its CSR representation
+    ///
+    /// # Arguments
+    /// * `rows`: number of rows
+    /// * `cols`: number of columns
+    /// * `row_offsets`: `rows + 1` offsets of the rows
+    /// * `col_indices`: column of every entry, sorted within every row
+    /// * `values`: value of every entry
+    ///
+    /// # Panics
+    /// * panics if the representation is not valid
+    pub fn from_csr(
+        rows: usize,
+        cols: usize,
+        row_offsets: Vec<usize>,
+        col_indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> SparseMatrix<T> {
+        let valid = row_offsets.len() == rows + 1
+            && row_offsets[0] == 0
+            && row_offsets[rows] == col_indices.len()
+            && col_indices.len() == values.len()
+            && row_offsets.windows(2).all(|w| w[0] <= w[1])
+            && (0..rows).all(|row| {
+                let columns = &col_indices[row_offsets[row]..row_offsets[row + 1]];
+                columns.windows(2).all(|w| w[0] < w[1]) && columns.iter().all(|col| *col < cols)
+            });
+        if !valid {
+            panic!("Invalid compressed sparse representation");
+        }
+
+        let mut matrix = SparseMatrix {
+            rows,
+            cols,
+            row_offsets,
+            col_indices,
+            values,
+        };
+        matrix.remove_zeros();
+
+        matrix
+    }
+
+    /// Creates a matrix from its CSC representation
+    ///
+    /// # Arguments
+    /// * `rows`: number of rows
+    /// * `cols`: number of columns
+    /// * `col_offsets`: `cols + 1` offsets of the columns
+    /// * `row_indices`: row of every entry, sorted within every column
+    /// * `values`: value of every entry
+    ///
+    /// # Panics
+    /// * panics if the representation is not valid
+    pub fn from_csc(
+        rows: usize,
+        cols: usize,
+        col_offsets: Vec<usize>,
+        row_indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> SparseMatrix<T> {
+        SparseMatrix::from_csr(cols, rows, col_offsets, row_indices, values).transpose()
+    }
+
+    /// Returns CSC representation of the matrix as (column offsets, row indices, values)
+    pub fn to_csc(&self) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+        let transpose = self.transpose();
+
+        (
+            transpose.row_offsets,
+            transpose.col_indices,
+            transpose.values,
+        )
+    }
+
+    /// Returns number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns number of non-zero entries
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns offsets of the rows in `col_indices` and `values`
+    pub fn row_offsets(&self) -> &[usize] {
+        &self.row_offsets
+    }
+
+    /// Returns column of every non-zero entry
+    pub fn col_indices(&self) -> &[usize] {
+        &self.col_indices
+    }
+
+    /// Returns value of every non-zero entry
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns value at `row` and `col`.
+    /// * Complexity: O(log nnz of the row)
+    ///
+    /// # Panics
+    /// * panics if `row` or `col` is out of range
+    pub fn get(&self, row: usize, col: usize) -> T {
+        if row >= self.rows || col >= self.cols {
+            panic!("Index out of range");
+        }
+
+        let range = self.row_offsets[row]..self.row_offsets[row + 1];
+        match self.col_indices[range.clone()].binary_search(&col) {
+            Ok(position) => self.values[range.start + position],
+            Err(_) => T::zero(),
+        }
+    }
+
+    /// Returns row `row` as a sparse vector
+    ///
+    /// # Panics
+    /// * panics if `row` is out of range
+    pub fn row(&self, row: usize) -> SparseVec<T> {
+        if row >= self.rows {
+            panic!("Index out of range");
+        }
+
+        let range = self.row_offsets[row]..self.row_offsets[row + 1];
+        SparseVec::from_sorted(
+            self.cols,
+            self.col_indices[range.clone()].to_vec(),
+            self.values[range].to_vec(),
+        )
+    }
+
+    /// Returns the transposed matrix.
+    /// * Complexity: O(nnz + rows + cols)
+    pub fn transpose(&self) -> SparseMatrix<T> {
+        let mut row_offsets = vec![0; self.cols + 1];
+        for col in self.col_indices.iter() {
+            row_offsets[col + 1] += 1;
+        }
+        for col in 0..self.cols {
+            row_offsets[col + 1] += row_offsets[col];
+        }
+
+        // rows are visited in order, so every new row comes out sorted
+        let mut next = row_offsets.clone();
+        let mut col_indices = vec![0; self.nnz()];
+        let mut values = vec![T::zero(); self.nnz()];
+        for row in 0..self.rows {
+            for position in self.row_offsets[row]..self.row_offsets[row + 1] {
+                let col = self.col_indices[position];
+                col_indices[next[col]] = row;
+                values[next[col]] = self.values[position];
+                next[col] += 1;
+            }
+        }
+
+        SparseMatrix {
+            rows: self.cols,
+            cols: self.rows,
+            row_offsets,
+            col_indices,
+            values,
+        }
+    }
+
+    /// Returns product of the matrix and the dense vector `vector`.
+    /// * Complexity: O(nnz + rows)
+    ///
+    /// # Panics
+    /// * panics if length of `vector` is not equal to number of columns
+    pub fn multiply_dense(&self, vector: &[T]) -> Vec<T> {
+        if vector.len() != self.cols {
+            panic!("Dimensions of the matrix and the vector do not match");
+        }
+
+        (0..self.rows)
+            .map(|row| {
+                (self.row_offsets[row]..self.row_offsets[row + 1]).fold(
+                    T::zero(),
+                    |sum, position| {
+                        sum + self.values[position] * vector[self.col_indices[position]]
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Returns product of the matrix and the sparse vector `vector`.
+    /// * Complexity: O(nnz + rows + cols)
+    ///
+    /// # Panics
+    /// * panics if dimension of `vector` is not equal to number of columns
+    pub fn multiply_vec(&self, vector: &SparseVec<T>) -> SparseVec<T> {
+        if vector.dimension() != self.cols {
+            panic!("Dimensions of the matrix and the vector do not match");
+        }
+
+        let product = self.multiply_dense(&vector.to_dense());
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        for (row, value) in product.into_iter().enumerate() {
+            if value != T::zero() {
+                indices.push(row);
+                values.push(value);
+            }
+        }
+
+        SparseVec::from_sorted(self.rows, indices, values)
+    }
+
+    /// Returns product of the matrices, using Gustavson's row by row algorithm.
+    /// * Complexity: O(rows + cols + number of multiplied entry pairs)
+    ///
+    /// # Panics
+    /// * panics if number of columns of the matrix is not equal to number of rows of `other`
+    pub fn multiply(&self, other: &SparseMatrix<T>) -> SparseMatrix<T> {
+        if self.cols != other.rows {
+            panic!("Dimensions of the matrices do not match");
+        }
+
+        let mut result = SparseMatrix::init(self.rows, other.cols);
+        let mut accumulator = vec![T::zero(); other.cols];
+        let mut occupied = vec![false; other.cols];
+        let mut columns: Vec<usize> = Vec::new();
+
+        for row in 0..self.rows {
+            for position in self.row_offsets[row]..self.row_offsets[row + 1] {
+                let middle = self.col_indices[position];
+                let value = self.values[position];
+
+                for other_position in other.row_offsets[middle]..other.row_offsets[middle + 1] {
+                    let col = other.col_indices[other_position];
+                    if !occupied[col] {
+                        occupied[col] = true;
+                        columns.push(col);
+                    }
+                    accumulator[col] = accumulator[col] + value * other.values[other_position];
+                }
+            }
+
+            columns.sort_unstable();
+            for col in columns.drain(..) {
+                if accumulator[col] != T::zero() {
+                    result.col_indices.push(col);
+                    result.values.push(accumulator[col]);
+                }
+                accumulator[col] = T::zero();
+                occupied[col] = false;
+            }
+            result.row_offsets[row + 1] = result.values.len();
+        }
+
+        result
+    }
+
+    /// Returns the matrix multiplied by `factor`
+    pub fn scale(&self, factor: T) -> SparseMatrix<T> {
+        let mut result = self.clone();
+        for value in result.values.iter_mut() {
+            *value = *value * factor;
+        }
+        result.remove_zeros();
+
+        result
+    }
+
+    /// Returns dense representation of the matrix as a vector of rows
+    pub fn to_dense(&self) -> Vec<Vec<T>> {
+        (0..self.rows).map(|row| self.row(row).to_dense()).collect()
+    }
+
+    // merges entries of both matrices with `combine`, treating missing entries as zero
+    fn combine<F: Fn(T, T) -> T>(&self, other: &SparseMatrix<T>, combine: F) -> SparseMatrix<T> {
+        if self.rows != other.rows || self.cols != other.cols {
+            panic!("Dimensions of the matrices do not match");
+        }
+
+        let mut result = SparseMatrix::init(self.rows, self.cols);
+        for row in 0..self.rows {
+            let (mut i, mut j) = (self.row_offsets[row], other.row_offsets[row]);
+            let (i_end, j_end) = (self.row_offsets[row + 1], other.row_offsets[row + 1]);
+
+            while i < i_end || j < j_end {
+                let left = if i < i_end {
+                    self.col_indices[i]
+                } else {
+                    usize::MAX
+                };
+                let right = if j < j_end {
+                    other.col_indices[j]
+                } else {
+                    usize::MAX
+                };
+
+                let (col, value) = if left < right {
+                    i += 1;
+                    (left, combine(self.values[i - 1], T::zero()))
+                } else if left > right {
+                    j += 1;
+                    (right, combine(T::zero(), other.values[j - 1]))
+                } else {
+                    i += 1;
+                    j += 1;
+                    (left, combine(self.values[i - 1], other.values[j - 1]))
+                };
+
+                if value != T::zero() {
+                    result.col_indices.push(col);
+                    result.values.push(value);
+                }
+            }
+            result.row_offsets[row + 1] = result.values.len();
+        }
+
+        result
+    }
+
+    fn remove_zeros(&mut self) {
+        let mut kept = 0;
+        let mut start = 0;
+        for row in 0..self.rows {
+            let end = self.row_offsets[row + 1];
+            for position in start..end {
+                if self.values[position] != T::zero() {
+                    self.col_indices[kept] = self.col_indices[position];
+                    self.values[kept] = self.values[position];
+                    kept += 1;
+                }
+            }
+            start = end;
+            self.row_offsets[row + 1] = kept;
+        }
+        self.col_indices.truncate(kept);
+        self.values.truncate(kept);
+    }
+}
+
+impl<T: Scalar> Add for &SparseMatrix<T> {
+    type Output = SparseMatrix<T>;
+
+    fn add(self, other: &SparseMatrix<T>) -> SparseMatrix<T> {
+        self.combine(other, |a, b| a + b)
+    }
+}
+
+impl<T: Scalar> Sub for &SparseMatrix<T> {
+    type Output = SparseMatrix<T>;
+
+    fn sub(self, other: &SparseMatrix<T>) -> SparseMatrix<T> {
+        self.combine(other, |a, b| a - b)
+    }
+}
+
+impl<T: Scalar> Neg for &SparseMatrix<T> {
+    type Output = SparseMatrix<T>;
+
+    fn neg(self) -> SparseMatrix<T> {
+        let mut result = self.clone();
+        for value in result.values.iter_mut() {
+            *value = -*value;
+        }
+
+        result
+    }
+}
+
+impl<T: Scalar> Add for SparseMatrix<T> {
+    type Output = SparseMatrix<T>;
+
+    fn add(self, other: SparseMatrix<T>) -> SparseMatrix<T> {
+        &self + &other
+    }
+}
+
+impl<T: Scalar> Sub for SparseMatrix<T> {
+    type Output = SparseMatrix<T>;
+
+    fn sub(self, other: SparseMatrix<T>) -> SparseMatrix<T> {
+        &self - &other
+    }
+}
+
+impl<T: Scalar> Neg for SparseMatrix<T> {
+    type Output = SparseMatrix<T>;
+
+    fn neg(self) -> SparseMatrix<T> {
+        -&self
+    }
+}
+
+impl<T: Scalar> MemoryFootprint for SparseMatrix<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.values.len())
+            .with_vec(&self.row_offsets)
+            .with_vec(&self.col_indices)
+            .with_vec(&self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dense_multiply(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let mut result = vec![vec![0.0; b[0].len()]; a.len()];
+        for i in 0..a.len() {
+            for k in 0..b.len() {
+                for j in 0..b[0].len() {
+                    result[i][j] += a[i][k] * b[k][j];
+                }
+            }
+        }
+        result
+    }
+
+    fn pseudo_random(rows: usize, cols: usize, seed: usize) -> SparseMatrix<f64> {
+        let mut triplets = Vec::new();
+        let mut state = seed;
+        for _ in 0..(rows * cols / 3) {
+            state = (state * 1103515245 + 12345) % (1 << 31);
+            let row = state % rows;
+            state = (state * 1103515245 + 12345) % (1 << 31);
+            let col = state % cols;
+            triplets.push((row, col, (state % 7) as f64 - 3.0));
+        }
+
+        SparseMatrix::from_triplets(rows, cols, &triplets)
+    }
+
+    #[test]
+    fn math_sparse_matrix_matches_dense() {
+        let a = pseudo_random(7, 5, 1);
+        let b = pseudo_random(5, 9, 2);
+
+        assert_eq!(
+            a.multiply(&b).to_dense(),
+            dense_multiply(&a.to_dense(), &b.to_dense())
+        );
+        assert!(a.values().iter().all(|value| *value != 0.0));
+
+        let transpose = a.transpose();
+        for row in 0..a.rows() {
+            for col in 0..a.cols() {
+                assert_eq!(a.get(row, col), transpose.get(col, row));
+            }
+        }
+        assert_eq!(transpose.transpose(), a);
+
+        let x = [1.0, -2.0, 0.5, 3.0, 0.0];
+        let dense: Vec<f64> = a
+            .to_dense()
+            .iter()
+            .map(|row| row.iter().zip(x.iter()).map(|(p, q)| p * q).sum())
+            .collect();
+        assert_eq!(a.multiply_dense(&x), dense);
+        assert_eq!(a.multiply_vec(&SparseVec::from_dense(&x)).to_dense(), dense);
+    }
+
+    #[test]
+    fn math_sparse_matrix_arithmetic() {
+        let a = pseudo_random(6, 6, 3);
+        let b = pseudo_random(6, 6, 4);
+
+        let sum = (&a + &b).to_dense();
+        let difference = (&a - &b).to_dense();
+        for row in 0..6 {
+            for col in 0..6 {
+                assert_eq!(sum[row][col], a.get(row, col) + b.get(row, col));
+                assert_eq!(difference[row][col], a.get(row, col) - b.get(row, col));
+            }
+        }
+
+        assert_eq!((&a - &a).nnz(), 0);
+        assert_eq!(&a + &(-&a), SparseMatrix::init(6, 6));
+        assert_eq!(a.multiply(&SparseMatrix::identity(6)), a);
+        assert_eq!(a.scale(2.0), &a + &a);
+    }
+
+    #[test]
+    fn math_sparse_matrix_csc() {
+        let a = pseudo_random(4, 6, 5);
+        let (col_offsets, row_indices, values) = a.to_csc();
+
+        assert_eq!(col_offsets.len(), 7);
+        assert_eq!(
+            SparseMatrix::from_csc(4, 6, col_offsets, row_indices, values),
+            a
+        );
+
+        let b = SparseMatrix::from_csr(
+            a.rows(),
+            a.cols(),
+            a.row_offsets().to_vec(),
+            a.col_indices().to_vec(),
+            a.values().to_vec(),
+        );
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn math_sparse_matrix_from_triplets_sums_duplicates() {
+        let a = SparseMatrix::from_triplets(
+            2,
+            2,
+            &[(1, 1, 2.0), (0, 1, 1.0), (1, 1, -2.0), (0, 1, 1.0)],
+        );
+
+        assert_eq!(a.nnz(), 1);
+        assert_eq!(a.get(0, 1), 2.0);
+        assert_eq!(a.row(1).nnz(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid compressed sparse representation")]
+    fn math_sparse_matrix_invalid_csr() {
+        SparseMatrix::from_csr(2, 2, vec![0, 2, 1], vec![0, 1], vec![1.0, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimensions of the matrices do not match")]
+    fn math_sparse_matrix_dimension_mismatch() {
+        let a: SparseMatrix<f64> = SparseMatrix::init(2, 3);
+
+        a.multiply(&a);
+    }
+}
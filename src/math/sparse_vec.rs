@@ -0,0 +1,381 @@
+use crate::math::Scalar;
+use crate::util::{Footprint, MemoryFootprint};
+use std::ops::{Add, Neg, Sub};
+
+/// A sparse vector stores only its non-zero entries, sorted by index.
+/// Zeros are never stored, so two equal vectors have identical representations.
+///
+/// # Examples
+/// ```
+/// use rudac::math::SparseVec;
+///
+/// let a = SparseVec::from_pairs(1000, &[(3, 1.0), (500, 2.0)]);
+/// let b = SparseVec::from_pairs(1000, &[(500, 4.0), (999, 1.0)]);
+///
+/// assert_eq!(a.nnz(), 2);
+/// assert_eq!(a.dot(&b), 8.0);
+///
+/// let sum = &a + &b;
+/// assert_eq!(sum.get(500), 6.0);
+/// assert_eq!(sum.nnz(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseVec<T: Scalar> {
+    dimension: usize,
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Scalar> SparseVec<T> {
+    /// Initializes a zero vector
+    ///
+    /// # Arguments
+    /// * `dimension`: dimension of the vector
+    pub fn init(dimension: usize) -> SparseVec<T> {
+        SparseVec {
+            dimension,
+            indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Creates a vector from (index, value) pairs. Values of repeated indices are added up
+    ///
+    /// # Arguments
+    /// * `dimension`: dimension of the vector
+    /// * `pairs`: entries of the vector
+    ///
+    /// # Panics
+    /// * panics if any index is out of range
+    pub fn from_pairs(dimension: usize, pairs: &[(usize, T)]) -> SparseVec<T> {
+        let mut pairs = pairs.to_vec();
+        pairs.sort_by_key(|(index, _)| *index);
+
+        let mut vector = SparseVec::init(dimension);
+        for (index, value) in pairs {
+            if index >= dimension {
+                panic!("Index out of range");
+            }
+
+            if vector.indices.last() == Some(&index) {
+                let last = vector.values.len() - 1;
+                vector.values[last] = vector.values[last] + value;
+            } else {
+                vector.indices.push(index);
+                vector.values.push(value);
+            }
+        }
+        vector.remove_zeros();
+
+        vector
+    }
+
+    /// Creates a vector from its dense representation
+    ///
+    /// # Arguments
+    /// * `values`: all values of the vector
+    pub fn from_dense(values: &[T]) -> SparseVec<T> {
+        let mut vector = SparseVec::init(values.len());
+        for (index, value) in values.iter().enumerate() {
+            if *value != T::zero() {
+                vector.indices.push(index);
+                vector.values.push(*value);
+            }
+        }
+
+        vector
+    }
+
+    // creates a vector from sorted, distinct indices and non-zero values
+    pub(crate) fn from_sorted(
+        dimension: usize,
+        indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> SparseVec<T> {
+        SparseVec {
+            dimension,
+            indices,
+            values,
+        }
+    }
+
+    /// Returns dimension of the vector
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns number of non-zero entries
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns indices of the non-zero entries in ascending order
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns values of the non-zero entries, in the order of `indices`
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns value at `index`.
+    /// * Complexity: O(log nnz)
+    ///
+    /// # Arguments
+    /// * `index`: index of the entry
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn get(&self, index: usize) -> T {
+        if index >= self.dimension {
+            panic!("Index out of range");
+        }
+
+        match self.indices.binary_search(&index) {
+            Ok(position) => self.values[position],
+            Err(_) => T::zero(),
+        }
+    }
+
+    /// Sets value at `index` to `value`.
+    /// * Complexity: O(nnz) in the worst case, O(log nnz) if the entry is already non-zero and stays so
+    ///
+    /// # Arguments
+    /// * `index`: index of the entry
+    /// * `value`: new value
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn set(&mut self, index: usize, value: T) {
+        if index >= self.dimension {
+            panic!("Index out of range");
+        }
+
+        match self.indices.binary_search(&index) {
+            Ok(position) if value == T::zero() => {
+                self.indices.remove(position);
+                self.values.remove(position);
+            }
+            Ok(position) => self.values[position] = value,
+            Err(_) if value == T::zero() => (),
+            Err(position) => {
+                self.indices.insert(position, index);
+                self.values.insert(position, value);
+            }
+        }
+    }
+
+    /// Returns dot product of the vectors.
+    /// * Complexity: O(nnz of both vectors)
+    ///
+    /// # Arguments
+    /// * `other`: the other vector
+    ///
+    /// # Panics
+    /// * panics if dimensions of the vectors do not match
+    pub fn dot(&self, other: &SparseVec<T>) -> T {
+        self.check_dimension(other);
+
+        let mut result = T::zero();
+        let (mut i, mut j) = (0, 0);
+        while i < self.indices.len() && j < other.indices.len() {
+            if self.indices[i] < other.indices[j] {
+                i += 1;
+            } else if self.indices[i] > other.indices[j] {
+                j += 1;
+            } else {
+                result = result + self.values[i] * other.values[j];
+                i += 1;
+                j += 1;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the vector multiplied by `factor`
+    ///
+    /// # Arguments
+    /// * `factor`: the scalar factor
+    pub fn scale(&self, factor: T) -> SparseVec<T> {
+        let mut result = SparseVec::from_sorted(
+            self.dimension,
+            self.indices.clone(),
+            self.values.iter().map(|value| *value * factor).collect(),
+        );
+        result.remove_zeros();
+
+        result
+    }
+
+    /// Returns dense representation of the vector
+    pub fn to_dense(&self) -> Vec<T> {
+        let mut dense = vec![T::zero(); self.dimension];
+        for (index, value) in self.indices.iter().zip(self.values.iter()) {
+            dense[*index] = *value;
+        }
+
+        dense
+    }
+
+    // merges entries of both vectors with `combine`, treating missing entries as zero
+    fn combine<F: Fn(T, T) -> T>(&self, other: &SparseVec<T>, combine: F) -> SparseVec<T> {
+        self.check_dimension(other);
+
+        let mut result = SparseVec::init(self.dimension);
+        let (mut i, mut j) = (0, 0);
+        while i < self.indices.len() || j < other.indices.len() {
+            let left = self.indices.get(i).copied().unwrap_or(usize::MAX);
+            let right = other.indices.get(j).copied().unwrap_or(usize::MAX);
+
+            let (index, value) = if left < right {
+                i += 1;
+                (left, combine(self.values[i - 1], T::zero()))
+            } else if left > right {
+                j += 1;
+                (right, combine(T::zero(), other.values[j - 1]))
+            } else {
+                i += 1;
+                j += 1;
+                (left, combine(self.values[i - 1], other.values[j - 1]))
+            };
+
+            if value != T::zero() {
+                result.indices.push(index);
+                result.values.push(value);
+            }
+        }
+
+        result
+    }
+
+    fn remove_zeros(&mut self) {
+        let mut kept = 0;
+        for position in 0..self.values.len() {
+            if self.values[position] != T::zero() {
+                self.indices[kept] = self.indices[position];
+                self.values[kept] = self.values[position];
+                kept += 1;
+            }
+        }
+        self.indices.truncate(kept);
+        self.values.truncate(kept);
+    }
+
+    fn check_dimension(&self, other: &SparseVec<T>) {
+        if self.dimension != other.dimension {
+            panic!("Dimensions of the vectors do not match");
+        }
+    }
+}
+
+impl<T: Scalar> Add for &SparseVec<T> {
+    type Output = SparseVec<T>;
+
+    fn add(self, other: &SparseVec<T>) -> SparseVec<T> {
+        self.combine(other, |a, b| a + b)
+    }
+}
+
+impl<T: Scalar> Sub for &SparseVec<T> {
+    type Output = SparseVec<T>;
+
+    fn sub(self, other: &SparseVec<T>) -> SparseVec<T> {
+        self.combine(other, |a, b| a - b)
+    }
+}
+
+impl<T: Scalar> Neg for &SparseVec<T> {
+    type Output = SparseVec<T>;
+
+    fn neg(self) -> SparseVec<T> {
+        SparseVec::from_sorted(
+            self.dimension,
+            self.indices.clone(),
+            self.values.iter().map(|value| -*value).collect(),
+        )
+    }
+}
+
+impl<T: Scalar> Add for SparseVec<T> {
+    type Output = SparseVec<T>;
+
+    fn add(self, other: SparseVec<T>) -> SparseVec<T> {
+        &self + &other
+    }
+}
+
+impl<T: Scalar> Sub for SparseVec<T> {
+    type Output = SparseVec<T>;
+
+    fn sub(self, other: SparseVec<T>) -> SparseVec<T> {
+        &self - &other
+    }
+}
+
+impl<T: Scalar> Neg for SparseVec<T> {
+    type Output = SparseVec<T>;
+
+    fn neg(self) -> SparseVec<T> {
+        -&self
+    }
+}
+
+impl<T: Scalar> MemoryFootprint for SparseVec<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.values.len())
+            .with_vec(&self.indices)
+            .with_vec(&self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_sparse_vec_from_pairs() {
+        let vector = SparseVec::from_pairs(10, &[(7, 1.0), (2, 3.0), (7, -1.0), (2, 1.0)]);
+
+        assert_eq!(vector.indices(), &[2]);
+        assert_eq!(vector.values(), &[4.0]);
+        assert_eq!(vector.get(7), 0.0);
+        assert_eq!(vector, SparseVec::from_dense(&vector.to_dense()));
+    }
+
+    #[test]
+    fn math_sparse_vec_set() {
+        let mut vector = SparseVec::init(5);
+        vector.set(3, 2.0);
+        vector.set(1, 1.0);
+        vector.set(3, 0.0);
+        vector.set(4, 0.0);
+
+        assert_eq!(vector.to_dense(), vec![0.0, 1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(vector.nnz(), 1);
+    }
+
+    #[test]
+    fn math_sparse_vec_arithmetic() {
+        let a = SparseVec::from_dense(&[1.0, 0.0, 2.0, 0.0, -1.0]);
+        let b = SparseVec::from_dense(&[0.0, 3.0, 2.0, 0.0, 1.0]);
+
+        assert_eq!((&a + &b).to_dense(), vec![1.0, 3.0, 4.0, 0.0, 0.0]);
+        assert_eq!((&a + &b).nnz(), 3);
+        assert_eq!((&a - &b).to_dense(), vec![1.0, -3.0, 0.0, 0.0, -2.0]);
+        assert_eq!((-a.clone()).to_dense(), vec![-1.0, 0.0, -2.0, 0.0, 1.0]);
+        assert_eq!(a.dot(&b), 3.0);
+        assert_eq!(a.scale(0.0).nnz(), 0);
+        assert_eq!(a.scale(2.0).get(2), 4.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimensions of the vectors do not match")]
+    fn math_sparse_vec_dimension_mismatch() {
+        let a: SparseVec<f64> = SparseVec::init(3);
+        let b = SparseVec::init(4);
+
+        a.dot(&b);
+    }
+}
@@ -0,0 +1,325 @@
+use crate::spatial::MinHashFamily;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// MinHash signature of a set. It is generated by `MinHash` and estimates Jaccard similarity to other signatures
+/// of the same `MinHash`, no matter how large the sets are.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MinHashSignature {
+    seed: u64,
+    values: Vec<u64>,
+}
+
+impl MinHashSignature {
+    /// Returns values of the signature
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+
+    /// Returns number of values of the signature
+    pub fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if the signature belongs to an empty set
+    pub fn is_empty(&self) -> bool {
+        self.values.iter().all(|value| *value == u64::MAX)
+    }
+
+    /// Merges `other` into the signature, so that it becomes signature of the union of both sets
+    ///
+    /// # Arguments
+    /// * `other`: signature to be merged
+    ///
+    /// # Panics
+    /// * panics if signatures are generated by `MinHash`es with different parameters
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::probabilistic::MinHash;
+    ///
+    /// let minhash = MinHash::init(64, 0);
+    ///
+    /// let mut a = minhash.signature(0..50);
+    /// let b = minhash.signature(50..100);
+    ///
+    /// a.merge(&b);
+    ///
+    /// assert_eq!(a, minhash.signature(0..100));
+    /// ```
+    pub fn merge(&mut self, other: &MinHashSignature) {
+        self.check_compatible(other);
+
+        for (value, other) in self.values.iter_mut().zip(other.values.iter()) {
+            *value = std::cmp::min(*value, *other);
+        }
+    }
+
+    /// Returns estimated Jaccard similarity `|A ∩ B| / |A ∪ B|` of the sets, which is the fraction of equal values.
+    /// Standard error of the estimate is about `1 / sqrt(size)`
+    ///
+    /// # Arguments
+    /// * `other`: signature of the other set
+    ///
+    /// # Panics
+    /// * panics if signatures are generated by `MinHash`es with different parameters
+    pub fn jaccard(&self, other: &MinHashSignature) -> f64 {
+        self.check_compatible(other);
+
+        if self.values.is_empty() {
+            return 1.0;
+        }
+
+        let equal = self
+            .values
+            .iter()
+            .zip(other.values.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+
+        equal as f64 / self.values.len() as f64
+    }
+
+    fn check_compatible(&self, other: &MinHashSignature) {
+        if self.seed != other.seed || self.values.len() != other.values.len() {
+            panic!("Signatures must be generated by MinHash with the same parameters");
+        }
+    }
+}
+
+/// MinHash generates compact signatures of sets whose similarity estimates Jaccard similarity of the sets.
+/// Signatures of large sets can be compared, merged and bucketed with banded LSH to find near-duplicates
+/// without comparing all pairs of sets.
+///
+/// # Examples
+/// ```
+/// use rudac::probabilistic::MinHash;
+///
+/// let minhash = MinHash::init(256, 42);
+///
+/// let a = minhash.signature("the quick brown fox jumps over the lazy dog".split(' '));
+/// let b = minhash.signature("the quick brown fox jumps over the lazy cat".split(' '));
+///
+/// // exact similarity is 7 / 9
+/// assert!((a.jaccard(&b) - 7.0 / 9.0).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinHash {
+    seed: u64,
+    size: usize,
+    family: MinHashFamily,
+}
+
+impl MinHash {
+    /// Creates a MinHash generating signatures with `size` values.
+    /// Signatures are comparable only if they are generated with the same size and seed
+    ///
+    /// # Arguments
+    /// * `size`: number of values of signatures
+    /// * `seed`: seed of the random hash functions
+    pub fn init(size: usize, seed: u64) -> MinHash {
+        MinHash {
+            seed,
+            size,
+            family: MinHashFamily::init(size, seed),
+        }
+    }
+
+    /// Returns number of values of generated signatures
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns signature of the empty set
+    pub fn empty_signature(&self) -> MinHashSignature {
+        MinHashSignature {
+            seed: self.seed,
+            values: vec![u64::MAX; self.size()],
+        }
+    }
+
+    /// Returns signature of the set of `elements`. Repeated elements do not change the signature
+    ///
+    /// # Arguments
+    /// * `elements`: elements of the set
+    pub fn signature<I, E>(&self, elements: I) -> MinHashSignature
+    where
+        I: IntoIterator<Item = E>,
+        E: Hash,
+    {
+        let mut signature = self.empty_signature();
+        for element in elements {
+            self.update(&mut signature, &element);
+        }
+
+        signature
+    }
+
+    /// Adds `element` to the set of `signature`
+    ///
+    /// # Arguments
+    /// * `signature`: signature to be updated
+    /// * `element`: new element of the set
+    ///
+    /// # Panics
+    /// * panics if `signature` is not generated by this `MinHash` or one with the same parameters
+    pub fn update<E: Hash + ?Sized>(&self, signature: &mut MinHashSignature, element: &E) {
+        if signature.seed != self.seed || signature.values.len() != self.size() {
+            panic!("Signatures must be generated by MinHash with the same parameters");
+        }
+
+        self.family.update(&mut signature.values, element);
+    }
+
+    /// Returns candidate pairs `(i, j)` with `i < j` of signatures using banded LSH, sorted by indices.
+    /// Signatures are split into `bands` bands and two signatures are candidates if they are equal in at least one band.
+    /// Pairs with Jaccard similarity above roughly `MinHash::threshold(bands, rows)` are likely to be reported.
+    ///
+    /// # Arguments
+    /// * `signatures`: signatures generated with the same parameters
+    /// * `bands`: number of bands
+    ///
+    /// # Panics
+    /// * panics if `bands` is zero or size of signatures is not a multiple of `bands`
+    /// * panics if signatures are generated by `MinHash`es with different parameters
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::probabilistic::MinHash;
+    ///
+    /// let minhash = MinHash::init(128, 7);
+    /// let signatures = vec![
+    ///     minhash.signature(0..1000),
+    ///     minhash.signature(5000..6000),
+    ///     minhash.signature(10..1000),
+    /// ];
+    ///
+    /// assert_eq!(MinHash::candidates(&signatures, 32), vec![(0, 2)]);
+    /// ```
+    pub fn candidates(signatures: &[MinHashSignature], bands: usize) -> Vec<(usize, usize)> {
+        let size = signatures.first().map_or(0, |signature| signature.size());
+        if bands == 0 || !size.is_multiple_of(bands) {
+            panic!("Size of signatures must be a positive multiple of number of bands");
+        }
+        for signature in signatures.iter() {
+            signatures[0].check_compatible(signature);
+        }
+
+        let rows = size / bands;
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (index, signature) in signatures.iter().enumerate() {
+            // empty sets have no meaningful similarity
+            if signature.is_empty() {
+                continue;
+            }
+
+            for (band, values) in signature.values.chunks(rows).enumerate() {
+                let mut hasher = DefaultHasher::new();
+                band.hash(&mut hasher);
+                values.hash(&mut hasher);
+                buckets.entry(hasher.finish()).or_default().push(index);
+            }
+        }
+
+        let mut pairs = HashSet::new();
+        for bucket in buckets.values() {
+            for (position, &i) in bucket.iter().enumerate() {
+                for &j in &bucket[position + 1..] {
+                    pairs.insert((i, j));
+                }
+            }
+        }
+
+        let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+
+        pairs
+    }
+
+    /// Returns the similarity at which a pair becomes a candidate with probability about 1/2
+    /// in banded LSH with `bands` bands of `rows` rows, which is `(1 / bands) ^ (1 / rows)`
+    ///
+    /// # Arguments
+    /// * `bands`: number of bands
+    /// * `rows`: number of rows in every band
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::probabilistic::MinHash;
+    ///
+    /// assert!((MinHash::threshold(20, 5) - 0.549).abs() < 1e-3);
+    /// ```
+    pub fn threshold(bands: usize, rows: usize) -> f64 {
+        (1.0 / bands as f64).powf(1.0 / rows as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probabilistic_minhash_estimate_accuracy() {
+        let minhash = MinHash::init(512, 3);
+
+        for overlap in [0u32, 250, 500, 750, 1000].iter() {
+            let a = minhash.signature(0..1000u32);
+            let b = minhash.signature(1000 - overlap..2000 - overlap);
+            let exact = *overlap as f64 / (2000 - overlap) as f64;
+
+            assert!((a.jaccard(&b) - exact).abs() < 0.07);
+        }
+    }
+
+    #[test]
+    fn probabilistic_minhash_update_and_empty() {
+        let minhash = MinHash::init(16, 0);
+        let mut signature = minhash.empty_signature();
+
+        assert!(signature.is_empty());
+
+        for word in ["a", "b", "c", "a"].iter() {
+            minhash.update(&mut signature, *word);
+        }
+
+        assert!(!signature.is_empty());
+        assert_eq!(signature, minhash.signature(vec!["c", "b", "a"]));
+        assert_eq!(signature.size(), 16);
+        assert_eq!(signature.jaccard(&signature.clone()), 1.0);
+    }
+
+    #[test]
+    fn probabilistic_minhash_candidates() {
+        let minhash = MinHash::init(100, 9);
+        let signatures: Vec<MinHashSignature> = vec![
+            minhash.signature(0..500),
+            minhash.signature(1000..1500),
+            minhash.signature(0..490),
+            minhash.empty_signature(),
+            minhash.empty_signature(),
+            minhash.signature(1010..1500),
+            minhash.signature(3000..3500),
+        ];
+
+        assert_eq!(MinHash::candidates(&signatures, 20), vec![(0, 2), (1, 5)]);
+        assert!(MinHash::candidates(&[], 1).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Signatures must be generated by MinHash with the same parameters")]
+    fn probabilistic_minhash_incompatible_merge() {
+        let mut a = MinHash::init(16, 0).signature(0..10);
+        let b = MinHash::init(16, 1).signature(0..10);
+
+        a.merge(&b);
+    }
+
+    #[test]
+    #[should_panic(expected = "Size of signatures must be a positive multiple of number of bands")]
+    fn probabilistic_minhash_candidates_bands() {
+        let minhash = MinHash::init(10, 0);
+
+        MinHash::candidates(&[minhash.signature(0..10)], 3);
+    }
+}
@@ -0,0 +1,7 @@
+mod minhash;
+mod space_saving;
+
+pub use minhash::MinHash;
+pub use minhash::MinHashSignature;
+pub use space_saving::Counter;
+pub use space_saving::SpaceSaving;
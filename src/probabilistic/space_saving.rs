@@ -0,0 +1,461 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+/// A monitored item of `SpaceSaving`. The true frequency of the item lies in [`count - error`, `count`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T> {
+    /// the monitored item
+    pub item: T,
+
+    /// overestimated frequency of the item
+    pub count: u64,
+
+    /// largest possible overestimation of `count`
+    pub error: u64,
+}
+
+impl<T> Counter<T> {
+    /// Returns guaranteed lower bound of the frequency of the item
+    pub fn lower_bound(&self) -> u64 {
+        self.count - self.error
+    }
+}
+
+/// Space-Saving summary of a stream keeps the most frequent items using a fixed number of counters.
+/// When a new item arrives and all counters are in use, the counter with the smallest count is given to the new item
+/// and its old count is recorded as the error of the new item.
+///
+/// With `capacity` counters over a stream of `n` items, every item with frequency greater than `n / capacity`
+/// is monitored and every count overestimates the frequency by at most `n / capacity`.
+///
+/// # Examples
+/// ```
+/// use rudac::probabilistic::SpaceSaving;
+///
+/// let mut summary = SpaceSaving::init(3);
+///
+/// for word in "a b a c a b d a b e a b".split(' ') {
+///     summary.insert(word);
+/// }
+///
+/// let top = summary.top(2);
+/// assert_eq!(top[0].item, "a");
+/// assert_eq!(top[1].item, "b");
+///
+/// // true frequency of "a" is 5
+/// let counter = summary.get(&"a").unwrap();
+/// assert!(counter.lower_bound() <= 5 && 5 <= counter.count);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpaceSaving<T>
+where
+    T: Hash + Eq + Clone,
+{
+    capacity: usize,
+
+    // total number of items in the stream
+    total: u64,
+
+    counters: Vec<Counter<T>>,
+
+    // item -> index of its counter
+    index: HashMap<T, usize>,
+
+    // (count, index of the counter), the smallest counter is the first one
+    order: BTreeSet<(u64, usize)>,
+}
+
+impl<T> SpaceSaving<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Initializes an empty summary
+    ///
+    /// # Arguments
+    /// * `capacity`: number of counters
+    ///
+    /// # Panics
+    /// * panics if `capacity` is zero
+    pub fn init(capacity: usize) -> SpaceSaving<T> {
+        if capacity == 0 {
+            panic!("Capacity must be greater than zero");
+        }
+
+        SpaceSaving {
+            capacity,
+            total: 0,
+            counters: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+            order: BTreeSet::new(),
+        }
+    }
+
+    /// Returns number of counters of the summary
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns number of monitored items
+    pub fn size(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Returns true if no item is monitored
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Returns total number of items in the stream
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the largest possible frequency of an item that is not monitored.
+    /// It is zero until all counters are in use and it never exceeds `total / capacity`
+    pub fn max_error(&self) -> u64 {
+        if self.counters.len() < self.capacity {
+            return 0;
+        }
+
+        self.order.iter().next().map_or(0, |(count, _)| *count)
+    }
+
+    /// Adds an occurrence of `item` to the stream.
+    /// * Complexity: O(log capacity)
+    ///
+    /// # Arguments
+    /// * `item`: the item
+    pub fn insert(&mut self, item: T) {
+        self.insert_count(item, 1);
+    }
+
+    /// Adds `count` occurrences of `item` to the stream.
+    /// * Complexity: O(log capacity)
+    ///
+    /// # Arguments
+    /// * `item`: the item
+    /// * `count`: number of occurrences
+    pub fn insert_count(&mut self, item: T, count: u64) {
+        if count == 0 {
+            return;
+        }
+        self.total += count;
+
+        if let Some(&i) = self.index.get(&item) {
+            self.set_count(i, self.counters[i].count + count);
+        } else if self.counters.len() < self.capacity {
+            self.push(Counter {
+                item,
+                count,
+                error: 0,
+            });
+        } else {
+            // the smallest counter is given to the new item
+            let (minimum, i) = *self.order.iter().next().unwrap();
+            self.index.remove(&self.counters[i].item);
+            self.index.insert(item.clone(), i);
+            self.counters[i].item = item;
+            self.counters[i].error = minimum;
+            self.set_count(i, minimum + count);
+        }
+    }
+
+    /// Returns counter of `item`, or `None` if the item is not monitored
+    ///
+    /// # Arguments
+    /// * `item`: the item
+    pub fn get(&self, item: &T) -> Option<&Counter<T>> {
+        self.index.get(item).map(|i| &self.counters[*i])
+    }
+
+    /// Returns an upper bound of the frequency of `item`
+    ///
+    /// # Arguments
+    /// * `item`: the item
+    pub fn estimate(&self, item: &T) -> u64 {
+        match self.get(item) {
+            Some(counter) => counter.count,
+            None => self.max_error(),
+        }
+    }
+
+    /// Returns at most `k` counters with the largest counts, sorted in descending order of count
+    ///
+    /// # Arguments
+    /// * `k`: number of counters
+    pub fn top(&self, k: usize) -> Vec<&Counter<T>> {
+        self.order
+            .iter()
+            .rev()
+            .take(k)
+            .map(|(_, i)| &self.counters[*i])
+            .collect()
+    }
+
+    /// Returns counters of items that may occur more than `fraction * total` times, sorted in descending order of count.
+    /// Every item that actually does is among them. Items whose `lower_bound` exceeds the threshold are guaranteed heavy hitters
+    ///
+    /// # Arguments
+    /// * `fraction`: the frequency threshold as a fraction of the stream
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::probabilistic::SpaceSaving;
+    ///
+    /// let mut summary = SpaceSaving::init(10);
+    /// for i in 0..1000u32 {
+    ///     summary.insert(if i % 3 == 0 { 0 } else { i });
+    /// }
+    ///
+    /// let heavy = summary.heavy_hitters(0.2);
+    ///
+    /// assert_eq!(heavy.len(), 1);
+    /// assert_eq!(heavy[0].item, 0);
+    /// ```
+    pub fn heavy_hitters(&self, fraction: f64) -> Vec<&Counter<T>> {
+        let threshold = fraction * self.total as f64;
+
+        self.order
+            .iter()
+            .rev()
+            .map(|(_, i)| &self.counters[*i])
+            .take_while(|counter| counter.count as f64 > threshold)
+            .collect()
+    }
+
+    /// Merges summary of another stream into this summary, so that it summarizes both streams.
+    /// Error bounds of both summaries add up and the capacity of this summary is kept
+    ///
+    /// # Arguments
+    /// * `other`: summary to be merged
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::probabilistic::SpaceSaving;
+    ///
+    /// let mut a = SpaceSaving::init(4);
+    /// let mut b = SpaceSaving::init(4);
+    ///
+    /// a.insert_count("x", 10);
+    /// a.insert_count("y", 3);
+    /// b.insert_count("y", 8);
+    /// b.insert_count("z", 1);
+    ///
+    /// a.merge(&b);
+    ///
+    /// assert_eq!(a.total(), 22);
+    /// assert_eq!(a.top(1)[0].item, "y");
+    /// assert_eq!(a.estimate(&"y"), 11);
+    /// ```
+    pub fn merge(&mut self, other: &SpaceSaving<T>) {
+        let own_error = self.max_error();
+        let other_error = other.max_error();
+
+        // an item missing from a summary may still have occurred up to its max error times
+        let mut merged: HashMap<T, (u64, u64)> = HashMap::new();
+        for counter in self.counters.iter() {
+            merged.insert(
+                counter.item.clone(),
+                (counter.count + other_error, counter.error + other_error),
+            );
+        }
+        for counter in other.counters.iter() {
+            let entry = merged
+                .entry(counter.item.clone())
+                .or_insert((own_error, own_error));
+            if self.index.contains_key(&counter.item) {
+                entry.0 -= other_error;
+                entry.1 -= other_error;
+            }
+            entry.0 += counter.count;
+            entry.1 += counter.error;
+        }
+
+        let mut counters: Vec<Counter<T>> = merged
+            .into_iter()
+            .map(|(item, (count, error))| Counter { item, count, error })
+            .collect();
+        if counters.len() > self.capacity {
+            counters.select_nth_unstable_by(self.capacity - 1, |a, b| b.count.cmp(&a.count));
+            counters.truncate(self.capacity);
+        }
+
+        self.total += other.total;
+        self.counters.clear();
+        self.index.clear();
+        self.order.clear();
+        for counter in counters {
+            self.push(counter);
+        }
+    }
+
+    /// Removes all counters
+    pub fn clear(&mut self) {
+        self.total = 0;
+        self.counters.clear();
+        self.index.clear();
+        self.order.clear();
+    }
+
+    fn push(&mut self, counter: Counter<T>) {
+        let i = self.counters.len();
+        self.index.insert(counter.item.clone(), i);
+        self.order.insert((counter.count, i));
+        self.counters.push(counter);
+    }
+
+    fn set_count(&mut self, i: usize, count: u64) {
+        self.order.remove(&(self.counters[i].count, i));
+        self.counters[i].count = count;
+        self.order.insert((count, i));
+    }
+}
+
+impl<T> MemoryFootprint for SpaceSaving<T>
+where
+    T: Hash + Eq + Clone,
+{
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<Counter<T>>(self.counters.len())
+            .with_vec(&self.counters)
+            .with_hash_map(&self.index)
+            .with_btree_set(&self.order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    // stream with a skewed distribution: item i occurs about 1 / (i + 1) as often as item 0
+    fn zipf_stream(length: usize, items: u64, seed: u64) -> Vec<u64> {
+        let mut rng = SplitMix64::init(seed);
+        let weights: Vec<f64> = (0..items).map(|i| 1.0 / (i + 1) as f64).collect();
+        let total: f64 = weights.iter().sum();
+
+        (0..length)
+            .map(|_| {
+                let mut u = rng.next_f64() * total;
+                let mut item = 0;
+                while item + 1 < items as usize && u >= weights[item] {
+                    u -= weights[item];
+                    item += 1;
+                }
+                item as u64
+            })
+            .collect()
+    }
+
+    fn exact(stream: &[u64]) -> HashMap<u64, u64> {
+        let mut counts = HashMap::new();
+        for item in stream {
+            *counts.entry(*item).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn check_bounds(summary: &SpaceSaving<u64>, counts: &HashMap<u64, u64>) {
+        let bound = summary.total() / summary.capacity() as u64;
+
+        for (item, count) in counts.iter() {
+            match summary.get(item) {
+                Some(counter) => {
+                    assert!(counter.lower_bound() <= *count && *count <= counter.count);
+                    assert!(counter.error <= bound);
+                }
+                None => assert!(*count <= summary.max_error()),
+            }
+        }
+        assert!(summary.max_error() <= bound);
+    }
+
+    #[test]
+    fn probabilistic_space_saving_bounds() {
+        let stream = zipf_stream(20000, 500, 1);
+        let counts = exact(&stream);
+
+        let mut summary = SpaceSaving::init(50);
+        for item in stream.iter() {
+            summary.insert(*item);
+        }
+
+        assert_eq!(summary.total(), 20000);
+        assert_eq!(summary.size(), 50);
+        check_bounds(&summary, &counts);
+
+        let top: Vec<u64> = summary.top(3).iter().map(|c| c.item).collect();
+        assert_eq!(top, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn probabilistic_space_saving_exact_when_not_full() {
+        let mut summary = SpaceSaving::init(10);
+        for i in 0..100u64 {
+            summary.insert(i % 5);
+        }
+
+        assert_eq!(summary.max_error(), 0);
+        for i in 0..5 {
+            let counter = summary.get(&i).unwrap();
+            assert_eq!(counter.count, 20);
+            assert_eq!(counter.error, 0);
+        }
+        assert_eq!(summary.estimate(&7), 0);
+    }
+
+    #[test]
+    fn probabilistic_space_saving_merge() {
+        let first = zipf_stream(10000, 300, 2);
+        let second = zipf_stream(10000, 300, 3);
+        let counts = exact(
+            &first
+                .iter()
+                .chain(second.iter())
+                .cloned()
+                .collect::<Vec<u64>>(),
+        );
+
+        let mut a = SpaceSaving::init(40);
+        let mut b = SpaceSaving::init(40);
+        for item in first.iter() {
+            a.insert(*item);
+        }
+        for item in second.iter() {
+            b.insert(*item);
+        }
+
+        a.merge(&b);
+
+        assert_eq!(a.total(), 20000);
+        assert_eq!(a.size(), 40);
+        assert_eq!(a.top(1)[0].item, 0);
+        for (item, count) in counts.iter() {
+            match a.get(item) {
+                Some(counter) => {
+                    assert!(counter.lower_bound() <= *count && *count <= counter.count)
+                }
+                None => assert!(*count <= a.max_error() + b.max_error()),
+            }
+        }
+    }
+
+    #[test]
+    fn probabilistic_space_saving_clear() {
+        let mut summary = SpaceSaving::init(2);
+        summary.insert_count('a', 3);
+        summary.insert_count('b', 0);
+        assert_eq!(summary.size(), 1);
+
+        summary.clear();
+        assert!(summary.is_empty());
+        assert_eq!(summary.total(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Capacity must be greater than zero")]
+    fn probabilistic_space_saving_zero_capacity() {
+        SpaceSaving::<u32>::init(0);
+    }
+}
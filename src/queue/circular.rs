@@ -1,3 +1,10 @@
+#[cfg(feature = "serde")]
+use crate::util::serialize::{deserialize_items, serialize_items};
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
 /// A circular buffer, circular queue, ring buffer is a data structure that uses a single, fixed-size buffer as if it were connected end-to-end.
 /// This structure lends itself easily to buffering data streams.
 ///
@@ -13,7 +20,7 @@
 /// }
 /// ```
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Circular<T> {
     front_index: usize,
     rear_index: usize,
@@ -37,10 +44,10 @@ impl<T> Circular<T> {
         Circular {
             front_index: 0,
             rear_index: 0,
-            internal_vec: Vec::with_capacity(std::cmp::max(capacity + 1, 1)),
+            internal_vec: Vec::with_capacity(core::cmp::max(capacity + 1, 1)),
             size: 0,
             push_enabled: true,
-            capacity: std::cmp::max(capacity + 1, 1),
+            capacity: core::cmp::max(capacity + 1, 1),
         }
     }
 
@@ -220,7 +227,7 @@ impl<T> Circular<T> {
     }
 }
 
-impl<T> std::ops::Index<usize> for Circular<T> {
+impl<T> core::ops::Index<usize> for Circular<T> {
     type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -233,7 +240,7 @@ impl<T> std::ops::Index<usize> for Circular<T> {
     }
 }
 
-impl<T> std::ops::IndexMut<usize> for Circular<T> {
+impl<T> core::ops::IndexMut<usize> for Circular<T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         if index >= self.size() {
             panic!("index out of bounds");
@@ -249,7 +256,7 @@ pub struct CircularIterator<'a, T> {
     index: usize,
 }
 
-impl<'a, T> std::iter::IntoIterator for &'a Circular<T> {
+impl<'a, T> core::iter::IntoIterator for &'a Circular<T> {
     type Item = &'a T;
     type IntoIter = CircularIterator<'a, T>;
 
@@ -261,7 +268,7 @@ impl<'a, T> std::iter::IntoIterator for &'a Circular<T> {
     }
 }
 
-impl<'a, T> std::iter::Iterator for CircularIterator<'a, T> {
+impl<'a, T> core::iter::Iterator for CircularIterator<'a, T> {
     type Item = &'a T;
     fn next(&mut self) -> Option<&'a T> {
         if self.index == self.vec_circular.rear_index || self.vec_circular.empty() {
@@ -274,6 +281,57 @@ impl<'a, T> std::iter::Iterator for CircularIterator<'a, T> {
     }
 }
 
+// queues are equal when they have the same capacity and the same items from front to rear
+impl<T: PartialEq> PartialEq for Circular<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.capacity == other.capacity
+            && self.size == other.size
+            && (0..self.size).all(|index| self[index] == other[index])
+    }
+}
+
+impl<T: Eq> Eq for Circular<T> {}
+
+impl<T> MemoryFootprint for Circular<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.size).with_vec(&self.internal_vec)
+    }
+}
+
+// capacity of the queue as given to `new` and its items from front to rear
+#[cfg(feature = "serde")]
+const FIELDS: &[&str; 2] = &["capacity", "items"];
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Circular<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items: Vec<&T> = (0..self.size).map(|index| &self[index]).collect();
+
+        serialize_items(serializer, "Circular", FIELDS, self.capacity - 1, &items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Circular<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (capacity, items): (usize, Vec<T>) =
+            deserialize_items(deserializer, "Circular", FIELDS)?;
+        if items.len() > capacity {
+            return Err(D::Error::invalid_length(
+                items.len(),
+                &"at most as many items as the capacity",
+            ));
+        }
+
+        let mut circular = Circular::new(capacity);
+        for item in items {
+            circular.enqueue(item);
+        }
+
+        Ok(circular)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -754,4 +812,41 @@ mod tests {
             None => panic!("Data must not be None"),
         }
     }
+
+    #[test]
+    fn clone_and_eq() {
+        let mut circular_buffer: Circular<usize> = Circular::new(3);
+        for item in 0..5 {
+            circular_buffer.enqueue(item);
+        }
+
+        let mut copy = circular_buffer.clone();
+        assert!(copy == circular_buffer);
+        assert_eq!(copy.dequeue(), Some(&2));
+        assert!(copy != circular_buffer);
+
+        // the same items at other places of the buffer are equal
+        let mut other: Circular<usize> = Circular::new(3);
+        for item in 2..5 {
+            other.enqueue(item);
+        }
+        assert!(other == circular_buffer);
+        assert!(other != Circular::new(4));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut circular_buffer = Circular::new(3);
+        for item in 0..5 {
+            circular_buffer.enqueue(item);
+        }
+
+        let json = serde_json::to_string(&circular_buffer).unwrap();
+        assert_eq!(json, r#"{"capacity":3,"items":[2,3,4]}"#);
+
+        let restored: Circular<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, circular_buffer);
+        assert!(serde_json::from_str::<Circular<i32>>(r#"{"capacity":1,"items":[1,2]}"#).is_err());
+    }
 }
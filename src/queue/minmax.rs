@@ -0,0 +1,250 @@
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec::Vec;
+
+// item of a stack, with the positions of the least and the greatest item from the bottom of its stack up to it
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    item: T,
+    min: usize,
+    max: usize,
+}
+
+/// A first in first out queue that tells its least and greatest items at any time, which is what sliding window
+/// minimums and maximums need. The queue is made of two stacks: items are pushed onto the back stack and popped from
+/// the front stack, which is refilled by moving the whole back stack over when it runs empty. Every stack entry
+/// remembers where the least and greatest items below it are, so the extremes of the queue are the better ones of
+/// the two stack tops. Every item is moved once, so pushing and popping take amortized O(1) time and queries O(1)
+///
+/// # Examples
+/// ```
+/// use rudac::queue::MinMaxQueue;
+///
+/// // maximums of the windows of three readings
+/// let readings = [4, 2, 12, 3, 8, 5];
+/// let mut window = MinMaxQueue::init();
+/// let mut maximums = Vec::new();
+/// for reading in readings {
+///     window.push(reading);
+///     if window.size() > 3 {
+///         window.pop();
+///     }
+///     if window.size() == 3 {
+///         maximums.push(*window.max().unwrap());
+///     }
+/// }
+///
+/// assert_eq!(maximums, vec![12, 12, 12, 8]);
+/// assert_eq!(window.min(), Some(&3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinMaxQueue<T> {
+    // oldest item on top
+    front: Vec<Entry<T>>,
+
+    // newest item on top
+    back: Vec<Entry<T>>,
+}
+
+impl<T: Ord> MinMaxQueue<T> {
+    /// Initializes an empty queue
+    pub fn init() -> MinMaxQueue<T> {
+        MinMaxQueue {
+            front: Vec::new(),
+            back: Vec::new(),
+        }
+    }
+
+    // pushes `item` onto `stack` along with the extremes of the stack up to it
+    fn push_onto(stack: &mut Vec<Entry<T>>, item: T) {
+        let (min, max) = match stack.last() {
+            None => (0, 0),
+            Some(top) => {
+                let position = stack.len();
+                let min = if item < stack[top.min].item {
+                    position
+                } else {
+                    top.min
+                };
+                let max = if item > stack[top.max].item {
+                    position
+                } else {
+                    top.max
+                };
+                (min, max)
+            }
+        };
+
+        stack.push(Entry { item, min, max });
+    }
+
+    /// Pushes `item` to the back of the queue
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `item`: data to be pushed into the queue
+    pub fn push(&mut self, item: T) {
+        MinMaxQueue::push_onto(&mut self.back, item);
+    }
+
+    /// Pops and returns the item at the front of the queue, the oldest one. Returns `None` if the queue is empty
+    /// * Complexity: amortized O(1)
+    pub fn pop(&mut self) -> Option<T> {
+        if self.front.is_empty() {
+            while let Some(entry) = self.back.pop() {
+                MinMaxQueue::push_onto(&mut self.front, entry.item);
+            }
+        }
+
+        self.front.pop().map(|entry| entry.item)
+    }
+
+    // returns the item at `position` of `stack` if the stack is not empty
+    fn extreme(stack: &[Entry<T>], position: fn(&Entry<T>) -> usize) -> Option<&T> {
+        stack.last().map(|top| &stack[position(top)].item)
+    }
+
+    /// Returns the least item of the queue, None if the queue is empty. Of equal items, any one may be returned
+    /// * Complexity: O(1)
+    pub fn min(&self) -> Option<&T> {
+        let front = MinMaxQueue::extreme(&self.front, |top| top.min);
+        let back = MinMaxQueue::extreme(&self.back, |top| top.min);
+
+        match (front, back) {
+            (Some(front), Some(back)) => Some(core::cmp::min(front, back)),
+            _ => front.or(back),
+        }
+    }
+
+    /// Returns the greatest item of the queue, None if the queue is empty. Of equal items, any one may be returned
+    /// * Complexity: O(1)
+    pub fn max(&self) -> Option<&T> {
+        let front = MinMaxQueue::extreme(&self.front, |top| top.max);
+        let back = MinMaxQueue::extreme(&self.back, |top| top.max);
+
+        match (front, back) {
+            (Some(front), Some(back)) => Some(core::cmp::max(front, back)),
+            _ => front.or(back),
+        }
+    }
+}
+
+impl<T> MinMaxQueue<T> {
+    /// Returns the item at the front of the queue, the next one to be popped. None if the queue is empty
+    /// * Complexity: O(1)
+    pub fn peek(&self) -> Option<&T> {
+        self.front
+            .last()
+            .or_else(|| self.back.first())
+            .map(|entry| &entry.item)
+    }
+
+    /// Returns number of items in the queue
+    pub fn size(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    /// Returns true if there are no items in the queue
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty() && self.back.is_empty()
+    }
+
+    /// Removes every item of the queue
+    pub fn clear(&mut self) {
+        self.front.clear();
+        self.back.clear();
+    }
+
+    /// Returns an iterator over the items of the queue from front to back
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.front
+            .iter()
+            .rev()
+            .chain(self.back.iter())
+            .map(|entry| &entry.item)
+    }
+}
+
+impl<T: Ord> core::iter::FromIterator<T> for MinMaxQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = MinMaxQueue::init();
+        queue.extend(iter);
+
+        queue
+    }
+}
+
+impl<T: Ord> Extend<T> for MinMaxQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T: Ord> Default for MinMaxQueue<T> {
+    fn default() -> Self {
+        MinMaxQueue::init()
+    }
+}
+
+// queues are equal when they hold the same items from front to back, however they are split over the stacks
+impl<T: PartialEq> PartialEq for MinMaxQueue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for MinMaxQueue<T> {}
+
+impl<T> MemoryFootprint for MinMaxQueue<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.size())
+            .with_vec(&self.front)
+            .with_vec(&self.back)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn queue_minmax_matches_model() {
+        let mut rng = SplitMix64::init(107);
+        let mut queue = MinMaxQueue::init();
+        let mut model = VecDeque::new();
+        for _ in 0..3000 {
+            if rng.next_below(5) < 2 {
+                assert_eq!(queue.pop(), model.pop_front());
+            } else {
+                let item = rng.next_below(50);
+                queue.push(item);
+                model.push_back(item);
+            }
+
+            assert_eq!(queue.size(), model.len());
+            assert_eq!(queue.peek(), model.front());
+            assert_eq!(queue.min(), model.iter().min());
+            assert_eq!(queue.max(), model.iter().max());
+        }
+        assert!(queue.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn queue_minmax_eq_ignores_split() {
+        let mut queue: MinMaxQueue<i32> = (0..4).collect();
+        queue.pop();
+        queue.push(4);
+        let other: MinMaxQueue<i32> = (1..5).collect();
+
+        assert_eq!(queue, other);
+        assert_eq!(queue.peek(), Some(&1));
+
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.min(), None);
+        assert_eq!(queue.pop(), None);
+    }
+}
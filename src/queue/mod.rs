@@ -1,3 +1,24 @@
 mod circular;
+mod minmax;
+#[cfg(feature = "std")]
+mod priority;
 
 pub use circular::Circular;
+pub use minmax::MinMaxQueue;
+#[cfg(feature = "std")]
+pub use priority::PriorityQueue;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_send_and_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn queue_containers_are_send_and_sync() {
+        is_send_and_sync::<Circular<String>>();
+        is_send_and_sync::<MinMaxQueue<String>>();
+        #[cfg(feature = "std")]
+        is_send_and_sync::<PriorityQueue<String, u64>>();
+    }
+}
@@ -0,0 +1,411 @@
+use crate::heap::IndexedHeap;
+#[cfg(feature = "serde")]
+use crate::util::serialize::{deserialize_items, serialize_items};
+use crate::util::{Footprint, MemoryFootprint};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A priority queue of keys, each with a priority, that behaves like a map: a key is in the queue at most once and
+/// its priority can be read, changed or removed by the key itself. Every key gets a slot number, a hash map finds the
+/// slot of a key and an `IndexedHeap` orders the slots by priority, so every operation takes O(log n) time.
+/// Slots of removed keys are reused
+///
+/// # Examples
+/// ```
+/// use rudac::queue::PriorityQueue;
+///
+/// let mut queue = PriorityQueue::init_max();
+/// queue.push("write docs", 2);
+/// queue.push("fix bug", 5);
+/// queue.push("lunch", 3);
+///
+/// queue.update_priority(&"write docs", 9);
+/// queue.remove(&"lunch");
+///
+/// assert_eq!(queue.get(&"fix bug"), Some(&5));
+/// assert_eq!(queue.pop(), Some(("write docs", 9)));
+/// assert_eq!(queue.pop(), Some(("fix bug", 5)));
+/// assert_eq!(queue.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PriorityQueue<K, P> {
+    // slot of every key
+    slots: HashMap<K, usize>,
+
+    // key of every slot, None if the slot is free
+    keys: Vec<Option<K>>,
+    free: Vec<usize>,
+    heap: IndexedHeap<P>,
+}
+
+impl<K, P> PriorityQueue<K, P>
+where
+    K: Hash + Eq + Clone,
+    P: Ord,
+{
+    /// Initializes a queue popping the key with the smallest priority first
+    pub fn init_min() -> PriorityQueue<K, P> {
+        PriorityQueue::init(IndexedHeap::init_min())
+    }
+
+    /// Initializes a queue popping the key with the largest priority first
+    pub fn init_max() -> PriorityQueue<K, P> {
+        PriorityQueue::init(IndexedHeap::init_max())
+    }
+
+    fn init(heap: IndexedHeap<P>) -> PriorityQueue<K, P> {
+        PriorityQueue {
+            slots: HashMap::new(),
+            keys: Vec::new(),
+            free: Vec::new(),
+            heap,
+        }
+    }
+
+    /// Pushes `key` with `priority` into the queue. If `key` is already in the queue, its priority is replaced and
+    /// the old priority is returned
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `key`: key of the entry
+    /// * `priority`: priority of the key
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::queue::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::init_min();
+    ///
+    /// assert_eq!(queue.push('a', 4), None);
+    /// assert_eq!(queue.push('a', 1), Some(4));
+    /// assert_eq!(queue.size(), 1);
+    /// ```
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        if self.slots.contains_key(&key) {
+            return self.update_priority(&key, priority);
+        }
+
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                self.keys.push(None);
+                self.keys.len() - 1
+            }
+        };
+        self.keys[slot] = Some(key.clone());
+        self.slots.insert(key, slot);
+        self.heap.push(slot, priority);
+
+        None
+    }
+
+    /// Changes the priority of `key` to `priority` and returns the old priority.
+    /// Returns `None` and leaves the queue unchanged if `key` is not in the queue
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `key`: key of the entry
+    /// * `priority`: new priority of the key
+    pub fn update_priority(&mut self, key: &K, priority: P) -> Option<P> {
+        let slot = *self.slots.get(key)?;
+        let old = self.heap.remove(slot).unwrap();
+        self.heap.push(slot, priority);
+
+        Some(old)
+    }
+
+    /// Removes `key` from the queue and returns its priority, None if it is not in the queue
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `key`: key of the entry
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let slot = self.slots.remove(key)?;
+        self.keys[slot] = None;
+        self.free.push(slot);
+
+        self.heap.remove(slot)
+    }
+
+    /// Pops and returns the key with highest priority along with its priority. Returns `None` if queue is empty
+    /// * Complexity: O(log n)
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        let (slot, priority) = self.heap.pop()?;
+        let key = self.keys[slot].take().unwrap();
+        self.slots.remove(&key);
+        self.free.push(slot);
+
+        Some((key, priority))
+    }
+
+    /// Returns the key with highest priority along with its priority, None if the queue is empty
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap
+            .peek()
+            .map(|(slot, priority)| (self.keys[slot].as_ref().unwrap(), priority))
+    }
+
+    /// Returns a reference to the priority of `key`, None if it is not in the queue
+    ///
+    /// # Arguments
+    /// * `key`: key of the entry
+    pub fn get(&self, key: &K) -> Option<&P> {
+        self.heap.get(*self.slots.get(key)?)
+    }
+
+    /// Returns true if `key` is in the queue
+    ///
+    /// # Arguments
+    /// * `key`: key of the entry
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.slots.contains_key(key)
+    }
+
+    /// Returns number of keys in the queue
+    pub fn size(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true if there are no keys in the queue
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Removes every key of the queue
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.keys.clear();
+        self.free.clear();
+        self.heap.clear();
+    }
+
+    /// Returns true if the queue pops the smallest priority first
+    pub fn is_min(&self) -> bool {
+        self.heap.is_min()
+    }
+
+    /// Returns true if the queue pops the largest priority first
+    pub fn is_max(&self) -> bool {
+        self.heap.is_max()
+    }
+
+    /// Returns an iterator over the keys and their priorities in no particular order
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::queue::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::init_min();
+    /// queue.push("b", 2);
+    /// queue.push("a", 1);
+    ///
+    /// let mut entries: Vec<(&str, i32)> = queue.iter().map(|(key, priority)| (*key, *priority)).collect();
+    /// entries.sort();
+    ///
+    /// assert_eq!(entries, vec![("a", 1), ("b", 2)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &P)> {
+        self.heap
+            .iter()
+            .map(move |(slot, priority)| (self.keys[slot].as_ref().unwrap(), priority))
+    }
+
+    /// Consumes the queue and returns its keys and priorities in the order they are popped
+    /// * Complexity: O(n log n)
+    pub fn into_sorted_vec(mut self) -> Vec<(K, P)> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
+}
+
+impl<K, P> std::iter::FromIterator<(K, P)> for PriorityQueue<K, P>
+where
+    K: Hash + Eq + Clone,
+    P: Ord,
+{
+    // later priorities of a key replace earlier ones
+    fn from_iter<I: IntoIterator<Item = (K, P)>>(iter: I) -> Self {
+        let mut queue = PriorityQueue::init_min();
+        queue.extend(iter);
+
+        queue
+    }
+}
+
+impl<K, P> Extend<(K, P)> for PriorityQueue<K, P>
+where
+    K: Hash + Eq + Clone,
+    P: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, P)>>(&mut self, iter: I) {
+        for (key, priority) in iter {
+            self.push(key, priority);
+        }
+    }
+}
+
+impl<K, P> Default for PriorityQueue<K, P>
+where
+    K: Hash + Eq + Clone,
+    P: Ord,
+{
+    fn default() -> Self {
+        PriorityQueue::init_min()
+    }
+}
+
+impl<K, P> MemoryFootprint for PriorityQueue<K, P> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<K>(self.slots.len())
+            .with_hash_map(&self.slots)
+            .with_vec(&self.keys)
+            .with_vec(&self.free)
+            .with(self.heap.footprint())
+    }
+}
+
+// whether the queue pops the smallest priority first and its entries in the order they would be popped
+#[cfg(feature = "serde")]
+const FIELDS: &[&str; 2] = &["min", "entries"];
+
+// a queue is written as its entries in the order they would be popped, keys of equal priority in no particular order
+#[cfg(feature = "serde")]
+impl<K: Serialize, P: Ord + Serialize> Serialize for PriorityQueue<K, P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<(&K, &P)> = self
+            .heap
+            .iter()
+            .map(|(slot, priority)| (self.keys[slot].as_ref().unwrap(), priority))
+            .collect();
+        entries.sort_by_key(|(_, priority)| *priority);
+        if self.heap.is_max() {
+            entries.reverse();
+        }
+
+        serialize_items(
+            serializer,
+            "PriorityQueue",
+            FIELDS,
+            self.heap.is_min(),
+            &entries,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, P> Deserialize<'de> for PriorityQueue<K, P>
+where
+    K: Hash + Eq + Clone + Deserialize<'de>,
+    P: Ord + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (min, entries): (bool, Vec<(K, P)>) =
+            deserialize_items(deserializer, "PriorityQueue", FIELDS)?;
+
+        let mut queue = if min {
+            PriorityQueue::init_min()
+        } else {
+            PriorityQueue::init_max()
+        };
+        queue.extend(entries);
+
+        Ok(queue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn queue_priority_matches_model() {
+        let mut rng = SplitMix64::init(37);
+        let mut queue = PriorityQueue::init_min();
+        let mut model: BTreeMap<String, u64> = BTreeMap::new();
+        for _ in 0..4000 {
+            let key = format!("key{}", rng.next_below(60));
+            let priority = rng.next_below(100);
+            match rng.next_below(5) {
+                0 => {
+                    let least = model.values().min().copied();
+                    match queue.pop() {
+                        Some((key, priority)) => {
+                            assert_eq!(Some(priority), least);
+                            assert_eq!(model.remove(&key), Some(priority));
+                        }
+                        None => assert!(least.is_none()),
+                    }
+                }
+                1 => assert_eq!(queue.remove(&key), model.remove(&key)),
+                2 => {
+                    let expected = model
+                        .get_mut(&key)
+                        .map(|old| std::mem::replace(old, priority));
+                    assert_eq!(queue.update_priority(&key, priority), expected);
+                }
+                _ => assert_eq!(
+                    queue.push(key.clone(), priority),
+                    model.insert(key, priority)
+                ),
+            }
+
+            assert_eq!(queue.size(), model.len());
+            assert_eq!(queue.peek().map(|(_, p)| *p), model.values().min().copied());
+        }
+
+        let mut entries: Vec<(String, u64)> = queue.iter().map(|(k, p)| (k.clone(), *p)).collect();
+        entries.sort();
+        assert_eq!(entries, model.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn queue_priority_into_sorted_vec() {
+        let queue: PriorityQueue<char, i32> = vec![('c', 3), ('a', 9), ('b', 2), ('a', 1)]
+            .into_iter()
+            .collect();
+
+        assert!(queue.is_min());
+        assert_eq!(queue.into_sorted_vec(), vec![('a', 1), ('b', 2), ('c', 3)]);
+    }
+
+    #[test]
+    fn queue_priority_reuses_slots() {
+        let mut queue = PriorityQueue::init_max();
+        for round in 0..10 {
+            for key in 0..5 {
+                queue.push(key, round * key);
+            }
+            while queue.pop().is_some() {}
+        }
+
+        assert!(queue.is_empty());
+        assert_eq!(queue.keys.len(), 5);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn queue_priority_serde() {
+        let mut queue = PriorityQueue::init_max();
+        queue.extend(vec![("lunch", 3), ("fix bug", 5), ("write docs", 2)]);
+        queue.remove(&"lunch");
+
+        let json = serde_json::to_string(&queue).unwrap();
+        assert_eq!(
+            json,
+            r#"{"min":false,"entries":[["fix bug",5],["write docs",2]]}"#
+        );
+
+        let restored: PriorityQueue<String, i32> = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_max());
+        assert_eq!(
+            restored.into_sorted_vec(),
+            vec![
+                (String::from("fix bug"), 5),
+                (String::from("write docs"), 2)
+            ]
+        );
+    }
+}
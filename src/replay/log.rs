@@ -0,0 +1,291 @@
+use super::{Op, Replayable};
+use crate::codec::{read_header, write_header, Decode, Encode, Reader, Writer};
+use alloc::vec::Vec;
+
+/// A sequence of operations that can be replayed onto any structure supporting them. A log can be stored
+/// in the binary format of the `codec` module, built from arbitrary fuzzer input and shrunk to a minimal log
+/// that still reproduces a bug
+///
+/// # Examples
+/// ```
+/// use rudac::replay::{Op, OpLog};
+/// use rudac::tree::AVL;
+///
+/// let mut log = OpLog::init();
+/// log.record(Op::Insert(2, "two"));
+/// log.record(Op::Insert(1, "one"));
+/// log.record(Op::Remove(2));
+///
+/// let mut avl_tree = AVL::<i32, &str>::init();
+/// log.replay(&mut avl_tree);
+///
+/// assert_eq!(avl_tree.keys(), vec![&1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OpLog<K, V = ()> {
+    ops: Vec<Op<K, V>>,
+}
+
+impl<K, V> OpLog<K, V> {
+    /// Initializes an empty log
+    pub fn init() -> OpLog<K, V> {
+        OpLog { ops: Vec::new() }
+    }
+
+    /// Returns number of operations in the log
+    pub fn size(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns true if there are no operations in the log
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Returns the operations in the order they were recorded
+    pub fn ops(&self) -> &[Op<K, V>] {
+        &self.ops
+    }
+
+    /// Appends `op` to the log
+    ///
+    /// # Arguments
+    /// * `op`: the operation
+    pub fn record(&mut self, op: Op<K, V>) {
+        self.ops.push(op);
+    }
+}
+
+impl<K: Clone, V: Clone> OpLog<K, V> {
+    /// Applies every operation of the log to `target` in order
+    ///
+    /// # Arguments
+    /// * `target`: the structure, usually a freshly initialized one
+    pub fn replay<S: Replayable<Key = K, Value = V>>(&self, target: &mut S) {
+        for op in &self.ops {
+            target.apply(op.clone());
+        }
+    }
+
+    /// Returns a smallest log found by removing operations from this log for which `fails` still returns true.
+    /// Chunks of operations are removed first, then shorter and shorter ones down to single operations,
+    /// so the result is a log where removing any single operation makes the failure disappear.
+    /// If `fails` returns false for this log, the log is returned unchanged
+    ///
+    /// # Arguments
+    /// * `fails`: returns true if a log reproduces the failure, usually by replaying it onto a fresh structure
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::heap::MinMax;
+    /// use rudac::replay::{Op, OpLog};
+    ///
+    /// let mut log = OpLog::init();
+    /// for i in 0..20 {
+    ///     log.record(Op::Push(i));
+    ///     if i % 3 == 0 {
+    ///         log.record(Op::Pop);
+    ///     }
+    /// }
+    ///
+    /// // the failure to reproduce: the heap ends up holding 13
+    /// let minimized = log.minimize(|log| {
+    ///     let mut minmax = MinMax::init();
+    ///     log.replay(&mut minmax);
+    ///     minmax.into_vec().contains(&13)
+    /// });
+    ///
+    /// assert_eq!(minimized.ops(), &[Op::Push(13)]);
+    /// ```
+    pub fn minimize<F: FnMut(&OpLog<K, V>) -> bool>(&self, mut fails: F) -> OpLog<K, V> {
+        let mut minimized = self.clone();
+        if !fails(&minimized) {
+            return minimized;
+        }
+
+        let mut chunk = (minimized.size() / 2).max(1);
+        loop {
+            let mut start = 0;
+            while start < minimized.size() {
+                let end = (start + chunk).min(minimized.size());
+                let mut candidate = OpLog::init();
+                candidate.ops.extend_from_slice(&minimized.ops[..start]);
+                candidate.ops.extend_from_slice(&minimized.ops[end..]);
+
+                if fails(&candidate) {
+                    minimized = candidate;
+                } else {
+                    start = end;
+                }
+            }
+
+            if chunk == 1 {
+                return minimized;
+            }
+            chunk /= 2;
+        }
+    }
+}
+
+impl<K: Encode, V: Encode> OpLog<K, V> {
+    /// Returns a binary snapshot of the log
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::replay::{Op, OpLog};
+    ///
+    /// let mut log: OpLog<String, u32> = OpLog::init();
+    /// log.record(Op::Insert(String::from("key"), 7));
+    /// log.record(Op::Remove(String::from("key")));
+    ///
+    /// let bytes = log.to_bytes();
+    ///
+    /// assert_eq!(OpLog::from_bytes(&bytes), Some(log));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"OL");
+        self.ops.encode(&mut writer);
+
+        writer.into_bytes()
+    }
+}
+
+impl<K: Decode, V: Decode> OpLog<K, V> {
+    /// Restores a log from a snapshot made by `to_bytes`. Returns `None` if the bytes are malformed
+    ///
+    /// # Arguments
+    /// * `bytes`: the snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Option<OpLog<K, V>> {
+        let mut reader = Reader::init(bytes);
+        read_header(&mut reader, b"OL")?;
+        let ops = Vec::<Op<K, V>>::decode(&mut reader)?;
+        if !reader.is_at_end() {
+            return None;
+        }
+
+        Some(OpLog { ops })
+    }
+
+    /// Builds a log from arbitrary bytes, like the input of a fuzzer. Every operation is a tag byte, whose value
+    /// modulo 4 picks `Push`, `Pop`, `Insert` or `Remove`, followed by its encoded key and value.
+    /// Reading stops at the first operation that can not be decoded, so every input gives a log
+    /// and small changes of the input give small changes of the log
+    ///
+    /// # Arguments
+    /// * `bytes`: the input
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::replay::{Op, OpLog};
+    ///
+    /// let log = OpLog::<u8, u8>::from_fuzz_input(&[0, 5, 1, 6, 3, 4, 7, 0]);
+    ///
+    /// assert_eq!(log.ops(), &[Op::Push(5), Op::Pop, Op::Insert(3, 4), Op::Remove(0)]);
+    /// ```
+    pub fn from_fuzz_input(bytes: &[u8]) -> OpLog<K, V> {
+        let mut reader = Reader::init(bytes);
+        let mut log = OpLog::init();
+        while let Some(tag) = reader.read_u8() {
+            let op = match tag % 4 {
+                0 => K::decode(&mut reader).map(Op::Push),
+                1 => Some(Op::Pop),
+                2 => K::decode(&mut reader)
+                    .and_then(|key| Some(Op::Insert(key, V::decode(&mut reader)?))),
+                _ => K::decode(&mut reader).map(Op::Remove),
+            };
+
+            match op {
+                Some(op) => log.record(op),
+                None => break,
+            }
+        }
+
+        log
+    }
+}
+
+impl<K, V> Default for OpLog<K, V> {
+    fn default() -> Self {
+        OpLog::init()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heap::MinMax;
+    use crate::tree::AVL;
+
+    #[test]
+    fn replay_log_round_trip() {
+        let mut log: OpLog<i64, Option<String>> = OpLog::init();
+        log.record(Op::Insert(-5, Some(String::from("a"))));
+        log.record(Op::Insert(7, None));
+        log.record(Op::Pop);
+        log.record(Op::Remove(-5));
+        log.record(Op::Push(0));
+
+        let bytes = log.to_bytes();
+        assert_eq!(OpLog::from_bytes(&bytes), Some(log));
+
+        // truncated and trailing input
+        assert_eq!(
+            OpLog::<i64, Option<String>>::from_bytes(&bytes[..bytes.len() - 1]),
+            None
+        );
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(OpLog::<i64, Option<String>>::from_bytes(&extended), None);
+    }
+
+    #[test]
+    fn replay_log_fuzz_input_stops_at_malformed_op() {
+        // 0x80 starts a varint that never ends
+        let log = OpLog::<u32, u32>::from_fuzz_input(&[4, 9, 5, 2, 1, 0x80]);
+        assert_eq!(log.ops(), &[Op::Push(9), Op::Pop]);
+
+        assert!(OpLog::<u32, u32>::from_fuzz_input(&[]).is_empty());
+    }
+
+    #[test]
+    fn replay_log_minimize_keeps_needed_ops() {
+        let mut log = OpLog::init();
+        for key in 0..50 {
+            log.record(Op::Insert(key, key));
+        }
+        for key in 0..50 {
+            log.record(Op::Remove(key));
+        }
+
+        // fails when 10 and 20 are in the tree but 30 is not
+        let fails = |log: &OpLog<i32, i32>| {
+            let mut avl_tree = AVL::<i32, i32>::init();
+            log.replay(&mut avl_tree);
+            avl_tree.contains(&10) && avl_tree.contains(&20) && !avl_tree.contains(&30)
+        };
+        assert!(!fails(&log));
+        assert_eq!(log.minimize(fails), log);
+
+        log.record(Op::Insert(10, 0));
+        log.record(Op::Insert(20, 0));
+        let minimized = log.minimize(fails);
+        assert_eq!(minimized.ops(), &[Op::Insert(10, 0), Op::Insert(20, 0)]);
+    }
+
+    #[test]
+    fn replay_log_replays_heap_ops() {
+        let mut log = OpLog::init();
+        for item in [5, 3, 8, 1].iter() {
+            log.record(Op::Push(*item));
+        }
+        log.record(Op::Pop);
+        log.record(Op::Remove(8));
+
+        let mut minmax = MinMax::init();
+        log.replay(&mut minmax);
+        assert_eq!(minmax.size(), 3);
+        assert_eq!(minmax.peek_min(), Some(&3));
+        assert_eq!(minmax.peek_max(), Some(&8));
+    }
+}
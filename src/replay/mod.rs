@@ -0,0 +1,12 @@
+//! Recording and replaying of operations on rudac structures. A log of pushes, pops, inserts and removals
+//! can be replayed onto a fresh structure, stored in the binary format of the `codec` module, built from fuzzer input
+//! and minimized, so a bug found by a fuzzer or a long test can be reported as a short reproduction case.
+
+mod log;
+mod op;
+mod replayable;
+
+pub use log::OpLog;
+pub use op::Op;
+pub use replayable::Recording;
+pub use replayable::Replayable;
@@ -0,0 +1,63 @@
+use crate::codec::{Decode, Encode, Reader, Writer};
+
+/// A single operation on a structure. Heaps support `Push` and `Pop`, maps and trees support `Insert` and `Remove`
+///
+/// # Examples
+/// ```
+/// use rudac::heap::MinMax;
+/// use rudac::replay::{Op, Replayable};
+///
+/// let mut minmax = MinMax::<i32>::init();
+/// minmax.apply(Op::Push(3));
+/// minmax.apply(Op::Push(1));
+/// minmax.apply(Op::Pop);
+///
+/// assert_eq!(minmax.peek_min(), Some(&3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Op<K, V = ()> {
+    /// pushes an item into a heap
+    Push(K),
+
+    /// pops the item with the highest priority from a heap
+    Pop,
+
+    /// inserts a key and its value into a map or a tree, replacing the value of an existing key
+    Insert(K, V),
+
+    /// removes a key and its value from a map or a tree
+    Remove(K),
+}
+
+impl<K: Encode, V: Encode> Encode for Op<K, V> {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            Op::Push(key) => {
+                writer.write_u8(0);
+                key.encode(writer);
+            }
+            Op::Pop => writer.write_u8(1),
+            Op::Insert(key, value) => {
+                writer.write_u8(2);
+                key.encode(writer);
+                value.encode(writer);
+            }
+            Op::Remove(key) => {
+                writer.write_u8(3);
+                key.encode(writer);
+            }
+        }
+    }
+}
+
+impl<K: Decode, V: Decode> Decode for Op<K, V> {
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        match reader.read_u8()? {
+            0 => Some(Op::Push(K::decode(reader)?)),
+            1 => Some(Op::Pop),
+            2 => Some(Op::Insert(K::decode(reader)?, V::decode(reader)?)),
+            3 => Some(Op::Remove(K::decode(reader)?)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use super::{Op, OpLog};
+
+/// Structures that operations of a log can be applied to
+pub trait Replayable {
+    /// type of the items of a heap or the keys of a map
+    type Key;
+
+    /// type of the values of a map, `()` for heaps
+    type Value;
+
+    /// Applies `op` to the structure. Operations the structure does not support, like `Insert` on a heap,
+    /// and removals of missing keys are ignored
+    ///
+    /// # Arguments
+    /// * `op`: the operation
+    fn apply(&mut self, op: Op<Self::Key, Self::Value>);
+}
+
+/// A structure together with the log of every operation applied to it. An operation is recorded before it is
+/// applied, so when the structure panics the log ends with the operation that made it panic
+///
+/// # Examples
+/// ```
+/// use rudac::heap::FibonacciHeap;
+/// use rudac::replay::{Op, Recording};
+///
+/// let mut recording = Recording::init(FibonacciHeap::init_min());
+/// recording.apply(Op::Push(4));
+/// recording.apply(Op::Push(2));
+/// recording.apply(Op::Pop);
+///
+/// // the same operations give the same heap
+/// let mut replayed = FibonacciHeap::init_min();
+/// recording.log().replay(&mut replayed);
+///
+/// assert_eq!(replayed.peek(), recording.structure().peek());
+/// assert_eq!(recording.log().size(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Recording<S: Replayable> {
+    structure: S,
+    log: OpLog<S::Key, S::Value>,
+}
+
+impl<S: Replayable> Recording<S> {
+    /// Starts recording the operations applied to `structure`
+    ///
+    /// # Arguments
+    /// * `structure`: the structure, usually a freshly initialized one so that the log alone reproduces its state
+    pub fn init(structure: S) -> Recording<S> {
+        Recording {
+            structure,
+            log: OpLog::init(),
+        }
+    }
+
+    /// Returns a reference to the structure
+    pub fn structure(&self) -> &S {
+        &self.structure
+    }
+
+    /// Returns the recorded operations
+    pub fn log(&self) -> &OpLog<S::Key, S::Value> {
+        &self.log
+    }
+
+    /// Consumes the recording and returns the structure and the log
+    pub fn into_parts(self) -> (S, OpLog<S::Key, S::Value>) {
+        (self.structure, self.log)
+    }
+}
+
+impl<S: Replayable> Recording<S>
+where
+    S::Key: Clone,
+    S::Value: Clone,
+{
+    /// Records `op` and applies it to the structure
+    ///
+    /// # Arguments
+    /// * `op`: the operation
+    pub fn apply(&mut self, op: Op<S::Key, S::Value>) {
+        self.log.record(op.clone());
+        self.structure.apply(op);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::map::HatTrie;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    // a structure that panics on the third push
+    struct Fragile {
+        pushes: usize,
+    }
+
+    impl Replayable for Fragile {
+        type Key = u8;
+        type Value = ();
+
+        fn apply(&mut self, op: Op<u8, ()>) {
+            if let Op::Push(_) = op {
+                self.pushes += 1;
+                if self.pushes == 3 {
+                    panic!("Third push");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn replay_recording_keeps_op_that_panicked() {
+        let mut recording = Recording::init(Fragile { pushes: 0 });
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            for item in 0..10 {
+                recording.apply(Op::Push(item));
+                recording.apply(Op::Pop);
+            }
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(recording.log().size(), 5);
+        assert_eq!(recording.log().ops().last(), Some(&Op::Push(2)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn replay_recording_into_parts() {
+        let mut recording = Recording::init(HatTrie::init());
+        recording.apply(Op::Insert(String::from("b"), 2));
+        recording.apply(Op::Insert(String::from("a"), 1));
+        recording.apply(Op::Remove(String::from("b")));
+        recording.apply(Op::Pop);
+
+        let (map, log) = recording.into_parts();
+        let mut replayed = HatTrie::init();
+        log.replay(&mut replayed);
+
+        assert_eq!(map.keys().collect::<Vec<String>>(), vec![String::from("a")]);
+        assert_eq!(
+            replayed.iter().collect::<Vec<(String, &i32)>>(),
+            map.iter().collect::<Vec<(String, &i32)>>()
+        );
+    }
+}
@@ -0,0 +1,7 @@
+mod rank_select;
+mod rle_vec;
+
+pub use rank_select::RankSelect;
+pub use rle_vec::Iter;
+pub use rle_vec::RleVec;
+pub use rle_vec::Runs;
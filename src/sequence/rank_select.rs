@@ -0,0 +1,263 @@
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec::Vec;
+
+const WORD_BITS: usize = 64;
+const BLOCK_WORDS: usize = 8;
+const BLOCK_BITS: usize = WORD_BITS * BLOCK_WORDS;
+
+// position of the `k`th(0-based) set bit of `word`
+fn select_in_word(mut word: u64, k: usize) -> usize {
+    for _ in 0..k {
+        word &= word - 1;
+    }
+
+    word.trailing_zeros() as usize
+}
+
+/// A static bit vector supporting rank and select queries. Bits are packed in 64 bit words and the number of set bits
+/// before every block of 512 bits is stored, so `rank` takes constant time and `select` a binary search over the blocks.
+/// The directory adds about 12% to the size of the bits on 64 bit targets.
+///
+/// # Examples
+/// ```
+/// use rudac::sequence::RankSelect;
+///
+/// let bits = RankSelect::from_bits(vec![true, false, false, true, true, false]);
+///
+/// assert_eq!(bits.count_ones(), 3);
+/// // number of set bits in [0, 4)
+/// assert_eq!(bits.rank1(4), 2);
+/// assert_eq!(bits.rank0(4), 2);
+/// // position of the third(0-based index 2) set bit
+/// assert_eq!(bits.select1(2), Some(4));
+/// assert_eq!(bits.select0(2), Some(5));
+/// assert_eq!(bits.select1(3), None);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct RankSelect {
+    words: Vec<u64>,
+    blocks: Vec<usize>,
+    len: usize,
+}
+
+impl RankSelect {
+    /// Builds the bit vector from a sequence of bits
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `bits`: the bits
+    pub fn from_bits<I: IntoIterator<Item = bool>>(bits: I) -> RankSelect {
+        let mut words: Vec<u64> = Vec::new();
+        let mut len = 0;
+        for bit in bits {
+            if len % WORD_BITS == 0 {
+                words.push(0);
+            }
+            if bit {
+                words[len / WORD_BITS] |= 1 << (len % WORD_BITS);
+            }
+            len += 1;
+        }
+
+        let mut blocks = Vec::with_capacity(words.len() / BLOCK_WORDS + 2);
+        let mut ones = 0;
+        blocks.push(0);
+        for chunk in words.chunks(BLOCK_WORDS) {
+            ones += chunk
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>();
+            blocks.push(ones);
+        }
+
+        RankSelect { words, blocks, len }
+    }
+
+    /// Returns number of bits
+    pub fn size(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.blocks[self.blocks.len() - 1]
+    }
+
+    /// Returns number of unset bits
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns bit at `index`
+    ///
+    /// # Arguments
+    /// * `index`: index of the bit
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn get(&self, index: usize) -> bool {
+        if index >= self.len {
+            panic!("Index out of range");
+        }
+
+        self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1
+    }
+
+    /// Returns number of set bits in `[0, index)`
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `index`: end of the prefix, at most the number of bits
+    ///
+    /// # Panics
+    /// * panics if `index` is greater than the number of bits
+    pub fn rank1(&self, index: usize) -> usize {
+        if index > self.len {
+            panic!("Index out of range");
+        }
+
+        let word = index / WORD_BITS;
+        let block = word / BLOCK_WORDS;
+        let mut rank = self.blocks[block];
+        for full in &self.words[block * BLOCK_WORDS..word] {
+            rank += full.count_ones() as usize;
+        }
+        let bit = index % WORD_BITS;
+        if bit > 0 {
+            rank += (self.words[word] & ((1 << bit) - 1)).count_ones() as usize;
+        }
+
+        rank
+    }
+
+    /// Returns number of unset bits in `[0, index)`
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `index`: end of the prefix, at most the number of bits
+    ///
+    /// # Panics
+    /// * panics if `index` is greater than the number of bits
+    pub fn rank0(&self, index: usize) -> usize {
+        index - self.rank1(index)
+    }
+
+    /// Returns position of the `k`th(0-based) set bit, or `None` if there are not that many set bits
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `k`: rank of the searched bit
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        if k >= self.count_ones() {
+            return None;
+        }
+
+        // last block with fewer than `k + 1` set bits before it
+        let block = self.blocks.partition_point(|ones| *ones <= k) - 1;
+        let mut remaining = k - self.blocks[block];
+        for (offset, word) in self.words[block * BLOCK_WORDS..].iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                let word_index = block * BLOCK_WORDS + offset;
+                return Some(word_index * WORD_BITS + select_in_word(*word, remaining));
+            }
+            remaining -= ones;
+        }
+
+        unreachable!()
+    }
+
+    /// Returns position of the `k`th(0-based) unset bit, or `None` if there are not that many unset bits
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `k`: rank of the searched bit
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        if k >= self.count_zeros() {
+            return None;
+        }
+
+        let zeros_before = |block: usize| block * BLOCK_BITS - self.blocks[block];
+        // last block with fewer than `k + 1` unset bits before it
+        let (mut block, mut high) = (0, self.blocks.len() - 1);
+        while high - block > 1 {
+            let middle = (block + high) / 2;
+            if zeros_before(middle) <= k {
+                block = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let mut remaining = k - zeros_before(block);
+        for (offset, word) in self.words[block * BLOCK_WORDS..].iter().enumerate() {
+            let zeros = word.count_zeros() as usize;
+            if remaining < zeros {
+                let word_index = block * BLOCK_WORDS + offset;
+                return Some(word_index * WORD_BITS + select_in_word(!*word, remaining));
+            }
+            remaining -= zeros;
+        }
+
+        unreachable!()
+    }
+}
+
+impl MemoryFootprint for RankSelect {
+    fn footprint(&self) -> Footprint {
+        // every element is a single bit
+        Footprint::init(self.len, self.len.div_ceil(8))
+            .with_vec(&self.words)
+            .with_vec(&self.blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn sequence_rank_select_matches_naive() {
+        let mut rng = SplitMix64::init(3);
+        for &(len, density) in [(0, 2), (1, 2), (63, 2), (64, 3), (1000, 2), (3000, 50)].iter() {
+            let bits: Vec<bool> = (0..len).map(|_| rng.next_below(density) == 0).collect();
+            let vector = RankSelect::from_bits(bits.iter().copied());
+            assert_eq!(vector.size(), len);
+
+            let mut ones = Vec::new();
+            let mut zeros = Vec::new();
+            for (index, bit) in bits.iter().enumerate() {
+                assert_eq!(vector.rank1(index), ones.len());
+                assert_eq!(vector.rank0(index), zeros.len());
+                assert_eq!(vector.get(index), *bit);
+                if *bit {
+                    ones.push(index);
+                } else {
+                    zeros.push(index);
+                }
+            }
+            assert_eq!(vector.rank1(len), ones.len());
+
+            for (k, position) in ones.iter().enumerate() {
+                assert_eq!(vector.select1(k), Some(*position));
+            }
+            for (k, position) in zeros.iter().enumerate() {
+                assert_eq!(vector.select0(k), Some(*position));
+            }
+            assert_eq!(vector.select1(ones.len()), None);
+            assert_eq!(vector.select0(zeros.len()), None);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn sequence_rank_select_rank_out_of_range() {
+        let vector = RankSelect::from_bits(vec![true, false]);
+        vector.rank1(3);
+    }
+}
@@ -0,0 +1,483 @@
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A run-length encoded vector stores consecutive equal items as a single run.
+/// Random access takes O(log r) time, where r is number of runs, and memory is proportional to number of runs
+/// instead of number of items, which suits sparse timelines, tile maps and other data with long repetitions.
+///
+/// Adjacent runs never have equal values: setters split runs when needed and merge equal neighbors back.
+///
+/// # Examples
+/// ```
+/// use rudac::sequence::RleVec;
+///
+/// let mut tiles = RleVec::init();
+/// tiles.push_run('~', 1000);
+/// tiles.push_run('#', 10);
+///
+/// assert_eq!(tiles.size(), 1010);
+/// assert_eq!(tiles.run_count(), 2);
+/// assert_eq!(tiles[500], '~');
+///
+/// tiles.set(500, '#');
+/// assert_eq!(tiles.run_count(), 4);
+///
+/// tiles.set(500, '~');
+/// assert_eq!(tiles.run_count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RleVec<T> {
+    // value of every run
+    values: Vec<T>,
+
+    // end(exclusive) of every run, strictly increasing
+    ends: Vec<usize>,
+}
+
+impl<T> RleVec<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Initializes an empty vector
+    pub fn init() -> RleVec<T> {
+        RleVec {
+            values: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Initializes a vector with `items`
+    ///
+    /// # Arguments
+    /// * `items`: initial items
+    pub fn from_slice(items: &[T]) -> RleVec<T> {
+        let mut rle = RleVec::init();
+        for item in items {
+            rle.push(item.clone());
+        }
+
+        rle
+    }
+
+    /// Returns number of items
+    pub fn size(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    /// Returns true if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns number of runs
+    pub fn run_count(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Appends `item` to the end of the vector
+    ///
+    /// # Arguments
+    /// * `item`: item to be appended
+    pub fn push(&mut self, item: T) {
+        self.push_run(item, 1);
+    }
+
+    /// Appends `count` copies of `item` to the end of the vector
+    ///
+    /// # Arguments
+    /// * `item`: item to be appended
+    /// * `count`: number of copies
+    pub fn push_run(&mut self, item: T, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        let end = self.size() + count;
+        if self.values.last() == Some(&item) {
+            *self.ends.last_mut().unwrap() = end;
+        } else {
+            self.values.push(item);
+            self.ends.push(end);
+        }
+    }
+
+    /// Returns the item at `index`, or `None` if `index` is out of range.
+    /// * Complexity: O(log r)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.size() {
+            return None;
+        }
+
+        Some(&self.values[self.run_index(index)])
+    }
+
+    /// Returns range and value of the run containing `index`, or `None` if `index` is out of range.
+    /// * Complexity: O(log r)
+    ///
+    /// # Arguments
+    /// * `index`: index of an item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::sequence::RleVec;
+    ///
+    /// let rle = RleVec::from_slice(&[1, 1, 2, 2, 2, 3]);
+    ///
+    /// assert_eq!(rle.run_at(3), Some((2..5, &2)));
+    /// assert_eq!(rle.run_at(6), None);
+    /// ```
+    pub fn run_at(&self, index: usize) -> Option<(Range<usize>, &T)> {
+        if index >= self.size() {
+            return None;
+        }
+
+        let run = self.run_index(index);
+        Some((self.run_start(run)..self.ends[run], &self.values[run]))
+    }
+
+    /// Sets the item at `index` to `value`, splitting and merging runs as needed.
+    /// * Complexity: O(r) in the worst case, O(log r) if number of runs does not change
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    /// * `value`: new value of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn set(&mut self, index: usize, value: T) {
+        self.set_range(index, index + 1, value);
+    }
+
+    /// Sets all items in range [`start`, `end`) to `value`, splitting and merging runs as needed.
+    /// * Complexity: O(r) in the worst case
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    /// * `value`: new value of the items
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end`
+    /// * panics if `end` is greater than size of the vector
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::sequence::RleVec;
+    ///
+    /// let mut rle = RleVec::from_slice(&[0, 0, 1, 1, 2, 2, 0, 0]);
+    ///
+    /// rle.set_range(2, 6, 0);
+    ///
+    /// assert_eq!(rle.run_count(), 1);
+    /// assert_eq!(rle.to_vec(), vec![0; 8]);
+    /// ```
+    pub fn set_range(&mut self, start: usize, end: usize, value: T) {
+        if start > end {
+            panic!("Start of the range must not be greater than its end");
+        }
+        if end > self.size() {
+            panic!("Index out of range");
+        }
+        if start == end {
+            return;
+        }
+
+        let first = self.run_index(start);
+        let last = self.run_index(end - 1);
+
+        // the range replaces runs first..=last, keeping the parts of the outer runs outside of it
+        let mut values = Vec::with_capacity(3);
+        let mut ends = Vec::with_capacity(3);
+        if self.run_start(first) < start {
+            values.push(self.values[first].clone());
+            ends.push(start);
+        }
+        values.push(value);
+        ends.push(end);
+        if self.ends[last] > end {
+            values.push(self.values[last].clone());
+            ends.push(self.ends[last]);
+        }
+
+        let inserted = values.len();
+        self.values.splice(first..=last, values);
+        self.ends.splice(first..=last, ends);
+
+        self.merge_runs(first.saturating_sub(1), first + inserted + 1);
+    }
+
+    /// Returns an iterator over the runs as (range, value) pairs
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::sequence::RleVec;
+    ///
+    /// let rle = RleVec::from_slice(&["a", "a", "b"]);
+    /// let runs: Vec<_> = rle.runs().collect();
+    ///
+    /// assert_eq!(runs, vec![(0..2, &"a"), (2..3, &"b")]);
+    /// ```
+    pub fn runs(&self) -> Runs<'_, T> {
+        Runs { rle: self, run: 0 }
+    }
+
+    /// Returns an iterator over the items
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            rle: self,
+            run: 0,
+            index: 0,
+        }
+    }
+
+    /// Returns the decoded items
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+
+    /// Removes all items
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.ends.clear();
+    }
+
+    fn run_index(&self, index: usize) -> usize {
+        self.ends.partition_point(|end| *end <= index)
+    }
+
+    fn run_start(&self, run: usize) -> usize {
+        if run == 0 {
+            0
+        } else {
+            self.ends[run - 1]
+        }
+    }
+
+    // merges adjacent runs with equal values among runs [start, end)
+    fn merge_runs(&mut self, start: usize, end: usize) {
+        let end = core::cmp::min(end, self.ends.len());
+
+        for run in (start + 1..end).rev() {
+            if self.values[run] == self.values[run - 1] {
+                self.ends[run - 1] = self.ends[run];
+                self.values.remove(run);
+                self.ends.remove(run);
+            }
+        }
+    }
+}
+
+impl<T> Default for RleVec<T>
+where
+    T: Clone + PartialEq,
+{
+    fn default() -> Self {
+        RleVec::init()
+    }
+}
+
+impl<T> core::ops::Index<usize> for RleVec<T>
+where
+    T: Clone + PartialEq,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Some(item) => item,
+            None => panic!("Index out of range"),
+        }
+    }
+}
+
+impl<T> core::iter::FromIterator<T> for RleVec<T>
+where
+    T: Clone + PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut rle = RleVec::init();
+        for item in iter {
+            rle.push(item);
+        }
+
+        rle
+    }
+}
+
+/// Iterator over the runs of a `RleVec`
+pub struct Runs<'a, T> {
+    rle: &'a RleVec<T>,
+    run: usize,
+}
+
+impl<'a, T> core::iter::Iterator for Runs<'a, T> {
+    type Item = (Range<usize>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.run == self.rle.ends.len() {
+            return None;
+        }
+
+        let start = if self.run == 0 {
+            0
+        } else {
+            self.rle.ends[self.run - 1]
+        };
+        let item = (start..self.rle.ends[self.run], &self.rle.values[self.run]);
+        self.run += 1;
+
+        Some(item)
+    }
+}
+
+/// Iterator over the items of a `RleVec`
+pub struct Iter<'a, T> {
+    rle: &'a RleVec<T>,
+    run: usize,
+    index: usize,
+}
+
+impl<'a, T> core::iter::Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.run == self.rle.ends.len() {
+            return None;
+        }
+
+        let item = &self.rle.values[self.run];
+        self.index += 1;
+        if self.index == self.rle.ends[self.run] {
+            self.run += 1;
+        }
+
+        Some(item)
+    }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a RleVec<T>
+where
+    T: Clone + PartialEq,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> MemoryFootprint for RleVec<T> {
+    fn footprint(&self) -> Footprint {
+        // long runs make the allocated bytes smaller than the payload
+        let len = self.ends.last().copied().unwrap_or(0);
+        Footprint::of::<T>(len)
+            .with_vec(&self.values)
+            .with_vec(&self.ends)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_invariants(rle: &RleVec<u8>) {
+        for run in 1..rle.values.len() {
+            assert_ne!(rle.values[run], rle.values[run - 1]);
+            assert!(rle.ends[run] > rle.ends[run - 1]);
+        }
+        if !rle.ends.is_empty() {
+            assert!(rle.ends[0] > 0);
+        }
+    }
+
+    #[test]
+    fn sequence_rle_vec_matches_naive() {
+        let mut naive: Vec<u8> = (0..200).map(|i| (i / 17 % 3) as u8).collect();
+        let mut rle: RleVec<u8> = naive.iter().cloned().collect();
+
+        let mut state: usize = 7;
+        for _ in 0..2000 {
+            state = (state * 1103515245 + 12345) % (1 << 31);
+            let start = state % naive.len();
+            state = (state * 1103515245 + 12345) % (1 << 31);
+            let end = start + state % std::cmp::min(8, naive.len() - start + 1);
+            let value = (state % 3) as u8;
+
+            if end == start + 1 {
+                rle.set(start, value);
+            } else {
+                rle.set_range(start, end, value);
+            }
+            for item in naive[start..end].iter_mut() {
+                *item = value;
+            }
+
+            check_invariants(&rle);
+        }
+
+        assert_eq!(rle.to_vec(), naive);
+        assert_eq!(rle.size(), naive.len());
+        for (index, item) in naive.iter().enumerate() {
+            assert_eq!(rle.get(index), Some(item));
+        }
+    }
+
+    #[test]
+    fn sequence_rle_vec_runs() {
+        let mut rle = RleVec::init();
+        rle.push_run(1u8, 3);
+        rle.push_run(1, 2);
+        rle.push_run(2, 0);
+        rle.push(2);
+
+        assert_eq!(rle.run_count(), 2);
+        let runs: Vec<(Range<usize>, u8)> = rle.runs().map(|(r, v)| (r, *v)).collect();
+        assert_eq!(runs, vec![(0..5, 1), (5..6, 2)]);
+
+        rle.set(0, 2);
+        rle.set(5, 1);
+        assert_eq!(rle.to_vec(), vec![2, 1, 1, 1, 1, 1]);
+        assert_eq!(rle.run_count(), 2);
+        check_invariants(&rle);
+    }
+
+    #[test]
+    fn sequence_rle_vec_empty() {
+        let mut rle: RleVec<u8> = RleVec::default();
+
+        assert!(rle.is_empty());
+        assert_eq!(rle.get(0), None);
+        assert_eq!(rle.iter().count(), 0);
+        rle.set_range(0, 0, 1);
+        assert!(rle.is_empty());
+
+        rle.push(1);
+        rle.clear();
+        assert_eq!(rle.size(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn sequence_rle_vec_set_out_of_range() {
+        let mut rle = RleVec::from_slice(&[1, 2, 3]);
+        rle.set(3, 0);
+    }
+
+    #[test]
+    fn sequence_rle_vec_footprint() {
+        let mut rle = RleVec::init();
+        rle.push_run(7u64, 1000);
+        rle.push_run(9u64, 1000);
+
+        let footprint = rle.footprint();
+        assert_eq!(footprint.elements(), 2000);
+        assert_eq!(footprint.payload_bytes(), 16000);
+        assert_eq!(footprint.nodes(), 2);
+        assert!(footprint.overhead_per_element() < 0.0);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::heap::MinMax;
+use crate::util::{Footprint, MemoryFootprint};
+use std::cmp::Ordering;
+
+// a scheduled event, ordered by time and then by the order it was scheduled in
+struct Entry<E> {
+    time: u64,
+    sequence: u64,
+    event: E,
+}
+
+impl<E> Ord for Entry<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time, self.sequence).cmp(&(other.time, other.sequence))
+    }
+}
+
+impl<E> PartialOrd for Entry<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E> PartialEq for Entry<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<E> Eq for Entry<E> {}
+
+/// A two-level priority queue for events with integer timestamps, in the style of a calendar queue. Events in the
+/// near future fall into a window of buckets of equal width, and the far future is kept in a min-max heap. Pushing
+/// into the window and popping from it take O(1) time when every bucket holds a few events, which is the case when
+/// the width is about the average gap between events. When the window runs empty it moves to the earliest event of
+/// the heap and takes every event it covers from there. Events are delivered in the order of their times, events
+/// with the same time in the order they were scheduled
+///
+/// # Examples
+/// ```
+/// use rudac::sim::CalendarQueue;
+///
+/// // 4 buckets of width 10 cover the times 0 to 39
+/// let mut queue = CalendarQueue::init(10, 4);
+/// queue.schedule_at(25, "near");
+/// queue.schedule_at(1000, "far");
+/// queue.schedule_at(3, "first");
+///
+/// assert_eq!(queue.far_size(), 1);
+/// assert_eq!(queue.pop(), Some((3, "first")));
+/// assert_eq!(queue.pop(), Some((25, "near")));
+///
+/// queue.schedule_at(1005, "later");
+/// assert_eq!(queue.pop(), Some((1000, "far")));
+/// assert_eq!(queue.pop(), Some((1005, "later")));
+/// assert_eq!(queue.pop(), None);
+/// ```
+pub struct CalendarQueue<E> {
+    buckets: Vec<Vec<Entry<E>>>,
+    width: u64,
+
+    // time at the start of the first bucket, and the first bucket that may hold events
+    start: u64,
+    cursor: usize,
+    near: usize,
+
+    // events at or after the end of the window
+    far: MinMax<Entry<E>>,
+
+    now: u64,
+    next_sequence: u64,
+}
+
+impl<E> CalendarQueue<E> {
+    /// Initializes an empty queue whose clock starts at zero
+    ///
+    /// # Arguments
+    /// * `width`: range of times covered by a bucket
+    /// * `buckets`: number of buckets of the near future window
+    ///
+    /// # Panics
+    /// * panics if `width` or `buckets` is zero
+    pub fn init(width: u64, buckets: usize) -> CalendarQueue<E> {
+        if width == 0 {
+            panic!("Bucket width must be positive");
+        }
+        if buckets == 0 {
+            panic!("There must be at least one bucket");
+        }
+
+        CalendarQueue {
+            buckets: (0..buckets).map(|_| Vec::new()).collect(),
+            width,
+            start: 0,
+            cursor: 0,
+            near: 0,
+            far: MinMax::init(),
+            now: 0,
+            next_sequence: 0,
+        }
+    }
+
+    /// Returns the current time: the time of the latest delivered event
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Returns number of scheduled events
+    pub fn size(&self) -> usize {
+        self.near + self.far.size()
+    }
+
+    /// Returns true if there are no scheduled events
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Returns number of events beyond the near future window, kept in the heap
+    pub fn far_size(&self) -> usize {
+        self.far.size()
+    }
+
+    // end of the near future window(exclusive)
+    fn window_end(&self) -> u64 {
+        self.width
+            .saturating_mul(self.buckets.len() as u64)
+            .saturating_add(self.start)
+    }
+
+    fn insert(&mut self, entry: Entry<E>) {
+        if entry.time < self.window_end() {
+            let bucket = ((entry.time - self.start) / self.width) as usize;
+            self.buckets[bucket].push(entry);
+            self.near += 1;
+        } else {
+            self.far.push(entry);
+        }
+    }
+
+    /// Schedules `event` at `time`
+    /// * Complexity: O(1) in the near future window, O(log n) beyond it
+    ///
+    /// # Arguments
+    /// * `time`: time of the event
+    /// * `event`: the event
+    ///
+    /// # Panics
+    /// * panics if `time` is before the current time
+    pub fn schedule_at(&mut self, time: u64, event: E) {
+        if time < self.now {
+            panic!("Event can not be scheduled in the past");
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.insert(Entry {
+            time,
+            sequence,
+            event,
+        });
+    }
+
+    /// Schedules `event` at `delay` after the current time
+    ///
+    /// # Arguments
+    /// * `delay`: time from now until the event
+    /// * `event`: the event
+    pub fn schedule_after(&mut self, delay: u64, event: E) {
+        self.schedule_at(self.now + delay, event)
+    }
+
+    /// Returns time of the next event, `None` if there are no scheduled events
+    pub fn peek_time(&self) -> Option<u64> {
+        if self.near == 0 {
+            return self.far.peek_min().map(|entry| entry.time);
+        }
+
+        self.buckets[self.cursor..]
+            .iter()
+            .find(|bucket| !bucket.is_empty())
+            .and_then(|bucket| bucket.iter().map(|entry| entry.time).min())
+    }
+
+    /// Removes the next event, moves the clock to its time and returns the time and the event.
+    /// Returns `None` if there are no scheduled events
+    pub fn pop(&mut self) -> Option<(u64, E)> {
+        if self.near == 0 {
+            // moves the window to the earliest event and fills it from the heap
+            let first = self.far.peek_min()?.time;
+            self.start = first - first % self.width;
+            self.cursor = 0;
+            let end = self.window_end();
+            while self.far.peek_min().is_some_and(|entry| entry.time < end) {
+                let entry = self.far.pop_min().unwrap();
+                self.insert(entry);
+            }
+        }
+
+        while self.buckets[self.cursor].is_empty() {
+            self.cursor += 1;
+        }
+        let bucket = &mut self.buckets[self.cursor];
+        let (index, _) = bucket
+            .iter()
+            .enumerate()
+            .min_by(|(_, first), (_, second)| first.cmp(second))
+            .unwrap();
+        let entry = bucket.swap_remove(index);
+        self.near -= 1;
+        self.now = entry.time;
+
+        Some((entry.time, entry.event))
+    }
+}
+
+impl<E> MemoryFootprint for CalendarQueue<E> {
+    fn footprint(&self) -> Footprint {
+        self.buckets.iter().fold(
+            Footprint::of::<(u64, E)>(self.size())
+                .with(self.far.footprint())
+                .with_vec(&self.buckets),
+            |footprint, bucket| footprint.with_vec(bucket),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn sim_calendar_matches_sorted_order() {
+        let mut rng = SplitMix64::init(95);
+        for (width, buckets) in [(1, 1), (5, 8), (100, 3), (u64::MAX, 2)] {
+            let mut queue = CalendarQueue::init(width, buckets);
+            let mut pending: Vec<(u64, usize)> = Vec::new();
+            for id in 0..3000 {
+                if rng.next_below(3) == 0 {
+                    let popped = queue.pop();
+                    pending.sort();
+                    assert_eq!(popped, (!pending.is_empty()).then(|| pending.remove(0)));
+                } else {
+                    // mostly close to the current time, sometimes far away
+                    let delay = match rng.next_below(10) {
+                        0 => rng.next_below(100_000),
+                        _ => rng.next_below(30),
+                    };
+                    queue.schedule_after(delay, id);
+                    pending.push((queue.now() + delay, id));
+                }
+                assert_eq!(queue.size(), pending.len());
+                assert_eq!(
+                    queue.peek_time(),
+                    pending.iter().map(|(time, _)| *time).min()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sim_calendar_same_time_in_schedule_order() {
+        let mut queue = CalendarQueue::init(4, 2);
+        for id in 0..5 {
+            queue.schedule_at(100, id);
+            queue.schedule_at(1, id + 10);
+        }
+
+        let popped: Vec<(u64, i32)> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(
+            popped,
+            vec![
+                (1, 10),
+                (1, 11),
+                (1, 12),
+                (1, 13),
+                (1, 14),
+                (100, 0),
+                (100, 1),
+                (100, 2),
+                (100, 3),
+                (100, 4)
+            ]
+        );
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Event can not be scheduled in the past")]
+    fn sim_calendar_panic_schedule_in_past() {
+        let mut queue = CalendarQueue::init(10, 10);
+        queue.schedule_at(30, ());
+        queue.pop();
+        queue.schedule_at(29, ());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::heap::MinMax;
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::HashMap;
+use std::ops::Add;
+
+/// Identifies a scheduled event, used to cancel it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventHandle(u64);
+
+/// A discrete-event simulation queue. Events are kept in a min-max heap keyed by their time and are delivered
+/// in the order of their times, events scheduled for the same time in the order they were scheduled.
+/// Delivering an event moves the clock of the queue to its time, so handlers can schedule follow-up events
+/// relative to the current time. Cancelled events stay in the heap until they reach its top
+/// or until they make up half of it, when the heap is rebuilt without them
+///
+/// # Examples
+/// ```
+/// use rudac::sim::EventQueue;
+///
+/// let mut queue = EventQueue::init();
+/// queue.schedule_at(5, "lunch");
+/// let meeting = queue.schedule_at(3, "meeting");
+/// queue.schedule_after(1, "coffee");
+/// queue.cancel(meeting);
+///
+/// let mut log = Vec::new();
+/// queue.run_until(10, |queue, event| {
+///     log.push((queue.now(), event));
+///     if event == "coffee" {
+///         queue.schedule_after(2, "more coffee");
+///     }
+/// });
+///
+/// assert_eq!(log, vec![(1, "coffee"), (3, "more coffee"), (5, "lunch")]);
+/// assert_eq!(queue.now(), 10);
+/// ```
+pub struct EventQueue<E, T: Ord + Copy = u64> {
+    // time and sequence number of scheduled events, including cancelled ones
+    heap: MinMax<(T, u64)>,
+
+    // events that are neither delivered nor cancelled, by sequence number
+    events: HashMap<u64, E>,
+
+    now: T,
+    next_sequence: u64,
+}
+
+impl<E, T: Ord + Copy + Default> EventQueue<E, T> {
+    /// Initializes an empty queue whose clock starts at the default time, zero for numbers
+    pub fn init() -> EventQueue<E, T> {
+        EventQueue::starting_at(T::default())
+    }
+}
+
+impl<E, T: Ord + Copy> EventQueue<E, T> {
+    /// Initializes an empty queue whose clock starts at `time`
+    ///
+    /// # Arguments
+    /// * `time`: start time of the simulation
+    pub fn starting_at(time: T) -> EventQueue<E, T> {
+        EventQueue {
+            heap: MinMax::init(),
+            events: HashMap::new(),
+            now: time,
+            next_sequence: 0,
+        }
+    }
+
+    /// Returns the current time: the time of the latest delivered event or the end of the latest run
+    pub fn now(&self) -> T {
+        self.now
+    }
+
+    /// Returns number of scheduled events
+    pub fn size(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if there are no scheduled events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Schedules `event` at `time` and returns a handle that can cancel it
+    ///
+    /// # Arguments
+    /// * `time`: time of the event
+    /// * `event`: the event
+    ///
+    /// # Panics
+    /// * panics if `time` is before the current time
+    pub fn schedule_at(&mut self, time: T, event: E) -> EventHandle {
+        if time < self.now {
+            panic!("Event can not be scheduled in the past");
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.heap.push((time, sequence));
+        self.events.insert(sequence, event);
+
+        EventHandle(sequence)
+    }
+
+    /// Cancels a scheduled event and returns it, or returns `None` if the event was already delivered or cancelled
+    ///
+    /// # Arguments
+    /// * `handle`: handle returned when the event was scheduled
+    pub fn cancel(&mut self, handle: EventHandle) -> Option<E> {
+        let event = self.events.remove(&handle.0)?;
+
+        if self.heap.size() > 2 * self.events.len() + 16 {
+            let events = &self.events;
+            let scheduled = core::mem::take(&mut self.heap)
+                .into_vec()
+                .into_iter()
+                .filter(|(_, sequence)| events.contains_key(sequence))
+                .collect();
+            self.heap = MinMax::build_heap(scheduled);
+        }
+
+        Some(event)
+    }
+
+    /// Returns true if the event of `handle` is scheduled, that is neither delivered nor cancelled
+    ///
+    /// # Arguments
+    /// * `handle`: handle returned when the event was scheduled
+    pub fn is_scheduled(&self, handle: EventHandle) -> bool {
+        self.events.contains_key(&handle.0)
+    }
+
+    /// Returns time of the next event, `None` if there are no scheduled events
+    pub fn peek_time(&mut self) -> Option<T> {
+        self.skip_cancelled();
+
+        self.heap.peek_min().map(|(time, _)| *time)
+    }
+
+    /// Removes the next event, moves the clock to its time and returns the time and the event.
+    /// Returns `None` if there are no scheduled events
+    pub fn pop(&mut self) -> Option<(T, E)> {
+        self.skip_cancelled();
+
+        let (time, sequence) = self.heap.pop_min()?;
+        self.now = time;
+
+        Some((time, self.events.remove(&sequence).unwrap()))
+    }
+
+    /// Delivers every event up to and including `time` to `handler` in order, then moves the clock to `time`.
+    /// The handler receives the queue, so it can read the current time and schedule or cancel events;
+    /// events it schedules up to `time` are delivered in the same run. Returns number of delivered events
+    ///
+    /// # Arguments
+    /// * `time`: end of the run
+    /// * `handler`: called with every delivered event
+    pub fn run_until<F: FnMut(&mut EventQueue<E, T>, E)>(
+        &mut self,
+        time: T,
+        mut handler: F,
+    ) -> usize {
+        let mut delivered = 0;
+        while self.peek_time().is_some_and(|next| next <= time) {
+            let (_, event) = self.pop().unwrap();
+            handler(self, event);
+            delivered += 1;
+        }
+
+        if self.now < time {
+            self.now = time;
+        }
+
+        delivered
+    }
+
+    /// Removes every scheduled event, the clock is not changed
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.events.clear();
+    }
+
+    // drops cancelled events from the top of the heap
+    fn skip_cancelled(&mut self) {
+        while let Some((_, sequence)) = self.heap.peek_min() {
+            if self.events.contains_key(sequence) {
+                break;
+            }
+            self.heap.pop_min();
+        }
+    }
+}
+
+impl<E, T: Ord + Copy + Add<Output = T>> EventQueue<E, T> {
+    /// Schedules `event` at `delay` after the current time and returns a handle that can cancel it
+    ///
+    /// # Arguments
+    /// * `delay`: time from now until the event
+    /// * `event`: the event
+    pub fn schedule_after(&mut self, delay: T, event: E) -> EventHandle {
+        self.schedule_at(self.now + delay, event)
+    }
+}
+
+impl<E, T: Ord + Copy + Default> Default for EventQueue<E, T> {
+    fn default() -> Self {
+        EventQueue::init()
+    }
+}
+
+impl<E, T: Ord + Copy> MemoryFootprint for EventQueue<E, T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<(T, E)>(self.size())
+            .with(self.heap.footprint())
+            .with_hash_map(&self.events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn sim_event_queue_delivers_in_time_order() {
+        let mut rng = SplitMix64::init(8);
+        let mut queue = EventQueue::init();
+        let mut expected = Vec::new();
+        let mut handles = Vec::new();
+        for id in 0..500 {
+            let time = rng.next_below(100);
+            handles.push(queue.schedule_at(time, id));
+            expected.push((time, id));
+        }
+        for id in (0..500).step_by(3) {
+            assert_eq!(queue.cancel(handles[id]), Some(id as u64));
+            assert_eq!(queue.cancel(handles[id]), None);
+            assert!(!queue.is_scheduled(handles[id]));
+        }
+        expected.retain(|(_, id)| id % 3 != 0);
+        // events at the same time keep the order they were scheduled in
+        expected.sort();
+
+        assert_eq!(queue.size(), expected.len());
+        assert!(queue.heap.size() < 2 * expected.len() + 16);
+
+        let delivered: Vec<(u64, u64)> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(delivered, expected);
+        assert!(queue.is_empty());
+        assert_eq!(queue.peek_time(), None);
+    }
+
+    #[test]
+    fn sim_event_queue_run_until() {
+        let mut queue: EventQueue<u32, i64> = EventQueue::starting_at(-10);
+        queue.schedule_after(5, 1);
+        queue.schedule_at(20, 2);
+
+        // every event schedules its successor 4 time units later
+        let mut seen = Vec::new();
+        let delivered = queue.run_until(10, |queue, event| {
+            seen.push((queue.now(), event));
+            if event < 10 {
+                queue.schedule_after(4, event + 10);
+            }
+        });
+
+        assert_eq!(delivered, 2);
+        assert_eq!(seen, vec![(-5, 1), (-1, 11)]);
+        assert_eq!(queue.now(), 10);
+        assert_eq!(queue.peek_time(), Some(20));
+
+        assert_eq!(queue.run_until(0, |_, _| {}), 0);
+        assert_eq!(queue.now(), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Event can not be scheduled in the past")]
+    fn sim_event_queue_panic_schedule_in_past() {
+        let mut queue = EventQueue::init();
+        queue.schedule_at(3, ());
+        queue.pop();
+        queue.schedule_at(2, ());
+    }
+}
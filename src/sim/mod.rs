@@ -0,0 +1,8 @@
+//! Discrete-event simulation on top of the rudac heaps.
+
+mod calendar;
+mod event_queue;
+
+pub use calendar::CalendarQueue;
+pub use event_queue::EventHandle;
+pub use event_queue::EventQueue;
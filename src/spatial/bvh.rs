@@ -0,0 +1,741 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+// nodes with at most this many items are always leaves
+const LEAF_SIZE: usize = 2;
+
+// number of buckets the centroids are sorted into when looking for a split
+const BUCKETS: usize = 16;
+
+// cost of visiting a node relative to testing an item
+const TRAVERSAL_COST: f64 = 1.0;
+
+// axis aligned box given by its low and high corners
+#[derive(Debug, Clone, Copy)]
+struct Aabb<const K: usize> {
+    low: [f64; K],
+    high: [f64; K],
+}
+
+impl<const K: usize> Aabb<K> {
+    // box containing nothing, growing it by a box gives that box
+    fn empty() -> Aabb<K> {
+        Aabb {
+            low: [f64::INFINITY; K],
+            high: [f64::NEG_INFINITY; K],
+        }
+    }
+
+    fn grow(&mut self, other: &Aabb<K>) {
+        for axis in 0..K {
+            self.low[axis] = self.low[axis].min(other.low[axis]);
+            self.high[axis] = self.high[axis].max(other.high[axis]);
+        }
+    }
+
+    fn centroid(&self, axis: usize) -> f64 {
+        (self.low[axis] + self.high[axis]) / 2.0
+    }
+
+    // half of the surface area in 3D, generalized to the sum over the axes of the product of the other extents
+    fn area(&self) -> f64 {
+        if (0..K).any(|axis| self.low[axis] > self.high[axis]) {
+            return 0.0;
+        }
+        if K == 1 {
+            return 1.0;
+        }
+
+        (0..K)
+            .map(|skipped| {
+                (0..K)
+                    .filter(|axis| *axis != skipped)
+                    .map(|axis| self.high[axis] - self.low[axis])
+                    .product::<f64>()
+            })
+            .sum()
+    }
+
+    fn overlaps(&self, low: &[f64; K], high: &[f64; K]) -> bool {
+        (0..K).all(|axis| self.low[axis] <= high[axis] && low[axis] <= self.high[axis])
+    }
+
+    // returns the distance along the ray at which it enters the box, None if it misses the box before `max_distance`
+    fn entry(&self, origin: &[f64; K], inverse: &[f64; K], max_distance: f64) -> Option<f64> {
+        let mut enter: f64 = 0.0;
+        let mut exit = max_distance;
+        for axis in 0..K {
+            let t1 = (self.low[axis] - origin[axis]) * inverse[axis];
+            let t2 = (self.high[axis] - origin[axis]) * inverse[axis];
+
+            // NaN comes from a ray parallel to the slab starting on its border, which does not restrict the ray
+            enter = enter.max(t1.min(t2));
+            exit = exit.min(t1.max(t2));
+        }
+
+        if enter <= exit {
+            Some(enter)
+        } else {
+            None
+        }
+    }
+}
+
+// node of the hierarchy, children come after their parent in the vector of nodes
+#[derive(Debug, Clone)]
+struct Node<const K: usize> {
+    bounds: Aabb<K>,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Inner { left: usize, right: usize },
+
+    // items order[start..start + count]
+    Leaf { start: usize, count: usize },
+}
+
+/// A bounding volume hierarchy is a tree of axis aligned boxes over items that have a box, such as the triangles of
+/// a mesh or the bodies of a physics scene, in `K` dimensions. Every node holds the box around the items below it,
+/// so a query skips every subtree whose box misses the ray or the box it looks for. The tree is built top down,
+/// splitting every node where the surface area heuristic predicts the cheapest ray queries, with the centroids of the
+/// items sorted into buckets along every axis. When items move a little, `refit` recomputes the boxes of the nodes
+/// in O(n) time without changing the tree, which stays efficient as long as the motion is small compared to the
+/// scene; `rebuild` starts over. Coordinates must not be NaN
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::Bvh;
+///
+/// let mut bvh = Bvh::build(vec![
+///     ("crate", [0.0, 0.0, 0.0], [1.0, 1.0, 1.0]),
+///     ("barrel", [3.0, 0.0, 0.0], [4.0, 2.0, 1.0]),
+///     ("lamp", [3.0, 5.0, 3.0], [3.5, 6.0, 3.5]),
+/// ]);
+///
+/// // a ray along the x axis hits the crate first
+/// let hits = bvh.ray(&[-5.0, 0.5, 0.5], &[1.0, 0.0, 0.0], f64::INFINITY);
+/// let names: Vec<&str> = hits.iter().map(|(name, _)| **name).collect();
+/// assert_eq!(names, vec!["crate", "barrel"]);
+/// assert_eq!(hits[0].1, 5.0);
+///
+/// bvh.set_bounds(2, [0.0, 0.0, 2.0], [1.0, 1.0, 3.0]);
+/// bvh.refit();
+/// let mut near: Vec<&str> = bvh.query_box(&[0.0, 0.0, 0.0], &[1.0, 1.0, 5.0]).into_iter().copied().collect();
+/// near.sort();
+/// assert_eq!(near, vec!["crate", "lamp"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bvh<T, const K: usize> {
+    items: Vec<T>,
+    bounds: Vec<Aabb<K>>,
+
+    // items in the order of the leaves
+    order: Vec<usize>,
+    nodes: Vec<Node<K>>,
+}
+
+impl<T, const K: usize> Bvh<T, K> {
+    /// Builds the hierarchy of `items`, every one with the low and high corners of its box
+    /// * Complexity: O(n log n)
+    ///
+    /// # Arguments
+    /// * `items`: items with the low and high corners of their boxes
+    ///
+    /// # Panics
+    /// * panics if `K` is zero
+    /// * panics if a coordinate of a low corner is larger than the one of its high corner
+    pub fn build(items: Vec<(T, [f64; K], [f64; K])>) -> Bvh<T, K> {
+        if K == 0 {
+            panic!("Points must have at least one dimension");
+        }
+
+        let mut bvh = Bvh {
+            items: Vec::with_capacity(items.len()),
+            bounds: Vec::with_capacity(items.len()),
+            order: Vec::new(),
+            nodes: Vec::new(),
+        };
+        for (item, low, high) in items {
+            if (0..K).any(|axis| low[axis] > high[axis]) {
+                panic!("Low corner must not be larger than high corner");
+            }
+            bvh.items.push(item);
+            bvh.bounds.push(Aabb { low, high });
+        }
+        bvh.rebuild();
+
+        bvh
+    }
+
+    /// Builds the tree again from the current boxes of the items, which is better than `refit` after large motions
+    /// * Complexity: O(n log n)
+    pub fn rebuild(&mut self) {
+        self.order = (0..self.items.len()).collect();
+        self.nodes.clear();
+        if !self.items.is_empty() {
+            self.split(0, self.items.len());
+        }
+    }
+
+    // builds the node of the items order[start..end] and its subtree, returns the index of the node
+    fn split(&mut self, start: usize, end: usize) -> usize {
+        let mut bounds = Aabb::empty();
+        let mut centroids = Aabb::empty();
+        for &item in &self.order[start..end] {
+            bounds.grow(&self.bounds[item]);
+            for axis in 0..K {
+                let centroid = self.bounds[item].centroid(axis);
+                centroids.low[axis] = centroids.low[axis].min(centroid);
+                centroids.high[axis] = centroids.high[axis].max(centroid);
+            }
+        }
+
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            kind: Kind::Leaf {
+                start,
+                count: end - start,
+            },
+        });
+        if end - start <= LEAF_SIZE {
+            return node;
+        }
+
+        let (axis, threshold, cost) = match self.best_split(start, end, &bounds, &centroids) {
+            Some(split) => split,
+            None => return node,
+        };
+        if cost >= (end - start) as f64 {
+            return node;
+        }
+
+        // moves the items whose centroid is left of the threshold to the front
+        let mut middle = start;
+        for index in start..end {
+            if self.bounds[self.order[index]].centroid(axis) < threshold {
+                self.order.swap(index, middle);
+                middle += 1;
+            }
+        }
+        if middle == start || middle == end {
+            return node;
+        }
+
+        let left = self.split(start, middle);
+        let right = self.split(middle, end);
+        self.nodes[node].kind = Kind::Inner { left, right };
+
+        node
+    }
+
+    // returns the axis, the centroid threshold and the expected cost of the cheapest split of order[start..end]
+    fn best_split(
+        &self,
+        start: usize,
+        end: usize,
+        bounds: &Aabb<K>,
+        centroids: &Aabb<K>,
+    ) -> Option<(usize, f64, f64)> {
+        let area = bounds.area();
+        let mut best: Option<(usize, f64, f64)> = None;
+        for axis in 0..K {
+            let first = centroids.low[axis];
+            let extent = centroids.high[axis] - first;
+            if extent <= 0.0 {
+                continue;
+            }
+
+            let bucket_of = |item: usize| {
+                let offset = (self.bounds[item].centroid(axis) - first) / extent;
+                ((offset * BUCKETS as f64) as usize).min(BUCKETS - 1)
+            };
+            let mut counts = [0usize; BUCKETS];
+            let mut boxes = [Aabb::empty(); BUCKETS];
+            for &item in &self.order[start..end] {
+                let bucket = bucket_of(item);
+                counts[bucket] += 1;
+                boxes[bucket].grow(&self.bounds[item]);
+            }
+
+            // areas and counts of the buckets right of every boundary, swept from the right
+            let mut right_area = [0.0; BUCKETS];
+            let mut right_count = [0usize; BUCKETS];
+            let mut grown = Aabb::empty();
+            let mut count = 0;
+            for bucket in (1..BUCKETS).rev() {
+                grown.grow(&boxes[bucket]);
+                count += counts[bucket];
+                right_area[bucket] = grown.area();
+                right_count[bucket] = count;
+            }
+
+            let mut grown = Aabb::empty();
+            let mut count = 0;
+            for boundary in 1..BUCKETS {
+                grown.grow(&boxes[boundary - 1]);
+                count += counts[boundary - 1];
+                if count == 0 || right_count[boundary] == 0 {
+                    continue;
+                }
+
+                let cost = TRAVERSAL_COST
+                    + if area > 0.0 {
+                        (grown.area() * count as f64
+                            + right_area[boundary] * right_count[boundary] as f64)
+                            / area
+                    } else {
+                        (end - start) as f64
+                    };
+                if best.is_none_or(|best| cost < best.2) {
+                    let threshold = first + extent * boundary as f64 / BUCKETS as f64;
+                    best = Some((axis, threshold, cost));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Returns number of items in the hierarchy
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items in the hierarchy
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns items of the hierarchy in the order they were given to `build`
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns the low and high corners of the box of the item at `index`
+    ///
+    /// # Arguments
+    /// * `index`: index of the item in the order given to `build`
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn bounds(&self, index: usize) -> (&[f64; K], &[f64; K]) {
+        let bounds = &self.bounds[index];
+
+        (&bounds.low, &bounds.high)
+    }
+
+    /// Returns the depth of the tree, 0 if it is empty
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut stack = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![(0, 1)]
+        };
+        while let Some((node, level)) = stack.pop() {
+            depth = depth.max(level);
+            if let Kind::Inner { left, right } = self.nodes[node].kind {
+                stack.push((left, level + 1));
+                stack.push((right, level + 1));
+            }
+        }
+
+        depth
+    }
+
+    /// Sets the box of the item at `index` to the corners `low` and `high`. Queries may miss the item until `refit`
+    /// or `rebuild` is called
+    ///
+    /// # Arguments
+    /// * `index`: index of the item in the order given to `build`
+    /// * `low`: the new corner with the smallest coordinates
+    /// * `high`: the new corner with the largest coordinates
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    /// * panics if a coordinate of `low` is larger than the one of `high`
+    pub fn set_bounds(&mut self, index: usize, low: [f64; K], high: [f64; K]) {
+        if (0..K).any(|axis| low[axis] > high[axis]) {
+            panic!("Low corner must not be larger than high corner");
+        }
+
+        self.bounds[index] = Aabb { low, high };
+    }
+
+    /// Recomputes the boxes of the nodes bottom up after boxes of items changed, keeping the shape of the tree
+    /// * Complexity: O(n)
+    pub fn refit(&mut self) {
+        // children come after their parent, so a backward sweep sees them first
+        for node in (0..self.nodes.len()).rev() {
+            let mut bounds = Aabb::empty();
+            match self.nodes[node].kind {
+                Kind::Inner { left, right } => {
+                    bounds.grow(&self.nodes[left].bounds);
+                    bounds.grow(&self.nodes[right].bounds);
+                }
+                Kind::Leaf { start, count } => {
+                    for &item in &self.order[start..start + count] {
+                        bounds.grow(&self.bounds[item]);
+                    }
+                }
+            }
+            self.nodes[node].bounds = bounds;
+        }
+    }
+
+    /// Returns the items whose boxes overlap the box with corners `low` and `high`, borders included, in no
+    /// particular order
+    ///
+    /// # Arguments
+    /// * `low`: the corner with the smallest coordinates
+    /// * `high`: the corner with the largest coordinates
+    pub fn query_box(&self, low: &[f64; K], high: &[f64; K]) -> Vec<&T> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if !node.bounds.overlaps(low, high) {
+                continue;
+            }
+            match node.kind {
+                Kind::Inner { left, right } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                Kind::Leaf { start, count } => {
+                    for &item in &self.order[start..start + count] {
+                        if self.bounds[item].overlaps(low, high) {
+                            result.push(&self.items[item]);
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns the items whose boxes are hit by the ray from `origin` along `direction` before `max_distance`,
+    /// with the distance at which the ray enters every box, sorted from nearest to farthest. Distances are measured
+    /// in lengths of `direction`, a ray starting inside a box enters it at 0
+    ///
+    /// # Arguments
+    /// * `origin`: start of the ray
+    /// * `direction`: direction of the ray
+    /// * `max_distance`: length of the ray
+    pub fn ray(
+        &self,
+        origin: &[f64; K],
+        direction: &[f64; K],
+        max_distance: f64,
+    ) -> Vec<(&T, f64)> {
+        let inverse = inverse(direction);
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if node.bounds.entry(origin, &inverse, max_distance).is_none() {
+                continue;
+            }
+            match node.kind {
+                Kind::Inner { left, right } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                Kind::Leaf { start, count } => {
+                    for &item in &self.order[start..start + count] {
+                        if let Some(distance) =
+                            self.bounds[item].entry(origin, &inverse, max_distance)
+                        {
+                            result.push((&self.items[item], distance));
+                        }
+                    }
+                }
+            }
+        }
+
+        result.sort_by(|hit1, hit2| hit1.1.total_cmp(&hit2.1));
+        result
+    }
+
+    /// Returns the nearest item actually hit by the ray from `origin` along `direction`, with the distance of the
+    /// hit. `hit` tests the ray against an item whose box the ray enters, such as a triangle, and returns the
+    /// distance of the hit or None. Nodes are visited front to back and skipped once their box starts behind the
+    /// nearest hit found so far, so most items are never tested
+    ///
+    /// # Arguments
+    /// * `origin`: start of the ray
+    /// * `direction`: direction of the ray
+    /// * `hit`: exact test of the ray against an item
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::Bvh;
+    ///
+    /// // spheres as (center, radius), hit by rays along the x axis
+    /// let spheres = vec![([4.0, 0.0, 0.0], 1.0), ([8.0, 0.0, 0.0], 2.0), ([4.0, 9.0, 0.0], 1.0)];
+    /// let bvh = Bvh::build(
+    ///     spheres
+    ///         .into_iter()
+    ///         .map(|(c, r): ([f64; 3], f64)| ((c, r), [c[0] - r, c[1] - r, c[2] - r], [c[0] + r, c[1] + r, c[2] + r]))
+    ///         .collect(),
+    /// );
+    ///
+    /// let along_x = |sphere: &([f64; 3], f64)| {
+    ///     let ([x, y, z], r) = *sphere;
+    ///     let squared = r * r - y * y - z * z;
+    ///     if squared < 0.0 { None } else { Some(x - squared.sqrt()) }
+    /// };
+    /// let (sphere, distance) = bvh.cast_ray(&[0.0, 0.0, 0.0], &[1.0, 0.0, 0.0], along_x).unwrap();
+    /// assert_eq!((sphere.1, distance), (1.0, 3.0));
+    /// ```
+    pub fn cast_ray<F>(
+        &self,
+        origin: &[f64; K],
+        direction: &[f64; K],
+        mut hit: F,
+    ) -> Option<(&T, f64)>
+    where
+        F: FnMut(&T) -> Option<f64>,
+    {
+        let inverse = inverse(direction);
+        let mut nearest: Option<(usize, f64)> = None;
+        let mut stack: Vec<(usize, f64)> = Vec::new();
+        if let Some(entry) = self
+            .nodes
+            .first()
+            .and_then(|root| root.bounds.entry(origin, &inverse, f64::INFINITY))
+        {
+            stack.push((0, entry));
+        }
+
+        while let Some((node, entry)) = stack.pop() {
+            let limit = nearest.map_or(f64::INFINITY, |nearest| nearest.1);
+            if entry > limit {
+                continue;
+            }
+
+            match self.nodes[node].kind {
+                Kind::Inner { left, right } => {
+                    let enter =
+                        |child: usize| self.nodes[child].bounds.entry(origin, &inverse, limit);
+                    let mut children: Vec<(usize, f64)> = [left, right]
+                        .iter()
+                        .filter_map(|&child| enter(child).map(|entry| (child, entry)))
+                        .collect();
+
+                    // the nearer child is pushed last so it is visited first
+                    children.sort_by(|child1, child2| child2.1.total_cmp(&child1.1));
+                    stack.extend(children);
+                }
+                Kind::Leaf { start, count } => {
+                    for &item in &self.order[start..start + count] {
+                        if self.bounds[item].entry(origin, &inverse, limit).is_none() {
+                            continue;
+                        }
+                        if let Some(distance) = hit(&self.items[item]) {
+                            if distance >= 0.0 && nearest.is_none_or(|nearest| distance < nearest.1)
+                            {
+                                nearest = Some((item, distance));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        nearest.map(|(item, distance)| (&self.items[item], distance))
+    }
+}
+
+// returns the inverse of every component of `direction`, infinite for zero components
+fn inverse<const K: usize>(direction: &[f64; K]) -> [f64; K] {
+    let mut inverse = [0.0; K];
+    for axis in 0..K {
+        inverse[axis] = 1.0 / direction[axis];
+    }
+
+    inverse
+}
+
+impl<T, const K: usize> MemoryFootprint for Bvh<T, K> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.items.len())
+            .with_vec(&self.items)
+            .with_vec(&self.bounds)
+            .with_vec(&self.order)
+            .with_vec(&self.nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn random_boxes(n: usize, rng: &mut SplitMix64) -> Vec<(usize, [f64; 3], [f64; 3])> {
+        (0..n)
+            .map(|index| {
+                let mut low = [0.0; 3];
+                let mut high = [0.0; 3];
+                for axis in 0..3 {
+                    low[axis] = rng.next_below(1000) as f64 / 10.0;
+                    high[axis] = low[axis] + rng.next_below(50) as f64 / 10.0;
+                }
+                (index, low, high)
+            })
+            .collect()
+    }
+
+    // spheres inscribed in the boxes, hit by the ray at the returned distance
+    fn sphere_hit(
+        boxes: &[(usize, [f64; 3], [f64; 3])],
+        origin: [f64; 3],
+        direction: [f64; 3],
+    ) -> impl Fn(&usize) -> Option<f64> + '_ {
+        move |index| {
+            let (_, low, high) = boxes[*index];
+            let radius = (0..3)
+                .map(|axis| high[axis] - low[axis])
+                .fold(f64::INFINITY, f64::min)
+                / 2.0;
+            let offset: Vec<f64> = (0..3)
+                .map(|axis| origin[axis] - (low[axis] + high[axis]) / 2.0)
+                .collect();
+            let a: f64 = direction.iter().map(|d| d * d).sum();
+            let b: f64 = (0..3)
+                .map(|axis| 2.0 * offset[axis] * direction[axis])
+                .sum();
+            let c: f64 = offset.iter().map(|o| o * o).sum::<f64>() - radius * radius;
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return None;
+            }
+            let t = (-b - discriminant.sqrt()) / (2.0 * a);
+            if t >= 0.0 {
+                Some(t)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn check_queries(
+        bvh: &Bvh<usize, 3>,
+        boxes: &[(usize, [f64; 3], [f64; 3])],
+        rng: &mut SplitMix64,
+    ) {
+        for _ in 0..50 {
+            let (_, low, high) = random_boxes(1, rng)[0];
+            let high = [high[0] + 10.0, high[1] + 10.0, high[2] + 10.0];
+            let mut found: Vec<usize> = bvh.query_box(&low, &high).into_iter().copied().collect();
+            found.sort_unstable();
+            let expected: Vec<usize> = boxes
+                .iter()
+                .filter(|(_, l, h)| {
+                    (0..3).all(|axis| l[axis] <= high[axis] && low[axis] <= h[axis])
+                })
+                .map(|(index, _, _)| *index)
+                .collect();
+            assert_eq!(found, expected);
+
+            let origin = [
+                -10.0,
+                rng.next_below(1000) as f64 / 10.0,
+                rng.next_below(1000) as f64 / 10.0,
+            ];
+            let direction = [
+                1.0,
+                rng.next_below(100) as f64 / 200.0 - 0.25,
+                rng.next_below(100) as f64 / 200.0 - 0.25,
+            ];
+            let inverse = inverse(&direction);
+            let mut hits: Vec<(usize, f64)> = boxes
+                .iter()
+                .filter_map(|(index, low, high)| {
+                    Aabb {
+                        low: *low,
+                        high: *high,
+                    }
+                    .entry(&origin, &inverse, 80.0)
+                    .map(|distance| (*index, distance))
+                })
+                .collect();
+            hits.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            let mut found: Vec<(usize, f64)> = bvh
+                .ray(&origin, &direction, 80.0)
+                .into_iter()
+                .map(|(i, d)| (*i, d))
+                .collect();
+            found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            assert_eq!(found, hits);
+
+            let hit = sphere_hit(boxes, origin, direction);
+            let expected = boxes
+                .iter()
+                .filter_map(|(index, _, _)| hit(index))
+                .min_by(f64::total_cmp);
+            assert_eq!(
+                bvh.cast_ray(&origin, &direction, &hit).map(|(_, d)| d),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn spatial_bvh_matches_brute_force() {
+        let mut rng = SplitMix64::init(59);
+        let boxes = random_boxes(500, &mut rng);
+        let bvh = Bvh::build(boxes.clone());
+
+        assert_eq!(bvh.size(), 500);
+        assert!(bvh.depth() < 40);
+        check_queries(&bvh, &boxes, &mut rng);
+    }
+
+    #[test]
+    fn spatial_bvh_refit_and_rebuild() {
+        let mut rng = SplitMix64::init(61);
+        let mut boxes = random_boxes(300, &mut rng);
+        let mut bvh = Bvh::build(boxes.clone());
+
+        for (index, low, high) in boxes.iter_mut() {
+            let shift = rng.next_below(40) as f64 / 10.0 - 2.0;
+            for axis in 0..3 {
+                low[axis] += shift;
+                high[axis] += shift;
+            }
+            bvh.set_bounds(*index, *low, *high);
+        }
+        bvh.refit();
+        check_queries(&bvh, &boxes, &mut rng);
+
+        bvh.rebuild();
+        check_queries(&bvh, &boxes, &mut rng);
+    }
+
+    #[test]
+    fn spatial_bvh_degenerate_items() {
+        // identical boxes cannot be split, and an empty hierarchy answers nothing
+        let bvh = Bvh::build((0..20).map(|i| (i, [1.0, 1.0], [2.0, 2.0])).collect());
+        assert_eq!(bvh.query_box(&[0.0, 0.0], &[1.0, 1.0]).len(), 20);
+        assert_eq!(bvh.ray(&[0.0, 1.5], &[1.0, 0.0], 10.0).len(), 20);
+
+        let empty: Bvh<u8, 2> = Bvh::build(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.depth(), 0);
+        assert!(empty
+            .cast_ray(&[0.0, 0.0], &[1.0, 0.0], |_| Some(1.0))
+            .is_none());
+    }
+}
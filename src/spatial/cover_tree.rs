@@ -0,0 +1,439 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// node of the tree, the node of an item has the index of the item. children of a node on level i are within
+// distance 2^i of it and are on level i - 1
+struct Node {
+    level: i32,
+    children: Vec<usize>,
+
+    // bound on the distance from the item of the node to the items of its descendants
+    max_distance: f64,
+}
+
+// candidate of a search, ordered by distance so that the farthest candidate is on top of the heap
+struct Candidate {
+    distance: f64,
+    item: usize,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.item.cmp(&other.item))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+// distance covered by the children of a node on `level`
+fn covering_distance(level: i32) -> f64 {
+    2f64.powi(level)
+}
+
+/// A cover tree is a metric tree for nearest neighbor search that grows one item at a time. Every node is on a
+/// level i, its children are within distance 2^i of it and on level i - 1, so the levels form a hierarchy of ever
+/// finer covers of the items. Searches prune subtrees with the triangle inequality like `VpTree` does, but items can
+/// be inserted at any time. For items of expansion constant c, an insertion and a nearest neighbor query take
+/// O(c^6 log n) time, in O(n) space
+///
+/// The metric must be non-negative, symmetric and satisfy the triangle inequality. Otherwise results may be wrong.
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::CoverTree;
+///
+/// let euclidean = |a: &(f64, f64), b: &(f64, f64)| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+/// let mut tree = CoverTree::init(euclidean);
+/// tree.insert((0.0, 0.0));
+/// tree.insert((5.0, 5.0));
+/// tree.insert((9.0, 2.0));
+///
+/// assert_eq!(tree.nearest(&(4.0, 4.0)).unwrap().0, &(5.0, 5.0));
+///
+/// tree.insert((3.5, 4.0));
+/// let (nearest, distance) = tree.nearest(&(4.0, 4.0)).unwrap();
+/// assert_eq!(*nearest, (3.5, 4.0));
+/// assert_eq!(distance, 0.5);
+/// ```
+pub struct CoverTree<T, F>
+where
+    F: Fn(&T, &T) -> f64,
+{
+    items: Vec<T>,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    metric: F,
+}
+
+impl<T, F> CoverTree<T, F>
+where
+    F: Fn(&T, &T) -> f64,
+{
+    /// Initializes an empty tree
+    ///
+    /// # Arguments
+    /// * `metric`: distance function
+    pub fn init(metric: F) -> CoverTree<T, F> {
+        CoverTree {
+            items: Vec::new(),
+            nodes: Vec::new(),
+            root: None,
+            metric,
+        }
+    }
+
+    /// Builds a tree of `items` by inserting them in order
+    ///
+    /// # Arguments
+    /// * `items`: items to be indexed
+    /// * `metric`: distance function
+    pub fn from_vec(items: Vec<T>, metric: F) -> CoverTree<T, F> {
+        let mut tree = CoverTree::init(metric);
+        tree.items.reserve(items.len());
+        tree.nodes.reserve(items.len());
+        for item in items {
+            tree.insert(item);
+        }
+
+        tree
+    }
+
+    /// Returns number of items in the tree
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items in the tree
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns items of the tree in the order they were inserted
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    // returns the distance between the items at `item1` and `item2`
+    fn distance(&self, item1: usize, item2: usize) -> f64 {
+        (self.metric)(&self.items[item1], &self.items[item2])
+    }
+
+    /// Inserts `item` into the tree
+    ///
+    /// # Arguments
+    /// * `item`: item to be inserted
+    pub fn insert(&mut self, item: T) {
+        let new = self.items.len();
+        self.items.push(item);
+        self.nodes.push(Node {
+            level: 0,
+            children: Vec::new(),
+            max_distance: 0.0,
+        });
+
+        let mut root = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(new);
+                return;
+            }
+        };
+
+        // a single node can take any level, the lowest one covering the new item keeps levels small
+        let mut distance = self.distance(root, new);
+        if self.nodes[root].children.is_empty() && distance > 0.0 {
+            let mut level = distance.log2().ceil() as i32;
+            while covering_distance(level) < distance {
+                level += 1;
+            }
+            self.nodes[root].level = level;
+        }
+
+        if distance > covering_distance(self.nodes[root].level) {
+            // a leaf moved above the root covers it, until the root is close enough to be a child of the new item
+            while distance > 2.0 * covering_distance(self.nodes[root].level) {
+                let leaf = self.take_leaf(root);
+                self.nodes[leaf] = Node {
+                    level: self.nodes[root].level + 1,
+                    children: vec![root],
+                    max_distance: self.distance(leaf, root) + self.nodes[root].max_distance,
+                };
+                root = leaf;
+                distance = self.distance(root, new);
+            }
+
+            self.nodes[new] = Node {
+                level: self.nodes[root].level + 1,
+                children: vec![root],
+                max_distance: distance + self.nodes[root].max_distance,
+            };
+            self.root = Some(new);
+            return;
+        }
+
+        // descend to the deepest node whose children do not cover the new item
+        let mut node = root;
+        loop {
+            self.nodes[node].max_distance = self.nodes[node].max_distance.max(distance);
+            let next = self.nodes[node].children.iter().find_map(|&child| {
+                let distance = self.distance(child, new);
+                if distance <= covering_distance(self.nodes[child].level) {
+                    Some((child, distance))
+                } else {
+                    None
+                }
+            });
+            match next {
+                Some((child, child_distance)) => {
+                    node = child;
+                    distance = child_distance;
+                }
+                None => break,
+            }
+        }
+
+        self.nodes[new].level = self.nodes[node].level - 1;
+        self.nodes[node].children.push(new);
+    }
+
+    // removes a leaf below `node`, which must have children, and returns it
+    fn take_leaf(&mut self, mut node: usize) -> usize {
+        loop {
+            let child = *self.nodes[node].children.last().unwrap();
+            if self.nodes[child].children.is_empty() {
+                self.nodes[node].children.pop();
+                return child;
+            }
+            node = child;
+        }
+    }
+
+    /// Returns the item nearest to `query` along with its distance, or `None` if the tree is empty
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    pub fn nearest(&self, query: &T) -> Option<(&T, f64)> {
+        self.k_nearest(query, 1).pop()
+    }
+
+    /// Returns at most `k` items nearest to `query` along with their distance, sorted from nearest to farthest
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    /// * `k`: number of items to be returned
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::CoverTree;
+    ///
+    /// let hamming = |a: &u32, b: &u32| (a ^ b).count_ones() as f64;
+    /// let tree = CoverTree::from_vec(vec![0b0000, 0b0111, 0b1000, 0b1111, 0b0011], hamming);
+    ///
+    /// let nearest: Vec<u32> = tree.k_nearest(&0b0001, 2).iter().map(|(item, _)| **item).collect();
+    ///
+    /// assert_eq!(nearest, vec![0b0000, 0b0011]);
+    /// ```
+    pub fn k_nearest(&self, query: &T, k: usize) -> Vec<(&T, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if let (Some(root), true) = (self.root, k > 0) {
+            let distance = (self.metric)(query, &self.items[root]);
+            self.search(root, distance, query, k, &mut heap);
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|candidate| (&self.items[candidate.item], candidate.distance))
+            .collect()
+    }
+
+    /// Returns all items whose distance to `query` is at most `radius`, sorted from nearest to farthest
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    /// * `radius`: largest accepted distance
+    pub fn within(&self, query: &T, radius: f64) -> Vec<(&T, f64)> {
+        let mut result = Vec::new();
+        let mut stack: Vec<(usize, f64)> = self
+            .root
+            .map(|root| (root, (self.metric)(query, &self.items[root])))
+            .into_iter()
+            .collect();
+
+        while let Some((node, distance)) = stack.pop() {
+            if distance <= radius {
+                result.push(Candidate {
+                    distance,
+                    item: node,
+                });
+            }
+            // every descendant is farther from the query than `distance - max_distance`
+            if distance - self.nodes[node].max_distance <= radius {
+                for &child in &self.nodes[node].children {
+                    stack.push((child, (self.metric)(query, &self.items[child])));
+                }
+            }
+        }
+
+        result.sort();
+        result
+            .into_iter()
+            .map(|candidate| (&self.items[candidate.item], candidate.distance))
+            .collect()
+    }
+
+    // collects k nearest items to the query in a max heap, `distance` is the distance from the query to `node`
+    fn search(
+        &self,
+        node: usize,
+        distance: f64,
+        query: &T,
+        k: usize,
+        heap: &mut BinaryHeap<Candidate>,
+    ) {
+        heap.push(Candidate {
+            distance,
+            item: node,
+        });
+        if heap.len() > k {
+            heap.pop();
+        }
+
+        // nearer children are more promising, so they are visited first
+        let mut children: Vec<(f64, usize)> = self.nodes[node]
+            .children
+            .iter()
+            .map(|&child| ((self.metric)(query, &self.items[child]), child))
+            .collect();
+        children.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for (distance, child) in children {
+            // largest distance that can still improve the result
+            let tau = if heap.len() < k {
+                f64::INFINITY
+            } else {
+                heap.peek().unwrap().distance
+            };
+            if distance - self.nodes[child].max_distance <= tau {
+                self.search(child, distance, query, k, heap);
+            }
+        }
+    }
+}
+
+impl<T, F> MemoryFootprint for CoverTree<T, F>
+where
+    F: Fn(&T, &T) -> f64,
+{
+    fn footprint(&self) -> Footprint {
+        let footprint = Footprint::of::<T>(self.items.len())
+            .with_vec(&self.items)
+            .with_vec(&self.nodes);
+        self.nodes.iter().fold(footprint, |footprint, node| {
+            footprint.with_vec(&node.children)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn euclidean(a: &(f64, f64), b: &(f64, f64)) -> f64 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    fn random_points(n: usize, rng: &mut SplitMix64) -> Vec<(f64, f64)> {
+        (0..n)
+            .map(|_| {
+                (
+                    rng.next_below(10000) as f64 / 100.0,
+                    rng.next_below(10000) as f64 / 100.0,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spatial_cover_tree_empty() {
+        let tree = CoverTree::init(euclidean);
+
+        assert!(tree.is_empty());
+        assert!(tree.nearest(&(0.0, 0.0)).is_none());
+        assert!(tree.within(&(0.0, 0.0), 10.0).is_empty());
+    }
+
+    #[test]
+    fn spatial_cover_tree_matches_brute_force_while_growing() {
+        let mut rng = SplitMix64::init(23);
+        let points = random_points(600, &mut rng);
+        let queries = random_points(30, &mut rng);
+        let mut tree = CoverTree::init(euclidean);
+
+        for (index, point) in points.iter().enumerate() {
+            tree.insert(*point);
+            if index % 100 != 99 {
+                continue;
+            }
+
+            for (i, query) in queries.iter().enumerate() {
+                let mut expected: Vec<f64> = points[..=index]
+                    .iter()
+                    .map(|point| euclidean(point, query))
+                    .collect();
+                expected.sort_by(f64::total_cmp);
+
+                let result: Vec<f64> = tree.k_nearest(query, 5).iter().map(|(_, d)| *d).collect();
+                assert_eq!(result, expected[..5].to_vec());
+
+                let radius = i as f64;
+                let within = tree.within(query, radius);
+                assert_eq!(
+                    within.len(),
+                    expected.iter().filter(|d| **d <= radius).count()
+                );
+                assert!(within.windows(2).all(|w| w[0].1 <= w[1].1));
+            }
+        }
+        assert_eq!(tree.size(), 600);
+    }
+
+    #[test]
+    fn spatial_cover_tree_sorted_and_duplicate_items() {
+        // items far from every earlier one keep moving leaves above the root
+        let mut tree = CoverTree::init(|a: &f64, b: &f64| (a - b).abs());
+        for i in 0..200 {
+            tree.insert(i as f64 * 1.5);
+            tree.insert(i as f64 * 1.5);
+        }
+        tree.insert(-1000.0);
+
+        for query in [-3.0, 0.4, 77.0, 150.2, 500.0] {
+            let mut expected: Vec<f64> = tree
+                .items()
+                .iter()
+                .map(|item| (item - query).abs())
+                .collect();
+            expected.sort_by(f64::total_cmp);
+
+            let result: Vec<f64> = tree.k_nearest(&query, 4).iter().map(|(_, d)| *d).collect();
+            assert_eq!(result, expected[..4].to_vec());
+        }
+        assert_eq!(tree.k_nearest(&0.0, 1000).len(), 401);
+    }
+}
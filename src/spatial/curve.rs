@@ -0,0 +1,279 @@
+// number of bits of every coordinate of a point of `K` dimensions in a 64 bit code
+pub(crate) fn coordinate_bits<const K: usize>() -> u32 {
+    if K == 0 {
+        panic!("Points must have at least one dimension");
+    }
+
+    (64 / K).min(32) as u32
+}
+
+// panics if a coordinate of `point` needs more than `bits` bits
+fn check_coordinates<const K: usize>(point: &[u32; K], bits: u32) {
+    if bits < 32 && point.iter().any(|coordinate| coordinate >> bits != 0) {
+        panic!("Coordinate does not fit in the code");
+    }
+}
+
+/// Returns the Morton code, or Z-order index, of `point`: the bits of its coordinates interleaved, so that bit `j` of
+/// coordinate `i` becomes bit `j * K + i` of the code. Points close in space tend to have close codes, and every
+/// aligned cube of side 2^l covers a contiguous range of codes. Every coordinate has 64 / K bits, at most 32:
+/// 32 bits in 2D, 21 bits in 3D
+/// * Complexity: O(K log C) for coordinates of log C bits
+///
+/// # Arguments
+/// * `point`: coordinates of the point
+///
+/// # Panics
+/// * panics if `K` is zero
+/// * panics if a coordinate does not fit in 64 / K bits
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::{morton_decode, morton_encode};
+///
+/// assert_eq!(morton_encode([0b11, 0b00]), 0b0101);
+/// assert_eq!(morton_encode([0b00, 0b11]), 0b1010);
+/// assert_eq!(morton_encode([1, 1, 1]), 0b111);
+///
+/// assert_eq!(morton_decode::<3>(morton_encode([5, 9, 2])), [5, 9, 2]);
+/// ```
+pub fn morton_encode<const K: usize>(point: [u32; K]) -> u64 {
+    let bits = coordinate_bits::<K>();
+    check_coordinates(&point, bits);
+
+    let mut code = 0;
+    for bit in 0..bits {
+        for (axis, coordinate) in point.iter().enumerate() {
+            code |= ((*coordinate as u64 >> bit) & 1) << (bit as usize * K + axis);
+        }
+    }
+
+    code
+}
+
+/// Returns the point whose Morton code is `code`, the inverse of `morton_encode`. Bits of `code` beyond the
+/// 64 / K bits of every coordinate are ignored
+/// * Complexity: O(K log C) for coordinates of log C bits
+///
+/// # Arguments
+/// * `code`: Morton code of the point
+///
+/// # Panics
+/// * panics if `K` is zero
+pub fn morton_decode<const K: usize>(code: u64) -> [u32; K] {
+    let bits = coordinate_bits::<K>();
+
+    let mut point = [0; K];
+    for bit in 0..bits {
+        for (axis, coordinate) in point.iter_mut().enumerate() {
+            *coordinate |= (((code >> (bit as usize * K + axis)) & 1) as u32) << bit;
+        }
+    }
+
+    point
+}
+
+// packs the transposed Hilbert index, where `transposed[i]` holds every K-th bit starting from the most significant,
+// into a single code
+fn pack<const K: usize>(transposed: &[u32; K], bits: u32) -> u64 {
+    let mut code = 0;
+    for bit in (0..bits).rev() {
+        for coordinate in transposed {
+            code = (code << 1) | ((*coordinate >> bit) & 1) as u64;
+        }
+    }
+
+    code
+}
+
+/// Returns the Hilbert index of `point`, its position along a Hilbert curve filling the cube of side 2^(64 / K).
+/// Unlike Morton codes, consecutive indices always belong to neighboring points, so ranges of indices are more
+/// compact in space. Every coordinate has 64 / K bits, at most 32: 32 bits in 2D, 21 bits in 3D.
+/// Uses John Skilling's transposition of the coordinates, which works for any number of dimensions
+/// * Complexity: O(K log C) for coordinates of log C bits
+///
+/// # Arguments
+/// * `point`: coordinates of the point
+///
+/// # Panics
+/// * panics if `K` is zero
+/// * panics if a coordinate does not fit in 64 / K bits
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::{hilbert_decode, hilbert_encode};
+///
+/// // the first four indices visit the unit square at the origin
+/// let square: Vec<[u32; 2]> = (0..4).map(hilbert_decode).collect();
+/// assert_eq!(square[0], [0, 0]);
+/// assert!(square.contains(&[0, 1]) && square.contains(&[1, 0]) && square.contains(&[1, 1]));
+///
+/// assert_eq!(hilbert_decode::<3>(hilbert_encode([7, 1, 12])), [7, 1, 12]);
+/// ```
+pub fn hilbert_encode<const K: usize>(point: [u32; K]) -> u64 {
+    let bits = coordinate_bits::<K>();
+    check_coordinates(&point, bits);
+
+    let mut transposed = point;
+    let top = 1u32 << (bits - 1);
+
+    // inverse undo
+    let mut q = top;
+    while q > 1 {
+        let p = q - 1;
+        for axis in 0..K {
+            if transposed[axis] & q != 0 {
+                transposed[0] ^= p;
+            } else {
+                let t = (transposed[0] ^ transposed[axis]) & p;
+                transposed[0] ^= t;
+                transposed[axis] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // gray encode
+    for axis in 1..K {
+        transposed[axis] ^= transposed[axis - 1];
+    }
+    let mut t = 0;
+    let mut q = top;
+    while q > 1 {
+        if transposed[K - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for coordinate in transposed.iter_mut() {
+        *coordinate ^= t;
+    }
+
+    pack(&transposed, bits)
+}
+
+/// Returns the point whose Hilbert index is `index`, the inverse of `hilbert_encode`. Bits of `index` beyond the
+/// 64 / K bits of every coordinate are ignored
+/// * Complexity: O(K log C) for coordinates of log C bits
+///
+/// # Arguments
+/// * `index`: Hilbert index of the point
+///
+/// # Panics
+/// * panics if `K` is zero
+pub fn hilbert_decode<const K: usize>(index: u64) -> [u32; K] {
+    let bits = coordinate_bits::<K>();
+
+    // unpacks the transposed index
+    let mut transposed = [0u32; K];
+    let mut shift = bits as usize * K;
+    for bit in (0..bits).rev() {
+        for coordinate in transposed.iter_mut() {
+            shift -= 1;
+            *coordinate |= (((index >> shift) & 1) as u32) << bit;
+        }
+    }
+
+    // gray decode
+    let t = transposed[K - 1] >> 1;
+    for axis in (1..K).rev() {
+        transposed[axis] ^= transposed[axis - 1];
+    }
+    transposed[0] ^= t;
+
+    // undo excess work
+    let mut q = 2u64;
+    while q != 1 << bits {
+        let p = (q - 1) as u32;
+        for axis in (0..K).rev() {
+            if transposed[axis] & q as u32 != 0 {
+                transposed[0] ^= p;
+            } else {
+                let t = (transposed[0] ^ transposed[axis]) & p;
+                transposed[0] ^= t;
+                transposed[axis] ^= t;
+            }
+        }
+        q <<= 1;
+    }
+
+    transposed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn round_trips<const K: usize>(rng: &mut SplitMix64) {
+        let bits = coordinate_bits::<K>();
+        for _ in 0..500 {
+            let mut point = [0u32; K];
+            for coordinate in point.iter_mut() {
+                *coordinate = (rng.next_u64() & ((1u64 << bits) - 1)) as u32;
+            }
+
+            assert_eq!(morton_decode::<K>(morton_encode(point)), point);
+            assert_eq!(hilbert_decode::<K>(hilbert_encode(point)), point);
+        }
+    }
+
+    #[test]
+    fn spatial_curve_round_trips() {
+        let mut rng = SplitMix64::init(43);
+        round_trips::<1>(&mut rng);
+        round_trips::<2>(&mut rng);
+        round_trips::<3>(&mut rng);
+        round_trips::<4>(&mut rng);
+        round_trips::<5>(&mut rng);
+    }
+
+    fn hilbert_steps_to_neighbors<const K: usize>(rng: &mut SplitMix64) {
+        let bits = coordinate_bits::<K>() as usize;
+        let mask = if bits * K == 64 {
+            u64::MAX
+        } else {
+            (1 << (bits * K)) - 1
+        };
+        for _ in 0..500 {
+            let index = rng.next_u64() & mask;
+            if index == mask {
+                continue;
+            }
+
+            let point = hilbert_decode::<K>(index);
+            let next = hilbert_decode::<K>(index + 1);
+            let steps: u64 = (0..K)
+                .map(|axis| (point[axis] as i64 - next[axis] as i64).unsigned_abs())
+                .sum();
+            assert_eq!(steps, 1);
+        }
+    }
+
+    #[test]
+    fn spatial_curve_hilbert_steps_to_neighbors() {
+        let mut rng = SplitMix64::init(47);
+        hilbert_steps_to_neighbors::<2>(&mut rng);
+        hilbert_steps_to_neighbors::<3>(&mut rng);
+        hilbert_steps_to_neighbors::<4>(&mut rng);
+    }
+
+    #[test]
+    fn spatial_curve_small_grid_is_contiguous() {
+        // the first 4^l indices fill the square of side 2^l at the origin
+        let mut seen = [false; 64];
+        for index in 0..64 {
+            let [x, y] = hilbert_decode::<2>(index);
+            assert!(x < 8 && y < 8);
+            seen[(y * 8 + x) as usize] = true;
+            assert_eq!(hilbert_encode([x, y]), index);
+        }
+        assert!(seen.iter().all(|seen| *seen));
+    }
+
+    #[test]
+    #[should_panic(expected = "Coordinate does not fit in the code")]
+    fn spatial_curve_panic_large_coordinate() {
+        morton_encode([1 << 21, 0, 0]);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::util::{Footprint, MemoryFootprint, Rng, SplitMix64};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+// mersenne prime 2^61 - 1 used as modulus of universal hash functions
+const MERSENNE_61: u64 = (1 << 61) - 1;
+
+/// A family of locality sensitive hash functions. Similar items(according to `distance`) are likely to
+/// get equal values from a hash function of the family, while distant items are not.
+pub trait HashFamily<T> {
+    /// Returns number of hash functions in the family, which is the length of signatures
+    fn size(&self) -> usize;
+
+    /// Returns values of all hash functions of the family for `item`
+    ///
+    /// # Arguments
+    /// * `item`: item to be hashed
+    fn signature(&self, item: &T) -> Vec<u64>;
+
+    /// Returns the distance between two items the family is sensitive to. It is used to rank candidates
+    ///
+    /// # Arguments
+    /// * `a`: first item
+    /// * `b`: second item
+    fn distance(&self, a: &T, b: &T) -> f64;
+}
+
+/// Random hyperplane hashing(SimHash) for vectors under angular distance.
+/// Each hash function is the side of a random hyperplane through the origin the vector lies on.
+/// Two vectors with angle θ between them collide with probability `1 - θ / π`
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::{HashFamily, RandomHyperplanes};
+///
+/// let family = RandomHyperplanes::init(3, 16, 7);
+///
+/// let a = vec![1.0, 2.0, 3.0];
+/// let b = vec![2.0, 4.0, 6.0];
+///
+/// // vectors in the same direction have equal signatures
+/// assert_eq!(family.signature(&a), family.signature(&b));
+/// assert!(family.distance(&a, &b) < 1e-6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RandomHyperplanes {
+    dimension: usize,
+    normals: Vec<Vec<f64>>,
+}
+
+impl RandomHyperplanes {
+    /// Creates `count` random hyperplanes in a space with `dimension` dimensions.
+    /// Families created with the same seed are equal
+    ///
+    /// # Arguments
+    /// * `dimension`: dimension of the vectors
+    /// * `count`: number of hyperplanes
+    /// * `seed`: seed of the random hyperplanes
+    pub fn init(dimension: usize, count: usize, seed: u64) -> RandomHyperplanes {
+        let mut random = SplitMix64::init(seed);
+        let normals = (0..count)
+            .map(|_| (0..dimension).map(|_| random.next_gaussian()).collect())
+            .collect();
+
+        RandomHyperplanes { dimension, normals }
+    }
+
+    /// Returns dimension of the vectors
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn check_dimension(&self, vector: &[f64]) {
+        if vector.len() != self.dimension {
+            panic!("Dimension of the vector does not match dimension of the family");
+        }
+    }
+}
+
+impl HashFamily<Vec<f64>> for RandomHyperplanes {
+    fn size(&self) -> usize {
+        self.normals.len()
+    }
+
+    /// # Panics
+    /// * panics if length of `item` is not equal to dimension of the family
+    fn signature(&self, item: &Vec<f64>) -> Vec<u64> {
+        self.check_dimension(item);
+
+        self.normals
+            .iter()
+            .map(|normal| {
+                let dot: f64 = normal.iter().zip(item.iter()).map(|(a, b)| a * b).sum();
+                (dot >= 0.0) as u64
+            })
+            .collect()
+    }
+
+    /// Returns the angle between `a` and `b` divided by π, which is in range [0, 1].
+    /// Zero vectors are considered perpendicular to all vectors
+    ///
+    /// # Panics
+    /// * panics if length of `a` or `b` is not equal to dimension of the family
+    fn distance(&self, a: &Vec<f64>, b: &Vec<f64>) -> f64 {
+        self.check_dimension(a);
+        self.check_dimension(b);
+
+        let dot: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+        let norms = a.iter().map(|x| x * x).sum::<f64>().sqrt()
+            * b.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norms == 0.0 {
+            return 0.5;
+        }
+
+        (dot / norms).clamp(-1.0, 1.0).acos() / std::f64::consts::PI
+    }
+}
+
+/// MinHash family for sets under Jaccard distance.
+/// Each hash function maps a set to the smallest hash of its elements under a random universal hash function.
+/// Two sets collide with probability equal to their Jaccard similarity `|A ∩ B| / |A ∪ B|`
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::{HashFamily, MinHashFamily};
+/// use std::collections::HashSet;
+///
+/// let family = MinHashFamily::init(64, 7);
+///
+/// let a: HashSet<u32> = (0..100).collect();
+/// let b: HashSet<u32> = (50..150).collect();
+///
+/// assert_eq!(family.distance(&a, &b), 1.0 - 50.0 / 150.0);
+/// assert_eq!(family.signature(&a), family.signature(&a.clone()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinHashFamily {
+    coefficients: Vec<(u64, u64)>,
+}
+
+impl MinHashFamily {
+    /// Creates `count` random hash functions. Families created with the same seed are equal
+    ///
+    /// # Arguments
+    /// * `count`: number of hash functions
+    /// * `seed`: seed of the random hash functions
+    pub fn init(count: usize, seed: u64) -> MinHashFamily {
+        let mut random = SplitMix64::init(seed);
+        let coefficients = (0..count)
+            .map(|_| {
+                (
+                    1 + random.next_u64() % (MERSENNE_61 - 1),
+                    random.next_u64() % MERSENNE_61,
+                )
+            })
+            .collect();
+
+        MinHashFamily { coefficients }
+    }
+
+    // hash of an element, reduced to a residue modulo the mersenne prime
+    fn element_hash<E: Hash + ?Sized>(element: &E) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        element.hash(&mut hasher);
+
+        hasher.finish() % MERSENNE_61
+    }
+
+    // universal hash function (a * x + b) mod (2^61 - 1)
+    fn universal_hash(a: u64, b: u64, x: u64) -> u64 {
+        ((a as u128 * x as u128 + b as u128) % MERSENNE_61 as u128) as u64
+    }
+
+    // lowers values of the signature to the hashes of `element` where they are smaller
+    pub(crate) fn update<E: Hash + ?Sized>(&self, signature: &mut [u64], element: &E) {
+        let x = MinHashFamily::element_hash(element);
+        for (value, (a, b)) in signature.iter_mut().zip(self.coefficients.iter()) {
+            *value = std::cmp::min(*value, MinHashFamily::universal_hash(*a, *b, x));
+        }
+    }
+}
+
+impl<E: Hash + Eq> HashFamily<HashSet<E>> for MinHashFamily {
+    fn size(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Signature of an empty set consists of `u64::MAX` values
+    fn signature(&self, item: &HashSet<E>) -> Vec<u64> {
+        let mut signature = vec![u64::MAX; self.coefficients.len()];
+
+        for element in item {
+            self.update(&mut signature, element);
+        }
+
+        signature
+    }
+
+    /// Returns Jaccard distance `1 - |A ∩ B| / |A ∪ B|`. Distance of two empty sets is zero
+    fn distance(&self, a: &HashSet<E>, b: &HashSet<E>) -> f64 {
+        let intersection = a.intersection(b).count();
+        let union = a.len() + b.len() - intersection;
+        if union == 0 {
+            return 0.0;
+        }
+
+        1.0 - intersection as f64 / union as f64
+    }
+}
+
+impl MemoryFootprint for RandomHyperplanes {
+    fn footprint(&self) -> Footprint {
+        self.normals.iter().fold(
+            Footprint::of::<f64>(self.normals.len() * self.dimension).with_vec(&self.normals),
+            |footprint, normal| footprint.with_vec(normal),
+        )
+    }
+}
+
+impl MemoryFootprint for MinHashFamily {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<(u64, u64)>(self.coefficients.len()).with_vec(&self.coefficients)
+    }
+}
@@ -0,0 +1,394 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// A spatial hash grid is a broad phase for collision detection: space is cut into cubic cells of a fixed size and
+/// every axis aligned box, keyed by an id, is listed in the cells it overlaps, which are kept in a hash map so only
+/// occupied cells take memory. Boxes that do not share a cell cannot overlap, so region queries and the search for
+/// overlapping pairs only look at the boxes of a few cells. Updates take O(c) time for a box overlapping c cells.
+/// Works best when the cell size is about the size of a typical box: boxes much larger than a cell are listed in many
+/// cells, while many small boxes in one cell are compared pairwise. Coordinates must not be NaN
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::SpatialHashGrid;
+///
+/// let mut grid = SpatialHashGrid::init(10.0);
+/// grid.insert("player", [1.0, 1.0], [3.0, 3.0]);
+/// grid.insert("wall", [2.0, 0.0], [2.5, 20.0]);
+/// grid.insert("coin", [30.0, 30.0], [31.0, 31.0]);
+///
+/// let pairs: Vec<(&str, &str)> = grid.potential_pairs().collect();
+/// assert!(pairs == vec![("player", "wall")] || pairs == vec![("wall", "player")]);
+///
+/// grid.update("player", [29.0, 29.0], [30.5, 30.5]);
+/// assert_eq!(grid.query_region(&[0.0, 0.0], &[5.0, 5.0]), vec!["wall"]);
+/// assert_eq!(grid.potential_pairs().count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpatialHashGrid<I, const K: usize> {
+    cell_size: f64,
+
+    // boxes by id, as their low and high corners
+    boxes: HashMap<I, ([f64; K], [f64; K])>,
+
+    // ids of the boxes overlapping every occupied cell
+    cells: HashMap<[i64; K], Vec<I>>,
+}
+
+impl<I, const K: usize> SpatialHashGrid<I, K>
+where
+    I: Copy + Eq + Hash,
+{
+    /// Initializes an empty grid of cells of side `cell_size`
+    ///
+    /// # Arguments
+    /// * `cell_size`: side of the cells
+    ///
+    /// # Panics
+    /// * panics if `K` is zero
+    /// * panics if `cell_size` is not positive
+    pub fn init(cell_size: f64) -> SpatialHashGrid<I, K> {
+        if K == 0 {
+            panic!("Points must have at least one dimension");
+        }
+        if cell_size.is_nan() || cell_size <= 0.0 {
+            panic!("Cell size must be positive");
+        }
+
+        SpatialHashGrid {
+            cell_size,
+            boxes: HashMap::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Returns side of the cells
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    /// Returns number of boxes in the grid
+    pub fn size(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Returns true if there are no boxes in the grid
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// Removes every box of the grid
+    pub fn clear(&mut self) {
+        self.boxes.clear();
+        self.cells.clear();
+    }
+
+    /// Returns true if a box with `id` is in the grid
+    ///
+    /// # Arguments
+    /// * `id`: id of the box
+    pub fn contains(&self, id: I) -> bool {
+        self.boxes.contains_key(&id)
+    }
+
+    /// Returns the low and high corners of the box with `id`, None if it is not in the grid
+    ///
+    /// # Arguments
+    /// * `id`: id of the box
+    pub fn get(&self, id: I) -> Option<(&[f64; K], &[f64; K])> {
+        self.boxes.get(&id).map(|(low, high)| (low, high))
+    }
+
+    // returns the cell containing `point`
+    fn cell(&self, point: &[f64; K]) -> [i64; K] {
+        let mut cell = [0; K];
+        for axis in 0..K {
+            cell[axis] = (point[axis] / self.cell_size).floor() as i64;
+        }
+
+        cell
+    }
+
+    // returns every cell between the cells `low` and `high`, both included
+    fn cells_between(low: [i64; K], high: [i64; K]) -> Vec<[i64; K]> {
+        let mut result = Vec::new();
+        let mut cell = low;
+        loop {
+            result.push(cell);
+
+            // advances the cell like an odometer, the first axis turning fastest
+            let mut axis = 0;
+            while axis < K && cell[axis] == high[axis] {
+                cell[axis] = low[axis];
+                axis += 1;
+            }
+            if axis == K {
+                return result;
+            }
+            cell[axis] += 1;
+        }
+    }
+
+    // returns the cells overlapped by the box with corners `low` and `high`
+    fn covered_cells(&self, low: &[f64; K], high: &[f64; K]) -> Vec<[i64; K]> {
+        SpatialHashGrid::<I, K>::cells_between(self.cell(low), self.cell(high))
+    }
+
+    /// Inserts the box with corners `low` and `high` under `id`
+    /// * Complexity: O(c) for a box overlapping c cells
+    ///
+    /// # Arguments
+    /// * `id`: id of the box
+    /// * `low`: the corner with the smallest coordinates
+    /// * `high`: the corner with the largest coordinates
+    ///
+    /// # Panics
+    /// * panics if a box with `id` is already in the grid
+    /// * panics if a coordinate of `low` is larger than the one of `high`
+    pub fn insert(&mut self, id: I, low: [f64; K], high: [f64; K]) {
+        if self.boxes.contains_key(&id) {
+            panic!("Id is already in the grid");
+        }
+        if (0..K).any(|axis| low[axis] > high[axis]) {
+            panic!("Low corner must not be larger than high corner");
+        }
+
+        for cell in self.covered_cells(&low, &high) {
+            self.cells.entry(cell).or_default().push(id);
+        }
+        self.boxes.insert(id, (low, high));
+    }
+
+    /// Moves the box with `id` to the corners `low` and `high`. Only the cells it enters or leaves are touched
+    /// * Complexity: O(c) for a box overlapping c cells
+    ///
+    /// # Arguments
+    /// * `id`: id of the box
+    /// * `low`: the new corner with the smallest coordinates
+    /// * `high`: the new corner with the largest coordinates
+    ///
+    /// # Panics
+    /// * panics if no box with `id` is in the grid
+    /// * panics if a coordinate of `low` is larger than the one of `high`
+    pub fn update(&mut self, id: I, low: [f64; K], high: [f64; K]) {
+        if (0..K).any(|axis| low[axis] > high[axis]) {
+            panic!("Low corner must not be larger than high corner");
+        }
+        let (old_low, old_high) = match self.boxes.get(&id) {
+            Some(corners) => *corners,
+            None => panic!("Id is not in the grid"),
+        };
+
+        let old_cells: HashSet<[i64; K]> = self
+            .covered_cells(&old_low, &old_high)
+            .into_iter()
+            .collect();
+        let new_cells: HashSet<[i64; K]> = self.covered_cells(&low, &high).into_iter().collect();
+        for cell in old_cells.difference(&new_cells) {
+            self.unlist(id, cell);
+        }
+        for cell in new_cells.difference(&old_cells) {
+            self.cells.entry(*cell).or_default().push(id);
+        }
+        self.boxes.insert(id, (low, high));
+    }
+
+    /// Removes the box with `id` and returns its low and high corners, None if it is not in the grid
+    /// * Complexity: O(c) for a box overlapping c cells
+    ///
+    /// # Arguments
+    /// * `id`: id of the box
+    pub fn remove(&mut self, id: I) -> Option<([f64; K], [f64; K])> {
+        let (low, high) = self.boxes.remove(&id)?;
+        for cell in self.covered_cells(&low, &high) {
+            self.unlist(id, &cell);
+        }
+
+        Some((low, high))
+    }
+
+    // removes `id` from the ids of `cell`, dropping the cell once it is empty
+    fn unlist(&mut self, id: I, cell: &[i64; K]) {
+        let ids = self.cells.get_mut(cell).unwrap();
+        let position = ids.iter().position(|other| *other == id).unwrap();
+        ids.swap_remove(position);
+        if ids.is_empty() {
+            self.cells.remove(cell);
+        }
+    }
+
+    /// Returns ids of the boxes overlapping the region with corners `low` and `high`, borders included,
+    /// in no particular order
+    /// * Complexity: O(c + m) for a region overlapping c cells that list m boxes
+    ///
+    /// # Arguments
+    /// * `low`: the corner with the smallest coordinates
+    /// * `high`: the corner with the largest coordinates
+    pub fn query_region(&self, low: &[f64; K], high: &[f64; K]) -> Vec<I> {
+        if (0..K).any(|axis| low[axis] > high[axis]) {
+            return Vec::new();
+        }
+
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for cell in self.covered_cells(low, high) {
+            for id in self.cells.get(&cell).into_iter().flatten() {
+                let (other_low, other_high) = &self.boxes[id];
+                if overlap(low, high, other_low, other_high) && seen.insert(*id) {
+                    result.push(*id);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns an iterator over the pairs of ids of overlapping boxes, borders included. Every pair is reported
+    /// once, in no particular order
+    /// * Complexity: O(c + p) where c is the number of occupied cells and p the number of pairs of boxes sharing
+    ///   a cell
+    pub fn potential_pairs(&self) -> impl Iterator<Item = (I, I)> + '_ {
+        self.cells.iter().flat_map(move |(cell, ids)| {
+            (0..ids.len()).flat_map(move |i| {
+                (i + 1..ids.len()).filter_map(move |j| {
+                    let (low1, high1) = &self.boxes[&ids[i]];
+                    let (low2, high2) = &self.boxes[&ids[j]];
+                    if !overlap(low1, high1, low2, high2) {
+                        return None;
+                    }
+
+                    // the pair shares every cell of the overlap, it is reported by the cell of its low corner
+                    let mut corner = [0.0; K];
+                    for axis in 0..K {
+                        corner[axis] = low1[axis].max(low2[axis]);
+                    }
+                    if self.cell(&corner) == *cell {
+                        Some((ids[i], ids[j]))
+                    } else {
+                        None
+                    }
+                })
+            })
+        })
+    }
+}
+
+// returns true if two boxes given by their corners overlap, borders included
+fn overlap<const K: usize>(
+    low1: &[f64; K],
+    high1: &[f64; K],
+    low2: &[f64; K],
+    high2: &[f64; K],
+) -> bool {
+    (0..K).all(|axis| low1[axis] <= high2[axis] && low2[axis] <= high1[axis])
+}
+
+impl<I, const K: usize> MemoryFootprint for SpatialHashGrid<I, K> {
+    fn footprint(&self) -> Footprint {
+        let footprint = Footprint::of::<I>(self.boxes.len())
+            .with_hash_map(&self.boxes)
+            .with_hash_map(&self.cells);
+        self.cells
+            .values()
+            .fold(footprint, |footprint, ids| footprint.with_vec(ids))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn random_box(rng: &mut SplitMix64) -> ([f64; 2], [f64; 2]) {
+        let x = rng.next_below(2000) as f64 / 10.0 - 100.0;
+        let y = rng.next_below(2000) as f64 / 10.0 - 100.0;
+        let width = rng.next_below(300) as f64 / 10.0;
+        let height = rng.next_below(300) as f64 / 10.0;
+
+        ([x, y], [x + width, y + height])
+    }
+
+    #[test]
+    fn spatial_hash_grid_matches_brute_force() {
+        let mut rng = SplitMix64::init(31);
+        let mut grid = SpatialHashGrid::init(8.0);
+        let mut boxes: Vec<Option<([f64; 2], [f64; 2])>> = vec![None; 80];
+
+        for _ in 0..600 {
+            let id = rng.next_below(80) as usize;
+            let (low, high) = random_box(&mut rng);
+            match (rng.next_below(3), boxes[id]) {
+                (0, _) => assert_eq!(grid.remove(id), boxes[id].take()),
+                (_, Some(_)) => {
+                    grid.update(id, low, high);
+                    boxes[id] = Some((low, high));
+                }
+                (_, None) => {
+                    grid.insert(id, low, high);
+                    boxes[id] = Some((low, high));
+                }
+            }
+
+            let (low, high) = random_box(&mut rng);
+            let mut found = grid.query_region(&low, &high);
+            found.sort_unstable();
+            let expected: Vec<usize> = (0..80)
+                .filter(|id| matches!(boxes[*id], Some((l, h)) if overlap(&low, &high, &l, &h)))
+                .collect();
+            assert_eq!(found, expected);
+
+            let mut pairs: Vec<(usize, usize)> = grid
+                .potential_pairs()
+                .map(|(a, b)| (a.min(b), a.max(b)))
+                .collect();
+            pairs.sort_unstable();
+            let mut expected = Vec::new();
+            for a in 0..80 {
+                for b in a + 1..80 {
+                    if let (Some((l1, h1)), Some((l2, h2))) = (boxes[a], boxes[b]) {
+                        if overlap(&l1, &h1, &l2, &h2) {
+                            expected.push((a, b));
+                        }
+                    }
+                }
+            }
+            assert_eq!(pairs, expected);
+            assert_eq!(grid.size(), boxes.iter().flatten().count());
+        }
+    }
+
+    #[test]
+    fn spatial_hash_grid_three_dimensions() {
+        let mut grid: SpatialHashGrid<u32, 3> = SpatialHashGrid::init(1.0);
+        grid.insert(1, [0.0, 0.0, 0.0], [2.5, 2.5, 2.5]);
+        grid.insert(2, [2.0, 2.0, 2.0], [3.0, 3.0, 3.0]);
+        grid.insert(3, [-1.0, -1.0, 2.6], [0.5, 0.5, 3.0]);
+
+        assert_eq!(grid.potential_pairs().count(), 1);
+        assert_eq!(
+            grid.query_region(&[0.0, 0.0, 2.8], &[0.1, 0.1, 2.9]),
+            vec![3]
+        );
+
+        grid.remove(2);
+        grid.remove(3);
+        grid.remove(1);
+        assert!(grid.is_empty());
+        assert!(grid.cells.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Id is already in the grid")]
+    fn spatial_hash_grid_panic_insert_twice() {
+        let mut grid = SpatialHashGrid::init(1.0);
+        grid.insert(7, [0.0], [1.0]);
+        grid.insert(7, [2.0], [3.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cell size must be positive")]
+    fn spatial_hash_grid_panic_cell_size() {
+        let _: SpatialHashGrid<u32, 2> = SpatialHashGrid::init(0.0);
+    }
+}
@@ -0,0 +1,677 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// a subtree is rebuilt when one of its children holds more than this share of its nodes
+const ALPHA: f64 = 0.7;
+
+// node of the tree. points of the left subtree are smaller than the point of the node on its axis,
+// points of the right subtree are not smaller
+struct Node<T, const K: usize> {
+    point: [f64; K],
+
+    // None if the point was removed, the node stays in place until its subtree is rebuilt
+    item: Option<T>,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+
+    // number of nodes in the subtree, removed ones included
+    size: usize,
+}
+
+// candidate of a search, ordered by distance so that the farthest candidate is on top of the heap
+struct Candidate {
+    distance: f64,
+    node: usize,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+/// A k-d tree indexes points of `K` dimensions, each with an item, for nearest neighbor and box queries under the
+/// euclidean distance. Every level splits the points by one coordinate, cycling through the dimensions.
+/// Points can be inserted and removed at any time: like a scapegoat tree, a subtree is rebuilt around its median
+/// when one of its sides grows too large, and removed points are dropped when they make up half of the tree,
+/// so the depth stays logarithmic and updates take amortized O(log² n) time without rebuilding from scratch.
+/// Coordinates must not be NaN
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::KdTree;
+///
+/// let mut tree = KdTree::from_vec(vec![([0.0, 0.0], "origin"), ([5.0, 5.0], "far"), ([9.0, 2.0], "east")]);
+/// tree.insert([1.0, 1.0], "near");
+///
+/// let (point, item, distance) = tree.nearest(&[2.0, 2.0]).unwrap();
+/// assert_eq!((*point, *item), ([1.0, 1.0], "near"));
+/// assert!((distance - 2f64.sqrt()).abs() < 1e-12);
+///
+/// assert_eq!(tree.remove(&[1.0, 1.0]), Some("near"));
+/// assert_eq!(*tree.nearest(&[2.0, 2.0]).unwrap().1, "origin");
+/// assert_eq!(tree.size(), 3);
+/// ```
+pub struct KdTree<T, const K: usize> {
+    nodes: Vec<Node<T, K>>,
+
+    // slots of nodes dropped by rebuilds, reused by later nodes
+    free: Vec<usize>,
+    root: Option<usize>,
+
+    // number of points in the tree, removed ones excluded
+    size: usize,
+}
+
+impl<T, const K: usize> KdTree<T, K> {
+    /// Initializes an empty tree
+    ///
+    /// # Panics
+    /// * panics if `K` is zero
+    pub fn init() -> KdTree<T, K> {
+        if K == 0 {
+            panic!("Points must have at least one dimension");
+        }
+
+        KdTree {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            size: 0,
+        }
+    }
+
+    /// Builds a balanced tree of `entries`, points with their items
+    /// * Complexity: O(n log n)
+    ///
+    /// # Arguments
+    /// * `entries`: points with their items
+    ///
+    /// # Panics
+    /// * panics if `K` is zero
+    pub fn from_vec(entries: Vec<([f64; K], T)>) -> KdTree<T, K> {
+        let mut tree = KdTree::init();
+        tree.size = entries.len();
+        tree.nodes.reserve(entries.len());
+        tree.root = tree.build(entries, 0);
+
+        tree
+    }
+
+    /// Returns number of points in the tree
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no points in the tree
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns number of nodes on the longest path from the root to a leaf, removed points included
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut stack: Vec<(usize, usize)> = self.root.map(|root| (root, 1)).into_iter().collect();
+        while let Some((node, level)) = stack.pop() {
+            depth = depth.max(level);
+            let node = &self.nodes[node];
+            for child in [node.left, node.right].iter().flatten() {
+                stack.push((*child, level + 1));
+            }
+        }
+
+        depth
+    }
+
+    /// Removes every point of the tree
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = None;
+        self.size = 0;
+    }
+
+    // stores a node, reusing a free slot if there is one
+    fn allocate(&mut self, node: Node<T, K>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // builds a balanced subtree of `entries` splitting on `axis` first and returns its root
+    fn build(&mut self, mut entries: Vec<([f64; K], T)>, axis: usize) -> Option<usize> {
+        if entries.is_empty() {
+            return None;
+        }
+
+        let size = entries.len();
+        let middle = size / 2;
+        entries.select_nth_unstable_by(middle, |a, b| a.0[axis].total_cmp(&b.0[axis]));
+
+        // points equal to the median on the axis must go right, so the median moves to the first of them
+        let median = entries[middle].0[axis];
+        let mut split = 0;
+        for index in 0..middle {
+            if entries[index].0[axis] < median {
+                entries.swap(split, index);
+                split += 1;
+            }
+        }
+        entries.swap(split, middle);
+
+        let right = entries.split_off(split + 1);
+        let (point, item) = entries.pop().unwrap();
+        let next = (axis + 1) % K;
+        let left = self.build(entries, next);
+        let right = self.build(right, next);
+
+        Some(self.allocate(Node {
+            point,
+            item: Some(item),
+            axis,
+            left,
+            right,
+            size,
+        }))
+    }
+
+    // returns the size of the subtree of `node`
+    fn subtree_size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |node| self.nodes[node].size)
+    }
+
+    // takes the remaining entries of the subtree of `node` and frees its nodes
+    fn collect(&mut self, node: usize, entries: &mut Vec<([f64; K], T)>) {
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            self.free.push(node);
+            let node = &mut self.nodes[node];
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+            if let Some(item) = node.item.take() {
+                entries.push((node.point, item));
+            }
+        }
+    }
+
+    // rebuilds the subtree of `node` without its removed points and returns its new root
+    fn rebuild(&mut self, node: usize) -> Option<usize> {
+        let axis = self.nodes[node].axis;
+        let mut entries = Vec::with_capacity(self.nodes[node].size);
+        self.collect(node, &mut entries);
+
+        self.build(entries, axis)
+    }
+
+    // replaces the child `old` of `parent`, or the root if there is no parent, by `new`
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: Option<usize>) {
+        match parent {
+            Some(parent) if self.nodes[parent].left == Some(old) => self.nodes[parent].left = new,
+            Some(parent) => self.nodes[parent].right = new,
+            None => self.root = new,
+        }
+    }
+
+    /// Inserts `point` with `item`. Points equal to ones already in the tree are kept as well
+    /// * Complexity: amortized O(log² n)
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    /// * `item`: item of the point
+    pub fn insert(&mut self, point: [f64; K], item: T) {
+        // nodes from the root to the parent of the new node
+        let mut path = Vec::new();
+        let mut current = self.root;
+        let mut axis = 0;
+        while let Some(node) = current {
+            path.push(node);
+            let node = &mut self.nodes[node];
+            node.size += 1;
+            axis = (node.axis + 1) % K;
+            current = if point[node.axis] < node.point[node.axis] {
+                node.left
+            } else {
+                node.right
+            };
+        }
+
+        let new = self.allocate(Node {
+            point,
+            item: Some(item),
+            axis,
+            left: None,
+            right: None,
+            size: 1,
+        });
+        match path.last() {
+            Some(&parent)
+                if point[self.nodes[parent].axis]
+                    < self.nodes[parent].point[self.nodes[parent].axis] =>
+            {
+                self.nodes[parent].left = Some(new)
+            }
+            Some(&parent) => self.nodes[parent].right = Some(new),
+            None => self.root = Some(new),
+        }
+        self.size += 1;
+
+        // the highest node with a side that is too large is the scapegoat
+        let scapegoat = path.iter().position(|&node| {
+            let node = &self.nodes[node];
+            let largest = self
+                .subtree_size(node.left)
+                .max(self.subtree_size(node.right));
+            largest as f64 > ALPHA * node.size as f64
+        });
+        if let Some(position) = scapegoat {
+            let node = path[position];
+            let old_size = self.nodes[node].size;
+            let new = self.rebuild(node);
+            let dropped = old_size - self.subtree_size(new);
+            let parent = position.checked_sub(1).map(|position| path[position]);
+            self.replace_child(parent, node, new);
+            for &ancestor in &path[..position] {
+                self.nodes[ancestor].size -= dropped;
+            }
+        }
+    }
+
+    /// Removes a point equal to `point` and returns its item, None if there is no such point
+    /// * Complexity: amortized O(log n)
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    pub fn remove(&mut self, point: &[f64; K]) -> Option<T> {
+        let mut current = self.root;
+        while let Some(index) = current {
+            let node = &mut self.nodes[index];
+            if node.point == *point && node.item.is_some() {
+                let item = node.item.take();
+                self.size -= 1;
+
+                // removed points are dropped once they make up half of the tree
+                if let Some(root) = self.root {
+                    if 2 * self.size <= self.nodes[root].size {
+                        self.root = self.rebuild(root);
+                    }
+                }
+
+                return item;
+            }
+            current = if point[node.axis] < node.point[node.axis] {
+                node.left
+            } else {
+                node.right
+            };
+        }
+
+        None
+    }
+
+    /// Returns true if there is a point equal to `point`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    pub fn contains(&self, point: &[f64; K]) -> bool {
+        let mut current = self.root;
+        while let Some(index) = current {
+            let node = &self.nodes[index];
+            if node.point == *point && node.item.is_some() {
+                return true;
+            }
+            current = if point[node.axis] < node.point[node.axis] {
+                node.left
+            } else {
+                node.right
+            };
+        }
+
+        false
+    }
+
+    /// Returns the point nearest to `query` with its item and distance, or `None` if the tree is empty
+    ///
+    /// # Arguments
+    /// * `query`: the query point
+    pub fn nearest(&self, query: &[f64; K]) -> Option<(&[f64; K], &T, f64)> {
+        self.k_nearest(query, 1).pop()
+    }
+
+    /// Returns at most `k` points nearest to `query` with their items and distances, sorted from nearest to farthest
+    ///
+    /// # Arguments
+    /// * `query`: the query point
+    /// * `k`: number of points to be returned
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::KdTree;
+    ///
+    /// let mut tree = KdTree::init();
+    /// for (index, x) in [0.0, 4.0, 1.0, 9.0, 3.0].iter().enumerate() {
+    ///     tree.insert([*x, 0.0, 0.0], index);
+    /// }
+    ///
+    /// let nearest: Vec<usize> = tree.k_nearest(&[2.1, 0.0, 0.0], 3).iter().map(|(_, index, _)| **index).collect();
+    ///
+    /// assert_eq!(nearest, vec![4, 2, 1]);
+    /// ```
+    pub fn k_nearest(&self, query: &[f64; K], k: usize) -> Vec<(&[f64; K], &T, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(self.root, query, k, &mut heap);
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|candidate| self.entry(candidate.node, candidate.distance.sqrt()))
+            .collect()
+    }
+
+    /// Returns all points whose distance to `query` is at most `radius` with their items and distances, sorted from
+    /// nearest to farthest
+    ///
+    /// # Arguments
+    /// * `query`: the query point
+    /// * `radius`: largest accepted distance
+    pub fn within(&self, query: &[f64; K], radius: f64) -> Vec<(&[f64; K], &T, f64)> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        let bound = radius * radius;
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let distance = distance_squared(query, &node.point);
+            if distance <= bound && node.item.is_some() {
+                result.push(Candidate {
+                    distance,
+                    node: index,
+                });
+            }
+
+            let offset = query[node.axis] - node.point[node.axis];
+            if let Some(left) = node.left {
+                if offset < radius {
+                    stack.push(left);
+                }
+            }
+            if let Some(right) = node.right {
+                if offset >= -radius {
+                    stack.push(right);
+                }
+            }
+        }
+
+        result.sort();
+        result
+            .into_iter()
+            .map(|candidate| self.entry(candidate.node, candidate.distance.sqrt()))
+            .collect()
+    }
+
+    /// Returns all points inside the box with corners `low` and `high` with their items, borders included
+    ///
+    /// # Arguments
+    /// * `low`: the corner with the smallest coordinates
+    /// * `high`: the corner with the largest coordinates
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::KdTree;
+    ///
+    /// let tree = KdTree::from_vec(vec![([1.0, 1.0], 'a'), ([2.0, 5.0], 'b'), ([4.0, 3.0], 'c'), ([6.0, 2.0], 'd')]);
+    ///
+    /// let mut found: Vec<char> = tree.range(&[1.5, 1.5], &[6.0, 5.0]).iter().map(|(_, item)| **item).collect();
+    /// found.sort();
+    ///
+    /// assert_eq!(found, vec!['b', 'c', 'd']);
+    /// ```
+    pub fn range(&self, low: &[f64; K], high: &[f64; K]) -> Vec<(&[f64; K], &T)> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let inside =
+                (0..K).all(|axis| low[axis] <= node.point[axis] && node.point[axis] <= high[axis]);
+            if let (true, Some(item)) = (inside, &node.item) {
+                result.push((&node.point, item));
+            }
+
+            let split = node.point[node.axis];
+            if let Some(left) = node.left {
+                if low[node.axis] < split {
+                    stack.push(left);
+                }
+            }
+            if let Some(right) = node.right {
+                if high[node.axis] >= split {
+                    stack.push(right);
+                }
+            }
+        }
+
+        result
+    }
+
+    // returns the point and the item of `node` with `distance`
+    fn entry(&self, node: usize, distance: f64) -> (&[f64; K], &T, f64) {
+        let node = &self.nodes[node];
+
+        (&node.point, node.item.as_ref().unwrap(), distance)
+    }
+
+    // collects k nearest points to the query in a max heap of squared distances
+    fn search(
+        &self,
+        node: Option<usize>,
+        query: &[f64; K],
+        k: usize,
+        heap: &mut BinaryHeap<Candidate>,
+    ) {
+        let index = match node {
+            Some(index) => index,
+            None => return,
+        };
+        let node = &self.nodes[index];
+
+        if node.item.is_some() {
+            heap.push(Candidate {
+                distance: distance_squared(query, &node.point),
+                node: index,
+            });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // the side containing the query is more promising, so it is visited first
+        let offset = query[node.axis] - node.point[node.axis];
+        let (near, far) = if offset < 0.0 {
+            (node.left, node.right)
+        } else {
+            (node.right, node.left)
+        };
+        self.search(near, query, k, heap);
+        if heap.len() < k || offset * offset <= heap.peek().unwrap().distance {
+            self.search(far, query, k, heap);
+        }
+    }
+}
+
+fn distance_squared<const K: usize>(a: &[f64; K], b: &[f64; K]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+impl<T, const K: usize> Default for KdTree<T, K> {
+    fn default() -> Self {
+        KdTree::init()
+    }
+}
+
+impl<T, const K: usize> MemoryFootprint for KdTree<T, K> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<([f64; K], T)>(self.size)
+            .with_vec(&self.nodes)
+            .with_vec(&self.free)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn random_point<const K: usize>(rng: &mut SplitMix64) -> [f64; K] {
+        let mut point = [0.0; K];
+        for coordinate in point.iter_mut() {
+            // few distinct values, so many points share coordinates
+            *coordinate = rng.next_below(20) as f64;
+        }
+        point
+    }
+
+    #[test]
+    fn spatial_kd_tree_matches_naive() {
+        let mut rng = SplitMix64::init(21);
+        let mut tree: KdTree<usize, 3> = KdTree::init();
+        let mut model: Vec<([f64; 3], usize)> = Vec::new();
+
+        for step in 0..3000 {
+            match rng.next_below(4) {
+                0 if !model.is_empty() => {
+                    let index = rng.next_below(model.len() as u64) as usize;
+                    let point = model[index].0;
+                    let item = tree.remove(&point).unwrap();
+                    let position = model
+                        .iter()
+                        .position(|entry| *entry == (point, item))
+                        .unwrap();
+                    model.swap_remove(position);
+                }
+                _ => {
+                    let point = random_point(&mut rng);
+                    tree.insert(point, step);
+                    model.push((point, step));
+                }
+            }
+            assert_eq!(tree.size(), model.len());
+
+            if step % 10 == 0 {
+                let query = random_point(&mut rng);
+                let mut distances: Vec<f64> = model
+                    .iter()
+                    .map(|(point, _)| distance_squared(&query, point).sqrt())
+                    .collect();
+                distances.sort_by(f64::total_cmp);
+
+                let found: Vec<f64> = tree
+                    .k_nearest(&query, 5)
+                    .iter()
+                    .map(|entry| entry.2)
+                    .collect();
+                assert_eq!(found, distances.iter().take(5).copied().collect::<Vec<_>>());
+
+                let within = tree.within(&query, 6.0);
+                assert_eq!(
+                    within.len(),
+                    distances
+                        .iter()
+                        .filter(|distance| **distance <= 6.0)
+                        .count()
+                );
+
+                let low = [query[0] - 4.0, query[1] - 4.0, query[2] - 4.0];
+                let high = [query[0] + 4.0, query[1] + 4.0, query[2] + 4.0];
+                let mut found: Vec<usize> = tree
+                    .range(&low, &high)
+                    .iter()
+                    .map(|(_, item)| **item)
+                    .collect();
+                let mut expected: Vec<usize> = model
+                    .iter()
+                    .filter(|(point, _)| {
+                        (0..3).all(|axis| low[axis] <= point[axis] && point[axis] <= high[axis])
+                    })
+                    .map(|(_, item)| *item)
+                    .collect();
+                found.sort_unstable();
+                expected.sort_unstable();
+                assert_eq!(found, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn spatial_kd_tree_sorted_inserts_stay_shallow() {
+        let mut tree: KdTree<usize, 2> = KdTree::init();
+        for index in 0..4096 {
+            tree.insert([index as f64, index as f64], index);
+        }
+
+        // a tree without rebuilds would be a path of 4096 nodes
+        assert!(tree.depth() <= 40);
+        assert_eq!(
+            tree.nearest(&[100.2, 100.4]).map(|entry| *entry.1),
+            Some(100)
+        );
+
+        for index in 0..4000 {
+            assert_eq!(tree.remove(&[index as f64, index as f64]), Some(index));
+        }
+        assert_eq!(tree.size(), 96);
+        assert!(tree.depth() <= 20);
+        assert!(!tree.contains(&[5.0, 5.0]));
+        assert!(tree.contains(&[4000.0, 4000.0]));
+    }
+
+    #[test]
+    fn spatial_kd_tree_duplicates_and_empty() {
+        let mut tree: KdTree<char, 1> =
+            KdTree::from_vec(vec![([1.0], 'a'), ([1.0], 'b'), ([1.0], 'c')]);
+        assert_eq!(tree.within(&[1.0], 0.0).len(), 3);
+
+        let mut removed = vec![
+            tree.remove(&[1.0]).unwrap(),
+            tree.remove(&[1.0]).unwrap(),
+            tree.remove(&[1.0]).unwrap(),
+        ];
+        removed.sort_unstable();
+        assert_eq!(removed, vec!['a', 'b', 'c']);
+        assert_eq!(tree.remove(&[1.0]), None);
+        assert!(tree.is_empty());
+        assert!(tree.nearest(&[0.0]).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Points must have at least one dimension")]
+    fn spatial_kd_tree_panic_zero_dimensions() {
+        let _: KdTree<(), 0> = KdTree::init();
+    }
+}
@@ -0,0 +1,347 @@
+use crate::spatial::HashFamily;
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Locality sensitive hashing index for approximate nearest neighbor search and near-duplicate detection.
+///
+/// Signatures of the hash family are split into `bands` bands of `rows` consecutive values.
+/// Every band has a hash table and two items become candidates of each other if they agree on all values of at least one band.
+/// More rows make buckets more selective and more bands increase the chance that similar items meet.
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::{LshIndex, MinHashFamily};
+/// use std::collections::HashSet;
+///
+/// let mut index = LshIndex::init(MinHashFamily::init(64, 1), 16);
+///
+/// let a: HashSet<u32> = (0..100).collect();
+/// let b: HashSet<u32> = (1..101).collect();
+/// let c: HashSet<u32> = (500..600).collect();
+///
+/// index.insert(a);
+/// index.insert(b);
+/// index.insert(c);
+///
+/// assert_eq!(index.near_duplicates(0.1), vec![(0, 1, 1.0 - 99.0 / 101.0)]);
+/// ```
+pub struct LshIndex<T, H>
+where
+    H: HashFamily<T>,
+{
+    family: H,
+    bands: usize,
+    rows: usize,
+
+    // for every band: hash of the band of the signature -> ids of items
+    tables: Vec<HashMap<u64, Vec<usize>>>,
+    items: Vec<T>,
+}
+
+impl<T, H> LshIndex<T, H>
+where
+    H: HashFamily<T>,
+{
+    /// Creates an empty index
+    ///
+    /// # Arguments
+    /// * `family`: hash family used to compute signatures
+    /// * `bands`: number of bands of the signature
+    ///
+    /// # Panics
+    /// * panics if `bands` is zero or size of the family is not a multiple of `bands`
+    pub fn init(family: H, bands: usize) -> LshIndex<T, H> {
+        if bands == 0 || family.size() % bands != 0 {
+            panic!("Size of the hash family must be a positive multiple of number of bands");
+        }
+
+        let rows = family.size() / bands;
+
+        LshIndex {
+            family,
+            bands,
+            rows,
+            tables: (0..bands).map(|_| HashMap::new()).collect(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Returns number of items in the index
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items in the index
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns number of bands
+    pub fn bands(&self) -> usize {
+        self.bands
+    }
+
+    /// Returns number of rows in every band
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the hash family of the index
+    pub fn family(&self) -> &H {
+        &self.family
+    }
+
+    /// Returns item with id `id`, or `None` if there is no such item
+    ///
+    /// # Arguments
+    /// * `id`: id of the item returned by `insert`
+    pub fn get(&self, id: usize) -> Option<&T> {
+        self.items.get(id)
+    }
+
+    /// Inserts `item` into the index and returns its id. Ids are assigned consecutively starting from zero
+    ///
+    /// # Arguments
+    /// * `item`: item to be inserted
+    pub fn insert(&mut self, item: T) -> usize {
+        let id = self.items.len();
+        let keys = self.band_keys(&item);
+
+        for (table, key) in self.tables.iter_mut().zip(keys) {
+            table.entry(key).or_default().push(id);
+        }
+        self.items.push(item);
+
+        id
+    }
+
+    /// Returns ids of the items sharing at least one band with `query`, sorted ascending
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    pub fn candidates(&self, query: &T) -> Vec<usize> {
+        let mut candidates = HashSet::new();
+
+        for (table, key) in self.tables.iter().zip(self.band_keys(query)) {
+            if let Some(bucket) = table.get(&key) {
+                candidates.extend(bucket.iter().copied());
+            }
+        }
+
+        let mut candidates: Vec<usize> = candidates.into_iter().collect();
+        candidates.sort_unstable();
+
+        candidates
+    }
+
+    /// Returns the id and distance of the nearest candidate of `query`, or `None` if it has no candidates.
+    /// The result is approximate: the true nearest item may not be a candidate
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    pub fn nearest(&self, query: &T) -> Option<(usize, f64)> {
+        self.k_nearest(query, 1).pop()
+    }
+
+    /// Returns at most `k` nearest candidates of `query` as (id, distance) pairs, sorted from nearest to farthest
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    /// * `k`: number of items to be returned
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::{LshIndex, RandomHyperplanes};
+    ///
+    /// let mut index = LshIndex::init(RandomHyperplanes::init(2, 32, 3), 8);
+    ///
+    /// index.insert(vec![1.0, 0.0]);
+    /// index.insert(vec![0.0, 1.0]);
+    /// index.insert(vec![10.0, 1.0]);
+    ///
+    /// let nearest = index.k_nearest(&vec![5.0, 0.2], 2);
+    ///
+    /// // angular distance: direction of the query is closest to the first vector
+    /// assert_eq!(nearest[0].0, 0);
+    /// assert_eq!(nearest[1].0, 2);
+    /// ```
+    pub fn k_nearest(&self, query: &T, k: usize) -> Vec<(usize, f64)> {
+        let mut ranked: Vec<(usize, f64)> = self
+            .candidates(query)
+            .into_iter()
+            .map(|id| (id, self.family.distance(query, &self.items[id])))
+            .collect();
+
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(k);
+
+        ranked
+    }
+
+    /// Returns all pairs `(i, j, distance)` with `i < j` of items sharing a bucket whose distance is at most `threshold`,
+    /// sorted by ids
+    ///
+    /// # Arguments
+    /// * `threshold`: largest distance of near-duplicate items
+    pub fn near_duplicates(&self, threshold: f64) -> Vec<(usize, usize, f64)> {
+        let mut pairs = HashSet::new();
+
+        for table in self.tables.iter() {
+            for bucket in table.values() {
+                for (position, &i) in bucket.iter().enumerate() {
+                    for &j in &bucket[position + 1..] {
+                        pairs.insert((i, j));
+                    }
+                }
+            }
+        }
+
+        let mut result: Vec<(usize, usize, f64)> = pairs
+            .into_iter()
+            .map(|(i, j)| (i, j, self.family.distance(&self.items[i], &self.items[j])))
+            .filter(|(_, _, distance)| *distance <= threshold)
+            .collect();
+        result.sort_by_key(|(i, j, _)| (*i, *j));
+
+        result
+    }
+
+    // hash of every band of the signature
+    fn band_keys(&self, item: &T) -> Vec<u64> {
+        self.family
+            .signature(item)
+            .chunks(self.rows)
+            .map(|band| {
+                let mut hasher = DefaultHasher::new();
+                band.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect()
+    }
+}
+
+impl<T, H> MemoryFootprint for LshIndex<T, H>
+where
+    H: HashFamily<T> + MemoryFootprint,
+{
+    fn footprint(&self) -> Footprint {
+        self.tables.iter().fold(
+            Footprint::of::<T>(self.items.len())
+                .with_vec(&self.items)
+                .with_vec(&self.tables)
+                .with(self.family.footprint()),
+            |footprint, table| {
+                table
+                    .values()
+                    .fold(footprint.with_hash_map(table), |footprint, ids| {
+                        footprint.with_vec(ids)
+                    })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::{MinHashFamily, RandomHyperplanes};
+    use crate::util::SplitMix64;
+
+    fn random_vector(random: &mut SplitMix64, dimension: usize) -> Vec<f64> {
+        (0..dimension).map(|_| random.next_gaussian()).collect()
+    }
+
+    #[test]
+    fn spatial_lsh_hyperplanes_finds_perturbed_vectors() {
+        let mut random = SplitMix64::init(99);
+        let mut index = LshIndex::init(RandomHyperplanes::init(32, 80, 5), 10);
+
+        let vectors: Vec<Vec<f64>> = (0..300).map(|_| random_vector(&mut random, 32)).collect();
+        for vector in vectors.iter() {
+            index.insert(vector.clone());
+        }
+
+        let mut found = 0;
+        for (id, vector) in vectors.iter().enumerate().take(50) {
+            let query: Vec<f64> = vector
+                .iter()
+                .map(|x| x + 0.05 * random.next_gaussian())
+                .collect();
+            if index.nearest(&query).map(|(nearest, _)| nearest) == Some(id) {
+                found += 1;
+            }
+        }
+
+        assert!(found >= 45);
+    }
+
+    #[test]
+    fn spatial_lsh_candidates_are_subset() {
+        let mut index = LshIndex::init(MinHashFamily::init(20, 3), 5);
+        let sets: Vec<HashSet<u32>> = (0..30).map(|i| (i * 10..i * 10 + 30).collect()).collect();
+        for set in sets.iter() {
+            index.insert(set.clone());
+        }
+
+        let candidates = index.candidates(&sets[7]);
+
+        assert!(candidates.contains(&7));
+        assert!(candidates.windows(2).all(|w| w[0] < w[1]));
+        assert!(!candidates.contains(&20));
+
+        let nearest = index.k_nearest(&sets[7], 3);
+        assert_eq!(nearest[0], (7, 0.0));
+        assert_eq!(index.get(7), Some(&sets[7]));
+        assert_eq!(index.get(30), None);
+    }
+
+    #[test]
+    fn spatial_lsh_near_duplicates() {
+        let mut index = LshIndex::init(MinHashFamily::init(100, 11), 20);
+
+        let documents: Vec<HashSet<u32>> = vec![
+            (0..200).collect(),
+            (1000..1200).collect(),
+            (2..200).collect(),
+            (2000..2200).collect(),
+            (1000..1195).collect(),
+        ];
+        for document in documents {
+            index.insert(document);
+        }
+
+        let duplicates: Vec<(usize, usize)> = index
+            .near_duplicates(0.05)
+            .into_iter()
+            .map(|(i, j, _)| (i, j))
+            .collect();
+
+        assert_eq!(duplicates, vec![(0, 2), (1, 4)]);
+    }
+
+    #[test]
+    fn spatial_lsh_hyperplane_distance() {
+        let family = RandomHyperplanes::init(2, 4, 0);
+
+        assert!((family.distance(&vec![1.0, 0.0], &vec![0.0, 1.0]) - 0.5).abs() < 1e-12);
+        assert!((family.distance(&vec![1.0, 0.0], &vec![-1.0, 0.0]) - 1.0).abs() < 1e-12);
+        assert_eq!(family.distance(&vec![0.0, 0.0], &vec![1.0, 1.0]), 0.5);
+        assert_eq!(family.signature(&vec![1.0, 1.0]).len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension of the vector does not match dimension of the family")]
+    fn spatial_lsh_hyperplane_dimension_mismatch() {
+        RandomHyperplanes::init(3, 4, 0).signature(&vec![1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Size of the hash family must be a positive multiple of number of bands"
+    )]
+    fn spatial_lsh_bands_must_divide_size() {
+        LshIndex::<HashSet<u32>, _>::init(MinHashFamily::init(10, 0), 3);
+    }
+}
@@ -0,0 +1,26 @@
+mod bvh;
+mod cover_tree;
+mod curve;
+mod hash_family;
+mod hash_grid;
+mod kd_tree;
+mod lsh;
+mod priority_search_tree;
+mod vp_tree;
+mod z_order;
+
+pub use bvh::Bvh;
+pub use cover_tree::CoverTree;
+pub use curve::hilbert_decode;
+pub use curve::hilbert_encode;
+pub use curve::morton_decode;
+pub use curve::morton_encode;
+pub use hash_family::HashFamily;
+pub use hash_family::MinHashFamily;
+pub use hash_family::RandomHyperplanes;
+pub use hash_grid::SpatialHashGrid;
+pub use kd_tree::KdTree;
+pub use lsh::LshIndex;
+pub use priority_search_tree::PrioritySearchTree;
+pub use vp_tree::VpTree;
+pub use z_order::ZOrderMap;
@@ -0,0 +1,278 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+// node of the tree. its entry has the least y of its subtree, the other entries of the subtree are split by x:
+// entries up to `split` in x order are on the left, the rest on the right
+#[derive(Debug, Clone)]
+struct Node {
+    // rank of the entry in x order
+    entry: usize,
+    split: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// A priority search tree stores points with an item each and answers three sided queries: the points with x in a
+/// range and y at most a bound, in O(log n + k) time for k points found, in O(n) space. It is a heap on y and a
+/// search tree on x at once: every node holds the point of least y of its subtree and splits the other points by
+/// the median x, so a query walks down the two borders of the x range and stops in every subtree whose least y is
+/// already too large. Points are given up front and may share coordinates
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::PrioritySearchTree;
+///
+/// // tasks as (release time, deadline)
+/// let tree = PrioritySearchTree::init(vec![
+///     ((1, 10), "backup"),
+///     ((3, 4), "email"),
+///     ((4, 20), "report"),
+///     ((6, 7), "call"),
+///     ((9, 2), "meeting"),
+/// ]);
+///
+/// // released between 2 and 8, due by 10
+/// let mut found: Vec<&str> = tree.query(2, 8, 10).iter().map(|(_, task)| **task).collect();
+/// found.sort();
+/// assert_eq!(found, vec!["call", "email"]);
+///
+/// assert_eq!(tree.lowest(0, 5), Some((&(3, 4), &"email")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrioritySearchTree<K, T> {
+    // entries in increasing order of x, ties by y
+    entries: Vec<((K, K), T)>,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+impl<K: Ord + Copy, T> PrioritySearchTree<K, T> {
+    /// Builds the tree of `entries`, points as (x, y) pairs with their items
+    /// * Complexity: O(n log n)
+    ///
+    /// # Arguments
+    /// * `entries`: points with their items
+    pub fn init(mut entries: Vec<((K, K), T)>) -> PrioritySearchTree<K, T> {
+        entries.sort_by_key(|entry| entry.0);
+
+        let mut tree = PrioritySearchTree {
+            entries,
+            nodes: Vec::new(),
+            root: None,
+        };
+        let ranks: Vec<usize> = (0..tree.entries.len()).collect();
+        tree.nodes.reserve(ranks.len());
+        tree.root = tree.build(ranks);
+
+        tree
+    }
+
+    // builds the subtree of the entries with `ranks`, which are increasing, and returns its root
+    fn build(&mut self, mut ranks: Vec<usize>) -> Option<usize> {
+        let lowest = (0..ranks.len()).min_by_key(|index| (self.y(ranks[*index]), ranks[*index]))?;
+        let entry = ranks.remove(lowest);
+
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            entry,
+            split: entry,
+            left: None,
+            right: None,
+        });
+        if ranks.is_empty() {
+            return Some(node);
+        }
+
+        let middle = (ranks.len() - 1) / 2;
+        let right = ranks.split_off(middle + 1);
+        self.nodes[node].split = ranks[middle];
+        self.nodes[node].left = self.build(ranks);
+        self.nodes[node].right = self.build(right);
+
+        Some(node)
+    }
+
+    fn x(&self, rank: usize) -> K {
+        self.entries[rank].0 .0
+    }
+
+    fn y(&self, rank: usize) -> K {
+        self.entries[rank].0 .1
+    }
+
+    /// Returns number of points in the tree
+    pub fn size(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no points in the tree
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // returns the ranks of the entries with x in [x_low, x_high] as a half open range
+    fn ranks(&self, x_low: K, x_high: K) -> (usize, usize) {
+        let low = self.entries.partition_point(|entry| entry.0 .0 < x_low);
+        let high = self.entries.partition_point(|entry| entry.0 .0 <= x_high);
+
+        (low, high)
+    }
+
+    /// Returns the points with `x_low` ≤ x ≤ `x_high` and y ≤ `y_high` with their items, in no particular order
+    /// * Complexity: O(log n + k) for k points found
+    ///
+    /// # Arguments
+    /// * `x_low`: lower bound of the x coordinates
+    /// * `x_high`: upper bound of the x coordinates
+    /// * `y_high`: upper bound of the y coordinates
+    pub fn query(&self, x_low: K, x_high: K, y_high: K) -> Vec<(&(K, K), &T)> {
+        let (low, high) = self.ranks(x_low, x_high);
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if self.y(node.entry) > y_high {
+                continue;
+            }
+
+            if low <= node.entry && node.entry < high {
+                let (point, item) = &self.entries[node.entry];
+                result.push((point, item));
+            }
+            if let (Some(left), true) = (node.left, low <= node.split) {
+                stack.push(left);
+            }
+            if let (Some(right), true) = (node.right, node.split + 1 < high) {
+                stack.push(right);
+            }
+        }
+
+        result
+    }
+
+    /// Returns the point of least y, ties by least x, among the points with `x_low` ≤ x ≤ `x_high` with its item,
+    /// None if there is no such point
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `x_low`: lower bound of the x coordinates
+    /// * `x_high`: upper bound of the x coordinates
+    pub fn lowest(&self, x_low: K, x_high: K) -> Option<(&(K, K), &T)> {
+        let (low, high) = self.ranks(x_low, x_high);
+        let mut best: Option<usize> = None;
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+
+            // every point of the subtree is at least as high as the point of the node
+            let key = (self.y(node.entry), self.x(node.entry), node.entry);
+            if best.is_some_and(|best| (self.y(best), self.x(best), best) <= key) {
+                continue;
+            }
+
+            if low <= node.entry && node.entry < high {
+                best = Some(node.entry);
+                continue;
+            }
+            if let (Some(left), true) = (node.left, low <= node.split) {
+                stack.push(left);
+            }
+            if let (Some(right), true) = (node.right, node.split + 1 < high) {
+                stack.push(right);
+            }
+        }
+
+        best.map(|rank| {
+            let (point, item) = &self.entries[rank];
+            (point, item)
+        })
+    }
+
+    /// Returns an iterator over the points and their items in increasing order of x
+    pub fn iter(&self) -> impl Iterator<Item = (&(K, K), &T)> {
+        self.entries.iter().map(|(point, item)| (point, item))
+    }
+}
+
+impl<K, T> MemoryFootprint for PrioritySearchTree<K, T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.entries.len())
+            .with_vec(&self.entries)
+            .with_vec(&self.nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn spatial_priority_search_tree_matches_brute_force() {
+        let mut rng = SplitMix64::init(71);
+        for size in [0, 1, 2, 3, 10, 500] {
+            let entries: Vec<((i32, i32), usize)> = (0..size)
+                .map(|index| {
+                    (
+                        (rng.next_below(60) as i32, rng.next_below(60) as i32),
+                        index,
+                    )
+                })
+                .collect();
+            let tree = PrioritySearchTree::init(entries.clone());
+            assert_eq!(tree.size(), size);
+
+            for _ in 0..200 {
+                let x_low = rng.next_below(64) as i32 - 2;
+                let x_high = rng.next_below(64) as i32 - 2;
+                let y_high = rng.next_below(64) as i32 - 2;
+                let inside = |x: i32| x_low <= x && x <= x_high;
+
+                let mut found: Vec<usize> = tree
+                    .query(x_low, x_high, y_high)
+                    .iter()
+                    .map(|(_, i)| **i)
+                    .collect();
+                found.sort_unstable();
+                let expected: Vec<usize> = entries
+                    .iter()
+                    .filter(|((x, y), _)| inside(*x) && *y <= y_high)
+                    .map(|(_, index)| *index)
+                    .collect();
+                assert_eq!(found, expected);
+
+                let lowest = entries
+                    .iter()
+                    .filter(|((x, _), _)| inside(*x))
+                    .map(|((x, y), _)| (*y, *x))
+                    .min();
+                assert_eq!(
+                    tree.lowest(x_low, x_high).map(|((x, y), _)| (*y, *x)),
+                    lowest
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn spatial_priority_search_tree_shared_coordinates() {
+        let entries: Vec<((u8, u8), char)> = vec![
+            ((2, 2), 'a'),
+            ((2, 2), 'b'),
+            ((2, 5), 'c'),
+            ((2, 1), 'd'),
+            ((7, 1), 'e'),
+        ];
+        let tree = PrioritySearchTree::init(entries);
+
+        assert_eq!(tree.query(2, 2, 2).len(), 3);
+        assert_eq!(tree.query(0, 9, 1).len(), 2);
+        assert!(tree.query(3, 6, 9).is_empty());
+        assert!(tree.query(5, 1, 9).is_empty());
+        assert_eq!(
+            tree.iter().map(|(point, _)| point.0).collect::<Vec<_>>(),
+            vec![2, 2, 2, 2, 7]
+        );
+    }
+}
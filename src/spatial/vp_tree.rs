@@ -0,0 +1,466 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// node of the tree. items not farther from the vantage point than threshold are in the inside subtree
+struct Node {
+    item: usize,
+    threshold: f64,
+    inside: Option<usize>,
+    outside: Option<usize>,
+}
+
+// candidate of a search, ordered by distance so that the farthest candidate is on top of the heap
+struct Candidate {
+    distance: f64,
+    item: usize,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.item.cmp(&other.item))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+/// A vantage point tree is a metric tree for nearest neighbor search under an arbitrary distance function.
+/// Every node picks an item(the vantage point) and splits the remaining items by the median of their distance to it.
+/// Searches prune subtrees using only the triangle inequality, so any metric works: edit distance of strings,
+/// cosine or euclidean distance of embeddings, hamming distance of hashes, etc.
+///
+/// The metric must be non-negative, symmetric and satisfy the triangle inequality. Otherwise results may be wrong.
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::VpTree;
+///
+/// let points = vec![(0.0, 0.0), (5.0, 5.0), (1.0, 1.0), (9.0, 2.0)];
+/// let euclidean = |a: &(f64, f64), b: &(f64, f64)| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+///
+/// let tree = VpTree::init(points, euclidean);
+///
+/// let (nearest, distance) = tree.nearest(&(4.0, 4.0)).unwrap();
+/// assert_eq!(*nearest, (5.0, 5.0));
+/// assert!((distance - 2f64.sqrt()).abs() < 1e-12);
+/// ```
+pub struct VpTree<T, F>
+where
+    F: Fn(&T, &T) -> f64,
+{
+    items: Vec<T>,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    metric: F,
+}
+
+impl<T, F> VpTree<T, F>
+where
+    F: Fn(&T, &T) -> f64,
+{
+    /// Builds a vantage point tree over `items`.
+    /// * Complexity: O(n log n) distance computations
+    ///
+    /// # Arguments
+    /// * `items`: items to be indexed
+    /// * `metric`: distance function
+    pub fn init(items: Vec<T>, metric: F) -> VpTree<T, F> {
+        let mut tree = VpTree {
+            nodes: Vec::with_capacity(items.len()),
+            items,
+            root: None,
+            metric,
+        };
+
+        let mut indices: Vec<(usize, f64)> = (0..tree.items.len()).map(|i| (i, 0.0)).collect();
+        tree.root = tree.build(&mut indices);
+
+        tree
+    }
+
+    fn build(&mut self, indices: &mut [(usize, f64)]) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+
+        // middle item is used as the vantage point, which avoids the worst case on presorted input
+        let middle = indices.len() / 2;
+        indices.swap(0, middle);
+        let vantage_point = indices[0].0;
+
+        let rest = &mut indices[1..];
+        for entry in rest.iter_mut() {
+            entry.1 = (self.metric)(&self.items[vantage_point], &self.items[entry.0]);
+        }
+
+        let mut threshold = 0.0;
+        let mut split = 0;
+        if !rest.is_empty() {
+            let median = (rest.len() - 1) / 2;
+            rest.select_nth_unstable_by(median, |a, b| a.1.total_cmp(&b.1));
+            threshold = rest[median].1;
+            split = median + 1;
+        }
+
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            item: vantage_point,
+            threshold,
+            inside: None,
+            outside: None,
+        });
+
+        let (inside, outside) = rest.split_at_mut(split);
+        self.nodes[node].inside = self.build(inside);
+        self.nodes[node].outside = self.build(outside);
+
+        Some(node)
+    }
+
+    /// Returns number of items in the tree
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items in the tree
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns items of the tree in the order they were given
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns the item nearest to `query` along with its distance, or `None` if the tree is empty
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    pub fn nearest(&self, query: &T) -> Option<(&T, f64)> {
+        self.k_nearest(query, 1).pop()
+    }
+
+    /// Returns at most `k` items nearest to `query` along with their distance, sorted from nearest to farthest
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    /// * `k`: number of items to be returned
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::VpTree;
+    ///
+    /// let words = vec!["apple", "apply", "ample", "maple", "angle"];
+    /// let hamming = |a: &&str, b: &&str| {
+    ///     a.bytes().zip(b.bytes()).filter(|(x, y)| x != y).count() as f64
+    /// };
+    ///
+    /// let tree = VpTree::init(words, hamming);
+    /// let nearest: Vec<&str> = tree.k_nearest(&"apple", 3).iter().map(|(w, _)| **w).collect();
+    ///
+    /// assert_eq!(nearest, vec!["apple", "apply", "ample"]);
+    /// ```
+    pub fn k_nearest(&self, query: &T, k: usize) -> Vec<(&T, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(self.root, query, k, None, f64::INFINITY, &mut heap);
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|candidate| (&self.items[candidate.item], candidate.distance))
+            .collect()
+    }
+
+    /// Returns all items whose distance to `query` is at most `radius`, sorted from nearest to farthest
+    ///
+    /// # Arguments
+    /// * `query`: the query item
+    /// * `radius`: largest accepted distance
+    pub fn within(&self, query: &T, radius: f64) -> Vec<(&T, f64)> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            let distance = (self.metric)(query, &self.items[node.item]);
+
+            if distance <= radius {
+                result.push(Candidate {
+                    distance,
+                    item: node.item,
+                });
+            }
+            if let Some(inside) = node.inside {
+                if distance - radius <= node.threshold {
+                    stack.push(inside);
+                }
+            }
+            if let Some(outside) = node.outside {
+                if distance + radius >= node.threshold {
+                    stack.push(outside);
+                }
+            }
+        }
+
+        result.sort();
+        result
+            .into_iter()
+            .map(|candidate| (&self.items[candidate.item], candidate.distance))
+            .collect()
+    }
+
+    /// Returns the closest pair of distinct items(by position) along with their distance,
+    /// or `None` if there are less than two items.
+    /// * Complexity: n nearest neighbor searches
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::VpTree;
+    ///
+    /// let numbers = vec![1.0, 10.0, 4.0, 12.5, 20.0];
+    /// let tree = VpTree::init(numbers, |a: &f64, b: &f64| (a - b).abs());
+    ///
+    /// let (a, b, distance) = tree.closest_pair().unwrap();
+    ///
+    /// assert_eq!(distance, 2.5);
+    /// assert_eq!((a.min(*b), a.max(*b)), (10.0, 12.5));
+    /// ```
+    pub fn closest_pair(&self) -> Option<(&T, &T, f64)> {
+        let mut best: Option<(usize, usize, f64)> = None;
+
+        for item in 0..self.items.len() {
+            let bound = best.map_or(f64::INFINITY, |(_, _, distance)| distance);
+            let mut heap = BinaryHeap::with_capacity(2);
+            self.search(
+                self.root,
+                &self.items[item],
+                1,
+                Some(item),
+                bound,
+                &mut heap,
+            );
+
+            if let Some(candidate) = heap.pop() {
+                if candidate.distance < bound {
+                    best = Some((item, candidate.item, candidate.distance));
+                }
+            }
+        }
+
+        best.map(|(a, b, distance)| (&self.items[a], &self.items[b], distance))
+    }
+
+    // collects k nearest items to the query in a max heap. items farther than bound and the excluded item are ignored
+    fn search(
+        &self,
+        node: Option<usize>,
+        query: &T,
+        k: usize,
+        exclude: Option<usize>,
+        bound: f64,
+        heap: &mut BinaryHeap<Candidate>,
+    ) {
+        let node = match node {
+            Some(node) => &self.nodes[node],
+            None => return,
+        };
+
+        let distance = (self.metric)(query, &self.items[node.item]);
+        if exclude != Some(node.item) && distance <= bound {
+            heap.push(Candidate {
+                distance,
+                item: node.item,
+            });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // largest distance that can still improve the result
+        let tau = |heap: &BinaryHeap<Candidate>| {
+            if heap.len() < k {
+                bound
+            } else {
+                heap.peek().unwrap().distance
+            }
+        };
+
+        // the side containing the query is more promising, so it is visited first
+        let inside_first = distance <= node.threshold;
+        for visit_inside in [inside_first, !inside_first].iter() {
+            let (child, prune) = if *visit_inside {
+                (node.inside, distance - tau(heap) > node.threshold)
+            } else {
+                (node.outside, distance + tau(heap) < node.threshold)
+            };
+
+            if !prune {
+                self.search(child, query, k, exclude, bound, heap);
+            }
+        }
+    }
+}
+
+impl<T, F> MemoryFootprint for VpTree<T, F>
+where
+    F: Fn(&T, &T) -> f64,
+{
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.items.len())
+            .with_vec(&self.items)
+            .with_vec(&self.nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn euclidean(a: &(f64, f64), b: &(f64, f64)) -> f64 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    fn levenshtein(a: &str, b: &str) -> f64 {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+        for i in 1..=a.len() {
+            let mut current = vec![i; b.len() + 1];
+            for j in 1..=b.len() {
+                let substitution = previous[j - 1] + if a[i - 1] == b[j - 1] { 0 } else { 1 };
+                current[j] = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+            }
+            previous = current;
+        }
+
+        previous[b.len()] as f64
+    }
+
+    fn random_points(n: usize, seed: u64) -> Vec<(f64, f64)> {
+        let mut seed = seed;
+        let mut next = || -> f64 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % 10000) as f64 / 100.0
+        };
+
+        (0..n).map(|_| (next(), next())).collect()
+    }
+
+    #[test]
+    fn spatial_vp_tree_empty() {
+        let tree = VpTree::init(Vec::new(), euclidean);
+
+        assert!(tree.is_empty());
+        assert!(tree.nearest(&(0.0, 0.0)).is_none());
+        assert!(tree.k_nearest(&(0.0, 0.0), 3).is_empty());
+        assert!(tree.within(&(0.0, 0.0), 10.0).is_empty());
+        assert!(tree.closest_pair().is_none());
+    }
+
+    #[test]
+    fn spatial_vp_tree_k_nearest_matches_brute_force() {
+        let points = random_points(500, 3);
+        let tree = VpTree::init(points.clone(), euclidean);
+
+        for query in random_points(50, 11) {
+            let mut expected: Vec<f64> = points.iter().map(|p| euclidean(p, &query)).collect();
+            expected.sort_by(|a, b| a.total_cmp(b));
+
+            let result: Vec<f64> = tree.k_nearest(&query, 7).iter().map(|(_, d)| *d).collect();
+            assert_eq!(result, expected[..7].to_vec());
+        }
+
+        assert_eq!(tree.k_nearest(&(0.0, 0.0), 1000).len(), 500);
+        assert!(tree.k_nearest(&(0.0, 0.0), 0).is_empty());
+    }
+
+    #[test]
+    fn spatial_vp_tree_within_matches_brute_force() {
+        let points = random_points(300, 5);
+        let tree = VpTree::init(points.clone(), euclidean);
+
+        for (i, query) in random_points(20, 13).iter().enumerate() {
+            let radius = i as f64 * 2.0;
+            let expected = points
+                .iter()
+                .filter(|p| euclidean(p, query) <= radius)
+                .count();
+            let result = tree.within(query, radius);
+
+            assert_eq!(result.len(), expected);
+            assert!(result.windows(2).all(|w| w[0].1 <= w[1].1));
+        }
+    }
+
+    #[test]
+    fn spatial_vp_tree_closest_pair_matches_brute_force() {
+        let points = random_points(400, 17);
+        let tree = VpTree::init(points.clone(), euclidean);
+
+        let mut expected = f64::INFINITY;
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                expected = expected.min(euclidean(&points[i], &points[j]));
+            }
+        }
+
+        let (a, b, distance) = tree.closest_pair().unwrap();
+        assert_eq!(distance, expected);
+        assert_eq!(euclidean(a, b), expected);
+
+        // duplicates are a pair at distance zero
+        let tree = VpTree::init(vec![(1.0, 1.0), (5.0, 5.0), (1.0, 1.0)], euclidean);
+        assert_eq!(tree.closest_pair().unwrap().2, 0.0);
+        assert!(VpTree::init(vec![(1.0, 1.0)], euclidean)
+            .closest_pair()
+            .is_none());
+    }
+
+    #[test]
+    fn spatial_vp_tree_edit_distance() {
+        let words: Vec<String> = vec![
+            "kitten", "sitting", "mitten", "fitting", "written", "bitten", "knitting", "sitter",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let tree = VpTree::init(words.clone(), |a: &String, b: &String| levenshtein(a, b));
+
+        let query = String::from("smitten");
+        let (nearest, distance) = tree.nearest(&query).unwrap();
+        assert_eq!(nearest, "mitten");
+        assert_eq!(distance, 1.0);
+
+        let mut expected: Vec<&String> = words
+            .iter()
+            .filter(|w| levenshtein(w, &query) <= 2.0)
+            .collect();
+        expected.sort();
+        let mut result: Vec<&String> = tree
+            .within(&query, 2.0)
+            .into_iter()
+            .map(|(w, _)| w)
+            .collect();
+        result.sort();
+
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,303 @@
+use super::curve::coordinate_bits;
+use super::{morton_decode, morton_encode};
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// A Z-order map stores a value for every point of a grid of `K` dimensions in a `BTreeMap` keyed by the Morton code
+/// of the point, so points close in space are mostly close in the map. A box query is decomposed into ranges of
+/// codes: the code space is split like a quadtree (an octree in 3D) into aligned cubes, a cube inside the box is one
+/// contiguous range, a cube outside it or without points is skipped, and only cubes crossing the border of the box are
+/// split further. Coordinates are unsigned with 64 / K bits each, at most 32: 32 bits in 2D, 21 bits in 3D
+///
+/// # Examples
+/// ```
+/// use rudac::spatial::ZOrderMap;
+///
+/// let mut map = ZOrderMap::init();
+/// map.insert([1, 1], "a");
+/// map.insert([6, 2], "b");
+/// map.insert([3, 5], "c");
+/// map.insert([9, 9], "d");
+///
+/// let found: Vec<&str> = map.range(&[0, 0], &[6, 5]).iter().map(|(_, value)| **value).collect();
+/// assert_eq!(found, vec!["a", "b", "c"]);
+///
+/// assert_eq!(map.remove(&[6, 2]), Some("b"));
+/// assert_eq!(map.get(&[3, 5]), Some(&"c"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZOrderMap<V, const K: usize> {
+    map: BTreeMap<u64, V>,
+}
+
+impl<V, const K: usize> ZOrderMap<V, K> {
+    /// Initializes an empty map
+    ///
+    /// # Panics
+    /// * panics if `K` is zero
+    pub fn init() -> ZOrderMap<V, K> {
+        coordinate_bits::<K>();
+
+        ZOrderMap {
+            map: BTreeMap::new(),
+        }
+    }
+
+    /// Returns number of points in the map
+    pub fn size(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if there are no points in the map
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes every point of the map
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Inserts `value` at `point` and returns the value that was there before, if any
+    /// * Complexity: O(K log C + log n) for coordinates of log C bits
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    /// * `value`: value of the point
+    ///
+    /// # Panics
+    /// * panics if a coordinate does not fit in 64 / K bits
+    pub fn insert(&mut self, point: [u32; K], value: V) -> Option<V> {
+        self.map.insert(morton_encode(point), value)
+    }
+
+    /// Removes `point` and returns its value, None if it is not in the map
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    ///
+    /// # Panics
+    /// * panics if a coordinate does not fit in 64 / K bits
+    pub fn remove(&mut self, point: &[u32; K]) -> Option<V> {
+        self.map.remove(&morton_encode(*point))
+    }
+
+    /// Returns a reference to the value at `point`, None if it is not in the map
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    ///
+    /// # Panics
+    /// * panics if a coordinate does not fit in 64 / K bits
+    pub fn get(&self, point: &[u32; K]) -> Option<&V> {
+        self.map.get(&morton_encode(*point))
+    }
+
+    /// Returns a mutable reference to the value at `point`, None if it is not in the map
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    ///
+    /// # Panics
+    /// * panics if a coordinate does not fit in 64 / K bits
+    pub fn get_mut(&mut self, point: &[u32; K]) -> Option<&mut V> {
+        self.map.get_mut(&morton_encode(*point))
+    }
+
+    /// Returns true if `point` is in the map
+    ///
+    /// # Arguments
+    /// * `point`: coordinates of the point
+    ///
+    /// # Panics
+    /// * panics if a coordinate does not fit in 64 / K bits
+    pub fn contains(&self, point: &[u32; K]) -> bool {
+        self.map.contains_key(&morton_encode(*point))
+    }
+
+    /// Returns an iterator over the points and their values in Z-order
+    pub fn iter(&self) -> impl Iterator<Item = ([u32; K], &V)> {
+        self.map
+            .iter()
+            .map(|(code, value)| (morton_decode(*code), value))
+    }
+
+    /// Returns the points inside the box with corners `low` and `high`, borders included, with their values in
+    /// Z-order
+    /// * Complexity: O(m + b log n) where m points are found and the border of the box crosses b cubes with points
+    ///
+    /// # Arguments
+    /// * `low`: the corner with the smallest coordinates
+    /// * `high`: the corner with the largest coordinates
+    pub fn range(&self, low: &[u32; K], high: &[u32; K]) -> Vec<([u32; K], &V)> {
+        self.ranges(low, high)
+            .into_iter()
+            .flat_map(|range| self.map.range(range))
+            .map(|(code, value)| (morton_decode(*code), value))
+            .collect()
+    }
+
+    /// Returns the ranges of Morton codes that cover the box with corners `low` and `high`, borders included, in
+    /// increasing order. Cubes without points in the map are left out and adjacent ranges are merged, so every point
+    /// of the map inside a range is inside the box
+    /// * Complexity: O(b log n) where the border of the box crosses b cubes with points
+    ///
+    /// # Arguments
+    /// * `low`: the corner with the smallest coordinates
+    /// * `high`: the corner with the largest coordinates
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::spatial::ZOrderMap;
+    ///
+    /// let mut map = ZOrderMap::init();
+    /// for x in 0..4 {
+    ///     for y in 0..4 {
+    ///         map.insert([x, y], ());
+    ///     }
+    /// }
+    ///
+    /// // the square of side 2 at the origin is a single range, the next column adds two more codes
+    /// assert_eq!(map.ranges(&[0, 0], &[1, 1]), vec![0..=3]);
+    /// assert_eq!(map.ranges(&[0, 0], &[2, 1]), vec![0..=4, 6..=6]);
+    /// ```
+    pub fn ranges(&self, low: &[u32; K], high: &[u32; K]) -> Vec<RangeInclusive<u64>> {
+        let mut result = Vec::new();
+        if (0..K).all(|axis| low[axis] <= high[axis]) {
+            self.decompose(0, coordinate_bits::<K>(), [0; K], low, high, &mut result);
+        }
+
+        result
+    }
+
+    // collects the ranges of the box in the cube of side 2^level with corner `corner`, whose codes start at `start`
+    fn decompose(
+        &self,
+        start: u64,
+        level: u32,
+        corner: [u32; K],
+        low: &[u32; K],
+        high: &[u32; K],
+        result: &mut Vec<RangeInclusive<u64>>,
+    ) {
+        let side = 1u64 << level;
+        let mut inside = true;
+        for axis in 0..K {
+            let first = corner[axis] as u64;
+            let last = first + side - 1;
+            if first > high[axis] as u64 || last < low[axis] as u64 {
+                return;
+            }
+            if first < low[axis] as u64 || last > high[axis] as u64 {
+                inside = false;
+            }
+        }
+
+        let end = start + span::<K>(level);
+        if self.map.range(start..=end).next().is_none() {
+            return;
+        }
+        if inside {
+            match result.last_mut() {
+                Some(last) if *last.end() + 1 == start => *last = *last.start()..=end,
+                _ => result.push(start..=end),
+            }
+            return;
+        }
+
+        // children in increasing order of codes, bit `axis` of the child number is the next bit of coordinate `axis`
+        let level = level - 1;
+        for child in 0..1u64 << K {
+            let mut child_corner = corner;
+            for (axis, coordinate) in child_corner.iter_mut().enumerate() {
+                if child >> axis & 1 == 1 {
+                    *coordinate += 1 << level;
+                }
+            }
+            let child_start = start + (child << (level as usize * K));
+            self.decompose(child_start, level, child_corner, low, high, result);
+        }
+    }
+}
+
+// returns the number of codes in a cube of side 2^level, minus one
+fn span<const K: usize>(level: u32) -> u64 {
+    let bits = level as usize * K;
+    if bits >= 64 {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+impl<V, const K: usize> Default for ZOrderMap<V, K> {
+    fn default() -> Self {
+        ZOrderMap::init()
+    }
+}
+
+impl<V, const K: usize> MemoryFootprint for ZOrderMap<V, K> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<V>(self.map.len()).with_btree_map(&self.map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn spatial_z_order_range_matches_brute_force() {
+        let mut rng = SplitMix64::init(53);
+        let mut map = ZOrderMap::init();
+        let mut points = Vec::new();
+        for value in 0..400 {
+            let point = [rng.next_below(64) as u32, rng.next_below(64) as u32];
+            if map.insert(point, value).is_none() {
+                points.push(point);
+            }
+        }
+
+        for _ in 0..200 {
+            let low = [rng.next_below(70) as u32, rng.next_below(70) as u32];
+            let high = [rng.next_below(70) as u32, rng.next_below(70) as u32];
+            let found: Vec<[u32; 2]> = map.range(&low, &high).iter().map(|(p, _)| *p).collect();
+
+            let mut expected: Vec<[u32; 2]> = points
+                .iter()
+                .filter(|p| (0..2).all(|axis| low[axis] <= p[axis] && p[axis] <= high[axis]))
+                .copied()
+                .collect();
+            expected.sort_by_key(|point| morton_encode(*point));
+            assert_eq!(found, expected);
+
+            let covered: usize = map
+                .ranges(&low, &high)
+                .into_iter()
+                .map(|range| map.map.range(range).count())
+                .sum();
+            assert_eq!(covered, expected.len());
+        }
+    }
+
+    #[test]
+    fn spatial_z_order_three_dimensions_far_coordinates() {
+        let mut map = ZOrderMap::init();
+        let far = (1 << 21) - 1;
+        map.insert([far, far, far], 'a');
+        map.insert([0, far, 0], 'b');
+        map.insert([5, 5, 5], 'c');
+
+        let found: Vec<char> = map
+            .range(&[0, 0, 0], &[far, far, far])
+            .iter()
+            .map(|(_, v)| **v)
+            .collect();
+        assert_eq!(found, vec!['c', 'b', 'a']);
+        assert_eq!(map.range(&[1, 1, 1], &[far - 1, far, far]).len(), 1);
+        assert_eq!(map.iter().count(), 3);
+        assert!(map.range(&[3, 0, 0], &[2, 9, 9]).is_empty());
+    }
+}
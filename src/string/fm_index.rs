@@ -0,0 +1,343 @@
+use crate::sequence::RankSelect;
+use crate::util::{Footprint, MemoryFootprint};
+
+// bytes are shifted by one so that the sentinel can use symbol 0
+const SYMBOL_BITS: usize = 9;
+const ALPHABET: usize = 1 << SYMBOL_BITS;
+const DEFAULT_SAMPLE_RATE: usize = 32;
+
+// suffix array by prefix doubling, `text` must end with a unique smallest symbol
+pub(crate) fn suffix_array(text: &[u16]) -> Vec<usize> {
+    let n = text.len();
+    let mut suffixes: Vec<usize> = (0..n).collect();
+    let mut rank: Vec<usize> = text.iter().map(|symbol| *symbol as usize).collect();
+    let mut next = vec![0; n];
+    let mut k = 1;
+
+    loop {
+        let key = |i: usize| (rank[i], if i + k < n { rank[i + k] + 1 } else { 0 });
+        suffixes.sort_unstable_by_key(|i| key(*i));
+
+        next[suffixes[0]] = 0;
+        for w in 1..n {
+            let step = if key(suffixes[w - 1]) < key(suffixes[w]) {
+                1
+            } else {
+                0
+            };
+            next[suffixes[w]] = next[suffixes[w - 1]] + step;
+        }
+        std::mem::swap(&mut rank, &mut next);
+
+        if rank[suffixes[n - 1]] == n - 1 {
+            return suffixes;
+        }
+        k *= 2;
+    }
+}
+
+// Wavelet matrix over 9 bit symbols, answers rank and access in O(number of bits of a symbol)
+struct WaveletMatrix {
+    levels: Vec<RankSelect>,
+    zeros: Vec<usize>,
+}
+
+impl WaveletMatrix {
+    fn init(symbols: &[u16]) -> WaveletMatrix {
+        let mut levels = Vec::with_capacity(SYMBOL_BITS);
+        let mut zeros = Vec::with_capacity(SYMBOL_BITS);
+        let mut current = symbols.to_vec();
+
+        for level in 0..SYMBOL_BITS {
+            let shift = SYMBOL_BITS - 1 - level;
+            let bits = RankSelect::from_bits(current.iter().map(|symbol| symbol >> shift & 1 == 1));
+            zeros.push(bits.count_zeros());
+            levels.push(bits);
+
+            // stable partition: symbols with a 0 bit first
+            let (mut low, high): (Vec<u16>, Vec<u16>) =
+                current.iter().partition(|symbol| *symbol >> shift & 1 == 0);
+            low.extend(high);
+            current = low;
+        }
+
+        WaveletMatrix { levels, zeros }
+    }
+
+    // number of occurrences of `symbol` in [0, index)
+    fn rank(&self, symbol: u16, index: usize) -> usize {
+        let mut start = 0;
+        let mut end = index;
+        for (level, bits) in self.levels.iter().enumerate() {
+            if symbol >> (SYMBOL_BITS - 1 - level) & 1 == 1 {
+                start = self.zeros[level] + bits.rank1(start);
+                end = self.zeros[level] + bits.rank1(end);
+            } else {
+                start = bits.rank0(start);
+                end = bits.rank0(end);
+            }
+        }
+
+        end - start
+    }
+
+    fn access(&self, mut index: usize) -> u16 {
+        let mut symbol = 0;
+        for (level, bits) in self.levels.iter().enumerate() {
+            symbol <<= 1;
+            if bits.get(index) {
+                symbol |= 1;
+                index = self.zeros[level] + bits.rank1(index);
+            } else {
+                index = bits.rank0(index);
+            }
+        }
+
+        symbol
+    }
+}
+
+/// An FM-index is a compressed full-text index of a byte string. It stores the Burrows-Wheeler transform of the text in
+/// a wavelet matrix built on rank/select bit vectors, and a sample of the suffix array. Occurrences of a pattern are
+/// counted by backward search in O(length of the pattern) rank queries without touching the text,
+/// and each occurrence is located with at most `sample_rate` additional steps.
+///
+/// # Examples
+/// ```
+/// use rudac::string::FmIndex;
+///
+/// let index = FmIndex::init(b"abracadabra");
+///
+/// assert_eq!(index.count(b"abra"), 2);
+/// assert_eq!(index.locate(b"abra"), vec![0, 7]);
+/// assert_eq!(index.locate(b"a"), vec![0, 3, 5, 7, 10]);
+/// assert!(!index.contains(b"abc"));
+/// ```
+pub struct FmIndex {
+    bwt: WaveletMatrix,
+    counts: Vec<usize>,
+    sampled: RankSelect,
+    samples: Vec<usize>,
+    sample_rate: usize,
+    len: usize,
+}
+
+impl FmIndex {
+    /// Builds the index of `text`, sampling every 32nd position of the suffix array
+    /// * Complexity: O(n log^2 n) construction
+    ///
+    /// # Arguments
+    /// * `text`: the indexed text
+    pub fn init(text: &[u8]) -> FmIndex {
+        FmIndex::with_sample_rate(text, DEFAULT_SAMPLE_RATE)
+    }
+
+    /// Builds the index of `text`, keeping the suffix array entries of text positions that are multiples of `sample_rate`.
+    /// Larger rates use less memory and make `locate` slower
+    /// * Complexity: O(n log^2 n) construction
+    ///
+    /// # Arguments
+    /// * `text`: the indexed text
+    /// * `sample_rate`: distance between sampled text positions
+    ///
+    /// # Panics
+    /// * panics if `sample_rate` is zero
+    pub fn with_sample_rate(text: &[u8], sample_rate: usize) -> FmIndex {
+        if sample_rate == 0 {
+            panic!("Sample rate must be greater than zero");
+        }
+
+        let mut symbols: Vec<u16> = text.iter().map(|byte| *byte as u16 + 1).collect();
+        symbols.push(0);
+        let suffixes = suffix_array(&symbols);
+
+        let bwt: Vec<u16> = suffixes
+            .iter()
+            .map(|suffix| symbols[(suffix + symbols.len() - 1) % symbols.len()])
+            .collect();
+
+        let mut counts = vec![0; ALPHABET + 1];
+        for symbol in symbols.iter() {
+            counts[*symbol as usize + 1] += 1;
+        }
+        for symbol in 1..counts.len() {
+            counts[symbol] += counts[symbol - 1];
+        }
+
+        let sampled =
+            RankSelect::from_bits(suffixes.iter().map(|suffix| suffix % sample_rate == 0));
+        let samples = suffixes
+            .iter()
+            .filter(|suffix| *suffix % sample_rate == 0)
+            .copied()
+            .collect();
+
+        FmIndex {
+            bwt: WaveletMatrix::init(&bwt),
+            counts,
+            sampled,
+            samples,
+            sample_rate,
+            len: text.len(),
+        }
+    }
+
+    /// Returns length of the indexed text
+    pub fn size(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the indexed text is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns distance between sampled text positions
+    pub fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    /// Returns number of occurrences of `pattern` in the text. Occurrences may overlap.
+    /// The empty pattern occurs at every position including the end of the text
+    /// * Complexity: O(m)
+    ///
+    /// # Arguments
+    /// * `pattern`: the searched pattern
+    pub fn count(&self, pattern: &[u8]) -> usize {
+        let (start, end) = self.backward_search(pattern);
+        end - start
+    }
+
+    /// Returns true if `pattern` occurs in the text
+    /// * Complexity: O(m)
+    ///
+    /// # Arguments
+    /// * `pattern`: the searched pattern
+    pub fn contains(&self, pattern: &[u8]) -> bool {
+        self.count(pattern) > 0
+    }
+
+    /// Returns starting positions of all occurrences of `pattern` in increasing order
+    /// * Complexity: O(m + occ * sample_rate + occ log occ)
+    ///
+    /// # Arguments
+    /// * `pattern`: the searched pattern
+    pub fn locate(&self, pattern: &[u8]) -> Vec<usize> {
+        let (start, end) = self.backward_search(pattern);
+        let mut positions: Vec<usize> = (start..end).map(|row| self.position(row)).collect();
+        positions.sort_unstable();
+
+        positions
+    }
+
+    // range of suffix array rows whose suffixes start with `pattern`
+    fn backward_search(&self, pattern: &[u8]) -> (usize, usize) {
+        let mut start = 0;
+        let mut end = self.len + 1;
+        for byte in pattern.iter().rev() {
+            let symbol = *byte as u16 + 1;
+            start = self.counts[symbol as usize] + self.bwt.rank(symbol, start);
+            end = self.counts[symbol as usize] + self.bwt.rank(symbol, end);
+            if start >= end {
+                return (0, 0);
+            }
+        }
+
+        (start, end)
+    }
+
+    // text position of the suffix at `row`, found by walking back to a sampled row
+    fn position(&self, mut row: usize) -> usize {
+        let mut steps = 0;
+        while !self.sampled.get(row) {
+            let symbol = self.bwt.access(row);
+            row = self.counts[symbol as usize] + self.bwt.rank(symbol, row);
+            steps += 1;
+        }
+
+        self.samples[self.sampled.rank1(row)] + steps
+    }
+}
+
+impl MemoryFootprint for FmIndex {
+    fn footprint(&self) -> Footprint {
+        // the text is a byte per element, the index may end up smaller than it
+        let footprint = Footprint::of::<u8>(self.len)
+            .with_vec(&self.bwt.levels)
+            .with_vec(&self.bwt.zeros)
+            .with_vec(&self.counts)
+            .with(self.sampled.footprint())
+            .with_vec(&self.samples);
+        self.bwt.levels.iter().fold(footprint, |footprint, level| {
+            footprint.with(level.footprint())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn naive_locate(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+        (0..=text.len())
+            .filter(|start| text[*start..].starts_with(pattern))
+            .collect()
+    }
+
+    #[test]
+    fn string_fm_index_matches_naive() {
+        let mut rng = SplitMix64::init(5);
+        for &(len, alphabet) in [(0, 2), (1, 2), (50, 2), (300, 3), (500, 256)].iter() {
+            let text: Vec<u8> = (0..len)
+                .map(|_| (rng.next_below(alphabet) as u8).wrapping_sub(1))
+                .collect();
+
+            for &rate in [1, 3, 32].iter() {
+                let index = FmIndex::with_sample_rate(&text, rate);
+                assert_eq!(index.size(), len);
+
+                for _ in 0..40 {
+                    let pattern: Vec<u8> = if len > 0 && rng.next_below(2) == 0 {
+                        let start = rng.next_below(len as u64) as usize;
+                        let end = start + rng.next_below((len - start) as u64 + 1) as usize;
+                        text[start..end.min(start + 6)].to_vec()
+                    } else {
+                        (0..rng.next_below(4))
+                            .map(|_| (rng.next_below(alphabet) as u8).wrapping_sub(1))
+                            .collect()
+                    };
+
+                    let expected = naive_locate(&text, &pattern);
+                    assert_eq!(index.count(&pattern), expected.len());
+                    assert_eq!(index.locate(&pattern), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn string_fm_index_empty_pattern() {
+        let index = FmIndex::init(b"mississippi");
+        assert_eq!(index.count(b""), 12);
+        assert_eq!(index.locate(b"ssi"), vec![2, 5]);
+        assert_eq!(index.locate(b"issi"), vec![1, 4]);
+        assert_eq!(index.count(b"mississippis"), 0);
+    }
+
+    #[test]
+    fn string_fm_index_suffix_array() {
+        let symbols: Vec<u16> = b"banana"
+            .iter()
+            .map(|b| *b as u16 + 1)
+            .chain(Some(0))
+            .collect();
+        assert_eq!(suffix_array(&symbols), vec![6, 5, 3, 1, 0, 4, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Sample rate must be greater than zero")]
+    fn string_fm_index_zero_sample_rate() {
+        FmIndex::with_sample_rate(b"text", 0);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::HashMap;
+
+/// A deterministic automaton accepting the strings within edit distance k of a word, where an edit inserts, removes
+/// or replaces a character. Its states are the rows of the edit distance table between the word and the input read
+/// so far, with distances above k cut to k + 1, and rows that share these values share a state. Every state is built
+/// up front, so matching a candidate takes one transition per character whatever the length of the word. Walking
+/// the automaton along the paths of a trie finds every key within distance k while skipping subtrees that cannot
+/// match, see `Trie::search_automaton`
+///
+/// # Examples
+/// ```
+/// use rudac::string::LevenshteinAutomaton;
+///
+/// let automaton = LevenshteinAutomaton::init("kitten", 2);
+///
+/// assert!(automaton.matches("sitten"));
+/// assert_eq!(automaton.distance("sittin"), Some(2));
+/// assert!(!automaton.matches("sitting"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LevenshteinAutomaton {
+    word: Vec<char>,
+    max_distance: usize,
+
+    // transitions on the characters of the word, and on any other character
+    transitions: Vec<HashMap<char, usize>>,
+    otherwise: Vec<Option<usize>>,
+
+    // distance between the word and the input of every state, if it is at most k
+    accepting: Vec<Option<usize>>,
+}
+
+impl LevenshteinAutomaton {
+    /// Builds the automaton of the strings within distance `max_distance` of `word`
+    ///
+    /// # Arguments
+    /// * `word`: the word
+    /// * `max_distance`: largest accepted edit distance k
+    pub fn init(word: &str, max_distance: usize) -> LevenshteinAutomaton {
+        let word: Vec<char> = word.chars().collect();
+        let limit = max_distance + 1;
+        let mut alphabet = word.clone();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        // next row of the table after reading `c`, `None` for a character outside the word
+        let step = |row: &[usize], c: Option<char>| -> Option<Vec<usize>> {
+            let mut next = Vec::with_capacity(row.len());
+            next.push(usize::min(row[0] + 1, limit));
+            for j in 1..row.len() {
+                let substitution = row[j - 1] + usize::from(c != Some(word[j - 1]));
+                next.push(substitution.min(row[j] + 1).min(next[j - 1] + 1).min(limit));
+            }
+
+            // a row without a distance of at most k can never lead to a match
+            next.iter()
+                .any(|distance| *distance < limit)
+                .then_some(next)
+        };
+
+        let start: Vec<usize> = (0..=word.len()).map(|j| j.min(limit)).collect();
+        let mut states = HashMap::new();
+        let mut rows = vec![start.clone()];
+        states.insert(start, 0);
+        let (mut transitions, mut otherwise, mut accepting) = (Vec::new(), Vec::new(), Vec::new());
+        let mut state_of = |row: Vec<usize>, rows: &mut Vec<Vec<usize>>| -> usize {
+            *states.entry(row).or_insert_with_key(|row| {
+                rows.push(row.clone());
+                rows.len() - 1
+            })
+        };
+
+        let mut state = 0;
+        while state < rows.len() {
+            let row = rows[state].clone();
+            let mut edges = HashMap::new();
+            for c in &alphabet {
+                if let Some(next) = step(&row, Some(*c)) {
+                    edges.insert(*c, state_of(next, &mut rows));
+                }
+            }
+            transitions.push(edges);
+            otherwise.push(step(&row, None).map(|next| state_of(next, &mut rows)));
+            accepting.push(Some(row[word.len()]).filter(|distance| *distance <= max_distance));
+            state += 1;
+        }
+
+        LevenshteinAutomaton {
+            word,
+            max_distance,
+            transitions,
+            otherwise,
+            accepting,
+        }
+    }
+
+    /// Returns the word of the automaton
+    pub fn word(&self) -> String {
+        self.word.iter().collect()
+    }
+
+    /// Returns the largest accepted edit distance
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// Returns number of states of the automaton
+    pub fn state_count(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// Returns the state before any input is read
+    pub fn start(&self) -> usize {
+        0
+    }
+
+    /// Returns the state after reading `c` in `state`, `None` if no string with the input read so far as a prefix is
+    /// within the distance
+    ///
+    /// # Arguments
+    /// * `state`: the current state
+    /// * `c`: the next character
+    ///
+    /// # Panics
+    /// * panics if `state` does not exist
+    pub fn step(&self, state: usize, c: char) -> Option<usize> {
+        match self.transitions[state].get(&c) {
+            Some(next) => Some(*next),
+            None => self.otherwise[state],
+        }
+    }
+
+    /// Returns the distance between the word and the input that led to `state`, `None` if it is larger than k
+    ///
+    /// # Arguments
+    /// * `state`: the state
+    ///
+    /// # Panics
+    /// * panics if `state` does not exist
+    pub fn accepts(&self, state: usize) -> Option<usize> {
+        self.accepting[state]
+    }
+
+    /// Returns the edit distance between the word and `candidate`, `None` if it is larger than k
+    /// * Complexity: O(length of the candidate)
+    ///
+    /// # Arguments
+    /// * `candidate`: the string to be compared with the word
+    pub fn distance(&self, candidate: &str) -> Option<usize> {
+        let state = candidate
+            .chars()
+            .try_fold(self.start(), |state, c| self.step(state, c))?;
+
+        self.accepts(state)
+    }
+
+    /// Returns true if `candidate` is within edit distance k of the word
+    ///
+    /// # Arguments
+    /// * `candidate`: the string to be compared with the word
+    pub fn matches(&self, candidate: &str) -> bool {
+        self.distance(candidate).is_some()
+    }
+}
+
+impl MemoryFootprint for LevenshteinAutomaton {
+    fn footprint(&self) -> Footprint {
+        self.transitions.iter().fold(
+            Footprint::of::<char>(self.word.len())
+                .with_vec(&self.word)
+                .with_vec(&self.transitions)
+                .with_vec(&self.otherwise)
+                .with_vec(&self.accepting),
+            |footprint, edges| footprint.with_hash_map(edges),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn levenshtein(a: &[char], b: &[char]) -> usize {
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+        for i in 1..=a.len() {
+            let mut current = vec![i; b.len() + 1];
+            for j in 1..=b.len() {
+                let cost = usize::from(a[i - 1] != b[j - 1]);
+                current[j] = (previous[j - 1] + cost)
+                    .min(previous[j] + 1)
+                    .min(current[j - 1] + 1);
+            }
+            previous = current;
+        }
+
+        previous[b.len()]
+    }
+
+    #[test]
+    fn string_levenshtein_matches_table() {
+        let mut rng = SplitMix64::init(100);
+        let letters = ['a', 'b', 'c', 'x'];
+        let mut random_word = |length: u64| -> Vec<char> {
+            (0..rng.next_below(length))
+                .map(|_| letters[rng.next_below(4) as usize])
+                .collect()
+        };
+        for _ in 0..30 {
+            let word = random_word(8);
+            for max_distance in 0..4 {
+                let automaton =
+                    LevenshteinAutomaton::init(&word.iter().collect::<String>(), max_distance);
+                for _ in 0..100 {
+                    let candidate = random_word(10);
+                    let distance = levenshtein(&word, &candidate);
+                    assert_eq!(
+                        automaton.distance(&candidate.iter().collect::<String>()),
+                        Some(distance).filter(|distance| *distance <= max_distance)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn string_levenshtein_states_are_shared() {
+        // the rows of a long word far from the input all collapse into few states
+        let automaton = LevenshteinAutomaton::init(&"ab".repeat(50), 1);
+
+        assert!(automaton.state_count() < 1000);
+        assert_eq!(automaton.distance(&"ab".repeat(50)), Some(0));
+        assert_eq!(
+            automaton.distance(&format!("{}c", "ab".repeat(50))),
+            Some(1)
+        );
+        assert_eq!(
+            automaton.step(automaton.start(), 'z'),
+            Some(automaton.otherwise[0].unwrap())
+        );
+        assert!(LevenshteinAutomaton::init("", 0).matches(""));
+    }
+}
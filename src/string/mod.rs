@@ -0,0 +1,30 @@
+mod fm_index;
+mod levenshtein;
+#[cfg(feature = "unicode")]
+mod segmentation;
+#[cfg(feature = "unicode")]
+mod ucd;
+
+pub(crate) use fm_index::suffix_array;
+pub use fm_index::FmIndex;
+pub use levenshtein::LevenshteinAutomaton;
+#[cfg(feature = "unicode")]
+pub use segmentation::graphemes;
+#[cfg(feature = "unicode")]
+pub use segmentation::is_grapheme_boundary;
+#[cfg(feature = "unicode")]
+pub use segmentation::is_word_boundary;
+#[cfg(feature = "unicode")]
+pub use segmentation::next_grapheme_boundary;
+#[cfg(feature = "unicode")]
+pub use segmentation::next_word_boundary;
+#[cfg(feature = "unicode")]
+pub use segmentation::previous_grapheme_boundary;
+#[cfg(feature = "unicode")]
+pub use segmentation::previous_word_boundary;
+#[cfg(feature = "unicode")]
+pub use segmentation::words;
+#[cfg(feature = "unicode")]
+pub use segmentation::Graphemes;
+#[cfg(feature = "unicode")]
+pub use segmentation::Words;
@@ -0,0 +1,557 @@
+use super::ucd::{grapheme_break, is_extended_pictographic, word_break, GraphemeBreak, WordBreak};
+
+// the character ending at `index`
+fn char_before(text: &str, index: usize) -> char {
+    text[..index].chars().next_back().unwrap()
+}
+
+// the character starting at `index`
+fn char_at(text: &str, index: usize) -> char {
+    text[index..].chars().next().unwrap()
+}
+
+fn check_index(text: &str, index: usize) {
+    if !text.is_char_boundary(index) {
+        panic!("Index must be at a character boundary of the text");
+    }
+}
+
+// the smallest boundary after `index`, `None` at the end of the text
+fn next_boundary(text: &str, index: usize, is_boundary: fn(&str, usize) -> bool) -> Option<usize> {
+    check_index(text, index);
+
+    text[index..]
+        .char_indices()
+        .skip(1)
+        .map(|(offset, _)| index + offset)
+        .chain(std::iter::once(text.len()))
+        .find(|candidate| *candidate > index && is_boundary(text, *candidate))
+}
+
+// the largest boundary before `index`, `None` at the start of the text
+fn previous_boundary(
+    text: &str,
+    index: usize,
+    is_boundary: fn(&str, usize) -> bool,
+) -> Option<usize> {
+    check_index(text, index);
+
+    text[..index]
+        .char_indices()
+        .rev()
+        .map(|(offset, _)| offset)
+        .find(|candidate| is_boundary(text, *candidate))
+}
+
+/// Returns true if a grapheme cluster, a user-perceived character, starts or ends at byte `index` of `text`.
+/// Follows the extended grapheme cluster rules of UAX #29 over a small table of character properties,
+/// enabled by the `unicode` feature. The boundary is decided by the characters around it, so cursors can move over
+/// large texts without scanning them from the start
+///
+/// # Arguments
+/// * `text`: the text
+/// * `index`: byte index in the text
+///
+/// # Panics
+/// * panics if `index` is not at a character boundary of `text`
+///
+/// # Examples
+/// ```
+/// use rudac::string::is_grapheme_boundary;
+///
+/// // 'e' followed by a combining acute accent
+/// let text = "cafe\u{301}!";
+///
+/// assert!(is_grapheme_boundary(text, 3));
+/// assert!(!is_grapheme_boundary(text, 4));
+/// assert!(is_grapheme_boundary(text, 6));
+/// ```
+pub fn is_grapheme_boundary(text: &str, index: usize) -> bool {
+    check_index(text, index);
+    if index == 0 || index == text.len() {
+        return true;
+    }
+
+    let (before, after) = (char_before(text, index), char_at(text, index));
+    match (grapheme_break(before), grapheme_break(after)) {
+        (GraphemeBreak::CR, GraphemeBreak::LF) => false,
+        (GraphemeBreak::CR | GraphemeBreak::LF | GraphemeBreak::Control, _) => true,
+        (_, GraphemeBreak::CR | GraphemeBreak::LF | GraphemeBreak::Control) => true,
+        // Hangul syllables
+        (
+            GraphemeBreak::L,
+            GraphemeBreak::L | GraphemeBreak::V | GraphemeBreak::Lv | GraphemeBreak::Lvt,
+        ) => false,
+        (GraphemeBreak::Lv | GraphemeBreak::V, GraphemeBreak::V | GraphemeBreak::T) => false,
+        (GraphemeBreak::Lvt | GraphemeBreak::T, GraphemeBreak::T) => false,
+        (_, GraphemeBreak::Extend | GraphemeBreak::Zwj | GraphemeBreak::SpacingMark) => false,
+        (GraphemeBreak::Prepend, _) => false,
+        // emoji joined by a zero width joiner
+        (GraphemeBreak::Zwj, _) if is_extended_pictographic(after) => !text
+            [..index - before.len_utf8()]
+            .chars()
+            .rev()
+            .find(|c| grapheme_break(*c) != GraphemeBreak::Extend)
+            .is_some_and(is_extended_pictographic),
+        // flags are pairs of regional indicators
+        (GraphemeBreak::RegionalIndicator, GraphemeBreak::RegionalIndicator) => {
+            let run = text[..index]
+                .chars()
+                .rev()
+                .take_while(|c| grapheme_break(*c) == GraphemeBreak::RegionalIndicator)
+                .count();
+            run % 2 == 0
+        }
+        _ => true,
+    }
+}
+
+/// Returns the byte index of the first grapheme cluster boundary after `index`, `None` if `index` is the end of
+/// `text`. Moves a cursor one user-perceived character forward
+///
+/// # Arguments
+/// * `text`: the text
+/// * `index`: byte index in the text
+///
+/// # Panics
+/// * panics if `index` is not at a character boundary of `text`
+///
+/// # Examples
+/// ```
+/// use rudac::string::next_grapheme_boundary;
+///
+/// // a flag is made of two regional indicators
+/// let text = "\u{1F1EB}\u{1F1F7}x";
+///
+/// assert_eq!(next_grapheme_boundary(text, 0), Some(8));
+/// assert_eq!(next_grapheme_boundary(text, 8), Some(9));
+/// assert_eq!(next_grapheme_boundary(text, 9), None);
+/// ```
+pub fn next_grapheme_boundary(text: &str, index: usize) -> Option<usize> {
+    next_boundary(text, index, is_grapheme_boundary)
+}
+
+/// Returns the byte index of the last grapheme cluster boundary before `index`, `None` if `index` is the start of
+/// `text`. Moves a cursor one user-perceived character backward
+///
+/// # Arguments
+/// * `text`: the text
+/// * `index`: byte index in the text
+///
+/// # Panics
+/// * panics if `index` is not at a character boundary of `text`
+///
+/// # Examples
+/// ```
+/// use rudac::string::previous_grapheme_boundary;
+///
+/// let text = "a\r\nb";
+///
+/// assert_eq!(previous_grapheme_boundary(text, 4), Some(3));
+/// assert_eq!(previous_grapheme_boundary(text, 3), Some(1));
+/// assert_eq!(previous_grapheme_boundary(text, 0), None);
+/// ```
+pub fn previous_grapheme_boundary(text: &str, index: usize) -> Option<usize> {
+    previous_boundary(text, index, is_grapheme_boundary)
+}
+
+/// Iterator over the grapheme clusters of a text, from either end. Created by `graphemes`
+///
+/// # Examples
+/// ```
+/// use rudac::string::graphemes;
+///
+/// let text = "ne\u{301}e \u{1F469}\u{200D}\u{1F52C}";
+///
+/// let clusters: Vec<&str> = graphemes(text).collect();
+///
+/// assert_eq!(clusters, vec!["n", "e\u{301}", "e", " ", "\u{1F469}\u{200D}\u{1F52C}"]);
+/// assert_eq!(graphemes(text).next_back(), Some("\u{1F469}\u{200D}\u{1F52C}"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Graphemes<'a> {
+    text: &'a str,
+    front: usize,
+    back: usize,
+}
+
+/// Returns an iterator over the grapheme clusters of `text`
+///
+/// # Arguments
+/// * `text`: the text
+pub fn graphemes(text: &str) -> Graphemes<'_> {
+    Graphemes {
+        text,
+        front: 0,
+        back: text.len(),
+    }
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let start = self.front;
+        self.front = next_grapheme_boundary(self.text, start).unwrap();
+        Some(&self.text[start..self.front])
+    }
+}
+
+impl<'a> DoubleEndedIterator for Graphemes<'a> {
+    fn next_back(&mut self) -> Option<&'a str> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let end = self.back;
+        self.back = previous_grapheme_boundary(self.text, end).unwrap();
+        Some(&self.text[self.back..end])
+    }
+}
+
+// extend, format and zero width joiner characters attach to the character before them
+fn is_ignored(property: WordBreak) -> bool {
+    matches!(
+        property,
+        WordBreak::Extend | WordBreak::Format | WordBreak::Zwj
+    )
+}
+
+// properties of the characters before `index`, skipping ignored ones, nearest first
+fn word_breaks_before(text: &str, index: usize) -> impl Iterator<Item = WordBreak> + '_ {
+    text[..index]
+        .chars()
+        .rev()
+        .map(word_break)
+        .filter(|property| !is_ignored(*property))
+}
+
+fn is_letter(property: Option<WordBreak>) -> bool {
+    matches!(property, Some(WordBreak::ALetter | WordBreak::HebrewLetter))
+}
+
+fn is_mid_letter(property: Option<WordBreak>) -> bool {
+    matches!(
+        property,
+        Some(WordBreak::MidLetter | WordBreak::MidNumLet | WordBreak::SingleQuote)
+    )
+}
+
+fn is_mid_num(property: Option<WordBreak>) -> bool {
+    matches!(
+        property,
+        Some(WordBreak::MidNum | WordBreak::MidNumLet | WordBreak::SingleQuote)
+    )
+}
+
+/// Returns true if a word boundary is at byte `index` of `text`. Follows the default word boundary rules of UAX #29
+/// over a small table of character properties, enabled by the `unicode` feature: letters, digits and the
+/// punctuation inside words like "can't" and "3.14" stay together, and every other character is a segment of its own.
+/// Ideographs and scripts written without spaces are split per character
+///
+/// # Arguments
+/// * `text`: the text
+/// * `index`: byte index in the text
+///
+/// # Panics
+/// * panics if `index` is not at a character boundary of `text`
+///
+/// # Examples
+/// ```
+/// use rudac::string::is_word_boundary;
+///
+/// let text = "can't stop";
+///
+/// assert!(!is_word_boundary(text, 3));
+/// assert!(is_word_boundary(text, 5));
+/// assert!(is_word_boundary(text, 6));
+/// ```
+pub fn is_word_boundary(text: &str, index: usize) -> bool {
+    check_index(text, index);
+    if index == 0 || index == text.len() {
+        return true;
+    }
+
+    let (before, after) = (char_before(text, index), char_at(text, index));
+    let (raw_before, current) = (word_break(before), word_break(after));
+    match (raw_before, current) {
+        (WordBreak::CR, WordBreak::LF) => return false,
+        (WordBreak::CR | WordBreak::LF | WordBreak::Newline, _) => return true,
+        (_, WordBreak::CR | WordBreak::LF | WordBreak::Newline) => return true,
+        (WordBreak::Zwj, _) if is_extended_pictographic(after) => return false,
+        (WordBreak::WSegSpace, WordBreak::WSegSpace) => return false,
+        (_, current) if is_ignored(current) => return false,
+        _ => {}
+    }
+
+    // ignored characters take the property of the character they attach to
+    let mut preceding = word_breaks_before(text, index);
+    let previous = Some(preceding.next().unwrap_or(raw_before));
+    let second_previous = preceding.next();
+    let next = text[index + after.len_utf8()..]
+        .chars()
+        .map(word_break)
+        .find(|property| !is_ignored(*property));
+    let current = Some(current);
+
+    let numeric = Some(WordBreak::Numeric);
+    let hebrew = Some(WordBreak::HebrewLetter);
+    let double_quote = Some(WordBreak::DoubleQuote);
+    let katakana = Some(WordBreak::Katakana);
+    let extend_num_let = Some(WordBreak::ExtendNumLet);
+    let joins = (is_letter(previous) && is_letter(current))
+        || (is_letter(previous) && is_mid_letter(current) && is_letter(next))
+        || (is_letter(second_previous) && is_mid_letter(previous) && is_letter(current))
+        || (previous == hebrew && current == Some(WordBreak::SingleQuote))
+        || (previous == hebrew && current == double_quote && next == hebrew)
+        || (second_previous == hebrew && previous == double_quote && current == hebrew)
+        || ((previous == numeric || is_letter(previous))
+            && (current == numeric || is_letter(current)))
+        || (second_previous == numeric && is_mid_num(previous) && current == numeric)
+        || (previous == numeric && is_mid_num(current) && next == numeric)
+        || (previous == katakana && current == katakana)
+        || ((is_letter(previous)
+            || previous == numeric
+            || previous == katakana
+            || previous == extend_num_let)
+            && current == extend_num_let)
+        || (previous == extend_num_let
+            && (is_letter(current) || current == numeric || current == katakana));
+    if joins {
+        return false;
+    }
+
+    // flags are pairs of regional indicators
+    if previous == Some(WordBreak::RegionalIndicator)
+        && current == Some(WordBreak::RegionalIndicator)
+    {
+        let run = word_breaks_before(text, index)
+            .take_while(|property| *property == WordBreak::RegionalIndicator)
+            .count();
+        return run % 2 == 0;
+    }
+
+    true
+}
+
+/// Returns the byte index of the first word boundary after `index`, `None` if `index` is the end of `text`
+///
+/// # Arguments
+/// * `text`: the text
+/// * `index`: byte index in the text
+///
+/// # Panics
+/// * panics if `index` is not at a character boundary of `text`
+pub fn next_word_boundary(text: &str, index: usize) -> Option<usize> {
+    next_boundary(text, index, is_word_boundary)
+}
+
+/// Returns the byte index of the last word boundary before `index`, `None` if `index` is the start of `text`
+///
+/// # Arguments
+/// * `text`: the text
+/// * `index`: byte index in the text
+///
+/// # Panics
+/// * panics if `index` is not at a character boundary of `text`
+pub fn previous_word_boundary(text: &str, index: usize) -> Option<usize> {
+    previous_boundary(text, index, is_word_boundary)
+}
+
+/// Iterator over the segments between word boundaries of a text, from either end. Created by `words`.
+/// Spaces and punctuation are segments too, keep the segments with an alphanumeric character to get the words only
+///
+/// # Examples
+/// ```
+/// use rudac::string::words;
+///
+/// let text = "The quick (\u{201C}brown\u{201D}) fox can\u{2019}t jump 32.3 feet, right?";
+///
+/// let segments: Vec<&str> = words(text)
+///     .filter(|segment| segment.chars().any(char::is_alphanumeric))
+///     .collect();
+///
+/// assert_eq!(
+///     segments,
+///     vec!["The", "quick", "brown", "fox", "can\u{2019}t", "jump", "32.3", "feet", "right"]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Words<'a> {
+    text: &'a str,
+    front: usize,
+    back: usize,
+}
+
+/// Returns an iterator over the segments between word boundaries of `text`
+///
+/// # Arguments
+/// * `text`: the text
+pub fn words(text: &str) -> Words<'_> {
+    Words {
+        text,
+        front: 0,
+        back: text.len(),
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let start = self.front;
+        self.front = next_word_boundary(self.text, start).unwrap();
+        Some(&self.text[start..self.front])
+    }
+}
+
+impl<'a> DoubleEndedIterator for Words<'a> {
+    fn next_back(&mut self) -> Option<&'a str> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let end = self.back;
+        self.back = previous_word_boundary(self.text, end).unwrap();
+        Some(&self.text[self.back..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn string_segmentation_graphemes() {
+        let cases: Vec<(&str, Vec<&str>)> = vec![
+            ("", vec![]),
+            ("a\r\n\nb", vec!["a", "\r\n", "\n", "b"]),
+            ("e\u{301}\u{302}x", vec!["e\u{301}\u{302}", "x"]),
+            // a family, a waving hand with skin tone and two flags
+            (
+                "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{1F44B}\u{1F3FD}\u{1F1FA}\u{1F1F8}\u{1F1EB}\u{1F1F7}",
+                vec![
+                    "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}",
+                    "\u{1F44B}\u{1F3FD}",
+                    "\u{1F1FA}\u{1F1F8}",
+                    "\u{1F1EB}\u{1F1F7}",
+                ],
+            ),
+            // an odd regional indicator stands alone
+            (
+                "\u{1F1FA}\u{1F1F8}\u{1F1EB}",
+                vec!["\u{1F1FA}\u{1F1F8}", "\u{1F1EB}"],
+            ),
+            // a joiner only joins pictographs
+            ("a\u{200D}\u{1F467}", vec!["a\u{200D}", "\u{1F467}"]),
+            // conjoining jamo and precomposed syllables
+            (
+                "\u{1100}\u{1161}\u{11A8}\u{D55C}\u{11AB}",
+                vec!["\u{1100}\u{1161}\u{11A8}", "\u{D55C}\u{11AB}"],
+            ),
+            ("\u{0600}1", vec!["\u{0600}1"]),
+            ("\u{0915}\u{093F}", vec!["\u{0915}\u{093F}"]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(graphemes(text).collect::<Vec<&str>>(), expected);
+            let mut reversed: Vec<&str> = graphemes(text).rev().collect();
+            reversed.reverse();
+            assert_eq!(reversed, expected);
+        }
+    }
+
+    #[test]
+    fn string_segmentation_words() {
+        let cases: Vec<(&str, Vec<&str>)> = vec![
+            ("Hello, world!", vec!["Hello", ",", " ", "world", "!"]),
+            ("a  b\r\nc", vec!["a", "  ", "b", "\r\n", "c"]),
+            (
+                "e.g. 3,141.59 x_1 a:b",
+                vec!["e.g", ".", " ", "3,141.59", " ", "x_1", " ", "a:b"],
+            ),
+            ("na\u{ef}ve\u{301}s", vec!["na\u{ef}ve\u{301}s"]),
+            ("\u{4E2D}\u{6587}", vec!["\u{4E2D}", "\u{6587}"]),
+            (
+                "\u{30AB}\u{30BF}\u{30AB}\u{30CA}",
+                vec!["\u{30AB}\u{30BF}\u{30AB}\u{30CA}"],
+            ),
+            ("\u{05D0}\"\u{05D1}", vec!["\u{05D0}\"\u{05D1}"]),
+            ("a'", vec!["a", "'"]),
+            (
+                "\u{1F1FA}\u{1F1F8}\u{1F1EB}\u{1F1F7}",
+                vec!["\u{1F1FA}\u{1F1F8}", "\u{1F1EB}\u{1F1F7}"],
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(words(text).collect::<Vec<&str>>(), expected);
+            let mut reversed: Vec<&str> = words(text).rev().collect();
+            reversed.reverse();
+            assert_eq!(reversed, expected);
+        }
+    }
+
+    #[test]
+    fn string_segmentation_random_texts() {
+        let mut rng = SplitMix64::init(102);
+        let pool = [
+            'a',
+            'Z',
+            '1',
+            ' ',
+            '.',
+            ',',
+            '\'',
+            '\r',
+            '\n',
+            '_',
+            '\u{301}',
+            '\u{200D}',
+            '\u{1F467}',
+            '\u{1F1EB}',
+            '\u{1100}',
+            '\u{1161}',
+            '\u{AC00}',
+            '\u{4E2D}',
+            '\u{30AB}',
+        ];
+        for _ in 0..500 {
+            let text: String = (0..rng.next_below(12))
+                .map(|_| pool[rng.next_below(pool.len() as u64) as usize])
+                .collect();
+
+            let forward: Vec<&str> = graphemes(&text).collect();
+            let mut backward: Vec<&str> = graphemes(&text).rev().collect();
+            backward.reverse();
+            assert_eq!(forward.concat(), text);
+            assert_eq!(forward, backward);
+
+            let forward: Vec<&str> = words(&text).collect();
+            let mut backward: Vec<&str> = words(&text).rev().collect();
+            backward.reverse();
+            assert_eq!(forward.concat(), text);
+            assert_eq!(forward, backward);
+
+            // word boundaries never split a grapheme cluster
+            for (index, _) in text.char_indices() {
+                if is_word_boundary(&text, index) {
+                    assert!(is_grapheme_boundary(&text, index), "{:?} {}", text, index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Index must be at a character boundary of the text")]
+    fn string_segmentation_panic_inside_character() {
+        next_grapheme_boundary("\u{e9}", 1);
+    }
+}
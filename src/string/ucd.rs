@@ -0,0 +1,359 @@
+// A small subset of the Unicode character database: the properties used by grapheme cluster and word segmentation
+// (UAX #29) for the common scripts, combining marks and emoji. Characters missing from the tables get the default
+// property, so rare scripts segment like plain letters
+
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GraphemeBreak {
+    CR,
+    LF,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    Prepend,
+    SpacingMark,
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WordBreak {
+    CR,
+    LF,
+    Newline,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    Format,
+    Katakana,
+    HebrewLetter,
+    ALetter,
+    SingleQuote,
+    DoubleQuote,
+    MidNumLet,
+    MidLetter,
+    MidNum,
+    Numeric,
+    ExtendNumLet,
+    WSegSpace,
+    Other,
+}
+
+// inclusive ranges, sorted and disjoint
+const GRAPHEME_BREAKS: &[(u32, u32, GraphemeBreak)] = &[
+    (0x0000, 0x0009, GraphemeBreak::Control),
+    (0x000A, 0x000A, GraphemeBreak::LF),
+    (0x000B, 0x000C, GraphemeBreak::Control),
+    (0x000D, 0x000D, GraphemeBreak::CR),
+    (0x000E, 0x001F, GraphemeBreak::Control),
+    (0x007F, 0x009F, GraphemeBreak::Control),
+    (0x00AD, 0x00AD, GraphemeBreak::Control),
+    (0x0300, 0x036F, GraphemeBreak::Extend),
+    (0x0483, 0x0489, GraphemeBreak::Extend),
+    (0x0591, 0x05BD, GraphemeBreak::Extend),
+    (0x05BF, 0x05BF, GraphemeBreak::Extend),
+    (0x05C1, 0x05C2, GraphemeBreak::Extend),
+    (0x05C4, 0x05C5, GraphemeBreak::Extend),
+    (0x05C7, 0x05C7, GraphemeBreak::Extend),
+    (0x0600, 0x0605, GraphemeBreak::Prepend),
+    (0x0610, 0x061A, GraphemeBreak::Extend),
+    (0x064B, 0x065F, GraphemeBreak::Extend),
+    (0x0670, 0x0670, GraphemeBreak::Extend),
+    (0x06D6, 0x06DC, GraphemeBreak::Extend),
+    (0x06DD, 0x06DD, GraphemeBreak::Prepend),
+    (0x06DF, 0x06E4, GraphemeBreak::Extend),
+    (0x06E7, 0x06E8, GraphemeBreak::Extend),
+    (0x06EA, 0x06ED, GraphemeBreak::Extend),
+    (0x070F, 0x070F, GraphemeBreak::Prepend),
+    (0x0900, 0x0902, GraphemeBreak::Extend),
+    (0x0903, 0x0903, GraphemeBreak::SpacingMark),
+    (0x093A, 0x093A, GraphemeBreak::Extend),
+    (0x093B, 0x093B, GraphemeBreak::SpacingMark),
+    (0x093C, 0x093C, GraphemeBreak::Extend),
+    (0x093E, 0x0940, GraphemeBreak::SpacingMark),
+    (0x0941, 0x0948, GraphemeBreak::Extend),
+    (0x0949, 0x094C, GraphemeBreak::SpacingMark),
+    (0x094D, 0x094D, GraphemeBreak::Extend),
+    (0x094E, 0x094F, GraphemeBreak::SpacingMark),
+    (0x0951, 0x0957, GraphemeBreak::Extend),
+    (0x0962, 0x0963, GraphemeBreak::Extend),
+    (0x0E31, 0x0E31, GraphemeBreak::Extend),
+    (0x0E33, 0x0E33, GraphemeBreak::SpacingMark),
+    (0x0E34, 0x0E3A, GraphemeBreak::Extend),
+    (0x0E47, 0x0E4E, GraphemeBreak::Extend),
+    (0x1100, 0x115F, GraphemeBreak::L),
+    (0x1160, 0x11A7, GraphemeBreak::V),
+    (0x11A8, 0x11FF, GraphemeBreak::T),
+    (0x1AB0, 0x1AFF, GraphemeBreak::Extend),
+    (0x1DC0, 0x1DFF, GraphemeBreak::Extend),
+    (0x200B, 0x200B, GraphemeBreak::Control),
+    (0x200C, 0x200C, GraphemeBreak::Extend),
+    (0x200D, 0x200D, GraphemeBreak::Zwj),
+    (0x200E, 0x200F, GraphemeBreak::Control),
+    (0x2028, 0x202E, GraphemeBreak::Control),
+    (0x2060, 0x206F, GraphemeBreak::Control),
+    (0x20D0, 0x20FF, GraphemeBreak::Extend),
+    (0xA960, 0xA97C, GraphemeBreak::L),
+    (0xD7B0, 0xD7C6, GraphemeBreak::V),
+    (0xD7CB, 0xD7FB, GraphemeBreak::T),
+    (0xFE00, 0xFE0F, GraphemeBreak::Extend),
+    (0xFE20, 0xFE2F, GraphemeBreak::Extend),
+    (0xFEFF, 0xFEFF, GraphemeBreak::Control),
+    (0xFFF0, 0xFFFB, GraphemeBreak::Control),
+    (0x1F1E6, 0x1F1FF, GraphemeBreak::RegionalIndicator),
+    (0x1F3FB, 0x1F3FF, GraphemeBreak::Extend),
+    (0xE0000, 0xE001F, GraphemeBreak::Control),
+    (0xE0020, 0xE007F, GraphemeBreak::Extend),
+    (0xE0080, 0xE00FF, GraphemeBreak::Control),
+    (0xE0100, 0xE01EF, GraphemeBreak::Extend),
+];
+
+const EXTENDED_PICTOGRAPHIC: &[(u32, u32)] = &[
+    (0x00A9, 0x00A9),
+    (0x00AE, 0x00AE),
+    (0x203C, 0x203C),
+    (0x2049, 0x2049),
+    (0x2122, 0x2122),
+    (0x2139, 0x2139),
+    (0x2194, 0x2199),
+    (0x21A9, 0x21AA),
+    (0x231A, 0x231B),
+    (0x2328, 0x2328),
+    (0x23CF, 0x23CF),
+    (0x23E9, 0x23F3),
+    (0x23F8, 0x23FA),
+    (0x24C2, 0x24C2),
+    (0x25AA, 0x25AB),
+    (0x25B6, 0x25B6),
+    (0x25C0, 0x25C0),
+    (0x25FB, 0x25FE),
+    (0x2600, 0x27BF),
+    (0x2934, 0x2935),
+    (0x2B05, 0x2B07),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x3030, 0x3030),
+    (0x303D, 0x303D),
+    (0x3297, 0x3297),
+    (0x3299, 0x3299),
+    (0x1F000, 0x1F0FF),
+    (0x1F10D, 0x1F10F),
+    (0x1F12F, 0x1F12F),
+    (0x1F16C, 0x1F171),
+    (0x1F17E, 0x1F17F),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F1AD, 0x1F1E5),
+    (0x1F201, 0x1F20F),
+    (0x1F21A, 0x1F21A),
+    (0x1F22F, 0x1F22F),
+    (0x1F232, 0x1F23A),
+    (0x1F23C, 0x1F23F),
+    (0x1F249, 0x1F3FA),
+    (0x1F400, 0x1F53D),
+    (0x1F546, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F774, 0x1F77F),
+    (0x1F7D5, 0x1F7FF),
+    (0x1F80C, 0x1F80F),
+    (0x1F848, 0x1F84F),
+    (0x1F85A, 0x1F85F),
+    (0x1F888, 0x1F88F),
+    (0x1F8AE, 0x1F8FF),
+    (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945),
+    (0x1F947, 0x1FAFF),
+    (0x1FC00, 0x1FFFD),
+];
+
+// word properties that do not follow from the grapheme properties or from `char::is_alphabetic`
+const WORD_BREAKS: &[(u32, u32, WordBreak)] = &[
+    (0x000A, 0x000A, WordBreak::LF),
+    (0x000B, 0x000C, WordBreak::Newline),
+    (0x000D, 0x000D, WordBreak::CR),
+    (0x0020, 0x0020, WordBreak::WSegSpace),
+    (0x0022, 0x0022, WordBreak::DoubleQuote),
+    (0x0027, 0x0027, WordBreak::SingleQuote),
+    (0x002C, 0x002C, WordBreak::MidNum),
+    (0x002E, 0x002E, WordBreak::MidNumLet),
+    (0x0030, 0x0039, WordBreak::Numeric),
+    (0x003A, 0x003A, WordBreak::MidLetter),
+    (0x003B, 0x003B, WordBreak::MidNum),
+    (0x005F, 0x005F, WordBreak::ExtendNumLet),
+    (0x0085, 0x0085, WordBreak::Newline),
+    (0x00AD, 0x00AD, WordBreak::Format),
+    (0x00B7, 0x00B7, WordBreak::MidLetter),
+    (0x037E, 0x037E, WordBreak::MidNum),
+    (0x0387, 0x0387, WordBreak::MidLetter),
+    (0x055F, 0x055F, WordBreak::MidLetter),
+    (0x0589, 0x0589, WordBreak::MidNum),
+    (0x05D0, 0x05EA, WordBreak::HebrewLetter),
+    (0x05EF, 0x05F2, WordBreak::HebrewLetter),
+    (0x05F4, 0x05F4, WordBreak::MidLetter),
+    (0x0600, 0x0605, WordBreak::Format),
+    (0x060C, 0x060D, WordBreak::MidNum),
+    (0x0660, 0x0669, WordBreak::Numeric),
+    (0x066C, 0x066C, WordBreak::MidNum),
+    (0x06DD, 0x06DD, WordBreak::Format),
+    (0x06F0, 0x06F9, WordBreak::Numeric),
+    (0x070F, 0x070F, WordBreak::Format),
+    (0x07F8, 0x07F8, WordBreak::MidNum),
+    (0x0966, 0x096F, WordBreak::Numeric),
+    (0x1680, 0x1680, WordBreak::WSegSpace),
+    (0x2000, 0x2006, WordBreak::WSegSpace),
+    (0x2008, 0x200A, WordBreak::WSegSpace),
+    (0x200E, 0x200F, WordBreak::Format),
+    (0x2018, 0x2019, WordBreak::MidNumLet),
+    (0x2024, 0x2024, WordBreak::MidNumLet),
+    (0x2027, 0x2027, WordBreak::MidLetter),
+    (0x2028, 0x2029, WordBreak::Newline),
+    (0x202A, 0x202E, WordBreak::Format),
+    (0x202F, 0x202F, WordBreak::ExtendNumLet),
+    (0x203F, 0x2040, WordBreak::ExtendNumLet),
+    (0x2044, 0x2044, WordBreak::MidNum),
+    (0x2054, 0x2054, WordBreak::ExtendNumLet),
+    (0x205F, 0x205F, WordBreak::WSegSpace),
+    (0x2060, 0x2064, WordBreak::Format),
+    (0x3000, 0x3000, WordBreak::WSegSpace),
+    (0x3031, 0x3035, WordBreak::Katakana),
+    (0x309B, 0x309C, WordBreak::Katakana),
+    (0x30A0, 0x30FA, WordBreak::Katakana),
+    (0x30FC, 0x30FF, WordBreak::Katakana),
+    (0x31F0, 0x31FF, WordBreak::Katakana),
+    (0xFE10, 0xFE10, WordBreak::MidNum),
+    (0xFE13, 0xFE13, WordBreak::MidLetter),
+    (0xFE14, 0xFE14, WordBreak::MidNum),
+    (0xFE33, 0xFE34, WordBreak::ExtendNumLet),
+    (0xFE4D, 0xFE4F, WordBreak::ExtendNumLet),
+    (0xFE50, 0xFE50, WordBreak::MidNum),
+    (0xFE52, 0xFE52, WordBreak::MidNumLet),
+    (0xFE54, 0xFE54, WordBreak::MidNum),
+    (0xFE55, 0xFE55, WordBreak::MidLetter),
+    (0xFEFF, 0xFEFF, WordBreak::Format),
+    (0xFF07, 0xFF07, WordBreak::MidNumLet),
+    (0xFF0C, 0xFF0C, WordBreak::MidNum),
+    (0xFF0E, 0xFF0E, WordBreak::MidNumLet),
+    (0xFF10, 0xFF19, WordBreak::Numeric),
+    (0xFF1A, 0xFF1A, WordBreak::MidLetter),
+    (0xFF1B, 0xFF1B, WordBreak::MidNum),
+    (0xFF3F, 0xFF3F, WordBreak::ExtendNumLet),
+    (0xFF66, 0xFF9D, WordBreak::Katakana),
+];
+
+// alphabetic characters that are not word letters: ideographs, kana and scripts written without spaces
+const NOT_ALETTER: &[(u32, u32)] = &[
+    (0x0E00, 0x0EFF),
+    (0x1000, 0x109F),
+    (0x1780, 0x17FF),
+    (0x3005, 0x3007),
+    (0x3040, 0x309F),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xF900, 0xFAFF),
+    (0x20000, 0x3FFFF),
+];
+
+// orders the range [`start`, `end`] relative to `c`
+fn compare(start: u32, end: u32, c: char) -> Ordering {
+    if end < c as u32 {
+        Ordering::Less
+    } else if start > c as u32 {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+fn find<V: Copy>(ranges: &[(u32, u32, V)], c: char) -> Option<V> {
+    ranges
+        .binary_search_by(|(start, end, _)| compare(*start, *end, c))
+        .ok()
+        .map(|index| ranges[index].2)
+}
+
+fn contains(ranges: &[(u32, u32)], c: char) -> bool {
+    ranges
+        .binary_search_by(|(start, end)| compare(*start, *end, c))
+        .is_ok()
+}
+
+pub(crate) fn grapheme_break(c: char) -> GraphemeBreak {
+    // precomposed Hangul syllables are Lv every 28 code points and Lvt in between
+    let code = c as u32;
+    if (0xAC00..=0xD7A3).contains(&code) {
+        return if (code - 0xAC00).is_multiple_of(28) {
+            GraphemeBreak::Lv
+        } else {
+            GraphemeBreak::Lvt
+        };
+    }
+
+    find(GRAPHEME_BREAKS, c).unwrap_or(GraphemeBreak::Other)
+}
+
+pub(crate) fn is_extended_pictographic(c: char) -> bool {
+    contains(EXTENDED_PICTOGRAPHIC, c)
+}
+
+pub(crate) fn word_break(c: char) -> WordBreak {
+    if let Some(property) = find(WORD_BREAKS, c) {
+        return property;
+    }
+    match grapheme_break(c) {
+        GraphemeBreak::Extend | GraphemeBreak::SpacingMark => return WordBreak::Extend,
+        GraphemeBreak::Zwj => return WordBreak::Zwj,
+        GraphemeBreak::RegionalIndicator => return WordBreak::RegionalIndicator,
+        _ => {}
+    }
+
+    if c.is_alphabetic() && !contains(NOT_ALETTER, c) {
+        WordBreak::ALetter
+    } else {
+        WordBreak::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_and_disjoint(ranges: impl Iterator<Item = (u32, u32)>) -> bool {
+        let ranges: Vec<(u32, u32)> = ranges.collect();
+        ranges.iter().all(|(start, end)| start <= end)
+            && ranges.windows(2).all(|pair| pair[0].1 < pair[1].0)
+    }
+
+    #[test]
+    fn string_ucd_tables_are_sorted() {
+        assert!(sorted_and_disjoint(
+            GRAPHEME_BREAKS.iter().map(|(start, end, _)| (*start, *end))
+        ));
+        assert!(sorted_and_disjoint(
+            WORD_BREAKS.iter().map(|(start, end, _)| (*start, *end))
+        ));
+        assert!(sorted_and_disjoint(EXTENDED_PICTOGRAPHIC.iter().copied()));
+        assert!(sorted_and_disjoint(NOT_ALETTER.iter().copied()));
+    }
+
+    #[test]
+    fn string_ucd_properties() {
+        assert_eq!(grapheme_break('\u{301}'), GraphemeBreak::Extend);
+        assert_eq!(grapheme_break('한'), GraphemeBreak::Lvt);
+        assert_eq!(grapheme_break('하'), GraphemeBreak::Lv);
+        assert_eq!(grapheme_break('a'), GraphemeBreak::Other);
+        assert!(is_extended_pictographic('😀'));
+        assert!(!is_extended_pictographic('a'));
+        assert_eq!(word_break('é'), WordBreak::ALetter);
+        assert_eq!(word_break('\u{301}'), WordBreak::Extend);
+        assert_eq!(word_break('中'), WordBreak::Other);
+        assert_eq!(word_break('カ'), WordBreak::Katakana);
+        assert_eq!(word_break('٣'), WordBreak::Numeric);
+    }
+}
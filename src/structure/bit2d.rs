@@ -0,0 +1,262 @@
+use crate::tree::Fenwick;
+use crate::util::{Footprint, MemoryFootprint};
+use std::ops::{Add, Sub};
+
+// a node of the outer tree: the y coordinates of the points it covers and the weights summed over them
+#[derive(Debug, Clone)]
+struct Column<K, W> {
+    ys: Vec<K>,
+    weights: Fenwick<W>,
+}
+
+/// A Fenwick tree of sorted vectors answers 2D dominance queries: the total weight of the points with x ≤ a and
+/// y ≤ b, while weights of points change. The points that may ever carry weight are given up front. The outer
+/// Fenwick tree runs over the distinct x coordinates and each of its nodes keeps the sorted y coordinates of the
+/// points it covers with an inner Fenwick tree over them, so updates and queries take O(log² n) time in O(n log n)
+/// space. It sits between a `Fenwick` tree, which has one dimension, and a full 2D segment tree, which needs a
+/// grid of all coordinates
+///
+/// # Examples
+/// ```
+/// use rudac::structure::Bit2d;
+///
+/// let points = [(1, 5), (2, 2), (4, 3), (4, 8), (6, 1)];
+/// let mut bit: Bit2d<i32, i64> = Bit2d::init(&points);
+/// for &point in &points {
+///     bit.add(point, 1);
+/// }
+///
+/// // points with x <= 4 and y <= 5
+/// assert_eq!(bit.prefix_sum(4, 5), 3);
+/// // points with 2 <= x <= 6 and 2 <= y <= 8
+/// assert_eq!(bit.rect_sum((2, 2), (6, 8)), 3);
+///
+/// bit.add((2, 2), -1);
+/// assert_eq!(bit.prefix_sum(4, 5), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bit2d<K, W> {
+    // distinct x coordinates in increasing order
+    xs: Vec<K>,
+
+    // 1-based outer tree, columns[i] covers the x coordinates in (i - lowbit(i), i]
+    columns: Vec<Column<K, W>>,
+}
+
+impl<K, W> Bit2d<K, W>
+where
+    K: Ord + Copy,
+    W: Copy + Default + Add<Output = W> + Sub<Output = W>,
+{
+    /// Builds the tree for `points`, all of them with weight `W::default()`. Only these points can be given weight
+    /// later, duplicates are allowed
+    /// * Complexity: O(n log² n)
+    ///
+    /// # Arguments
+    /// * `points`: the points as (x, y) pairs
+    pub fn init(points: &[(K, K)]) -> Bit2d<K, W> {
+        let mut xs: Vec<K> = points.iter().map(|point| point.0).collect();
+        xs.sort_unstable();
+        xs.dedup();
+
+        let mut ys: Vec<Vec<K>> = vec![Vec::new(); xs.len() + 1];
+        for &(x, y) in points {
+            let mut i = xs.binary_search(&x).unwrap() + 1;
+            while i <= xs.len() {
+                ys[i].push(y);
+                i += lowbit(i);
+            }
+        }
+
+        let columns = ys
+            .into_iter()
+            .map(|mut ys| {
+                ys.sort_unstable();
+                ys.dedup();
+                Column {
+                    weights: Fenwick::init(ys.len()),
+                    ys,
+                }
+            })
+            .collect();
+
+        Bit2d { xs, columns }
+    }
+
+    /// Returns number of distinct x coordinates
+    pub fn size(&self) -> usize {
+        self.xs.len()
+    }
+
+    /// Returns true if the tree was built without points
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    /// Adds `delta` to the weight of `point`
+    /// * Complexity: O(log² n)
+    ///
+    /// # Arguments
+    /// * `point`: the point as an (x, y) pair
+    /// * `delta`: value to be added
+    ///
+    /// # Panics
+    /// * panics if `point` was not given when the tree was built
+    pub fn add(&mut self, point: (K, K), delta: W) {
+        let (x, y) = point;
+        let mut i = match self.xs.binary_search(&x) {
+            Ok(index) => index + 1,
+            Err(_) => panic!("Point was not given when the tree was built"),
+        };
+        while i <= self.xs.len() {
+            let column = &mut self.columns[i];
+            match column.ys.binary_search(&y) {
+                Ok(index) => column.weights.add(index, delta),
+                Err(_) => panic!("Point was not given when the tree was built"),
+            }
+            i += lowbit(i);
+        }
+    }
+
+    // sum of the weights of the points whose x is among the first `count` coordinates and whose y passes `below`
+    fn sum<P: Fn(&K) -> bool>(&self, count: usize, below: P) -> W {
+        let mut sum = W::default();
+        let mut i = count;
+        while i > 0 {
+            let column = &self.columns[i];
+            sum = sum + column.weights.prefix_sum(column.ys.partition_point(&below));
+            i -= lowbit(i);
+        }
+
+        sum
+    }
+
+    /// Returns the total weight of the points with x ≤ `x` and y ≤ `y`
+    /// * Complexity: O(log² n)
+    ///
+    /// # Arguments
+    /// * `x`: bound of the x coordinates
+    /// * `y`: bound of the y coordinates
+    pub fn prefix_sum(&self, x: K, y: K) -> W {
+        let count = self.xs.partition_point(|other| *other <= x);
+
+        self.sum(count, |other| *other <= y)
+    }
+
+    /// Returns the total weight of the points inside the rectangle with corners `low` and `high`, borders included
+    /// * Complexity: O(log² n)
+    ///
+    /// # Arguments
+    /// * `low`: the corner with the smallest coordinates
+    /// * `high`: the corner with the largest coordinates
+    pub fn rect_sum(&self, low: (K, K), high: (K, K)) -> W {
+        if low.0 > high.0 || low.1 > high.1 {
+            return W::default();
+        }
+
+        let inside = self.xs.partition_point(|other| *other <= high.0);
+        let left = self.xs.partition_point(|other| *other < low.0);
+        let top = |other: &K| *other <= high.1;
+        let bottom = |other: &K| *other < low.1;
+
+        // both differences are weights of strips, which keeps unsigned weights from underflowing
+        (self.sum(inside, top) - self.sum(inside, bottom))
+            - (self.sum(left, top) - self.sum(left, bottom))
+    }
+}
+
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+impl<K, W> MemoryFootprint for Bit2d<K, W> {
+    fn footprint(&self) -> Footprint {
+        let footprint = Footprint::of::<K>(self.xs.len())
+            .with_vec(&self.xs)
+            .with_vec(&self.columns);
+        self.columns.iter().fold(footprint, |footprint, column| {
+            footprint
+                .with_vec(&column.ys)
+                .with(column.weights.footprint())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn structure_bit2d_matches_naive() {
+        let mut rng = SplitMix64::init(41);
+        let points: Vec<(i32, i32)> = (0..300)
+            .map(|_| {
+                (
+                    rng.next_below(40) as i32 - 20,
+                    rng.next_below(40) as i32 - 20,
+                )
+            })
+            .collect();
+        let mut bit: Bit2d<i32, i64> = Bit2d::init(&points);
+        let mut weights = vec![0; points.len()];
+
+        for _ in 0..2000 {
+            if rng.next_below(2) == 0 {
+                let index = rng.next_below(points.len() as u64) as usize;
+                let delta = rng.next_below(20) as i64 - 10;
+                bit.add(points[index], delta);
+                weights[index] += delta;
+            } else {
+                let x1 = rng.next_below(44) as i32 - 22;
+                let y1 = rng.next_below(44) as i32 - 22;
+                let x2 = rng.next_below(44) as i32 - 22;
+                let y2 = rng.next_below(44) as i32 - 22;
+                let naive = |inside: &dyn Fn(i32, i32) -> bool| -> i64 {
+                    points
+                        .iter()
+                        .zip(&weights)
+                        .filter(|((x, y), _)| inside(*x, *y))
+                        .map(|(_, weight)| *weight)
+                        .sum()
+                };
+
+                assert_eq!(bit.prefix_sum(x1, y1), naive(&|x, y| x <= x1 && y <= y1));
+                assert_eq!(
+                    bit.rect_sum((x1, y1), (x2, y2)),
+                    naive(&|x, y| x1 <= x && x <= x2 && y1 <= y && y <= y2)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn structure_bit2d_unsigned_counts() {
+        let points = [(0u32, 0u32), (1, 1), (1, 1), (2, 0), (3, 3)];
+        let mut bit: Bit2d<u32, u32> = Bit2d::init(&points);
+        for &point in &points {
+            bit.add(point, 1);
+        }
+
+        assert_eq!(bit.size(), 4);
+        assert_eq!(bit.prefix_sum(1, 1), 3);
+        assert_eq!(bit.rect_sum((1, 0), (3, 1)), 3);
+        assert_eq!(bit.rect_sum((2, 2), (1, 3)), 0);
+        assert_eq!(bit.prefix_sum(3, 3), 5);
+    }
+
+    #[test]
+    fn structure_bit2d_empty() {
+        let bit: Bit2d<i32, i32> = Bit2d::init(&[]);
+
+        assert!(bit.is_empty());
+        assert_eq!(bit.prefix_sum(10, 10), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Point was not given when the tree was built")]
+    fn structure_bit2d_panic_unknown_point() {
+        let mut bit: Bit2d<i32, i32> = Bit2d::init(&[(1, 1), (2, 2)]);
+        bit.add((1, 2), 1);
+    }
+}
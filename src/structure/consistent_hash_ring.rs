@@ -0,0 +1,402 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+// size of the hash space as a floating point number
+const HASH_SPACE: f64 = 18446744073709551616.0;
+
+/// Rebalancing statistics of a consistent hash ring. Moved fractions are fractions of the key space
+/// whose owner changed, which approximates the fraction of keys that must be migrated
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RingStats {
+    /// number of nodes added to the ring
+    pub additions: usize,
+
+    /// number of nodes removed from the ring
+    pub removals: usize,
+
+    /// fraction of the key space moved by the last addition or removal
+    pub last_moved: f64,
+
+    /// total fraction of the key space moved by all additions and removals
+    pub total_moved: f64,
+}
+
+/// Consistent hash ring maps keys to nodes so that adding or removing a node only moves the keys of the neighboring arcs.
+/// Every node is placed on the ring at several points(virtual nodes) to spread its load evenly.
+/// A key belongs to the first virtual node clockwise from the hash of the key.
+///
+/// # Examples
+/// ```
+/// use rudac::structure::ConsistentHashRing;
+///
+/// let mut ring = ConsistentHashRing::init(100);
+///
+/// ring.add_node("cache-1");
+/// ring.add_node("cache-2");
+/// ring.add_node("cache-3");
+///
+/// let owner = *ring.get_node(&"user:42").unwrap();
+///
+/// // removing another node does not move the key
+/// let other = if owner == "cache-1" { "cache-2" } else { "cache-1" };
+/// ring.remove_node(&other);
+///
+/// assert_eq!(*ring.get_node(&"user:42").unwrap(), owner);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConsistentHashRing<N>
+where
+    N: Hash + Eq + Clone,
+{
+    // number of virtual nodes of every node
+    virtual_nodes: usize,
+
+    // position of every virtual node on the ring -> node
+    ring: BTreeMap<u64, N>,
+
+    // nodes in order of addition
+    nodes: Vec<N>,
+
+    stats: RingStats,
+}
+
+impl<N> ConsistentHashRing<N>
+where
+    N: Hash + Eq + Clone,
+{
+    /// Initializes an empty ring
+    ///
+    /// # Arguments
+    /// * `virtual_nodes`: number of points of every node on the ring
+    ///
+    /// # Panics
+    /// * panics if `virtual_nodes` is zero
+    pub fn init(virtual_nodes: usize) -> ConsistentHashRing<N> {
+        if virtual_nodes == 0 {
+            panic!("Number of virtual nodes must be greater than zero");
+        }
+
+        ConsistentHashRing {
+            virtual_nodes,
+            ring: BTreeMap::new(),
+            nodes: Vec::new(),
+            stats: RingStats::default(),
+        }
+    }
+
+    /// Returns number of nodes
+    pub fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if there are no nodes in the ring
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns number of virtual nodes of every node
+    pub fn virtual_nodes(&self) -> usize {
+        self.virtual_nodes
+    }
+
+    /// Returns nodes of the ring in order of addition
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Returns true if `node` is in the ring
+    ///
+    /// # Arguments
+    /// * `node`: node to be checked
+    pub fn contains(&self, node: &N) -> bool {
+        self.nodes.contains(node)
+    }
+
+    /// Adds `node` to the ring. Returns false if the node is already in the ring
+    ///
+    /// # Arguments
+    /// * `node`: node to be added
+    pub fn add_node(&mut self, node: N) -> bool {
+        if self.contains(&node) {
+            return false;
+        }
+
+        for replica in 0..self.virtual_nodes {
+            let point = ConsistentHashRing::<N>::point(&node, replica);
+
+            // points are 64 bit hashes, a collision with another node is astronomically unlikely and the older point wins
+            self.ring.entry(point).or_insert_with(|| node.clone());
+        }
+        self.nodes.push(node.clone());
+
+        // every moved key now belongs to the new node
+        let moved = self.load(&node);
+        self.stats.additions += 1;
+        self.record_move(moved);
+
+        true
+    }
+
+    /// Removes `node` from the ring. Returns false if the node is not in the ring
+    ///
+    /// # Arguments
+    /// * `node`: node to be removed
+    pub fn remove_node(&mut self, node: &N) -> bool {
+        let position = match self.nodes.iter().position(|n| n == node) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        // every moved key belonged to the removed node
+        let moved = self.load(node);
+
+        for replica in 0..self.virtual_nodes {
+            let point = ConsistentHashRing::<N>::point(node, replica);
+            if self.ring.get(&point) == Some(node) {
+                self.ring.remove(&point);
+            }
+        }
+        self.nodes.remove(position);
+
+        self.stats.removals += 1;
+        self.record_move(moved);
+
+        true
+    }
+
+    /// Returns the node owning `key`, or `None` if the ring is empty
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn get_node<K: Hash + ?Sized>(&self, key: &K) -> Option<&N> {
+        let hash = ConsistentHashRing::<N>::hash(key);
+
+        self.ring
+            .range(hash..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, node)| node)
+    }
+
+    /// Returns at most `count` distinct nodes for `key` in clockwise order, starting with its owner.
+    /// It is useful to pick replicas of a key
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    /// * `count`: number of nodes
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::ConsistentHashRing;
+    ///
+    /// let mut ring = ConsistentHashRing::init(10);
+    /// ring.add_node(1);
+    /// ring.add_node(2);
+    /// ring.add_node(3);
+    ///
+    /// let replicas = ring.get_nodes(&"key", 2);
+    ///
+    /// assert_eq!(replicas.len(), 2);
+    /// assert_eq!(replicas[0], ring.get_node(&"key").unwrap());
+    /// assert_ne!(replicas[0], replicas[1]);
+    /// assert_eq!(ring.get_nodes(&"key", 5).len(), 3);
+    /// ```
+    pub fn get_nodes<K: Hash + ?Sized>(&self, key: &K, count: usize) -> Vec<&N> {
+        let hash = ConsistentHashRing::<N>::hash(key);
+        let count = std::cmp::min(count, self.nodes.len());
+        let mut result: Vec<&N> = Vec::with_capacity(count);
+
+        for (_, node) in self.ring.range(hash..).chain(self.ring.range(..hash)) {
+            if result.len() == count {
+                break;
+            }
+            if !result.contains(&node) {
+                result.push(node);
+            }
+        }
+
+        result
+    }
+
+    /// Returns fraction of the key space owned by `node`. It is zero if the node is not in the ring
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    pub fn load(&self, node: &N) -> f64 {
+        let mut owned: u128 = 0;
+
+        for replica in 0..self.virtual_nodes {
+            let point = ConsistentHashRing::<N>::point(node, replica);
+            if self.ring.get(&point) != Some(node) {
+                continue;
+            }
+
+            // the virtual node owns the arc from its predecessor(exclusive) to itself(inclusive)
+            let predecessor = self
+                .ring
+                .range(..point)
+                .next_back()
+                .or_else(|| self.ring.iter().next_back())
+                .map(|(p, _)| *p)
+                .unwrap();
+
+            owned += if predecessor == point {
+                1u128 << 64
+            } else {
+                point.wrapping_sub(predecessor) as u128
+            };
+        }
+
+        owned as f64 / HASH_SPACE
+    }
+
+    /// Returns ratio of the largest load of a node to the average load. It is 1.0 for a perfectly balanced ring
+    /// and 0.0 for an empty ring
+    pub fn imbalance(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+
+        let largest = self
+            .nodes
+            .iter()
+            .map(|node| self.load(node))
+            .fold(0.0, f64::max);
+
+        largest * self.nodes.len() as f64
+    }
+
+    /// Returns rebalancing statistics of the ring
+    pub fn stats(&self) -> RingStats {
+        self.stats
+    }
+
+    /// Resets rebalancing statistics
+    pub fn reset_stats(&mut self) {
+        self.stats = RingStats::default();
+    }
+
+    fn record_move(&mut self, moved: f64) {
+        self.stats.last_moved = moved;
+        self.stats.total_moved += moved;
+    }
+
+    fn point(node: &N, replica: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        replica.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    fn hash<K: Hash + ?Sized>(key: &K) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        hasher.finish()
+    }
+}
+
+impl<N> MemoryFootprint for ConsistentHashRing<N>
+where
+    N: Hash + Eq + Clone,
+{
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<N>(self.nodes.len())
+            .with_btree_map(&self.ring)
+            .with_vec(&self.nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structure_consistent_hash_ring_empty() {
+        let mut ring: ConsistentHashRing<String> = ConsistentHashRing::init(3);
+
+        assert!(ring.is_empty());
+        assert_eq!(ring.get_node(&1), None);
+        assert!(ring.get_nodes(&1, 3).is_empty());
+        assert!(!ring.remove_node(&String::from("a")));
+        assert_eq!(ring.imbalance(), 0.0);
+    }
+
+    #[test]
+    fn structure_consistent_hash_ring_single_node() {
+        let mut ring = ConsistentHashRing::init(1);
+
+        assert!(ring.add_node('a'));
+        assert!(!ring.add_node('a'));
+
+        assert_eq!(ring.load(&'a'), 1.0);
+        assert_eq!(ring.stats().last_moved, 1.0);
+        for key in 0..100 {
+            assert_eq!(ring.get_node(&key), Some(&'a'));
+        }
+    }
+
+    #[test]
+    fn structure_consistent_hash_ring_loads_sum_to_one() {
+        let mut ring = ConsistentHashRing::init(200);
+        for node in 0..10 {
+            ring.add_node(node);
+        }
+
+        let total: f64 = (0..10).map(|node| ring.load(&node)).sum();
+
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(ring.imbalance() < 1.5);
+        assert_eq!(ring.load(&42), 0.0);
+    }
+
+    #[test]
+    fn structure_consistent_hash_ring_minimal_movement() {
+        let mut ring = ConsistentHashRing::init(100);
+        for node in 0..5 {
+            ring.add_node(format!("node-{}", node));
+        }
+
+        let keys: Vec<u32> = (0..5000).collect();
+        let before: Vec<String> = keys
+            .iter()
+            .map(|k| ring.get_node(k).unwrap().clone())
+            .collect();
+
+        ring.add_node(String::from("node-5"));
+        let moved_fraction = ring.stats().last_moved;
+
+        let mut moved = 0;
+        for (key, owner) in keys.iter().zip(before.iter()) {
+            let new_owner = ring.get_node(key).unwrap();
+            if new_owner != owner {
+                // keys only move to the new node
+                assert_eq!(new_owner, "node-5");
+                moved += 1;
+            }
+        }
+
+        let observed = moved as f64 / keys.len() as f64;
+        assert!((observed - moved_fraction).abs() < 0.03);
+        assert!(moved_fraction > 0.1 && moved_fraction < 0.25);
+
+        ring.remove_node(&String::from("node-5"));
+        for (key, owner) in keys.iter().zip(before.iter()) {
+            assert_eq!(ring.get_node(key).unwrap(), owner);
+        }
+
+        let stats = ring.stats();
+        assert_eq!(stats.additions, 6);
+        assert_eq!(stats.removals, 1);
+        assert!((stats.last_moved - moved_fraction).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "Number of virtual nodes must be greater than zero")]
+    fn structure_consistent_hash_ring_zero_virtual_nodes() {
+        ConsistentHashRing::<u32>::init(0);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+/// A disjoint sparse table answers range queries for any associative operation, like sums, products or matrix
+/// products, in O(1) time after O(n log n) preprocessing. Unlike a standard sparse table it never combines an item
+/// with itself, so the operation does not have to be idempotent. Every level splits the sequence into blocks and
+/// stores the combination of every item up to the middle of its block, from either side
+///
+/// # Examples
+/// ```
+/// use rudac::structure::DisjointSparseTable;
+///
+/// let table = DisjointSparseTable::init(&[3, 1, 4, 1, 5, 9, 2, 6], |a: &i32, b: &i32| a + b);
+///
+/// assert_eq!(table.query(0, 8), 31);
+/// assert_eq!(table.query(2, 5), 10);
+/// assert_eq!(table.query(7, 8), 6);
+///
+/// // string concatenation is associative but not commutative
+/// let words = ["a", "b", "c", "d"].map(String::from);
+/// let table = DisjointSparseTable::init(&words, |a: &String, b: &String| format!("{}{}", a, b));
+///
+/// assert_eq!(table.query(1, 4), "bcd");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisjointSparseTable<T, F> {
+    items: Vec<T>,
+
+    // levels[h][i] combines the items from i to the middle of its block of size 2^(h + 1), the middle excluded
+    // for items before it and included for items after it
+    levels: Vec<Vec<T>>,
+    operation: F,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> DisjointSparseTable<T, F> {
+    /// Builds the table from `items` in O(n log n) time and space
+    ///
+    /// # Arguments
+    /// * `items`: the items
+    /// * `operation`: an associative operation
+    pub fn init(items: &[T], operation: F) -> DisjointSparseTable<T, F> {
+        let size = items.len();
+        let mut levels = Vec::new();
+        let mut half = 1;
+        while half < size {
+            let mut level = items.to_vec();
+            for middle in (half..size).step_by(2 * half) {
+                for index in (middle - half..middle - 1).rev() {
+                    level[index] = operation(&items[index], &level[index + 1]);
+                }
+                for index in middle + 1..usize::min(middle + half, size) {
+                    level[index] = operation(&level[index - 1], &items[index]);
+                }
+            }
+            levels.push(level);
+            half *= 2;
+        }
+
+        DisjointSparseTable {
+            items: items.to_vec(),
+            levels,
+            operation,
+        }
+    }
+
+    /// Returns number of items
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the combination of the items in range [`start`, `end`), in order
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    ///
+    /// # Panics
+    /// * panics if the range is empty
+    /// * panics if `end` is greater than number of items
+    pub fn query(&self, start: usize, end: usize) -> T {
+        if start >= end {
+            panic!("Range must not be empty");
+        }
+        if end > self.size() {
+            panic!("Index out of range");
+        }
+
+        let last = end - 1;
+        if start == last {
+            return self.items[start].clone();
+        }
+
+        // the highest differing bit is the level whose block middle separates the two ends
+        let level = (usize::BITS - 1 - (start ^ last).leading_zeros()) as usize;
+        (self.operation)(&self.levels[level][start], &self.levels[level][last])
+    }
+}
+
+impl<T, F> MemoryFootprint for DisjointSparseTable<T, F> {
+    fn footprint(&self) -> Footprint {
+        self.levels.iter().fold(
+            Footprint::of::<T>(self.items.len())
+                .with_vec(&self.items)
+                .with_vec(&self.levels),
+            |footprint, level| footprint.with_vec(level),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn structure_disjoint_sparse_table_matches_fold() {
+        let mut rng = SplitMix64::init(80);
+        for size in [1, 2, 3, 7, 8, 9, 33, 64, 100] {
+            let items: Vec<u64> = (0..size).map(|_| rng.next_below(1000)).collect();
+
+            // affine maps x -> ax + b modulo a prime compose associatively but not commutatively
+            let maps: Vec<(u64, u64)> =
+                items.iter().map(|item| (item % 97 + 1, item / 7)).collect();
+            let compose =
+                |f: &(u64, u64), g: &(u64, u64)| ((f.0 * g.0) % 1009, (g.0 * f.1 + g.1) % 1009);
+            let sums = DisjointSparseTable::init(&items, |a: &u64, b: &u64| a + b);
+            let composed = DisjointSparseTable::init(&maps, compose);
+            assert_eq!(sums.size(), size);
+
+            for start in 0..size {
+                for end in start + 1..=size {
+                    assert_eq!(
+                        sums.query(start, end),
+                        items[start..end].iter().sum::<u64>()
+                    );
+                    let folded = maps[start + 1..end]
+                        .iter()
+                        .fold(maps[start], |f, g| compose(&f, g));
+                    assert_eq!(composed.query(start, end), folded);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Range must not be empty")]
+    fn structure_disjoint_sparse_table_panic_empty_range() {
+        DisjointSparseTable::init(&[1, 2], |a: &i32, b: &i32| a * b).query(1, 1);
+    }
+}
@@ -0,0 +1,750 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::{BTreeSet, HashMap};
+
+// how the bytes used by a version are measured
+enum Measure<T> {
+    // the whole state
+    Full(fn(&T) -> usize),
+
+    // the part of the state not shared with the state of the parent version, the whole state if there is no parent
+    Unshared(fn(&T, Option<&T>) -> usize),
+}
+
+impl<T> Measure<T> {
+    fn bytes(&self, state: &T, parent: Option<&T>) -> usize {
+        match self {
+            Measure::Full(size_of) => size_of(state),
+            Measure::Unshared(size_over) => size_over(state, parent),
+        }
+    }
+}
+
+// a single version of the state stored in the history
+struct Version<T> {
+    // snapshot of the state at this version
+    state: T,
+
+    // version this one was committed on top of. root version has no parent
+    parent: Option<usize>,
+
+    // versions committed on top of this one, in order of creation
+    children: Vec<usize>,
+
+    // child that redo will move into. it is the most recently visited branch
+    redo_child: Option<usize>,
+
+    // approximate number of bytes used by the state, or by its part not shared with the parent's
+    bytes: usize,
+}
+
+/// A versioned undo/redo history.
+/// Every commit creates a new version on top of the current one. Committing after an undo does not discard the undone versions,
+/// instead it starts a new branch so the history forms a tree of versions.
+/// Old versions can be trimmed automatically to keep the history within a memory budget.
+///
+/// States are stored by value. With a persistent structure as the state(ex. `PersistentAVL`), a commit copies only
+/// what changed and every version shares the rest of its memory with its parent. `with_shared_budget` charges each
+/// version only for the memory it does not share with its parent, so the budget holds many more such versions.
+///
+/// # Examples
+/// ```
+/// use rudac::structure::History;
+///
+/// let mut history = History::init(String::from(""));
+///
+/// history.commit(String::from("a"));
+/// history.commit(String::from("ab"));
+///
+/// assert_eq!(*history.undo().unwrap(), String::from("a"));
+/// assert_eq!(*history.redo().unwrap(), String::from("ab"));
+///
+/// // committing after an undo starts a new branch
+/// history.undo();
+/// history.commit(String::from("ac"));
+///
+/// assert_eq!(*history.current(), String::from("ac"));
+/// assert_eq!(history.branches(history.parent(history.current_version()).unwrap()).len(), 2);
+/// ```
+pub struct History<T> {
+    // versions still present in the history by their ids. trimmed versions are removed
+    versions: HashMap<usize, Version<T>>,
+
+    // id of the next version to be committed
+    next_id: usize,
+
+    // ids of the versions without children, the oldest is trimmed first
+    leaves: BTreeSet<usize>,
+
+    // id of the version that is currently checked out
+    current: usize,
+
+    // id of the oldest version still present in the history
+    root: usize,
+
+    // maximum number of bytes the history is allowed to use. None means unlimited
+    memory_budget: Option<usize>,
+
+    // total number of bytes used by all versions
+    memory_usage: usize,
+
+    // measures the bytes used by each version
+    measure: Measure<T>,
+}
+
+impl<T> History<T> {
+    /// Initializes a history with `initial` as its first version and no memory budget
+    ///
+    /// # Arguments
+    /// * `initial`: initial state
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let history = History::init(0);
+    ///
+    /// assert_eq!(*history.current(), 0);
+    /// assert_eq!(history.size(), 1);
+    /// ```
+    pub fn init(initial: T) -> History<T> {
+        History::build(initial, None, Measure::Full(|_| std::mem::size_of::<T>()))
+    }
+
+    /// Initializes a history with `initial` as its first version which trims old versions whenever total size of
+    /// the stored states exceeds `memory_budget`
+    ///
+    /// # Arguments
+    /// * `initial`: initial state
+    /// * `memory_budget`: maximum number of bytes history is allowed to use
+    /// * `size_of`: function that returns number of bytes used by a state
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::with_budget(String::from("a"), 2, |state: &String| state.len());
+    ///
+    /// history.commit(String::from("ab"));
+    ///
+    /// // "a" is trimmed to keep history within budget
+    /// assert_eq!(history.size(), 1);
+    /// assert_eq!(history.undo(), None);
+    /// ```
+    pub fn with_budget(initial: T, memory_budget: usize, size_of: fn(&T) -> usize) -> History<T> {
+        History::build(initial, Some(memory_budget), Measure::Full(size_of))
+    }
+
+    /// Initializes a history with `initial` as its first version whose states share memory with each other, like
+    /// snapshots of persistent structures. Every version is charged for the bytes it does not share with its parent,
+    /// and old versions are trimmed whenever the total exceeds `memory_budget`. The oldest version is charged in full
+    ///
+    /// # Arguments
+    /// * `initial`: initial state
+    /// * `memory_budget`: maximum number of bytes history is allowed to use
+    /// * `size_over`: function that returns number of bytes used by a state and not shared with the state of its
+    ///   parent version, given as the second argument if there is one
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    /// use rudac::tree::PersistentAVL;
+    /// use rudac::util::MemoryFootprint;
+    ///
+    /// fn size_over(state: &PersistentAVL<u32, u32>, parent: Option<&PersistentAVL<u32, u32>>) -> usize {
+    ///     match parent {
+    ///         Some(parent) => state.footprint_over(parent).allocated_bytes(),
+    ///         None => state.footprint().allocated_bytes(),
+    ///     }
+    /// }
+    ///
+    /// let mut document = PersistentAVL::init();
+    /// for line in 0..1000 {
+    ///     document.insert(line, 0);
+    /// }
+    /// let full = document.footprint().allocated_bytes();
+    ///
+    /// // every edit shares all but a path of nodes with the previous version
+    /// let mut history = History::with_shared_budget(document.clone(), 3 * full, size_over);
+    /// for edit in 1..=100 {
+    ///     document.insert(edit * 7, edit);
+    ///     history.commit(document.clone());
+    /// }
+    ///
+    /// assert_eq!(history.size(), 101);
+    /// assert!(history.memory_usage() < 3 * full);
+    /// assert_eq!(history.get(0).unwrap().get(&7), Some(&0));
+    /// ```
+    pub fn with_shared_budget(
+        initial: T,
+        memory_budget: usize,
+        size_over: fn(&T, Option<&T>) -> usize,
+    ) -> History<T> {
+        History::build(initial, Some(memory_budget), Measure::Unshared(size_over))
+    }
+
+    fn build(initial: T, memory_budget: Option<usize>, measure: Measure<T>) -> History<T> {
+        let bytes = measure.bytes(&initial, None);
+
+        let root = Version {
+            state: initial,
+            parent: None,
+            children: Vec::new(),
+            redo_child: None,
+            bytes,
+        };
+
+        History {
+            versions: HashMap::from([(0, root)]),
+            next_id: 1,
+            leaves: BTreeSet::from([0]),
+            current: 0,
+            root: 0,
+            memory_budget,
+            memory_usage: bytes,
+            measure,
+        }
+    }
+
+    /// Commits `state` as a new version on top of the current version and checks it out.
+    /// Returns id of the newly created version
+    ///
+    /// # Arguments
+    /// * `state`: new state
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    ///
+    /// let version = history.commit(1);
+    ///
+    /// assert_eq!(history.current_version(), version);
+    /// assert_eq!(*history.current(), 1);
+    /// ```
+    pub fn commit(&mut self, state: T) -> usize {
+        let bytes = self.measure.bytes(&state, Some(self.current()));
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.versions.insert(
+            id,
+            Version {
+                state,
+                parent: Some(self.current),
+                children: Vec::new(),
+                redo_child: None,
+                bytes,
+            },
+        );
+
+        let current = self.version_mut(self.current);
+        current.children.push(id);
+        current.redo_child = Some(id);
+
+        self.leaves.remove(&self.current);
+        self.leaves.insert(id);
+        self.current = id;
+        self.memory_usage += bytes;
+
+        self.trim();
+
+        id
+    }
+
+    /// Moves to the parent of the current version and returns a reference to its state.
+    /// Returns `None` if current version has no parent
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    /// history.commit(1);
+    ///
+    /// assert_eq!(history.undo(), Some(&0));
+    /// assert_eq!(history.undo(), None);
+    /// ```
+    pub fn undo(&mut self) -> Option<&T> {
+        let parent = self.version(self.current).parent?;
+
+        let current = self.current;
+        self.version_mut(parent).redo_child = Some(current);
+        self.current = parent;
+
+        Some(self.current())
+    }
+
+    /// Moves to the most recently visited child of the current version and returns a reference to its state.
+    /// Returns `None` if current version has no children
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    /// history.commit(1);
+    /// history.undo();
+    ///
+    /// assert_eq!(history.redo(), Some(&1));
+    /// assert_eq!(history.redo(), None);
+    /// ```
+    pub fn redo(&mut self) -> Option<&T> {
+        self.current = self.version(self.current).redo_child?;
+
+        Some(self.current())
+    }
+
+    /// Checks out the specified `version` and returns a reference to its state.
+    /// Next commit will create a new branch on top of `version`.
+    /// Returns `None` if `version` does not exist or has been trimmed
+    ///
+    /// # Arguments
+    /// * `version`: id of the version to check out
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    /// let first = history.commit(1);
+    /// history.commit(2);
+    ///
+    /// assert_eq!(history.checkout(first), Some(&1));
+    /// assert_eq!(history.checkout(100), None);
+    /// ```
+    pub fn checkout(&mut self, version: usize) -> Option<&T> {
+        if !self.contains(version) {
+            return None;
+        }
+
+        // make redo follow the path to the checked out version
+        let mut child = version;
+        while let Some(parent) = self.version(child).parent {
+            self.version_mut(parent).redo_child = Some(child);
+            child = parent;
+        }
+
+        self.current = version;
+
+        Some(self.current())
+    }
+
+    /// Returns a reference to the state of the current version
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let history = History::init(0);
+    ///
+    /// assert_eq!(*history.current(), 0);
+    /// ```
+    pub fn current(&self) -> &T {
+        &self.version(self.current).state
+    }
+
+    /// Returns id of the current version
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    ///
+    /// assert_eq!(history.current_version(), 0);
+    /// ```
+    pub fn current_version(&self) -> usize {
+        self.current
+    }
+
+    /// Returns a reference to the state of the specified `version`.
+    /// Returns `None` if `version` does not exist or has been trimmed
+    ///
+    /// # Arguments
+    /// * `version`: id of the version
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    /// let version = history.commit(1);
+    ///
+    /// assert_eq!(history.get(version), Some(&1));
+    /// ```
+    pub fn get(&self, version: usize) -> Option<&T> {
+        self.versions.get(&version).map(|v| &v.state)
+    }
+
+    /// Returns true if the specified `version` exists in history
+    ///
+    /// # Arguments
+    /// * `version`: id of the version
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let history = History::init(0);
+    ///
+    /// assert_eq!(history.contains(0), true);
+    /// assert_eq!(history.contains(1), false);
+    /// ```
+    pub fn contains(&self, version: usize) -> bool {
+        self.get(version).is_some()
+    }
+
+    /// Returns id of the parent of the specified `version`.
+    /// Returns `None` if `version` is the oldest version or does not exist
+    ///
+    /// # Arguments
+    /// * `version`: id of the version
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    /// let version = history.commit(1);
+    ///
+    /// assert_eq!(history.parent(version), Some(0));
+    /// assert_eq!(history.parent(0), None);
+    /// ```
+    pub fn parent(&self, version: usize) -> Option<usize> {
+        self.versions.get(&version).and_then(|v| v.parent)
+    }
+
+    /// Returns ids of all versions committed on top of the specified `version`, in order of creation
+    ///
+    /// # Arguments
+    /// * `version`: id of the version
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    /// let first = history.commit(1);
+    /// history.undo();
+    /// let second = history.commit(2);
+    ///
+    /// assert_eq!(history.branches(0), vec![first, second]);
+    /// ```
+    pub fn branches(&self, version: usize) -> Vec<usize> {
+        match self.versions.get(&version) {
+            Some(v) => v.children.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns number of versions in the history
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::init(0);
+    /// history.commit(1);
+    ///
+    /// assert_eq!(history.size(), 2);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// Returns total number of bytes used by the stored states, counting only the unshared bytes of a version
+    /// for a history initialized by `with_shared_budget`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::with_budget(String::from("a"), 100, |state: &String| state.len());
+    /// history.commit(String::from("ab"));
+    ///
+    /// assert_eq!(history.memory_usage(), 3);
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    /// Sets a new memory budget and trims the history if necessary. `None` removes the budget
+    ///
+    /// # Arguments
+    /// * `memory_budget`: maximum number of bytes history is allowed to use
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::History;
+    ///
+    /// let mut history = History::with_budget(String::from("a"), 100, |state: &String| state.len());
+    /// history.commit(String::from("ab"));
+    ///
+    /// history.set_memory_budget(Some(2));
+    ///
+    /// assert_eq!(history.size(), 1);
+    /// ```
+    pub fn set_memory_budget(&mut self, memory_budget: Option<usize>) {
+        self.memory_budget = memory_budget;
+
+        self.trim();
+    }
+
+    // removes versions until the history fits in the memory budget
+    // versions that are not on the path from the oldest version to the current version are trimmed first, oldest first.
+    // such a version is trimmed once it is a leaf, and the only leaf on the path is the current version.
+    // after that the oldest version is trimmed repeatedly. current version is never trimmed. a trimmed version
+    // costs O(log n) to find
+    fn trim(&mut self) {
+        let budget = match self.memory_budget {
+            Some(budget) => budget,
+            None => return,
+        };
+
+        while self.memory_usage > budget {
+            // oldest leaf that is not on the path
+            let current = self.current;
+            let leaf = self.leaves.iter().copied().find(|&id| id != current);
+
+            match leaf {
+                Some(id) => self.remove_leaf(id),
+                None => {
+                    // history is a single path from root to current
+                    if self.root == self.current {
+                        break;
+                    }
+                    self.remove_root();
+                }
+            }
+        }
+
+        // give the slots of trimmed versions back
+        if self.versions.capacity() > 4 * self.versions.len() {
+            self.versions.shrink_to_fit();
+        }
+    }
+
+    fn remove_leaf(&mut self, id: usize) {
+        let version = self.versions.remove(&id).unwrap();
+        self.leaves.remove(&id);
+        let parent_id = version.parent.unwrap();
+        let parent = self.version_mut(parent_id);
+
+        parent.children.retain(|&child| child != id);
+        if parent.redo_child == Some(id) {
+            parent.redo_child = parent.children.last().copied();
+        }
+        if parent.children.is_empty() {
+            self.leaves.insert(parent_id);
+        }
+
+        self.memory_usage -= version.bytes;
+    }
+
+    fn remove_root(&mut self) {
+        let version = self.versions.remove(&self.root).unwrap();
+        let new_root = version.children[0];
+
+        // memory the new root shared with the old one is no longer shared, so the new root is charged in full
+        let root = self.version(new_root);
+        let bytes = self.measure.bytes(&root.state, None);
+        self.memory_usage = self.memory_usage - version.bytes - root.bytes + bytes;
+
+        let root = self.version_mut(new_root);
+        root.parent = None;
+        root.bytes = bytes;
+        self.root = new_root;
+    }
+
+    fn version(&self, id: usize) -> &Version<T> {
+        &self.versions[&id]
+    }
+
+    fn version_mut(&mut self, id: usize) -> &mut Version<T> {
+        self.versions.get_mut(&id).unwrap()
+    }
+}
+
+impl<T> MemoryFootprint for History<T> {
+    /// Heap memory owned by the states is not included, it is what `memory_usage` measures
+    fn footprint(&self) -> Footprint {
+        self.versions.values().fold(
+            Footprint::of::<T>(self.versions.len())
+                .with_hash_map(&self.versions)
+                .with_btree_set(&self.leaves),
+            |footprint, version| footprint.with_vec(&version.children),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structure_history_init() {
+        let history = History::init(0);
+
+        assert_eq!(history.size(), 1);
+        assert_eq!(history.current_version(), 0);
+        assert_eq!(history.parent(0), None);
+        assert_eq!(history.memory_usage(), std::mem::size_of::<i32>());
+    }
+
+    #[test]
+    fn structure_history_undo_redo() {
+        let mut history = History::init(0);
+        history.commit(1);
+        history.commit(2);
+
+        assert_eq!(history.undo(), Some(&1));
+        assert_eq!(history.undo(), Some(&0));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), Some(&1));
+        assert_eq!(history.redo(), Some(&2));
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn structure_history_branch() {
+        let mut history = History::init(0);
+        let v1 = history.commit(1);
+        let v2 = history.commit(2);
+        history.undo();
+        let v3 = history.commit(3);
+
+        assert_eq!(history.branches(v1), vec![v2, v3]);
+        assert_eq!(history.size(), 4);
+
+        // redo follows the latest branch
+        history.undo();
+        assert_eq!(history.redo(), Some(&3));
+
+        // checking out an older branch makes redo follow it
+        history.checkout(v2);
+        history.undo();
+        history.undo();
+        assert_eq!(history.redo(), Some(&1));
+        assert_eq!(history.redo(), Some(&2));
+    }
+
+    #[test]
+    fn structure_history_trim_dead_branches_first() {
+        let mut history = History::with_budget(0, 3, |_| 1);
+        let v1 = history.commit(1);
+        let v2 = history.commit(2);
+        history.undo();
+        let v3 = history.commit(3);
+
+        // v2 is not on the path to current version, so it goes first
+        assert_eq!(history.size(), 3);
+        assert!(!history.contains(v2));
+        assert_eq!(history.branches(v1), vec![v3]);
+
+        history.undo();
+        assert_eq!(history.redo(), Some(&3));
+    }
+
+    #[test]
+    fn structure_history_trim_oldest() {
+        let mut history = History::with_budget(0, 2, |_| 1);
+        history.commit(1);
+        history.commit(2);
+        history.commit(3);
+
+        assert_eq!(history.size(), 2);
+        assert_eq!(history.memory_usage(), 2);
+        assert_eq!(history.undo(), Some(&2));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.get(0), None);
+    }
+
+    #[test]
+    fn structure_history_trim_keeps_current() {
+        let mut history = History::with_budget(String::from("ab"), 1, |state: &String| state.len());
+
+        assert_eq!(history.size(), 1);
+
+        history.commit(String::from("abc"));
+
+        assert_eq!(history.size(), 1);
+        assert_eq!(*history.current(), String::from("abc"));
+    }
+
+    #[test]
+    fn structure_history_shared_budget() {
+        // states are their bytes in full and the bytes they do not share with the previous state
+        let size_over = |state: &(usize, usize), parent: Option<&(usize, usize)>| match parent {
+            Some(_) => state.1,
+            None => state.0,
+        };
+        let mut history = History::with_shared_budget((10, 10), 25, size_over);
+        history.commit((12, 2));
+        history.commit((14, 2));
+        let v3 = history.commit((20, 11));
+
+        assert_eq!(history.size(), 4);
+        assert_eq!(history.memory_usage(), 25);
+
+        // every trimmed root leaves its child charged in full, until the history fits again
+        let v4 = history.commit((21, 1));
+
+        assert_eq!(history.size(), 2);
+        assert_eq!(history.memory_usage(), 21);
+        assert_eq!(history.parent(v3), None);
+        assert_eq!(history.parent(v4), Some(v3));
+    }
+
+    #[test]
+    fn structure_history_trim_whole_dead_branch() {
+        let mut history = History::with_budget(0, 100, |_| 1);
+        let v1 = history.commit(1);
+        let v2 = history.commit(2);
+        history.checkout(0);
+        let v3 = history.commit(3);
+
+        // v1 becomes a leaf once v2 is trimmed, and goes before the root
+        history.set_memory_budget(Some(2));
+
+        assert_eq!(history.size(), 2);
+        assert!(!history.contains(v1) && !history.contains(v2));
+        assert_eq!(history.parent(v3), Some(0));
+        assert_eq!(history.branches(0), vec![v3]);
+    }
+
+    #[test]
+    fn structure_history_trim_releases_versions() {
+        let mut history = History::init(0);
+        for i in 1..1000 {
+            history.commit(i);
+            if i % 3 == 0 {
+                history.undo();
+            }
+        }
+        let full = history.footprint().allocated_bytes();
+
+        history.set_memory_budget(Some(std::mem::size_of::<i32>() * 4));
+
+        assert_eq!(history.size(), 4);
+        assert_eq!(*history.current(), 998);
+        assert!(history.footprint().allocated_bytes() * 10 < full);
+    }
+
+    #[test]
+    fn structure_history_set_memory_budget() {
+        let mut history = History::init(0);
+        for i in 1..10 {
+            history.commit(i);
+        }
+
+        history.set_memory_budget(Some(std::mem::size_of::<i32>() * 3));
+
+        assert_eq!(history.size(), 3);
+        assert_eq!(*history.current(), 9);
+
+        history.set_memory_budget(None);
+        history.commit(10);
+        assert_eq!(history.size(), 4);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+/// Jump pointers(binary lifting) over a functional graph, where every element has exactly one successor, like the
+/// states of a deterministic simulation. Every element keeps where it is after 1, 2, 4, ... 2^h steps, so any number of
+/// steps is a sum of at most log(n) jumps. A walk from any element ends up going around a cycle, which gives the
+/// walk the shape of the greek letter rho: a tail of distinct elements followed by the cycle. The length of the tail
+/// and of the cycle of every element are found while building, so walks of any length, far more steps than there are
+/// elements, are fast-forwarded around the cycle in O(log n) time
+///
+/// # Examples
+/// ```
+/// use rudac::structure::JumpPointers;
+///
+/// // 0 -> 1 -> 2 -> 3 -> 4 -> 2
+/// let jumps = JumpPointers::init(vec![1, 2, 3, 4, 2]);
+///
+/// assert_eq!(jumps.step(0, 3), 3);
+/// assert_eq!(jumps.step(0, 1_000_000_000_000), 4);
+/// assert_eq!(jumps.tail_length(0), 2);
+/// assert_eq!(jumps.cycle_length(0), 3);
+/// assert_eq!(jumps.cycle_entry(0), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JumpPointers {
+    // jumps[h][x] is the element 2^h steps after x, jumps[0] are the successors
+    jumps: Vec<Vec<usize>>,
+
+    // number of steps from every element to the first element of its walk that is on a cycle
+    tails: Vec<usize>,
+
+    // length of the cycle every element ends up on
+    cycles: Vec<usize>,
+}
+
+impl JumpPointers {
+    /// Builds the jump pointers of the elements `0..successors.len()` in O(n log n) time and space
+    ///
+    /// # Arguments
+    /// * `successors`: the element that follows every element
+    ///
+    /// # Panics
+    /// * panics if a successor is not one of the elements
+    pub fn init(successors: Vec<usize>) -> JumpPointers {
+        let size = successors.len();
+        if successors.iter().any(|&successor| successor >= size) {
+            panic!("Element does not exist");
+        }
+
+        let (tails, cycles) = rho(&successors);
+
+        // enough jumps to cover the walks shorter than the number of elements
+        let levels = (usize::BITS - size.leading_zeros()).max(1) as usize;
+        let mut jumps = vec![successors];
+        while jumps.len() < levels {
+            let previous = jumps.last().unwrap();
+            let level = previous.iter().map(|&middle| previous[middle]).collect();
+            jumps.push(level);
+        }
+
+        JumpPointers {
+            jumps,
+            tails,
+            cycles,
+        }
+    }
+
+    /// Builds the jump pointers of the elements `0..size`, asking `successor` for the element that follows every one
+    ///
+    /// # Arguments
+    /// * `size`: number of elements
+    /// * `successor`: the element that follows an element
+    ///
+    /// # Panics
+    /// * panics if a successor is not one of the elements
+    pub fn init_with<F: Fn(usize) -> usize>(size: usize, successor: F) -> JumpPointers {
+        JumpPointers::init((0..size).map(successor).collect())
+    }
+
+    /// Returns number of elements
+    pub fn size(&self) -> usize {
+        self.tails.len()
+    }
+
+    /// Returns true if there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.tails.is_empty()
+    }
+
+    fn check_element(&self, element: usize) {
+        if element >= self.size() {
+            panic!("Element does not exist");
+        }
+    }
+
+    /// Returns the element that follows `element`
+    ///
+    /// # Arguments
+    /// * `element`: the element
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn successor(&self, element: usize) -> usize {
+        self.check_element(element);
+
+        self.jumps[0][element]
+    }
+
+    // follows the jumps of the set bits of `steps`, which is less than 2^levels
+    fn lift(&self, mut element: usize, steps: usize) -> usize {
+        for (level, jumps) in self.jumps.iter().enumerate() {
+            if steps >> level & 1 == 1 {
+                element = jumps[element];
+            }
+        }
+
+        element
+    }
+
+    /// Returns where a walk from `element` is after `steps` steps
+    /// * Complexity: O(log n), however large `steps` is
+    ///
+    /// # Arguments
+    /// * `element`: start of the walk
+    /// * `steps`: number of steps
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn step(&self, element: usize, steps: u64) -> usize {
+        self.check_element(element);
+
+        let tail = self.tails[element];
+        if steps <= tail as u64 {
+            return self.lift(element, steps as usize);
+        }
+
+        // past the tail the walk goes around the cycle, whole rounds are skipped
+        let around = (steps - tail as u64) % self.cycles[element] as u64;
+        self.lift(self.lift(element, tail), around as usize)
+    }
+
+    /// Returns the number of steps a walk from `element` takes before it reaches a cycle, 0 if `element` is on a cycle
+    ///
+    /// # Arguments
+    /// * `element`: start of the walk
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn tail_length(&self, element: usize) -> usize {
+        self.check_element(element);
+
+        self.tails[element]
+    }
+
+    /// Returns the length of the cycle a walk from `element` ends up on
+    ///
+    /// # Arguments
+    /// * `element`: start of the walk
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn cycle_length(&self, element: usize) -> usize {
+        self.check_element(element);
+
+        self.cycles[element]
+    }
+
+    /// Returns the first element of the cycle that a walk from `element` reaches
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `element`: start of the walk
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn cycle_entry(&self, element: usize) -> usize {
+        self.check_element(element);
+
+        self.lift(element, self.tails[element])
+    }
+
+    /// Returns true if a walk from `element` comes back to it
+    ///
+    /// # Arguments
+    /// * `element`: the element
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn on_cycle(&self, element: usize) -> bool {
+        self.tail_length(element) == 0
+    }
+}
+
+// finds the tail and cycle length of every element by walking from every element until the walk meets an element
+// that is done or one of its own elements, which closes a cycle
+fn rho(successors: &[usize]) -> (Vec<usize>, Vec<usize>) {
+    const NEW: u8 = 0;
+    const WALKED: u8 = 1;
+    const DONE: u8 = 2;
+
+    let size = successors.len();
+    let mut state = vec![NEW; size];
+    let mut tails = vec![0; size];
+    let mut cycles = vec![0; size];
+    let mut walk = Vec::new();
+    for start in 0..size {
+        let mut element = start;
+        while state[element] == NEW {
+            state[element] = WALKED;
+            walk.push(element);
+            element = successors[element];
+        }
+
+        if state[element] == WALKED {
+            // the walk closed a cycle from `element` to its end
+            let first = walk.iter().position(|&walked| walked == element).unwrap();
+            let length = walk.len() - first;
+            for walked in walk.drain(first..) {
+                cycles[walked] = length;
+                state[walked] = DONE;
+            }
+        }
+
+        // the rest of the walk leads into a part that is done
+        while let Some(walked) = walk.pop() {
+            let next = successors[walked];
+            tails[walked] = tails[next] + 1;
+            cycles[walked] = cycles[next];
+            state[walked] = DONE;
+        }
+    }
+
+    (tails, cycles)
+}
+
+impl MemoryFootprint for JumpPointers {
+    fn footprint(&self) -> Footprint {
+        let footprint = Footprint::of::<usize>(self.size())
+            .with_vec(&self.jumps)
+            .with_vec(&self.tails)
+            .with_vec(&self.cycles);
+        self.jumps
+            .iter()
+            .fold(footprint, |footprint, jumps| footprint.with_vec(jumps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn structure_jump_pointers_matches_walk() {
+        let mut rng = SplitMix64::init(127);
+        for size in [1, 2, 7, 40, 129] {
+            let successors: Vec<usize> = (0..size)
+                .map(|_| rng.next_below(size as u64) as usize)
+                .collect();
+            let jumps = JumpPointers::init(successors.clone());
+            assert_eq!(jumps.size(), size);
+
+            for start in 0..size {
+                // the first repeated element of the walk is the cycle entry
+                let mut walk = vec![start];
+                let mut seen = vec![None; size];
+                seen[start] = Some(0);
+                let entry = loop {
+                    let next = successors[*walk.last().unwrap()];
+                    if let Some(index) = seen[next] {
+                        break index;
+                    }
+                    seen[next] = Some(walk.len());
+                    walk.push(next);
+                };
+                assert_eq!(jumps.tail_length(start), entry);
+                assert_eq!(jumps.cycle_length(start), walk.len() - entry);
+                assert_eq!(jumps.cycle_entry(start), walk[entry]);
+                assert_eq!(jumps.on_cycle(start), entry == 0);
+
+                let mut element = start;
+                for steps in 0..3 * size as u64 {
+                    assert_eq!(jumps.step(start, steps), element);
+                    element = successors[element];
+                }
+
+                let steps = rng.next_u64();
+                let around = (steps - entry as u64) % (walk.len() - entry) as u64;
+                assert_eq!(jumps.step(start, steps), walk[entry + around as usize]);
+            }
+        }
+    }
+
+    #[test]
+    fn structure_jump_pointers_init_with() {
+        // x -> x^2 + 1 modulo 10, the rho of Pollard's method: 3 -> 0 -> 1 -> 2 -> 5 -> 6 -> 7 -> 0
+        let jumps = JumpPointers::init_with(10, |x| (x * x + 1) % 10);
+
+        assert_eq!(jumps.successor(3), 0);
+        assert_eq!(jumps.step(3, 4), 5);
+        assert_eq!(jumps.tail_length(3), 1);
+        assert_eq!(jumps.cycle_entry(3), 0);
+        assert_eq!(jumps.cycle_length(3), 6);
+        assert_eq!(
+            jumps.step(3, u64::MAX),
+            jumps.step(3, 1 + (u64::MAX - 1) % 6)
+        );
+
+        assert!(JumpPointers::init(Vec::new()).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Element does not exist")]
+    fn structure_jump_pointers_panic_successor() {
+        JumpPointers::init(vec![0, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Element does not exist")]
+    fn structure_jump_pointers_panic_element() {
+        JumpPointers::init(vec![0, 1]).step(2, 1);
+    }
+}
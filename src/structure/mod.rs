@@ -0,0 +1,20 @@
+mod bit2d;
+mod consistent_hash_ring;
+mod disjoint_sparse_table;
+mod history;
+mod jump_pointers;
+mod persistent_disjoint_set;
+mod range_minimum;
+mod weighted_selector;
+mod zobrist;
+
+pub use bit2d::Bit2d;
+pub use consistent_hash_ring::ConsistentHashRing;
+pub use consistent_hash_ring::RingStats;
+pub use disjoint_sparse_table::DisjointSparseTable;
+pub use history::History;
+pub use jump_pointers::JumpPointers;
+pub use persistent_disjoint_set::PersistentDisjointSet;
+pub use range_minimum::RangeMinimum;
+pub use weighted_selector::WeightedSelector;
+pub use zobrist::ZobristHasher;
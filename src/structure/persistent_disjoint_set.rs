@@ -0,0 +1,426 @@
+use crate::util::{Footprint, MemoryFootprint};
+
+// join time of an element that is still a root
+const ROOT: usize = usize::MAX;
+
+/// A partially persistent disjoint set union keeps the sets of its elements at every point in time: the t-th call to
+/// `union` happens at time t, and queries may ask about any past time, like whether two elements were connected at
+/// time t or since when they are. Sets are joined by rank and paths are never compressed, so the links of an element
+/// never change once it stops being a root. Every element records the time it was linked under another one, and
+/// finding its root at time t follows the links made up to t, which takes O(log n) time. The last unions can be
+/// undone, which makes it a rollback disjoint set union as well
+///
+/// # Examples
+/// ```
+/// use rudac::structure::PersistentDisjointSet;
+///
+/// let mut disjoint_set = PersistentDisjointSet::init(4);
+/// disjoint_set.union(0, 1); // time 1
+/// disjoint_set.union(2, 3); // time 2
+/// disjoint_set.union(1, 3); // time 3
+///
+/// assert!(disjoint_set.connected(0, 2));
+/// assert!(!disjoint_set.connected_at(0, 2, 2));
+/// assert_eq!(disjoint_set.connected_since(0, 2), Some(3));
+/// assert_eq!(disjoint_set.set_size_at(3, 2), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PersistentDisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+
+    // time every element was linked under its parent, ROOT for roots
+    joined: Vec<usize>,
+
+    // sizes of the set of every element while it was a root, with the times they were reached
+    sizes: Vec<Vec<(usize, usize)>>,
+
+    // element linked under another one by every union with whether the rank of its new root grew, None for unions of
+    // elements that were already in the same set
+    links: Vec<Option<(usize, bool)>>,
+
+    time: usize,
+    set_count: usize,
+}
+
+impl PersistentDisjointSet {
+    /// Initializes `size` elements, each in a set of its own, at time 0
+    ///
+    /// # Arguments
+    /// * `size`: number of elements
+    pub fn init(size: usize) -> PersistentDisjointSet {
+        PersistentDisjointSet {
+            parent: (0..size).collect(),
+            rank: vec![0; size],
+            joined: vec![ROOT; size],
+            sizes: vec![vec![(0, 1)]; size],
+            links: Vec::new(),
+            time: 0,
+            set_count: size,
+        }
+    }
+
+    /// Adds an element in a set of its own and returns it. The element is alone in its set at every earlier time
+    pub fn make_set(&mut self) -> usize {
+        self.parent.push(self.parent.len());
+        self.rank.push(0);
+        self.joined.push(ROOT);
+        self.sizes.push(vec![(0, 1)]);
+        self.set_count += 1;
+
+        self.parent.len() - 1
+    }
+
+    /// Returns number of elements
+    pub fn size(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Returns true if there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the current number of sets
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// Returns the current time, the number of calls to `union` so far
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
+    fn check_element(&self, element: usize) {
+        if element >= self.size() {
+            panic!("Element does not exist");
+        }
+    }
+
+    /// Joins the sets of `first` and `second` and advances the time by one. Returns false if they were already in
+    /// the same set, the time still advances
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `first`: element of the first set
+    /// * `second`: element of the second set
+    ///
+    /// # Panics
+    /// * panics if `first` or `second` does not exist
+    pub fn union(&mut self, first: usize, second: usize) -> bool {
+        let first = self.find(first);
+        let second = self.find(second);
+        self.time += 1;
+        if first == second {
+            self.links.push(None);
+            return false;
+        }
+
+        let (root, child) = if self.rank[first] < self.rank[second] {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        let grown = self.rank[root] == self.rank[child];
+        if grown {
+            self.rank[root] += 1;
+        }
+        let size = self.set_size(root) + self.set_size(child);
+        self.parent[child] = root;
+        self.joined[child] = self.time;
+        self.sizes[root].push((self.time, size));
+        self.links.push(Some((child, grown)));
+        self.set_count -= 1;
+
+        true
+    }
+
+    /// Reverts the last call to `union` and moves the time back by one, as if the union never happened.
+    /// Returns false if there is no union to undo
+    /// * Complexity: O(1)
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::structure::PersistentDisjointSet;
+    ///
+    /// let mut disjoint_set = PersistentDisjointSet::init(3);
+    /// disjoint_set.union(0, 1);
+    /// disjoint_set.union(1, 2);
+    ///
+    /// assert!(disjoint_set.undo());
+    /// assert!(disjoint_set.connected(0, 1));
+    /// assert!(!disjoint_set.connected(1, 2));
+    /// assert_eq!(disjoint_set.time(), 1);
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let link = match self.links.pop() {
+            Some(link) => link,
+            None => return false,
+        };
+        self.time -= 1;
+
+        if let Some((child, grown)) = link {
+            let root = self.parent[child];
+            if grown {
+                self.rank[root] -= 1;
+            }
+            self.parent[child] = child;
+            self.joined[child] = ROOT;
+            self.sizes[root].pop();
+            self.set_count += 1;
+        }
+
+        true
+    }
+
+    /// Returns the representative of the current set of `element`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `element`: the element
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn find(&self, element: usize) -> usize {
+        self.find_at(element, self.time)
+    }
+
+    /// Returns the representative of the set of `element` at `time`, times past the current one are the current one
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `element`: the element
+    /// * `time`: point in time of the query
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn find_at(&self, element: usize, time: usize) -> usize {
+        self.check_element(element);
+
+        let mut element = element;
+        while self.joined[element] <= time {
+            element = self.parent[element];
+        }
+
+        element
+    }
+
+    /// Returns true if `first` and `second` are in the same set
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `first`: the first element
+    /// * `second`: the second element
+    ///
+    /// # Panics
+    /// * panics if `first` or `second` does not exist
+    pub fn connected(&self, first: usize, second: usize) -> bool {
+        self.connected_at(first, second, self.time)
+    }
+
+    /// Returns true if `first` and `second` were in the same set at `time`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `first`: the first element
+    /// * `second`: the second element
+    /// * `time`: point in time of the query
+    ///
+    /// # Panics
+    /// * panics if `first` or `second` does not exist
+    pub fn connected_at(&self, first: usize, second: usize, time: usize) -> bool {
+        self.find_at(first, time) == self.find_at(second, time)
+    }
+
+    /// Returns the earliest time `first` and `second` are in the same set, 0 for an element and itself.
+    /// None if they are not in the same set yet
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `first`: the first element
+    /// * `second`: the second element
+    ///
+    /// # Panics
+    /// * panics if `first` or `second` does not exist
+    pub fn connected_since(&self, first: usize, second: usize) -> Option<usize> {
+        self.check_element(first);
+        self.check_element(second);
+
+        // join times grow along the way to the root, so following the earlier link first makes both walks meet at
+        // their common ancestor, and the last link followed is the one that connected them
+        let (mut first, mut second) = (first, second);
+        let mut time = 0;
+        while first != second {
+            let element = if self.joined[first] < self.joined[second] {
+                &mut first
+            } else {
+                &mut second
+            };
+            time = self.joined[*element];
+            if time == ROOT {
+                return None;
+            }
+            *element = self.parent[*element];
+        }
+
+        Some(time)
+    }
+
+    /// Returns number of elements in the current set of `element`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `element`: the element
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn set_size(&self, element: usize) -> usize {
+        self.set_size_at(element, self.time)
+    }
+
+    /// Returns number of elements in the set of `element` at `time`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `element`: the element
+    /// * `time`: point in time of the query
+    ///
+    /// # Panics
+    /// * panics if `element` does not exist
+    pub fn set_size_at(&self, element: usize, time: usize) -> usize {
+        let sizes = &self.sizes[self.find_at(element, time)];
+        let reached = sizes.partition_point(|(since, _)| *since <= time);
+
+        sizes[reached - 1].1
+    }
+}
+
+impl MemoryFootprint for PersistentDisjointSet {
+    fn footprint(&self) -> Footprint {
+        let footprint = Footprint::of::<usize>(self.size())
+            .with_vec(&self.parent)
+            .with_vec(&self.rank)
+            .with_vec(&self.joined)
+            .with_vec(&self.sizes)
+            .with_vec(&self.links);
+        self.sizes
+            .iter()
+            .fold(footprint, |footprint, sizes| footprint.with_vec(sizes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    // labels every element with the smallest element of its set after the first `unions` unions
+    fn components(size: usize, unions: &[(usize, usize)]) -> Vec<usize> {
+        let mut label: Vec<usize> = (0..size).collect();
+        for &(first, second) in unions {
+            let (from, to) = (
+                label[first].max(label[second]),
+                label[first].min(label[second]),
+            );
+            for item in label.iter_mut() {
+                if *item == from {
+                    *item = to;
+                }
+            }
+        }
+
+        label
+    }
+
+    #[test]
+    fn structure_persistent_disjoint_set_matches_snapshots() {
+        let mut rng = SplitMix64::init(97);
+        let size = 40;
+        let mut disjoint_set = PersistentDisjointSet::init(size);
+        let mut unions = Vec::new();
+        for _ in 0..60 {
+            let first = rng.next_below(size as u64) as usize;
+            let second = rng.next_below(size as u64) as usize;
+            disjoint_set.union(first, second);
+            unions.push((first, second));
+        }
+        assert_eq!(disjoint_set.time(), 60);
+
+        let snapshots: Vec<Vec<usize>> = (0..=unions.len())
+            .map(|time| components(size, &unions[..time]))
+            .collect();
+        let last = &snapshots[unions.len()];
+        let mut labels = last.clone();
+        labels.sort_unstable();
+        labels.dedup();
+        assert_eq!(disjoint_set.set_count(), labels.len());
+
+        for (time, snapshot) in snapshots.iter().enumerate() {
+            for first in 0..size {
+                let count = snapshot
+                    .iter()
+                    .filter(|label| **label == snapshot[first])
+                    .count();
+                assert_eq!(disjoint_set.set_size_at(first, time), count);
+                for second in 0..size {
+                    let connected = snapshot[first] == snapshot[second];
+                    assert_eq!(disjoint_set.connected_at(first, second, time), connected);
+                }
+            }
+        }
+
+        for first in 0..size {
+            for second in 0..size {
+                let since = (0..snapshots.len())
+                    .find(|time| snapshots[*time][first] == snapshots[*time][second]);
+                assert_eq!(disjoint_set.connected_since(first, second), since);
+            }
+        }
+    }
+
+    #[test]
+    fn structure_persistent_disjoint_set_undo() {
+        let mut rng = SplitMix64::init(101);
+        let size = 30;
+        let mut disjoint_set = PersistentDisjointSet::init(size);
+        let mut unions = Vec::new();
+        for _ in 0..400 {
+            if rng.next_below(3) == 0 {
+                assert_eq!(disjoint_set.undo(), unions.pop().is_some());
+            } else {
+                let first = rng.next_below(size as u64) as usize;
+                let second = rng.next_below(size as u64) as usize;
+                disjoint_set.union(first, second);
+                unions.push((first, second));
+            }
+
+            let mut replayed = PersistentDisjointSet::init(size);
+            for &(first, second) in &unions {
+                replayed.union(first, second);
+            }
+            assert_eq!(disjoint_set, replayed);
+        }
+    }
+
+    #[test]
+    fn structure_persistent_disjoint_set_make_set() {
+        let mut disjoint_set = PersistentDisjointSet::default();
+        let first = disjoint_set.make_set();
+        let second = disjoint_set.make_set();
+
+        assert!(!disjoint_set.union(first, first));
+        assert!(disjoint_set.union(first, second));
+        assert!(!disjoint_set.union(second, first));
+        assert_eq!(disjoint_set.connected_since(first, second), Some(2));
+        assert_eq!(disjoint_set.set_size_at(second, 1), 1);
+        assert_eq!(disjoint_set.set_size(second), 2);
+        assert_eq!(disjoint_set.set_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Element does not exist")]
+    fn structure_persistent_disjoint_set_panic_element() {
+        let disjoint_set = PersistentDisjointSet::init(2);
+        disjoint_set.connected(0, 2);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::util::{Footprint, MemoryFootprint};
+use std::collections::HashMap;
+
+/// Range minimum queries in O(1) time after O(n) preprocessing, by Fischer and Heun. The items are split into
+/// blocks of about log(n) / 4 items. A sparse table over the block minima answers the part of a query made of whole
+/// blocks, and the rest is looked up in tables for every block shape: blocks with the same Cartesian tree have
+/// their minima at the same positions, and there are only about sqrt(n) different shapes.
+/// Ties are broken towards the leftmost item
+///
+/// # Examples
+/// ```
+/// use rudac::structure::RangeMinimum;
+///
+/// let minimum = RangeMinimum::init(vec![5, 2, 8, 2, 9, 1, 7]);
+///
+/// assert_eq!(minimum.min_index(0, 5), 1);
+/// assert_eq!(minimum.min(2, 5), &2);
+/// assert_eq!(minimum.min_index(0, 7), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangeMinimum<T> {
+    items: Vec<T>,
+    block_size: usize,
+
+    // sparse[h][b] is the index of the minimum of blocks b to b + 2^h - 1
+    sparse: Vec<Vec<usize>>,
+
+    // shape of every block, and for every shape the offset of the minimum of every range [i, j] in the block,
+    // at i * block_size + j
+    shapes: Vec<(usize, u64)>,
+    tables: HashMap<(usize, u64), Vec<u8>>,
+}
+
+impl<T: Ord> RangeMinimum<T> {
+    /// Builds the structure from `items` in O(n) time and space
+    ///
+    /// # Arguments
+    /// * `items`: the items
+    pub fn init(items: Vec<T>) -> RangeMinimum<T> {
+        let bits = usize::BITS - items.len().leading_zeros();
+        let block_size = usize::max(1, bits.div_ceil(4) as usize);
+
+        let mut shapes = Vec::new();
+        let mut tables = HashMap::new();
+        let mut minima = Vec::new();
+        for (block, chunk) in items.chunks(block_size).enumerate() {
+            // the pushes and pops of building the Cartesian tree with a stack, one bit each
+            let mut stack: Vec<&T> = Vec::with_capacity(block_size);
+            let mut signature = 0u64;
+            for item in chunk {
+                while stack.last().is_some_and(|top| *top > item) {
+                    stack.pop();
+                    signature <<= 1;
+                }
+                stack.push(item);
+                signature = signature << 1 | 1;
+            }
+            let shape = (chunk.len(), signature);
+            shapes.push(shape);
+            tables
+                .entry(shape)
+                .or_insert_with(|| block_table(chunk, block_size));
+            minima.push(block * block_size + tables[&shape][chunk.len() - 1] as usize);
+        }
+
+        let mut sparse = vec![minima];
+        let mut width = 1;
+        while 2 * width <= sparse[0].len() {
+            let previous = sparse.last().unwrap();
+            let level = (0..previous.len() - width)
+                .map(|block| leftmost_min(&items, previous[block], previous[block + width]))
+                .collect::<Vec<usize>>();
+            sparse.push(level);
+            width *= 2;
+        }
+
+        RangeMinimum {
+            items,
+            block_size,
+            sparse,
+            shapes,
+            tables,
+        }
+    }
+
+    /// Returns number of items
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    // index of the minimum of [start, last] within one block
+    fn in_block(&self, start: usize, last: usize) -> usize {
+        let block = start / self.block_size;
+        let offset = block * self.block_size;
+        let table = &self.tables[&self.shapes[block]];
+
+        offset + table[(start - offset) * self.block_size + last - offset] as usize
+    }
+
+    /// Returns the index of the leftmost minimum of the items in range [`start`, `end`)
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    ///
+    /// # Panics
+    /// * panics if the range is empty
+    /// * panics if `end` is greater than number of items
+    pub fn min_index(&self, start: usize, end: usize) -> usize {
+        if start >= end {
+            panic!("Range must not be empty");
+        }
+        if end > self.size() {
+            panic!("Index out of range");
+        }
+
+        let last = end - 1;
+        let (first_block, last_block) = (start / self.block_size, last / self.block_size);
+        if first_block == last_block {
+            return self.in_block(start, last);
+        }
+
+        let mut best = self.in_block(start, (first_block + 1) * self.block_size - 1);
+        if first_block + 1 < last_block {
+            // two overlapping power of two runs of blocks cover the blocks in between
+            let (from, to) = (first_block + 1, last_block - 1);
+            let level = (usize::BITS - 1 - (to - from + 1).leading_zeros()) as usize;
+            let left = self.sparse[level][from];
+            let right = self.sparse[level][to + 1 - (1 << level)];
+            best = leftmost_min(&self.items, best, leftmost_min(&self.items, left, right));
+        }
+
+        leftmost_min(
+            &self.items,
+            best,
+            self.in_block(last_block * self.block_size, last),
+        )
+    }
+
+    /// Returns the minimum of the items in range [`start`, `end`)
+    /// * Complexity: O(1)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    ///
+    /// # Panics
+    /// * panics if the range is empty
+    /// * panics if `end` is greater than number of items
+    pub fn min(&self, start: usize, end: usize) -> &T {
+        &self.items[self.min_index(start, end)]
+    }
+}
+
+// of two indices, the one with the smaller item, or the first one if they are equal
+fn leftmost_min<T: Ord>(items: &[T], first: usize, second: usize) -> usize {
+    if items[second] < items[first] {
+        second
+    } else {
+        first
+    }
+}
+
+// offsets of the leftmost minimum of every range [i, j] of a block, at i * block_size + j
+fn block_table<T: Ord>(chunk: &[T], block_size: usize) -> Vec<u8> {
+    let mut table = vec![0; block_size * block_size];
+    for start in 0..chunk.len() {
+        let mut best = start;
+        for last in start..chunk.len() {
+            if chunk[last] < chunk[best] {
+                best = last;
+            }
+            table[start * block_size + last] = best as u8;
+        }
+    }
+
+    table
+}
+
+impl<T> MemoryFootprint for RangeMinimum<T> {
+    fn footprint(&self) -> Footprint {
+        let footprint = Footprint::of::<T>(self.items.len())
+            .with_vec(&self.items)
+            .with_vec(&self.sparse)
+            .with_vec(&self.shapes)
+            .with_hash_map(&self.tables);
+        let footprint = self
+            .sparse
+            .iter()
+            .fold(footprint, |footprint, level| footprint.with_vec(level));
+
+        self.tables
+            .values()
+            .fold(footprint, |footprint, table| footprint.with_vec(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn naive(items: &[u64], start: usize, end: usize) -> usize {
+        (start..end).fold(start, |best, index| {
+            if items[index] < items[best] {
+                index
+            } else {
+                best
+            }
+        })
+    }
+
+    #[test]
+    fn structure_range_minimum_matches_scan() {
+        let mut rng = SplitMix64::init(81);
+        for size in [1, 2, 5, 16, 17, 100, 257] {
+            // few distinct values exercise the tie breaking
+            let items: Vec<u64> = (0..size).map(|_| rng.next_below(8)).collect();
+            let minimum = RangeMinimum::init(items.clone());
+            assert_eq!(minimum.size(), size);
+
+            for start in 0..size {
+                for end in start + 1..=size {
+                    assert_eq!(minimum.min_index(start, end), naive(&items, start, end));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn structure_range_minimum_shares_block_tables() {
+        // a repeating pattern has a single block shape apart from the last block
+        let items: Vec<u64> = (0..1 << 16).map(|index| index % 5).collect();
+        let minimum = RangeMinimum::init(items);
+
+        assert!(minimum.tables.len() <= 2);
+        assert_eq!(minimum.block_size, 5);
+        assert_eq!(minimum.min_index(9, 60000), 10);
+        assert!(RangeMinimum::<u64>::init(Vec::new()).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn structure_range_minimum_panic_out_of_range() {
+        RangeMinimum::init(vec![1, 2]).min_index(0, 3);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::tree::Fenwick;
+use crate::util::{Footprint, MemoryFootprint, Rng};
+
+/// Weighted random selection over a dynamic set of items.
+/// Item `i` is selected with probability `weight(i) / total()`. Weights can be changed at any time
+/// and every operation takes O(log n) time, as weights are kept in a Fenwick tree.
+///
+/// Items are identified by their index, which never changes. Removing an item sets its weight to zero.
+///
+/// # Examples
+/// ```
+/// use rudac::structure::WeightedSelector;
+/// use rudac::util::SplitMix64;
+///
+/// let mut selector = WeightedSelector::from_weights(&[1.0, 0.0, 3.0]);
+/// let mut rng = SplitMix64::init(42);
+///
+/// let mut counts = [0; 3];
+/// for _ in 0..4000 {
+///     counts[selector.sample(&mut rng).unwrap()] += 1;
+/// }
+///
+/// assert_eq!(counts[1], 0);
+/// assert!(counts[2] > 2 * counts[0]);
+///
+/// selector.remove(2);
+/// assert_eq!(selector.sample(&mut rng), Some(0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedSelector {
+    weights: Vec<f64>,
+    fenwick: Fenwick<f64>,
+
+    // number of items with positive weight
+    positive: usize,
+}
+
+impl WeightedSelector {
+    /// Initializes an empty selector
+    pub fn init() -> WeightedSelector {
+        WeightedSelector {
+            weights: Vec::new(),
+            fenwick: Fenwick::init(0),
+            positive: 0,
+        }
+    }
+
+    /// Initializes a selector with items having `weights`, in O(n)
+    ///
+    /// # Arguments
+    /// * `weights`: weights of the items
+    ///
+    /// # Panics
+    /// * panics if any weight is negative or not finite
+    pub fn from_weights(weights: &[f64]) -> WeightedSelector {
+        for weight in weights {
+            WeightedSelector::check_weight(*weight);
+        }
+
+        WeightedSelector {
+            weights: weights.to_vec(),
+            fenwick: Fenwick::from_slice(weights),
+            positive: weights.iter().filter(|w| **w > 0.0).count(),
+        }
+    }
+
+    /// Returns number of items, including removed ones
+    pub fn size(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Returns true if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Returns sum of all weights
+    pub fn total(&self) -> f64 {
+        if self.positive == 0 {
+            return 0.0;
+        }
+
+        self.fenwick.total()
+    }
+
+    /// Returns weight of item `index`
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn weight(&self, index: usize) -> f64 {
+        self.weights[index]
+    }
+
+    /// Adds a new item with `weight` and returns its index
+    ///
+    /// # Arguments
+    /// * `weight`: weight of the item
+    ///
+    /// # Panics
+    /// * panics if `weight` is negative or not finite
+    pub fn push(&mut self, weight: f64) -> usize {
+        WeightedSelector::check_weight(weight);
+
+        self.weights.push(weight);
+        self.fenwick.push(weight);
+        if weight > 0.0 {
+            self.positive += 1;
+        }
+
+        self.weights.len() - 1
+    }
+
+    /// Sets weight of item `index` to `weight`
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    /// * `weight`: new weight of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    /// * panics if `weight` is negative or not finite
+    pub fn set_weight(&mut self, index: usize, weight: f64) {
+        WeightedSelector::check_weight(weight);
+        if index >= self.weights.len() {
+            panic!("Index out of range");
+        }
+
+        let old = self.weights[index];
+        self.weights[index] = weight;
+        self.fenwick.add(index, weight - old);
+
+        match (old > 0.0, weight > 0.0) {
+            (false, true) => self.positive += 1,
+            (true, false) => self.positive -= 1,
+            _ => (),
+        }
+
+        // drop accumulated rounding errors once nothing is left
+        if self.positive == 0 {
+            self.fenwick = Fenwick::init(self.weights.len());
+        }
+    }
+
+    /// Removes item `index` from selection by setting its weight to zero. Returns its previous weight
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn remove(&mut self, index: usize) -> f64 {
+        let old = self.weight(index);
+        self.set_weight(index, 0.0);
+
+        old
+    }
+
+    /// Returns index of a random item, selected proportionally to its weight.
+    /// Returns `None` if all weights are zero
+    ///
+    /// # Arguments
+    /// * `rng`: source of randomness
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<usize> {
+        if self.positive == 0 {
+            return None;
+        }
+
+        let target = rng.next_f64() * self.fenwick.total();
+        let index = std::cmp::min(self.fenwick.search(target), self.weights.len() - 1);
+        if self.weights[index] > 0.0 {
+            return Some(index);
+        }
+
+        // rounding errors of the tree can only land next to a boundary, choose the closest positive item
+        (0..index)
+            .rev()
+            .chain(index + 1..self.weights.len())
+            .find(|i| self.weights[*i] > 0.0)
+    }
+
+    fn check_weight(weight: f64) {
+        if !weight.is_finite() || weight < 0.0 {
+            panic!("Weight must be finite and non-negative");
+        }
+    }
+}
+
+impl Default for WeightedSelector {
+    fn default() -> Self {
+        WeightedSelector::init()
+    }
+}
+
+impl MemoryFootprint for WeightedSelector {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<f64>(self.weights.len())
+            .with_vec(&self.weights)
+            .with(self.fenwick.footprint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+
+    #[test]
+    fn structure_weighted_selector_distribution() {
+        let mut selector = WeightedSelector::init();
+        for weight in [1.0, 2.0, 3.0, 4.0].iter() {
+            selector.push(*weight);
+        }
+
+        let mut rng = SplitMix64::init(1);
+        let mut counts = [0.0; 4];
+        let samples = 100000;
+        for _ in 0..samples {
+            counts[selector.sample(&mut rng).unwrap()] += 1.0;
+        }
+
+        for (i, count) in counts.iter().enumerate() {
+            let expected = (i + 1) as f64 / 10.0;
+            assert!((count / samples as f64 - expected).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn structure_weighted_selector_updates() {
+        let mut selector = WeightedSelector::from_weights(&[5.0, 5.0, 5.0]);
+        let mut rng = SplitMix64::init(2);
+
+        selector.set_weight(0, 0.0);
+        assert_eq!(selector.remove(1), 5.0);
+        assert_eq!(selector.total(), 5.0);
+
+        for _ in 0..100 {
+            assert_eq!(selector.sample(&mut rng), Some(2));
+        }
+
+        selector.remove(2);
+        assert_eq!(selector.total(), 0.0);
+        assert_eq!(selector.sample(&mut rng), None);
+
+        selector.set_weight(1, 0.5);
+        assert_eq!(selector.sample(&mut rng), Some(1));
+    }
+
+    #[test]
+    fn structure_weighted_selector_many_updates() {
+        let mut rng = SplitMix64::init(3);
+        let mut selector = WeightedSelector::from_weights(&vec![0.1; 1000]);
+
+        for step in 0..10000 {
+            selector.set_weight(step % 1000, rng.next_f64());
+        }
+
+        let naive: f64 = (0..1000).map(|i| selector.weight(i)).sum();
+        assert!((selector.total() - naive).abs() < 1e-9);
+
+        for _ in 0..1000 {
+            let index = selector.sample(&mut rng).unwrap();
+            assert!(selector.weight(index) > 0.0);
+        }
+    }
+
+    #[test]
+    fn structure_weighted_selector_empty() {
+        let selector = WeightedSelector::init();
+
+        assert!(selector.is_empty());
+        assert_eq!(selector.sample(&mut SplitMix64::init(0)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Weight must be finite and non-negative")]
+    fn structure_weighted_selector_negative_weight() {
+        WeightedSelector::init().push(-1.0);
+    }
+}
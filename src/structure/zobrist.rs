@@ -0,0 +1,239 @@
+use crate::util::{Rng, SplitMix64};
+
+/// Zobrist hashing of boards with `N` squares, each empty or holding one of a fixed number of piece kinds.
+/// Every piece on every square gets a random 64 bit key and the hash of a board is the XOR of the keys of its
+/// pieces, so placing, removing or moving a piece updates the hash in O(1) instead of hashing the whole board again.
+/// Another key marks the side to move. Game states that keep their hash up to date this way can use it as their
+/// `Hash`, which makes lookups in a `TranspositionTable` cheap
+///
+/// # Examples
+/// ```
+/// use rudac::algo::game::{alpha_beta_with_table, GameState, TranspositionTable};
+/// use rudac::structure::ZobristHasher;
+/// use std::hash::{Hash, Hasher};
+/// use std::rc::Rc;
+///
+/// // a row of 6 cells, players put their piece on an empty cell and the first to own two adjacent cells wins
+/// #[derive(Clone)]
+/// struct Row {
+///     cells: [Option<usize>; 6],
+///     turn: usize,
+///     hash: u64,
+///     zobrist: Rc<ZobristHasher<6>>,
+/// }
+///
+/// impl PartialEq for Row {
+///     fn eq(&self, other: &Row) -> bool {
+///         self.cells == other.cells && self.turn == other.turn
+///     }
+/// }
+///
+/// impl Eq for Row {}
+///
+/// impl Hash for Row {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         state.write_u64(self.hash);
+///     }
+/// }
+///
+/// impl GameState for Row {
+///     type Move = usize;
+///
+///     fn moves(&self) -> Vec<usize> {
+///         if self.evaluate() != 0.0 {
+///             return Vec::new();
+///         }
+///         (0..6).filter(|&cell| self.cells[cell].is_none()).collect()
+///     }
+///
+///     fn apply(&self, &cell: &usize) -> Row {
+///         let mut next = self.clone();
+///         next.cells[cell] = Some(self.turn);
+///         next.hash = self.zobrist.toggle_side(self.zobrist.toggle(self.hash, self.turn, cell));
+///         next.turn = 1 - self.turn;
+///         next
+///     }
+///
+///     fn evaluate(&self) -> f64 {
+///         match (0..5).find(|&cell| self.cells[cell].is_some() && self.cells[cell] == self.cells[cell + 1]) {
+///             Some(cell) if self.cells[cell] == Some(self.turn) => 1.0,
+///             Some(_) => -1.0,
+///             None => 0.0,
+///         }
+///     }
+/// }
+///
+/// let zobrist = Rc::new(ZobristHasher::with_seed(2, 7));
+/// let row = Row { cells: [None; 6], turn: 0, hash: zobrist.hash(&[None; 6]), zobrist };
+///
+/// // the hash kept up to date move by move matches the hash of the board, the first player is to move again
+/// let after = row.apply(&1).apply(&4);
+/// assert_eq!(after.hash, after.zobrist.hash(&after.cells));
+///
+/// let mut table = TranspositionTable::init();
+/// let evaluation = alpha_beta_with_table(&row, 6, &mut table);
+/// assert_eq!(evaluation.value, 1.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZobristHasher<const N: usize> {
+    // keys of every piece kind on every square
+    keys: Vec<[u64; N]>,
+
+    // marks the second player to move
+    side: u64,
+}
+
+impl<const N: usize> ZobristHasher<N> {
+    /// Initializes keys for `pieces` kinds of pieces on `N` squares, drawn from `rng`
+    ///
+    /// # Arguments
+    /// * `pieces`: number of piece kinds
+    /// * `rng`: source of the keys
+    pub fn init<R: Rng>(pieces: usize, rng: &mut R) -> ZobristHasher<N> {
+        let keys = (0..pieces)
+            .map(|_| {
+                let mut squares = [0; N];
+                for key in squares.iter_mut() {
+                    *key = rng.next_u64();
+                }
+                squares
+            })
+            .collect();
+
+        ZobristHasher {
+            keys,
+            side: rng.next_u64(),
+        }
+    }
+
+    /// Initializes keys for `pieces` kinds of pieces on `N` squares, drawn from a generator seeded with `seed`.
+    /// Hashers with equal seeds have equal keys
+    ///
+    /// # Arguments
+    /// * `pieces`: number of piece kinds
+    /// * `seed`: seed of the keys
+    pub fn with_seed(pieces: usize, seed: u64) -> ZobristHasher<N> {
+        ZobristHasher::init(pieces, &mut SplitMix64::init(seed))
+    }
+
+    /// Returns number of piece kinds
+    pub fn pieces(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the key of `piece` on `square`
+    ///
+    /// # Panics
+    /// * panics if `piece` or `square` is out of range
+    pub fn key(&self, piece: usize, square: usize) -> u64 {
+        self.keys[piece][square]
+    }
+
+    /// Returns the hash of `board`, where every square holds the kind of its piece or None if it is empty.
+    /// The hash is that of the first player to move
+    /// * Complexity: O(N)
+    ///
+    /// # Arguments
+    /// * `board`: the pieces on the squares
+    ///
+    /// # Panics
+    /// * panics if a piece is out of range
+    pub fn hash(&self, board: &[Option<usize>; N]) -> u64 {
+        board
+            .iter()
+            .enumerate()
+            .filter_map(|(square, piece)| piece.map(|piece| self.key(piece, square)))
+            .fold(0, |hash, key| hash ^ key)
+    }
+
+    /// Returns `hash` with `piece` put on `square`, or taken off it if it is there
+    ///
+    /// # Arguments
+    /// * `hash`: hash of the board before the change
+    /// * `piece`: kind of the piece
+    /// * `square`: square of the piece
+    pub fn toggle(&self, hash: u64, piece: usize, square: usize) -> u64 {
+        hash ^ self.key(piece, square)
+    }
+
+    /// Returns `hash` with `piece` moved from square `from` to square `to`
+    ///
+    /// # Arguments
+    /// * `hash`: hash of the board before the move
+    /// * `piece`: kind of the moved piece
+    /// * `from`: square the piece leaves
+    /// * `to`: square the piece moves to
+    pub fn move_piece(&self, hash: u64, piece: usize, from: usize, to: usize) -> u64 {
+        hash ^ self.key(piece, from) ^ self.key(piece, to)
+    }
+
+    /// Returns `hash` with the other player to move
+    ///
+    /// # Arguments
+    /// * `hash`: hash of the position before the turn passes
+    pub fn toggle_side(&self, hash: u64) -> u64 {
+        hash ^ self.side
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn structure_zobrist_incremental_matches_full() {
+        let zobrist: ZobristHasher<64> = ZobristHasher::with_seed(12, 3);
+        let mut rng = SplitMix64::init(4);
+        let mut board = [None; 64];
+        let mut hash = zobrist.hash(&board);
+        assert_eq!(hash, 0);
+
+        for _ in 0..5000 {
+            let square = rng.next_below(64) as usize;
+            match board[square] {
+                Some(piece) => {
+                    // move the piece to an empty square or take it off
+                    let to = rng.next_below(64) as usize;
+                    if board[to].is_none() {
+                        hash = zobrist.move_piece(hash, piece, square, to);
+                        board[to] = Some(piece);
+                    } else {
+                        hash = zobrist.toggle(hash, piece, square);
+                    }
+                    board[square] = None;
+                }
+                None => {
+                    let piece = rng.next_below(12) as usize;
+                    hash = zobrist.toggle(hash, piece, square);
+                    board[square] = Some(piece);
+                }
+            }
+            assert_eq!(hash, zobrist.hash(&board));
+        }
+    }
+
+    #[test]
+    fn structure_zobrist_keys() {
+        let zobrist: ZobristHasher<9> = ZobristHasher::with_seed(2, 5);
+        assert_eq!(zobrist.pieces(), 2);
+
+        let mut keys: HashSet<u64> = (0..2)
+            .flat_map(|piece| (0..9).map(move |square| (piece, square)))
+            .map(|(piece, square)| zobrist.key(piece, square))
+            .collect();
+        keys.insert(zobrist.toggle_side(0));
+        assert_eq!(keys.len(), 19);
+
+        assert_eq!(zobrist.toggle_side(zobrist.toggle_side(17)), 17);
+        let same: ZobristHasher<9> = ZobristHasher::with_seed(2, 5);
+        assert_eq!(same.key(1, 8), zobrist.key(1, 8));
+    }
+
+    #[test]
+    #[should_panic]
+    fn structure_zobrist_panic_piece_out_of_range() {
+        let zobrist: ZobristHasher<4> = ZobristHasher::with_seed(1, 0);
+        zobrist.key(1, 0);
+    }
+}
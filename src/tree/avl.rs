@@ -1,6 +1,22 @@
-use std::collections::VecDeque;
-
-struct Node<K: std::cmp::Ord, V> {
+use crate::codec::{read_header, write_header, Decode, Encode, Reader, Writer, MAX_DEPTH};
+use crate::replay::{Op, Replayable};
+use crate::tree::batch;
+use crate::tree::layout::NIL;
+use crate::tree::{Boxed, Layout, Soa};
+use crate::util::stats::{self, Recorder};
+#[cfg(feature = "stats")]
+use crate::util::OpStats;
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+#[derive(Clone)]
+pub struct Node<K: core::cmp::Ord, V> {
     key: Option<K>,
     value: Option<V>,
     height: usize,
@@ -9,7 +25,7 @@ struct Node<K: std::cmp::Ord, V> {
     right_child: Option<Box<Node<K, V>>>,
 }
 
-impl<K: std::cmp::Ord, V> Node<K, V> {
+impl<K: core::cmp::Ord, V> Node<K, V> {
     fn init(key: K, value: V, height: usize, size: usize) -> Node<K, V> {
         Node {
             key: Some(key),
@@ -46,7 +62,7 @@ impl<K: std::cmp::Ord, V> Node<K, V> {
     }
 
     fn _max_height(node1: &Option<Box<Node<K, V>>>, node2: &Option<Box<Node<K, V>>>) -> i64 {
-        std::cmp::max(Node::height(node1), Node::height(node2))
+        core::cmp::max(Node::height(node1), Node::height(node2))
     }
 
     fn height(node: &Option<Box<Node<K, V>>>) -> i64 {
@@ -69,7 +85,8 @@ impl<K: std::cmp::Ord, V> Node<K, V> {
 }
 
 /// An AVL tree is a self-balancing binary search tree.
-/// For lookup-intensive applications, AVL trees are faster than red–black trees because they are more strictly balanced
+/// For lookup-intensive applications, AVL trees are faster than red–black trees because they are more strictly balanced.
+/// The node layout is chosen by the last type parameter, `Boxed` by default or `Soa` for an index based arena
 ///
 /// # Examples
 /// ```
@@ -94,27 +111,53 @@ impl<K: std::cmp::Ord, V> Node<K, V> {
 /// avl_tree.delete(&4);
 /// assert_eq!(avl_tree.get(&4), None);
 /// ```
-pub struct AVL<K: std::cmp::Ord, V> {
-    root: Option<Box<Node<K, V>>>,
+pub struct AVL<K: core::cmp::Ord, V, L: Layout = Boxed> {
+    root: L::Avl<K, V>,
+    stats: Recorder,
 }
 
-impl<K: std::cmp::Ord, V> AVL<K, V> {
+impl<K: core::cmp::Ord, V, L: Layout> AVL<K, V, L>
+where
+    L::Avl<K, V>: Default,
+{
     /// Initializes an empty AVL tree
     ///
     /// # Examples
     /// ```
-    /// use rudac::tree::AVL;
+    /// use rudac::tree::{Soa, AVL};
     ///
     /// let usize_to_string = AVL::<usize, String>::init();
     ///
     /// let string_to_usize = AVL::<String, usize>::init();
     ///
     /// let string_to_string = AVL::<String, String>::init();
+    ///
+    /// // nodes are stored in a structure of arrays
+    /// let soa_usize_to_usize = AVL::<usize, usize, Soa>::init();
     /// ```
-    pub fn init() -> AVL<K, V> {
-        AVL { root: None }
+    pub fn init() -> AVL<K, V, L> {
+        AVL {
+            root: Default::default(),
+            stats: Recorder::default(),
+        }
+    }
+}
+
+#[cfg(feature = "stats")]
+impl<K: core::cmp::Ord, V, L: Layout> AVL<K, V, L> {
+    /// Returns counts of the operations performed by the tree since it was created or since the last `reset_stats`.
+    /// Available with the `stats` feature
+    pub fn stats(&self) -> OpStats {
+        self.stats.stats()
     }
 
+    /// Sets all operation counts to zero. Available with the `stats` feature
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+}
+
+impl<K: core::cmp::Ord, V> AVL<K, V> {
     /// Returns `true` if tree is empty and `false` otherwise
     ///
     /// # Examples
@@ -196,6 +239,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(avl_tree.get(&1), None);
     /// ```
     pub fn get(&self, key: &K) -> Option<&V> {
+        let _stats = self.stats.scope();
         AVL::_get(&self.root, key)
     }
 
@@ -206,6 +250,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
 
         let node_ref = node.as_ref().unwrap();
 
+        stats::comparison();
         if *key < *node_ref.key() {
             AVL::_get(&node_ref.left_child, key)
         } else if *key > *node_ref.key() {
@@ -238,16 +283,19 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(*avl_tree.get(&1).unwrap(), 11);
     /// ```
     pub fn insert(&mut self, key: K, value: V) {
+        let _stats = self.stats.scope();
         self.root = AVL::_insert(self.root.take(), key, value);
     }
 
     fn _insert(node: Option<Box<Node<K, V>>>, key: K, value: V) -> Option<Box<Node<K, V>>> {
         if node.is_none() {
+            stats::allocation();
             return Some(Box::new(Node::init(key, value, 0, 1)));
         }
 
         let mut node_ref = node.unwrap();
 
+        stats::comparison();
         if key < *node_ref.key() {
             node_ref.left_child = AVL::_insert(node_ref.left_child, key, value);
         } else if key > *node_ref.key() {
@@ -263,6 +311,129 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
         Some(AVL::balance(node_ref))
     }
 
+    /// Inserts a run of items sorted by key into the tree. The run is built into a balanced tree and merged
+    /// with split and join in O(m log(n/m + 1)) time for m items, which is much faster than m inserts for bulk loads.
+    /// Like `insert`, an item replaces the value of an existing key, and a later item in the run replaces an earlier one
+    ///
+    /// # Arguments
+    /// * `sorted_items`: `(key, value)` pairs in ascending order of keys
+    ///
+    /// # Panics
+    /// * panics if items are not sorted by key
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::AVL;
+    ///
+    /// let mut avl_tree = AVL::<usize, usize>::init();
+    /// avl_tree.insert(5, 0);
+    ///
+    /// avl_tree.insert_batch((0..10).map(|key| (key, key * 10)));
+    /// assert_eq!(avl_tree.size(), 10);
+    /// assert_eq!(avl_tree.get(&5), Some(&50));
+    /// ```
+    pub fn insert_batch<I: IntoIterator<Item = (K, V)>>(&mut self, sorted_items: I) {
+        let _stats = self.stats.scope();
+        let items = batch::sorted_run(sorted_items);
+        let size = items.len();
+        let batch = AVL::build(&mut items.into_iter(), size);
+
+        self.root = AVL::union(batch, self.root.take());
+    }
+
+    // builds a perfectly balanced tree out of the next `size` sorted items
+    fn build<I: Iterator<Item = (K, V)>>(items: &mut I, size: usize) -> Option<Box<Node<K, V>>> {
+        if size == 0 {
+            return None;
+        }
+
+        let left_child = AVL::build(items, size / 2);
+        let (key, value) = items.next().unwrap();
+        stats::allocation();
+        let mut node = Box::new(Node::init(key, value, 0, 1));
+        node.left_child = left_child;
+        node.right_child = AVL::build(items, size - size / 2 - 1);
+
+        node.update_height();
+        node.update_size();
+
+        Some(node)
+    }
+
+    // merges two trees, entries of `batch` replace entries of `tree` with equal keys
+    fn union(
+        batch: Option<Box<Node<K, V>>>,
+        tree: Option<Box<Node<K, V>>>,
+    ) -> Option<Box<Node<K, V>>> {
+        match batch {
+            None => tree,
+            Some(mut node) => {
+                if tree.is_none() {
+                    return Some(node);
+                }
+
+                let (tree_left, tree_right) = AVL::split(tree, node.key());
+                let left_child = AVL::union(node.left_child.take(), tree_left);
+                let right_child = AVL::union(node.right_child.take(), tree_right);
+
+                Some(AVL::join(left_child, node, right_child))
+            }
+        }
+    }
+
+    // splits the tree into keys less than and greater than `key`, the node holding `key` is dropped
+    fn split(node: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>) {
+        match node {
+            None => (None, None),
+            Some(mut _node) => {
+                let left_child = _node.left_child.take();
+                let right_child = _node.right_child.take();
+
+                stats::comparison();
+                if *key < *_node.key() {
+                    let (less, greater) = AVL::split(left_child, key);
+                    (less, Some(AVL::join(greater, _node, right_child)))
+                } else if *key > *_node.key() {
+                    let (less, greater) = AVL::split(right_child, key);
+                    (Some(AVL::join(left_child, _node, less)), greater)
+                } else {
+                    (left_child, right_child)
+                }
+            }
+        }
+    }
+
+    // links two trees through `node`, all keys of `left` are less and all keys of `right` are greater than its key.
+    // the taller tree is descended along its inner spine until the heights match, then rebalanced on the way up
+    fn join(
+        left: Option<Box<Node<K, V>>>,
+        mut node: Box<Node<K, V>>,
+        right: Option<Box<Node<K, V>>>,
+    ) -> Box<Node<K, V>> {
+        let left_height = Node::height(&left);
+        let right_height = Node::height(&right);
+
+        if left_height > right_height + 1 {
+            let mut left = left.unwrap();
+            left.right_child = Some(AVL::join(left.right_child.take(), node, right));
+            left.update_height();
+            left.update_size();
+            AVL::balance(left)
+        } else if right_height > left_height + 1 {
+            let mut right = right.unwrap();
+            right.left_child = Some(AVL::join(left, node, right.left_child.take()));
+            right.update_height();
+            right.update_size();
+            AVL::balance(right)
+        } else {
+            node.left_child = left;
+            node.right_child = right;
+            node.update_height();
+            node.update_size();
+            node
+        }
+    }
+
     fn balance(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
         if Node::balance_factor(&node) < -1 {
             if Node::balance_factor(node.right_child.as_ref().unwrap()) > 0 {
@@ -279,6 +450,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     }
 
     fn rotate_right(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        stats::rotation();
         let mut y = node.left_child.unwrap();
         node.left_child = y.right_child;
         y.size = node.size;
@@ -292,6 +464,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     }
 
     fn rotate_left(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        stats::rotation();
         let mut y = node.right_child.unwrap();
         node.right_child = y.left_child;
         y.size = node.size;
@@ -325,6 +498,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(avl_tree.get(&1), None);
     /// ```
     pub fn delete(&mut self, key: &K) {
+        let _stats = self.stats.scope();
         if !self.is_empty() {
             self.root = AVL::_delete(self.root.take(), key);
         }
@@ -334,6 +508,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
         match node {
             None => node,
             Some(mut _node) => {
+                stats::comparison();
                 if *key < *_node.key() {
                     _node.left_child = AVL::_delete(_node.left_child.take(), key);
                 } else if *key > *_node.key() {
@@ -361,6 +536,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
         match node {
             Some(_node) => {
                 if _node.left_child.is_none() {
+                    stats::allocation();
                     Box::new(Node::init(_node.get_key(), _node.get_value(), 0, 1))
                 } else {
                     AVL::_min(&mut _node.left_child)
@@ -391,6 +567,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(avl_tree.get(&2), None);
     /// ```
     pub fn delete_min(&mut self) {
+        let _stats = self.stats.scope();
         if !self.is_empty() {
             self.root = AVL::_delete_min(self.root.take().unwrap());
         }
@@ -430,6 +607,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(avl_tree.get(&3), None);
     /// ```
     pub fn delete_max(&mut self) {
+        let _stats = self.stats.scope();
         if !self.is_empty() {
             self.root = AVL::_delete_max(self.root.take().unwrap());
         }
@@ -468,6 +646,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(avl_tree.floor(&0), None);
     /// ```
     pub fn floor(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
         AVL::_floor(&self.root, key)
     }
 
@@ -476,6 +655,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
             return None;
         }
         let node_ref = node.as_ref().unwrap();
+        stats::comparison();
         if *key == *node_ref.key() {
             return Some(node_ref.key());
         } else if *key < *node_ref.key() {
@@ -509,6 +689,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(avl_tree.ceiling(&8), None);
     /// ```
     pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
         AVL::_ceiling(&self.root, key)
     }
 
@@ -517,6 +698,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
             return None;
         }
         let node_ref = node.as_ref().unwrap();
+        stats::comparison();
         if *key == *node_ref.key() {
             return Some(node_ref.key());
         } else if *key > *node_ref.key() {
@@ -648,6 +830,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(avl_tree.rank(&99), 98);
     /// ```
     pub fn rank(&self, key: &K) -> usize {
+        let _stats = self.stats.scope();
         AVL::_rank(&self.root, key)
     }
     fn _rank(node: &Option<Box<Node<K, V>>>, key: &K) -> usize {
@@ -655,6 +838,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
             return 0;
         }
         let node_ref = node.as_ref().unwrap();
+        stats::comparison();
         if *key < *node_ref.key() {
             AVL::_rank(&node_ref.left_child, key)
         } else if *key > *node_ref.key() {
@@ -703,6 +887,22 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
         AVL::_keys_in_order(&node_ref.right_child, keys);
     }
 
+    // returns the entries of the tree in order of their keys
+    fn entries(&self) -> Vec<(&K, &V)> {
+        let mut entries = Vec::with_capacity(self.size());
+        AVL::_entries_in_order(&self.root, &mut entries);
+
+        entries
+    }
+
+    fn _entries_in_order<'a>(node: &'a Option<Box<Node<K, V>>>, entries: &mut Vec<(&'a K, &'a V)>) {
+        if let Some(node_ref) = node {
+            AVL::_entries_in_order(&node_ref.left_child, entries);
+            entries.push((node_ref.key(), node_ref.value()));
+            AVL::_entries_in_order(&node_ref.right_child, entries);
+        }
+    }
+
     /// Returns all keys in the tree following a level-order traversal
     pub fn keys_in_level_order(&self) -> Vec<&K> {
         let mut keys: Vec<&K> = Vec::new();
@@ -754,6 +954,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(keys.len(), 98);
     /// ```
     pub fn keys_between(&self, low_key: &K, high_key: &K) -> Vec<&K> {
+        let _stats = self.stats.scope();
         let mut keys: Vec<&K> = Vec::new();
 
         AVL::_keys_between(&self.root, low_key, high_key, &mut keys);
@@ -772,6 +973,7 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
         }
 
         let node_ref = node.as_ref().unwrap();
+        stats::comparison();
         if *low_key < *node_ref.key() {
             AVL::_keys_between(&node_ref.left_child, low_key, high_key, keys);
         }
@@ -804,9 +1006,11 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     /// assert_eq!(keys, 98);
     /// ```
     pub fn size_between(&self, low_key: &K, high_key: &K) -> usize {
+        let _stats = self.stats.scope();
         if self.is_empty() {
             return 0;
         }
+        stats::comparison();
         if *low_key > *high_key {
             return 0;
         }
@@ -815,9 +1019,706 @@ impl<K: std::cmp::Ord, V> AVL<K, V> {
     }
 }
 
+impl<K: core::cmp::Ord, V> AVL<K, V, Soa> {
+    /// Returns `true` if tree is empty and `false` otherwise
+    pub fn is_empty(&self) -> bool {
+        self.root.len() == 0
+    }
+
+    /// Returns number of nodes in the tree
+    pub fn size(&self) -> usize {
+        self.root.len()
+    }
+
+    /// Returns the height of the tree.
+    /// An empty tree has height -1 and a tree with one node has height 0
+    pub fn height(&self) -> i64 {
+        soa::node_height(&self.root, self.root.root())
+    }
+
+    /// Returns `true` if tree contains the specified `key`, false otherwise
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched in the tree
+    pub fn contains(&self, key: &K) -> bool {
+        self.root.get(key).is_some()
+    }
+
+    /// Returns a reference to value associated with specified `key` in tree, `None` otherwise
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched in the tree
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let _stats = self.stats.scope();
+        self.root.get(key)
+    }
+
+    /// Insert a node which contains the specified `key` and `value` into the tree.
+    /// if `key` already exists, this method will replace `value` as the new value of the node
+    ///
+    /// # Arguments
+    /// * `key`: key of the new node
+    /// * `value`: value associated with the `key`
+    pub fn insert(&mut self, key: K, value: V) {
+        let _stats = self.stats.scope();
+        let root = self.root.root();
+        let root = soa::insert(&mut self.root, root, key, value);
+        self.root.set_root(root);
+    }
+
+    /// Inserts a run of items sorted by key into the tree in O(m log(n/m + 1)) time for m items.
+    /// Like `insert`, an item replaces the value of an existing key, and a later item in the run replaces an earlier one
+    ///
+    /// # Arguments
+    /// * `sorted_items`: `(key, value)` pairs in ascending order of keys
+    ///
+    /// # Panics
+    /// * panics if items are not sorted by key
+    pub fn insert_batch<I: IntoIterator<Item = (K, V)>>(&mut self, sorted_items: I) {
+        let _stats = self.stats.scope();
+        let items = batch::sorted_run(sorted_items);
+        let size = items.len();
+        let batch = soa::build(&mut self.root, &mut items.into_iter(), size);
+
+        let mut removed = Vec::new();
+        let root = self.root.root();
+        let root = soa::union(&mut self.root, batch, root, &mut removed);
+        self.root.set_root(root);
+        self.root.remove_all(removed);
+    }
+
+    /// Deletes the node containing the specified `key`
+    ///
+    /// # Arguments
+    /// * `key`: key of the node to be deleted from the tree
+    pub fn delete(&mut self, key: &K) {
+        let _stats = self.stats.scope();
+        let mut removed = NIL;
+        let root = self.root.root();
+        let root = soa::delete(&mut self.root, root, key, &mut removed);
+        self.remove(root, removed);
+    }
+
+    /// Deletes node with smallest key from the tree
+    pub fn delete_min(&mut self) {
+        let _stats = self.stats.scope();
+        if !self.is_empty() {
+            let mut removed = NIL;
+            let root = self.root.root();
+            let root = soa::delete_min(&mut self.root, root, &mut removed);
+            self.remove(root, removed);
+        }
+    }
+
+    /// Deletes node with largest key from the tree
+    pub fn delete_max(&mut self) {
+        let _stats = self.stats.scope();
+        if !self.is_empty() {
+            let mut removed = NIL;
+            let root = self.root.root();
+            let root = soa::delete_max(&mut self.root, root, &mut removed);
+            self.remove(root, removed);
+        }
+    }
+
+    fn remove(&mut self, root: usize, removed: usize) {
+        self.root.set_root(root);
+        if removed != NIL {
+            self.root.remove(removed);
+        }
+    }
+
+    /// Returns the largest key in the tree less than or equal to `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
+        self.root.floor(key)
+    }
+
+    /// Returns the smallest key in the tree greater than or equal to `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
+        self.root.ceiling(key)
+    }
+
+    /// Returns the kth smallest key and its associated value in the tree
+    ///
+    /// # Arguments
+    /// * `k`: the order statistic
+    ///
+    /// # Panics
+    /// * panics if k is not in range: 0 <= k <= size - 1
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        if k > self.size() {
+            panic!("K must be in range 0 <= k <= size - 1");
+        }
+        self.root.select(k)
+    }
+
+    /// Returns the smallest key and its associated value in the tree
+    pub fn min(&self) -> Option<(&K, &V)> {
+        self.root.select(0)
+    }
+
+    /// Returns the largest key and its associated value in the tree
+    pub fn max(&self) -> Option<(&K, &V)> {
+        self.root.select(self.size().checked_sub(1)?)
+    }
+
+    /// Returns the number of keys in the symbol table strictly less than `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn rank(&self, key: &K) -> usize {
+        let _stats = self.stats.scope();
+        self.root.rank(key)
+    }
+
+    /// Returns all keys in the tree following an in-order traversal.
+    /// Therefore keys are sorted from smallest to largest
+    pub fn keys(&self) -> Vec<&K> {
+        self.root.keys()
+    }
+
+    // returns the entries of the tree in order of their keys
+    fn entries(&self) -> Vec<(&K, &V)> {
+        self.root.entries()
+    }
+
+    /// Returns all keys in the tree following a level-order traversal
+    pub fn keys_in_level_order(&self) -> Vec<&K> {
+        self.root.keys_in_level_order()
+    }
+
+    /// Returns all keys in the symbol table between `low_key`(inclusive) and `high_key`(exclusive)
+    ///
+    /// # Arguments
+    /// * `low_key`: lowest key of the range
+    /// * `high_key`: highest key of the range
+    pub fn keys_between(&self, low_key: &K, high_key: &K) -> Vec<&K> {
+        let _stats = self.stats.scope();
+        self.root.keys_between(low_key, high_key)
+    }
+
+    /// Returns the number of keys in the tree between `low_key`(inclusive) and `high_key`(exclusive)
+    ///
+    /// # Arguments
+    /// * `low_key`: lowest key of the range
+    /// * `high_key`: highest key of the range
+    pub fn size_between(&self, low_key: &K, high_key: &K) -> usize {
+        let _stats = self.stats.scope();
+        stats::comparison();
+        if *low_key > *high_key {
+            return 0;
+        }
+
+        self.rank(high_key) - self.rank(low_key)
+    }
+}
+
+// balancing of the `Soa` layout, nodes are indices into the arena
+mod soa {
+    use crate::tree::layout::{Arena, NIL};
+    use crate::util::stats;
+    use alloc::vec::Vec;
+
+    pub(super) fn insert<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        node: usize,
+        key: K,
+        value: V,
+    ) -> usize {
+        if node == NIL {
+            return arena.push(key, value, 0);
+        }
+
+        stats::comparison();
+        if key < *arena.key(node) {
+            let left = insert(arena, arena.left(node), key, value);
+            arena.set_left(node, left);
+        } else if key > *arena.key(node) {
+            let right = insert(arena, arena.right(node), key, value);
+            arena.set_right(node, right);
+        } else {
+            arena.set_value(node, value);
+            return node;
+        }
+
+        balance(arena, node)
+    }
+
+    pub(super) fn node_height<K: core::cmp::Ord, V>(arena: &Arena<K, V>, node: usize) -> i64 {
+        if node == NIL {
+            -1
+        } else {
+            arena.tag(node) as i64
+        }
+    }
+
+    pub(super) fn balance_factor<K: core::cmp::Ord, V>(arena: &Arena<K, V>, node: usize) -> i64 {
+        node_height(arena, arena.left(node)) - node_height(arena, arena.right(node))
+    }
+
+    // recomputes height and size of `node` from its children
+    pub(super) fn update<K: core::cmp::Ord, V>(arena: &mut Arena<K, V>, node: usize) {
+        let height = 1 + core::cmp::max(
+            node_height(arena, arena.left(node)),
+            node_height(arena, arena.right(node)),
+        );
+        arena.set_tag(node, height as u8);
+        arena.update_size(node);
+    }
+
+    pub(super) fn balance<K: core::cmp::Ord, V>(arena: &mut Arena<K, V>, mut node: usize) -> usize {
+        update(arena, node);
+
+        if balance_factor(arena, node) < -1 {
+            if balance_factor(arena, arena.right(node)) > 0 {
+                let right = rotate_right(arena, arena.right(node));
+                arena.set_right(node, right);
+            }
+            node = rotate_left(arena, node);
+        } else if balance_factor(arena, node) > 1 {
+            if balance_factor(arena, arena.left(node)) < 0 {
+                let left = rotate_left(arena, arena.left(node));
+                arena.set_left(node, left);
+            }
+            node = rotate_right(arena, node);
+        }
+        node
+    }
+
+    pub(super) fn rotate_right<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        node: usize,
+    ) -> usize {
+        stats::rotation();
+        let y = arena.left(node);
+        arena.set_left(node, arena.right(y));
+        update(arena, node);
+
+        arena.set_right(y, node);
+        update(arena, y);
+
+        y
+    }
+
+    pub(super) fn rotate_left<K: core::cmp::Ord, V>(arena: &mut Arena<K, V>, node: usize) -> usize {
+        stats::rotation();
+        let y = arena.right(node);
+        arena.set_right(node, arena.left(y));
+        update(arena, node);
+
+        arena.set_left(y, node);
+        update(arena, y);
+
+        y
+    }
+
+    // unlinks the node holding `key` from the subtree and stores its index in `removed`.
+    // the arena slot is freed by the caller once all links are final
+    pub(super) fn delete<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        node: usize,
+        key: &K,
+        removed: &mut usize,
+    ) -> usize {
+        if node == NIL {
+            return NIL;
+        }
+
+        stats::comparison();
+        if *key < *arena.key(node) {
+            let left = delete(arena, arena.left(node), key, removed);
+            arena.set_left(node, left);
+        } else if *key > *arena.key(node) {
+            let right = delete(arena, arena.right(node), key, removed);
+            arena.set_right(node, right);
+        } else if arena.left(node) == NIL {
+            *removed = node;
+            return arena.right(node);
+        } else if arena.right(node) == NIL {
+            *removed = node;
+            return arena.left(node);
+        } else {
+            // the successor is unlinked instead and its entry takes the place of the deleted one
+            let right = delete_min(arena, arena.right(node), removed);
+            arena.swap_entries(node, *removed);
+            arena.set_right(node, right);
+        }
+
+        balance(arena, node)
+    }
+
+    pub(super) fn delete_min<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        node: usize,
+        removed: &mut usize,
+    ) -> usize {
+        if arena.left(node) == NIL {
+            *removed = node;
+            return arena.right(node);
+        }
+
+        let left = delete_min(arena, arena.left(node), removed);
+        arena.set_left(node, left);
+
+        balance(arena, node)
+    }
+
+    pub(super) fn delete_max<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        node: usize,
+        removed: &mut usize,
+    ) -> usize {
+        if arena.right(node) == NIL {
+            *removed = node;
+            return arena.left(node);
+        }
+
+        let right = delete_max(arena, arena.right(node), removed);
+        arena.set_right(node, right);
+
+        balance(arena, node)
+    }
+
+    // builds a perfectly balanced tree out of the next `size` sorted items
+    pub(super) fn build<K: core::cmp::Ord, V, I: Iterator<Item = (K, V)>>(
+        arena: &mut Arena<K, V>,
+        items: &mut I,
+        size: usize,
+    ) -> usize {
+        if size == 0 {
+            return NIL;
+        }
+
+        let left = build(arena, items, size / 2);
+        let (key, value) = items.next().unwrap();
+        let node = arena.push(key, value, 0);
+        let right = build(arena, items, size - size / 2 - 1);
+        arena.set_left(node, left);
+        arena.set_right(node, right);
+        update(arena, node);
+
+        node
+    }
+
+    // merges two trees, entries of `batch` replace entries of `tree` with equal keys.
+    // the replaced nodes are unlinked and collected in `removed`
+    pub(super) fn union<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        batch: usize,
+        tree: usize,
+        removed: &mut Vec<usize>,
+    ) -> usize {
+        if batch == NIL {
+            return tree;
+        }
+        if tree == NIL {
+            return batch;
+        }
+
+        let (tree_left, tree_right) = split(arena, tree, batch, removed);
+        let left = union(arena, arena.left(batch), tree_left, removed);
+        let right = union(arena, arena.right(batch), tree_right, removed);
+
+        join(arena, left, batch, right)
+    }
+
+    // splits the tree into keys less than and greater than the key of `pivot`, a node with an equal key is unlinked
+    fn split<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        node: usize,
+        pivot: usize,
+        removed: &mut Vec<usize>,
+    ) -> (usize, usize) {
+        if node == NIL {
+            return (NIL, NIL);
+        }
+
+        let left = arena.left(node);
+        let right = arena.right(node);
+        stats::comparison();
+        match arena.key(pivot).cmp(arena.key(node)) {
+            core::cmp::Ordering::Less => {
+                let (less, greater) = split(arena, left, pivot, removed);
+                (less, join(arena, greater, node, right))
+            }
+            core::cmp::Ordering::Greater => {
+                let (less, greater) = split(arena, right, pivot, removed);
+                (join(arena, left, node, less), greater)
+            }
+            core::cmp::Ordering::Equal => {
+                removed.push(node);
+                (left, right)
+            }
+        }
+    }
+
+    // links two trees through `node`, descending the taller one until the heights match
+    fn join<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        left: usize,
+        node: usize,
+        right: usize,
+    ) -> usize {
+        let left_height = node_height(arena, left);
+        let right_height = node_height(arena, right);
+
+        if left_height > right_height + 1 {
+            let child = join(arena, arena.right(left), node, right);
+            arena.set_right(left, child);
+            balance(arena, left)
+        } else if right_height > left_height + 1 {
+            let child = join(arena, left, node, arena.left(right));
+            arena.set_left(right, child);
+            balance(arena, right)
+        } else {
+            arena.set_left(node, left);
+            arena.set_right(node, right);
+            update(arena, node);
+            node
+        }
+    }
+}
+
+impl<K: core::cmp::Ord + Encode, V: Encode> Node<K, V> {
+    // writes the subtree in preorder, a leading byte tells whether the node exists
+    fn write(node: &Option<Box<Node<K, V>>>, writer: &mut Writer) {
+        match node {
+            None => writer.write_u8(0),
+            Some(node) => {
+                writer.write_u8(1);
+                node.key().encode(writer);
+                node.value().encode(writer);
+                Node::write(&node.left_child, writer);
+                Node::write(&node.right_child, writer);
+            }
+        }
+    }
+}
+
+impl<K: core::cmp::Ord + Decode, V: Decode> Node<K, V> {
+    // reads a subtree written by `write`, heights and sizes are recomputed and balance is checked
+    fn read(reader: &mut Reader, depth: usize) -> Option<Option<Box<Node<K, V>>>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        match reader.read_u8()? {
+            0 => Some(None),
+            1 => {
+                let mut node = Box::new(Node::init(K::decode(reader)?, V::decode(reader)?, 0, 1));
+                node.left_child = Node::read(reader, depth + 1)?;
+                node.right_child = Node::read(reader, depth + 1)?;
+                node.update_height();
+                node.update_size();
+
+                if Node::balance_factor(&node).abs() > 1 {
+                    return None;
+                }
+
+                Some(Some(node))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<K: core::cmp::Ord + Encode, V: Encode> AVL<K, V> {
+    /// Returns a snapshot of the tree in the binary format of `rudac::codec`.
+    /// The snapshot keeps the exact shape of the tree, not only its items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::AVL;
+    ///
+    /// let mut avl_tree = AVL::<usize, String>::init();
+    /// for key in 0..10 {
+    ///     avl_tree.insert(key, key.to_string());
+    /// }
+    ///
+    /// let bytes = avl_tree.to_bytes();
+    /// let restored = AVL::<usize, String>::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(restored.keys_in_level_order(), avl_tree.keys_in_level_order());
+    /// assert_eq!(restored.get(&7), Some(&String::from("7")));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"AV");
+        Node::write(&self.root, &mut writer);
+
+        writer.into_bytes()
+    }
+}
+
+impl<K: core::cmp::Ord + Decode, V: Decode> AVL<K, V> {
+    /// Restores a tree from a snapshot made by `to_bytes`.
+    /// Returns `None` if the bytes are malformed or do not describe a valid AVL tree
+    ///
+    /// # Arguments
+    /// * `bytes`: the snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Option<AVL<K, V>> {
+        let mut reader = Reader::init(bytes);
+        read_header(&mut reader, b"AV")?;
+        let root = Node::read(&mut reader, 0)?;
+        if !reader.is_at_end() {
+            return None;
+        }
+
+        let avl_tree: AVL<K, V> = AVL {
+            root,
+            stats: Recorder::default(),
+        };
+        if !avl_tree.keys().windows(2).all(|pair| pair[0] < pair[1]) {
+            return None;
+        }
+
+        Some(avl_tree)
+    }
+}
+
+impl<K: core::cmp::Ord, V, L: Layout> Clone for AVL<K, V, L>
+where
+    L::Avl<K, V>: Clone,
+{
+    fn clone(&self) -> Self {
+        AVL {
+            root: self.root.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+impl<K: core::cmp::Ord, V, L: Layout> Default for AVL<K, V, L>
+where
+    L::Avl<K, V>: Default,
+{
+    fn default() -> Self {
+        AVL::init()
+    }
+}
+
+// trees are equal when they hold the same entries, whatever their shape
+impl<K: core::cmp::Ord, V: PartialEq> PartialEq for AVL<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.entries() == other.entries()
+    }
+}
+
+impl<K: core::cmp::Ord, V: Eq> Eq for AVL<K, V> {}
+
+impl<K: core::cmp::Ord, V: PartialEq> PartialEq for AVL<K, V, Soa> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.entries() == other.entries()
+    }
+}
+
+impl<K: core::cmp::Ord, V: Eq> Eq for AVL<K, V, Soa> {}
+
+impl<K: core::cmp::Ord, V> MemoryFootprint for AVL<K, V> {
+    fn footprint(&self) -> Footprint {
+        let size = self.size();
+        Footprint::of::<(K, V)>(size).with_boxes::<Node<K, V>>(size)
+    }
+}
+
+impl<K: core::cmp::Ord, V> MemoryFootprint for AVL<K, V, Soa> {
+    fn footprint(&self) -> Footprint {
+        self.root.footprint()
+    }
+}
+
+impl<K: core::cmp::Ord, V> Replayable for AVL<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<K, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(key, value);
+            }
+            Op::Remove(key) => {
+                self.delete(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+impl<K: core::cmp::Ord, V> Replayable for AVL<K, V, Soa> {
+    type Key = K;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<K, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(key, value);
+            }
+            Op::Remove(key) => {
+                self.delete(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+// a tree is written as its entries in increasing order of keys and rebuilt by inserting them, later entries of a key
+// replace earlier ones
+#[cfg(feature = "serde")]
+impl<K: core::cmp::Ord + Serialize, V: Serialize> Serialize for AVL<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.entries())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: core::cmp::Ord + Serialize, V: Serialize> Serialize for AVL<K, V, Soa> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.entries())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K: core::cmp::Ord + Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de>
+    for AVL<K, V>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries: Vec<(K, V)> = Vec::deserialize(deserializer)?;
+        let mut avl_tree: AVL<K, V> = AVL::init();
+        for (key, value) in entries {
+            avl_tree.insert(key, value);
+        }
+
+        Ok(avl_tree)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K: core::cmp::Ord + Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de>
+    for AVL<K, V, Soa>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries: Vec<(K, V)> = Vec::deserialize(deserializer)?;
+        let mut avl_tree: AVL<K, V, Soa> = AVL::init();
+        for (key, value) in entries {
+            avl_tree.insert(key, value);
+        }
+
+        Ok(avl_tree)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tree::layout::Arena;
 
     fn is_avl<K: std::cmp::Ord, V>(node: &Option<Box<Node<K, V>>>) -> bool {
         if node.is_none() {
@@ -1301,4 +2202,288 @@ mod tests {
         assert!(is_size_consistent(&avl_tree.root));
         assert!(is_rank_consistent(&avl_tree));
     }
+
+    #[test]
+    fn tree_avl_bytes_round_trip() {
+        let mut avl_tree: AVL<i32, String> = AVL::init();
+        for key in 0..200 {
+            avl_tree.insert((key * 37) % 200, key.to_string());
+        }
+        for key in (0..200).step_by(3) {
+            avl_tree.delete(&key);
+        }
+
+        let bytes = avl_tree.to_bytes();
+        let restored = AVL::<i32, String>::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            restored.keys_in_level_order(),
+            avl_tree.keys_in_level_order()
+        );
+        assert_eq!(restored.size(), avl_tree.size());
+        assert_eq!(restored.height(), avl_tree.height());
+        assert!(is_avl(&restored.root));
+        assert_eq!(restored.get(&1), avl_tree.get(&1));
+
+        assert!(AVL::<i32, String>::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        assert!(AVL::<i64, String>::from_bytes(&AVL::<i64, String>::init().to_bytes()).is_some());
+    }
+
+    #[test]
+    fn tree_avl_bytes_rejects_invalid_trees() {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"AV");
+        // root 1 with left child 2 breaks the key order
+        for byte in [1, 2, 0, 1, 4, 0, 0, 0, 0].iter() {
+            writer.write_u8(*byte);
+        }
+        assert!(AVL::<u8, u8>::from_bytes(writer.bytes()).is_none());
+
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"AV");
+        // a right leaning chain of three nodes is not balanced
+        for byte in [1, 1, 0, 0, 1, 2, 0, 0, 1, 3, 0, 0, 0].iter() {
+            writer.write_u8(*byte);
+        }
+        assert!(AVL::<u8, u8>::from_bytes(writer.bytes()).is_none());
+    }
+
+    // checks heights, sizes, balance factors and parent links of a `Soa` subtree, returns its height
+    fn soa_height<K: std::cmp::Ord, V>(arena: &Arena<K, V>, node: usize, parent: usize) -> i64 {
+        if node == NIL {
+            return -1;
+        }
+
+        assert_eq!(arena.parent(node), parent);
+        let left = soa_height(arena, arena.left(node), node);
+        let right = soa_height(arena, arena.right(node), node);
+        assert!((left - right).abs() <= 1);
+        assert_eq!(arena.tag(node) as i64, 1 + std::cmp::max(left, right));
+        assert_eq!(
+            arena.size(node),
+            1 + arena.size(arena.left(node)) + arena.size(arena.right(node))
+        );
+
+        arena.tag(node) as i64
+    }
+
+    #[test]
+    fn tree_avl_soa_matches_boxed() {
+        use crate::util::{Rng, SplitMix64};
+
+        let mut rng = SplitMix64::init(968);
+        let mut boxed = AVL::<u64, u64>::init();
+        let mut soa = AVL::<u64, u64, Soa>::init();
+        for step in 0..3000 {
+            let key = rng.next_below(500);
+            match rng.next_below(10) {
+                0..=5 => {
+                    boxed.insert(key, step);
+                    soa.insert(key, step);
+                }
+                6 | 7 => {
+                    boxed.delete(&key);
+                    soa.delete(&key);
+                }
+                8 => {
+                    boxed.delete_min();
+                    soa.delete_min();
+                }
+                _ => {
+                    boxed.delete_max();
+                    soa.delete_max();
+                }
+            }
+            assert_eq!(soa.size(), boxed.size());
+            assert_eq!(soa.get(&key), boxed.get(&key));
+        }
+
+        assert_eq!(soa_height(&soa.root, soa.root.root(), NIL), soa.height());
+        assert_eq!(soa.height(), boxed.height());
+        assert_eq!(soa.keys_in_level_order(), boxed.keys_in_level_order());
+        assert_eq!(soa.keys_between(&100, &300), boxed.keys_between(&100, &300));
+        assert_eq!(soa.size_between(&100, &300), boxed.size_between(&100, &300));
+        assert_eq!(soa.min(), boxed.min());
+        assert_eq!(soa.max(), boxed.max());
+        for key in 0..510 {
+            assert_eq!(soa.contains(&key), boxed.contains(&key));
+            assert_eq!(soa.rank(&key), boxed.rank(&key));
+            assert_eq!(soa.floor(&key), boxed.floor(&key));
+            assert_eq!(soa.ceiling(&key), boxed.ceiling(&key));
+        }
+        for k in 0..soa.size() {
+            assert_eq!(soa.select(k), boxed.select(k));
+        }
+
+        while !soa.is_empty() {
+            soa.delete_min();
+        }
+        assert_eq!(soa.height(), -1);
+        assert_eq!(soa.max(), None);
+    }
+
+    #[test]
+    fn tree_avl_insert_batch() {
+        use crate::util::{Rng, SplitMix64};
+        use std::collections::BTreeMap;
+
+        let mut rng = SplitMix64::init(969);
+        let mut expected = BTreeMap::new();
+        let mut boxed = AVL::<u64, u64>::init();
+        let mut soa = AVL::<u64, u64, Soa>::init();
+        for round in 0..40 {
+            // alternate small batches into large trees and large batches into small trees
+            let count = if round % 2 == 0 {
+                rng.next_below(8)
+            } else {
+                rng.next_below(300)
+            };
+            let mut batch: Vec<(u64, u64)> =
+                (0..count).map(|_| (rng.next_below(2000), round)).collect();
+            batch.sort_by_key(|(key, _)| *key);
+            for (key, value) in batch.iter() {
+                expected.insert(*key, *value);
+            }
+
+            boxed.insert_batch(batch.clone());
+            soa.insert_batch(batch);
+            assert!(is_avl(&boxed.root));
+            assert!(is_size_consistent(&boxed.root));
+            soa_height(&soa.root, soa.root.root(), NIL);
+        }
+
+        let expected_keys: Vec<&u64> = expected.keys().collect();
+        assert_eq!(boxed.keys(), expected_keys);
+        assert_eq!(soa.keys(), expected_keys);
+        for (key, value) in expected.iter() {
+            assert_eq!(boxed.get(key), Some(value));
+            assert_eq!(soa.get(key), Some(value));
+        }
+    }
+
+    #[test]
+    fn tree_avl_insert_batch_duplicates() {
+        let mut avl_tree = AVL::<usize, &str>::init();
+        avl_tree.insert_batch(vec![(1, "a"), (2, "b"), (2, "c")]);
+        assert_eq!(avl_tree.size(), 2);
+        assert_eq!(avl_tree.get(&2), Some(&"c"));
+
+        avl_tree.insert_batch(Vec::new());
+        assert_eq!(avl_tree.size(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Items must be sorted by key")]
+    fn tree_avl_panic_insert_batch() {
+        let mut avl_tree = AVL::<usize, usize, Soa>::init();
+        avl_tree.insert_batch(vec![(2, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn tree_avl_footprint() {
+        let mut boxed = AVL::<u32, u32>::init();
+        let mut soa = AVL::<u32, u32, Soa>::init();
+        assert_eq!(boxed.footprint().allocated_bytes(), 0);
+        assert_eq!(soa.footprint().allocated_bytes(), 0);
+
+        for i in 0..100 {
+            boxed.insert(i, i);
+            soa.insert(i, i);
+        }
+
+        let footprint = boxed.footprint();
+        assert_eq!(footprint.elements(), 100);
+        assert_eq!(footprint.payload_bytes(), 800);
+        assert_eq!(footprint.nodes(), 100);
+        assert_eq!(
+            footprint.allocated_bytes(),
+            100 * std::mem::size_of::<Node<u32, u32>>()
+        );
+
+        let footprint = soa.footprint();
+        assert_eq!(footprint.elements(), 100);
+        assert_eq!(footprint.nodes(), 7);
+        assert!(footprint.allocated_bytes() >= 100 * (8 + 4 * std::mem::size_of::<usize>() + 1));
+
+        boxed.delete(&0);
+        assert_eq!(boxed.footprint().nodes(), 99);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn tree_avl_stats() {
+        let mut boxed = AVL::<usize, usize>::init();
+        let mut soa = AVL::<usize, usize, Soa>::init();
+        for i in 0..64 {
+            boxed.insert(i, i);
+            soa.insert(i, i);
+        }
+
+        let boxed_stats = boxed.stats();
+        let soa_stats = soa.stats();
+        assert_eq!(boxed_stats.allocations, 64);
+        assert_eq!(boxed_stats.rotations, soa_stats.rotations);
+        assert_eq!(boxed_stats.comparisons, soa_stats.comparisons);
+        assert_eq!(boxed_stats.consolidations, 0);
+
+        // ascending inserts rotate at every level where the right spine becomes too tall
+        assert_eq!(boxed_stats.rotations, 64 - 7);
+
+        boxed.reset_stats();
+        assert_eq!(boxed.stats(), crate::util::OpStats::default());
+
+        // an existing key needs at most one comparison per level and no allocation
+        boxed.insert(0, 1);
+        assert!(boxed.stats().comparisons <= boxed.height() as u64 + 1);
+        assert_eq!(boxed.stats().allocations, 0);
+
+        boxed.reset_stats();
+        assert!(boxed.contains(&10));
+        assert!(boxed.stats().comparisons <= boxed.height() as u64 + 1);
+    }
+
+    #[test]
+    fn tree_avl_clone_and_eq() {
+        let mut tree = AVL::<usize, usize>::default();
+        let mut soa = AVL::<usize, usize, Soa>::default();
+        for key in 0..50 {
+            tree.insert(key, key * 2);
+            soa.insert(key, key * 2);
+        }
+
+        let mut copy = tree.clone();
+        let mut soa_copy = soa.clone();
+        assert!(copy == tree);
+        assert!(soa_copy == soa);
+        copy.delete(&7);
+        soa_copy.insert(7, 0);
+        assert!(copy != tree);
+        assert!(soa_copy != soa);
+        assert_eq!(tree.get(&7), Some(&14));
+        assert_eq!(soa.get(&7), Some(&14));
+
+        // the same entries inserted in another order give an equal tree of another shape
+        let mut other = AVL::<usize, usize>::init();
+        for key in (0..50).rev() {
+            other.insert(key, key * 2);
+        }
+        assert!(other == tree);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tree_avl_serde() {
+        let mut avl_tree = AVL::<u32, String>::init();
+        for key in [5, 2, 8, 1, 9] {
+            avl_tree.insert(key, key.to_string());
+        }
+
+        let json = serde_json::to_string(&avl_tree).unwrap();
+        assert_eq!(json, r#"[[1,"1"],[2,"2"],[5,"5"],[8,"8"],[9,"9"]]"#);
+
+        let restored: AVL<u32, String> = serde_json::from_str(&json).unwrap();
+        assert!(restored == avl_tree);
+        let soa: AVL<u32, String, Soa> = serde_json::from_str(&json).unwrap();
+        assert_eq!(soa.keys(), avl_tree.keys());
+        assert_eq!(serde_json::to_string(&soa).unwrap(), json);
+    }
 }
@@ -0,0 +1,18 @@
+use alloc::vec::Vec;
+
+// collects a batch of items for `insert_batch`. Items must be sorted by key, a later item replaces
+// an earlier one with an equal key just like consecutive inserts would
+pub(crate) fn sorted_run<K: core::cmp::Ord, V, I: IntoIterator<Item = (K, V)>>(
+    items: I,
+) -> Vec<(K, V)> {
+    let mut run: Vec<(K, V)> = Vec::new();
+    for (key, value) in items {
+        match run.last_mut() {
+            Some(last) if last.0 == key => last.1 = value,
+            Some(last) if last.0 > key => panic!("Items must be sorted by key"),
+            _ => run.push((key, value)),
+        }
+    }
+
+    run
+}
@@ -0,0 +1,517 @@
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec;
+use alloc::vec::Vec;
+
+// node of the tree, summarizing its range of items
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    sum: i64,
+
+    // largest item, number of items equal to it and the largest item below it, i64::MIN if there is none
+    max: i64,
+    max_count: usize,
+    second_max: i64,
+
+    // smallest item, number of items equal to it and the smallest item above it, i64::MAX if there is none
+    min: i64,
+    min_count: usize,
+    second_min: i64,
+
+    // pending addition to every item of the children
+    add: i64,
+}
+
+impl Node {
+    fn leaf(item: i64) -> Node {
+        Node {
+            sum: item,
+            max: item,
+            max_count: 1,
+            second_max: i64::MIN,
+            min: item,
+            min_count: 1,
+            second_min: i64::MAX,
+            add: 0,
+        }
+    }
+
+    fn merge(left: &Node, right: &Node) -> Node {
+        let (max, max_count, second_max) = if left.max == right.max {
+            (
+                left.max,
+                left.max_count + right.max_count,
+                left.second_max.max(right.second_max),
+            )
+        } else if left.max > right.max {
+            (left.max, left.max_count, left.second_max.max(right.max))
+        } else {
+            (right.max, right.max_count, right.second_max.max(left.max))
+        };
+        let (min, min_count, second_min) = if left.min == right.min {
+            (
+                left.min,
+                left.min_count + right.min_count,
+                left.second_min.min(right.second_min),
+            )
+        } else if left.min < right.min {
+            (left.min, left.min_count, left.second_min.min(right.min))
+        } else {
+            (right.min, right.min_count, right.second_min.min(left.min))
+        };
+
+        Node {
+            sum: left.sum + right.sum,
+            max,
+            max_count,
+            second_max,
+            min,
+            min_count,
+            second_min,
+            add: 0,
+        }
+    }
+
+    // adds `delta` to the `length` items of the node
+    fn apply_add(&mut self, delta: i64, length: usize) {
+        self.sum += delta * length as i64;
+        self.max += delta;
+        self.min += delta;
+        if self.second_max != i64::MIN {
+            self.second_max += delta;
+        }
+        if self.second_min != i64::MAX {
+            self.second_min += delta;
+        }
+        self.add += delta;
+    }
+
+    // lowers the largest items to `bound`, which must be above the second largest item
+    fn apply_min(&mut self, bound: i64) {
+        if bound >= self.max {
+            return;
+        }
+
+        self.sum -= (self.max - bound) * self.max_count as i64;
+        if self.min == self.max {
+            self.min = bound;
+        } else if self.second_min == self.max {
+            self.second_min = bound;
+        }
+        self.max = bound;
+    }
+
+    // raises the smallest items to `bound`, which must be below the second smallest item
+    fn apply_max(&mut self, bound: i64) {
+        if bound <= self.min {
+            return;
+        }
+
+        self.sum += (bound - self.min) * self.min_count as i64;
+        if self.max == self.min {
+            self.max = bound;
+        } else if self.second_max == self.min {
+            self.second_max = bound;
+        }
+        self.min = bound;
+    }
+}
+
+/// Segment tree beats, or Ji Driver's segment tree, keeps a sequence of integers under range updates that clamp
+/// items, `chmin` (every item becomes min(item, x)) and `chmax` (max(item, x)), along with range additions, and
+/// answers range sum, minimum and maximum queries. Plain lazy propagation cannot do this since the change of a sum
+/// under clamping depends on the items. Every node also keeps its second largest and second smallest item: an update
+/// that only touches the extreme items of a node is applied there, otherwise it goes further down, which happens
+/// rarely enough that every operation takes amortized O(log² n) time, O(log n) without additions
+///
+/// # Examples
+/// ```
+/// use rudac::tree::SegmentTreeBeats;
+///
+/// let mut beats = SegmentTreeBeats::from_slice(&[5, 1, 8, 3, 9, 2]);
+///
+/// // clamps items 1..5 to at most 4
+/// beats.chmin(1, 5, 4);
+/// assert_eq!(beats.to_vec(), vec![5, 1, 4, 3, 4, 2]);
+///
+/// // raises every item to at least 3
+/// beats.chmax(0, 6, 3);
+/// assert_eq!(beats.sum(0, 6), 22);
+/// assert_eq!(beats.max(0, 3), Some(5));
+/// assert_eq!(beats.min(0, 6), Some(3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SegmentTreeBeats {
+    // 1-based implicit tree, node i has children 2i and 2i + 1
+    nodes: Vec<Node>,
+    size: usize,
+}
+
+impl SegmentTreeBeats {
+    /// Initializes a tree of `size` items equal to 0
+    ///
+    /// # Arguments
+    /// * `size`: number of items
+    pub fn init(size: usize) -> SegmentTreeBeats {
+        SegmentTreeBeats::from_slice(&vec![0; size])
+    }
+
+    /// Builds a tree from `items`
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `items`: items of the sequence
+    pub fn from_slice(items: &[i64]) -> SegmentTreeBeats {
+        let mut beats = SegmentTreeBeats {
+            nodes: vec![Node::leaf(0); 4 * items.len().max(1)],
+            size: items.len(),
+        };
+        if !items.is_empty() {
+            beats.build(1, 0, items.len(), items);
+        }
+
+        beats
+    }
+
+    fn build(&mut self, node: usize, start: usize, end: usize, items: &[i64]) {
+        if end - start == 1 {
+            self.nodes[node] = Node::leaf(items[start]);
+            return;
+        }
+
+        let middle = (start + end) / 2;
+        self.build(2 * node, start, middle, items);
+        self.build(2 * node + 1, middle, end, items);
+        self.pull(node);
+    }
+
+    /// Returns number of items
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    fn pull(&mut self, node: usize) {
+        self.nodes[node] = Node::merge(&self.nodes[2 * node], &self.nodes[2 * node + 1]);
+    }
+
+    // passes the pending updates of `node`, which covers [start, end), to its children
+    fn push(&mut self, node: usize, start: usize, end: usize) {
+        let middle = (start + end) / 2;
+        let Node { add, max, min, .. } = self.nodes[node];
+        for (child, length) in [(2 * node, middle - start), (2 * node + 1, end - middle)] {
+            let child = &mut self.nodes[child];
+            if add != 0 {
+                child.apply_add(add, length);
+            }
+            child.apply_min(max);
+            child.apply_max(min);
+        }
+        self.nodes[node].add = 0;
+    }
+
+    // panics if [start, end) is not a range of items
+    fn check_range(&self, start: usize, end: usize) {
+        if start > end {
+            panic!("Start of the range must not be greater than its end");
+        }
+        if end > self.size {
+            panic!("Range is out of bounds");
+        }
+    }
+
+    /// Replaces every item in range [`start`, `end`) by the minimum of the item and `bound`
+    /// * Complexity: amortized O(log² n)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    /// * `bound`: largest value left in the range
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end` or `end` is greater than size of the tree
+    pub fn chmin(&mut self, start: usize, end: usize, bound: i64) {
+        self.check_range(start, end);
+        if start < end {
+            self.update_min(1, 0, self.size, start, end, bound);
+        }
+    }
+
+    fn update_min(
+        &mut self,
+        node: usize,
+        left: usize,
+        right: usize,
+        start: usize,
+        end: usize,
+        bound: i64,
+    ) {
+        if end <= left || right <= start || self.nodes[node].max <= bound {
+            return;
+        }
+        if start <= left && right <= end && self.nodes[node].second_max < bound {
+            self.nodes[node].apply_min(bound);
+            return;
+        }
+
+        self.push(node, left, right);
+        let middle = (left + right) / 2;
+        self.update_min(2 * node, left, middle, start, end, bound);
+        self.update_min(2 * node + 1, middle, right, start, end, bound);
+        self.pull(node);
+    }
+
+    /// Replaces every item in range [`start`, `end`) by the maximum of the item and `bound`
+    /// * Complexity: amortized O(log² n)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    /// * `bound`: smallest value left in the range
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end` or `end` is greater than size of the tree
+    pub fn chmax(&mut self, start: usize, end: usize, bound: i64) {
+        self.check_range(start, end);
+        if start < end {
+            self.update_max(1, 0, self.size, start, end, bound);
+        }
+    }
+
+    fn update_max(
+        &mut self,
+        node: usize,
+        left: usize,
+        right: usize,
+        start: usize,
+        end: usize,
+        bound: i64,
+    ) {
+        if end <= left || right <= start || self.nodes[node].min >= bound {
+            return;
+        }
+        if start <= left && right <= end && self.nodes[node].second_min > bound {
+            self.nodes[node].apply_max(bound);
+            return;
+        }
+
+        self.push(node, left, right);
+        let middle = (left + right) / 2;
+        self.update_max(2 * node, left, middle, start, end, bound);
+        self.update_max(2 * node + 1, middle, right, start, end, bound);
+        self.pull(node);
+    }
+
+    /// Adds `delta` to every item in range [`start`, `end`)
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    /// * `delta`: value to be added
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end` or `end` is greater than size of the tree
+    pub fn add(&mut self, start: usize, end: usize, delta: i64) {
+        self.check_range(start, end);
+        if start < end {
+            self.update_add(1, 0, self.size, start, end, delta);
+        }
+    }
+
+    fn update_add(
+        &mut self,
+        node: usize,
+        left: usize,
+        right: usize,
+        start: usize,
+        end: usize,
+        delta: i64,
+    ) {
+        if end <= left || right <= start {
+            return;
+        }
+        if start <= left && right <= end {
+            self.nodes[node].apply_add(delta, right - left);
+            return;
+        }
+
+        self.push(node, left, right);
+        let middle = (left + right) / 2;
+        self.update_add(2 * node, left, middle, start, end, delta);
+        self.update_add(2 * node + 1, middle, right, start, end, delta);
+        self.pull(node);
+    }
+
+    // returns the summary of the items of `node`, which covers [left, right), that are in [start, end)
+    fn query(
+        &mut self,
+        node: usize,
+        left: usize,
+        right: usize,
+        start: usize,
+        end: usize,
+    ) -> Option<Node> {
+        if end <= left || right <= start {
+            return None;
+        }
+        if start <= left && right <= end {
+            return Some(self.nodes[node]);
+        }
+
+        self.push(node, left, right);
+        let middle = (left + right) / 2;
+        let first = self.query(2 * node, left, middle, start, end);
+        let second = self.query(2 * node + 1, middle, right, start, end);
+        match (first, second) {
+            (Some(first), Some(second)) => Some(Node::merge(&first, &second)),
+            (first, second) => first.or(second),
+        }
+    }
+
+    /// Returns sum of the items in range [`start`, `end`)
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end` or `end` is greater than size of the tree
+    pub fn sum(&mut self, start: usize, end: usize) -> i64 {
+        self.check_range(start, end);
+        self.query(1, 0, self.size, start, end)
+            .map_or(0, |node| node.sum)
+    }
+
+    /// Returns the largest item in range [`start`, `end`), None if the range is empty
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end` or `end` is greater than size of the tree
+    pub fn max(&mut self, start: usize, end: usize) -> Option<i64> {
+        self.check_range(start, end);
+        self.query(1, 0, self.size, start, end).map(|node| node.max)
+    }
+
+    /// Returns the smallest item in range [`start`, `end`), None if the range is empty
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end` or `end` is greater than size of the tree
+    pub fn min(&mut self, start: usize, end: usize) -> Option<i64> {
+        self.check_range(start, end);
+        self.query(1, 0, self.size, start, end).map(|node| node.min)
+    }
+
+    /// Returns the item at `index`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is out of bounds
+    pub fn get(&mut self, index: usize) -> i64 {
+        self.sum(index, index + 1)
+    }
+
+    /// Returns the items of the sequence
+    /// * Complexity: O(n)
+    pub fn to_vec(&mut self) -> Vec<i64> {
+        let mut items = Vec::with_capacity(self.size);
+        if self.size > 0 {
+            self.collect(1, 0, self.size, &mut items);
+        }
+
+        items
+    }
+
+    fn collect(&mut self, node: usize, start: usize, end: usize, items: &mut Vec<i64>) {
+        if end - start == 1 {
+            items.push(self.nodes[node].sum);
+            return;
+        }
+
+        self.push(node, start, end);
+        let middle = (start + end) / 2;
+        self.collect(2 * node, start, middle, items);
+        self.collect(2 * node + 1, middle, end, items);
+    }
+}
+
+impl MemoryFootprint for SegmentTreeBeats {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<i64>(self.size).with_vec(&self.nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    #[test]
+    fn tree_beats_matches_naive() {
+        let mut rng = SplitMix64::init(83);
+        for size in [1, 2, 7, 64, 100] {
+            let mut naive: Vec<i64> = (0..size)
+                .map(|_| rng.next_below(200) as i64 - 100)
+                .collect();
+            let mut beats = SegmentTreeBeats::from_slice(&naive);
+            for _ in 0..2000 {
+                let a = rng.next_below(size as u64 + 1) as usize;
+                let b = rng.next_below(size as u64 + 1) as usize;
+                let (start, end) = (a.min(b), a.max(b));
+                let value = rng.next_below(200) as i64 - 100;
+                let range = &mut naive[start..end];
+                match rng.next_below(6) {
+                    0 => {
+                        beats.chmin(start, end, value);
+                        range.iter_mut().for_each(|item| *item = (*item).min(value));
+                    }
+                    1 => {
+                        beats.chmax(start, end, value);
+                        range.iter_mut().for_each(|item| *item = (*item).max(value));
+                    }
+                    2 => {
+                        beats.add(start, end, value / 10);
+                        range.iter_mut().for_each(|item| *item += value / 10);
+                    }
+                    3 => assert_eq!(beats.sum(start, end), range.iter().sum::<i64>()),
+                    4 => assert_eq!(beats.max(start, end), range.iter().max().copied()),
+                    _ => assert_eq!(beats.min(start, end), range.iter().min().copied()),
+                }
+            }
+            assert_eq!(beats.to_vec(), naive);
+        }
+    }
+
+    #[test]
+    fn tree_beats_empty() {
+        let mut beats = SegmentTreeBeats::init(0);
+
+        assert!(beats.is_empty());
+        assert_eq!(beats.sum(0, 0), 0);
+        assert_eq!(beats.max(0, 0), None);
+        assert!(beats.to_vec().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Range is out of bounds")]
+    fn tree_beats_panic_out_of_bounds() {
+        let mut beats = SegmentTreeBeats::init(3);
+        beats.chmin(1, 4, 0);
+    }
+}
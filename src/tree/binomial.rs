@@ -1,3 +1,10 @@
+use crate::codec::{read_header, write_header, Decode, Encode, Reader, Writer, MAX_DEPTH};
+use crate::util::{stats, Footprint, MemoryFootprint, Order};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
 /// A binomial tree of rank(order) k is a general tree with a recursive definition
 ///
 /// B<sub>k</sub>:
@@ -20,8 +27,8 @@
 /// assert_eq!(merged_tree.rank(), 1);
 /// ```
 ///
-#[derive(Debug)]
-pub struct BinomialTree<T: std::cmp::Ord> {
+#[derive(Debug, Clone)]
+pub struct BinomialTree<T> {
     // rank of the tree
     rank: usize,
 
@@ -33,9 +40,12 @@ pub struct BinomialTree<T: std::cmp::Ord> {
 
     // indicates wether the binomial tree is a min or max one
     min: bool,
+
+    // order of the payloads, the root is the least payload by it
+    order: Order<T>,
 }
 
-impl<T: std::cmp::Ord> BinomialTree<T> {
+impl<T: core::cmp::Ord> BinomialTree<T> {
     /// Creates a min binomial tree with rank 0 which holds the `payload`.
     /// in this binomial tree each node is smaller than its children
     ///
@@ -80,11 +90,78 @@ impl<T: std::cmp::Ord> BinomialTree<T> {
 
     /// **Note**: this method is for internal use. use init_min or init_max functions instead.
     pub fn init(payload: T, min: bool) -> BinomialTree<T> {
+        BinomialTree::init_ordered(payload, min, Order::natural(min))
+    }
+
+    /// Compares payloads which reside in roots of two binomial trees `first` and `other`.
+    /// Returns true if payload of `first` is smaller or equal than payload of `other`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::BinomialTree;
+    ///
+    /// let bt1 = BinomialTree::init_min(0);
+    /// let bt2 = BinomialTree::init_min(1);
+    ///
+    /// assert_eq!(true, BinomialTree::is_smaller_or_equal(&bt1, &bt2));
+    /// assert_eq!(false, BinomialTree::is_smaller_or_equal(&bt2, &bt1));
+    /// ```
+    pub fn is_smaller_or_equal(first: &BinomialTree<T>, other: &BinomialTree<T>) -> bool {
+        BinomialTree::compare_roots(first, other, &Order::natural(true)) != Ordering::Greater
+    }
+
+    /// Compares payloads which reside in roots of two binomial trees `first` and `other`.
+    /// Returns true if payload of `first` is greater or equal than payload of `other`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::BinomialTree;
+    ///
+    /// let bt1 = BinomialTree::init_min(0);
+    /// let bt2 = BinomialTree::init_min(1);
+    ///
+    /// assert_eq!(false, BinomialTree::is_greater_or_equal(&bt1, &bt2));
+    /// assert_eq!(true, BinomialTree::is_greater_or_equal(&bt2, &bt1));
+    /// ```
+    pub fn is_greater_or_equal(first: &BinomialTree<T>, other: &BinomialTree<T>) -> bool {
+        BinomialTree::compare_roots(first, other, &Order::natural(true)) != Ordering::Less
+    }
+}
+
+impl<T> BinomialTree<T> {
+    /// Creates a binomial tree with rank 0 which holds the `payload`, ordered by `compare` instead of `Ord`.
+    /// in this binomial tree no node is greater than its children by `compare`. The tree counts as a min binomial tree
+    ///
+    /// # Arguments
+    /// * `payload` - data stored inside the node
+    /// * `compare` - order of the payloads
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::BinomialTree;
+    ///
+    /// let bt1 = BinomialTree::init_with("rudac", |s1: &&str, s2: &&str| s1.len().cmp(&s2.len()));
+    /// let bt2 = BinomialTree::init_with("tree", |s1: &&str, s2: &&str| s1.len().cmp(&s2.len()));
+    ///
+    /// let merged_tree = BinomialTree::merge(bt1, bt2);
+    ///
+    /// assert_eq!(BinomialTree::preorder(&merged_tree), String::from("tree rudac"))
+    /// ```
+    pub fn init_with<F>(payload: T, compare: F) -> BinomialTree<T>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        BinomialTree::init_ordered(payload, true, Order::with(compare))
+    }
+
+    // creates a tree of rank 0 with the given order, `min` only tells how the order was made
+    pub(crate) fn init_ordered(payload: T, min: bool, order: Order<T>) -> BinomialTree<T> {
         BinomialTree {
             rank: 0,
             children: Vec::new(),
             payload: Some(payload),
             min,
+            order,
         }
     }
 
@@ -93,6 +170,7 @@ impl<T: std::cmp::Ord> BinomialTree<T> {
     // if you call this function directly you should check compatibility between ranks and types of these two trees
     fn add(&mut self, binomial_tree: BinomialTree<T>) {
         // add the binomial tree as the rightmost child
+        stats::growth(self.children.len(), self.children.capacity());
         self.children.push(Some(binomial_tree));
 
         // merged tree of two binomial trees of rank k has rank k + 1
@@ -138,16 +216,11 @@ impl<T: std::cmp::Ord> BinomialTree<T> {
             panic!("Both binomial trees must be of the same type(both min or both max)");
         }
 
-        // trees_are_min indicates wether the comparison is between two min trees or two max trees
-        let trees_are_min = binomial_tree_1.is_min();
-        let trees_are_max = binomial_tree_1.is_max();
+        stats::consolidation();
 
         // if two trees are min binomial trees the minimum must become the new root
         // if two trees are max binomial trees the maximum must become the new root
-        if (trees_are_min && BinomialTree::is_smaller_or_equal(&binomial_tree_1, &binomial_tree_2))
-            || (trees_are_max
-                && BinomialTree::is_greater_or_equal(&binomial_tree_1, &binomial_tree_2))
-        {
+        if BinomialTree::has_higher_priority(&binomial_tree_1, &binomial_tree_2) {
             binomial_tree_1.add(binomial_tree_2);
             return binomial_tree_1;
         } else {
@@ -216,7 +289,7 @@ impl<T: std::cmp::Ord> BinomialTree<T> {
     /// assert_eq!(bt.get_payload(), "rudac is awesome");
     /// ```
     pub fn get_payload(&mut self) -> T {
-        if self.payload == None {
+        if self.payload.is_none() {
             panic!("Payload is None");
         }
 
@@ -237,42 +310,20 @@ impl<T: std::cmp::Ord> BinomialTree<T> {
         &self.payload
     }
 
-    /// Compares payloads which reside in roots of two binomial trees `first` and `other`.
-    /// Returns true if payload of `first` is smaller or equal than payload of `other`
-    ///
-    /// # Examples
-    /// ```
-    /// use rudac::tree::BinomialTree;
-    ///
-    /// let bt1 = BinomialTree::init_min(0);
-    /// let bt2 = BinomialTree::init_min(1);
-    ///
-    /// assert_eq!(true, BinomialTree::is_smaller_or_equal(&bt1, &bt2));
-    /// assert_eq!(false, BinomialTree::is_smaller_or_equal(&bt2, &bt1));
-    /// ```
-    pub fn is_smaller_or_equal(first: &BinomialTree<T>, other: &BinomialTree<T>) -> bool {
-        match (first.peek_payload(), other.peek_payload()) {
-            (Some(payload1), Some(payload2)) => payload1 <= payload2,
-            _ => panic!("Payloads can not be None"), // if one of the payloads or both of them are None
-        }
+    // returns true if root of `first` is not greater than root of `other` by the order of `first`
+    pub(crate) fn has_higher_priority(first: &BinomialTree<T>, other: &BinomialTree<T>) -> bool {
+        BinomialTree::compare_roots(first, other, &first.order) != Ordering::Greater
     }
 
-    /// Compares payloads which reside in roots of two binomial trees `first` and `other`.
-    /// Returns true if payload of `first` is greater or equal than payload of `other`
-    ///
-    /// # Examples
-    /// ```
-    /// use rudac::tree::BinomialTree;
-    ///
-    /// let bt1 = BinomialTree::init_min(0);
-    /// let bt2 = BinomialTree::init_min(1);
-    ///
-    /// assert_eq!(false, BinomialTree::is_greater_or_equal(&bt1, &bt2));
-    /// assert_eq!(true, BinomialTree::is_greater_or_equal(&bt2, &bt1));
-    /// ```
-    pub fn is_greater_or_equal(first: &BinomialTree<T>, other: &BinomialTree<T>) -> bool {
+    // compares the roots of `first` and `other` by `order`, every comparison of roots goes through here
+    fn compare_roots(
+        first: &BinomialTree<T>,
+        other: &BinomialTree<T>,
+        order: &Order<T>,
+    ) -> Ordering {
+        stats::comparison();
         match (first.peek_payload(), other.peek_payload()) {
-            (Some(payload1), Some(payload2)) => payload1 >= payload2,
+            (Some(payload1), Some(payload2)) => order.compare(payload1, payload2),
             _ => panic!("Payloads can not be None"), // if one of the payloads or both of them are None
         }
     }
@@ -288,7 +339,7 @@ impl<T: std::cmp::Ord> BinomialTree<T> {
     }
 }
 
-impl<T: std::cmp::Ord + std::fmt::Display> BinomialTree<T> {
+impl<T: core::fmt::Display> BinomialTree<T> {
     /// Returns the preorder representation of the heap
     ///
     /// # Arguments
@@ -347,6 +398,126 @@ impl<T: std::cmp::Ord + std::fmt::Display> BinomialTree<T> {
     }
 }
 
+impl<T: Encode> BinomialTree<T> {
+    // writes payload and children in preorder, the min flag is written once by the caller
+    pub(crate) fn write(&self, writer: &mut Writer) {
+        self.payload.encode(writer);
+        writer.write_varint(self.children.len() as u64);
+        for child in self.children.iter() {
+            match child {
+                None => writer.write_u8(0),
+                Some(child) => {
+                    writer.write_u8(1);
+                    child.write(writer);
+                }
+            }
+        }
+    }
+
+    /// Returns a snapshot of the tree in the binary format of `rudac::codec`.
+    /// The snapshot keeps the exact shape of the tree, not only its items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::BinomialTree;
+    ///
+    /// let merged_tree = BinomialTree::merge(BinomialTree::init_max(0), BinomialTree::init_max(1));
+    ///
+    /// let bytes = merged_tree.to_bytes();
+    /// let restored = BinomialTree::<i32>::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(BinomialTree::preorder(&restored), "1 0");
+    /// assert!(restored.is_max());
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"BT");
+        self.min.encode(&mut writer);
+        self.write(&mut writer);
+
+        writer.into_bytes()
+    }
+}
+
+impl<T: core::cmp::Ord + Decode> BinomialTree<T> {
+    // reads a tree written by `write`, checking ranks of the children and the heap order
+    pub(crate) fn read(reader: &mut Reader, min: bool, depth: usize) -> Option<BinomialTree<T>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let payload = Option::<T>::decode(reader)?;
+        let rank = reader.read_len()?;
+        let mut children = Vec::with_capacity(rank);
+        for index in 0..rank {
+            let child = match reader.read_u8()? {
+                0 => None,
+                1 => Some(BinomialTree::read(reader, min, depth + 1)?),
+                _ => return None,
+            };
+            if let Some(child) = &child {
+                if child.rank != index {
+                    return None;
+                }
+                if let (Some(parent), Some(payload)) = (&payload, &child.payload) {
+                    if child.order.compare(parent, payload) == Ordering::Greater {
+                        return None;
+                    }
+                }
+            }
+            children.push(child);
+        }
+
+        Some(BinomialTree {
+            rank,
+            children,
+            payload,
+            min,
+            order: Order::natural(min),
+        })
+    }
+
+    /// Restores a tree from a snapshot made by `to_bytes`.
+    /// Returns `None` if the bytes are malformed or do not describe a valid binomial tree
+    ///
+    /// # Arguments
+    /// * `bytes`: the snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Option<BinomialTree<T>> {
+        let mut reader = Reader::init(bytes);
+        read_header(&mut reader, b"BT")?;
+        let min = bool::decode(&mut reader)?;
+        let binomial_tree = BinomialTree::read(&mut reader, min, 0)?;
+        if !reader.is_at_end() {
+            return None;
+        }
+
+        Some(binomial_tree)
+    }
+}
+
+// trees are equal when both are min or both are max trees of the same shape and payloads, orders given to
+// `init_with` are not compared
+impl<T: PartialEq> PartialEq for BinomialTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+            && self.min == other.min
+            && self.payload == other.payload
+            && self.children == other.children
+    }
+}
+
+impl<T: Eq> Eq for BinomialTree<T> {}
+
+impl<T> MemoryFootprint for BinomialTree<T> {
+    fn footprint(&self) -> Footprint {
+        // a tree of rank k holds 2^k payloads
+        self.children.iter().flatten().fold(
+            Footprint::of::<T>(1 << self.rank).with_vec(&self.children),
+            |footprint, child| footprint.with(child.footprint()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -558,4 +729,40 @@ mod tests {
         bt1.get_payload();
         bt1.get_payload();
     }
+
+    #[test]
+    fn tree_binomial_bytes_round_trip() {
+        let merged_1 = BinomialTree::merge(BinomialTree::init_min(3), BinomialTree::init_min(1));
+        let merged_2 = BinomialTree::merge(BinomialTree::init_min(0), BinomialTree::init_min(2));
+        let merged_tree = BinomialTree::merge(merged_1, merged_2);
+
+        let bytes = merged_tree.to_bytes();
+        let restored = BinomialTree::<i32>::from_bytes(&bytes).unwrap();
+        assert_eq!(BinomialTree::preorder(&restored), "0 2 1 3");
+        assert_eq!(restored.rank(), 2);
+        assert!(restored.is_min());
+
+        // a max snapshot of the same nodes violates the heap order
+        let mut corrupted = bytes.clone();
+        corrupted[3] = 0;
+        assert!(BinomialTree::<i32>::from_bytes(&corrupted).is_none());
+    }
+
+    #[test]
+    fn tree_binomial_compare_roots() {
+        // the payload comparisons follow Ord whatever order the trees keep
+        let max1 = BinomialTree::init_max(0);
+        let max2 = BinomialTree::init_max(1);
+        assert!(BinomialTree::is_smaller_or_equal(&max1, &max2));
+        assert!(!BinomialTree::is_greater_or_equal(&max1, &max2));
+        assert!(BinomialTree::has_higher_priority(&max2, &max1));
+
+        let by_length = |s1: &&str, s2: &&str| s1.len().cmp(&s2.len());
+        let long = BinomialTree::init_with("rudac", by_length);
+        let short = BinomialTree::init_with("tree", by_length);
+        assert!(BinomialTree::is_greater_or_equal(&short, &long));
+        assert!(BinomialTree::has_higher_priority(&short, &long));
+        assert!(BinomialTree::is_smaller_or_equal(&long, &long));
+        assert!(BinomialTree::is_greater_or_equal(&long, &long));
+    }
 }
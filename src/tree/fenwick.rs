@@ -0,0 +1,301 @@
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Sub};
+
+/// A Fenwick tree(binary indexed tree) keeps prefix sums of a sequence under point updates.
+/// Both updates and prefix sum queries take O(log n) time
+///
+/// # Examples
+/// ```
+/// use rudac::tree::Fenwick;
+///
+/// let mut fenwick = Fenwick::from_slice(&[1, 2, 3, 4, 5]);
+///
+/// assert_eq!(fenwick.prefix_sum(3), 6);
+///
+/// fenwick.add(1, 10);
+///
+/// assert_eq!(fenwick.range_sum(1, 3), 15);
+/// assert_eq!(fenwick.total(), 25);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fenwick<T> {
+    // 1-based implicit tree. tree[i] is the sum of items in (i - lowbit(i), i]
+    tree: Vec<T>,
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Initializes a tree of `size` items equal to `T::default()`
+    ///
+    /// # Arguments
+    /// * `size`: number of items
+    pub fn init(size: usize) -> Fenwick<T> {
+        Fenwick {
+            tree: vec![T::default(); size + 1],
+        }
+    }
+
+    /// Builds a tree from `items` in O(n)
+    ///
+    /// # Arguments
+    /// * `items`: initial items
+    pub fn from_slice(items: &[T]) -> Fenwick<T> {
+        let mut tree = vec![T::default(); items.len() + 1];
+        tree[1..].copy_from_slice(items);
+
+        for i in 1..tree.len() {
+            let parent = i + lowbit(i);
+            if parent < tree.len() {
+                tree[parent] = tree[parent] + tree[i];
+            }
+        }
+
+        Fenwick { tree }
+    }
+
+    /// Returns number of items
+    pub fn size(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    /// Returns true if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Appends `item` to the end of the sequence.
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `item`: the new item
+    pub fn push(&mut self, item: T) {
+        let i = self.tree.len();
+
+        // the new node covers (i - lowbit(i), i]
+        let covered = self.prefix_sum(i - 1) - self.prefix_sum(i - lowbit(i));
+        self.tree.push(covered + item);
+    }
+
+    /// Adds `delta` to the item at `index`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    /// * `delta`: value to be added
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn add(&mut self, index: usize, delta: T) {
+        if index >= self.size() {
+            panic!("Index out of range");
+        }
+
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i] + delta;
+            i += lowbit(i);
+        }
+    }
+
+    /// Returns the item at `index`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn get(&self, index: usize) -> T {
+        if index >= self.size() {
+            panic!("Index out of range");
+        }
+
+        self.range_sum(index, index + 1)
+    }
+
+    /// Sets the item at `index` to `value`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `index`: index of the item
+    /// * `value`: new value of the item
+    ///
+    /// # Panics
+    /// * panics if `index` is out of range
+    pub fn set(&mut self, index: usize, value: T) {
+        let current = self.get(index);
+
+        // subtracting the old value first keeps unsigned types from underflowing
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i] - current + value;
+            i += lowbit(i);
+        }
+    }
+
+    /// Returns sum of the first `count` items, which are the items in range [0, `count`)
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `count`: number of items
+    ///
+    /// # Panics
+    /// * panics if `count` is greater than size of the tree
+    pub fn prefix_sum(&self, count: usize) -> T {
+        if count > self.size() {
+            panic!("Index out of range");
+        }
+
+        let mut sum = T::default();
+        let mut i = count;
+        while i > 0 {
+            sum = sum + self.tree[i];
+            i -= lowbit(i);
+        }
+
+        sum
+    }
+
+    /// Returns sum of the items in range [`start`, `end`)
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `start`: first index of the range
+    /// * `end`: end of the range(exclusive)
+    ///
+    /// # Panics
+    /// * panics if `start` is greater than `end` or `end` is greater than size of the tree
+    pub fn range_sum(&self, start: usize, end: usize) -> T {
+        if start > end {
+            panic!("Start of the range must not be greater than its end");
+        }
+
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+
+    /// Returns sum of all items
+    pub fn total(&self) -> T {
+        self.prefix_sum(self.size())
+    }
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T> + PartialOrd,
+{
+    /// Returns the largest `count` such that sum of the first `count` items is not greater than `target`.
+    /// If no item is negative, it is the index of the item whose range of prefix sums contains `target`,
+    /// which makes it the core of weighted sampling
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `target`: the target sum
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::Fenwick;
+    ///
+    /// // prefix sums: 0, 3, 3, 5, 9
+    /// let fenwick = Fenwick::from_slice(&[3, 0, 2, 4]);
+    ///
+    /// assert_eq!(fenwick.search(0), 0);
+    /// assert_eq!(fenwick.search(3), 2);
+    /// assert_eq!(fenwick.search(8), 3);
+    /// assert_eq!(fenwick.search(9), 4);
+    /// ```
+    pub fn search(&self, target: T) -> usize {
+        let mut position = 0;
+        let mut remaining = target;
+        let mut step = (self.tree.len()).next_power_of_two();
+
+        while step > 0 {
+            let next = position + step;
+            if next < self.tree.len() && self.tree[next] <= remaining {
+                position = next;
+                remaining = remaining - self.tree[next];
+            }
+            step /= 2;
+        }
+
+        position
+    }
+}
+
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+impl<T> MemoryFootprint for Fenwick<T> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<T>(self.tree.len() - 1).with_vec(&self.tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_fenwick_matches_naive() {
+        let mut items: Vec<i64> = (0..37).map(|i| (i * 7919) % 23 - 11).collect();
+        let mut fenwick = Fenwick::from_slice(&items);
+
+        for step in 0..200usize {
+            let index = (step * 31) % items.len();
+            let delta = (step as i64 * 13) % 9 - 4;
+            items[index] += delta;
+            fenwick.add(index, delta);
+
+            let start = (step * 7) % items.len();
+            let end = start + (step * 3) % (items.len() - start + 1);
+            assert_eq!(
+                fenwick.range_sum(start, end),
+                items[start..end].iter().sum::<i64>()
+            );
+        }
+
+        for (index, item) in items.iter().enumerate() {
+            assert_eq!(fenwick.get(index), *item);
+        }
+    }
+
+    #[test]
+    fn tree_fenwick_push_matches_build() {
+        let items: Vec<u64> = (1..=50).collect();
+        let mut pushed = Fenwick::init(0);
+        for item in items.iter() {
+            pushed.push(*item);
+        }
+
+        let built = Fenwick::from_slice(&items);
+        for count in 0..=items.len() {
+            assert_eq!(pushed.prefix_sum(count), built.prefix_sum(count));
+        }
+        assert_eq!(pushed.total(), 1275);
+    }
+
+    #[test]
+    fn tree_fenwick_set_and_search() {
+        let mut fenwick: Fenwick<u32> = Fenwick::init(6);
+        fenwick.set(2, 5);
+        fenwick.set(4, 1);
+        fenwick.set(2, 3);
+
+        assert_eq!(fenwick.total(), 4);
+        assert_eq!(fenwick.search(0), 2);
+        assert_eq!(fenwick.search(2), 2);
+        assert_eq!(fenwick.search(3), 4);
+        assert_eq!(fenwick.search(4), 6);
+        assert!(Fenwick::<u32>::init(0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn tree_fenwick_out_of_range() {
+        Fenwick::<i32>::init(3).add(3, 1);
+    }
+}
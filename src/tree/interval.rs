@@ -1,14 +1,19 @@
-use crate::util::Interval;
-use std::cmp::Ord;
-use std::fmt::Debug;
-use std::ops::Bound;
-use std::ops::Bound::*;
-use std::rc::Rc;
-
-#[derive(Hash)]
+use crate::tree::StabbingQueries;
+use crate::util::{Footprint, Interval, MemoryFootprint};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ord;
+use core::fmt::Debug;
+use core::hash::{Hash, Hasher};
+use core::ops::Bound;
+use core::ops::Bound::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 struct Node<T: Ord> {
     interval: Option<Interval<T>>,
-    max: Option<Rc<Bound<T>>>,
+    max: Option<Arc<Bound<T>>>,
     height: usize,
     size: usize,
     left_child: Option<Box<Node<T>>>,
@@ -16,7 +21,7 @@ struct Node<T: Ord> {
 }
 
 impl<T: Ord> Node<T> {
-    fn init(interval: Interval<T>, max: Rc<Bound<T>>, height: usize, size: usize) -> Node<T> {
+    fn init(interval: Interval<T>, max: Arc<Bound<T>>, height: usize, size: usize) -> Node<T> {
         Node {
             interval: Some(interval),
             max: Some(max),
@@ -35,8 +40,8 @@ impl<T: Ord> Node<T> {
         self.interval.take().unwrap()
     }
 
-    fn get_max(&self) -> Rc<Bound<T>> {
-        Rc::clone(&self.max.as_ref().unwrap())
+    fn get_max(&self) -> Arc<Bound<T>> {
+        Arc::clone(&self.max.as_ref().unwrap())
     }
 
     fn update_height(&mut self) {
@@ -62,10 +67,10 @@ impl<T: Ord> Node<T> {
             (None, None) => self.interval().get_high(),
         };
 
-        self.max = Some(Rc::clone(&max));
+        self.max = Some(Arc::clone(&max));
     }
 
-    fn find_max(bound1: Rc<Bound<T>>, bound2: Rc<Bound<T>>) -> Rc<Bound<T>> {
+    fn find_max(bound1: Arc<Bound<T>>, bound2: Arc<Bound<T>>) -> Arc<Bound<T>> {
         match (bound1.as_ref(), bound2.as_ref()) {
             (Included(_val1), Included(_val2))
             | (Included(_val1), Excluded(_val2))
@@ -88,7 +93,7 @@ impl<T: Ord> Node<T> {
         }
     }
 
-    fn is_ge(bound1: Rc<Bound<T>>, bound2: Rc<Bound<T>>) -> bool {
+    fn is_ge(bound1: Arc<Bound<T>>, bound2: Arc<Bound<T>>) -> bool {
         match (bound1.as_ref(), bound2.as_ref()) {
             (Included(_val1), Included(_val2)) => _val1 >= _val2,
             (Included(_val1), Excluded(_val2)) => _val1 > _val2,
@@ -105,7 +110,7 @@ impl<T: Ord> Node<T> {
     }
 
     fn _max_height(node1: &Option<Box<Node<T>>>, node2: &Option<Box<Node<T>>>) -> i64 {
-        std::cmp::max(Node::height(node1), Node::height(node2))
+        core::cmp::max(Node::height(node1), Node::height(node2))
     }
 
     fn height(node: &Option<Box<Node<T>>>) -> i64 {
@@ -176,7 +181,6 @@ impl<T: Ord> Node<T> {
 /// // intervals are: (15,23), [16,21), [17,19), (19,20]
 /// let intervals = interval_tree.intervals_between(&low, &high);
 /// ```
-#[derive(Hash)]
 pub struct IntervalTree<T: Ord> {
     root: Option<Box<Node<T>>>,
 }
@@ -363,6 +367,54 @@ impl<T: Ord> IntervalTree<T> {
         IntervalTree::_find_overlaps(&node_ref.right_child, interval, overlaps);
     }
 
+    /// Returns for every point of `points` the intervals of the tree containing it, sorted from smallest to largest.
+    /// The points are answered together by a sweep over the intervals in O((n + q) log n) time plus the size
+    /// of the answer, which is much faster than stabbing the tree once per point when there are many points.
+    /// See `StabbingQueries` for answering several batches over the same intervals
+    ///
+    /// # Arguments
+    /// * `points`: points to be stabbed, in any order
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::IntervalTree;
+    /// use rudac::util::Interval;
+    /// use std::ops::Bound::*;
+    ///
+    /// let mut interval_tree = IntervalTree::<usize>::init();
+    ///
+    /// interval_tree.insert(Interval::new(Included(0), Excluded(3)));
+    /// interval_tree.insert(Interval::new(Included(6), Included(10)));
+    /// interval_tree.insert(Interval::new(Excluded(8), Included(9)));
+    /// interval_tree.insert(Interval::new(Excluded(15), Excluded(23)));
+    ///
+    /// let stabbed = interval_tree.query_batch(&[9, 3, 20]);
+    ///
+    /// assert_eq!(
+    ///     stabbed[0],
+    ///     vec![
+    ///         &Interval::new(Included(6), Included(10)),
+    ///         &Interval::new(Excluded(8), Included(9))
+    ///     ]
+    /// );
+    /// assert!(stabbed[1].is_empty());
+    /// assert_eq!(stabbed[2], vec![&Interval::new(Excluded(15), Excluded(23))]);
+    /// ```
+    pub fn query_batch(&self, points: &[T]) -> Vec<Vec<&Interval<T>>> {
+        let mut intervals = Vec::with_capacity(self.size());
+        IntervalTree::_interval_refs(&self.root, &mut intervals);
+
+        StabbingQueries::init(intervals).stab(points)
+    }
+
+    fn _interval_refs<'a>(node: &'a Option<Box<Node<T>>>, intervals: &mut Vec<&'a Interval<T>>) {
+        if let Some(node_ref) = node {
+            IntervalTree::_interval_refs(&node_ref.left_child, intervals);
+            intervals.push(node_ref.interval());
+            IntervalTree::_interval_refs(&node_ref.right_child, intervals);
+        }
+    }
+
     /// Inserts an interval in the tree. if interval already exists, `interval` will be ignored
     ///
     /// # Arguments
@@ -397,7 +449,7 @@ impl<T: Ord> IntervalTree<T> {
     fn _insert(
         node: Option<Box<Node<T>>>,
         interval: Interval<T>,
-        max: Rc<Bound<T>>,
+        max: Arc<Bound<T>>,
     ) -> Option<Box<Node<T>>> {
         if node.is_none() {
             return Some(Box::new(Node::init(interval, max, 0, 1)));
@@ -781,9 +833,8 @@ impl<T: Ord> IntervalTree<T> {
         }
     }
 
-
     /// Returns all intervals in the tree following an in-order traversal.
-    /// Therefore intervals are sorted from smallest to largest 
+    /// Therefore intervals are sorted from smallest to largest
     pub fn intervals(&self) -> Vec<Interval<T>> {
         let mut intervals: Vec<Interval<T>> = Vec::new();
 
@@ -888,13 +939,88 @@ impl<T: Ord> IntervalTree<T> {
     }
 }
 
+// copies share the bounds of their intervals with the original, so `T` does not have to be `Clone`
+impl<T: Ord> Clone for Node<T> {
+    fn clone(&self) -> Self {
+        Node {
+            interval: self.interval.clone(),
+            max: self.max.clone(),
+            height: self.height,
+            size: self.size,
+            left_child: self.left_child.clone(),
+            right_child: self.right_child.clone(),
+        }
+    }
+}
+
+impl<T: Ord> Clone for IntervalTree<T> {
+    fn clone(&self) -> Self {
+        IntervalTree {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<T: Ord> Default for IntervalTree<T> {
+    fn default() -> Self {
+        IntervalTree::init()
+    }
+}
+
+// trees are equal when they hold the same intervals, whatever their shape
+impl<T: Ord> PartialEq for IntervalTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.intervals() == other.intervals()
+    }
+}
+
+impl<T: Ord> Eq for IntervalTree<T> {}
+
+// hashes the intervals in order, like equality does
+impl<T: Ord + Hash> Hash for IntervalTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.intervals().hash(state);
+    }
+}
+
 impl<T: Debug + Ord> Debug for IntervalTree<T> {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         fmt.write_str("IntervalTree ")?;
         fmt.debug_set().entries(self.intervals().iter()).finish()
     }
 }
 
+impl<T: Ord> MemoryFootprint for IntervalTree<T> {
+    fn footprint(&self) -> Footprint {
+        // every interval owns its two bounds, the maximum of a subtree shares the bound of one of its intervals
+        let size = self.size();
+        Footprint::of::<(Bound<T>, Bound<T>)>(size)
+            .with_boxes::<Node<T>>(size)
+            .with_rcs::<Bound<T>>(2 * size)
+    }
+}
+
+// a tree is written as its intervals in increasing order and rebuilt by inserting them
+#[cfg(feature = "serde")]
+impl<T: Ord + Serialize> Serialize for IntervalTree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.intervals())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Ord + Deserialize<'de>> Deserialize<'de> for IntervalTree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let intervals: Vec<Interval<T>> = Vec::deserialize(deserializer)?;
+        let mut interval_tree = IntervalTree::init();
+        for interval in intervals {
+            interval_tree.insert(interval);
+        }
+
+        Ok(interval_tree)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1388,7 +1514,72 @@ mod tests {
         let mut interval_tree = IntervalTree::<usize>::init();
         assert_eq!(format!("{:?}", &interval_tree), "IntervalTree {}");
         interval_tree.insert(Interval::new(Excluded(0), Included(1)));
-        assert_eq!(format!("{:?}", &interval_tree),
-            "IntervalTree {Interval { low: Excluded(0), high: Included(1) }}");
+        assert_eq!(
+            format!("{:?}", &interval_tree),
+            "IntervalTree {Interval { low: Excluded(0), high: Included(1) }}"
+        );
+    }
+
+    #[test]
+    fn tree_interval_query_batch() {
+        let mut interval_tree = IntervalTree::<usize>::init();
+        for low in 0..100 {
+            interval_tree.insert(Interval::new(Included(low), Excluded(low + low % 7 + 1)));
+            interval_tree.insert(Interval::new(Excluded(low), Unbounded));
+        }
+
+        let points: Vec<usize> = (0..110).rev().collect();
+        let stabbed = interval_tree.query_batch(&points);
+        for (point, stabbed) in points.iter().zip(stabbed) {
+            let mut expected = interval_tree.find_overlaps(&Interval::point(*point));
+            expected.sort();
+            let stabbed: Vec<Interval<usize>> =
+                stabbed.into_iter().map(Interval::duplicate).collect();
+            assert_eq!(stabbed, expected);
+        }
+
+        assert!(
+            IntervalTree::<usize>::init().query_batch(&[1]) == vec![Vec::<&Interval<usize>>::new()]
+        );
+    }
+
+    #[test]
+    fn tree_interval_clone_and_eq() {
+        let mut interval_tree = IntervalTree::default();
+        for low in [5, 1, 9, 3] {
+            interval_tree.insert(Interval::new(Included(low), Excluded(low + 2)));
+        }
+
+        let mut copy = interval_tree.clone();
+        assert!(copy == interval_tree);
+        copy.delete(&Interval::new(Included(1), Excluded(3)));
+        assert!(copy != interval_tree);
+        assert_eq!(interval_tree.size(), 4);
+
+        // the same intervals inserted in another order give an equal tree
+        let mut other = IntervalTree::init();
+        for low in [9, 5, 3] {
+            other.insert(Interval::new(Included(low), Excluded(low + 2)));
+        }
+        assert!(copy == other);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tree_interval_serde() {
+        let mut interval_tree = IntervalTree::<u32>::init();
+        interval_tree.insert(Interval::new(Excluded(5), Included(8)));
+        interval_tree.insert(Interval::new(Included(0), Excluded(3)));
+        interval_tree.insert(Interval::point(4));
+
+        let json = serde_json::to_string(&interval_tree).unwrap();
+        assert_eq!(
+            json,
+            r#"[[{"Included":0},{"Excluded":3}],[{"Included":4},{"Included":4}],[{"Excluded":5},{"Included":8}]]"#
+        );
+
+        let restored: IntervalTree<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, interval_tree);
+        assert!(restored.overlaps(&Interval::point(7)));
     }
 }
@@ -0,0 +1,505 @@
+use crate::tree::{avl, rb};
+use crate::util::{stats, Footprint, MemoryFootprint};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+// index of a missing child or parent
+pub(crate) const NIL: usize = usize::MAX;
+
+/// Node layout of the balanced search trees `AVL` and `RedBlack`, selected by their last type parameter
+///
+/// * `Boxed`: every node is a separate heap allocation linked by boxes. This is the default layout
+/// * `Soa`: nodes live in an arena as a structure of arrays, linked by indices
+pub trait Layout {
+    /// Storage of an AVL tree with this layout
+    type Avl<K: core::cmp::Ord, V>;
+
+    /// Storage of a Red Black tree with this layout
+    type RedBlack<K: core::cmp::Ord, V>;
+}
+
+/// Pointer based layout, every node is allocated separately and owns its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Boxed;
+
+/// Index based structure-of-arrays layout. Keys, values and the left, right and parent indices of the nodes
+/// are stored in separate dense vectors, so a search only touches the keys and child indices it needs and
+/// the nodes of a tree are never scattered over the heap. It is the better choice for read-heavy workloads on large key sets
+///
+/// # Examples
+/// ```
+/// use rudac::tree::{Soa, AVL, RedBlack};
+///
+/// let mut avl_tree = AVL::<usize, usize, Soa>::init();
+/// let mut rb_tree = RedBlack::<usize, usize, Soa>::init();
+///
+/// for i in 0..100 {
+///     avl_tree.insert(i, i * 10);
+///     rb_tree.insert(i, i * 10);
+/// }
+/// avl_tree.delete(&50);
+/// rb_tree.delete(&50);
+///
+/// assert_eq!(avl_tree.get(&51), Some(&510));
+/// assert_eq!(rb_tree.floor(&50), Some(&49));
+/// assert_eq!(avl_tree.keys(), rb_tree.keys());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Soa;
+
+impl Layout for Boxed {
+    type Avl<K: core::cmp::Ord, V> = Option<Box<avl::Node<K, V>>>;
+    type RedBlack<K: core::cmp::Ord, V> = Option<Box<rb::Node<K, V>>>;
+}
+
+impl Layout for Soa {
+    type Avl<K: core::cmp::Ord, V> = Arena<K, V>;
+    type RedBlack<K: core::cmp::Ord, V> = Arena<K, V>;
+}
+
+/// Node storage of the `Soa` layout. Node `i` is made of the `i`th entry of every vector, `tags` holds the height
+/// of AVL nodes and the color of Red Black nodes. Removed nodes are filled with the last node so the vectors stay dense
+#[derive(Clone)]
+pub struct Arena<K, V> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    parent: Vec<usize>,
+    sizes: Vec<usize>,
+    tags: Vec<u8>,
+    root: usize,
+}
+
+impl<K, V> Default for Arena<K, V> {
+    fn default() -> Arena<K, V> {
+        Arena::init()
+    }
+}
+
+impl<K, V> Arena<K, V> {
+    pub(crate) fn init() -> Arena<K, V> {
+        Arena {
+            keys: Vec::new(),
+            values: Vec::new(),
+            left: Vec::new(),
+            right: Vec::new(),
+            parent: Vec::new(),
+            sizes: Vec::new(),
+            tags: Vec::new(),
+            root: NIL,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub(crate) fn root(&self) -> usize {
+        self.root
+    }
+
+    // adds a node without children and returns its index
+    pub(crate) fn push(&mut self, key: K, value: V, tag: u8) -> usize {
+        let len = self.keys.len();
+        for capacity in [
+            self.keys.capacity(),
+            self.values.capacity(),
+            self.left.capacity(),
+            self.right.capacity(),
+            self.parent.capacity(),
+            self.sizes.capacity(),
+            self.tags.capacity(),
+        ] {
+            stats::growth(len, capacity);
+        }
+
+        self.keys.push(key);
+        self.values.push(value);
+        self.left.push(NIL);
+        self.right.push(NIL);
+        self.parent.push(NIL);
+        self.sizes.push(1);
+        self.tags.push(tag);
+
+        self.keys.len() - 1
+    }
+
+    pub(crate) fn key(&self, node: usize) -> &K {
+        &self.keys[node]
+    }
+
+    pub(crate) fn set_value(&mut self, node: usize, value: V) {
+        self.values[node] = value;
+    }
+
+    pub(crate) fn left(&self, node: usize) -> usize {
+        self.left[node]
+    }
+
+    pub(crate) fn right(&self, node: usize) -> usize {
+        self.right[node]
+    }
+
+    #[cfg(test)]
+    pub(crate) fn parent(&self, node: usize) -> usize {
+        self.parent[node]
+    }
+
+    pub(crate) fn tag(&self, node: usize) -> u8 {
+        self.tags[node]
+    }
+
+    pub(crate) fn set_tag(&mut self, node: usize, tag: u8) {
+        self.tags[node] = tag;
+    }
+
+    pub(crate) fn size(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.sizes[node]
+        }
+    }
+
+    pub(crate) fn update_size(&mut self, node: usize) {
+        self.sizes[node] = 1 + self.size(self.left[node]) + self.size(self.right[node]);
+    }
+
+    pub(crate) fn set_left(&mut self, node: usize, child: usize) {
+        self.left[node] = child;
+        if child != NIL {
+            self.parent[child] = node;
+        }
+    }
+
+    pub(crate) fn set_right(&mut self, node: usize, child: usize) {
+        self.right[node] = child;
+        if child != NIL {
+            self.parent[child] = node;
+        }
+    }
+
+    pub(crate) fn set_root(&mut self, node: usize) {
+        self.root = node;
+        if node != NIL {
+            self.parent[node] = NIL;
+        }
+    }
+
+    // exchanges keys and values of two nodes, links are kept
+    pub(crate) fn swap_entries(&mut self, node1: usize, node2: usize) {
+        self.keys.swap(node1, node2);
+        self.values.swap(node1, node2);
+    }
+
+    // removes a node that is no longer linked into the tree and returns its entry.
+    // the last node is moved into the freed slot, the parent pointers tell whose links have to follow it
+    pub(crate) fn remove(&mut self, node: usize) -> (K, V) {
+        let last = self.keys.len() - 1;
+        if node != last {
+            let parent = self.parent[last];
+            if parent == NIL {
+                self.root = node;
+            } else if self.left[parent] == last {
+                self.left[parent] = node;
+            } else {
+                self.right[parent] = node;
+            }
+            for child in [self.left[last], self.right[last]].iter() {
+                if *child != NIL {
+                    self.parent[*child] = node;
+                }
+            }
+        }
+
+        self.left.swap_remove(node);
+        self.right.swap_remove(node);
+        self.parent.swap_remove(node);
+        self.sizes.swap_remove(node);
+        self.tags.swap_remove(node);
+
+        (self.keys.swap_remove(node), self.values.swap_remove(node))
+    }
+
+    // removes several unlinked nodes, highest index first so that no node waiting for removal is moved
+    pub(crate) fn remove_all(&mut self, mut nodes: Vec<usize>) {
+        nodes.sort_unstable();
+        for node in nodes.into_iter().rev() {
+            self.remove(node);
+        }
+    }
+
+    // returns the entries in order of their keys
+    pub(crate) fn entries(&self) -> Vec<(&K, &V)> {
+        let mut entries = Vec::with_capacity(self.len());
+        let mut stack = Vec::new();
+        let mut node = self.root;
+        while node != NIL || !stack.is_empty() {
+            while node != NIL {
+                stack.push(node);
+                node = self.left[node];
+            }
+            node = stack.pop().unwrap();
+            entries.push((&self.keys[node], &self.values[node]));
+            node = self.right[node];
+        }
+
+        entries
+    }
+
+    pub(crate) fn height(&self, node: usize) -> i64 {
+        if node == NIL {
+            return -1;
+        }
+
+        1 + core::cmp::max(self.height(self.left[node]), self.height(self.right[node]))
+    }
+}
+
+impl<K: core::cmp::Ord, V> Arena<K, V> {
+    pub(crate) fn get(&self, key: &K) -> Option<&V> {
+        let mut node = self.root;
+        while node != NIL {
+            stats::comparison();
+            match key.cmp(&self.keys[node]) {
+                core::cmp::Ordering::Less => node = self.left[node],
+                core::cmp::Ordering::Greater => node = self.right[node],
+                core::cmp::Ordering::Equal => return Some(&self.values[node]),
+            }
+        }
+
+        None
+    }
+
+    pub(crate) fn floor(&self, key: &K) -> Option<&K> {
+        let mut node = self.root;
+        let mut found = None;
+        while node != NIL {
+            stats::comparison();
+            match key.cmp(&self.keys[node]) {
+                core::cmp::Ordering::Less => node = self.left[node],
+                core::cmp::Ordering::Greater => {
+                    found = Some(&self.keys[node]);
+                    node = self.right[node];
+                }
+                core::cmp::Ordering::Equal => return Some(&self.keys[node]),
+            }
+        }
+
+        found
+    }
+
+    pub(crate) fn ceiling(&self, key: &K) -> Option<&K> {
+        let mut node = self.root;
+        let mut found = None;
+        while node != NIL {
+            stats::comparison();
+            match key.cmp(&self.keys[node]) {
+                core::cmp::Ordering::Less => {
+                    found = Some(&self.keys[node]);
+                    node = self.left[node];
+                }
+                core::cmp::Ordering::Greater => node = self.right[node],
+                core::cmp::Ordering::Equal => return Some(&self.keys[node]),
+            }
+        }
+
+        found
+    }
+
+    pub(crate) fn select(&self, mut k: usize) -> Option<(&K, &V)> {
+        let mut node = self.root;
+        while node != NIL {
+            let t = self.size(self.left[node]);
+            if t > k {
+                node = self.left[node];
+            } else if t < k {
+                k -= t + 1;
+                node = self.right[node];
+            } else {
+                return Some((&self.keys[node], &self.values[node]));
+            }
+        }
+
+        None
+    }
+
+    pub(crate) fn rank(&self, key: &K) -> usize {
+        let mut node = self.root;
+        let mut rank = 0;
+        while node != NIL {
+            stats::comparison();
+            match key.cmp(&self.keys[node]) {
+                core::cmp::Ordering::Less => node = self.left[node],
+                core::cmp::Ordering::Greater => {
+                    rank += 1 + self.size(self.left[node]);
+                    node = self.right[node];
+                }
+                core::cmp::Ordering::Equal => return rank + self.size(self.left[node]),
+            }
+        }
+
+        rank
+    }
+
+    pub(crate) fn keys(&self) -> Vec<&K> {
+        let mut keys = Vec::with_capacity(self.len());
+        self._keys_between(self.root, None, None, &mut keys);
+
+        keys
+    }
+
+    pub(crate) fn keys_between(&self, low_key: &K, high_key: &K) -> Vec<&K> {
+        let mut keys = Vec::new();
+        self._keys_between(self.root, Some(low_key), Some(high_key), &mut keys);
+
+        keys
+    }
+
+    fn _keys_between<'a>(
+        &'a self,
+        node: usize,
+        low_key: Option<&K>,
+        high_key: Option<&K>,
+        keys: &mut Vec<&'a K>,
+    ) {
+        if node == NIL {
+            return;
+        }
+
+        let key = &self.keys[node];
+        stats::comparison();
+        let above_low = low_key.is_none_or(|low_key| *low_key <= *key);
+        let below_high = high_key.is_none_or(|high_key| *key < *high_key);
+        if low_key.is_none_or(|low_key| *low_key < *key) {
+            self._keys_between(self.left[node], low_key, high_key, keys);
+        }
+        if above_low && below_high {
+            keys.push(key);
+        }
+        if below_high {
+            self._keys_between(self.right[node], low_key, high_key, keys);
+        }
+    }
+
+    pub(crate) fn keys_in_level_order(&self) -> Vec<&K> {
+        let mut keys = Vec::with_capacity(self.len());
+        if self.root == NIL {
+            return keys;
+        }
+
+        let mut queue = VecDeque::with_capacity(self.len());
+        queue.push_back(self.root);
+        while let Some(node) = queue.pop_front() {
+            keys.push(&self.keys[node]);
+            if self.left[node] != NIL {
+                queue.push_back(self.left[node]);
+            }
+            if self.right[node] != NIL {
+                queue.push_back(self.right[node]);
+            }
+        }
+
+        keys
+    }
+}
+
+impl<K, V> MemoryFootprint for Arena<K, V> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<(K, V)>(self.len())
+            .with_vec(&self.keys)
+            .with_vec(&self.values)
+            .with_vec(&self.left)
+            .with_vec(&self.right)
+            .with_vec(&self.parent)
+            .with_vec(&self.sizes)
+            .with_vec(&self.tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // checks that every child points back to its parent and that sizes add up
+    fn is_linked<K, V>(arena: &Arena<K, V>, node: usize, parent: usize) -> bool {
+        if node == NIL {
+            return true;
+        }
+
+        arena.parent[node] == parent
+            && arena.size(node) == 1 + arena.size(arena.left(node)) + arena.size(arena.right(node))
+            && is_linked(arena, arena.left(node), node)
+            && is_linked(arena, arena.right(node), node)
+    }
+
+    #[test]
+    fn tree_layout_arena_remove_moves_last_node() {
+        let mut arena = Arena::init();
+        let a = arena.push(2, "b", 0);
+        let b = arena.push(1, "a", 0);
+        let c = arena.push(3, "c", 0);
+        arena.set_left(a, b);
+        arena.set_right(a, c);
+        arena.update_size(a);
+        arena.set_root(a);
+        assert!(is_linked(&arena, arena.root(), NIL));
+
+        // unlink the left leaf, the right leaf takes over its slot
+        arena.set_left(a, NIL);
+        arena.update_size(a);
+        assert_eq!(arena.remove(b), (1, "a"));
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena.right(a), b);
+        assert_eq!(*arena.key(b), 3);
+        assert!(is_linked(&arena, arena.root(), NIL));
+
+        // unlink the root, the remaining leaf becomes the root in slot 0
+        let leaf = arena.right(a);
+        arena.set_right(a, NIL);
+        arena.set_root(leaf);
+        assert_eq!(arena.remove(a), (2, "b"));
+        assert_eq!(arena.root(), 0);
+        assert_eq!(arena.keys(), vec![&3]);
+        assert!(is_linked(&arena, arena.root(), NIL));
+    }
+
+    #[test]
+    fn tree_layout_arena_queries() {
+        let mut arena = Arena::init();
+        let mut nodes = Vec::new();
+        for key in [4, 2, 6, 1, 3, 5, 7].iter() {
+            nodes.push(arena.push(*key, *key * 10, 0));
+        }
+        arena.set_left(nodes[0], nodes[1]);
+        arena.set_right(nodes[0], nodes[2]);
+        arena.set_left(nodes[1], nodes[3]);
+        arena.set_right(nodes[1], nodes[4]);
+        arena.set_left(nodes[2], nodes[5]);
+        arena.set_right(nodes[2], nodes[6]);
+        for node in [1, 2, 0].iter() {
+            arena.update_size(nodes[*node]);
+        }
+        arena.set_root(nodes[0]);
+
+        assert_eq!(arena.get(&5), Some(&50));
+        assert_eq!(arena.get(&8), None);
+        assert_eq!(arena.floor(&0), None);
+        assert_eq!(arena.floor(&8), Some(&7));
+        assert_eq!(arena.ceiling(&0), Some(&1));
+        assert_eq!(arena.ceiling(&8), None);
+        assert_eq!(arena.select(4), Some((&5, &50)));
+        assert_eq!(arena.select(7), None);
+        assert_eq!(arena.rank(&5), 4);
+        assert_eq!(arena.rank(&9), 7);
+        assert_eq!(arena.keys(), vec![&1, &2, &3, &4, &5, &6, &7]);
+        assert_eq!(arena.keys_between(&2, &6), vec![&2, &3, &4, &5]);
+        assert_eq!(
+            arena.keys_in_level_order(),
+            vec![&4, &2, &6, &1, &3, &5, &7]
+        );
+        assert_eq!(arena.height(arena.root()), 2);
+    }
+}
@@ -0,0 +1,453 @@
+use crate::sequence::RankSelect;
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A static ordered tree in the level-order unary degree sequence(LOUDS) encoding. Nodes are numbered in level order,
+/// root first, and every node is written as as many set bits as it has children followed by an unset bit.
+/// With a leading `10` for a virtual parent of the root, a tree of n nodes takes 2n + 1 bits, plus the directory of
+/// `RankSelect`. Navigation is answered by rank and select over the bits, and the children of a node have consecutive
+/// numbers, so labels of the nodes can be kept in a plain vector indexed by node. Suits huge hierarchies that do not
+/// change, like taxonomies and file trees
+///
+/// # Examples
+/// ```
+/// use rudac::tree::LoudsTree;
+///
+/// //        0
+/// //      / | \
+/// //     1  2  3
+/// //    / \    |
+/// //   4   5   6
+/// let tree = LoudsTree::from_degrees(vec![3, 2, 0, 1, 0, 0, 0]);
+///
+/// assert_eq!(tree.size(), 7);
+/// assert_eq!(tree.children(0), 1..4);
+/// assert_eq!(tree.child(1, 1), Some(5));
+/// assert_eq!(tree.parent(6), Some(3));
+/// assert_eq!(tree.next_sibling(2), Some(3));
+/// assert_eq!(tree.next_sibling(3), None);
+/// assert_eq!(tree.depth(5), 2);
+/// assert!(tree.is_leaf(2));
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct LoudsTree {
+    bits: RankSelect,
+
+    // number of nodes
+    size: usize,
+}
+
+impl LoudsTree {
+    /// Builds the tree from the number of children of every node, nodes given in level order
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `degrees`: number of children of every node in level order
+    ///
+    /// # Panics
+    /// * panics if the degrees do not describe a single tree: there must be one node more than the sum of the degrees
+    ///   and every node but the root must be a child of an earlier node
+    pub fn from_degrees<I: IntoIterator<Item = usize>>(degrees: I) -> LoudsTree {
+        let mut bits = vec![true, false];
+        let mut size = 0;
+
+        // nodes written so far, and nodes that are already known as children
+        let mut reached = 1;
+        for degree in degrees {
+            if size == reached {
+                panic!("Degrees do not describe a tree");
+            }
+            size += 1;
+            reached += degree;
+            bits.extend(core::iter::repeat_n(true, degree));
+            bits.push(false);
+        }
+
+        if size == 0 {
+            return LoudsTree {
+                bits: RankSelect::from_bits(Vec::new()),
+                size,
+            };
+        }
+        if size != reached {
+            panic!("Degrees do not describe a tree");
+        }
+
+        LoudsTree {
+            bits: RankSelect::from_bits(bits),
+            size,
+        }
+    }
+
+    /// Builds the tree whose node `node` has the children `children[node]`, in order, with node 0 as the root.
+    /// Nodes are numbered again in level order, so the nodes of the input at every node of the tree are returned as
+    /// well
+    /// * Complexity: O(n)
+    ///
+    /// # Arguments
+    /// * `children`: children of every node, empty for no nodes
+    ///
+    /// # Panics
+    /// * panics if a child is out of range or the lists do not describe a single tree rooted at node 0
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::LoudsTree;
+    ///
+    /// // a file tree
+    /// let names = vec!["/", "usr", "etc", "bin", "hosts", "lib"];
+    /// let children = vec![vec![1, 2], vec![3, 5], vec![4], vec![], vec![], vec![]];
+    ///
+    /// let (tree, order) = LoudsTree::from_children(&children);
+    /// let labels: Vec<&str> = order.iter().map(|&node| names[node]).collect();
+    ///
+    /// assert_eq!(labels, vec!["/", "usr", "etc", "bin", "lib", "hosts"]);
+    /// let hosts = labels.iter().position(|&label| label == "hosts").unwrap();
+    /// assert_eq!(labels[tree.parent(hosts).unwrap()], "etc");
+    /// ```
+    pub fn from_children(children: &[Vec<usize>]) -> (LoudsTree, Vec<usize>) {
+        let mut order = Vec::with_capacity(children.len());
+        let mut seen = vec![false; children.len()];
+        let mut queue = VecDeque::new();
+        if !children.is_empty() {
+            seen[0] = true;
+            queue.push_back(0);
+        }
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &child in &children[node] {
+                if seen[child] {
+                    panic!("Children do not describe a tree");
+                }
+                seen[child] = true;
+                queue.push_back(child);
+            }
+        }
+        if order.len() != children.len() {
+            panic!("Children do not describe a tree");
+        }
+
+        let tree = LoudsTree::from_degrees(order.iter().map(|&node| children[node].len()));
+
+        (tree, order)
+    }
+
+    /// Returns number of nodes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if the tree has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the root, None if the tree is empty
+    pub fn root(&self) -> Option<usize> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    }
+
+    // position of the unset bit before the children of `node`
+    fn children_start(&self, node: usize) -> usize {
+        if node >= self.size {
+            panic!("Node out of range");
+        }
+
+        self.bits.select0(node).unwrap()
+    }
+
+    /// Returns number of children of `node`
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn degree(&self, node: usize) -> usize {
+        let start = self.children_start(node);
+
+        self.bits.select0(node + 1).unwrap() - start - 1
+    }
+
+    /// Returns true if `node` has no children
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn is_leaf(&self, node: usize) -> bool {
+        !self.bits.get(self.children_start(node) + 1)
+    }
+
+    /// Returns the children of `node`, which are numbered consecutively
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn children(&self, node: usize) -> Range<usize> {
+        let start = self.children_start(node) + 1;
+        let first = self.bits.rank1(start);
+
+        first..first + self.degree(node)
+    }
+
+    /// Returns the first child of `node`, None if it is a leaf
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn first_child(&self, node: usize) -> Option<usize> {
+        self.child(node, 0)
+    }
+
+    /// Returns the `k`th(0-based) child of `node`, None if it has not that many children
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    /// * `k`: index of the child among the children of `node`
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn child(&self, node: usize, k: usize) -> Option<usize> {
+        let children = self.children(node);
+        if k < children.len() {
+            Some(children.start + k)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the parent of `node`, None for the root
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        if node >= self.size {
+            panic!("Node out of range");
+        }
+        if node == 0 {
+            return None;
+        }
+
+        // the unset bits before the bit of a node close its parent and the nodes before the parent
+        let position = self.bits.select1(node).unwrap();
+
+        Some(self.bits.rank0(position) - 1)
+    }
+
+    /// Returns the next child of the parent of `node`, None if `node` is the last child or the root
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn next_sibling(&self, node: usize) -> Option<usize> {
+        if node >= self.size {
+            panic!("Node out of range");
+        }
+        let position = self.bits.select1(node).unwrap();
+
+        if self.bits.get(position + 1) {
+            Some(node + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the previous child of the parent of `node`, None if `node` is the first child or the root
+    /// * Complexity: O(log n)
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn previous_sibling(&self, node: usize) -> Option<usize> {
+        if node >= self.size {
+            panic!("Node out of range");
+        }
+        if node == 0 {
+            return None;
+        }
+        let position = self.bits.select1(node).unwrap();
+
+        if self.bits.get(position - 1) {
+            Some(node - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns number of edges from the root to `node`
+    /// * Complexity: O(d log n) where d is the depth of `node`
+    ///
+    /// # Arguments
+    /// * `node`: the node
+    ///
+    /// # Panics
+    /// * panics if `node` is out of range
+    pub fn depth(&self, node: usize) -> usize {
+        let mut depth = 0;
+        let mut current = node;
+        while let Some(parent) = self.parent(current) {
+            depth += 1;
+            current = parent;
+        }
+
+        depth
+    }
+}
+
+impl MemoryFootprint for LoudsTree {
+    fn footprint(&self) -> Footprint {
+        // the shape of a node carries no payload
+        Footprint::init(self.size, 0).with(self.bits.footprint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    // random tree as lists of children, node 0 is the root and every other node has an earlier parent
+    fn random_children(size: usize, rng: &mut SplitMix64) -> (Vec<Vec<usize>>, Vec<Option<usize>>) {
+        let mut children = vec![Vec::new(); size];
+        let mut parents = vec![None; size];
+        for (node, parent) in parents.iter_mut().enumerate().skip(1) {
+            let chosen = rng.next_below(node as u64) as usize;
+            children[chosen].push(node);
+            *parent = Some(chosen);
+        }
+
+        (children, parents)
+    }
+
+    #[test]
+    fn tree_louds_matches_pointer_tree() {
+        let mut rng = SplitMix64::init(17);
+        for size in [1, 2, 10, 300, 2000] {
+            let (children, parents) = random_children(size, &mut rng);
+            let (tree, order) = LoudsTree::from_children(&children);
+            assert_eq!(tree.size(), size);
+
+            // node of the tree of every input node
+            let mut position = vec![0; size];
+            for (node, &input) in order.iter().enumerate() {
+                position[input] = node;
+            }
+
+            for (node, &input) in order.iter().enumerate() {
+                assert_eq!(
+                    tree.parent(node),
+                    parents[input].map(|parent| position[parent])
+                );
+                assert_eq!(tree.degree(node), children[input].len());
+                assert_eq!(tree.is_leaf(node), children[input].is_empty());
+
+                let expected: Vec<usize> = children[input]
+                    .iter()
+                    .map(|&child| position[child])
+                    .collect();
+                assert_eq!(tree.children(node).collect::<Vec<_>>(), expected);
+                for (k, &child) in expected.iter().enumerate() {
+                    assert_eq!(tree.child(node, k), Some(child));
+                }
+                assert_eq!(tree.child(node, expected.len()), None);
+                assert_eq!(tree.first_child(node), expected.first().copied());
+
+                let mut depth = 0;
+                let mut current = input;
+                while let Some(parent) = parents[current] {
+                    depth += 1;
+                    current = parent;
+                }
+                assert_eq!(tree.depth(node), depth);
+            }
+
+            for node in 0..size {
+                let siblings = match tree.parent(node) {
+                    Some(parent) => tree.children(parent),
+                    None => 0..1,
+                };
+                let next = Some(node + 1).filter(|next| *next < siblings.end && node > 0);
+                let previous = Some(node.wrapping_sub(1)).filter(|_| node > siblings.start);
+                assert_eq!(tree.next_sibling(node), next);
+                assert_eq!(tree.previous_sibling(node), previous);
+            }
+        }
+    }
+
+    #[test]
+    fn tree_louds_empty_and_single() {
+        let tree = LoudsTree::from_degrees(Vec::new());
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), None);
+
+        let tree = LoudsTree::from_degrees(vec![0]);
+        assert_eq!(tree.root(), Some(0));
+        assert_eq!(tree.parent(0), None);
+        assert_eq!(tree.children(0), 1..1);
+        assert_eq!(tree.next_sibling(0), None);
+        assert_eq!(tree.previous_sibling(0), None);
+    }
+
+    #[test]
+    fn tree_louds_footprint() {
+        let mut rng = SplitMix64::init(18);
+        let (children, _) = random_children(100_000, &mut rng);
+        let (tree, _) = LoudsTree::from_children(&children);
+
+        // 2 bits per node, the directory of the bits and spare capacity
+        let bits = tree.footprint().allocated_bytes() * 8;
+        assert!(bits < 100_000 * 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Degrees do not describe a tree")]
+    fn tree_louds_panic_forest() {
+        LoudsTree::from_degrees(vec![1, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Degrees do not describe a tree")]
+    fn tree_louds_panic_missing_children() {
+        LoudsTree::from_degrees(vec![2, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Children do not describe a tree")]
+    fn tree_louds_panic_shared_child() {
+        LoudsTree::from_children(&[vec![1, 2], vec![2], vec![]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Node out of range")]
+    fn tree_louds_panic_node_out_of_range() {
+        let tree = LoudsTree::from_degrees(vec![1, 0]);
+        tree.parent(2);
+    }
+}
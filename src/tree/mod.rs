@@ -1,9 +1,51 @@
 mod avl;
+mod batch;
+mod beats;
 mod binomial;
+mod fenwick;
 mod rb;
+mod stabbing;
+mod treap;
 mod interval;
+mod layout;
+mod louds;
+mod persistent_avl;
+mod trie;
 
 pub use avl::AVL;
+pub use beats::SegmentTreeBeats;
 pub use binomial::BinomialTree;
+pub use fenwick::Fenwick;
 pub use rb::RedBlack;
+pub use stabbing::StabbingQueries;
+pub use treap::Treap;
 pub use interval::IntervalTree;
+pub use layout::Boxed;
+pub use layout::Layout;
+pub use layout::Soa;
+pub use louds::LoudsTree;
+pub use persistent_avl::PersistentAVL;
+pub use trie::Trie;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_send_and_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn tree_containers_are_send_and_sync() {
+        is_send_and_sync::<AVL<String, String>>();
+        is_send_and_sync::<AVL<String, String, Soa>>();
+        is_send_and_sync::<BinomialTree<String>>();
+        is_send_and_sync::<Fenwick<i64>>();
+        is_send_and_sync::<IntervalTree<String>>();
+        is_send_and_sync::<LoudsTree>();
+        is_send_and_sync::<PersistentAVL<String, String>>();
+        is_send_and_sync::<RedBlack<String, String>>();
+        is_send_and_sync::<RedBlack<String, String, Soa>>();
+        is_send_and_sync::<SegmentTreeBeats>();
+        is_send_and_sync::<Treap<String, String>>();
+        is_send_and_sync::<Trie<String>>();
+    }
+}
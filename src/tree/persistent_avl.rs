@@ -0,0 +1,770 @@
+use crate::replay::{Op, Replayable};
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+type Link<K, V> = Option<Arc<Node<K, V>>>;
+
+#[derive(Clone)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    height: usize,
+    size: usize,
+    left_child: Link<K, V>,
+    right_child: Link<K, V>,
+}
+
+impl<K, V> Node<K, V> {
+    fn init(key: K, value: V) -> Node<K, V> {
+        Node {
+            key,
+            value,
+            height: 0,
+            size: 1,
+            left_child: None,
+            right_child: None,
+        }
+    }
+
+    fn height(node: &Link<K, V>) -> i64 {
+        match node {
+            Some(_node) => _node.height as i64,
+            None => -1,
+        }
+    }
+
+    fn size(node: &Link<K, V>) -> usize {
+        match node {
+            Some(_node) => _node.size,
+            None => 0,
+        }
+    }
+
+    fn update(&mut self) {
+        self.height = (1 + core::cmp::max(
+            Node::height(&self.left_child),
+            Node::height(&self.right_child),
+        )) as usize;
+        self.size = 1 + Node::size(&self.left_child) + Node::size(&self.right_child);
+    }
+
+    fn balance_factor(&self) -> i64 {
+        Node::height(&self.left_child) - Node::height(&self.right_child)
+    }
+}
+
+/// A persistent AVL tree whose nodes are shared through `Arc`. Cloning the tree is O(1) and every update copies
+/// only the nodes on the path from the root to the changed node, all other nodes stay shared with older clones.
+/// A clone is therefore a cheap snapshot that can be read from other threads while the original keeps changing
+///
+/// # Examples
+/// ```
+/// use rudac::tree::PersistentAVL;
+/// use std::thread;
+///
+/// let mut config = PersistentAVL::<String, usize>::init();
+/// config.insert(String::from("workers"), 4);
+/// config.insert(String::from("timeout"), 30);
+///
+/// // take a snapshot and hand it to a reader thread
+/// let snapshot = config.clone();
+/// let reader = thread::spawn(move || *snapshot.get(&String::from("workers")).unwrap());
+///
+/// // updates do not affect the snapshot
+/// config.insert(String::from("workers"), 8);
+/// config.delete(&String::from("timeout"));
+///
+/// assert_eq!(reader.join().unwrap(), 4);
+/// assert_eq!(config.get(&String::from("workers")), Some(&8));
+/// assert_eq!(config.size(), 1);
+/// ```
+pub struct PersistentAVL<K, V> {
+    root: Link<K, V>,
+}
+
+impl<K, V> Clone for PersistentAVL<K, V> {
+    fn clone(&self) -> PersistentAVL<K, V> {
+        PersistentAVL {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<K, V> Default for PersistentAVL<K, V> {
+    fn default() -> PersistentAVL<K, V> {
+        PersistentAVL::init()
+    }
+}
+
+// trees are equal when they hold the same entries, snapshots sharing their root are equal right away
+impl<K: PartialEq, V: PartialEq> PartialEq for PersistentAVL<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(root), Some(other_root)) if Arc::ptr_eq(root, other_root) => true,
+            _ => self.size() == other.size() && self.entries() == other.entries(),
+        }
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for PersistentAVL<K, V> {}
+
+impl<K, V> PersistentAVL<K, V> {
+    /// Initializes an empty persistent AVL tree
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::PersistentAVL;
+    ///
+    /// let avl_tree = PersistentAVL::<usize, String>::init();
+    /// assert!(avl_tree.is_empty());
+    /// ```
+    pub fn init() -> PersistentAVL<K, V> {
+        PersistentAVL { root: None }
+    }
+
+    /// Returns `true` if tree is empty and `false` otherwise
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns number of nodes in the tree
+    pub fn size(&self) -> usize {
+        Node::size(&self.root)
+    }
+
+    /// Returns the height of the tree.
+    /// An empty tree has height -1 and a tree with one node has height 0
+    pub fn height(&self) -> i64 {
+        Node::height(&self.root)
+    }
+
+    /// Returns `true` if both trees share the same root, which means one is an unchanged clone of the other
+    ///
+    /// # Arguments
+    /// * `other`: the other tree
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::PersistentAVL;
+    ///
+    /// let mut avl_tree = PersistentAVL::<usize, usize>::init();
+    /// avl_tree.insert(1, 10);
+    ///
+    /// let mut snapshot = avl_tree.clone();
+    /// assert!(avl_tree.ptr_eq(&snapshot));
+    ///
+    /// snapshot.insert(2, 20);
+    /// assert!(!avl_tree.ptr_eq(&snapshot));
+    /// ```
+    pub fn ptr_eq(&self, other: &PersistentAVL<K, V>) -> bool {
+        match (&self.root, &other.root) {
+            (None, None) => true,
+            (Some(root), Some(other_root)) => Arc::ptr_eq(root, other_root),
+            _ => false,
+        }
+    }
+
+    /// Returns the footprint of the nodes of the tree that are not shared with `base`, the memory this tree keeps
+    /// alive on top of `base`. A snapshot shares every node that later updates did not copy
+    /// * Complexity: O(m log m + k log m) for the m nodes of `base` and the k nodes not shared with it
+    ///
+    /// # Arguments
+    /// * `base`: the tree whose nodes are not counted
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::PersistentAVL;
+    /// use rudac::util::MemoryFootprint;
+    ///
+    /// let mut avl_tree = PersistentAVL::<usize, usize>::init();
+    /// for key in 0..100 {
+    ///     avl_tree.insert(key, key);
+    /// }
+    ///
+    /// let mut snapshot = avl_tree.clone();
+    /// assert_eq!(snapshot.footprint_over(&avl_tree).elements(), 0);
+    ///
+    /// // the insert copies the nodes on the path to the key, the other nodes stay shared
+    /// snapshot.insert(50, 0);
+    /// let unshared = snapshot.footprint_over(&avl_tree);
+    /// assert!(unshared.elements() <= snapshot.height() as usize + 1);
+    /// assert!(unshared.allocated_bytes() < snapshot.footprint().allocated_bytes() / 10);
+    /// ```
+    pub fn footprint_over(&self, base: &PersistentAVL<K, V>) -> Footprint {
+        let mut shared = BTreeSet::new();
+        let mut stack: Vec<&Arc<Node<K, V>>> = base.root.iter().collect();
+        while let Some(node) = stack.pop() {
+            shared.insert(Arc::as_ptr(node));
+            stack.extend(node.left_child.iter().chain(node.right_child.iter()));
+        }
+
+        // the subtree of a shared node is shared as a whole
+        let mut unshared = 0;
+        let mut stack: Vec<&Arc<Node<K, V>>> = self.root.iter().collect();
+        while let Some(node) = stack.pop() {
+            if !shared.contains(&Arc::as_ptr(node)) {
+                unshared += 1;
+                stack.extend(node.left_child.iter().chain(node.right_child.iter()));
+            }
+        }
+
+        Footprint::of::<(K, V)>(unshared).with_rcs::<Node<K, V>>(unshared)
+    }
+
+    /// Returns the kth smallest key and its associated value in the tree
+    ///
+    /// # Arguments
+    /// * `k`: the order statistic
+    ///
+    /// # Panics
+    /// * panics if k is not in range: 0 <= k <= size - 1
+    pub fn select(&self, mut k: usize) -> Option<(&K, &V)> {
+        if k >= self.size() {
+            panic!("K must be in range 0 <= k <= size - 1");
+        }
+
+        let mut node = &self.root;
+        while let Some(_node) = node {
+            let t = Node::size(&_node.left_child);
+            if t > k {
+                node = &_node.left_child;
+            } else if t < k {
+                k -= t + 1;
+                node = &_node.right_child;
+            } else {
+                return Some((&_node.key, &_node.value));
+            }
+        }
+
+        None
+    }
+
+    /// Returns the smallest key and its associated value in the tree
+    pub fn min(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left_child) = &node.left_child {
+            node = left_child;
+        }
+
+        Some((&node.key, &node.value))
+    }
+
+    /// Returns the largest key and its associated value in the tree
+    pub fn max(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right_child) = &node.right_child {
+            node = right_child;
+        }
+
+        Some((&node.key, &node.value))
+    }
+
+    /// Returns all keys in the tree following an in-order traversal.
+    /// Therefore keys are sorted from smallest to largest
+    pub fn keys(&self) -> Vec<&K> {
+        let mut keys = Vec::with_capacity(self.size());
+        PersistentAVL::_keys_in_order(&self.root, &mut keys);
+
+        keys
+    }
+
+    fn _keys_in_order<'a>(node: &'a Link<K, V>, keys: &mut Vec<&'a K>) {
+        if let Some(_node) = node {
+            PersistentAVL::_keys_in_order(&_node.left_child, keys);
+            keys.push(&_node.key);
+            PersistentAVL::_keys_in_order(&_node.right_child, keys);
+        }
+    }
+
+    // returns the entries of the tree in order of their keys
+    fn entries(&self) -> Vec<(&K, &V)> {
+        let mut entries = Vec::with_capacity(self.size());
+        PersistentAVL::_entries_in_order(&self.root, &mut entries);
+
+        entries
+    }
+
+    fn _entries_in_order<'a>(node: &'a Link<K, V>, entries: &mut Vec<(&'a K, &'a V)>) {
+        if let Some(_node) = node {
+            PersistentAVL::_entries_in_order(&_node.left_child, entries);
+            entries.push((&_node.key, &_node.value));
+            PersistentAVL::_entries_in_order(&_node.right_child, entries);
+        }
+    }
+}
+
+impl<K: core::cmp::Ord, V> PersistentAVL<K, V> {
+    /// Returns a reference to value associated with specified `key` in tree, `None` otherwise
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched in the tree
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut node = &self.root;
+        while let Some(_node) = node {
+            match key.cmp(&_node.key) {
+                core::cmp::Ordering::Less => node = &_node.left_child,
+                core::cmp::Ordering::Greater => node = &_node.right_child,
+                core::cmp::Ordering::Equal => return Some(&_node.value),
+            }
+        }
+
+        None
+    }
+
+    /// Returns `true` if tree contains the specified `key`, false otherwise
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched in the tree
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the largest key in the tree less than or equal to `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let mut node = &self.root;
+        let mut found = None;
+        while let Some(_node) = node {
+            match key.cmp(&_node.key) {
+                core::cmp::Ordering::Less => node = &_node.left_child,
+                core::cmp::Ordering::Greater => {
+                    found = Some(&_node.key);
+                    node = &_node.right_child;
+                }
+                core::cmp::Ordering::Equal => return Some(&_node.key),
+            }
+        }
+
+        found
+    }
+
+    /// Returns the smallest key in the tree greater than or equal to `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let mut node = &self.root;
+        let mut found = None;
+        while let Some(_node) = node {
+            match key.cmp(&_node.key) {
+                core::cmp::Ordering::Less => {
+                    found = Some(&_node.key);
+                    node = &_node.left_child;
+                }
+                core::cmp::Ordering::Greater => node = &_node.right_child,
+                core::cmp::Ordering::Equal => return Some(&_node.key),
+            }
+        }
+
+        found
+    }
+
+    /// Returns the number of keys in the tree strictly less than `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn rank(&self, key: &K) -> usize {
+        let mut node = &self.root;
+        let mut rank = 0;
+        while let Some(_node) = node {
+            match key.cmp(&_node.key) {
+                core::cmp::Ordering::Less => node = &_node.left_child,
+                core::cmp::Ordering::Greater => {
+                    rank += 1 + Node::size(&_node.left_child);
+                    node = &_node.right_child;
+                }
+                core::cmp::Ordering::Equal => return rank + Node::size(&_node.left_child),
+            }
+        }
+
+        rank
+    }
+}
+
+impl<K: core::cmp::Ord + Clone, V: Clone> PersistentAVL<K, V> {
+    /// Insert a node which contains the specified `key` and `value` into the tree.
+    /// if `key` already exists, this method will replace `value` as the new value of the node.
+    /// Nodes on the search path that are shared with a clone are copied, all other nodes stay shared
+    ///
+    /// # Arguments
+    /// * `key`: key of the new node
+    /// * `value`: value associated with the `key`
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::PersistentAVL;
+    ///
+    /// let mut avl_tree = PersistentAVL::<usize, usize>::init();
+    /// avl_tree.insert(1, 10);
+    ///
+    /// let snapshot = avl_tree.clone();
+    /// avl_tree.insert(1, 11);
+    ///
+    /// assert_eq!(avl_tree.get(&1), Some(&11));
+    /// assert_eq!(snapshot.get(&1), Some(&10));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) {
+        PersistentAVL::_insert(&mut self.root, key, value);
+    }
+
+    fn _insert(link: &mut Link<K, V>, key: K, value: V) {
+        let node = match link {
+            None => {
+                *link = Some(Arc::new(Node::init(key, value)));
+                return;
+            }
+            Some(node) => Arc::make_mut(node),
+        };
+
+        match key.cmp(&node.key) {
+            core::cmp::Ordering::Less => PersistentAVL::_insert(&mut node.left_child, key, value),
+            core::cmp::Ordering::Greater => {
+                PersistentAVL::_insert(&mut node.right_child, key, value)
+            }
+            core::cmp::Ordering::Equal => {
+                node.value = value;
+                return;
+            }
+        }
+
+        PersistentAVL::balance(link);
+    }
+
+    /// Deletes the node containing the specified `key`
+    ///
+    /// # Arguments
+    /// * `key`: key of the node to be deleted from the tree
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::PersistentAVL;
+    ///
+    /// let mut avl_tree = PersistentAVL::<usize, usize>::init();
+    /// avl_tree.insert(1, 10);
+    /// avl_tree.insert(2, 20);
+    ///
+    /// let snapshot = avl_tree.clone();
+    /// avl_tree.delete(&1);
+    ///
+    /// assert_eq!(avl_tree.get(&1), None);
+    /// assert_eq!(snapshot.get(&1), Some(&10));
+    /// ```
+    pub fn delete(&mut self, key: &K) {
+        // nothing is copied if the key is missing
+        if self.contains(key) {
+            PersistentAVL::_delete(&mut self.root, key);
+        }
+    }
+
+    fn _delete(link: &mut Link<K, V>, key: &K) {
+        let node = Arc::make_mut(link.as_mut().unwrap());
+
+        match key.cmp(&node.key) {
+            core::cmp::Ordering::Less => PersistentAVL::_delete(&mut node.left_child, key),
+            core::cmp::Ordering::Greater => PersistentAVL::_delete(&mut node.right_child, key),
+            core::cmp::Ordering::Equal => {
+                if node.left_child.is_none() {
+                    *link = node.right_child.take();
+                    return;
+                } else if node.right_child.is_none() {
+                    *link = node.left_child.take();
+                    return;
+                }
+
+                // the entry of the successor takes the place of the deleted one
+                let (key, value) = PersistentAVL::_delete_min(&mut node.right_child);
+                node.key = key;
+                node.value = value;
+            }
+        }
+
+        PersistentAVL::balance(link);
+    }
+
+    /// Deletes node with smallest key from the tree
+    pub fn delete_min(&mut self) {
+        if !self.is_empty() {
+            PersistentAVL::_delete_min(&mut self.root);
+        }
+    }
+
+    // unlinks the smallest node of a non-empty subtree and returns its entry
+    fn _delete_min(link: &mut Link<K, V>) -> (K, V) {
+        let node = Arc::make_mut(link.as_mut().unwrap());
+        if node.left_child.is_none() {
+            let right_child = node.right_child.take();
+            let node = link.take().unwrap();
+            *link = right_child;
+
+            // the node was made unique above, so the entry is moved out without a copy
+            let node = Arc::try_unwrap(node).ok().unwrap();
+            return (node.key, node.value);
+        }
+
+        let entry = PersistentAVL::_delete_min(&mut node.left_child);
+        PersistentAVL::balance(link);
+
+        entry
+    }
+
+    /// Deletes node with largest key from the tree
+    pub fn delete_max(&mut self) {
+        if !self.is_empty() {
+            PersistentAVL::_delete_max(&mut self.root);
+        }
+    }
+
+    fn _delete_max(link: &mut Link<K, V>) {
+        let node = Arc::make_mut(link.as_mut().unwrap());
+        if node.right_child.is_none() {
+            *link = node.left_child.take();
+            return;
+        }
+
+        PersistentAVL::_delete_max(&mut node.right_child);
+        PersistentAVL::balance(link);
+    }
+
+    // updates the node in `link`, which must be uniquely owned, and restores its balance with rotations
+    fn balance(link: &mut Link<K, V>) {
+        let node = Arc::get_mut(link.as_mut().unwrap()).unwrap();
+        node.update();
+
+        if node.balance_factor() < -1 {
+            let right_child = Arc::make_mut(node.right_child.as_mut().unwrap());
+            if right_child.balance_factor() > 0 {
+                PersistentAVL::rotate_right(&mut node.right_child);
+            }
+            PersistentAVL::rotate_left(link);
+        } else if node.balance_factor() > 1 {
+            let left_child = Arc::make_mut(node.left_child.as_mut().unwrap());
+            if left_child.balance_factor() < 0 {
+                PersistentAVL::rotate_left(&mut node.left_child);
+            }
+            PersistentAVL::rotate_right(link);
+        }
+    }
+
+    fn rotate_right(link: &mut Link<K, V>) {
+        let mut node = link.take().unwrap();
+        let node_mut = Arc::make_mut(&mut node);
+        let mut y = node_mut.left_child.take().unwrap();
+        let y_mut = Arc::make_mut(&mut y);
+
+        node_mut.left_child = y_mut.right_child.take();
+        node_mut.update();
+
+        y_mut.right_child = Some(node);
+        y_mut.update();
+
+        *link = Some(y);
+    }
+
+    fn rotate_left(link: &mut Link<K, V>) {
+        let mut node = link.take().unwrap();
+        let node_mut = Arc::make_mut(&mut node);
+        let mut y = node_mut.right_child.take().unwrap();
+        let y_mut = Arc::make_mut(&mut y);
+
+        node_mut.right_child = y_mut.left_child.take();
+        node_mut.update();
+
+        y_mut.left_child = Some(node);
+        y_mut.update();
+
+        *link = Some(y);
+    }
+}
+
+impl<K, V> MemoryFootprint for PersistentAVL<K, V> {
+    /// Nodes shared with other snapshots are counted in full, so the footprints of snapshots do not add up
+    fn footprint(&self) -> Footprint {
+        let size = self.size();
+        Footprint::of::<(K, V)>(size).with_rcs::<Node<K, V>>(size)
+    }
+}
+
+impl<K: core::cmp::Ord + Clone, V: Clone> Replayable for PersistentAVL<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<K, V>) {
+        match op {
+            Op::Insert(key, value) => self.insert(key, value),
+            Op::Remove(key) => self.delete(&key),
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_avl<K: std::cmp::Ord, V>(node: &Link<K, V>) -> bool {
+        match node {
+            None => true,
+            Some(_node) => {
+                _node.balance_factor().abs() <= 1
+                    && _node.height as i64
+                        == 1 + std::cmp::max(
+                            Node::height(&_node.left_child),
+                            Node::height(&_node.right_child),
+                        )
+                    && _node.size
+                        == 1 + Node::size(&_node.left_child) + Node::size(&_node.right_child)
+                    && is_avl(&_node.left_child)
+                    && is_avl(&_node.right_child)
+            }
+        }
+    }
+
+    fn count_shared<K, V>(node: &Link<K, V>, other: &Link<K, V>) -> usize {
+        match (node, other) {
+            (Some(_node), Some(_other)) if Arc::ptr_eq(_node, _other) => _node.size,
+            (Some(_node), Some(_other)) => {
+                count_shared(&_node.left_child, &_other.left_child)
+                    + count_shared(&_node.right_child, &_other.right_child)
+            }
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn tree_persistent_avl_snapshots() {
+        use crate::tree::AVL;
+        use crate::util::{Rng, SplitMix64};
+
+        let mut rng = SplitMix64::init(970);
+        let mut avl_tree = PersistentAVL::<u64, u64>::init();
+        let mut expected = AVL::<u64, u64>::init();
+        let mut snapshots = Vec::new();
+        for step in 0..2000 {
+            let key = rng.next_below(300);
+            match rng.next_below(8) {
+                0..=4 => {
+                    avl_tree.insert(key, step);
+                    expected.insert(key, step);
+                }
+                5 => {
+                    avl_tree.delete(&key);
+                    expected.delete(&key);
+                }
+                6 => {
+                    avl_tree.delete_min();
+                    expected.delete_min();
+                }
+                _ => {
+                    avl_tree.delete_max();
+                    expected.delete_max();
+                }
+            }
+            assert!(is_avl(&avl_tree.root));
+            if step % 200 == 0 {
+                let keys: Vec<u64> = expected.keys().into_iter().cloned().collect();
+                let values: Vec<u64> = keys.iter().map(|key| *expected.get(key).unwrap()).collect();
+                snapshots.push((avl_tree.clone(), keys, values));
+            }
+        }
+
+        // every snapshot still holds the entries it had when it was taken
+        for (snapshot, keys, values) in snapshots.iter() {
+            assert!(is_avl(&snapshot.root));
+            assert_eq!(snapshot.keys(), keys.iter().collect::<Vec<_>>());
+            for (key, value) in keys.iter().zip(values.iter()) {
+                assert_eq!(snapshot.get(key), Some(value));
+            }
+        }
+
+        assert_eq!(avl_tree.keys(), expected.keys());
+        assert_eq!(avl_tree.min(), expected.min());
+        assert_eq!(avl_tree.max(), expected.max());
+        for key in 0..310 {
+            assert_eq!(avl_tree.rank(&key), expected.rank(&key));
+            assert_eq!(avl_tree.floor(&key), expected.floor(&key));
+            assert_eq!(avl_tree.ceiling(&key), expected.ceiling(&key));
+        }
+        for k in 0..avl_tree.size() {
+            assert_eq!(avl_tree.select(k), expected.select(k));
+        }
+    }
+
+    #[test]
+    fn tree_persistent_avl_copies_only_the_path() {
+        let mut avl_tree = PersistentAVL::<usize, usize>::init();
+        for key in 0..1023 {
+            avl_tree.insert(key, key);
+        }
+
+        let snapshot = avl_tree.clone();
+        avl_tree.insert(500, 0);
+        assert_eq!(avl_tree.get(&500), Some(&0));
+        assert_eq!(snapshot.get(&500), Some(&500));
+
+        // only the nodes on the path to 500 are new
+        let shared = count_shared(&avl_tree.root, &snapshot.root);
+        assert!(shared >= 1023 - avl_tree.height() as usize - 1);
+        assert_eq!(avl_tree.footprint_over(&snapshot).elements(), 1023 - shared);
+        assert_eq!(
+            avl_tree.footprint_over(&PersistentAVL::init()),
+            avl_tree.footprint()
+        );
+
+        // deleting a missing key copies nothing
+        let snapshot = avl_tree.clone();
+        avl_tree.delete(&5000);
+        assert!(avl_tree.ptr_eq(&snapshot));
+    }
+
+    #[test]
+    fn tree_persistent_avl_shared_across_threads() {
+        let mut avl_tree = PersistentAVL::<usize, String>::init();
+        for key in 0..100 {
+            avl_tree.insert(key, key.to_string());
+        }
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let snapshot = avl_tree.clone();
+                std::thread::spawn(move || {
+                    (0..100).filter(|key| snapshot.get(key).is_some()).count()
+                })
+            })
+            .collect();
+        for key in 0..100 {
+            avl_tree.delete(&key);
+        }
+
+        for reader in readers {
+            assert_eq!(reader.join().unwrap(), 100);
+        }
+        assert!(avl_tree.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "K must be in range 0 <= k <= size - 1")]
+    fn tree_persistent_avl_panic_select() {
+        let avl_tree = PersistentAVL::<usize, usize>::init();
+        avl_tree.select(0);
+    }
+
+    #[test]
+    fn tree_persistent_avl_eq() {
+        let mut avl_tree = PersistentAVL::default();
+        let mut other = PersistentAVL::init();
+        for key in 0..20 {
+            avl_tree.insert(key, key * 3);
+            other.insert(19 - key, (19 - key) * 3);
+        }
+
+        let snapshot = avl_tree.clone();
+        assert!(snapshot == avl_tree);
+        assert!(other == avl_tree);
+        avl_tree.insert(5, 0);
+        assert!(snapshot != avl_tree);
+    }
+}
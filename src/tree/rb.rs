@@ -1,9 +1,28 @@
-use std::collections::VecDeque;
+use crate::codec::{read_header, write_header, Decode, Encode, Reader, Writer, MAX_DEPTH};
+use crate::replay::{Op, Replayable};
+use crate::tree::batch;
+use crate::tree::layout::NIL;
+use crate::tree::{Boxed, Layout, Soa};
+use crate::util::stats::{self, Recorder};
+#[cfg(feature = "stats")]
+use crate::util::OpStats;
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 const RED: bool = true;
 const BLACK: bool = false;
 
-struct Node<K: std::cmp::Ord, V> {
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+// a subtree paired with its black height, the number of black nodes on any path down from its root
+type Subtree<K, V> = (Link<K, V>, usize);
+
+#[derive(Clone)]
+pub struct Node<K: core::cmp::Ord, V> {
     key: Option<K>,
     value: Option<V>,
     color: bool,
@@ -12,7 +31,7 @@ struct Node<K: std::cmp::Ord, V> {
     right_child: Option<Box<Node<K, V>>>,
 }
 
-impl<K: std::cmp::Ord, V> Node<K, V> {
+impl<K: core::cmp::Ord, V> Node<K, V> {
     fn init(key: K, value: V, color: bool, size: usize) -> Node<K, V> {
         Node {
             key: Some(key),
@@ -75,7 +94,8 @@ impl<K: std::cmp::Ord, V> Node<K, V> {
 }
 
 /// A Red Black tree is a self-balancing binary search tree.
-/// Red Black Trees provide faster insertion and removal operations than AVL trees
+/// Red Black Trees provide faster insertion and removal operations than AVL trees.
+/// The node layout is chosen by the last type parameter, `Boxed` by default or `Soa` for an index based arena
 ///
 /// # Examples
 /// ```
@@ -100,27 +120,53 @@ impl<K: std::cmp::Ord, V> Node<K, V> {
 /// rb_tree.delete(&4);
 /// assert_eq!(rb_tree.get(&4), None);
 /// ```
-pub struct RedBlack<K: std::cmp::Ord, V> {
-    root: Option<Box<Node<K, V>>>,
+pub struct RedBlack<K: core::cmp::Ord, V, L: Layout = Boxed> {
+    root: L::RedBlack<K, V>,
+    stats: Recorder,
 }
 
-impl<K: std::cmp::Ord, V> RedBlack<K, V> {
+impl<K: core::cmp::Ord, V, L: Layout> RedBlack<K, V, L>
+where
+    L::RedBlack<K, V>: Default,
+{
     /// Initializes an empty Red Black tree
     ///
     /// # Examples
     /// ```
-    /// use rudac::tree::RedBlack;
+    /// use rudac::tree::{RedBlack, Soa};
     ///
     /// let usize_to_string = RedBlack::<usize, String>::init();
     ///
     /// let string_to_usize = RedBlack::<String, usize>::init();
     ///
     /// let string_to_string = RedBlack::<String, String>::init();
+    ///
+    /// // nodes are stored in a structure of arrays
+    /// let soa_usize_to_usize = RedBlack::<usize, usize, Soa>::init();
     /// ```
-    pub fn init() -> RedBlack<K, V> {
-        RedBlack { root: None }
+    pub fn init() -> RedBlack<K, V, L> {
+        RedBlack {
+            root: Default::default(),
+            stats: Recorder::default(),
+        }
+    }
+}
+
+#[cfg(feature = "stats")]
+impl<K: core::cmp::Ord, V, L: Layout> RedBlack<K, V, L> {
+    /// Returns counts of the operations performed by the tree since it was created or since the last `reset_stats`.
+    /// Available with the `stats` feature
+    pub fn stats(&self) -> OpStats {
+        self.stats.stats()
     }
 
+    /// Sets all operation counts to zero. Available with the `stats` feature
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+}
+
+impl<K: core::cmp::Ord, V> RedBlack<K, V> {
     /// Returns total number of nodes in the tree
     ///
     /// # Examples
@@ -175,12 +221,14 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(rb_tree.get(&1), None);
     /// ```
     pub fn get(&self, key: &K) -> Option<&V> {
+        let _stats = self.stats.scope();
         RedBlack::_get(&self.root, key)
     }
 
     fn _get<'a>(mut node: &'a Option<Box<Node<K, V>>>, key: &K) -> Option<&'a V> {
         while !node.is_none() {
             let node_ref = node.as_ref().unwrap();
+            stats::comparison();
             if key < node_ref.key() {
                 node = &node_ref.left_child
             } else if key > node_ref.key() {
@@ -236,6 +284,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(*rb_tree.get(&1).unwrap(), 11);
     /// ```
     pub fn insert(&mut self, key: K, value: V) {
+        let _stats = self.stats.scope();
         let mut root = RedBlack::_insert(self.root.take(), key, value).unwrap();
 
         root.color = BLACK;
@@ -245,11 +294,13 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
 
     fn _insert(node: Option<Box<Node<K, V>>>, key: K, value: V) -> Option<Box<Node<K, V>>> {
         if node.is_none() {
+            stats::allocation();
             return Some(Box::new(Node::init(key, value, RED, 1)));
         }
 
         let mut node_ref = node.unwrap();
 
+        stats::comparison();
         if key < *node_ref.key() {
             node_ref.left_child = RedBlack::_insert(node_ref.left_child, key, value);
         } else if key > *node_ref.key() {
@@ -295,6 +346,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(rb_tree.get(&2), None);
     /// ```
     pub fn delete_min(&mut self) {
+        let _stats = self.stats.scope();
         if self.root.is_none() {
             return;
         }
@@ -353,6 +405,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(rb_tree.get(&3), None);
     /// ```
     pub fn delete_max(&mut self) {
+        let _stats = self.stats.scope();
         if self.root.is_none() {
             return;
         }
@@ -419,6 +472,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(rb_tree.get(&1), None);
     /// ```
     pub fn delete(&mut self, key: &K) {
+        let _stats = self.stats.scope();
         if self.root.is_none() || !self.contains(key) {
             return;
         }
@@ -447,6 +501,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
 
         let mut node_ref = node.unwrap();
 
+        stats::comparison();
         if *key < *node_ref.key() {
             if !Node::is_red(&node_ref.left_child)
                 && Node::is_red(&node_ref.left_child().left_child)
@@ -458,6 +513,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
             if Node::is_red(&node_ref.left_child) {
                 node_ref = RedBlack::rotate_right(node_ref);
             }
+            stats::comparison();
             if *key == *node_ref.key() && node_ref.right_child.is_none() {
                 return None;
             }
@@ -466,13 +522,14 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
             {
                 node_ref = RedBlack::move_red_right(node_ref);
             }
+            stats::comparison();
             if *key == *node_ref.key() {
                 let mut x = RedBlack::_min(&mut node_ref.right_child);
                 // swap keys
-                std::mem::swap(x.key_mut(), node_ref.key_mut());
+                core::mem::swap(x.key_mut(), node_ref.key_mut());
 
                 // swap values
-                std::mem::swap(x.value_mut(), node_ref.value_mut());
+                core::mem::swap(x.value_mut(), node_ref.value_mut());
 
                 node_ref.right_child = RedBlack::_delete_min(node_ref.right_child);
             } else {
@@ -488,6 +545,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
             None => panic!("Called min on None node"),
             Some(_node) => {
                 if _node.left_child.is_none() {
+                    stats::allocation();
                     return Box::new(Node::init(_node.get_key(), _node.get_value(), RED, 1));
                 } else {
                     return RedBlack::_min(&mut _node.left_child);
@@ -509,7 +567,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
 
         let node_ref = node.as_ref().unwrap();
 
-        return 1 + std::cmp::max(
+        return 1 + core::cmp::max(
             RedBlack::_height(&node_ref.left_child),
             RedBlack::_height(&node_ref.right_child),
         );
@@ -535,6 +593,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(rb_tree.floor(&0), None);
     /// ```
     pub fn floor(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
         RedBlack::_floor(&self.root, key)
     }
 
@@ -545,9 +604,11 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
 
         let node_ref = node.as_ref().unwrap();
 
+        stats::comparison();
         if *key == *node_ref.key() {
             return Some(node_ref.key());
         }
+        stats::comparison();
         if *key < *node_ref.key() {
             return RedBlack::_floor(&node_ref.left_child, key);
         }
@@ -581,6 +642,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(rb_tree.ceiling(&8), None);
     /// ```
     pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
         RedBlack::_ceiling(&self.root, key)
     }
 
@@ -591,9 +653,11 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
 
         let node_ref = node.as_ref().unwrap();
 
+        stats::comparison();
         if *key == *node_ref.key() {
             return Some(node_ref.key());
         }
+        stats::comparison();
         if *key > *node_ref.key() {
             return RedBlack::_ceiling(&node_ref.right_child, key);
         }
@@ -727,6 +791,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(rb_tree.rank(&99), 98);
     /// ```
     pub fn rank(&self, key: &K) -> usize {
+        let _stats = self.stats.scope();
         RedBlack::_rank(&self.root, key)
     }
 
@@ -737,6 +802,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
 
         let node_ref = node.as_ref().unwrap();
 
+        stats::comparison();
         if *key < *node_ref.key() {
             return RedBlack::_rank(&node_ref.left_child, key);
         } else if *key > *node_ref.key() {
@@ -787,6 +853,22 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
         RedBlack::_keys_in_order(&node_ref.right_child, keys);
     }
 
+    // returns the entries of the tree in order of their keys
+    fn entries(&self) -> Vec<(&K, &V)> {
+        let mut entries = Vec::with_capacity(self.size());
+        RedBlack::_entries_in_order(&self.root, &mut entries);
+
+        entries
+    }
+
+    fn _entries_in_order<'a>(node: &'a Option<Box<Node<K, V>>>, entries: &mut Vec<(&'a K, &'a V)>) {
+        if let Some(node_ref) = node {
+            RedBlack::_entries_in_order(&node_ref.left_child, entries);
+            entries.push((node_ref.key(), node_ref.value()));
+            RedBlack::_entries_in_order(&node_ref.right_child, entries);
+        }
+    }
+
     /// Returns all keys in the tree following a level-order traversal
     pub fn keys_in_level_order(&self) -> Vec<&K> {
         let mut keys: Vec<&K> = Vec::new();
@@ -838,6 +920,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(keys.len(), 98);
     /// ```
     pub fn keys_between(&self, low_key: &K, high_key: &K) -> Vec<&K> {
+        let _stats = self.stats.scope();
         let mut keys: Vec<&K> = Vec::new();
 
         RedBlack::_keys_between(&self.root, low_key, high_key, &mut keys);
@@ -856,6 +939,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
         }
 
         let node_ref = node.as_ref().unwrap();
+        stats::comparison();
         if *low_key < *node_ref.key() {
             RedBlack::_keys_between(&node_ref.left_child, low_key, high_key, keys);
         }
@@ -888,9 +972,11 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     /// assert_eq!(keys, 98);
     /// ```
     pub fn size_between(&self, low_key: &K, high_key: &K) -> usize {
+        let _stats = self.stats.scope();
         if self.is_empty() {
             return 0;
         }
+        stats::comparison();
         if *low_key > *high_key {
             return 0;
         }
@@ -898,7 +984,211 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
         return self.rank(high_key) - self.rank(low_key);
     }
 
+    /// Inserts a run of items sorted by key into the tree. The run is built into a balanced tree and merged
+    /// with split and join in O(m log(n/m + 1)) time for m items, which is much faster than m inserts for bulk loads.
+    /// Like `insert`, an item replaces the value of an existing key, and a later item in the run replaces an earlier one
+    ///
+    /// # Arguments
+    /// * `sorted_items`: `(key, value)` pairs in ascending order of keys
+    ///
+    /// # Panics
+    /// * panics if items are not sorted by key
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::RedBlack;
+    ///
+    /// let mut rb_tree = RedBlack::<usize, usize>::init();
+    /// rb_tree.insert(5, 0);
+    ///
+    /// rb_tree.insert_batch((0..10).map(|key| (key, key * 10)));
+    /// assert_eq!(rb_tree.size(), 10);
+    /// assert_eq!(rb_tree.get(&5), Some(&50));
+    /// ```
+    pub fn insert_batch<I: IntoIterator<Item = (K, V)>>(&mut self, sorted_items: I) {
+        let _stats = self.stats.scope();
+        let items = batch::sorted_run(sorted_items);
+        let size = items.len();
+        let batch = RedBlack::build(&mut items.into_iter(), size);
+
+        let mut black_height = 0;
+        let mut node = &self.root;
+        while let Some(_node) = node {
+            black_height += !_node.color as usize;
+            node = &_node.left_child;
+        }
+
+        self.root = RedBlack::union(batch, (self.root.take(), black_height)).0;
+    }
+
+    // builds a tree out of the next `size` sorted items, joining halves whose black heights differ by at most one
+    fn build<I: Iterator<Item = (K, V)>>(items: &mut I, size: usize) -> Subtree<K, V> {
+        if size == 0 {
+            return (None, 0);
+        }
+
+        let left = RedBlack::build(items, size / 2);
+        let (key, value) = items.next().unwrap();
+        stats::allocation();
+        let node = Box::new(Node::init(key, value, BLACK, 1));
+        let right = RedBlack::build(items, size - size / 2 - 1);
+
+        RedBlack::join(left, node, right)
+    }
+
+    // merges two trees, entries of `batch` replace entries of `tree` with equal keys
+    fn union(batch: Subtree<K, V>, tree: Subtree<K, V>) -> Subtree<K, V> {
+        let (mut node, black_height) = match batch {
+            (None, _) => return tree,
+            (Some(node), black_height) => (node, black_height),
+        };
+        if tree.0.is_none() {
+            return (Some(node), black_height);
+        }
+
+        let child_height = black_height - !node.color as usize;
+        let (tree_left, tree_right) = RedBlack::split(tree, node.key());
+        let left = RedBlack::union((node.left_child.take(), child_height), tree_left);
+        let right = RedBlack::union((node.right_child.take(), child_height), tree_right);
+
+        RedBlack::join(left, node, right)
+    }
+
+    // splits the tree into keys less than and greater than `key`, the node holding `key` is dropped
+    fn split(tree: Subtree<K, V>, key: &K) -> (Subtree<K, V>, Subtree<K, V>) {
+        let (mut node, black_height) = match tree {
+            (None, _) => return ((None, 0), (None, 0)),
+            (Some(node), black_height) => (node, black_height),
+        };
+
+        let child_height = black_height - !node.color as usize;
+        let left = (node.left_child.take(), child_height);
+        let right = (node.right_child.take(), child_height);
+
+        stats::comparison();
+        if *key < *node.key() {
+            let (less, greater) = RedBlack::split(left, key);
+            (less, RedBlack::join(greater, node, right))
+        } else if *key > *node.key() {
+            let (less, greater) = RedBlack::split(right, key);
+            (RedBlack::join(left, node, less), greater)
+        } else {
+            (left, right)
+        }
+    }
+
+    // links two trees through `node`, all keys of `left` are less and all keys of `right` are greater than its key.
+    // `node` is attached as a red node where the black heights match and the tree is fixed up like after an insert
+    fn join(left: Subtree<K, V>, mut node: Box<Node<K, V>>, right: Subtree<K, V>) -> Subtree<K, V> {
+        let (left, left_height) = RedBlack::blacken(left);
+        let (right, right_height) = RedBlack::blacken(right);
+
+        let (mut root, black_height) = if left_height == right_height {
+            node.left_child = left;
+            node.right_child = right;
+            node.color = BLACK;
+            node.update_size();
+            return (Some(node), left_height + 1);
+        } else if left_height > right_height {
+            let root = RedBlack::join_right(left.unwrap(), left_height, node, right, right_height);
+            (root, left_height)
+        } else {
+            let root = RedBlack::join_left(left, left_height, node, right.unwrap(), right_height);
+            (root, right_height)
+        };
+
+        if root.color == RED {
+            root.color = BLACK;
+            return (Some(root), black_height + 1);
+        }
+
+        (Some(root), black_height)
+    }
+
+    // colors the root of a tree black
+    fn blacken(tree: Subtree<K, V>) -> Subtree<K, V> {
+        match tree {
+            (Some(mut node), black_height) if node.color == RED => {
+                node.color = BLACK;
+                (Some(node), black_height + 1)
+            }
+            tree => tree,
+        }
+    }
+
+    // descends the right spine of the taller `tree`, right links are black so every step lowers the black height by one
+    fn join_right(
+        mut tree: Box<Node<K, V>>,
+        black_height: usize,
+        mut node: Box<Node<K, V>>,
+        right: Link<K, V>,
+        right_height: usize,
+    ) -> Box<Node<K, V>> {
+        if black_height == right_height {
+            node.left_child = Some(tree);
+            node.right_child = right;
+            node.color = RED;
+            node.update_size();
+            return node;
+        }
+
+        tree.right_child = match tree.right_child.take() {
+            Some(child) => Some(RedBlack::join_right(
+                child,
+                black_height - 1,
+                node,
+                right,
+                right_height,
+            )),
+            None => {
+                node.right_child = right;
+                node.color = RED;
+                node.update_size();
+                Some(node)
+            }
+        };
+
+        RedBlack::balance(tree)
+    }
+
+    // descends the left spine of the taller `tree` down to a black node with the black height of `left`
+    fn join_left(
+        left: Link<K, V>,
+        left_height: usize,
+        mut node: Box<Node<K, V>>,
+        mut tree: Box<Node<K, V>>,
+        black_height: usize,
+    ) -> Box<Node<K, V>> {
+        if black_height == left_height && tree.color == BLACK {
+            node.left_child = left;
+            node.right_child = Some(tree);
+            node.color = RED;
+            node.update_size();
+            return node;
+        }
+
+        let child_height = black_height - !tree.color as usize;
+        tree.left_child = match tree.left_child.take() {
+            Some(child) => Some(RedBlack::join_left(
+                left,
+                left_height,
+                node,
+                child,
+                child_height,
+            )),
+            None => {
+                node.left_child = left;
+                node.color = RED;
+                node.update_size();
+                Some(node)
+            }
+        };
+
+        RedBlack::balance(tree)
+    }
+
     fn rotate_left(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        stats::rotation();
         let mut y = node.right_child.unwrap();
         node.right_child = y.left_child;
 
@@ -918,6 +1208,7 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     }
 
     fn rotate_right(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        stats::rotation();
         let mut y = node.left_child.unwrap();
         node.left_child = y.right_child;
 
@@ -994,9 +1285,853 @@ impl<K: std::cmp::Ord, V> RedBlack<K, V> {
     }
 }
 
+impl<K: core::cmp::Ord, V> RedBlack<K, V, Soa> {
+    /// Returns total number of nodes in the tree
+    pub fn size(&self) -> usize {
+        self.root.len()
+    }
+
+    /// Returns `true` if tree is empty and `false` otherwise
+    pub fn is_empty(&self) -> bool {
+        self.root.len() == 0
+    }
+
+    /// Returns a reference to value associated with specified `key` in tree, `None` otherwise
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched in the tree
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let _stats = self.stats.scope();
+        self.root.get(key)
+    }
+
+    /// Returns `true` if tree contains the specified `key`, false otherwise
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched in the tree
+    pub fn contains(&self, key: &K) -> bool {
+        self.root.get(key).is_some()
+    }
+
+    /// Insert a node which contains the specified `key` and `value` into the tree.
+    /// if `key` already exists, this method will replace `value` as the new value of the node
+    ///
+    /// # Arguments
+    /// * `key`: key of the new node
+    /// * `value`: value associated with the `key`
+    pub fn insert(&mut self, key: K, value: V) {
+        let _stats = self.stats.scope();
+        let root = self.root.root();
+        let root = soa::insert(&mut self.root, root, key, value);
+        self.root.set_root(root);
+        self.root.set_tag(root, soa::BLACK);
+    }
+
+    /// Inserts a run of items sorted by key into the tree in O(m log(n/m + 1)) time for m items.
+    /// Like `insert`, an item replaces the value of an existing key, and a later item in the run replaces an earlier one
+    ///
+    /// # Arguments
+    /// * `sorted_items`: `(key, value)` pairs in ascending order of keys
+    ///
+    /// # Panics
+    /// * panics if items are not sorted by key
+    pub fn insert_batch<I: IntoIterator<Item = (K, V)>>(&mut self, sorted_items: I) {
+        let _stats = self.stats.scope();
+        let items = batch::sorted_run(sorted_items);
+        let size = items.len();
+        let batch = soa::build(&mut self.root, &mut items.into_iter(), size);
+
+        let mut removed = Vec::new();
+        let root = self.root.root();
+        let tree = (root, soa::black_height(&self.root, root));
+        let (root, _) = soa::union(&mut self.root, batch, tree, &mut removed);
+        self.root.set_root(root);
+        self.root.remove_all(removed);
+    }
+
+    /// Deletes node with smallest key from the tree
+    pub fn delete_min(&mut self) {
+        let _stats = self.stats.scope();
+        if !self.is_empty() {
+            let root = self.prepare_root();
+            let mut removed = NIL;
+            let root = soa::delete_min(&mut self.root, root, &mut removed);
+            self.remove(root, removed);
+        }
+    }
+
+    /// Deletes node with largest key from the tree
+    pub fn delete_max(&mut self) {
+        let _stats = self.stats.scope();
+        if !self.is_empty() {
+            let root = self.prepare_root();
+            let mut removed = NIL;
+            let root = soa::delete_max(&mut self.root, root, &mut removed);
+            self.remove(root, removed);
+        }
+    }
+
+    /// Deletes the node containing the specified `key`
+    ///
+    /// # Arguments
+    /// * `key`: key of the node to be deleted from the tree
+    pub fn delete(&mut self, key: &K) {
+        let _stats = self.stats.scope();
+        if self.contains(key) {
+            let root = self.prepare_root();
+            let mut removed = NIL;
+            let root = soa::delete(&mut self.root, root, key, &mut removed);
+            self.remove(root, removed);
+        }
+    }
+
+    // colors the root red if both its children are black, so the deletion can borrow from it
+    fn prepare_root(&mut self) -> usize {
+        let root = self.root.root();
+        if !soa::is_red(&self.root, self.root.left(root))
+            && !soa::is_red(&self.root, self.root.right(root))
+        {
+            self.root.set_tag(root, soa::RED);
+        }
+
+        root
+    }
+
+    fn remove(&mut self, root: usize, removed: usize) {
+        self.root.set_root(root);
+        if root != NIL {
+            self.root.set_tag(root, soa::BLACK);
+        }
+        self.root.remove(removed);
+    }
+
+    /// Returns the height of the tree.
+    /// An empty tree has height -1 and a tree with one node has height 0
+    pub fn height(&self) -> i64 {
+        self.root.height(self.root.root())
+    }
+
+    /// Returns the largest key in the tree less than or equal to `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
+        self.root.floor(key)
+    }
+
+    /// Returns the smallest key in the tree greater than or equal to `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let _stats = self.stats.scope();
+        self.root.ceiling(key)
+    }
+
+    /// Returns the kth smallest key and its associated value in the tree
+    ///
+    /// # Arguments
+    /// * `k`: the order statistic
+    ///
+    /// # Panics
+    /// * panics if k is not in range: 0 <= k <= size - 1
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        if k > self.size() {
+            panic!("K must be in range 0 <= k <= size - 1");
+        }
+        self.root.select(k)
+    }
+
+    /// Returns the smallest key and its associated value in the tree
+    pub fn min(&self) -> Option<(&K, &V)> {
+        self.root.select(0)
+    }
+
+    /// Returns the largest key and its associated value in the tree
+    pub fn max(&self) -> Option<(&K, &V)> {
+        self.root.select(self.size().checked_sub(1)?)
+    }
+
+    /// Returns the number of keys in the symbol table strictly less than `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched for
+    pub fn rank(&self, key: &K) -> usize {
+        let _stats = self.stats.scope();
+        self.root.rank(key)
+    }
+
+    /// Returns all keys in the tree following an in-order traversal.
+    /// Therefore keys are sorted from smallest to largest
+    pub fn keys(&self) -> Vec<&K> {
+        self.root.keys()
+    }
+
+    // returns the entries of the tree in order of their keys
+    fn entries(&self) -> Vec<(&K, &V)> {
+        self.root.entries()
+    }
+
+    /// Returns all keys in the tree following a level-order traversal
+    pub fn keys_in_level_order(&self) -> Vec<&K> {
+        self.root.keys_in_level_order()
+    }
+
+    /// Returns all keys in the symbol table between `low_key`(inclusive) and `high_key`(exclusive)
+    ///
+    /// # Arguments
+    /// * `low_key`: lowest key of the range
+    /// * `high_key`: highest key of the range
+    pub fn keys_between(&self, low_key: &K, high_key: &K) -> Vec<&K> {
+        let _stats = self.stats.scope();
+        self.root.keys_between(low_key, high_key)
+    }
+
+    /// Returns the number of keys in the tree between `low_key`(inclusive) and `high_key`(exclusive)
+    ///
+    /// # Arguments
+    /// * `low_key`: lowest key of the range
+    /// * `high_key`: highest key of the range
+    pub fn size_between(&self, low_key: &K, high_key: &K) -> usize {
+        let _stats = self.stats.scope();
+        stats::comparison();
+        if *low_key > *high_key {
+            return 0;
+        }
+
+        self.rank(high_key) - self.rank(low_key)
+    }
+}
+
+// left-leaning red-black balancing of the `Soa` layout, nodes are indices into the arena and tags hold colors
+mod soa {
+    use crate::tree::layout::{Arena, NIL};
+    use crate::util::stats;
+    use alloc::vec::Vec;
+
+    pub(super) const RED: u8 = 1;
+    pub(super) const BLACK: u8 = 0;
+
+    pub(super) fn is_red<K, V>(arena: &Arena<K, V>, node: usize) -> bool {
+        node != NIL && arena.tag(node) == RED
+    }
+
+    pub(super) fn insert<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        node: usize,
+        key: K,
+        value: V,
+    ) -> usize {
+        if node == NIL {
+            return arena.push(key, value, RED);
+        }
+
+        stats::comparison();
+        if key < *arena.key(node) {
+            let left = insert(arena, arena.left(node), key, value);
+            arena.set_left(node, left);
+        } else if key > *arena.key(node) {
+            let right = insert(arena, arena.right(node), key, value);
+            arena.set_right(node, right);
+        } else {
+            arena.set_value(node, value);
+        }
+
+        balance(arena, node)
+    }
+
+    // unlinks the node holding `key` from the subtree and stores its index in `removed`.
+    // the arena slot is freed by the caller once all links are final
+    pub(super) fn delete<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        mut node: usize,
+        key: &K,
+        removed: &mut usize,
+    ) -> usize {
+        stats::comparison();
+        if *key < *arena.key(node) {
+            if !is_red(arena, arena.left(node)) && !is_red(arena, arena.left(arena.left(node))) {
+                node = move_red_left(arena, node);
+            }
+            let left = delete(arena, arena.left(node), key, removed);
+            arena.set_left(node, left);
+        } else {
+            if is_red(arena, arena.left(node)) {
+                node = rotate_right(arena, node);
+            }
+            stats::comparison();
+            if *key == *arena.key(node) && arena.right(node) == NIL {
+                *removed = node;
+                return NIL;
+            }
+            if !is_red(arena, arena.right(node)) && !is_red(arena, arena.left(arena.right(node))) {
+                node = move_red_right(arena, node);
+            }
+            stats::comparison();
+            if *key == *arena.key(node) {
+                // the successor is unlinked instead and its entry takes the place of the deleted one
+                let right = delete_min(arena, arena.right(node), removed);
+                arena.swap_entries(node, *removed);
+                arena.set_right(node, right);
+            } else {
+                let right = delete(arena, arena.right(node), key, removed);
+                arena.set_right(node, right);
+            }
+        }
+
+        balance(arena, node)
+    }
+
+    pub(super) fn delete_min<K, V>(
+        arena: &mut Arena<K, V>,
+        mut node: usize,
+        removed: &mut usize,
+    ) -> usize {
+        if arena.left(node) == NIL {
+            *removed = node;
+            return NIL;
+        }
+
+        if !is_red(arena, arena.left(node)) && !is_red(arena, arena.left(arena.left(node))) {
+            node = move_red_left(arena, node);
+        }
+        let left = delete_min(arena, arena.left(node), removed);
+        arena.set_left(node, left);
+
+        balance(arena, node)
+    }
+
+    pub(super) fn delete_max<K, V>(
+        arena: &mut Arena<K, V>,
+        mut node: usize,
+        removed: &mut usize,
+    ) -> usize {
+        if is_red(arena, arena.left(node)) {
+            node = rotate_right(arena, node);
+        }
+        if arena.right(node) == NIL {
+            *removed = node;
+            return NIL;
+        }
+
+        if !is_red(arena, arena.right(node)) && !is_red(arena, arena.left(arena.right(node))) {
+            node = move_red_right(arena, node);
+        }
+        let right = delete_max(arena, arena.right(node), removed);
+        arena.set_right(node, right);
+
+        balance(arena, node)
+    }
+
+    fn rotate_left<K, V>(arena: &mut Arena<K, V>, node: usize) -> usize {
+        stats::rotation();
+        let y = arena.right(node);
+        arena.set_right(node, arena.left(y));
+        arena.set_left(y, node);
+
+        arena.set_tag(y, arena.tag(node));
+        arena.set_tag(node, RED);
+
+        arena.update_size(node);
+        arena.update_size(y);
+
+        y
+    }
+
+    fn rotate_right<K, V>(arena: &mut Arena<K, V>, node: usize) -> usize {
+        stats::rotation();
+        let y = arena.left(node);
+        arena.set_left(node, arena.right(y));
+        arena.set_right(y, node);
+
+        arena.set_tag(y, arena.tag(node));
+        arena.set_tag(node, RED);
+
+        arena.update_size(node);
+        arena.update_size(y);
+
+        y
+    }
+
+    fn flip_colors<K, V>(arena: &mut Arena<K, V>, node: usize) {
+        for node in [node, arena.left(node), arena.right(node)].iter() {
+            arena.set_tag(*node, RED - arena.tag(*node));
+        }
+    }
+
+    fn move_red_left<K, V>(arena: &mut Arena<K, V>, mut node: usize) -> usize {
+        flip_colors(arena, node);
+        if is_red(arena, arena.left(arena.right(node))) {
+            let right = rotate_right(arena, arena.right(node));
+            arena.set_right(node, right);
+            node = rotate_left(arena, node);
+            flip_colors(arena, node);
+        }
+
+        node
+    }
+
+    fn move_red_right<K, V>(arena: &mut Arena<K, V>, mut node: usize) -> usize {
+        flip_colors(arena, node);
+        if is_red(arena, arena.left(arena.left(node))) {
+            node = rotate_right(arena, node);
+            flip_colors(arena, node);
+        }
+
+        node
+    }
+
+    fn balance<K, V>(arena: &mut Arena<K, V>, mut node: usize) -> usize {
+        if is_red(arena, arena.right(node)) && !is_red(arena, arena.left(node)) {
+            node = rotate_left(arena, node);
+        }
+        if is_red(arena, arena.left(node)) && is_red(arena, arena.left(arena.left(node))) {
+            node = rotate_right(arena, node);
+        }
+        if is_red(arena, arena.left(node)) && is_red(arena, arena.right(node)) {
+            flip_colors(arena, node);
+        }
+        arena.update_size(node);
+
+        node
+    }
+
+    // builds a tree out of the next `size` sorted items, joining halves whose black heights differ by at most one.
+    // subtrees are paired with their black height, the number of black nodes on any path down from the root
+    pub(super) fn build<K: core::cmp::Ord, V, I: Iterator<Item = (K, V)>>(
+        arena: &mut Arena<K, V>,
+        items: &mut I,
+        size: usize,
+    ) -> (usize, usize) {
+        if size == 0 {
+            return (NIL, 0);
+        }
+
+        let left = build(arena, items, size / 2);
+        let (key, value) = items.next().unwrap();
+        let node = arena.push(key, value, BLACK);
+        let right = build(arena, items, size - size / 2 - 1);
+
+        join(arena, left, node, right)
+    }
+
+    pub(super) fn black_height<K, V>(arena: &Arena<K, V>, mut node: usize) -> usize {
+        let mut black_height = 0;
+        while node != NIL {
+            black_height += !is_red(arena, node) as usize;
+            node = arena.left(node);
+        }
+
+        black_height
+    }
+
+    // merges two trees, entries of `batch` replace entries of `tree` with equal keys.
+    // the replaced nodes are unlinked and collected in `removed`
+    pub(super) fn union<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        batch: (usize, usize),
+        tree: (usize, usize),
+        removed: &mut Vec<usize>,
+    ) -> (usize, usize) {
+        let (node, black_height) = batch;
+        if node == NIL {
+            return tree;
+        }
+        if tree.0 == NIL {
+            return batch;
+        }
+
+        let child_height = black_height - !is_red(arena, node) as usize;
+        let (tree_left, tree_right) = split(arena, tree, node, removed);
+        let left = union(arena, (arena.left(node), child_height), tree_left, removed);
+        let right = union(
+            arena,
+            (arena.right(node), child_height),
+            tree_right,
+            removed,
+        );
+
+        join(arena, left, node, right)
+    }
+
+    // splits the tree into keys less than and greater than the key of `pivot`, a node with an equal key is unlinked
+    fn split<K: core::cmp::Ord, V>(
+        arena: &mut Arena<K, V>,
+        tree: (usize, usize),
+        pivot: usize,
+        removed: &mut Vec<usize>,
+    ) -> ((usize, usize), (usize, usize)) {
+        let (node, black_height) = tree;
+        if node == NIL {
+            return ((NIL, 0), (NIL, 0));
+        }
+
+        let child_height = black_height - !is_red(arena, node) as usize;
+        let left = (arena.left(node), child_height);
+        let right = (arena.right(node), child_height);
+        stats::comparison();
+        match arena.key(pivot).cmp(arena.key(node)) {
+            core::cmp::Ordering::Less => {
+                let (less, greater) = split(arena, left, pivot, removed);
+                (less, join(arena, greater, node, right))
+            }
+            core::cmp::Ordering::Greater => {
+                let (less, greater) = split(arena, right, pivot, removed);
+                (join(arena, left, node, less), greater)
+            }
+            core::cmp::Ordering::Equal => {
+                removed.push(node);
+                (left, right)
+            }
+        }
+    }
+
+    // links two trees through `node`, which is attached as a red node where the black heights match
+    fn join<K, V>(
+        arena: &mut Arena<K, V>,
+        left: (usize, usize),
+        node: usize,
+        right: (usize, usize),
+    ) -> (usize, usize) {
+        let (left, left_height) = blacken(arena, left);
+        let (right, right_height) = blacken(arena, right);
+
+        let (root, black_height) = if left_height == right_height {
+            arena.set_left(node, left);
+            arena.set_right(node, right);
+            arena.set_tag(node, BLACK);
+            arena.update_size(node);
+            return (node, left_height + 1);
+        } else if left_height > right_height {
+            (
+                join_right(arena, left, left_height, node, right, right_height),
+                left_height,
+            )
+        } else {
+            (
+                join_left(arena, left, left_height, node, right, right_height),
+                right_height,
+            )
+        };
+
+        if is_red(arena, root) {
+            arena.set_tag(root, BLACK);
+            return (root, black_height + 1);
+        }
+
+        (root, black_height)
+    }
+
+    fn blacken<K, V>(arena: &mut Arena<K, V>, tree: (usize, usize)) -> (usize, usize) {
+        let (node, black_height) = tree;
+        if is_red(arena, node) {
+            arena.set_tag(node, BLACK);
+            return (node, black_height + 1);
+        }
+
+        tree
+    }
+
+    // descends the right spine of the taller `tree`, right links are black so every step lowers the black height by one
+    fn join_right<K, V>(
+        arena: &mut Arena<K, V>,
+        tree: usize,
+        black_height: usize,
+        node: usize,
+        right: usize,
+        right_height: usize,
+    ) -> usize {
+        if black_height == right_height {
+            arena.set_left(node, tree);
+            arena.set_right(node, right);
+            arena.set_tag(node, RED);
+            arena.update_size(node);
+            return node;
+        }
+
+        let child = join_right(
+            arena,
+            arena.right(tree),
+            black_height - 1,
+            node,
+            right,
+            right_height,
+        );
+        arena.set_right(tree, child);
+
+        balance(arena, tree)
+    }
+
+    // descends the left spine of the taller `tree` down to a black node with the black height of `left`
+    fn join_left<K, V>(
+        arena: &mut Arena<K, V>,
+        left: usize,
+        left_height: usize,
+        node: usize,
+        tree: usize,
+        black_height: usize,
+    ) -> usize {
+        if black_height == left_height && !is_red(arena, tree) {
+            arena.set_left(node, left);
+            arena.set_right(node, tree);
+            arena.set_tag(node, RED);
+            arena.update_size(node);
+            return node;
+        }
+
+        let child_height = black_height - !is_red(arena, tree) as usize;
+        let child = join_left(
+            arena,
+            left,
+            left_height,
+            node,
+            arena.left(tree),
+            child_height,
+        );
+        arena.set_left(tree, child);
+
+        balance(arena, tree)
+    }
+}
+
+impl<K: core::cmp::Ord + Encode, V: Encode> Node<K, V> {
+    // writes the subtree in preorder, a leading byte tells whether the node exists and its color
+    fn write(node: &Option<Box<Node<K, V>>>, writer: &mut Writer) {
+        match node {
+            None => writer.write_u8(0),
+            Some(node) => {
+                writer.write_u8(if node.color == RED { 2 } else { 1 });
+                node.key().encode(writer);
+                node.value().encode(writer);
+                Node::write(&node.left_child, writer);
+                Node::write(&node.right_child, writer);
+            }
+        }
+    }
+}
+
+impl<K: core::cmp::Ord + Decode, V: Decode> Node<K, V> {
+    // reads a subtree written by `write` along with its black height, sizes are recomputed
+    // and the left-leaning red-black invariants are checked
+    fn read(reader: &mut Reader, depth: usize) -> Option<(Link<K, V>, usize)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let color = match reader.read_u8()? {
+            0 => return Some((None, 0)),
+            1 => BLACK,
+            2 => RED,
+            _ => return None,
+        };
+
+        let mut node = Box::new(Node::init(K::decode(reader)?, V::decode(reader)?, color, 1));
+        let (left_child, left_height) = Node::read(reader, depth + 1)?;
+        let (right_child, right_height) = Node::read(reader, depth + 1)?;
+        node.left_child = left_child;
+        node.right_child = right_child;
+        node.update_size();
+
+        let red_violation = Node::is_red(&node.right_child)
+            || (depth > 0 && color == RED && Node::is_red(&node.left_child));
+        if red_violation || left_height != right_height {
+            return None;
+        }
+
+        Some((Some(node), left_height + if color == BLACK { 1 } else { 0 }))
+    }
+}
+
+impl<K: core::cmp::Ord + Encode, V: Encode> RedBlack<K, V> {
+    /// Returns a snapshot of the tree in the binary format of `rudac::codec`.
+    /// The snapshot keeps the exact shape and colors of the tree, not only its items
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::RedBlack;
+    ///
+    /// let mut rb_tree = RedBlack::<String, i32>::init();
+    /// for (index, word) in ["rudac", "is", "awesome"].iter().enumerate() {
+    ///     rb_tree.insert(word.to_string(), index as i32);
+    /// }
+    ///
+    /// let bytes = rb_tree.to_bytes();
+    /// let restored = RedBlack::<String, i32>::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(restored.keys_in_level_order(), rb_tree.keys_in_level_order());
+    /// assert_eq!(restored.get(&String::from("is")), Some(&1));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"RB");
+        Node::write(&self.root, &mut writer);
+
+        writer.into_bytes()
+    }
+}
+
+impl<K: core::cmp::Ord + Decode, V: Decode> RedBlack<K, V> {
+    /// Restores a tree from a snapshot made by `to_bytes`.
+    /// Returns `None` if the bytes are malformed or do not describe a valid red-black tree
+    ///
+    /// # Arguments
+    /// * `bytes`: the snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Option<RedBlack<K, V>> {
+        let mut reader = Reader::init(bytes);
+        read_header(&mut reader, b"RB")?;
+        let (root, _) = Node::read(&mut reader, 0)?;
+        if !reader.is_at_end() {
+            return None;
+        }
+
+        let rb_tree: RedBlack<K, V> = RedBlack {
+            root,
+            stats: Recorder::default(),
+        };
+        if !rb_tree.keys().windows(2).all(|pair| pair[0] < pair[1]) {
+            return None;
+        }
+
+        Some(rb_tree)
+    }
+}
+
+impl<K: core::cmp::Ord, V, L: Layout> Clone for RedBlack<K, V, L>
+where
+    L::RedBlack<K, V>: Clone,
+{
+    fn clone(&self) -> Self {
+        RedBlack {
+            root: self.root.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+impl<K: core::cmp::Ord, V, L: Layout> Default for RedBlack<K, V, L>
+where
+    L::RedBlack<K, V>: Default,
+{
+    fn default() -> Self {
+        RedBlack::init()
+    }
+}
+
+// trees are equal when they hold the same entries, whatever their shape
+impl<K: core::cmp::Ord, V: PartialEq> PartialEq for RedBlack<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.entries() == other.entries()
+    }
+}
+
+impl<K: core::cmp::Ord, V: Eq> Eq for RedBlack<K, V> {}
+
+impl<K: core::cmp::Ord, V: PartialEq> PartialEq for RedBlack<K, V, Soa> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.entries() == other.entries()
+    }
+}
+
+impl<K: core::cmp::Ord, V: Eq> Eq for RedBlack<K, V, Soa> {}
+
+impl<K: core::cmp::Ord, V> MemoryFootprint for RedBlack<K, V> {
+    fn footprint(&self) -> Footprint {
+        let size = self.size();
+        Footprint::of::<(K, V)>(size).with_boxes::<Node<K, V>>(size)
+    }
+}
+
+impl<K: core::cmp::Ord, V> MemoryFootprint for RedBlack<K, V, Soa> {
+    fn footprint(&self) -> Footprint {
+        self.root.footprint()
+    }
+}
+
+impl<K: core::cmp::Ord, V> Replayable for RedBlack<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<K, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(key, value);
+            }
+            Op::Remove(key) => {
+                self.delete(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+impl<K: core::cmp::Ord, V> Replayable for RedBlack<K, V, Soa> {
+    type Key = K;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<K, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(key, value);
+            }
+            Op::Remove(key) => {
+                self.delete(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+// a tree is written as its entries in increasing order of keys and rebuilt by inserting them, later entries of a key
+// replace earlier ones
+#[cfg(feature = "serde")]
+impl<K: core::cmp::Ord + Serialize, V: Serialize> Serialize for RedBlack<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.entries())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: core::cmp::Ord + Serialize, V: Serialize> Serialize for RedBlack<K, V, Soa> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.entries())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K: core::cmp::Ord + Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de>
+    for RedBlack<K, V>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries: Vec<(K, V)> = Vec::deserialize(deserializer)?;
+        let mut red_black: RedBlack<K, V> = RedBlack::init();
+        for (key, value) in entries {
+            red_black.insert(key, value);
+        }
+
+        Ok(red_black)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K: core::cmp::Ord + Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de>
+    for RedBlack<K, V, Soa>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries: Vec<(K, V)> = Vec::deserialize(deserializer)?;
+        let mut red_black: RedBlack<K, V, Soa> = RedBlack::init();
+        for (key, value) in entries {
+            red_black.insert(key, value);
+        }
+
+        Ok(red_black)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tree::layout::Arena;
 
     fn is_23<K: std::cmp::Ord, V>(node: &Option<Box<Node<K, V>>>, is_root: bool) -> bool {
         if node.is_none() {
@@ -1446,4 +2581,299 @@ mod tests {
         assert!(is_size_consistent(&rb_tree.root));
         assert!(is_rank_consistent(&rb_tree));
     }
+
+    #[test]
+    fn tree_rb_bytes_round_trip() {
+        let mut rb_tree: RedBlack<i32, i32> = RedBlack::init();
+        for key in 0..300 {
+            rb_tree.insert((key * 71) % 300, key);
+        }
+        rb_tree.delete_min();
+        rb_tree.delete_max();
+
+        let bytes = rb_tree.to_bytes();
+        let restored = RedBlack::<i32, i32>::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            restored.keys_in_level_order(),
+            rb_tree.keys_in_level_order()
+        );
+        assert!(is_23(&restored.root, true));
+        assert!(is_size_consistent(&restored.root));
+        assert_eq!(restored.to_bytes(), bytes);
+
+        assert!(RedBlack::<i32, i32>::from_bytes(&bytes[1..]).is_none());
+    }
+
+    #[test]
+    fn tree_rb_bytes_rejects_invalid_colors() {
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"RB");
+        // black root with a red right child
+        for byte in [1, 1, 0, 0, 2, 2, 0, 0, 0].iter() {
+            writer.write_u8(*byte);
+        }
+        assert!(RedBlack::<u8, u8>::from_bytes(writer.bytes()).is_none());
+
+        let mut writer = Writer::init();
+        write_header(&mut writer, b"RB");
+        // black root with a single black child has unequal black heights
+        for byte in [1, 2, 0, 1, 1, 0, 0, 0, 0].iter() {
+            writer.write_u8(*byte);
+        }
+        assert!(RedBlack::<u8, u8>::from_bytes(writer.bytes()).is_none());
+    }
+
+    // checks colors, sizes and parent links of a `Soa` subtree, returns its black height
+    fn soa_black_height<K: std::cmp::Ord, V>(
+        arena: &Arena<K, V>,
+        node: usize,
+        parent: usize,
+    ) -> usize {
+        if node == NIL {
+            return 0;
+        }
+
+        assert_eq!(arena.parent(node), parent);
+        assert!(!soa::is_red(arena, arena.right(node)));
+        if soa::is_red(arena, node) {
+            assert!(!soa::is_red(arena, arena.left(node)));
+        }
+        let left = soa_black_height(arena, arena.left(node), node);
+        let right = soa_black_height(arena, arena.right(node), node);
+        assert_eq!(left, right);
+        assert_eq!(
+            arena.size(node),
+            1 + arena.size(arena.left(node)) + arena.size(arena.right(node))
+        );
+
+        left + !soa::is_red(arena, node) as usize
+    }
+
+    #[test]
+    fn tree_rb_soa_insert_matches_boxed() {
+        let mut boxed = RedBlack::<usize, usize>::init();
+        let mut soa = RedBlack::<usize, usize, Soa>::init();
+        for key in 0..500 {
+            boxed.insert((key * 113) % 500, key);
+            soa.insert((key * 113) % 500, key);
+        }
+
+        soa_black_height(&soa.root, soa.root.root(), NIL);
+        assert_eq!(soa.keys_in_level_order(), boxed.keys_in_level_order());
+        assert_eq!(soa.height(), boxed.height());
+        for key in 0..510 {
+            assert_eq!(soa.get(&key), boxed.get(&key));
+            assert_eq!(soa.rank(&key), boxed.rank(&key));
+        }
+    }
+
+    #[test]
+    fn tree_rb_soa_random_operations() {
+        use crate::util::{Rng, SplitMix64};
+        use std::collections::BTreeMap;
+
+        let mut rng = SplitMix64::init(968);
+        let mut expected = BTreeMap::new();
+        let mut soa = RedBlack::<u64, u64, Soa>::init();
+        for step in 0..3000 {
+            let key = rng.next_below(500);
+            match rng.next_below(10) {
+                0..=5 => {
+                    expected.insert(key, step);
+                    soa.insert(key, step);
+                }
+                6 | 7 => {
+                    expected.remove(&key);
+                    soa.delete(&key);
+                }
+                8 => {
+                    let min = expected.keys().next().cloned();
+                    if let Some(min) = min {
+                        expected.remove(&min);
+                    }
+                    soa.delete_min();
+                }
+                _ => {
+                    let max = expected.keys().next_back().cloned();
+                    if let Some(max) = max {
+                        expected.remove(&max);
+                    }
+                    soa.delete_max();
+                }
+            }
+            assert_eq!(soa.size(), expected.len());
+            assert_eq!(soa.get(&key), expected.get(&key));
+            if step % 100 == 0 {
+                soa_black_height(&soa.root, soa.root.root(), NIL);
+            }
+        }
+
+        soa_black_height(&soa.root, soa.root.root(), NIL);
+        assert_eq!(soa.keys(), expected.keys().collect::<Vec<_>>());
+        assert_eq!(soa.min(), expected.iter().next());
+        assert_eq!(soa.max(), expected.iter().next_back());
+        assert_eq!(
+            soa.floor(&250),
+            expected.range(..=250).next_back().map(|(key, _)| key)
+        );
+        assert_eq!(
+            soa.ceiling(&250),
+            expected.range(250..).next().map(|(key, _)| key)
+        );
+        assert_eq!(
+            soa.size_between(&100, &300),
+            expected.range(100..300).count()
+        );
+
+        while !soa.is_empty() {
+            soa.delete_max();
+        }
+        assert_eq!(soa.height(), -1);
+    }
+
+    fn black_height<K: std::cmp::Ord, V>(node: &Option<Box<Node<K, V>>>) -> Option<usize> {
+        match node {
+            None => Some(0),
+            Some(node_ref) => {
+                let left = black_height(&node_ref.left_child)?;
+                let right = black_height(&node_ref.right_child)?;
+                if left != right {
+                    return None;
+                }
+
+                Some(left + !node_ref.color as usize)
+            }
+        }
+    }
+
+    #[test]
+    fn tree_rb_insert_batch() {
+        use crate::util::{Rng, SplitMix64};
+        use std::collections::BTreeMap;
+
+        let mut rng = SplitMix64::init(969);
+        let mut expected = BTreeMap::new();
+        let mut boxed = RedBlack::<u64, u64>::init();
+        let mut soa = RedBlack::<u64, u64, Soa>::init();
+        for round in 0..40 {
+            // alternate small batches into large trees and large batches into small trees
+            let count = if round % 2 == 0 {
+                rng.next_below(8)
+            } else {
+                rng.next_below(300)
+            };
+            let mut batch: Vec<(u64, u64)> =
+                (0..count).map(|_| (rng.next_below(2000), round)).collect();
+            batch.sort_by_key(|(key, _)| *key);
+            for (key, value) in batch.iter() {
+                expected.insert(*key, *value);
+            }
+
+            boxed.insert_batch(batch.clone());
+            soa.insert_batch(batch);
+            assert!(is_23(&boxed.root, true));
+            assert!(!Node::is_red(&boxed.root));
+            assert!(black_height(&boxed.root).is_some());
+            assert!(is_size_consistent(&boxed.root));
+            soa_black_height(&soa.root, soa.root.root(), NIL);
+        }
+
+        let expected_keys: Vec<&u64> = expected.keys().collect();
+        assert_eq!(boxed.keys(), expected_keys);
+        assert_eq!(soa.keys(), expected_keys);
+        for (key, value) in expected.iter() {
+            assert_eq!(boxed.get(key), Some(value));
+            assert_eq!(soa.get(key), Some(value));
+        }
+    }
+
+    #[test]
+    fn tree_rb_insert_batch_duplicates() {
+        let mut rb_tree = RedBlack::<usize, &str, Soa>::init();
+        rb_tree.insert(2, "x");
+        rb_tree.insert_batch(vec![(1, "a"), (2, "b"), (2, "c")]);
+        assert_eq!(rb_tree.size(), 2);
+        assert_eq!(rb_tree.get(&2), Some(&"c"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Items must be sorted by key")]
+    fn tree_rb_panic_insert_batch() {
+        let mut rb_tree = RedBlack::<usize, usize>::init();
+        rb_tree.insert_batch(vec![(2, 2), (1, 1)]);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn tree_rb_stats() {
+        let mut boxed = RedBlack::<usize, usize>::init();
+        let mut soa = RedBlack::<usize, usize, Soa>::init();
+        for i in 0..100 {
+            boxed.insert(i, i);
+            soa.insert(i, i);
+        }
+
+        assert_eq!(boxed.stats().allocations, 100);
+        assert_eq!(boxed.stats().rotations, soa.stats().rotations);
+        assert_eq!(boxed.stats().comparisons, soa.stats().comparisons);
+        assert!(boxed.stats().rotations > 0);
+
+        soa.reset_stats();
+        soa.delete_min();
+        assert_eq!(soa.stats().comparisons, 0);
+        assert!(soa.stats().rotations > 0);
+
+        // operations on a clone are not counted by the original
+        let before = boxed.stats();
+        let mut batch = RedBlack::<usize, usize>::init();
+        batch.insert_batch((100..110).map(|i| (i, i)));
+        assert_eq!(batch.stats().allocations, 10);
+        assert_eq!(boxed.stats(), before);
+    }
+
+    #[test]
+    fn tree_rb_clone_and_eq() {
+        let mut tree = RedBlack::<usize, usize>::default();
+        let mut soa = RedBlack::<usize, usize, Soa>::default();
+        for key in 0..50 {
+            tree.insert(key, key * 2);
+            soa.insert(key, key * 2);
+        }
+
+        let mut copy = tree.clone();
+        let mut soa_copy = soa.clone();
+        assert!(copy == tree);
+        assert!(soa_copy == soa);
+        copy.delete(&7);
+        soa_copy.insert(7, 0);
+        assert!(copy != tree);
+        assert!(soa_copy != soa);
+        assert_eq!(tree.get(&7), Some(&14));
+        assert_eq!(soa.get(&7), Some(&14));
+
+        // the same entries inserted in another order give an equal tree of another shape
+        let mut other = RedBlack::<usize, usize>::init();
+        for key in (0..50).rev() {
+            other.insert(key, key * 2);
+        }
+        assert!(other == tree);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tree_rb_serde() {
+        let mut red_black = RedBlack::<u32, String>::init();
+        for key in [5, 2, 8, 1, 9] {
+            red_black.insert(key, key.to_string());
+        }
+
+        let json = serde_json::to_string(&red_black).unwrap();
+        assert_eq!(json, r#"[[1,"1"],[2,"2"],[5,"5"],[8,"8"],[9,"9"]]"#);
+
+        let restored: RedBlack<u32, String> = serde_json::from_str(&json).unwrap();
+        assert!(restored == red_black);
+        let soa: RedBlack<u32, String, Soa> = serde_json::from_str(&json).unwrap();
+        assert_eq!(soa.keys(), red_black.keys());
+        assert_eq!(serde_json::to_string(&soa).unwrap(), json);
+    }
 }
@@ -0,0 +1,233 @@
+use crate::util::Interval;
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use core::ops::Bound;
+use core::ops::Bound::*;
+
+// returns true if `point` is not below the low end `low`
+fn above_low<T: Ord>(low: &Bound<T>, point: &T) -> bool {
+    match low {
+        Included(low) => low <= point,
+        Excluded(low) => low < point,
+        Unbounded => true,
+    }
+}
+
+// returns true if `point` is not above the high end `high`
+fn below_high<T: Ord>(high: &Bound<T>, point: &T) -> bool {
+    match high {
+        Included(high) => point <= high,
+        Excluded(high) => point < high,
+        Unbounded => true,
+    }
+}
+
+// orders high ends by the points they admit, a high end admits every point a smaller one admits
+fn cmp_high<T: Ord>(first: &Bound<T>, second: &Bound<T>) -> Ordering {
+    match (first, second) {
+        (Included(first), Included(second)) | (Excluded(first), Excluded(second)) => {
+            first.cmp(second)
+        }
+        (Included(first), Excluded(second)) => {
+            if first < second {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }
+        (Excluded(first), Included(second)) => {
+            if first <= second {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Greater,
+        (_, Unbounded) => Ordering::Less,
+    }
+}
+
+// an interval that contains the current sweep point from below, ordered by its high end
+struct Active<'a, T: Ord> {
+    high: &'a Bound<T>,
+    index: usize,
+}
+
+impl<T: Ord> PartialEq for Active<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Active<'_, T> {}
+
+impl<T: Ord> PartialOrd for Active<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Active<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_high(self.high, other.high).then(self.index.cmp(&other.index))
+    }
+}
+
+/// Offline stabbing queries: finds the intervals containing each point of a batch by sweeping the sorted points
+/// over the sorted intervals, which takes O((n + q) log n) time plus the size of the answer
+/// for n intervals and q points. The intervals are sorted once and can answer any number of batches
+///
+/// # Examples
+/// ```
+/// use rudac::tree::StabbingQueries;
+/// use rudac::util::Interval;
+/// use std::ops::Bound::*;
+///
+/// let intervals = vec![
+///     Interval::new(Included(0), Excluded(5)),
+///     Interval::new(Excluded(3), Included(8)),
+///     Interval::new(Included(7), Unbounded),
+/// ];
+///
+/// let queries = StabbingQueries::init(&intervals);
+/// let stabbed = queries.stab(&[8, 3, 100]);
+///
+/// assert_eq!(stabbed[0], vec![&intervals[1], &intervals[2]]);
+/// assert_eq!(stabbed[1], vec![&intervals[0]]);
+/// assert_eq!(stabbed[2], vec![&intervals[2]]);
+/// ```
+pub struct StabbingQueries<'a, T: Ord> {
+    // sorted by low end
+    intervals: Vec<&'a Interval<T>>,
+}
+
+impl<'a, T: Ord> StabbingQueries<'a, T> {
+    /// Sorts `intervals` for answering batches of stabbing queries
+    ///
+    /// # Arguments
+    /// * `intervals`: the intervals, in any order
+    pub fn init<I: IntoIterator<Item = &'a Interval<T>>>(intervals: I) -> StabbingQueries<'a, T> {
+        let mut intervals: Vec<&'a Interval<T>> = intervals.into_iter().collect();
+        // already sorted input, like the intervals of an interval tree, is detected in linear time
+        intervals.sort();
+
+        StabbingQueries { intervals }
+    }
+
+    /// Returns number of intervals
+    pub fn size(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns true if there are no intervals
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns for every point the intervals containing it, sorted from smallest to largest
+    ///
+    /// # Arguments
+    /// * `points`: the points, in any order
+    pub fn stab(&self, points: &[T]) -> Vec<Vec<&'a Interval<T>>> {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by(|first, second| points[*first].cmp(&points[*second]));
+
+        let mut stabbed = vec![Vec::new(); points.len()];
+        let mut active = BinaryHeap::new();
+        let mut next = 0;
+        for query in order {
+            let point = &points[query];
+
+            // points are visited in ascending order, so an interval enters and leaves the sweep only once
+            while next < self.intervals.len() && above_low(self.intervals[next].low(), point) {
+                active.push(Reverse(Active {
+                    high: self.intervals[next].high(),
+                    index: next,
+                }));
+                next += 1;
+            }
+            while let Some(Reverse(first)) = active.peek() {
+                if below_high(first.high, point) {
+                    break;
+                }
+                active.pop();
+            }
+
+            let mut indices: Vec<usize> = active
+                .iter()
+                .map(|Reverse(interval)| interval.index)
+                .collect();
+            indices.sort_unstable();
+            stabbed[query] = indices
+                .into_iter()
+                .map(|index| self.intervals[index])
+                .collect();
+        }
+
+        stabbed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Rng, SplitMix64};
+
+    fn random_bound(rng: &mut SplitMix64, value: u64) -> Bound<u64> {
+        match rng.next_below(5) {
+            0 => Unbounded,
+            1 | 2 => Included(value),
+            _ => Excluded(value),
+        }
+    }
+
+    #[test]
+    fn tree_stabbing_matches_linear_scan() {
+        let mut rng = SplitMix64::init(13);
+        let mut intervals = Vec::new();
+        while intervals.len() < 300 {
+            let low = rng.next_below(100);
+            let high = low + rng.next_below(30);
+            let low = random_bound(&mut rng, low);
+            let high = random_bound(&mut rng, high);
+            let valid = match (&low, &high) {
+                (Excluded(low), Excluded(high))
+                | (Included(low), Excluded(high))
+                | (Excluded(low), Included(high)) => low < high,
+                _ => true,
+            };
+            if valid {
+                intervals.push(Interval::new(low, high));
+            }
+        }
+        let points: Vec<u64> = (0..200).map(|_| rng.next_below(140)).collect();
+
+        let queries = StabbingQueries::init(intervals.iter().rev());
+        assert_eq!(queries.size(), 300);
+        let stabbed = queries.stab(&points);
+
+        let mut sorted: Vec<&Interval<u64>> = intervals.iter().collect();
+        sorted.sort();
+        for (point, stabbed) in points.iter().zip(stabbed.iter()) {
+            let expected: Vec<&Interval<u64>> = sorted
+                .iter()
+                .copied()
+                .filter(|interval| Interval::overlaps(interval, &Interval::point(*point)))
+                .collect();
+            assert_eq!(*stabbed, expected);
+        }
+    }
+
+    #[test]
+    fn tree_stabbing_empty() {
+        let intervals: Vec<Interval<u64>> = Vec::new();
+        let queries = StabbingQueries::init(&intervals);
+
+        assert!(queries.is_empty());
+        assert!(queries.stab(&[1, 2]).iter().all(Vec::is_empty));
+        assert!(queries.stab(&[]).is_empty());
+    }
+}
@@ -0,0 +1,489 @@
+use crate::replay::{Op, Replayable};
+use crate::util::{Footprint, MemoryFootprint, Rng, SeedableRng, SplitMix64};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+// seed of the generator created by `init`
+const DEFAULT_SEED: u64 = 0x0074_7265_6170;
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+#[derive(Debug, Clone)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    priority: u64,
+    size: usize,
+    left_child: Link<K, V>,
+    right_child: Link<K, V>,
+}
+
+impl<K, V> Node<K, V> {
+    fn size(node: &Link<K, V>) -> usize {
+        match node {
+            Some(_node) => _node.size,
+            None => 0,
+        }
+    }
+
+    fn update(&mut self) {
+        self.size = 1 + Node::size(&self.left_child) + Node::size(&self.right_child);
+    }
+}
+
+/// A treap is a binary search tree on the keys and a max heap on random priorities drawn for every key.
+/// Its shape is the shape the tree would have if the keys were inserted in the order of their priorities,
+/// so its height is O(log n) expected. Priorities are drawn from the generator `R`, so treaps built
+/// from the same seed and the same operations have the same shape on every run
+///
+/// # Examples
+/// ```
+/// use rudac::tree::Treap;
+///
+/// let mut treap: Treap<i32, i32> = Treap::with_seed(42);
+/// for i in 0..100 {
+///     treap.insert(i, i * 2);
+/// }
+///
+/// assert_eq!(treap.get(&21), Some(&42));
+/// assert_eq!(treap.select(10), Some((&10, &20)));
+/// assert_eq!(treap.rank(&50), 50);
+///
+/// treap.delete(&21);
+/// assert!(!treap.contains(&21));
+/// assert_eq!(treap.size(), 99);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Treap<K: Ord, V, R: Rng = SplitMix64> {
+    root: Link<K, V>,
+    rng: R,
+}
+
+impl<K: Ord, V> Treap<K, V> {
+    /// Initializes an empty treap whose generator starts from a fixed seed
+    pub fn init() -> Treap<K, V> {
+        Treap::with_rng(SplitMix64::init(DEFAULT_SEED))
+    }
+}
+
+impl<K: Ord, V, R: SeedableRng> Treap<K, V, R> {
+    /// Initializes an empty treap with a generator created from `seed`
+    ///
+    /// # Arguments
+    /// * `seed`: seed of the generator
+    pub fn with_seed(seed: u64) -> Treap<K, V, R> {
+        Treap::with_rng(R::from_seed(seed))
+    }
+}
+
+impl<K: Ord, V, R: Rng> Treap<K, V, R> {
+    /// Initializes an empty treap that draws priorities from `rng`
+    ///
+    /// # Arguments
+    /// * `rng`: the generator
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::Treap;
+    /// use rudac::util::ScriptedRng;
+    ///
+    /// // increasing priorities move every new key to the root
+    /// let mut treap = Treap::with_rng(ScriptedRng::init((1..=5).collect()));
+    /// for i in 0..5 {
+    ///     treap.insert(i, ());
+    /// }
+    ///
+    /// assert_eq!(treap.height(), 4);
+    /// ```
+    pub fn with_rng(rng: R) -> Treap<K, V, R> {
+        Treap { root: None, rng }
+    }
+
+    /// Returns true if there are no keys in the treap
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns number of keys in the treap
+    pub fn size(&self) -> usize {
+        Node::size(&self.root)
+    }
+
+    /// Returns height of the treap, -1 if the treap is empty
+    pub fn height(&self) -> i64 {
+        fn height<K, V>(node: &Link<K, V>) -> i64 {
+            match node {
+                Some(_node) => 1 + height(&_node.left_child).max(height(&_node.right_child)),
+                None => -1,
+            }
+        }
+
+        height(&self.root)
+    }
+
+    /// Returns the generator of the treap
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Returns true if the treap contains `key`
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value associated with `key`, None if the key does not exist
+    ///
+    /// # Arguments
+    /// * `key`: key to be searched
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = &node.left_child,
+                Ordering::Greater => current = &node.right_child,
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+
+        None
+    }
+
+    /// Inserts `key` with `value`. If the key already exists, only its value is replaced and no priority is drawn
+    ///
+    /// # Arguments
+    /// * `key`: key to be inserted
+    /// * `value`: value associated with the key
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(existing) = self.get_mut(&key) {
+            *existing = value;
+            return;
+        }
+
+        let node = Box::new(Node {
+            key,
+            value,
+            priority: self.rng.next_u64(),
+            size: 1,
+            left_child: None,
+            right_child: None,
+        });
+
+        let root = self.root.take();
+        self.root = Some(insert_node(root, node));
+    }
+
+    /// Deletes `key` and its value, does nothing if the key does not exist
+    ///
+    /// # Arguments
+    /// * `key`: key to be deleted
+    pub fn delete(&mut self, key: &K) {
+        if self.contains(key) {
+            let root = self.root.take();
+            self.root = delete_node(root, key);
+        }
+    }
+
+    /// Returns the `k`th smallest key and its value, None if `k` is out of bounds
+    ///
+    /// # Arguments
+    /// * `k`: zero based rank of the key
+    pub fn select(&self, mut k: usize) -> Option<(&K, &V)> {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            let left_size = Node::size(&node.left_child);
+            match k.cmp(&left_size) {
+                Ordering::Less => current = &node.left_child,
+                Ordering::Equal => return Some((&node.key, &node.value)),
+                Ordering::Greater => {
+                    k -= left_size + 1;
+                    current = &node.right_child;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns number of keys smaller than `key`
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn rank(&self, key: &K) -> usize {
+        let mut rank = 0;
+        let mut current = &self.root;
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = &node.left_child,
+                Ordering::Greater => {
+                    rank += 1 + Node::size(&node.left_child);
+                    current = &node.right_child;
+                }
+                Ordering::Equal => return rank + Node::size(&node.left_child),
+            }
+        }
+
+        rank
+    }
+
+    /// Returns the smallest key and its value, None if the treap is empty
+    pub fn min(&self) -> Option<(&K, &V)> {
+        self.select(0)
+    }
+
+    /// Returns the largest key and its value, None if the treap is empty
+    pub fn max(&self) -> Option<(&K, &V)> {
+        self.select(self.size().checked_sub(1)?)
+    }
+
+    /// Returns keys of the treap in ascending order
+    pub fn keys(&self) -> Vec<&K> {
+        fn collect<'a, K, V>(node: &'a Link<K, V>, keys: &mut Vec<&'a K>) {
+            if let Some(_node) = node {
+                collect(&_node.left_child, keys);
+                keys.push(&_node.key);
+                collect(&_node.right_child, keys);
+            }
+        }
+
+        let mut keys = Vec::with_capacity(self.size());
+        collect(&self.root, &mut keys);
+        keys
+    }
+
+    // returns the entries of the treap in ascending order of keys
+    fn entries(&self) -> Vec<(&K, &V)> {
+        fn collect<'a, K, V>(node: &'a Link<K, V>, entries: &mut Vec<(&'a K, &'a V)>) {
+            if let Some(_node) = node {
+                collect(&_node.left_child, entries);
+                entries.push((&_node.key, &_node.value));
+                collect(&_node.right_child, entries);
+            }
+        }
+
+        let mut entries = Vec::with_capacity(self.size());
+        collect(&self.root, &mut entries);
+        entries
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = &mut self.root;
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = &mut node.left_child,
+                Ordering::Greater => current = &mut node.right_child,
+                Ordering::Equal => return Some(&mut node.value),
+            }
+        }
+
+        None
+    }
+}
+
+// inserts a node whose key is not in the subtree, the node becomes the root of the first subtree
+// whose root has a lower priority
+fn insert_node<K: Ord, V>(root: Link<K, V>, mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    match root {
+        None => node,
+        Some(mut _root) if _root.priority >= node.priority => {
+            if node.key < _root.key {
+                _root.left_child = Some(insert_node(_root.left_child.take(), node));
+            } else {
+                _root.right_child = Some(insert_node(_root.right_child.take(), node));
+            }
+            _root.update();
+            _root
+        }
+        Some(_root) => {
+            let (left, right) = split(Some(_root), &node.key);
+            node.left_child = left;
+            node.right_child = right;
+            node.update();
+            node
+        }
+    }
+}
+
+// splits the subtree into keys smaller than `key` and keys greater than `key`
+fn split<K: Ord, V>(root: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>) {
+    match root {
+        None => (None, None),
+        Some(mut _root) => {
+            if _root.key < *key {
+                let (left, right) = split(_root.right_child.take(), key);
+                _root.right_child = left;
+                _root.update();
+                (Some(_root), right)
+            } else {
+                let (left, right) = split(_root.left_child.take(), key);
+                _root.left_child = right;
+                _root.update();
+                (left, Some(_root))
+            }
+        }
+    }
+}
+
+// joins two subtrees where every key of `left` is smaller than every key of `right`
+fn merge<K, V>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+    match (left, right) {
+        (None, link) | (link, None) => link,
+        (Some(mut _left), Some(mut _right)) => {
+            if _left.priority >= _right.priority {
+                _left.right_child = merge(_left.right_child.take(), Some(_right));
+                _left.update();
+                Some(_left)
+            } else {
+                _right.left_child = merge(Some(_left), _right.left_child.take());
+                _right.update();
+                Some(_right)
+            }
+        }
+    }
+}
+
+fn delete_node<K: Ord, V>(root: Link<K, V>, key: &K) -> Link<K, V> {
+    let mut _root = root?;
+    match key.cmp(&_root.key) {
+        Ordering::Less => _root.left_child = delete_node(_root.left_child.take(), key),
+        Ordering::Greater => _root.right_child = delete_node(_root.right_child.take(), key),
+        Ordering::Equal => return merge(_root.left_child.take(), _root.right_child.take()),
+    }
+    _root.update();
+
+    Some(_root)
+}
+
+impl<K: Ord, V> Default for Treap<K, V> {
+    fn default() -> Self {
+        Treap::init()
+    }
+}
+
+// treaps are equal when they hold the same entries, whatever their priorities and generators
+impl<K: Ord, V: PartialEq, R: Rng> PartialEq for Treap<K, V, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.entries() == other.entries()
+    }
+}
+
+impl<K: Ord, V: Eq, R: Rng> Eq for Treap<K, V, R> {}
+
+impl<K: Ord, V, R: Rng> MemoryFootprint for Treap<K, V, R> {
+    fn footprint(&self) -> Footprint {
+        let size = self.size();
+        Footprint::of::<(K, V)>(size).with_boxes::<Node<K, V>>(size)
+    }
+}
+
+impl<K: Ord, V, R: Rng> Replayable for Treap<K, V, R> {
+    type Key = K;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<K, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(key, value);
+            }
+            Op::Remove(key) => {
+                self.delete(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::ScriptedRng;
+    use std::collections::BTreeMap;
+
+    fn assert_heap_ordered<K, V>(node: &Link<K, V>, parent_priority: u64) {
+        if let Some(_node) = node {
+            assert!(_node.priority <= parent_priority);
+            assert_heap_ordered(&_node.left_child, _node.priority);
+            assert_heap_ordered(&_node.right_child, _node.priority);
+        }
+    }
+
+    #[test]
+    fn tree_treap_matches_btree_map() {
+        let mut rng = SplitMix64::init(5);
+        let mut treap = Treap::init();
+        let mut map = BTreeMap::new();
+        for _ in 0..2000 {
+            let key = rng.next_below(300);
+            if rng.next_below(3) == 0 {
+                treap.delete(&key);
+                map.remove(&key);
+            } else {
+                treap.insert(key, key + 1);
+                map.insert(key, key + 1);
+            }
+        }
+
+        assert_heap_ordered(&treap.root, u64::MAX);
+        assert_eq!(treap.size(), map.len());
+        assert_eq!(treap.keys(), map.keys().collect::<Vec<&u64>>());
+        assert_eq!(treap.min(), map.iter().next());
+        assert_eq!(treap.max(), map.iter().next_back());
+        for key in 0..300 {
+            assert_eq!(treap.get(&key), map.get(&key));
+            assert_eq!(treap.rank(&key), map.range(..key).count());
+        }
+        assert!(treap.height() < 40);
+    }
+
+    #[test]
+    fn tree_treap_same_seed_same_shape() {
+        let mut a = Treap::<u64, ()>::with_seed(17);
+        let mut b = Treap::<u64, ()>::with_seed(17);
+        for i in 0..300 {
+            a.insert(i * 31 % 300, ());
+            b.insert(i * 31 % 300, ());
+        }
+        a.delete(&150);
+        b.delete(&150);
+
+        assert_eq!(format!("{:?}", a.root), format!("{:?}", b.root));
+    }
+
+    #[test]
+    fn tree_treap_scripted_rng_forces_shape() {
+        // equal priorities keep the insertion order shape, a sorted insertion degenerates into a list
+        let mut treap = Treap::with_rng(ScriptedRng::init(vec![7]));
+        for i in 0..10 {
+            treap.insert(i, i);
+        }
+        assert_eq!(treap.height(), 9);
+
+        // replacing a value draws no priority
+        treap.insert(3, 30);
+        assert_eq!(treap.rng().draws(), 10);
+        assert_eq!(treap.get(&3), Some(&30));
+
+        treap.delete(&0);
+        assert_eq!(treap.height(), 8);
+        assert_eq!(treap.min(), Some((&1, &1)));
+    }
+
+    #[test]
+    fn tree_treap_clone_and_eq() {
+        let mut treap = Treap::default();
+        let mut other: Treap<usize, usize> = Treap::with_seed(99);
+        for key in 0..30 {
+            treap.insert(key, key + 1);
+            other.insert(29 - key, 30 - key);
+        }
+        assert!(treap == other);
+
+        let mut copy = treap.clone();
+        copy.delete(&3);
+        assert!(copy != treap);
+        assert_eq!(treap.size(), 30);
+    }
+}
@@ -0,0 +1,521 @@
+use crate::replay::{Op, Replayable};
+#[cfg(feature = "std")]
+use crate::string::LevenshteinAutomaton;
+use crate::util::{Footprint, MemoryFootprint};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Clone, PartialEq, Eq)]
+struct Node<V> {
+    children: BTreeMap<char, Node<V>>,
+    value: Option<V>,
+}
+
+impl<V> Node<V> {
+    fn init() -> Node<V> {
+        Node {
+            children: BTreeMap::new(),
+            value: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.is_empty()
+    }
+
+    // removes `key[depth..]` below this node and prunes nodes left without values
+    fn remove(&mut self, key: &[char], depth: usize) -> Option<V> {
+        if depth == key.len() {
+            return self.value.take();
+        }
+
+        let child = self.children.get_mut(&key[depth])?;
+        let removed = child.remove(key, depth + 1);
+        if child.is_empty() {
+            self.children.remove(&key[depth]);
+        }
+
+        removed
+    }
+
+    // collects all entries below this node in lexicographic order
+    fn collect<'a>(&'a self, prefix: &mut String, result: &mut Vec<(String, &'a V)>) {
+        if let Some(value) = &self.value {
+            result.push((prefix.clone(), value));
+        }
+
+        for (c, child) in self.children.iter() {
+            prefix.push(*c);
+            child.collect(prefix, result);
+            prefix.pop();
+        }
+    }
+
+    // walks the trie keeping the last row of the edit distance table between `key` and the current prefix
+    fn search_levenshtein<'a>(
+        &'a self,
+        key: &[char],
+        row: &[usize],
+        max_distance: usize,
+        prefix: &mut String,
+        result: &mut Vec<(String, &'a V, usize)>,
+    ) {
+        let distance = row[key.len()];
+        if let Some(value) = &self.value {
+            if distance <= max_distance {
+                result.push((prefix.clone(), value, distance));
+            }
+        }
+
+        for (c, child) in self.children.iter() {
+            let mut next = Vec::with_capacity(row.len());
+            next.push(row[0] + 1);
+            for j in 1..row.len() {
+                let substitution = row[j - 1] + if key[j - 1] == *c { 0 } else { 1 };
+                next.push(substitution.min(row[j] + 1).min(next[j - 1] + 1));
+            }
+
+            // distances never decrease along a path, so the subtree has no match if the whole row is too large
+            if next.iter().min().copied().unwrap_or(0) > max_distance {
+                continue;
+            }
+
+            prefix.push(*c);
+            child.search_levenshtein(key, &next, max_distance, prefix, result);
+            prefix.pop();
+        }
+    }
+
+    // walks the trie along with `automaton`, leaving subtrees where the automaton has no state
+    #[cfg(feature = "std")]
+    fn search_automaton<'a>(
+        &'a self,
+        automaton: &LevenshteinAutomaton,
+        state: usize,
+        prefix: &mut String,
+        result: &mut Vec<(String, &'a V, usize)>,
+    ) {
+        if let (Some(value), Some(distance)) = (&self.value, automaton.accepts(state)) {
+            result.push((prefix.clone(), value, distance));
+        }
+
+        for (c, child) in self.children.iter() {
+            if let Some(next) = automaton.step(state, *c) {
+                prefix.push(*c);
+                child.search_automaton(automaton, next, prefix, result);
+                prefix.pop();
+            }
+        }
+    }
+}
+
+/// A trie(prefix tree) maps string keys to values. Keys sharing a prefix share the path of the prefix,
+/// which makes prefix queries and typo tolerant lookups efficient. Children are kept in order,
+/// so entries are always returned in lexicographic order of their keys.
+///
+/// # Examples
+/// ```
+/// use rudac::tree::Trie;
+///
+/// let mut trie = Trie::init();
+/// trie.insert("car", 1);
+/// trie.insert("cart", 2);
+/// trie.insert("care", 3);
+/// trie.insert("dog", 4);
+///
+/// assert_eq!(trie.get("cart"), Some(&2));
+/// assert_eq!(trie.get("ca"), None);
+///
+/// let keys: Vec<String> = trie.with_prefix("car").into_iter().map(|(key, _)| key).collect();
+/// assert_eq!(keys, vec!["car", "care", "cart"]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Trie<V> {
+    root: Node<V>,
+    size: usize,
+}
+
+impl<V> Trie<V> {
+    /// Initializes an empty trie
+    pub fn init() -> Trie<V> {
+        Trie {
+            root: Node::init(),
+            size: 0,
+        }
+    }
+
+    /// Returns number of keys in the trie
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no keys in the trie
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Inserts `key` with `value`. Returns the previous value of the key, if any
+    /// * Complexity: O(length of the key * log alphabet)
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    /// * `value`: value of the key
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            node = node.children.entry(c).or_insert_with(Node::init);
+        }
+
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.size += 1;
+        }
+
+        previous
+    }
+
+    /// Returns value of `key`, or `None` if the key is not in the trie
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.find(key).and_then(|node| node.value.as_ref())
+    }
+
+    /// Returns a mutable reference to value of `key`, or `None` if the key is not in the trie
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            node = node.children.get_mut(&c)?;
+        }
+
+        node.value.as_mut()
+    }
+
+    /// Returns true if `key` is in the trie
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` and returns its value, or `None` if the key is not in the trie
+    ///
+    /// # Arguments
+    /// * `key`: the key
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let key: Vec<char> = key.chars().collect();
+        let removed = self.root.remove(&key, 0);
+        if removed.is_some() {
+            self.size -= 1;
+        }
+
+        removed
+    }
+
+    /// Returns all entries whose key starts with `prefix`, in lexicographic order of their keys
+    ///
+    /// # Arguments
+    /// * `prefix`: the prefix
+    pub fn with_prefix(&self, prefix: &str) -> Vec<(String, &V)> {
+        let mut result = Vec::new();
+        if let Some(node) = self.find(prefix) {
+            node.collect(&mut prefix.to_string(), &mut result);
+        }
+
+        result
+    }
+
+    /// Returns all keys in lexicographic order
+    pub fn keys(&self) -> Vec<String> {
+        self.with_prefix("")
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Returns all entries whose key is within Levenshtein(edit) distance `max_distance` of `key`,
+    /// along with their distance. Results are sorted by distance, then by key.
+    /// * Complexity: O(number of visited nodes * length of the key). Subtrees that can not contain a match are skipped,
+    ///   so only a small part of the trie is visited for small distances
+    ///
+    /// # Arguments
+    /// * `key`: the searched key
+    /// * `max_distance`: largest accepted number of insertions, deletions and substitutions
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::tree::Trie;
+    ///
+    /// let mut trie = Trie::init();
+    /// for word in ["apple", "apply", "ample", "maple", "applesauce"].iter() {
+    ///     trie.insert(word, ());
+    /// }
+    ///
+    /// let matches: Vec<(String, usize)> = trie
+    ///     .search_levenshtein("appel", 2)
+    ///     .into_iter()
+    ///     .map(|(key, _, distance)| (key, distance))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     matches,
+    ///     vec![(String::from("apple"), 2), (String::from("apply"), 2)]
+    /// );
+    /// ```
+    pub fn search_levenshtein(&self, key: &str, max_distance: usize) -> Vec<(String, &V, usize)> {
+        let key: Vec<char> = key.chars().collect();
+        let row: Vec<usize> = (0..=key.len()).collect();
+
+        let mut result = Vec::new();
+        self.root
+            .search_levenshtein(&key, &row, max_distance, &mut String::new(), &mut result);
+        result.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+
+        result
+    }
+
+    /// Returns every key accepted by `automaton` along with its value and its edit distance to the word of the
+    /// automaton, sorted by distance and then by key. Gives the same result as `search_levenshtein`, but the work
+    /// per visited node does not depend on the length of the word, which pays off when one automaton searches many
+    /// tries or a large one
+    ///
+    /// # Arguments
+    /// * `automaton`: automaton of the word and the largest distance
+    ///
+    /// # Examples
+    /// ```
+    /// use rudac::string::LevenshteinAutomaton;
+    /// use rudac::tree::Trie;
+    ///
+    /// let mut trie = Trie::init();
+    /// for word in ["apple", "apply", "ample", "maple", "applesauce"].iter() {
+    ///     trie.insert(word, ());
+    /// }
+    ///
+    /// let automaton = LevenshteinAutomaton::init("appel", 2);
+    /// let matches: Vec<String> = trie
+    ///     .search_automaton(&automaton)
+    ///     .into_iter()
+    ///     .map(|(key, _, _)| key)
+    ///     .collect();
+    ///
+    /// assert_eq!(matches, vec!["apple", "apply"]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn search_automaton(&self, automaton: &LevenshteinAutomaton) -> Vec<(String, &V, usize)> {
+        let mut result = Vec::new();
+        self.root.search_automaton(
+            automaton,
+            automaton.start(),
+            &mut String::new(),
+            &mut result,
+        );
+        result.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+
+        result
+    }
+
+    /// Removes all keys
+    pub fn clear(&mut self) {
+        self.root = Node::init();
+        self.size = 0;
+    }
+
+    fn find(&self, prefix: &str) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        for c in prefix.chars() {
+            node = node.children.get(&c)?;
+        }
+
+        Some(node)
+    }
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Trie::init()
+    }
+}
+
+impl<V> Node<V> {
+    // returns the allocations of the children of this node and of all its descendants
+    fn footprint(&self) -> Footprint {
+        self.children.values().fold(
+            Footprint::init(0, 0).with_btree_map(&self.children),
+            |footprint, child| footprint.with(child.footprint()),
+        )
+    }
+}
+
+impl<V> MemoryFootprint for Trie<V> {
+    fn footprint(&self) -> Footprint {
+        Footprint::of::<V>(self.size).with(self.root.footprint())
+    }
+}
+
+impl<V> Replayable for Trie<V> {
+    type Key = String;
+    type Value = V;
+
+    fn apply(&mut self, op: Op<String, V>) {
+        match op {
+            Op::Insert(key, value) => {
+                self.insert(&key, value);
+            }
+            Op::Remove(key) => {
+                self.remove(&key);
+            }
+            Op::Push(_) | Op::Pop => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levenshtein(a: &str, b: &str) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+        for i in 1..=a.len() {
+            let mut current = vec![i; b.len() + 1];
+            for j in 1..=b.len() {
+                let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+                current[j] = (previous[j - 1] + cost)
+                    .min(previous[j] + 1)
+                    .min(current[j - 1] + 1);
+            }
+            previous = current;
+        }
+
+        previous[b.len()]
+    }
+
+    fn words() -> Vec<String> {
+        let mut words = Vec::new();
+        let mut state: u64 = 11;
+        for _ in 0..300 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let length = 1 + (state >> 60) as usize % 7;
+            let word: String = (0..length)
+                .map(|i| (b'a' + ((state >> (i * 5 + 3)) % 4) as u8) as char)
+                .collect();
+            words.push(word);
+        }
+        words
+    }
+
+    #[test]
+    fn tree_trie_search_levenshtein_matches_naive() {
+        let words = words();
+        let mut trie = Trie::init();
+        for (index, word) in words.iter().enumerate() {
+            trie.insert(word, index);
+        }
+
+        for query in ["", "a", "abc", "dddd", "abcdab", "bad"].iter() {
+            for max_distance in 0..3 {
+                let mut expected: Vec<(String, usize)> = trie
+                    .keys()
+                    .into_iter()
+                    .map(|key| {
+                        let distance = levenshtein(query, &key);
+                        (key, distance)
+                    })
+                    .filter(|(_, distance)| *distance <= max_distance)
+                    .collect();
+                expected.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+                let found: Vec<(String, usize)> = trie
+                    .search_levenshtein(query, max_distance)
+                    .into_iter()
+                    .map(|(key, _, distance)| (key, distance))
+                    .collect();
+                assert_eq!(found, expected);
+
+                #[cfg(feature = "std")]
+                {
+                    let automaton = LevenshteinAutomaton::init(query, max_distance);
+                    let found: Vec<(String, usize)> = trie
+                        .search_automaton(&automaton)
+                        .into_iter()
+                        .map(|(key, _, distance)| (key, distance))
+                        .collect();
+                    assert_eq!(found, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tree_trie_insert_get_remove() {
+        let mut trie = Trie::init();
+
+        assert_eq!(trie.insert("to", 1), None);
+        assert_eq!(trie.insert("tea", 2), None);
+        assert_eq!(trie.insert("ten", 3), None);
+        assert_eq!(trie.insert("to", 4), Some(1));
+        assert_eq!(trie.insert("", 0), None);
+        assert_eq!(trie.size(), 4);
+
+        *trie.get_mut("tea").unwrap() += 10;
+        assert_eq!(trie.get("tea"), Some(&12));
+        assert_eq!(trie.keys(), vec!["", "tea", "ten", "to"]);
+
+        assert_eq!(trie.remove("te"), None);
+        assert_eq!(trie.remove("tea"), Some(12));
+        assert_eq!(trie.remove("tea"), None);
+        assert!(trie.contains_key("ten"));
+        assert!(!trie.root.children[&'t'].children[&'e']
+            .children
+            .contains_key(&'a'));
+        assert_eq!(trie.size(), 3);
+
+        trie.clear();
+        assert!(trie.is_empty());
+        assert!(trie.with_prefix("").is_empty());
+    }
+
+    #[test]
+    fn tree_trie_unicode() {
+        let mut trie = Trie::init();
+        trie.insert("größe", 1);
+        trie.insert("grosse", 2);
+
+        let found: Vec<String> = trie
+            .search_levenshtein("große", 1)
+            .into_iter()
+            .map(|(key, _, _)| key)
+            .collect();
+        assert_eq!(found, vec!["größe"]);
+        assert_eq!(trie.with_prefix("grö").len(), 1);
+    }
+
+    #[test]
+    fn tree_trie_clone_and_eq() {
+        let mut trie = Trie::init();
+        for (value, word) in words().iter().enumerate() {
+            trie.insert(word, value);
+        }
+
+        let mut copy = trie.clone();
+        assert!(copy == trie);
+        let word = &words()[0];
+        let value = copy.remove(word).unwrap();
+        assert!(copy != trie);
+
+        // removing prunes the nodes of the key, so inserting it again restores an equal trie
+        copy.insert(word, value);
+        assert!(copy == trie);
+    }
+}
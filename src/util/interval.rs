@@ -1,7 +1,11 @@
-use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
-use std::ops::Bound;
-use std::ops::Bound::*;
-use std::rc::Rc;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+use core::ops::Bound;
+use core::ops::Bound::*;
+#[cfg(feature = "serde")]
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
 /// A utility data structure to represent intervals.
 /// It supports open, close and unbounded intervals
@@ -39,8 +43,8 @@ use std::rc::Rc;
 /// ```
 #[derive(Debug, Hash)]
 pub struct Interval<T: Ord> {
-    low: Rc<Bound<T>>,
-    high: Rc<Bound<T>>,
+    low: Arc<Bound<T>>,
+    high: Arc<Bound<T>>,
 }
 
 impl<T: Ord> Interval<T> {
@@ -70,8 +74,8 @@ impl<T: Ord> Interval<T> {
     /// ```
     pub fn new(low: Bound<T>, high: Bound<T>) -> Interval<T> {
         let interval = Interval {
-            low: Rc::new(low),
-            high: Rc::new(high),
+            low: Arc::new(low),
+            high: Arc::new(high),
         };
 
         if !Interval::valid(&interval) {
@@ -94,8 +98,8 @@ impl<T: Ord> Interval<T> {
     /// let point1 = Interval::point(2);
     /// ```
     pub fn point(value: T) -> Interval<T> {
-        let low = Rc::new(Included(value));
-        let high = Rc::clone(&low);
+        let low = Arc::new(Included(value));
+        let high = Arc::clone(&low);
 
         let interval = Interval { low, high };
 
@@ -143,8 +147,8 @@ impl<T: Ord> Interval<T> {
     }
 
     /// Get a duplicate of lower bound of the interval
-    pub fn get_low(&self) -> Rc<Bound<T>> {
-        Rc::clone(&self.low)
+    pub fn get_low(&self) -> Arc<Bound<T>> {
+        Arc::clone(&self.low)
     }
 
     /// Get reference to higher bound of the interval
@@ -153,8 +157,8 @@ impl<T: Ord> Interval<T> {
     }
 
     /// Get a duplicate of higher bound of the interval
-    pub fn get_high(&self) -> Rc<Bound<T>> {
-        Rc::clone(&self.high)
+    pub fn get_high(&self) -> Arc<Bound<T>> {
+        Arc::clone(&self.high)
     }
 
     /// Returns true if `first` and `second` intervals overlap, false otherwise
@@ -240,22 +244,22 @@ impl<T: Ord> Interval<T> {
             | (Excluded(low1), Included(low2))
             | (Excluded(low1), Excluded(low2)) => {
                 if low1 >= low2 {
-                    Rc::clone(&first.low)
+                    Arc::clone(&first.low)
                 } else {
-                    Rc::clone(&second.low)
+                    Arc::clone(&second.low)
                 }
             }
             (Included(low1), Excluded(low2)) => {
                 if low1 > low2 {
-                    Rc::clone(&first.low)
+                    Arc::clone(&first.low)
                 } else {
-                    Rc::clone(&second.low)
+                    Arc::clone(&second.low)
                 }
             }
-            (Unbounded, Included(_)) | (Unbounded, Excluded(_)) => Rc::clone(&second.low),
-            (Included(_), Unbounded) | (Excluded(_), Unbounded) => Rc::clone(&first.low),
+            (Unbounded, Included(_)) | (Unbounded, Excluded(_)) => Arc::clone(&second.low),
+            (Included(_), Unbounded) | (Excluded(_), Unbounded) => Arc::clone(&first.low),
 
-            (Unbounded, Unbounded) => Rc::new(Unbounded),
+            (Unbounded, Unbounded) => Arc::new(Unbounded),
         };
 
         let high = match (&first.high(), &second.high()) {
@@ -263,30 +267,30 @@ impl<T: Ord> Interval<T> {
             | (Excluded(high1), Included(high2))
             | (Excluded(high1), Excluded(high2)) => {
                 if high1 <= high2 {
-                    Rc::clone(&first.high)
+                    Arc::clone(&first.high)
                 } else {
-                    Rc::clone(&second.high)
+                    Arc::clone(&second.high)
                 }
             }
             (Included(high1), Excluded(high2)) => {
                 if high1 < high2 {
-                    Rc::clone(&first.high)
+                    Arc::clone(&first.high)
                 } else {
-                    Rc::clone(&second.high)
+                    Arc::clone(&second.high)
                 }
             }
-            (Unbounded, Included(_)) | (Unbounded, Excluded(_)) => Rc::clone(&second.high),
-            (Included(_), Unbounded) | (Excluded(_), Unbounded) => Rc::clone(&first.high),
+            (Unbounded, Included(_)) | (Unbounded, Excluded(_)) => Arc::clone(&second.high),
+            (Included(_), Unbounded) | (Excluded(_), Unbounded) => Arc::clone(&first.high),
 
-            (Unbounded, Unbounded) => Rc::new(Unbounded),
+            (Unbounded, Unbounded) => Arc::new(Unbounded),
         };
 
         Some(Interval { low, high })
     }
 }
 
-impl<T: Ord + std::fmt::Display> std::fmt::Display for Interval<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T: Ord + core::fmt::Display> core::fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let low: String;
         let high: String;
 
@@ -314,6 +318,16 @@ impl<T: Ord> PartialEq for Interval<T> {
 
 impl<T: Ord> Eq for Interval<T> {}
 
+impl<T: Ord> Clone for Interval<T> {
+    // the copy shares its bounds with the original
+    fn clone(&self) -> Self {
+        Interval {
+            low: Arc::clone(&self.low),
+            high: Arc::clone(&self.high),
+        }
+    }
+}
+
 impl<T: Ord> PartialOrd for Interval<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         // compare low end of the intervals
@@ -415,6 +429,30 @@ impl<T: Ord> Ord for Interval<T> {
     }
 }
 
+// an interval is written as the pair of its bounds
+#[cfg(feature = "serde")]
+impl<T: Ord + Serialize> Serialize for Interval<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.low(), self.high()).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Ord + Deserialize<'de>> Deserialize<'de> for Interval<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (low, high): (Bound<T>, Bound<T>) = Deserialize::deserialize(deserializer)?;
+        let interval = Interval {
+            low: Arc::new(low),
+            high: Arc::new(high),
+        };
+        if !Interval::valid(&interval) {
+            return Err(D::Error::custom("Interval is not valid"));
+        }
+
+        Ok(interval)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -698,4 +736,19 @@ mod tests {
 
         assert!(Interval::contains(&interval1, &interval2));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn util_interval_serde() {
+        let interval = Interval::new(Included(2), Unbounded);
+
+        let json = serde_json::to_string(&interval).unwrap();
+        assert_eq!(json, r#"[{"Included":2},"Unbounded"]"#);
+
+        let restored: Interval<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, interval);
+        assert!(
+            serde_json::from_str::<Interval<i32>>(r#"[{"Included":3},{"Excluded":3}]"#).is_err()
+        );
+    }
 }
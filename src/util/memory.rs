@@ -0,0 +1,268 @@
+use alloc::collections::{BTreeMap, BTreeSet, LinkedList};
+use alloc::vec::Vec;
+use core::mem::size_of;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+// bookkeeping of a single heap allocation made by the standard collections: a node header or a reference count
+const HEADER_BYTES: usize = 2 * size_of::<usize>();
+
+// entries a node of the standard B-tree holds on average, it holds between 5 and 11 of them
+const BTREE_NODE_ENTRIES: usize = 8;
+
+/// Structures that can report how much memory they use. The numbers are computed from capacities and node sizes,
+/// so they tell what the structure requested from the allocator, not what the allocator reserved for it.
+/// Heap memory owned by the elements themselves, like the contents of a `String`, is not included
+///
+/// # Examples
+/// ```
+/// use rudac::heap::MinMax;
+/// use rudac::tree::{Soa, AVL};
+/// use rudac::util::MemoryFootprint;
+///
+/// let mut boxed = AVL::<u64, u64>::init();
+/// let mut soa = AVL::<u64, u64, Soa>::init();
+/// let mut minmax = MinMax::<u64>::init();
+/// for i in 0..1000 {
+///     boxed.insert(i, i);
+///     soa.insert(i, i);
+///     minmax.push(i);
+/// }
+///
+/// // every node of the boxed tree is a separate allocation
+/// assert_eq!(boxed.footprint().nodes(), 1000);
+/// assert!(soa.footprint().nodes() < 10);
+///
+/// // a tree pays for child links, a binary heap only for spare capacity
+/// assert!(boxed.footprint().overhead_per_element() > minmax.footprint().overhead_per_element());
+/// ```
+pub trait MemoryFootprint {
+    /// Returns the memory usage of the structure
+    fn footprint(&self) -> Footprint;
+}
+
+/// Memory usage of a structure: allocated bytes, number of allocations and size of the stored elements
+///
+/// # Examples
+/// ```
+/// use rudac::util::Footprint;
+///
+/// let items: Vec<u32> = Vec::with_capacity(8);
+/// let footprint = Footprint::init(4, 16).with_vec(&items);
+///
+/// assert_eq!(footprint.allocated_bytes(), 32);
+/// assert_eq!(footprint.nodes(), 1);
+/// assert_eq!(footprint.overhead_per_element(), 4.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Footprint {
+    allocated_bytes: usize,
+    nodes: usize,
+    elements: usize,
+    payload_bytes: usize,
+}
+
+impl Footprint {
+    /// Initializes the footprint of a structure holding `elements` elements of `payload_bytes` bytes in total.
+    /// Allocations are added with the `with_*` methods
+    ///
+    /// # Arguments
+    /// * `elements`: number of elements
+    /// * `payload_bytes`: bytes needed to store the elements themselves
+    pub fn init(elements: usize, payload_bytes: usize) -> Footprint {
+        Footprint {
+            allocated_bytes: 0,
+            nodes: 0,
+            elements,
+            payload_bytes,
+        }
+    }
+
+    /// Returns the footprint of `elements` elements of type `T`
+    ///
+    /// # Arguments
+    /// * `elements`: number of elements
+    pub fn of<T>(elements: usize) -> Footprint {
+        Footprint::init(elements, elements * size_of::<T>())
+    }
+
+    /// Returns number of bytes allocated on the heap
+    pub fn allocated_bytes(&self) -> usize {
+        self.allocated_bytes
+    }
+
+    /// Returns number of separate heap allocations, like tree nodes and buffers
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns number of elements
+    pub fn elements(&self) -> usize {
+        self.elements
+    }
+
+    /// Returns number of bytes needed to store the elements themselves
+    pub fn payload_bytes(&self) -> usize {
+        self.payload_bytes
+    }
+
+    /// Returns the allocated bytes beyond the payload, divided by the number of elements. It is negative
+    /// for structures that store less than the payload, like compressed ones, and zero for empty structures
+    pub fn overhead_per_element(&self) -> f64 {
+        if self.elements == 0 {
+            return 0.0;
+        }
+
+        (self.allocated_bytes as f64 - self.payload_bytes as f64) / self.elements as f64
+    }
+
+    /// Adds `count` allocations of `bytes` bytes each
+    ///
+    /// # Arguments
+    /// * `count`: number of allocations
+    /// * `bytes`: size of every allocation
+    pub fn with_allocations(mut self, count: usize, bytes: usize) -> Footprint {
+        self.nodes += count;
+        self.allocated_bytes += count * bytes;
+        self
+    }
+
+    /// Adds `count` boxed values of type `T`
+    ///
+    /// # Arguments
+    /// * `count`: number of boxes
+    pub fn with_boxes<T>(self, count: usize) -> Footprint {
+        self.with_allocations(count, size_of::<T>())
+    }
+
+    /// Adds `count` reference counted values of type `T`, shared through `Rc` or `Arc`
+    ///
+    /// # Arguments
+    /// * `count`: number of reference counted allocations
+    pub fn with_rcs<T>(self, count: usize) -> Footprint {
+        self.with_allocations(count, HEADER_BYTES + size_of::<T>())
+    }
+
+    /// Adds the buffer of `vec`
+    ///
+    /// # Arguments
+    /// * `vec`: the vector
+    pub fn with_vec<T>(self, vec: &Vec<T>) -> Footprint {
+        let bytes = vec.capacity() * size_of::<T>();
+        if bytes == 0 {
+            return self;
+        }
+
+        self.with_allocations(1, bytes)
+    }
+
+    /// Adds the table of `map`, estimated from its capacity
+    ///
+    /// # Arguments
+    /// * `map`: the map
+    #[cfg(feature = "std")]
+    pub fn with_hash_map<K, V, S>(self, map: &HashMap<K, V, S>) -> Footprint {
+        let capacity = map.capacity();
+        if capacity == 0 {
+            return self;
+        }
+
+        // the table keeps a control byte per bucket and at most 7 of every 8 buckets in use
+        let buckets = if capacity < 8 {
+            (capacity + 1).next_power_of_two()
+        } else {
+            (capacity * 8 / 7).next_power_of_two()
+        };
+        self.with_allocations(1, buckets * (size_of::<(K, V)>() + 1) + 16)
+    }
+
+    /// Adds the nodes of `map`, estimated from its length
+    ///
+    /// # Arguments
+    /// * `map`: the map
+    pub fn with_btree_map<K, V>(self, map: &BTreeMap<K, V>) -> Footprint {
+        let nodes = map.len().div_ceil(BTREE_NODE_ENTRIES);
+        let node_bytes =
+            HEADER_BYTES + (2 * BTREE_NODE_ENTRIES - 1) * (size_of::<K>() + size_of::<V>());
+        self.with_allocations(nodes, node_bytes)
+    }
+
+    /// Adds the nodes of `set`, estimated from its length
+    ///
+    /// # Arguments
+    /// * `set`: the set
+    pub fn with_btree_set<T>(self, set: &BTreeSet<T>) -> Footprint {
+        let nodes = set.len().div_ceil(BTREE_NODE_ENTRIES);
+        let node_bytes = HEADER_BYTES + (2 * BTREE_NODE_ENTRIES - 1) * size_of::<T>();
+        self.with_allocations(nodes, node_bytes)
+    }
+
+    /// Adds the nodes of `list`
+    ///
+    /// # Arguments
+    /// * `list`: the list
+    pub fn with_linked_list<T>(self, list: &LinkedList<T>) -> Footprint {
+        self.with_allocations(list.len(), HEADER_BYTES + size_of::<T>())
+    }
+
+    /// Adds the allocations of `other`, its elements are not added
+    ///
+    /// # Arguments
+    /// * `other`: footprint of a part of the structure
+    pub fn with(mut self, other: Footprint) -> Footprint {
+        self.nodes += other.nodes;
+        self.allocated_bytes += other.allocated_bytes;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn util_memory_footprint_accumulates() {
+        let mut vec: Vec<u64> = Vec::with_capacity(10);
+        vec.push(1);
+        let footprint = Footprint::of::<u64>(1)
+            .with_vec(&vec)
+            .with_boxes::<[u8; 24]>(2);
+
+        assert_eq!(footprint.allocated_bytes(), 80 + 48);
+        assert_eq!(footprint.nodes(), 3);
+        assert_eq!(footprint.elements(), 1);
+        assert_eq!(footprint.payload_bytes(), 8);
+        assert_eq!(footprint.overhead_per_element(), 120.0);
+
+        let empty: Vec<u64> = Vec::new();
+        let combined = Footprint::init(0, 0).with_vec(&empty).with(footprint);
+        assert_eq!(combined.nodes(), 3);
+        assert_eq!(combined.elements(), 0);
+        assert_eq!(combined.overhead_per_element(), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn util_memory_footprint_std_collections() {
+        let mut map = HashMap::new();
+        let mut tree = BTreeMap::new();
+        let mut list = LinkedList::new();
+        for i in 0..100u32 {
+            map.insert(i, i);
+            tree.insert(i, i);
+            list.push_back(i);
+        }
+
+        let hashed = Footprint::init(0, 0).with_hash_map(&map);
+        assert_eq!(hashed.nodes(), 1);
+        assert!(hashed.allocated_bytes() >= map.capacity() * 8);
+
+        let sorted = Footprint::init(0, 0).with_btree_map(&tree);
+        assert!(sorted.nodes() >= 100 / 11);
+        assert!(sorted.allocated_bytes() >= 100 * 8);
+
+        let linked = Footprint::init(0, 0).with_linked_list(&list);
+        assert_eq!(linked.nodes(), 100);
+        assert_eq!(linked.allocated_bytes(), 100 * (HEADER_BYTES + 4));
+    }
+}
@@ -1,3 +1,18 @@
 mod interval;
+mod memory;
+mod order;
+mod random;
+#[cfg(feature = "serde")]
+pub(crate) mod serialize;
+pub(crate) mod stats;
 
 pub use interval::Interval;
+pub use memory::Footprint;
+pub use memory::MemoryFootprint;
+pub(crate) use order::Order;
+pub use random::Rng;
+pub use random::ScriptedRng;
+pub use random::SeedableRng;
+pub use random::SplitMix64;
+#[cfg(feature = "stats")]
+pub use stats::OpStats;
@@ -0,0 +1,68 @@
+use alloc::sync::Arc;
+use core::cmp::Ordering;
+
+// a comparator shared by clones of a heap, `Send` and `Sync` so the heap stays so
+type Closure<T> = dyn Fn(&T, &T) -> Ordering + Send + Sync;
+
+/// Order of the items of a heap or tree. `init_min` and `init_max` use a function pointer, which keeps items that
+/// are not `'static` usable, while `init_with` and `init_by_key` box a closure behind a reference count so cloning
+/// a heap shares it
+pub(crate) enum Order<T> {
+    Pointer(fn(&T, &T) -> Ordering),
+    Closure(Arc<Closure<T>>),
+}
+
+impl<T> Order<T> {
+    // orders by `compare`
+    pub(crate) fn with<F>(compare: F) -> Order<T>
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        Order::Closure(Arc::new(compare))
+    }
+
+    // orders by the key `key` extracts, computing it twice per comparison
+    pub(crate) fn by_key<K, F>(key: F) -> Order<T>
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        Order::with(move |item1, item2| key(item1).cmp(&key(item2)))
+    }
+
+    pub(crate) fn compare(&self, item1: &T, item2: &T) -> Ordering {
+        match self {
+            Order::Pointer(compare) => compare(item1, item2),
+            Order::Closure(compare) => compare(item1, item2),
+        }
+    }
+}
+
+impl<T: Ord> Order<T> {
+    // the order of `Ord` for a min heap, reversed for a max heap
+    pub(crate) fn natural(min: bool) -> Order<T> {
+        if min {
+            Order::Pointer(T::cmp)
+        } else {
+            Order::Pointer(|item1, item2| item2.cmp(item1))
+        }
+    }
+}
+
+impl<T> Clone for Order<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Order::Pointer(compare) => Order::Pointer(*compare),
+            Order::Closure(compare) => Order::Closure(Arc::clone(compare)),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for Order<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Order::Pointer(_) => f.write_str("Order::Pointer"),
+            Order::Closure(_) => f.write_str("Order::Closure"),
+        }
+    }
+}
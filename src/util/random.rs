@@ -0,0 +1,197 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A source of random numbers. Randomized structures and algorithms of the crate take their randomness from it,
+/// so results are reproducible with a seeded generator and any other generator can be plugged in.
+///
+/// # Examples
+/// ```
+/// use rudac::util::{Rng, SplitMix64};
+///
+/// let mut rng = SplitMix64::init(7);
+///
+/// let die = rng.next_below(6) + 1;
+/// assert!(1 <= die && die <= 6);
+///
+/// let x = rng.next_f64();
+/// assert!(0.0 <= x && x < 1.0);
+/// ```
+pub trait Rng {
+    /// Returns the next uniformly distributed 64 bit number
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a uniformly distributed number in range [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a uniformly distributed number in range [0, `bound`)
+    ///
+    /// # Panics
+    /// * panics if `bound` is zero
+    fn next_below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            panic!("Bound must be greater than zero");
+        }
+
+        // rejection sampling removes the bias of the modulo
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// A generator that is fully determined by a seed. Randomized structures use it to create their generator
+/// from a seed, so equal seeds give equal structures and equal results across runs
+///
+/// # Examples
+/// ```
+/// use rudac::util::{Rng, SeedableRng, SplitMix64};
+///
+/// let mut a = SplitMix64::from_seed(42);
+/// let mut b = SplitMix64::from_seed(42);
+///
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// ```
+pub trait SeedableRng: Rng {
+    /// Creates a generator from `seed`
+    ///
+    /// # Arguments
+    /// * `seed`: the seed
+    fn from_seed(seed: u64) -> Self;
+}
+
+/// SplitMix64 pseudo random generator. It is small, fast and fully determined by its seed,
+/// but it is not cryptographically secure
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Creates a generator from `seed`. Generators with equal seeds produce equal sequences
+    ///
+    /// # Arguments
+    /// * `seed`: the seed
+    pub fn init(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    // standard normal distribution, using the Box-Muller transform
+    #[cfg(feature = "std")]
+    pub(crate) fn next_gaussian(&mut self) -> f64 {
+        let u = 1.0 - self.next_f64();
+        let v = self.next_f64();
+
+        (-2.0 * u.ln()).sqrt() * (2.0 * core::f64::consts::PI * v).cos()
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        z ^ (z >> 31)
+    }
+}
+
+impl SeedableRng for SplitMix64 {
+    fn from_seed(seed: u64) -> SplitMix64 {
+        SplitMix64::init(seed)
+    }
+}
+
+/// A debugging generator that returns a scripted list of numbers and starts over when it runs out.
+/// It makes the random choices of a structure explicit, so a test can force a particular shape or a rare branch
+///
+/// # Examples
+/// ```
+/// use rudac::util::{Rng, ScriptedRng};
+///
+/// let mut rng = ScriptedRng::init(vec![3, 1]);
+///
+/// assert_eq!(rng.next_u64(), 3);
+/// assert_eq!(rng.next_u64(), 1);
+/// assert_eq!(rng.next_u64(), 3);
+/// assert_eq!(rng.draws(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScriptedRng {
+    values: Vec<u64>,
+    draws: usize,
+}
+
+impl ScriptedRng {
+    /// Creates a generator that returns `values` in order, over and over
+    ///
+    /// # Arguments
+    /// * `values`: the numbers to return
+    ///
+    /// # Panics
+    /// * panics if `values` is empty
+    pub fn init(values: Vec<u64>) -> ScriptedRng {
+        if values.is_empty() {
+            panic!("Script must contain at least one value");
+        }
+
+        ScriptedRng { values, draws: 0 }
+    }
+
+    /// Returns number of values drawn so far
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+}
+
+impl Rng for ScriptedRng {
+    fn next_u64(&mut self) -> u64 {
+        let value = self.values[self.draws % self.values.len()];
+        self.draws += 1;
+
+        value
+    }
+}
+
+impl SeedableRng for ScriptedRng {
+    // the seed becomes the only scripted value
+    fn from_seed(seed: u64) -> ScriptedRng {
+        ScriptedRng::init(vec![seed])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn util_random_scripted_rng_repeats() {
+        let mut rng = ScriptedRng::init(vec![0, u64::MAX, 5]);
+        let drawn: Vec<u64> = (0..6).map(|_| rng.next_u64()).collect();
+
+        assert_eq!(drawn, vec![0, u64::MAX, 5, 0, u64::MAX, 5]);
+        assert_eq!(rng.next_below(4), 0);
+        assert_eq!(rng.draws(), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "Script must contain at least one value")]
+    fn util_random_panic_empty_script() {
+        ScriptedRng::init(Vec::new());
+    }
+
+    #[test]
+    fn util_random_seeded_generators_agree() {
+        let mut a = SplitMix64::from_seed(9);
+        let mut b = SplitMix64::init(9);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+}
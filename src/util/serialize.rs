@@ -0,0 +1,102 @@
+//! Shared shape of the structures that are serialized as a sequence of their elements along with one setting, like
+//! the order of a heap or the capacity of a queue. They are written as a struct of two fields and rebuilt from the
+//! elements on deserialize, so the serialized form does not depend on the internal layout of the structure.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes `setting` and `items` as the fields of struct `name`
+pub(crate) fn serialize_items<S, H, E>(
+    serializer: S,
+    name: &'static str,
+    fields: &'static [&'static str; 2],
+    setting: H,
+    items: &[E],
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    H: Serialize,
+    E: Serialize,
+{
+    let mut state = serializer.serialize_struct(name, 2)?;
+    state.serialize_field(fields[0], &setting)?;
+    state.serialize_field(fields[1], items)?;
+    state.end()
+}
+
+/// Deserializes the setting and the items written by `serialize_items`
+pub(crate) fn deserialize_items<'de, D, H, E>(
+    deserializer: D,
+    name: &'static str,
+    fields: &'static [&'static str; 2],
+) -> Result<(H, Vec<E>), D::Error>
+where
+    D: Deserializer<'de>,
+    H: Deserialize<'de>,
+    E: Deserialize<'de>,
+{
+    deserializer.deserialize_struct(
+        name,
+        fields,
+        ItemsVisitor {
+            name,
+            fields,
+            marker: PhantomData,
+        },
+    )
+}
+
+struct ItemsVisitor<H, E> {
+    name: &'static str,
+    fields: &'static [&'static str; 2],
+    marker: PhantomData<(H, E)>,
+}
+
+impl<'de, H: Deserialize<'de>, E: Deserialize<'de>> Visitor<'de> for ItemsVisitor<H, E> {
+    type Value = (H, Vec<E>);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "struct {}", self.name)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let setting = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let items = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+
+        Ok((setting, items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut setting = None;
+        let mut items = None;
+        while let Some(field) = map.next_key::<String>()? {
+            if field == self.fields[0] && setting.is_none() {
+                setting = Some(map.next_value()?);
+            } else if field == self.fields[1] && items.is_none() {
+                items = Some(map.next_value()?);
+            } else if field == self.fields[0] || field == self.fields[1] {
+                return Err(A::Error::custom(format!("duplicate field `{}`", field)));
+            } else {
+                return Err(A::Error::unknown_field(&field, self.fields));
+            }
+        }
+
+        let setting = setting.ok_or_else(|| A::Error::missing_field(self.fields[0]))?;
+        let items = items.ok_or_else(|| A::Error::missing_field(self.fields[1]))?;
+
+        Ok((setting, items))
+    }
+}
+
+/// Fields of heaps, whether the heap is a min heap and its items in the order they would be popped
+pub(crate) const HEAP_FIELDS: &[&str; 2] = &["min", "items"];
@@ -0,0 +1,268 @@
+// Operation counting behind the `stats` feature.
+//
+// Helpers deep inside a structure have no access to the structure itself, so they report operations to counters
+// of the current thread. Public methods of an instrumented structure open a scope on the structure's recorder
+// and when the scope ends, the operations counted in the meantime are added to the recorder.
+// Without the feature every function here is empty and scopes and recorders are zero sized.
+
+#[cfg(feature = "stats")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "stats")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "stats")]
+use std::sync::Arc;
+
+/// Counts of the elementary operations performed by a heap or a tree. Available with the `stats` feature
+///
+/// # Examples
+/// ```
+/// use rudac::tree::AVL;
+///
+/// let mut avl_tree = AVL::<usize, usize>::init();
+/// for i in 0..100 {
+///     avl_tree.insert(i, i);
+/// }
+///
+/// let stats = avl_tree.stats();
+/// assert_eq!(stats.allocations, 100);
+/// assert!(stats.rotations > 0);
+///
+/// avl_tree.reset_stats();
+/// avl_tree.get(&50);
+/// assert!(avl_tree.stats().comparisons <= 10);
+/// ```
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct OpStats {
+    /// number of comparisons between items or keys
+    pub comparisons: u64,
+
+    /// number of tree rotations
+    pub rotations: u64,
+
+    /// number of times two heap-ordered trees were linked into one
+    pub consolidations: u64,
+
+    /// number of heap allocations: new nodes and growth of buffers
+    pub allocations: u64,
+}
+
+#[cfg(feature = "stats")]
+impl OpStats {
+    fn since(&self, start: &OpStats) -> OpStats {
+        OpStats {
+            comparisons: self.comparisons - start.comparisons,
+            rotations: self.rotations - start.rotations,
+            consolidations: self.consolidations - start.consolidations,
+            allocations: self.allocations - start.allocations,
+        }
+    }
+}
+
+#[cfg(feature = "stats")]
+thread_local! {
+    // operations performed by the current thread since it started
+    static COUNTERS: Cell<OpStats> = Cell::new(OpStats::default());
+
+    // recorders with an open scope on the current thread
+    static OPEN: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "stats")]
+fn count(update: impl FnOnce(&mut OpStats)) {
+    COUNTERS.with(|counters| {
+        let mut stats = counters.get();
+        update(&mut stats);
+        counters.set(stats);
+    })
+}
+
+/// Counts a comparison of two items or keys
+#[inline]
+pub(crate) fn comparison() {
+    #[cfg(feature = "stats")]
+    count(|stats| stats.comparisons += 1);
+}
+
+/// Counts a rotation
+#[inline]
+pub(crate) fn rotation() {
+    #[cfg(feature = "stats")]
+    count(|stats| stats.rotations += 1);
+}
+
+/// Counts linking of two trees
+#[inline]
+pub(crate) fn consolidation() {
+    #[cfg(feature = "stats")]
+    count(|stats| stats.consolidations += 1);
+}
+
+/// Counts a heap allocation
+#[inline]
+pub(crate) fn allocation() {
+    #[cfg(feature = "stats")]
+    count(|stats| stats.allocations += 1);
+}
+
+/// Counts an allocation if pushing one more item into a vector with `len` items and `capacity` slots reallocates it
+#[inline]
+pub(crate) fn growth(len: usize, capacity: usize) {
+    if len == capacity {
+        allocation();
+    }
+}
+
+/// Operation counts of a single structure
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    #[cfg(feature = "stats")]
+    counts: Arc<[AtomicU64; 4]>,
+}
+
+impl Clone for Recorder {
+    // a clone starts with the counts of the original but does not share them
+    fn clone(&self) -> Recorder {
+        let recorder = Recorder::default();
+        recorder.absorb(self);
+        recorder
+    }
+}
+
+impl Recorder {
+    /// Attributes the operations counted on the current thread to this recorder until the returned scope is dropped.
+    /// Scopes opened while another scope of the same recorder is open count nothing, so public methods
+    /// can call each other
+    #[must_use]
+    pub(crate) fn scope(&self) -> Scope {
+        #[cfg(feature = "stats")]
+        {
+            let id = Arc::as_ptr(&self.counts) as usize;
+            let nested = OPEN.with(|open| {
+                let mut open = open.borrow_mut();
+                let nested = open.contains(&id);
+                if !nested {
+                    open.push(id);
+                }
+                nested
+            });
+
+            Scope {
+                counts: if nested {
+                    None
+                } else {
+                    Some(Arc::clone(&self.counts))
+                },
+                start: COUNTERS.with(|counters| counters.get()),
+            }
+        }
+
+        #[cfg(not(feature = "stats"))]
+        Scope {}
+    }
+
+    /// Adds counts of `other` to this recorder. Used when a structure is merged into another one
+    pub(crate) fn absorb(&self, other: &Recorder) {
+        #[cfg(feature = "stats")]
+        self.add(&other.stats());
+
+        #[cfg(not(feature = "stats"))]
+        let _ = other;
+    }
+
+    #[cfg(feature = "stats")]
+    pub(crate) fn stats(&self) -> OpStats {
+        OpStats {
+            comparisons: self.counts[0].load(Ordering::Relaxed),
+            rotations: self.counts[1].load(Ordering::Relaxed),
+            consolidations: self.counts[2].load(Ordering::Relaxed),
+            allocations: self.counts[3].load(Ordering::Relaxed),
+        }
+    }
+
+    #[cfg(feature = "stats")]
+    pub(crate) fn reset(&self) {
+        for count in self.counts.iter() {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[cfg(feature = "stats")]
+    fn add(&self, stats: &OpStats) {
+        add_to(&self.counts, stats);
+    }
+}
+
+#[cfg(feature = "stats")]
+fn add_to(counts: &[AtomicU64; 4], stats: &OpStats) {
+    counts[0].fetch_add(stats.comparisons, Ordering::Relaxed);
+    counts[1].fetch_add(stats.rotations, Ordering::Relaxed);
+    counts[2].fetch_add(stats.consolidations, Ordering::Relaxed);
+    counts[3].fetch_add(stats.allocations, Ordering::Relaxed);
+}
+
+/// An open scope of a recorder, see `Recorder::scope`
+pub(crate) struct Scope {
+    #[cfg(feature = "stats")]
+    counts: Option<Arc<[AtomicU64; 4]>>,
+    #[cfg(feature = "stats")]
+    start: OpStats,
+}
+
+#[cfg(feature = "stats")]
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if let Some(counts) = &self.counts {
+            add_to(
+                counts,
+                &COUNTERS.with(|counters| counters.get()).since(&self.start),
+            );
+
+            let id = Arc::as_ptr(counts) as usize;
+            OPEN.with(|open| open.borrow_mut().retain(|open_id| *open_id != id));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "stats"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn util_stats_scope_counts_operations() {
+        let recorder = Recorder::default();
+        comparison();
+        {
+            let _stats = recorder.scope();
+            comparison();
+            rotation();
+            {
+                // nested scopes of the same recorder count nothing
+                let _nested = recorder.scope();
+                consolidation();
+                growth(4, 4);
+                growth(3, 4);
+            }
+        }
+        comparison();
+
+        assert_eq!(
+            recorder.stats(),
+            OpStats {
+                comparisons: 1,
+                rotations: 1,
+                consolidations: 1,
+                allocations: 1,
+            }
+        );
+
+        let clone = recorder.clone();
+        recorder.reset();
+        assert_eq!(recorder.stats(), OpStats::default());
+        assert_eq!(clone.stats().comparisons, 1);
+
+        recorder.absorb(&clone);
+        recorder.absorb(&clone);
+        assert_eq!(recorder.stats().rotations, 2);
+    }
+}